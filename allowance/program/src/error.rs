@@ -0,0 +1,27 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AllowanceError {
+    /// The allowance isn't the program address for the source account and the child.
+    InvalidAllowance,
+    /// An allowance needs a limit above zero.
+    InvalidLimit,
+    /// An allowance needs a period above zero.
+    InvalidPeriod,
+    /// The account isn't the allowance's source.
+    InvalidSource,
+    /// Withdrawals can't be empty.
+    InvalidAmount,
+    /// The signer doesn't own the source account.
+    NotFunder,
+    /// The signer isn't the allowance's child.
+    NotChild,
+    /// The withdrawal would take more than is left of this period's limit.
+    LimitExceeded,
+}
+
+impl From<AllowanceError> for ProgramError {
+    fn from(e: AllowanceError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,218 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of an allowance, followed by the source account's and the child's
+/// addresses.
+pub const ALLOWANCE_SEED: &[u8] = b"allowance";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AllowanceInstruction {
+    /// Lets the child withdraw up to `limit` from the source account every `period`
+    /// seconds, starting now. The child can only draw on what the funder approves the
+    /// allowance's address for, with the bank's `Approve`.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The funder, owner of the source account, paying for the
+    ///      allowance.
+    ///   1. `[writable]` The allowance, the program address for the source and the child.
+    ///   2. `[]` The source account.
+    ///   3. `[]` The child.
+    ///   4. `[]` The system program.
+    CreateAllowance { limit: u64, period: i64 },
+
+    /// Moves `amount` from the source account into the destination account, as the
+    /// source's delegate, if that keeps the child within this period's limit.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The child.
+    ///   1. `[writable]` The allowance.
+    ///   2. `[writable]` The source account.
+    ///   3. `[writable]` The destination account.
+    ///   4. `[]` The accounts' bank.
+    ///   5. `[]` The bank program, owner of the source account.
+    Withdraw { amount: u64 },
+
+    /// Revokes the source account's delegation, cancelling what is left of the approved
+    /// amount, and closes the allowance.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The funder, credited the allowance's rent.
+    ///   1. `[writable]` The allowance.
+    ///   2. `[writable]` The source account.
+    ///   3. `[]` The source account's bank.
+    ///   4. `[]` The bank program, owner of the source account.
+    Close,
+}
+
+impl AllowanceInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (limit, rest) = Self::unpack_u64(rest)?;
+                let (period, _rest) = Self::unpack_u64(rest)?;
+                Self::CreateAllowance {
+                    limit,
+                    period: period as i64,
+                }
+            }
+            1 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Withdraw { amount }
+            }
+            2 => Self::Close,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(17);
+        match *self {
+            Self::CreateAllowance { limit, period } => {
+                buf.push(0);
+                buf.extend_from_slice(&limit.to_le_bytes());
+                buf.extend_from_slice(&period.to_le_bytes());
+            }
+            Self::Withdraw { amount } => {
+                buf.push(1);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Close => buf.push(2),
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of `child`'s allowance out of `source_account`.
+pub fn allowance_address(
+    allowance_program_id: &Pubkey,
+    source_account: &Pubkey,
+    child: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ALLOWANCE_SEED, source_account.as_ref(), child.as_ref()],
+        allowance_program_id,
+    )
+}
+
+pub fn create_allowance(
+    allowance_program_id: &Pubkey,
+    funder: &Pubkey,
+    source_account: &Pubkey,
+    child: &Pubkey,
+    limit: u64,
+    period: i64,
+) -> Result<Instruction, ProgramError> {
+    let data = AllowanceInstruction::CreateAllowance { limit, period }.pack();
+    let (allowance, _) = allowance_address(allowance_program_id, source_account, child);
+    let accounts = vec![
+        AccountMeta::new(*funder, true),
+        AccountMeta::new(allowance, false),
+        AccountMeta::new_readonly(*source_account, false),
+        AccountMeta::new_readonly(*child, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *allowance_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn withdraw(
+    allowance_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    child: &Pubkey,
+    source_account: &Pubkey,
+    destination_account: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = AllowanceInstruction::Withdraw { amount }.pack();
+    let (allowance, _) = allowance_address(allowance_program_id, source_account, child);
+    let accounts = vec![
+        AccountMeta::new_readonly(*child, true),
+        AccountMeta::new(allowance, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *allowance_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn close(
+    allowance_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    funder: &Pubkey,
+    source_account: &Pubkey,
+    child: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = AllowanceInstruction::Close.pack();
+    let (allowance, _) = allowance_address(allowance_program_id, source_account, child);
+    let accounts = vec![
+        AccountMeta::new(*funder, true),
+        AccountMeta::new(allowance, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *allowance_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            AllowanceInstruction::CreateAllowance {
+                limit: 100,
+                period: 604_800,
+            },
+            AllowanceInstruction::Withdraw { amount: u64::MAX },
+            AllowanceInstruction::Close,
+        ];
+        for instruction in cases {
+            assert_eq!(
+                AllowanceInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            AllowanceInstruction::unpack(&[0, 1, 0, 0, 0, 0, 0, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            AllowanceInstruction::unpack(&[3]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
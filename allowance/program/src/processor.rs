@@ -0,0 +1,237 @@
+use crate::{
+    error::AllowanceError,
+    instruction::{AllowanceInstruction, ALLOWANCE_SEED},
+    state::Allowance,
+};
+use solana_bank::{instruction as bank_instruction, state::Account as BankAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = AllowanceInstruction::unpack(input)?;
+
+        match instruction {
+            AllowanceInstruction::CreateAllowance { limit, period } => {
+                msg!("Instruction: CreateAllowance");
+                Self::process_create_allowance(program_id, accounts, limit, period)
+            }
+            AllowanceInstruction::Withdraw { amount } => {
+                msg!("Instruction: Withdraw");
+                Self::process_withdraw(program_id, accounts, amount)
+            }
+            AllowanceInstruction::Close => {
+                msg!("Instruction: Close");
+                Self::process_close(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_create_allowance(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        limit: u64,
+        period: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let funder_info = next_account_info(account_info_iter)?;
+        let allowance_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let child_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !funder_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if BankAccount::unpack(&source_info.data.borrow())?.owner != *funder_info.key {
+            return Err(AllowanceError::NotFunder.into());
+        }
+        let (address, bump) = Pubkey::find_program_address(
+            &[
+                ALLOWANCE_SEED,
+                source_info.key.as_ref(),
+                child_info.key.as_ref(),
+            ],
+            program_id,
+        );
+        if address != *allowance_info.key {
+            return Err(AllowanceError::InvalidAllowance.into());
+        }
+        if !allowance_info.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if limit == 0 {
+            return Err(AllowanceError::InvalidLimit.into());
+        }
+        if period <= 0 {
+            return Err(AllowanceError::InvalidPeriod.into());
+        }
+
+        invoke_signed(
+            &system_instruction::create_account(
+                funder_info.key,
+                allowance_info.key,
+                Rent::get()?.minimum_balance(Allowance::LEN),
+                Allowance::LEN as u64,
+                program_id,
+            ),
+            &[
+                funder_info.clone(),
+                allowance_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[&[
+                ALLOWANCE_SEED,
+                source_info.key.as_ref(),
+                child_info.key.as_ref(),
+                &[bump],
+            ]],
+        )?;
+
+        let allowance = Allowance {
+            is_initialized: true,
+            funder: *funder_info.key,
+            source: *source_info.key,
+            child: *child_info.key,
+            limit,
+            period,
+            period_start: Clock::get()?.unix_timestamp,
+            spent: 0,
+            bump,
+        };
+        Allowance::pack(allowance, &mut allowance_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_withdraw(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let child_info = next_account_info(account_info_iter)?;
+        let allowance_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !child_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut allowance =
+            Self::load_allowance(program_id, allowance_info, source_info, bank_program_info)?;
+        if allowance.child != *child_info.key {
+            return Err(AllowanceError::NotChild.into());
+        }
+        if amount == 0 {
+            return Err(AllowanceError::InvalidAmount.into());
+        }
+        allowance.roll(Clock::get()?.unix_timestamp);
+        if amount > allowance.remaining() {
+            return Err(AllowanceError::LimitExceeded.into());
+        }
+
+        // The bank takes the amount out of what the funder approved the allowance for.
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                destination_info.key,
+                allowance_info.key,
+                amount,
+            )?,
+            &[
+                source_info.clone(),
+                destination_info.clone(),
+                allowance_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[
+                ALLOWANCE_SEED,
+                allowance.source.as_ref(),
+                allowance.child.as_ref(),
+                &[allowance.bump],
+            ]],
+        )?;
+
+        allowance.spent += amount;
+        Allowance::pack(allowance, &mut allowance_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_close(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let funder_info = next_account_info(account_info_iter)?;
+        let allowance_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !funder_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let allowance =
+            Self::load_allowance(program_id, allowance_info, source_info, bank_program_info)?;
+        if allowance.funder != *funder_info.key {
+            return Err(AllowanceError::NotFunder.into());
+        }
+
+        invoke(
+            &bank_instruction::revoke(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                funder_info.key,
+            )?,
+            &[
+                source_info.clone(),
+                funder_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        let lamports = allowance_info.lamports();
+        **allowance_info.lamports.borrow_mut() = 0;
+        **funder_info.lamports.borrow_mut() = funder_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(ProgramError::InvalidArgument)?;
+        allowance_info.data.borrow_mut().fill(0);
+        Ok(())
+    }
+
+    /// Loads the allowance of `source_info`. The allowance is the source's delegate, so
+    /// pinning the bank program to the source's owner keeps any other program from
+    /// taking its signature, and spending past the limit with it.
+    fn load_allowance(
+        program_id: &Pubkey,
+        allowance_info: &AccountInfo,
+        source_info: &AccountInfo,
+        bank_program_info: &AccountInfo,
+    ) -> Result<Allowance, ProgramError> {
+        if allowance_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let allowance = Allowance::unpack(&allowance_info.data.borrow())?;
+        if allowance.source != *source_info.key || source_info.owner != bank_program_info.key {
+            return Err(AllowanceError::InvalidSource.into());
+        }
+        Ok(allowance)
+    }
+}
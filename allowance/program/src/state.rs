@@ -0,0 +1,157 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Seconds in a week, the usual allowance period.
+pub const WEEK: i64 = 7 * 24 * 60 * 60;
+
+/// Lets `child` take up to `limit` out of `source`, a bank account of `funder`, in each
+/// `period` counted from the allowance's creation. Lives at a program address derived
+/// from the source and the child, which the funder approves as the source's delegate:
+/// the approved amount is what the child can draw on at all, and the limit how fast.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Allowance {
+    pub is_initialized: bool,
+    pub funder: Pubkey,
+    pub source: Pubkey,
+    pub child: Pubkey,
+    pub limit: u64,
+    /// Length of a period, in seconds.
+    pub period: i64,
+    /// Unix timestamp the current period started at.
+    pub period_start: i64,
+    /// Withdrawn in the current period.
+    pub spent: u64,
+    /// Bump seed of the allowance's address.
+    pub bump: u8,
+}
+
+impl Allowance {
+    /// Moves on to the period containing `now`, if the current one is over, which
+    /// resets what has been spent.
+    pub fn roll(&mut self, now: i64) {
+        let elapsed = now.saturating_sub(self.period_start) / self.period;
+        if elapsed > 0 {
+            self.period_start += elapsed * self.period;
+            self.spent = 0;
+        }
+    }
+
+    /// What is left to withdraw in the current period.
+    pub fn remaining(&self) -> u64 {
+        self.limit.saturating_sub(self.spent)
+    }
+}
+
+impl Sealed for Allowance {}
+impl IsInitialized for Allowance {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Allowance {
+    const LEN: usize = 130;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 130];
+        let (is_initialized, funder, source, child, limit, period, period_start, spent, bump) =
+            array_refs![src, 1, 32, 32, 32, 8, 8, 8, 8, 1];
+        Ok(Allowance {
+            is_initialized: unpack_bool(is_initialized)?,
+            funder: Pubkey::new_from_array(*funder),
+            source: Pubkey::new_from_array(*source),
+            child: Pubkey::new_from_array(*child),
+            limit: u64::from_le_bytes(*limit),
+            period: i64::from_le_bytes(*period),
+            period_start: i64::from_le_bytes(*period_start),
+            spent: u64::from_le_bytes(*spent),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 130];
+        let (is_initialized, funder, source, child, limit, period, period_start, spent, bump) =
+            mut_array_refs![dst, 1, 32, 32, 32, 8, 8, 8, 8, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        funder.copy_from_slice(self.funder.as_ref());
+        source.copy_from_slice(self.source.as_ref());
+        child.copy_from_slice(self.child.as_ref());
+        *limit = self.limit.to_le_bytes();
+        *period = self.period.to_le_bytes();
+        *period_start = self.period_start.to_le_bytes();
+        *spent = self.spent.to_le_bytes();
+        bump[0] = self.bump;
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let allowance = Allowance {
+            is_initialized: true,
+            funder: Pubkey::new_unique(),
+            source: Pubkey::new_unique(),
+            child: Pubkey::new_unique(),
+            limit: 100,
+            period: WEEK,
+            period_start: 1_700_000_000,
+            spent: 60,
+            bump: 253,
+        };
+        let mut packed = vec![0u8; Allowance::LEN];
+        Allowance::pack(allowance, &mut packed).unwrap();
+        assert_eq!(Allowance::unpack(&packed), Ok(allowance));
+
+        packed[0] = 2;
+        assert_eq!(
+            Allowance::unpack_unchecked(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_roll() {
+        let mut allowance = Allowance {
+            limit: 100,
+            period: WEEK,
+            period_start: 1_000,
+            spent: 60,
+            ..Allowance::default()
+        };
+        allowance.roll(1_000 + WEEK - 1);
+        assert_eq!((allowance.period_start, allowance.remaining()), (1_000, 40));
+        allowance.roll(1_000 + WEEK);
+        assert_eq!(
+            (allowance.period_start, allowance.remaining()),
+            (1_000 + WEEK, 100)
+        );
+
+        // Skipped periods don't carry anything over, and the schedule stays aligned.
+        allowance.spent = 100;
+        allowance.roll(1_000 + 4 * WEEK + 5);
+        assert_eq!(allowance.period_start, 1_000 + 4 * WEEK);
+        assert_eq!(allowance.remaining(), 100);
+        // A clock behind the period's start changes nothing.
+        allowance.spent = 30;
+        allowance.roll(0);
+        assert_eq!(
+            (allowance.period_start, allowance.spent),
+            (1_000 + 4 * WEEK, 30)
+        );
+    }
+}
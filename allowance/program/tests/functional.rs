@@ -0,0 +1,282 @@
+use solana_allowance::{
+    error::AllowanceError,
+    instruction::{allowance_address, close, create_allowance, withdraw},
+    processor::Processor,
+    state::{Allowance, WEEK},
+};
+use solana_bank::{
+    error::BankError, instruction::approve, state::Account, test_utils::program_test::Env,
+};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// Time the fixture's allowance starts at.
+const NOW: i64 = 1_000;
+
+/// A parent holding 1_000 tokens who lets their child take 100 a week, having approved
+/// the allowance for 500.
+struct Fixture {
+    env: Env,
+    parent: Keypair,
+    bank: Pubkey,
+    source: Pubkey,
+    child: Keypair,
+    pocket: Pubkey,
+    allowance: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_allowance", processor!(Processor::process)).await;
+        env.set_time(NOW);
+        let bank = env.create_bank().await;
+        let (parent, child) = (Keypair::new(), Keypair::new());
+        env.fund(&parent.pubkey(), 100_000_000).await;
+        let source = env.create_bank_account(&bank, &parent, 1_000).await;
+        let pocket = env.create_bank_account(&bank, &child, 0).await;
+        let (allowance, _) = allowance_address(&env.program_id, &source, &child.pubkey());
+        let instructions = [
+            create_allowance(
+                &env.program_id,
+                &parent.pubkey(),
+                &source,
+                &child.pubkey(),
+                100,
+                WEEK,
+            )
+            .unwrap(),
+            approve(
+                &env.bank_program_id,
+                &bank,
+                &source,
+                &allowance,
+                &parent.pubkey(),
+                500,
+            )
+            .unwrap(),
+        ];
+        env.process(&instructions, &[&parent]).await.unwrap();
+        Fixture {
+            env,
+            parent,
+            bank,
+            source,
+            child,
+            pocket,
+            allowance,
+        }
+    }
+
+    async fn allowance(&mut self) -> Option<Allowance> {
+        self.env
+            .context
+            .banks_client
+            .get_account(self.allowance)
+            .await
+            .unwrap()
+            .map(|account| Allowance::unpack(&account.data).unwrap())
+    }
+
+    async fn withdraw(&mut self, amount: u64) -> Result<(), TransactionError> {
+        let instruction = withdraw(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &self.child.pubkey(),
+            &self.source,
+            &self.pocket,
+            amount,
+        )
+        .unwrap();
+        let child = Keypair::from_bytes(&self.child.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&child]).await
+    }
+}
+
+fn custom(error: AllowanceError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_allowance() {
+    let mut fixture = Fixture::new().await;
+    let allowance = fixture.allowance().await.unwrap();
+    assert_eq!(allowance.funder, fixture.parent.pubkey());
+    assert_eq!(allowance.source, fixture.source);
+    assert_eq!(allowance.child, fixture.child.pubkey());
+    assert_eq!((allowance.limit, allowance.period), (100, WEEK));
+    assert_eq!((allowance.period_start, allowance.spent), (NOW, 0));
+
+    // Only the source's owner can hand out an allowance on it.
+    let stranger = Keypair::new();
+    fixture.env.fund(&stranger.pubkey(), 100_000_000).await;
+    let instruction = create_allowance(
+        &fixture.env.program_id,
+        &stranger.pubkey(),
+        &fixture.source,
+        &stranger.pubkey(),
+        100,
+        WEEK,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&stranger]).await,
+        custom(AllowanceError::NotFunder)
+    );
+    let parent = Keypair::from_bytes(&fixture.parent.to_bytes()).unwrap();
+    let instruction = create_allowance(
+        &fixture.env.program_id,
+        &parent.pubkey(),
+        &fixture.source,
+        &stranger.pubkey(),
+        100,
+        0,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&parent]).await,
+        custom(AllowanceError::InvalidPeriod)
+    );
+}
+
+#[tokio::test]
+async fn test_withdraw_within_limit() {
+    let mut fixture = Fixture::new().await;
+    let pocket = fixture.pocket;
+    fixture.withdraw(60).await.unwrap();
+    assert_eq!(
+        fixture.withdraw(50).await,
+        custom(AllowanceError::LimitExceeded)
+    );
+    fixture.withdraw(40).await.unwrap();
+    assert_eq!(fixture.env.amount(&pocket).await, 100);
+
+    // A new week starts with the whole limit again, and skipped weeks aren't saved up.
+    fixture.env.set_time(NOW + 2 * WEEK + 1);
+    assert_eq!(
+        fixture.withdraw(101).await,
+        custom(AllowanceError::LimitExceeded)
+    );
+    fixture.withdraw(100).await.unwrap();
+    let allowance = fixture.allowance().await.unwrap();
+    assert_eq!(
+        (allowance.period_start, allowance.spent),
+        (NOW + 2 * WEEK, 100)
+    );
+    let source = fixture.source;
+    let source = fixture.env.unpack::<Account>(&source).await;
+    assert_eq!((source.amount, source.delegated_amount), (800, 300));
+
+    let outsider = Keypair::new();
+    let instruction = withdraw(
+        &fixture.env.program_id,
+        &fixture.env.bank_program_id,
+        &fixture.bank,
+        &outsider.pubkey(),
+        &fixture.source,
+        &pocket,
+        1,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&outsider]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_withdraw_limited_by_approval() {
+    let mut fixture = Fixture::new().await;
+    // Five weeks of allowance use up nearly all of the 500 approved, so the sixth week
+    // only has what is left, whatever the limit says.
+    for week in 0..5 {
+        fixture.env.set_time(NOW + week * WEEK);
+        fixture.withdraw(100 - week as u64).await.unwrap();
+    }
+    fixture.env.set_time(NOW + 5 * WEEK);
+    assert_eq!(
+        fixture.withdraw(11).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::InsufficientFunds as u32)
+        ))
+    );
+    fixture.withdraw(10).await.unwrap();
+    let pocket = fixture.pocket;
+    assert_eq!(fixture.env.amount(&pocket).await, 500);
+}
+
+#[tokio::test]
+async fn test_close() {
+    let mut fixture = Fixture::new().await;
+    fixture.withdraw(70).await.unwrap();
+
+    let child = Keypair::from_bytes(&fixture.child.to_bytes()).unwrap();
+    let instruction = close(
+        &fixture.env.program_id,
+        &fixture.env.bank_program_id,
+        &fixture.bank,
+        &child.pubkey(),
+        &fixture.source,
+        &child.pubkey(),
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&child]).await,
+        custom(AllowanceError::NotFunder)
+    );
+
+    let parent = Keypair::from_bytes(&fixture.parent.to_bytes()).unwrap();
+    let instruction = close(
+        &fixture.env.program_id,
+        &fixture.env.bank_program_id,
+        &fixture.bank,
+        &parent.pubkey(),
+        &fixture.source,
+        &child.pubkey(),
+    )
+    .unwrap();
+    fixture
+        .env
+        .process(&[instruction], &[&parent])
+        .await
+        .unwrap();
+    assert_eq!(fixture.allowance().await, None);
+    let source = fixture.source;
+    let source = fixture.env.unpack::<Account>(&source).await;
+    assert_eq!((source.amount, source.delegated_amount), (930, 0));
+    assert!(source.delegate.is_none());
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+
+    // Through a program posing as the bank, the allowance would sign as the source's
+    // delegate for whatever that program asked the bank for, past the weekly limit.
+    let instruction = withdraw(
+        &fixture.env.program_id,
+        &fixture.env.fake_bank_program_id,
+        &fixture.bank,
+        &fixture.child.pubkey(),
+        &fixture.source,
+        &fixture.pocket,
+        100,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&fixture.child]).await,
+        custom(AllowanceError::InvalidSource)
+    );
+    assert_eq!(fixture.allowance().await.unwrap().spent, 0);
+}
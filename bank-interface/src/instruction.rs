@@ -0,0 +1,86 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Eq)]
+pub enum BankInstruction {
+    /// Writes a new `BankAccount` into an account owned by the program.
+    ///
+    /// Accounts expected:
+    ///
+    ///   0. `[writable]` The account to initialize.
+    ///   1. `[signer]` The authority of the account.
+    InitAccount { amount: u64, name: String },
+
+    /// Moves `amount` between two bank accounts.
+    ///
+    /// Accounts expected:
+    ///
+    ///   0. `[writable]` The source account.
+    ///   1. `[writable]` The destination account.
+    ///   2. `[signer]` The authority of the source account.
+    Transfer { amount: u64 },
+}
+
+/// Creates an `InitAccount` instruction.
+pub fn init_account(
+    program_id: &Pubkey,
+    account: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+    name: String,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &BankInstruction::InitAccount { amount, name },
+        vec![
+            AccountMeta::new(*account, false),
+            AccountMeta::new(*authority, true),
+        ],
+    )
+}
+
+/// Creates a `Transfer` instruction.
+pub fn transfer(
+    program_id: &Pubkey,
+    from: &Pubkey,
+    to: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &BankInstruction::Transfer { amount },
+        vec![
+            AccountMeta::new(*from, false),
+            AccountMeta::new(*to, false),
+            AccountMeta::new(*authority, true),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer() {
+        let program_id = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let ix = transfer(
+            &program_id,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &authority,
+            50,
+        );
+        assert_eq!(ix.program_id, program_id);
+        assert_eq!(ix.accounts[2], AccountMeta::new(authority, true));
+        assert_eq!(
+            BankInstruction::try_from_slice(&ix.data).unwrap(),
+            BankInstruction::Transfer { amount: 50 }
+        );
+    }
+}
@@ -0,0 +1,8 @@
+//! Types shared by the borsh bank program in `program/` and its clients, so both
+//! sides encode accounts and instructions the same way.
+
+pub mod instruction;
+pub mod state;
+
+pub use instruction::BankInstruction;
+pub use state::BankAccount;
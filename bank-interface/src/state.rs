@@ -0,0 +1,32 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct BankAccount {
+    pub amount: u64,
+    pub authority: Pubkey,
+    pub name: String,
+}
+
+impl BankAccount {
+    pub fn new(amount: u64, authority: Pubkey, name: String) -> BankAccount {
+        BankAccount {
+            amount,
+            authority,
+            name,
+        }
+    }
+
+    pub fn sub_with(&mut self, sub: u64) -> Result<&BankAccount, String> {
+        if self.amount < sub {
+            return Err("amount is insufficient".to_string());
+        }
+        self.amount -= sub;
+        Ok(self)
+    }
+
+    pub fn add_with(&mut self, add: u64) -> Result<&BankAccount, String> {
+        self.amount += add;
+        Ok(self)
+    }
+}
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum BankError {
+    /// The accounts of an instruction belong to different banks.
+    BankMismatch,
+    /// The account has been closed, or was never opened.
+    AccountNotOpen,
+    /// Moving tokens from an account into itself.
+    SelfTransfer,
+    /// The account holds, or has delegated, less than the amount.
+    InsufficientFunds,
+    /// The account is already delegated to someone else.
+    DelegateMismatch,
+    /// The signer is neither the account's owner nor its delegate.
+    Unauthorized,
+    /// The amount would overflow a balance or the total supply.
+    Overflow,
+}
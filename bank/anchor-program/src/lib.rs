@@ -0,0 +1,233 @@
+//! The bank program's instruction set written with Anchor. Instructions, arguments and
+//! the accounts they take line up with `bank/program`, so the two can be read side by side;
+//! where the raw program checks owners, signers and banks by hand, this one states them as
+//! account constraints. Anchor adds what it always does: 8-byte discriminators on
+//! instructions and accounts, and `init`/`close` creating and closing accounts itself, so the
+//! bank and account keypairs sign their own initialization.
+
+use anchor_lang::prelude::*;
+
+pub mod error;
+pub mod state;
+
+use error::BankError;
+use state::{Bank, BankAccount};
+
+declare_id!("BankAnchor111111111111111111111111111111111");
+
+#[program]
+pub mod bank_anchor {
+    use super::*;
+
+    /// Opens a bank with `decimals`; the signer becomes its owner.
+    pub fn initialize_bank(ctx: Context<InitializeBank>, decimals: u8) -> Result<()> {
+        let bank = &mut ctx.accounts.bank;
+        bank.decimals = decimals;
+        bank.bank_owner = ctx.accounts.bank_owner.key();
+        bank.is_opened = true;
+        Ok(())
+    }
+
+    /// Opens an empty account in a bank.
+    pub fn initialize_account(ctx: Context<InitializeAccount>) -> Result<()> {
+        let account = &mut ctx.accounts.account;
+        account.bank = ctx.accounts.bank.key();
+        account.owner = ctx.accounts.owner.key();
+        account.is_initialized = true;
+        account.is_opened = true;
+        Ok(())
+    }
+
+    /// Moves `amount` between two accounts of the same bank.
+    pub fn transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
+        let from = &mut ctx.accounts.from;
+        // A delegate spends the account's own tokens, as far as its allowance goes.
+        if from.is_delegate(ctx.accounts.owner.key)? {
+            from.delegated_amount = from
+                .delegated_amount
+                .checked_sub(amount)
+                .ok_or(BankError::InsufficientFunds)?;
+            // A spent allowance frees the account for approving another delegate.
+            if from.delegated_amount == 0 {
+                from.delegate = None;
+            }
+        }
+        from.amount = from
+            .amount
+            .checked_sub(amount)
+            .ok_or(BankError::InsufficientFunds)?;
+        let to = &mut ctx.accounts.to;
+        to.amount = to.amount.checked_add(amount).ok_or(BankError::Overflow)?;
+        Ok(())
+    }
+
+    /// Lets a delegate spend up to `amount` more of an account's tokens, which stay in it.
+    pub fn approve(ctx: Context<Approve>, amount: u64) -> Result<()> {
+        let account = &mut ctx.accounts.account;
+        let delegate = ctx.accounts.delegate.key();
+        match account.delegate {
+            Some(current) => require_keys_eq!(current, delegate, BankError::DelegateMismatch),
+            None => account.delegate = Some(delegate),
+        }
+        account.is_delegate(ctx.accounts.owner.key)?;
+        account.delegated_amount = account
+            .delegated_amount
+            .checked_add(amount)
+            .ok_or(BankError::Overflow)?;
+        Ok(())
+    }
+
+    /// Creates `amount` new tokens in an account.
+    pub fn mint_to(ctx: Context<MintTo>, amount: u64) -> Result<()> {
+        let bank = &mut ctx.accounts.bank;
+        bank.total_supply = bank
+            .total_supply
+            .checked_add(amount)
+            .ok_or(BankError::Overflow)?;
+        let account = &mut ctx.accounts.account;
+        account.amount = account
+            .amount
+            .checked_add(amount)
+            .ok_or(BankError::Overflow)?;
+        Ok(())
+    }
+
+    /// Destroys `amount` tokens of an account.
+    pub fn burn(ctx: Context<Burn>, amount: u64) -> Result<()> {
+        let account = &mut ctx.accounts.account;
+        account.amount = account
+            .amount
+            .checked_sub(amount)
+            .ok_or(BankError::InsufficientFunds)?;
+        let bank = &mut ctx.accounts.bank;
+        bank.total_supply = bank
+            .total_supply
+            .checked_sub(amount)
+            .ok_or(BankError::InsufficientFunds)?;
+        Ok(())
+    }
+
+    /// Closes an account and sends its lamports to the destination.
+    pub fn close_account(_ctx: Context<CloseAccount>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Cancels the delegation of an account along with what is left of its allowance.
+    pub fn revoke(ctx: Context<Revoke>) -> Result<()> {
+        let account = &mut ctx.accounts.account;
+        account.delegated_amount = 0;
+        account.delegate = None;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeBank<'info> {
+    #[account(init, payer = bank_owner, space = 8 + Bank::INIT_SPACE)]
+    pub bank: Account<'info, Bank>,
+    #[account(mut)]
+    pub bank_owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeAccount<'info> {
+    #[account(constraint = bank.is_opened @ BankError::AccountNotOpen)]
+    pub bank: Account<'info, Bank>,
+    #[account(init, payer = owner, space = 8 + BankAccount::INIT_SPACE)]
+    pub account: Account<'info, BankAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Transfer<'info> {
+    #[account(
+        mut,
+        constraint = from.key() != to.key() @ BankError::SelfTransfer,
+        constraint = from.can_trade() @ BankError::AccountNotOpen,
+        constraint = from.bank == bank.key() @ BankError::BankMismatch,
+    )]
+    pub from: Account<'info, BankAccount>,
+    #[account(
+        mut,
+        constraint = to.can_trade() @ BankError::AccountNotOpen,
+        constraint = to.bank == bank.key() @ BankError::BankMismatch,
+    )]
+    pub to: Account<'info, BankAccount>,
+    /// The owner of `from`, or its delegate.
+    pub owner: Signer<'info>,
+    #[account(constraint = bank.is_opened @ BankError::AccountNotOpen)]
+    pub bank: Account<'info, Bank>,
+}
+
+#[derive(Accounts)]
+pub struct Approve<'info> {
+    #[account(
+        mut,
+        constraint = account.can_trade() @ BankError::AccountNotOpen,
+        constraint = account.bank == bank.key() @ BankError::BankMismatch,
+    )]
+    pub account: Account<'info, BankAccount>,
+    /// CHECK: only its address is recorded.
+    pub delegate: UncheckedAccount<'info>,
+    pub owner: Signer<'info>,
+    pub bank: Account<'info, Bank>,
+}
+
+#[derive(Accounts)]
+pub struct MintTo<'info> {
+    #[account(mut, has_one = bank_owner @ BankError::Unauthorized)]
+    pub bank: Account<'info, Bank>,
+    #[account(
+        mut,
+        constraint = account.bank == bank.key() @ BankError::BankMismatch,
+        constraint = account.can_trade() @ BankError::AccountNotOpen,
+    )]
+    pub account: Account<'info, BankAccount>,
+    pub bank_owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Burn<'info> {
+    #[account(mut, has_one = bank_owner @ BankError::Unauthorized)]
+    pub bank: Account<'info, Bank>,
+    #[account(
+        mut,
+        has_one = owner @ BankError::Unauthorized,
+        constraint = account.bank == bank.key() @ BankError::BankMismatch,
+    )]
+    pub account: Account<'info, BankAccount>,
+    pub bank_owner: Signer<'info>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAccount<'info> {
+    #[account(
+        mut,
+        close = destination,
+        has_one = owner @ BankError::Unauthorized,
+        constraint = account.key() != destination.key() @ BankError::SelfTransfer,
+        constraint = account.bank == bank.key() @ BankError::BankMismatch,
+    )]
+    pub account: Account<'info, BankAccount>,
+    /// CHECK: only receives lamports.
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+    pub owner: Signer<'info>,
+    pub bank: Account<'info, Bank>,
+}
+
+#[derive(Accounts)]
+pub struct Revoke<'info> {
+    #[account(
+        mut,
+        has_one = owner @ BankError::Unauthorized,
+        constraint = account.bank == bank.key() @ BankError::BankMismatch,
+    )]
+    pub account: Account<'info, BankAccount>,
+    pub owner: Signer<'info>,
+    pub bank: Account<'info, Bank>,
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+/// Same fields as `solana_bank::state::Bank`, behind Anchor's 8-byte discriminator.
+#[account]
+#[derive(Default, Debug, PartialEq, InitSpace)]
+pub struct Bank {
+    pub decimals: u8,
+    pub bank_owner: Pubkey,
+    pub is_opened: bool,
+    pub total_supply: u64,
+}
+
+/// Same fields as `solana_bank::state::Account`; `BankAccount` so it doesn't shadow
+/// Anchor's `Account` wrapper.
+#[account]
+#[derive(Default, Debug, PartialEq, InitSpace)]
+pub struct BankAccount {
+    pub amount: u64,
+    pub is_opened: bool,
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    pub delegate: Option<Pubkey>,
+    pub delegated_amount: u64,
+    pub bank: Pubkey,
+}
+
+impl BankAccount {
+    pub fn can_trade(&self) -> bool {
+        self.is_opened && self.is_initialized
+    }
+
+    /// Whether `signer` acts as the account's delegate rather than its owner, as
+    /// `solana_bank::processor::Processor::validate_owner` decides.
+    pub fn is_delegate(&self, signer: &Pubkey) -> Result<bool> {
+        if self.delegate == Some(*signer) {
+            return Ok(true);
+        }
+        require_keys_eq!(self.owner, *signer, crate::error::BankError::Unauthorized);
+        Ok(false)
+    }
+}
@@ -0,0 +1,347 @@
+use anchor_lang::{
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        system_program,
+    },
+    AccountDeserialize, InstructionData, ToAccountMetas,
+};
+use solana_bank_anchor::{
+    accounts, error::BankError, instruction as bank_instruction, state::BankAccount,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+/// `entry` wants the accounts slice to live as long as the accounts' data, which
+/// `processor!` can't promise; leaking the slice for the length of a test is harmless.
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    solana_bank_anchor::entry(program_id, accounts, data)
+}
+
+fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: solana_bank_anchor::id(),
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn custom(error: BankError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error.into()),
+    ))
+}
+
+/// A bank owned by the payer, with alice's and bob's accounts in it.
+struct Fixture {
+    context: ProgramTestContext,
+    bank: Pubkey,
+    alice: Keypair,
+    alice_account: Pubkey,
+    bob: Keypair,
+    bob_account: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let program_test = ProgramTest::new(
+            "solana_bank_anchor",
+            solana_bank_anchor::id(),
+            processor!(process_instruction),
+        );
+        let mut context = program_test.start_with_context().await;
+        let bank = Keypair::new();
+        let instruction = instruction(
+            accounts::InitializeBank {
+                bank: bank.pubkey(),
+                bank_owner: context.payer.pubkey(),
+                system_program: system_program::id(),
+            },
+            bank_instruction::InitializeBank { decimals: 2 },
+        );
+        process(&mut context, &[instruction], &[&bank])
+            .await
+            .unwrap();
+
+        let mut fixture = Fixture {
+            context,
+            bank: bank.pubkey(),
+            alice: Keypair::new(),
+            alice_account: Pubkey::default(),
+            bob: Keypair::new(),
+            bob_account: Pubkey::default(),
+        };
+        let alice = Keypair::from_bytes(&fixture.alice.to_bytes()).unwrap();
+        fixture.alice_account = fixture.open_account(&alice, &bank.pubkey()).await;
+        let bob = Keypair::from_bytes(&fixture.bob.to_bytes()).unwrap();
+        fixture.bob_account = fixture.open_account(&bob, &bank.pubkey()).await;
+        fixture
+    }
+
+    /// Funds `owner` and opens an account for them in `bank`.
+    async fn open_account(&mut self, owner: &Keypair, bank: &Pubkey) -> Pubkey {
+        let account = Keypair::new();
+        let instructions = [
+            anchor_lang::solana_program::system_instruction::transfer(
+                &self.context.payer.pubkey(),
+                &owner.pubkey(),
+                100_000_000,
+            ),
+            instruction(
+                accounts::InitializeAccount {
+                    bank: *bank,
+                    account: account.pubkey(),
+                    owner: owner.pubkey(),
+                    system_program: system_program::id(),
+                },
+                bank_instruction::InitializeAccount {},
+            ),
+        ];
+        self.process(&instructions, &[owner, &account])
+            .await
+            .unwrap();
+        account.pubkey()
+    }
+
+    async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), TransactionError> {
+        process(&mut self.context, instructions, signers).await
+    }
+
+    async fn account(&mut self, account: &Pubkey) -> BankAccount {
+        let data = self
+            .context
+            .banks_client
+            .get_account(*account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+        BankAccount::try_deserialize(&mut data.as_slice()).unwrap()
+    }
+
+    async fn mint_to(&mut self, account: &Pubkey, amount: u64) -> Result<(), TransactionError> {
+        let instruction = instruction(
+            accounts::MintTo {
+                bank: self.bank,
+                account: *account,
+                bank_owner: self.context.payer.pubkey(),
+            },
+            bank_instruction::MintTo { amount },
+        );
+        self.process(&[instruction], &[]).await
+    }
+
+    /// Moves `amount` from alice's account to bob's, signed by `signer`.
+    async fn transfer(&mut self, signer: &Keypair, amount: u64) -> Result<(), TransactionError> {
+        let instruction = instruction(
+            accounts::Transfer {
+                from: self.alice_account,
+                to: self.bob_account,
+                owner: signer.pubkey(),
+                bank: self.bank,
+            },
+            bank_instruction::Transfer { amount },
+        );
+        self.process(&[instruction], &[signer]).await
+    }
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|e| e.unwrap())
+}
+
+#[tokio::test]
+async fn test_mint_and_transfer() {
+    let mut fixture = Fixture::new().await;
+    let (alice_account, bob_account) = (fixture.alice_account, fixture.bob_account);
+    let account = fixture.account(&alice_account).await;
+    assert_eq!(
+        (account.owner, account.bank, account.can_trade()),
+        (fixture.alice.pubkey(), fixture.bank, true)
+    );
+
+    fixture.mint_to(&alice_account, 100).await.unwrap();
+    let alice = Keypair::from_bytes(&fixture.alice.to_bytes()).unwrap();
+    fixture.transfer(&alice, 30).await.unwrap();
+    assert_eq!(fixture.account(&alice_account).await.amount, 70);
+    assert_eq!(fixture.account(&bob_account).await.amount, 30);
+
+    assert_eq!(
+        fixture.transfer(&alice, 71).await,
+        custom(BankError::InsufficientFunds)
+    );
+    let bob = Keypair::from_bytes(&fixture.bob.to_bytes()).unwrap();
+    assert_eq!(
+        fixture.transfer(&bob, 1).await,
+        custom(BankError::Unauthorized)
+    );
+}
+
+#[tokio::test]
+async fn test_approve_and_revoke() {
+    let mut fixture = Fixture::new().await;
+    let alice_account = fixture.alice_account;
+    fixture.mint_to(&alice_account, 100).await.unwrap();
+    let alice = Keypair::from_bytes(&fixture.alice.to_bytes()).unwrap();
+    let bob = Keypair::from_bytes(&fixture.bob.to_bytes()).unwrap();
+
+    let approve = instruction(
+        accounts::Approve {
+            account: alice_account,
+            delegate: bob.pubkey(),
+            owner: alice.pubkey(),
+            bank: fixture.bank,
+        },
+        bank_instruction::Approve { amount: 40 },
+    );
+    fixture.process(&[approve], &[&alice]).await.unwrap();
+    fixture.transfer(&bob, 25).await.unwrap();
+    let account = fixture.account(&alice_account).await;
+    assert_eq!(
+        (account.amount, account.delegated_amount, account.delegate),
+        (75, 15, Some(bob.pubkey()))
+    );
+    assert_eq!(
+        fixture.transfer(&bob, 16).await,
+        custom(BankError::InsufficientFunds)
+    );
+
+    let revoke = instruction(
+        accounts::Revoke {
+            account: alice_account,
+            owner: alice.pubkey(),
+            bank: fixture.bank,
+        },
+        bank_instruction::Revoke {},
+    );
+    fixture.process(&[revoke], &[&alice]).await.unwrap();
+    let account = fixture.account(&alice_account).await;
+    assert_eq!(
+        (account.amount, account.delegated_amount, account.delegate),
+        (75, 0, None)
+    );
+}
+
+#[tokio::test]
+async fn test_burn_and_close() {
+    let mut fixture = Fixture::new().await;
+    let alice_account = fixture.alice_account;
+    fixture.mint_to(&alice_account, 10).await.unwrap();
+    let alice = Keypair::from_bytes(&fixture.alice.to_bytes()).unwrap();
+
+    let burn = instruction(
+        accounts::Burn {
+            bank: fixture.bank,
+            account: alice_account,
+            bank_owner: fixture.context.payer.pubkey(),
+            owner: alice.pubkey(),
+        },
+        bank_instruction::Burn { amount: 10 },
+    );
+    fixture.process(&[burn], &[&alice]).await.unwrap();
+    assert_eq!(fixture.account(&alice_account).await.amount, 0);
+
+    let destination = Pubkey::new_unique();
+    let close = instruction(
+        accounts::CloseAccount {
+            account: alice_account,
+            destination,
+            owner: alice.pubkey(),
+            bank: fixture.bank,
+        },
+        bank_instruction::CloseAccount {},
+    );
+    fixture.process(&[close], &[&alice]).await.unwrap();
+    assert!(fixture
+        .context
+        .banks_client
+        .get_account(alice_account)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(
+        fixture
+            .context
+            .banks_client
+            .get_balance(destination)
+            .await
+            .unwrap()
+            > 0
+    );
+}
+
+#[tokio::test]
+async fn test_bank_mismatch() {
+    let mut fixture = Fixture::new().await;
+    let other_bank = Keypair::new();
+    let initialize_bank = instruction(
+        accounts::InitializeBank {
+            bank: other_bank.pubkey(),
+            bank_owner: fixture.context.payer.pubkey(),
+            system_program: system_program::id(),
+        },
+        bank_instruction::InitializeBank { decimals: 0 },
+    );
+    fixture
+        .process(&[initialize_bank], &[&other_bank])
+        .await
+        .unwrap();
+    let carol = Keypair::new();
+    let carol_account = fixture.open_account(&carol, &other_bank.pubkey()).await;
+
+    assert_eq!(
+        fixture.mint_to(&carol_account, 1).await,
+        custom(BankError::BankMismatch)
+    );
+    let alice = Keypair::from_bytes(&fixture.alice.to_bytes()).unwrap();
+    // Into another bank's account, or naming a bank the accounts aren't in.
+    for (to, bank) in [
+        (carol_account, fixture.bank),
+        (fixture.bob_account, other_bank.pubkey()),
+    ] {
+        let transfer = instruction(
+            accounts::Transfer {
+                from: fixture.alice_account,
+                to,
+                owner: fixture.alice.pubkey(),
+                bank,
+            },
+            bank_instruction::Transfer { amount: 0 },
+        );
+        assert_eq!(
+            fixture.process(&[transfer], &[&alice]).await,
+            custom(BankError::BankMismatch)
+        );
+    }
+}
@@ -0,0 +1,640 @@
+#ifndef SOLANA_BANK_H
+#define SOLANA_BANK_H
+
+/* Generated by `cargo xtask header` from bank/ffi; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The longest instruction data, so a buffer of this size fits any instruction: an
+ * `AssignToProgram` with 16 seeds of 32 bytes.
+ */
+#define BANK_INSTRUCTION_MAX_LEN 530
+
+/**
+ * Size of a bank's data.
+ */
+#define BANK_LEN 220
+
+/**
+ * Size of an account's data.
+ */
+#define BANK_ACCOUNT_LEN 135
+
+typedef enum BankStatus {
+  BANK_STATUS_OK = 0,
+  /**
+   * A pointer argument was null.
+   */
+  BANK_STATUS_NULL_POINTER = 1,
+  /**
+   * The output buffer is shorter than the instruction data.
+   */
+  BANK_STATUS_BUFFER_TOO_SMALL = 2,
+  /**
+   * The data isn't an initialized bank or account, or a close policy isn't one of
+   * `ClosePolicy`'s.
+   */
+  BANK_STATUS_INVALID_DATA = 3,
+} BankStatus;
+
+typedef struct BankState {
+  uint8_t decimals;
+  uint8_t bank_owner[32];
+  bool is_opened;
+  uint64_t total_supply;
+  /**
+   * A `ClosePolicy`: 0 rejects, 1 sweeps, 2 burns.
+   */
+  uint8_t close_policy;
+  bool require_rent_exempt;
+  /**
+   * A `BurnPolicy`: 0 needs the bank owner to sign burns too, 1 the holder alone.
+   */
+  uint8_t burn_policy;
+  /**
+   * Lamports per token `DepositSol` and `WithdrawSol` trade at; 0 if the bank doesn't
+   * take SOL.
+   */
+  uint64_t lamports_per_token;
+  /**
+   * Transfers between accounts of the bank, one per `TransferRoute` hop.
+   */
+  uint64_t transfer_count;
+  /**
+   * Tokens those transfers moved.
+   */
+  uint64_t cumulative_volume;
+  /**
+   * Accounts of the bank holding tokens.
+   */
+  uint64_t holder_count;
+  /**
+   * Accounts appended to the bank's registry.
+   */
+  uint64_t registered_accounts;
+  /**
+   * Balances below it are dust; 0 makes none.
+   */
+  uint64_t dust_threshold;
+  /**
+   * Whether `freeze_authority` holds a key; it is zeroed otherwise.
+   */
+  bool has_freeze_authority;
+  uint8_t freeze_authority[32];
+  /**
+   * Whether the bank owner paused the bank's transfers, approvals, mints and burns.
+   */
+  bool is_paused;
+  /**
+   * Yearly interest the bank pays on balances, in basis points; 0 pays none.
+   */
+  uint16_t interest_rate_bps;
+  /**
+   * Whether `emergency_authority` holds a key; it is zeroed otherwise.
+   */
+  bool has_emergency_authority;
+  uint8_t emergency_authority[32];
+  /**
+   * Slots a bank owner change waits before it can be applied; 0 applies it at once.
+   */
+  uint64_t owner_change_delay;
+  /**
+   * Whether `pending_bank_owner` holds a key; it is zeroed otherwise.
+   */
+  bool has_pending_bank_owner;
+  uint8_t pending_bank_owner[32];
+  /**
+   * Slot from which the pending bank owner change can be applied.
+   */
+  uint64_t owner_change_slot;
+} BankState;
+
+typedef struct BankAccountState {
+  uint64_t amount;
+  bool is_opened;
+  bool is_initialized;
+  uint8_t owner[32];
+  /**
+   * Whether `delegate` holds a key; it is zeroed otherwise.
+   */
+  bool has_delegate;
+  uint8_t delegate[32];
+  uint64_t delegated_amount;
+  uint8_t bank[32];
+  uint64_t permit_nonce;
+  bool is_frozen;
+  /**
+   * Unix timestamp the account last accrued interest at; 0 until it first does.
+   */
+  int64_t last_accrual;
+} BankAccountState;
+
+/**
+ * Writes the data of `InitializeBank`, for a bank with `BurnPolicy::OwnerAndHolder` that
+ * doesn't take SOL, has no freeze authority and pays no interest, to `out` and its length
+ * to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_initialize_bank_data(uint8_t decimals,
+                                          uint8_t *out,
+                                          size_t out_len,
+                                          size_t *written);
+
+/**
+ * Writes the data of `InitializeAccount` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_initialize_account_data(uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `Transfer`, without sweeping dust, to `out` and its length to
+ * `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_transfer_data(uint64_t amount, uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `TransferWithExpiry` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_transfer_with_expiry_data(uint64_t amount,
+                                               uint64_t max_slot,
+                                               uint8_t *out,
+                                               size_t out_len,
+                                               size_t *written);
+
+/**
+ * Writes the data of `TransferRoute` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_transfer_route_data(uint64_t amount,
+                                         uint8_t *out,
+                                         size_t out_len,
+                                         size_t *written);
+
+/**
+ * Writes the data of `AssignToProgram` to `out` and its length to `written`. The
+ * `seed_count` seeds are given back to back in `seeds`, the length of each in
+ * `seed_lens`; more than 16 seeds, or one longer than 32 bytes, is `InvalidData`.
+ *
+ * # Safety
+ *
+ * `seed_lens` must be valid for `seed_count` bytes of reads and `seeds` for their sum,
+ * `out` for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_assign_to_program_data(const uint8_t *seeds,
+                                            const uint8_t *seed_lens,
+                                            size_t seed_count,
+                                            uint8_t *out,
+                                            size_t out_len,
+                                            size_t *written);
+
+/**
+ * Writes the data of `Approve` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_approve_data(uint64_t amount, uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `MintTo` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_mint_to_data(uint64_t amount, uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `Burn` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_burn_data(uint64_t amount, uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `CloseAccount` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_close_account_data(uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `Revoke` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_revoke_data(uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `SetClosePolicy` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_set_close_policy_data(uint8_t policy,
+                                           uint8_t *out,
+                                           size_t out_len,
+                                           size_t *written);
+
+/**
+ * Writes the data of `SetRequireRentExempt` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_set_require_rent_exempt_data(bool required,
+                                                  uint8_t *out,
+                                                  size_t out_len,
+                                                  size_t *written);
+
+/**
+ * Writes the data of `PermitApprove` to `out` and its length to `written`. The permit's
+ * signature goes in an ed25519 program instruction before it; see `solana_bank::permit`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_permit_approve_data(uint64_t amount,
+                                         uint64_t nonce,
+                                         int64_t expiry,
+                                         uint8_t *out,
+                                         size_t out_len,
+                                         size_t *written);
+
+/**
+ * Writes the data of `DepositSol` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_deposit_sol_data(uint64_t lamports,
+                                      uint8_t *out,
+                                      size_t out_len,
+                                      size_t *written);
+
+/**
+ * Writes the data of `WithdrawSol` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_withdraw_sol_data(uint64_t lamports,
+                                       uint8_t *out,
+                                       size_t out_len,
+                                       size_t *written);
+
+/**
+ * Writes the data of `SetExchangeRate` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_set_exchange_rate_data(uint64_t numerator,
+                                            uint64_t denominator,
+                                            uint8_t *out,
+                                            size_t out_len,
+                                            size_t *written);
+
+/**
+ * Writes the data of `Convert` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_convert_data(uint64_t amount, uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `SetDustThreshold` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_set_dust_threshold_data(uint64_t threshold,
+                                             uint8_t *out,
+                                             size_t out_len,
+                                             size_t *written);
+
+/**
+ * Writes the data of `CloseDustAccount` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_close_dust_account_data(uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `SetGuardians` to `out` and its length to `written`. The
+ * `guardian_count` guardians' keys are given back to back in `guardians`; more than 8 is
+ * `InvalidData`.
+ *
+ * # Safety
+ *
+ * `guardians` must be valid for `guardian_count` times 32 bytes of reads, `out` for
+ * `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_set_guardians_data(uint8_t threshold,
+                                        uint64_t delay,
+                                        const uint8_t *guardians,
+                                        size_t guardian_count,
+                                        uint8_t *out,
+                                        size_t out_len,
+                                        size_t *written);
+
+/**
+ * Writes the data of `StartRecovery` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `new_owner` must be valid for 32 bytes of reads, `out` for `out_len` bytes of writes
+ * and `written` for one `size_t`.
+ */
+enum BankStatus bank_start_recovery_data(const uint8_t *new_owner,
+                                         uint8_t *out,
+                                         size_t out_len,
+                                         size_t *written);
+
+/**
+ * Writes the data of `RecoverOwner` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_recover_owner_data(uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `CancelRecovery` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_cancel_recovery_data(uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `CreateSessionKey` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `session_key` must be valid for 32 bytes of reads, `out` for `out_len` bytes of writes
+ * and `written` for one `size_t`.
+ */
+enum BankStatus bank_create_session_key_data(const uint8_t *session_key,
+                                             uint64_t max_spend,
+                                             uint64_t expiry_slot,
+                                             uint8_t *out,
+                                             size_t out_len,
+                                             size_t *written);
+
+/**
+ * Writes the data of `SetRateLimit` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_set_rate_limit_data(uint64_t capacity,
+                                         uint64_t refill_per_slot,
+                                         uint8_t *out,
+                                         size_t out_len,
+                                         size_t *written);
+
+/**
+ * Writes the data of `InitializeVersion` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_initialize_version_data(uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `SetFeatures` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_set_features_data(uint64_t enabled,
+                                       uint8_t *out,
+                                       size_t out_len,
+                                       size_t *written);
+
+/**
+ * Writes the data of `FreezeAccount` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_freeze_account_data(uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `ThawAccount` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_thaw_account_data(uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `InitializeMultisig` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_initialize_multisig_data(uint8_t threshold,
+                                              uint8_t *out,
+                                              size_t out_len,
+                                              size_t *written);
+
+/**
+ * Writes the data of `InitializeAssociatedAccount` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_initialize_associated_account_data(uint8_t *out,
+                                                        size_t out_len,
+                                                        size_t *written);
+
+/**
+ * Writes the data of `TransferChecked` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_transfer_checked_data(uint64_t amount,
+                                           uint8_t decimals,
+                                           uint8_t *out,
+                                           size_t out_len,
+                                           size_t *written);
+
+/**
+ * Writes the data of `MintToChecked` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_mint_to_checked_data(uint64_t amount,
+                                          uint8_t decimals,
+                                          uint8_t *out,
+                                          size_t out_len,
+                                          size_t *written);
+
+/**
+ * Writes the data of `BurnChecked` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_burn_checked_data(uint64_t amount,
+                                       uint8_t decimals,
+                                       uint8_t *out,
+                                       size_t out_len,
+                                       size_t *written);
+
+/**
+ * Writes the data of `SetAuthority` to `out` and its length to `written`; a null
+ * `new_authority` removes the authority.
+ *
+ * # Safety
+ *
+ * `new_authority` must be null or valid for 32 bytes of reads, `out` for `out_len`
+ * bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_set_authority_data(uint8_t authority_type,
+                                        const uint8_t *new_authority,
+                                        uint8_t *out,
+                                        size_t out_len,
+                                        size_t *written);
+
+/**
+ * Writes the data of `TransferBatch` to `out` and its length to `written`, paying
+ * `amount_count` amounts; none, or more than 64, is `InvalidData`.
+ *
+ * # Safety
+ *
+ * `amounts` must be valid for `amount_count` reads of a `uint64_t`, `out` for `out_len`
+ * bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_transfer_batch_data(const uint64_t *amounts,
+                                         size_t amount_count,
+                                         uint8_t *out,
+                                         size_t out_len,
+                                         size_t *written);
+
+/**
+ * Writes the data of `PauseBank` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_pause_bank_data(uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `ResumeBank` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_resume_bank_data(uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `AccrueInterest` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_accrue_interest_data(uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Writes the data of `SetOwnerChangeDelay` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_set_owner_change_delay_data(uint64_t delay,
+                                                 uint8_t *out,
+                                                 size_t out_len,
+                                                 size_t *written);
+
+/**
+ * Writes the data of `CancelOwnerChange` to `out` and its length to `written`.
+ *
+ * # Safety
+ *
+ * `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+ */
+enum BankStatus bank_cancel_owner_change_data(uint8_t *out, size_t out_len, size_t *written);
+
+/**
+ * Decodes `BANK_LEN` bytes of bank data into `out`.
+ *
+ * # Safety
+ *
+ * `data` must be valid for `data_len` bytes of reads and `out` for one `BankState`.
+ */
+enum BankStatus bank_decode_bank(const uint8_t *data, size_t data_len, struct BankState *out);
+
+/**
+ * Decodes account data of at least `BANK_ACCOUNT_LEN` bytes, the rest being the
+ * account's transfer history, into `out`.
+ *
+ * # Safety
+ *
+ * `data` must be valid for `data_len` bytes of reads and `out` for one `BankAccountState`.
+ */
+enum BankStatus bank_decode_account(const uint8_t *data,
+                                    size_t data_len,
+                                    struct BankAccountState *out);
+
+#endif /* SOLANA_BANK_H */
@@ -0,0 +1,1316 @@
+//! C bindings of the bank program: the data of each instruction and decoders of the bank
+//! and account layouts, for backends that build transactions with their own Solana
+//! library. Account metas aren't covered; each instruction's accounts are listed on
+//! `BankInstruction`. Every function returns a `BankStatus` and writes its result through
+//! the last pointer only on `BANK_STATUS_OK`.
+
+use solana_bank::{
+    instruction::{AuthorityType, BankInstruction, MAX_BATCH_TRANSFERS},
+    recovery::MAX_GUARDIANS,
+    solana_program::{
+        program_option::COption,
+        program_pack::Pack,
+        pubkey::{Pubkey, MAX_SEEDS, MAX_SEED_LEN},
+    },
+    state::{Account, Bank, BurnPolicy, ClosePolicy},
+};
+use std::{convert::TryFrom, ptr, slice};
+
+/// The longest instruction data, so a buffer of this size fits any instruction: an
+/// `AssignToProgram` with 16 seeds of 32 bytes.
+pub const BANK_INSTRUCTION_MAX_LEN: usize = 530;
+/// Size of a bank's data.
+pub const BANK_LEN: usize = 220;
+/// Size of an account's data.
+pub const BANK_ACCOUNT_LEN: usize = 135;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BankStatus {
+    Ok = 0,
+    /// A pointer argument was null.
+    NullPointer = 1,
+    /// The output buffer is shorter than the instruction data.
+    BufferTooSmall = 2,
+    /// The data isn't an initialized bank or account, or a close policy isn't one of
+    /// `ClosePolicy`'s.
+    InvalidData = 3,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BankState {
+    pub decimals: u8,
+    pub bank_owner: [u8; 32],
+    pub is_opened: bool,
+    pub total_supply: u64,
+    /// A `ClosePolicy`: 0 rejects, 1 sweeps, 2 burns.
+    pub close_policy: u8,
+    pub require_rent_exempt: bool,
+    /// A `BurnPolicy`: 0 needs the bank owner to sign burns too, 1 the holder alone.
+    pub burn_policy: u8,
+    /// Lamports per token `DepositSol` and `WithdrawSol` trade at; 0 if the bank doesn't
+    /// take SOL.
+    pub lamports_per_token: u64,
+    /// Transfers between accounts of the bank, one per `TransferRoute` hop.
+    pub transfer_count: u64,
+    /// Tokens those transfers moved.
+    pub cumulative_volume: u64,
+    /// Accounts of the bank holding tokens.
+    pub holder_count: u64,
+    /// Accounts appended to the bank's registry.
+    pub registered_accounts: u64,
+    /// Balances below it are dust; 0 makes none.
+    pub dust_threshold: u64,
+    /// Whether `freeze_authority` holds a key; it is zeroed otherwise.
+    pub has_freeze_authority: bool,
+    pub freeze_authority: [u8; 32],
+    /// Whether the bank owner paused the bank's transfers, approvals, mints and burns.
+    pub is_paused: bool,
+    /// Yearly interest the bank pays on balances, in basis points; 0 pays none.
+    pub interest_rate_bps: u16,
+    /// Whether `emergency_authority` holds a key; it is zeroed otherwise.
+    pub has_emergency_authority: bool,
+    pub emergency_authority: [u8; 32],
+    /// Slots a bank owner change waits before it can be applied; 0 applies it at once.
+    pub owner_change_delay: u64,
+    /// Whether `pending_bank_owner` holds a key; it is zeroed otherwise.
+    pub has_pending_bank_owner: bool,
+    pub pending_bank_owner: [u8; 32],
+    /// Slot from which the pending bank owner change can be applied.
+    pub owner_change_slot: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BankAccountState {
+    pub amount: u64,
+    pub is_opened: bool,
+    pub is_initialized: bool,
+    pub owner: [u8; 32],
+    /// Whether `delegate` holds a key; it is zeroed otherwise.
+    pub has_delegate: bool,
+    pub delegate: [u8; 32],
+    pub delegated_amount: u64,
+    pub bank: [u8; 32],
+    pub permit_nonce: u64,
+    pub is_frozen: bool,
+    /// Unix timestamp the account last accrued interest at; 0 until it first does.
+    pub last_accrual: i64,
+}
+
+unsafe fn write_data(
+    instruction: BankInstruction,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    if out.is_null() || written.is_null() {
+        return BankStatus::NullPointer;
+    }
+    let data = instruction.pack();
+    if data.len() > out_len {
+        return BankStatus::BufferTooSmall;
+    }
+    ptr::copy_nonoverlapping(data.as_ptr(), out, data.len());
+    *written = data.len();
+    BankStatus::Ok
+}
+
+/// Writes the data of `InitializeBank`, for a bank with `BurnPolicy::OwnerAndHolder` that
+/// doesn't take SOL, has no freeze authority and pays no interest, to `out` and its length
+/// to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_initialize_bank_data(
+    decimals: u8,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::InitializeBank {
+            decimals,
+            burn_policy: BurnPolicy::OwnerAndHolder,
+            lamports_per_token: 0,
+            freeze_authority: COption::None,
+            interest_rate_bps: 0,
+        },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `InitializeAccount` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_initialize_account_data(
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::InitializeAccount, out, out_len, written)
+}
+
+/// Writes the data of `Transfer`, without sweeping dust, to `out` and its length to
+/// `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_transfer_data(
+    amount: u64,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::Transfer {
+            amount,
+            sweep_dust: false,
+        },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `TransferWithExpiry` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_transfer_with_expiry_data(
+    amount: u64,
+    max_slot: u64,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::TransferWithExpiry { amount, max_slot },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `TransferRoute` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_transfer_route_data(
+    amount: u64,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::TransferRoute { amount },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `AssignToProgram` to `out` and its length to `written`. The
+/// `seed_count` seeds are given back to back in `seeds`, the length of each in
+/// `seed_lens`; more than 16 seeds, or one longer than 32 bytes, is `InvalidData`.
+///
+/// # Safety
+///
+/// `seed_lens` must be valid for `seed_count` bytes of reads and `seeds` for their sum,
+/// `out` for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_assign_to_program_data(
+    seeds: *const u8,
+    seed_lens: *const u8,
+    seed_count: usize,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    if seed_count > MAX_SEEDS {
+        return BankStatus::InvalidData;
+    }
+    if seed_count > 0 && (seeds.is_null() || seed_lens.is_null()) {
+        return BankStatus::NullPointer;
+    }
+    let lens = match seed_count {
+        0 => &[][..],
+        _ => slice::from_raw_parts(seed_lens, seed_count),
+    };
+    if lens.iter().any(|&len| len as usize > MAX_SEED_LEN) {
+        return BankStatus::InvalidData;
+    }
+    let mut offset = 0;
+    let seeds = lens
+        .iter()
+        .map(|&len| {
+            let seed = slice::from_raw_parts(seeds.add(offset), len as usize).to_vec();
+            offset += len as usize;
+            seed
+        })
+        .collect();
+    write_data(
+        BankInstruction::AssignToProgram { seeds },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `Approve` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_approve_data(
+    amount: u64,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::Approve { amount }, out, out_len, written)
+}
+
+/// Writes the data of `MintTo` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_mint_to_data(
+    amount: u64,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::MintTo { amount }, out, out_len, written)
+}
+
+/// Writes the data of `Burn` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_burn_data(
+    amount: u64,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::Burn { amount }, out, out_len, written)
+}
+
+/// Writes the data of `CloseAccount` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_close_account_data(
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::CloseAccount, out, out_len, written)
+}
+
+/// Writes the data of `Revoke` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_revoke_data(
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::Revoke, out, out_len, written)
+}
+
+/// Writes the data of `SetClosePolicy` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_set_close_policy_data(
+    policy: u8,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    let policy = match ClosePolicy::try_from(policy) {
+        Ok(policy) => policy,
+        Err(_) => return BankStatus::InvalidData,
+    };
+    write_data(
+        BankInstruction::SetClosePolicy { policy },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `SetRequireRentExempt` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_set_require_rent_exempt_data(
+    required: bool,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::SetRequireRentExempt { required },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `PermitApprove` to `out` and its length to `written`. The permit's
+/// signature goes in an ed25519 program instruction before it; see `solana_bank::permit`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_permit_approve_data(
+    amount: u64,
+    nonce: u64,
+    expiry: i64,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::PermitApprove {
+            amount,
+            nonce,
+            expiry,
+        },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `DepositSol` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_deposit_sol_data(
+    lamports: u64,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::DepositSol { lamports },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `WithdrawSol` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_withdraw_sol_data(
+    lamports: u64,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::WithdrawSol { lamports },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `SetExchangeRate` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_set_exchange_rate_data(
+    numerator: u64,
+    denominator: u64,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::SetExchangeRate {
+            numerator,
+            denominator,
+        },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `Convert` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_convert_data(
+    amount: u64,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::Convert { amount }, out, out_len, written)
+}
+
+/// Writes the data of `SetDustThreshold` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_set_dust_threshold_data(
+    threshold: u64,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::SetDustThreshold { threshold },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `CloseDustAccount` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_close_dust_account_data(
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::CloseDustAccount, out, out_len, written)
+}
+
+/// Writes the data of `SetGuardians` to `out` and its length to `written`. The
+/// `guardian_count` guardians' keys are given back to back in `guardians`; more than 8 is
+/// `InvalidData`.
+///
+/// # Safety
+///
+/// `guardians` must be valid for `guardian_count` times 32 bytes of reads, `out` for
+/// `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_set_guardians_data(
+    threshold: u8,
+    delay: u64,
+    guardians: *const u8,
+    guardian_count: usize,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    if guardian_count > MAX_GUARDIANS {
+        return BankStatus::InvalidData;
+    }
+    if guardian_count > 0 && guardians.is_null() {
+        return BankStatus::NullPointer;
+    }
+    let guardians = match guardian_count {
+        0 => Vec::new(),
+        _ => slice::from_raw_parts(guardians, guardian_count * 32)
+            .chunks(32)
+            .map(Pubkey::new)
+            .collect(),
+    };
+    write_data(
+        BankInstruction::SetGuardians {
+            threshold,
+            delay,
+            guardians,
+        },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `StartRecovery` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `new_owner` must be valid for 32 bytes of reads, `out` for `out_len` bytes of writes
+/// and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_start_recovery_data(
+    new_owner: *const u8,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    if new_owner.is_null() {
+        return BankStatus::NullPointer;
+    }
+    write_data(
+        BankInstruction::StartRecovery {
+            new_owner: Pubkey::new(slice::from_raw_parts(new_owner, 32)),
+        },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `RecoverOwner` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_recover_owner_data(
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::RecoverOwner, out, out_len, written)
+}
+
+/// Writes the data of `CancelRecovery` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_cancel_recovery_data(
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::CancelRecovery, out, out_len, written)
+}
+
+/// Writes the data of `CreateSessionKey` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `session_key` must be valid for 32 bytes of reads, `out` for `out_len` bytes of writes
+/// and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_create_session_key_data(
+    session_key: *const u8,
+    max_spend: u64,
+    expiry_slot: u64,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    if session_key.is_null() {
+        return BankStatus::NullPointer;
+    }
+    write_data(
+        BankInstruction::CreateSessionKey {
+            session_key: Pubkey::new(slice::from_raw_parts(session_key, 32)),
+            max_spend,
+            expiry_slot,
+        },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `SetRateLimit` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_set_rate_limit_data(
+    capacity: u64,
+    refill_per_slot: u64,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::SetRateLimit {
+            capacity,
+            refill_per_slot,
+        },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `InitializeVersion` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_initialize_version_data(
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::InitializeVersion, out, out_len, written)
+}
+
+/// Writes the data of `SetFeatures` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_set_features_data(
+    enabled: u64,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::SetFeatures { enabled },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `FreezeAccount` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_freeze_account_data(
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::FreezeAccount, out, out_len, written)
+}
+
+/// Writes the data of `ThawAccount` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_thaw_account_data(
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::ThawAccount, out, out_len, written)
+}
+
+/// Writes the data of `InitializeMultisig` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_initialize_multisig_data(
+    threshold: u8,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::InitializeMultisig { threshold },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `InitializeAssociatedAccount` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_initialize_associated_account_data(
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::InitializeAssociatedAccount,
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `TransferChecked` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_transfer_checked_data(
+    amount: u64,
+    decimals: u8,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::TransferChecked { amount, decimals },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `MintToChecked` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_mint_to_checked_data(
+    amount: u64,
+    decimals: u8,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::MintToChecked { amount, decimals },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `BurnChecked` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_burn_checked_data(
+    amount: u64,
+    decimals: u8,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::BurnChecked { amount, decimals },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `SetAuthority` to `out` and its length to `written`; a null
+/// `new_authority` removes the authority.
+///
+/// # Safety
+///
+/// `new_authority` must be null or valid for 32 bytes of reads, `out` for `out_len`
+/// bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_set_authority_data(
+    authority_type: u8,
+    new_authority: *const u8,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    let authority_type = match AuthorityType::try_from(authority_type) {
+        Ok(authority_type) => authority_type,
+        Err(_) => return BankStatus::InvalidData,
+    };
+    let new_authority = if new_authority.is_null() {
+        COption::None
+    } else {
+        COption::Some(Pubkey::new(slice::from_raw_parts(new_authority, 32)))
+    };
+    write_data(
+        BankInstruction::SetAuthority {
+            authority_type,
+            new_authority,
+        },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `TransferBatch` to `out` and its length to `written`, paying
+/// `amount_count` amounts; none, or more than 64, is `InvalidData`.
+///
+/// # Safety
+///
+/// `amounts` must be valid for `amount_count` reads of a `uint64_t`, `out` for `out_len`
+/// bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_transfer_batch_data(
+    amounts: *const u64,
+    amount_count: usize,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    if amount_count == 0 || amount_count > MAX_BATCH_TRANSFERS {
+        return BankStatus::InvalidData;
+    }
+    if amounts.is_null() {
+        return BankStatus::NullPointer;
+    }
+    write_data(
+        BankInstruction::TransferBatch {
+            amounts: slice::from_raw_parts(amounts, amount_count).to_vec(),
+        },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `PauseBank` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_pause_bank_data(
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::PauseBank, out, out_len, written)
+}
+
+/// Writes the data of `ResumeBank` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_resume_bank_data(
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::ResumeBank, out, out_len, written)
+}
+
+/// Writes the data of `AccrueInterest` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_accrue_interest_data(
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::AccrueInterest, out, out_len, written)
+}
+
+/// Writes the data of `SetOwnerChangeDelay` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_set_owner_change_delay_data(
+    delay: u64,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(
+        BankInstruction::SetOwnerChangeDelay { delay },
+        out,
+        out_len,
+        written,
+    )
+}
+
+/// Writes the data of `CancelOwnerChange` to `out` and its length to `written`.
+///
+/// # Safety
+///
+/// `out` must be valid for `out_len` bytes of writes and `written` for one `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_cancel_owner_change_data(
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> BankStatus {
+    write_data(BankInstruction::CancelOwnerChange, out, out_len, written)
+}
+
+/// Decodes `BANK_LEN` bytes of bank data into `out`.
+///
+/// # Safety
+///
+/// `data` must be valid for `data_len` bytes of reads and `out` for one `BankState`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_decode_bank(
+    data: *const u8,
+    data_len: usize,
+    out: *mut BankState,
+) -> BankStatus {
+    if data.is_null() || out.is_null() {
+        return BankStatus::NullPointer;
+    }
+    let bank = match Bank::unpack(slice::from_raw_parts(data, data_len)) {
+        Ok(bank) => bank,
+        Err(_) => return BankStatus::InvalidData,
+    };
+    let (has_freeze_authority, freeze_authority) = match bank.freeze_authority {
+        COption::Some(authority) => (true, authority.to_bytes()),
+        COption::None => (false, [0; 32]),
+    };
+    let (has_emergency_authority, emergency_authority) = match bank.emergency_authority {
+        COption::Some(authority) => (true, authority.to_bytes()),
+        COption::None => (false, [0; 32]),
+    };
+    let (has_pending_bank_owner, pending_bank_owner) = match bank.pending_bank_owner {
+        COption::Some(owner) => (true, owner.to_bytes()),
+        COption::None => (false, [0; 32]),
+    };
+    *out = BankState {
+        decimals: bank.decimals,
+        bank_owner: bank.bank_owner.to_bytes(),
+        is_opened: bank.is_opened,
+        total_supply: bank.total_supply,
+        close_policy: bank.close_policy as u8,
+        require_rent_exempt: bank.require_rent_exempt,
+        burn_policy: bank.burn_policy as u8,
+        lamports_per_token: bank.lamports_per_token,
+        transfer_count: bank.transfer_count,
+        cumulative_volume: bank.cumulative_volume,
+        holder_count: bank.holder_count,
+        registered_accounts: bank.registered_accounts,
+        dust_threshold: bank.dust_threshold,
+        has_freeze_authority,
+        freeze_authority,
+        is_paused: bank.is_paused,
+        interest_rate_bps: bank.interest_rate_bps,
+        has_emergency_authority,
+        emergency_authority,
+        owner_change_delay: bank.owner_change_delay,
+        has_pending_bank_owner,
+        pending_bank_owner,
+        owner_change_slot: bank.owner_change_slot,
+    };
+    BankStatus::Ok
+}
+
+/// Decodes account data of at least `BANK_ACCOUNT_LEN` bytes, the rest being the
+/// account's transfer history, into `out`.
+///
+/// # Safety
+///
+/// `data` must be valid for `data_len` bytes of reads and `out` for one `BankAccountState`.
+#[no_mangle]
+pub unsafe extern "C" fn bank_decode_account(
+    data: *const u8,
+    data_len: usize,
+    out: *mut BankAccountState,
+) -> BankStatus {
+    if data.is_null() || out.is_null() {
+        return BankStatus::NullPointer;
+    }
+    let account = match Account::unpack_extended(slice::from_raw_parts(data, data_len)) {
+        Ok(account) => account,
+        Err(_) => return BankStatus::InvalidData,
+    };
+    let (has_delegate, delegate) = match account.delegate {
+        COption::Some(delegate) => (true, delegate.to_bytes()),
+        COption::None => (false, [0; 32]),
+    };
+    *out = BankAccountState {
+        amount: account.amount,
+        is_opened: account.is_opened,
+        is_initialized: account.is_initialized,
+        owner: account.owner.to_bytes(),
+        has_delegate,
+        delegate,
+        delegated_amount: account.delegated_amount,
+        bank: account.bank.to_bytes(),
+        permit_nonce: account.permit_nonce,
+        is_frozen: account.is_frozen,
+        last_accrual: account.last_accrual,
+    };
+    BankStatus::Ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instruction_data() {
+        assert_eq!(Bank::LEN, BANK_LEN);
+        assert_eq!(Account::LEN, BANK_ACCOUNT_LEN);
+
+        let mut out = [0u8; BANK_INSTRUCTION_MAX_LEN];
+        let mut written = 0;
+        let status = unsafe { bank_burn_data(u64::MAX, out.as_mut_ptr(), out.len(), &mut written) };
+        assert_eq!(status, BankStatus::Ok);
+        assert_eq!(
+            &out[..written],
+            &BankInstruction::Burn { amount: u64::MAX }.pack()[..]
+        );
+
+        let status = unsafe { bank_revoke_data(out.as_mut_ptr(), out.len(), &mut written) };
+        assert_eq!((status, &out[..written]), (BankStatus::Ok, &[7u8][..]));
+        let status = unsafe { bank_resume_bank_data(out.as_mut_ptr(), out.len(), &mut written) };
+        assert_eq!((status, &out[..written]), (BankStatus::Ok, &[38u8][..]));
+        let status =
+            unsafe { bank_accrue_interest_data(out.as_mut_ptr(), out.len(), &mut written) };
+        assert_eq!((status, &out[..written]), (BankStatus::Ok, &[39u8][..]));
+        let status = unsafe {
+            bank_set_owner_change_delay_data(150, out.as_mut_ptr(), out.len(), &mut written)
+        };
+        assert_eq!(
+            (status, &out[..written]),
+            (BankStatus::Ok, &[40u8, 150, 0, 0, 0, 0, 0, 0, 0][..])
+        );
+        let status =
+            unsafe { bank_cancel_owner_change_data(out.as_mut_ptr(), out.len(), &mut written) };
+        assert_eq!((status, &out[..written]), (BankStatus::Ok, &[41u8][..]));
+        let status = unsafe { bank_transfer_data(1, out.as_mut_ptr(), 8, &mut written) };
+        assert_eq!(status, BankStatus::BufferTooSmall);
+        let status = unsafe { bank_revoke_data(ptr::null_mut(), 0, &mut written) };
+        assert_eq!(status, BankStatus::NullPointer);
+
+        let status =
+            unsafe { bank_set_close_policy_data(2, out.as_mut_ptr(), out.len(), &mut written) };
+        assert_eq!((status, &out[..written]), (BankStatus::Ok, &[8u8, 2][..]));
+        let status =
+            unsafe { bank_set_close_policy_data(3, out.as_mut_ptr(), out.len(), &mut written) };
+        assert_eq!(status, BankStatus::InvalidData);
+        let amounts = [1, u64::MAX];
+        let status = unsafe {
+            bank_transfer_batch_data(
+                amounts.as_ptr(),
+                amounts.len(),
+                out.as_mut_ptr(),
+                out.len(),
+                &mut written,
+            )
+        };
+        assert_eq!((status, written, out[1]), (BankStatus::Ok, 18, 2));
+        let status = unsafe {
+            bank_transfer_batch_data(
+                amounts.as_ptr(),
+                0,
+                out.as_mut_ptr(),
+                out.len(),
+                &mut written,
+            )
+        };
+        assert_eq!(status, BankStatus::InvalidData);
+
+        let new_authority = [7u8; 32];
+        let status = unsafe {
+            bank_set_authority_data(
+                3,
+                new_authority.as_ptr(),
+                out.as_mut_ptr(),
+                out.len(),
+                &mut written,
+            )
+        };
+        assert_eq!(status, BankStatus::InvalidData);
+        let status = unsafe {
+            bank_set_authority_data(2, ptr::null(), out.as_mut_ptr(), out.len(), &mut written)
+        };
+        assert_eq!(
+            (status, &out[..written]),
+            (BankStatus::Ok, &[35u8, 2, 0][..])
+        );
+
+        let status = unsafe {
+            bank_set_require_rent_exempt_data(true, out.as_mut_ptr(), out.len(), &mut written)
+        };
+        assert_eq!((status, &out[..written]), (BankStatus::Ok, &[9u8, 1][..]));
+
+        let status = unsafe {
+            bank_permit_approve_data(1, 2, -1, out.as_mut_ptr(), out.len(), &mut written)
+        };
+        assert_eq!((status, written), (BankStatus::Ok, 25));
+
+        let (seeds, lens) = ([7u8; 16 * 32], [32u8; 16]);
+        let status = unsafe {
+            bank_assign_to_program_data(
+                seeds.as_ptr(),
+                lens.as_ptr(),
+                16,
+                out.as_mut_ptr(),
+                out.len(),
+                &mut written,
+            )
+        };
+        assert_eq!(
+            (status, written),
+            (BankStatus::Ok, BANK_INSTRUCTION_MAX_LEN)
+        );
+        let (seeds, lens) = (b"vaultx", [5u8, 1]);
+        let status = unsafe {
+            bank_assign_to_program_data(
+                seeds.as_ptr(),
+                lens.as_ptr(),
+                2,
+                out.as_mut_ptr(),
+                out.len(),
+                &mut written,
+            )
+        };
+        assert_eq!(status, BankStatus::Ok);
+        assert_eq!(
+            BankInstruction::unpack(&out[..written]),
+            Ok(BankInstruction::AssignToProgram {
+                seeds: vec![b"vault".to_vec(), b"x".to_vec()]
+            })
+        );
+        let status = unsafe {
+            bank_assign_to_program_data(
+                seeds.as_ptr(),
+                [33u8].as_ptr(),
+                1,
+                out.as_mut_ptr(),
+                out.len(),
+                &mut written,
+            )
+        };
+        assert_eq!(status, BankStatus::InvalidData);
+
+        let guardians = [5u8; 9 * 32];
+        let status = unsafe {
+            bank_set_guardians_data(
+                2,
+                10,
+                guardians.as_ptr(),
+                2,
+                out.as_mut_ptr(),
+                out.len(),
+                &mut written,
+            )
+        };
+        assert_eq!(status, BankStatus::Ok);
+        assert_eq!(
+            BankInstruction::unpack(&out[..written]),
+            Ok(BankInstruction::SetGuardians {
+                threshold: 2,
+                delay: 10,
+                guardians: vec![Pubkey::new_from_array([5; 32]); 2],
+            })
+        );
+        let status = unsafe {
+            bank_set_guardians_data(
+                2,
+                10,
+                guardians.as_ptr(),
+                9,
+                out.as_mut_ptr(),
+                out.len(),
+                &mut written,
+            )
+        };
+        assert_eq!(status, BankStatus::InvalidData);
+        let status = unsafe {
+            bank_start_recovery_data(
+                guardians.as_ptr(),
+                out.as_mut_ptr(),
+                out.len(),
+                &mut written,
+            )
+        };
+        assert_eq!((status, out[0], written), (BankStatus::Ok, 21, 33));
+    }
+
+    #[test]
+    fn test_decode() {
+        let account = Account {
+            amount: 70,
+            is_opened: true,
+            is_initialized: true,
+            owner: Pubkey::new_unique(),
+            delegate: COption::Some(Pubkey::new_unique()),
+            delegated_amount: 30,
+            bank: Pubkey::new_unique(),
+            permit_nonce: 4,
+            is_frozen: true,
+            last_accrual: 1_600_000_000,
+        };
+        let mut data = vec![0u8; Account::LEN];
+        Account::pack(account, &mut data).unwrap();
+
+        let mut out = BankAccountState::default();
+        let status = unsafe { bank_decode_account(data.as_ptr(), data.len(), &mut out) };
+        assert_eq!(status, BankStatus::Ok);
+        assert_eq!(
+            (
+                out.owner,
+                out.has_delegate,
+                out.delegate,
+                out.delegated_amount,
+                out.permit_nonce,
+                out.is_frozen,
+                out.last_accrual
+            ),
+            (
+                account.owner.to_bytes(),
+                true,
+                account.delegate.unwrap().to_bytes(),
+                30,
+                4,
+                true,
+                1_600_000_000
+            )
+        );
+
+        let mut bank = BankState::default();
+        let status = unsafe { bank_decode_bank(data.as_ptr(), data.len(), &mut bank) };
+        assert_eq!(status, BankStatus::InvalidData);
+    }
+}
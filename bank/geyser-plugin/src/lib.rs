@@ -0,0 +1,333 @@
+//! A Geyser plugin that forwards every update of the bank program's accounts, decoded,
+//! to Kafka or an HTTP endpoint, so a validator we run indexes the program without an
+//! RPC subscription in between. The validator loads it through `--geyser-plugin-config`
+//! with a JSON file like
+//!
+//! ```json
+//! { "libpath": "target/release/libgeyser_plugin_bank.so",
+//!   "program_id": "<base58>",
+//!   "sink": { "type": "http", "url": "http://indexer.internal/updates", "batch_size": 100 } }
+//! ```
+//!
+//! or, built with the `kafka` feature, `"sink": { "type": "kafka", "brokers":
+//! "localhost:9092", "topic": "bank-updates" }`. Updates are queued to a worker thread
+//! so the validator never waits on the sink; when the queue is full they are dropped
+//! and logged rather than stalling replay.
+
+mod sink;
+
+pub use sink::SinkConfig;
+
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use solana_bank::{
+    solana_program::{program_option::COption, program_pack::Pack, pubkey::Pubkey},
+    state::{Account, Bank},
+};
+use solana_geyser_plugin_interface::geyser_plugin_interface::{
+    GeyserPlugin, GeyserPluginError, ReplicaAccountInfoVersions, Result,
+};
+use std::{
+    convert::TryFrom,
+    fs,
+    str::FromStr,
+    sync::mpsc::{self, SyncSender, TrySendError},
+    thread::{self, JoinHandle},
+};
+
+fn default_queue_size() -> usize {
+    10_000
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Config {
+    pub program_id: String,
+    pub sink: SinkConfig,
+    /// Updates held for the sink before new ones are dropped.
+    #[serde(default = "default_queue_size")]
+    pub queue_size: usize,
+}
+
+/// A decoded bank program account as it is sent to the sink, keys in base58.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BankAccountUpdate {
+    Bank {
+        pubkey: String,
+        slot: u64,
+        write_version: u64,
+        /// Sent while the validator loads its snapshot, before live updates.
+        is_startup: bool,
+        decimals: u8,
+        bank_owner: String,
+        is_opened: bool,
+        total_supply: u64,
+    },
+    Account {
+        pubkey: String,
+        slot: u64,
+        write_version: u64,
+        is_startup: bool,
+        bank: String,
+        owner: String,
+        amount: u64,
+        delegate: Option<String>,
+        delegated_amount: u64,
+        is_opened: bool,
+    },
+}
+
+impl BankAccountUpdate {
+    pub fn pubkey(&self) -> &str {
+        match self {
+            BankAccountUpdate::Bank { pubkey, .. } | BankAccountUpdate::Account { pubkey, .. } => {
+                pubkey
+            }
+        }
+    }
+}
+
+/// Decodes program account data by its size, as `client::watch::decode_update` does;
+/// anything that isn't a packed `Bank` or `Account` is skipped.
+pub fn decode(
+    pubkey: &Pubkey,
+    data: &[u8],
+    slot: u64,
+    write_version: u64,
+    is_startup: bool,
+) -> Option<BankAccountUpdate> {
+    let pubkey = pubkey.to_string();
+    match data.len() {
+        Bank::LEN => Bank::unpack(data).ok().map(|bank| BankAccountUpdate::Bank {
+            pubkey,
+            slot,
+            write_version,
+            is_startup,
+            decimals: bank.decimals,
+            bank_owner: bank.bank_owner.to_string(),
+            is_opened: bank.is_opened,
+            total_supply: bank.total_supply,
+        }),
+        Account::LEN => Account::unpack(data)
+            .ok()
+            .map(|account| BankAccountUpdate::Account {
+                pubkey,
+                slot,
+                write_version,
+                is_startup,
+                bank: account.bank.to_string(),
+                owner: account.owner.to_string(),
+                amount: account.amount,
+                delegate: match account.delegate {
+                    COption::Some(delegate) => Some(delegate.to_string()),
+                    COption::None => None,
+                },
+                delegated_amount: account.delegated_amount,
+                is_opened: account.is_opened,
+            }),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct GeyserPluginBank {
+    program_id: Pubkey,
+    queue: Option<SyncSender<BankAccountUpdate>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl GeyserPluginBank {
+    /// Starts forwarding the updates of `program_id` to `sink`.
+    fn start(&mut self, program_id: Pubkey, mut sink: Box<dyn sink::Sink>, queue_size: usize) {
+        let (queue, updates) = mpsc::sync_channel(queue_size);
+        self.program_id = program_id;
+        self.queue = Some(queue);
+        self.worker = Some(thread::spawn(move || sink::forward(sink.as_mut(), updates)));
+    }
+}
+
+impl GeyserPlugin for GeyserPluginBank {
+    fn name(&self) -> &'static str {
+        "GeyserPluginBank"
+    }
+
+    fn setup_logger(&self, logger: &'static dyn log::Log, level: log::LevelFilter) -> Result<()> {
+        log::set_max_level(level);
+        // Already set when the plugin is reloaded.
+        let _ = log::set_logger(logger);
+        Ok(())
+    }
+
+    fn on_load(&mut self, config_file: &str, _is_reload: bool) -> Result<()> {
+        let contents = fs::read_to_string(config_file)?;
+        let config: Config = serde_json::from_str(&contents).map_err(|e| {
+            GeyserPluginError::ConfigFileReadError {
+                msg: format!("invalid config {}: {}", config_file, e),
+            }
+        })?;
+        let program_id = Pubkey::from_str(&config.program_id).map_err(|e| {
+            GeyserPluginError::ConfigFileReadError {
+                msg: format!("invalid program_id {}: {}", config.program_id, e),
+            }
+        })?;
+        let sink = config
+            .sink
+            .connect()
+            .map_err(|msg| GeyserPluginError::ConfigFileReadError { msg })?;
+        self.start(program_id, sink, config.queue_size);
+        info!("forwarding updates of {} to {:?}", program_id, config.sink);
+        Ok(())
+    }
+
+    fn on_unload(&mut self) {
+        // Dropping the queue lets the worker flush what is left and stop.
+        self.queue = None;
+        if let Some(worker) = self.worker.take() {
+            if worker.join().is_err() {
+                error!("sink worker panicked");
+            }
+        }
+    }
+
+    fn update_account(
+        &self,
+        account: ReplicaAccountInfoVersions,
+        slot: u64,
+        is_startup: bool,
+    ) -> Result<()> {
+        let (pubkey, owner, data, write_version) = match account {
+            ReplicaAccountInfoVersions::V0_0_1(a) => (a.pubkey, a.owner, a.data, a.write_version),
+            ReplicaAccountInfoVersions::V0_0_2(a) => (a.pubkey, a.owner, a.data, a.write_version),
+            ReplicaAccountInfoVersions::V0_0_3(a) => (a.pubkey, a.owner, a.data, a.write_version),
+        };
+        if owner != self.program_id.as_ref() {
+            return Ok(());
+        }
+        let pubkey =
+            Pubkey::try_from(pubkey).map_err(|_| GeyserPluginError::AccountsUpdateError {
+                msg: "invalid account pubkey".to_string(),
+            })?;
+        let update = match decode(&pubkey, data, slot, write_version, is_startup) {
+            Some(u) => u,
+            None => return Ok(()),
+        };
+        let queue = match &self.queue {
+            Some(q) => q,
+            None => return Ok(()),
+        };
+        match queue.try_send(update) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(update)) => {
+                warn!("sink queue is full, dropped update of {}", update.pubkey());
+                Ok(())
+            }
+            Err(TrySendError::Disconnected(_)) => Err(GeyserPluginError::AccountsUpdateError {
+                msg: "sink worker stopped".to_string(),
+            }),
+        }
+    }
+
+    fn account_data_notifications_enabled(&self) -> bool {
+        true
+    }
+
+    fn transaction_notifications_enabled(&self) -> bool {
+        false
+    }
+}
+
+/// Entry point the validator looks up in the loaded library.
+///
+/// # Safety
+///
+/// The validator takes ownership of the returned plugin; it has to be built with the
+/// same compiler and solana version as the validator.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub unsafe extern "C" fn _create_plugin() -> *mut dyn GeyserPlugin {
+    let plugin: Box<dyn GeyserPlugin> = Box::new(GeyserPluginBank::default());
+    Box::into_raw(plugin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV3;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Recorder(Arc<Mutex<Vec<BankAccountUpdate>>>);
+
+    impl sink::Sink for Recorder {
+        fn send(&mut self, updates: &[BankAccountUpdate]) -> std::result::Result<(), String> {
+            self.0.lock().unwrap().extend_from_slice(updates);
+            Ok(())
+        }
+    }
+
+    fn account_data(amount: u64) -> Vec<u8> {
+        let mut data = vec![0; Account::LEN];
+        Account {
+            amount,
+            is_opened: true,
+            is_initialized: true,
+            ..Account::default()
+        }
+        .pack_into_slice(&mut data);
+        data
+    }
+
+    #[test]
+    fn test_config() {
+        let config: Config = serde_json::from_str(
+            r#"{"libpath": "libgeyser_plugin_bank.so", "program_id": "11111111111111111111111111111111",
+                "sink": {"type": "http", "url": "http://localhost:8080/updates"}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.queue_size, 10_000);
+        assert_eq!(
+            config.sink,
+            SinkConfig::Http {
+                url: "http://localhost:8080/updates".to_string(),
+                batch_size: 100,
+            }
+        );
+    }
+
+    #[test]
+    fn test_update_account() {
+        let program_id = Pubkey::new_unique();
+        let recorded = Arc::new(Mutex::new(Vec::new()));
+        let mut plugin = GeyserPluginBank::default();
+        plugin.start(program_id, Box::new(Recorder(recorded.clone())), 8);
+
+        let key = Pubkey::new_unique();
+        let data = account_data(42);
+        let notify = |owner: &Pubkey, data: &[u8]| {
+            let info = ReplicaAccountInfoV3 {
+                pubkey: key.as_ref(),
+                lamports: 1,
+                owner: owner.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data,
+                write_version: 7,
+                txn: None,
+            };
+            plugin
+                .update_account(ReplicaAccountInfoVersions::V0_0_3(&info), 5, false)
+                .unwrap();
+        };
+        notify(&program_id, &data);
+        notify(&Pubkey::new_unique(), &data);
+        notify(&program_id, &[1, 2, 3]);
+        plugin.on_unload();
+
+        let recorded = recorded.lock().unwrap();
+        assert_eq!(*recorded, vec![decode(&key, &data, 5, 7, false).unwrap()]);
+        let json = serde_json::to_value(&recorded[0]).unwrap();
+        assert_eq!(json["type"], "account");
+        assert_eq!(json["amount"], 42);
+        assert_eq!(json["pubkey"], key.to_string());
+    }
+}
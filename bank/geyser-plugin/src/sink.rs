@@ -0,0 +1,132 @@
+use crate::BankAccountUpdate;
+use log::error;
+use serde::Deserialize;
+use std::sync::mpsc::Receiver;
+
+fn default_batch_size() -> usize {
+    100
+}
+
+/// Where decoded updates go.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SinkConfig {
+    /// POSTs JSON arrays of up to `batch_size` updates to `url`.
+    Http {
+        url: String,
+        #[serde(default = "default_batch_size")]
+        batch_size: usize,
+    },
+    /// Produces one JSON message per update to `topic`, keyed by the account so the
+    /// updates of an account stay in order within a partition.
+    #[cfg(feature = "kafka")]
+    Kafka { brokers: String, topic: String },
+}
+
+impl SinkConfig {
+    pub(crate) fn connect(&self) -> Result<Box<dyn Sink>, String> {
+        match self {
+            SinkConfig::Http { url, batch_size } => Ok(Box::new(HttpSink {
+                url: url.clone(),
+                batch_size: *batch_size,
+            })),
+            #[cfg(feature = "kafka")]
+            SinkConfig::Kafka { brokers, topic } => {
+                kafka::KafkaSink::new(brokers, topic).map(|sink| Box::new(sink) as Box<dyn Sink>)
+            }
+        }
+    }
+}
+
+pub(crate) trait Sink: Send {
+    fn send(&mut self, updates: &[BankAccountUpdate]) -> Result<(), String>;
+
+    /// Most updates handed to one `send`.
+    fn batch_size(&self) -> usize {
+        1
+    }
+}
+
+/// Sends what arrives on `updates`, batching whatever is already queued, until every
+/// sender is dropped. A batch the sink fails on is logged and dropped; the consumer
+/// catches up from the next update of the same account.
+pub(crate) fn forward(sink: &mut dyn Sink, updates: Receiver<BankAccountUpdate>) {
+    let batch_size = sink.batch_size().max(1);
+    while let Ok(first) = updates.recv() {
+        let mut batch = vec![first];
+        batch.extend(updates.try_iter().take(batch_size - 1));
+        if let Err(e) = sink.send(&batch) {
+            error!("sink dropped {} updates: {}", batch.len(), e);
+        }
+    }
+}
+
+struct HttpSink {
+    url: String,
+    batch_size: usize,
+}
+
+impl Sink for HttpSink {
+    fn send(&mut self, updates: &[BankAccountUpdate]) -> Result<(), String> {
+        ureq::post(&self.url)
+            .send_json(updates)
+            .map(|_| ())
+            .map_err(|e| format!("POST {} failed: {}", self.url, e))
+    }
+
+    fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+}
+
+#[cfg(feature = "kafka")]
+mod kafka {
+    use super::Sink;
+    use crate::BankAccountUpdate;
+    use rdkafka::{
+        config::ClientConfig,
+        producer::{BaseProducer, BaseRecord, Producer},
+    };
+    use std::time::Duration;
+
+    pub struct KafkaSink {
+        producer: BaseProducer,
+        topic: String,
+    }
+
+    impl KafkaSink {
+        pub fn new(brokers: &str, topic: &str) -> Result<KafkaSink, String> {
+            let producer = ClientConfig::new()
+                .set("bootstrap.servers", brokers)
+                .create()
+                .map_err(|e| format!("create kafka producer failed: {}", e))?;
+            Ok(KafkaSink {
+                producer,
+                topic: topic.to_string(),
+            })
+        }
+    }
+
+    impl Sink for KafkaSink {
+        fn send(&mut self, updates: &[BankAccountUpdate]) -> Result<(), String> {
+            for update in updates {
+                let payload = serde_json::to_vec(update).map_err(|e| e.to_string())?;
+                let record = BaseRecord::to(&self.topic)
+                    .key(update.pubkey())
+                    .payload(&payload);
+                self.producer
+                    .send(record)
+                    .map_err(|(e, _)| format!("produce to {} failed: {}", self.topic, e))?;
+            }
+            // Serves the delivery callbacks of what was produced so far.
+            self.producer.poll(Duration::from_millis(0));
+            Ok(())
+        }
+    }
+
+    impl Drop for KafkaSink {
+        fn drop(&mut self) {
+            self.producer.flush(Duration::from_secs(5));
+        }
+    }
+}
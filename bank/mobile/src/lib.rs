@@ -0,0 +1,1085 @@
+//! Kotlin and Swift bindings of the bank program, generated with uniffi. A `BankProgram`
+//! builds instructions as plain records (`programId`, `accounts`, `data`) for the app's
+//! Solana library to sign and send, and `decodeBank`/`decodeAccount` read account data
+//! fetched the same way; like the browser bindings, no keys or RPC live here.
+
+use solana_bank::{
+    instruction::{self, AuthorityType},
+    permit,
+    solana_program::{
+        instruction::Instruction, program_error::ProgramError, program_option::COption,
+        program_pack::Pack, pubkey::Pubkey,
+    },
+    state::{Account, Bank, ClosePolicy},
+};
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt,
+    str::FromStr,
+    sync::Arc,
+};
+
+uniffi::setup_scaffolding!();
+
+#[derive(Debug, PartialEq, uniffi::Error)]
+pub enum BankMobileError {
+    /// An address isn't a base58 public key.
+    InvalidPubkey { name: String, value: String },
+    /// A builder rejected its arguments.
+    InvalidInstruction { message: String },
+    /// The data isn't an initialized bank or account.
+    InvalidData { message: String },
+}
+
+impl fmt::Display for BankMobileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BankMobileError::InvalidPubkey { name, value } => {
+                write!(f, "invalid {}: {}", name, value)
+            }
+            BankMobileError::InvalidInstruction { message }
+            | BankMobileError::InvalidData { message } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for BankMobileError {}
+
+#[derive(Debug, PartialEq, uniffi::Record)]
+pub struct AccountMetaView {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(Debug, PartialEq, uniffi::Record)]
+pub struct InstructionView {
+    pub program_id: String,
+    pub accounts: Vec<AccountMetaView>,
+    pub data: Vec<u8>,
+}
+
+impl From<Instruction> for InstructionView {
+    fn from(ix: Instruction) -> InstructionView {
+        InstructionView {
+            program_id: ix.program_id.to_string(),
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|meta| AccountMetaView {
+                    pubkey: meta.pubkey.to_string(),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: ix.data,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, uniffi::Record)]
+pub struct BankView {
+    pub decimals: u8,
+    pub bank_owner: String,
+    pub is_opened: bool,
+    pub total_supply: u64,
+    /// The bank's `ClosePolicy`, as its `u8` discriminant.
+    pub close_policy: u8,
+    pub require_rent_exempt: bool,
+    /// The bank's `BurnPolicy`, as its `u8` discriminant.
+    pub burn_policy: u8,
+    pub lamports_per_token: u64,
+    pub transfer_count: u64,
+    pub cumulative_volume: u64,
+    pub holder_count: u64,
+    pub registered_accounts: u64,
+    pub dust_threshold: u64,
+    pub freeze_authority: Option<String>,
+    pub is_paused: bool,
+    pub interest_rate_bps: u16,
+    pub emergency_authority: Option<String>,
+    pub owner_change_delay: u64,
+    pub pending_bank_owner: Option<String>,
+    pub owner_change_slot: u64,
+}
+
+#[derive(Debug, PartialEq, uniffi::Record)]
+pub struct AccountView {
+    pub bank: String,
+    pub owner: String,
+    pub amount: u64,
+    pub delegate: Option<String>,
+    pub delegated_amount: u64,
+    pub is_opened: bool,
+    pub is_initialized: bool,
+    pub permit_nonce: u64,
+    pub is_frozen: bool,
+    pub last_accrual: i64,
+}
+
+fn parse_pubkey(name: &str, value: &str) -> Result<Pubkey, BankMobileError> {
+    Pubkey::from_str(value).map_err(|_| BankMobileError::InvalidPubkey {
+        name: name.to_string(),
+        value: value.to_string(),
+    })
+}
+
+fn parse_guardians(guardians: &[String]) -> Result<Vec<Pubkey>, BankMobileError> {
+    guardians
+        .iter()
+        .map(|guardian| parse_pubkey("guardian", guardian))
+        .collect()
+}
+
+fn parse_signers(signers: &[String]) -> Result<Vec<Pubkey>, BankMobileError> {
+    signers
+        .iter()
+        .map(|signer| parse_pubkey("signer", signer))
+        .collect()
+}
+
+fn to_view(ix: Result<Instruction, ProgramError>) -> Result<InstructionView, BankMobileError> {
+    ix.map(InstructionView::from)
+        .map_err(|e| BankMobileError::InvalidInstruction {
+            message: e.to_string(),
+        })
+}
+
+/// Decodes the data of a bank (`Bank`, 220 bytes).
+#[uniffi::export]
+pub fn decode_bank(data: Vec<u8>) -> Result<BankView, BankMobileError> {
+    let bank = Bank::unpack(&data).map_err(|e| BankMobileError::InvalidData {
+        message: e.to_string(),
+    })?;
+    let freeze_authority = match bank.freeze_authority {
+        COption::Some(authority) => Some(authority.to_string()),
+        COption::None => None,
+    };
+    let emergency_authority = match bank.emergency_authority {
+        COption::Some(authority) => Some(authority.to_string()),
+        COption::None => None,
+    };
+    let pending_bank_owner = match bank.pending_bank_owner {
+        COption::Some(owner) => Some(owner.to_string()),
+        COption::None => None,
+    };
+    Ok(BankView {
+        decimals: bank.decimals,
+        bank_owner: bank.bank_owner.to_string(),
+        is_opened: bank.is_opened,
+        total_supply: bank.total_supply,
+        close_policy: bank.close_policy as u8,
+        require_rent_exempt: bank.require_rent_exempt,
+        burn_policy: bank.burn_policy as u8,
+        lamports_per_token: bank.lamports_per_token,
+        transfer_count: bank.transfer_count,
+        cumulative_volume: bank.cumulative_volume,
+        holder_count: bank.holder_count,
+        registered_accounts: bank.registered_accounts,
+        dust_threshold: bank.dust_threshold,
+        freeze_authority,
+        is_paused: bank.is_paused,
+        interest_rate_bps: bank.interest_rate_bps,
+        emergency_authority,
+        owner_change_delay: bank.owner_change_delay,
+        pending_bank_owner,
+        owner_change_slot: bank.owner_change_slot,
+    })
+}
+
+/// Decodes the data of a bank token account (`Account`, 135 bytes, more with a
+/// transfer history).
+#[uniffi::export]
+pub fn decode_account(data: Vec<u8>) -> Result<AccountView, BankMobileError> {
+    let account = Account::unpack_extended(&data).map_err(|e| BankMobileError::InvalidData {
+        message: e.to_string(),
+    })?;
+    let delegate = match account.delegate {
+        COption::Some(d) => Some(d.to_string()),
+        COption::None => None,
+    };
+    Ok(AccountView {
+        bank: account.bank.to_string(),
+        owner: account.owner.to_string(),
+        amount: account.amount,
+        delegate,
+        delegated_amount: account.delegated_amount,
+        is_opened: account.is_opened,
+        is_initialized: account.is_initialized,
+        permit_nonce: account.permit_nonce,
+        is_frozen: account.is_frozen,
+        last_accrual: account.last_accrual,
+    })
+}
+
+/// The instruction builders of one deployment of the bank program.
+#[derive(uniffi::Object)]
+pub struct BankProgram {
+    program_id: Pubkey,
+}
+
+#[uniffi::export]
+impl BankProgram {
+    #[uniffi::constructor]
+    pub fn new(program_id: String) -> Result<Arc<BankProgram>, BankMobileError> {
+        Ok(Arc::new(BankProgram {
+            program_id: parse_pubkey("program id", &program_id)?,
+        }))
+    }
+
+    pub fn program_id(&self) -> String {
+        self.program_id.to_string()
+    }
+
+    pub fn initialize_bank(
+        &self,
+        bank: String,
+        bank_owner: String,
+        decimals: u8,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::initialize_bank(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("bank owner", &bank_owner)?,
+            decimals,
+        ))
+    }
+
+    pub fn initialize_account(
+        &self,
+        bank: String,
+        account: String,
+        owner: String,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::initialize_account(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("owner", &owner)?,
+        ))
+    }
+
+    pub fn transfer(
+        &self,
+        bank: String,
+        from: String,
+        to: String,
+        owner: String,
+        amount: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::transfer(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("from", &from)?,
+            &parse_pubkey("to", &to)?,
+            &parse_pubkey("owner", &owner)?,
+            amount,
+        ))
+    }
+
+    pub fn transfer_with_expiry(
+        &self,
+        bank: String,
+        from: String,
+        to: String,
+        owner: String,
+        amount: u64,
+        max_slot: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::transfer_with_expiry(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("from", &from)?,
+            &parse_pubkey("to", &to)?,
+            &parse_pubkey("owner", &owner)?,
+            amount,
+            max_slot,
+        ))
+    }
+
+    /// Routes `amount` from `accounts[0]` to the last of `accounts`, each sending account
+    /// signed for by the authority at its index.
+    pub fn transfer_route(
+        &self,
+        bank: String,
+        accounts: Vec<String>,
+        authorities: Vec<String>,
+        amount: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        let invalid = |message: &str| BankMobileError::InvalidInstruction {
+            message: message.to_string(),
+        };
+        let (destination, senders) = accounts
+            .split_last()
+            .ok_or_else(|| invalid("a route needs accounts"))?;
+        if senders.len() != authorities.len() {
+            return Err(invalid("one authority per sending account"));
+        }
+        let hops = senders
+            .iter()
+            .zip(&authorities)
+            .map(|(account, authority)| {
+                Ok((
+                    parse_pubkey("account", account)?,
+                    parse_pubkey("authority", authority)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, BankMobileError>>()?;
+        to_view(instruction::transfer_route(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &hops,
+            &parse_pubkey("destination", destination)?,
+            amount,
+        ))
+    }
+
+    /// Pays the `i`th of `amounts` from `from` to the `i`th of `destinations`, whole or
+    /// not at all.
+    pub fn transfer_batch(
+        &self,
+        bank: String,
+        from: String,
+        owner: String,
+        destinations: Vec<String>,
+        amounts: Vec<u64>,
+    ) -> Result<InstructionView, BankMobileError> {
+        if destinations.len() != amounts.len() {
+            return Err(BankMobileError::InvalidInstruction {
+                message: "one amount per destination".to_string(),
+            });
+        }
+        let destinations = destinations
+            .iter()
+            .map(|destination| parse_pubkey("destination", destination))
+            .collect::<Result<Vec<_>, BankMobileError>>()?;
+        let transfers: Vec<_> = destinations.iter().zip(amounts).collect();
+        to_view(instruction::transfer_batch(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("from", &from)?,
+            &parse_pubkey("owner", &owner)?,
+            &transfers,
+        ))
+    }
+
+    /// Hands `account` over to the address `seeds`, bump last, derive from `program`.
+    pub fn assign_to_program(
+        &self,
+        bank: String,
+        account: String,
+        owner: String,
+        program: String,
+        seeds: Vec<Vec<u8>>,
+    ) -> Result<InstructionView, BankMobileError> {
+        let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+        to_view(instruction::assign_to_program(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("owner", &owner)?,
+            &parse_pubkey("program", &program)?,
+            &seeds,
+        ))
+    }
+
+    pub fn approve(
+        &self,
+        bank: String,
+        account: String,
+        delegate: String,
+        owner: String,
+        amount: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::approve(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("delegate", &delegate)?,
+            &parse_pubkey("owner", &owner)?,
+            amount,
+        ))
+    }
+
+    pub fn revoke(
+        &self,
+        bank: String,
+        account: String,
+        owner: String,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::revoke(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("owner", &owner)?,
+        ))
+    }
+
+    pub fn set_authority(
+        &self,
+        owned: String,
+        authority_type: u8,
+        current_authority: String,
+        new_authority: Option<String>,
+    ) -> Result<InstructionView, BankMobileError> {
+        let authority_type = AuthorityType::try_from(authority_type).map_err(|_| {
+            BankMobileError::InvalidInstruction {
+                message: format!("invalid authority type: {}", authority_type),
+            }
+        })?;
+        let new_authority = new_authority
+            .map(|key| parse_pubkey("new authority", &key))
+            .transpose()?;
+        to_view(instruction::set_authority(
+            &self.program_id,
+            &parse_pubkey("owned", &owned)?,
+            authority_type,
+            &parse_pubkey("current authority", &current_authority)?,
+            new_authority.as_ref(),
+        ))
+    }
+
+    pub fn mint_to(
+        &self,
+        bank: String,
+        account: String,
+        bank_owner: String,
+        amount: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::mint_to(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("bank owner", &bank_owner)?,
+            amount,
+        ))
+    }
+
+    pub fn burn(
+        &self,
+        bank: String,
+        account: String,
+        bank_owner: String,
+        account_owner: String,
+        amount: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::burn(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("bank owner", &bank_owner)?,
+            &parse_pubkey("account owner", &account_owner)?,
+            amount,
+        ))
+    }
+
+    pub fn transfer_checked(
+        &self,
+        bank: String,
+        from: String,
+        to: String,
+        owner: String,
+        amount: u64,
+        decimals: u8,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::transfer_checked(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("from", &from)?,
+            &parse_pubkey("to", &to)?,
+            &parse_pubkey("owner", &owner)?,
+            &[],
+            amount,
+            decimals,
+        ))
+    }
+
+    pub fn mint_to_checked(
+        &self,
+        bank: String,
+        account: String,
+        bank_owner: String,
+        amount: u64,
+        decimals: u8,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::mint_to_checked(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("bank owner", &bank_owner)?,
+            &[],
+            amount,
+            decimals,
+        ))
+    }
+
+    pub fn burn_checked(
+        &self,
+        bank: String,
+        account: String,
+        bank_owner: String,
+        account_owner: String,
+        amount: u64,
+        decimals: u8,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::burn_checked(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("bank owner", &bank_owner)?,
+            &parse_pubkey("account owner", &account_owner)?,
+            amount,
+            decimals,
+        ))
+    }
+
+    pub fn close_account(
+        &self,
+        bank: String,
+        account: String,
+        destination: String,
+        owner: String,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::close_account(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("destination", &destination)?,
+            &parse_pubkey("owner", &owner)?,
+        ))
+    }
+
+    pub fn set_close_policy(
+        &self,
+        bank: String,
+        bank_owner: String,
+        policy: u8,
+    ) -> Result<InstructionView, BankMobileError> {
+        let policy =
+            ClosePolicy::try_from(policy).map_err(|_| BankMobileError::InvalidInstruction {
+                message: format!("invalid close policy: {}", policy),
+            })?;
+        to_view(instruction::set_close_policy(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("bank owner", &bank_owner)?,
+            policy,
+        ))
+    }
+
+    pub fn set_require_rent_exempt(
+        &self,
+        bank: String,
+        bank_owner: String,
+        required: bool,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::set_require_rent_exempt(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("bank owner", &bank_owner)?,
+            required,
+        ))
+    }
+
+    /// The message the account owner signs for `permit_approve`.
+    pub fn permit_message(
+        &self,
+        account: String,
+        delegate: String,
+        amount: u64,
+        nonce: u64,
+        expiry: i64,
+    ) -> Result<Vec<u8>, BankMobileError> {
+        Ok(permit::message(
+            &self.program_id,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("delegate", &delegate)?,
+            amount,
+            nonce,
+            expiry,
+        ))
+    }
+
+    /// The ed25519 program instruction carrying the owner's signature of a permit, to go
+    /// right before its `permit_approve`.
+    pub fn permit_signature(
+        &self,
+        owner: String,
+        signature: Vec<u8>,
+        message: Vec<u8>,
+    ) -> Result<InstructionView, BankMobileError> {
+        let signature =
+            signature
+                .as_slice()
+                .try_into()
+                .map_err(|_| BankMobileError::InvalidInstruction {
+                    message: "a signature is 64 bytes".to_string(),
+                })?;
+        Ok(InstructionView::from(permit::signature_instruction(
+            &parse_pubkey("owner", &owner)?,
+            signature,
+            &message,
+        )))
+    }
+
+    pub fn permit_approve(
+        &self,
+        bank: String,
+        account: String,
+        delegate: String,
+        amount: u64,
+        nonce: u64,
+        expiry: i64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::permit_approve(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("delegate", &delegate)?,
+            amount,
+            nonce,
+            expiry,
+        ))
+    }
+
+    pub fn deposit_sol(
+        &self,
+        bank: String,
+        account: String,
+        depositor: String,
+        lamports: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::deposit_sol(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("depositor", &depositor)?,
+            lamports,
+        ))
+    }
+
+    pub fn withdraw_sol(
+        &self,
+        bank: String,
+        account: String,
+        owner: String,
+        destination: String,
+        lamports: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::withdraw_sol(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("owner", &owner)?,
+            &parse_pubkey("destination", &destination)?,
+            lamports,
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_exchange_rate(
+        &self,
+        rate: String,
+        from_bank: String,
+        to_bank: String,
+        from_bank_owner: String,
+        to_bank_owner: String,
+        numerator: u64,
+        denominator: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::set_exchange_rate(
+            &self.program_id,
+            &parse_pubkey("rate", &rate)?,
+            &parse_pubkey("from bank", &from_bank)?,
+            &parse_pubkey("to bank", &to_bank)?,
+            &parse_pubkey("from bank owner", &from_bank_owner)?,
+            &parse_pubkey("to bank owner", &to_bank_owner)?,
+            numerator,
+            denominator,
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn convert(
+        &self,
+        rate: String,
+        from_bank: String,
+        from_account: String,
+        owner: String,
+        to_bank: String,
+        to_account: String,
+        amount: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::convert(
+            &self.program_id,
+            &parse_pubkey("rate", &rate)?,
+            &parse_pubkey("from bank", &from_bank)?,
+            &parse_pubkey("from account", &from_account)?,
+            &parse_pubkey("owner", &owner)?,
+            &parse_pubkey("to bank", &to_bank)?,
+            &parse_pubkey("to account", &to_account)?,
+            amount,
+        ))
+    }
+
+    pub fn set_dust_threshold(
+        &self,
+        bank: String,
+        bank_owner: String,
+        threshold: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::set_dust_threshold(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("bank owner", &bank_owner)?,
+            threshold,
+        ))
+    }
+
+    pub fn pause_bank(
+        &self,
+        bank: String,
+        bank_owner: String,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::pause_bank(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("bank owner", &bank_owner)?,
+        ))
+    }
+
+    pub fn resume_bank(
+        &self,
+        bank: String,
+        bank_owner: String,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::resume_bank(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("bank owner", &bank_owner)?,
+        ))
+    }
+
+    pub fn accrue_interest(
+        &self,
+        bank: String,
+        account: String,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::accrue_interest(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+        ))
+    }
+
+    pub fn set_owner_change_delay(
+        &self,
+        bank: String,
+        bank_owner: String,
+        delay: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::set_owner_change_delay(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("bank owner", &bank_owner)?,
+            delay,
+        ))
+    }
+
+    pub fn cancel_owner_change(
+        &self,
+        bank: String,
+        authority: String,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::cancel_owner_change(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("authority", &authority)?,
+        ))
+    }
+
+    pub fn close_dust_account(
+        &self,
+        bank: String,
+        account: String,
+        owner: String,
+        sweep_account: Option<String>,
+    ) -> Result<InstructionView, BankMobileError> {
+        let sweep_account = sweep_account
+            .map(|key| parse_pubkey("sweep account", &key))
+            .transpose()?;
+        to_view(instruction::close_dust_account(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("owner", &owner)?,
+            sweep_account.as_ref(),
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_guardians(
+        &self,
+        bank: String,
+        account: String,
+        account_owner: String,
+        payer: String,
+        guardians: Vec<String>,
+        threshold: u8,
+        delay: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::set_guardians(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("account owner", &account_owner)?,
+            &parse_pubkey("payer", &payer)?,
+            &parse_guardians(&guardians)?,
+            threshold,
+            delay,
+        ))
+    }
+
+    pub fn start_recovery(
+        &self,
+        bank: String,
+        account: String,
+        guardians: Vec<String>,
+        new_owner: String,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::start_recovery(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_guardians(&guardians)?,
+            &parse_pubkey("new owner", &new_owner)?,
+        ))
+    }
+
+    pub fn recover_owner(
+        &self,
+        bank: String,
+        account: String,
+        guardians: Vec<String>,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::recover_owner(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_guardians(&guardians)?,
+        ))
+    }
+
+    pub fn cancel_recovery(
+        &self,
+        bank: String,
+        account: String,
+        account_owner: String,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::cancel_recovery(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("account owner", &account_owner)?,
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_session_key(
+        &self,
+        bank: String,
+        account: String,
+        account_owner: String,
+        payer: String,
+        session_key: String,
+        max_spend: u64,
+        expiry_slot: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::create_session_key(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("account owner", &account_owner)?,
+            &parse_pubkey("payer", &payer)?,
+            &parse_pubkey("session key", &session_key)?,
+            max_spend,
+            expiry_slot,
+        ))
+    }
+
+    pub fn transfer_with_session_key(
+        &self,
+        bank: String,
+        from: String,
+        to: String,
+        session_key: String,
+        amount: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::transfer_with_session_key(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("from", &from)?,
+            &parse_pubkey("to", &to)?,
+            &parse_pubkey("session key", &session_key)?,
+            amount,
+        ))
+    }
+
+    pub fn set_rate_limit(
+        &self,
+        bank: String,
+        account: String,
+        account_owner: String,
+        capacity: u64,
+        refill_per_slot: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::set_rate_limit(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("account owner", &account_owner)?,
+            capacity,
+            refill_per_slot,
+        ))
+    }
+
+    pub fn initialize_version(&self, payer: String) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::initialize_version(
+            &self.program_id,
+            &parse_pubkey("payer", &payer)?,
+        ))
+    }
+
+    pub fn set_features(
+        &self,
+        bank: String,
+        bank_owner: String,
+        payer: String,
+        enabled: u64,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::set_features(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("bank owner", &bank_owner)?,
+            &parse_pubkey("payer", &payer)?,
+            enabled,
+        ))
+    }
+
+    pub fn freeze_account(
+        &self,
+        bank: String,
+        account: String,
+        freeze_authority: String,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::freeze_account(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("freeze authority", &freeze_authority)?,
+        ))
+    }
+
+    pub fn thaw_account(
+        &self,
+        bank: String,
+        account: String,
+        freeze_authority: String,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::thaw_account(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("account", &account)?,
+            &parse_pubkey("freeze authority", &freeze_authority)?,
+        ))
+    }
+
+    pub fn initialize_multisig(
+        &self,
+        multisig: String,
+        signers: Vec<String>,
+        threshold: u8,
+    ) -> Result<InstructionView, BankMobileError> {
+        let signers = parse_signers(&signers)?;
+        to_view(instruction::initialize_multisig(
+            &self.program_id,
+            &parse_pubkey("multisig", &multisig)?,
+            &signers.iter().collect::<Vec<_>>(),
+            threshold,
+        ))
+    }
+
+    pub fn initialize_associated_account(
+        &self,
+        bank: String,
+        wallet: String,
+        payer: String,
+    ) -> Result<InstructionView, BankMobileError> {
+        to_view(instruction::initialize_associated_account(
+            &self.program_id,
+            &parse_pubkey("bank", &bank)?,
+            &parse_pubkey("wallet", &wallet)?,
+            &parse_pubkey("payer", &payer)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bank_program() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let program = BankProgram::new(program_id.to_string()).unwrap();
+        let bank = Pubkey::new_unique().to_string();
+        let view = program
+            .revoke(
+                bank.clone(),
+                Pubkey::new_unique().to_string(),
+                owner.to_string(),
+            )
+            .unwrap();
+        assert_eq!(view.program_id, program_id.to_string());
+        assert_eq!(
+            view.accounts[1],
+            AccountMetaView {
+                pubkey: owner.to_string(),
+                is_signer: true,
+                is_writable: true,
+            }
+        );
+        assert_eq!(view.data, vec![7]);
+
+        assert_eq!(
+            program.revoke(bank, "nope".to_string(), owner.to_string()),
+            Err(BankMobileError::InvalidPubkey {
+                name: "account".to_string(),
+                value: "nope".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_account() {
+        let account = Account {
+            amount: u64::MAX,
+            is_opened: true,
+            is_initialized: true,
+            owner: Pubkey::new_unique(),
+            delegate: COption::None,
+            delegated_amount: 0,
+            bank: Pubkey::new_unique(),
+            permit_nonce: 0,
+            is_frozen: false,
+            last_accrual: 0,
+        };
+        let mut data = vec![0u8; Account::LEN];
+        Account::pack(account, &mut data).unwrap();
+        let view = decode_account(data.clone()).unwrap();
+        assert_eq!((view.amount, view.delegate), (u64::MAX, None));
+        assert_eq!(view.owner, account.owner.to_string());
+        assert!(decode_bank(data).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! Pack/unpack cost of the bank's state, run with `cargo bench -p solana_bank`.
+//! The `in_place` cases touch only the balance bytes of a packed `Account`, through
+//! the helpers `Transfer` uses, and show what they save over a full unpack/repack.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use solana_bank::state::{Account, Bank, BurnPolicy, ClosePolicy};
+use solana_program::{program_option::COption, program_pack::Pack, pubkey::Pubkey};
+
+fn delegated_account() -> Account {
+    Account {
+        amount: 1_250_000_000,
+        is_opened: true,
+        is_initialized: true,
+        owner: Pubkey::new_unique(),
+        delegate: COption::Some(Pubkey::new_unique()),
+        delegated_amount: 40_000_000,
+        bank: Pubkey::new_unique(),
+        permit_nonce: 0,
+    }
+}
+
+fn packed<T: Pack>(value: T) -> Vec<u8> {
+    let mut buf = vec![0u8; T::LEN];
+    T::pack(value, &mut buf).unwrap();
+    buf
+}
+
+fn bench_account(c: &mut Criterion) {
+    let account = delegated_account();
+    let mut buf = packed(account);
+
+    c.bench_function("account_unpack", |b| {
+        b.iter(|| Account::unpack(black_box(&buf)).unwrap())
+    });
+    c.bench_function("account_pack", |b| {
+        b.iter(|| Account::pack(black_box(account), black_box(&mut buf)).unwrap())
+    });
+    // The debit side of a Transfer: unpack, adjust the balance, pack back.
+    c.bench_function("account_debit_repack", |b| {
+        b.iter(|| {
+            let mut account = Account::unpack(black_box(&buf)).unwrap();
+            account.amount -= black_box(1);
+            account.amount += black_box(1);
+            Account::pack(account, &mut buf).unwrap();
+        })
+    });
+
+    c.bench_function("account_read_amount_in_place", |b| {
+        b.iter(|| Account::unpack_amount(black_box(&buf)))
+    });
+    c.bench_function("account_debit_in_place", |b| {
+        b.iter(|| {
+            let amount = Account::unpack_amount(black_box(&buf));
+            Account::pack_amount(black_box(&mut buf), amount - black_box(1) + black_box(1));
+        })
+    });
+}
+
+fn bench_bank(c: &mut Criterion) {
+    let bank = Bank {
+        decimals: 9,
+        bank_owner: Pubkey::new_unique(),
+        is_opened: true,
+        total_supply: 5_000_000_000,
+        close_policy: ClosePolicy::Reject,
+        require_rent_exempt: false,
+        burn_policy: BurnPolicy::OwnerAndHolder,
+        lamports_per_token: 0,
+        transfer_count: 0,
+        cumulative_volume: 0,
+        holder_count: 0,
+        registered_accounts: 0,
+        dust_threshold: 0,
+        freeze_authority: COption::None,
+        is_paused: false,
+        interest_rate_bps: 0,
+        emergency_authority: COption::None,
+        owner_change_delay: 0,
+        pending_bank_owner: COption::None,
+        owner_change_slot: 0,
+    };
+    let mut buf = packed(bank);
+
+    c.bench_function("bank_unpack", |b| {
+        b.iter(|| Bank::unpack(black_box(&buf)).unwrap())
+    });
+    c.bench_function("bank_pack", |b| {
+        b.iter(|| Bank::pack(black_box(bank), black_box(&mut buf)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_account, bench_bank);
+criterion_main!(benches);
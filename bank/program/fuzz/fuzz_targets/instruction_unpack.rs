@@ -0,0 +1,17 @@
+#![no_main]
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(instruction) = solana_bank::instruction::BankInstruction::unpack(data) {
+        let packed = instruction.pack();
+        assert_eq!(&data[..packed.len()], &packed[..]);
+    }
+
+    // try_from_slice rejects trailing bytes, so a decoded instruction must re-encode
+    // to exactly the input.
+    if let Ok(instruction) = bank_interface::BankInstruction::try_from_slice(data) {
+        assert_eq!(instruction.try_to_vec().unwrap(), data);
+    }
+});
@@ -0,0 +1,510 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use solana_bank::{
+    instruction::{AuthorityType, BankInstruction},
+    multisig::MAX_SIGNERS,
+    processor::Processor,
+    solana_program::{
+        account_info::AccountInfo, clock::Epoch, entrypoint::ProgramResult,
+        program_option::COption, program_pack::Pack, pubkey::Pubkey,
+    },
+    state::{Account, Bank, BurnPolicy, ClosePolicy, Multisig},
+};
+use std::convert::TryFrom;
+
+/// Keys are drawn from a small pool so owners, delegates and banks line up often
+/// enough to get past the validation checks.
+const KEY_POOL: u8 = 6;
+
+fn key(index: u8) -> Pubkey {
+    Pubkey::new_from_array([index % KEY_POOL + 1; 32])
+}
+
+fn program_id() -> Pubkey {
+    Pubkey::new_from_array([0xbb; 32])
+}
+
+#[derive(Arbitrary, Debug)]
+enum FuzzData {
+    Bank {
+        decimals: u8,
+        bank_owner: u8,
+        is_opened: bool,
+        total_supply: u64,
+        close_policy: u8,
+        require_rent_exempt: bool,
+        burn_policy: u8,
+        freeze_authority: Option<u8>,
+        is_paused: bool,
+        interest_rate_bps: u16,
+        emergency_authority: Option<u8>,
+        owner_change_delay: u64,
+        pending_bank_owner: Option<u8>,
+        owner_change_slot: u64,
+    },
+    Account {
+        amount: u64,
+        is_opened: bool,
+        is_initialized: bool,
+        owner: u8,
+        delegate: Option<u8>,
+        delegated_amount: u64,
+        bank: u8,
+        permit_nonce: u64,
+        is_frozen: bool,
+        last_accrual: i64,
+    },
+    Multisig {
+        threshold: u8,
+        signers: Vec<u8>,
+    },
+    Raw(Vec<u8>),
+}
+
+impl FuzzData {
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            FuzzData::Bank {
+                decimals,
+                bank_owner,
+                is_opened,
+                total_supply,
+                close_policy,
+                require_rent_exempt,
+                burn_policy,
+                freeze_authority,
+                is_paused,
+                interest_rate_bps,
+                emergency_authority,
+                owner_change_delay,
+                pending_bank_owner,
+                owner_change_slot,
+            } => {
+                let mut data = vec![0; Bank::LEN];
+                let bank = Bank {
+                    decimals: *decimals,
+                    bank_owner: key(*bank_owner),
+                    is_opened: *is_opened,
+                    total_supply: *total_supply,
+                    close_policy: ClosePolicy::try_from(close_policy % 3).unwrap(),
+                    require_rent_exempt: *require_rent_exempt,
+                    burn_policy: BurnPolicy::try_from(burn_policy % 2).unwrap(),
+                    freeze_authority: freeze_authority.map(key).into(),
+                    is_paused: *is_paused,
+                    interest_rate_bps: *interest_rate_bps,
+                    emergency_authority: emergency_authority.map(key).into(),
+                    owner_change_delay: *owner_change_delay,
+                    pending_bank_owner: pending_bank_owner.map(key).into(),
+                    owner_change_slot: *owner_change_slot,
+                    ..Bank::default()
+                };
+                bank.pack_into_slice(&mut data);
+                data
+            }
+            FuzzData::Account {
+                amount,
+                is_opened,
+                is_initialized,
+                owner,
+                delegate,
+                delegated_amount,
+                bank,
+                permit_nonce,
+                is_frozen,
+                last_accrual,
+            } => {
+                let mut data = vec![0; Account::LEN];
+                let account = Account {
+                    amount: *amount,
+                    is_opened: *is_opened,
+                    is_initialized: *is_initialized,
+                    owner: key(*owner),
+                    delegate: delegate.map(key).map_or(COption::None, COption::Some),
+                    delegated_amount: *delegated_amount,
+                    bank: key(*bank),
+                    permit_nonce: *permit_nonce,
+                    is_frozen: *is_frozen,
+                    last_accrual: *last_accrual,
+                };
+                account.pack_into_slice(&mut data);
+                data
+            }
+            FuzzData::Multisig { threshold, signers } => {
+                let mut data = vec![0; Multisig::LEN];
+                let mut multisig = Multisig {
+                    is_initialized: true,
+                    threshold: *threshold,
+                    signer_count: signers.len().min(MAX_SIGNERS) as u8,
+                    ..Multisig::default()
+                };
+                for (slot, signer) in multisig.signers.iter_mut().zip(signers) {
+                    *slot = key(*signer);
+                }
+                multisig.pack_into_slice(&mut data);
+                data
+            }
+            FuzzData::Raw(data) => data.clone(),
+        }
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+enum FuzzOwner {
+    Program,
+    System,
+    Pool(u8),
+}
+
+impl FuzzOwner {
+    fn to_pubkey(&self) -> Pubkey {
+        match self {
+            FuzzOwner::Program => program_id(),
+            FuzzOwner::System => Pubkey::default(),
+            FuzzOwner::Pool(index) => key(*index),
+        }
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+struct FuzzAccount {
+    key: u8,
+    is_signer: bool,
+    is_writable: bool,
+    owner: FuzzOwner,
+    lamports: u64,
+    data: FuzzData,
+    /// Cuts or pads the packed data to this length, for the short-buffer paths.
+    resize: Option<u8>,
+}
+
+#[derive(Arbitrary, Debug)]
+enum FuzzSlot {
+    Account(FuzzAccount),
+    /// The account of an earlier slot passed again. Like the runtime does, both
+    /// slots share one lamports and one data cell, so a handler borrowing them
+    /// twice at once would panic.
+    Duplicate(u8),
+}
+
+#[derive(Arbitrary, Debug)]
+enum FuzzInstruction {
+    InitializeBank {
+        decimals: u8,
+        burn_policy: u8,
+        lamports_per_token: u64,
+        freeze_authority: Option<u8>,
+        interest_rate_bps: u16,
+    },
+    InitializeAccount,
+    Transfer {
+        amount: u64,
+        sweep_dust: bool,
+    },
+    Approve {
+        amount: u64,
+    },
+    MintTo {
+        amount: u64,
+    },
+    Burn {
+        amount: u64,
+    },
+    CloseAccount,
+    Revoke,
+    SetClosePolicy {
+        policy: u8,
+    },
+    SetRequireRentExempt {
+        required: bool,
+    },
+    FreezeAccount,
+    ThawAccount,
+    InitializeMultisig {
+        threshold: u8,
+    },
+    TransferChecked {
+        amount: u64,
+        decimals: u8,
+    },
+    MintToChecked {
+        amount: u64,
+        decimals: u8,
+    },
+    BurnChecked {
+        amount: u64,
+        decimals: u8,
+    },
+    SetAuthority {
+        authority_type: u8,
+        new_authority: Option<u8>,
+    },
+    TransferBatch {
+        amounts: Vec<u64>,
+    },
+    PauseBank,
+    ResumeBank,
+    AccrueInterest,
+    SetOwnerChangeDelay {
+        delay: u64,
+    },
+    CancelOwnerChange,
+    Raw(Vec<u8>),
+}
+
+impl FuzzInstruction {
+    fn to_bytes(&self) -> Vec<u8> {
+        let instruction = match self {
+            FuzzInstruction::InitializeBank {
+                decimals,
+                burn_policy,
+                lamports_per_token,
+                freeze_authority,
+                interest_rate_bps,
+            } => BankInstruction::InitializeBank {
+                decimals: *decimals,
+                burn_policy: BurnPolicy::try_from(burn_policy % 2).unwrap(),
+                lamports_per_token: *lamports_per_token,
+                freeze_authority: freeze_authority.map(key).into(),
+                interest_rate_bps: *interest_rate_bps,
+            },
+            FuzzInstruction::InitializeAccount => BankInstruction::InitializeAccount,
+            FuzzInstruction::Transfer { amount, sweep_dust } => BankInstruction::Transfer {
+                amount: *amount,
+                sweep_dust: *sweep_dust,
+            },
+            FuzzInstruction::Approve { amount } => BankInstruction::Approve { amount: *amount },
+            FuzzInstruction::MintTo { amount } => BankInstruction::MintTo { amount: *amount },
+            FuzzInstruction::Burn { amount } => BankInstruction::Burn { amount: *amount },
+            FuzzInstruction::CloseAccount => BankInstruction::CloseAccount,
+            FuzzInstruction::Revoke => BankInstruction::Revoke,
+            FuzzInstruction::SetClosePolicy { policy } => BankInstruction::SetClosePolicy {
+                policy: ClosePolicy::try_from(policy % 3).unwrap(),
+            },
+            FuzzInstruction::SetRequireRentExempt { required } => {
+                BankInstruction::SetRequireRentExempt {
+                    required: *required,
+                }
+            }
+            FuzzInstruction::FreezeAccount => BankInstruction::FreezeAccount,
+            FuzzInstruction::ThawAccount => BankInstruction::ThawAccount,
+            FuzzInstruction::InitializeMultisig { threshold } => {
+                BankInstruction::InitializeMultisig {
+                    threshold: *threshold,
+                }
+            }
+            FuzzInstruction::TransferChecked { amount, decimals } => {
+                BankInstruction::TransferChecked {
+                    amount: *amount,
+                    decimals: *decimals,
+                }
+            }
+            FuzzInstruction::MintToChecked { amount, decimals } => BankInstruction::MintToChecked {
+                amount: *amount,
+                decimals: *decimals,
+            },
+            FuzzInstruction::BurnChecked { amount, decimals } => BankInstruction::BurnChecked {
+                amount: *amount,
+                decimals: *decimals,
+            },
+            FuzzInstruction::SetAuthority {
+                authority_type,
+                new_authority,
+            } => BankInstruction::SetAuthority {
+                authority_type: AuthorityType::try_from(authority_type % 3).unwrap(),
+                new_authority: new_authority.map(key).into(),
+            },
+            FuzzInstruction::TransferBatch { amounts } => BankInstruction::TransferBatch {
+                amounts: amounts.clone(),
+            },
+            FuzzInstruction::PauseBank => BankInstruction::PauseBank,
+            FuzzInstruction::ResumeBank => BankInstruction::ResumeBank,
+            FuzzInstruction::AccrueInterest => BankInstruction::AccrueInterest,
+            FuzzInstruction::SetOwnerChangeDelay { delay } => {
+                BankInstruction::SetOwnerChangeDelay { delay: *delay }
+            }
+            FuzzInstruction::CancelOwnerChange => BankInstruction::CancelOwnerChange,
+            FuzzInstruction::Raw(data) => return data.clone(),
+        };
+        instruction.pack()
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+struct ProcessInput {
+    instruction: FuzzInstruction,
+    /// Call the instruction's handler directly instead of going through `Processor::process`.
+    direct: bool,
+    slots: Vec<FuzzSlot>,
+}
+
+fn dispatch(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction: &FuzzInstruction,
+    direct: bool,
+) -> ProgramResult {
+    if !direct {
+        return Processor::process(program_id, accounts, &instruction.to_bytes());
+    }
+    match instruction {
+        FuzzInstruction::InitializeBank {
+            decimals,
+            burn_policy,
+            lamports_per_token,
+            freeze_authority,
+            interest_rate_bps,
+        } => Processor::process_initialize_bank(
+            program_id,
+            accounts,
+            *decimals,
+            BurnPolicy::try_from(burn_policy % 2).unwrap(),
+            *lamports_per_token,
+            freeze_authority.map(key).into(),
+            *interest_rate_bps,
+        ),
+        FuzzInstruction::InitializeAccount => {
+            Processor::process_initialize_account(program_id, accounts)
+        }
+        FuzzInstruction::Transfer { amount, sweep_dust } => {
+            Processor::process_transfer(program_id, accounts, *amount, *sweep_dust)
+        }
+        FuzzInstruction::Approve { amount } => {
+            Processor::process_approve(program_id, accounts, *amount)
+        }
+        FuzzInstruction::MintTo { amount } => {
+            Processor::process_mint_to(program_id, accounts, *amount)
+        }
+        FuzzInstruction::Burn { amount } => Processor::process_burn(program_id, accounts, *amount),
+        FuzzInstruction::CloseAccount => Processor::process_close_account(program_id, accounts),
+        FuzzInstruction::Revoke => Processor::process_revoke(program_id, accounts),
+        FuzzInstruction::SetClosePolicy { policy } => Processor::process_set_close_policy(
+            program_id,
+            accounts,
+            ClosePolicy::try_from(policy % 3).unwrap(),
+        ),
+        FuzzInstruction::SetRequireRentExempt { required } => {
+            Processor::process_set_require_rent_exempt(program_id, accounts, *required)
+        }
+        FuzzInstruction::FreezeAccount => {
+            Processor::process_freeze_account(program_id, accounts, true)
+        }
+        FuzzInstruction::ThawAccount => {
+            Processor::process_freeze_account(program_id, accounts, false)
+        }
+        FuzzInstruction::InitializeMultisig { threshold } => {
+            Processor::process_initialize_multisig(program_id, accounts, *threshold)
+        }
+        // The checked variants are `check_decimals` before their unchecked handler, which
+        // only `Processor::process` puts together.
+        FuzzInstruction::TransferChecked { .. }
+        | FuzzInstruction::MintToChecked { .. }
+        | FuzzInstruction::BurnChecked { .. } => {
+            Processor::process(program_id, accounts, &instruction.to_bytes())
+        }
+        FuzzInstruction::SetAuthority {
+            authority_type,
+            new_authority,
+        } => Processor::process_set_authority(
+            program_id,
+            accounts,
+            AuthorityType::try_from(authority_type % 3).unwrap(),
+            new_authority.map(key).into(),
+        ),
+        FuzzInstruction::TransferBatch { amounts } => {
+            Processor::process_transfer_batch(program_id, accounts, amounts)
+        }
+        FuzzInstruction::PauseBank => Processor::process_pause_bank(program_id, accounts, true),
+        FuzzInstruction::ResumeBank => Processor::process_pause_bank(program_id, accounts, false),
+        FuzzInstruction::AccrueInterest => Processor::process_accrue_interest(program_id, accounts),
+        FuzzInstruction::SetOwnerChangeDelay { delay } => {
+            Processor::process_set_owner_change_delay(program_id, accounts, *delay)
+        }
+        FuzzInstruction::CancelOwnerChange => {
+            Processor::process_cancel_owner_change(program_id, accounts)
+        }
+        FuzzInstruction::Raw(data) => Processor::process(program_id, accounts, data),
+    }
+}
+
+fuzz_target!(|input: ProcessInput| {
+    let program_id = program_id();
+    // Duplicates refer back to an earlier slot; leading ones have nothing to refer to and are dropped.
+    let accounts = input
+        .slots
+        .iter()
+        .take(6)
+        .filter_map(|slot| match slot {
+            FuzzSlot::Account(account) => Some(account),
+            FuzzSlot::Duplicate(_) => None,
+        })
+        .collect::<Vec<_>>();
+    if accounts.is_empty() {
+        return;
+    }
+    let keys = accounts.iter().map(|a| key(a.key)).collect::<Vec<_>>();
+    let owners = accounts
+        .iter()
+        .map(|a| a.owner.to_pubkey())
+        .collect::<Vec<_>>();
+    let mut lamports = accounts.iter().map(|a| a.lamports).collect::<Vec<_>>();
+    let mut data = accounts
+        .iter()
+        .map(|a| {
+            let mut data = a.data.to_bytes();
+            if let Some(len) = a.resize {
+                data.resize(len as usize, 0);
+            }
+            data
+        })
+        .collect::<Vec<_>>();
+    let lamports_before = lamports.iter().map(|&l| l as u128).sum::<u128>();
+
+    let unique_infos = accounts
+        .iter()
+        .zip(keys.iter().zip(owners.iter()))
+        .zip(lamports.iter_mut().zip(data.iter_mut()))
+        .map(|((account, (key, owner)), (lamports, data))| {
+            AccountInfo::new(
+                key,
+                account.is_signer,
+                account.is_writable,
+                lamports,
+                data,
+                owner,
+                false,
+                Epoch::default(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut next_unique = unique_infos.iter();
+    let mut account_infos: Vec<AccountInfo> = Vec::new();
+    for slot in input.slots.iter().take(6) {
+        match slot {
+            FuzzSlot::Account(_) => account_infos.push(next_unique.next().unwrap().clone()),
+            FuzzSlot::Duplicate(index) if !account_infos.is_empty() => {
+                let original = account_infos[*index as usize % account_infos.len()].clone();
+                account_infos.push(original);
+            }
+            FuzzSlot::Duplicate(_) => {}
+        }
+    }
+
+    let result = dispatch(
+        &program_id,
+        &account_infos,
+        &input.instruction,
+        input.direct,
+    );
+    if result.is_ok() {
+        let lamports_after = unique_infos
+            .iter()
+            .map(|info| info.lamports() as u128)
+            .sum::<u128>();
+        assert_eq!(
+            lamports_before, lamports_after,
+            "lamports created or destroyed"
+        );
+    }
+});
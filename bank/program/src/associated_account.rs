@@ -0,0 +1,27 @@
+//! Associated accounts: the one account of a bank every wallet can have at an address
+//! anybody can work out, so a client finds someone's account without tracking it or
+//! reading a registry.
+//!
+//! The address is a PDA of this program derived from the wallet and the bank.
+//! `InitializeAssociatedAccount` creates the account there, rent-exempt and `Account::LEN`
+//! bytes long at the expense of a payer, and opens it for the wallet. The wallet needn't
+//! sign, so anybody paying to open someone's associated account can send to it right away.
+
+use solana_program::pubkey::Pubkey;
+
+/// Prefix of the seeds an associated account derives from.
+pub const SEED: &[u8] = b"associated";
+
+/// The address of `wallet`'s associated account in `bank`, and its bump.
+pub fn address(program_id: &Pubkey, wallet: &Pubkey, bank: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SEED, wallet.as_ref(), bank.as_ref()], program_id)
+}
+
+/// The address of `wallet`'s associated account in `bank`.
+pub fn get_associated_bank_account_address(
+    wallet: &Pubkey,
+    bank: &Pubkey,
+    program_id: &Pubkey,
+) -> Pubkey {
+    address(program_id, wallet, bank).0
+}
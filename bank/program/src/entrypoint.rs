@@ -1,4 +1,4 @@
-use crate::processor::Processor;
+use crate::error::BankError;
 use solana_program::{
     account_info::AccountInfo, entrypoint, entrypoint::ProgramResult,
     program_error::PrintProgramError, pubkey::Pubkey,
@@ -10,8 +10,20 @@ fn process_instruction(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    if let Err(error) = Processor::process(program_id, accounts, instruction_data) {
-        return Err(error);
+    #[cfg(feature = "spl-token-compat")]
+    let result = crate::spl_token::process(program_id, accounts, instruction_data);
+    #[cfg(not(feature = "spl-token-compat"))]
+    let result = crate::processor::Processor::process(program_id, accounts, instruction_data);
+    if let Err(error) = &result {
+        error.print::<BankError>();
     }
-    Ok(())
+    result
+}
+
+/// Stands in for solana-program's handler, which formats the panic message, when built
+/// with `custom-panic`. The runtime still reports the failed instruction.
+#[cfg(all(feature = "custom-panic", target_arch = "bpf"))]
+#[no_mangle]
+fn custom_panic(_info: &core::panic::PanicInfo<'_>) {
+    solana_program::log::sol_log("panicked");
 }
@@ -1,4 +1,151 @@
-#[derive(Clone, Debug, Eq, PartialEq)]
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    program_error::{PrintProgramError, ProgramError},
+};
+use thiserror::Error;
+
+/// Errors the bank program returns as `ProgramError::Custom`, numbered in declaration
+/// order; new ones go at the end so existing codes stay put.
+#[derive(Clone, Debug, Eq, Error, FromPrimitive, PartialEq)]
 pub enum BankError {
+    #[error("Invalid instruction")]
     InvalidInstruction,
+    /// The accounts of an instruction belong to different banks.
+    #[error("Accounts belong to different banks")]
+    BankMismatch,
+    /// An amount or supply would pass `u64::MAX`.
+    #[error("Amount overflow")]
+    Overflow,
+    /// An account holds less than the instruction takes from it.
+    #[error("Insufficient funds")]
+    InsufficientFunds,
+    /// An account still holds tokens and its bank's close policy is `Reject`.
+    #[error("Account still holds tokens")]
+    NonZeroBalance,
+    /// A bank or account holds too few lamports to be rent-exempt.
+    #[error("Account is not rent-exempt")]
+    NotRentExempt,
+    /// The bank isn't open, so its supply can't grow.
+    #[error("Bank is closed")]
+    BankClosed,
+    /// A `PermitApprove` isn't backed by the account owner's signature of its permit, or
+    /// carries a nonce the account has moved past.
+    #[error("Invalid permit")]
+    InvalidPermit,
+    /// A `PermitApprove`'s permit expired.
+    #[error("Permit expired")]
+    PermitExpired,
+    /// The slot passed a `TransferWithExpiry`'s `max_slot`.
+    #[error("Transfer expired")]
+    TransferExpired,
+    /// The bank's `lamports_per_token` is 0, so it doesn't take or pay out SOL.
+    #[error("Bank has no SOL rate")]
+    NoSolRate,
+    /// A `Convert` rounds down to nothing in the other bank.
+    #[error("Conversion rounds to zero")]
+    ConvertsToZero,
+    /// A transfer would change the bank's holder count, but was given the bank read-only.
+    #[error("Bank must be writable")]
+    ReadOnlyBank,
+    /// A `CloseDustAccount` account holds more than dust.
+    #[error("Account holds more than dust")]
+    NotDust,
+    /// Fewer of an account's guardians signed than its recovery threshold.
+    #[error("Not enough guardians signed")]
+    NotEnoughGuardians,
+    /// A `RecoverOwner` or `CancelRecovery` found no recovery started.
+    #[error("No recovery in progress")]
+    NoPendingRecovery,
+    /// A `RecoverOwner` came before its recovery's delay passed.
+    #[error("Recovery delay has not passed")]
+    RecoveryNotReady,
+    /// A session key signed a transfer after its expiry slot.
+    #[error("Session key expired")]
+    SessionExpired,
+    /// A transfer would take a session key past what it may still spend.
+    #[error("Session key spend limit exceeded")]
+    SessionLimitExceeded,
+    /// A transfer would take more out of an account than its rate limit has left, see
+    /// `rate_limit`.
+    #[error("Rate limit exceeded")]
+    RateLimited,
+    /// The program's version record names a newer state layout than this build
+    /// understands, see `version`.
+    #[error("Unsupported state layout")]
+    UnsupportedLayout,
+    /// The bank hasn't enabled the feature an instruction belongs to, see `feature_gate`.
+    #[error("Feature disabled for this bank")]
+    FeatureDisabled,
+    /// The account is frozen, so nothing moves in or out of it until it's thawed.
+    #[error("Account is frozen")]
+    AccountFrozen,
+    /// A `FreezeAccount` or `ThawAccount` for a bank without a freeze authority.
+    #[error("Bank has no freeze authority")]
+    NoFreezeAuthority,
+    /// The account was closed, so it can't take part in transfers or mints.
+    #[error("Account is closed")]
+    AccountClosed,
+    /// An `Approve` or `PermitApprove` names a delegate other than the account's current one.
+    #[error("Account is delegated to another key")]
+    DelegateMismatch,
+    /// A checked instruction's `decimals` aren't its bank's.
+    #[error("Decimals don't match the bank's")]
+    DecimalsMismatch,
+    /// The bank is paused, see `PauseBank`.
+    #[error("Bank is paused")]
+    BankPaused,
+    /// A `CancelOwnerChange` found no change of bank owner pending.
+    #[error("No bank owner change pending")]
+    NoPendingOwnerChange,
+    /// A `SetAuthority` completing a change of bank owner came before its delay passed.
+    #[error("Bank owner change delay has not passed")]
+    OwnerChangeNotReady,
+}
+
+impl From<BankError> for ProgramError {
+    fn from(e: BankError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl<T> DecodeError<T> for BankError {
+    fn type_of() -> &'static str {
+        "BankError"
+    }
+}
+
+impl PrintProgramError for BankError {
+    fn print<E>(&self)
+    where
+        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + FromPrimitive,
+    {
+        log!("Error: {}", self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_code_round_trip() {
+        for error in [
+            BankError::InvalidInstruction,
+            BankError::AccountFrozen,
+            BankError::DelegateMismatch,
+        ] {
+            let code = match ProgramError::from(error.clone()) {
+                ProgramError::Custom(code) => code,
+                e => panic!("unexpected {:?}", e),
+            };
+            assert_eq!(BankError::from_u32(code), Some(error));
+        }
+        assert_eq!(BankError::from_u32(u32::MAX), None);
+        assert_eq!(
+            BankError::DelegateMismatch.to_string(),
+            "Account is delegated to another key"
+        );
+    }
 }
@@ -0,0 +1,75 @@
+//! Feature gates: which of the program's experimental instructions each bank runs, so
+//! they can ship dark and be turned on bank by bank without redeploying.
+//!
+//! A bank's gate is a PDA of this program derived from the bank, holding a bitmask of
+//! the features enabled in it; the bank owner sets it with `SetFeatures`, which creates
+//! it on first use at the expense of a payer. A bank without one runs
+//! `DEFAULT_FEATURES`: the features that shipped before gates did, so that banks opened
+//! before them keep working. A feature shipped dark is left out of it.
+//!
+//! Every gated instruction takes its bank's gate as its last account, whether the gate
+//! exists yet or not. `Processor::process` checks it before the instruction runs and
+//! hands the instruction the accounts before it.
+
+use crate::instruction::BankInstruction;
+use solana_program::pubkey::Pubkey;
+
+/// Prefix of the seeds a gate derives from.
+pub const SEED: &[u8] = b"features";
+
+/// `PermitApprove`.
+pub const PERMITS: u64 = 1 << 0;
+/// `TransferRoute`.
+pub const ROUTES: u64 = 1 << 1;
+
+/// The features a bank without a gate runs.
+pub const DEFAULT_FEATURES: u64 = PERMITS | ROUTES;
+
+/// The address of `bank`'s gate, and its bump.
+pub fn address(program_id: &Pubkey, bank: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SEED, bank.as_ref()], program_id)
+}
+
+/// The feature `instruction` belongs to and the index of its bank among its accounts,
+/// or `None` if no gate stops it.
+pub fn gated(instruction: &BankInstruction) -> Option<(u64, usize)> {
+    match instruction {
+        BankInstruction::PermitApprove { .. } => Some((PERMITS, 2)),
+        BankInstruction::TransferRoute { .. } => Some((ROUTES, 0)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gated() {
+        assert_eq!(
+            gated(&BankInstruction::TransferRoute { amount: 1 }),
+            Some((ROUTES, 0))
+        );
+        let permit = BankInstruction::PermitApprove {
+            amount: 1,
+            nonce: 0,
+            expiry: 0,
+        };
+        assert_eq!(gated(&permit), Some((PERMITS, 2)));
+        assert_eq!(
+            gated(&BankInstruction::Transfer {
+                amount: 1,
+                sweep_dust: false
+            }),
+            None
+        );
+        assert_eq!(gated(&BankInstruction::SetFeatures { enabled: 0 }), None);
+
+        let program_id = Pubkey::new_unique();
+        let (bank, other_bank) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_ne!(
+            address(&program_id, &bank).0,
+            address(&program_id, &other_bank).0
+        );
+    }
+}
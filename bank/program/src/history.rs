@@ -0,0 +1,160 @@
+//! Transfer history: the last transfers of an account, kept in its extension area.
+//!
+//! An account created longer than `Account::LEN` carries the extra bytes as its
+//! extension area, which holds a ring of `TransferRecord`s behind a count of every
+//! transfer recorded so far. Transfers write a record into both accounts, so a light
+//! client shows recent activity from the account alone, without an indexer. Create the
+//! account `account_len(capacity)` bytes long for room for `capacity` records; an
+//! account of plain `Account::LEN` keeps no history. A rate-limit bucket may follow the
+//! records, see `rate_limit`.
+
+use crate::rate_limit;
+use crate::state::Account;
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+
+/// The count of recorded transfers in front of the records.
+pub const HEADER_LEN: usize = 8;
+/// Size of a packed `TransferRecord`.
+pub const RECORD_LEN: usize = 49;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TransferRecord {
+    /// The account on the other side of the transfer.
+    pub counterparty: Pubkey,
+    pub amount: u64,
+    pub slot: u64,
+    /// Whether the tokens came in from `counterparty` rather than went out to it.
+    pub incoming: bool,
+}
+
+impl TransferRecord {
+    fn unpack(src: &[u8; RECORD_LEN]) -> Self {
+        let (counterparty, amount, slot, incoming) = array_refs![src, 32, 8, 8, 1];
+        TransferRecord {
+            counterparty: Pubkey::new_from_array(*counterparty),
+            amount: u64::from_le_bytes(*amount),
+            slot: u64::from_le_bytes(*slot),
+            incoming: incoming[0] == 1,
+        }
+    }
+
+    fn pack(&self, dst: &mut [u8; RECORD_LEN]) {
+        let (counterparty, amount, slot, incoming) = mut_array_refs![dst, 32, 8, 8, 1];
+        counterparty.copy_from_slice(self.counterparty.as_ref());
+        *amount = self.amount.to_le_bytes();
+        *slot = self.slot.to_le_bytes();
+        incoming[0] = self.incoming as u8;
+    }
+}
+
+/// Size of an account with room for `capacity` records.
+pub fn account_len(capacity: usize) -> usize {
+    Account::LEN + HEADER_LEN + capacity * RECORD_LEN
+}
+
+/// Fails with `InvalidAccountData` unless `len` is `Account::LEN`, `account_len` of at
+/// least one record, or `rate_limit::account_len` of any number.
+pub fn check_len(len: usize) -> Result<(), ProgramError> {
+    if len == Account::LEN || rate_limit::has_bucket(len) {
+        return Ok(());
+    }
+    match len.checked_sub(account_len(0)) {
+        Some(records) if records > 0 && records % RECORD_LEN == 0 => Ok(()),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+/// How many records the account `data` keeps; 0 without an extension area. A bucket
+/// behind the records is shorter than one, so it doesn't count.
+pub fn capacity(data: &[u8]) -> usize {
+    data.len().saturating_sub(account_len(0)) / RECORD_LEN
+}
+
+/// Writes `record` over the oldest one once the account's history is full. Does nothing
+/// to an account without an extension area.
+pub fn record(data: &mut [u8], record: &TransferRecord) {
+    let capacity = capacity(data);
+    if capacity == 0 {
+        return;
+    }
+    let count = array_mut_ref![data, Account::LEN, HEADER_LEN];
+    let recorded = u64::from_le_bytes(*count);
+    *count = recorded.wrapping_add(1).to_le_bytes();
+    let offset = account_len((recorded % capacity as u64) as usize);
+    record.pack(array_mut_ref![data, offset, RECORD_LEN]);
+}
+
+/// The records the account `data` keeps, newest first.
+pub fn records(data: &[u8]) -> Vec<TransferRecord> {
+    let capacity = capacity(data);
+    if capacity == 0 {
+        return Vec::new();
+    }
+    let recorded = u64::from_le_bytes(*array_ref![data, Account::LEN, HEADER_LEN]);
+    let kept = recorded.min(capacity as u64);
+    (1..=kept)
+        .map(|age| {
+            let slot = (recorded - age) % capacity as u64;
+            TransferRecord::unpack(array_ref![data, account_len(slot as usize), RECORD_LEN])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer(amount: u64) -> TransferRecord {
+        TransferRecord {
+            counterparty: Pubkey::new_unique(),
+            amount,
+            slot: amount * 10,
+            incoming: amount % 2 == 0,
+        }
+    }
+
+    #[test]
+    fn test_check_len() {
+        assert_eq!(check_len(Account::LEN), Ok(()));
+        assert_eq!(check_len(account_len(1)), Ok(()));
+        assert_eq!(check_len(account_len(32)), Ok(()));
+        for len in [
+            Account::LEN - 1,
+            Account::LEN + 1,
+            account_len(0),
+            account_len(1) + 1,
+            rate_limit::account_len(1) - 1,
+        ] {
+            assert_eq!(check_len(len), Err(ProgramError::InvalidAccountData));
+        }
+        assert_eq!(check_len(rate_limit::account_len(0)), Ok(()));
+        assert_eq!(check_len(rate_limit::account_len(2)), Ok(()));
+    }
+
+    #[test]
+    fn test_ring() {
+        let mut plain = vec![0; Account::LEN];
+        record(&mut plain, &transfer(1));
+        assert_eq!(plain, vec![0; Account::LEN]);
+        assert_eq!(records(&plain), vec![]);
+
+        let mut data = vec![0; account_len(3)];
+        assert_eq!(capacity(&data), 3);
+        assert_eq!(records(&data), vec![]);
+        let transfers: Vec<_> = (1..=5).map(transfer).collect();
+        record(&mut data, &transfers[0]);
+        record(&mut data, &transfers[1]);
+        assert_eq!(records(&data), vec![transfers[1], transfers[0]]);
+        // Past three, each record replaces the oldest.
+        for transfer in &transfers[2..] {
+            record(&mut data, transfer);
+        }
+        assert_eq!(
+            records(&data),
+            vec![transfers[4], transfers[3], transfers[2]]
+        );
+        // The account itself is left alone.
+        assert_eq!(data[..Account::LEN], [0; Account::LEN][..]);
+    }
+}
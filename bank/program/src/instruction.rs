@@ -1,26 +1,529 @@
 use solana_program::instruction::{AccountMeta, Instruction};
 // use crate::error::{self};
-use solana_program::{program_error::ProgramError, pubkey::Pubkey};
-use std::convert::TryInto;
-use std::iter::Inspect;
+use crate::associated_account;
+use crate::feature_gate;
+use crate::multisig::MAX_SIGNERS;
+use crate::pda;
+use crate::recovery::{self, MAX_GUARDIANS};
+use crate::registry;
+use crate::session;
+use crate::state::{BurnPolicy, ClosePolicy};
+use crate::version;
+use solana_program::{
+    program_error::ProgramError, program_option::COption, pubkey::Pubkey, system_program, sysvar,
+};
+use std::convert::{TryFrom, TryInto};
 use std::mem::size_of;
 
+/// Most payments a `TransferBatch` makes; a transaction can't carry many more destinations.
+pub const MAX_BATCH_TRANSFERS: usize = 64;
+
+/// Sysvars are read with `Sysvar::get`, so no instruction takes one as an account, except
+/// `PermitApprove`: the instructions sysvar can only be read as one.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq)]
 pub enum BankInstruction {
-    InitializeBank { decimals: u8 },
+    /// Opens a bank with `decimals` and `burn_policy`; the signer becomes its owner.
+    /// Data without the policy's byte opens it with `BurnPolicy::OwnerAndHolder`. A
+    /// `lamports_per_token` other than 0 lets holders buy and sell its tokens for SOL at
+    /// that rate, see `DepositSol`; data without it opens a bank that doesn't. A
+    /// `freeze_authority` may freeze and thaw the bank's accounts, see `FreezeAccount`;
+    /// data without it opens a bank without one. An `interest_rate_bps` other than 0 pays
+    /// its holders that much interest a year, see `interest`; data without it opens a
+    /// bank that doesn't. The bank must be rent-exempt.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The bank, owned by this program.
+    ///   1. `[signer]` The bank owner, or a multisig that doesn't sign.
+    InitializeBank {
+        decimals: u8,
+        burn_policy: BurnPolicy,
+        lamports_per_token: u64,
+        freeze_authority: COption<Pubkey>,
+        interest_rate_bps: u16,
+    },
 
+    /// Opens an empty account in a bank, which must be rent-exempt. Given the bank's next registry page, the account is also appended to the
+    /// bank's registry, see `registry`.
+    ///
+    /// Accounts expected:
+    ///   0. `[]` The bank, opened and owned by this program; writable to register.
+    ///   1. `[writable]` The account, owned by this program.
+    ///   2. `[signer]` The account owner, or a multisig that doesn't sign.
+    ///   3. `[writable]` Optional: the registry page the account goes on.
+    ///   4. `[writable, signer]` With 3, the payer of the page's rent if it's new.
+    ///   5. `[]` With 3, the system program.
     InitializeAccount,
 
-    Transfer { amount: u64 },
+    /// Moves `amount` between two accounts of the same bank. The bank's statistics count
+    /// it if the bank is writable; a read-only one, as SPL tooling passes, leaves them be,
+    /// and so only takes transfers that leave the number of holders as it is. With
+    /// `sweep_dust`, a source the transfer would leave holding dust, see
+    /// `Bank::dust_threshold`, sends its whole balance instead; data without the flag's
+    /// byte doesn't sweep.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The source account.
+    ///   1. `[writable]` The destination account.
+    ///   2. `[signer]` The source account's owner or delegate, or a session key of it;
+    ///      unsigned if it's a multisig, see `multisig`.
+    ///   3. `[writable]` The accounts' bank, read-only for an uncounted transfer.
+    ///   4. `[writable]` With a session key, its state, see `session::address`.
+    ///   4. ..4+M `[signer]` With a multisig owner instead, its signers.
+    Transfer { amount: u64, sweep_dust: bool },
 
+    /// Lets a delegate spend up to `amount` more of an account's tokens, which stay in it.
+    ///
+    /// Approving used to move the tokens out of `amount` into `delegated_amount`, so the
+    /// tokens of an account approved before the change are missing from its `amount`, and
+    /// the new Revoke drops them. Revoke such delegations with the old program, which
+    /// hands them back, before upgrading it.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account.
+    ///   1. `[]` The delegate.
+    ///   2. `[signer]` The account owner, unsigned if it's a multisig.
+    ///   3. `[]` The account's bank.
+    ///   4. ..4+M `[signer]` With a multisig owner, its signers.
     Approve { amount: u64 },
 
+    /// Creates `amount` new tokens in an account.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The bank, opened.
+    ///   1. `[writable]` The account.
+    ///   2. `[signer]` The bank owner, unsigned if it's a multisig.
+    ///   3. ..3+M `[signer]` With a multisig owner, its signers.
     MintTo { amount: u64 },
 
+    /// Destroys `amount` tokens of an account. The bank owner can be left out when the
+    /// bank's `BurnPolicy` is `HolderOnly`.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The bank.
+    ///   1. `[writable]` The account.
+    ///   2. `[signer]` The bank owner, optional under `HolderOnly`.
+    ///   3. `[signer]` The account owner.
     Burn { amount: u64 },
 
+    /// Closes an account and sends its lamports to the destination. Tokens the account
+    /// still holds are dealt with as the bank's `ClosePolicy` says.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account.
+    ///   1. `[writable]` The destination.
+    ///   2. `[signer]` The account owner.
+    ///   3. `[]` The account's bank, writable if the account still holds tokens.
+    ///   4. `[writable]` Under `ClosePolicy::Sweep`, the account of the same bank that
+    ///      receives the tokens.
     CloseAccount,
+
+    /// Cancels the delegation of an account along with what is left of its allowance.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account.
+    ///   1. `[signer]` The account owner.
+    ///   2. `[]` The account's bank.
+    Revoke,
+
+    /// Sets what closing an account of the bank does with the tokens it still holds.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The bank.
+    ///   1. `[signer]` The bank owner.
+    SetClosePolicy { policy: ClosePolicy },
+
+    /// Sets the bank's `require_rent_exempt` flag. Requiring it needs the bank itself to be
+    /// rent-exempt. `InitializeAccount` requires exemption of every account regardless, so
+    /// the flag only records the bank owner's choice for clients that read it.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The bank.
+    ///   1. `[signer]` The bank owner.
+    SetRequireRentExempt { required: bool },
+
+    /// Lets a delegate spend up to `amount` more of an account's tokens on the owner's
+    /// off-chain signature of the permit `permit::message` describes, instead of theirs
+    /// on the transaction. The instruction right before it must be the ed25519 program's,
+    /// verifying that signature; see `permit::signature_instruction`. `nonce` must be the
+    /// account's `permit_nonce`, which the permit bumps, and the permit is refused once
+    /// the clock passes `expiry`.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account.
+    ///   1. `[]` The delegate.
+    ///   2. `[]` The account's bank.
+    ///   3. `[]` The instructions sysvar.
+    ///   4. `[]` The bank's feature gate, see `feature_gate::address`.
+    PermitApprove {
+        amount: u64,
+        nonce: u64,
+        expiry: i64,
+    },
+
+    /// `Transfer`, refused once the clock's slot passes `max_slot`, so a transfer signed
+    /// ahead of time (e.g. against a durable nonce) can't be sent arbitrarily late.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The source account.
+    ///   1. `[writable]` The destination account.
+    ///   2. `[signer]` The source account's owner or delegate.
+    ///   3. `[writable]` The accounts' bank.
+    TransferWithExpiry { amount: u64, max_slot: u64 },
+
+    /// Moves `amount` along a chain of accounts of the same bank, each hop a `Transfer`
+    /// from one account to the next, so the route goes through whole or not at all.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The accounts' bank.
+    ///   1. `[writable]` The source account.
+    ///   2. `[signer]` The source account's owner or delegate.
+    ///   3. `[writable]` The next account; the destination if the route ends here.
+    ///   4. `[signer]` Unless the route ends at 3, its owner or delegate, and so on: an
+    ///      account and its authority per further hop, then the destination.
+    ///   5. `[]` After the destination, the bank's feature gate, see
+    ///      `feature_gate::address`.
+    TransferRoute { amount: u64 },
+
+    /// Hands an account over to the address `seeds`, bump included, derive from a
+    /// program, so that only that program can move its tokens, by signing with the seeds.
+    /// Its delegation is cancelled. See `pda` for the CPI side.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account.
+    ///   1. `[signer]` The account owner.
+    ///   2. `[]` The account's bank.
+    ///   3. `[]` The program the new owner derives from.
+    AssignToProgram { seeds: Vec<Vec<u8>> },
+
+    /// Moves `lamports` from the depositor into the bank and credits the account with
+    /// the tokens they buy at the bank's `lamports_per_token`. `lamports` must be a
+    /// whole number of tokens' worth.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The bank, taking SOL.
+    ///   1. `[writable]` The account.
+    ///   2. `[writable, signer]` The depositor, a system account.
+    ///   3. `[]` The system program.
+    DepositSol { lamports: u64 },
+
+    /// Burns the tokens `lamports` buys at the bank's `lamports_per_token` from the
+    /// account and pays `lamports` out of the bank. The bank keeps enough to stay
+    /// rent-exempt, so it can't pay for tokens minted with `MintTo`.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The bank, taking SOL.
+    ///   1. `[writable]` The account.
+    ///   2. `[signer]` The account's owner or delegate, unsigned if it's a multisig.
+    ///   3. `[writable]` The destination of the lamports.
+    ///   4. ..4+M `[signer]` With a multisig owner, its signers.
+    WithdrawSol { lamports: u64 },
+
+    /// Sets the rate `Convert` moves tokens of one bank into another at, to `numerator /
+    /// denominator` whole tokens of the second per whole token of the first. Both bank
+    /// owners sign; the first call opens the rate account for the two banks.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The rate account, owned by this program.
+    ///   1. `[]` The bank converted from.
+    ///   2. `[]` The bank converted to.
+    ///   3. `[writable, signer]` The owner of the bank converted from.
+    ///   4. `[writable, signer]` The owner of the bank converted to.
+    SetExchangeRate { numerator: u64, denominator: u64 },
+
+    /// Burns `amount` from an account and mints what it's worth at an `ExchangeRate` to an
+    /// account of the other bank, rescaled between the banks' decimals and rounded down.
+    /// Neither bank's burn policy applies: its owner agreed to the rate.
+    ///
+    /// Accounts expected:
+    ///   0. `[]` The rate account.
+    ///   1. `[writable]` The bank converted from.
+    ///   2. `[writable]` The account converted from.
+    ///   3. `[signer]` Its owner or delegate, unsigned if it's a multisig.
+    ///   4. `[writable]` The bank converted to.
+    ///   5. `[writable]` The account converted to.
+    ///   6. ..6+M `[signer]` With a multisig owner, its signers.
+    Convert { amount: u64 },
+
+    /// Sets the bank's `dust_threshold`.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The bank.
+    ///   1. `[signer]` The bank owner.
+    SetDustThreshold { threshold: u64 },
+
+    /// Closes an account holding nothing or dust, and sends its lamports to its owner.
+    /// Anyone can: the dust is dealt with as the bank's `ClosePolicy` says, except that
+    /// under `Sweep` it goes to another account of the same owner.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account.
+    ///   1. `[writable]` The account owner.
+    ///   2. `[writable]` The account's bank.
+    ///   3. `[writable]` Under `ClosePolicy::Sweep`, if the account holds dust, the
+    ///      owner's account of the same bank that receives it.
+    CloseDustAccount,
+
+    /// Names the guardians who can recover an account, `threshold` of whom must sign,
+    /// and the `delay` in slots between starting and completing a recovery; see
+    /// `recovery`. Cancels any recovery started. The first call creates the account's
+    /// recovery state.
+    ///
+    /// Accounts expected:
+    ///   0. `[]` The account.
+    ///   1. `[signer]` The account owner.
+    ///   2. `[]` The account's bank.
+    ///   3. `[writable]` The account's recovery state, see `recovery::address`.
+    ///   4. `[writable, signer]` The payer of the recovery state's rent if it's new.
+    ///   5. `[]` The system program.
+    SetGuardians {
+        threshold: u8,
+        delay: u64,
+        guardians: Vec<Pubkey>,
+    },
+
+    /// Starts a recovery of an account to `new_owner`, replacing any started before.
+    ///
+    /// Accounts expected:
+    ///   0. `[]` The account.
+    ///   1. `[]` The account's bank.
+    ///   2. `[writable]` The account's recovery state.
+    ///   3. `[signer]` A guardian, and so on: at least the threshold of them.
+    StartRecovery { new_owner: Pubkey },
+
+    /// Completes the started recovery once its delay has passed, making its new owner
+    /// the account's. The account's delegation is cancelled.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account.
+    ///   1. `[]` The account's bank.
+    ///   2. `[writable]` The account's recovery state.
+    ///   3. `[signer]` A guardian, and so on: at least the threshold of them.
+    RecoverOwner,
+
+    /// Calls off the started recovery.
+    ///
+    /// Accounts expected:
+    ///   0. `[]` The account.
+    ///   1. `[signer]` The account owner.
+    ///   2. `[]` The account's bank.
+    ///   3. `[writable]` The account's recovery state.
+    CancelRecovery,
+
+    /// Lets `session_key` transfer up to `max_spend` out of an account, until the slot
+    /// passes `expiry_slot`; see `session`. Replaces any limits the key had. The first
+    /// call for a key creates its session state.
+    ///
+    /// Accounts expected:
+    ///   0. `[]` The account.
+    ///   1. `[signer]` The account owner.
+    ///   2. `[]` The account's bank.
+    ///   3. `[writable]` The key's session state, see `session::address`.
+    ///   4. `[writable, signer]` The payer of the session state's rent if it's new.
+    ///   5. `[]` The system program.
+    CreateSessionKey {
+        session_key: Pubkey,
+        max_spend: u64,
+        expiry_slot: u64,
+    },
+
+    /// Caps how fast tokens leave an account with room for a rate-limit bucket: up to
+    /// `capacity` at once, regaining `refill_per_slot` per slot; see `rate_limit`. A
+    /// `capacity` of 0 lifts the limit. A new limit starts full, a changed one keeps what
+    /// the bucket holds, up to the new capacity.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account, created `rate_limit::account_len` bytes long.
+    ///   1. `[signer]` The account owner.
+    ///   2. `[]` The account's bank.
+    SetRateLimit { capacity: u64, refill_per_slot: u64 },
+
+    /// Records this build's release and state layout in the program's version record,
+    /// see `version`. The first call creates the record.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The version record, see `version::address`.
+    ///   1. `[writable, signer]` The payer of the record's rent if it's new.
+    ///   2. `[]` The system program.
+    InitializeVersion,
+
+    /// Sets the features the bank runs to the bitmask `enabled`, see `feature_gate`.
+    /// The first call for a bank creates its gate.
+    ///
+    /// Accounts expected:
+    ///   0. `[]` The bank.
+    ///   1. `[signer]` The bank owner.
+    ///   2. `[writable]` The bank's feature gate, see `feature_gate::address`.
+    ///   3. `[writable, signer]` The payer of the gate's rent if it's new.
+    ///   4. `[]` The system program.
+    SetFeatures { enabled: u64 },
+
+    /// Freezes an account of a bank with a freeze authority: until it's thawed, no
+    /// instruction moves tokens or SOL in or out of it, approves a delegate of it, or
+    /// closes it.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account.
+    ///   1. `[]` The account's bank.
+    ///   2. `[signer]` The bank's freeze authority or emergency authority.
+    FreezeAccount,
+
+    /// Thaws an account `FreezeAccount` froze. The emergency authority can't: it only stops
+    /// trouble, and the freeze authority decides when an account is safe again.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account.
+    ///   1. `[]` The account's bank.
+    ///   2. `[signer]` The bank's freeze authority.
+    ThawAccount,
+
+    /// Names the signers of a multisig owner, `threshold` of whom must sign in its place,
+    /// see `multisig`. No signer may be named twice.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The multisig, owned by this program and `Multisig::LEN` bytes long.
+    ///   1. ..1+N `[]` The signers, up to `multisig::MAX_SIGNERS`.
+    InitializeMultisig { threshold: u8 },
+
+    /// Creates and opens a wallet's associated account in a bank, see
+    /// `associated_account`. Fails if the account was already created.
+    ///
+    /// Accounts expected:
+    ///   0. `[]` The bank, opened and owned by this program.
+    ///   1. `[writable]` The associated account, see `associated_account::address`.
+    ///   2. `[]` The wallet, the account's owner.
+    ///   3. `[writable, signer]` The payer of the account's rent.
+    ///   4. `[]` The system program.
+    InitializeAssociatedAccount,
+
+    /// `Transfer`, refused with `DecimalsMismatch` unless the bank has `decimals`, so that
+    /// an amount worked out in another bank's units isn't sent as it is. Doesn't sweep dust.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The source account.
+    ///   1. `[writable]` The destination account.
+    ///   2. `[signer]` The source account's owner or delegate, or a session key of it;
+    ///      unsigned if it's a multisig, see `multisig`.
+    ///   3. `[writable]` The accounts' bank, read-only for an uncounted transfer.
+    ///   4. `[writable]` With a session key, its state, see `session::address`.
+    ///   4. ..4+M `[signer]` With a multisig owner instead, its signers.
+    TransferChecked { amount: u64, decimals: u8 },
+
+    /// `MintTo`, refused with `DecimalsMismatch` unless the bank has `decimals`.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The bank, opened.
+    ///   1. `[writable]` The account.
+    ///   2. `[signer]` The bank owner, unsigned if it's a multisig.
+    ///   3. ..3+M `[signer]` With a multisig owner, its signers.
+    MintToChecked { amount: u64, decimals: u8 },
+
+    /// `Burn`, refused with `DecimalsMismatch` unless the bank has `decimals`.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The bank.
+    ///   1. `[writable]` The account.
+    ///   2. `[signer]` The bank owner, optional under `HolderOnly`.
+    ///   3. `[signer]` The account owner.
+    BurnChecked { amount: u64, decimals: u8 },
+
+    /// Hands the `authority_type` authority of an account or a bank to `new_authority`.
+    /// A new account owner starts without a delegate: the old owner's isn't the new one's.
+    /// Only `AuthorityType::EmergencyAuthority` may be removed, with `COption::None`.
+    ///
+    /// In a bank with an `owner_change_delay`, a new bank owner is only recorded as
+    /// pending, from `owner_change_delay` slots on; the same `SetAuthority` sent again
+    /// after that hands the bank over, and `CancelOwnerChange` calls it off meanwhile.
+    /// The bank owner is also the one who mints, and the freeze authority is fixed, so
+    /// this covers every authority over a bank's tokens.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account, or the bank for `AuthorityType::BankOwner` and
+    ///      `AuthorityType::EmergencyAuthority`.
+    ///   1. `[signer]` The current authority, or the bank owner for
+    ///      `AuthorityType::EmergencyAuthority`; unsigned if it's a multisig.
+    ///   2. ..2+M `[signer]` With a multisig authority, its signers.
+    SetAuthority {
+        authority_type: AuthorityType,
+        new_authority: COption<Pubkey>,
+    },
+
+    /// Pays the `i`th of `amounts` from one account to the `i`th destination, each payment
+    /// a `Transfer` of its own, so the batch goes through whole or not at all. Together
+    /// the amounts mustn't pass `u64::MAX`, and there are at most `MAX_BATCH_TRANSFERS`.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The source account.
+    ///   1. `[signer]` The source account's owner or delegate.
+    ///   2. `[writable]` The accounts' bank.
+    ///   3. ..3+N `[writable]` The destinations, one per amount.
+    TransferBatch { amounts: Vec<u64> },
+
+    /// Pauses a bank for an emergency: until `ResumeBank`, every kind of transfer between
+    /// its accounts, `Approve`, `PermitApprove`, `MintTo`, `Burn`, `AccrueInterest`,
+    /// `DepositSol`, `WithdrawSol` and `Convert` fail with `BankPaused`. Revoking, closing
+    /// accounts that don't sweep a balance and handing over authorities still work.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The bank.
+    ///   1. `[signer]` The bank owner or the bank's emergency authority.
+    PauseBank,
+
+    /// Resumes a bank `PauseBank` paused. The emergency authority can't: it only stops
+    /// trouble, and the bank owner decides when the bank is safe again.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The bank.
+    ///   1. `[signer]` The bank owner.
+    ResumeBank,
+
+    /// Pays an account the interest its bank owes it so far, see `interest`. Anyone can
+    /// send it; it does nothing in a bank that pays no interest.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account.
+    ///   1. `[writable]` The account's bank.
+    AccrueInterest,
+
+    /// Sets the bank's `owner_change_delay` to `delay` slots. It can only be raised:
+    /// otherwise a stolen owner key could lower it and take the bank at once.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The bank.
+    ///   1. `[signer]` The bank owner.
+    SetOwnerChangeDelay { delay: u64 },
+
+    /// Calls off the change of bank owner a `SetAuthority` left pending.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The bank.
+    ///   1. `[signer]` The bank owner or the bank's emergency authority.
+    CancelOwnerChange,
+}
+
+/// Which authority a `SetAuthority` hands over.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AuthorityType {
+    /// The owner of an account, who transfers out of it and approves delegates.
+    AccountOwner,
+    /// The owner of a bank, who mints and sets its policies.
+    BankOwner,
+    /// The bank's `emergency_authority`, who may pause it and freeze its accounts.
+    EmergencyAuthority,
+}
+
+impl TryFrom<u8> for AuthorityType {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::AccountOwner),
+            1 => Ok(Self::BankOwner),
+            2 => Ok(Self::EmergencyAuthority),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
 }
 
 impl BankInstruction {
@@ -31,25 +534,231 @@ impl BankInstruction {
 
         Ok(match tag {
             0 => {
-                let (&decimal, _rest) = rest.split_first().ok_or(InvalidInstructionData)?;
-                Self::InitializeBank { decimals: decimal }
+                let (&decimal, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let burn_policy = match rest.first() {
+                    Some(&policy) => {
+                        BurnPolicy::try_from(policy).map_err(|_| InvalidInstructionData)?
+                    }
+                    None => BurnPolicy::default(),
+                };
+                let lamports_per_token = match rest.get(1..) {
+                    None | Some([]) => 0,
+                    Some(rate) => rate
+                        .get(..8)
+                        .and_then(|slice| slice.try_into().ok())
+                        .map(u64::from_le_bytes)
+                        .ok_or(InvalidInstructionData)?,
+                };
+                let (freeze_authority, rest) = match rest.get(9..) {
+                    None | Some([]) => (COption::None, &[][..]),
+                    Some(rest) => Self::unpack_pubkey_option(rest)?,
+                };
+                let interest_rate_bps = match rest {
+                    [] => 0,
+                    [low, high] => u16::from_le_bytes([*low, *high]),
+                    _ => return Err(InvalidInstructionData),
+                };
+                Self::InitializeBank {
+                    decimals: decimal,
+                    burn_policy,
+                    lamports_per_token,
+                    freeze_authority,
+                    interest_rate_bps,
+                }
             }
             1 => Self::InitializeAccount,
-            2 | 3 | 4 | 5 => {
+            2 | 3 | 4 | 5 | 12 | 14 | 15 | 17 | 18 | 27 | 32 | 33 | 34 | 40 => {
                 let amount = rest
                     .get(..8)
                     .and_then(|slice| slice.try_into().ok())
                     .map(u64::from_le_bytes)
                     .ok_or(InvalidInstructionData)?;
+                let decimals = || rest.get(8).copied().ok_or(InvalidInstructionData);
                 match tag {
-                    2 => Self::Transfer { amount },
+                    2 => {
+                        let sweep_dust = match rest.get(8) {
+                            None | Some(0) => false,
+                            Some(1) => true,
+                            Some(_) => return Err(InvalidInstructionData),
+                        };
+                        Self::Transfer { amount, sweep_dust }
+                    }
                     3 => Self::Approve { amount },
                     4 => Self::MintTo { amount },
                     5 => Self::Burn { amount },
+                    12 => Self::TransferRoute { amount },
+                    14 => Self::DepositSol { lamports: amount },
+                    15 => Self::WithdrawSol { lamports: amount },
+                    17 => Self::Convert { amount },
+                    18 => Self::SetDustThreshold { threshold: amount },
+                    27 => Self::SetFeatures { enabled: amount },
+                    32 => Self::TransferChecked {
+                        amount,
+                        decimals: decimals()?,
+                    },
+                    33 => Self::MintToChecked {
+                        amount,
+                        decimals: decimals()?,
+                    },
+                    34 => Self::BurnChecked {
+                        amount,
+                        decimals: decimals()?,
+                    },
+                    40 => Self::SetOwnerChangeDelay { delay: amount },
                     _ => unreachable!(),
                 }
             }
             6 => Self::CloseAccount,
+            19 => Self::CloseDustAccount,
+            7 => Self::Revoke,
+            8 => {
+                let (&policy, _rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let policy = ClosePolicy::try_from(policy).map_err(|_| InvalidInstructionData)?;
+                Self::SetClosePolicy { policy }
+            }
+            9 => {
+                let required = match rest.first() {
+                    Some(0) => false,
+                    Some(1) => true,
+                    _ => return Err(InvalidInstructionData),
+                };
+                Self::SetRequireRentExempt { required }
+            }
+            10 => {
+                let field = |i: usize| -> Result<[u8; 8], ProgramError> {
+                    rest.get(8 * i..8 * i + 8)
+                        .and_then(|slice| slice.try_into().ok())
+                        .ok_or(InvalidInstructionData)
+                };
+                Self::PermitApprove {
+                    amount: u64::from_le_bytes(field(0)?),
+                    nonce: u64::from_le_bytes(field(1)?),
+                    expiry: i64::from_le_bytes(field(2)?),
+                }
+            }
+            11 => {
+                let field = |i: usize| -> Result<u64, ProgramError> {
+                    rest.get(8 * i..8 * i + 8)
+                        .and_then(|slice| slice.try_into().ok())
+                        .map(u64::from_le_bytes)
+                        .ok_or(InvalidInstructionData)
+                };
+                Self::TransferWithExpiry {
+                    amount: field(0)?,
+                    max_slot: field(1)?,
+                }
+            }
+            13 => {
+                let (&count, mut rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let mut seeds = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    let (&len, tail) = rest.split_first().ok_or(InvalidInstructionData)?;
+                    if tail.len() < len as usize {
+                        return Err(InvalidInstructionData);
+                    }
+                    let (seed, tail) = tail.split_at(len as usize);
+                    seeds.push(seed.to_vec());
+                    rest = tail;
+                }
+                Self::AssignToProgram { seeds }
+            }
+            16 => {
+                let field = |i: usize| -> Result<u64, ProgramError> {
+                    rest.get(8 * i..8 * i + 8)
+                        .and_then(|slice| slice.try_into().ok())
+                        .map(u64::from_le_bytes)
+                        .ok_or(InvalidInstructionData)
+                };
+                Self::SetExchangeRate {
+                    numerator: field(0)?,
+                    denominator: field(1)?,
+                }
+            }
+            20 => {
+                let (&threshold, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let delay = rest
+                    .get(..8)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u64::from_le_bytes)
+                    .ok_or(InvalidInstructionData)?;
+                let (&count, mut rest) = rest[8..].split_first().ok_or(InvalidInstructionData)?;
+                let mut guardians = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    let (guardian, tail) = Self::unpack_pubkey(rest)?;
+                    guardians.push(guardian);
+                    rest = tail;
+                }
+                Self::SetGuardians {
+                    threshold,
+                    delay,
+                    guardians,
+                }
+            }
+            21 => {
+                let (new_owner, _rest) = Self::unpack_pubkey(rest)?;
+                Self::StartRecovery { new_owner }
+            }
+            22 => Self::RecoverOwner,
+            23 => Self::CancelRecovery,
+            24 => {
+                let (session_key, rest) = Self::unpack_pubkey(rest)?;
+                let field = |i: usize| -> Result<u64, ProgramError> {
+                    rest.get(8 * i..8 * i + 8)
+                        .and_then(|slice| slice.try_into().ok())
+                        .map(u64::from_le_bytes)
+                        .ok_or(InvalidInstructionData)
+                };
+                Self::CreateSessionKey {
+                    session_key,
+                    max_spend: field(0)?,
+                    expiry_slot: field(1)?,
+                }
+            }
+            25 => {
+                let field = |i: usize| -> Result<u64, ProgramError> {
+                    rest.get(8 * i..8 * i + 8)
+                        .and_then(|slice| slice.try_into().ok())
+                        .map(u64::from_le_bytes)
+                        .ok_or(InvalidInstructionData)
+                };
+                Self::SetRateLimit {
+                    capacity: field(0)?,
+                    refill_per_slot: field(1)?,
+                }
+            }
+            26 => Self::InitializeVersion,
+            28 => Self::FreezeAccount,
+            29 => Self::ThawAccount,
+            30 => {
+                let (&threshold, _rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                Self::InitializeMultisig { threshold }
+            }
+            31 => Self::InitializeAssociatedAccount,
+            35 => {
+                let (&authority_type, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let (new_authority, _rest) = Self::unpack_pubkey_option(rest)?;
+                Self::SetAuthority {
+                    authority_type: AuthorityType::try_from(authority_type)?,
+                    new_authority,
+                }
+            }
+            36 => {
+                let (&count, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let count = count as usize;
+                if count == 0 || count > MAX_BATCH_TRANSFERS || rest.len() < 8 * count {
+                    return Err(InvalidInstructionData);
+                }
+                let amounts = rest
+                    .chunks_exact(8)
+                    .take(count)
+                    .map(|amount| u64::from_le_bytes(amount.try_into().unwrap()))
+                    .collect();
+                Self::TransferBatch { amounts }
+            }
+            37 => Self::PauseBank,
+            38 => Self::ResumeBank,
+            39 => Self::AccrueInterest,
+            41 => Self::CancelOwnerChange,
             _ => {
                 return Err(InvalidInstructionData);
             }
@@ -59,16 +768,27 @@ impl BankInstruction {
     pub fn pack(&self) -> Vec<u8> {
         let mut buf = Vec::with_capacity(size_of::<Self>());
         match self {
-            &Self::InitializeBank { decimals } => {
+            &Self::InitializeBank {
+                decimals,
+                burn_policy,
+                lamports_per_token,
+                freeze_authority,
+                interest_rate_bps,
+            } => {
                 buf.push(0);
                 buf.push(decimals);
+                buf.push(burn_policy as u8);
+                buf.extend_from_slice(&lamports_per_token.to_le_bytes());
+                Self::pack_pubkey_option(&freeze_authority, &mut buf);
+                buf.extend_from_slice(&interest_rate_bps.to_le_bytes());
             }
             &Self::InitializeAccount => {
                 buf.push(1);
             }
-            &Self::Transfer { amount } => {
+            &Self::Transfer { amount, sweep_dust } => {
                 buf.push(2);
                 buf.extend_from_slice(&amount.to_le_bytes());
+                buf.push(sweep_dust as u8);
             }
             &Self::Approve { amount } => {
                 buf.push(3);
@@ -85,6 +805,178 @@ impl BankInstruction {
             &Self::CloseAccount => {
                 buf.push(6);
             }
+            &Self::Revoke => {
+                buf.push(7);
+            }
+            &Self::SetClosePolicy { policy } => {
+                buf.push(8);
+                buf.push(policy as u8);
+            }
+            &Self::SetRequireRentExempt { required } => {
+                buf.push(9);
+                buf.push(required as u8);
+            }
+            &Self::PermitApprove {
+                amount,
+                nonce,
+                expiry,
+            } => {
+                buf.push(10);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(&nonce.to_le_bytes());
+                buf.extend_from_slice(&expiry.to_le_bytes());
+            }
+            &Self::TransferWithExpiry { amount, max_slot } => {
+                buf.push(11);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(&max_slot.to_le_bytes());
+            }
+            &Self::TransferRoute { amount } => {
+                buf.push(12);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::AssignToProgram { seeds } => {
+                buf.push(13);
+                buf.push(seeds.len() as u8);
+                for seed in seeds {
+                    buf.push(seed.len() as u8);
+                    buf.extend_from_slice(seed);
+                }
+            }
+            &Self::DepositSol { lamports } => {
+                buf.push(14);
+                buf.extend_from_slice(&lamports.to_le_bytes());
+            }
+            &Self::WithdrawSol { lamports } => {
+                buf.push(15);
+                buf.extend_from_slice(&lamports.to_le_bytes());
+            }
+            &Self::SetExchangeRate {
+                numerator,
+                denominator,
+            } => {
+                buf.push(16);
+                buf.extend_from_slice(&numerator.to_le_bytes());
+                buf.extend_from_slice(&denominator.to_le_bytes());
+            }
+            &Self::Convert { amount } => {
+                buf.push(17);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            &Self::SetDustThreshold { threshold } => {
+                buf.push(18);
+                buf.extend_from_slice(&threshold.to_le_bytes());
+            }
+            &Self::CloseDustAccount => {
+                buf.push(19);
+            }
+            Self::SetGuardians {
+                threshold,
+                delay,
+                guardians,
+            } => {
+                buf.push(20);
+                buf.push(*threshold);
+                buf.extend_from_slice(&delay.to_le_bytes());
+                buf.push(guardians.len() as u8);
+                for guardian in guardians {
+                    buf.extend_from_slice(guardian.as_ref());
+                }
+            }
+            Self::StartRecovery { new_owner } => {
+                buf.push(21);
+                buf.extend_from_slice(new_owner.as_ref());
+            }
+            &Self::RecoverOwner => {
+                buf.push(22);
+            }
+            &Self::CancelRecovery => {
+                buf.push(23);
+            }
+            Self::CreateSessionKey {
+                session_key,
+                max_spend,
+                expiry_slot,
+            } => {
+                buf.push(24);
+                buf.extend_from_slice(session_key.as_ref());
+                buf.extend_from_slice(&max_spend.to_le_bytes());
+                buf.extend_from_slice(&expiry_slot.to_le_bytes());
+            }
+            Self::SetRateLimit {
+                capacity,
+                refill_per_slot,
+            } => {
+                buf.push(25);
+                buf.extend_from_slice(&capacity.to_le_bytes());
+                buf.extend_from_slice(&refill_per_slot.to_le_bytes());
+            }
+            &Self::InitializeVersion => {
+                buf.push(26);
+            }
+            Self::SetFeatures { enabled } => {
+                buf.push(27);
+                buf.extend_from_slice(&enabled.to_le_bytes());
+            }
+            &Self::FreezeAccount => {
+                buf.push(28);
+            }
+            &Self::ThawAccount => {
+                buf.push(29);
+            }
+            &Self::InitializeMultisig { threshold } => {
+                buf.push(30);
+                buf.push(threshold);
+            }
+            &Self::InitializeAssociatedAccount => {
+                buf.push(31);
+            }
+            &Self::TransferChecked { amount, decimals } => {
+                buf.push(32);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.push(decimals);
+            }
+            &Self::MintToChecked { amount, decimals } => {
+                buf.push(33);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.push(decimals);
+            }
+            &Self::BurnChecked { amount, decimals } => {
+                buf.push(34);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.push(decimals);
+            }
+            Self::SetAuthority {
+                authority_type,
+                new_authority,
+            } => {
+                buf.push(35);
+                buf.push(*authority_type as u8);
+                Self::pack_pubkey_option(new_authority, &mut buf);
+            }
+            Self::TransferBatch { amounts } => {
+                buf.push(36);
+                buf.push(amounts.len() as u8);
+                for amount in amounts {
+                    buf.extend_from_slice(&amount.to_le_bytes());
+                }
+            }
+            &Self::PauseBank => {
+                buf.push(37);
+            }
+            &Self::ResumeBank => {
+                buf.push(38);
+            }
+            &Self::AccrueInterest => {
+                buf.push(39);
+            }
+            &Self::SetOwnerChangeDelay { delay } => {
+                buf.push(40);
+                buf.extend_from_slice(&delay.to_le_bytes());
+            }
+            &Self::CancelOwnerChange => {
+                buf.push(41);
+            }
         };
         buf
     }
@@ -97,6 +989,27 @@ impl BankInstruction {
         }
         Err(ProgramError::InvalidInstructionData)
     }
+
+    fn unpack_pubkey_option(input: &[u8]) -> Result<(COption<Pubkey>, &[u8]), ProgramError> {
+        match input.split_first() {
+            Some((&0, rest)) => Ok((COption::None, rest)),
+            Some((&1, rest)) => {
+                let (key, rest) = Self::unpack_pubkey(rest)?;
+                Ok((COption::Some(key), rest))
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+
+    fn pack_pubkey_option(value: &COption<Pubkey>, buf: &mut Vec<u8>) {
+        match value {
+            COption::Some(key) => {
+                buf.push(1);
+                buf.extend_from_slice(key.as_ref());
+            }
+            COption::None => buf.push(0),
+        }
+    }
 }
 
 pub fn initialize_bank(
@@ -105,7 +1018,90 @@ pub fn initialize_bank(
     bank_owner: &Pubkey,
     decimals: u8,
 ) -> Result<Instruction, ProgramError> {
-    let data = BankInstruction::InitializeBank { decimals }.pack();
+    initialize_bank_with_burn_policy(
+        bank_program_id,
+        bank,
+        bank_owner,
+        decimals,
+        BurnPolicy::default(),
+    )
+}
+
+/// `initialize_bank` for a bank whose holders burn their tokens as `burn_policy` says.
+pub fn initialize_bank_with_burn_policy(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    bank_owner: &Pubkey,
+    decimals: u8,
+    burn_policy: BurnPolicy,
+) -> Result<Instruction, ProgramError> {
+    initialize_bank_with_sol_rate(bank_program_id, bank, bank_owner, decimals, burn_policy, 0)
+}
+
+/// `initialize_bank_with_burn_policy` for a bank whose tokens sell for
+/// `lamports_per_token` each.
+pub fn initialize_bank_with_sol_rate(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    bank_owner: &Pubkey,
+    decimals: u8,
+    burn_policy: BurnPolicy,
+    lamports_per_token: u64,
+) -> Result<Instruction, ProgramError> {
+    initialize_bank_with_freeze_authority(
+        bank_program_id,
+        bank,
+        bank_owner,
+        decimals,
+        burn_policy,
+        lamports_per_token,
+        None,
+    )
+}
+
+/// `initialize_bank_with_sol_rate` for a bank whose accounts `freeze_authority`, if any,
+/// may freeze.
+pub fn initialize_bank_with_freeze_authority(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    bank_owner: &Pubkey,
+    decimals: u8,
+    burn_policy: BurnPolicy,
+    lamports_per_token: u64,
+    freeze_authority: Option<&Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    initialize_bank_with_interest_rate(
+        bank_program_id,
+        bank,
+        bank_owner,
+        decimals,
+        burn_policy,
+        lamports_per_token,
+        freeze_authority,
+        0,
+    )
+}
+
+/// `initialize_bank_with_freeze_authority` for a bank paying `interest_rate_bps` a year.
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_bank_with_interest_rate(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    bank_owner: &Pubkey,
+    decimals: u8,
+    burn_policy: BurnPolicy,
+    lamports_per_token: u64,
+    freeze_authority: Option<&Pubkey>,
+    interest_rate_bps: u16,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::InitializeBank {
+        decimals,
+        burn_policy,
+        lamports_per_token,
+        freeze_authority: freeze_authority.cloned().into(),
+        interest_rate_bps,
+    }
+    .pack();
     let accounts = vec![
         AccountMeta::new(*bank, false),
         AccountMeta::new(*bank_owner, true),
@@ -136,19 +1132,69 @@ pub fn initialize_account(
     })
 }
 
+/// `initialize_account` that appends the account to the bank's registry, on page `page`
+/// as `registry::next_slot` says; `payer` pays for the page if the account is its first.
+pub fn initialize_registered_account(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    bank_account: &Pubkey,
+    bank_account_owner: &Pubkey,
+    payer: &Pubkey,
+    page: u32,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction =
+        initialize_account(bank_program_id, bank, bank_account, bank_account_owner)?;
+    let (registry_page, _) = registry::page_address(bank_program_id, bank, page);
+    instruction.accounts.extend_from_slice(&[
+        AccountMeta::new(registry_page, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]);
+    Ok(instruction)
+}
+
 pub fn transfer(
     bank_program_id: &Pubkey,
+    bank: &Pubkey,
     from_account: &Pubkey,
     to_account: &Pubkey,
     from_account_owner: &Pubkey,
     amount: u64,
 ) -> Result<Instruction, ProgramError> {
-    let data = BankInstruction::Transfer { amount }.pack();
-    let accounts = vec![
+    transfer_multisig(
+        bank_program_id,
+        bank,
+        from_account,
+        to_account,
+        from_account_owner,
+        &[],
+        amount,
+    )
+}
+
+/// `transfer` out of an account whose owner is the multisig `from_account_owner`, on the
+/// signatures of `signers`; without signers, the owner signs as in `transfer`.
+pub fn transfer_multisig(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    from_account: &Pubkey,
+    to_account: &Pubkey,
+    from_account_owner: &Pubkey,
+    signers: &[&Pubkey],
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::Transfer {
+        amount,
+        sweep_dust: false,
+    }
+    .pack();
+    let mut accounts = vec![
         AccountMeta::new(*from_account, false),
         AccountMeta::new(*to_account, false),
-        AccountMeta::new(*from_account_owner, true),
+        AccountMeta::new(*from_account_owner, signers.is_empty()),
+        AccountMeta::new(*bank, false),
     ];
+    accounts.extend(signer_metas(signers));
     Ok(Instruction {
         program_id: *bank_program_id,
         accounts,
@@ -156,18 +1202,72 @@ pub fn transfer(
     })
 }
 
-pub fn approve(
+/// `transfer_multisig` refused unless the bank has `decimals`; without signers, the owner
+/// signs as in `transfer`.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked(
     bank_program_id: &Pubkey,
-    account: &Pubkey,
-    delegated_account: &Pubkey,
-    account_owner: &Pubkey,
+    bank: &Pubkey,
+    from_account: &Pubkey,
+    to_account: &Pubkey,
+    from_account_owner: &Pubkey,
+    signers: &[&Pubkey],
     amount: u64,
+    decimals: u8,
 ) -> Result<Instruction, ProgramError> {
-    let data = BankInstruction::Approve { amount }.pack();
+    let mut instruction = transfer_multisig(
+        bank_program_id,
+        bank,
+        from_account,
+        to_account,
+        from_account_owner,
+        signers,
+        amount,
+    )?;
+    instruction.data = BankInstruction::TransferChecked { amount, decimals }.pack();
+    Ok(instruction)
+}
+
+/// `transfer` that sends the source's whole balance if `amount` would leave it dust.
+pub fn transfer_sweeping_dust(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    from_account: &Pubkey,
+    to_account: &Pubkey,
+    from_account_owner: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = transfer(
+        bank_program_id,
+        bank,
+        from_account,
+        to_account,
+        from_account_owner,
+        amount,
+    )?;
+    instruction.data = BankInstruction::Transfer {
+        amount,
+        sweep_dust: true,
+    }
+    .pack();
+    Ok(instruction)
+}
+
+pub fn transfer_with_expiry(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    from_account: &Pubkey,
+    to_account: &Pubkey,
+    from_account_owner: &Pubkey,
+    amount: u64,
+    max_slot: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::TransferWithExpiry { amount, max_slot }.pack();
     let accounts = vec![
-        AccountMeta::new(*account, false),
-        AccountMeta::new(*delegated_account, false),
-        AccountMeta::new(*account_owner, true),
+        AccountMeta::new(*from_account, false),
+        AccountMeta::new(*to_account, false),
+        AccountMeta::new(*from_account_owner, true),
+        AccountMeta::new(*bank, false),
     ];
     Ok(Instruction {
         program_id: *bank_program_id,
@@ -176,19 +1276,27 @@ pub fn approve(
     })
 }
 
-pub fn mint_to(
+/// `hops` are the accounts the route sends from, each with its owner or delegate, in
+/// order.
+pub fn transfer_route(
     bank_program_id: &Pubkey,
     bank: &Pubkey,
-    mint_account: &Pubkey,
-    bank_owner: &Pubkey,
+    hops: &[(Pubkey, Pubkey)],
+    destination: &Pubkey,
     amount: u64,
 ) -> Result<Instruction, ProgramError> {
-    let data = BankInstruction::MintTo { amount }.pack();
-    let accounts = vec![
-        AccountMeta::new(*bank, false),
-        AccountMeta::new(*mint_account, false),
-        AccountMeta::new(*bank_owner, true),
-    ];
+    if hops.is_empty() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let data = BankInstruction::TransferRoute { amount }.pack();
+    let mut accounts = vec![AccountMeta::new(*bank, false)];
+    for (account, authority) in hops {
+        accounts.push(AccountMeta::new(*account, false));
+        accounts.push(AccountMeta::new(*authority, true));
+    }
+    accounts.push(AccountMeta::new(*destination, false));
+    let (gate, _) = feature_gate::address(bank_program_id, bank);
+    accounts.push(AccountMeta::new_readonly(gate, false));
     Ok(Instruction {
         program_id: *bank_program_id,
         accounts,
@@ -196,21 +1304,30 @@ pub fn mint_to(
     })
 }
 
-pub fn burn(
+/// Pays each destination of `transfers` its amount from `from`, all or nothing.
+pub fn transfer_batch(
     bank_program_id: &Pubkey,
     bank: &Pubkey,
-    burn_account: &Pubkey,
-    bank_owner: &Pubkey,
-    burn_account_owner: &Pubkey,
-    amount: u64,
+    from: &Pubkey,
+    owner: &Pubkey,
+    transfers: &[(&Pubkey, u64)],
 ) -> Result<Instruction, ProgramError> {
-    let data = BankInstruction::Burn { amount }.pack();
-    let accounts = vec![
+    if transfers.is_empty() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    if transfers.len() > MAX_BATCH_TRANSFERS {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let amounts = transfers.iter().map(|&(_, amount)| amount).collect();
+    let data = BankInstruction::TransferBatch { amounts }.pack();
+    let mut accounts = vec![
+        AccountMeta::new(*from, false),
+        AccountMeta::new(*owner, true),
         AccountMeta::new(*bank, false),
-        AccountMeta::new(*burn_account, false),
-        AccountMeta::new(*bank_owner, true),
-        AccountMeta::new(*burn_account_owner, true),
     ];
+    for (destination, _) in transfers {
+        accounts.push(AccountMeta::new(**destination, false));
+    }
     Ok(Instruction {
         program_id: *bank_program_id,
         accounts,
@@ -218,15 +1335,26 @@ pub fn burn(
     })
 }
 
-pub fn close_account(
+/// Fails like the program would unless `seeds` derive an address from `program`, so
+/// they also fit the instruction's length bytes.
+pub fn assign_to_program(
     bank_program_id: &Pubkey,
-    closed_account: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
     account_owner: &Pubkey,
+    program: &Pubkey,
+    seeds: &[&[u8]],
 ) -> Result<Instruction, ProgramError> {
-    let data = BankInstruction::CloseAccount.pack();
+    pda::derive_owner(seeds, program)?;
+    let data = BankInstruction::AssignToProgram {
+        seeds: seeds.iter().map(|seed| seed.to_vec()).collect(),
+    }
+    .pack();
     let accounts = vec![
-        AccountMeta::new(*closed_account, false),
+        AccountMeta::new(*account, false),
         AccountMeta::new(*account_owner, true),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*program, false),
     ];
     Ok(Instruction {
         program_id: *bank_program_id,
@@ -234,3 +1362,925 @@ pub fn close_account(
         data,
     })
 }
+
+pub fn approve(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+    delegated_account: &Pubkey,
+    account_owner: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    approve_multisig(
+        bank_program_id,
+        bank,
+        account,
+        delegated_account,
+        account_owner,
+        &[],
+        amount,
+    )
+}
+
+/// `approve` for an account whose owner is the multisig `account_owner`, on the
+/// signatures of `signers`; without signers, the owner signs as in `approve`.
+pub fn approve_multisig(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+    delegated_account: &Pubkey,
+    account_owner: &Pubkey,
+    signers: &[&Pubkey],
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::Approve { amount }.pack();
+    let mut accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new(*delegated_account, false),
+        AccountMeta::new(*account_owner, signers.is_empty()),
+        AccountMeta::new_readonly(*bank, false),
+    ];
+    accounts.extend(signer_metas(signers));
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn revoke(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+    account_owner: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::Revoke.pack();
+    let accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new(*account_owner, true),
+        AccountMeta::new_readonly(*bank, false),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn mint_to(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    mint_account: &Pubkey,
+    bank_owner: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    mint_to_multisig(bank_program_id, bank, mint_account, bank_owner, &[], amount)
+}
+
+/// `mint_to` for a bank whose owner is the multisig `bank_owner`, on the signatures of
+/// `signers`; without signers, the owner signs as in `mint_to`.
+pub fn mint_to_multisig(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    mint_account: &Pubkey,
+    bank_owner: &Pubkey,
+    signers: &[&Pubkey],
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::MintTo { amount }.pack();
+    let mut accounts = vec![
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(*mint_account, false),
+        AccountMeta::new(*bank_owner, signers.is_empty()),
+    ];
+    accounts.extend(signer_metas(signers));
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// `mint_to_multisig` refused unless the bank has `decimals`; without signers, the owner
+/// signs as in `mint_to`.
+pub fn mint_to_checked(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    mint_account: &Pubkey,
+    bank_owner: &Pubkey,
+    signers: &[&Pubkey],
+    amount: u64,
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = mint_to_multisig(
+        bank_program_id,
+        bank,
+        mint_account,
+        bank_owner,
+        signers,
+        amount,
+    )?;
+    instruction.data = BankInstruction::MintToChecked { amount, decimals }.pack();
+    Ok(instruction)
+}
+
+pub fn burn(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    burn_account: &Pubkey,
+    bank_owner: &Pubkey,
+    burn_account_owner: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::Burn { amount }.pack();
+    let accounts = vec![
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(*burn_account, false),
+        AccountMeta::new(*bank_owner, true),
+        AccountMeta::new(*burn_account_owner, true),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// `burn` in a bank whose `BurnPolicy` is `HolderOnly`, signed by the account owner alone.
+pub fn burn_by_holder(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    burn_account: &Pubkey,
+    burn_account_owner: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::Burn { amount }.pack();
+    let accounts = vec![
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(*burn_account, false),
+        AccountMeta::new(*burn_account_owner, true),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// `burn` refused unless the bank has `decimals`.
+pub fn burn_checked(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    burn_account: &Pubkey,
+    bank_owner: &Pubkey,
+    burn_account_owner: &Pubkey,
+    amount: u64,
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = burn(
+        bank_program_id,
+        bank,
+        burn_account,
+        bank_owner,
+        burn_account_owner,
+        amount,
+    )?;
+    instruction.data = BankInstruction::BurnChecked { amount, decimals }.pack();
+    Ok(instruction)
+}
+
+pub fn set_authority(
+    bank_program_id: &Pubkey,
+    owned: &Pubkey,
+    authority_type: AuthorityType,
+    current_authority: &Pubkey,
+    new_authority: Option<&Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    set_authority_multisig(
+        bank_program_id,
+        owned,
+        authority_type,
+        current_authority,
+        &[],
+        new_authority,
+    )
+}
+
+/// `set_authority` for a multisig `current_authority`, on the signatures of `signers`;
+/// without signers, the authority signs as in `set_authority`.
+pub fn set_authority_multisig(
+    bank_program_id: &Pubkey,
+    owned: &Pubkey,
+    authority_type: AuthorityType,
+    current_authority: &Pubkey,
+    signers: &[&Pubkey],
+    new_authority: Option<&Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::SetAuthority {
+        authority_type,
+        new_authority: new_authority.cloned().into(),
+    }
+    .pack();
+    let mut accounts = vec![
+        AccountMeta::new(*owned, false),
+        AccountMeta::new(*current_authority, signers.is_empty()),
+    ];
+    accounts.extend(signer_metas(signers));
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn close_account(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    closed_account: &Pubkey,
+    destination: &Pubkey,
+    account_owner: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::CloseAccount.pack();
+    let accounts = vec![
+        AccountMeta::new(*closed_account, false),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new(*account_owner, true),
+        AccountMeta::new_readonly(*bank, false),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// `close_account` for an account that may still hold tokens: the bank is writable, for
+/// its supply and holder count, and `sweep_account` receives them under
+/// `ClosePolicy::Sweep`.
+pub fn close_account_with_balance(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    closed_account: &Pubkey,
+    destination: &Pubkey,
+    account_owner: &Pubkey,
+    sweep_account: Option<&Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::CloseAccount.pack();
+    let mut accounts = vec![
+        AccountMeta::new(*closed_account, false),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new(*account_owner, true),
+        AccountMeta::new(*bank, false),
+    ];
+    if let Some(sweep_account) = sweep_account {
+        accounts.push(AccountMeta::new(*sweep_account, false));
+    }
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn set_close_policy(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    bank_owner: &Pubkey,
+    policy: ClosePolicy,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::SetClosePolicy { policy }.pack();
+    let accounts = vec![
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(*bank_owner, true),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn set_require_rent_exempt(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    bank_owner: &Pubkey,
+    required: bool,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::SetRequireRentExempt { required }.pack();
+    let accounts = vec![
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(*bank_owner, true),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn permit_approve(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+    delegated_account: &Pubkey,
+    amount: u64,
+    nonce: u64,
+    expiry: i64,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::PermitApprove {
+        amount,
+        nonce,
+        expiry,
+    }
+    .pack();
+    let accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new_readonly(*delegated_account, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        AccountMeta::new_readonly(feature_gate::address(bank_program_id, bank).0, false),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn deposit_sol(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+    depositor: &Pubkey,
+    lamports: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::DepositSol { lamports }.pack();
+    let accounts = vec![
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(*account, false),
+        AccountMeta::new(*depositor, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn withdraw_sol(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+    account_owner: &Pubkey,
+    destination: &Pubkey,
+    lamports: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::WithdrawSol { lamports }.pack();
+    let accounts = vec![
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(*account, false),
+        AccountMeta::new(*account_owner, true),
+        AccountMeta::new(*destination, false),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn set_exchange_rate(
+    bank_program_id: &Pubkey,
+    rate: &Pubkey,
+    from_bank: &Pubkey,
+    to_bank: &Pubkey,
+    from_bank_owner: &Pubkey,
+    to_bank_owner: &Pubkey,
+    numerator: u64,
+    denominator: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::SetExchangeRate {
+        numerator,
+        denominator,
+    }
+    .pack();
+    let accounts = vec![
+        AccountMeta::new(*rate, false),
+        AccountMeta::new_readonly(*from_bank, false),
+        AccountMeta::new_readonly(*to_bank, false),
+        AccountMeta::new(*from_bank_owner, true),
+        AccountMeta::new(*to_bank_owner, true),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn convert(
+    bank_program_id: &Pubkey,
+    rate: &Pubkey,
+    from_bank: &Pubkey,
+    from_account: &Pubkey,
+    from_account_owner: &Pubkey,
+    to_bank: &Pubkey,
+    to_account: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::Convert { amount }.pack();
+    let accounts = vec![
+        AccountMeta::new_readonly(*rate, false),
+        AccountMeta::new(*from_bank, false),
+        AccountMeta::new(*from_account, false),
+        AccountMeta::new(*from_account_owner, true),
+        AccountMeta::new(*to_bank, false),
+        AccountMeta::new(*to_account, false),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn set_dust_threshold(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    bank_owner: &Pubkey,
+    threshold: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::SetDustThreshold { threshold }.pack();
+    let accounts = vec![
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(*bank_owner, true),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn set_owner_change_delay(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    bank_owner: &Pubkey,
+    delay: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::SetOwnerChangeDelay { delay }.pack();
+    let accounts = vec![
+        AccountMeta::new(*bank, false),
+        AccountMeta::new_readonly(*bank_owner, true),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// `authority` is the bank owner or the bank's emergency authority.
+pub fn cancel_owner_change(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    authority: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*bank, false),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data: BankInstruction::CancelOwnerChange.pack(),
+    })
+}
+
+pub fn pause_bank(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    bank_owner: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    pause_instruction(
+        bank_program_id,
+        bank,
+        bank_owner,
+        BankInstruction::PauseBank,
+    )
+}
+
+pub fn resume_bank(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    bank_owner: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    pause_instruction(
+        bank_program_id,
+        bank,
+        bank_owner,
+        BankInstruction::ResumeBank,
+    )
+}
+
+fn pause_instruction(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    bank_owner: &Pubkey,
+    instruction: BankInstruction,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*bank, false),
+        AccountMeta::new_readonly(*bank_owner, true),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data: instruction.pack(),
+    })
+}
+
+pub fn accrue_interest(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new(*bank, false),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data: BankInstruction::AccrueInterest.pack(),
+    })
+}
+
+/// `sweep_account`, an account of the same owner, receives the dust under
+/// `ClosePolicy::Sweep`.
+pub fn close_dust_account(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+    account_owner: &Pubkey,
+    sweep_account: Option<&Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::CloseDustAccount.pack();
+    let mut accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new(*account_owner, false),
+        AccountMeta::new(*bank, false),
+    ];
+    if let Some(sweep_account) = sweep_account {
+        accounts.push(AccountMeta::new(*sweep_account, false));
+    }
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Fails like the program would given more than `MAX_GUARDIANS` guardians, so they also
+/// fit the instruction's count byte. `payer` pays for the recovery state if it's new.
+#[allow(clippy::too_many_arguments)]
+pub fn set_guardians(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+    account_owner: &Pubkey,
+    payer: &Pubkey,
+    guardians: &[Pubkey],
+    threshold: u8,
+    delay: u64,
+) -> Result<Instruction, ProgramError> {
+    if guardians.len() > MAX_GUARDIANS {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let data = BankInstruction::SetGuardians {
+        threshold,
+        delay,
+        guardians: guardians.to_vec(),
+    }
+    .pack();
+    let (recovery, _) = recovery::address(bank_program_id, account);
+    let accounts = vec![
+        AccountMeta::new_readonly(*account, false),
+        AccountMeta::new_readonly(*account_owner, true),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new(recovery, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// `guardians` are the ones signing.
+pub fn start_recovery(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+    guardians: &[Pubkey],
+    new_owner: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::StartRecovery {
+        new_owner: *new_owner,
+    }
+    .pack();
+    let (recovery, _) = recovery::address(bank_program_id, account);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*account, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new(recovery, false),
+    ];
+    accounts.extend(
+        guardians
+            .iter()
+            .map(|guardian| AccountMeta::new_readonly(*guardian, true)),
+    );
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// `guardians` are the ones signing.
+pub fn recover_owner(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+    guardians: &[Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::RecoverOwner.pack();
+    let (recovery, _) = recovery::address(bank_program_id, account);
+    let mut accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new(recovery, false),
+    ];
+    accounts.extend(
+        guardians
+            .iter()
+            .map(|guardian| AccountMeta::new_readonly(*guardian, true)),
+    );
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn cancel_recovery(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+    account_owner: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::CancelRecovery.pack();
+    let (recovery, _) = recovery::address(bank_program_id, account);
+    let accounts = vec![
+        AccountMeta::new_readonly(*account, false),
+        AccountMeta::new_readonly(*account_owner, true),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new(recovery, false),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// `payer` pays for the session state if it's new.
+#[allow(clippy::too_many_arguments)]
+pub fn create_session_key(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+    account_owner: &Pubkey,
+    payer: &Pubkey,
+    session_key: &Pubkey,
+    max_spend: u64,
+    expiry_slot: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::CreateSessionKey {
+        session_key: *session_key,
+        max_spend,
+        expiry_slot,
+    }
+    .pack();
+    let (session, _) = session::address(bank_program_id, account, session_key);
+    let accounts = vec![
+        AccountMeta::new_readonly(*account, false),
+        AccountMeta::new_readonly(*account_owner, true),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new(session, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// `transfer` signed by a session key of the source instead of its owner.
+pub fn transfer_with_session_key(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    from_account: &Pubkey,
+    to_account: &Pubkey,
+    session_key: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = transfer(
+        bank_program_id,
+        bank,
+        from_account,
+        to_account,
+        session_key,
+        amount,
+    )?;
+    let (session, _) = session::address(bank_program_id, from_account, session_key);
+    instruction.accounts.push(AccountMeta::new(session, false));
+    Ok(instruction)
+}
+
+pub fn set_rate_limit(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+    account_owner: &Pubkey,
+    capacity: u64,
+    refill_per_slot: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::SetRateLimit {
+        capacity,
+        refill_per_slot,
+    }
+    .pack();
+    let accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new_readonly(*account_owner, true),
+        AccountMeta::new_readonly(*bank, false),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// `payer` pays for the version record if it's new.
+pub fn initialize_version(
+    bank_program_id: &Pubkey,
+    payer: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::InitializeVersion.pack();
+    let (record, _) = version::address(bank_program_id);
+    let accounts = vec![
+        AccountMeta::new(record, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// `payer` pays for the bank's feature gate if it's new.
+pub fn set_features(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    bank_owner: &Pubkey,
+    payer: &Pubkey,
+    enabled: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::SetFeatures { enabled }.pack();
+    let (gate, _) = feature_gate::address(bank_program_id, bank);
+    let accounts = vec![
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_owner, true),
+        AccountMeta::new(gate, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn freeze_account(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+    freeze_authority: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    freeze_instruction(
+        bank_program_id,
+        bank,
+        account,
+        freeze_authority,
+        BankInstruction::FreezeAccount,
+    )
+}
+
+pub fn thaw_account(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+    freeze_authority: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    freeze_instruction(
+        bank_program_id,
+        bank,
+        account,
+        freeze_authority,
+        BankInstruction::ThawAccount,
+    )
+}
+
+fn freeze_instruction(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    account: &Pubkey,
+    freeze_authority: &Pubkey,
+    instruction: BankInstruction,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*freeze_authority, true),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data: instruction.pack(),
+    })
+}
+
+/// Fails like the program would given more than `MAX_SIGNERS` signers.
+pub fn initialize_multisig(
+    bank_program_id: &Pubkey,
+    multisig: &Pubkey,
+    signers: &[&Pubkey],
+    threshold: u8,
+) -> Result<Instruction, ProgramError> {
+    if signers.len() > MAX_SIGNERS {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let data = BankInstruction::InitializeMultisig { threshold }.pack();
+    let mut accounts = vec![AccountMeta::new(*multisig, false)];
+    accounts.extend(
+        signers
+            .iter()
+            .map(|signer| AccountMeta::new_readonly(**signer, false)),
+    );
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn initialize_associated_account(
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    wallet: &Pubkey,
+    payer: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = BankInstruction::InitializeAssociatedAccount.pack();
+    let (account, _) = associated_account::address(bank_program_id, wallet, bank);
+    let accounts = vec![
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new(account, false),
+        AccountMeta::new_readonly(*wallet, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *bank_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// The metas of a multisig's `signers`, following an instruction's other accounts.
+fn signer_metas<'a>(signers: &'a [&Pubkey]) -> impl Iterator<Item = AccountMeta> + 'a {
+    signers
+        .iter()
+        .map(|signer| AccountMeta::new_readonly(**signer, true))
+}
@@ -0,0 +1,116 @@
+//! Interest: the yield a bank pays on its accounts' balances, at the yearly
+//! `Bank::interest_rate_bps` it was opened with.
+//!
+//! Each account keeps the time it last accrued. Accruing pays it simple interest on its
+//! balance for the seconds since, mints that into the bank's supply, and restarts its
+//! clock; an account's first accrual only starts it. In a bank paying interest, every
+//! instruction that changes a balance accrues the account first, so interest is always
+//! paid on what the account held all along. `AccrueInterest` accrues an account nobody
+//! touches, which compounds its interest. Interest rounds down to whole base units, in
+//! the bank's favour.
+
+use crate::error::BankError;
+use crate::math::try_add;
+use crate::state::{Account, Bank};
+use solana_program::program_error::ProgramError;
+use std::convert::TryFrom;
+
+/// Basis points in a whole.
+pub const BPS: u128 = 10_000;
+/// Seconds in the year `Bank::interest_rate_bps` is paid over.
+pub const SECONDS_PER_YEAR: u128 = 365 * 24 * 60 * 60;
+
+/// Interest on `amount` at `rate_bps` a year for `elapsed` seconds, rounded down; none
+/// for a negative `elapsed`. `BankError::Overflow` if it passes `u64::MAX`.
+pub fn earned(amount: u64, rate_bps: u16, elapsed: i64) -> Result<u64, ProgramError> {
+    let elapsed = u128::try_from(elapsed).unwrap_or(0);
+    let interest = (amount as u128 * rate_bps as u128)
+        .checked_mul(elapsed)
+        .ok_or(BankError::Overflow)?
+        / (BPS * SECONDS_PER_YEAR);
+    u64::try_from(interest).map_err(|_| BankError::Overflow.into())
+}
+
+/// Pays the packed account `data` the interest `bank` owes it at `now`, adding it to the
+/// bank's supply. Does nothing in a bank that pays none.
+pub fn accrue(bank: &mut Bank, data: &mut [u8], now: i64) -> Result<(), ProgramError> {
+    if bank.interest_rate_bps == 0 {
+        return Ok(());
+    }
+    let last_accrual = Account::unpack_last_accrual(data);
+    if last_accrual != 0 {
+        let amount = Account::unpack_amount(data);
+        let interest = earned(
+            amount,
+            bank.interest_rate_bps,
+            now.saturating_sub(last_accrual),
+        )?;
+        bank.total_supply = try_add(bank.total_supply, interest)?;
+        Account::pack_amount(data, try_add(amount, interest)?);
+    }
+    // A clock behind the account's doesn't wind it back.
+    Account::pack_last_accrual(data, now.max(last_accrual));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::program_pack::Pack;
+
+    const YEAR: i64 = SECONDS_PER_YEAR as i64;
+
+    #[test]
+    fn test_earned() {
+        // 5% of 1,000,000 is 50,000 a year, and half that in half a year.
+        assert_eq!(earned(1_000_000, 500, YEAR), Ok(50_000));
+        assert_eq!(earned(1_000_000, 500, YEAR / 2), Ok(25_000));
+        // A second's interest on it is a fraction of a unit, which rounds down.
+        assert_eq!(earned(1_000_000, 500, 1), Ok(0));
+        assert_eq!(earned(1_000_000, 500, -YEAR), Ok(0));
+        assert_eq!(earned(0, 500, YEAR), Ok(0));
+        assert_eq!(
+            earned(u64::MAX, 10_000, YEAR * 2),
+            Err(BankError::Overflow.into())
+        );
+        assert_eq!(
+            earned(u64::MAX, u16::MAX, i64::MAX),
+            Err(BankError::Overflow.into())
+        );
+    }
+
+    #[test]
+    fn test_accrue() {
+        let mut data = vec![0; Account::LEN];
+        Account::pack_amount(&mut data, 1_000_000);
+        let mut bank = Bank {
+            total_supply: 1_000_000,
+            ..Bank::default()
+        };
+        // A bank paying no interest leaves the account be.
+        accrue(&mut bank, &mut data, YEAR).unwrap();
+        assert_eq!(Account::unpack_last_accrual(&data), 0);
+
+        bank.interest_rate_bps = 500;
+        // The first accrual only starts the account's clock.
+        accrue(&mut bank, &mut data, YEAR).unwrap();
+        assert_eq!(Account::unpack_amount(&data), 1_000_000);
+        assert_eq!(Account::unpack_last_accrual(&data), YEAR);
+
+        accrue(&mut bank, &mut data, YEAR * 2).unwrap();
+        assert_eq!(Account::unpack_amount(&data), 1_050_000);
+        assert_eq!(bank.total_supply, 1_050_000);
+        assert_eq!(Account::unpack_last_accrual(&data), YEAR * 2);
+
+        // A clock running behind pays nothing and keeps the account's time.
+        accrue(&mut bank, &mut data, YEAR).unwrap();
+        assert_eq!(Account::unpack_amount(&data), 1_050_000);
+        assert_eq!(Account::unpack_last_accrual(&data), YEAR * 2);
+
+        bank.total_supply = u64::MAX;
+        assert_eq!(
+            accrue(&mut bank, &mut data, YEAR * 3),
+            Err(BankError::Overflow.into())
+        );
+    }
+}
@@ -1,7 +1,31 @@
+/// `msg!` unless built with the `no-log` feature.
+macro_rules! log {
+    ($($arg:tt)*) => {{
+        #[cfg(not(feature = "no-log"))]
+        solana_program::msg!($($arg)*);
+    }};
+}
+
+pub mod associated_account;
 pub mod error;
+pub mod feature_gate;
+pub mod history;
 pub mod instruction;
-pub mod state;
+pub mod interest;
+pub mod math;
+pub mod multisig;
+pub mod pda;
+pub mod permit;
 pub mod processor;
+pub mod rate_limit;
+pub mod recovery;
+pub mod registry;
+pub mod session;
+pub mod spl_token;
+pub mod state;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+pub mod version;
 
 #[cfg(not(feature = "no-entrypoint"))]
 mod entrypoint;
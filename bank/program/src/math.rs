@@ -0,0 +1,84 @@
+//! Checked arithmetic on amounts, failing with the bank's own errors rather than a
+//! generic `InvalidArgument`.
+
+use crate::error::BankError;
+use solana_program::program_error::ProgramError;
+use std::convert::TryFrom;
+
+/// `a + b`, or `BankError::Overflow` past `u64::MAX`.
+pub fn try_add(a: u64, b: u64) -> Result<u64, ProgramError> {
+    a.checked_add(b).ok_or_else(|| BankError::Overflow.into())
+}
+
+/// `a - b`, or `BankError::InsufficientFunds` when `a` holds less than `b`.
+pub fn try_sub(a: u64, b: u64) -> Result<u64, ProgramError> {
+    a.checked_sub(b)
+        .ok_or_else(|| BankError::InsufficientFunds.into())
+}
+
+/// `amount` base units of a bank with `from_decimals` in base units of one with
+/// `to_decimals`, at `numerator / denominator` whole tokens for one. Rounds down, so a
+/// conversion never comes out worth more than went in; `BankError::Overflow` if the
+/// result passes `u64::MAX`. `denominator` must not be 0.
+pub fn convert(
+    amount: u64,
+    numerator: u64,
+    denominator: u64,
+    from_decimals: u8,
+    to_decimals: u8,
+) -> Result<u64, ProgramError> {
+    let value = amount as u128 * numerator as u128;
+    let converted = if to_decimals >= from_decimals {
+        pow10(to_decimals - from_decimals)
+            .and_then(|scale| value.checked_mul(scale))
+            .ok_or(BankError::Overflow)?
+            / denominator as u128
+    } else {
+        match pow10(from_decimals - to_decimals)
+            .and_then(|scale| scale.checked_mul(denominator as u128))
+        {
+            Some(divisor) => value / divisor,
+            // Past `u128::MAX`, the divisor is larger than any `value`.
+            None => 0,
+        }
+    };
+    u64::try_from(converted).map_err(|_| BankError::Overflow.into())
+}
+
+fn pow10(exponent: u8) -> Option<u128> {
+    10u128.checked_pow(exponent as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds() {
+        assert_eq!(try_add(u64::MAX - 1, 1), Ok(u64::MAX));
+        assert_eq!(try_add(u64::MAX, 1), Err(BankError::Overflow.into()));
+        assert_eq!(try_sub(5, 5), Ok(0));
+        assert_eq!(try_sub(5, 6), Err(BankError::InsufficientFunds.into()));
+    }
+
+    #[test]
+    fn test_convert() {
+        // 1.50 at 3 for 2 is 2.250.
+        assert_eq!(convert(150, 3, 2, 2, 3), Ok(2_250));
+        // 2.250 back at 2 for 3 is 1.50.
+        assert_eq!(convert(2_250, 2, 3, 3, 2), Ok(150));
+        // Fractions of the smallest unit round down: 0.01 at 1 for 3 is 0.00333...
+        assert_eq!(convert(1, 1, 3, 2, 2), Ok(0));
+        assert_eq!(convert(5, 1, 1, 2, 1), Ok(0));
+        assert_eq!(
+            convert(u64::MAX, 1, 1, 0, 255),
+            Err(BankError::Overflow.into())
+        );
+        assert_eq!(
+            convert(u64::MAX, 2, 1, 0, 0),
+            Err(BankError::Overflow.into())
+        );
+        assert_eq!(convert(u64::MAX, u64::MAX, 1, 255, 0), Ok(0));
+        assert_eq!(convert(u64::MAX, u64::MAX, u64::MAX, 9, 9), Ok(u64::MAX));
+    }
+}
@@ -0,0 +1,80 @@
+//! Multisig owners: an account of this program standing in for a bank owner or an
+//! account owner, whose `threshold` of up to `MAX_SIGNERS` signers sign in its place.
+//!
+//! `InitializeMultisig` names the signers and the threshold once, in an account created
+//! `Multisig::LEN` bytes long and owned by this program. A bank or account whose owner is
+//! the multisig's address is then minted to, transferred out of or approved from by
+//! passing the multisig, unsigned, where the owner goes, and enough of its signers after
+//! the instruction's other accounts. A multisig can't be changed; name a new one instead.
+
+use crate::state::Multisig;
+use solana_program::{account_info::AccountInfo, program_pack::Pack, pubkey::Pubkey};
+
+/// Most signers a multisig can have, as in SPL Token.
+pub const MAX_SIGNERS: usize = 11;
+
+/// Whether `authority_info` is a multisig: an account of this program `Multisig::LEN`
+/// bytes long. An account already borrowed, as the one an instruction acts on is, isn't.
+pub fn is_multisig(program_id: &Pubkey, authority_info: &AccountInfo) -> bool {
+    authority_info.owner == program_id
+        && authority_info
+            .try_borrow_data()
+            .map_or(false, |data| data.len() == Multisig::LEN)
+}
+
+/// How many of `multisig`'s signers signed among `signers`, each counted once.
+pub fn approvals(multisig: &Multisig, signers: &[AccountInfo]) -> usize {
+    multisig
+        .signers()
+        .iter()
+        .filter(|signer| {
+            signers
+                .iter()
+                .any(|info| info.is_signer && info.key == *signer)
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    #[test]
+    fn test_approvals() {
+        let keys: Vec<_> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let mut multisig = Multisig {
+            threshold: 2,
+            signer_count: 3,
+            ..Multisig::default()
+        };
+        multisig.signers[..3].copy_from_slice(&keys);
+
+        let program_id = Pubkey::new_unique();
+        let mut lamports = vec![0; 4];
+        let mut data = vec![vec![]; 4];
+        let signer_keys = [keys[0], keys[0], keys[1], keys[2]];
+        let signed = [true, true, false, true];
+        let infos: Vec<_> = lamports
+            .iter_mut()
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, (lamports, data))| {
+                AccountInfo::new(
+                    &signer_keys[i],
+                    signed[i],
+                    false,
+                    lamports,
+                    data,
+                    &program_id,
+                    false,
+                    Epoch::default(),
+                )
+            })
+            .collect();
+        // A signer passed twice counts once, and one that didn't sign not at all.
+        assert_eq!(approvals(&multisig, &infos), 2);
+        assert_eq!(approvals(&multisig, &infos[..2]), 1);
+        assert!(!is_multisig(&program_id, &infos[0]));
+    }
+}
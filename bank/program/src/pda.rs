@@ -0,0 +1,73 @@
+//! Accounts owned by another program, through a program derived address (PDA).
+//!
+//! Nobody holds the key of a PDA, so only the program it's derived from can sign for it,
+//! with `invoke_signed` and the seeds that derive it. `AssignToProgram` makes such an
+//! address the owner of an account, checking it derives from the given program and
+//! seeds; from then on the program moves the account's tokens by CPI into the bank.
+//! A vault paying out of an account it was handed, for instance:
+//!
+//! ```ignore
+//! let (vault, bump) = Pubkey::find_program_address(&[b"vault"], program_id);
+//! let seeds: &[&[u8]] = &[b"vault", &[bump]];
+//! pda::assert_pda_owner(&Account::unpack(&account_info.data.borrow())?, seeds, program_id)?;
+//! let instruction = instruction::transfer(
+//!     bank_program_info.key,
+//!     bank_info.key,
+//!     account_info.key,
+//!     destination_info.key,
+//!     &vault,
+//!     amount,
+//! )?;
+//! invoke_signed(
+//!     &instruction,
+//!     &[account_info.clone(), destination_info.clone(), vault_info.clone(), bank_info.clone()],
+//!     &[seeds],
+//! )?;
+//! ```
+
+use crate::state::Account;
+use solana_program::{entrypoint::ProgramResult, program_error::ProgramError, pubkey::Pubkey};
+
+/// The address `seeds`, bump included, derive from `program_id`.
+pub fn derive_owner(seeds: &[&[u8]], program_id: &Pubkey) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(seeds, program_id)?)
+}
+
+/// Fails with `IllegalOwner` unless `account` is owned by the address `seeds` derive
+/// from `program_id`, so the program can sign for it with those seeds.
+pub fn assert_pda_owner(account: &Account, seeds: &[&[u8]], program_id: &Pubkey) -> ProgramResult {
+    if account.owner != derive_owner(seeds, program_id)? {
+        return Err(ProgramError::IllegalOwner);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assert_pda_owner() {
+        let program_id = Pubkey::new_unique();
+        let (vault, bump) = Pubkey::find_program_address(&[b"vault"], &program_id);
+        let seeds: &[&[u8]] = &[b"vault", &[bump]];
+        assert_eq!(derive_owner(seeds, &program_id), Ok(vault));
+
+        let account = Account {
+            owner: vault,
+            ..Account::default()
+        };
+        assert_eq!(assert_pda_owner(&account, seeds, &program_id), Ok(()));
+        // The vault of another program.
+        let other_program_id = Pubkey::new_unique();
+        let (_, other_bump) = Pubkey::find_program_address(&[b"vault"], &other_program_id);
+        assert_eq!(
+            assert_pda_owner(&account, &[b"vault", &[other_bump]], &other_program_id),
+            Err(ProgramError::IllegalOwner)
+        );
+        assert_eq!(
+            assert_pda_owner(&account, &[&[0; 33]], &program_id),
+            Err(ProgramError::MaxSeedLengthExceeded)
+        );
+    }
+}
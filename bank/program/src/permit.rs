@@ -0,0 +1,142 @@
+//! Permits: approvals an account owner signs off-chain for `PermitApprove`.
+//!
+//! The program can't check an ed25519 signature itself. The transaction carries an
+//! instruction of the ed25519 program, which fails the transaction unless the signature
+//! holds, right before `PermitApprove`; the program reads it through the instructions
+//! sysvar and checks that it verified the owner's key over the permit's message.
+
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use std::convert::TryInto;
+
+/// The ed25519 program, whose id solana-program 1.8 doesn't export.
+pub mod ed25519_program {
+    solana_program::declare_id!("Ed25519SigVerify111111111111111111111111111");
+}
+
+/// What every permit message starts with, so it can't pass for another signed message.
+pub const MESSAGE_PREFIX: &[u8] = b"solana_bank permit";
+
+// The ed25519 program's data: a count of signatures and a padding byte, then, per
+// signature, 7 `u16`s locating its parts. A part's instruction index of `u16::MAX`
+// means the ed25519 instruction's own data.
+const OFFSETS_START: usize = 2;
+const OFFSETS_LEN: usize = 14;
+const PUBKEY_OFFSET: usize = OFFSETS_START + OFFSETS_LEN;
+const SIGNATURE_OFFSET: usize = PUBKEY_OFFSET + 32;
+const MESSAGE_OFFSET: usize = SIGNATURE_OFFSET + 64;
+const THIS_INSTRUCTION: u16 = u16::MAX;
+
+/// The message the owner of `account` signs to let `delegate` spend `amount` more of its
+/// tokens, with the account's current permit nonce, until `expiry` (a unix timestamp).
+pub fn message(
+    program_id: &Pubkey,
+    account: &Pubkey,
+    delegate: &Pubkey,
+    amount: u64,
+    nonce: u64,
+    expiry: i64,
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(MESSAGE_PREFIX.len() + 3 * 32 + 3 * 8);
+    message.extend_from_slice(MESSAGE_PREFIX);
+    message.extend_from_slice(program_id.as_ref());
+    message.extend_from_slice(account.as_ref());
+    message.extend_from_slice(delegate.as_ref());
+    message.extend_from_slice(&amount.to_le_bytes());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message.extend_from_slice(&expiry.to_le_bytes());
+    message
+}
+
+/// The ed25519 program instruction verifying `signature` by `owner` of `message`, to go
+/// right before the `PermitApprove` it's for.
+pub fn signature_instruction(owner: &Pubkey, signature: &[u8; 64], message: &[u8]) -> Instruction {
+    let mut data = Vec::with_capacity(MESSAGE_OFFSET + message.len());
+    data.push(1);
+    data.push(0);
+    for offset in [
+        SIGNATURE_OFFSET as u16,
+        THIS_INSTRUCTION,
+        PUBKEY_OFFSET as u16,
+        THIS_INSTRUCTION,
+        MESSAGE_OFFSET as u16,
+        message.len() as u16,
+        THIS_INSTRUCTION,
+    ] {
+        data.extend_from_slice(&offset.to_le_bytes());
+    }
+    data.extend_from_slice(owner.as_ref());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+    Instruction {
+        program_id: ed25519_program::id(),
+        accounts: vec![],
+        data,
+    }
+}
+
+/// The key and message an ed25519 program instruction's data verifies, if it verifies
+/// exactly one signature, all of whose parts are in the data itself.
+pub fn verified(data: &[u8]) -> Option<(Pubkey, &[u8])> {
+    if data.first() != Some(&1) {
+        return None;
+    }
+    let offsets = data.get(OFFSETS_START..OFFSETS_START + OFFSETS_LEN)?;
+    let offset = |i: usize| u16::from_le_bytes(offsets[2 * i..2 * i + 2].try_into().unwrap());
+    let (pubkey_offset, message_offset, message_size) =
+        (offset(2) as usize, offset(4) as usize, offset(5) as usize);
+    if [offset(1), offset(3), offset(6)]
+        .iter()
+        .any(|&index| index != THIS_INSTRUCTION)
+    {
+        return None;
+    }
+    let pubkey = data
+        .get(pubkey_offset..pubkey_offset + 32)?
+        .try_into()
+        .ok()?;
+    let message = data.get(message_offset..message_offset + message_size)?;
+    Some((Pubkey::new_from_array(pubkey), message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verified() {
+        let owner = Pubkey::new_unique();
+        let message = message(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            100,
+            0,
+            1_700_000_000,
+        );
+        let instruction = signature_instruction(&owner, &[7; 64], &message);
+        assert_eq!(instruction.program_id, ed25519_program::id());
+        assert_eq!(
+            &instruction.data[SIGNATURE_OFFSET..MESSAGE_OFFSET],
+            &[7; 64][..]
+        );
+        assert_eq!(
+            verified(&instruction.data),
+            Some((owner, message.as_slice()))
+        );
+
+        // Two signatures.
+        let mut data = instruction.data.clone();
+        data[0] = 2;
+        assert_eq!(verified(&data), None);
+
+        // The message in another instruction.
+        let mut data = instruction.data.clone();
+        data[OFFSETS_START + 12..OFFSETS_START + 14].copy_from_slice(&0u16.to_le_bytes());
+        assert_eq!(verified(&data), None);
+
+        // A message running past the data.
+        let data = &instruction.data[..instruction.data.len() - 1];
+        assert_eq!(verified(data), None);
+        assert_eq!(verified(&[]), None);
+    }
+}
@@ -1,60 +1,300 @@
-use std::{alloc::GlobalAlloc, borrow::Borrow};
-
-use crate::instruction::BankInstruction;
-use crate::state::{Account, Bank};
+use crate::associated_account;
+use crate::error::BankError;
+use crate::feature_gate;
+use crate::history::{self, TransferRecord};
+use crate::instruction::{AuthorityType, BankInstruction};
+use crate::interest;
+use crate::math::{convert, try_add, try_sub};
+use crate::multisig::{self, MAX_SIGNERS};
+use crate::pda;
+use crate::permit;
+use crate::rate_limit;
+use crate::recovery::{self, MAX_GUARDIANS};
+use crate::registry;
+use crate::session;
+use crate::state::{
+    Account, Balances, Bank, BurnPolicy, ClosePolicy, ExchangeRate, FeatureGate, Multisig,
+    Recovery, Session, Version,
+};
+use crate::version;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
-    decode_error::DecodeError,
+    clock::Clock,
     entrypoint::ProgramResult,
-    msg,
-    program_error::{PrintProgramError, ProgramError},
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
     program_option::COption,
-    program_pack::{IsInitialized, Pack},
+    program_pack::Pack,
     pubkey::Pubkey,
-    sysvar::{rent::Rent, Sysvar},
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::{instructions, Sysvar},
 };
 
 pub struct Processor {}
 impl Processor {
     pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
         let instruction = BankInstruction::unpack(input)?;
+        let accounts = Self::check_feature_gate(program_id, accounts, &instruction)?;
 
         match instruction {
-            BankInstruction::InitializeBank { decimals } => {
-                msg!("Instruction: InitializeBank");
-                Self::process_initialize_bank(program_id, accounts, decimals)
+            BankInstruction::InitializeBank {
+                decimals,
+                burn_policy,
+                lamports_per_token,
+                freeze_authority,
+                interest_rate_bps,
+            } => {
+                log!("Instruction: InitializeBank");
+                Self::process_initialize_bank(
+                    program_id,
+                    accounts,
+                    decimals,
+                    burn_policy,
+                    lamports_per_token,
+                    freeze_authority,
+                    interest_rate_bps,
+                )
             }
             BankInstruction::InitializeAccount => {
-                msg!("Instruction: InitializeAccount");
+                log!("Instruction: InitializeAccount");
                 Self::process_initialize_account(program_id, accounts)
             }
-            BankInstruction::Transfer { amount } => {
-                msg!("Instruction: Transfer");
-                Self::process_transfer(program_id, accounts, amount)
+            BankInstruction::Transfer { amount, sweep_dust } => {
+                log!("Instruction: Transfer");
+                Self::process_transfer(program_id, accounts, amount, sweep_dust)
             }
             BankInstruction::Approve { amount } => {
-                msg!("Instruction: Approve");
+                log!("Instruction: Approve");
                 Self::process_approve(program_id, accounts, amount)
             }
             BankInstruction::MintTo { amount } => {
-                msg!("Instruction: MintTo");
+                log!("Instruction: MintTo");
                 Self::process_mint_to(program_id, accounts, amount)
             }
             BankInstruction::Burn { amount } => {
-                msg!("Instruction: Burn");
+                log!("Instruction: Burn");
                 Self::process_burn(program_id, accounts, amount)
             }
             BankInstruction::CloseAccount => {
-                msg!("Instruction: CloseAccount");
+                log!("Instruction: CloseAccount");
                 Self::process_close_account(program_id, accounts)
             }
+            BankInstruction::Revoke => {
+                log!("Instruction: Revoke");
+                Self::process_revoke(program_id, accounts)
+            }
+            BankInstruction::SetClosePolicy { policy } => {
+                log!("Instruction: SetClosePolicy");
+                Self::process_set_close_policy(program_id, accounts, policy)
+            }
+            BankInstruction::SetRequireRentExempt { required } => {
+                log!("Instruction: SetRequireRentExempt");
+                Self::process_set_require_rent_exempt(program_id, accounts, required)
+            }
+            BankInstruction::PermitApprove {
+                amount,
+                nonce,
+                expiry,
+            } => {
+                log!("Instruction: PermitApprove");
+                Self::process_permit_approve(program_id, accounts, amount, nonce, expiry)
+            }
+            BankInstruction::TransferWithExpiry { amount, max_slot } => {
+                log!("Instruction: TransferWithExpiry");
+                Self::process_transfer_with_expiry(program_id, accounts, amount, max_slot)
+            }
+            BankInstruction::TransferRoute { amount } => {
+                log!("Instruction: TransferRoute");
+                Self::process_transfer_route(program_id, accounts, amount)
+            }
+            BankInstruction::AssignToProgram { seeds } => {
+                log!("Instruction: AssignToProgram");
+                let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+                Self::process_assign_to_program(program_id, accounts, &seeds)
+            }
+            BankInstruction::DepositSol { lamports } => {
+                log!("Instruction: DepositSol");
+                Self::process_deposit_sol(program_id, accounts, lamports)
+            }
+            BankInstruction::WithdrawSol { lamports } => {
+                log!("Instruction: WithdrawSol");
+                Self::process_withdraw_sol(program_id, accounts, lamports)
+            }
+            BankInstruction::SetExchangeRate {
+                numerator,
+                denominator,
+            } => {
+                log!("Instruction: SetExchangeRate");
+                Self::process_set_exchange_rate(program_id, accounts, numerator, denominator)
+            }
+            BankInstruction::Convert { amount } => {
+                log!("Instruction: Convert");
+                Self::process_convert(program_id, accounts, amount)
+            }
+            BankInstruction::SetDustThreshold { threshold } => {
+                log!("Instruction: SetDustThreshold");
+                Self::process_set_dust_threshold(program_id, accounts, threshold)
+            }
+            BankInstruction::CloseDustAccount => {
+                log!("Instruction: CloseDustAccount");
+                Self::process_close_dust_account(program_id, accounts)
+            }
+            BankInstruction::SetGuardians {
+                threshold,
+                delay,
+                guardians,
+            } => {
+                log!("Instruction: SetGuardians");
+                Self::process_set_guardians(program_id, accounts, threshold, delay, &guardians)
+            }
+            BankInstruction::StartRecovery { new_owner } => {
+                log!("Instruction: StartRecovery");
+                Self::process_start_recovery(program_id, accounts, new_owner)
+            }
+            BankInstruction::RecoverOwner => {
+                log!("Instruction: RecoverOwner");
+                Self::process_recover_owner(program_id, accounts)
+            }
+            BankInstruction::CancelRecovery => {
+                log!("Instruction: CancelRecovery");
+                Self::process_cancel_recovery(program_id, accounts)
+            }
+            BankInstruction::CreateSessionKey {
+                session_key,
+                max_spend,
+                expiry_slot,
+            } => {
+                log!("Instruction: CreateSessionKey");
+                Self::process_create_session_key(
+                    program_id,
+                    accounts,
+                    session_key,
+                    max_spend,
+                    expiry_slot,
+                )
+            }
+            BankInstruction::SetRateLimit {
+                capacity,
+                refill_per_slot,
+            } => {
+                log!("Instruction: SetRateLimit");
+                Self::process_set_rate_limit(program_id, accounts, capacity, refill_per_slot)
+            }
+            BankInstruction::InitializeVersion => {
+                log!("Instruction: InitializeVersion");
+                Self::process_initialize_version(program_id, accounts)
+            }
+            BankInstruction::SetFeatures { enabled } => {
+                log!("Instruction: SetFeatures");
+                Self::process_set_features(program_id, accounts, enabled)
+            }
+            BankInstruction::FreezeAccount => {
+                log!("Instruction: FreezeAccount");
+                Self::process_freeze_account(program_id, accounts, true)
+            }
+            BankInstruction::ThawAccount => {
+                log!("Instruction: ThawAccount");
+                Self::process_freeze_account(program_id, accounts, false)
+            }
+            BankInstruction::InitializeMultisig { threshold } => {
+                log!("Instruction: InitializeMultisig");
+                Self::process_initialize_multisig(program_id, accounts, threshold)
+            }
+            BankInstruction::InitializeAssociatedAccount => {
+                log!("Instruction: InitializeAssociatedAccount");
+                Self::process_initialize_associated_account(program_id, accounts)
+            }
+            BankInstruction::TransferChecked { amount, decimals } => {
+                log!("Instruction: TransferChecked");
+                Self::check_decimals(program_id, accounts, 3, decimals)?;
+                Self::process_transfer(program_id, accounts, amount, false)
+            }
+            BankInstruction::MintToChecked { amount, decimals } => {
+                log!("Instruction: MintToChecked");
+                Self::check_decimals(program_id, accounts, 0, decimals)?;
+                Self::process_mint_to(program_id, accounts, amount)
+            }
+            BankInstruction::BurnChecked { amount, decimals } => {
+                log!("Instruction: BurnChecked");
+                Self::check_decimals(program_id, accounts, 0, decimals)?;
+                Self::process_burn(program_id, accounts, amount)
+            }
+            BankInstruction::SetAuthority {
+                authority_type,
+                new_authority,
+            } => {
+                log!("Instruction: SetAuthority");
+                Self::process_set_authority(program_id, accounts, authority_type, new_authority)
+            }
+            BankInstruction::TransferBatch { amounts } => {
+                log!("Instruction: TransferBatch");
+                Self::process_transfer_batch(program_id, accounts, &amounts)
+            }
+            BankInstruction::PauseBank => {
+                log!("Instruction: PauseBank");
+                Self::process_pause_bank(program_id, accounts, true)
+            }
+            BankInstruction::ResumeBank => {
+                log!("Instruction: ResumeBank");
+                Self::process_pause_bank(program_id, accounts, false)
+            }
+            BankInstruction::AccrueInterest => {
+                log!("Instruction: AccrueInterest");
+                Self::process_accrue_interest(program_id, accounts)
+            }
+            BankInstruction::SetOwnerChangeDelay { delay } => {
+                log!("Instruction: SetOwnerChangeDelay");
+                Self::process_set_owner_change_delay(program_id, accounts, delay)
+            }
+            BankInstruction::CancelOwnerChange => {
+                log!("Instruction: CancelOwnerChange");
+                Self::process_cancel_owner_change(program_id, accounts)
+            }
         }
     }
 
+    /// Fails with `FeatureDisabled` if `instruction` is gated and its bank hasn't enabled
+    /// its feature, otherwise returns the accounts the instruction runs with: all but the
+    /// gate. A list whose last account isn't the gate of the bank lacks it.
+    fn check_feature_gate<'a, 'b>(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+        instruction: &BankInstruction,
+    ) -> Result<&'a [AccountInfo<'b>], ProgramError> {
+        let (feature, bank_index) = match feature_gate::gated(instruction) {
+            Some(gated) => gated,
+            None => return Ok(accounts),
+        };
+        let (gate_info, accounts) = accounts
+            .split_last()
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        // The instruction checks the bank itself, as it would ungated.
+        let bank_info = accounts
+            .get(bank_index)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if *gate_info.key != feature_gate::address(program_id, bank_info.key).0 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let enabled = if gate_info.owner == program_id {
+            FeatureGate::unpack(&gate_info.data.borrow())?.enabled
+        } else {
+            feature_gate::DEFAULT_FEATURES
+        };
+        if enabled & feature == 0 {
+            return Err(BankError::FeatureDisabled.into());
+        }
+        Ok(accounts)
+    }
+
     pub fn process_initialize_bank(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
         decimals: u8,
+        burn_policy: BurnPolicy,
+        lamports_per_token: u64,
+        freeze_authority: COption<Pubkey>,
+        interest_rate_bps: u16,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
         let bank_account_info = next_account_info(account_info_iter)?;
@@ -62,9 +302,11 @@ impl Processor {
         if bank_account_info.owner != program_id {
             return Err(ProgramError::IllegalOwner);
         }
-        if !bank_owner_info.is_signer {
+        // A multisig can't sign, so naming one as the owner is taken as its consent.
+        if !bank_owner_info.is_signer && !multisig::is_multisig(program_id, bank_owner_info) {
             return Err(ProgramError::MissingRequiredSignature);
         }
+        Self::check_rent_exempt(bank_account_info)?;
 
         let mut bank = Bank::unpack_unchecked(&mut bank_account_info.data.borrow_mut())?;
         if bank.is_opened {
@@ -74,6 +316,10 @@ impl Processor {
         bank.decimals = decimals;
         bank.bank_owner = *bank_owner_info.key;
         bank.is_opened = true;
+        bank.burn_policy = burn_policy;
+        bank.lamports_per_token = lamports_per_token;
+        bank.freeze_authority = freeze_authority;
+        bank.interest_rate_bps = interest_rate_bps;
 
         Bank::pack(bank, &mut bank_account_info.data.borrow_mut())?;
         Ok(())
@@ -87,26 +333,100 @@ impl Processor {
         let bank_account_info = next_account_info(account_info_iter)?;
         let account_info = next_account_info(account_info_iter)?;
         let account_owner_info = next_account_info(account_info_iter)?;
-        if bank_account_info.owner != program_id || account_info.owner != program_id {
+        if account_info.owner != program_id {
             return Err(ProgramError::IllegalOwner);
         }
-        if !account_owner_info.is_signer {
+        if !account_owner_info.is_signer && !multisig::is_multisig(program_id, account_owner_info) {
             return Err(ProgramError::MissingRequiredSignature);
         }
-        let mut bank_account = Account::unpack_unchecked(&mut account_info.data.borrow_mut())?;
-        if bank_account.is_initialized {
+        let mut bank = Self::check_bank_open(program_id, bank_account_info)?;
+        Self::check_rent_exempt(account_info)?;
+        let mut data = account_info.data.borrow_mut();
+        history::check_len(data.len())?;
+        if Account::unpack_is_initialized(&data) {
             return Err(ProgramError::AccountAlreadyInitialized);
         }
 
-        bank_account.amount = 0;
-        bank_account.bank = *bank_account_info.key;
-        bank_account.owner = *account_owner_info.key;
-        bank_account.is_initialized = true;
-        bank_account.is_opened = true;
-        bank_account.delegate = COption::None;
-        bank_account.delegated_amount = 0;
+        // The registry accounts are optional, so callers that don't keep one are unchanged.
+        if account_info_iter.len() > 0 {
+            let registry_info = next_account_info(account_info_iter)?;
+            let payer_info = next_account_info(account_info_iter)?;
+            let system_program_info = next_account_info(account_info_iter)?;
+            Self::register(
+                program_id,
+                &mut bank,
+                bank_account_info,
+                account_info.key,
+                registry_info,
+                payer_info,
+                system_program_info,
+            )?;
+            Bank::pack(bank, &mut bank_account_info.data.borrow_mut())?;
+        }
+
+        Account {
+            amount: 0,
+            is_opened: true,
+            is_initialized: true,
+            owner: *account_owner_info.key,
+            delegate: COption::None,
+            delegated_amount: 0,
+            bank: *bank_account_info.key,
+            permit_nonce: 0,
+            is_frozen: false,
+            last_accrual: 0,
+        }
+        .pack_into_slice(&mut data);
+        Ok(())
+    }
+
+    /// Appends `account` to the registry of the bank at `bank_info`, creating the page at
+    /// `registry_info` with lamports from `payer_info` if it's the page's first key.
+    fn register<'a>(
+        program_id: &Pubkey,
+        bank: &mut Bank,
+        bank_info: &AccountInfo<'a>,
+        account: &Pubkey,
+        registry_info: &AccountInfo<'a>,
+        payer_info: &AccountInfo<'a>,
+        system_program_info: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        let (page, slot) = registry::next_slot(bank)?;
+        let (address, bump) = registry::page_address(program_id, bank_info.key, page);
+        if *registry_info.key != address {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if slot == 0 {
+            if *system_program_info.key != system_program::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            // The system program checks the payer signed and can pay.
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer_info.key,
+                    registry_info.key,
+                    Rent::get()?.minimum_balance(registry::PAGE_LEN),
+                    registry::PAGE_LEN as u64,
+                    program_id,
+                ),
+                &[
+                    payer_info.clone(),
+                    registry_info.clone(),
+                    system_program_info.clone(),
+                ],
+                &[&[
+                    registry::SEED,
+                    bank_info.key.as_ref(),
+                    &page.to_le_bytes(),
+                    &[bump],
+                ]],
+            )?;
+        } else if registry_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
 
-        Account::pack(bank_account, &mut account_info.data.borrow_mut())?;
+        registry::write(&mut registry_info.data.borrow_mut(), slot, account);
+        bank.registered_accounts = try_add(bank.registered_accounts, 1)?;
         Ok(())
     }
 
@@ -114,53 +434,284 @@ impl Processor {
         program_id: &Pubkey,
         accounts: &[AccountInfo],
         transfer_amount: u64,
+        sweep_dust: bool,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
         let from_account_info = next_account_info(account_info_iter)?;
         let to_account_info = next_account_info(account_info_iter)?;
         let from_account_owner_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        // A multisig owner's signers follow where a session key's state would.
+        let (session_info, signers) = if multisig::is_multisig(program_id, from_account_owner_info)
+        {
+            (None, account_info_iter.as_slice())
+        } else {
+            (account_info_iter.next(), &[][..])
+        };
+        Self::transfer_between(
+            program_id,
+            from_account_info,
+            to_account_info,
+            from_account_owner_info,
+            signers,
+            bank_info,
+            session_info,
+            transfer_amount,
+            sweep_dust,
+        )
+    }
 
+    /// Moves `transfer_amount` from one account of the bank at `bank_info` to another,
+    /// spending the allowance if `from_account_owner_info` is the source's delegate, or
+    /// the session at `session_info` if given. `signers` sign for a multisig authority.
+    /// With `sweep_dust`, dust the source would be left with goes along.
+    #[allow(clippy::too_many_arguments)]
+    fn transfer_between(
+        program_id: &Pubkey,
+        from_account_info: &AccountInfo,
+        to_account_info: &AccountInfo,
+        from_account_owner_info: &AccountInfo,
+        signers: &[AccountInfo],
+        bank_info: &AccountInfo,
+        session_info: Option<&AccountInfo>,
+        transfer_amount: u64,
+        sweep_dust: bool,
+    ) -> ProgramResult {
         if from_account_info.owner != program_id || to_account_info.owner != program_id {
             return Err(ProgramError::IllegalOwner);
         }
+        // Both sides are packed back separately, so the credit would overwrite the debit.
+        if from_account_info.key == to_account_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
 
-        let mut from_account = Account::unpack(&from_account_info.data.borrow_mut())?;
-        let mut to_account = Account::unpack(&to_account_info.data.borrow_mut())?;
-        if !from_account.can_trade() || !to_account.can_trade() {
-            return Err(ProgramError::InvalidAccountData);
+        // Transfer is the hottest instruction, so it reads and writes only the fields it
+        // needs instead of unpacking and repacking both accounts.
+        let mut from_data = from_account_info.data.borrow_mut();
+        let mut to_data = to_account_info.data.borrow_mut();
+        Account::check_initialized(&from_data)?;
+        Account::check_initialized(&to_data)?;
+        if !Account::unpack_is_opened(&from_data) || !Account::unpack_is_opened(&to_data) {
+            return Err(BankError::AccountClosed.into());
+        }
+        // Bank-wide rules come from the bank itself, so it must be open and not paused,
+        // and both accounts must belong to it.
+        let bank = Self::check_bank_open(program_id, bank_info)?;
+        Self::check_not_paused(&bank)?;
+        Self::check_account_belongs_to_bank(&from_data, bank_info)?;
+        Self::check_account_belongs_to_bank(&to_data, bank_info)?;
+        Self::check_not_frozen(&from_data)?;
+        Self::check_not_frozen(&to_data)?;
+        let mut counted = bank;
+        Self::accrue_interest(&mut counted, &mut from_data)?;
+        Self::accrue_interest(&mut counted, &mut to_data)?;
+
+        let from_amount = Account::unpack_amount(&from_data);
+        let to_amount = Account::unpack_amount(&to_data);
+        let transfer_amount = match from_amount.checked_sub(transfer_amount) {
+            Some(left) if sweep_dust && left > 0 && bank.is_dust(left) => from_amount,
+            _ => transfer_amount,
+        };
+        match session_info {
+            Some(session_info) => Self::debit_session(
+                program_id,
+                from_account_info.key,
+                &mut from_data,
+                from_account_owner_info,
+                session_info,
+                transfer_amount,
+            )?,
+            None => Self::debit(
+                program_id,
+                &mut from_data,
+                from_account_owner_info,
+                signers,
+                transfer_amount,
+            )?,
         }
-        if from_account.bank != to_account.bank {
-            return Err(ProgramError::InvalidAccountData);
+        let new_to_amount = try_add(to_amount, transfer_amount)?;
+        Account::pack_amount(&mut to_data, new_to_amount);
+        counted.record_balance(from_amount, Account::unpack_amount(&from_data));
+        counted.record_balance(to_amount, new_to_amount);
+        // SPL tooling, and callers from before the statistics, pass the bank read-only;
+        // their transfers go through uncounted, unless they'd make or empty a holder,
+        // since the governance program counts on `holder_count`, or pay interest into
+        // the supply.
+        if bank_info.is_writable {
+            counted.record_transfer(transfer_amount);
+            Bank::pack(counted, &mut bank_info.data.borrow_mut())?;
+        } else if counted.holder_count != bank.holder_count
+            || counted.total_supply != bank.total_supply
+        {
+            return Err(BankError::ReadOnlyBank.into());
+        }
+
+        // Only accounts with an extension area pay for reading the clock.
+        if from_data.len() > Account::LEN || to_data.len() > Account::LEN {
+            let slot = Clock::get()?.slot;
+            rate_limit::spend(&mut from_data, slot, transfer_amount)?;
+            let outgoing = TransferRecord {
+                counterparty: *to_account_info.key,
+                amount: transfer_amount,
+                slot,
+                incoming: false,
+            };
+            history::record(&mut from_data, &outgoing);
+            let incoming = TransferRecord {
+                counterparty: *from_account_info.key,
+                incoming: true,
+                ..outgoing
+            };
+            history::record(&mut to_data, &incoming);
         }
+        Ok(())
+    }
 
-        let use_deletegate = Self::validate_owner(&from_account, &from_account_owner_info)?;
+    /// Takes `amount` out of the packed account `data` on the signature of its owner or
+    /// delegate at `authority_info`, or of enough of `signers` if that's a multisig.
+    fn debit(
+        program_id: &Pubkey,
+        data: &mut [u8],
+        authority_info: &AccountInfo,
+        signers: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let use_deletegate = Self::validate_authority(
+            program_id,
+            &Account::unpack_owner(data),
+            &Account::unpack_delegate(data)?,
+            authority_info,
+            signers,
+        )?;
+        let remaining = try_sub(Account::unpack_amount(data), amount)?;
+        // A delegate spends the account's own tokens, as far as its allowance goes.
         if use_deletegate {
-            if from_account.delegated_amount < transfer_amount {
-                return Err(ProgramError::InvalidAccountData);
+            let delegated_amount = try_sub(Account::unpack_delegated_amount(data), amount)?;
+            Account::pack_delegated_amount(data, delegated_amount);
+            // A spent allowance frees the account for approving another delegate.
+            if delegated_amount == 0 {
+                Account::pack_delegate(data, &COption::None);
             }
+        }
+        Account::pack_amount(data, remaining);
+        Ok(())
+    }
 
-            from_account.delegated_amount = from_account
-                .delegated_amount
-                .checked_sub(transfer_amount)
-                .ok_or(ProgramError::InvalidArgument)?
-        } else {
-            if from_account.amount < transfer_amount {
-                return Err(ProgramError::InvalidAccountData);
-            }
+    /// Takes `amount` out of the packed `data` of the account at `account` on the
+    /// signature of the session key at `session_key_info`, counting it against the key's
+    /// state at `session_info`.
+    fn debit_session(
+        program_id: &Pubkey,
+        account: &Pubkey,
+        data: &mut [u8],
+        session_key_info: &AccountInfo,
+        session_info: &AccountInfo,
+        amount: u64,
+    ) -> ProgramResult {
+        if !session_key_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if *session_info.key != session::address(program_id, account, session_key_info.key).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if session_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut session = Session::unpack(&session_info.data.borrow())?;
+        if Account::unpack_owner(data) != session.owner {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if Clock::get()?.slot > session.expiry_slot {
+            return Err(BankError::SessionExpired.into());
+        }
+        session.remaining_spend = session
+            .remaining_spend
+            .checked_sub(amount)
+            .ok_or(BankError::SessionLimitExceeded)?;
+        let remaining = try_sub(Account::unpack_amount(data), amount)?;
+        Account::pack_amount(data, remaining);
+        Session::pack(session, &mut session_info.data.borrow_mut())
+    }
 
-            from_account.amount = from_account
-                .amount
-                .checked_sub(transfer_amount)
-                .ok_or(ProgramError::InvalidArgument)?
+    pub fn process_transfer_with_expiry(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        transfer_amount: u64,
+        max_slot: u64,
+    ) -> ProgramResult {
+        if Clock::get()?.slot > max_slot {
+            return Err(BankError::TransferExpired.into());
+        }
+        Self::process_transfer(program_id, accounts, transfer_amount, false)
+    }
+
+    pub fn process_transfer_route(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        transfer_amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let bank_info = next_account_info(account_info_iter)?;
+        // A sending hop and its authority per pair, then the destination.
+        let hops = account_info_iter.as_slice();
+        if hops.len() < 3 || hops.len() % 2 == 0 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        // Each hop is a transfer of its own; a failing one fails the route, and the
+        // runtime rolls back the hops before it.
+        for hop in hops.windows(3).step_by(2) {
+            Self::transfer_between(
+                program_id,
+                &hop[0],
+                &hop[2],
+                &hop[1],
+                &[],
+                bank_info,
+                None,
+                transfer_amount,
+                false,
+            )?;
         }
-        to_account.amount = to_account
-            .amount
-            .checked_add(transfer_amount)
-            .ok_or(ProgramError::InvalidArgument)?;
+        Ok(())
+    }
 
-        Account::pack(from_account, &mut from_account_info.data.borrow_mut())?;
-        Account::pack(to_account, &mut to_account_info.data.borrow_mut())?;
+    pub fn process_transfer_batch(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amounts: &[u64],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let from_account_info = next_account_info(account_info_iter)?;
+        let from_account_owner_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let destinations = account_info_iter.as_slice();
+        if destinations.len() < amounts.len() {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        if destinations.len() > amounts.len() {
+            return Err(ProgramError::InvalidArgument);
+        }
+        // No account could cover such a batch, but `Overflow` tells the payer why.
+        amounts
+            .iter()
+            .try_fold(0, |total, &amount| try_add(total, amount))?;
 
+        // As with a route, a failing payment fails the batch, and the runtime rolls back
+        // the payments before it.
+        for (to_account_info, &amount) in destinations.iter().zip(amounts) {
+            Self::transfer_between(
+                program_id,
+                from_account_info,
+                to_account_info,
+                from_account_owner_info,
+                &[],
+                bank_info,
+                None,
+                amount,
+                false,
+            )?;
+        }
         Ok(())
     }
 
@@ -173,450 +724,2880 @@ impl Processor {
         let account_info = next_account_info(account_info_iter)?;
         let account_delegate_info = next_account_info(account_info_iter)?;
         let account_owner_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
         if account_info.owner != program_id {
             return Err(ProgramError::IllegalOwner);
         }
-        let mut bank_account = Account::unpack_unchecked(&mut account_info.data.borrow_mut())?;
-        if !bank_account.can_trade() {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        if bank_account.amount < delegate_amount {
-            return Err(ProgramError::InvalidArgument);
-        }
-        if bank_account.delegate.is_some() {
-            if bank_account.delegate.unwrap() != *account_delegate_info.key {
-                return Err(ProgramError::InvalidArgument);
-            }
-        } else {
-            bank_account.delegate = COption::Some(*account_delegate_info.key);
+        let mut data = account_info.data.borrow_mut();
+        Account::check_can_trade(&data)?;
+        Self::check_not_paused(&Self::check_bank_open(program_id, bank_info)?)?;
+        Self::check_account_belongs_to_bank(&data, bank_info)?;
+        Self::check_not_frozen(&data)?;
+        let delegate = Account::unpack_delegate(&data)?;
+        if delegate.is_some() && delegate != COption::Some(*account_delegate_info.key) {
+            return Err(BankError::DelegateMismatch.into());
         }
 
-        Self::validate_owner(&bank_account, &account_owner_info)?;
-        bank_account.amount = bank_account
-            .amount
-            .checked_sub(delegate_amount)
-            .ok_or(ProgramError::InvalidArgument)?;
-        bank_account.delegated_amount = bank_account
-            .delegated_amount
-            .checked_add(delegate_amount)
-            .ok_or(ProgramError::InvalidArgument)?;
+        // Only the owner approves: a delegate approving itself could raise its allowance
+        // to the whole balance.
+        if Account::unpack_owner(&data) != *account_owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Self::check_signed(program_id, account_owner_info, account_info_iter.as_slice())?;
+        // Only the allowance grows; the tokens stay the owner's to spend until the delegate
+        // does.
+        let delegated_amount = try_add(Account::unpack_delegated_amount(&data), delegate_amount)?;
 
-        Account::pack(bank_account, &mut account_info.data.borrow_mut())?;
+        if delegate.is_none() {
+            Account::pack_delegate(&mut data, &COption::Some(*account_delegate_info.key));
+        }
+        Account::pack_delegated_amount(&mut data, delegated_amount);
 
         Ok(())
     }
 
-    pub fn process_mint_to(
+    pub fn process_permit_approve(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
-        mint_amount: u64,
+        delegate_amount: u64,
+        nonce: u64,
+        expiry: i64,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
-        let bank_account_info = next_account_info(account_info_iter)?;
-        let to_account_info = next_account_info(account_info_iter)?;
-        let bank_owner_info = next_account_info(account_info_iter)?;
-
-        if bank_account_info.owner != program_id || to_account_info.owner != program_id {
+        let account_info = next_account_info(account_info_iter)?;
+        let account_delegate_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let instructions_info = next_account_info(account_info_iter)?;
+        if account_info.owner != program_id {
             return Err(ProgramError::IllegalOwner);
         }
-        if !bank_owner_info.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
+        let mut data = account_info.data.borrow_mut();
+        Account::check_can_trade(&data)?;
+        Self::check_not_paused(&Self::check_bank_open(program_id, bank_info)?)?;
+        Self::check_account_belongs_to_bank(&data, bank_info)?;
+        Self::check_not_frozen(&data)?;
+        if Clock::get()?.unix_timestamp > expiry {
+            return Err(BankError::PermitExpired.into());
         }
-        let mut bank = Bank::unpack(&mut bank_account_info.data.borrow_mut())?;
-        let mut to_account = Account::unpack(&mut to_account_info.data.borrow_mut())?;
-        if to_account.bank != *bank_account_info.key {
-            return Err(ProgramError::IllegalOwner);
+        let permit_nonce = Account::unpack_permit_nonce(&data);
+        if nonce != permit_nonce {
+            return Err(BankError::InvalidPermit.into());
         }
-        if !to_account.can_trade() {
-            return Err(ProgramError::InvalidAccountData);
+
+        // The ed25519 program already failed the transaction if the signature is bad; what's
+        // left is checking it signed this permit, by this owner.
+        if *instructions_info.key != instructions::id() {
+            return Err(ProgramError::UnsupportedSysvar);
         }
-        if bank.bank_owner != *bank_owner_info.key {
-            return Err(ProgramError::IllegalOwner);
+        let instructions_data = instructions_info.data.borrow();
+        let index = match instructions::load_current_index(&instructions_data).checked_sub(1) {
+            Some(index) => index,
+            None => return Err(BankError::InvalidPermit.into()),
+        };
+        let signature = instructions::load_instruction_at(index as usize, &instructions_data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        let message = permit::message(
+            program_id,
+            account_info.key,
+            account_delegate_info.key,
+            delegate_amount,
+            nonce,
+            expiry,
+        );
+        let owner = Account::unpack_owner(&data);
+        if signature.program_id != permit::ed25519_program::id()
+            || permit::verified(&signature.data) != Some((owner, message.as_slice()))
+        {
+            return Err(BankError::InvalidPermit.into());
         }
-        bank.total_supply = bank
-            .total_supply
-            .checked_add(mint_amount)
-            .ok_or(ProgramError::InvalidArgument)?;
-        to_account.amount = to_account
-            .amount
-            .checked_add(mint_amount)
-            .ok_or(ProgramError::InvalidArgument)?;
 
-        Bank::pack(bank, &mut bank_account_info.data.borrow_mut())?;
-        Account::pack(to_account, &mut to_account_info.data.borrow_mut())?;
+        let delegate = Account::unpack_delegate(&data)?;
+        if delegate.is_some() && delegate != COption::Some(*account_delegate_info.key) {
+            return Err(BankError::DelegateMismatch.into());
+        }
+        let delegated_amount = try_add(Account::unpack_delegated_amount(&data), delegate_amount)?;
+        let permit_nonce = try_add(permit_nonce, 1)?;
 
+        if delegate.is_none() {
+            Account::pack_delegate(&mut data, &COption::Some(*account_delegate_info.key));
+        }
+        Account::pack_delegated_amount(&mut data, delegated_amount);
+        Account::pack_permit_nonce(&mut data, permit_nonce);
         Ok(())
     }
 
-    pub fn process_burn(
-        program_id: &Pubkey,
-        accounts: &[AccountInfo],
-        burn_amount: u64,
-    ) -> ProgramResult {
+    pub fn process_revoke(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
+        let account_info = next_account_info(account_info_iter)?;
+        let account_owner_info = next_account_info(account_info_iter)?;
         let bank_info = next_account_info(account_info_iter)?;
-        let burn_account_info = next_account_info(account_info_iter)?;
-        let bank_owner_info = next_account_info(account_info_iter)?;
-        let burn_account_owner_info = next_account_info(account_info_iter)?;
-
-        if bank_info.owner != program_id || burn_account_info.owner != program_id {
+        if account_info.owner != program_id {
             return Err(ProgramError::IllegalOwner);
         }
-        if !bank_owner_info.is_signer || !burn_account_owner_info.is_signer {
+        if !account_owner_info.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
-
-        let mut bank = Bank::unpack(&mut bank_info.data.borrow_mut())?;
-        let mut burn_bank_account = Account::unpack(&mut burn_account_info.data.borrow_mut())?;
-        if burn_bank_account.bank != *bank_info.key {
-            return Err(ProgramError::IllegalOwner);
-        }
-        if bank.bank_owner != *bank_owner_info.key
-            || burn_bank_account.owner != *burn_account_owner_info.key
-        {
+        let mut data = account_info.data.borrow_mut();
+        Account::check_initialized(&data)?;
+        Self::check_bank_open(program_id, bank_info)?;
+        Self::check_account_belongs_to_bank(&data, bank_info)?;
+        if Account::unpack_owner(&data) != *account_owner_info.key {
             return Err(ProgramError::IllegalOwner);
         }
-        if burn_bank_account.amount < burn_amount {
-            return Err(ProgramError::InvalidArgument);
-        }
 
-        bank.total_supply = bank
-            .total_supply
-            .checked_sub(burn_amount)
-            .ok_or(ProgramError::InvalidArgument)?;
-        burn_bank_account.amount = burn_bank_account
-            .amount
-            .checked_sub(burn_amount)
-            .ok_or(ProgramError::InvalidArgument)?;
+        Account::pack_delegated_amount(&mut data, 0);
+        Account::pack_delegate(&mut data, &COption::None);
+        Ok(())
+    }
 
-        Bank::pack(bank, &mut bank_info.data.borrow_mut())?;
-        Account::pack(burn_bank_account, &mut burn_account_info.data.borrow_mut())?;
+    pub fn process_set_authority(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        authority_type: AuthorityType,
+        new_authority: COption<Pubkey>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owned_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        if owned_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Self::check_signed(program_id, authority_info, account_info_iter.as_slice())?;
 
+        match authority_type {
+            AuthorityType::AccountOwner => {
+                let mut data = owned_info.data.borrow_mut();
+                Account::check_can_trade(&data)?;
+                Self::check_not_frozen(&data)?;
+                if Account::unpack_owner(&data) != *authority_info.key {
+                    return Err(ProgramError::IllegalOwner);
+                }
+                let new_owner = new_authority.ok_or(BankError::InvalidInstruction)?;
+                // The delegate was trusted by the old owner, not the new one.
+                Account::pack_owner(&mut data, &new_owner);
+                Account::pack_delegated_amount(&mut data, 0);
+                Account::pack_delegate(&mut data, &COption::None);
+            }
+            AuthorityType::BankOwner => {
+                let mut bank = Self::check_bank_open(program_id, owned_info)?;
+                if bank.bank_owner != *authority_info.key {
+                    return Err(ProgramError::IllegalOwner);
+                }
+                let new_owner = new_authority.ok_or(BankError::InvalidInstruction)?;
+                if bank.owner_change_delay == 0 {
+                    bank.bank_owner = new_owner;
+                } else if bank.pending_bank_owner == COption::Some(new_owner) {
+                    if Clock::get()?.slot < bank.owner_change_slot {
+                        return Err(BankError::OwnerChangeNotReady.into());
+                    }
+                    bank.bank_owner = new_owner;
+                    bank.pending_bank_owner = COption::None;
+                    bank.owner_change_slot = 0;
+                } else {
+                    // A stolen key can only start the clock; the owner has the delay to
+                    // notice and cancel.
+                    bank.pending_bank_owner = COption::Some(new_owner);
+                    bank.owner_change_slot = try_add(Clock::get()?.slot, bank.owner_change_delay)?;
+                }
+                Bank::pack(bank, &mut owned_info.data.borrow_mut())?;
+            }
+            // The bank owner names it, so that a lost on-call key can be replaced or removed.
+            AuthorityType::EmergencyAuthority => {
+                let mut bank = Self::check_bank_open(program_id, owned_info)?;
+                if bank.bank_owner != *authority_info.key {
+                    return Err(ProgramError::IllegalOwner);
+                }
+                bank.emergency_authority = new_authority;
+                Bank::pack(bank, &mut owned_info.data.borrow_mut())?;
+            }
+        }
         Ok(())
     }
 
-    pub fn process_close_account(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    pub fn process_assign_to_program(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        seeds: &[&[u8]],
+    ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
-        let closed_account_info = next_account_info(account_info_iter)?;
-        let closed_account_owner_info = next_account_info(account_info_iter)?;
-        if closed_account_info.owner != program_id {
+        let account_info = next_account_info(account_info_iter)?;
+        let account_owner_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let owner_program_info = next_account_info(account_info_iter)?;
+        if account_info.owner != program_id {
             return Err(ProgramError::IllegalOwner);
         }
-        if !closed_account_owner_info.is_signer {
+        if !account_owner_info.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
-        let mut closed_account = Account::unpack(&mut closed_account_info.data.borrow_mut())?;
-        if closed_account.owner != *closed_account_owner_info.key {
+        let mut data = account_info.data.borrow_mut();
+        Account::check_can_trade(&data)?;
+        Self::check_bank_open(program_id, bank_info)?;
+        Self::check_account_belongs_to_bank(&data, bank_info)?;
+        Self::check_not_frozen(&data)?;
+        if Account::unpack_owner(&data) != *account_owner_info.key {
             return Err(ProgramError::IllegalOwner);
         }
+        let owner = pda::derive_owner(seeds, owner_program_info.key)?;
 
-        closed_account.is_opened = false;
-        Account::pack(closed_account, &mut closed_account_info.data.borrow_mut())?;
+        // The delegate was trusted by the old owner, not the program.
+        Account::pack_owner(&mut data, &owner);
+        Account::pack_delegated_amount(&mut data, 0);
+        Account::pack_delegate(&mut data, &COption::None);
         Ok(())
     }
 
-    pub fn validate_owner(
-        from_account: &Account,
-        owner_account_info: &AccountInfo,
-    ) -> Result<bool, ProgramError> {
-        if !owner_account_info.is_signer {
+    pub fn process_set_guardians(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        threshold: u8,
+        delay: u64,
+        guardians: &[Pubkey],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let account_info = next_account_info(account_info_iter)?;
+        let account_owner_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let recovery_info = next_account_info(account_info_iter)?;
+        let payer_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        if account_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if !account_owner_info.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
-        if from_account.delegate.is_some() {
-            if from_account.delegate.unwrap() == *owner_account_info.key {
-                return Ok(true);
-            }
+        if guardians.len() > MAX_GUARDIANS
+            || threshold == 0
+            || threshold as usize > guardians.len()
+            || (1..guardians.len()).any(|i| guardians[..i].contains(&guardians[i]))
+        {
+            return Err(ProgramError::InvalidArgument);
         }
-        if from_account.owner != *owner_account_info.key {
+        let data = account_info.data.borrow();
+        Account::check_initialized(&data)?;
+        Self::check_bank_open(program_id, bank_info)?;
+        Self::check_account_belongs_to_bank(&data, bank_info)?;
+        if Account::unpack_owner(&data) != *account_owner_info.key {
             return Err(ProgramError::IllegalOwner);
         }
-        Ok(false)
-    }
-}
-
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-    use crate::instruction::{
-        self, approve, burn, close_account, initialize_account, initialize_bank, mint_to, transfer,
-    };
-    use solana_program::{
-        account_info::IntoAccountInfo, bpf_loader_upgradeable::close, clock::Epoch,
-        instruction::Instruction, native_token::Sol, program_error, system_program, sysvar::rent,
-    };
-    use solana_sdk::account::{
-        create_account_for_test, create_is_signer_account_infos, Account as SolanaAccount,
-    };
-
-    struct TestSuite {
-        program_id: Pubkey,
-        bank_info: (Pubkey, SolanaAccount),
-        bank_owner_info: (Pubkey, SolanaAccount),
-        bank_accounts_info: Vec<(Pubkey, SolanaAccount)>,
-        bank_accounts_owner_info: Vec<(Pubkey, SolanaAccount)>,
-        lamports: u64,
-    }
-
-    impl TestSuite {
-        fn new_key_account(lamports: u64) -> (Pubkey, SolanaAccount) {
-            (
-                Pubkey::new_unique(),
-                SolanaAccount::new(lamports, Account::get_packed_len(), &system_program::ID),
-            )
+        let (address, bump) = recovery::address(program_id, account_info.key);
+        if *recovery_info.key != address {
+            return Err(ProgramError::InvalidSeeds);
         }
-
-        fn default(lamports: u64) -> TestSuite {
-            let program_id = Pubkey::new_unique();
-            TestSuite {
-                program_id,
-                bank_info: (
-                    Pubkey::new_unique(),
-                    SolanaAccount::new(lamports, Bank::get_packed_len(), &program_id),
-                ),
-                bank_owner_info: (
-                    Pubkey::new_unique(),
-                    SolanaAccount::new(lamports, Bank::get_packed_len(), &system_program::ID),
-                ),
-                bank_accounts_info: Vec::with_capacity(2),
-                bank_accounts_owner_info: Vec::with_capacity(2),
-                lamports,
+        if recovery_info.owner != program_id {
+            if *system_program_info.key != system_program::id() {
+                return Err(ProgramError::IncorrectProgramId);
             }
+            // The system program checks the payer signed and can pay.
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer_info.key,
+                    recovery_info.key,
+                    Rent::get()?.minimum_balance(Recovery::LEN),
+                    Recovery::LEN as u64,
+                    program_id,
+                ),
+                &[
+                    payer_info.clone(),
+                    recovery_info.clone(),
+                    system_program_info.clone(),
+                ],
+                &[&[recovery::SEED, account_info.key.as_ref(), &[bump]]],
+            )?;
         }
 
-        fn add_default_bank_accounts<'a>(&'a mut self, num: u64) -> &'a mut Self {
-            for _ in 0..num {
-                self.bank_accounts_info.push((
-                    Pubkey::new_unique(),
-                    SolanaAccount::new(self.lamports, Account::get_packed_len(), &self.program_id),
-                ));
-                self.bank_accounts_owner_info.push((
-                    Pubkey::new_unique(),
-                    SolanaAccount::new(
-                        self.lamports,
-                        Account::get_packed_len(),
-                        &system_program::ID,
-                    ),
-                ));
+        let mut keys = [Pubkey::default(); MAX_GUARDIANS];
+        keys[..guardians.len()].copy_from_slice(guardians);
+        let recovery = Recovery {
+            is_initialized: true,
+            account: *account_info.key,
+            owner: *account_owner_info.key,
+            threshold,
+            guardian_count: guardians.len() as u8,
+            guardians: keys,
+            delay,
+            pending_owner: COption::None,
+            activation_slot: 0,
+        };
+        Recovery::pack(recovery, &mut recovery_info.data.borrow_mut())
+    }
+
+    pub fn process_start_recovery(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        new_owner: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let account_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let recovery_info = next_account_info(account_info_iter)?;
+        if account_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let data = account_info.data.borrow();
+        Account::check_initialized(&data)?;
+        Self::check_bank_open(program_id, bank_info)?;
+        Self::check_account_belongs_to_bank(&data, bank_info)?;
+        let mut recovery = Self::check_recovery(program_id, account_info, &data, recovery_info)?;
+        let approvals = recovery::approvals(&recovery, account_info_iter.as_slice());
+        if approvals < recovery.threshold as usize {
+            return Err(BankError::NotEnoughGuardians.into());
+        }
+
+        recovery.pending_owner = COption::Some(new_owner);
+        recovery.activation_slot = try_add(Clock::get()?.slot, recovery.delay)?;
+        Recovery::pack(recovery, &mut recovery_info.data.borrow_mut())
+    }
+
+    pub fn process_recover_owner(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let account_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let recovery_info = next_account_info(account_info_iter)?;
+        if account_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut data = account_info.data.borrow_mut();
+        Account::check_initialized(&data)?;
+        Self::check_bank_open(program_id, bank_info)?;
+        Self::check_account_belongs_to_bank(&data, bank_info)?;
+        let mut recovery = Self::check_recovery(program_id, account_info, &data, recovery_info)?;
+        let new_owner = match recovery.pending_owner {
+            COption::Some(new_owner) => new_owner,
+            COption::None => return Err(BankError::NoPendingRecovery.into()),
+        };
+        let approvals = recovery::approvals(&recovery, account_info_iter.as_slice());
+        if approvals < recovery.threshold as usize {
+            return Err(BankError::NotEnoughGuardians.into());
+        }
+        if Clock::get()?.slot < recovery.activation_slot {
+            return Err(BankError::RecoveryNotReady.into());
+        }
+
+        // The delegate was trusted by the old owner, not the new one.
+        Account::pack_owner(&mut data, &new_owner);
+        Account::pack_delegated_amount(&mut data, 0);
+        Account::pack_delegate(&mut data, &COption::None);
+        // The guardians stay on for the new owner.
+        recovery.owner = new_owner;
+        recovery.pending_owner = COption::None;
+        recovery.activation_slot = 0;
+        Recovery::pack(recovery, &mut recovery_info.data.borrow_mut())
+    }
+
+    pub fn process_cancel_recovery(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let account_info = next_account_info(account_info_iter)?;
+        let account_owner_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let recovery_info = next_account_info(account_info_iter)?;
+        if account_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if !account_owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let data = account_info.data.borrow();
+        Account::check_initialized(&data)?;
+        Self::check_bank_open(program_id, bank_info)?;
+        Self::check_account_belongs_to_bank(&data, bank_info)?;
+        if Account::unpack_owner(&data) != *account_owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut recovery = Self::check_recovery(program_id, account_info, &data, recovery_info)?;
+        if recovery.pending_owner.is_none() {
+            return Err(BankError::NoPendingRecovery.into());
+        }
+
+        recovery.pending_owner = COption::None;
+        recovery.activation_slot = 0;
+        Recovery::pack(recovery, &mut recovery_info.data.borrow_mut())
+    }
+
+    pub fn process_create_session_key(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        session_key: Pubkey,
+        max_spend: u64,
+        expiry_slot: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let account_info = next_account_info(account_info_iter)?;
+        let account_owner_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let session_info = next_account_info(account_info_iter)?;
+        let payer_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        if account_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if !account_owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        // The owner signing with their own key needs no session.
+        if session_key == *account_owner_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let data = account_info.data.borrow();
+        Account::check_initialized(&data)?;
+        Self::check_bank_open(program_id, bank_info)?;
+        Self::check_account_belongs_to_bank(&data, bank_info)?;
+        if Account::unpack_owner(&data) != *account_owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let (address, bump) = session::address(program_id, account_info.key, &session_key);
+        if *session_info.key != address {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if session_info.owner != program_id {
+            if *system_program_info.key != system_program::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            // The system program checks the payer signed and can pay.
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer_info.key,
+                    session_info.key,
+                    Rent::get()?.minimum_balance(Session::LEN),
+                    Session::LEN as u64,
+                    program_id,
+                ),
+                &[
+                    payer_info.clone(),
+                    session_info.clone(),
+                    system_program_info.clone(),
+                ],
+                &[&[
+                    session::SEED,
+                    account_info.key.as_ref(),
+                    session_key.as_ref(),
+                    &[bump],
+                ]],
+            )?;
+        }
+
+        let session = Session {
+            is_initialized: true,
+            account: *account_info.key,
+            owner: *account_owner_info.key,
+            session_key,
+            remaining_spend: max_spend,
+            expiry_slot,
+        };
+        Session::pack(session, &mut session_info.data.borrow_mut())
+    }
+
+    pub fn process_set_rate_limit(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        capacity: u64,
+        refill_per_slot: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let account_info = next_account_info(account_info_iter)?;
+        let account_owner_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        if account_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if !account_owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut data = account_info.data.borrow_mut();
+        Account::check_initialized(&data)?;
+        Self::check_bank_open(program_id, bank_info)?;
+        Self::check_account_belongs_to_bank(&data, bank_info)?;
+        if Account::unpack_owner(&data) != *account_owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        // Only an account created with room for one has a bucket.
+        let mut bucket = rate_limit::bucket(&data).ok_or(ProgramError::InvalidAccountData)?;
+
+        let slot = Clock::get()?.slot;
+        // Changing the limit doesn't hand out a fresh bucket.
+        bucket.available = match bucket.capacity {
+            0 => capacity,
+            _ => {
+                bucket.refill(slot);
+                bucket.available.min(capacity)
+            }
+        };
+        bucket.capacity = capacity;
+        bucket.refill_per_slot = refill_per_slot;
+        bucket.last_refill_slot = slot;
+        rate_limit::pack_bucket(&mut data, &bucket);
+        Ok(())
+    }
+
+    pub fn process_initialize_version(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let version_info = next_account_info(account_info_iter)?;
+        let payer_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let (address, bump) = version::address(program_id);
+        if *version_info.key != address {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if version_info.owner == program_id {
+            let recorded = Version::unpack_unchecked(&version_info.data.borrow())?;
+            if !version::understands_layout(&recorded) {
+                return Err(BankError::UnsupportedLayout.into());
+            }
+        } else {
+            if *system_program_info.key != system_program::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            // The system program checks the payer signed and can pay.
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer_info.key,
+                    version_info.key,
+                    Rent::get()?.minimum_balance(Version::LEN),
+                    Version::LEN as u64,
+                    program_id,
+                ),
+                &[
+                    payer_info.clone(),
+                    version_info.clone(),
+                    system_program_info.clone(),
+                ],
+                &[&[version::SEED, &[bump]]],
+            )?;
+        }
+
+        Version::pack(version::current(), &mut version_info.data.borrow_mut())
+    }
+
+    pub fn process_set_features(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        enabled: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_owner_info = next_account_info(account_info_iter)?;
+        let gate_info = next_account_info(account_info_iter)?;
+        let payer_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        if !bank_owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let bank = Self::check_bank_open(program_id, bank_info)?;
+        if bank.bank_owner != *bank_owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let (address, bump) = feature_gate::address(program_id, bank_info.key);
+        if *gate_info.key != address {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if gate_info.owner != program_id {
+            if *system_program_info.key != system_program::id() {
+                return Err(ProgramError::IncorrectProgramId);
             }
-            self
+            // The system program checks the payer signed and can pay.
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer_info.key,
+                    gate_info.key,
+                    Rent::get()?.minimum_balance(FeatureGate::LEN),
+                    FeatureGate::LEN as u64,
+                    program_id,
+                ),
+                &[
+                    payer_info.clone(),
+                    gate_info.clone(),
+                    system_program_info.clone(),
+                ],
+                &[&[feature_gate::SEED, bank_info.key.as_ref(), &[bump]]],
+            )?;
+        }
+
+        let gate = FeatureGate {
+            is_initialized: true,
+            bank: *bank_info.key,
+            enabled,
+        };
+        FeatureGate::pack(gate, &mut gate_info.data.borrow_mut())
+    }
+
+    /// Unpacks the recovery state at `recovery_info`, failing unless it's the one of the
+    /// account at `account_info`, whose packed `data` still names the owner who set its
+    /// guardians.
+    fn check_recovery(
+        program_id: &Pubkey,
+        account_info: &AccountInfo,
+        data: &[u8],
+        recovery_info: &AccountInfo,
+    ) -> Result<Recovery, ProgramError> {
+        if *recovery_info.key != recovery::address(program_id, account_info.key).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if recovery_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let recovery = Recovery::unpack(&recovery_info.data.borrow())?;
+        if Account::unpack_owner(data) != recovery.owner {
+            return Err(ProgramError::IllegalOwner);
         }
+        Ok(recovery)
+    }
+
+    pub fn process_deposit_sol(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        lamports: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let bank_info = next_account_info(account_info_iter)?;
+        let account_info = next_account_info(account_info_iter)?;
+        let depositor_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        if account_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if *system_program_info.key != system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut bank = Self::check_bank_open(program_id, bank_info)?;
+        Self::check_not_paused(&bank)?;
+        let tokens = Self::tokens_for_lamports(&bank, lamports)?;
+        let mut data = account_info.data.borrow_mut();
+        Account::check_can_trade(&data)?;
+        Self::check_account_belongs_to_bank(&data, bank_info)?;
+        Self::check_not_frozen(&data)?;
+        Self::accrue_interest(&mut bank, &mut data)?;
+        bank.total_supply = try_add(bank.total_supply, tokens)?;
+        let amount = try_add(Account::unpack_amount(&data), tokens)?;
+        bank.record_balance(Account::unpack_amount(&data), amount);
+
+        // The system program checks the depositor signed and can pay.
+        invoke(
+            &system_instruction::transfer(depositor_info.key, bank_info.key, lamports),
+            &[
+                depositor_info.clone(),
+                bank_info.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+        Bank::pack(bank, &mut bank_info.data.borrow_mut())?;
+        Account::pack_amount(&mut data, amount);
+        Ok(())
+    }
+
+    pub fn process_withdraw_sol(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        lamports: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let bank_info = next_account_info(account_info_iter)?;
+        let account_info = next_account_info(account_info_iter)?;
+        let account_owner_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        if account_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        // The bank's lamports are set before the destination's, which would undo it.
+        if destination_info.key == bank_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let mut bank = Self::check_bank_open(program_id, bank_info)?;
+        Self::check_not_paused(&bank)?;
+        let tokens = Self::tokens_for_lamports(&bank, lamports)?;
+        let mut data = account_info.data.borrow_mut();
+        Account::check_can_trade(&data)?;
+        Self::check_account_belongs_to_bank(&data, bank_info)?;
+        Self::check_not_frozen(&data)?;
+        Self::accrue_interest(&mut bank, &mut data)?;
+        bank.total_supply = try_sub(bank.total_supply, tokens)?;
+        let reserve = try_sub(bank_info.lamports(), lamports)?;
+        if reserve < Rent::get()?.minimum_balance(bank_info.data_len()) {
+            return Err(BankError::InsufficientFunds.into());
+        }
+        let destination_lamports = try_add(destination_info.lamports(), lamports)?;
+
+        let amount = Account::unpack_amount(&data);
+        Self::debit(
+            program_id,
+            &mut data,
+            account_owner_info,
+            account_info_iter.as_slice(),
+            tokens,
+        )?;
+        bank.record_balance(amount, Account::unpack_amount(&data));
+        Bank::pack(bank, &mut bank_info.data.borrow_mut())?;
+        **bank_info.lamports.borrow_mut() = reserve;
+        **destination_info.lamports.borrow_mut() = destination_lamports;
+        Ok(())
+    }
+
+    /// The tokens `lamports` buys in `bank`, failing unless the bank takes SOL and
+    /// `lamports` is a whole number of tokens' worth.
+    fn tokens_for_lamports(bank: &Bank, lamports: u64) -> Result<u64, ProgramError> {
+        if bank.lamports_per_token == 0 {
+            return Err(BankError::NoSolRate.into());
+        }
+        if lamports % bank.lamports_per_token != 0 {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(lamports / bank.lamports_per_token)
+    }
+
+    pub fn process_set_exchange_rate(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        numerator: u64,
+        denominator: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let rate_info = next_account_info(account_info_iter)?;
+        let from_bank_info = next_account_info(account_info_iter)?;
+        let to_bank_info = next_account_info(account_info_iter)?;
+        let from_bank_owner_info = next_account_info(account_info_iter)?;
+        let to_bank_owner_info = next_account_info(account_info_iter)?;
+        if rate_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if !from_bank_owner_info.is_signer || !to_bank_owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if numerator == 0 || denominator == 0 || from_bank_info.key == to_bank_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let from_bank = Self::check_bank_open(program_id, from_bank_info)?;
+        let to_bank = Self::check_bank_open(program_id, to_bank_info)?;
+        if from_bank.bank_owner != *from_bank_owner_info.key
+            || to_bank.bank_owner != *to_bank_owner_info.key
+        {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut data = rate_info.data.borrow_mut();
+        let rate = ExchangeRate::unpack_unchecked(&data)?;
+        // A rate account stays with the banks it was opened for.
+        if rate.is_initialized
+            && (rate.from_bank != *from_bank_info.key || rate.to_bank != *to_bank_info.key)
+        {
+            return Err(BankError::BankMismatch.into());
+        }
+
+        ExchangeRate {
+            is_initialized: true,
+            from_bank: *from_bank_info.key,
+            to_bank: *to_bank_info.key,
+            numerator,
+            denominator,
+        }
+        .pack_into_slice(&mut data);
+        Ok(())
+    }
+
+    pub fn process_convert(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let rate_info = next_account_info(account_info_iter)?;
+        let from_bank_info = next_account_info(account_info_iter)?;
+        let from_account_info = next_account_info(account_info_iter)?;
+        let from_account_owner_info = next_account_info(account_info_iter)?;
+        let to_bank_info = next_account_info(account_info_iter)?;
+        let to_account_info = next_account_info(account_info_iter)?;
+        if rate_info.owner != program_id
+            || from_account_info.owner != program_id
+            || to_account_info.owner != program_id
+        {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if from_account_info.key == to_account_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let rate = ExchangeRate::unpack(&rate_info.data.borrow())?;
+        if rate.from_bank != *from_bank_info.key || rate.to_bank != *to_bank_info.key {
+            return Err(BankError::BankMismatch.into());
+        }
+        let mut from_bank = Self::check_bank_open(program_id, from_bank_info)?;
+        let mut to_bank = Self::check_bank_open(program_id, to_bank_info)?;
+        Self::check_not_paused(&from_bank)?;
+        Self::check_not_paused(&to_bank)?;
+        let converted = convert(
+            amount,
+            rate.numerator,
+            rate.denominator,
+            from_bank.decimals,
+            to_bank.decimals,
+        )?;
+        if converted == 0 {
+            return Err(BankError::ConvertsToZero.into());
+        }
+
+        let mut from_data = from_account_info.data.borrow_mut();
+        let mut to_data = to_account_info.data.borrow_mut();
+        Account::check_can_trade(&from_data)?;
+        Account::check_can_trade(&to_data)?;
+        Self::check_account_belongs_to_bank(&from_data, from_bank_info)?;
+        Self::check_account_belongs_to_bank(&to_data, to_bank_info)?;
+        Self::check_not_frozen(&from_data)?;
+        Self::check_not_frozen(&to_data)?;
+        Self::accrue_interest(&mut from_bank, &mut from_data)?;
+        Self::accrue_interest(&mut to_bank, &mut to_data)?;
+        from_bank.total_supply = try_sub(from_bank.total_supply, amount)?;
+        to_bank.total_supply = try_add(to_bank.total_supply, converted)?;
+        let to_amount = try_add(Account::unpack_amount(&to_data), converted)?;
+        to_bank.record_balance(Account::unpack_amount(&to_data), to_amount);
+
+        let from_amount = Account::unpack_amount(&from_data);
+        Self::debit(
+            program_id,
+            &mut from_data,
+            from_account_owner_info,
+            account_info_iter.as_slice(),
+            amount,
+        )?;
+        from_bank.record_balance(from_amount, Account::unpack_amount(&from_data));
+        Account::pack_amount(&mut to_data, to_amount);
+        Bank::pack(from_bank, &mut from_bank_info.data.borrow_mut())?;
+        Bank::pack(to_bank, &mut to_bank_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_mint_to(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        mint_amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let bank_account_info = next_account_info(account_info_iter)?;
+        let to_account_info = next_account_info(account_info_iter)?;
+        let bank_owner_info = next_account_info(account_info_iter)?;
+
+        if to_account_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Self::check_signed(program_id, bank_owner_info, account_info_iter.as_slice())?;
+        if bank_account_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut bank = Bank::unpack_unchecked(&bank_account_info.data.borrow())?;
+        if !bank.is_opened {
+            return Err(BankError::BankClosed.into());
+        }
+        Self::check_not_paused(&bank)?;
+        let mut to_data = to_account_info.data.borrow_mut();
+        Account::check_initialized(&to_data)?;
+        Self::check_account_belongs_to_bank(&to_data, bank_account_info)?;
+        if !Account::unpack_is_opened(&to_data) {
+            return Err(BankError::AccountClosed.into());
+        }
+        Self::check_not_frozen(&to_data)?;
+        if bank.bank_owner != *bank_owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Self::accrue_interest(&mut bank, &mut to_data)?;
+        bank.total_supply = try_add(bank.total_supply, mint_amount)?;
+        let to_amount = try_add(Account::unpack_amount(&to_data), mint_amount)?;
+        bank.record_balance(Account::unpack_amount(&to_data), to_amount);
+
+        Bank::pack(bank, &mut bank_account_info.data.borrow_mut())?;
+        Account::pack_amount(&mut to_data, to_amount);
+
+        Ok(())
+    }
+
+    pub fn process_burn(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        burn_amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let bank_info = next_account_info(account_info_iter)?;
+        let burn_account_info = next_account_info(account_info_iter)?;
+        let bank = Self::check_bank_open(program_id, bank_info);
+        // Only a `HolderOnly` bank lets the bank owner be left out.
+        let holder_only = matches!(
+            bank,
+            Ok(Bank {
+                burn_policy: BurnPolicy::HolderOnly,
+                ..
+            })
+        );
+        let bank_owner_info = if holder_only && account_info_iter.len() == 1 {
+            None
+        } else {
+            Some(next_account_info(account_info_iter)?)
+        };
+        let burn_account_owner_info = next_account_info(account_info_iter)?;
+
+        if burn_account_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if !bank_owner_info.map_or(true, |info| info.is_signer)
+            || !burn_account_owner_info.is_signer
+        {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut bank = bank?;
+        Self::check_not_paused(&bank)?;
+        let mut burn_data = burn_account_info.data.borrow_mut();
+        Account::check_initialized(&burn_data)?;
+        Self::check_account_belongs_to_bank(&burn_data, bank_info)?;
+        Self::check_not_frozen(&burn_data)?;
+        if !bank_owner_info.map_or(true, |info| bank.bank_owner == *info.key)
+            || Account::unpack_owner(&burn_data) != *burn_account_owner_info.key
+        {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Self::accrue_interest(&mut bank, &mut burn_data)?;
+        let burn_account_amount = try_sub(Account::unpack_amount(&burn_data), burn_amount)?;
+        bank.total_supply = try_sub(bank.total_supply, burn_amount)?;
+        bank.record_balance(Account::unpack_amount(&burn_data), burn_account_amount);
+
+        Bank::pack(bank, &mut bank_info.data.borrow_mut())?;
+        Account::pack_amount(&mut burn_data, burn_account_amount);
+
+        Ok(())
+    }
+
+    pub fn process_close_account(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let closed_account_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let closed_account_owner_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        if closed_account_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if !closed_account_owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if closed_account_info.key == destination_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let mut closed_data = closed_account_info.data.borrow_mut();
+        Account::check_initialized(&closed_data)?;
+        let mut bank = Self::check_bank_open(program_id, bank_info)?;
+        Self::check_account_belongs_to_bank(&closed_data, bank_info)?;
+        Self::check_not_frozen(&closed_data)?;
+        if Account::unpack_owner(&closed_data) != *closed_account_owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        let remaining = Account::unpack_amount(&closed_data);
+        if remaining > 0 {
+            Self::settle_closed_balance(
+                program_id,
+                closed_account_info,
+                bank_info,
+                &mut bank,
+                remaining,
+                account_info_iter,
+                None,
+            )?;
+        }
+        Self::drain_closed(closed_account_info, &mut closed_data, destination_info)
+    }
+
+    pub fn process_close_dust_account(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let closed_account_info = next_account_info(account_info_iter)?;
+        let closed_account_owner_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        if closed_account_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if closed_account_info.key == closed_account_owner_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let mut closed_data = closed_account_info.data.borrow_mut();
+        Account::check_initialized(&closed_data)?;
+        let mut bank = Self::check_bank_open(program_id, bank_info)?;
+        Self::check_account_belongs_to_bank(&closed_data, bank_info)?;
+        Self::check_not_frozen(&closed_data)?;
+        // Nobody signs, so the lamports and any swept dust stay with the owner.
+        let owner = Account::unpack_owner(&closed_data);
+        if owner != *closed_account_owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        let remaining = Account::unpack_amount(&closed_data);
+        if remaining > 0 {
+            if !bank.is_dust(remaining) {
+                return Err(BankError::NotDust.into());
+            }
+            Self::settle_closed_balance(
+                program_id,
+                closed_account_info,
+                bank_info,
+                &mut bank,
+                remaining,
+                account_info_iter,
+                Some(&owner),
+            )?;
+        }
+        Self::drain_closed(
+            closed_account_info,
+            &mut closed_data,
+            closed_account_owner_info,
+        )
+    }
+
+    /// Deals with the `remaining` tokens of a closing account as the bank's close policy
+    /// says, under `Sweep` moving them to the next account of `account_info_iter`, which
+    /// must belong to `sweep_owner` if given. Packs the bank.
+    fn settle_closed_balance(
+        program_id: &Pubkey,
+        closed_account_info: &AccountInfo,
+        bank_info: &AccountInfo,
+        bank: &mut Bank,
+        remaining: u64,
+        account_info_iter: &mut std::slice::Iter<AccountInfo>,
+        sweep_owner: Option<&Pubkey>,
+    ) -> ProgramResult {
+        bank.record_balance(remaining, 0);
+        match bank.close_policy {
+            ClosePolicy::Reject => return Err(BankError::NonZeroBalance.into()),
+            ClosePolicy::Sweep => {
+                // Sweeping moves tokens between the bank's accounts like a transfer.
+                Self::check_not_paused(bank)?;
+                let sweep_info = next_account_info(account_info_iter)?;
+                if sweep_info.owner != program_id {
+                    return Err(ProgramError::IllegalOwner);
+                }
+                if sweep_info.key == closed_account_info.key {
+                    return Err(ProgramError::InvalidArgument);
+                }
+                let mut sweep_data = sweep_info.data.borrow_mut();
+                Account::check_can_trade(&sweep_data)?;
+                Self::check_account_belongs_to_bank(&sweep_data, bank_info)?;
+                Self::check_not_frozen(&sweep_data)?;
+                if let Some(sweep_owner) = sweep_owner {
+                    if Account::unpack_owner(&sweep_data) != *sweep_owner {
+                        return Err(ProgramError::IllegalOwner);
+                    }
+                }
+                Self::accrue_interest(bank, &mut sweep_data)?;
+                let sweep_amount = try_add(Account::unpack_amount(&sweep_data), remaining)?;
+                bank.record_balance(Account::unpack_amount(&sweep_data), sweep_amount);
+                Account::pack_amount(&mut sweep_data, sweep_amount);
+            }
+            ClosePolicy::Burn => {
+                bank.total_supply = try_sub(bank.total_supply, remaining)?;
+            }
+        }
+        Bank::pack(*bank, &mut bank_info.data.borrow_mut())
+    }
+
+    /// Empties and closes an account, handing its lamports to `destination_info`.
+    fn drain_closed(
+        closed_account_info: &AccountInfo,
+        closed_data: &mut [u8],
+        destination_info: &AccountInfo,
+    ) -> ProgramResult {
+        // Any allowance goes with the account.
+        Account::pack_balances(closed_data, &Balances::default());
+        Account::pack_delegate(closed_data, &COption::None);
+        Account::pack_is_opened(closed_data, false);
+
+        let destination_lamports = destination_info.lamports();
+        **destination_info.lamports.borrow_mut() =
+            try_add(destination_lamports, closed_account_info.lamports())?;
+        **closed_account_info.lamports.borrow_mut() = 0;
+        Ok(())
+    }
+
+    pub fn process_freeze_account(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        freeze: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let account_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let freeze_authority_info = next_account_info(account_info_iter)?;
+        if account_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if !freeze_authority_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let bank = Self::check_bank_open(program_id, bank_info)?;
+        let mut data = account_info.data.borrow_mut();
+        Account::check_can_trade(&data)?;
+        Self::check_account_belongs_to_bank(&data, bank_info)?;
+        // The emergency authority may freeze as well, though only where the freeze
+        // authority is there to thaw.
+        match bank.freeze_authority {
+            COption::None => return Err(BankError::NoFreezeAuthority.into()),
+            COption::Some(authority)
+                if authority != *freeze_authority_info.key
+                    && !(freeze && bank.is_emergency_authority(freeze_authority_info.key)) =>
+            {
+                return Err(ProgramError::IllegalOwner)
+            }
+            COption::Some(_) => {}
+        }
+        // Freezing twice, or thawing an account that isn't frozen, is a mistake.
+        if Account::unpack_is_frozen(&data) == freeze {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Account::pack_is_frozen(&mut data, freeze);
+        Ok(())
+    }
+
+    pub fn process_initialize_multisig(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        threshold: u8,
+    ) -> ProgramResult {
+        let (multisig_info, signer_infos) = accounts
+            .split_first()
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if multisig_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let signers: Vec<Pubkey> = signer_infos.iter().map(|info| *info.key).collect();
+        if signers.len() > MAX_SIGNERS
+            || threshold == 0
+            || threshold as usize > signers.len()
+            || (1..signers.len()).any(|i| signers[..i].contains(&signers[i]))
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let mut data = multisig_info.data.borrow_mut();
+        if Multisig::unpack_unchecked(&data)?.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        let mut keys = [Pubkey::default(); MAX_SIGNERS];
+        keys[..signers.len()].copy_from_slice(&signers);
+        let multisig = Multisig {
+            is_initialized: true,
+            threshold,
+            signer_count: signers.len() as u8,
+            signers: keys,
+        };
+        Multisig::pack(multisig, &mut data)
+    }
+
+    pub fn process_initialize_associated_account(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let bank_info = next_account_info(account_info_iter)?;
+        let account_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let payer_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        Self::check_bank_open(program_id, bank_info)?;
+        let (address, bump) =
+            associated_account::address(program_id, wallet_info.key, bank_info.key);
+        if *account_info.key != address {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if account_info.owner == program_id {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if *system_program_info.key != system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let seeds: &[&[u8]] = &[
+            associated_account::SEED,
+            wallet_info.key.as_ref(),
+            bank_info.key.as_ref(),
+            &[bump],
+        ];
+        let lamports = Rent::get()?.minimum_balance(Account::LEN);
+        // The system program checks the payer signed and can pay.
+        if account_info.lamports() == 0 {
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer_info.key,
+                    account_info.key,
+                    lamports,
+                    Account::LEN as u64,
+                    program_id,
+                ),
+                &[
+                    payer_info.clone(),
+                    account_info.clone(),
+                    system_program_info.clone(),
+                ],
+                &[seeds],
+            )?;
+        } else {
+            // Anyone can send lamports to the address, which would fail `create_account`;
+            // top it up to rent-exempt and take it over instead.
+            let top_up = lamports.saturating_sub(account_info.lamports());
+            if top_up > 0 {
+                invoke(
+                    &system_instruction::transfer(payer_info.key, account_info.key, top_up),
+                    &[
+                        payer_info.clone(),
+                        account_info.clone(),
+                        system_program_info.clone(),
+                    ],
+                )?;
+            }
+            invoke_signed(
+                &system_instruction::allocate(account_info.key, Account::LEN as u64),
+                &[account_info.clone(), system_program_info.clone()],
+                &[seeds],
+            )?;
+            invoke_signed(
+                &system_instruction::assign(account_info.key, program_id),
+                &[account_info.clone(), system_program_info.clone()],
+                &[seeds],
+            )?;
+        }
+
+        let account = Account {
+            is_opened: true,
+            is_initialized: true,
+            owner: *wallet_info.key,
+            bank: *bank_info.key,
+            ..Account::default()
+        };
+        Account::pack(account, &mut account_info.data.borrow_mut())
+    }
+
+    pub fn process_set_close_policy(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        policy: ClosePolicy,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_owner_info = next_account_info(account_info_iter)?;
+        if !bank_owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut bank = Self::check_bank_open(program_id, bank_info)?;
+        if bank.bank_owner != *bank_owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        bank.close_policy = policy;
+        Bank::pack(bank, &mut bank_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_set_require_rent_exempt(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        required: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_owner_info = next_account_info(account_info_iter)?;
+        if !bank_owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut bank = Self::check_bank_open(program_id, bank_info)?;
+        if bank.bank_owner != *bank_owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if required {
+            Self::check_rent_exempt(bank_info)?;
+        }
+        bank.require_rent_exempt = required;
+        Bank::pack(bank, &mut bank_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_set_dust_threshold(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        threshold: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_owner_info = next_account_info(account_info_iter)?;
+        if !bank_owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut bank = Self::check_bank_open(program_id, bank_info)?;
+        if bank.bank_owner != *bank_owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        bank.dust_threshold = threshold;
+        Bank::pack(bank, &mut bank_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_pause_bank(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        pause: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let bank_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        if !authority_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut bank = Self::check_bank_open(program_id, bank_info)?;
+        // The emergency authority may stop the bank, but only the owner restarts it.
+        if bank.bank_owner != *authority_info.key
+            && !(pause && bank.is_emergency_authority(authority_info.key))
+        {
+            return Err(ProgramError::IllegalOwner);
+        }
+        // Pausing twice, or resuming a bank that isn't paused, is a mistake.
+        if bank.is_paused == pause {
+            return Err(ProgramError::InvalidArgument);
+        }
+        bank.is_paused = pause;
+        Bank::pack(bank, &mut bank_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_set_owner_change_delay(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        delay: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_owner_info = next_account_info(account_info_iter)?;
+        if !bank_owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut bank = Self::check_bank_open(program_id, bank_info)?;
+        if bank.bank_owner != *bank_owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if delay < bank.owner_change_delay {
+            return Err(ProgramError::InvalidArgument);
+        }
+        bank.owner_change_delay = delay;
+        Bank::pack(bank, &mut bank_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_cancel_owner_change(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let bank_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        if !authority_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut bank = Self::check_bank_open(program_id, bank_info)?;
+        if bank.bank_owner != *authority_info.key
+            && !bank.is_emergency_authority(authority_info.key)
+        {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if bank.pending_bank_owner.is_none() {
+            return Err(BankError::NoPendingOwnerChange.into());
+        }
+        bank.pending_bank_owner = COption::None;
+        bank.owner_change_slot = 0;
+        Bank::pack(bank, &mut bank_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_accrue_interest(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let account_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        if account_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut bank = Self::check_bank_open(program_id, bank_info)?;
+        Self::check_not_paused(&bank)?;
+        let mut data = account_info.data.borrow_mut();
+        Account::check_can_trade(&data)?;
+        Self::check_account_belongs_to_bank(&data, bank_info)?;
+        Self::check_not_frozen(&data)?;
+        Self::accrue_interest(&mut bank, &mut data)?;
+        Bank::pack(bank, &mut bank_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    /// Pays the packed account `data` the interest `bank` owes it, see `interest`. Only
+    /// banks paying interest pay for reading the clock.
+    fn accrue_interest(bank: &mut Bank, data: &mut [u8]) -> ProgramResult {
+        if bank.interest_rate_bps == 0 {
+            return Ok(());
+        }
+        interest::accrue(bank, data, Clock::get()?.unix_timestamp)
+    }
+
+    /// Fails with `NotRentExempt` unless `info` holds enough lamports for its data to be
+    /// exempt from rent, lest the runtime collect it out from under its bank.
+    pub fn check_rent_exempt(info: &AccountInfo) -> ProgramResult {
+        if !Rent::get()?.is_exempt(info.lamports(), info.data_len()) {
+            return Err(BankError::NotRentExempt.into());
+        }
+        Ok(())
+    }
+
+    /// Unpacks the bank at `bank_info`, failing unless this program owns it and it has
+    /// been opened.
+    pub fn check_bank_open(
+        program_id: &Pubkey,
+        bank_info: &AccountInfo,
+    ) -> Result<Bank, ProgramError> {
+        if bank_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Bank::unpack(&bank_info.data.borrow())
+    }
+
+    /// Fails with `DecimalsMismatch` unless the bank at `accounts[bank_index]` has
+    /// `decimals`. The checked instructions call it before their unchecked selves, so a
+    /// mismatch fails them before they touch any account.
+    pub fn check_decimals(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        bank_index: usize,
+        decimals: u8,
+    ) -> ProgramResult {
+        let bank_info = accounts
+            .get(bank_index)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if Self::check_bank_open(program_id, bank_info)?.decimals != decimals {
+            return Err(BankError::DecimalsMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Fails with `BankMismatch` unless the packed account `data` was opened in the bank
+    /// at `bank_info`.
+    pub fn check_account_belongs_to_bank(data: &[u8], bank_info: &AccountInfo) -> ProgramResult {
+        if Account::unpack_bank_bytes(data) != &bank_info.key.to_bytes() {
+            return Err(BankError::BankMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Fails with `AccountFrozen` if the packed account `data` is frozen.
+    pub fn check_not_frozen(data: &[u8]) -> ProgramResult {
+        if Account::unpack_is_frozen(data) {
+            return Err(BankError::AccountFrozen.into());
+        }
+        Ok(())
+    }
+
+    /// Fails with `BankPaused` if `bank` is paused.
+    pub fn check_not_paused(bank: &Bank) -> ProgramResult {
+        if bank.is_paused {
+            return Err(BankError::BankPaused.into());
+        }
+        Ok(())
+    }
+
+    /// Fails with `MissingRequiredSignature` unless `authority_info` signed, or, if it's a
+    /// multisig, enough of its signers did among `signers`.
+    ///
+    /// Only `is_signer` is checked, never who the signer is, so a program can own an
+    /// account through a PDA and sign for it with `invoke_signed`.
+    pub fn check_signed(
+        program_id: &Pubkey,
+        authority_info: &AccountInfo,
+        signers: &[AccountInfo],
+    ) -> ProgramResult {
+        if !multisig::is_multisig(program_id, authority_info) {
+            if !authority_info.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            return Ok(());
+        }
+        let multisig = Multisig::unpack(&authority_info.try_borrow_data()?)?;
+        if multisig::approvals(&multisig, signers) < multisig.threshold as usize {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        Ok(())
+    }
+
+    pub fn validate_owner(
+        program_id: &Pubkey,
+        from_account: &Account,
+        owner_account_info: &AccountInfo,
+        signers: &[AccountInfo],
+    ) -> Result<bool, ProgramError> {
+        Self::validate_authority(
+            program_id,
+            &from_account.owner,
+            &from_account.delegate,
+            owner_account_info,
+            signers,
+        )
+    }
+
+    /// `validate_owner` over an account's owner and delegate alone, for callers that
+    /// read them in place. Returns whether the delegate signed.
+    pub fn validate_authority(
+        program_id: &Pubkey,
+        owner: &Pubkey,
+        delegate: &COption<Pubkey>,
+        owner_account_info: &AccountInfo,
+        signers: &[AccountInfo],
+    ) -> Result<bool, ProgramError> {
+        Self::check_signed(program_id, owner_account_info, signers)?;
+        if *delegate == COption::Some(*owner_account_info.key) {
+            return Ok(true);
+        }
+        if owner != owner_account_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::instruction::{burn_by_holder, initialize_multisig, mint_to, set_close_policy};
+    use crate::test_utils::{do_process_instruction, TestSuite};
+    use proptest::prelude::*;
+    use solana_program::instruction::Instruction;
+    use solana_sdk::account::Account as SolanaAccount;
+
+    #[test]
+    fn test_initialize_bank() {
+        let mut test_suite = TestSuite::default(60);
+        test_suite.process_init_bank_instruction(8).unwrap();
+
+        assert_eq!(
+            Ok(true),
+            test_suite.bank_eq(&Bank {
+                decimals: 8,
+                bank_owner: test_suite.bank_owner_info.0,
+                is_opened: true,
+                total_supply: 0,
+                close_policy: ClosePolicy::Reject,
+                require_rent_exempt: false,
+                burn_policy: BurnPolicy::OwnerAndHolder,
+                lamports_per_token: 0,
+                transfer_count: 0,
+                cumulative_volume: 0,
+                holder_count: 0,
+                registered_accounts: 0,
+                dust_threshold: 0,
+                freeze_authority: COption::None,
+                is_paused: false,
+                interest_rate_bps: 0,
+                emergency_authority: COption::None,
+            })
+        );
+
+        assert_eq!(
+            Err(ProgramError::AccountAlreadyInitialized),
+            test_suite.process_init_bank_instruction(8)
+        );
+    }
+
+    #[test]
+    fn test_initialize_bank_account() {
+        let mut test_suite = TestSuite::default(60);
+        test_suite.add_default_bank_accounts(1);
+        test_suite.process_init_bank_instruction(8).unwrap();
+        test_suite.process_init_bank_account_instruction(0).unwrap();
+
+        assert_eq!(
+            Ok(true),
+            test_suite.account_eq(
+                0,
+                &Account {
+                    amount: 0,
+                    is_initialized: true,
+                    is_opened: true,
+                    owner: test_suite.bank_accounts_owner_info[0].0,
+                    delegate: COption::None,
+                    delegated_amount: 0,
+                    bank: test_suite.bank_info.0,
+                    permit_nonce: 0,
+                    is_frozen: false,
+                    last_accrual: 0,
+                }
+            )
+        );
+
+        assert_eq!(
+            Err(ProgramError::AccountAlreadyInitialized),
+            test_suite.process_init_bank_account_instruction(0)
+        );
+    }
+
+    #[test]
+    fn test_initialize_account_in_unopened_bank() {
+        let mut test_suite = TestSuite::default(60);
+        test_suite.add_default_bank_accounts(1);
+        assert_eq!(
+            Err(ProgramError::UninitializedAccount),
+            test_suite.process_init_bank_account_instruction(0)
+        );
+        assert!(!Account::unpack_is_initialized(
+            &test_suite.bank_accounts_info[0].1.data
+        ));
+    }
+
+    #[test]
+    fn test_initialize_account_in_garbage_bank() {
+        use crate::instruction::initialize_account;
+        let mut test_suite = TestSuite::default(60);
+        test_suite.add_default_bank_accounts(2);
+        test_suite.process_init_bank_instruction(8).unwrap();
+        test_suite.process_init_bank_account_instruction(0).unwrap();
+
+        // An account of the program passed as the bank.
+        let instruction = initialize_account(
+            &test_suite.program_id,
+            &test_suite.bank_accounts_info[0].0,
+            &test_suite.bank_accounts_info[1].0,
+            &test_suite.bank_accounts_owner_info[1].0,
+        )
+        .unwrap();
+        let (bank, rest) = test_suite.bank_accounts_info.split_at_mut(1);
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                instruction,
+                vec![
+                    &mut bank[0].1,
+                    &mut rest[0].1,
+                    &mut test_suite.bank_accounts_owner_info[1].1,
+                ],
+            )
+        );
+
+        // A bank whose data doesn't decode, its close policy being out of range.
+        test_suite.bank_info.1.data[42] = 3;
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            test_suite.process_init_bank_account_instruction(1)
+        );
+        assert!(!Account::unpack_is_initialized(
+            &test_suite.bank_accounts_info[1].1.data
+        ));
+    }
+
+    #[test]
+    fn test_initialize_account_with_history() {
+        let mut test_suite = TestSuite::builder().accounts(1).build().unwrap();
+        test_suite.process_init_bank_instruction(8).unwrap();
+        for len in [
+            Account::LEN + 1,
+            history::account_len(0),
+            history::account_len(2) - 1,
+        ] {
+            test_suite.bank_accounts_info[0].1.data = vec![0; len];
+            assert_eq!(
+                Err(ProgramError::InvalidAccountData),
+                test_suite.process_init_bank_account_instruction(0)
+            );
+        }
+
+        test_suite.bank_accounts_info[0].1.data = vec![0; history::account_len(2)];
+        test_suite.process_init_bank_account_instruction(0).unwrap();
+        let data = &test_suite.bank_accounts_info[0].1.data;
+        assert!(Account::unpack_extended(data).unwrap().can_trade());
+        assert_eq!(history::capacity(data), 2);
+        assert_eq!(history::records(data), vec![]);
+        // The rest of the instructions take the longer account as they do a plain one.
+        test_suite.process_mint_to(0, 10).unwrap();
+        assert_eq!(
+            Account::unpack_extended(&test_suite.bank_accounts_info[0].1.data)
+                .unwrap()
+                .amount,
+            10
+        );
+    }
+
+    #[test]
+    fn test_mint_to() {
+        let mut test_suite = TestSuite::default(60);
+        test_suite.add_default_bank_accounts(1);
+        test_suite.process_init_bank_instruction(8).unwrap();
+        test_suite.process_init_bank_account_instruction(0).unwrap();
+        test_suite.process_mint_to(0, 100).unwrap();
+
+        assert_eq!(
+            Ok(true),
+            test_suite.bank_eq(&Bank {
+                decimals: 8,
+                bank_owner: test_suite.bank_owner_info.0,
+                is_opened: true,
+                total_supply: 100,
+                close_policy: ClosePolicy::Reject,
+                require_rent_exempt: false,
+                burn_policy: BurnPolicy::OwnerAndHolder,
+                lamports_per_token: 0,
+                transfer_count: 0,
+                cumulative_volume: 0,
+                holder_count: 1,
+                registered_accounts: 0,
+                dust_threshold: 0,
+                freeze_authority: COption::None,
+                is_paused: false,
+                interest_rate_bps: 0,
+                emergency_authority: COption::None,
+            })
+        );
+
+        assert_eq!(
+            Ok(true),
+            test_suite.account_eq(
+                0,
+                &Account {
+                    amount: 100,
+                    is_initialized: true,
+                    is_opened: true,
+                    owner: test_suite.bank_accounts_owner_info[0].0,
+                    delegate: COption::None,
+                    delegated_amount: 0,
+                    bank: test_suite.bank_info.0,
+                    permit_nonce: 0,
+                    is_frozen: false,
+                    last_accrual: 0,
+                }
+            )
+        );
+
+        let failed_instruction = mint_to(
+            &test_suite.program_id,
+            &test_suite.bank_info.0,
+            &test_suite.bank_accounts_info[0].0,
+            &Pubkey::new_unique(),
+            100,
+        )
+        .unwrap();
+
+        assert_eq!(
+            Err(ProgramError::IllegalOwner),
+            do_process_instruction(
+                failed_instruction,
+                vec![
+                    &mut test_suite.bank_info.1,
+                    &mut test_suite.bank_accounts_info[0].1,
+                    &mut test_suite.bank_owner_info.1
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_mint_to_closed_bank() {
+        let mut test_suite = TestSuite::default(60);
+        test_suite.add_default_bank_accounts(1);
+        assert_eq!(
+            Err(BankError::BankClosed.into()),
+            test_suite.process_mint_to(0, 100)
+        );
+
+        // A bank that was opened, its accounts with it, and has since been closed.
+        test_suite.process_init_bank_instruction(8).unwrap();
+        test_suite.process_init_bank_account_instruction(0).unwrap();
+        test_suite.process_mint_to(0, 100).unwrap();
+        let mut bank = Bank::unpack(&test_suite.bank_info.1.data).unwrap();
+        bank.is_opened = false;
+        Bank::pack_into_slice(&bank, &mut test_suite.bank_info.1.data);
+        assert_eq!(
+            Err(BankError::BankClosed.into()),
+            test_suite.process_mint_to(0, 100)
+        );
+        assert_eq!(
+            Bank::unpack_unchecked(&test_suite.bank_info.1.data)
+                .unwrap()
+                .total_supply,
+            100
+        );
+        assert_eq!(
+            Account::unpack(&test_suite.bank_accounts_info[0].1.data)
+                .unwrap()
+                .amount,
+            100
+        );
+    }
+
+    #[test]
+    fn test_transfer() {
+        let mut test_suite = TestSuite::default(60);
+        test_suite.add_default_bank_accounts(2);
+        test_suite.process_init_bank_instruction(8).unwrap();
+        test_suite.process_init_all_accounts().unwrap();
+        test_suite.process_mint_to(0, 100).unwrap();
+        test_suite.process_transfer(0, 1, 60).unwrap();
+
+        assert_eq!(
+            Ok(true),
+            test_suite.account_eq(
+                0,
+                &Account {
+                    amount: 40,
+                    is_initialized: true,
+                    is_opened: true,
+                    owner: test_suite.bank_accounts_owner_info[0].0,
+                    delegate: COption::None,
+                    delegated_amount: 0,
+                    bank: test_suite.bank_info.0,
+                    permit_nonce: 0,
+                    is_frozen: false,
+                    last_accrual: 0,
+                },
+            )
+        );
+
+        assert_eq!(
+            Ok(true),
+            test_suite.account_eq(
+                1,
+                &Account {
+                    amount: 60,
+                    is_initialized: true,
+                    is_opened: true,
+                    owner: test_suite.bank_accounts_owner_info[1].0,
+                    delegate: COption::None,
+                    delegated_amount: 0,
+                    bank: test_suite.bank_info.0,
+                    permit_nonce: 0,
+                    is_frozen: false,
+                    last_accrual: 0,
+                },
+            )
+        );
+
+        assert_eq!(
+            Err(BankError::InsufficientFunds.into()),
+            test_suite.process_transfer(0, 1, 60)
+        );
+    }
+
+    #[test]
+    fn test_bank_statistics() {
+        let mut test_suite = TestSuite::builder()
+            .accounts(3)
+            .initialized(2)
+            .mint(0, 100)
+            .build()
+            .unwrap();
+        let stats = |test_suite: &TestSuite| {
+            let bank = Bank::unpack(&test_suite.bank_info.1.data).unwrap();
+            (
+                bank.transfer_count,
+                bank.cumulative_volume,
+                bank.holder_count,
+            )
+        };
+        assert_eq!(stats(&test_suite), (0, 0, 1));
+        test_suite.process_transfer(0, 1, 40).unwrap();
+        assert_eq!(stats(&test_suite), (1, 40, 2));
+        // Account 1 empties into account 2, which takes its place among the holders.
+        test_suite.process_transfer(1, 2, 40).unwrap();
+        assert_eq!(stats(&test_suite), (2, 80, 2));
+        // Each hop counts; account 1 holds tokens only in between.
+        test_suite.process_transfer_route(&[0, 1, 2], 10).unwrap();
+        assert_eq!(stats(&test_suite), (4, 100, 2));
+        test_suite.process_burn(2, 50).unwrap();
+        assert_eq!(stats(&test_suite), (4, 100, 1));
+
+        // A read-only bank, as SPL tooling passes, leaves the counters alone, so it only
+        // takes transfers that don't make or empty a holder.
+        assert_eq!(
+            test_suite.process_transfer_uncounted(0, 1, 5),
+            Err(BankError::ReadOnlyBank.into())
+        );
+        test_suite.process_transfer(0, 1, 5).unwrap();
+        assert_eq!(stats(&test_suite), (5, 105, 2));
+        test_suite.process_transfer_uncounted(0, 1, 5).unwrap();
+        assert_eq!(
+            Account::unpack(&test_suite.bank_accounts_info[1].1.data)
+                .unwrap()
+                .amount,
+            10
+        );
+        assert_eq!(stats(&test_suite), (5, 105, 2));
+    }
+
+    #[test]
+    fn test_transfer_route() {
+        let mut suite = TestSuite::builder()
+            .accounts(3)
+            .initialized(2)
+            .mint(0, 100)
+            .build()
+            .unwrap();
+        let amounts = |suite: &TestSuite| {
+            suite
+                .bank_accounts_info
+                .iter()
+                .map(|(_, account)| Account::unpack(&account.data).unwrap().amount)
+                .collect::<Vec<_>>()
+        };
+
+        // Account 1 starts empty; the route only passes through it.
+        suite.process_transfer_route(&[0, 1, 2], 30).unwrap();
+        assert_eq!(amounts(&suite), vec![70, 0, 30]);
+        suite.process_transfer_route(&[2, 0], 10).unwrap();
+        assert_eq!(amounts(&suite), vec![80, 0, 20]);
+
+        assert_eq!(
+            suite.process_transfer_route(&[0, 1, 2], 81),
+            Err(BankError::InsufficientFunds.into())
+        );
+        assert_eq!(
+            suite.process_transfer_route(&[0], 1),
+            Err(ProgramError::NotEnoughAccountKeys)
+        );
+        assert_eq!(amounts(&suite), vec![80, 0, 20]);
+    }
+
+    #[test]
+    fn test_approve() {
+        let mut test_suite = TestSuite::default(64);
+        test_suite.add_default_bank_accounts(1);
+        let (key, mut account) = TestSuite::new_key_account(64);
+        test_suite.process_init_bank_instruction(8).unwrap();
+        test_suite.process_init_all_accounts().unwrap();
+        test_suite.process_mint_to(0, 100).unwrap();
+        test_suite
+            .process_approve(0, (&key, &mut account), 50)
+            .unwrap();
+
+        assert_eq!(
+            Ok(true),
+            test_suite.account_eq(
+                0,
+                &Account {
+                    amount: 100,
+                    is_initialized: true,
+                    is_opened: true,
+                    owner: test_suite.bank_accounts_owner_info[0].0,
+                    delegate: COption::Some(key),
+                    delegated_amount: 50,
+                    bank: test_suite.bank_info.0,
+                    permit_nonce: 0,
+                    is_frozen: false,
+                    last_accrual: 0,
+                }
+            )
+        );
+
+        // The allowance grows only for the delegate it was given to.
+        let (other_key, mut other_account) = TestSuite::new_key_account(64);
+        assert_eq!(
+            Err(BankError::DelegateMismatch.into()),
+            test_suite.process_approve(0, (&other_key, &mut other_account), 10)
+        );
+    }
+
+    #[test]
+    fn test_approve_transfer() {
+        let mut test_suite = TestSuite::default(64);
+        test_suite.add_default_bank_accounts(2);
+        let (key, mut account) = TestSuite::new_key_account(64);
+        test_suite.process_init_bank_instruction(8).unwrap();
+        test_suite.process_init_all_accounts().unwrap();
+        test_suite.process_mint_to(0, 100).unwrap();
+        test_suite
+            .process_approve(0, (&key, &mut account), 50)
+            .unwrap();
+        test_suite
+            .process_transfer_delegate(0, (&key, &mut account), 1, 30)
+            .unwrap();
+
+        assert_eq!(
+            Ok(true),
+            test_suite.account_eq(
+                0,
+                &Account {
+                    amount: 70,
+                    is_initialized: true,
+                    is_opened: true,
+                    owner: test_suite.bank_accounts_owner_info[0].0,
+                    delegate: COption::Some(key),
+                    delegated_amount: 20,
+                    bank: test_suite.bank_info.0,
+                    permit_nonce: 0,
+                    is_frozen: false,
+                    last_accrual: 0,
+                },
+            )
+        );
+
+        assert_eq!(
+            Ok(true),
+            test_suite.account_eq(
+                1,
+                &Account {
+                    amount: 30,
+                    is_initialized: true,
+                    is_opened: true,
+                    owner: test_suite.bank_accounts_owner_info[1].0,
+                    delegate: COption::None,
+                    delegated_amount: 0,
+                    bank: test_suite.bank_info.0,
+                    permit_nonce: 0,
+                    is_frozen: false,
+                    last_accrual: 0,
+                },
+            )
+        );
+
+        assert_eq!(
+            Err(BankError::InsufficientFunds.into()),
+            test_suite.process_transfer_delegate(0, (&key, &mut account), 1, 30)
+        );
+
+        // Spending the rest of the allowance clears the delegate, so another one can be
+        // approved.
+        test_suite
+            .process_transfer_delegate(0, (&key, &mut account), 1, 20)
+            .unwrap();
+        let (new_key, mut new_account) = TestSuite::new_key_account(64);
+        test_suite
+            .process_approve(0, (&new_key, &mut new_account), 10)
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            test_suite.account_eq(
+                0,
+                &Account {
+                    amount: 50,
+                    is_initialized: true,
+                    is_opened: true,
+                    owner: test_suite.bank_accounts_owner_info[0].0,
+                    delegate: COption::Some(new_key),
+                    delegated_amount: 10,
+                    bank: test_suite.bank_info.0,
+                    permit_nonce: 0,
+                    is_frozen: false,
+                    last_accrual: 0,
+                },
+            )
+        );
+        assert_eq!(
+            Err(ProgramError::IllegalOwner),
+            test_suite.process_transfer_delegate(0, (&key, &mut account), 1, 1)
+        );
+
+        // The owner can still spend approved tokens, and the delegate can't spend more
+        // than the account holds.
+        test_suite.process_transfer(0, 1, 45).unwrap();
+        assert_eq!(
+            Err(BankError::InsufficientFunds.into()),
+            test_suite.process_transfer_delegate(0, (&new_key, &mut new_account), 1, 10)
+        );
+        test_suite
+            .process_transfer_delegate(0, (&new_key, &mut new_account), 1, 5)
+            .unwrap();
+        let account = Account::unpack(&test_suite.bank_accounts_info[0].1.data).unwrap();
+        assert_eq!((account.amount, account.delegated_amount), (0, 5));
+    }
+
+    #[test]
+    fn test_revoke() {
+        let mut test_suite = TestSuite::default(64);
+        test_suite.add_default_bank_accounts(2);
+        let (key, mut account) = TestSuite::new_key_account(64);
+        test_suite.process_init_bank_instruction(8).unwrap();
+        test_suite.process_init_all_accounts().unwrap();
+        test_suite.process_mint_to(0, 100).unwrap();
+        test_suite
+            .process_approve(0, (&key, &mut account), 50)
+            .unwrap();
+        test_suite
+            .process_transfer_delegate(0, (&key, &mut account), 1, 20)
+            .unwrap();
+        test_suite.process_revoke(0).unwrap();
+
+        assert_eq!(
+            Ok(true),
+            test_suite.account_eq(
+                0,
+                &Account {
+                    amount: 80,
+                    is_initialized: true,
+                    is_opened: true,
+                    owner: test_suite.bank_accounts_owner_info[0].0,
+                    delegate: COption::None,
+                    delegated_amount: 0,
+                    bank: test_suite.bank_info.0,
+                    permit_nonce: 0,
+                    is_frozen: false,
+                    last_accrual: 0,
+                }
+            )
+        );
+
+        assert_eq!(
+            Err(ProgramError::IllegalOwner),
+            test_suite.process_transfer_delegate(0, (&key, &mut account), 1, 10)
+        );
+    }
+
+    #[test]
+    fn test_assign_to_program() {
+        let mut test_suite = TestSuite::builder()
+            .accounts(2)
+            .initialized(8)
+            .mint(0, 100)
+            .build()
+            .unwrap();
+        let (key, mut account) = TestSuite::new_key_account(64);
+        test_suite
+            .process_approve(0, (&key, &mut account), 50)
+            .unwrap();
+        let program = Pubkey::new_unique();
+        let (vault, bump) = Pubkey::find_program_address(&[b"vault"], &program);
+        test_suite
+            .process_assign_to_program(0, &program, &[b"vault", &[bump]])
+            .unwrap();
 
-        fn init_bank_instruction(&self, decimal: u8) -> Result<Instruction, ProgramError> {
-            initialize_bank(
-                &self.program_id,
-                &self.bank_info.0,
-                &self.bank_owner_info.0,
-                decimal,
+        assert_eq!(
+            Ok(true),
+            test_suite.account_eq(
+                0,
+                &Account {
+                    amount: 100,
+                    is_initialized: true,
+                    is_opened: true,
+                    owner: vault,
+                    delegate: COption::None,
+                    delegated_amount: 0,
+                    bank: test_suite.bank_info.0,
+                    permit_nonce: 0,
+                    is_frozen: false,
+                    last_accrual: 0,
+                }
             )
-        }
+        );
+
+        // Neither the old owner nor its delegate can spend from it any more.
+        assert_eq!(
+            Err(ProgramError::IllegalOwner),
+            test_suite.process_transfer(0, 1, 10)
+        );
+        assert_eq!(
+            Err(ProgramError::IllegalOwner),
+            test_suite.process_transfer_delegate(0, (&key, &mut account), 1, 10)
+        );
+        assert_eq!(
+            Err(ProgramError::IllegalOwner),
+            test_suite.process_assign_to_program(0, &program, &[b"vault", &[bump]])
+        );
+    }
+
+    #[test]
+    fn test_set_guardians() {
+        let mut test_suite = TestSuite::builder()
+            .accounts(2)
+            .initialized(8)
+            .build()
+            .unwrap();
+        let (_, mut recovery) = test_suite.new_recovery_account(0);
+        let guardians: Vec<_> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        test_suite
+            .process_set_guardians(0, &mut recovery, &guardians, 2, 10)
+            .unwrap();
+        let state = Recovery::unpack(&recovery.data).unwrap();
+        assert_eq!(state.guardians(), &guardians[..]);
+        assert_eq!(state.owner, test_suite.bank_accounts_owner_info[0].0);
+        assert_eq!((state.threshold, state.delay), (2, 10));
+        assert_eq!(state.pending_owner, COption::None);
 
-        fn bank_eq(&self, expect_bank: &Bank) -> Result<bool, ProgramError> {
-            let bank = Bank::unpack_unchecked(&self.bank_info.1.data)?;
-            Ok(expect_bank.eq(&bank))
+        // No threshold, one above the guardians there are, and a guardian named twice.
+        let twice = [guardians[0], guardians[0]];
+        for (guardians, threshold) in [(&guardians[..], 0), (&guardians[..], 4), (&twice[..], 1)] {
+            assert_eq!(
+                Err(ProgramError::InvalidArgument),
+                test_suite.process_set_guardians(0, &mut recovery, guardians, threshold, 0)
+            );
         }
+        let (_, mut other) = test_suite.new_recovery_account(1);
+        assert_eq!(
+            Err(ProgramError::InvalidSeeds),
+            test_suite.process_set_guardians(0, &mut other, &guardians, 2, 10)
+        );
 
-        fn account_eq(&self, i: usize, expect_account: &Account) -> Result<bool, ProgramError> {
-            if i >= self.bank_accounts_info.len() {
-                return Err(ProgramError::Custom(000));
-            }
+        assert_eq!(
+            Err(BankError::NoPendingRecovery.into()),
+            test_suite.process_cancel_recovery(0, &mut recovery)
+        );
+        let pending = Recovery {
+            pending_owner: COption::Some(Pubkey::new_unique()),
+            activation_slot: 10,
+            ..state
+        };
+        pending.pack_into_slice(&mut recovery.data);
+        test_suite
+            .process_cancel_recovery(0, &mut recovery)
+            .unwrap();
+        assert_eq!(Recovery::unpack(&recovery.data).unwrap(), state);
+
+        // Naming the guardians again cancels too.
+        pending.pack_into_slice(&mut recovery.data);
+        test_suite
+            .process_set_guardians(0, &mut recovery, &guardians[..1], 1, 0)
+            .unwrap();
+        assert_eq!(
+            Recovery::unpack(&recovery.data).unwrap().pending_owner,
+            COption::None
+        );
 
-            let account = Account::unpack_unchecked(&self.bank_accounts_info[i].1.data)?;
-            Ok(expect_account.eq(&account))
+        // Guardians named by an earlier owner don't act for the account.
+        Recovery {
+            owner: Pubkey::new_unique(),
+            ..pending
         }
+        .pack_into_slice(&mut recovery.data);
+        assert_eq!(
+            Err(ProgramError::IllegalOwner),
+            test_suite.process_cancel_recovery(0, &mut recovery)
+        );
+    }
 
-        fn process_init_bank_instruction(&mut self, decimal: u8) -> ProgramResult {
-            let instruction = self.init_bank_instruction(decimal).unwrap();
-            do_process_instruction(
-                instruction,
-                vec![&mut self.bank_info.1, &mut self.bank_owner_info.1],
-            )
+    #[test]
+    fn test_initialize_multisig() {
+        let program_id = Pubkey::new_unique();
+        let multisig = Pubkey::new_unique();
+        let signers: Vec<_> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let signer_refs: Vec<_> = signers.iter().collect();
+        let process = |signers: &[&Pubkey], threshold: u8, account: &mut SolanaAccount| {
+            let instruction =
+                initialize_multisig(&program_id, &multisig, signers, threshold).unwrap();
+            let mut signer_accounts = vec![SolanaAccount::default(); signers.len()];
+            let mut accounts = vec![account];
+            accounts.extend(signer_accounts.iter_mut());
+            do_process_instruction(instruction, accounts)
+        };
+
+        let mut account = SolanaAccount::new(0, Multisig::LEN, &program_id);
+        // No threshold, one above the signers there are, and a signer named twice.
+        let twice = [signer_refs[0], signer_refs[0]];
+        for (signers, threshold) in [
+            (&signer_refs[..], 0),
+            (&signer_refs[..], 4),
+            (&twice[..], 1),
+        ] {
+            assert_eq!(
+                Err(ProgramError::InvalidArgument),
+                process(signers, threshold, &mut account)
+            );
         }
+        process(&signer_refs, 2, &mut account).unwrap();
+        let state = Multisig::unpack(&account.data).unwrap();
+        assert_eq!(state.signers(), &signers[..]);
+        assert_eq!(state.threshold, 2);
+        assert_eq!(
+            Err(ProgramError::AccountAlreadyInitialized),
+            process(&signer_refs, 2, &mut account)
+        );
 
-        fn process_init_bank_account_instruction(&mut self, i: usize) -> ProgramResult {
-            self.check_index(i)?;
-            let instruction = initialize_account(
-                &self.program_id,
-                &self.bank_info.0,
-                &self.bank_accounts_info[i].0,
-                &self.bank_accounts_owner_info[i].0,
-            )?;
-            do_process_instruction(
-                instruction,
-                vec![
-                    &mut self.bank_info.1,
-                    &mut self.bank_accounts_info[i].1,
-                    &mut self.bank_accounts_owner_info[i].1,
-                ],
+        let mut other = SolanaAccount::new(0, Multisig::LEN, &Pubkey::new_unique());
+        assert_eq!(
+            Err(ProgramError::IllegalOwner),
+            process(&signer_refs, 2, &mut other)
+        );
+        let mut short = SolanaAccount::new(0, Multisig::LEN - 1, &program_id);
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            process(&signer_refs, 2, &mut short)
+        );
+    }
+
+    #[test]
+    fn test_checked_decimals() {
+        use crate::instruction::{burn_checked, mint_to_checked, transfer_checked};
+        let suite = TestSuite::builder()
+            .accounts(2)
+            .initialized(2)
+            .mint(0, 100)
+            .build()
+            .unwrap();
+        let program_id = &suite.program_id;
+        let bank = &suite.bank_info;
+        let bank_owner = &suite.bank_owner_info;
+        let (account, owner) = (
+            &suite.bank_accounts_info[0],
+            &suite.bank_accounts_owner_info[0],
+        );
+        let empty = &suite.bank_accounts_info[1];
+        let transfer = |decimals| {
+            let instruction = transfer_checked(
+                program_id,
+                &bank.0,
+                &account.0,
+                &empty.0,
+                &owner.0,
+                &[],
+                40,
+                decimals,
             )
-        }
+            .unwrap();
+            let accounts = vec![
+                account.1.clone(),
+                empty.1.clone(),
+                owner.1.clone(),
+                bank.1.clone(),
+            ];
+            run(&instruction, accounts)
+        };
+        let mint = |decimals| {
+            let instruction = mint_to_checked(
+                program_id,
+                &bank.0,
+                &account.0,
+                &bank_owner.0,
+                &[],
+                40,
+                decimals,
+            )
+            .unwrap();
+            let accounts = vec![bank.1.clone(), account.1.clone(), bank_owner.1.clone()];
+            run(&instruction, accounts)
+        };
+        let burn = |decimals| {
+            let instruction = burn_checked(
+                program_id,
+                &bank.0,
+                &account.0,
+                &bank_owner.0,
+                &owner.0,
+                40,
+                decimals,
+            )
+            .unwrap();
+            let accounts = vec![
+                bank.1.clone(),
+                account.1.clone(),
+                bank_owner.1.clone(),
+                owner.1.clone(),
+            ];
+            run(&instruction, accounts)
+        };
 
-        fn process_init_all_accounts(&mut self) -> ProgramResult {
-            for i in 0..self.bank_accounts_info.len() {
-                self.process_init_bank_account_instruction(i)?;
-            }
-            Ok(())
+        let mismatch: ProgramResult = Err(BankError::DecimalsMismatch.into());
+        for process in [&transfer as &dyn Fn(u8) -> ProgramResult, &mint, &burn] {
+            assert_eq!(process(2), Ok(()));
+            assert_eq!(process(3), mismatch);
+            assert_eq!(process(0), mismatch);
         }
+    }
 
-        fn process_mint_to(&mut self, i: usize, amount: u64) -> ProgramResult {
-            self.check_index(i)?;
+    #[test]
+    fn test_set_authority() {
+        use crate::instruction::{set_authority, AuthorityType};
+        let mut suite = TestSuite::builder()
+            .accounts(1)
+            .initialized(2)
+            .build()
+            .unwrap();
+        let (delegate, mut delegate_account) = TestSuite::new_key_account(0);
+        suite
+            .process_approve(0, (&delegate, &mut delegate_account), 50)
+            .unwrap();
+        let program_id = suite.program_id;
+        let new_authority = Pubkey::new_unique();
+        let (bank, owner) = (suite.bank_info.0, suite.bank_accounts_owner_info[0].0);
+        let account = suite.bank_accounts_info[0].0;
 
-            let instruction = mint_to(
-                &self.program_id,
-                &self.bank_info.0,
-                &self.bank_accounts_info[i].0,
-                &self.bank_owner_info.0,
-                amount,
-            )?;
+        // Only the current owner hands the account over.
+        let instruction = set_authority(
+            &program_id,
+            &account,
+            AuthorityType::AccountOwner,
+            &suite.bank_owner_info.0,
+            Some(&new_authority),
+        )
+        .unwrap();
+        assert_eq!(
             do_process_instruction(
                 instruction,
                 vec![
-                    &mut self.bank_info.1,
-                    &mut self.bank_accounts_info[i].1,
-                    &mut self.bank_owner_info.1,
+                    &mut suite.bank_accounts_info[0].1,
+                    &mut suite.bank_owner_info.1
                 ],
-            )
+            ),
+            Err(ProgramError::IllegalOwner)
+        );
+        let mut instruction = set_authority(
+            &program_id,
+            &account,
+            AuthorityType::AccountOwner,
+            &owner,
+            Some(&new_authority),
+        )
+        .unwrap();
+        for signed in [false, true] {
+            instruction.accounts[1].is_signer = signed;
+            let result = do_process_instruction(
+                instruction.clone(),
+                vec![
+                    &mut suite.bank_accounts_info[0].1,
+                    &mut suite.bank_accounts_owner_info[0].1,
+                ],
+            );
+            if signed {
+                result.unwrap();
+            } else {
+                assert_eq!(result, Err(ProgramError::MissingRequiredSignature));
+            }
         }
+        let state = Account::unpack(&suite.bank_accounts_info[0].1.data).unwrap();
+        assert_eq!(state.owner, new_authority);
+        assert_eq!((state.delegate, state.delegated_amount), (COption::None, 0));
 
-        fn process_transfer(&mut self, from: usize, to: usize, amount: u64) -> ProgramResult {
-            self.check_index(from)?;
-            self.check_index(to)?;
-
-            let instruction = transfer(
-                &self.program_id,
-                &self.bank_accounts_info[from].0,
-                &self.bank_accounts_info[to].0,
-                &self.bank_accounts_owner_info[from].0,
-                amount,
-            )?;
-            let mut from_acc = self.bank_accounts_info[from].1.clone();
-            let mut to_acc = self.bank_accounts_info[to].1.clone();
+        // A bank always has an owner.
+        let instruction = set_authority(
+            &program_id,
+            &bank,
+            AuthorityType::BankOwner,
+            &suite.bank_owner_info.0,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
             do_process_instruction(
                 instruction,
-                vec![&mut from_acc, &mut to_acc, &mut self.bank_owner_info.1],
-            )?;
-            self.bank_accounts_info[from].1 = from_acc;
-            self.bank_accounts_info[to].1 = to_acc;
-            Ok(())
-        }
-
-        fn process_transfer_delegate(
-            &mut self,
-            from: usize,
-            delegate: (&Pubkey, &mut SolanaAccount),
-            to: usize,
-            amount: u64,
-        ) -> ProgramResult {
-            self.check_index(from)?;
-            self.check_index(to)?;
-
-            let instruction = transfer(
-                &self.program_id,
-                &self.bank_accounts_info[from].0,
-                &self.bank_accounts_info[to].0,
-                delegate.0,
-                amount,
-            )?;
-            let mut from_acc = self.bank_accounts_info[from].1.clone();
-            let mut to_acc = self.bank_accounts_info[to].1.clone();
-            do_process_instruction(instruction, vec![&mut from_acc, &mut to_acc, delegate.1])?;
-            self.bank_accounts_info[from].1 = from_acc;
-            self.bank_accounts_info[to].1 = to_acc;
-            Ok(())
-        }
-
-        fn process_approve(
-            &mut self,
-            i: usize,
-            delegate_key: (&Pubkey, &mut SolanaAccount),
-            delegate_amount: u64,
-        ) -> ProgramResult {
-            self.check_index(i)?;
-
-            let instruction = approve(
-                &self.program_id,
-                &self.bank_accounts_info[i].0,
-                delegate_key.0,
-                &self.bank_accounts_owner_info[i].0,
-                delegate_amount,
-            )?;
+                vec![&mut suite.bank_info.1, &mut suite.bank_owner_info.1],
+            ),
+            Err(BankError::InvalidInstruction.into())
+        );
+        let instruction = set_authority(
+            &program_id,
+            &bank,
+            AuthorityType::BankOwner,
+            &suite.bank_owner_info.0,
+            Some(&new_authority),
+        )
+        .unwrap();
+        do_process_instruction(
+            instruction,
+            vec![&mut suite.bank_info.1, &mut suite.bank_owner_info.1],
+        )
+        .unwrap();
+        let state = Bank::unpack(&suite.bank_info.1.data).unwrap();
+        assert_eq!(state.bank_owner, new_authority);
+        // The old owner can't mint anymore.
+        assert_eq!(suite.process_mint_to(0, 1), Err(ProgramError::IllegalOwner));
+    }
 
-            do_process_instruction(
-                instruction,
-                vec![
-                    &mut self.bank_accounts_info[i].1,
-                    delegate_key.1,
-                    &mut self.bank_accounts_owner_info[i].1,
-                ],
-            )
-        }
+    #[test]
+    fn test_transfer_batch() {
+        use crate::instruction::transfer_batch;
+        let mut suite = TestSuite::builder()
+            .accounts(3)
+            .initialized(2)
+            .mint(0, 100)
+            .build()
+            .unwrap();
+        let program_id = suite.program_id;
+        let bank = suite.bank_info.0;
+        let owner = suite.bank_accounts_owner_info[0].0;
+        let keys: Vec<_> = suite
+            .bank_accounts_info
+            .iter()
+            .map(|(key, _)| *key)
+            .collect();
+        let batch = |transfers: &[(&Pubkey, u64)]| {
+            transfer_batch(&program_id, &bank, &keys[0], &owner, transfers).unwrap()
+        };
+        let mut process = |instruction: Instruction, destinations: usize| {
+            let (from, rest) = suite.bank_accounts_info.split_first_mut().unwrap();
+            let mut accounts = vec![
+                &mut from.1,
+                &mut suite.bank_accounts_owner_info[0].1,
+                &mut suite.bank_info.1,
+            ];
+            accounts.extend(
+                rest.iter_mut()
+                    .take(destinations)
+                    .map(|(_, account)| account),
+            );
+            do_process_instruction(instruction, accounts)
+        };
 
-        fn process_burn(&mut self, i: usize, burn_amount: u64) -> ProgramResult {
-            self.check_index(i)?;
-            let instruction = burn(
-                &self.program_id,
-                &self.bank_info.0,
-                &self.bank_accounts_info[i].0,
-                &self.bank_owner_info.0,
-                &self.bank_accounts_owner_info[i].0,
-                burn_amount,
-            )?;
+        assert_eq!(
+            process(batch(&[(&keys[1], u64::MAX), (&keys[2], 1)]), 2),
+            Err(BankError::Overflow.into())
+        );
+        let instruction = batch(&[(&keys[1], 30), (&keys[2], 20)]);
+        assert_eq!(
+            process(instruction.clone(), 1),
+            Err(ProgramError::NotEnoughAccountKeys)
+        );
+        process(instruction, 2).unwrap();
+        let amounts: Vec<_> = suite
+            .bank_accounts_info
+            .iter()
+            .map(|(_, account)| Account::unpack(&account.data).unwrap().amount)
+            .collect();
+        assert_eq!(amounts, [50, 30, 20]);
+        let bank = Bank::unpack(&suite.bank_info.1.data).unwrap();
+        assert_eq!((bank.transfer_count, bank.cumulative_volume), (2, 50));
+    }
 
-            do_process_instruction(
-                instruction,
-                vec![
-                    &mut self.bank_info.1,
-                    &mut self.bank_accounts_info[i].1,
-                    &mut self.bank_owner_info.1,
-                    &mut self.bank_accounts_owner_info[i].1,
-                ],
-            )
-        }
+    #[test]
+    fn test_pause_bank() {
+        let mut suite = TestSuite::builder()
+            .accounts(2)
+            .initialized(2)
+            .mint(0, 100)
+            .build()
+            .unwrap();
+        suite.process_pause_bank(true).unwrap();
+        assert!(Bank::unpack(&suite.bank_info.1.data).unwrap().is_paused);
+        assert_eq!(
+            suite.process_pause_bank(true),
+            Err(ProgramError::InvalidArgument)
+        );
 
-        fn process_close(&mut self, i: usize) -> ProgramResult {
-            self.check_index(i)?;
-            let instruction = close_account(
-                &self.program_id,
-                &self.bank_accounts_info[i].0,
-                &self.bank_accounts_owner_info[i].0,
-            )?;
+        let paused = Err(BankError::BankPaused.into());
+        assert_eq!(suite.process_transfer(0, 1, 10), paused);
+        assert_eq!(suite.process_mint_to(1, 10), paused);
+        assert_eq!(suite.process_burn(0, 10), paused);
+        let (delegate, mut delegate_account) = TestSuite::new_key_account(0);
+        assert_eq!(
+            suite.process_approve(0, (&delegate, &mut delegate_account), 10),
+            paused
+        );
+
+        suite.process_pause_bank(false).unwrap();
+        suite.process_transfer(0, 1, 10).unwrap();
+        assert_eq!(
+            suite.process_pause_bank(false),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_accrue_interest() {
+        let mut suite = TestSuite::builder()
+            .accounts(1)
+            .initialized(2)
+            .mint(0, 100)
+            .build()
+            .unwrap();
+        // A bank paying no interest leaves the account be, without reading the clock;
+        // accruing interest itself is tested in `interest` and the functional tests.
+        let before = suite.bank_accounts_info[0].1.data.clone();
+        suite.process_accrue_interest(0).unwrap();
+        assert_eq!(suite.bank_accounts_info[0].1.data, before);
+        assert_eq!(
+            Bank::unpack(&suite.bank_info.1.data).unwrap().total_supply,
+            100
+        );
+
+        suite.process_pause_bank(true).unwrap();
+        assert_eq!(
+            suite.process_accrue_interest(0),
+            Err(BankError::BankPaused.into())
+        );
+    }
+
+    #[test]
+    fn test_create_session_key() {
+        let mut test_suite = TestSuite::builder()
+            .accounts(2)
+            .initialized(8)
+            .build()
+            .unwrap();
+        let session_key = Pubkey::new_unique();
+        let (_, mut session) = test_suite.new_session_account(0, &session_key);
+        test_suite
+            .process_create_session_key(0, &mut session, &session_key, 50, 100)
+            .unwrap();
+        let state = Session::unpack(&session.data).unwrap();
+        assert_eq!(state.owner, test_suite.bank_accounts_owner_info[0].0);
+        assert_eq!(state.session_key, session_key);
+        assert_eq!((state.remaining_spend, state.expiry_slot), (50, 100));
+
+        // Creating the key again replaces its limits.
+        test_suite
+            .process_create_session_key(0, &mut session, &session_key, 0, 100)
+            .unwrap();
+        assert_eq!(Session::unpack(&session.data).unwrap().remaining_spend, 0);
+
+        let owner = test_suite.bank_accounts_owner_info[0].0;
+        assert_eq!(
+            Err(ProgramError::InvalidArgument),
+            test_suite.process_create_session_key(0, &mut session, &owner, 50, 100)
+        );
+        let (_, mut other) = test_suite.new_session_account(1, &session_key);
+        assert_eq!(
+            Err(ProgramError::InvalidSeeds),
+            test_suite.process_create_session_key(0, &mut other, &session_key, 50, 100)
+        );
+    }
+
+    #[test]
+    fn test_feature_gate() {
+        let mut suite = TestSuite::builder()
+            .accounts(2)
+            .initialized(2)
+            .mint(0, 100)
+            .build()
+            .unwrap();
+        // A bank without a gate runs the default features.
+        suite.process_transfer_route(&[0, 1], 10).unwrap();
+
+        suite.process_set_features(feature_gate::PERMITS).unwrap();
+        let gate = FeatureGate::unpack(&suite.feature_gate_info.1.data).unwrap();
+        assert_eq!(gate.bank, suite.bank_info.0);
+        assert_eq!(gate.enabled, feature_gate::PERMITS);
+        assert_eq!(
+            Err(BankError::FeatureDisabled.into()),
+            suite.process_transfer_route(&[0, 1], 10)
+        );
+        suite
+            .process_set_features(feature_gate::PERMITS | feature_gate::ROUTES)
+            .unwrap();
+        suite.process_transfer_route(&[0, 1], 10).unwrap();
+        assert_eq!(
+            Account::unpack_amount(&suite.bank_accounts_info[1].1.data),
+            20
+        );
+
+        // Only the bank owner sets the gate.
+        suite.bank_owner_info.0 = Pubkey::new_unique();
+        assert_eq!(
+            Err(ProgramError::IllegalOwner),
+            suite.process_set_features(0)
+        );
+    }
+
+    #[test]
+    fn test_initialize_version() {
+        let mut test_suite = TestSuite::builder().build().unwrap();
+        let (_, mut record) = test_suite.new_version_account();
+        test_suite.process_initialize_version(&mut record).unwrap();
+        assert_eq!(Version::unpack(&record.data), Ok(version::current()));
+
+        // An upgrade brings the record up to date, but a rollback past a layout change
+        // leaves it be.
+        let older = Version {
+            minor: 0,
+            layout: 0,
+            ..version::current()
+        };
+        Version::pack(older, &mut record.data).unwrap();
+        test_suite.process_initialize_version(&mut record).unwrap();
+        assert_eq!(Version::unpack(&record.data), Ok(version::current()));
+        let newer = Version {
+            layout: version::LAYOUT + 1,
+            ..version::current()
+        };
+        Version::pack(newer, &mut record.data).unwrap();
+        assert_eq!(
+            Err(BankError::UnsupportedLayout.into()),
+            test_suite.process_initialize_version(&mut record)
+        );
+        assert_eq!(Version::unpack(&record.data), Ok(newer));
+    }
+
+    #[test]
+    fn test_sol_rate() {
+        let mut test_suite = TestSuite::builder()
+            .accounts(1)
+            .initialized(2)
+            .mint(0, 100)
+            .build()
+            .unwrap();
+        let (key, mut account) = TestSuite::new_key_account(10_000);
+        assert_eq!(
+            Err(BankError::NoSolRate.into()),
+            test_suite.process_deposit_sol(0, (&key, &mut account), 1_000)
+        );
+        assert_eq!(
+            Err(BankError::NoSolRate.into()),
+            test_suite.process_withdraw_sol(0, (&key, &mut account), 1_000)
+        );
+
+        let mut test_suite = TestSuite::builder().accounts(1).build().unwrap();
+        test_suite
+            .process_init_bank_with_sol_rate(2, 1_000)
+            .unwrap();
+        test_suite.process_init_all_accounts().unwrap();
+        assert_eq!(
+            Ok(true),
+            test_suite.bank_eq(&Bank {
+                decimals: 2,
+                bank_owner: test_suite.bank_owner_info.0,
+                is_opened: true,
+                total_supply: 0,
+                close_policy: ClosePolicy::Reject,
+                require_rent_exempt: false,
+                burn_policy: BurnPolicy::OwnerAndHolder,
+                lamports_per_token: 1_000,
+                transfer_count: 0,
+                cumulative_volume: 0,
+                holder_count: 0,
+                registered_accounts: 0,
+                dust_threshold: 0,
+                freeze_authority: COption::None,
+                is_paused: false,
+                interest_rate_bps: 0,
+                emergency_authority: COption::None,
+            })
+        );
+        // Only whole tokens are bought and sold.
+        assert_eq!(
+            Err(ProgramError::InvalidArgument),
+            test_suite.process_deposit_sol(0, (&key, &mut account), 1_500)
+        );
+        assert_eq!(
+            Err(ProgramError::InvalidArgument),
+            test_suite.process_withdraw_sol(0, (&key, &mut account), 1_500)
+        );
+        // Paying the bank back to itself.
+        let (bank_key, mut bank_account) = (test_suite.bank_info.0, test_suite.bank_info.1.clone());
+        assert_eq!(
+            Err(ProgramError::InvalidArgument),
+            test_suite.process_withdraw_sol(0, (&bank_key, &mut bank_account), 1_000)
+        );
+    }
 
+    #[test]
+    fn test_convert() {
+        // 1.50 tokens of a bank with 2 decimals, converting into one with 3.
+        let mut from = TestSuite::builder()
+            .accounts(1)
+            .initialized(2)
+            .mint(0, 150)
+            .build()
+            .unwrap();
+        let mut to = TestSuite::builder()
+            .program_id(from.program_id)
+            .accounts(1)
+            .initialized(3)
+            .build()
+            .unwrap();
+        let program_id = from.program_id;
+        let (rate_key, mut rate) = (Pubkey::new_unique(), SolanaAccount::default());
+        rate.owner = program_id;
+        rate.data = vec![0; ExchangeRate::LEN];
+        let mut set_rate = |from: &mut TestSuite, to: &mut TestSuite, numerator, denominator| {
+            let instruction = crate::instruction::set_exchange_rate(
+                &program_id,
+                &rate_key,
+                &from.bank_info.0,
+                &to.bank_info.0,
+                &from.bank_owner_info.0,
+                &to.bank_owner_info.0,
+                numerator,
+                denominator,
+            )
+            .unwrap();
             do_process_instruction(
                 instruction,
                 vec![
-                    &mut self.bank_accounts_info[i].1,
-                    &mut self.bank_accounts_owner_info[i].1,
+                    &mut rate,
+                    &mut from.bank_info.1,
+                    &mut to.bank_info.1,
+                    &mut from.bank_owner_info.1,
+                    &mut to.bank_owner_info.1,
                 ],
             )
-        }
-
-        fn check_index(&self, i: usize) -> ProgramResult {
-            if i >= self.bank_accounts_info.len() {
-                return Err(ProgramError::Custom(000));
-            }
-            Ok(())
-        }
-    }
+        };
+        assert_eq!(
+            Err(ProgramError::InvalidArgument),
+            set_rate(&mut from, &mut to, 3, 0)
+        );
+        set_rate(&mut from, &mut to, 3, 2).unwrap();
+        // The rate account belongs to the two banks in that order.
+        assert_eq!(
+            Err(BankError::BankMismatch.into()),
+            set_rate(&mut to, &mut from, 2, 3)
+        );
+        assert_eq!(
+            ExchangeRate::unpack(&rate.data),
+            Ok(ExchangeRate {
+                is_initialized: true,
+                from_bank: from.bank_info.0,
+                to_bank: to.bank_info.0,
+                numerator: 3,
+                denominator: 2,
+            })
+        );
 
-    fn do_process_instruction(
-        instruction: Instruction,
-        accounts: Vec<&mut SolanaAccount>,
-    ) -> ProgramResult {
-        let mut meta = instruction
-            .accounts
-            .iter()
-            .zip(accounts)
-            .map(|(account_meta, account)| (&account_meta.pubkey, account_meta.is_signer, account))
-            .collect::<Vec<_>>();
+        let convert =
+            |from: &mut TestSuite, to: &mut TestSuite, mut rate: SolanaAccount, amount| {
+                let instruction = crate::instruction::convert(
+                    &program_id,
+                    &rate_key,
+                    &from.bank_info.0,
+                    &from.bank_accounts_info[0].0,
+                    &from.bank_accounts_owner_info[0].0,
+                    &to.bank_info.0,
+                    &to.bank_accounts_info[0].0,
+                    amount,
+                )
+                .unwrap();
+                do_process_instruction(
+                    instruction,
+                    vec![
+                        &mut rate,
+                        &mut from.bank_info.1,
+                        &mut from.bank_accounts_info[0].1,
+                        &mut from.bank_accounts_owner_info[0].1,
+                        &mut to.bank_info.1,
+                        &mut to.bank_accounts_info[0].1,
+                    ],
+                )
+            };
+        // 1.00 at 3 for 2 is 1.500.
+        convert(&mut from, &mut to, rate.clone(), 100).unwrap();
+        assert_eq!(
+            Account::unpack(&from.bank_accounts_info[0].1.data)
+                .unwrap()
+                .amount,
+            50
+        );
+        assert_eq!(
+            Bank::unpack(&from.bank_info.1.data).unwrap().total_supply,
+            50
+        );
+        assert_eq!(
+            Account::unpack(&to.bank_accounts_info[0].1.data)
+                .unwrap()
+                .amount,
+            1_500
+        );
+        assert_eq!(
+            Bank::unpack(&to.bank_info.1.data).unwrap().total_supply,
+            1_500
+        );
 
-        let account_infos = create_is_signer_account_infos(&mut meta);
-        Processor::process(&instruction.program_id, &account_infos, &instruction.data)
+        // At 1 for 10,000, 0.01 is worth 0.000001, less than the other bank's smallest unit.
+        let tiny = rate_account(&program_id, &from.bank_info.0, &to.bank_info.0, 1, 10_000);
+        assert_eq!(
+            Err(BankError::ConvertsToZero.into()),
+            convert(&mut from, &mut to, tiny, 1)
+        );
+        assert_eq!(
+            Err(BankError::InsufficientFunds.into()),
+            convert(&mut from, &mut to, rate, 51)
+        );
+        // The rate only goes one way.
+        let reverse = rate_account(&program_id, &to.bank_info.0, &from.bank_info.0, 2, 3);
+        assert_eq!(
+            Err(BankError::BankMismatch.into()),
+            convert(&mut from, &mut to, reverse, 10)
+        );
     }
 
     #[test]
-    fn test_initialize_bank() {
-        let mut test_suite = TestSuite::default(60);
+    fn test_burn() {
+        let mut test_suite = TestSuite::default(64);
+        test_suite.add_default_bank_accounts(1);
         test_suite.process_init_bank_instruction(8).unwrap();
+        test_suite.process_init_all_accounts().unwrap();
+        test_suite.process_mint_to(0, 100).unwrap();
+        test_suite.process_burn(0, 50).unwrap();
 
         assert_eq!(
             Ok(true),
@@ -624,22 +3605,118 @@ mod tests {
                 decimals: 8,
                 bank_owner: test_suite.bank_owner_info.0,
                 is_opened: true,
-                total_supply: 0,
+                total_supply: 50,
+                close_policy: ClosePolicy::Reject,
+                require_rent_exempt: false,
+                burn_policy: BurnPolicy::OwnerAndHolder,
+                lamports_per_token: 0,
+                transfer_count: 0,
+                cumulative_volume: 0,
+                holder_count: 1,
+                registered_accounts: 0,
+                dust_threshold: 0,
+                freeze_authority: COption::None,
+                is_paused: false,
+                interest_rate_bps: 0,
+                emergency_authority: COption::None,
             })
         );
 
         assert_eq!(
-            Err(ProgramError::AccountAlreadyInitialized),
-            test_suite.process_init_bank_instruction(8)
+            Ok(true),
+            test_suite.account_eq(
+                0,
+                &Account {
+                    amount: 50,
+                    is_initialized: true,
+                    is_opened: true,
+                    owner: test_suite.bank_accounts_owner_info[0].0,
+                    delegate: COption::None,
+                    delegated_amount: 0,
+                    bank: test_suite.bank_info.0,
+                    permit_nonce: 0,
+                    is_frozen: false,
+                    last_accrual: 0,
+                }
+            )
+        );
+    }
+
+    fn test_burn_holder_only() {
+        let mut test_suite = TestSuite::default(64);
+        test_suite.add_default_bank_accounts(1);
+        test_suite
+            .process_init_bank_with_burn_policy(8, BurnPolicy::HolderOnly)
+            .unwrap();
+        test_suite.process_init_all_accounts().unwrap();
+        test_suite.process_mint_to(0, 100).unwrap();
+        test_suite.process_burn_by_holder(0, 30).unwrap();
+
+        let bank = Bank::unpack(&test_suite.bank_info.1.data).unwrap();
+        assert_eq!(
+            (bank.burn_policy, bank.total_supply),
+            (BurnPolicy::HolderOnly, 70)
+        );
+        let account = Account::unpack(&test_suite.bank_accounts_info[0].1.data).unwrap();
+        assert_eq!(account.amount, 70);
+
+        // The bank owner may still sign along, as it must in other banks.
+        test_suite.process_burn(0, 30).unwrap();
+        let account = Account::unpack(&test_suite.bank_accounts_info[0].1.data).unwrap();
+        assert_eq!(account.amount, 40);
+        let mut unsigned = burn_by_holder(
+            &test_suite.program_id,
+            &test_suite.bank_info.0,
+            &test_suite.bank_accounts_info[0].0,
+            &test_suite.bank_accounts_owner_info[0].0,
+            30,
+        )
+        .unwrap();
+        unsigned.accounts[2].is_signer = false;
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(
+                unsigned,
+                vec![
+                    &mut test_suite.bank_info.1,
+                    &mut test_suite.bank_accounts_info[0].1,
+                    &mut test_suite.bank_accounts_owner_info[0].1,
+                ],
+            )
         );
     }
 
     #[test]
-    fn test_initialize_bank_account() {
-        let mut test_suite = TestSuite::default(60);
+    fn test_burn_owner_and_holder() {
+        let mut test_suite = TestSuite::default(64);
+        test_suite.add_default_bank_accounts(1);
+        test_suite.process_init_bank_instruction(8).unwrap();
+        test_suite.process_init_all_accounts().unwrap();
+        test_suite.process_mint_to(0, 100).unwrap();
+
+        // Without the bank owner, the list is a signer short.
+        assert_eq!(
+            Err(ProgramError::NotEnoughAccountKeys),
+            test_suite.process_burn_by_holder(0, 30)
+        );
+        assert_eq!(
+            Account::unpack(&test_suite.bank_accounts_info[0].1.data)
+                .unwrap()
+                .amount,
+            100
+        );
+    }
+
+    #[test]
+    fn test_close() {
+        let mut test_suite = TestSuite::default(64);
         test_suite.add_default_bank_accounts(1);
         test_suite.process_init_bank_instruction(8).unwrap();
         test_suite.process_init_bank_account_instruction(0).unwrap();
+        let (key, mut destination) = TestSuite::new_key_account(10);
+        test_suite
+            .process_close(0, (&key, &mut destination))
+            .unwrap();
 
         assert_eq!(
             Ok(true),
@@ -648,269 +3725,1177 @@ mod tests {
                 &Account {
                     amount: 0,
                     is_initialized: true,
-                    is_opened: true,
+                    is_opened: false,
                     owner: test_suite.bank_accounts_owner_info[0].0,
                     delegate: COption::None,
                     delegated_amount: 0,
                     bank: test_suite.bank_info.0,
+                    permit_nonce: 0,
+                    is_frozen: false,
+                    last_accrual: 0,
                 }
             )
         );
+        assert_eq!(test_suite.bank_accounts_info[0].1.lamports, 0);
+        assert_eq!(destination.lamports, 74);
 
         assert_eq!(
-            Err(ProgramError::AccountAlreadyInitialized),
-            test_suite.process_init_bank_account_instruction(0)
+            Err(BankError::AccountClosed.into()),
+            test_suite.process_mint_to(0, 50)
         );
     }
 
     #[test]
-    fn test_mint_to() {
-        let mut test_suite = TestSuite::default(60);
-        test_suite.add_default_bank_accounts(1);
-        test_suite.process_init_bank_instruction(8).unwrap();
-        test_suite.process_init_bank_account_instruction(0).unwrap();
-        test_suite.process_mint_to(0, 100).unwrap();
+    fn test_close_with_balance() {
+        let mut test_suite = TestSuite::builder()
+            .accounts(3)
+            .initialized(8)
+            .mint(0, 100)
+            .mint(1, 100)
+            .mint(2, 100)
+            .build()
+            .unwrap();
+        let (key, mut destination) = TestSuite::new_key_account(0);
+        let supply = |test_suite: &TestSuite| {
+            Bank::unpack(&test_suite.bank_info.1.data)
+                .unwrap()
+                .total_supply
+        };
+        let amount = |test_suite: &TestSuite, i: usize| {
+            Account::unpack(&test_suite.bank_accounts_info[i].1.data)
+                .unwrap()
+                .amount
+        };
 
+        // Banks start out refusing to close accounts that hold anything, approved tokens
+        // included.
         assert_eq!(
-            Ok(true),
-            test_suite.bank_eq(&Bank {
-                decimals: 8,
-                bank_owner: test_suite.bank_owner_info.0,
-                is_opened: true,
-                total_supply: 100,
-            })
+            Err(BankError::NonZeroBalance.into()),
+            test_suite.process_close(0, (&key, &mut destination))
+        );
+        let (delegate, mut delegate_account) = TestSuite::new_key_account(0);
+        test_suite
+            .process_approve(0, (&delegate, &mut delegate_account), 100)
+            .unwrap();
+        assert_eq!(
+            Err(BankError::NonZeroBalance.into()),
+            test_suite.process_close(0, (&key, &mut destination))
+        );
+        test_suite.process_revoke(0).unwrap();
+
+        // Only the bank owner sets the policy.
+        let mut instruction = set_close_policy(
+            &test_suite.program_id,
+            &test_suite.bank_info.0,
+            &test_suite.bank_accounts_owner_info[0].0,
+            ClosePolicy::Burn,
+        )
+        .unwrap();
+        assert_eq!(
+            Err(ProgramError::IllegalOwner),
+            do_process_instruction(
+                instruction.clone(),
+                vec![
+                    &mut test_suite.bank_info.1,
+                    &mut test_suite.bank_accounts_owner_info[0].1,
+                ],
+            )
+        );
+        instruction.accounts[1].is_signer = false;
+        instruction.accounts[1].pubkey = test_suite.bank_owner_info.0;
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(
+                instruction,
+                vec![
+                    &mut test_suite.bank_info.1,
+                    &mut test_suite.bank_owner_info.1
+                ],
+            )
+        );
+
+        // Sweeping needs an open account of the same bank to take the tokens.
+        test_suite
+            .process_set_close_policy(ClosePolicy::Sweep)
+            .unwrap();
+        assert_eq!(
+            Err(ProgramError::NotEnoughAccountKeys),
+            test_suite.process_close_with_balance(0, (&key, &mut destination), None)
+        );
+        test_suite
+            .process_close_with_balance(0, (&key, &mut destination), Some(1))
+            .unwrap();
+        assert_eq!((amount(&test_suite, 0), amount(&test_suite, 1)), (0, 200));
+        assert_eq!(supply(&test_suite), 300);
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            test_suite.process_close_with_balance(1, (&key, &mut destination), Some(0))
+        );
+
+        // Burning takes the tokens out of the supply.
+        test_suite
+            .process_set_close_policy(ClosePolicy::Burn)
+            .unwrap();
+        test_suite
+            .process_close_with_balance(2, (&key, &mut destination), None)
+            .unwrap();
+        assert_eq!(amount(&test_suite, 2), 0);
+        assert_eq!(supply(&test_suite), 200);
+        assert_eq!(
+            Bank::unpack(&test_suite.bank_info.1.data)
+                .unwrap()
+                .close_policy,
+            ClosePolicy::Burn
+        );
+    }
+
+    #[test]
+    fn test_dust() {
+        let mut test_suite = TestSuite::builder()
+            .accounts(4)
+            .initialized(8)
+            .mint(0, 100)
+            .mint(1, 5)
+            .build()
+            .unwrap();
+        let amount = |test_suite: &TestSuite, i: usize| {
+            Account::unpack(&test_suite.bank_accounts_info[i].1.data)
+                .unwrap()
+                .amount
+        };
+
+        // Banks start out with no dust.
+        assert_eq!(
+            Err(BankError::NotDust.into()),
+            test_suite.process_close_dust(1, None)
+        );
+        test_suite.process_transfer_sweeping_dust(0, 2, 95).unwrap();
+        assert_eq!((amount(&test_suite, 0), amount(&test_suite, 2)), (5, 95));
+
+        // Once 5 is dust, a sweeping transfer that would leave it takes it along.
+        test_suite.process_set_dust_threshold(10).unwrap();
+        test_suite.process_transfer_sweeping_dust(0, 2, 1).unwrap();
+        assert_eq!((amount(&test_suite, 0), amount(&test_suite, 2)), (0, 100));
+
+        // Anyone can close an empty account, for its owner's benefit; dust goes as the
+        // close policy says, and more than dust stays put.
+        let owner_lamports = test_suite.bank_accounts_owner_info[0].1.lamports;
+        let account_lamports = test_suite.bank_accounts_info[0].1.lamports;
+        test_suite.process_close_dust(0, None).unwrap();
+        assert_eq!(test_suite.bank_accounts_info[0].1.lamports, 0);
+        assert_eq!(
+            test_suite.bank_accounts_owner_info[0].1.lamports,
+            owner_lamports + account_lamports
+        );
+        assert_eq!(
+            Err(BankError::NonZeroBalance.into()),
+            test_suite.process_close_dust(1, None)
+        );
+        assert_eq!(
+            Err(BankError::NotDust.into()),
+            test_suite.process_close_dust(2, None)
+        );
+
+        // Swept dust stays with its owner.
+        test_suite
+            .process_set_close_policy(ClosePolicy::Sweep)
+            .unwrap();
+        assert_eq!(
+            Err(ProgramError::IllegalOwner),
+            test_suite.process_close_dust(1, Some(2))
+        );
+        let mut sweep_account = Account::unpack(&test_suite.bank_accounts_info[3].1.data).unwrap();
+        sweep_account.owner = test_suite.bank_accounts_owner_info[1].0;
+        Account::pack(sweep_account, &mut test_suite.bank_accounts_info[3].1.data).unwrap();
+        test_suite.process_close_dust(1, Some(3)).unwrap();
+        assert_eq!((amount(&test_suite, 1), amount(&test_suite, 3)), (0, 5));
+
+        let bank = Bank::unpack(&test_suite.bank_info.1.data).unwrap();
+        assert_eq!((bank.total_supply, bank.holder_count), (105, 2));
+    }
+
+    #[test]
+    fn test_transfer_to_self() {
+        let mut test_suite = TestSuite::builder()
+            .accounts(1)
+            .initialized(8)
+            .mint(0, 100)
+            .build()
+            .unwrap();
+        assert_eq!(
+            Err(ProgramError::InvalidArgument),
+            test_suite.process_transfer(0, 0, 40)
+        );
+        assert_eq!(
+            Account::unpack(&test_suite.bank_accounts_info[0].1.data)
+                .unwrap()
+                .amount,
+            100
+        );
+    }
+
+    #[test]
+    fn test_u64_max_bounds() {
+        let mut test_suite = TestSuite::builder()
+            .accounts(2)
+            .initialized(8)
+            .mint(0, u64::MAX)
+            .build()
+            .unwrap();
+        let amount = |test_suite: &TestSuite, i: usize| {
+            Account::unpack(&test_suite.bank_accounts_info[i].1.data)
+                .unwrap()
+                .amount
+        };
+        // The supply is already at the limit, whichever account the mint goes to.
+        assert_eq!(
+            Err(BankError::Overflow.into()),
+            test_suite.process_mint_to(1, 1)
+        );
+        assert_eq!(amount(&test_suite, 1), 0);
+
+        test_suite.process_transfer(0, 1, u64::MAX).unwrap();
+        assert_eq!(amount(&test_suite, 0), 0);
+        assert_eq!(amount(&test_suite, 1), u64::MAX);
+        assert_eq!(
+            Err(BankError::InsufficientFunds.into()),
+            test_suite.process_transfer(0, 1, 1)
+        );
+
+        // An allowance isn't bounded by the balance, only spending it is.
+        let (key, mut account) = TestSuite::new_key_account(64);
+        test_suite
+            .process_approve(0, (&key, &mut account), 1)
+            .unwrap();
+        assert_eq!(
+            Err(BankError::InsufficientFunds.into()),
+            test_suite.process_transfer_delegate(0, (&key, &mut account), 1, 1)
+        );
+        test_suite
+            .process_approve(1, (&key, &mut account), u64::MAX)
+            .unwrap();
+        assert_eq!(
+            Account::unpack(&test_suite.bank_accounts_info[1].1.data)
+                .unwrap()
+                .delegated_amount,
+            u64::MAX
+        );
+        assert_eq!(
+            Err(BankError::Overflow.into()),
+            test_suite.process_approve(1, (&key, &mut account), 1)
+        );
+    }
+
+    #[test]
+    fn test_credit_overflow() {
+        // Supply and balances can only reach `u64::MAX` together, so the credit side
+        // of a transfer is checked on hand-packed balances.
+        let mut test_suite = TestSuite::builder()
+            .accounts(2)
+            .initialized(8)
+            .mint(0, 10)
+            .build()
+            .unwrap();
+        let mut to = Account::unpack(&test_suite.bank_accounts_info[1].1.data).unwrap();
+        to.amount = u64::MAX - 5;
+        Account::pack(to, &mut test_suite.bank_accounts_info[1].1.data).unwrap();
+
+        assert_eq!(
+            Err(BankError::Overflow.into()),
+            test_suite.process_transfer(0, 1, 6)
+        );
+        test_suite.process_transfer(0, 1, 5).unwrap();
+        assert_eq!(
+            Account::unpack(&test_suite.bank_accounts_info[1].1.data)
+                .unwrap()
+                .amount,
+            u64::MAX
         );
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    enum Op {
+        Mint(usize, u64),
+        Transfer(usize, usize, u64),
+        UncountedTransfer(usize, usize, u64),
+        SweepingTransfer(usize, usize, u64),
+        Approve(usize, u64),
+        DelegateTransfer(usize, usize, u64),
+        Revoke(usize),
+        Burn(usize, u64),
+        Close(usize),
+        CloseDust(usize),
+        SetClosePolicy(ClosePolicy),
+        SetDustThreshold(u64),
+    }
+
+    const OP_ACCOUNTS: usize = 3;
+
+    fn arb_op() -> impl Strategy<Value = Op> {
+        let i = 0..OP_ACCOUNTS;
+        let amount = 0..1_000u64;
+        prop_oneof![
+            (i.clone(), amount.clone()).prop_map(|(i, a)| Op::Mint(i, a)),
+            (i.clone(), i.clone(), amount.clone()).prop_map(|(f, t, a)| Op::Transfer(f, t, a)),
+            (i.clone(), i.clone(), amount.clone())
+                .prop_map(|(f, t, a)| Op::UncountedTransfer(f, t, a)),
+            (i.clone(), i.clone(), amount.clone())
+                .prop_map(|(f, t, a)| Op::SweepingTransfer(f, t, a)),
+            (i.clone(), amount.clone()).prop_map(|(i, a)| Op::Approve(i, a)),
+            (i.clone(), i.clone(), amount.clone())
+                .prop_map(|(f, t, a)| Op::DelegateTransfer(f, t, a)),
+            i.clone().prop_map(Op::Revoke),
+            (i.clone(), amount).prop_map(|(i, a)| Op::Burn(i, a)),
+            i.clone().prop_map(Op::Close),
+            i.prop_map(Op::CloseDust),
+            prop_oneof![
+                Just(ClosePolicy::Reject),
+                Just(ClosePolicy::Sweep),
+                Just(ClosePolicy::Burn),
+            ]
+            .prop_map(Op::SetClosePolicy),
+            (0..200u64).prop_map(Op::SetDustThreshold),
+        ]
+    }
+
+    fn supply_drift(test_suite: &TestSuite) -> Option<(u64, u64)> {
+        let bank = Bank::unpack(&test_suite.bank_info.1.data).unwrap();
+        let held = test_suite
+            .bank_accounts_info
+            .iter()
+            .map(|(_, account)| Account::unpack(&account.data).unwrap().amount)
+            .sum::<u64>();
+        if bank.total_supply == held {
+            None
+        } else {
+            Some((bank.total_supply, held))
+        }
+    }
+
+    fn holder_drift(test_suite: &TestSuite) -> Option<(u64, u64)> {
+        let bank = Bank::unpack(&test_suite.bank_info.1.data).unwrap();
+        let holders = test_suite
+            .bank_accounts_info
+            .iter()
+            .filter(|(_, account)| Account::unpack(&account.data).unwrap().amount > 0)
+            .count() as u64;
+        if bank.holder_count == holders {
+            None
+        } else {
+            Some((bank.holder_count, holders))
+        }
+    }
+
+    /// A suite of `OP_ACCOUNTS` opened accounts that random `Op`s are applied to, with
+    /// one shared delegate and close destination. Closing sweeps into the next account.
+    struct OpRunner {
+        suite: TestSuite,
+        delegate: (Pubkey, SolanaAccount),
+        destination: (Pubkey, SolanaAccount),
+    }
+
+    impl OpRunner {
+        fn new() -> OpRunner {
+            OpRunner {
+                suite: TestSuite::builder()
+                    .accounts(OP_ACCOUNTS as u64)
+                    .initialized(8)
+                    .build()
+                    .unwrap(),
+                delegate: TestSuite::new_key_account(0),
+                destination: TestSuite::new_key_account(0),
+            }
+        }
+
+        fn apply(&mut self, op: Op) -> ProgramResult {
+            let suite = &mut self.suite;
+            let delegate = (&self.delegate.0, &mut self.delegate.1);
+            match op {
+                Op::Mint(i, amount) => suite.process_mint_to(i, amount),
+                Op::Transfer(from, to, amount) => suite.process_transfer(from, to, amount),
+                Op::UncountedTransfer(from, to, amount) => {
+                    suite.process_transfer_uncounted(from, to, amount)
+                }
+                Op::SweepingTransfer(from, to, amount) => {
+                    suite.process_transfer_sweeping_dust(from, to, amount)
+                }
+                Op::Approve(i, amount) => suite.process_approve(i, delegate, amount),
+                Op::DelegateTransfer(from, to, amount) => {
+                    suite.process_transfer_delegate(from, delegate, to, amount)
+                }
+                Op::Revoke(i) => suite.process_revoke(i),
+                Op::Burn(i, amount) => suite.process_burn(i, amount),
+                Op::Close(i) => suite.process_close_with_balance(
+                    i,
+                    (&self.destination.0, &mut self.destination.1),
+                    Some((i + 1) % OP_ACCOUNTS),
+                ),
+                Op::CloseDust(i) => suite.process_close_dust(i, Some((i + 1) % OP_ACCOUNTS)),
+                Op::SetClosePolicy(policy) => suite.process_set_close_policy(policy),
+                Op::SetDustThreshold(threshold) => suite.process_set_dust_threshold(threshold),
+            }
+        }
+
+        fn accounts(&self) -> Vec<Account> {
+            self.suite
+                .bank_accounts_info
+                .iter()
+                .map(|(_, account)| Account::unpack(&account.data).unwrap())
+                .collect()
+        }
+    }
+
+    proptest! {
+        // Whatever succeeds or fails, no sequence of instructions may create or destroy
+        // tokens outside of mint and burn.
+        #[test]
+        fn test_total_supply_invariant(ops in proptest::collection::vec(arb_op(), 1..40)) {
+            let mut runner = OpRunner::new();
+            for op in ops {
+                let _ = runner.apply(op);
+                prop_assert_eq!(supply_drift(&runner.suite), None, "after {:?}", op);
+            }
+        }
+
+        // The bank's holder count follows every balance that crosses zero, whichever
+        // instruction moves it and whether or not it passes the bank writable.
+        #[test]
+        fn test_holder_count_invariant(ops in proptest::collection::vec(arb_op(), 1..40)) {
+            let mut runner = OpRunner::new();
+            for op in ops {
+                let _ = runner.apply(op);
+                prop_assert_eq!(holder_drift(&runner.suite), None, "after {:?}", op);
+            }
+        }
+
+        // A delegate can only ever spend what was approved to it since the last revoke,
+        // and neither approving nor revoking moves any tokens.
+        #[test]
+        fn test_delegated_amount_bookkeeping(
+            ops in proptest::collection::vec(arb_op(), 1..40),
+        ) {
+            let mut runner = OpRunner::new();
+            // Approved minus spent by the delegate, per account, since its last revoke.
+            let mut allowance = [0u64; OP_ACCOUNTS];
+            for op in ops {
+                let before = runner.accounts();
+                let result = runner.apply(op);
+                let after = runner.accounts();
+                match (op, result) {
+                    (Op::Approve(i, amount), Ok(())) => {
+                        prop_assert_eq!(after[i].amount, before[i].amount);
+                        allowance[i] += amount;
+                    }
+                    (Op::DelegateTransfer(from, _, amount), Ok(())) => {
+                        allowance[from] -= amount;
+                        if allowance[from] == 0 {
+                            prop_assert!(after[from].delegate.is_none());
+                        }
+                    }
+                    (Op::Revoke(i), Ok(())) => {
+                        prop_assert_eq!(after[i].amount, before[i].amount);
+                        prop_assert!(after[i].delegate.is_none());
+                        allowance[i] = 0;
+                    }
+                    (Op::Close(i), Ok(())) | (Op::CloseDust(i), Ok(())) => allowance[i] = 0,
+                    _ => {}
+                }
+                for (i, account) in after.iter().enumerate() {
+                    prop_assert_eq!(
+                        account.delegated_amount,
+                        allowance[i],
+                        "account {} after {:?}",
+                        i,
+                        op
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cross_bank_rejected() {
+        use crate::instruction::{approve, burn, close_account, revoke, transfer};
+        let program_id = Pubkey::new_unique();
+        let mut bank_a = TestSuite::builder()
+            .program_id(program_id)
+            .accounts(2)
+            .initialized(2)
+            .mint(0, 100)
+            .build()
+            .unwrap();
+        let mut bank_b = TestSuite::builder()
+            .program_id(program_id)
+            .accounts(1)
+            .initialized(2)
+            .build()
+            .unwrap();
+        let mismatch = Err(BankError::BankMismatch.into());
 
+        let instruction = transfer(
+            &program_id,
+            &bank_a.bank_info.0,
+            &bank_a.bank_accounts_info[0].0,
+            &bank_b.bank_accounts_info[0].0,
+            &bank_a.bank_accounts_owner_info[0].0,
+            40,
+        )
+        .unwrap();
         assert_eq!(
-            Ok(true),
-            test_suite.account_eq(
-                0,
-                &Account {
-                    amount: 100,
-                    is_initialized: true,
-                    is_opened: true,
-                    owner: test_suite.bank_accounts_owner_info[0].0,
-                    delegate: COption::None,
-                    delegated_amount: 0,
-                    bank: test_suite.bank_info.0,
-                }
+            mismatch,
+            do_process_instruction(
+                instruction,
+                vec![
+                    &mut bank_a.bank_accounts_info[0].1,
+                    &mut bank_b.bank_accounts_info[0].1,
+                    &mut bank_a.bank_accounts_owner_info[0].1,
+                    &mut bank_a.bank_info.1,
+                ],
             )
         );
 
-        let failed_instruction = mint_to(
-            &test_suite.program_id,
-            &test_suite.bank_info.0,
-            &test_suite.bank_accounts_info[0].0,
-            &Pubkey::new_unique(),
-            100,
+        // Both accounts are in bank A, but the transfer names bank B.
+        let instruction = transfer(
+            &program_id,
+            &bank_b.bank_info.0,
+            &bank_a.bank_accounts_info[0].0,
+            &bank_a.bank_accounts_info[1].0,
+            &bank_a.bank_accounts_owner_info[0].0,
+            40,
         )
         .unwrap();
-
+        let (from, to) = bank_a.bank_accounts_info.split_at_mut(1);
         assert_eq!(
-            Err(ProgramError::IllegalOwner),
+            mismatch,
             do_process_instruction(
-                failed_instruction,
+                instruction,
                 vec![
-                    &mut test_suite.bank_info.1,
-                    &mut test_suite.bank_accounts_info[0].1,
-                    &mut test_suite.bank_owner_info.1
-                ]
+                    &mut from[0].1,
+                    &mut to[0].1,
+                    &mut bank_a.bank_accounts_owner_info[0].1,
+                    &mut bank_b.bank_info.1,
+                ],
             )
         );
-    }
-
-    #[test]
-    fn test_transfer() {
-        let mut test_suite = TestSuite::default(60);
-        test_suite.add_default_bank_accounts(2);
-        test_suite.process_init_bank_instruction(8).unwrap();
-        test_suite.process_init_all_accounts().unwrap();
-        test_suite.process_mint_to(0, 100).unwrap();
-        test_suite.process_transfer(0, 1, 60).unwrap();
 
+        let instruction = mint_to(
+            &program_id,
+            &bank_a.bank_info.0,
+            &bank_b.bank_accounts_info[0].0,
+            &bank_a.bank_owner_info.0,
+            40,
+        )
+        .unwrap();
         assert_eq!(
-            Ok(true),
-            test_suite.account_eq(
-                0,
-                &Account {
-                    amount: 40,
-                    is_initialized: true,
-                    is_opened: true,
-                    owner: test_suite.bank_accounts_owner_info[0].0,
-                    delegate: COption::None,
-                    delegated_amount: 0,
-                    bank: test_suite.bank_info.0,
-                },
+            mismatch,
+            do_process_instruction(
+                instruction,
+                vec![
+                    &mut bank_a.bank_info.1,
+                    &mut bank_b.bank_accounts_info[0].1,
+                    &mut bank_a.bank_owner_info.1,
+                ],
             )
         );
 
+        let instruction = burn(
+            &program_id,
+            &bank_b.bank_info.0,
+            &bank_a.bank_accounts_info[0].0,
+            &bank_b.bank_owner_info.0,
+            &bank_a.bank_accounts_owner_info[0].0,
+            40,
+        )
+        .unwrap();
         assert_eq!(
-            Ok(true),
-            test_suite.account_eq(
-                1,
-                &Account {
-                    amount: 60,
-                    is_initialized: true,
-                    is_opened: true,
-                    owner: test_suite.bank_accounts_owner_info[1].0,
-                    delegate: COption::None,
-                    delegated_amount: 0,
-                    bank: test_suite.bank_info.0,
-                },
+            mismatch,
+            do_process_instruction(
+                instruction,
+                vec![
+                    &mut bank_b.bank_info.1,
+                    &mut bank_a.bank_accounts_info[0].1,
+                    &mut bank_b.bank_owner_info.1,
+                    &mut bank_a.bank_accounts_owner_info[0].1,
+                ],
             )
         );
 
+        // Approve, Revoke and CloseAccount only touch the account, but still have to name
+        // its bank.
+        let (delegate_key, mut delegate) = TestSuite::new_key_account(0);
+        let account = &bank_a.bank_accounts_info[0].0;
+        let owner = &bank_a.bank_accounts_owner_info[0].0;
+        let bank = &bank_b.bank_info.0;
+        let instructions = [
+            approve(&program_id, bank, account, &delegate_key, owner, 40).unwrap(),
+            revoke(&program_id, bank, account, owner).unwrap(),
+            close_account(&program_id, bank, account, &delegate_key, owner).unwrap(),
+        ];
+        for instruction in instructions.iter() {
+            let mut accounts = vec![
+                &mut bank_a.bank_accounts_info[0].1,
+                &mut bank_a.bank_accounts_owner_info[0].1,
+                &mut bank_b.bank_info.1,
+            ];
+            if instruction.accounts.len() == 4 {
+                accounts.insert(1, &mut delegate);
+            }
+            assert_eq!(
+                mismatch,
+                do_process_instruction(instruction.clone(), accounts)
+            );
+        }
+
+        for suite in [&bank_a, &bank_b].iter() {
+            let bank = Bank::unpack(&suite.bank_info.1.data).unwrap();
+            let account = Account::unpack(&suite.bank_accounts_info[0].1.data).unwrap();
+            assert_eq!(bank.total_supply, account.amount);
+        }
         assert_eq!(
-            Err(ProgramError::InvalidAccountData),
-            test_suite.process_transfer(0, 1, 60)
+            Account::unpack(&bank_a.bank_accounts_info[0].1.data)
+                .unwrap()
+                .amount,
+            100
         );
     }
 
-    #[test]
-    fn test_approve() {
-        let mut test_suite = TestSuite::default(64);
-        test_suite.add_default_bank_accounts(1);
-        let (key, mut account) = TestSuite::new_key_account(64);
-        test_suite.process_init_bank_instruction(8).unwrap();
-        test_suite.process_init_all_accounts().unwrap();
-        test_suite.process_mint_to(0, 100).unwrap();
-        test_suite
-            .process_approve(0, (&key, &mut account), 50)
-            .unwrap();
+    /// A rate account already set between `from_bank` and `to_bank`.
+    fn rate_account(
+        program_id: &Pubkey,
+        from_bank: &Pubkey,
+        to_bank: &Pubkey,
+        numerator: u64,
+        denominator: u64,
+    ) -> SolanaAccount {
+        let mut account = SolanaAccount::new(0, ExchangeRate::LEN, program_id);
+        ExchangeRate {
+            is_initialized: true,
+            from_bank: *from_bank,
+            to_bank: *to_bank,
+            numerator,
+            denominator,
+        }
+        .pack_into_slice(&mut account.data);
+        account
+    }
 
-        assert_eq!(
-            Ok(true),
-            test_suite.account_eq(
-                0,
-                &Account {
-                    amount: 50,
-                    is_initialized: true,
-                    is_opened: true,
-                    owner: test_suite.bank_accounts_owner_info[0].0,
-                    delegate: COption::Some(key),
-                    delegated_amount: 50,
-                    bank: test_suite.bank_info.0,
-                }
-            )
-        );
+    /// `account`'s recovery state, set by `owner` and with a recovery started.
+    fn recovery_account(program_id: &Pubkey, account: &Pubkey, owner: &Pubkey) -> SolanaAccount {
+        let mut recovery = SolanaAccount::new(0, Recovery::LEN, program_id);
+        Recovery {
+            is_initialized: true,
+            account: *account,
+            owner: *owner,
+            threshold: 1,
+            guardian_count: 1,
+            guardians: [Pubkey::new_unique(); MAX_GUARDIANS],
+            delay: 0,
+            pending_owner: COption::Some(Pubkey::new_unique()),
+            activation_slot: 0,
+        }
+        .pack_into_slice(&mut recovery.data);
+        recovery
     }
 
-    #[test]
-    fn test_approve_transfer() {
-        let mut test_suite = TestSuite::default(64);
-        test_suite.add_default_bank_accounts(2);
-        let (key, mut account) = TestSuite::new_key_account(64);
-        test_suite.process_init_bank_instruction(8).unwrap();
-        test_suite.process_init_all_accounts().unwrap();
-        test_suite.process_mint_to(0, 100).unwrap();
-        test_suite
-            .process_approve(0, (&key, &mut account), 50)
-            .unwrap();
-        test_suite
-            .process_transfer_delegate(0, (&key, &mut account), 1, 30)
-            .unwrap();
+    fn run(instruction: &Instruction, mut accounts: Vec<SolanaAccount>) -> ProgramResult {
+        do_process_instruction(instruction.clone(), accounts.iter_mut().collect())
+    }
 
-        assert_eq!(
-            Ok(true),
-            test_suite.account_eq(
-                0,
-                &Account {
-                    amount: 50,
-                    is_initialized: true,
-                    is_opened: true,
-                    owner: test_suite.bank_accounts_owner_info[0].0,
-                    delegate: COption::Some(key),
-                    delegated_amount: 20,
-                    bank: test_suite.bank_info.0,
-                },
-            )
-        );
+    /// An instruction that succeeds against `accounts`, given in the order of its metas.
+    struct Case {
+        name: &'static str,
+        instruction: Instruction,
+        accounts: Vec<SolanaAccount>,
+        /// Error expected when account `i` is owned by another program, `None` where
+        /// the program doesn't care who owns it.
+        owner_errors: Vec<Option<ProgramError>>,
+    }
 
-        assert_eq!(
-            Ok(true),
-            test_suite.account_eq(
-                1,
-                &Account {
-                    amount: 30,
-                    is_initialized: true,
-                    is_opened: true,
-                    owner: test_suite.bank_accounts_owner_info[1].0,
-                    delegate: COption::None,
-                    delegated_amount: 0,
-                    bank: test_suite.bank_info.0,
-                },
+    fn instruction_cases() -> Vec<Case> {
+        use crate::instruction::{
+            accrue_interest, approve, assign_to_program, burn, burn_checked, cancel_owner_change,
+            cancel_recovery, close_account, convert, initialize_account, mint_to_checked,
+            pause_bank, revoke, set_authority, set_close_policy, set_exchange_rate,
+            set_owner_change_delay, set_require_rent_exempt, transfer, transfer_batch,
+            transfer_checked, transfer_route, AuthorityType,
+        };
+        let illegal = Some(ProgramError::IllegalOwner);
+        let mut cases = Vec::new();
+
+        let mut fresh = TestSuite::builder().accounts(1).build().unwrap();
+        cases.push(Case {
+            name: "InitializeBank",
+            instruction: fresh.init_bank_instruction(2).unwrap(),
+            accounts: vec![fresh.bank_info.1.clone(), fresh.bank_owner_info.1.clone()],
+            owner_errors: vec![illegal.clone(), None],
+        });
+
+        fresh.process_init_bank_instruction(2).unwrap();
+        cases.push(Case {
+            name: "InitializeAccount",
+            instruction: initialize_account(
+                &fresh.program_id,
+                &fresh.bank_info.0,
+                &fresh.bank_accounts_info[0].0,
+                &fresh.bank_accounts_owner_info[0].0,
             )
+            .unwrap(),
+            accounts: vec![
+                fresh.bank_info.1.clone(),
+                fresh.bank_accounts_info[0].1.clone(),
+                fresh.bank_accounts_owner_info[0].1.clone(),
+            ],
+            owner_errors: vec![illegal.clone(), illegal.clone(), None],
+        });
+
+        // Account 0 holds 100 tokens, account 1 is empty.
+        let suite = TestSuite::builder()
+            .accounts(2)
+            .initialized(2)
+            .mint(0, 100)
+            .build()
+            .unwrap();
+        let program_id = &suite.program_id;
+        let bank = &suite.bank_info;
+        let bank_owner = &suite.bank_owner_info;
+        let (account, owner) = (
+            &suite.bank_accounts_info[0],
+            &suite.bank_accounts_owner_info[0],
+        );
+        let (empty, empty_owner) = (
+            &suite.bank_accounts_info[1],
+            &suite.bank_accounts_owner_info[1],
         );
+        let (other_key, other) = TestSuite::new_key_account(0);
 
-        assert_eq!(
-            Err(ProgramError::InvalidAccountData),
-            test_suite.process_transfer_delegate(0, (&key, &mut account), 1, 30)
+        cases.push(Case {
+            name: "Transfer",
+            instruction: transfer(program_id, &bank.0, &account.0, &empty.0, &owner.0, 40).unwrap(),
+            accounts: vec![
+                account.1.clone(),
+                empty.1.clone(),
+                owner.1.clone(),
+                bank.1.clone(),
+            ],
+            owner_errors: vec![illegal.clone(), illegal.clone(), None, illegal.clone()],
+        });
+        cases.push(Case {
+            name: "TransferRoute",
+            instruction: transfer_route(program_id, &bank.0, &[(account.0, owner.0)], &empty.0, 40)
+                .unwrap(),
+            accounts: vec![
+                bank.1.clone(),
+                account.1.clone(),
+                owner.1.clone(),
+                empty.1.clone(),
+                suite.feature_gate_info.1.clone(),
+            ],
+            // A gate the program doesn't own is one the bank hasn't set.
+            owner_errors: vec![
+                illegal.clone(),
+                illegal.clone(),
+                None,
+                illegal.clone(),
+                None,
+            ],
+        });
+        cases.push(Case {
+            name: "TransferBatch",
+            instruction: transfer_batch(
+                program_id,
+                &bank.0,
+                &account.0,
+                &owner.0,
+                &[(&empty.0, 40)],
+            )
+            .unwrap(),
+            accounts: vec![
+                account.1.clone(),
+                owner.1.clone(),
+                bank.1.clone(),
+                empty.1.clone(),
+            ],
+            owner_errors: vec![illegal.clone(), None, illegal.clone(), illegal.clone()],
+        });
+        cases.push(Case {
+            name: "Approve",
+            instruction: approve(program_id, &bank.0, &account.0, &other_key, &owner.0, 40)
+                .unwrap(),
+            accounts: vec![
+                account.1.clone(),
+                other.clone(),
+                owner.1.clone(),
+                bank.1.clone(),
+            ],
+            owner_errors: vec![illegal.clone(), None, None, illegal.clone()],
+        });
+        cases.push(Case {
+            name: "Revoke",
+            instruction: revoke(program_id, &bank.0, &account.0, &owner.0).unwrap(),
+            accounts: vec![account.1.clone(), owner.1.clone(), bank.1.clone()],
+            owner_errors: vec![illegal.clone(), None, illegal.clone()],
+        });
+        cases.push(Case {
+            name: "SetAuthority",
+            instruction: set_authority(
+                program_id,
+                &account.0,
+                AuthorityType::AccountOwner,
+                &owner.0,
+                Some(&Pubkey::new_unique()),
+            )
+            .unwrap(),
+            accounts: vec![account.1.clone(), owner.1.clone()],
+            owner_errors: vec![illegal.clone(), None],
+        });
+        let (program, program_account) = TestSuite::new_key_account(0);
+        let (_, bump) = Pubkey::find_program_address(&[b"vault"], &program);
+        cases.push(Case {
+            name: "AssignToProgram",
+            instruction: assign_to_program(
+                program_id,
+                &bank.0,
+                &account.0,
+                &owner.0,
+                &program,
+                &[b"vault", &[bump]],
+            )
+            .unwrap(),
+            accounts: vec![
+                account.1.clone(),
+                owner.1.clone(),
+                bank.1.clone(),
+                program_account,
+            ],
+            owner_errors: vec![illegal.clone(), None, illegal.clone(), None],
+        });
+        cases.push(Case {
+            name: "MintTo",
+            instruction: mint_to(program_id, &bank.0, &account.0, &bank_owner.0, 40).unwrap(),
+            accounts: vec![bank.1.clone(), account.1.clone(), bank_owner.1.clone()],
+            owner_errors: vec![illegal.clone(), illegal.clone(), None],
+        });
+        cases.push(Case {
+            name: "Burn",
+            instruction: burn(program_id, &bank.0, &account.0, &bank_owner.0, &owner.0, 40)
+                .unwrap(),
+            accounts: vec![
+                bank.1.clone(),
+                account.1.clone(),
+                bank_owner.1.clone(),
+                owner.1.clone(),
+            ],
+            owner_errors: vec![illegal.clone(), illegal.clone(), None, None],
+        });
+        cases.push(Case {
+            name: "TransferChecked",
+            instruction: transfer_checked(
+                program_id,
+                &bank.0,
+                &account.0,
+                &empty.0,
+                &owner.0,
+                &[],
+                40,
+                2,
+            )
+            .unwrap(),
+            accounts: vec![
+                account.1.clone(),
+                empty.1.clone(),
+                owner.1.clone(),
+                bank.1.clone(),
+            ],
+            owner_errors: vec![illegal.clone(), illegal.clone(), None, illegal.clone()],
+        });
+        cases.push(Case {
+            name: "MintToChecked",
+            instruction: mint_to_checked(
+                program_id,
+                &bank.0,
+                &account.0,
+                &bank_owner.0,
+                &[],
+                40,
+                2,
+            )
+            .unwrap(),
+            accounts: vec![bank.1.clone(), account.1.clone(), bank_owner.1.clone()],
+            owner_errors: vec![illegal.clone(), illegal.clone(), None],
+        });
+        cases.push(Case {
+            name: "BurnChecked",
+            instruction: burn_checked(
+                program_id,
+                &bank.0,
+                &account.0,
+                &bank_owner.0,
+                &owner.0,
+                40,
+                2,
+            )
+            .unwrap(),
+            accounts: vec![
+                bank.1.clone(),
+                account.1.clone(),
+                bank_owner.1.clone(),
+                owner.1.clone(),
+            ],
+            owner_errors: vec![illegal.clone(), illegal.clone(), None, None],
+        });
+        cases.push(Case {
+            name: "SetClosePolicy",
+            instruction: set_close_policy(program_id, &bank.0, &bank_owner.0, ClosePolicy::Sweep)
+                .unwrap(),
+            accounts: vec![bank.1.clone(), bank_owner.1.clone()],
+            owner_errors: vec![illegal.clone(), None],
+        });
+        cases.push(Case {
+            name: "PauseBank",
+            instruction: pause_bank(program_id, &bank.0, &bank_owner.0).unwrap(),
+            accounts: vec![bank.1.clone(), bank_owner.1.clone()],
+            owner_errors: vec![illegal.clone(), None],
+        });
+        cases.push(Case {
+            name: "AccrueInterest",
+            instruction: accrue_interest(program_id, &bank.0, &account.0).unwrap(),
+            accounts: vec![account.1.clone(), bank.1.clone()],
+            owner_errors: vec![illegal.clone(), illegal.clone()],
+        });
+        cases.push(Case {
+            name: "SetAuthority of a bank",
+            instruction: set_authority(
+                program_id,
+                &bank.0,
+                AuthorityType::BankOwner,
+                &bank_owner.0,
+                Some(&Pubkey::new_unique()),
+            )
+            .unwrap(),
+            accounts: vec![bank.1.clone(), bank_owner.1.clone()],
+            owner_errors: vec![illegal.clone(), None],
+        });
+        cases.push(Case {
+            name: "SetOwnerChangeDelay",
+            instruction: set_owner_change_delay(program_id, &bank.0, &bank_owner.0, 10).unwrap(),
+            accounts: vec![bank.1.clone(), bank_owner.1.clone()],
+            owner_errors: vec![illegal.clone(), None],
+        });
+        let mut pending = bank.1.clone();
+        let mut state = Bank::unpack(&pending.data).unwrap();
+        state.pending_bank_owner = COption::Some(Pubkey::new_unique());
+        Bank::pack(state, &mut pending.data).unwrap();
+        cases.push(Case {
+            name: "CancelOwnerChange",
+            instruction: cancel_owner_change(program_id, &bank.0, &bank_owner.0).unwrap(),
+            accounts: vec![pending, bank_owner.1.clone()],
+            owner_errors: vec![illegal.clone(), None],
+        });
+        cases.push(Case {
+            name: "SetRequireRentExempt",
+            instruction: set_require_rent_exempt(program_id, &bank.0, &bank_owner.0, false)
+                .unwrap(),
+            accounts: vec![bank.1.clone(), bank_owner.1.clone()],
+            owner_errors: vec![illegal.clone(), None],
+        });
+        // A second bank with 3 decimals and an empty account, and a rate of 1 for 1 into it.
+        // The rate account is already set, so swapping the banks doesn't set a new one.
+        let to_suite = TestSuite::builder()
+            .program_id(*program_id)
+            .accounts(1)
+            .initialized(3)
+            .build()
+            .unwrap();
+        let (to_bank, to_bank_owner) = (&to_suite.bank_info, &to_suite.bank_owner_info);
+        let to_account = &to_suite.bank_accounts_info[0];
+        let rate = (
+            Pubkey::new_unique(),
+            rate_account(program_id, &bank.0, &to_bank.0, 1, 1),
         );
+        cases.push(Case {
+            name: "SetExchangeRate",
+            instruction: set_exchange_rate(
+                program_id,
+                &rate.0,
+                &bank.0,
+                &to_bank.0,
+                &bank_owner.0,
+                &to_bank_owner.0,
+                3,
+                2,
+            )
+            .unwrap(),
+            accounts: vec![
+                rate.1.clone(),
+                bank.1.clone(),
+                to_bank.1.clone(),
+                bank_owner.1.clone(),
+                to_bank_owner.1.clone(),
+            ],
+            owner_errors: vec![
+                illegal.clone(),
+                illegal.clone(),
+                illegal.clone(),
+                None,
+                None,
+            ],
+        });
+        cases.push(Case {
+            name: "Convert",
+            instruction: convert(
+                program_id,
+                &rate.0,
+                &bank.0,
+                &account.0,
+                &owner.0,
+                &to_bank.0,
+                &to_account.0,
+                40,
+            )
+            .unwrap(),
+            accounts: vec![
+                rate.1.clone(),
+                bank.1.clone(),
+                account.1.clone(),
+                owner.1.clone(),
+                to_bank.1.clone(),
+                to_account.1.clone(),
+            ],
+            owner_errors: vec![
+                illegal.clone(),
+                illegal.clone(),
+                illegal.clone(),
+                None,
+                illegal.clone(),
+                illegal.clone(),
+            ],
+        });
+        cases.push(Case {
+            name: "CancelRecovery",
+            instruction: cancel_recovery(program_id, &bank.0, &account.0, &owner.0).unwrap(),
+            accounts: vec![
+                account.1.clone(),
+                owner.1.clone(),
+                bank.1.clone(),
+                recovery_account(program_id, &account.0, &owner.0),
+            ],
+            owner_errors: vec![illegal.clone(), None, illegal.clone(), illegal.clone()],
+        });
+        // PermitApprove, TransferWithExpiry, StartRecovery and RecoverOwner have no case:
+        // they read the clock, which only a runtime provides. Nor do DepositSol, which pays
+        // through the system program, WithdrawSol, which reads the rent sysvar, and
+        // SetGuardians, CreateSessionKey, InitializeVersion and SetFeatures, which do both
+        // to create their state. `tests/functional.rs` covers them.
+        cases.push(Case {
+            name: "CloseAccount",
+            instruction: close_account(program_id, &bank.0, &empty.0, &other_key, &empty_owner.0)
+                .unwrap(),
+            accounts: vec![
+                empty.1.clone(),
+                other,
+                empty_owner.1.clone(),
+                bank.1.clone(),
+            ],
+            owner_errors: vec![illegal.clone(), None, None, illegal],
+        });
+        cases
     }
 
+    /// Each case runs as given, then once per signer with its signature dropped and
+    /// once per account with its owner swapped for an unknown program.
     #[test]
-    fn test_burn() {
-        let mut test_suite = TestSuite::default(64);
-        test_suite.add_default_bank_accounts(1);
-        test_suite.process_init_bank_instruction(8).unwrap();
-        test_suite.process_init_all_accounts().unwrap();
-        test_suite.process_mint_to(0, 100).unwrap();
-        test_suite.process_burn(0, 50).unwrap();
-
-        assert_eq!(
-            Ok(true),
-            test_suite.bank_eq(&Bank {
-                decimals: 8,
-                bank_owner: test_suite.bank_owner_info.0,
-                is_opened: true,
-                total_supply: 50,
-            })
-        );
+    fn test_permission_matrix() {
+        for case in instruction_cases() {
+            let name = case.name;
+            let instruction = &case.instruction;
+            assert_eq!(
+                instruction.accounts.len(),
+                case.owner_errors.len(),
+                "{}",
+                name
+            );
+            assert_eq!(run(instruction, case.accounts.clone()), Ok(()), "{}", name);
 
-        assert_eq!(
-            Ok(true),
-            test_suite.account_eq(
-                0,
-                &Account {
-                    amount: 50,
-                    is_initialized: true,
-                    is_opened: true,
-                    owner: test_suite.bank_accounts_owner_info[0].0,
-                    delegate: COption::None,
-                    delegated_amount: 0,
-                    bank: test_suite.bank_info.0,
+            for (i, owner_error) in case.owner_errors.iter().enumerate() {
+                if instruction.accounts[i].is_signer {
+                    let mut unsigned = instruction.clone();
+                    unsigned.accounts[i].is_signer = false;
+                    assert_eq!(
+                        run(&unsigned, case.accounts.clone()),
+                        Err(ProgramError::MissingRequiredSignature),
+                        "{}: account {} unsigned",
+                        name,
+                        i
+                    );
                 }
-            )
-        );
+
+                let mut wrong_owner = case.accounts.clone();
+                wrong_owner[i].owner = Pubkey::new_unique();
+                assert_eq!(
+                    run(instruction, wrong_owner),
+                    owner_error.clone().map_or(Ok(()), Err),
+                    "{}: account {} owned by another program",
+                    name,
+                    i
+                );
+            }
+        }
     }
 
+    fn permutations(n: usize) -> Vec<Vec<usize>> {
+        if n == 0 {
+            return vec![Vec::new()];
+        }
+        let mut all = Vec::new();
+        for rest in permutations(n - 1) {
+            for position in 0..n {
+                let mut order = rest.clone();
+                order.insert(position, n - 1);
+                all.push(order);
+            }
+        }
+        all
+    }
+
+    /// Every handler reads its whole account list up front, so a short list fails with
+    /// `NotEnoughAccountKeys`, and no reordering of a valid list may still succeed.
     #[test]
-    fn test_close() {
-        let mut test_suite = TestSuite::default(64);
-        test_suite.add_default_bank_accounts(1);
-        test_suite.process_init_bank_instruction(8).unwrap();
-        test_suite.process_init_bank_account_instruction(0).unwrap();
-        test_suite.process_close(0).unwrap();
+    fn test_account_ordering() {
+        for case in instruction_cases() {
+            let name = case.name;
+            let len = case.accounts.len();
+            for omitted in 0..len {
+                let mut instruction = case.instruction.clone();
+                let mut accounts = case.accounts.clone();
+                instruction.accounts.remove(omitted);
+                accounts.remove(omitted);
+                assert_eq!(
+                    run(&instruction, accounts),
+                    Err(ProgramError::NotEnoughAccountKeys),
+                    "{}: account {} omitted",
+                    name,
+                    omitted
+                );
+            }
 
-        assert_eq!(
-            Ok(true),
-            test_suite.account_eq(
-                0,
-                &Account {
-                    amount: 0,
-                    is_initialized: true,
-                    is_opened: false,
-                    owner: test_suite.bank_accounts_owner_info[0].0,
-                    delegate: COption::None,
-                    delegated_amount: 0,
-                    bank: test_suite.bank_info.0,
+            let identity = (0..len).collect::<Vec<_>>();
+            for order in permutations(len) {
+                if order == identity {
+                    continue;
                 }
-            )
-        );
-
-        assert_eq!(
-            Err(ProgramError::InvalidAccountData),
-            test_suite.process_mint_to(0, 50)
-        );
+                let mut instruction = case.instruction.clone();
+                instruction.accounts = order
+                    .iter()
+                    .map(|&i| case.instruction.accounts[i].clone())
+                    .collect();
+                let accounts = order.iter().map(|&i| case.accounts[i].clone()).collect();
+                assert!(
+                    run(&instruction, accounts).is_err(),
+                    "{}: accounts in order {:?} succeeded",
+                    name,
+                    order
+                );
+            }
+        }
     }
 }
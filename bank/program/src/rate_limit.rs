@@ -0,0 +1,164 @@
+//! Rate limits: a token bucket in an account's extension area capping how fast tokens
+//! leave it, so a treasury can set velocity limits no single transfer gets around.
+//!
+//! An account created `account_len(capacity)` bytes long carries a bucket in its last
+//! `LEN` bytes, behind room for `capacity` transfer records, see `history`. The bucket
+//! starts unlimited; `SetRateLimit` gives it a capacity and a refill rate per slot. Each
+//! transfer out of the account first tops the bucket up for the slots since the last
+//! one, never past its capacity, then takes the amount out of it, failing with
+//! `RateLimited` if the bucket holds less. Only creating the account that long opts in,
+//! so a transfer can't leave the bucket out.
+
+use crate::error::BankError;
+use crate::history;
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::program_error::ProgramError;
+
+/// Size of a packed `Bucket`.
+pub const LEN: usize = 32;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Bucket {
+    /// Most tokens the bucket holds; 0 leaves the account unlimited.
+    pub capacity: u64,
+    /// Tokens the bucket regains per slot.
+    pub refill_per_slot: u64,
+    /// Tokens that may leave the account as of `last_refill_slot`.
+    pub available: u64,
+    pub last_refill_slot: u64,
+}
+
+impl Bucket {
+    fn unpack(src: &[u8; LEN]) -> Self {
+        let (capacity, refill_per_slot, available, last_refill_slot) = array_refs![src, 8, 8, 8, 8];
+        Bucket {
+            capacity: u64::from_le_bytes(*capacity),
+            refill_per_slot: u64::from_le_bytes(*refill_per_slot),
+            available: u64::from_le_bytes(*available),
+            last_refill_slot: u64::from_le_bytes(*last_refill_slot),
+        }
+    }
+
+    fn pack(&self, dst: &mut [u8; LEN]) {
+        let (capacity, refill_per_slot, available, last_refill_slot) =
+            mut_array_refs![dst, 8, 8, 8, 8];
+        *capacity = self.capacity.to_le_bytes();
+        *refill_per_slot = self.refill_per_slot.to_le_bytes();
+        *available = self.available.to_le_bytes();
+        *last_refill_slot = self.last_refill_slot.to_le_bytes();
+    }
+
+    /// Tops the bucket up for the slots between its last refill and `slot`.
+    pub fn refill(&mut self, slot: u64) {
+        let elapsed = slot.saturating_sub(self.last_refill_slot);
+        self.available = self
+            .refill_per_slot
+            .saturating_mul(elapsed)
+            .saturating_add(self.available)
+            .min(self.capacity);
+        self.last_refill_slot = self.last_refill_slot.max(slot);
+    }
+
+    /// Takes `amount` out of the bucket at `slot`. An unlimited bucket takes anything.
+    pub fn spend(&mut self, slot: u64, amount: u64) -> Result<(), ProgramError> {
+        if self.capacity == 0 {
+            return Ok(());
+        }
+        self.refill(slot);
+        self.available = self
+            .available
+            .checked_sub(amount)
+            .ok_or(BankError::RateLimited)?;
+        Ok(())
+    }
+}
+
+/// Size of an account with room for `capacity` records and a bucket.
+pub fn account_len(capacity: usize) -> usize {
+    history::account_len(capacity) + LEN
+}
+
+/// Whether an account `len` bytes long carries a bucket.
+pub fn has_bucket(len: usize) -> bool {
+    match len.checked_sub(account_len(0)) {
+        Some(records) => records % history::RECORD_LEN == 0,
+        None => false,
+    }
+}
+
+/// The bucket of the account `data`, if it carries one.
+pub fn bucket(data: &[u8]) -> Option<Bucket> {
+    if !has_bucket(data.len()) {
+        return None;
+    }
+    Some(Bucket::unpack(array_ref![data, data.len() - LEN, LEN]))
+}
+
+/// Writes `bucket` into the account `data`, which must carry one.
+pub fn pack_bucket(data: &mut [u8], bucket: &Bucket) {
+    let offset = data.len() - LEN;
+    bucket.pack(array_mut_ref![data, offset, LEN]);
+}
+
+/// Takes `amount` leaving the account `data` at `slot` out of its bucket. Does nothing
+/// to an account without one.
+pub fn spend(data: &mut [u8], slot: u64, amount: u64) -> Result<(), ProgramError> {
+    if let Some(mut bucket) = bucket(data) {
+        bucket.spend(slot, amount)?;
+        pack_bucket(data, &bucket);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Account;
+    use solana_program::program_pack::Pack;
+
+    #[test]
+    fn test_has_bucket() {
+        assert!(has_bucket(account_len(0)));
+        assert!(has_bucket(account_len(3)));
+        for len in [
+            Account::LEN,
+            history::account_len(0),
+            history::account_len(3),
+            account_len(0) - 1,
+            account_len(1) + 1,
+        ] {
+            assert!(!has_bucket(len), "{}", len);
+        }
+    }
+
+    #[test]
+    fn test_spend() {
+        let mut data = vec![0; account_len(2)];
+        // A new bucket is unlimited.
+        spend(&mut data, 5, u64::MAX).unwrap();
+        assert_eq!(bucket(&data), Some(Bucket::default()));
+
+        let limited = Bucket {
+            capacity: 100,
+            refill_per_slot: 10,
+            available: 100,
+            last_refill_slot: 5,
+        };
+        pack_bucket(&mut data, &limited);
+        spend(&mut data, 5, 60).unwrap();
+        spend(&mut data, 5, 40).unwrap();
+        assert_eq!(spend(&mut data, 5, 1), Err(BankError::RateLimited.into()));
+        // Three slots later 30 more can go, and a long pause fills it to its capacity.
+        assert_eq!(spend(&mut data, 8, 31), Err(BankError::RateLimited.into()));
+        spend(&mut data, 8, 30).unwrap();
+        spend(&mut data, 1_000, 0).unwrap();
+        assert_eq!(bucket(&data).unwrap().available, 100);
+        assert_eq!(bucket(&data).unwrap().last_refill_slot, 1_000);
+
+        // The bucket sits past the history, which leaves it be.
+        assert_eq!(history::capacity(&data), 2);
+        let mut plain = vec![0; Account::LEN];
+        spend(&mut plain, 5, u64::MAX).unwrap();
+        assert_eq!(plain, vec![0; Account::LEN]);
+    }
+}
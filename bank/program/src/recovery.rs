@@ -0,0 +1,94 @@
+//! Social recovery: guardians who can hand an account to a new owner once its owner has
+//! lost their key.
+//!
+//! The owner names up to `MAX_GUARDIANS` guardians, how many of them must agree, and a
+//! delay, with `SetGuardians`, in the account's recovery state: a PDA of this program
+//! derived from the account, created on first use at the expense of a payer. Enough
+//! guardians signing `StartRecovery` name a new owner; once the delay has passed, enough
+//! of them signing `RecoverOwner` makes it the account's owner. Meanwhile an owner who
+//! still holds their key calls the recovery off with `CancelRecovery`, and naming the
+//! guardians again cancels it too. The guardians only act for the owner who named them:
+//! once the account has changed hands any other way, the next owner names their own.
+
+use crate::state::Recovery;
+use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+
+/// Prefix of the seeds a recovery state derives from.
+pub const SEED: &[u8] = b"recovery";
+/// Most guardians an account can have.
+pub const MAX_GUARDIANS: usize = 8;
+
+/// The address of `account`'s recovery state, and its bump.
+pub fn address(program_id: &Pubkey, account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SEED, account.as_ref()], program_id)
+}
+
+/// How many of `recovery`'s guardians signed among `signers`, each counted once.
+pub fn approvals(recovery: &Recovery, signers: &[AccountInfo]) -> usize {
+    recovery
+        .guardians()
+        .iter()
+        .filter(|guardian| {
+            signers
+                .iter()
+                .any(|info| info.is_signer && info.key == *guardian)
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    #[test]
+    fn test_approvals() {
+        let guardians: Vec<_> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let stranger = Pubkey::new_unique();
+        let mut recovery = Recovery {
+            threshold: 2,
+            guardian_count: 3,
+            ..Recovery::default()
+        };
+        recovery.guardians[..3].copy_from_slice(&guardians);
+
+        let owner = Pubkey::new_unique();
+        let mut lamports = vec![0; 5];
+        let mut data = vec![vec![]; 5];
+        let keys = [
+            guardians[0],
+            guardians[0],
+            guardians[1],
+            guardians[2],
+            stranger,
+        ];
+        let signed = [true, true, true, false, true];
+        let infos: Vec<_> = lamports
+            .iter_mut()
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, (lamports, data))| {
+                AccountInfo::new(
+                    &keys[i],
+                    signed[i],
+                    false,
+                    lamports,
+                    data,
+                    &owner,
+                    false,
+                    Epoch::default(),
+                )
+            })
+            .collect();
+
+        // A guardian signing twice counts once, and neither an unsigned guardian nor a
+        // signer who isn't one counts.
+        assert_eq!(approvals(&recovery, &infos), 2);
+        assert_eq!(approvals(&recovery, &infos[..2]), 1);
+        assert_eq!(approvals(&recovery, &infos[3..]), 0);
+
+        // Keys past `guardian_count` aren't guardians.
+        recovery.guardian_count = 1;
+        assert_eq!(approvals(&recovery, &infos), 1);
+    }
+}
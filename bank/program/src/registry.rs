@@ -0,0 +1,105 @@
+//! Account registry: every account opened in a bank with a registry page, in order.
+//!
+//! RPC providers that disable getProgramAccounts leave clients no way to find a bank's
+//! accounts, so `InitializeAccount` can append the new account's key to the bank's
+//! registry instead. The registry is a run of pages, PDAs of this program derived from
+//! the bank and the page's index, each `PAGE_CAPACITY` keys long. The bank counts the
+//! accounts registered so far in `Bank::registered_accounts`, which says both which page
+//! the next one goes on and how many keys of the last page are filled. A page is created
+//! when its first key is appended, at the expense of the account's payer.
+
+use crate::state::Bank;
+use arrayref::{array_mut_ref, array_ref};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use std::convert::TryFrom;
+
+/// Prefix of the seeds a registry page derives from.
+pub const SEED: &[u8] = b"registry";
+/// Keys per page.
+pub const PAGE_CAPACITY: usize = 128;
+/// Size of a page.
+pub const PAGE_LEN: usize = PAGE_CAPACITY * 32;
+
+/// The address of page `page` of `bank`'s registry, and its bump.
+pub fn page_address(program_id: &Pubkey, bank: &Pubkey, page: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SEED, bank.as_ref(), &page.to_le_bytes()], program_id)
+}
+
+/// The page the next account registered in `bank` goes on, and its slot there.
+pub fn next_slot(bank: &Bank) -> Result<(u32, usize), ProgramError> {
+    let page = u32::try_from(bank.registered_accounts / PAGE_CAPACITY as u64)
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    Ok((
+        page,
+        (bank.registered_accounts % PAGE_CAPACITY as u64) as usize,
+    ))
+}
+
+/// How many pages `bank`'s registry takes up.
+pub fn page_count(bank: &Bank) -> u32 {
+    let pages = (bank.registered_accounts + PAGE_CAPACITY as u64 - 1) / PAGE_CAPACITY as u64;
+    pages as u32
+}
+
+/// Writes `key` into `slot` of the page `data`.
+pub fn write(data: &mut [u8], slot: usize, key: &Pubkey) {
+    array_mut_ref![data, slot * 32, 32].copy_from_slice(key.as_ref());
+}
+
+/// The keys on page `page` of `bank`'s registry, read from its `data`.
+pub fn entries(bank: &Bank, page: u32, data: &[u8]) -> Vec<Pubkey> {
+    let first = page as u64 * PAGE_CAPACITY as u64;
+    let filled = bank
+        .registered_accounts
+        .saturating_sub(first)
+        .min(PAGE_CAPACITY as u64) as usize;
+    (0..filled.min(data.len() / 32))
+        .map(|slot| Pubkey::new_from_array(*array_ref![data, slot * 32, 32]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bank(registered_accounts: u64) -> Bank {
+        Bank {
+            registered_accounts,
+            ..Bank::default()
+        }
+    }
+
+    #[test]
+    fn test_paging() {
+        assert_eq!(next_slot(&bank(0)), Ok((0, 0)));
+        assert_eq!(page_count(&bank(0)), 0);
+        assert_eq!(next_slot(&bank(127)), Ok((0, 127)));
+        assert_eq!(page_count(&bank(127)), 1);
+        assert_eq!(next_slot(&bank(128)), Ok((1, 0)));
+        assert_eq!(page_count(&bank(128)), 1);
+        assert_eq!(page_count(&bank(129)), 2);
+        assert_eq!(
+            next_slot(&bank(u64::MAX)),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        let program_id = Pubkey::new_unique();
+        let (bank_key, other_bank) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (first, _) = page_address(&program_id, &bank_key, 0);
+        assert_ne!(first, page_address(&program_id, &bank_key, 1).0);
+        assert_ne!(first, page_address(&program_id, &other_bank, 0).0);
+    }
+
+    #[test]
+    fn test_entries() {
+        let keys: Vec<_> = (0..130).map(|_| Pubkey::new_unique()).collect();
+        let mut pages = vec![vec![0; PAGE_LEN]; 2];
+        for (i, key) in keys.iter().enumerate() {
+            write(&mut pages[i / PAGE_CAPACITY], i % PAGE_CAPACITY, key);
+        }
+        assert_eq!(entries(&bank(3), 0, &pages[0]), keys[..3]);
+        assert_eq!(entries(&bank(3), 1, &pages[1]), vec![]);
+        assert_eq!(entries(&bank(130), 0, &pages[0]), keys[..128]);
+        assert_eq!(entries(&bank(130), 1, &pages[1]), keys[128..]);
+    }
+}
@@ -0,0 +1,19 @@
+//! Session keys: short-lived secondary signers an account's owner hands to a game or a
+//! bot, so it can transfer from the account without the owner's own key.
+//!
+//! `CreateSessionKey` records, in a PDA of this program derived from the account and the
+//! session key, how much the key may still spend and the last slot it may spend in. A
+//! `Transfer` signed by the key and given that state spends from the account as the owner
+//! would, counting down what the key has left. Creating the same key again replaces its
+//! limits, so a `max_spend` of 0 revokes it. A key only acts for the owner who created
+//! it: once the account has changed hands, it's spent.
+
+use solana_program::pubkey::Pubkey;
+
+/// Prefix of the seeds a session state derives from.
+pub const SEED: &[u8] = b"session";
+
+/// The address of the state of `account`'s session key `session_key`, and its bump.
+pub fn address(program_id: &Pubkey, account: &Pubkey, session_key: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SEED, account.as_ref(), session_key.as_ref()], program_id)
+}
@@ -0,0 +1,462 @@
+//! SPL Token's instruction layout for the operations the bank shares with it, so SPL
+//! tooling can drive the program; building with the `spl-token-compat` feature makes the
+//! entrypoint read instructions this way instead of as `BankInstruction`s. A bank plays
+//! the part of a mint and its accounts the part of token accounts.
+//!
+//! Only instructions are translated. Banks and accounts keep their own layouts, so they
+//! must be created `Bank::LEN` and `Account::LEN` bytes long rather than SPL's 82 and 165,
+//! and decoded as such. Two bank rules stay in force: `Burn` needs the bank owner to sign
+//! as a fourth account unless the bank's burn policy is `HolderOnly`, and `Transfer`
+//! needs the bank as a fourth account; `TransferChecked` already carries it. Tooling passes that bank read-only, so such transfers can't fill
+//! an empty account or empty a full one, see `BankError::ReadOnlyBank`.
+
+use crate::processor::Processor;
+use crate::state::{Account, Bank};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SplTokenInstruction {
+    /// Opens a bank with `decimals`, owned by `mint_authority`. A `freeze_authority`
+    /// becomes the bank's, see `FreezeAccount`.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The bank, owned by this program.
+    ///   1. `[]` The rent sysvar, unused; rent is read with `Rent::get`.
+    InitializeMint {
+        decimals: u8,
+        mint_authority: Pubkey,
+        freeze_authority: COption<Pubkey>,
+    },
+
+    /// Opens an empty account in a bank; unlike `BankInstruction::InitializeAccount`, the
+    /// owner doesn't sign.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account, owned by this program.
+    ///   1. `[]` The bank.
+    ///   2. `[]` The account owner.
+    ///   3. `[]` The rent sysvar, unused; rent is read with `Rent::get`.
+    InitializeAccount,
+
+    /// Moves `amount` between two accounts of the same bank.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The source account.
+    ///   1. `[writable]` The destination account.
+    ///   2. `[signer]` The source account's owner or delegate.
+    ///   3. `[]` The bank, which SPL Token doesn't pass.
+    Transfer { amount: u64 },
+
+    /// Lets a delegate spend up to `amount` more of an account's tokens, which stay in it.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account.
+    ///   1. `[]` The delegate.
+    ///   2. `[signer]` The account owner.
+    ///   3. `[]` The bank, which SPL Token doesn't pass.
+    Approve { amount: u64 },
+
+    /// Cancels the delegation of an account along with what is left of its allowance.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account.
+    ///   1. `[signer]` The account owner.
+    ///   2. `[]` The bank, which SPL Token doesn't pass.
+    Revoke,
+
+    /// Creates `amount` new tokens in an account.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The bank, opened.
+    ///   1. `[writable]` The account.
+    ///   2. `[signer]` The bank owner.
+    MintTo { amount: u64 },
+
+    /// Destroys `amount` tokens of an account.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account.
+    ///   1. `[writable]` The bank.
+    ///   2. `[signer]` The account owner.
+    ///   3. `[signer]` The bank owner, optional under `BurnPolicy::HolderOnly`.
+    Burn { amount: u64 },
+
+    /// Closes an account and sends its lamports to the destination.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account.
+    ///   1. `[writable]` The destination.
+    ///   2. `[signer]` The account owner.
+    ///   3. `[]` The bank, which SPL Token doesn't pass.
+    CloseAccount,
+
+    /// Freezes an account of a bank with a freeze authority.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account.
+    ///   1. `[]` The bank.
+    ///   2. `[signer]` The bank's freeze authority.
+    FreezeAccount,
+
+    /// Thaws an account `FreezeAccount` froze.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The account.
+    ///   1. `[]` The bank.
+    ///   2. `[signer]` The bank's freeze authority.
+    ThawAccount,
+
+    /// `Transfer`, checking the accounts' bank and its decimals first; what wallets send.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The bank.
+    ///   2. `[writable]` The destination account.
+    ///   3. `[signer]` The source account's owner or delegate.
+    TransferChecked { amount: u64, decimals: u8 },
+}
+
+impl SplTokenInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (&decimals, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let (mint_authority, rest) = Self::unpack_pubkey(rest)?;
+                let freeze_authority = match rest.split_first() {
+                    Some((0, _)) => COption::None,
+                    Some((1, rest)) => COption::Some(Self::unpack_pubkey(rest)?.0),
+                    _ => return Err(InvalidInstructionData),
+                };
+                Self::InitializeMint {
+                    decimals,
+                    mint_authority,
+                    freeze_authority,
+                }
+            }
+            1 => Self::InitializeAccount,
+            3 => Self::Transfer {
+                amount: Self::unpack_amount(rest)?,
+            },
+            4 => Self::Approve {
+                amount: Self::unpack_amount(rest)?,
+            },
+            5 => Self::Revoke,
+            7 => Self::MintTo {
+                amount: Self::unpack_amount(rest)?,
+            },
+            8 => Self::Burn {
+                amount: Self::unpack_amount(rest)?,
+            },
+            9 => Self::CloseAccount,
+            10 => Self::FreezeAccount,
+            11 => Self::ThawAccount,
+            12 => {
+                let amount = Self::unpack_amount(rest)?;
+                let &decimals = rest.get(8).ok_or(InvalidInstructionData)?;
+                Self::TransferChecked { amount, decimals }
+            }
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match *self {
+            Self::InitializeMint {
+                decimals,
+                ref mint_authority,
+                ref freeze_authority,
+            } => {
+                buf.push(0);
+                buf.push(decimals);
+                buf.extend_from_slice(mint_authority.as_ref());
+                match freeze_authority {
+                    COption::Some(freeze_authority) => {
+                        buf.push(1);
+                        buf.extend_from_slice(freeze_authority.as_ref());
+                    }
+                    COption::None => buf.push(0),
+                }
+            }
+            Self::InitializeAccount => buf.push(1),
+            Self::Transfer { amount } => {
+                buf.push(3);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Approve { amount } => {
+                buf.push(4);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Revoke => buf.push(5),
+            Self::MintTo { amount } => {
+                buf.push(7);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Burn { amount } => {
+                buf.push(8);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::CloseAccount => buf.push(9),
+            Self::FreezeAccount => buf.push(10),
+            Self::ThawAccount => buf.push(11),
+            Self::TransferChecked { amount, decimals } => {
+                buf.push(12);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.push(decimals);
+            }
+        }
+        buf
+    }
+
+    fn unpack_amount(input: &[u8]) -> Result<u64, ProgramError> {
+        input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)
+    }
+
+    fn unpack_pubkey(input: &[u8]) -> Result<(Pubkey, &[u8]), ProgramError> {
+        if input.len() < 32 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (key, rest) = input.split_at(32);
+        Ok((Pubkey::new_from_array(key.try_into().unwrap()), rest))
+    }
+}
+
+/// Processes an instruction in SPL Token's layout, handing it to the bank's own processor
+/// with the accounts in the bank's order wherever the two agree on what it does.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+    match SplTokenInstruction::unpack(input)? {
+        SplTokenInstruction::InitializeMint {
+            decimals,
+            mint_authority,
+            freeze_authority,
+        } => {
+            log!("Instruction: InitializeMint");
+            process_initialize_mint(
+                program_id,
+                accounts,
+                decimals,
+                mint_authority,
+                freeze_authority,
+            )
+        }
+        SplTokenInstruction::InitializeAccount => {
+            log!("Instruction: InitializeAccount");
+            process_initialize_account(program_id, accounts)
+        }
+        SplTokenInstruction::Transfer { amount } => {
+            log!("Instruction: Transfer");
+            Processor::process_transfer(program_id, accounts, amount, false)
+        }
+        SplTokenInstruction::Approve { amount } => {
+            log!("Instruction: Approve");
+            Processor::process_approve(program_id, accounts, amount)
+        }
+        SplTokenInstruction::Revoke => {
+            log!("Instruction: Revoke");
+            Processor::process_revoke(program_id, accounts)
+        }
+        SplTokenInstruction::MintTo { amount } => {
+            log!("Instruction: MintTo");
+            Processor::process_mint_to(program_id, accounts, amount)
+        }
+        SplTokenInstruction::Burn { amount } => {
+            log!("Instruction: Burn");
+            let order: &[usize] = match accounts.len() {
+                3 => &[1, 0, 2],
+                _ => &[1, 0, 3, 2],
+            };
+            let accounts = reorder(accounts, order)?;
+            Processor::process_burn(program_id, &accounts, amount)
+        }
+        SplTokenInstruction::CloseAccount => {
+            log!("Instruction: CloseAccount");
+            Processor::process_close_account(program_id, accounts)
+        }
+        SplTokenInstruction::FreezeAccount => {
+            log!("Instruction: FreezeAccount");
+            Processor::process_freeze_account(program_id, accounts, true)
+        }
+        SplTokenInstruction::ThawAccount => {
+            log!("Instruction: ThawAccount");
+            Processor::process_freeze_account(program_id, accounts, false)
+        }
+        SplTokenInstruction::TransferChecked { amount, decimals } => {
+            log!("Instruction: TransferChecked");
+            process_transfer_checked(program_id, accounts, amount, decimals)
+        }
+    }
+}
+
+/// `accounts[order[0]], accounts[order[1]], ...`.
+fn reorder<'a>(
+    accounts: &[AccountInfo<'a>],
+    order: &[usize],
+) -> Result<Vec<AccountInfo<'a>>, ProgramError> {
+    order
+        .iter()
+        .map(|&i| accounts.get(i).cloned())
+        .collect::<Option<_>>()
+        .ok_or(ProgramError::NotEnoughAccountKeys)
+}
+
+fn process_initialize_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    decimals: u8,
+    mint_authority: Pubkey,
+    freeze_authority: COption<Pubkey>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let bank_account_info = next_account_info(account_info_iter)?;
+    if bank_account_info.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    Processor::check_rent_exempt(bank_account_info)?;
+
+    let mut bank = Bank::unpack_unchecked(&bank_account_info.data.borrow())?;
+    if bank.is_opened {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    bank.decimals = decimals;
+    bank.bank_owner = mint_authority;
+    bank.is_opened = true;
+    bank.freeze_authority = freeze_authority;
+
+    Bank::pack(bank, &mut bank_account_info.data.borrow_mut())?;
+    Ok(())
+}
+
+fn process_initialize_account(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let account_info = next_account_info(account_info_iter)?;
+    let bank_account_info = next_account_info(account_info_iter)?;
+    let account_owner_info = next_account_info(account_info_iter)?;
+    if account_info.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    Processor::check_bank_open(program_id, bank_account_info)?;
+    Processor::check_rent_exempt(account_info)?;
+    let mut bank_account = Account::unpack_unchecked(&account_info.data.borrow())?;
+    if bank_account.is_initialized {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    bank_account.amount = 0;
+    bank_account.bank = *bank_account_info.key;
+    bank_account.owner = *account_owner_info.key;
+    bank_account.is_initialized = true;
+    bank_account.is_opened = true;
+    bank_account.delegate = COption::None;
+    bank_account.delegated_amount = 0;
+    bank_account.permit_nonce = 0;
+
+    Account::pack(bank_account, &mut account_info.data.borrow_mut())?;
+    Ok(())
+}
+
+fn process_transfer_checked(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    decimals: u8,
+) -> ProgramResult {
+    let accounts = reorder(accounts, &[0, 2, 3, 1])?;
+    let bank = Processor::check_bank_open(program_id, &accounts[3])?;
+    if bank.decimals != decimals {
+        log!("Bank has {} decimals, not {}", bank.decimals, decimals);
+        return Err(ProgramError::InvalidArgument);
+    }
+    Processor::process_transfer(program_id, &accounts, amount, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let key = Pubkey::new_unique();
+        let instructions = vec![
+            SplTokenInstruction::InitializeMint {
+                decimals: 6,
+                mint_authority: key,
+                freeze_authority: COption::None,
+            },
+            SplTokenInstruction::InitializeMint {
+                decimals: 6,
+                mint_authority: key,
+                freeze_authority: COption::Some(key),
+            },
+            SplTokenInstruction::InitializeAccount,
+            SplTokenInstruction::Transfer { amount: 1 },
+            SplTokenInstruction::Approve { amount: 2 },
+            SplTokenInstruction::Revoke,
+            SplTokenInstruction::MintTo { amount: 3 },
+            SplTokenInstruction::Burn { amount: 4 },
+            SplTokenInstruction::CloseAccount,
+            SplTokenInstruction::FreezeAccount,
+            SplTokenInstruction::ThawAccount,
+            SplTokenInstruction::TransferChecked {
+                amount: 5,
+                decimals: 6,
+            },
+        ];
+        for instruction in instructions {
+            assert_eq!(
+                SplTokenInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+    }
+
+    #[test]
+    fn test_spl_token_layout() {
+        // As `spl_token::instruction::TokenInstruction::pack` writes them.
+        let mut initialize_mint = vec![0, 9];
+        initialize_mint.extend_from_slice(&[7; 32]);
+        initialize_mint.push(0);
+        assert_eq!(
+            SplTokenInstruction::unpack(&initialize_mint),
+            Ok(SplTokenInstruction::InitializeMint {
+                decimals: 9,
+                mint_authority: Pubkey::new_from_array([7; 32]),
+                freeze_authority: COption::None,
+            })
+        );
+        assert_eq!(
+            SplTokenInstruction::unpack(&[12, 1, 0, 0, 0, 0, 0, 0, 0, 2]),
+            Ok(SplTokenInstruction::TransferChecked {
+                amount: 1,
+                decimals: 2
+            })
+        );
+        assert_eq!(
+            SplTokenInstruction::unpack(&[10]),
+            Ok(SplTokenInstruction::FreezeAccount)
+        );
+        // SetAuthority and the other operations the bank doesn't have.
+        assert_eq!(
+            SplTokenInstruction::unpack(&[6]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            SplTokenInstruction::unpack(&[3, 1, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -1,5 +1,7 @@
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 
+use crate::multisig::MAX_SIGNERS;
+use crate::recovery::MAX_GUARDIANS;
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 use solana_program::{
     program_error::ProgramError,
@@ -15,6 +17,134 @@ pub struct Bank {
     pub bank_owner: Pubkey,
     pub is_opened: bool,
     pub total_supply: u64,
+    pub close_policy: ClosePolicy,
+    /// Set by `SetRequireRentExempt`. Accounts opened in the bank must be rent-exempt
+    /// whether or not it is set.
+    pub require_rent_exempt: bool,
+    /// Chosen when the bank is opened and fixed from then on.
+    pub burn_policy: BurnPolicy,
+    /// Lamports `DepositSol` takes and `WithdrawSol` pays out per token, which the bank
+    /// holds meanwhile; 0 when the bank doesn't take SOL. Fixed when the bank is opened.
+    pub lamports_per_token: u64,
+    /// Transfers between accounts of the bank, a `TransferRoute` counting one per hop.
+    pub transfer_count: u64,
+    /// Tokens those transfers moved.
+    pub cumulative_volume: u64,
+    /// Accounts of the bank holding tokens.
+    pub holder_count: u64,
+    /// Accounts appended to the bank's registry so far, see `registry`.
+    pub registered_accounts: u64,
+    /// Balances below it are dust: a transfer can take them along with it, and anyone
+    /// can close an account holding no more, see `CloseDustAccount`. 0 makes no balance
+    /// dust.
+    pub dust_threshold: u64,
+    /// May freeze and thaw the bank's accounts, see `FreezeAccount`. Fixed when the bank
+    /// is opened; the accounts of a bank without one can't be frozen.
+    pub freeze_authority: COption<Pubkey>,
+    /// Set by the bank owner for an emergency: until the bank is resumed, its tokens
+    /// aren't transferred, approved, minted, burned or traded for SOL, see `PauseBank`.
+    pub is_paused: bool,
+    /// Yearly interest the bank pays on its accounts' balances, in basis points, see
+    /// `interest`. Fixed when the bank is opened; 0 pays none.
+    pub interest_rate_bps: u16,
+    /// An on-call key the bank owner names or removes with `SetAuthority` that may pause
+    /// the bank and freeze its accounts, but not resume or thaw them, mint or move tokens.
+    pub emergency_authority: COption<Pubkey>,
+    /// Slots a change of `bank_owner` waits before it takes effect, see `SetAuthority`;
+    /// 0 hands the bank over at once. Set by `SetOwnerChangeDelay`, which only raises it.
+    pub owner_change_delay: u64,
+    /// The owner a pending `SetAuthority` hands the bank to.
+    pub pending_bank_owner: COption<Pubkey>,
+    /// The slot from which the pending change can take effect.
+    pub owner_change_slot: u64,
+}
+
+/// The statistics counters are for dashboards, so they saturate rather than fail the
+/// instruction that moves them.
+impl Bank {
+    pub fn record_transfer(&mut self, amount: u64) {
+        self.transfer_count = self.transfer_count.saturating_add(1);
+        self.cumulative_volume = self.cumulative_volume.saturating_add(amount);
+    }
+
+    /// Counts an account of the bank in or out of `holder_count` as its balance goes
+    /// from `before` to `after`.
+    pub fn record_balance(&mut self, before: u64, after: u64) {
+        if before == 0 && after > 0 {
+            self.holder_count = self.holder_count.saturating_add(1);
+        } else if before > 0 && after == 0 {
+            self.holder_count = self.holder_count.saturating_sub(1);
+        }
+    }
+
+    /// Whether `amount` is a balance left too small to keep, see `dust_threshold`.
+    pub fn is_dust(&self, amount: u64) -> bool {
+        amount < self.dust_threshold
+    }
+
+    /// Whether `key` is the bank's `emergency_authority`.
+    pub fn is_emergency_authority(&self, key: &Pubkey) -> bool {
+        self.emergency_authority == COption::Some(*key)
+    }
+}
+
+/// What `CloseAccount` does with the tokens an account of the bank still holds.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClosePolicy {
+    /// Only empty accounts can be closed.
+    Reject,
+    /// The tokens move to another account of the bank, passed after the bank.
+    Sweep,
+    /// The tokens are destroyed, taking them out of the bank's supply.
+    Burn,
+}
+
+impl Default for ClosePolicy {
+    fn default() -> Self {
+        Self::Reject
+    }
+}
+
+impl TryFrom<u8> for ClosePolicy {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Reject),
+            1 => Ok(Self::Sweep),
+            2 => Ok(Self::Burn),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// Who has to sign a `Burn` of an account's tokens.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BurnPolicy {
+    /// The bank owner and the account owner both do.
+    OwnerAndHolder,
+    /// The account owner alone does.
+    HolderOnly,
+}
+
+impl Default for BurnPolicy {
+    fn default() -> Self {
+        Self::OwnerAndHolder
+    }
+}
+
+impl TryFrom<u8> for BurnPolicy {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::OwnerAndHolder),
+            1 => Ok(Self::HolderOnly),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
 }
 
 impl Sealed for Bank {}
@@ -25,30 +155,118 @@ impl IsInitialized for Bank {
 }
 
 impl Pack for Bank {
-    const LEN: usize = 42;
+    const LEN: usize = 220;
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        let src = array_ref![src, 0, 42];
-        let (decimals, bank_owner, is_opened, total_supply) = array_refs![src, 1, 32, 1, 8];
+        let src = array_ref![src, 0, 220];
+        let (
+            decimals,
+            bank_owner,
+            is_opened,
+            total_supply,
+            close_policy,
+            require_rent_exempt,
+            burn_policy,
+            lamports_per_token,
+            transfer_count,
+            cumulative_volume,
+            holder_count,
+            registered_accounts,
+            dust_threshold,
+            freeze_authority,
+            is_paused,
+            interest_rate_bps,
+            emergency_authority,
+            owner_change_delay,
+            pending_bank_owner,
+            owner_change_slot,
+        ) = array_refs![src, 1, 32, 1, 8, 1, 1, 1, 8, 8, 8, 8, 8, 8, 36, 1, 2, 36, 8, 36, 8];
         let decimals = decimals[0];
         let bank_owner = Pubkey::new(bank_owner);
         let is_opened = is_opened[0] == 1;
         let total_supply = u64::from_le_bytes(*total_supply);
+        let close_policy = ClosePolicy::try_from(close_policy[0])?;
+        let require_rent_exempt = require_rent_exempt[0] == 1;
+        let burn_policy = BurnPolicy::try_from(burn_policy[0])?;
+        let lamports_per_token = u64::from_le_bytes(*lamports_per_token);
+        let transfer_count = u64::from_le_bytes(*transfer_count);
+        let cumulative_volume = u64::from_le_bytes(*cumulative_volume);
+        let holder_count = u64::from_le_bytes(*holder_count);
+        let registered_accounts = u64::from_le_bytes(*registered_accounts);
+        let dust_threshold = u64::from_le_bytes(*dust_threshold);
+        let freeze_authority = unpack_coption_key(freeze_authority)?;
+        let is_paused = is_paused[0] == 1;
+        let interest_rate_bps = u16::from_le_bytes(*interest_rate_bps);
+        let emergency_authority = unpack_coption_key(emergency_authority)?;
+        let owner_change_delay = u64::from_le_bytes(*owner_change_delay);
+        let pending_bank_owner = unpack_coption_key(pending_bank_owner)?;
+        let owner_change_slot = u64::from_le_bytes(*owner_change_slot);
         Ok(Bank {
             decimals,
             bank_owner,
             is_opened,
             total_supply,
+            close_policy,
+            require_rent_exempt,
+            burn_policy,
+            lamports_per_token,
+            transfer_count,
+            cumulative_volume,
+            holder_count,
+            registered_accounts,
+            dust_threshold,
+            freeze_authority,
+            is_paused,
+            interest_rate_bps,
+            emergency_authority,
+            owner_change_delay,
+            pending_bank_owner,
+            owner_change_slot,
         })
     }
     fn pack_into_slice(&self, dst: &mut [u8]) {
-        let dst = array_mut_ref![dst, 0, 42];
-        let (decimals, bank_owner, is_opened, total_supply) = mut_array_refs![dst, 1, 32, 1, 8];
+        let dst = array_mut_ref![dst, 0, 220];
+        let (
+            decimals,
+            bank_owner,
+            is_opened,
+            total_supply,
+            close_policy,
+            require_rent_exempt,
+            burn_policy,
+            lamports_per_token,
+            transfer_count,
+            cumulative_volume,
+            holder_count,
+            registered_accounts,
+            dust_threshold,
+            freeze_authority,
+            is_paused,
+            interest_rate_bps,
+            emergency_authority,
+            owner_change_delay,
+            pending_bank_owner,
+            owner_change_slot,
+        ) = mut_array_refs![dst, 1, 32, 1, 8, 1, 1, 1, 8, 8, 8, 8, 8, 8, 36, 1, 2, 36, 8, 36, 8];
         decimals[0] = self.decimals;
         bank_owner.copy_from_slice(&self.bank_owner.as_ref());
-        if self.is_opened {
-            is_opened[0] = 1;
-        }
+        is_opened[0] = self.is_opened as u8;
         total_supply.copy_from_slice(&self.total_supply.to_le_bytes());
+        close_policy[0] = self.close_policy as u8;
+        require_rent_exempt[0] = self.require_rent_exempt as u8;
+        burn_policy[0] = self.burn_policy as u8;
+        lamports_per_token.copy_from_slice(&self.lamports_per_token.to_le_bytes());
+        transfer_count.copy_from_slice(&self.transfer_count.to_le_bytes());
+        cumulative_volume.copy_from_slice(&self.cumulative_volume.to_le_bytes());
+        holder_count.copy_from_slice(&self.holder_count.to_le_bytes());
+        registered_accounts.copy_from_slice(&self.registered_accounts.to_le_bytes());
+        dust_threshold.copy_from_slice(&self.dust_threshold.to_le_bytes());
+        pack_coption_key(&self.freeze_authority, freeze_authority);
+        is_paused[0] = self.is_paused as u8;
+        *interest_rate_bps = self.interest_rate_bps.to_le_bytes();
+        pack_coption_key(&self.emergency_authority, emergency_authority);
+        *owner_change_delay = self.owner_change_delay.to_le_bytes();
+        pack_coption_key(&self.pending_bank_owner, pending_bank_owner);
+        *owner_change_slot = self.owner_change_slot.to_le_bytes();
     }
 }
 
@@ -62,12 +280,164 @@ pub struct Account {
     pub delegate: COption<Pubkey>,
     pub delegated_amount: u64,
     pub bank: Pubkey,
+    /// The nonce the next `PermitApprove` of the account must carry; using one bumps it.
+    pub permit_nonce: u64,
+    /// Set by the bank's freeze authority: no tokens move into or out of the account
+    /// until it is thawed, see `FreezeAccount`.
+    pub is_frozen: bool,
+    /// Unix timestamp the account last accrued interest at, see `interest`; 0 until it
+    /// first does.
+    pub last_accrual: i64,
 }
 
 impl Account {
     pub fn can_trade(&self) -> bool {
         return self.is_opened && self.is_initialized;
     }
+
+    /// Unpacks an account that may carry an extension area past `Account::LEN`.
+    pub fn unpack_extended(src: &[u8]) -> Result<Self, ProgramError> {
+        Self::unpack(
+            src.get(..Self::LEN)
+                .ok_or(ProgramError::InvalidAccountData)?,
+        )
+    }
+}
+
+/// The balances of an `Account`, the fields most instructions change.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Balances {
+    pub amount: u64,
+    pub delegated_amount: u64,
+}
+
+/// Single fields of a packed `Account`, read and written in place so that the
+/// processor never copies the whole account onto the stack to change a few of them.
+/// Apart from the `check_` functions, these expect a slice that passed one. The slice
+/// may run past `Account::LEN` into the account's extension area (see `history`).
+impl Account {
+    const AMOUNT_OFFSET: usize = 0;
+    const IS_OPENED_OFFSET: usize = 8;
+    const IS_INITIALIZED_OFFSET: usize = 9;
+    const OWNER_OFFSET: usize = 10;
+    const DELEGATE_OFFSET: usize = 42;
+    const DELEGATED_AMOUNT_OFFSET: usize = 78;
+    const BANK_OFFSET: usize = 86;
+    const PERMIT_NONCE_OFFSET: usize = 118;
+    const IS_FROZEN_OFFSET: usize = 126;
+    const LAST_ACCRUAL_OFFSET: usize = 127;
+
+    /// Fails the way `Account::unpack` does on a slice that isn't an initialized account.
+    pub fn check_initialized(src: &[u8]) -> Result<(), ProgramError> {
+        if src.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Self::unpack_delegate(src)?;
+        if !Self::unpack_is_initialized(src) {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        Ok(())
+    }
+
+    /// Fails the way unpacking and `can_trade` together do.
+    pub fn check_can_trade(src: &[u8]) -> Result<(), ProgramError> {
+        if src.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Self::unpack_delegate(src)?;
+        if !Self::unpack_is_initialized(src) || !Self::unpack_is_opened(src) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(())
+    }
+
+    pub fn unpack_is_initialized(src: &[u8]) -> bool {
+        src[Self::IS_INITIALIZED_OFFSET] == 1
+    }
+
+    pub fn unpack_is_opened(src: &[u8]) -> bool {
+        src[Self::IS_OPENED_OFFSET] == 1
+    }
+
+    pub fn pack_is_opened(dst: &mut [u8], is_opened: bool) {
+        dst[Self::IS_OPENED_OFFSET] = is_opened as u8;
+    }
+
+    pub fn unpack_amount(src: &[u8]) -> u64 {
+        u64::from_le_bytes(*array_ref![src, Self::AMOUNT_OFFSET, 8])
+    }
+
+    pub fn pack_amount(dst: &mut [u8], amount: u64) {
+        array_mut_ref![dst, Self::AMOUNT_OFFSET, 8].copy_from_slice(&amount.to_le_bytes());
+    }
+
+    pub fn unpack_owner(src: &[u8]) -> Pubkey {
+        Pubkey::new_from_array(*array_ref![src, Self::OWNER_OFFSET, 32])
+    }
+
+    pub fn pack_owner(dst: &mut [u8], owner: &Pubkey) {
+        array_mut_ref![dst, Self::OWNER_OFFSET, 32].copy_from_slice(owner.as_ref());
+    }
+
+    pub fn unpack_delegate(src: &[u8]) -> Result<COption<Pubkey>, ProgramError> {
+        unpack_coption_key(array_ref![src, Self::DELEGATE_OFFSET, 36])
+    }
+
+    pub fn pack_delegate(dst: &mut [u8], delegate: &COption<Pubkey>) {
+        pack_coption_key(delegate, array_mut_ref![dst, Self::DELEGATE_OFFSET, 36]);
+    }
+
+    pub fn unpack_balances(src: &[u8]) -> Balances {
+        Balances {
+            amount: Self::unpack_amount(src),
+            delegated_amount: Self::unpack_delegated_amount(src),
+        }
+    }
+
+    pub fn pack_balances(dst: &mut [u8], balances: &Balances) {
+        Self::pack_amount(dst, balances.amount);
+        Self::pack_delegated_amount(dst, balances.delegated_amount);
+    }
+
+    pub fn unpack_delegated_amount(src: &[u8]) -> u64 {
+        u64::from_le_bytes(*array_ref![src, Self::DELEGATED_AMOUNT_OFFSET, 8])
+    }
+
+    pub fn pack_delegated_amount(dst: &mut [u8], delegated_amount: u64) {
+        array_mut_ref![dst, Self::DELEGATED_AMOUNT_OFFSET, 8]
+            .copy_from_slice(&delegated_amount.to_le_bytes());
+    }
+
+    /// The bank's key as stored, for comparing two accounts without building `Pubkey`s.
+    pub fn unpack_bank_bytes(src: &[u8]) -> &[u8; 32] {
+        array_ref![src, Self::BANK_OFFSET, 32]
+    }
+
+    pub fn unpack_permit_nonce(src: &[u8]) -> u64 {
+        u64::from_le_bytes(*array_ref![src, Self::PERMIT_NONCE_OFFSET, 8])
+    }
+
+    pub fn pack_permit_nonce(dst: &mut [u8], permit_nonce: u64) {
+        array_mut_ref![dst, Self::PERMIT_NONCE_OFFSET, 8]
+            .copy_from_slice(&permit_nonce.to_le_bytes());
+    }
+
+    pub fn unpack_is_frozen(src: &[u8]) -> bool {
+        src[Self::IS_FROZEN_OFFSET] == 1
+    }
+
+    pub fn pack_is_frozen(dst: &mut [u8], is_frozen: bool) {
+        dst[Self::IS_FROZEN_OFFSET] = is_frozen as u8;
+    }
+
+    pub fn unpack_last_accrual(src: &[u8]) -> i64 {
+        i64::from_le_bytes(*array_ref![src, Self::LAST_ACCRUAL_OFFSET, 8])
+    }
+
+    pub fn pack_last_accrual(dst: &mut [u8], last_accrual: i64) {
+        array_mut_ref![dst, Self::LAST_ACCRUAL_OFFSET, 8]
+            .copy_from_slice(&last_accrual.to_le_bytes());
+    }
 }
 
 impl Sealed for Account {}
@@ -78,11 +448,21 @@ impl IsInitialized for Account {
 }
 
 impl Pack for Account {
-    const LEN: usize = 118;
+    const LEN: usize = 135;
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        let src = array_ref![src, 0, 118];
-        let (amount, is_opened, is_initialized, owner, delegate, delegated_amount, bank) =
-            array_refs![src, 8, 1, 1, 32, 36, 8, 32];
+        let src = array_ref![src, 0, 135];
+        let (
+            amount,
+            is_opened,
+            is_initialized,
+            owner,
+            delegate,
+            delegated_amount,
+            bank,
+            permit_nonce,
+            is_frozen,
+            last_accrual,
+        ) = array_refs![src, 8, 1, 1, 32, 36, 8, 32, 8, 1, 8];
         let amount = u64::from_le_bytes(*amount);
         let is_opened = is_opened[0] == 1;
         let is_initialized = is_initialized[0] == 1;
@@ -90,6 +470,9 @@ impl Pack for Account {
         let delegate = unpack_coption_key(delegate)?;
         let delegated_amount = u64::from_le_bytes(*delegated_amount);
         let bank = Pubkey::new(&bank[..]);
+        let permit_nonce = u64::from_le_bytes(*permit_nonce);
+        let is_frozen = is_frozen[0] == 1;
+        let last_accrual = i64::from_le_bytes(*last_accrual);
         Ok(Account {
             amount,
             is_opened,
@@ -98,21 +481,366 @@ impl Pack for Account {
             delegate,
             delegated_amount,
             bank,
+            permit_nonce,
+            is_frozen,
+            last_accrual,
         })
     }
     fn pack_into_slice(&self, dst: &mut [u8]) {
-        let dst = array_mut_ref![dst, 0, 118];
-        let (amount, is_opened, is_initialized, owner, delegate, delegated_amount, bank) =
-            mut_array_refs![dst, 8, 1, 1, 32, 36, 8, 32];
+        let dst = array_mut_ref![dst, 0, 135];
+        let (
+            amount,
+            is_opened,
+            is_initialized,
+            owner,
+            delegate,
+            delegated_amount,
+            bank,
+            permit_nonce,
+            is_frozen,
+            last_accrual,
+        ) = mut_array_refs![dst, 8, 1, 1, 32, 36, 8, 32, 8, 1, 8];
         amount.copy_from_slice(&self.amount.to_le_bytes());
-        is_opened[0] = if self.is_opened { 1 } else { 0 };
-        if self.is_initialized {
-            is_initialized[0] = 1;
-        }
+        is_opened[0] = self.is_opened as u8;
+        is_initialized[0] = self.is_initialized as u8;
         owner.copy_from_slice(&self.owner.to_bytes());
         pack_coption_key(&self.delegate, delegate);
         delegated_amount.copy_from_slice(&self.delegated_amount.to_le_bytes());
         bank.copy_from_slice(&self.bank.to_bytes());
+        permit_nonce.copy_from_slice(&self.permit_nonce.to_le_bytes());
+        is_frozen[0] = self.is_frozen as u8;
+        *last_accrual = self.last_accrual.to_le_bytes();
+    }
+}
+
+/// The rate `Convert` moves tokens of `from_bank` into `to_bank` at, set by the owners
+/// of both banks with `SetExchangeRate`. Converting back takes a rate of its own.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ExchangeRate {
+    pub is_initialized: bool,
+    pub from_bank: Pubkey,
+    pub to_bank: Pubkey,
+    /// `numerator / denominator` whole tokens of `to_bank` per whole token of
+    /// `from_bank`; neither is 0.
+    pub numerator: u64,
+    pub denominator: u64,
+}
+
+impl Sealed for ExchangeRate {}
+impl IsInitialized for ExchangeRate {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for ExchangeRate {
+    const LEN: usize = 81;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 81];
+        let (is_initialized, from_bank, to_bank, numerator, denominator) =
+            array_refs![src, 1, 32, 32, 8, 8];
+        Ok(ExchangeRate {
+            is_initialized: is_initialized[0] == 1,
+            from_bank: Pubkey::new_from_array(*from_bank),
+            to_bank: Pubkey::new_from_array(*to_bank),
+            numerator: u64::from_le_bytes(*numerator),
+            denominator: u64::from_le_bytes(*denominator),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 81];
+        let (is_initialized, from_bank, to_bank, numerator, denominator) =
+            mut_array_refs![dst, 1, 32, 32, 8, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        from_bank.copy_from_slice(self.from_bank.as_ref());
+        to_bank.copy_from_slice(self.to_bank.as_ref());
+        numerator.copy_from_slice(&self.numerator.to_le_bytes());
+        denominator.copy_from_slice(&self.denominator.to_le_bytes());
+    }
+}
+
+/// The guardians of an account, who can hand it to a new owner, and the recovery they
+/// have started, see `recovery`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Recovery {
+    pub is_initialized: bool,
+    pub account: Pubkey,
+    /// The owner who named the guardians. They only act for the account while it still
+    /// has this owner.
+    pub owner: Pubkey,
+    /// Guardians that must sign to start or complete a recovery; at least 1 and at most
+    /// `guardian_count`.
+    pub threshold: u8,
+    pub guardian_count: u8,
+    /// The first `guardian_count` are the guardians, the rest are unused.
+    pub guardians: [Pubkey; MAX_GUARDIANS],
+    /// Slots a started recovery waits before it can complete.
+    pub delay: u64,
+    /// The owner a started recovery hands the account to.
+    pub pending_owner: COption<Pubkey>,
+    /// The slot from which the started recovery can complete.
+    pub activation_slot: u64,
+}
+
+impl Recovery {
+    pub fn guardians(&self) -> &[Pubkey] {
+        &self.guardians[..self.guardian_count as usize]
+    }
+}
+
+impl Sealed for Recovery {}
+impl IsInitialized for Recovery {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Recovery {
+    const LEN: usize = 375;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 375];
+        let (
+            is_initialized,
+            account,
+            owner,
+            threshold,
+            guardian_count,
+            guardians,
+            delay,
+            pending_owner,
+            activation_slot,
+        ) = array_refs![src, 1, 32, 32, 1, 1, 256, 8, 36, 8];
+        if guardian_count[0] as usize > MAX_GUARDIANS {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let mut keys = [Pubkey::default(); MAX_GUARDIANS];
+        for (i, key) in keys.iter_mut().enumerate() {
+            *key = Pubkey::new_from_array(*array_ref![guardians, i * 32, 32]);
+        }
+        Ok(Recovery {
+            is_initialized: is_initialized[0] == 1,
+            account: Pubkey::new_from_array(*account),
+            owner: Pubkey::new_from_array(*owner),
+            threshold: threshold[0],
+            guardian_count: guardian_count[0],
+            guardians: keys,
+            delay: u64::from_le_bytes(*delay),
+            pending_owner: unpack_coption_key(pending_owner)?,
+            activation_slot: u64::from_le_bytes(*activation_slot),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 375];
+        let (
+            is_initialized,
+            account,
+            owner,
+            threshold,
+            guardian_count,
+            guardians,
+            delay,
+            pending_owner,
+            activation_slot,
+        ) = mut_array_refs![dst, 1, 32, 32, 1, 1, 256, 8, 36, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        account.copy_from_slice(self.account.as_ref());
+        owner.copy_from_slice(self.owner.as_ref());
+        threshold[0] = self.threshold;
+        guardian_count[0] = self.guardian_count;
+        for (i, key) in self.guardians.iter().enumerate() {
+            array_mut_ref![guardians, i * 32, 32].copy_from_slice(key.as_ref());
+        }
+        delay.copy_from_slice(&self.delay.to_le_bytes());
+        pack_coption_key(&self.pending_owner, pending_owner);
+        activation_slot.copy_from_slice(&self.activation_slot.to_le_bytes());
+    }
+}
+
+/// What a session key may still spend from an account, and until when, see `session`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Session {
+    pub is_initialized: bool,
+    pub account: Pubkey,
+    /// The owner who created the key. It only spends while the account still has them.
+    pub owner: Pubkey,
+    pub session_key: Pubkey,
+    /// Tokens the key may still transfer out of the account.
+    pub remaining_spend: u64,
+    /// The last slot the key may transfer in.
+    pub expiry_slot: u64,
+}
+
+impl Sealed for Session {}
+impl IsInitialized for Session {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Session {
+    const LEN: usize = 113;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 113];
+        let (is_initialized, account, owner, session_key, remaining_spend, expiry_slot) =
+            array_refs![src, 1, 32, 32, 32, 8, 8];
+        Ok(Session {
+            is_initialized: is_initialized[0] == 1,
+            account: Pubkey::new_from_array(*account),
+            owner: Pubkey::new_from_array(*owner),
+            session_key: Pubkey::new_from_array(*session_key),
+            remaining_spend: u64::from_le_bytes(*remaining_spend),
+            expiry_slot: u64::from_le_bytes(*expiry_slot),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 113];
+        let (is_initialized, account, owner, session_key, remaining_spend, expiry_slot) =
+            mut_array_refs![dst, 1, 32, 32, 32, 8, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        account.copy_from_slice(self.account.as_ref());
+        owner.copy_from_slice(self.owner.as_ref());
+        session_key.copy_from_slice(self.session_key.as_ref());
+        remaining_spend.copy_from_slice(&self.remaining_spend.to_le_bytes());
+        expiry_slot.copy_from_slice(&self.expiry_slot.to_le_bytes());
+    }
+}
+
+/// The features enabled in a bank, see `feature_gate`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FeatureGate {
+    pub is_initialized: bool,
+    pub bank: Pubkey,
+    /// Bitmask of the `feature_gate` features the bank runs.
+    pub enabled: u64,
+}
+
+impl Sealed for FeatureGate {}
+impl IsInitialized for FeatureGate {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for FeatureGate {
+    const LEN: usize = 41;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 41];
+        let (is_initialized, bank, enabled) = array_refs![src, 1, 32, 8];
+        Ok(FeatureGate {
+            is_initialized: is_initialized[0] == 1,
+            bank: Pubkey::new_from_array(*bank),
+            enabled: u64::from_le_bytes(*enabled),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 41];
+        let (is_initialized, bank, enabled) = mut_array_refs![dst, 1, 32, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        bank.copy_from_slice(self.bank.as_ref());
+        enabled.copy_from_slice(&self.enabled.to_le_bytes());
+    }
+}
+
+/// Which release of the program is deployed and which state layout it writes, see
+/// `version`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Version {
+    pub is_initialized: bool,
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+    pub layout: u16,
+}
+
+impl Sealed for Version {}
+impl IsInitialized for Version {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Version {
+    const LEN: usize = 9;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 9];
+        let (is_initialized, major, minor, patch, layout) = array_refs![src, 1, 2, 2, 2, 2];
+        Ok(Version {
+            is_initialized: is_initialized[0] == 1,
+            major: u16::from_le_bytes(*major),
+            minor: u16::from_le_bytes(*minor),
+            patch: u16::from_le_bytes(*patch),
+            layout: u16::from_le_bytes(*layout),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 9];
+        let (is_initialized, major, minor, patch, layout) = mut_array_refs![dst, 1, 2, 2, 2, 2];
+        is_initialized[0] = self.is_initialized as u8;
+        *major = self.major.to_le_bytes();
+        *minor = self.minor.to_le_bytes();
+        *patch = self.patch.to_le_bytes();
+        *layout = self.layout.to_le_bytes();
+    }
+}
+
+/// An m-of-n owner: `threshold` of its signers sign in its place, see `multisig`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Multisig {
+    pub is_initialized: bool,
+    /// Signers that must sign; at least 1 and at most `signer_count`.
+    pub threshold: u8,
+    pub signer_count: u8,
+    /// The first `signer_count` are the signers, the rest are unused.
+    pub signers: [Pubkey; MAX_SIGNERS],
+}
+
+impl Multisig {
+    pub fn signers(&self) -> &[Pubkey] {
+        &self.signers[..self.signer_count as usize]
+    }
+}
+
+impl Sealed for Multisig {}
+impl IsInitialized for Multisig {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Multisig {
+    const LEN: usize = 355;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 355];
+        let (is_initialized, threshold, signer_count, signers) = array_refs![src, 1, 1, 1, 352];
+        if signer_count[0] as usize > MAX_SIGNERS {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let mut keys = [Pubkey::default(); MAX_SIGNERS];
+        for (i, key) in keys.iter_mut().enumerate() {
+            *key = Pubkey::new_from_array(*array_ref![signers, i * 32, 32]);
+        }
+        Ok(Multisig {
+            is_initialized: is_initialized[0] == 1,
+            threshold: threshold[0],
+            signer_count: signer_count[0],
+            signers: keys,
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 355];
+        let (is_initialized, threshold, signer_count, signers) = mut_array_refs![dst, 1, 1, 1, 352];
+        is_initialized[0] = self.is_initialized as u8;
+        threshold[0] = self.threshold;
+        signer_count[0] = self.signer_count;
+        for (i, key) in self.signers.iter().enumerate() {
+            array_mut_ref![signers, i * 32, 32].copy_from_slice(key.as_ref());
+        }
     }
 }
 
@@ -125,6 +853,7 @@ fn pack_coption_key(src: &COption<Pubkey>, dst: &mut [u8; 36]) {
         }
         COption::None => {
             *tag = [0; 4];
+            *body = [0; 32];
         }
     }
 }
@@ -140,7 +869,12 @@ fn unpack_coption_key(src: &[u8; 36]) -> Result<COption<Pubkey>, ProgramError> {
 
 #[cfg(test)]
 mod tests {
-    use super::{pack_coption_key, Account, Bank};
+    use super::{
+        pack_coption_key, Account, Balances, Bank, BurnPolicy, ClosePolicy, ExchangeRate,
+        FeatureGate, Multisig, Recovery, Session, Version,
+    };
+    use proptest::prelude::*;
+    use solana_program::program_error::ProgramError;
     use solana_program::program_option::COption;
     use solana_program::program_pack::Pack;
     use solana_program::pubkey::Pubkey;
@@ -149,25 +883,79 @@ mod tests {
     #[test]
     fn test_bank_pack_unpack() {
         let bank_owner = Pubkey::default();
+        let emergency_authority = Pubkey::new_from_array([3; 32]);
+        let pending_bank_owner = Pubkey::new_from_array([4; 32]);
         let bank = Bank {
             decimals: 10,
             bank_owner,
             is_opened: true,
             total_supply: 100,
+            close_policy: ClosePolicy::Burn,
+            require_rent_exempt: true,
+            burn_policy: BurnPolicy::HolderOnly,
+            lamports_per_token: 1_000,
+            transfer_count: 7,
+            cumulative_volume: 350,
+            holder_count: 3,
+            registered_accounts: 4,
+            dust_threshold: 5,
+            freeze_authority: COption::Some(bank_owner),
+            is_paused: true,
+            interest_rate_bps: 250,
+            emergency_authority: COption::Some(emergency_authority),
+            owner_change_delay: 9,
+            pending_bank_owner: COption::Some(pending_bank_owner),
+            owner_change_slot: 11,
         };
-        let mut buf: Vec<u8> = vec![0; 42];
+        let mut buf: Vec<u8> = vec![0; 220];
         bank.pack_into_slice(&mut buf[..]);
 
         assert_eq!(buf[0], 10);
         assert_eq!(buf[1..33], *bank_owner.as_ref());
         assert_eq!(buf[33] == 1, true);
         assert_eq!(u64::from_le_bytes(buf[34..42].try_into().unwrap()), 100);
+        assert_eq!(buf[42], 2);
+        assert_eq!(buf[43], 1);
+        assert_eq!(buf[44], 1);
+        assert_eq!(u64::from_le_bytes(buf[45..53].try_into().unwrap()), 1_000);
+        assert_eq!(u64::from_le_bytes(buf[53..61].try_into().unwrap()), 7);
+        assert_eq!(u64::from_le_bytes(buf[61..69].try_into().unwrap()), 350);
+        assert_eq!(u64::from_le_bytes(buf[69..77].try_into().unwrap()), 3);
+        assert_eq!(u64::from_le_bytes(buf[77..85].try_into().unwrap()), 4);
+        assert_eq!(u64::from_le_bytes(buf[85..93].try_into().unwrap()), 5);
+        let mut c_option_buf = [0; 36];
+        pack_coption_key(&bank.freeze_authority, &mut c_option_buf);
+        assert_eq!(buf[93..129], c_option_buf);
+        assert_eq!(buf[129], 1);
+        assert_eq!(buf[130..132], u16::to_le_bytes(250));
+        pack_coption_key(&bank.emergency_authority, &mut c_option_buf);
+        assert_eq!(buf[132..168], c_option_buf);
+        assert_eq!(u64::from_le_bytes(buf[168..176].try_into().unwrap()), 9);
+        pack_coption_key(&bank.pending_bank_owner, &mut c_option_buf);
+        assert_eq!(buf[176..212], c_option_buf);
+        assert_eq!(u64::from_le_bytes(buf[212..220].try_into().unwrap()), 11);
 
         if let Ok(bank) = Bank::unpack_from_slice(&buf[..]) {
             assert_eq!(bank.decimals, 10);
             assert_eq!(bank.bank_owner, bank_owner);
             assert_eq!(bank.is_opened, true);
             assert_eq!(bank.total_supply, 100);
+            assert_eq!(bank.close_policy, ClosePolicy::Burn);
+            assert_eq!(bank.require_rent_exempt, true);
+            assert_eq!(bank.burn_policy, BurnPolicy::HolderOnly);
+            assert_eq!(bank.lamports_per_token, 1_000);
+            assert_eq!(bank.transfer_count, 7);
+            assert_eq!(bank.cumulative_volume, 350);
+            assert_eq!(bank.holder_count, 3);
+            assert_eq!(bank.registered_accounts, 4);
+            assert_eq!(bank.dust_threshold, 5);
+            assert_eq!(bank.freeze_authority, COption::Some(bank_owner));
+            assert_eq!(bank.is_paused, true);
+            assert_eq!(bank.interest_rate_bps, 250);
+            assert_eq!(bank.emergency_authority, COption::Some(emergency_authority));
+            assert_eq!(bank.owner_change_delay, 9);
+            assert_eq!(bank.pending_bank_owner, COption::Some(pending_bank_owner));
+            assert_eq!(bank.owner_change_slot, 11);
         } else {
             panic!("unpack failed")
         }
@@ -186,8 +974,11 @@ mod tests {
             delegate: COption::Some(account_delegate),
             delegated_amount: 50,
             bank,
+            permit_nonce: 7,
+            is_frozen: true,
+            last_accrual: 1_600_000_000,
         };
-        let mut buf: Vec<u8> = vec![0; 118];
+        let mut buf: Vec<u8> = vec![0; 135];
         account.pack_into_slice(&mut buf[..]);
         assert_eq!(buf[..8], u64::to_le_bytes(100));
         assert_eq!(buf[8], 1);
@@ -198,6 +989,9 @@ mod tests {
         assert_eq!(buf[42..78], c_option_buf);
         assert_eq!(buf[78..86], u64::to_le_bytes(50));
         assert_eq!(buf[86..118], bank.to_bytes());
+        assert_eq!(buf[118..126], u64::to_le_bytes(7));
+        assert_eq!(buf[126], 1);
+        assert_eq!(buf[127..135], i64::to_le_bytes(1_600_000_000));
 
         if let Ok(account) = Account::unpack_from_slice(&buf[..]) {
             assert_eq!(account.amount, 100);
@@ -208,8 +1002,370 @@ mod tests {
             assert_eq!(account.delegate, COption::Some(account_delegate));
             assert_eq!(account.delegated_amount, 50);
             assert_eq!(account.bank, bank);
+            assert_eq!(account.permit_nonce, 7);
+            assert_eq!(account.is_frozen, true);
+            assert_eq!(account.last_accrual, 1_600_000_000);
         } else {
             panic!("unpack failed")
         }
     }
+
+    #[test]
+    fn test_exchange_rate_pack_unpack() {
+        let rate = ExchangeRate {
+            is_initialized: true,
+            from_bank: Pubkey::new_from_array([1; 32]),
+            to_bank: Pubkey::new_from_array([2; 32]),
+            numerator: 3,
+            denominator: 4,
+        };
+        let mut buf = vec![0; ExchangeRate::LEN];
+        rate.pack_into_slice(&mut buf);
+        assert_eq!(buf[0], 1);
+        assert_eq!(buf[1..33], [1; 32]);
+        assert_eq!(buf[33..65], [2; 32]);
+        assert_eq!(buf[65..73], u64::to_le_bytes(3));
+        assert_eq!(buf[73..81], u64::to_le_bytes(4));
+        assert_eq!(ExchangeRate::unpack(&buf), Ok(rate));
+
+        buf[0] = 0;
+        assert_eq!(
+            ExchangeRate::unpack(&buf),
+            Err(ProgramError::UninitializedAccount)
+        );
+    }
+
+    #[test]
+    fn test_recovery_pack_unpack() {
+        let mut guardians = [Pubkey::default(); 8];
+        guardians[0] = Pubkey::new_from_array([3; 32]);
+        guardians[1] = Pubkey::new_from_array([4; 32]);
+        let recovery = Recovery {
+            is_initialized: true,
+            account: Pubkey::new_from_array([1; 32]),
+            owner: Pubkey::new_from_array([2; 32]),
+            threshold: 1,
+            guardian_count: 2,
+            guardians,
+            delay: 5,
+            pending_owner: COption::Some(Pubkey::new_from_array([6; 32])),
+            activation_slot: 7,
+        };
+        assert_eq!(recovery.guardians(), &guardians[..2]);
+        let mut buf = vec![0; Recovery::LEN];
+        recovery.pack_into_slice(&mut buf);
+        assert_eq!(buf[0], 1);
+        assert_eq!(buf[1..33], [1; 32]);
+        assert_eq!(buf[33..65], [2; 32]);
+        assert_eq!(buf[65..67], [1, 2]);
+        assert_eq!(buf[67..99], [3; 32]);
+        assert_eq!(buf[99..131], [4; 32]);
+        assert_eq!(buf[131..323], [0; 192]);
+        assert_eq!(buf[323..331], u64::to_le_bytes(5));
+        assert_eq!(buf[331..335], [1, 0, 0, 0]);
+        assert_eq!(buf[335..367], [6; 32]);
+        assert_eq!(buf[367..375], u64::to_le_bytes(7));
+        assert_eq!(Recovery::unpack(&buf), Ok(recovery));
+
+        buf[66] = 9;
+        assert_eq!(
+            Recovery::unpack(&buf),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_multisig_pack_unpack() {
+        let mut signers = [Pubkey::default(); 11];
+        signers[0] = Pubkey::new_from_array([1; 32]);
+        signers[1] = Pubkey::new_from_array([2; 32]);
+        let multisig = Multisig {
+            is_initialized: true,
+            threshold: 2,
+            signer_count: 2,
+            signers,
+        };
+        assert_eq!(multisig.signers(), &signers[..2]);
+        let mut buf = vec![0; Multisig::LEN];
+        multisig.pack_into_slice(&mut buf);
+        assert_eq!(buf[..3], [1, 2, 2]);
+        assert_eq!(buf[3..35], [1; 32]);
+        assert_eq!(buf[35..67], [2; 32]);
+        assert_eq!(buf[67..355], [0; 288]);
+        assert_eq!(Multisig::unpack(&buf), Ok(multisig));
+
+        buf[2] = 12;
+        assert_eq!(
+            Multisig::unpack(&buf),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_session_pack_unpack() {
+        let session = Session {
+            is_initialized: true,
+            account: Pubkey::new_from_array([1; 32]),
+            owner: Pubkey::new_from_array([2; 32]),
+            session_key: Pubkey::new_from_array([3; 32]),
+            remaining_spend: 4,
+            expiry_slot: 5,
+        };
+        let mut buf = vec![0; Session::LEN];
+        session.pack_into_slice(&mut buf);
+        assert_eq!(buf[0], 1);
+        assert_eq!(buf[1..33], [1; 32]);
+        assert_eq!(buf[33..65], [2; 32]);
+        assert_eq!(buf[65..97], [3; 32]);
+        assert_eq!(buf[97..105], u64::to_le_bytes(4));
+        assert_eq!(buf[105..113], u64::to_le_bytes(5));
+        assert_eq!(Session::unpack(&buf), Ok(session));
+    }
+
+    #[test]
+    fn test_feature_gate_pack_unpack() {
+        let gate = FeatureGate {
+            is_initialized: true,
+            bank: Pubkey::new_from_array([1; 32]),
+            enabled: 5,
+        };
+        let mut buf = vec![0; FeatureGate::LEN];
+        gate.pack_into_slice(&mut buf);
+        assert_eq!(buf[0], 1);
+        assert_eq!(buf[1..33], [1; 32]);
+        assert_eq!(buf[33..41], u64::to_le_bytes(5));
+        assert_eq!(FeatureGate::unpack(&buf), Ok(gate));
+    }
+
+    #[test]
+    fn test_version_pack_unpack() {
+        let version = Version {
+            is_initialized: true,
+            major: 1,
+            minor: 2,
+            patch: 3,
+            layout: 4,
+        };
+        let mut buf = vec![0; Version::LEN];
+        version.pack_into_slice(&mut buf);
+        assert_eq!(buf, [1, 1, 0, 2, 0, 3, 0, 4, 0]);
+        assert_eq!(Version::unpack(&buf), Ok(version));
+    }
+
+    fn arb_pubkey() -> impl Strategy<Value = Pubkey> {
+        any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+    }
+
+    fn arb_bank() -> impl Strategy<Value = Bank> {
+        let close_policy = prop_oneof![
+            Just(ClosePolicy::Reject),
+            Just(ClosePolicy::Sweep),
+            Just(ClosePolicy::Burn),
+        ];
+        let burn_policy = prop_oneof![
+            Just(BurnPolicy::OwnerAndHolder),
+            Just(BurnPolicy::HolderOnly),
+        ];
+        let freeze_authority =
+            prop_oneof![Just(COption::None), arb_pubkey().prop_map(COption::Some)];
+        let emergency_authority =
+            prop_oneof![Just(COption::None), arb_pubkey().prop_map(COption::Some)];
+        let pending_bank_owner =
+            prop_oneof![Just(COption::None), arb_pubkey().prop_map(COption::Some)];
+        (
+            any::<u8>(),
+            arb_pubkey(),
+            any::<bool>(),
+            any::<u64>(),
+            close_policy,
+            any::<bool>(),
+            burn_policy,
+            any::<u64>(),
+            // Proptest's tuples stop at 12, so the counters come as one.
+            (any::<u64>(), any::<u64>(), any::<u64>(), any::<u64>()),
+            any::<u64>(),
+            freeze_authority,
+            (
+                any::<bool>(),
+                any::<u16>(),
+                emergency_authority,
+                any::<u64>(),
+                pending_bank_owner,
+                any::<u64>(),
+            ),
+        )
+            .prop_map(
+                |(
+                    decimals,
+                    bank_owner,
+                    is_opened,
+                    total_supply,
+                    close_policy,
+                    require_rent_exempt,
+                    burn_policy,
+                    lamports_per_token,
+                    (transfer_count, cumulative_volume, holder_count, registered_accounts),
+                    dust_threshold,
+                    freeze_authority,
+                    (
+                        is_paused,
+                        interest_rate_bps,
+                        emergency_authority,
+                        owner_change_delay,
+                        pending_bank_owner,
+                        owner_change_slot,
+                    ),
+                )| {
+                    Bank {
+                        decimals,
+                        bank_owner,
+                        is_opened,
+                        total_supply,
+                        close_policy,
+                        require_rent_exempt,
+                        burn_policy,
+                        lamports_per_token,
+                        transfer_count,
+                        cumulative_volume,
+                        holder_count,
+                        registered_accounts,
+                        dust_threshold,
+                        freeze_authority,
+                        is_paused,
+                        interest_rate_bps,
+                        emergency_authority,
+                        owner_change_delay,
+                        pending_bank_owner,
+                        owner_change_slot,
+                    }
+                },
+            )
+    }
+
+    fn arb_account() -> impl Strategy<Value = Account> {
+        let delegate = prop_oneof![Just(COption::None), arb_pubkey().prop_map(COption::Some)];
+        (
+            any::<u64>(),
+            any::<bool>(),
+            any::<bool>(),
+            arb_pubkey(),
+            delegate,
+            any::<u64>(),
+            arb_pubkey(),
+            any::<u64>(),
+            any::<bool>(),
+            any::<i64>(),
+        )
+            .prop_map(
+                |(
+                    amount,
+                    is_opened,
+                    is_initialized,
+                    owner,
+                    delegate,
+                    delegated_amount,
+                    bank,
+                    permit_nonce,
+                    is_frozen,
+                    last_accrual,
+                )| {
+                    Account {
+                        amount,
+                        is_opened,
+                        is_initialized,
+                        owner,
+                        delegate,
+                        delegated_amount,
+                        bank,
+                        permit_nonce,
+                        is_frozen,
+                        last_accrual,
+                    }
+                },
+            )
+    }
+
+    proptest! {
+        // Packing over leftover bytes must overwrite every field, false booleans included.
+        #[test]
+        fn test_bank_round_trip(
+            bank in arb_bank(),
+            noise in proptest::collection::vec(any::<u8>(), Bank::LEN),
+        ) {
+            let mut buf = noise;
+            Bank::pack_into_slice(&bank, &mut buf);
+            prop_assert_eq!(Bank::unpack_unchecked(&buf).unwrap(), bank);
+        }
+
+        #[test]
+        fn test_account_round_trip(
+            account in arb_account(),
+            noise in proptest::collection::vec(any::<u8>(), Account::LEN),
+        ) {
+            let mut buf = noise;
+            Account::pack_into_slice(&account, &mut buf);
+            prop_assert_eq!(Account::unpack_unchecked(&buf).unwrap(), account);
+        }
+
+        #[test]
+        fn test_account_in_place(account in arb_account(), amount in any::<u64>()) {
+            let mut buf = vec![0; Account::LEN];
+            Account::pack_into_slice(&account, &mut buf);
+            if account.is_initialized {
+                prop_assert_eq!(Account::check_initialized(&buf), Ok(()));
+            } else {
+                prop_assert_eq!(
+                    Account::check_initialized(&buf),
+                    Err(ProgramError::UninitializedAccount)
+                );
+            }
+            prop_assert_eq!(Account::unpack_is_opened(&buf), account.is_opened);
+            prop_assert_eq!(Account::unpack_amount(&buf), account.amount);
+            prop_assert_eq!(Account::unpack_owner(&buf), account.owner);
+            prop_assert_eq!(Account::unpack_delegate(&buf), Ok(account.delegate));
+            prop_assert_eq!(Account::unpack_delegated_amount(&buf), account.delegated_amount);
+            prop_assert_eq!(Account::unpack_bank_bytes(&buf), &account.bank.to_bytes());
+            prop_assert_eq!(Account::unpack_permit_nonce(&buf), account.permit_nonce);
+            prop_assert_eq!(Account::unpack_is_frozen(&buf), account.is_frozen);
+            prop_assert_eq!(Account::unpack_last_accrual(&buf), account.last_accrual);
+
+            prop_assert_eq!(
+                Account::check_can_trade(&buf).is_ok(),
+                account.can_trade()
+            );
+
+            let balances = Balances {
+                amount,
+                delegated_amount: amount / 2,
+            };
+            Account::pack_balances(&mut buf, &balances);
+            prop_assert_eq!(Account::unpack_balances(&buf), balances);
+            Account::pack_delegate(&mut buf, &COption::None);
+            Account::pack_is_opened(&mut buf, !account.is_opened);
+            Account::pack_permit_nonce(&mut buf, amount);
+            Account::pack_is_frozen(&mut buf, !account.is_frozen);
+            Account::pack_last_accrual(&mut buf, amount as i64);
+            Account::pack_owner(&mut buf, &account.bank);
+            let expected = Account {
+                amount,
+                delegated_amount: amount / 2,
+                delegate: COption::None,
+                is_opened: !account.is_opened,
+                permit_nonce: amount,
+                is_frozen: !account.is_frozen,
+                last_accrual: amount as i64,
+                owner: account.bank,
+                ..account
+            };
+            prop_assert_eq!(Account::unpack_unchecked(&buf).unwrap(), expected);
+        }
+
+        #[test]
+        fn test_unpack_noise(noise in proptest::collection::vec(any::<u8>(), 0..200)) {
+            let _ = Bank::unpack_unchecked(&noise);
+            let _ = Bank::unpack(&noise);
+            let _ = Account::unpack_unchecked(&noise);
+            let _ = Account::unpack(&noise);
+        }
+    }
 }
@@ -0,0 +1,987 @@
+//! In-memory harness that runs bank instructions straight through
+//! `Processor::process`, for the program's own tests and for programs that CPI
+//! into the bank. Enabled by the `test-utils` feature, which also brings in the
+//! `ProgramTest` environment of `program_test` and its `clock`.
+
+pub mod clock;
+pub mod program_test;
+
+use crate::{
+    feature_gate,
+    instruction::{
+        accrue_interest, approve, assign_to_program, burn, burn_by_holder, cancel_recovery,
+        close_account, close_account_with_balance, close_dust_account, create_session_key,
+        deposit_sol, initialize_account, initialize_bank, initialize_bank_with_burn_policy,
+        initialize_bank_with_sol_rate, initialize_version, mint_to, pause_bank, resume_bank,
+        revoke, set_close_policy, set_dust_threshold, set_guardians, transfer, transfer_route,
+        transfer_sweeping_dust, withdraw_sol,
+    },
+    processor::Processor,
+    recovery, session,
+    state::{Account, Bank, BurnPolicy, ClosePolicy, FeatureGate, Recovery, Session, Version},
+    version,
+};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::{ProgramResult, SUCCESS},
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_pack::Pack,
+    program_stubs,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+};
+use solana_sdk::account::Account as SolanaAccount;
+
+/// Sets up a `TestSuite` with an opened bank, initialized accounts and minted
+/// balances:
+///
+/// ```ignore
+/// let mut suite = TestSuite::builder().accounts(2).initialized(8).mint(0, 100).build()?;
+/// suite.process_transfer(0, 1, 40)?;
+/// ```
+pub struct TestSuiteBuilder {
+    program_id: Pubkey,
+    lamports: u64,
+    accounts: u64,
+    decimals: Option<u8>,
+    mints: Vec<(usize, u64)>,
+}
+
+impl TestSuiteBuilder {
+    /// Use the id the bank is deployed under in the caller's own tests.
+    pub fn program_id(mut self, program_id: Pubkey) -> Self {
+        self.program_id = program_id;
+        self
+    }
+
+    /// Lamports given to every account the suite creates.
+    pub fn lamports(mut self, lamports: u64) -> Self {
+        self.lamports = lamports;
+        self
+    }
+
+    pub fn accounts(mut self, num: u64) -> Self {
+        self.accounts = num;
+        self
+    }
+
+    /// Initialize the bank with `decimals` and every account of it.
+    pub fn initialized(mut self, decimals: u8) -> Self {
+        self.decimals = Some(decimals);
+        self
+    }
+
+    /// Mint `amount` to account `i`; needs `initialized`.
+    pub fn mint(mut self, i: usize, amount: u64) -> Self {
+        self.mints.push((i, amount));
+        self
+    }
+
+    pub fn build(self) -> Result<TestSuite, ProgramError> {
+        let mut suite = TestSuite::with_program_id(self.program_id, self.lamports);
+        suite.add_default_bank_accounts(self.accounts);
+        if let Some(decimals) = self.decimals {
+            suite.process_init_bank_instruction(decimals)?;
+            suite.process_init_all_accounts()?;
+        }
+        for (i, amount) in self.mints {
+            suite.process_mint_to(i, amount)?;
+        }
+        Ok(suite)
+    }
+}
+
+pub struct TestSuite {
+    pub program_id: Pubkey,
+    pub bank_info: (Pubkey, SolanaAccount),
+    pub bank_owner_info: (Pubkey, SolanaAccount),
+    pub bank_accounts_info: Vec<(Pubkey, SolanaAccount)>,
+    pub bank_accounts_owner_info: Vec<(Pubkey, SolanaAccount)>,
+    /// The bank's feature gate, which gated instructions take; not created until
+    /// `process_set_features`.
+    pub feature_gate_info: (Pubkey, SolanaAccount),
+    lamports: u64,
+}
+
+impl TestSuite {
+    pub fn new_key_account(lamports: u64) -> (Pubkey, SolanaAccount) {
+        (
+            Pubkey::new_unique(),
+            SolanaAccount::new(lamports, Account::get_packed_len(), &system_program::ID),
+        )
+    }
+
+    pub fn builder() -> TestSuiteBuilder {
+        TestSuiteBuilder {
+            program_id: Pubkey::new_unique(),
+            lamports: 64,
+            accounts: 0,
+            decimals: None,
+            mints: Vec::new(),
+        }
+    }
+
+    /// An uninitialized bank owned by a fresh program id, with no accounts yet.
+    pub fn default(lamports: u64) -> TestSuite {
+        TestSuite::with_program_id(Pubkey::new_unique(), lamports)
+    }
+
+    fn with_program_id(program_id: Pubkey, lamports: u64) -> TestSuite {
+        let bank = Pubkey::new_unique();
+        TestSuite {
+            program_id,
+            bank_info: (
+                bank,
+                SolanaAccount::new(lamports, Bank::get_packed_len(), &program_id),
+            ),
+            bank_owner_info: (
+                Pubkey::new_unique(),
+                SolanaAccount::new(lamports, Bank::get_packed_len(), &system_program::ID),
+            ),
+            bank_accounts_info: Vec::with_capacity(2),
+            bank_accounts_owner_info: Vec::with_capacity(2),
+            feature_gate_info: (
+                feature_gate::address(&program_id, &bank).0,
+                SolanaAccount::default(),
+            ),
+            lamports,
+        }
+    }
+
+    pub fn add_default_bank_accounts(&mut self, num: u64) -> &mut Self {
+        for _ in 0..num {
+            self.bank_accounts_info.push((
+                Pubkey::new_unique(),
+                SolanaAccount::new(self.lamports, Account::get_packed_len(), &self.program_id),
+            ));
+            self.bank_accounts_owner_info.push((
+                Pubkey::new_unique(),
+                SolanaAccount::new(
+                    self.lamports,
+                    Account::get_packed_len(),
+                    &system_program::ID,
+                ),
+            ));
+        }
+        self
+    }
+
+    pub fn init_bank_instruction(&self, decimal: u8) -> Result<Instruction, ProgramError> {
+        initialize_bank(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_owner_info.0,
+            decimal,
+        )
+    }
+
+    pub fn bank_eq(&self, expect_bank: &Bank) -> Result<bool, ProgramError> {
+        let bank = Bank::unpack_unchecked(&self.bank_info.1.data)?;
+        Ok(expect_bank.eq(&bank))
+    }
+
+    pub fn account_eq(&self, i: usize, expect_account: &Account) -> Result<bool, ProgramError> {
+        if i >= self.bank_accounts_info.len() {
+            return Err(ProgramError::Custom(000));
+        }
+
+        let account = Account::unpack_unchecked(&self.bank_accounts_info[i].1.data)?;
+        Ok(expect_account.eq(&account))
+    }
+
+    pub fn process_init_bank_instruction(&mut self, decimal: u8) -> ProgramResult {
+        let instruction = self.init_bank_instruction(decimal).unwrap();
+        do_process_instruction(
+            instruction,
+            vec![&mut self.bank_info.1, &mut self.bank_owner_info.1],
+        )
+    }
+
+    pub fn process_init_bank_with_burn_policy(
+        &mut self,
+        decimal: u8,
+        burn_policy: BurnPolicy,
+    ) -> ProgramResult {
+        let instruction = initialize_bank_with_burn_policy(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_owner_info.0,
+            decimal,
+            burn_policy,
+        )?;
+        do_process_instruction(
+            instruction,
+            vec![&mut self.bank_info.1, &mut self.bank_owner_info.1],
+        )
+    }
+
+    pub fn process_init_bank_with_sol_rate(
+        &mut self,
+        decimal: u8,
+        lamports_per_token: u64,
+    ) -> ProgramResult {
+        let instruction = initialize_bank_with_sol_rate(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_owner_info.0,
+            decimal,
+            BurnPolicy::default(),
+            lamports_per_token,
+        )?;
+        do_process_instruction(
+            instruction,
+            vec![&mut self.bank_info.1, &mut self.bank_owner_info.1],
+        )
+    }
+
+    pub fn process_init_bank_account_instruction(&mut self, i: usize) -> ProgramResult {
+        self.check_index(i)?;
+        let instruction = initialize_account(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[i].0,
+            &self.bank_accounts_owner_info[i].0,
+        )?;
+        do_process_instruction(
+            instruction,
+            vec![
+                &mut self.bank_info.1,
+                &mut self.bank_accounts_info[i].1,
+                &mut self.bank_accounts_owner_info[i].1,
+            ],
+        )
+    }
+
+    pub fn process_init_all_accounts(&mut self) -> ProgramResult {
+        for i in 0..self.bank_accounts_info.len() {
+            self.process_init_bank_account_instruction(i)?;
+        }
+        Ok(())
+    }
+
+    pub fn process_mint_to(&mut self, i: usize, amount: u64) -> ProgramResult {
+        self.check_index(i)?;
+
+        let instruction = mint_to(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[i].0,
+            &self.bank_owner_info.0,
+            amount,
+        )?;
+        do_process_instruction(
+            instruction,
+            vec![
+                &mut self.bank_info.1,
+                &mut self.bank_accounts_info[i].1,
+                &mut self.bank_owner_info.1,
+            ],
+        )
+    }
+
+    pub fn process_transfer(&mut self, from: usize, to: usize, amount: u64) -> ProgramResult {
+        self.transfer(from, to, amount, false, true)
+    }
+
+    /// Transfers with the bank passed read-only, as SPL tooling does.
+    pub fn process_transfer_uncounted(
+        &mut self,
+        from: usize,
+        to: usize,
+        amount: u64,
+    ) -> ProgramResult {
+        self.transfer(from, to, amount, false, false)
+    }
+
+    pub fn process_transfer_sweeping_dust(
+        &mut self,
+        from: usize,
+        to: usize,
+        amount: u64,
+    ) -> ProgramResult {
+        self.transfer(from, to, amount, true, true)
+    }
+
+    fn transfer(
+        &mut self,
+        from: usize,
+        to: usize,
+        amount: u64,
+        sweep_dust: bool,
+        bank_writable: bool,
+    ) -> ProgramResult {
+        self.check_index(from)?;
+        self.check_index(to)?;
+
+        let build = if sweep_dust {
+            transfer_sweeping_dust
+        } else {
+            transfer
+        };
+        let mut instruction = build(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[from].0,
+            &self.bank_accounts_info[to].0,
+            &self.bank_accounts_owner_info[from].0,
+            amount,
+        )?;
+        instruction.accounts[3].is_writable = bank_writable;
+        let mut from_acc = self.bank_accounts_info[from].1.clone();
+        let mut to_acc = self.bank_accounts_info[to].1.clone();
+        do_process_instruction(
+            instruction,
+            vec![
+                &mut from_acc,
+                &mut to_acc,
+                &mut self.bank_owner_info.1,
+                &mut self.bank_info.1,
+            ],
+        )?;
+        self.bank_accounts_info[from].1 = from_acc;
+        self.bank_accounts_info[to].1 = to_acc;
+        Ok(())
+    }
+
+    /// Routes `amount` through the accounts at `route`, in order, each hop signed by its
+    /// sending account's owner. The accounts must be distinct.
+    pub fn process_transfer_route(&mut self, route: &[usize], amount: u64) -> ProgramResult {
+        for &i in route {
+            self.check_index(i)?;
+        }
+        let (&destination, senders) = route.split_last().ok_or(ProgramError::InvalidArgument)?;
+        let hops = senders
+            .iter()
+            .map(|&i| {
+                (
+                    self.bank_accounts_info[i].0,
+                    self.bank_accounts_owner_info[i].0,
+                )
+            })
+            .collect::<Vec<_>>();
+        let instruction = transfer_route(
+            &self.program_id,
+            &self.bank_info.0,
+            &hops,
+            &self.bank_accounts_info[destination].0,
+            amount,
+        )?;
+        let mut accounts = route
+            .iter()
+            .map(|&i| self.bank_accounts_info[i].1.clone())
+            .collect::<Vec<_>>();
+        let mut owners = senders
+            .iter()
+            .map(|&i| self.bank_accounts_owner_info[i].1.clone())
+            .collect::<Vec<_>>();
+        let (last, hop_accounts) = accounts.split_last_mut().unwrap();
+        let mut metas = vec![&mut self.bank_info.1];
+        for (account, owner) in hop_accounts.iter_mut().zip(owners.iter_mut()) {
+            metas.push(account);
+            metas.push(owner);
+        }
+        metas.push(last);
+        metas.push(&mut self.feature_gate_info.1);
+        do_process_instruction(instruction, metas)?;
+        for (&i, account) in route.iter().zip(accounts) {
+            self.bank_accounts_info[i].1 = account;
+        }
+        Ok(())
+    }
+
+    pub fn process_transfer_delegate(
+        &mut self,
+        from: usize,
+        delegate: (&Pubkey, &mut SolanaAccount),
+        to: usize,
+        amount: u64,
+    ) -> ProgramResult {
+        self.check_index(from)?;
+        self.check_index(to)?;
+
+        let instruction = transfer(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[from].0,
+            &self.bank_accounts_info[to].0,
+            delegate.0,
+            amount,
+        )?;
+        let mut from_acc = self.bank_accounts_info[from].1.clone();
+        let mut to_acc = self.bank_accounts_info[to].1.clone();
+        do_process_instruction(
+            instruction,
+            vec![
+                &mut from_acc,
+                &mut to_acc,
+                delegate.1,
+                &mut self.bank_info.1,
+            ],
+        )?;
+        self.bank_accounts_info[from].1 = from_acc;
+        self.bank_accounts_info[to].1 = to_acc;
+        Ok(())
+    }
+
+    pub fn process_approve(
+        &mut self,
+        i: usize,
+        delegate_key: (&Pubkey, &mut SolanaAccount),
+        delegate_amount: u64,
+    ) -> ProgramResult {
+        self.check_index(i)?;
+
+        let instruction = approve(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[i].0,
+            delegate_key.0,
+            &self.bank_accounts_owner_info[i].0,
+            delegate_amount,
+        )?;
+
+        do_process_instruction(
+            instruction,
+            vec![
+                &mut self.bank_accounts_info[i].1,
+                delegate_key.1,
+                &mut self.bank_accounts_owner_info[i].1,
+                &mut self.bank_info.1,
+            ],
+        )
+    }
+
+    pub fn process_revoke(&mut self, i: usize) -> ProgramResult {
+        self.check_index(i)?;
+        let instruction = revoke(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[i].0,
+            &self.bank_accounts_owner_info[i].0,
+        )?;
+
+        do_process_instruction(
+            instruction,
+            vec![
+                &mut self.bank_accounts_info[i].1,
+                &mut self.bank_accounts_owner_info[i].1,
+                &mut self.bank_info.1,
+            ],
+        )
+    }
+
+    /// Hands account `i` over to the address `seeds` derive from `program`.
+    pub fn process_assign_to_program(
+        &mut self,
+        i: usize,
+        program: &Pubkey,
+        seeds: &[&[u8]],
+    ) -> ProgramResult {
+        self.check_index(i)?;
+        let instruction = assign_to_program(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[i].0,
+            &self.bank_accounts_owner_info[i].0,
+            program,
+            seeds,
+        )?;
+
+        do_process_instruction(
+            instruction,
+            vec![
+                &mut self.bank_accounts_info[i].1,
+                &mut self.bank_accounts_owner_info[i].1,
+                &mut self.bank_info.1,
+                &mut SolanaAccount::default(),
+            ],
+        )
+    }
+
+    /// The recovery state of account `i`, not yet set. The harness runs no CPI, so it's
+    /// owned by the program already rather than created by `SetGuardians`.
+    pub fn new_recovery_account(&self, i: usize) -> (Pubkey, SolanaAccount) {
+        let (address, _) = recovery::address(&self.program_id, &self.bank_accounts_info[i].0);
+        (
+            address,
+            SolanaAccount::new(self.lamports, Recovery::LEN, &self.program_id),
+        )
+    }
+
+    /// Names the guardians of account `i`, its owner paying for `recovery`.
+    pub fn process_set_guardians(
+        &mut self,
+        i: usize,
+        recovery: &mut SolanaAccount,
+        guardians: &[Pubkey],
+        threshold: u8,
+        delay: u64,
+    ) -> ProgramResult {
+        self.check_index(i)?;
+        let instruction = set_guardians(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[i].0,
+            &self.bank_accounts_owner_info[i].0,
+            &self.bank_accounts_owner_info[i].0,
+            guardians,
+            threshold,
+            delay,
+        )?;
+
+        do_process_instruction(
+            instruction,
+            vec![
+                &mut self.bank_accounts_info[i].1,
+                &mut self.bank_accounts_owner_info[i].1,
+                &mut self.bank_info.1,
+                recovery,
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+            ],
+        )
+    }
+
+    pub fn process_cancel_recovery(
+        &mut self,
+        i: usize,
+        recovery: &mut SolanaAccount,
+    ) -> ProgramResult {
+        self.check_index(i)?;
+        let instruction = cancel_recovery(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[i].0,
+            &self.bank_accounts_owner_info[i].0,
+        )?;
+
+        do_process_instruction(
+            instruction,
+            vec![
+                &mut self.bank_accounts_info[i].1,
+                &mut self.bank_accounts_owner_info[i].1,
+                &mut self.bank_info.1,
+                recovery,
+            ],
+        )
+    }
+
+    /// The state of account `i`'s session key `session_key`, not yet created. Like
+    /// `new_recovery_account`, it's owned by the program already.
+    pub fn new_session_account(&self, i: usize, session_key: &Pubkey) -> (Pubkey, SolanaAccount) {
+        let (address, _) =
+            session::address(&self.program_id, &self.bank_accounts_info[i].0, session_key);
+        (
+            address,
+            SolanaAccount::new(self.lamports, Session::LEN, &self.program_id),
+        )
+    }
+
+    /// Creates the session key `session_key` of account `i`, its owner paying for
+    /// `session`.
+    pub fn process_create_session_key(
+        &mut self,
+        i: usize,
+        session: &mut SolanaAccount,
+        session_key: &Pubkey,
+        max_spend: u64,
+        expiry_slot: u64,
+    ) -> ProgramResult {
+        self.check_index(i)?;
+        let instruction = create_session_key(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[i].0,
+            &self.bank_accounts_owner_info[i].0,
+            &self.bank_accounts_owner_info[i].0,
+            session_key,
+            max_spend,
+            expiry_slot,
+        )?;
+
+        do_process_instruction(
+            instruction,
+            vec![
+                &mut self.bank_accounts_info[i].1,
+                &mut self.bank_accounts_owner_info[i].1,
+                &mut self.bank_info.1,
+                session,
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+            ],
+        )
+    }
+
+    /// The program's version record, already owned by the program: the harness runs no
+    /// CPI, so `InitializeVersion` can't create it.
+    pub fn new_version_account(&self) -> (Pubkey, SolanaAccount) {
+        let (address, _) = version::address(&self.program_id);
+        (
+            address,
+            SolanaAccount::new(self.lamports, Version::LEN, &self.program_id),
+        )
+    }
+
+    /// Writes this build's version into `record`, the bank owner paying for it.
+    pub fn process_initialize_version(&mut self, record: &mut SolanaAccount) -> ProgramResult {
+        let instruction = initialize_version(&self.program_id, &self.bank_owner_info.0)?;
+
+        do_process_instruction(
+            instruction,
+            vec![
+                record,
+                &mut self.bank_owner_info.1,
+                &mut SolanaAccount::default(),
+            ],
+        )
+    }
+
+    /// Enables the features `enabled` in the bank, the bank owner paying for its gate.
+    /// The harness runs no CPI, so the gate is handed to the program up front.
+    pub fn process_set_features(&mut self, enabled: u64) -> ProgramResult {
+        let instruction = set_features(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_owner_info.0,
+            &self.bank_owner_info.0,
+            enabled,
+        )?;
+        let gate = &mut self.feature_gate_info.1;
+        if gate.owner != self.program_id {
+            *gate = SolanaAccount::new(self.lamports, FeatureGate::LEN, &self.program_id);
+        }
+
+        do_process_instruction(
+            instruction,
+            vec![
+                &mut self.bank_info.1,
+                &mut self.bank_owner_info.1,
+                &mut self.feature_gate_info.1,
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+            ],
+        )
+    }
+
+    /// Deposits `lamports` from `depositor` into account `i`. The harness runs no CPI, so
+    /// the system program's transfer does nothing here.
+    pub fn process_deposit_sol(
+        &mut self,
+        i: usize,
+        depositor: (&Pubkey, &mut SolanaAccount),
+        lamports: u64,
+    ) -> ProgramResult {
+        self.check_index(i)?;
+        let instruction = deposit_sol(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[i].0,
+            depositor.0,
+            lamports,
+        )?;
+
+        do_process_instruction(
+            instruction,
+            vec![
+                &mut self.bank_info.1,
+                &mut self.bank_accounts_info[i].1,
+                depositor.1,
+                &mut SolanaAccount::default(),
+            ],
+        )
+    }
+
+    pub fn process_withdraw_sol(
+        &mut self,
+        i: usize,
+        destination: (&Pubkey, &mut SolanaAccount),
+        lamports: u64,
+    ) -> ProgramResult {
+        self.check_index(i)?;
+        let instruction = withdraw_sol(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[i].0,
+            &self.bank_accounts_owner_info[i].0,
+            destination.0,
+            lamports,
+        )?;
+
+        do_process_instruction(
+            instruction,
+            vec![
+                &mut self.bank_info.1,
+                &mut self.bank_accounts_info[i].1,
+                &mut self.bank_accounts_owner_info[i].1,
+                destination.1,
+            ],
+        )
+    }
+
+    pub fn process_burn(&mut self, i: usize, burn_amount: u64) -> ProgramResult {
+        self.check_index(i)?;
+        let instruction = burn(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[i].0,
+            &self.bank_owner_info.0,
+            &self.bank_accounts_owner_info[i].0,
+            burn_amount,
+        )?;
+
+        do_process_instruction(
+            instruction,
+            vec![
+                &mut self.bank_info.1,
+                &mut self.bank_accounts_info[i].1,
+                &mut self.bank_owner_info.1,
+                &mut self.bank_accounts_owner_info[i].1,
+            ],
+        )
+    }
+
+    /// Burns without the bank owner, as a `BurnPolicy::HolderOnly` bank allows.
+    pub fn process_burn_by_holder(&mut self, i: usize, burn_amount: u64) -> ProgramResult {
+        self.check_index(i)?;
+        let instruction = burn_by_holder(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[i].0,
+            &self.bank_accounts_owner_info[i].0,
+            burn_amount,
+        )?;
+
+        do_process_instruction(
+            instruction,
+            vec![
+                &mut self.bank_info.1,
+                &mut self.bank_accounts_info[i].1,
+                &mut self.bank_accounts_owner_info[i].1,
+            ],
+        )
+    }
+
+    pub fn process_close(
+        &mut self,
+        i: usize,
+        destination: (&Pubkey, &mut SolanaAccount),
+    ) -> ProgramResult {
+        self.check_index(i)?;
+        let instruction = close_account(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[i].0,
+            destination.0,
+            &self.bank_accounts_owner_info[i].0,
+        )?;
+
+        do_process_instruction(
+            instruction,
+            vec![
+                &mut self.bank_accounts_info[i].1,
+                destination.1,
+                &mut self.bank_accounts_owner_info[i].1,
+                &mut self.bank_info.1,
+            ],
+        )
+    }
+
+    /// Closes account `i` whatever it holds, passing account `sweep` for
+    /// `ClosePolicy::Sweep` to credit.
+    pub fn process_close_with_balance(
+        &mut self,
+        i: usize,
+        destination: (&Pubkey, &mut SolanaAccount),
+        sweep: Option<usize>,
+    ) -> ProgramResult {
+        self.check_index(i)?;
+        if let Some(sweep) = sweep {
+            self.check_index(sweep)?;
+        }
+        let instruction = close_account_with_balance(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[i].0,
+            destination.0,
+            &self.bank_accounts_owner_info[i].0,
+            sweep.map(|sweep| &self.bank_accounts_info[sweep].0),
+        )?;
+
+        let mut closed_acc = self.bank_accounts_info[i].1.clone();
+        let mut sweep_acc = sweep.map(|sweep| self.bank_accounts_info[sweep].1.clone());
+        let mut accounts = vec![
+            &mut closed_acc,
+            destination.1,
+            &mut self.bank_accounts_owner_info[i].1,
+            &mut self.bank_info.1,
+        ];
+        accounts.extend(sweep_acc.as_mut());
+        do_process_instruction(instruction, accounts)?;
+        self.bank_accounts_info[i].1 = closed_acc;
+        if let (Some(sweep), Some(sweep_acc)) = (sweep, sweep_acc) {
+            self.bank_accounts_info[sweep].1 = sweep_acc;
+        }
+        Ok(())
+    }
+
+    /// Closes account `i` without its owner's signature, as `CloseDustAccount` lets anyone.
+    pub fn process_close_dust(&mut self, i: usize, sweep: Option<usize>) -> ProgramResult {
+        self.check_index(i)?;
+        if let Some(sweep) = sweep {
+            self.check_index(sweep)?;
+        }
+        let instruction = close_dust_account(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[i].0,
+            &self.bank_accounts_owner_info[i].0,
+            sweep.map(|sweep| &self.bank_accounts_info[sweep].0),
+        )?;
+
+        let mut closed_acc = self.bank_accounts_info[i].1.clone();
+        let mut sweep_acc = sweep.map(|sweep| self.bank_accounts_info[sweep].1.clone());
+        let mut accounts = vec![
+            &mut closed_acc,
+            &mut self.bank_accounts_owner_info[i].1,
+            &mut self.bank_info.1,
+        ];
+        accounts.extend(sweep_acc.as_mut());
+        do_process_instruction(instruction, accounts)?;
+        self.bank_accounts_info[i].1 = closed_acc;
+        if let (Some(sweep), Some(sweep_acc)) = (sweep, sweep_acc) {
+            self.bank_accounts_info[sweep].1 = sweep_acc;
+        }
+        Ok(())
+    }
+
+    pub fn process_set_dust_threshold(&mut self, threshold: u64) -> ProgramResult {
+        let instruction = set_dust_threshold(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_owner_info.0,
+            threshold,
+        )?;
+        do_process_instruction(
+            instruction,
+            vec![&mut self.bank_info.1, &mut self.bank_owner_info.1],
+        )
+    }
+
+    pub fn process_set_close_policy(&mut self, policy: ClosePolicy) -> ProgramResult {
+        let instruction = set_close_policy(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_owner_info.0,
+            policy,
+        )?;
+        do_process_instruction(
+            instruction,
+            vec![&mut self.bank_info.1, &mut self.bank_owner_info.1],
+        )
+    }
+
+    /// Pauses the bank, or with `pause` false resumes it.
+    pub fn process_pause_bank(&mut self, pause: bool) -> ProgramResult {
+        let build = if pause { pause_bank } else { resume_bank };
+        let instruction = build(&self.program_id, &self.bank_info.0, &self.bank_owner_info.0)?;
+        do_process_instruction(
+            instruction,
+            vec![&mut self.bank_info.1, &mut self.bank_owner_info.1],
+        )
+    }
+
+    pub fn process_accrue_interest(&mut self, i: usize) -> ProgramResult {
+        self.check_index(i)?;
+        let instruction = accrue_interest(
+            &self.program_id,
+            &self.bank_info.0,
+            &self.bank_accounts_info[i].0,
+        )?;
+        do_process_instruction(
+            instruction,
+            vec![&mut self.bank_accounts_info[i].1, &mut self.bank_info.1],
+        )
+    }
+
+    fn check_index(&self, i: usize) -> ProgramResult {
+        if i >= self.bank_accounts_info.len() {
+            return Err(ProgramError::Custom(000));
+        }
+        Ok(())
+    }
+}
+
+/// Answers `Rent::get` off-chain with a rent nothing owes, so the suite's accounts pass
+/// as rent-exempt whatever they hold. The functional tests run against the real rent.
+struct RentStubs;
+
+impl program_stubs::SyscallStubs for RentStubs {
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::free() };
+        SUCCESS
+    }
+}
+
+/// Runs `instruction` through `Processor::process` against `accounts`, given in the
+/// order of the instruction's account metas, which say which are signers and writable.
+pub fn do_process_instruction(
+    instruction: Instruction,
+    accounts: Vec<&mut SolanaAccount>,
+) -> ProgramResult {
+    program_stubs::set_syscall_stubs(Box::new(RentStubs));
+    let mut meta = instruction
+        .accounts
+        .iter()
+        .zip(accounts)
+        .collect::<Vec<_>>();
+    let account_infos = meta
+        .iter_mut()
+        .map(|(account_meta, account)| {
+            AccountInfo::new(
+                &account_meta.pubkey,
+                account_meta.is_signer,
+                account_meta.is_writable,
+                &mut account.lamports,
+                &mut account.data,
+                &account.owner,
+                account.executable,
+                account.rent_epoch,
+            )
+        })
+        .collect::<Vec<_>>();
+    Processor::process(&instruction.program_id, &account_infos, &instruction.data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::BankError;
+
+    #[test]
+    fn test_builder() {
+        let mut suite = TestSuite::builder()
+            .accounts(2)
+            .initialized(2)
+            .mint(0, 100)
+            .build()
+            .unwrap();
+        suite.process_transfer(0, 1, 40).unwrap();
+
+        let bank = Bank::unpack(&suite.bank_info.1.data).unwrap();
+        assert_eq!(bank.decimals, 2);
+        assert_eq!(bank.total_supply, 100);
+        let amounts = suite
+            .bank_accounts_info
+            .iter()
+            .map(|(_, account)| Account::unpack(&account.data).unwrap().amount)
+            .collect::<Vec<_>>();
+        assert_eq!(amounts, vec![60, 40]);
+
+        assert_eq!(
+            TestSuite::builder().accounts(1).mint(0, 1).build().err(),
+            Some(BankError::BankClosed.into())
+        );
+    }
+}
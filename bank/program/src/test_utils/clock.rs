@@ -0,0 +1,122 @@
+//! The time the programs' `Clock` shows in `ProgramTest` tests. solana-program-test 1.8
+//! can't write a sysvar, and its bank, which sees no votes, never moves the time, so
+//! `set_time` rewrites the `Clock` that `Clock::get` hands the native processors. BPF
+//! builds of the programs keep reading the bank's.
+//!
+//! The time is the process's, so a test holds a `TimeLock` for as long as it runs:
+//!
+//! ```ignore
+//! let _time = clock::lock();
+//! let mut context = program_test.start_with_context().await;
+//! clock::set_time(1_700_000_000);
+//! ```
+
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    program_stubs::{self, SyscallStubs},
+};
+use std::sync::{Mutex, MutexGuard, Once, PoisonError, RwLock};
+
+static LOCK: Mutex<()> = Mutex::new(());
+static TIME: RwLock<Option<i64>> = RwLock::new(None);
+/// solana-program-test's stubs, which `ClockStubs` passes everything else on to.
+static INNER: RwLock<Option<Box<dyn SyscallStubs>>> = RwLock::new(None);
+static INSTALL: Once = Once::new();
+
+/// Keeps the other tests of the process from moving the time; it goes back to the
+/// bank's when this drops.
+pub struct TimeLock {
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl Drop for TimeLock {
+    fn drop(&mut self) {
+        *TIME.write().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+}
+
+/// Waits for the tests holding the time to finish. A test that panicked still lets go.
+pub fn lock() -> TimeLock {
+    TimeLock {
+        _guard: LOCK.lock().unwrap_or_else(PoisonError::into_inner),
+    }
+}
+
+/// Moves the time seen by the programs' `Clock` to `unix_timestamp`. Call it with a
+/// `TimeLock` held, once a `ProgramTest` has started, as the stubs it wraps are only in
+/// place from then on.
+pub fn set_time(unix_timestamp: i64) {
+    INSTALL.call_once(|| {
+        let mut inner = INNER.write().unwrap();
+        *inner = Some(program_stubs::set_syscall_stubs(Box::new(ClockStubs)));
+    });
+    *TIME.write().unwrap_or_else(PoisonError::into_inner) = Some(unix_timestamp);
+}
+
+struct ClockStubs;
+
+fn inner<R>(f: impl FnOnce(&dyn SyscallStubs) -> R) -> R {
+    f(INNER
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_deref()
+        .unwrap())
+}
+
+impl SyscallStubs for ClockStubs {
+    fn sol_log(&self, message: &str) {
+        inner(|stubs| stubs.sol_log(message))
+    }
+
+    fn sol_log_compute_units(&self) {
+        inner(|stubs| stubs.sol_log_compute_units())
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        inner(|stubs| stubs.sol_invoke_signed(instruction, account_infos, signers_seeds))
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let result = inner(|stubs| stubs.sol_get_clock_sysvar(var_addr));
+        if let Some(unix_timestamp) = *TIME.read().unwrap_or_else(PoisonError::into_inner) {
+            unsafe { (*(var_addr as *mut Clock)).unix_timestamp = unix_timestamp };
+        }
+        result
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        inner(|stubs| stubs.sol_get_epoch_schedule_sysvar(var_addr))
+    }
+
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        inner(|stubs| stubs.sol_get_fees_sysvar(var_addr))
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        inner(|stubs| stubs.sol_get_rent_sysvar(var_addr))
+    }
+
+    unsafe fn sol_memcpy(&self, dst: *mut u8, src: *const u8, n: usize) {
+        inner(|stubs| stubs.sol_memcpy(dst, src, n))
+    }
+
+    unsafe fn sol_memmove(&self, dst: *mut u8, src: *const u8, n: usize) {
+        inner(|stubs| stubs.sol_memmove(dst, src, n))
+    }
+
+    unsafe fn sol_memcmp(&self, s1: *const u8, s2: *const u8, n: usize, result: *mut i32) {
+        inner(|stubs| stubs.sol_memcmp(s1, s2, n, result))
+    }
+
+    unsafe fn sol_memset(&self, s: *mut u8, c: u8, n: usize) {
+        inner(|stubs| stubs.sol_memset(s, c, n))
+    }
+}
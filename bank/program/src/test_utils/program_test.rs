@@ -0,0 +1,226 @@
+//! `ProgramTest` environment for the functional tests of programs that CPI into the
+//! bank. It deploys the program under test next to the bank, and next to `fake_bank`,
+//! which such a program must refuse to take for the bank.
+
+use crate::{
+    instruction::{initialize_account, initialize_bank, mint_to},
+    state::{Account, Bank},
+    test_utils::clock::{self, TimeLock},
+};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+    system_instruction, sysvar,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account as SolanaAccount,
+    process_instruction::ProcessInstructionWithContext,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+pub struct Env {
+    pub context: ProgramTestContext,
+    /// The program under test.
+    pub program_id: Pubkey,
+    pub bank_program_id: Pubkey,
+    /// `fake_bank`, posing as the bank.
+    pub fake_bank_program_id: Pubkey,
+    _time: TimeLock,
+}
+
+/// Accepts every instruction and does nothing, as a program posing as the bank might.
+fn fake_bank(_program_id: &Pubkey, _accounts: &[AccountInfo], _input: &[u8]) -> ProgramResult {
+    Ok(())
+}
+
+impl Env {
+    /// Starts the program `name`, run by `processor`, next to the bank:
+    ///
+    /// ```ignore
+    /// let mut env = Env::start("solana_escrow", processor!(Processor::process)).await;
+    /// ```
+    pub async fn start(name: &str, processor: Option<ProcessInstructionWithContext>) -> Env {
+        Self::start_with(name, processor, |_| {}).await
+    }
+
+    /// Like `start`, with the programs `add` puts in the test as well.
+    pub async fn start_with(
+        name: &str,
+        processor: Option<ProcessInstructionWithContext>,
+        add: impl FnOnce(&mut ProgramTest),
+    ) -> Env {
+        let program_id = Pubkey::new_unique();
+        let bank_program_id = Pubkey::new_unique();
+        let fake_bank_program_id = Pubkey::new_unique();
+        let mut program_test = ProgramTest::new(name, program_id, processor);
+        program_test.add_program(
+            "solana_bank",
+            bank_program_id,
+            processor!(crate::processor::Processor::process),
+        );
+        program_test.add_program("fake_bank", fake_bank_program_id, processor!(fake_bank));
+        add(&mut program_test);
+        let time = clock::lock();
+        Env {
+            context: program_test.start_with_context().await,
+            program_id,
+            bank_program_id,
+            fake_bank_program_id,
+            _time: time,
+        }
+    }
+
+    /// Sends `instructions` in one transaction paid for by the payer, who signs next
+    /// to `signers`.
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), TransactionError> {
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            self.context.last_blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .map_err(|e| e.unwrap())
+    }
+
+    /// Moves the time seen by the programs' `Clock` to `unix_timestamp`, see `clock`.
+    pub fn set_time(&mut self, unix_timestamp: i64) {
+        clock::set_time(unix_timestamp);
+    }
+
+    /// Warps the bank ahead to `slot`, which the programs' `Clock` then shows.
+    pub async fn set_slot(&mut self, slot: u64) {
+        let current = self
+            .context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .slot;
+        if current != slot {
+            self.context.warp_to_slot(slot).unwrap();
+        }
+    }
+
+    /// The SlotHashes sysvar's data. After `set_slot` its most recent entry is the slot
+    /// before.
+    pub async fn slot_hashes(&mut self) -> Vec<u8> {
+        self.account(&sysvar::slot_hashes::id()).await.unwrap().data
+    }
+
+    /// Sends `lamports` from the payer to `to`.
+    pub async fn fund(&mut self, to: &Pubkey, lamports: u64) {
+        let instruction = system_instruction::transfer(&self.context.payer.pubkey(), to, lamports);
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    /// Creates a rent-exempt account of `space` bytes owned by `owner`.
+    pub async fn create_account(&mut self, account: &Keypair, space: usize, owner: &Pubkey) {
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let instruction = system_instruction::create_account(
+            &self.context.payer.pubkey(),
+            &account.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            owner,
+        );
+        self.process(&[instruction], &[account]).await.unwrap();
+    }
+
+    /// Opens a bank owned by the payer.
+    pub async fn create_bank(&mut self) -> Pubkey {
+        self.create_bank_with_decimals(0).await
+    }
+
+    /// Opens a bank with `decimals`, owned by the payer.
+    pub async fn create_bank_with_decimals(&mut self, decimals: u8) -> Pubkey {
+        let bank = Keypair::new();
+        let bank_program_id = self.bank_program_id;
+        self.create_account(&bank, Bank::LEN, &bank_program_id)
+            .await;
+        let instruction = initialize_bank(
+            &bank_program_id,
+            &bank.pubkey(),
+            &self.context.payer.pubkey(),
+            decimals,
+        )
+        .unwrap();
+        self.process(&[instruction], &[]).await.unwrap();
+        bank.pubkey()
+    }
+
+    /// Opens an account of `owner` in `bank` holding `amount` freshly minted tokens. An
+    /// empty account needs no minting, so `bank` need not be the payer's.
+    pub async fn create_bank_account(
+        &mut self,
+        bank: &Pubkey,
+        owner: &Keypair,
+        amount: u64,
+    ) -> Pubkey {
+        let account = Keypair::new();
+        let bank_program_id = self.bank_program_id;
+        self.create_account(&account, Account::LEN, &bank_program_id)
+            .await;
+        let mut instructions =
+            vec![
+                initialize_account(&bank_program_id, bank, &account.pubkey(), &owner.pubkey())
+                    .unwrap(),
+            ];
+        if amount > 0 {
+            instructions.push(
+                mint_to(
+                    &bank_program_id,
+                    bank,
+                    &account.pubkey(),
+                    &self.context.payer.pubkey(),
+                    amount,
+                )
+                .unwrap(),
+            );
+        }
+        self.process(&instructions, &[owner]).await.unwrap();
+        account.pubkey()
+    }
+
+    /// The account at `address`, None if there is none.
+    pub async fn account(&mut self, address: &Pubkey) -> Option<SolanaAccount> {
+        self.context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+    }
+
+    pub async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(*address)
+            .await
+            .unwrap()
+    }
+
+    pub async fn unpack<T: Pack + IsInitialized>(&mut self, address: &Pubkey) -> T {
+        let account = self.account(address).await.unwrap();
+        T::unpack(&account.data).unwrap()
+    }
+
+    /// The balance of the bank account at `address`.
+    pub async fn amount(&mut self, address: &Pubkey) -> u64 {
+        self.unpack::<Account>(address).await.amount
+    }
+}
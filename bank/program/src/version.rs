@@ -0,0 +1,91 @@
+//! Program version: a record of which release of this program is deployed and which
+//! state layout it writes, so a client can tell it understands the program before
+//! sending it anything.
+//!
+//! The record is a PDA of this program, created by the first `InitializeVersion` at the
+//! expense of a payer. `InitializeVersion` writes the running build's `MAJOR`, `MINOR`,
+//! `PATCH` and `LAYOUT` into it; running it again after an upgrade brings the record up to
+//! date. Anyone can run it, since it only ever records the program that runs it. A build
+//! never writes over a record of a newer layout than its own: a program rolled back past
+//! a layout change would misread the state its successor wrote, so it fails with
+//! `UnsupportedLayout` instead of claiming that state as its own.
+
+use crate::state::Version;
+use solana_program::pubkey::Pubkey;
+
+/// Seed the version record derives from.
+pub const SEED: &[u8] = b"version";
+
+/// This build's release, kept in step with the crate's version.
+pub const MAJOR: u16 = 0;
+pub const MINOR: u16 = 1;
+pub const PATCH: u16 = 0;
+/// Version of the state layouts this build reads and writes, bumped whenever one of
+/// them changes.
+pub const LAYOUT: u16 = 6;
+
+/// The address of the program's version record, and its bump.
+pub fn address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SEED], program_id)
+}
+
+/// This build's version, as `InitializeVersion` records it.
+pub fn current() -> Version {
+    Version {
+        is_initialized: true,
+        major: MAJOR,
+        minor: MINOR,
+        patch: PATCH,
+        layout: LAYOUT,
+    }
+}
+
+/// Whether the layout `version` records is one this build understands.
+pub fn understands_layout(version: &Version) -> bool {
+    version.layout <= LAYOUT
+}
+
+/// Whether this build is compatible with the program `version` records: the same major
+/// release, writing a layout it understands.
+pub fn is_compatible(version: &Version) -> bool {
+    version.major == MAJOR && understands_layout(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current_matches_crate_version() {
+        let crate_version = format!("{}.{}.{}", MAJOR, MINOR, PATCH);
+        assert_eq!(crate_version, env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn test_is_compatible() {
+        assert!(is_compatible(&current()));
+        let newer_minor = Version {
+            minor: MINOR + 1,
+            ..current()
+        };
+        assert!(is_compatible(&newer_minor));
+        let older_layout = Version {
+            layout: LAYOUT - 1,
+            ..current()
+        };
+        assert!(is_compatible(&older_layout));
+
+        let newer_layout = Version {
+            layout: LAYOUT + 1,
+            ..current()
+        };
+        assert!(!understands_layout(&newer_layout));
+        assert!(!is_compatible(&newer_layout));
+        let newer_major = Version {
+            major: MAJOR + 1,
+            ..current()
+        };
+        assert!(understands_layout(&newer_major));
+        assert!(!is_compatible(&newer_major));
+    }
+}
@@ -0,0 +1,349 @@
+//! Compute units used by each bank instruction, checked against
+//! `tests/compute_units.json` and against a fixed budget per instruction. Needs the
+//! BPF build of the program (`cargo test-bpf`); the native processor isn't metered,
+//! so the tests are skipped when `solana_bank.so` can't be found. Set
+//! `UPDATE_CU_BASELINE=1` to rewrite the baseline after an intended change.
+
+use solana_bank::{
+    instruction::{
+        approve, burn, close_account, initialize_account, initialize_bank, mint_to, revoke,
+        set_close_policy, set_require_rent_exempt, transfer,
+    },
+    state::{Account, Bank, ClosePolicy},
+};
+use solana_program::{
+    hash::Hash, instruction::Instruction, program_pack::Pack, pubkey::Pubkey, system_instruction,
+};
+use solana_program_test::{BanksClient, ProgramTest};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use std::{collections::BTreeMap, env, fs, sync::Mutex};
+
+const BASELINE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/compute_units.json");
+/// Allowed growth over the baseline before the test fails.
+const MAX_REGRESSION_PERCENT: u64 = 10;
+/// Upper bound on the compute units of each instruction. Unlike the baseline these
+/// only move by hand, so they hold across any number of small regressions.
+const BUDGETS: &[(&str, u64)] = &[
+    ("InitializeBank", 8_000),
+    ("InitializeAccount", 8_000),
+    ("MintTo", 10_000),
+    ("Transfer", 10_000),
+    ("Approve", 8_000),
+    ("Revoke", 8_000),
+    ("Burn", 10_000),
+    ("CloseAccount", 8_000),
+    ("SetClosePolicy", 8_000),
+    ("SetRequireRentExempt", 8_000),
+];
+
+/// The "Program <id> consumed <n> of <m> compute units" lines the runtime logs for each
+/// BPF program it runs, which is all solana-program-test 1.8 tells of compute units.
+static CONSUMED: Mutex<Vec<(String, u64)>> = Mutex::new(Vec::new());
+
+struct ConsumedLog;
+
+impl log::Log for ConsumedLog {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target() == "solana_runtime::message_processor"
+    }
+
+    fn log(&self, record: &log::Record) {
+        let message = record.args().to_string();
+        let words: Vec<&str> = message.split(' ').collect();
+        if let ["Program", program_id, "consumed", units, "of", _, "compute", "units"] = words[..] {
+            if let Ok(units) = units.parse() {
+                CONSUMED
+                    .lock()
+                    .unwrap()
+                    .push((program_id.to_string(), units));
+            }
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn bpf_program_available() -> bool {
+    solana_program_test::find_file("solana_bank.so").is_some()
+}
+
+async fn process(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    recent_blockhash: Hash,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> u64 {
+    let program_id = instruction.program_id.to_string();
+    let mut all_signers = vec![payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &all_signers,
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await.unwrap();
+    // The instruction's line comes after those of the programs it invokes.
+    let mut consumed = CONSUMED.lock().unwrap();
+    let units = consumed
+        .iter()
+        .rev()
+        .find(|(id, _)| *id == program_id)
+        .map_or(0, |(_, units)| *units);
+    consumed.retain(|(id, _)| *id != program_id);
+    units
+}
+
+async fn create_account(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    recent_blockhash: Hash,
+    program_id: &Pubkey,
+    account: &Keypair,
+    space: usize,
+) {
+    let rent = banks_client.get_rent().await.unwrap();
+    let instruction = system_instruction::create_account(
+        &payer.pubkey(),
+        &account.pubkey(),
+        rent.minimum_balance(space),
+        space as u64,
+        program_id,
+    );
+    process(
+        banks_client,
+        payer,
+        recent_blockhash,
+        instruction,
+        &[account],
+    )
+    .await;
+}
+
+async fn measure() -> BTreeMap<String, u64> {
+    // Ahead of the logger `ProgramTest` sets up, which then leaves this one in place but
+    // sets the level it logs at.
+    let _ = log::set_logger(&ConsumedLog);
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new("solana_bank", program_id, None);
+    log::set_max_level(log::LevelFilter::Debug);
+    program_test.prefer_bpf(true);
+    // Every transaction here carries a single instruction, so the runtime itself
+    // stops anything that blows through the largest budget.
+    program_test.set_bpf_compute_max_units(BUDGETS.iter().map(|(_, units)| *units).max().unwrap());
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+    let client = &mut banks_client;
+
+    let bank = Keypair::new();
+    let bank_owner = Keypair::new();
+    let owner = Keypair::new();
+    let from = Keypair::new();
+    let to = Keypair::new();
+    let delegate = Keypair::new();
+    create_account(
+        client,
+        &payer,
+        recent_blockhash,
+        &program_id,
+        &bank,
+        Bank::LEN,
+    )
+    .await;
+    for account in [&from, &to] {
+        create_account(
+            client,
+            &payer,
+            recent_blockhash,
+            &program_id,
+            account,
+            Account::LEN,
+        )
+        .await;
+    }
+
+    // Steps without a name only set up state for the ones after them.
+    let steps: Vec<(Option<&str>, Instruction, Vec<&Keypair>)> = vec![
+        (
+            Some("InitializeBank"),
+            initialize_bank(&program_id, &bank.pubkey(), &bank_owner.pubkey(), 2).unwrap(),
+            vec![&bank_owner],
+        ),
+        // Before the accounts open, so that InitializeAccount is measured checking rent.
+        (
+            Some("SetRequireRentExempt"),
+            set_require_rent_exempt(&program_id, &bank.pubkey(), &bank_owner.pubkey(), true)
+                .unwrap(),
+            vec![&bank_owner],
+        ),
+        (
+            Some("InitializeAccount"),
+            initialize_account(&program_id, &bank.pubkey(), &from.pubkey(), &owner.pubkey())
+                .unwrap(),
+            vec![&owner],
+        ),
+        (
+            None,
+            initialize_account(&program_id, &bank.pubkey(), &to.pubkey(), &owner.pubkey()).unwrap(),
+            vec![&owner],
+        ),
+        (
+            Some("MintTo"),
+            mint_to(
+                &program_id,
+                &bank.pubkey(),
+                &from.pubkey(),
+                &bank_owner.pubkey(),
+                1_000,
+            )
+            .unwrap(),
+            vec![&bank_owner],
+        ),
+        (
+            Some("Transfer"),
+            transfer(
+                &program_id,
+                &bank.pubkey(),
+                &from.pubkey(),
+                &to.pubkey(),
+                &owner.pubkey(),
+                100,
+            )
+            .unwrap(),
+            vec![&owner],
+        ),
+        (
+            Some("Approve"),
+            approve(
+                &program_id,
+                &bank.pubkey(),
+                &from.pubkey(),
+                &delegate.pubkey(),
+                &owner.pubkey(),
+                50,
+            )
+            .unwrap(),
+            vec![&owner],
+        ),
+        (
+            Some("Revoke"),
+            revoke(&program_id, &bank.pubkey(), &from.pubkey(), &owner.pubkey()).unwrap(),
+            vec![&owner],
+        ),
+        (
+            Some("Burn"),
+            burn(
+                &program_id,
+                &bank.pubkey(),
+                &to.pubkey(),
+                &bank_owner.pubkey(),
+                &owner.pubkey(),
+                100,
+            )
+            .unwrap(),
+            vec![&bank_owner, &owner],
+        ),
+        (
+            Some("SetClosePolicy"),
+            set_close_policy(
+                &program_id,
+                &bank.pubkey(),
+                &bank_owner.pubkey(),
+                ClosePolicy::Burn,
+            )
+            .unwrap(),
+            vec![&bank_owner],
+        ),
+        (
+            Some("CloseAccount"),
+            close_account(
+                &program_id,
+                &bank.pubkey(),
+                &to.pubkey(),
+                &payer.pubkey(),
+                &owner.pubkey(),
+            )
+            .unwrap(),
+            vec![&owner],
+        ),
+    ];
+
+    let mut units = BTreeMap::new();
+    for (name, instruction, signers) in steps {
+        let consumed = process(client, &payer, recent_blockhash, instruction, &signers).await;
+        if let Some(name) = name {
+            units.insert(name.to_string(), consumed);
+        }
+    }
+    units
+}
+
+#[tokio::test]
+async fn test_compute_units() {
+    if !bpf_program_available() {
+        eprintln!("solana_bank.so not found, skipping; run `cargo test-bpf` to measure");
+        return;
+    }
+    let measured = measure().await;
+
+    let baseline: Option<BTreeMap<String, u64>> = fs::read_to_string(BASELINE)
+        .ok()
+        .map(|json| serde_json::from_str(&json).unwrap());
+    let baseline = match baseline {
+        Some(b) if env::var_os("UPDATE_CU_BASELINE").is_none() => b,
+        _ => {
+            let json = serde_json::to_string_pretty(&measured).unwrap();
+            fs::write(BASELINE, json + "\n").unwrap();
+            eprintln!("wrote compute unit baseline to {}", BASELINE);
+            return;
+        }
+    };
+
+    let regressions = measured
+        .iter()
+        .filter_map(|(name, &units)| {
+            let expected = *baseline.get(name)?;
+            if units * 100 > expected * (100 + MAX_REGRESSION_PERCENT) {
+                Some(format!("{}: {} CU, baseline {} CU", name, units, expected))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        regressions.is_empty(),
+        "compute units regressed by more than {}%:\n{}",
+        MAX_REGRESSION_PERCENT,
+        regressions.join("\n")
+    );
+}
+
+#[tokio::test]
+async fn test_compute_budgets() {
+    if !bpf_program_available() {
+        eprintln!("solana_bank.so not found, skipping; run `cargo test-bpf` to measure");
+        return;
+    }
+    let measured = measure().await;
+    for name in measured.keys() {
+        assert!(
+            BUDGETS.iter().any(|(budgeted, _)| budgeted == name),
+            "{} has no compute budget",
+            name
+        );
+    }
+
+    let over_budget = BUDGETS
+        .iter()
+        .filter(|(name, budget)| measured[*name] > *budget)
+        .map(|(name, budget)| format!("{}: {} CU, budget {} CU", name, measured[*name], budget))
+        .collect::<Vec<_>>();
+    assert!(
+        over_budget.is_empty(),
+        "compute budget exceeded:\n{}",
+        over_budget.join("\n")
+    );
+}
@@ -0,0 +1,3183 @@
+use solana_bank::{
+    associated_account::get_associated_bank_account_address,
+    error::BankError,
+    feature_gate,
+    history::{self, TransferRecord},
+    instruction::{
+        accrue_interest, approve, assign_to_program, burn, burn_checked, cancel_owner_change,
+        cancel_recovery, close_account, close_account_with_balance, close_dust_account, convert,
+        create_session_key, deposit_sol, freeze_account, initialize_account,
+        initialize_associated_account, initialize_bank, initialize_bank_with_freeze_authority,
+        initialize_bank_with_interest_rate, initialize_bank_with_sol_rate, initialize_multisig,
+        initialize_registered_account, initialize_version, mint_to, mint_to_checked,
+        mint_to_multisig, pause_bank, permit_approve, recover_owner, resume_bank, revoke,
+        set_authority, set_close_policy, set_dust_threshold, set_exchange_rate, set_features,
+        set_guardians, set_owner_change_delay, set_rate_limit, set_require_rent_exempt,
+        start_recovery, thaw_account, transfer, transfer_batch, transfer_checked,
+        transfer_multisig, transfer_route, transfer_sweeping_dust, transfer_with_expiry,
+        transfer_with_session_key, withdraw_sol, AuthorityType,
+    },
+    interest, pda, permit,
+    processor::Processor,
+    rate_limit, recovery, registry, session,
+    state::{
+        Account, Bank, BurnPolicy, ClosePolicy, ExchangeRate, FeatureGate, Multisig, Recovery,
+        Session, Version,
+    },
+    test_utils::clock::{self, TimeLock},
+    version,
+};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestBanksClientExt, ProgramTestContext};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use std::convert::TryInto;
+
+struct Env {
+    context: ProgramTestContext,
+    program_id: Pubkey,
+    _time: TimeLock,
+}
+
+impl Env {
+    /// Starts the bank next to the programs `add` puts in the test.
+    async fn start_with(add: impl FnOnce(&mut ProgramTest)) -> Env {
+        let program_id = Pubkey::new_unique();
+        let mut program_test =
+            ProgramTest::new("solana_bank", program_id, processor!(Processor::process));
+        add(&mut program_test);
+        let time = clock::lock();
+        Env {
+            context: program_test.start_with_context().await,
+            program_id,
+            _time: time,
+        }
+    }
+
+    async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), TransactionError> {
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            self.context.last_blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .map_err(|e| e.unwrap())
+    }
+
+    /// Moves the time seen by the program's `Clock` to `unix_timestamp`.
+    fn set_time(&mut self, unix_timestamp: i64) {
+        clock::set_time(unix_timestamp);
+    }
+
+    /// Moves to a new blockhash, so a transaction can be sent again without being taken
+    /// for the one already processed.
+    async fn refresh_blockhash(&mut self) {
+        let (blockhash, _) = self
+            .context
+            .banks_client
+            .get_new_blockhash(&self.context.last_blockhash)
+            .await
+            .unwrap();
+        self.context.last_blockhash = blockhash;
+    }
+
+    /// Creates a rent-exempt account of `space` bytes owned by the bank program.
+    async fn create_account(&mut self, account: &Keypair, space: usize) {
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let instruction = system_instruction::create_account(
+            &self.context.payer.pubkey(),
+            &account.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            &self.program_id,
+        );
+        self.process(&[instruction], &[account]).await.unwrap();
+    }
+
+    async fn create_bank(&mut self, bank: &Keypair, bank_owner: &Keypair, decimals: u8) {
+        self.create_account(bank, Bank::LEN).await;
+        let instruction = initialize_bank(
+            &self.program_id,
+            &bank.pubkey(),
+            &bank_owner.pubkey(),
+            decimals,
+        )
+        .unwrap();
+        self.process(&[instruction], &[bank_owner]).await.unwrap();
+    }
+
+    async fn create_bank_account(&mut self, bank: &Pubkey, account: &Keypair, owner: &Keypair) {
+        self.create_account(account, Account::LEN).await;
+        let instruction =
+            initialize_account(&self.program_id, bank, &account.pubkey(), &owner.pubkey()).unwrap();
+        self.process(&[instruction], &[owner]).await.unwrap();
+    }
+
+    /// Opens `account` in `bank` for a new multisig owner that all of `signers` sign for,
+    /// and returns the multisig.
+    async fn create_multisig_bank_account(
+        &mut self,
+        bank: &Pubkey,
+        account: &Keypair,
+        signers: &[Pubkey],
+    ) -> Pubkey {
+        let multisig = Keypair::new();
+        self.create_account(&multisig, Multisig::LEN).await;
+        let instruction = initialize_multisig(
+            &self.program_id,
+            &multisig.pubkey(),
+            &signers.iter().collect::<Vec<_>>(),
+            signers.len() as u8,
+        )
+        .unwrap();
+        self.process(&[instruction], &[]).await.unwrap();
+        self.create_account(account, Account::LEN).await;
+        let mut instruction = initialize_account(
+            &self.program_id,
+            bank,
+            &account.pubkey(),
+            &multisig.pubkey(),
+        )
+        .unwrap();
+        instruction.accounts[2].is_signer = false;
+        self.process(&[instruction], &[]).await.unwrap();
+        multisig.pubkey()
+    }
+
+    async fn get_bank(&mut self, bank: &Pubkey) -> Bank {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*bank)
+            .await
+            .unwrap()
+            .unwrap();
+        Bank::unpack(&account.data).unwrap()
+    }
+
+    async fn get_bank_account(&mut self, account: &Pubkey) -> Account {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*account)
+            .await
+            .unwrap()
+            .unwrap();
+        Account::unpack(&account.data).unwrap()
+    }
+}
+
+/// `instruction` with its multisig owner at `owner` left unsigned and `signers` signing
+/// after its other accounts.
+fn signed_by_multisig(
+    mut instruction: Instruction,
+    owner: usize,
+    signers: &[Pubkey],
+) -> Instruction {
+    instruction.accounts[owner].is_signer = false;
+    instruction.accounts.extend(
+        signers
+            .iter()
+            .map(|signer| AccountMeta::new_readonly(*signer, true)),
+    );
+    instruction
+}
+
+/// A bank with two opened accounts; `alice` holds 100 freshly minted tokens.
+struct Fixture {
+    env: Env,
+    bank: Keypair,
+    bank_owner: Keypair,
+    alice: Keypair,
+    alice_account: Keypair,
+    bob: Keypair,
+    bob_account: Keypair,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        Self::new_with(|_| {}).await
+    }
+
+    /// The fixture, next to the programs `add` puts in the test.
+    async fn new_with(add: impl FnOnce(&mut ProgramTest)) -> Fixture {
+        let mut env = Env::start_with(add).await;
+        let bank = Keypair::new();
+        let bank_owner = Keypair::new();
+        let alice = Keypair::new();
+        let alice_account = Keypair::new();
+        let bob = Keypair::new();
+        let bob_account = Keypair::new();
+
+        env.create_bank(&bank, &bank_owner, 2).await;
+        env.create_bank_account(&bank.pubkey(), &alice_account, &alice)
+            .await;
+        env.create_bank_account(&bank.pubkey(), &bob_account, &bob)
+            .await;
+        let instruction = mint_to(
+            &env.program_id,
+            &bank.pubkey(),
+            &alice_account.pubkey(),
+            &bank_owner.pubkey(),
+            100,
+        )
+        .unwrap();
+        env.process(&[instruction], &[&bank_owner]).await.unwrap();
+
+        Fixture {
+            env,
+            bank,
+            bank_owner,
+            alice,
+            alice_account,
+            bob,
+            bob_account,
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_initialize_bank_and_accounts() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+
+    let bank = env.get_bank(&fixture.bank.pubkey()).await;
+    assert!(bank.is_opened);
+    assert_eq!(bank.decimals, 2);
+    assert_eq!(bank.bank_owner, fixture.bank_owner.pubkey());
+    assert_eq!(bank.total_supply, 100);
+
+    let account = env.get_bank_account(&fixture.bob_account.pubkey()).await;
+    assert!(account.is_initialized);
+    assert_eq!(account.bank, fixture.bank.pubkey());
+    assert_eq!(account.owner, fixture.bob.pubkey());
+    assert_eq!(account.amount, 0);
+
+    let instruction = initialize_bank(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &fixture.bank_owner.pubkey(),
+        6,
+    )
+    .unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&fixture.bank_owner]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::AccountAlreadyInitialized
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_mint_to_requires_bank_owner_signature() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+
+    let mut instruction = mint_to(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &fixture.alice_account.pubkey(),
+        &fixture.bank_owner.pubkey(),
+        50,
+    )
+    .unwrap();
+    instruction.accounts[2] = AccountMeta::new_readonly(fixture.bank_owner.pubkey(), false);
+    assert_eq!(
+        env.process(&[instruction], &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::MissingRequiredSignature
+        ))
+    );
+
+    let instruction = mint_to(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &fixture.alice_account.pubkey(),
+        &fixture.alice.pubkey(),
+        50,
+    )
+    .unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&fixture.alice]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_transfer() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+
+    let instruction = transfer(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &fixture.alice_account.pubkey(),
+        &fixture.bob_account.pubkey(),
+        &fixture.alice.pubkey(),
+        40,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&fixture.alice])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.get_bank_account(&fixture.alice_account.pubkey())
+            .await
+            .amount,
+        60
+    );
+    assert_eq!(
+        env.get_bank_account(&fixture.bob_account.pubkey())
+            .await
+            .amount,
+        40
+    );
+    let bank = env.get_bank(&fixture.bank.pubkey()).await;
+    assert_eq!(
+        (
+            bank.transfer_count,
+            bank.cumulative_volume,
+            bank.holder_count
+        ),
+        (1, 40, 2)
+    );
+
+    let instruction = transfer(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &fixture.alice_account.pubkey(),
+        &fixture.bob_account.pubkey(),
+        &fixture.alice.pubkey(),
+        61,
+    )
+    .unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&fixture.alice]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::InsufficientFunds as u32)
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_transfer_with_expiry() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let slot = env
+        .context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .slot;
+    let (program_id, bank) = (env.program_id, fixture.bank.pubkey());
+    let (from, to) = (fixture.alice_account.pubkey(), fixture.bob_account.pubkey());
+    let owner = fixture.alice.pubkey();
+    let transfer = |amount, max_slot| {
+        transfer_with_expiry(&program_id, &bank, &from, &to, &owner, amount, max_slot).unwrap()
+    };
+
+    // `max_slot` itself is still in time.
+    env.process(&[transfer(10, slot)], &[&fixture.alice])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.process(&[transfer(20, slot - 1)], &[&fixture.alice])
+            .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::TransferExpired as u32)
+        ))
+    );
+    assert_eq!(
+        env.get_bank_account(&fixture.bob_account.pubkey())
+            .await
+            .amount,
+        10
+    );
+}
+
+#[tokio::test]
+async fn test_transfer_history() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let (program_id, bank) = (env.program_id, fixture.bank.pubkey());
+    let (alice_account, bob_account) =
+        (fixture.alice_account.pubkey(), fixture.bob_account.pubkey());
+    let carol = Keypair::new();
+    let carol_account = Keypair::new();
+    env.create_account(&carol_account, history::account_len(2))
+        .await;
+    let instruction =
+        initialize_account(&program_id, &bank, &carol_account.pubkey(), &carol.pubkey()).unwrap();
+    env.process(&[instruction], &[&carol]).await.unwrap();
+    let (carol_pubkey, carol_account_pubkey) = (carol.pubkey(), carol_account.pubkey());
+    let transfer =
+        |from, to, owner, amount| transfer(&program_id, &bank, &from, &to, &owner, amount).unwrap();
+
+    for amount in [10, 20, 30] {
+        env.process(
+            &[transfer(
+                alice_account,
+                carol_account_pubkey,
+                fixture.alice.pubkey(),
+                amount,
+            )],
+            &[&fixture.alice],
+        )
+        .await
+        .unwrap();
+    }
+    env.process(
+        &[transfer(carol_account_pubkey, bob_account, carol_pubkey, 5)],
+        &[&carol],
+    )
+    .await
+    .unwrap();
+
+    let slot = env
+        .context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .slot;
+    let data = env
+        .context
+        .banks_client
+        .get_account(carol_account_pubkey)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    assert_eq!(Account::unpack_extended(&data).unwrap().amount, 55);
+    // Of the four transfers, the two newest are kept.
+    assert_eq!(
+        history::records(&data),
+        vec![
+            TransferRecord {
+                counterparty: bob_account,
+                amount: 5,
+                slot,
+                incoming: false,
+            },
+            TransferRecord {
+                counterparty: alice_account,
+                amount: 30,
+                slot,
+                incoming: true,
+            },
+        ]
+    );
+    // Plain accounts keep no history.
+    assert_eq!(env.get_bank_account(&bob_account).await.amount, 5);
+}
+
+#[tokio::test]
+async fn test_transfer_route_through_delegate() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let delegate = Keypair::new();
+    let (alice_account, bob_account) =
+        (fixture.alice_account.pubkey(), fixture.bob_account.pubkey());
+
+    // Bob's account only forwards what it receives, and his delegate signs for it.
+    let instruction = approve(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &bob_account,
+        &delegate.pubkey(),
+        &fixture.bob.pubkey(),
+        20,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&fixture.bob]).await.unwrap();
+    let (program_id, bank) = (env.program_id, fixture.bank.pubkey());
+    let hops = [
+        (alice_account, fixture.alice.pubkey()),
+        (bob_account, delegate.pubkey()),
+    ];
+    let route = |amount| transfer_route(&program_id, &bank, &hops, &alice_account, amount).unwrap();
+    let (over_allowance, within_allowance) = (route(30), route(20));
+
+    // The second hop overdraws the allowance, which undoes the first.
+    assert_eq!(
+        env.process(&[over_allowance], &[&fixture.alice, &delegate])
+            .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::InsufficientFunds as u32)
+        ))
+    );
+    assert_eq!(env.get_bank_account(&alice_account).await.amount, 100);
+
+    env.process(&[within_allowance], &[&fixture.alice, &delegate])
+        .await
+        .unwrap();
+    assert_eq!(env.get_bank_account(&alice_account).await.amount, 100);
+    let bob = env.get_bank_account(&bob_account).await;
+    assert_eq!((bob.amount, bob.delegated_amount), (0, 0));
+    assert!(bob.delegate.is_none());
+}
+
+#[tokio::test]
+async fn test_transfer_batch() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let (program_id, bank) = (env.program_id, fixture.bank.pubkey());
+    let (alice_account, bob_account) =
+        (fixture.alice_account.pubkey(), fixture.bob_account.pubkey());
+    let carol_account = Keypair::new();
+    env.create_bank_account(&bank, &carol_account, &Keypair::new())
+        .await;
+    let (carol_account, alice) = (carol_account.pubkey(), fixture.alice.pubkey());
+    let batch = |second| {
+        transfer_batch(
+            &program_id,
+            &bank,
+            &alice_account,
+            &alice,
+            &[(&bob_account, 40), (&carol_account, second)],
+        )
+        .unwrap()
+    };
+    let (overdrawn, covered) = (batch(61), batch(60));
+
+    // The second payment overdraws the account, which undoes the first.
+    assert_eq!(
+        env.process(&[overdrawn], &[&fixture.alice]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::InsufficientFunds as u32)
+        ))
+    );
+    assert_eq!(env.get_bank_account(&bob_account).await.amount, 0);
+
+    env.process(&[covered], &[&fixture.alice]).await.unwrap();
+    assert_eq!(env.get_bank_account(&alice_account).await.amount, 0);
+    assert_eq!(env.get_bank_account(&bob_account).await.amount, 40);
+    assert_eq!(env.get_bank_account(&carol_account).await.amount, 60);
+}
+
+#[tokio::test]
+async fn test_approve_transfer_and_revoke() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let delegate = Keypair::new();
+
+    let instruction = approve(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &fixture.alice_account.pubkey(),
+        &delegate.pubkey(),
+        &fixture.alice.pubkey(),
+        30,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&fixture.alice])
+        .await
+        .unwrap();
+    let account = env.get_bank_account(&fixture.alice_account.pubkey()).await;
+    assert_eq!(account.amount, 100);
+    assert_eq!(account.delegated_amount, 30);
+
+    let instruction = transfer(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &fixture.alice_account.pubkey(),
+        &fixture.bob_account.pubkey(),
+        &delegate.pubkey(),
+        20,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&delegate]).await.unwrap();
+    assert_eq!(
+        env.get_bank_account(&fixture.bob_account.pubkey())
+            .await
+            .amount,
+        20
+    );
+
+    // The delegate can't raise its own allowance.
+    let instruction = approve(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &fixture.alice_account.pubkey(),
+        &delegate.pubkey(),
+        &delegate.pubkey(),
+        1_000,
+    )
+    .unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&delegate]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    let account = env.get_bank_account(&fixture.alice_account.pubkey()).await;
+    assert_eq!(account.delegated_amount, 10);
+
+    let instruction = revoke(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &fixture.alice_account.pubkey(),
+        &fixture.alice.pubkey(),
+    )
+    .unwrap();
+    env.process(&[instruction], &[&fixture.alice])
+        .await
+        .unwrap();
+    let account = env.get_bank_account(&fixture.alice_account.pubkey()).await;
+    assert_eq!(account.amount, 80);
+    assert_eq!(account.delegated_amount, 0);
+    assert!(account.delegate.is_none());
+}
+
+/// `PermitApprove` of `alice_account` to `delegate`, after the ed25519 instruction
+/// verifying `signer`'s signature of the permit.
+fn permit_instructions(
+    fixture: &Fixture,
+    signer: &Keypair,
+    delegate: &Pubkey,
+    amount: u64,
+    nonce: u64,
+    expiry: i64,
+) -> Vec<Instruction> {
+    let program_id = fixture.env.program_id;
+    let account = fixture.alice_account.pubkey();
+    let message = permit::message(&program_id, &account, delegate, amount, nonce, expiry);
+    let signature = signer.sign_message(&message);
+    vec![
+        permit::signature_instruction(
+            &signer.pubkey(),
+            signature.as_ref().try_into().unwrap(),
+            &message,
+        ),
+        permit_approve(
+            &program_id,
+            &fixture.bank.pubkey(),
+            &account,
+            delegate,
+            amount,
+            nonce,
+            expiry,
+        )
+        .unwrap(),
+    ]
+}
+
+#[tokio::test]
+async fn test_permit_approve() {
+    let mut fixture = Fixture::new().await;
+    let delegate = Keypair::new();
+
+    // Only the payer signs the transaction; alice signed the permit off-chain.
+    let instructions = permit_instructions(
+        &fixture,
+        &fixture.alice,
+        &delegate.pubkey(),
+        30,
+        0,
+        i64::MAX,
+    );
+    let env = &mut fixture.env;
+    env.process(&instructions, &[]).await.unwrap();
+    let account = env.get_bank_account(&fixture.alice_account.pubkey()).await;
+    assert_eq!(account.delegate, Some(delegate.pubkey()).into());
+    assert_eq!(account.delegated_amount, 30);
+    assert_eq!(account.permit_nonce, 1);
+
+    let instruction = transfer(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &fixture.alice_account.pubkey(),
+        &fixture.bob_account.pubkey(),
+        &delegate.pubkey(),
+        20,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&delegate]).await.unwrap();
+    assert_eq!(
+        env.get_bank_account(&fixture.bob_account.pubkey())
+            .await
+            .amount,
+        20
+    );
+
+    // The next permit carries the bumped nonce and adds to the allowance.
+    let instructions =
+        permit_instructions(&fixture, &fixture.alice, &delegate.pubkey(), 5, 1, i64::MAX);
+    let env = &mut fixture.env;
+    env.process(&instructions, &[]).await.unwrap();
+    let account = env.get_bank_account(&fixture.alice_account.pubkey()).await;
+    assert_eq!(account.delegated_amount, 15);
+    assert_eq!(account.permit_nonce, 2);
+}
+
+#[tokio::test]
+async fn test_permit_approve_rejected() {
+    let mut fixture = Fixture::new().await;
+    let delegate = Keypair::new().pubkey();
+    let alice = &fixture.alice;
+    let used = permit_instructions(&fixture, alice, &delegate, 30, 0, i64::MAX);
+    fixture.env.process(&used, &[]).await.unwrap();
+
+    // The same permit again, made another transaction by a transfer after it.
+    let mut replayed = used.clone();
+    replayed.push(system_instruction::transfer(
+        &fixture.env.context.payer.pubkey(),
+        &fixture.alice_account.pubkey(),
+        1,
+    ));
+    let mut mismatched = permit_instructions(&fixture, alice, &delegate, 30, 1, i64::MAX);
+    mismatched[1] = permit_approve(
+        &fixture.env.program_id,
+        &fixture.bank.pubkey(),
+        &fixture.alice_account.pubkey(),
+        &delegate,
+        31,
+        1,
+        i64::MAX,
+    )
+    .unwrap();
+    let cases = [
+        ("replayed", replayed, 1, BankError::InvalidPermit),
+        (
+            "signed by another key",
+            permit_instructions(&fixture, &fixture.bob, &delegate, 30, 1, i64::MAX),
+            1,
+            BankError::InvalidPermit,
+        ),
+        (
+            "without its signature",
+            permit_instructions(&fixture, alice, &delegate, 30, 1, i64::MAX)[1..].to_vec(),
+            0,
+            BankError::InvalidPermit,
+        ),
+        (
+            "for another amount",
+            mismatched,
+            1,
+            BankError::InvalidPermit,
+        ),
+        (
+            "expired",
+            permit_instructions(&fixture, alice, &delegate, 30, 1, 0),
+            1,
+            BankError::PermitExpired,
+        ),
+    ];
+    for (name, instructions, index, error) in cases {
+        assert_eq!(
+            fixture.env.process(&instructions, &[]).await,
+            Err(TransactionError::InstructionError(
+                index,
+                InstructionError::Custom(error as u32)
+            )),
+            "{}",
+            name
+        );
+    }
+
+    let account = fixture
+        .env
+        .get_bank_account(&fixture.alice_account.pubkey())
+        .await;
+    assert_eq!(account.delegated_amount, 30);
+    assert_eq!(account.permit_nonce, 1);
+}
+
+#[tokio::test]
+async fn test_burn() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+
+    let instruction = burn(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &fixture.alice_account.pubkey(),
+        &fixture.bank_owner.pubkey(),
+        &fixture.alice.pubkey(),
+        25,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&fixture.bank_owner, &fixture.alice])
+        .await
+        .unwrap();
+    assert_eq!(env.get_bank(&fixture.bank.pubkey()).await.total_supply, 75);
+    assert_eq!(
+        env.get_bank_account(&fixture.alice_account.pubkey())
+            .await
+            .amount,
+        75
+    );
+}
+
+#[tokio::test]
+async fn test_close_account() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let destination = Pubkey::new_unique();
+    let rent = env
+        .context
+        .banks_client
+        .get_account(fixture.bob_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+
+    let instruction = close_account(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &fixture.bob_account.pubkey(),
+        &destination,
+        &fixture.bob.pubkey(),
+    )
+    .unwrap();
+    env.process(&[instruction], &[&fixture.bob]).await.unwrap();
+
+    // With its lamports gone the runtime drops the account at the end of the transaction.
+    assert_eq!(
+        env.context
+            .banks_client
+            .get_account(fixture.bob_account.pubkey())
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        env.context
+            .banks_client
+            .get_balance(destination)
+            .await
+            .unwrap(),
+        rent
+    );
+}
+
+#[tokio::test]
+async fn test_dust() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let (bank, alice_account) = (fixture.bank.pubkey(), fixture.alice_account.pubkey());
+    let set_threshold =
+        set_dust_threshold(&env.program_id, &bank, &fixture.bank_owner.pubkey(), 10).unwrap();
+    let sweep = transfer_sweeping_dust(
+        &env.program_id,
+        &bank,
+        &alice_account,
+        &fixture.bob_account.pubkey(),
+        &fixture.alice.pubkey(),
+        95,
+    )
+    .unwrap();
+    env.process(
+        &[set_threshold, sweep],
+        &[&fixture.bank_owner, &fixture.alice],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.get_bank_account(&alice_account).await.amount, 0);
+    assert_eq!(
+        env.get_bank_account(&fixture.bob_account.pubkey())
+            .await
+            .amount,
+        100
+    );
+
+    // The payer closes alice's emptied account without her, and she gets its rent.
+    let rent = env
+        .context
+        .banks_client
+        .get_balance(alice_account)
+        .await
+        .unwrap();
+    let close = close_dust_account(
+        &env.program_id,
+        &bank,
+        &alice_account,
+        &fixture.alice.pubkey(),
+        None,
+    )
+    .unwrap();
+    env.process(&[close], &[]).await.unwrap();
+    assert_eq!(
+        env.context
+            .banks_client
+            .get_account(alice_account)
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        env.context
+            .banks_client
+            .get_balance(fixture.alice.pubkey())
+            .await
+            .unwrap(),
+        rent
+    );
+}
+
+#[tokio::test]
+async fn test_require_rent_exempt() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let (bank, bank_owner) = (fixture.bank.pubkey(), fixture.bank_owner.pubkey());
+    let set_required = |env: &Env, required| {
+        set_require_rent_exempt(&env.program_id, &bank, &bank_owner, required).unwrap()
+    };
+    let not_rent_exempt = Err(TransactionError::InstructionError(
+        1,
+        InstructionError::Custom(BankError::NotRentExempt as u32),
+    ));
+
+    // A bank or account a lamport short of exemption can't be opened, whoever pays for it.
+    let owner = Keypair::new();
+    let rent = env.context.banks_client.get_rent().await.unwrap();
+    let open_short = |env: &Env, account: &Keypair, space: usize, initialize| {
+        [
+            system_instruction::create_account(
+                &env.context.payer.pubkey(),
+                &account.pubkey(),
+                rent.minimum_balance(space) - 1,
+                space as u64,
+                &env.program_id,
+            ),
+            initialize,
+        ]
+    };
+    let open_short_account = |env: &Env, account: &Keypair| {
+        let instruction =
+            initialize_account(&env.program_id, &bank, &account.pubkey(), &owner.pubkey()).unwrap();
+        open_short(env, account, Account::LEN, instruction)
+    };
+    let short_bank = Keypair::new();
+    let instruction =
+        initialize_bank(&env.program_id, &short_bank.pubkey(), &owner.pubkey(), 2).unwrap();
+    let instructions = open_short(env, &short_bank, Bank::LEN, instruction);
+    assert_eq!(
+        env.process(&instructions, &[&short_bank, &owner]).await,
+        not_rent_exempt
+    );
+    let account = Keypair::new();
+    let instructions = open_short_account(env, &account);
+    assert_eq!(
+        env.process(&instructions, &[&account, &owner]).await,
+        not_rent_exempt
+    );
+
+    let instruction = set_required(env, true);
+    env.process(&[instruction], &[&fixture.bank_owner])
+        .await
+        .unwrap();
+    assert!(env.get_bank(&bank).await.require_rent_exempt);
+    let account = Keypair::new();
+    let instructions = open_short_account(env, &account);
+    assert_eq!(
+        env.process(&instructions, &[&account, &owner]).await,
+        not_rent_exempt
+    );
+    env.create_bank_account(&bank, &Keypair::new(), &owner)
+        .await;
+
+    // Dropping the requirement doesn't let a short account in either.
+    let instruction = set_required(env, false);
+    env.process(&[instruction], &[&fixture.bank_owner])
+        .await
+        .unwrap();
+    assert!(!env.get_bank(&bank).await.require_rent_exempt);
+    let account = Keypair::new();
+    let instructions = open_short_account(env, &account);
+    assert_eq!(
+        env.process(&instructions, &[&account, &owner]).await,
+        not_rent_exempt
+    );
+}
+
+#[tokio::test]
+async fn test_open_fund_and_spend_in_one_transaction() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let carol = Keypair::new();
+    let carol_account = Keypair::new();
+    let rent = env.context.banks_client.get_rent().await.unwrap();
+
+    // Every instruction sees the account as the previous one left it.
+    let instructions = [
+        system_instruction::create_account(
+            &env.context.payer.pubkey(),
+            &carol_account.pubkey(),
+            rent.minimum_balance(Account::LEN),
+            Account::LEN as u64,
+            &env.program_id,
+        ),
+        initialize_account(
+            &env.program_id,
+            &fixture.bank.pubkey(),
+            &carol_account.pubkey(),
+            &carol.pubkey(),
+        )
+        .unwrap(),
+        mint_to(
+            &env.program_id,
+            &fixture.bank.pubkey(),
+            &carol_account.pubkey(),
+            &fixture.bank_owner.pubkey(),
+            50,
+        )
+        .unwrap(),
+        transfer(
+            &env.program_id,
+            &fixture.bank.pubkey(),
+            &carol_account.pubkey(),
+            &fixture.alice_account.pubkey(),
+            &carol.pubkey(),
+            20,
+        )
+        .unwrap(),
+        burn(
+            &env.program_id,
+            &fixture.bank.pubkey(),
+            &carol_account.pubkey(),
+            &fixture.bank_owner.pubkey(),
+            &carol.pubkey(),
+            10,
+        )
+        .unwrap(),
+    ];
+    env.process(
+        &instructions,
+        &[&carol_account, &carol, &fixture.bank_owner],
+    )
+    .await
+    .unwrap();
+
+    let account = env.get_bank_account(&carol_account.pubkey()).await;
+    assert!(account.is_opened);
+    assert_eq!(account.bank, fixture.bank.pubkey());
+    assert_eq!(account.amount, 20);
+    assert_eq!(
+        env.get_bank_account(&fixture.alice_account.pubkey())
+            .await
+            .amount,
+        120
+    );
+    assert_eq!(env.get_bank(&fixture.bank.pubkey()).await.total_supply, 140);
+}
+
+#[tokio::test]
+async fn test_delegation_round_trip_in_one_transaction() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let delegate = Keypair::new();
+
+    let instructions = [
+        approve(
+            &env.program_id,
+            &fixture.bank.pubkey(),
+            &fixture.alice_account.pubkey(),
+            &delegate.pubkey(),
+            &fixture.alice.pubkey(),
+            30,
+        )
+        .unwrap(),
+        transfer(
+            &env.program_id,
+            &fixture.bank.pubkey(),
+            &fixture.alice_account.pubkey(),
+            &fixture.bob_account.pubkey(),
+            &delegate.pubkey(),
+            20,
+        )
+        .unwrap(),
+        revoke(
+            &env.program_id,
+            &fixture.bank.pubkey(),
+            &fixture.alice_account.pubkey(),
+            &fixture.alice.pubkey(),
+        )
+        .unwrap(),
+    ];
+    env.process(&instructions, &[&fixture.alice, &delegate])
+        .await
+        .unwrap();
+
+    let account = env.get_bank_account(&fixture.alice_account.pubkey()).await;
+    assert_eq!(account.amount, 80);
+    assert_eq!(account.delegated_amount, 0);
+    assert!(account.delegate.is_none());
+    assert_eq!(
+        env.get_bank_account(&fixture.bob_account.pubkey())
+            .await
+            .amount,
+        20
+    );
+}
+
+#[tokio::test]
+async fn test_failed_instruction_rolls_back_transaction() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+
+    // The first transfer alone would succeed; the second overdraws the balance it left.
+    let instructions = [
+        mint_to(
+            &env.program_id,
+            &fixture.bank.pubkey(),
+            &fixture.bob_account.pubkey(),
+            &fixture.bank_owner.pubkey(),
+            10,
+        )
+        .unwrap(),
+        transfer(
+            &env.program_id,
+            &fixture.bank.pubkey(),
+            &fixture.alice_account.pubkey(),
+            &fixture.bob_account.pubkey(),
+            &fixture.alice.pubkey(),
+            60,
+        )
+        .unwrap(),
+        transfer(
+            &env.program_id,
+            &fixture.bank.pubkey(),
+            &fixture.alice_account.pubkey(),
+            &fixture.bob_account.pubkey(),
+            &fixture.alice.pubkey(),
+            60,
+        )
+        .unwrap(),
+    ];
+    assert_eq!(
+        env.process(&instructions, &[&fixture.bank_owner, &fixture.alice])
+            .await,
+        Err(TransactionError::InstructionError(
+            2,
+            InstructionError::Custom(BankError::InsufficientFunds as u32)
+        ))
+    );
+
+    assert_eq!(
+        env.get_bank_account(&fixture.alice_account.pubkey())
+            .await
+            .amount,
+        100
+    );
+    assert_eq!(
+        env.get_bank_account(&fixture.bob_account.pubkey())
+            .await
+            .amount,
+        0
+    );
+    assert_eq!(env.get_bank(&fixture.bank.pubkey()).await.total_supply, 100);
+}
+
+const VAULT_SEED: &[u8] = b"vault";
+
+/// A program owning bank accounts through its PDA: it forwards its instruction to the
+/// bank program, its last account, signing for the PDA.
+fn process_vault(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+    let (bank_program_info, accounts) = accounts
+        .split_last()
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let (vault, bump) = Pubkey::find_program_address(&[VAULT_SEED], program_id);
+    let metas = accounts
+        .iter()
+        .map(|info| {
+            let is_signer = info.is_signer || *info.key == vault;
+            if info.is_writable {
+                AccountMeta::new(*info.key, is_signer)
+            } else {
+                AccountMeta::new_readonly(*info.key, is_signer)
+            }
+        })
+        .collect();
+    let instruction = Instruction {
+        program_id: *bank_program_info.key,
+        accounts: metas,
+        data: input.to_vec(),
+    };
+    invoke_signed(&instruction, accounts, &[&[VAULT_SEED, &[bump]]])
+}
+
+/// `instruction`, with the vault's PDA among its signers, sent through the vault program.
+fn through_vault(vault_program_id: &Pubkey, instruction: Instruction) -> Instruction {
+    let mut accounts: Vec<_> = instruction
+        .accounts
+        .into_iter()
+        .map(|meta| AccountMeta {
+            is_signer: false,
+            ..meta
+        })
+        .collect();
+    accounts.push(AccountMeta::new_readonly(instruction.program_id, false));
+    Instruction {
+        program_id: *vault_program_id,
+        accounts,
+        data: instruction.data,
+    }
+}
+
+#[tokio::test]
+async fn test_pda_owned_account() {
+    let vault_program_id = Pubkey::new_unique();
+    let mut env = Env::start_with(|program_test| {
+        program_test.add_program("vault", vault_program_id, processor!(process_vault))
+    })
+    .await;
+    let (vault, _) = Pubkey::find_program_address(&[VAULT_SEED], &vault_program_id);
+    let (bank, bank_owner) = (Keypair::new(), Keypair::new());
+    env.create_bank(&bank, &bank_owner, 2).await;
+    let (bob, bob_account) = (Keypair::new(), Keypair::new());
+    env.create_bank_account(&bank.pubkey(), &bob_account, &bob)
+        .await;
+
+    // The vault opens an account of its own and is given tokens.
+    let vault_account = Keypair::new();
+    env.create_account(&vault_account, Account::LEN).await;
+    let instruction = initialize_account(
+        &env.program_id,
+        &bank.pubkey(),
+        &vault_account.pubkey(),
+        &vault,
+    )
+    .unwrap();
+    env.process(&[through_vault(&vault_program_id, instruction)], &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.get_bank_account(&vault_account.pubkey()).await.owner,
+        vault
+    );
+    let instruction = mint_to(
+        &env.program_id,
+        &bank.pubkey(),
+        &vault_account.pubkey(),
+        &bank_owner.pubkey(),
+        100,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&bank_owner]).await.unwrap();
+
+    // Nobody but the vault program can sign for the PDA.
+    let instruction = transfer(
+        &env.program_id,
+        &bank.pubkey(),
+        &vault_account.pubkey(),
+        &bob_account.pubkey(),
+        &vault,
+        40,
+    )
+    .unwrap();
+    let mut unsigned = instruction.clone();
+    unsigned.accounts[2].is_signer = false;
+    assert_eq!(
+        env.process(&[unsigned], &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::MissingRequiredSignature
+        ))
+    );
+    env.process(&[through_vault(&vault_program_id, instruction)], &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.get_bank_account(&vault_account.pubkey()).await.amount,
+        60
+    );
+    assert_eq!(env.get_bank_account(&bob_account.pubkey()).await.amount, 40);
+}
+
+#[tokio::test]
+async fn test_assign_to_program() {
+    let vault_program_id = Pubkey::new_unique();
+    let mut fixture = Fixture::new_with(|program_test| {
+        program_test.add_program("vault", vault_program_id, processor!(process_vault))
+    })
+    .await;
+    let env = &mut fixture.env;
+    let (vault, bump) = Pubkey::find_program_address(&[VAULT_SEED], &vault_program_id);
+    let seeds: &[&[u8]] = &[VAULT_SEED, &[bump]];
+    let (alice_account, bob_account) =
+        (fixture.alice_account.pubkey(), fixture.bob_account.pubkey());
+
+    // Alice hands her account to the vault.
+    let instruction = assign_to_program(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &alice_account,
+        &fixture.alice.pubkey(),
+        &vault_program_id,
+        seeds,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&fixture.alice])
+        .await
+        .unwrap();
+    let account = env.get_bank_account(&alice_account).await;
+    assert_eq!(account.owner, vault);
+    assert_eq!(
+        pda::assert_pda_owner(&account, seeds, &vault_program_id),
+        Ok(())
+    );
+
+    // From then on only the vault program moves its tokens.
+    let instruction = transfer(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &alice_account,
+        &bob_account,
+        &fixture.alice.pubkey(),
+        40,
+    )
+    .unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&fixture.alice]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    let instruction = transfer(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &alice_account,
+        &bob_account,
+        &vault,
+        40,
+    )
+    .unwrap();
+    env.process(&[through_vault(&vault_program_id, instruction)], &[])
+        .await
+        .unwrap();
+    assert_eq!(env.get_bank_account(&alice_account).await.amount, 60);
+    assert_eq!(env.get_bank_account(&bob_account).await.amount, 40);
+}
+
+#[tokio::test]
+async fn test_deposit_and_withdraw_sol() {
+    const RATE: u64 = 1_000_000;
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let payer = env.context.payer.pubkey();
+
+    // The fixture's bank doesn't take SOL.
+    let instruction = deposit_sol(
+        &env.program_id,
+        &fixture.bank.pubkey(),
+        &fixture.alice_account.pubkey(),
+        &payer,
+        RATE,
+    )
+    .unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::NoSolRate as u32)
+        ))
+    );
+
+    let bank = Keypair::new();
+    let account = Keypair::new();
+    env.create_account(&bank, Bank::LEN).await;
+    let instruction = initialize_bank_with_sol_rate(
+        &env.program_id,
+        &bank.pubkey(),
+        &fixture.bank_owner.pubkey(),
+        2,
+        BurnPolicy::OwnerAndHolder,
+        RATE,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&fixture.bank_owner])
+        .await
+        .unwrap();
+    env.create_bank_account(&bank.pubkey(), &account, &fixture.alice)
+        .await;
+    let rent = env
+        .context
+        .banks_client
+        .get_balance(bank.pubkey())
+        .await
+        .unwrap();
+
+    let instruction = deposit_sol(
+        &env.program_id,
+        &bank.pubkey(),
+        &account.pubkey(),
+        &payer,
+        5 * RATE,
+    )
+    .unwrap();
+    env.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(env.get_bank_account(&account.pubkey()).await.amount, 5);
+    assert_eq!(env.get_bank(&bank.pubkey()).await.total_supply, 5);
+    assert_eq!(
+        env.context
+            .banks_client
+            .get_balance(bank.pubkey())
+            .await
+            .unwrap(),
+        rent + 5 * RATE
+    );
+
+    let (bank, account, alice) = (bank.pubkey(), account.pubkey(), fixture.alice.pubkey());
+    let destination = Pubkey::new_unique();
+    let withdraw = |env: &Env, lamports| {
+        withdraw_sol(
+            &env.program_id,
+            &bank,
+            &account,
+            &alice,
+            &destination,
+            lamports,
+        )
+        .unwrap()
+    };
+    let instruction = withdraw(env, 2 * RATE);
+    env.process(&[instruction], &[&fixture.alice])
+        .await
+        .unwrap();
+    assert_eq!(env.get_bank_account(&account).await.amount, 3);
+    assert_eq!(env.get_bank(&bank).await.total_supply, 3);
+    assert_eq!(
+        env.context.banks_client.get_balance(bank).await.unwrap(),
+        rent + 3 * RATE
+    );
+    assert_eq!(
+        env.context
+            .banks_client
+            .get_balance(destination)
+            .await
+            .unwrap(),
+        2 * RATE
+    );
+
+    // Minted tokens aren't backed, so the bank won't pay for them out of its rent.
+    let instruction = mint_to(
+        &env.program_id,
+        &bank,
+        &account,
+        &fixture.bank_owner.pubkey(),
+        1,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&fixture.bank_owner])
+        .await
+        .unwrap();
+    let instruction = withdraw(env, 4 * RATE);
+    assert_eq!(
+        env.process(&[instruction], &[&fixture.alice]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::InsufficientFunds as u32)
+        ))
+    );
+    assert_eq!(env.get_bank_account(&account).await.amount, 4);
+
+    // An account with a multisig owner withdraws on its signers' signatures.
+    let signers = [Keypair::new(), Keypair::new()];
+    let signer_keys: Vec<_> = signers.iter().map(|signer| signer.pubkey()).collect();
+    let multisig_account = Keypair::new();
+    let multisig = env
+        .create_multisig_bank_account(&bank, &multisig_account, &signer_keys)
+        .await;
+    let multisig_account = multisig_account.pubkey();
+    let instruction =
+        deposit_sol(&env.program_id, &bank, &multisig_account, &payer, 2 * RATE).unwrap();
+    env.process(&[instruction], &[]).await.unwrap();
+    let withdraw = |env: &Env, signers: &[Pubkey]| {
+        let instruction = withdraw_sol(
+            &env.program_id,
+            &bank,
+            &multisig_account,
+            &multisig,
+            &destination,
+            RATE,
+        )
+        .unwrap();
+        signed_by_multisig(instruction, 2, signers)
+    };
+    assert_eq!(
+        env.process(&[withdraw(env, &signer_keys[..1])], &[&signers[0]])
+            .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::MissingRequiredSignature
+        ))
+    );
+    env.process(&[withdraw(env, &signer_keys)], &[&signers[0], &signers[1]])
+        .await
+        .unwrap();
+    assert_eq!(env.get_bank_account(&multisig_account).await.amount, 1);
+    assert_eq!(
+        env.context
+            .banks_client
+            .get_balance(destination)
+            .await
+            .unwrap(),
+        3 * RATE
+    );
+}
+
+#[tokio::test]
+async fn test_convert() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let (bank, alice_account) = (fixture.bank.pubkey(), fixture.alice_account.pubkey());
+
+    // A second bank, with 3 decimals to the fixture's 2, where Bob holds an account.
+    let other_bank = Keypair::new();
+    let other_bank_owner = Keypair::new();
+    let bob_other_account = Keypair::new();
+    env.create_bank(&other_bank, &other_bank_owner, 3).await;
+    env.create_bank_account(&other_bank.pubkey(), &bob_other_account, &fixture.bob)
+        .await;
+    let (other_bank, bob_other_account) = (other_bank.pubkey(), bob_other_account.pubkey());
+
+    let rate = Keypair::new();
+    env.create_account(&rate, ExchangeRate::LEN).await;
+    let set_rate = |env: &Env, from_bank_owner: &Pubkey| {
+        set_exchange_rate(
+            &env.program_id,
+            &rate.pubkey(),
+            &bank,
+            &other_bank,
+            from_bank_owner,
+            &other_bank_owner.pubkey(),
+            3,
+            2,
+        )
+        .unwrap()
+    };
+    // Only the owners of both banks set a rate between them.
+    let instruction = set_rate(env, &fixture.alice.pubkey());
+    assert_eq!(
+        env.process(&[instruction], &[&fixture.alice, &other_bank_owner])
+            .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    let instruction = set_rate(env, &fixture.bank_owner.pubkey());
+    env.process(&[instruction], &[&fixture.bank_owner, &other_bank_owner])
+        .await
+        .unwrap();
+
+    // 0.40 at 3 for 2 is 0.600.
+    let instruction = convert(
+        &env.program_id,
+        &rate.pubkey(),
+        &bank,
+        &alice_account,
+        &fixture.alice.pubkey(),
+        &other_bank,
+        &bob_other_account,
+        40,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&fixture.alice])
+        .await
+        .unwrap();
+    assert_eq!(env.get_bank_account(&alice_account).await.amount, 60);
+    assert_eq!(env.get_bank(&bank).await.total_supply, 60);
+    assert_eq!(env.get_bank_account(&bob_other_account).await.amount, 600);
+    assert_eq!(env.get_bank(&other_bank).await.total_supply, 600);
+
+    // An account with a multisig owner converts on its signers' signatures; 0.10 is 0.150.
+    let signers = [Keypair::new(), Keypair::new()];
+    let signer_keys: Vec<_> = signers.iter().map(|signer| signer.pubkey()).collect();
+    let multisig_account = Keypair::new();
+    let multisig = env
+        .create_multisig_bank_account(&bank, &multisig_account, &signer_keys)
+        .await;
+    let multisig_account = multisig_account.pubkey();
+    let instruction = mint_to(
+        &env.program_id,
+        &bank,
+        &multisig_account,
+        &fixture.bank_owner.pubkey(),
+        10,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&fixture.bank_owner])
+        .await
+        .unwrap();
+    let convert_from_multisig = |env: &Env, signers: &[Pubkey]| {
+        let instruction = convert(
+            &env.program_id,
+            &rate.pubkey(),
+            &bank,
+            &multisig_account,
+            &multisig,
+            &other_bank,
+            &bob_other_account,
+            10,
+        )
+        .unwrap();
+        signed_by_multisig(instruction, 3, signers)
+    };
+    assert_eq!(
+        env.process(
+            &[convert_from_multisig(env, &signer_keys[1..])],
+            &[&signers[1]]
+        )
+        .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::MissingRequiredSignature
+        ))
+    );
+    env.process(
+        &[convert_from_multisig(env, &signer_keys)],
+        &[&signers[0], &signers[1]],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.get_bank_account(&multisig_account).await.amount, 0);
+    assert_eq!(env.get_bank_account(&bob_other_account).await.amount, 750);
+}
+
+#[tokio::test]
+async fn test_registry() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let bank = fixture.bank.pubkey();
+    let payer = env.context.payer.pubkey();
+
+    // The fixture's accounts were opened without registering.
+    assert_eq!(env.get_bank(&bank).await.registered_accounts, 0);
+    let mut registered = Vec::new();
+    for _ in 0..2 {
+        let account = Keypair::new();
+        env.create_account(&account, Account::LEN).await;
+        let instruction = initialize_registered_account(
+            &env.program_id,
+            &bank,
+            &account.pubkey(),
+            &fixture.bob.pubkey(),
+            &payer,
+            0,
+        )
+        .unwrap();
+        env.process(&[instruction], &[&fixture.bob]).await.unwrap();
+        registered.push(account.pubkey());
+    }
+
+    let bank_state = env.get_bank(&bank).await;
+    assert_eq!(bank_state.registered_accounts, 2);
+    let (page, _) = registry::page_address(&env.program_id, &bank, 0);
+    let page_account = env
+        .context
+        .banks_client
+        .get_account(page)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(page_account.owner, env.program_id);
+    assert_eq!(
+        registry::entries(&bank_state, 0, &page_account.data),
+        registered
+    );
+
+    // The next account goes on page 0 too, not page 1.
+    let account = Keypair::new();
+    env.create_account(&account, Account::LEN).await;
+    let instruction = initialize_registered_account(
+        &env.program_id,
+        &bank,
+        &account.pubkey(),
+        &fixture.bob.pubkey(),
+        &payer,
+        1,
+    )
+    .unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&fixture.bob]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidSeeds
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_social_recovery() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let program_id = env.program_id;
+    let bank = fixture.bank.pubkey();
+    let alice = fixture.alice.pubkey();
+    let alice_account = fixture.alice_account.pubkey();
+    let payer = env.context.payer.pubkey();
+    let guardians: Vec<_> = (0..3).map(|_| Keypair::new()).collect();
+    let keys: Vec<_> = guardians.iter().map(Keypair::pubkey).collect();
+    let heir = Keypair::new();
+    let (recovery, _) = recovery::address(&program_id, &alice_account);
+    let name_guardians = |delay| {
+        set_guardians(
+            &program_id,
+            &bank,
+            &alice_account,
+            &alice,
+            &payer,
+            &keys,
+            2,
+            delay,
+        )
+        .unwrap()
+    };
+
+    // Alice names three guardians, any two of whom can recover her account after a
+    // long delay; the payer funds her recovery state.
+    env.process(&[name_guardians(1_000_000)], &[&fixture.alice])
+        .await
+        .unwrap();
+    let recovery_account = env
+        .context
+        .banks_client
+        .get_account(recovery)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(recovery_account.owner, program_id);
+    let state = Recovery::unpack(&recovery_account.data).unwrap();
+    assert_eq!(state.guardians(), &keys[..]);
+    assert_eq!(state.owner, alice);
+
+    // One guardian isn't enough to start a recovery.
+    let instruction = start_recovery(
+        &program_id,
+        &bank,
+        &alice_account,
+        &keys[..1],
+        &heir.pubkey(),
+    )
+    .unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&guardians[0]]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::NotEnoughGuardians as u32)
+        ))
+    );
+    let instruction = start_recovery(
+        &program_id,
+        &bank,
+        &alice_account,
+        &keys[..2],
+        &heir.pubkey(),
+    )
+    .unwrap();
+    env.process(&[instruction], &[&guardians[0], &guardians[1]])
+        .await
+        .unwrap();
+    let slot = env
+        .context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .slot;
+    let state = Recovery::unpack(
+        &env.context
+            .banks_client
+            .get_account(recovery)
+            .await
+            .unwrap()
+            .unwrap()
+            .data,
+    )
+    .unwrap();
+    assert_eq!(state.pending_owner, COption::Some(heir.pubkey()));
+    assert!(state.activation_slot > slot);
+
+    // It can't complete before the delay passes, and Alice calls it off meanwhile.
+    let instruction = recover_owner(&program_id, &bank, &alice_account, &keys[..2]).unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&guardians[0], &guardians[1]])
+            .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::RecoveryNotReady as u32)
+        ))
+    );
+    let instruction = cancel_recovery(&program_id, &bank, &alice_account, &alice).unwrap();
+    env.process(&[instruction], &[&fixture.alice])
+        .await
+        .unwrap();
+    let instruction = recover_owner(&program_id, &bank, &alice_account, &keys[1..]).unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&guardians[1], &guardians[2]])
+            .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::NoPendingRecovery as u32)
+        ))
+    );
+
+    // Without the delay, the guardians hand the account to the heir outright.
+    env.process(&[name_guardians(0)], &[&fixture.alice])
+        .await
+        .unwrap();
+    let signers = [keys[0], keys[2]];
+    let instruction =
+        start_recovery(&program_id, &bank, &alice_account, &signers, &heir.pubkey()).unwrap();
+    env.process(&[instruction], &[&guardians[0], &guardians[2]])
+        .await
+        .unwrap();
+    let instruction = recover_owner(&program_id, &bank, &alice_account, &signers).unwrap();
+    env.process(&[instruction], &[&guardians[0], &guardians[2]])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.get_bank_account(&alice_account).await.owner,
+        heir.pubkey()
+    );
+
+    let instruction = transfer(
+        &program_id,
+        &bank,
+        &alice_account,
+        &fixture.bob_account.pubkey(),
+        &alice,
+        40,
+    )
+    .unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&fixture.alice]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    let instruction = transfer(
+        &program_id,
+        &bank,
+        &alice_account,
+        &fixture.bob_account.pubkey(),
+        &heir.pubkey(),
+        40,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&heir]).await.unwrap();
+    assert_eq!(env.get_bank_account(&alice_account).await.amount, 60);
+}
+
+#[tokio::test]
+async fn test_session_key() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let program_id = env.program_id;
+    let bank = fixture.bank.pubkey();
+    let alice = fixture.alice.pubkey();
+    let alice_account = fixture.alice_account.pubkey();
+    let bob_account = fixture.bob_account.pubkey();
+    let payer = env.context.payer.pubkey();
+    let slot = env
+        .context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .slot;
+    let game = Keypair::new();
+    let (session, _) = session::address(&program_id, &alice_account, &game.pubkey());
+    let create = |session_key: &Pubkey, max_spend, expiry_slot| {
+        create_session_key(
+            &program_id,
+            &bank,
+            &alice_account,
+            &alice,
+            &payer,
+            session_key,
+            max_spend,
+            expiry_slot,
+        )
+        .unwrap()
+    };
+    let spend = |session_key: &Pubkey, amount| {
+        transfer_with_session_key(
+            &program_id,
+            &bank,
+            &alice_account,
+            &bob_account,
+            session_key,
+            amount,
+        )
+        .unwrap()
+    };
+
+    // Alice lets the game spend 50 of her tokens; the payer funds its session state.
+    env.process(
+        &[create(&game.pubkey(), 50, slot + 1_000)],
+        &[&fixture.alice],
+    )
+    .await
+    .unwrap();
+    let session_account = env
+        .context
+        .banks_client
+        .get_account(session)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(session_account.owner, program_id);
+
+    env.process(&[spend(&game.pubkey(), 30)], &[&game])
+        .await
+        .unwrap();
+    assert_eq!(env.get_bank_account(&alice_account).await.amount, 70);
+    assert_eq!(env.get_bank_account(&bob_account).await.amount, 30);
+    let state = Session::unpack(
+        &env.context
+            .banks_client
+            .get_account(session)
+            .await
+            .unwrap()
+            .unwrap()
+            .data,
+    )
+    .unwrap();
+    assert_eq!(state.remaining_spend, 20);
+
+    // Past its limit the key is refused, and without its state it's just a stranger.
+    assert_eq!(
+        env.process(&[spend(&game.pubkey(), 21)], &[&game]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::SessionLimitExceeded as u32)
+        ))
+    );
+    let instruction = transfer(
+        &program_id,
+        &bank,
+        &alice_account,
+        &bob_account,
+        &game.pubkey(),
+        20,
+    )
+    .unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&game]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    env.process(&[spend(&game.pubkey(), 20)], &[&game])
+        .await
+        .unwrap();
+    assert_eq!(env.get_bank_account(&alice_account).await.amount, 50);
+
+    // A key past its expiry slot is refused too.
+    let bot = Keypair::new();
+    env.process(&[create(&bot.pubkey(), 50, slot - 1)], &[&fixture.alice])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.process(&[spend(&bot.pubkey(), 10)], &[&bot]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::SessionExpired as u32)
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_rate_limit() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let (program_id, bank) = (env.program_id, fixture.bank.pubkey());
+    let (alice_account, bob_account) =
+        (fixture.alice_account.pubkey(), fixture.bob_account.pubkey());
+    let treasury = Keypair::new();
+    let treasury_account = Keypair::new();
+    env.create_account(&treasury_account, rate_limit::account_len(0))
+        .await;
+    let instruction = initialize_account(
+        &program_id,
+        &bank,
+        &treasury_account.pubkey(),
+        &treasury.pubkey(),
+    )
+    .unwrap();
+    env.process(&[instruction], &[&treasury]).await.unwrap();
+    let (treasury_pubkey, treasury_account_pubkey) = (treasury.pubkey(), treasury_account.pubkey());
+    let limit = |capacity| {
+        set_rate_limit(
+            &program_id,
+            &bank,
+            &treasury_account_pubkey,
+            &treasury_pubkey,
+            capacity,
+            0,
+        )
+        .unwrap()
+    };
+    let pay = |amount| {
+        transfer(
+            &program_id,
+            &bank,
+            &treasury_account_pubkey,
+            &bob_account,
+            &treasury_pubkey,
+            amount,
+        )
+        .unwrap()
+    };
+    let rate_limited = Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(BankError::RateLimited as u32),
+    ));
+
+    // Tokens coming in aren't limited.
+    let instruction = transfer(
+        &program_id,
+        &bank,
+        &alice_account,
+        &treasury_account_pubkey,
+        &fixture.alice.pubkey(),
+        100,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&fixture.alice])
+        .await
+        .unwrap();
+
+    // With no refill, 50 can go out in all.
+    env.process(&[limit(50)], &[&treasury]).await.unwrap();
+    env.process(&[pay(30)], &[&treasury]).await.unwrap();
+    assert_eq!(env.process(&[pay(25)], &[&treasury]).await, rate_limited);
+    env.process(&[pay(20)], &[&treasury]).await.unwrap();
+
+    // A higher capacity doesn't refill the bucket; lifting the limit frees the rest.
+    env.process(&[limit(80)], &[&treasury]).await.unwrap();
+    assert_eq!(env.process(&[pay(1)], &[&treasury]).await, rate_limited);
+    env.process(&[limit(0)], &[&treasury]).await.unwrap();
+    env.process(&[pay(50)], &[&treasury]).await.unwrap();
+    let data = env
+        .context
+        .banks_client
+        .get_account(treasury_account_pubkey)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    assert_eq!(Account::unpack_extended(&data).unwrap().amount, 0);
+    assert_eq!(rate_limit::bucket(&data).unwrap().capacity, 0);
+    assert_eq!(env.get_bank_account(&bob_account).await.amount, 100);
+
+    // An account created without room for a bucket can't be limited.
+    let instruction = set_rate_limit(
+        &program_id,
+        &bank,
+        &alice_account,
+        &fixture.alice.pubkey(),
+        50,
+        1,
+    )
+    .unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&fixture.alice]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidAccountData
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_version() {
+    let mut env = Env::start_with(|_| {}).await;
+    let program_id = env.program_id;
+    let (record, _) = version::address(&program_id);
+    let instruction = initialize_version(&program_id, &env.context.payer.pubkey()).unwrap();
+    env.process(&[instruction], &[]).await.unwrap();
+    let account = env
+        .context
+        .banks_client
+        .get_account(record)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, program_id);
+    let recorded = Version::unpack(&account.data).unwrap();
+    assert_eq!(recorded, version::current());
+    assert!(version::is_compatible(&recorded));
+
+    // Running it again rewrites the record in place, so its payer pays nothing.
+    let unfunded = Keypair::new();
+    let instruction = initialize_version(&program_id, &unfunded.pubkey()).unwrap();
+    env.process(&[instruction], &[&unfunded]).await.unwrap();
+    let account = env
+        .context
+        .banks_client
+        .get_account(record)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Version::unpack(&account.data), Ok(version::current()));
+}
+
+#[tokio::test]
+async fn test_feature_gate() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let (program_id, bank) = (env.program_id, fixture.bank.pubkey());
+    let (alice_account, bob_account) =
+        (fixture.alice_account.pubkey(), fixture.bob_account.pubkey());
+    let payer = env.context.payer.pubkey();
+    let hops = [(alice_account, fixture.alice.pubkey())];
+    let route = |amount| transfer_route(&program_id, &bank, &hops, &bob_account, amount).unwrap();
+    let set = |enabled| {
+        set_features(
+            &program_id,
+            &bank,
+            &fixture.bank_owner.pubkey(),
+            &payer,
+            enabled,
+        )
+        .unwrap()
+    };
+
+    // The bank owner turns routes off; the payer funds the bank's gate.
+    env.process(&[set(feature_gate::PERMITS)], &[&fixture.bank_owner])
+        .await
+        .unwrap();
+    let (gate, _) = feature_gate::address(&program_id, &bank);
+    let gate_account = env
+        .context
+        .banks_client
+        .get_account(gate)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(gate_account.owner, program_id);
+    assert_eq!(
+        FeatureGate::unpack(&gate_account.data).unwrap().enabled,
+        feature_gate::PERMITS
+    );
+    assert_eq!(
+        env.process(&[route(5)], &[&fixture.alice]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::FeatureDisabled as u32)
+        ))
+    );
+
+    // Turning them back on lets the route through.
+    env.process(&[set(feature_gate::ROUTES)], &[&fixture.bank_owner])
+        .await
+        .unwrap();
+    env.process(&[route(10)], &[&fixture.alice]).await.unwrap();
+    assert_eq!(env.get_bank_account(&bob_account).await.amount, 10);
+
+    // Nobody but the bank owner sets the features.
+    let instruction = set_features(&program_id, &bank, &fixture.alice.pubkey(), &payer, 0).unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&fixture.alice]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_freeze_account() {
+    let mut env = Env::start_with(|_| {}).await;
+    let program_id = env.program_id;
+    let (bank, bank_owner, freeze_authority) = (Keypair::new(), Keypair::new(), Keypair::new());
+    let (alice, alice_account) = (Keypair::new(), Keypair::new());
+    let (bob, bob_account) = (Keypair::new(), Keypair::new());
+    env.create_account(&bank, Bank::LEN).await;
+    let instruction = initialize_bank_with_freeze_authority(
+        &program_id,
+        &bank.pubkey(),
+        &bank_owner.pubkey(),
+        2,
+        BurnPolicy::OwnerAndHolder,
+        0,
+        Some(&freeze_authority.pubkey()),
+    )
+    .unwrap();
+    env.process(&[instruction], &[&bank_owner]).await.unwrap();
+    assert_eq!(
+        env.get_bank(&bank.pubkey()).await.freeze_authority,
+        COption::Some(freeze_authority.pubkey())
+    );
+    env.create_bank_account(&bank.pubkey(), &alice_account, &alice)
+        .await;
+    env.create_bank_account(&bank.pubkey(), &bob_account, &bob)
+        .await;
+    let mint = |amount| {
+        mint_to(
+            &program_id,
+            &bank.pubkey(),
+            &alice_account.pubkey(),
+            &bank_owner.pubkey(),
+            amount,
+        )
+        .unwrap()
+    };
+    env.process(&[mint(100)], &[&bank_owner]).await.unwrap();
+    let send = |amount| {
+        transfer(
+            &program_id,
+            &bank.pubkey(),
+            &alice_account.pubkey(),
+            &bob_account.pubkey(),
+            &alice.pubkey(),
+            amount,
+        )
+        .unwrap()
+    };
+    let frozen = Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(BankError::AccountFrozen as u32),
+    ));
+
+    let freeze = freeze_account(
+        &program_id,
+        &bank.pubkey(),
+        &alice_account.pubkey(),
+        &freeze_authority.pubkey(),
+    )
+    .unwrap();
+    env.process(&[freeze.clone()], &[&freeze_authority])
+        .await
+        .unwrap();
+    assert!(
+        env.get_bank_account(&alice_account.pubkey())
+            .await
+            .is_frozen
+    );
+
+    // Nothing moves out of or into a frozen account.
+    assert_eq!(env.process(&[send(10)], &[&alice]).await, frozen);
+    assert_eq!(env.process(&[mint(1)], &[&bank_owner]).await, frozen);
+    let instruction = transfer(
+        &program_id,
+        &bank.pubkey(),
+        &bob_account.pubkey(),
+        &alice_account.pubkey(),
+        &bob.pubkey(),
+        0,
+    )
+    .unwrap();
+    assert_eq!(env.process(&[instruction], &[&bob]).await, frozen);
+    let instruction = close_account(
+        &program_id,
+        &bank.pubkey(),
+        &alice_account.pubkey(),
+        &alice.pubkey(),
+        &alice.pubkey(),
+    )
+    .unwrap();
+    assert_eq!(env.process(&[instruction], &[&alice]).await, frozen);
+
+    // Only the freeze authority freezes, and an account freezes once.
+    let instruction = freeze_account(
+        &program_id,
+        &bank.pubkey(),
+        &bob_account.pubkey(),
+        &bank_owner.pubkey(),
+    )
+    .unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&bank_owner]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    assert_eq!(
+        env.process(&[freeze.clone(), freeze], &[&freeze_authority])
+            .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidArgument
+        ))
+    );
+
+    let thaw = thaw_account(
+        &program_id,
+        &bank.pubkey(),
+        &alice_account.pubkey(),
+        &freeze_authority.pubkey(),
+    )
+    .unwrap();
+    env.process(&[thaw], &[&freeze_authority]).await.unwrap();
+    env.process(&[send(20)], &[&alice]).await.unwrap();
+    assert_eq!(env.get_bank_account(&bob_account.pubkey()).await.amount, 20);
+
+    // A bank opened without a freeze authority has nobody to freeze its accounts.
+    let (other_bank, other_account) = (Keypair::new(), Keypair::new());
+    env.create_bank(&other_bank, &bank_owner, 2).await;
+    env.create_bank_account(&other_bank.pubkey(), &other_account, &alice)
+        .await;
+    let instruction = freeze_account(
+        &program_id,
+        &other_bank.pubkey(),
+        &other_account.pubkey(),
+        &freeze_authority.pubkey(),
+    )
+    .unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&freeze_authority]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::NoFreezeAuthority as u32)
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_multisig() {
+    let mut env = Env::start_with(|_| {}).await;
+    let program_id = env.program_id;
+    let multisig = Keypair::new();
+    let signers = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let signer_keys: Vec<_> = signers.iter().map(|signer| signer.pubkey()).collect();
+    let (bank, alice_account) = (Keypair::new(), Keypair::new());
+    let (bob, bob_account) = (Keypair::new(), Keypair::new());
+    env.create_account(&multisig, Multisig::LEN).await;
+    let instruction = initialize_multisig(
+        &program_id,
+        &multisig.pubkey(),
+        &signer_keys.iter().collect::<Vec<_>>(),
+        2,
+    )
+    .unwrap();
+    env.process(&[instruction.clone()], &[]).await.unwrap();
+    let account = env
+        .context
+        .banks_client
+        .get_account(multisig.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let state = Multisig::unpack(&account.data).unwrap();
+    assert_eq!((state.threshold, state.signers()), (2, &signer_keys[..]));
+    assert_eq!(
+        env.process(&[instruction], &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::AccountAlreadyInitialized
+        ))
+    );
+
+    // The multisig owns both the bank and alice's account, without signing for either.
+    env.create_account(&bank, Bank::LEN).await;
+    let mut instruction =
+        initialize_bank(&program_id, &bank.pubkey(), &multisig.pubkey(), 2).unwrap();
+    instruction.accounts[1].is_signer = false;
+    env.process(&[instruction], &[]).await.unwrap();
+    env.create_account(&alice_account, Account::LEN).await;
+    let mut instruction = initialize_account(
+        &program_id,
+        &bank.pubkey(),
+        &alice_account.pubkey(),
+        &multisig.pubkey(),
+    )
+    .unwrap();
+    instruction.accounts[2].is_signer = false;
+    env.process(&[instruction], &[]).await.unwrap();
+    env.create_bank_account(&bank.pubkey(), &bob_account, &bob)
+        .await;
+
+    let mint = |signers: &[&Pubkey], amount| {
+        mint_to_multisig(
+            &program_id,
+            &bank.pubkey(),
+            &alice_account.pubkey(),
+            &multisig.pubkey(),
+            signers,
+            amount,
+        )
+        .unwrap()
+    };
+    let send = |signers: &[&Pubkey], amount| {
+        transfer_multisig(
+            &program_id,
+            &bank.pubkey(),
+            &alice_account.pubkey(),
+            &bob_account.pubkey(),
+            &multisig.pubkey(),
+            signers,
+            amount,
+        )
+        .unwrap()
+    };
+    let unsigned = Err(TransactionError::InstructionError(
+        0,
+        InstructionError::MissingRequiredSignature,
+    ));
+
+    // Two of the three signers mint and transfer; one alone does neither.
+    assert_eq!(
+        env.process(&[mint(&[&signer_keys[0]], 100)], &[&signers[0]])
+            .await,
+        unsigned
+    );
+    env.process(
+        &[mint(&[&signer_keys[0], &signer_keys[2]], 100)],
+        &[&signers[0], &signers[2]],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        env.get_bank_account(&alice_account.pubkey()).await.amount,
+        100
+    );
+    assert_eq!(
+        env.process(&[send(&[&signer_keys[1]], 40)], &[&signers[1]])
+            .await,
+        unsigned
+    );
+    // A signer passed twice counts once.
+    assert_eq!(
+        env.process(
+            &[send(&[&signer_keys[1], &signer_keys[1]], 41)],
+            &[&signers[1]]
+        )
+        .await,
+        unsigned
+    );
+    env.process(
+        &[send(&[&signer_keys[1], &signer_keys[2]], 40)],
+        &[&signers[1], &signers[2]],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        env.get_bank_account(&alice_account.pubkey()).await.amount,
+        60
+    );
+    assert_eq!(env.get_bank_account(&bob_account.pubkey()).await.amount, 40);
+}
+
+#[tokio::test]
+async fn test_associated_account() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let program_id = env.program_id;
+    let bank = fixture.bank.pubkey();
+    let carol = Keypair::new();
+    let address = get_associated_bank_account_address(&carol.pubkey(), &bank, &program_id);
+    let instruction = initialize_associated_account(
+        &program_id,
+        &bank,
+        &carol.pubkey(),
+        &env.context.payer.pubkey(),
+    )
+    .unwrap();
+
+    // Only the address derived from the wallet and the bank will do.
+    let mut wrong = instruction.clone();
+    wrong.accounts[1].pubkey =
+        get_associated_bank_account_address(&fixture.bob.pubkey(), &bank, &program_id);
+    assert_eq!(
+        env.process(&[wrong], &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidSeeds
+        ))
+    );
+    assert_eq!(
+        env.process(&[instruction.clone(), instruction.clone()], &[])
+            .await,
+        Err(TransactionError::InstructionError(
+            1,
+            InstructionError::AccountAlreadyInitialized
+        ))
+    );
+
+    // Carol doesn't sign for her account to be opened.
+    env.process(&[instruction], &[]).await.unwrap();
+    let account = env.get_bank_account(&address).await;
+    assert!(account.is_opened && account.is_initialized);
+    assert_eq!((account.owner, account.bank), (carol.pubkey(), bank));
+    let rent = env.context.banks_client.get_rent().await.unwrap();
+    let lamports = env
+        .context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+    assert_eq!(lamports, rent.minimum_balance(Account::LEN));
+
+    let instruction = transfer(
+        &program_id,
+        &bank,
+        &fixture.alice_account.pubkey(),
+        &address,
+        &fixture.alice.pubkey(),
+        30,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&fixture.alice])
+        .await
+        .unwrap();
+    assert_eq!(env.get_bank_account(&address).await.amount, 30);
+}
+
+#[tokio::test]
+async fn test_associated_account_prefunded() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let program_id = env.program_id;
+    let bank = fixture.bank.pubkey();
+    let carol = Keypair::new();
+    let address = get_associated_bank_account_address(&carol.pubkey(), &bank, &program_id);
+
+    // Lamports sent to the address ahead of time don't keep the account from opening.
+    let prefund = system_instruction::transfer(&env.context.payer.pubkey(), &address, 1_000);
+    env.process(&[prefund], &[]).await.unwrap();
+    let instruction = initialize_associated_account(
+        &program_id,
+        &bank,
+        &carol.pubkey(),
+        &env.context.payer.pubkey(),
+    )
+    .unwrap();
+    env.process(&[instruction], &[]).await.unwrap();
+
+    let account = env.get_bank_account(&address).await;
+    assert!(account.is_opened && account.is_initialized);
+    assert_eq!((account.owner, account.bank), (carol.pubkey(), bank));
+    let rent = env.context.banks_client.get_rent().await.unwrap();
+    let account = env
+        .context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, program_id);
+    assert_eq!(account.data.len(), Account::LEN);
+    assert_eq!(account.lamports, rent.minimum_balance(Account::LEN));
+}
+
+#[tokio::test]
+async fn test_checked_instructions() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let program_id = env.program_id;
+    let bank = fixture.bank.pubkey();
+    let mismatch = Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(BankError::DecimalsMismatch as u32),
+    ));
+
+    // The fixture's bank has 2 decimals, so 9 is refused and 2 goes through.
+    for decimals in [9, 2] {
+        let instruction = mint_to_checked(
+            &program_id,
+            &bank,
+            &fixture.alice_account.pubkey(),
+            &fixture.bank_owner.pubkey(),
+            &[],
+            10,
+            decimals,
+        )
+        .unwrap();
+        let result = env.process(&[instruction], &[&fixture.bank_owner]).await;
+        if decimals == 2 {
+            result.unwrap();
+        } else {
+            assert_eq!(result, mismatch);
+        }
+
+        let instruction = transfer_checked(
+            &program_id,
+            &bank,
+            &fixture.alice_account.pubkey(),
+            &fixture.bob_account.pubkey(),
+            &fixture.alice.pubkey(),
+            &[],
+            30,
+            decimals,
+        )
+        .unwrap();
+        let result = env.process(&[instruction], &[&fixture.alice]).await;
+        if decimals == 2 {
+            result.unwrap();
+        } else {
+            assert_eq!(result, mismatch);
+        }
+
+        let instruction = burn_checked(
+            &program_id,
+            &bank,
+            &fixture.bob_account.pubkey(),
+            &fixture.bank_owner.pubkey(),
+            &fixture.bob.pubkey(),
+            5,
+            decimals,
+        )
+        .unwrap();
+        let result = env
+            .process(&[instruction], &[&fixture.bank_owner, &fixture.bob])
+            .await;
+        if decimals == 2 {
+            result.unwrap();
+        } else {
+            assert_eq!(result, mismatch);
+        }
+    }
+
+    assert_eq!(env.get_bank(&bank).await.total_supply, 105);
+    assert_eq!(
+        env.get_bank_account(&fixture.alice_account.pubkey())
+            .await
+            .amount,
+        80
+    );
+    assert_eq!(
+        env.get_bank_account(&fixture.bob_account.pubkey())
+            .await
+            .amount,
+        25
+    );
+}
+
+#[tokio::test]
+async fn test_set_authority() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let program_id = env.program_id;
+    let bank = fixture.bank.pubkey();
+    let account = fixture.alice_account.pubkey();
+    let bob_account = fixture.bob_account.pubkey();
+    let carol = Keypair::new();
+
+    let instruction = approve(
+        &program_id,
+        &bank,
+        &account,
+        &fixture.bob.pubkey(),
+        &fixture.alice.pubkey(),
+        40,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&fixture.alice])
+        .await
+        .unwrap();
+    let instruction = set_authority(
+        &program_id,
+        &account,
+        AuthorityType::AccountOwner,
+        &fixture.alice.pubkey(),
+        Some(&carol.pubkey()),
+    )
+    .unwrap();
+    env.process(&[instruction], &[&fixture.alice])
+        .await
+        .unwrap();
+    let state = env.get_bank_account(&account).await;
+    assert_eq!(state.owner, carol.pubkey());
+    assert_eq!((state.delegate, state.delegated_amount), (COption::None, 0));
+
+    // Alice no longer spends from the account; Carol does.
+    let send = |owner: &Keypair, amount| {
+        transfer(
+            &program_id,
+            &bank,
+            &account,
+            &bob_account,
+            &owner.pubkey(),
+            amount,
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        env.process(&[send(&fixture.alice, 10)], &[&fixture.alice])
+            .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    env.process(&[send(&carol, 10)], &[&carol]).await.unwrap();
+
+    let instruction = set_authority(
+        &program_id,
+        &bank,
+        AuthorityType::BankOwner,
+        &fixture.bank_owner.pubkey(),
+        Some(&carol.pubkey()),
+    )
+    .unwrap();
+    env.process(&[instruction], &[&fixture.bank_owner])
+        .await
+        .unwrap();
+    assert_eq!(env.get_bank(&bank).await.bank_owner, carol.pubkey());
+    let mint = |bank_owner: &Keypair| {
+        mint_to(&program_id, &bank, &account, &bank_owner.pubkey(), 5).unwrap()
+    };
+    assert_eq!(
+        env.process(&[mint(&fixture.bank_owner)], &[&fixture.bank_owner])
+            .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    env.process(&[mint(&carol)], &[&carol]).await.unwrap();
+    assert_eq!(env.get_bank_account(&account).await.amount, 95);
+}
+
+#[tokio::test]
+async fn test_pause_bank() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+    let program_id = env.program_id;
+    let bank = fixture.bank.pubkey();
+    let (alice_account, bob_account) =
+        (fixture.alice_account.pubkey(), fixture.bob_account.pubkey());
+    let (alice, bank_owner) = (fixture.alice.pubkey(), fixture.bank_owner.pubkey());
+    let send = |amount| {
+        transfer(
+            &program_id,
+            &bank,
+            &alice_account,
+            &bob_account,
+            &alice,
+            amount,
+        )
+        .unwrap()
+    };
+
+    // A rate to a second bank where bob holds an account, and a policy that sweeps the
+    // balance of a closed account, both set before the pause.
+    let (other_bank, other_bank_owner) = (Keypair::new(), Keypair::new());
+    let bob_other_account = Keypair::new();
+    env.create_bank(&other_bank, &other_bank_owner, 2).await;
+    env.create_bank_account(&other_bank.pubkey(), &bob_other_account, &fixture.bob)
+        .await;
+    let (other_bank, bob_other_account) = (other_bank.pubkey(), bob_other_account.pubkey());
+    let rate = Keypair::new();
+    env.create_account(&rate, ExchangeRate::LEN).await;
+    let instructions = [
+        set_exchange_rate(
+            &program_id,
+            &rate.pubkey(),
+            &bank,
+            &other_bank,
+            &bank_owner,
+            &other_bank_owner.pubkey(),
+            1,
+            1,
+        )
+        .unwrap(),
+        set_close_policy(&program_id, &bank, &bank_owner, ClosePolicy::Sweep).unwrap(),
+    ];
+    env.process(&instructions, &[&fixture.bank_owner, &other_bank_owner])
+        .await
+        .unwrap();
+
+    // Only the bank owner pauses the bank.
+    let instruction = pause_bank(&program_id, &bank, &alice).unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&fixture.alice]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    let instruction = pause_bank(&program_id, &bank, &bank_owner).unwrap();
+    env.process(&[instruction], &[&fixture.bank_owner])
+        .await
+        .unwrap();
+    assert!(env.get_bank(&bank).await.is_paused);
+
+    let paused = Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(BankError::BankPaused as u32),
+    ));
+    assert_eq!(env.process(&[send(10)], &[&fixture.alice]).await, paused);
+    let instruction = mint_to(&program_id, &bank, &bob_account, &bank_owner, 10).unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&fixture.bank_owner]).await,
+        paused
+    );
+    let instruction =
+        approve(&program_id, &bank, &alice_account, &bob_account, &alice, 10).unwrap();
+    assert_eq!(env.process(&[instruction], &[&fixture.alice]).await, paused);
+    let payer = env.context.payer.pubkey();
+    let instruction = deposit_sol(&program_id, &bank, &alice_account, &payer, 1).unwrap();
+    assert_eq!(env.process(&[instruction], &[]).await, paused);
+    let destination = Pubkey::new_unique();
+    let instruction =
+        withdraw_sol(&program_id, &bank, &alice_account, &alice, &destination, 1).unwrap();
+    assert_eq!(env.process(&[instruction], &[&fixture.alice]).await, paused);
+    let instruction = convert(
+        &program_id,
+        &rate.pubkey(),
+        &bank,
+        &alice_account,
+        &alice,
+        &other_bank,
+        &bob_other_account,
+        10,
+    )
+    .unwrap();
+    assert_eq!(env.process(&[instruction], &[&fixture.alice]).await, paused);
+    let instruction = close_account_with_balance(
+        &program_id,
+        &bank,
+        &alice_account,
+        &destination,
+        &alice,
+        Some(&bob_account),
+    )
+    .unwrap();
+    assert_eq!(env.process(&[instruction], &[&fixture.alice]).await, paused);
+
+    let instruction = resume_bank(&program_id, &bank, &bank_owner).unwrap();
+    env.process(&[instruction], &[&fixture.bank_owner])
+        .await
+        .unwrap();
+    env.process(&[send(20)], &[&fixture.alice]).await.unwrap();
+    assert_eq!(env.get_bank_account(&bob_account).await.amount, 20);
+}
+
+#[tokio::test]
+async fn test_emergency_authority() {
+    let mut env = Env::start_with(|_| {}).await;
+    let program_id = env.program_id;
+    let (bank, bank_owner, freeze_authority) = (Keypair::new(), Keypair::new(), Keypair::new());
+    let (alice, alice_account, on_call) = (Keypair::new(), Keypair::new(), Keypair::new());
+    env.create_account(&bank, Bank::LEN).await;
+    let instruction = initialize_bank_with_freeze_authority(
+        &program_id,
+        &bank.pubkey(),
+        &bank_owner.pubkey(),
+        2,
+        BurnPolicy::OwnerAndHolder,
+        0,
+        Some(&freeze_authority.pubkey()),
+    )
+    .unwrap();
+    env.process(&[instruction], &[&bank_owner]).await.unwrap();
+    env.create_bank_account(&bank.pubkey(), &alice_account, &alice)
+        .await;
+    let (bank, alice_account) = (bank.pubkey(), alice_account.pubkey());
+    let illegal_owner = Err(TransactionError::InstructionError(
+        0,
+        InstructionError::IllegalOwner,
+    ));
+
+    // Only the bank owner names the emergency authority.
+    let name = |current_authority: &Keypair| {
+        set_authority(
+            &program_id,
+            &bank,
+            AuthorityType::EmergencyAuthority,
+            &current_authority.pubkey(),
+            Some(&on_call.pubkey()),
+        )
+        .unwrap()
+    };
+    let instruction = pause_bank(&program_id, &bank, &on_call.pubkey()).unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&on_call]).await,
+        illegal_owner
+    );
+    assert_eq!(env.process(&[name(&alice)], &[&alice]).await, illegal_owner);
+    env.process(&[name(&bank_owner)], &[&bank_owner])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.get_bank(&bank).await.emergency_authority,
+        COption::Some(on_call.pubkey())
+    );
+
+    // It freezes, but only the freeze authority thaws.
+    let instruction =
+        freeze_account(&program_id, &bank, &alice_account, &on_call.pubkey()).unwrap();
+    env.process(&[instruction], &[&on_call]).await.unwrap();
+    assert!(env.get_bank_account(&alice_account).await.is_frozen);
+    let instruction = thaw_account(&program_id, &bank, &alice_account, &on_call.pubkey()).unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&on_call]).await,
+        illegal_owner
+    );
+    assert!(env.get_bank_account(&alice_account).await.is_frozen);
+    let instruction = thaw_account(
+        &program_id,
+        &bank,
+        &alice_account,
+        &freeze_authority.pubkey(),
+    )
+    .unwrap();
+    env.process(&[instruction], &[&freeze_authority])
+        .await
+        .unwrap();
+
+    // It pauses, but only the bank owner resumes.
+    let instruction = pause_bank(&program_id, &bank, &on_call.pubkey()).unwrap();
+    env.process(&[instruction], &[&on_call]).await.unwrap();
+    assert!(env.get_bank(&bank).await.is_paused);
+    let instruction = resume_bank(&program_id, &bank, &on_call.pubkey()).unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&on_call]).await,
+        illegal_owner
+    );
+    assert!(env.get_bank(&bank).await.is_paused);
+    let instruction = resume_bank(&program_id, &bank, &bank_owner.pubkey()).unwrap();
+    env.process(&[instruction], &[&bank_owner]).await.unwrap();
+
+    // It never mints.
+    let instruction = mint_to(&program_id, &bank, &alice_account, &on_call.pubkey(), 10).unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&on_call]).await,
+        illegal_owner
+    );
+    assert_eq!(env.get_bank_account(&alice_account).await.amount, 0);
+
+    // The bank owner removes it, after which it pauses nothing.
+    let instruction = set_authority(
+        &program_id,
+        &bank,
+        AuthorityType::EmergencyAuthority,
+        &bank_owner.pubkey(),
+        None,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&bank_owner]).await.unwrap();
+    assert_eq!(env.get_bank(&bank).await.emergency_authority, COption::None);
+    let instruction = pause_bank(&program_id, &bank, &on_call.pubkey()).unwrap();
+    assert_eq!(
+        env.process(&[instruction], &[&on_call]).await,
+        illegal_owner
+    );
+}
+
+#[tokio::test]
+async fn test_owner_change_delay() {
+    let mut env = Env::start_with(|_| {}).await;
+    let program_id = env.program_id;
+    let (bank, bank_owner, on_call, carol) = (
+        Keypair::new(),
+        Keypair::new(),
+        Keypair::new(),
+        Keypair::new(),
+    );
+    env.create_bank(&bank, &bank_owner, 2).await;
+    let bank = bank.pubkey();
+    let instruction = set_authority(
+        &program_id,
+        &bank,
+        AuthorityType::EmergencyAuthority,
+        &bank_owner.pubkey(),
+        Some(&on_call.pubkey()),
+    )
+    .unwrap();
+    env.process(&[instruction], &[&bank_owner]).await.unwrap();
+
+    // The delay only goes up.
+    let set_delay =
+        |delay| set_owner_change_delay(&program_id, &bank, &bank_owner.pubkey(), delay).unwrap();
+    env.process(&[set_delay(100)], &[&bank_owner])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.process(&[set_delay(50)], &[&bank_owner]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidArgument
+        ))
+    );
+    assert_eq!(env.get_bank(&bank).await.owner_change_delay, 100);
+
+    // Handing the bank to Carol only makes her the pending owner.
+    let hand_over = set_authority(
+        &program_id,
+        &bank,
+        AuthorityType::BankOwner,
+        &bank_owner.pubkey(),
+        Some(&carol.pubkey()),
+    )
+    .unwrap();
+    env.process(&[hand_over.clone()], &[&bank_owner])
+        .await
+        .unwrap();
+    let slot = env
+        .context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .slot;
+    let state = env.get_bank(&bank).await;
+    assert_eq!(state.bank_owner, bank_owner.pubkey());
+    assert_eq!(state.pending_bank_owner, COption::Some(carol.pubkey()));
+    assert!(state.owner_change_slot >= slot + 100);
+
+    // The emergency authority calls it off.
+    let cancel =
+        |authority: &Keypair| cancel_owner_change(&program_id, &bank, &authority.pubkey()).unwrap();
+    assert_eq!(
+        env.process(&[cancel(&carol)], &[&carol]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    env.process(&[cancel(&on_call)], &[&on_call]).await.unwrap();
+    assert_eq!(env.get_bank(&bank).await.pending_bank_owner, COption::None);
+    assert_eq!(
+        env.process(&[cancel(&bank_owner)], &[&bank_owner]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::NoPendingOwnerChange as u32)
+        ))
+    );
+
+    // Started again, the change can't be applied before the delay passes.
+    env.refresh_blockhash().await;
+    env.process(&[hand_over.clone()], &[&bank_owner])
+        .await
+        .unwrap();
+    env.refresh_blockhash().await;
+    assert_eq!(
+        env.process(&[hand_over.clone()], &[&bank_owner]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::OwnerChangeNotReady as u32)
+        ))
+    );
+    assert_eq!(env.get_bank(&bank).await.bank_owner, bank_owner.pubkey());
+
+    // After it, sending the change again applies it.
+    let owner_change_slot = env.get_bank(&bank).await.owner_change_slot;
+    env.context.warp_to_slot(owner_change_slot).unwrap();
+    env.refresh_blockhash().await;
+    env.process(&[hand_over], &[&bank_owner]).await.unwrap();
+    let state = env.get_bank(&bank).await;
+    assert_eq!(state.bank_owner, carol.pubkey());
+    assert_eq!(state.pending_bank_owner, COption::None);
+}
+
+#[tokio::test]
+async fn test_interest() {
+    const START: i64 = 1_600_000_000;
+    const YEAR: i64 = interest::SECONDS_PER_YEAR as i64;
+    let mut env = Env::start_with(|_| {}).await;
+    let program_id = env.program_id;
+    let (bank, bank_owner) = (Keypair::new(), Keypair::new());
+    let (alice, alice_account) = (Keypair::new(), Keypair::new());
+    let (bob, bob_account) = (Keypair::new(), Keypair::new());
+    env.create_account(&bank, Bank::LEN).await;
+    // 10% a year.
+    let instruction = initialize_bank_with_interest_rate(
+        &program_id,
+        &bank.pubkey(),
+        &bank_owner.pubkey(),
+        2,
+        BurnPolicy::default(),
+        0,
+        None,
+        1_000,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&bank_owner]).await.unwrap();
+    env.create_bank_account(&bank.pubkey(), &alice_account, &alice)
+        .await;
+    env.create_bank_account(&bank.pubkey(), &bob_account, &bob)
+        .await;
+    let (bank, alice_key) = (bank.pubkey(), alice.pubkey());
+    let (alice_account, bob_account) = (alice_account.pubkey(), bob_account.pubkey());
+
+    // Minting to alice starts the account's clock.
+    env.set_time(START);
+    let instruction = mint_to(
+        &program_id,
+        &bank,
+        &alice_account,
+        &bank_owner.pubkey(),
+        1_000,
+    )
+    .unwrap();
+    env.process(&[instruction], &[&bank_owner]).await.unwrap();
+    assert_eq!(
+        env.get_bank_account(&alice_account).await.last_accrual,
+        START
+    );
+
+    // A year on, alice is paid 10% before sending bob 100.
+    env.set_time(START + YEAR);
+    let mut send = transfer(
+        &program_id,
+        &bank,
+        &alice_account,
+        &bob_account,
+        &alice_key,
+        100,
+    )
+    .unwrap();
+    // A read-only bank can't take the interest into its supply.
+    send.accounts[3].is_writable = false;
+    assert_eq!(
+        env.process(&[send.clone()], &[&alice]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::ReadOnlyBank as u32)
+        ))
+    );
+    send.accounts[3].is_writable = true;
+    env.process(&[send], &[&alice]).await.unwrap();
+    assert_eq!(env.get_bank_account(&alice_account).await.amount, 1_000);
+    assert_eq!(env.get_bank_account(&bob_account).await.amount, 100);
+    assert_eq!(env.get_bank(&bank).await.total_supply, 1_100);
+
+    // Half a year later, anybody can pay bob the 5 owed.
+    env.set_time(START + YEAR + YEAR / 2);
+    let instruction = accrue_interest(&program_id, &bank, &bob_account).unwrap();
+    env.process(&[instruction], &[]).await.unwrap();
+    let bob_state = env.get_bank_account(&bob_account).await;
+    assert_eq!(bob_state.amount, 105);
+    assert_eq!(bob_state.last_accrual, START + YEAR + YEAR / 2);
+    assert_eq!(env.get_bank(&bank).await.total_supply, 1_105);
+    // Alice's interest waits for the account's next accrual.
+    assert_eq!(env.get_bank_account(&alice_account).await.amount, 1_000);
+}
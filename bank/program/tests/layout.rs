@@ -0,0 +1,518 @@
+//! Byte layouts of every instruction and state struct, pinned to the hex fixtures in
+//! `tests/fixtures/layout`. Deployed clients encode and decode these bytes on their
+//! own, so a mismatch here is a breaking change, not a fixture to refresh. After an
+//! intended layout change, rewrite the fixtures with `UPDATE_LAYOUT_FIXTURES=1`.
+
+use solana_bank::{
+    instruction::{AuthorityType, BankInstruction},
+    state::{
+        Account, Bank, BurnPolicy, ClosePolicy, ExchangeRate, FeatureGate, Multisig, Recovery,
+        Session, Version,
+    },
+};
+use solana_program::{
+    program_error::ProgramError, program_option::COption, program_pack::Pack, pubkey::Pubkey,
+};
+use std::{env, fs, path::PathBuf};
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/layout")
+        .join(format!("{}.hex", name))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+/// Compares `bytes` against the fixture `name` and returns the fixture's bytes.
+fn check_fixture(name: &str, bytes: &[u8]) -> Vec<u8> {
+    let path = fixture_path(name);
+    if env::var_os("UPDATE_LAYOUT_FIXTURES").is_some() {
+        fs::write(&path, to_hex(bytes) + "\n").unwrap();
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("read fixture {}: {}", path.display(), e));
+    assert_eq!(
+        to_hex(bytes),
+        expected.trim(),
+        "layout of {} changed; this breaks deployed clients",
+        name
+    );
+    from_hex(expected.trim())
+}
+
+fn key(byte: u8) -> Pubkey {
+    Pubkey::new_from_array([byte; 32])
+}
+
+#[test]
+fn test_instruction_layouts() {
+    let cases = vec![
+        (
+            "instruction_initialize_bank",
+            BankInstruction::InitializeBank {
+                decimals: 9,
+                burn_policy: BurnPolicy::HolderOnly,
+                lamports_per_token: 0x0102_0304,
+                freeze_authority: COption::Some(key(5)),
+                interest_rate_bps: 0x0102,
+            },
+        ),
+        (
+            "instruction_initialize_account",
+            BankInstruction::InitializeAccount,
+        ),
+        (
+            "instruction_transfer",
+            BankInstruction::Transfer {
+                amount: 0x0102_0304_0506_0708,
+                sweep_dust: true,
+            },
+        ),
+        (
+            "instruction_approve",
+            BankInstruction::Approve { amount: 1_000 },
+        ),
+        (
+            "instruction_mint_to",
+            BankInstruction::MintTo { amount: u64::MAX },
+        ),
+        ("instruction_burn", BankInstruction::Burn { amount: 1 }),
+        ("instruction_close_account", BankInstruction::CloseAccount),
+        ("instruction_revoke", BankInstruction::Revoke),
+        (
+            "instruction_set_close_policy",
+            BankInstruction::SetClosePolicy {
+                policy: ClosePolicy::Sweep,
+            },
+        ),
+        (
+            "instruction_set_require_rent_exempt",
+            BankInstruction::SetRequireRentExempt { required: true },
+        ),
+        (
+            "instruction_permit_approve",
+            BankInstruction::PermitApprove {
+                amount: 30,
+                nonce: 0x0102_0304,
+                expiry: -2,
+            },
+        ),
+        (
+            "instruction_transfer_with_expiry",
+            BankInstruction::TransferWithExpiry {
+                amount: 1,
+                max_slot: u64::MAX,
+            },
+        ),
+        (
+            "instruction_transfer_route",
+            BankInstruction::TransferRoute { amount: 1 },
+        ),
+        (
+            "instruction_assign_to_program",
+            BankInstruction::AssignToProgram {
+                seeds: vec![b"vault".to_vec(), vec![], vec![0xff]],
+            },
+        ),
+        (
+            "instruction_deposit_sol",
+            BankInstruction::DepositSol {
+                lamports: 0x0102_0304_0506_0708,
+            },
+        ),
+        (
+            "instruction_withdraw_sol",
+            BankInstruction::WithdrawSol { lamports: u64::MAX },
+        ),
+        (
+            "instruction_set_exchange_rate",
+            BankInstruction::SetExchangeRate {
+                numerator: 3,
+                denominator: 0x0102_0304_0506_0708,
+            },
+        ),
+        (
+            "instruction_convert",
+            BankInstruction::Convert { amount: 1 },
+        ),
+        (
+            "instruction_set_dust_threshold",
+            BankInstruction::SetDustThreshold { threshold: 500 },
+        ),
+        (
+            "instruction_close_dust_account",
+            BankInstruction::CloseDustAccount,
+        ),
+        (
+            "instruction_set_guardians",
+            BankInstruction::SetGuardians {
+                threshold: 2,
+                delay: 0x0102_0304,
+                guardians: vec![key(5), key(6), key(7)],
+            },
+        ),
+        (
+            "instruction_start_recovery",
+            BankInstruction::StartRecovery { new_owner: key(4) },
+        ),
+        ("instruction_recover_owner", BankInstruction::RecoverOwner),
+        (
+            "instruction_cancel_recovery",
+            BankInstruction::CancelRecovery,
+        ),
+        (
+            "instruction_create_session_key",
+            BankInstruction::CreateSessionKey {
+                session_key: key(5),
+                max_spend: 0x0102_0304,
+                expiry_slot: 0x1122_3344_5566_7788,
+            },
+        ),
+        (
+            "instruction_set_rate_limit",
+            BankInstruction::SetRateLimit {
+                capacity: 0x0102_0304,
+                refill_per_slot: 0x1122_3344_5566_7788,
+            },
+        ),
+        (
+            "instruction_initialize_version",
+            BankInstruction::InitializeVersion,
+        ),
+        (
+            "instruction_set_features",
+            BankInstruction::SetFeatures {
+                enabled: 0x0102_0304,
+            },
+        ),
+        ("instruction_freeze_account", BankInstruction::FreezeAccount),
+        ("instruction_thaw_account", BankInstruction::ThawAccount),
+        (
+            "instruction_initialize_multisig",
+            BankInstruction::InitializeMultisig { threshold: 2 },
+        ),
+        (
+            "instruction_initialize_associated_account",
+            BankInstruction::InitializeAssociatedAccount,
+        ),
+        (
+            "instruction_transfer_checked",
+            BankInstruction::TransferChecked {
+                amount: 0x0102_0304_0506_0708,
+                decimals: 9,
+            },
+        ),
+        (
+            "instruction_mint_to_checked",
+            BankInstruction::MintToChecked {
+                amount: u64::MAX,
+                decimals: 0,
+            },
+        ),
+        (
+            "instruction_burn_checked",
+            BankInstruction::BurnChecked {
+                amount: 1,
+                decimals: 255,
+            },
+        ),
+        (
+            "instruction_set_authority",
+            BankInstruction::SetAuthority {
+                authority_type: AuthorityType::BankOwner,
+                new_authority: COption::Some(key(7)),
+            },
+        ),
+        (
+            "instruction_set_authority_removed",
+            BankInstruction::SetAuthority {
+                authority_type: AuthorityType::EmergencyAuthority,
+                new_authority: COption::None,
+            },
+        ),
+        (
+            "instruction_transfer_batch",
+            BankInstruction::TransferBatch {
+                amounts: vec![1, u64::MAX],
+            },
+        ),
+        ("instruction_pause_bank", BankInstruction::PauseBank),
+        ("instruction_resume_bank", BankInstruction::ResumeBank),
+        (
+            "instruction_accrue_interest",
+            BankInstruction::AccrueInterest,
+        ),
+        (
+            "instruction_set_owner_change_delay",
+            BankInstruction::SetOwnerChangeDelay {
+                delay: 0x0102_0304_0506_0708,
+            },
+        ),
+        (
+            "instruction_cancel_owner_change",
+            BankInstruction::CancelOwnerChange,
+        ),
+    ];
+    for (name, instruction) in cases {
+        let bytes = check_fixture(name, &instruction.pack());
+        assert_eq!(BankInstruction::unpack(&bytes), Ok(instruction), "{}", name);
+    }
+
+    // Clients built before burn policies send `InitializeBank` without one, those built
+    // before SOL rates without a rate, those built before freeze authorities without an
+    // authority, and those built before interest without an interest rate.
+    assert_eq!(
+        BankInstruction::unpack(&[0, 9]),
+        Ok(BankInstruction::InitializeBank {
+            decimals: 9,
+            burn_policy: BurnPolicy::OwnerAndHolder,
+            lamports_per_token: 0,
+            freeze_authority: COption::None,
+            interest_rate_bps: 0,
+        })
+    );
+    assert_eq!(
+        BankInstruction::unpack(&[0, 9, 1]),
+        Ok(BankInstruction::InitializeBank {
+            decimals: 9,
+            burn_policy: BurnPolicy::HolderOnly,
+            lamports_per_token: 0,
+            freeze_authority: COption::None,
+            interest_rate_bps: 0,
+        })
+    );
+    assert_eq!(
+        BankInstruction::unpack(&[0, 9, 1, 5]),
+        Err(ProgramError::InvalidInstructionData)
+    );
+    assert_eq!(
+        BankInstruction::unpack(&[0, 9, 1, 5, 0, 0, 0, 0, 0, 0, 0]),
+        Ok(BankInstruction::InitializeBank {
+            decimals: 9,
+            burn_policy: BurnPolicy::HolderOnly,
+            lamports_per_token: 5,
+            freeze_authority: COption::None,
+            interest_rate_bps: 0,
+        })
+    );
+    assert_eq!(
+        BankInstruction::unpack(&[0, 9, 1, 5, 0, 0, 0, 0, 0, 0, 0, 1, 7]),
+        Err(ProgramError::InvalidInstructionData)
+    );
+    let mut with_authority = vec![0, 9, 1, 5, 0, 0, 0, 0, 0, 0, 0, 1];
+    with_authority.extend_from_slice(key(5).as_ref());
+    assert_eq!(
+        BankInstruction::unpack(&with_authority),
+        Ok(BankInstruction::InitializeBank {
+            decimals: 9,
+            burn_policy: BurnPolicy::HolderOnly,
+            lamports_per_token: 5,
+            freeze_authority: COption::Some(key(5)),
+            interest_rate_bps: 0,
+        })
+    );
+    assert_eq!(
+        BankInstruction::unpack(&[0, 9, 1, 5, 0, 0, 0, 0, 0, 0, 0, 0, 0xf4, 1]),
+        Ok(BankInstruction::InitializeBank {
+            decimals: 9,
+            burn_policy: BurnPolicy::HolderOnly,
+            lamports_per_token: 5,
+            freeze_authority: COption::None,
+            interest_rate_bps: 500,
+        })
+    );
+    assert_eq!(
+        BankInstruction::unpack(&[0, 9, 1, 5, 0, 0, 0, 0, 0, 0, 0, 0, 0xf4]),
+        Err(ProgramError::InvalidInstructionData)
+    );
+
+    // Those built before dust sweeping send `Transfer` without the flag.
+    assert_eq!(
+        BankInstruction::unpack(&[2, 1, 0, 0, 0, 0, 0, 0, 0]),
+        Ok(BankInstruction::Transfer {
+            amount: 1,
+            sweep_dust: false,
+        })
+    );
+    assert_eq!(
+        BankInstruction::unpack(&[2, 1, 0, 0, 0, 0, 0, 0, 0, 2]),
+        Err(ProgramError::InvalidInstructionData)
+    );
+}
+
+#[test]
+fn test_bank_layout() {
+    let bank = Bank {
+        decimals: 6,
+        bank_owner: key(1),
+        is_opened: true,
+        total_supply: 0x1122_3344_5566_7788,
+        close_policy: ClosePolicy::Burn,
+        require_rent_exempt: true,
+        burn_policy: BurnPolicy::HolderOnly,
+        lamports_per_token: 1_000_000,
+        transfer_count: 0x0a0b_0c0d,
+        cumulative_volume: 0x0102_0304_0506_0708,
+        holder_count: 42,
+        registered_accounts: 0x0f0e_0d0c,
+        dust_threshold: 0x0506_0708,
+        freeze_authority: COption::Some(key(7)),
+        is_paused: true,
+        interest_rate_bps: 0x0a0b,
+        emergency_authority: COption::Some(key(8)),
+        owner_change_delay: 0x0102_0304,
+        pending_bank_owner: COption::Some(key(9)),
+        owner_change_slot: 0x0506_0708_090a,
+    };
+    let mut packed = vec![0u8; Bank::LEN];
+    Bank::pack(bank, &mut packed).unwrap();
+    let bytes = check_fixture("state_bank", &packed);
+    assert_eq!(Bank::unpack(&bytes), Ok(bank));
+}
+
+#[test]
+fn test_account_layout() {
+    let accounts = vec![
+        (
+            "state_account",
+            Account {
+                amount: 70,
+                is_opened: true,
+                is_initialized: true,
+                owner: key(2),
+                delegate: COption::None,
+                delegated_amount: 0,
+                bank: key(3),
+                permit_nonce: 0,
+                is_frozen: false,
+                last_accrual: 0,
+            },
+        ),
+        (
+            "state_account_delegated",
+            Account {
+                amount: 40,
+                is_opened: true,
+                is_initialized: true,
+                owner: key(2),
+                delegate: COption::Some(key(4)),
+                delegated_amount: 30,
+                bank: key(3),
+                permit_nonce: 0x0102_0304,
+                is_frozen: true,
+                last_accrual: 0x0102_0304_0506,
+            },
+        ),
+    ];
+    for (name, account) in accounts {
+        let mut packed = vec![0u8; Account::LEN];
+        Account::pack(account, &mut packed).unwrap();
+        let bytes = check_fixture(name, &packed);
+        assert_eq!(Account::unpack(&bytes), Ok(account), "{}", name);
+    }
+}
+
+#[test]
+fn test_exchange_rate_layout() {
+    let rate = ExchangeRate {
+        is_initialized: true,
+        from_bank: key(1),
+        to_bank: key(3),
+        numerator: 3,
+        denominator: 0x1122_3344_5566_7788,
+    };
+    let mut packed = vec![0u8; ExchangeRate::LEN];
+    ExchangeRate::pack(rate, &mut packed).unwrap();
+    let bytes = check_fixture("state_exchange_rate", &packed);
+    assert_eq!(ExchangeRate::unpack(&bytes), Ok(rate));
+}
+
+#[test]
+fn test_recovery_layout() {
+    let mut guardians = [Pubkey::default(); 8];
+    guardians[..2].copy_from_slice(&[key(5), key(6)]);
+    let recovery = Recovery {
+        is_initialized: true,
+        account: key(3),
+        owner: key(2),
+        threshold: 2,
+        guardian_count: 2,
+        guardians,
+        delay: 0x0102_0304,
+        pending_owner: COption::Some(key(4)),
+        activation_slot: 0x1122_3344_5566_7788,
+    };
+    let mut packed = vec![0u8; Recovery::LEN];
+    Recovery::pack(recovery, &mut packed).unwrap();
+    let bytes = check_fixture("state_recovery", &packed);
+    assert_eq!(Recovery::unpack(&bytes), Ok(recovery));
+}
+
+#[test]
+fn test_session_layout() {
+    let session = Session {
+        is_initialized: true,
+        account: key(3),
+        owner: key(2),
+        session_key: key(5),
+        remaining_spend: 0x0102_0304,
+        expiry_slot: 0x1122_3344_5566_7788,
+    };
+    let mut packed = vec![0u8; Session::LEN];
+    Session::pack(session, &mut packed).unwrap();
+    let bytes = check_fixture("state_session", &packed);
+    assert_eq!(Session::unpack(&bytes), Ok(session));
+}
+
+#[test]
+fn test_version_layout() {
+    let version = Version {
+        is_initialized: true,
+        major: 0x0102,
+        minor: 0x0304,
+        patch: 0x0506,
+        layout: 0x0708,
+    };
+    let mut packed = vec![0u8; Version::LEN];
+    Version::pack(version, &mut packed).unwrap();
+    let bytes = check_fixture("state_version", &packed);
+    assert_eq!(Version::unpack(&bytes), Ok(version));
+}
+
+#[test]
+fn test_feature_gate_layout() {
+    let gate = FeatureGate {
+        is_initialized: true,
+        bank: key(1),
+        enabled: 0x0102_0304,
+    };
+    let mut packed = vec![0u8; FeatureGate::LEN];
+    FeatureGate::pack(gate, &mut packed).unwrap();
+    let bytes = check_fixture("state_feature_gate", &packed);
+    assert_eq!(FeatureGate::unpack(&bytes), Ok(gate));
+}
+
+#[test]
+fn test_multisig_layout() {
+    let mut signers = [Pubkey::default(); 11];
+    signers[..3].copy_from_slice(&[key(4), key(5), key(6)]);
+    let multisig = Multisig {
+        is_initialized: true,
+        threshold: 2,
+        signer_count: 3,
+        signers,
+    };
+    let mut packed = vec![0u8; Multisig::LEN];
+    Multisig::pack(multisig, &mut packed).unwrap();
+    let bytes = check_fixture("state_multisig", &packed);
+    assert_eq!(Multisig::unpack(&bytes), Ok(multisig));
+}
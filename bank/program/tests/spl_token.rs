@@ -0,0 +1,415 @@
+//! The bank driven by instructions in SPL Token's layout and account order, as the
+//! entrypoint reads them when built with `spl-token-compat`.
+
+use solana_bank::{
+    error::BankError,
+    spl_token::{self, SplTokenInstruction},
+    state::{Account, Bank},
+};
+use solana_program::{
+    hash::Hash,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction, sysvar,
+};
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+/// A bank ("mint") with alice's and bob's accounts; alice holds 100 tokens.
+struct Env {
+    banks_client: BanksClient,
+    payer: Keypair,
+    recent_blockhash: Hash,
+    program_id: Pubkey,
+    bank: Keypair,
+    bank_owner: Keypair,
+    alice: Keypair,
+    alice_account: Keypair,
+    bob_account: Keypair,
+}
+
+impl Env {
+    async fn start() -> Env {
+        let program_id = Pubkey::new_unique();
+        let program_test =
+            ProgramTest::new("solana_bank", program_id, processor!(spl_token::process));
+        let (banks_client, payer, recent_blockhash) = program_test.start().await;
+        let mut env = Env {
+            banks_client,
+            payer,
+            recent_blockhash,
+            program_id,
+            bank: Keypair::new(),
+            bank_owner: Keypair::new(),
+            alice: Keypair::new(),
+            alice_account: Keypair::new(),
+            bob_account: Keypair::new(),
+        };
+
+        let bank = env.bank.pubkey();
+        let initialize_mint = env.instruction(
+            SplTokenInstruction::InitializeMint {
+                decimals: 2,
+                mint_authority: env.bank_owner.pubkey(),
+                freeze_authority: COption::None,
+            },
+            vec![
+                AccountMeta::new(bank, false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+        );
+        let bank_keypair = Keypair::from_bytes(&env.bank.to_bytes()).unwrap();
+        let create_bank = env.create_account(&bank, Bank::LEN).await;
+        env.process(&[create_bank, initialize_mint], &[&bank_keypair])
+            .await
+            .unwrap();
+
+        // SPL doesn't have the owner sign; bob's account is opened for a key nobody holds.
+        let accounts = [
+            (
+                Keypair::from_bytes(&env.alice_account.to_bytes()).unwrap(),
+                env.alice.pubkey(),
+            ),
+            (
+                Keypair::from_bytes(&env.bob_account.to_bytes()).unwrap(),
+                Pubkey::new_unique(),
+            ),
+        ];
+        for (account, owner) in accounts.iter() {
+            let create_account = env.create_account(&account.pubkey(), Account::LEN).await;
+            let initialize_account = env.instruction(
+                SplTokenInstruction::InitializeAccount,
+                vec![
+                    AccountMeta::new(account.pubkey(), false),
+                    AccountMeta::new_readonly(bank, false),
+                    AccountMeta::new_readonly(*owner, false),
+                    AccountMeta::new_readonly(sysvar::rent::id(), false),
+                ],
+            );
+            env.process(&[create_account, initialize_account], &[account])
+                .await
+                .unwrap();
+        }
+
+        let mint_to = env.instruction(
+            SplTokenInstruction::MintTo { amount: 100 },
+            vec![
+                AccountMeta::new(bank, false),
+                AccountMeta::new(env.alice_account.pubkey(), false),
+                AccountMeta::new_readonly(env.bank_owner.pubkey(), true),
+            ],
+        );
+        let bank_owner = Keypair::from_bytes(&env.bank_owner.to_bytes()).unwrap();
+        env.process(&[mint_to], &[&bank_owner]).await.unwrap();
+        env
+    }
+
+    fn instruction(
+        &self,
+        instruction: SplTokenInstruction,
+        accounts: Vec<AccountMeta>,
+    ) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data: instruction.pack(),
+        }
+    }
+
+    /// A rent-exempt account of `space` bytes owned by the bank program.
+    async fn create_account(&mut self, account: &Pubkey, space: usize) -> Instruction {
+        let rent = self.banks_client.get_rent().await.unwrap();
+        system_instruction::create_account(
+            &self.payer.pubkey(),
+            account,
+            rent.minimum_balance(space),
+            space as u64,
+            &self.program_id,
+        )
+    }
+
+    async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), TransactionError> {
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            self.recent_blockhash,
+        );
+        self.banks_client
+            .process_transaction(transaction)
+            .await
+            .map_err(|e| e.unwrap())
+    }
+
+    async fn get_bank(&mut self) -> Bank {
+        let account = self
+            .banks_client
+            .get_account(self.bank.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        Bank::unpack(&account.data).unwrap()
+    }
+
+    async fn get_bank_account(&mut self, account: &Pubkey) -> Account {
+        let account = self
+            .banks_client
+            .get_account(*account)
+            .await
+            .unwrap()
+            .unwrap();
+        Account::unpack(&account.data).unwrap()
+    }
+
+    /// `TransferChecked` of `amount` from alice to bob, as a wallet sends it.
+    fn transfer_checked(&self, amount: u64, decimals: u8) -> Instruction {
+        self.instruction(
+            SplTokenInstruction::TransferChecked { amount, decimals },
+            vec![
+                AccountMeta::new(self.alice_account.pubkey(), false),
+                AccountMeta::new_readonly(self.bank.pubkey(), false),
+                AccountMeta::new(self.bob_account.pubkey(), false),
+                AccountMeta::new_readonly(self.alice.pubkey(), true),
+            ],
+        )
+    }
+}
+
+#[tokio::test]
+async fn test_initialize() {
+    let mut env = Env::start().await;
+    let bank = env.get_bank().await;
+    assert_eq!(
+        (bank.decimals, bank.bank_owner, bank.total_supply),
+        (2, env.bank_owner.pubkey(), 100)
+    );
+    let account = env.get_bank_account(&env.alice_account.pubkey()).await;
+    assert_eq!(
+        (account.owner, account.bank, account.amount),
+        (env.alice.pubkey(), env.bank.pubkey(), 100)
+    );
+}
+
+#[tokio::test]
+async fn test_transfer() {
+    let mut env = Env::start().await;
+    let alice = Keypair::from_bytes(&env.alice.to_bytes()).unwrap();
+    // Bob holds nothing yet, so the bank counts a new holder and must be writable.
+    let first = env.transfer_checked(30, 2);
+    assert_eq!(
+        env.process(&[first], &[&alice]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::ReadOnlyBank as u32)
+        ))
+    );
+    let transfer = env.instruction(
+        SplTokenInstruction::Transfer { amount: 30 },
+        vec![
+            AccountMeta::new(env.alice_account.pubkey(), false),
+            AccountMeta::new(env.bob_account.pubkey(), false),
+            AccountMeta::new_readonly(alice.pubkey(), true),
+            AccountMeta::new(env.bank.pubkey(), false),
+        ],
+    );
+    let transfer_checked = env.transfer_checked(20, 2);
+    env.process(&[transfer, transfer_checked], &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.get_bank_account(&env.alice_account.pubkey())
+            .await
+            .amount,
+        50
+    );
+    assert_eq!(
+        env.get_bank_account(&env.bob_account.pubkey()).await.amount,
+        50
+    );
+
+    let wrong_decimals = env.transfer_checked(1, 9);
+    assert_eq!(
+        env.process(&[wrong_decimals], &[&alice]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidArgument
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_approve_burn_and_close() {
+    let mut env = Env::start().await;
+    let alice = Keypair::from_bytes(&env.alice.to_bytes()).unwrap();
+    let bank_owner = Keypair::from_bytes(&env.bank_owner.to_bytes()).unwrap();
+    let (alice_account, bank) = (env.alice_account.pubkey(), env.bank.pubkey());
+
+    let delegate = Pubkey::new_unique();
+    let approve = env.instruction(
+        SplTokenInstruction::Approve { amount: 40 },
+        vec![
+            AccountMeta::new(alice_account, false),
+            AccountMeta::new_readonly(delegate, false),
+            AccountMeta::new_readonly(alice.pubkey(), true),
+            AccountMeta::new_readonly(bank, false),
+        ],
+    );
+    let revoke = env.instruction(
+        SplTokenInstruction::Revoke,
+        vec![
+            AccountMeta::new(alice_account, false),
+            AccountMeta::new_readonly(alice.pubkey(), true),
+            AccountMeta::new_readonly(bank, false),
+        ],
+    );
+    env.process(&[approve, revoke], &[&alice]).await.unwrap();
+    let account = env.get_bank_account(&alice_account).await;
+    assert_eq!((account.amount, account.delegate), (100, COption::None));
+
+    let burn = |env: &Env, signers: Vec<AccountMeta>| {
+        let mut accounts = vec![
+            AccountMeta::new(alice_account, false),
+            AccountMeta::new(bank, false),
+        ];
+        accounts.extend(signers);
+        env.instruction(SplTokenInstruction::Burn { amount: 100 }, accounts)
+    };
+    let owner_only = burn(&env, vec![AccountMeta::new_readonly(alice.pubkey(), true)]);
+    assert_eq!(
+        env.process(&[owner_only], &[&alice]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::NotEnoughAccountKeys
+        ))
+    );
+    let with_bank_owner = burn(
+        &env,
+        vec![
+            AccountMeta::new_readonly(alice.pubkey(), true),
+            AccountMeta::new_readonly(bank_owner.pubkey(), true),
+        ],
+    );
+    let destination = Pubkey::new_unique();
+    let close = env.instruction(
+        SplTokenInstruction::CloseAccount,
+        vec![
+            AccountMeta::new(alice_account, false),
+            AccountMeta::new(destination, false),
+            AccountMeta::new_readonly(alice.pubkey(), true),
+            AccountMeta::new_readonly(bank, false),
+        ],
+    );
+    env.process(&[with_bank_owner, close], &[&alice, &bank_owner])
+        .await
+        .unwrap();
+    assert_eq!(env.get_bank().await.total_supply, 0);
+    assert!(env.banks_client.get_balance(destination).await.unwrap() > 0);
+}
+
+#[tokio::test]
+async fn test_freeze_authority() {
+    let mut env = Env::start().await;
+    let bank = Keypair::new();
+    let freeze_authority = Keypair::new();
+    let account = Keypair::new();
+    let create_bank = env.create_account(&bank.pubkey(), Bank::LEN).await;
+    let initialize_mint = env.instruction(
+        SplTokenInstruction::InitializeMint {
+            decimals: 0,
+            mint_authority: env.bank_owner.pubkey(),
+            freeze_authority: COption::Some(freeze_authority.pubkey()),
+        },
+        vec![
+            AccountMeta::new(bank.pubkey(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+    );
+    let create_account = env.create_account(&account.pubkey(), Account::LEN).await;
+    let initialize_account = env.instruction(
+        SplTokenInstruction::InitializeAccount,
+        vec![
+            AccountMeta::new(account.pubkey(), false),
+            AccountMeta::new_readonly(bank.pubkey(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+    );
+    env.process(
+        &[
+            create_bank,
+            initialize_mint,
+            create_account,
+            initialize_account,
+        ],
+        &[&bank, &account],
+    )
+    .await
+    .unwrap();
+    let state = env
+        .banks_client
+        .get_account(bank.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        Bank::unpack(&state.data).unwrap().freeze_authority,
+        COption::Some(freeze_authority.pubkey())
+    );
+
+    let freeze = |env: &Env, instruction, bank: &Pubkey, account: &Pubkey| {
+        env.instruction(
+            instruction,
+            vec![
+                AccountMeta::new(*account, false),
+                AccountMeta::new_readonly(*bank, false),
+                AccountMeta::new_readonly(freeze_authority.pubkey(), true),
+            ],
+        )
+    };
+    let instruction = freeze(
+        &env,
+        SplTokenInstruction::FreezeAccount,
+        &bank.pubkey(),
+        &account.pubkey(),
+    );
+    env.process(&[instruction], &[&freeze_authority])
+        .await
+        .unwrap();
+    assert!(env.get_bank_account(&account.pubkey()).await.is_frozen);
+    let instruction = freeze(
+        &env,
+        SplTokenInstruction::ThawAccount,
+        &bank.pubkey(),
+        &account.pubkey(),
+    );
+    env.process(&[instruction], &[&freeze_authority])
+        .await
+        .unwrap();
+    assert!(!env.get_bank_account(&account.pubkey()).await.is_frozen);
+
+    // A mint opened without one can't have its accounts frozen.
+    let instruction = freeze(
+        &env,
+        SplTokenInstruction::FreezeAccount,
+        &env.bank.pubkey(),
+        &env.alice_account.pubkey(),
+    );
+    assert_eq!(
+        env.process(&[instruction], &[&freeze_authority]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::NoFreezeAuthority as u32)
+        ))
+    );
+}
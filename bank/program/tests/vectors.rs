@@ -0,0 +1,707 @@
+//! JSON test vectors for SDKs in other languages, kept in `tests/fixtures/vectors.json`:
+//! every instruction as built by `solana_bank::instruction` (account metas and data
+//! bytes) and packed examples of each state struct, each next to its field values.
+//! Amounts are decimal strings so they survive JavaScript numbers. Regenerate the
+//! file with `UPDATE_TEST_VECTORS=1` after an intended change.
+
+use serde_json::{json, Value};
+use solana_bank::{
+    instruction::{
+        accrue_interest, approve, assign_to_program, burn, burn_checked, cancel_owner_change,
+        cancel_recovery, close_account, close_dust_account, convert, create_session_key,
+        deposit_sol, freeze_account, initialize_account, initialize_associated_account,
+        initialize_bank_with_interest_rate, initialize_multisig, initialize_version, mint_to,
+        mint_to_checked, pause_bank, permit_approve, recover_owner, resume_bank, revoke,
+        set_authority, set_close_policy, set_dust_threshold, set_exchange_rate, set_features,
+        set_guardians, set_owner_change_delay, set_rate_limit, set_require_rent_exempt,
+        start_recovery, thaw_account, transfer_batch, transfer_checked, transfer_route,
+        transfer_sweeping_dust, transfer_with_expiry, withdraw_sol, AuthorityType, BankInstruction,
+    },
+    state::{
+        Account, Bank, BurnPolicy, ClosePolicy, ExchangeRate, FeatureGate, Multisig, Recovery,
+        Session, Version,
+    },
+};
+use solana_program::{
+    instruction::Instruction, program_option::COption, program_pack::Pack, pubkey::Pubkey,
+};
+use std::{env, fs};
+
+const VECTORS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/vectors.json");
+
+fn key(byte: u8) -> Pubkey {
+    Pubkey::new_from_array([byte; 32])
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn instruction_vector(name: &str, fields: Value, instruction: Instruction) -> Value {
+    let accounts = instruction
+        .accounts
+        .iter()
+        .map(|meta| {
+            json!({
+                "pubkey": meta.pubkey.to_string(),
+                "is_signer": meta.is_signer,
+                "is_writable": meta.is_writable,
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "name": name,
+        "fields": fields,
+        "program_id": instruction.program_id.to_string(),
+        "accounts": accounts,
+        "data": to_hex(&instruction.data),
+    })
+}
+
+fn instruction_vectors() -> Vec<Value> {
+    let program_id = key(0xaa);
+    let bank = key(1);
+    let bank_owner = key(2);
+    let account = key(3);
+    let other_account = key(4);
+    let owner = key(5);
+    let delegate = key(6);
+    let program = key(7);
+    let other_bank = key(8);
+    let rate = key(9);
+    let multisig = key(10);
+    let (_, bump) = Pubkey::find_program_address(&[b"vault"], &program);
+    let amount = 0x0102_0304_0506_0708;
+
+    let vectors = vec![
+        (
+            "InitializeBank",
+            json!({
+                "decimals": 9,
+                "burn_policy": BurnPolicy::HolderOnly as u8,
+                "lamports_per_token": 1_000_000.to_string(),
+                "freeze_authority": bank_owner.to_string(),
+                "interest_rate_bps": 250,
+            }),
+            initialize_bank_with_interest_rate(
+                &program_id,
+                &bank,
+                &bank_owner,
+                9,
+                BurnPolicy::HolderOnly,
+                1_000_000,
+                Some(&bank_owner),
+                250,
+            ),
+        ),
+        (
+            "InitializeAccount",
+            json!({}),
+            initialize_account(&program_id, &bank, &account, &owner),
+        ),
+        (
+            "Transfer",
+            json!({ "amount": amount.to_string(), "sweep_dust": true }),
+            transfer_sweeping_dust(&program_id, &bank, &account, &other_account, &owner, amount),
+        ),
+        (
+            "Approve",
+            json!({ "amount": amount.to_string() }),
+            approve(&program_id, &bank, &account, &delegate, &owner, amount),
+        ),
+        (
+            "MintTo",
+            json!({ "amount": u64::MAX.to_string() }),
+            mint_to(&program_id, &bank, &account, &bank_owner, u64::MAX),
+        ),
+        (
+            "Burn",
+            json!({ "amount": "1" }),
+            burn(&program_id, &bank, &account, &bank_owner, &owner, 1),
+        ),
+        (
+            "CloseAccount",
+            json!({}),
+            close_account(&program_id, &bank, &account, &other_account, &owner),
+        ),
+        (
+            "Revoke",
+            json!({}),
+            revoke(&program_id, &bank, &account, &owner),
+        ),
+        (
+            "SetClosePolicy",
+            json!({ "policy": ClosePolicy::Sweep as u8 }),
+            set_close_policy(&program_id, &bank, &bank_owner, ClosePolicy::Sweep),
+        ),
+        (
+            "SetRequireRentExempt",
+            json!({ "required": true }),
+            set_require_rent_exempt(&program_id, &bank, &bank_owner, true),
+        ),
+        (
+            "PermitApprove",
+            json!({ "amount": "30", "nonce": "2", "expiry": "-1" }),
+            permit_approve(&program_id, &bank, &account, &delegate, 30, 2, -1),
+        ),
+        (
+            "TransferWithExpiry",
+            json!({ "amount": amount.to_string(), "max_slot": "1000" }),
+            transfer_with_expiry(
+                &program_id,
+                &bank,
+                &account,
+                &other_account,
+                &owner,
+                amount,
+                1000,
+            ),
+        ),
+        (
+            "TransferRoute",
+            json!({ "amount": amount.to_string() }),
+            transfer_route(
+                &program_id,
+                &bank,
+                &[(account, owner)],
+                &other_account,
+                amount,
+            ),
+        ),
+        (
+            "AssignToProgram",
+            json!({ "seeds": [to_hex(b"vault"), to_hex(&[bump])] }),
+            assign_to_program(
+                &program_id,
+                &bank,
+                &account,
+                &owner,
+                &program,
+                &[b"vault", &[bump]],
+            ),
+        ),
+        (
+            "DepositSol",
+            json!({ "lamports": amount.to_string() }),
+            deposit_sol(&program_id, &bank, &account, &owner, amount),
+        ),
+        (
+            "WithdrawSol",
+            json!({ "lamports": amount.to_string() }),
+            withdraw_sol(&program_id, &bank, &account, &owner, &other_account, amount),
+        ),
+        (
+            "SetExchangeRate",
+            json!({ "numerator": "3", "denominator": amount.to_string() }),
+            set_exchange_rate(
+                &program_id,
+                &rate,
+                &bank,
+                &other_bank,
+                &bank_owner,
+                &owner,
+                3,
+                amount,
+            ),
+        ),
+        (
+            "Convert",
+            json!({ "amount": amount.to_string() }),
+            convert(
+                &program_id,
+                &rate,
+                &bank,
+                &account,
+                &owner,
+                &other_bank,
+                &other_account,
+                amount,
+            ),
+        ),
+        (
+            "SetDustThreshold",
+            json!({ "threshold": "100" }),
+            set_dust_threshold(&program_id, &bank, &bank_owner, 100),
+        ),
+        (
+            "CloseDustAccount",
+            json!({}),
+            close_dust_account(&program_id, &bank, &account, &owner, None),
+        ),
+        (
+            "SetGuardians",
+            json!({
+                "threshold": 2,
+                "delay": "1000",
+                "guardians": [delegate.to_string(), program.to_string()],
+            }),
+            set_guardians(
+                &program_id,
+                &bank,
+                &account,
+                &owner,
+                &bank_owner,
+                &[delegate, program],
+                2,
+                1000,
+            ),
+        ),
+        (
+            "StartRecovery",
+            json!({ "new_owner": other_account.to_string() }),
+            start_recovery(&program_id, &bank, &account, &[delegate], &other_account),
+        ),
+        (
+            "RecoverOwner",
+            json!({}),
+            recover_owner(&program_id, &bank, &account, &[delegate]),
+        ),
+        (
+            "CancelRecovery",
+            json!({}),
+            cancel_recovery(&program_id, &bank, &account, &owner),
+        ),
+        (
+            "CreateSessionKey",
+            json!({
+                "session_key": delegate.to_string(),
+                "max_spend": amount.to_string(),
+                "expiry_slot": "1000",
+            }),
+            create_session_key(
+                &program_id,
+                &bank,
+                &account,
+                &owner,
+                &bank_owner,
+                &delegate,
+                amount,
+                1000,
+            ),
+        ),
+        (
+            "SetRateLimit",
+            json!({ "capacity": amount.to_string(), "refill_per_slot": "10" }),
+            set_rate_limit(&program_id, &bank, &account, &owner, amount, 10),
+        ),
+        (
+            "InitializeVersion",
+            json!({}),
+            initialize_version(&program_id, &bank_owner),
+        ),
+        (
+            "SetFeatures",
+            json!({ "enabled": "3" }),
+            set_features(&program_id, &bank, &bank_owner, &bank_owner, 3),
+        ),
+        (
+            "FreezeAccount",
+            json!({}),
+            freeze_account(&program_id, &bank, &account, &bank_owner),
+        ),
+        (
+            "ThawAccount",
+            json!({}),
+            thaw_account(&program_id, &bank, &account, &bank_owner),
+        ),
+        (
+            "InitializeMultisig",
+            json!({ "threshold": 1 }),
+            initialize_multisig(&program_id, &multisig, &[&owner], 1),
+        ),
+        (
+            "InitializeAssociatedAccount",
+            json!({}),
+            initialize_associated_account(&program_id, &bank, &owner, &bank_owner),
+        ),
+        (
+            "TransferChecked",
+            json!({ "amount": amount.to_string(), "decimals": 9 }),
+            transfer_checked(
+                &program_id,
+                &bank,
+                &account,
+                &other_account,
+                &owner,
+                &[],
+                amount,
+                9,
+            ),
+        ),
+        (
+            "MintToChecked",
+            json!({ "amount": u64::MAX.to_string(), "decimals": 0 }),
+            mint_to_checked(&program_id, &bank, &account, &bank_owner, &[], u64::MAX, 0),
+        ),
+        (
+            "BurnChecked",
+            json!({ "amount": "1", "decimals": 255 }),
+            burn_checked(&program_id, &bank, &account, &bank_owner, &owner, 1, 255),
+        ),
+        (
+            "SetAuthority",
+            json!({
+                "authority_type": AuthorityType::AccountOwner as u8,
+                "new_authority": delegate.to_string(),
+            }),
+            set_authority(
+                &program_id,
+                &account,
+                AuthorityType::AccountOwner,
+                &owner,
+                Some(&delegate),
+            ),
+        ),
+        (
+            "TransferBatch",
+            json!({ "amounts": [amount.to_string()] }),
+            transfer_batch(
+                &program_id,
+                &bank,
+                &account,
+                &owner,
+                &[(&other_account, amount)],
+            ),
+        ),
+        (
+            "PauseBank",
+            json!({}),
+            pause_bank(&program_id, &bank, &bank_owner),
+        ),
+        (
+            "ResumeBank",
+            json!({}),
+            resume_bank(&program_id, &bank, &bank_owner),
+        ),
+        (
+            "AccrueInterest",
+            json!({}),
+            accrue_interest(&program_id, &bank, &account),
+        ),
+        (
+            "SetOwnerChangeDelay",
+            json!({ "delay": "150" }),
+            set_owner_change_delay(&program_id, &bank, &bank_owner, 150),
+        ),
+        (
+            "CancelOwnerChange",
+            json!({}),
+            cancel_owner_change(&program_id, &bank, &bank_owner),
+        ),
+    ];
+    vectors
+        .into_iter()
+        .map(|(name, fields, instruction)| {
+            let instruction = instruction.unwrap();
+            assert!(
+                BankInstruction::unpack(&instruction.data).is_ok(),
+                "{} does not decode",
+                name
+            );
+            instruction_vector(name, fields, instruction)
+        })
+        .collect()
+}
+
+fn bank_vector(bank: Bank) -> Value {
+    let mut packed = vec![0u8; Bank::LEN];
+    Bank::pack(bank, &mut packed).unwrap();
+    let freeze_authority = match bank.freeze_authority {
+        COption::Some(authority) => Value::String(authority.to_string()),
+        COption::None => Value::Null,
+    };
+    let emergency_authority = match bank.emergency_authority {
+        COption::Some(authority) => Value::String(authority.to_string()),
+        COption::None => Value::Null,
+    };
+    let pending_bank_owner = match bank.pending_bank_owner {
+        COption::Some(owner) => Value::String(owner.to_string()),
+        COption::None => Value::Null,
+    };
+    json!({
+        "type": "Bank",
+        "fields": {
+            "decimals": bank.decimals,
+            "bank_owner": bank.bank_owner.to_string(),
+            "is_opened": bank.is_opened,
+            "total_supply": bank.total_supply.to_string(),
+            "close_policy": bank.close_policy as u8,
+            "require_rent_exempt": bank.require_rent_exempt,
+            "burn_policy": bank.burn_policy as u8,
+            "lamports_per_token": bank.lamports_per_token.to_string(),
+            "transfer_count": bank.transfer_count.to_string(),
+            "cumulative_volume": bank.cumulative_volume.to_string(),
+            "holder_count": bank.holder_count.to_string(),
+            "registered_accounts": bank.registered_accounts.to_string(),
+            "dust_threshold": bank.dust_threshold.to_string(),
+            "freeze_authority": freeze_authority,
+            "is_paused": bank.is_paused,
+            "interest_rate_bps": bank.interest_rate_bps,
+            "emergency_authority": emergency_authority,
+            "owner_change_delay": bank.owner_change_delay.to_string(),
+            "pending_bank_owner": pending_bank_owner,
+            "owner_change_slot": bank.owner_change_slot.to_string(),
+        },
+        "data": to_hex(&packed),
+    })
+}
+
+fn account_vector(account: Account) -> Value {
+    let mut packed = vec![0u8; Account::LEN];
+    Account::pack(account, &mut packed).unwrap();
+    let delegate = match account.delegate {
+        COption::Some(d) => Value::String(d.to_string()),
+        COption::None => Value::Null,
+    };
+    json!({
+        "type": "Account",
+        "fields": {
+            "amount": account.amount.to_string(),
+            "is_opened": account.is_opened,
+            "is_initialized": account.is_initialized,
+            "owner": account.owner.to_string(),
+            "delegate": delegate,
+            "delegated_amount": account.delegated_amount.to_string(),
+            "bank": account.bank.to_string(),
+            "permit_nonce": account.permit_nonce.to_string(),
+            "is_frozen": account.is_frozen,
+            "last_accrual": account.last_accrual.to_string(),
+        },
+        "data": to_hex(&packed),
+    })
+}
+
+fn exchange_rate_vector(rate: ExchangeRate) -> Value {
+    let mut packed = vec![0u8; ExchangeRate::LEN];
+    ExchangeRate::pack(rate, &mut packed).unwrap();
+    json!({
+        "type": "ExchangeRate",
+        "fields": {
+            "is_initialized": rate.is_initialized,
+            "from_bank": rate.from_bank.to_string(),
+            "to_bank": rate.to_bank.to_string(),
+            "numerator": rate.numerator.to_string(),
+            "denominator": rate.denominator.to_string(),
+        },
+        "data": to_hex(&packed),
+    })
+}
+
+fn recovery_vector(recovery: Recovery) -> Value {
+    let mut packed = vec![0u8; Recovery::LEN];
+    Recovery::pack(recovery, &mut packed).unwrap();
+    let pending_owner = match recovery.pending_owner {
+        COption::Some(owner) => Value::String(owner.to_string()),
+        COption::None => Value::Null,
+    };
+    let guardians = recovery
+        .guardians
+        .iter()
+        .map(|guardian| guardian.to_string())
+        .collect::<Vec<_>>();
+    json!({
+        "type": "Recovery",
+        "fields": {
+            "is_initialized": recovery.is_initialized,
+            "account": recovery.account.to_string(),
+            "owner": recovery.owner.to_string(),
+            "threshold": recovery.threshold,
+            "guardian_count": recovery.guardian_count,
+            "guardians": guardians,
+            "delay": recovery.delay.to_string(),
+            "pending_owner": pending_owner,
+            "activation_slot": recovery.activation_slot.to_string(),
+        },
+        "data": to_hex(&packed),
+    })
+}
+
+fn session_vector(session: Session) -> Value {
+    let mut packed = vec![0u8; Session::LEN];
+    Session::pack(session, &mut packed).unwrap();
+    json!({
+        "type": "Session",
+        "fields": {
+            "is_initialized": session.is_initialized,
+            "account": session.account.to_string(),
+            "owner": session.owner.to_string(),
+            "session_key": session.session_key.to_string(),
+            "remaining_spend": session.remaining_spend.to_string(),
+            "expiry_slot": session.expiry_slot.to_string(),
+        },
+        "data": to_hex(&packed),
+    })
+}
+
+fn feature_gate_vector(gate: FeatureGate) -> Value {
+    let mut packed = vec![0u8; FeatureGate::LEN];
+    FeatureGate::pack(gate, &mut packed).unwrap();
+    json!({
+        "type": "FeatureGate",
+        "fields": {
+            "is_initialized": gate.is_initialized,
+            "bank": gate.bank.to_string(),
+            "enabled": gate.enabled.to_string(),
+        },
+        "data": to_hex(&packed),
+    })
+}
+
+fn version_vector(version: Version) -> Value {
+    let mut packed = vec![0u8; Version::LEN];
+    Version::pack(version, &mut packed).unwrap();
+    json!({
+        "type": "Version",
+        "fields": {
+            "is_initialized": version.is_initialized,
+            "major": version.major,
+            "minor": version.minor,
+            "patch": version.patch,
+            "layout": version.layout,
+        },
+        "data": to_hex(&packed),
+    })
+}
+
+fn multisig_vector(multisig: Multisig) -> Value {
+    let mut packed = vec![0u8; Multisig::LEN];
+    Multisig::pack(multisig, &mut packed).unwrap();
+    let signers = multisig
+        .signers
+        .iter()
+        .map(|signer| signer.to_string())
+        .collect::<Vec<_>>();
+    json!({
+        "type": "Multisig",
+        "fields": {
+            "is_initialized": multisig.is_initialized,
+            "threshold": multisig.threshold,
+            "signer_count": multisig.signer_count,
+            "signers": signers,
+        },
+        "data": to_hex(&packed),
+    })
+}
+
+fn state_vectors() -> Vec<Value> {
+    let account = Account {
+        amount: 70,
+        is_opened: true,
+        is_initialized: true,
+        owner: key(5),
+        delegate: COption::None,
+        delegated_amount: 0,
+        bank: key(1),
+        permit_nonce: 0,
+        is_frozen: false,
+        last_accrual: 0,
+    };
+    let mut guardians = [Pubkey::default(); 8];
+    guardians[..2].copy_from_slice(&[key(6), key(7)]);
+    let mut signers = [Pubkey::default(); 11];
+    signers[..3].copy_from_slice(&[key(5), key(6), key(7)]);
+    vec![
+        bank_vector(Bank {
+            decimals: 6,
+            bank_owner: key(2),
+            is_opened: true,
+            total_supply: 0x1122_3344_5566_7788,
+            close_policy: ClosePolicy::Burn,
+            require_rent_exempt: true,
+            burn_policy: BurnPolicy::HolderOnly,
+            lamports_per_token: 1_000_000,
+            transfer_count: 12,
+            cumulative_volume: 0x0102_0304_0506_0708,
+            holder_count: 5,
+            registered_accounts: 9,
+            dust_threshold: 100,
+            freeze_authority: COption::Some(key(2)),
+            is_paused: true,
+            interest_rate_bps: 250,
+            emergency_authority: COption::Some(key(3)),
+            owner_change_delay: 150,
+            pending_bank_owner: COption::Some(key(4)),
+            owner_change_slot: 0x0a0b_0c0d,
+        }),
+        bank_vector(Bank::default()),
+        account_vector(account),
+        account_vector(Account {
+            amount: 40,
+            delegate: COption::Some(key(6)),
+            delegated_amount: 30,
+            permit_nonce: 2,
+            is_frozen: true,
+            last_accrual: 1_600_000_000,
+            ..account
+        }),
+        account_vector(Account {
+            amount: 0,
+            is_opened: false,
+            ..account
+        }),
+        exchange_rate_vector(ExchangeRate {
+            is_initialized: true,
+            from_bank: key(1),
+            to_bank: key(7),
+            numerator: 3,
+            denominator: u64::MAX,
+        }),
+        recovery_vector(Recovery {
+            is_initialized: true,
+            account: key(3),
+            owner: key(5),
+            threshold: 1,
+            guardian_count: 2,
+            guardians,
+            delay: 1000,
+            pending_owner: COption::Some(key(4)),
+            activation_slot: u64::MAX,
+        }),
+        session_vector(Session {
+            is_initialized: true,
+            account: key(3),
+            owner: key(5),
+            session_key: key(6),
+            remaining_spend: 250,
+            expiry_slot: 1000,
+        }),
+        version_vector(Version {
+            is_initialized: true,
+            major: 1,
+            minor: 2,
+            patch: 3,
+            layout: 1,
+        }),
+        feature_gate_vector(FeatureGate {
+            is_initialized: true,
+            bank: key(1),
+            enabled: 1,
+        }),
+        multisig_vector(Multisig {
+            is_initialized: true,
+            threshold: 2,
+            signer_count: 3,
+            signers,
+        }),
+    ]
+}
+
+#[test]
+fn test_vectors() {
+    let vectors = json!({
+        "instructions": instruction_vectors(),
+        "states": state_vectors(),
+    });
+    if env::var_os("UPDATE_TEST_VECTORS").is_some() {
+        let json = serde_json::to_string_pretty(&vectors).unwrap();
+        fs::write(VECTORS, json + "\n").unwrap();
+    }
+    let expected: Value = fs::read_to_string(VECTORS)
+        .map(|json| serde_json::from_str(&json).unwrap())
+        .unwrap_or_else(|e| panic!("read {}: {}", VECTORS, e));
+    assert!(
+        vectors == expected,
+        "test vectors changed; other SDKs rely on these bytes, regenerate with \
+         UPDATE_TEST_VECTORS=1 only after an intended layout change"
+    );
+}
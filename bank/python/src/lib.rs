@@ -0,0 +1,1320 @@
+//! The `solana_play_bank` Python module. Instructions come back as dicts
+//! (`program_id`, `accounts` as `(pubkey, is_signer, is_writable)` tuples, `data` as
+//! bytes) that map directly onto `solders.instruction.Instruction`; account decoders
+//! take the raw account data and return dicts keyed by field name.
+
+use client::bank::BankClient;
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    prelude::*,
+    types::{PyBytes, PyDict, PyList},
+};
+use solana_bank::{
+    instruction::{self, AuthorityType},
+    permit,
+    solana_program::{
+        instruction::Instruction, program_error::ProgramError, program_option::COption,
+        program_pack::Pack, pubkey::Pubkey,
+    },
+    state::{Account, Bank, ClosePolicy},
+};
+use solana_client::rpc_client::RpcClient;
+use std::{
+    convert::{TryFrom, TryInto},
+    str::FromStr,
+};
+
+fn parse_pubkey(name: &str, value: &str) -> PyResult<Pubkey> {
+    Pubkey::from_str(value)
+        .map_err(|_| PyValueError::new_err(format!("invalid {}: {}", name, value)))
+}
+
+fn parse_guardians(guardians: &[String]) -> PyResult<Vec<Pubkey>> {
+    guardians
+        .iter()
+        .map(|guardian| parse_pubkey("guardian", guardian))
+        .collect()
+}
+
+fn parse_signers(signers: &[String]) -> PyResult<Vec<Pubkey>> {
+    signers
+        .iter()
+        .map(|signer| parse_pubkey("signer", signer))
+        .collect()
+}
+
+fn instruction_dict(
+    py: Python<'_>,
+    ix: Result<Instruction, ProgramError>,
+) -> PyResult<Bound<'_, PyDict>> {
+    let ix = ix.map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let accounts = ix
+        .accounts
+        .iter()
+        .map(|meta| (meta.pubkey.to_string(), meta.is_signer, meta.is_writable))
+        .collect::<Vec<_>>();
+    let dict = PyDict::new(py);
+    dict.set_item("program_id", ix.program_id.to_string())?;
+    dict.set_item("accounts", accounts)?;
+    dict.set_item("data", PyBytes::new(py, &ix.data))?;
+    Ok(dict)
+}
+
+fn bank_dict<'py>(py: Python<'py>, bank: &Bank) -> PyResult<Bound<'py, PyDict>> {
+    let freeze_authority = match bank.freeze_authority {
+        COption::Some(authority) => Some(authority.to_string()),
+        COption::None => None,
+    };
+    let emergency_authority = match bank.emergency_authority {
+        COption::Some(authority) => Some(authority.to_string()),
+        COption::None => None,
+    };
+    let pending_bank_owner = match bank.pending_bank_owner {
+        COption::Some(owner) => Some(owner.to_string()),
+        COption::None => None,
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("decimals", bank.decimals)?;
+    dict.set_item("bank_owner", bank.bank_owner.to_string())?;
+    dict.set_item("is_opened", bank.is_opened)?;
+    dict.set_item("total_supply", bank.total_supply)?;
+    dict.set_item("close_policy", bank.close_policy as u8)?;
+    dict.set_item("require_rent_exempt", bank.require_rent_exempt)?;
+    dict.set_item("burn_policy", bank.burn_policy as u8)?;
+    dict.set_item("lamports_per_token", bank.lamports_per_token)?;
+    dict.set_item("transfer_count", bank.transfer_count)?;
+    dict.set_item("cumulative_volume", bank.cumulative_volume)?;
+    dict.set_item("holder_count", bank.holder_count)?;
+    dict.set_item("registered_accounts", bank.registered_accounts)?;
+    dict.set_item("dust_threshold", bank.dust_threshold)?;
+    dict.set_item("freeze_authority", freeze_authority)?;
+    dict.set_item("is_paused", bank.is_paused)?;
+    dict.set_item("interest_rate_bps", bank.interest_rate_bps)?;
+    dict.set_item("emergency_authority", emergency_authority)?;
+    dict.set_item("owner_change_delay", bank.owner_change_delay)?;
+    dict.set_item("pending_bank_owner", pending_bank_owner)?;
+    dict.set_item("owner_change_slot", bank.owner_change_slot)?;
+    Ok(dict)
+}
+
+fn account_dict<'py>(py: Python<'py>, account: &Account) -> PyResult<Bound<'py, PyDict>> {
+    let delegate = match account.delegate {
+        COption::Some(d) => Some(d.to_string()),
+        COption::None => None,
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("bank", account.bank.to_string())?;
+    dict.set_item("owner", account.owner.to_string())?;
+    dict.set_item("amount", account.amount)?;
+    dict.set_item("delegate", delegate)?;
+    dict.set_item("delegated_amount", account.delegated_amount)?;
+    dict.set_item("is_opened", account.is_opened)?;
+    dict.set_item("is_initialized", account.is_initialized)?;
+    dict.set_item("permit_nonce", account.permit_nonce)?;
+    dict.set_item("is_frozen", account.is_frozen)?;
+    dict.set_item("last_accrual", account.last_accrual)?;
+    Ok(dict)
+}
+
+/// Decodes the data of a bank account (`Bank`, 220 bytes).
+#[pyfunction]
+fn decode_bank<'py>(py: Python<'py>, data: &[u8]) -> PyResult<Bound<'py, PyDict>> {
+    let bank = Bank::unpack(data).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    bank_dict(py, &bank)
+}
+
+/// Decodes the data of a bank token account (`Account`, 135 bytes, more with a
+/// transfer history).
+#[pyfunction]
+fn decode_account<'py>(py: Python<'py>, data: &[u8]) -> PyResult<Bound<'py, PyDict>> {
+    let account =
+        Account::unpack_extended(data).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    account_dict(py, &account)
+}
+
+#[pyfunction]
+fn initialize_bank<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    bank_owner: &str,
+    decimals: u8,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::initialize_bank(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("bank_owner", bank_owner)?,
+            decimals,
+        ),
+    )
+}
+
+#[pyfunction]
+fn initialize_account<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    owner: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::initialize_account(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("owner", owner)?,
+        ),
+    )
+}
+
+#[pyfunction]
+fn transfer<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    from_account: &str,
+    to_account: &str,
+    owner: &str,
+    amount: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::transfer(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("from_account", from_account)?,
+            &parse_pubkey("to_account", to_account)?,
+            &parse_pubkey("owner", owner)?,
+            amount,
+        ),
+    )
+}
+
+#[pyfunction]
+fn transfer_with_expiry<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    from_account: &str,
+    to_account: &str,
+    owner: &str,
+    amount: u64,
+    max_slot: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::transfer_with_expiry(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("from_account", from_account)?,
+            &parse_pubkey("to_account", to_account)?,
+            &parse_pubkey("owner", owner)?,
+            amount,
+            max_slot,
+        ),
+    )
+}
+
+/// Routes `amount` from `accounts[0]` to the last of `accounts`, each sending account
+/// signed for by the authority at its index.
+#[pyfunction]
+fn transfer_route<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    accounts: Vec<String>,
+    authorities: Vec<String>,
+    amount: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    let (destination, senders) = accounts
+        .split_last()
+        .ok_or_else(|| PyValueError::new_err("a route needs accounts"))?;
+    if senders.len() != authorities.len() {
+        return Err(PyValueError::new_err("one authority per sending account"));
+    }
+    let hops = senders
+        .iter()
+        .zip(&authorities)
+        .map(|(account, authority)| {
+            Ok((
+                parse_pubkey("account", account)?,
+                parse_pubkey("authority", authority)?,
+            ))
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    instruction_dict(
+        py,
+        instruction::transfer_route(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &hops,
+            &parse_pubkey("destination", destination)?,
+            amount,
+        ),
+    )
+}
+
+/// Pays the `i`th of `amounts` from `from` to the `i`th of `destinations`, whole or not
+/// at all.
+#[pyfunction]
+fn transfer_batch<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    from: &str,
+    owner: &str,
+    destinations: Vec<String>,
+    amounts: Vec<u64>,
+) -> PyResult<Bound<'py, PyDict>> {
+    if destinations.len() != amounts.len() {
+        return Err(PyValueError::new_err("one amount per destination"));
+    }
+    let destinations = destinations
+        .iter()
+        .map(|destination| parse_pubkey("destination", destination))
+        .collect::<PyResult<Vec<_>>>()?;
+    let transfers: Vec<_> = destinations.iter().zip(amounts).collect();
+    instruction_dict(
+        py,
+        instruction::transfer_batch(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("from", from)?,
+            &parse_pubkey("owner", owner)?,
+            &transfers,
+        ),
+    )
+}
+
+/// Hands `account` over to the address `seeds`, bump last, derive from `program`.
+#[pyfunction]
+fn assign_to_program<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    owner: &str,
+    program: &str,
+    seeds: Vec<Vec<u8>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+    instruction_dict(
+        py,
+        instruction::assign_to_program(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("owner", owner)?,
+            &parse_pubkey("program", program)?,
+            &seeds,
+        ),
+    )
+}
+
+#[pyfunction]
+fn approve<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    delegate: &str,
+    owner: &str,
+    amount: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::approve(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("delegate", delegate)?,
+            &parse_pubkey("owner", owner)?,
+            amount,
+        ),
+    )
+}
+
+#[pyfunction]
+fn revoke<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    owner: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::revoke(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("owner", owner)?,
+        ),
+    )
+}
+
+#[pyfunction]
+fn set_authority<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    owned: &str,
+    authority_type: u8,
+    current_authority: &str,
+    new_authority: Option<&str>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let authority_type = AuthorityType::try_from(authority_type).map_err(|_| {
+        PyValueError::new_err(format!("invalid authority type: {}", authority_type))
+    })?;
+    let new_authority = new_authority
+        .map(|key| parse_pubkey("new_authority", key))
+        .transpose()?;
+    instruction_dict(
+        py,
+        instruction::set_authority(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("owned", owned)?,
+            authority_type,
+            &parse_pubkey("current_authority", current_authority)?,
+            new_authority.as_ref(),
+        ),
+    )
+}
+
+#[pyfunction]
+fn mint_to<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    bank_owner: &str,
+    amount: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::mint_to(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("bank_owner", bank_owner)?,
+            amount,
+        ),
+    )
+}
+
+#[pyfunction]
+fn burn<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    bank_owner: &str,
+    account_owner: &str,
+    amount: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::burn(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("bank_owner", bank_owner)?,
+            &parse_pubkey("account_owner", account_owner)?,
+            amount,
+        ),
+    )
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+fn transfer_checked<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    from: &str,
+    to: &str,
+    owner: &str,
+    amount: u64,
+    decimals: u8,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::transfer_checked(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("from", from)?,
+            &parse_pubkey("to", to)?,
+            &parse_pubkey("owner", owner)?,
+            &[],
+            amount,
+            decimals,
+        ),
+    )
+}
+
+#[pyfunction]
+fn mint_to_checked<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    bank_owner: &str,
+    amount: u64,
+    decimals: u8,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::mint_to_checked(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("bank_owner", bank_owner)?,
+            &[],
+            amount,
+            decimals,
+        ),
+    )
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+fn burn_checked<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    bank_owner: &str,
+    account_owner: &str,
+    amount: u64,
+    decimals: u8,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::burn_checked(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("bank_owner", bank_owner)?,
+            &parse_pubkey("account_owner", account_owner)?,
+            amount,
+            decimals,
+        ),
+    )
+}
+
+#[pyfunction]
+fn close_account<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    destination: &str,
+    owner: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::close_account(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("destination", destination)?,
+            &parse_pubkey("owner", owner)?,
+        ),
+    )
+}
+
+#[pyfunction]
+fn set_close_policy<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    bank_owner: &str,
+    policy: u8,
+) -> PyResult<Bound<'py, PyDict>> {
+    let policy = ClosePolicy::try_from(policy)
+        .map_err(|_| PyValueError::new_err(format!("invalid close policy: {}", policy)))?;
+    instruction_dict(
+        py,
+        instruction::set_close_policy(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("bank_owner", bank_owner)?,
+            policy,
+        ),
+    )
+}
+
+#[pyfunction]
+fn set_require_rent_exempt<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    bank_owner: &str,
+    required: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::set_require_rent_exempt(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("bank_owner", bank_owner)?,
+            required,
+        ),
+    )
+}
+
+/// The message the account owner signs for `permit_approve`.
+#[pyfunction]
+fn permit_message<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    account: &str,
+    delegate: &str,
+    amount: u64,
+    nonce: u64,
+    expiry: i64,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let message = permit::message(
+        &parse_pubkey("program_id", program_id)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("delegate", delegate)?,
+        amount,
+        nonce,
+        expiry,
+    );
+    Ok(PyBytes::new(py, &message))
+}
+
+/// The ed25519 program instruction carrying the owner's signature of a permit, to go
+/// right before its `permit_approve`.
+#[pyfunction]
+fn permit_signature<'py>(
+    py: Python<'py>,
+    owner: &str,
+    signature: &[u8],
+    message: &[u8],
+) -> PyResult<Bound<'py, PyDict>> {
+    let signature = signature
+        .try_into()
+        .map_err(|_| PyValueError::new_err("a signature is 64 bytes"))?;
+    instruction_dict(
+        py,
+        Ok(permit::signature_instruction(
+            &parse_pubkey("owner", owner)?,
+            signature,
+            message,
+        )),
+    )
+}
+
+#[pyfunction]
+fn permit_approve<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    delegate: &str,
+    amount: u64,
+    nonce: u64,
+    expiry: i64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::permit_approve(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("delegate", delegate)?,
+            amount,
+            nonce,
+            expiry,
+        ),
+    )
+}
+
+#[pyfunction]
+fn deposit_sol<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    depositor: &str,
+    lamports: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::deposit_sol(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("depositor", depositor)?,
+            lamports,
+        ),
+    )
+}
+
+#[pyfunction]
+fn withdraw_sol<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    owner: &str,
+    destination: &str,
+    lamports: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::withdraw_sol(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("owner", owner)?,
+            &parse_pubkey("destination", destination)?,
+            lamports,
+        ),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+fn set_exchange_rate<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    rate: &str,
+    from_bank: &str,
+    to_bank: &str,
+    from_bank_owner: &str,
+    to_bank_owner: &str,
+    numerator: u64,
+    denominator: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::set_exchange_rate(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("rate", rate)?,
+            &parse_pubkey("from_bank", from_bank)?,
+            &parse_pubkey("to_bank", to_bank)?,
+            &parse_pubkey("from_bank_owner", from_bank_owner)?,
+            &parse_pubkey("to_bank_owner", to_bank_owner)?,
+            numerator,
+            denominator,
+        ),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+fn convert<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    rate: &str,
+    from_bank: &str,
+    from_account: &str,
+    owner: &str,
+    to_bank: &str,
+    to_account: &str,
+    amount: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::convert(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("rate", rate)?,
+            &parse_pubkey("from_bank", from_bank)?,
+            &parse_pubkey("from_account", from_account)?,
+            &parse_pubkey("owner", owner)?,
+            &parse_pubkey("to_bank", to_bank)?,
+            &parse_pubkey("to_account", to_account)?,
+            amount,
+        ),
+    )
+}
+
+#[pyfunction]
+fn set_dust_threshold<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    bank_owner: &str,
+    threshold: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::set_dust_threshold(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("bank_owner", bank_owner)?,
+            threshold,
+        ),
+    )
+}
+
+#[pyfunction]
+fn pause_bank<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    bank_owner: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::pause_bank(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("bank_owner", bank_owner)?,
+        ),
+    )
+}
+
+#[pyfunction]
+fn resume_bank<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    bank_owner: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::resume_bank(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("bank_owner", bank_owner)?,
+        ),
+    )
+}
+
+#[pyfunction]
+fn accrue_interest<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::accrue_interest(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+        ),
+    )
+}
+
+#[pyfunction]
+fn set_owner_change_delay<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    bank_owner: &str,
+    delay: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::set_owner_change_delay(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("bank_owner", bank_owner)?,
+            delay,
+        ),
+    )
+}
+
+#[pyfunction]
+fn cancel_owner_change<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    authority: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::cancel_owner_change(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("authority", authority)?,
+        ),
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (program_id, bank, account, owner, sweep_account=None))]
+fn close_dust_account<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    owner: &str,
+    sweep_account: Option<&str>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let sweep_account = sweep_account
+        .map(|key| parse_pubkey("sweep_account", key))
+        .transpose()?;
+    instruction_dict(
+        py,
+        instruction::close_dust_account(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("owner", owner)?,
+            sweep_account.as_ref(),
+        ),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+fn set_guardians<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    account_owner: &str,
+    payer: &str,
+    guardians: Vec<String>,
+    threshold: u8,
+    delay: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::set_guardians(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("account_owner", account_owner)?,
+            &parse_pubkey("payer", payer)?,
+            &parse_guardians(&guardians)?,
+            threshold,
+            delay,
+        ),
+    )
+}
+
+#[pyfunction]
+fn start_recovery<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    guardians: Vec<String>,
+    new_owner: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::start_recovery(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_guardians(&guardians)?,
+            &parse_pubkey("new_owner", new_owner)?,
+        ),
+    )
+}
+
+#[pyfunction]
+fn recover_owner<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    guardians: Vec<String>,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::recover_owner(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_guardians(&guardians)?,
+        ),
+    )
+}
+
+#[pyfunction]
+fn cancel_recovery<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    account_owner: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::cancel_recovery(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("account_owner", account_owner)?,
+        ),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+fn create_session_key<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    account_owner: &str,
+    payer: &str,
+    session_key: &str,
+    max_spend: u64,
+    expiry_slot: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::create_session_key(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("account_owner", account_owner)?,
+            &parse_pubkey("payer", payer)?,
+            &parse_pubkey("session_key", session_key)?,
+            max_spend,
+            expiry_slot,
+        ),
+    )
+}
+
+#[pyfunction]
+fn transfer_with_session_key<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    from_account: &str,
+    to_account: &str,
+    session_key: &str,
+    amount: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::transfer_with_session_key(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("from_account", from_account)?,
+            &parse_pubkey("to_account", to_account)?,
+            &parse_pubkey("session_key", session_key)?,
+            amount,
+        ),
+    )
+}
+
+#[pyfunction]
+fn set_rate_limit<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    account_owner: &str,
+    capacity: u64,
+    refill_per_slot: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::set_rate_limit(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("account_owner", account_owner)?,
+            capacity,
+            refill_per_slot,
+        ),
+    )
+}
+
+#[pyfunction]
+fn initialize_version<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    payer: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::initialize_version(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("payer", payer)?,
+        ),
+    )
+}
+
+#[pyfunction]
+fn set_features<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    bank_owner: &str,
+    payer: &str,
+    enabled: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::set_features(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("bank_owner", bank_owner)?,
+            &parse_pubkey("payer", payer)?,
+            enabled,
+        ),
+    )
+}
+
+#[pyfunction]
+fn freeze_account<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    freeze_authority: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::freeze_account(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("freeze_authority", freeze_authority)?,
+        ),
+    )
+}
+
+#[pyfunction]
+fn thaw_account<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    freeze_authority: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::thaw_account(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("account", account)?,
+            &parse_pubkey("freeze_authority", freeze_authority)?,
+        ),
+    )
+}
+
+#[pyfunction]
+fn initialize_multisig<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    multisig: &str,
+    signers: Vec<String>,
+    threshold: u8,
+) -> PyResult<Bound<'py, PyDict>> {
+    let signers = parse_signers(&signers)?;
+    instruction_dict(
+        py,
+        instruction::initialize_multisig(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("multisig", multisig)?,
+            &signers.iter().collect::<Vec<_>>(),
+            threshold,
+        ),
+    )
+}
+
+#[pyfunction]
+fn initialize_associated_account<'py>(
+    py: Python<'py>,
+    program_id: &str,
+    bank: &str,
+    wallet: &str,
+    payer: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    instruction_dict(
+        py,
+        instruction::initialize_associated_account(
+            &parse_pubkey("program_id", program_id)?,
+            &parse_pubkey("bank", bank)?,
+            &parse_pubkey("wallet", wallet)?,
+            &parse_pubkey("payer", payer)?,
+        ),
+    )
+}
+
+/// Read-only access to a deployed bank program over JSON RPC.
+#[pyclass(unsendable)]
+struct BankRpc {
+    client: BankClient,
+}
+
+#[pymethods]
+impl BankRpc {
+    #[new]
+    fn new(url: String, program_id: &str) -> PyResult<BankRpc> {
+        let program_id = parse_pubkey("program_id", program_id)?;
+        Ok(BankRpc {
+            client: BankClient::new(RpcClient::new(url), program_id),
+        })
+    }
+
+    fn get_bank<'py>(&self, py: Python<'py>, address: &str) -> PyResult<Bound<'py, PyDict>> {
+        let bank = self
+            .client
+            .get_bank(&parse_pubkey("address", address)?)
+            .map_err(PyRuntimeError::new_err)?;
+        bank_dict(py, &bank)
+    }
+
+    fn get_account<'py>(&self, py: Python<'py>, address: &str) -> PyResult<Bound<'py, PyDict>> {
+        let account = self
+            .client
+            .get_account(&parse_pubkey("address", address)?)
+            .map_err(PyRuntimeError::new_err)?;
+        account_dict(py, &account)
+    }
+
+    /// `(address, account)` pairs of every account of `bank`.
+    fn get_bank_accounts<'py>(&self, py: Python<'py>, bank: &str) -> PyResult<Bound<'py, PyList>> {
+        let accounts = self
+            .client
+            .get_bank_accounts(&parse_pubkey("bank", bank)?)
+            .map_err(PyRuntimeError::new_err)?;
+        keyed_accounts(py, &accounts)
+    }
+
+    /// `(address, account)` pairs of every account owned by `owner`.
+    fn get_owner_accounts<'py>(
+        &self,
+        py: Python<'py>,
+        owner: &str,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let accounts = self
+            .client
+            .get_owner_accounts(&parse_pubkey("owner", owner)?)
+            .map_err(PyRuntimeError::new_err)?;
+        keyed_accounts(py, &accounts)
+    }
+}
+
+fn keyed_accounts<'py>(
+    py: Python<'py>,
+    accounts: &[(Pubkey, Account)],
+) -> PyResult<Bound<'py, PyList>> {
+    let list = PyList::empty(py);
+    for (key, account) in accounts.iter() {
+        list.append((key.to_string(), account_dict(py, account)?))?;
+    }
+    Ok(list)
+}
+
+#[pymodule]
+fn solana_play_bank(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(decode_bank, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_account, m)?)?;
+    m.add_function(wrap_pyfunction!(initialize_bank, m)?)?;
+    m.add_function(wrap_pyfunction!(initialize_account, m)?)?;
+    m.add_function(wrap_pyfunction!(transfer, m)?)?;
+    m.add_function(wrap_pyfunction!(transfer_with_expiry, m)?)?;
+    m.add_function(wrap_pyfunction!(transfer_route, m)?)?;
+    m.add_function(wrap_pyfunction!(transfer_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(assign_to_program, m)?)?;
+    m.add_function(wrap_pyfunction!(approve, m)?)?;
+    m.add_function(wrap_pyfunction!(revoke, m)?)?;
+    m.add_function(wrap_pyfunction!(set_authority, m)?)?;
+    m.add_function(wrap_pyfunction!(mint_to, m)?)?;
+    m.add_function(wrap_pyfunction!(burn, m)?)?;
+    m.add_function(wrap_pyfunction!(transfer_checked, m)?)?;
+    m.add_function(wrap_pyfunction!(mint_to_checked, m)?)?;
+    m.add_function(wrap_pyfunction!(burn_checked, m)?)?;
+    m.add_function(wrap_pyfunction!(close_account, m)?)?;
+    m.add_function(wrap_pyfunction!(set_close_policy, m)?)?;
+    m.add_function(wrap_pyfunction!(set_require_rent_exempt, m)?)?;
+    m.add_function(wrap_pyfunction!(permit_message, m)?)?;
+    m.add_function(wrap_pyfunction!(permit_signature, m)?)?;
+    m.add_function(wrap_pyfunction!(permit_approve, m)?)?;
+    m.add_function(wrap_pyfunction!(deposit_sol, m)?)?;
+    m.add_function(wrap_pyfunction!(withdraw_sol, m)?)?;
+    m.add_function(wrap_pyfunction!(set_exchange_rate, m)?)?;
+    m.add_function(wrap_pyfunction!(convert, m)?)?;
+    m.add_function(wrap_pyfunction!(set_dust_threshold, m)?)?;
+    m.add_function(wrap_pyfunction!(pause_bank, m)?)?;
+    m.add_function(wrap_pyfunction!(resume_bank, m)?)?;
+    m.add_function(wrap_pyfunction!(accrue_interest, m)?)?;
+    m.add_function(wrap_pyfunction!(set_owner_change_delay, m)?)?;
+    m.add_function(wrap_pyfunction!(cancel_owner_change, m)?)?;
+    m.add_function(wrap_pyfunction!(close_dust_account, m)?)?;
+    m.add_function(wrap_pyfunction!(set_guardians, m)?)?;
+    m.add_function(wrap_pyfunction!(start_recovery, m)?)?;
+    m.add_function(wrap_pyfunction!(recover_owner, m)?)?;
+    m.add_function(wrap_pyfunction!(cancel_recovery, m)?)?;
+    m.add_function(wrap_pyfunction!(create_session_key, m)?)?;
+    m.add_function(wrap_pyfunction!(transfer_with_session_key, m)?)?;
+    m.add_function(wrap_pyfunction!(set_rate_limit, m)?)?;
+    m.add_function(wrap_pyfunction!(initialize_version, m)?)?;
+    m.add_function(wrap_pyfunction!(set_features, m)?)?;
+    m.add_function(wrap_pyfunction!(freeze_account, m)?)?;
+    m.add_function(wrap_pyfunction!(thaw_account, m)?)?;
+    m.add_function(wrap_pyfunction!(initialize_multisig, m)?)?;
+    m.add_function(wrap_pyfunction!(initialize_associated_account, m)?)?;
+    m.add_class::<BankRpc>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_and_decode_account() {
+        Python::with_gil(|py| {
+            let program_id = Pubkey::new_unique();
+            let owner = Pubkey::new_unique();
+            let bank = Pubkey::new_unique();
+            let ix = transfer(
+                py,
+                &program_id.to_string(),
+                &bank.to_string(),
+                &Pubkey::new_unique().to_string(),
+                &Pubkey::new_unique().to_string(),
+                &owner.to_string(),
+                50,
+            )
+            .unwrap();
+            let accounts: Vec<(String, bool, bool)> =
+                ix.get_item("accounts").unwrap().unwrap().extract().unwrap();
+            assert_eq!(accounts[2], (owner.to_string(), true, true));
+            assert_eq!(accounts[3], (bank.to_string(), false, false));
+            let data: Vec<u8> = ix.get_item("data").unwrap().unwrap().extract().unwrap();
+            assert_eq!(
+                instruction::BankInstruction::unpack(&data).unwrap(),
+                instruction::BankInstruction::Transfer {
+                    amount: 50,
+                    sweep_dust: false
+                }
+            );
+
+            let account = Account {
+                amount: 70,
+                is_opened: true,
+                is_initialized: true,
+                owner,
+                delegate: COption::None,
+                delegated_amount: 0,
+                bank: Pubkey::new_unique(),
+                permit_nonce: 0,
+                is_frozen: false,
+                last_accrual: 0,
+            };
+            let mut data = vec![0u8; Account::LEN];
+            Account::pack(account, &mut data).unwrap();
+            let decoded = decode_account(py, &data).unwrap();
+            let amount: u64 = decoded
+                .get_item("amount")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(amount, 70);
+            assert!(decode_bank(py, &data).is_err());
+            assert!(transfer(py, "bad", "bad", "bad", "bad", "bad", 1).is_err());
+        });
+    }
+}
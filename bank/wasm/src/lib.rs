@@ -0,0 +1,1049 @@
+//! Browser bindings of the bank program. Builders return plain instruction objects
+//! (`{ programId, keys: [{ pubkey, isSigner, isWritable }], data }`) that the host
+//! turns into a `TransactionInstruction` and has the user's wallet sign; no keys
+//! ever reach this module.
+
+use serde::Serialize;
+use solana_bank::{
+    instruction::{self, AuthorityType},
+    permit,
+    solana_program::{
+        instruction::Instruction, program_error::ProgramError, program_option::COption,
+        program_pack::Pack, pubkey::Pubkey,
+    },
+    state::{Account, Bank, ClosePolicy},
+};
+use std::{
+    convert::{TryFrom, TryInto},
+    str::FromStr,
+};
+use wasm_bindgen::prelude::*;
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountMetaView {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstructionView {
+    pub program_id: String,
+    pub keys: Vec<AccountMetaView>,
+    pub data: Vec<u8>,
+}
+
+impl From<Instruction> for InstructionView {
+    fn from(ix: Instruction) -> InstructionView {
+        InstructionView {
+            program_id: ix.program_id.to_string(),
+            keys: ix
+                .accounts
+                .iter()
+                .map(|meta| AccountMetaView {
+                    pubkey: meta.pubkey.to_string(),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: ix.data,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BankView {
+    pub decimals: u8,
+    pub bank_owner: String,
+    pub is_opened: bool,
+    /// Stringified so amounts above 2^53 survive the trip into a JS number.
+    pub total_supply: String,
+    /// The bank's `ClosePolicy`, as its `u8` discriminant.
+    pub close_policy: u8,
+    pub require_rent_exempt: bool,
+    /// The bank's `BurnPolicy`, as its `u8` discriminant.
+    pub burn_policy: u8,
+    /// Stringified like `total_supply`, as are the statistics.
+    pub lamports_per_token: String,
+    pub transfer_count: String,
+    pub cumulative_volume: String,
+    pub holder_count: String,
+    pub registered_accounts: String,
+    pub dust_threshold: String,
+    pub freeze_authority: Option<String>,
+    pub is_paused: bool,
+    pub interest_rate_bps: u16,
+    pub emergency_authority: Option<String>,
+    pub owner_change_delay: String,
+    pub pending_bank_owner: Option<String>,
+    pub owner_change_slot: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountView {
+    pub bank: String,
+    pub owner: String,
+    pub amount: String,
+    pub delegate: Option<String>,
+    pub delegated_amount: String,
+    pub is_opened: bool,
+    pub is_initialized: bool,
+    pub permit_nonce: String,
+    pub is_frozen: bool,
+    /// Stringified like `amount`.
+    pub last_accrual: String,
+}
+
+fn parse_pubkey(name: &str, value: &str) -> Result<Pubkey, JsError> {
+    Pubkey::from_str(value).map_err(|_| JsError::new(&format!("invalid {}: {}", name, value)))
+}
+
+fn parse_guardians(guardians: &[String]) -> Result<Vec<Pubkey>, JsError> {
+    guardians
+        .iter()
+        .map(|guardian| parse_pubkey("guardian", guardian))
+        .collect()
+}
+
+fn parse_signers(signers: &[String]) -> Result<Vec<Pubkey>, JsError> {
+    signers
+        .iter()
+        .map(|signer| parse_pubkey("signer", signer))
+        .collect()
+}
+
+fn to_js(ix: Result<Instruction, ProgramError>) -> Result<JsValue, JsError> {
+    let ix = ix.map_err(|e| JsError::new(&e.to_string()))?;
+    Ok(serde_wasm_bindgen::to_value(&InstructionView::from(ix))?)
+}
+
+pub fn bank_view(data: &[u8]) -> Result<BankView, String> {
+    let bank = Bank::unpack(data).map_err(|e| e.to_string())?;
+    let freeze_authority = match bank.freeze_authority {
+        COption::Some(authority) => Some(authority.to_string()),
+        COption::None => None,
+    };
+    let emergency_authority = match bank.emergency_authority {
+        COption::Some(authority) => Some(authority.to_string()),
+        COption::None => None,
+    };
+    let pending_bank_owner = match bank.pending_bank_owner {
+        COption::Some(owner) => Some(owner.to_string()),
+        COption::None => None,
+    };
+    Ok(BankView {
+        decimals: bank.decimals,
+        bank_owner: bank.bank_owner.to_string(),
+        is_opened: bank.is_opened,
+        total_supply: bank.total_supply.to_string(),
+        close_policy: bank.close_policy as u8,
+        require_rent_exempt: bank.require_rent_exempt,
+        burn_policy: bank.burn_policy as u8,
+        lamports_per_token: bank.lamports_per_token.to_string(),
+        transfer_count: bank.transfer_count.to_string(),
+        cumulative_volume: bank.cumulative_volume.to_string(),
+        holder_count: bank.holder_count.to_string(),
+        registered_accounts: bank.registered_accounts.to_string(),
+        dust_threshold: bank.dust_threshold.to_string(),
+        freeze_authority,
+        is_paused: bank.is_paused,
+        interest_rate_bps: bank.interest_rate_bps,
+        emergency_authority,
+        owner_change_delay: bank.owner_change_delay.to_string(),
+        pending_bank_owner,
+        owner_change_slot: bank.owner_change_slot.to_string(),
+    })
+}
+
+pub fn account_view(data: &[u8]) -> Result<AccountView, String> {
+    let account = Account::unpack_extended(data).map_err(|e| e.to_string())?;
+    let delegate = match account.delegate {
+        COption::Some(d) => Some(d.to_string()),
+        COption::None => None,
+    };
+    Ok(AccountView {
+        bank: account.bank.to_string(),
+        owner: account.owner.to_string(),
+        amount: account.amount.to_string(),
+        delegate,
+        delegated_amount: account.delegated_amount.to_string(),
+        is_opened: account.is_opened,
+        is_initialized: account.is_initialized,
+        permit_nonce: account.permit_nonce.to_string(),
+        is_frozen: account.is_frozen,
+        last_accrual: account.last_accrual.to_string(),
+    })
+}
+
+#[wasm_bindgen(js_name = decodeBank)]
+pub fn decode_bank(data: &[u8]) -> Result<JsValue, JsError> {
+    let view = bank_view(data).map_err(|e| JsError::new(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&view)?)
+}
+
+#[wasm_bindgen(js_name = decodeAccount)]
+pub fn decode_account(data: &[u8]) -> Result<JsValue, JsError> {
+    let view = account_view(data).map_err(|e| JsError::new(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&view)?)
+}
+
+#[wasm_bindgen(js_name = initializeBank)]
+pub fn initialize_bank(
+    program_id: &str,
+    bank: &str,
+    bank_owner: &str,
+    decimals: u8,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::initialize_bank(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("bank owner", bank_owner)?,
+        decimals,
+    ))
+}
+
+#[wasm_bindgen(js_name = initializeAccount)]
+pub fn initialize_account(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    owner: &str,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::initialize_account(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("owner", owner)?,
+    ))
+}
+
+#[wasm_bindgen]
+pub fn transfer(
+    program_id: &str,
+    bank: &str,
+    from: &str,
+    to: &str,
+    owner: &str,
+    amount: u64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::transfer(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("from", from)?,
+        &parse_pubkey("to", to)?,
+        &parse_pubkey("owner", owner)?,
+        amount,
+    ))
+}
+
+#[wasm_bindgen(js_name = transferWithExpiry)]
+pub fn transfer_with_expiry(
+    program_id: &str,
+    bank: &str,
+    from: &str,
+    to: &str,
+    owner: &str,
+    amount: u64,
+    max_slot: u64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::transfer_with_expiry(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("from", from)?,
+        &parse_pubkey("to", to)?,
+        &parse_pubkey("owner", owner)?,
+        amount,
+        max_slot,
+    ))
+}
+
+/// Routes `amount` from `accounts[0]` to the last of `accounts`, each sending account
+/// signed for by the authority at its index.
+#[wasm_bindgen(js_name = transferRoute)]
+pub fn transfer_route(
+    program_id: &str,
+    bank: &str,
+    accounts: Vec<String>,
+    authorities: Vec<String>,
+    amount: u64,
+) -> Result<JsValue, JsError> {
+    let (destination, senders) = accounts
+        .split_last()
+        .ok_or_else(|| JsError::new("a route needs accounts"))?;
+    if senders.len() != authorities.len() {
+        return Err(JsError::new("one authority per sending account"));
+    }
+    let hops = senders
+        .iter()
+        .zip(&authorities)
+        .map(|(account, authority)| {
+            Ok((
+                parse_pubkey("account", account)?,
+                parse_pubkey("authority", authority)?,
+            ))
+        })
+        .collect::<Result<Vec<_>, JsError>>()?;
+    to_js(instruction::transfer_route(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &hops,
+        &parse_pubkey("destination", destination)?,
+        amount,
+    ))
+}
+
+/// Pays the `i`th of `amounts` from `from` to the `i`th of `destinations`, whole or not
+/// at all.
+#[wasm_bindgen(js_name = transferBatch)]
+pub fn transfer_batch(
+    program_id: &str,
+    bank: &str,
+    from: &str,
+    owner: &str,
+    destinations: Vec<String>,
+    amounts: Vec<u64>,
+) -> Result<JsValue, JsError> {
+    if destinations.len() != amounts.len() {
+        return Err(JsError::new("one amount per destination"));
+    }
+    let destinations = destinations
+        .iter()
+        .map(|destination| parse_pubkey("destination", destination))
+        .collect::<Result<Vec<_>, JsError>>()?;
+    let transfers: Vec<_> = destinations.iter().zip(amounts).collect();
+    to_js(instruction::transfer_batch(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("from", from)?,
+        &parse_pubkey("owner", owner)?,
+        &transfers,
+    ))
+}
+
+/// Hands `account` over to the address `seeds`, an array of byte arrays with the bump
+/// last, derive from `program`.
+#[wasm_bindgen(js_name = assignToProgram)]
+pub fn assign_to_program(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    owner: &str,
+    program: &str,
+    seeds: JsValue,
+) -> Result<JsValue, JsError> {
+    let seeds: Vec<Vec<u8>> = serde_wasm_bindgen::from_value(seeds)?;
+    let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+    to_js(instruction::assign_to_program(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("owner", owner)?,
+        &parse_pubkey("program", program)?,
+        &seeds,
+    ))
+}
+
+#[wasm_bindgen]
+pub fn approve(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    delegate: &str,
+    owner: &str,
+    amount: u64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::approve(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("delegate", delegate)?,
+        &parse_pubkey("owner", owner)?,
+        amount,
+    ))
+}
+
+#[wasm_bindgen]
+pub fn revoke(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    owner: &str,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::revoke(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("owner", owner)?,
+    ))
+}
+
+#[wasm_bindgen(js_name = setAuthority)]
+pub fn set_authority(
+    program_id: &str,
+    owned: &str,
+    authority_type: u8,
+    current_authority: &str,
+    new_authority: Option<String>,
+) -> Result<JsValue, JsError> {
+    let authority_type = AuthorityType::try_from(authority_type)
+        .map_err(|_| JsError::new(&format!("invalid authority type: {}", authority_type)))?;
+    let new_authority = new_authority
+        .map(|key| parse_pubkey("new authority", &key))
+        .transpose()?;
+    to_js(instruction::set_authority(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("owned", owned)?,
+        authority_type,
+        &parse_pubkey("current authority", current_authority)?,
+        new_authority.as_ref(),
+    ))
+}
+
+#[wasm_bindgen(js_name = mintTo)]
+pub fn mint_to(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    bank_owner: &str,
+    amount: u64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::mint_to(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("bank owner", bank_owner)?,
+        amount,
+    ))
+}
+
+#[wasm_bindgen]
+pub fn burn(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    bank_owner: &str,
+    account_owner: &str,
+    amount: u64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::burn(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("bank owner", bank_owner)?,
+        &parse_pubkey("account owner", account_owner)?,
+        amount,
+    ))
+}
+
+#[wasm_bindgen(js_name = transferChecked)]
+pub fn transfer_checked(
+    program_id: &str,
+    bank: &str,
+    from: &str,
+    to: &str,
+    owner: &str,
+    amount: u64,
+    decimals: u8,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::transfer_checked(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("from", from)?,
+        &parse_pubkey("to", to)?,
+        &parse_pubkey("owner", owner)?,
+        &[],
+        amount,
+        decimals,
+    ))
+}
+
+#[wasm_bindgen(js_name = mintToChecked)]
+pub fn mint_to_checked(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    bank_owner: &str,
+    amount: u64,
+    decimals: u8,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::mint_to_checked(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("bank owner", bank_owner)?,
+        &[],
+        amount,
+        decimals,
+    ))
+}
+
+#[wasm_bindgen(js_name = burnChecked)]
+pub fn burn_checked(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    bank_owner: &str,
+    account_owner: &str,
+    amount: u64,
+    decimals: u8,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::burn_checked(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("bank owner", bank_owner)?,
+        &parse_pubkey("account owner", account_owner)?,
+        amount,
+        decimals,
+    ))
+}
+
+#[wasm_bindgen(js_name = closeAccount)]
+pub fn close_account(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    destination: &str,
+    owner: &str,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::close_account(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("destination", destination)?,
+        &parse_pubkey("owner", owner)?,
+    ))
+}
+
+#[wasm_bindgen(js_name = setClosePolicy)]
+pub fn set_close_policy(
+    program_id: &str,
+    bank: &str,
+    bank_owner: &str,
+    policy: u8,
+) -> Result<JsValue, JsError> {
+    let policy = ClosePolicy::try_from(policy)
+        .map_err(|_| JsError::new(&format!("invalid close policy: {}", policy)))?;
+    to_js(instruction::set_close_policy(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("bank owner", bank_owner)?,
+        policy,
+    ))
+}
+
+#[wasm_bindgen(js_name = setRequireRentExempt)]
+pub fn set_require_rent_exempt(
+    program_id: &str,
+    bank: &str,
+    bank_owner: &str,
+    required: bool,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::set_require_rent_exempt(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("bank owner", bank_owner)?,
+        required,
+    ))
+}
+
+/// The message the account owner signs for `permitApprove`.
+#[wasm_bindgen(js_name = permitMessage)]
+pub fn permit_message(
+    program_id: &str,
+    account: &str,
+    delegate: &str,
+    amount: u64,
+    nonce: u64,
+    expiry: i64,
+) -> Result<Vec<u8>, JsError> {
+    Ok(permit::message(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("delegate", delegate)?,
+        amount,
+        nonce,
+        expiry,
+    ))
+}
+
+/// The ed25519 program instruction carrying the owner's signature of a permit, to go
+/// right before its `permitApprove`.
+#[wasm_bindgen(js_name = permitSignature)]
+pub fn permit_signature(owner: &str, signature: &[u8], message: &[u8]) -> Result<JsValue, JsError> {
+    let signature = signature
+        .try_into()
+        .map_err(|_| JsError::new("a signature is 64 bytes"))?;
+    to_js(Ok(permit::signature_instruction(
+        &parse_pubkey("owner", owner)?,
+        signature,
+        message,
+    )))
+}
+
+#[wasm_bindgen(js_name = permitApprove)]
+pub fn permit_approve(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    delegate: &str,
+    amount: u64,
+    nonce: u64,
+    expiry: i64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::permit_approve(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("delegate", delegate)?,
+        amount,
+        nonce,
+        expiry,
+    ))
+}
+
+#[wasm_bindgen(js_name = depositSol)]
+pub fn deposit_sol(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    depositor: &str,
+    lamports: u64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::deposit_sol(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("depositor", depositor)?,
+        lamports,
+    ))
+}
+
+#[wasm_bindgen(js_name = withdrawSol)]
+pub fn withdraw_sol(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    owner: &str,
+    destination: &str,
+    lamports: u64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::withdraw_sol(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("owner", owner)?,
+        &parse_pubkey("destination", destination)?,
+        lamports,
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen(js_name = setExchangeRate)]
+pub fn set_exchange_rate(
+    program_id: &str,
+    rate: &str,
+    from_bank: &str,
+    to_bank: &str,
+    from_bank_owner: &str,
+    to_bank_owner: &str,
+    numerator: u64,
+    denominator: u64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::set_exchange_rate(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("rate", rate)?,
+        &parse_pubkey("from bank", from_bank)?,
+        &parse_pubkey("to bank", to_bank)?,
+        &parse_pubkey("from bank owner", from_bank_owner)?,
+        &parse_pubkey("to bank owner", to_bank_owner)?,
+        numerator,
+        denominator,
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen]
+pub fn convert(
+    program_id: &str,
+    rate: &str,
+    from_bank: &str,
+    from_account: &str,
+    owner: &str,
+    to_bank: &str,
+    to_account: &str,
+    amount: u64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::convert(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("rate", rate)?,
+        &parse_pubkey("from bank", from_bank)?,
+        &parse_pubkey("from account", from_account)?,
+        &parse_pubkey("owner", owner)?,
+        &parse_pubkey("to bank", to_bank)?,
+        &parse_pubkey("to account", to_account)?,
+        amount,
+    ))
+}
+
+#[wasm_bindgen(js_name = setDustThreshold)]
+pub fn set_dust_threshold(
+    program_id: &str,
+    bank: &str,
+    bank_owner: &str,
+    threshold: u64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::set_dust_threshold(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("bank owner", bank_owner)?,
+        threshold,
+    ))
+}
+
+#[wasm_bindgen(js_name = pauseBank)]
+pub fn pause_bank(program_id: &str, bank: &str, bank_owner: &str) -> Result<JsValue, JsError> {
+    to_js(instruction::pause_bank(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("bank owner", bank_owner)?,
+    ))
+}
+
+#[wasm_bindgen(js_name = resumeBank)]
+pub fn resume_bank(program_id: &str, bank: &str, bank_owner: &str) -> Result<JsValue, JsError> {
+    to_js(instruction::resume_bank(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("bank owner", bank_owner)?,
+    ))
+}
+
+#[wasm_bindgen(js_name = accrueInterest)]
+pub fn accrue_interest(program_id: &str, bank: &str, account: &str) -> Result<JsValue, JsError> {
+    to_js(instruction::accrue_interest(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+    ))
+}
+
+#[wasm_bindgen(js_name = setOwnerChangeDelay)]
+pub fn set_owner_change_delay(
+    program_id: &str,
+    bank: &str,
+    bank_owner: &str,
+    delay: u64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::set_owner_change_delay(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("bank owner", bank_owner)?,
+        delay,
+    ))
+}
+
+#[wasm_bindgen(js_name = cancelOwnerChange)]
+pub fn cancel_owner_change(
+    program_id: &str,
+    bank: &str,
+    authority: &str,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::cancel_owner_change(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("authority", authority)?,
+    ))
+}
+
+#[wasm_bindgen(js_name = closeDustAccount)]
+pub fn close_dust_account(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    owner: &str,
+    sweep_account: Option<String>,
+) -> Result<JsValue, JsError> {
+    let sweep_account = sweep_account
+        .map(|key| parse_pubkey("sweep account", &key))
+        .transpose()?;
+    to_js(instruction::close_dust_account(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("owner", owner)?,
+        sweep_account.as_ref(),
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen(js_name = setGuardians)]
+pub fn set_guardians(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    account_owner: &str,
+    payer: &str,
+    guardians: Vec<String>,
+    threshold: u8,
+    delay: u64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::set_guardians(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("account owner", account_owner)?,
+        &parse_pubkey("payer", payer)?,
+        &parse_guardians(&guardians)?,
+        threshold,
+        delay,
+    ))
+}
+
+#[wasm_bindgen(js_name = startRecovery)]
+pub fn start_recovery(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    guardians: Vec<String>,
+    new_owner: &str,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::start_recovery(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_guardians(&guardians)?,
+        &parse_pubkey("new owner", new_owner)?,
+    ))
+}
+
+#[wasm_bindgen(js_name = recoverOwner)]
+pub fn recover_owner(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    guardians: Vec<String>,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::recover_owner(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_guardians(&guardians)?,
+    ))
+}
+
+#[wasm_bindgen(js_name = cancelRecovery)]
+pub fn cancel_recovery(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    account_owner: &str,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::cancel_recovery(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("account owner", account_owner)?,
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen(js_name = createSessionKey)]
+pub fn create_session_key(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    account_owner: &str,
+    payer: &str,
+    session_key: &str,
+    max_spend: u64,
+    expiry_slot: u64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::create_session_key(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("account owner", account_owner)?,
+        &parse_pubkey("payer", payer)?,
+        &parse_pubkey("session key", session_key)?,
+        max_spend,
+        expiry_slot,
+    ))
+}
+
+#[wasm_bindgen(js_name = transferWithSessionKey)]
+pub fn transfer_with_session_key(
+    program_id: &str,
+    bank: &str,
+    from: &str,
+    to: &str,
+    session_key: &str,
+    amount: u64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::transfer_with_session_key(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("from", from)?,
+        &parse_pubkey("to", to)?,
+        &parse_pubkey("session key", session_key)?,
+        amount,
+    ))
+}
+
+#[wasm_bindgen(js_name = setRateLimit)]
+pub fn set_rate_limit(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    account_owner: &str,
+    capacity: u64,
+    refill_per_slot: u64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::set_rate_limit(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("account owner", account_owner)?,
+        capacity,
+        refill_per_slot,
+    ))
+}
+
+#[wasm_bindgen(js_name = initializeVersion)]
+pub fn initialize_version(program_id: &str, payer: &str) -> Result<JsValue, JsError> {
+    to_js(instruction::initialize_version(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("payer", payer)?,
+    ))
+}
+
+#[wasm_bindgen(js_name = setFeatures)]
+pub fn set_features(
+    program_id: &str,
+    bank: &str,
+    bank_owner: &str,
+    payer: &str,
+    enabled: u64,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::set_features(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("bank owner", bank_owner)?,
+        &parse_pubkey("payer", payer)?,
+        enabled,
+    ))
+}
+
+#[wasm_bindgen(js_name = freezeAccount)]
+pub fn freeze_account(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    freeze_authority: &str,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::freeze_account(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("freeze authority", freeze_authority)?,
+    ))
+}
+
+#[wasm_bindgen(js_name = thawAccount)]
+pub fn thaw_account(
+    program_id: &str,
+    bank: &str,
+    account: &str,
+    freeze_authority: &str,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::thaw_account(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("account", account)?,
+        &parse_pubkey("freeze authority", freeze_authority)?,
+    ))
+}
+
+#[wasm_bindgen(js_name = initializeMultisig)]
+pub fn initialize_multisig(
+    program_id: &str,
+    multisig: &str,
+    signers: Vec<String>,
+    threshold: u8,
+) -> Result<JsValue, JsError> {
+    let signers = parse_signers(&signers)?;
+    to_js(instruction::initialize_multisig(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("multisig", multisig)?,
+        &signers.iter().collect::<Vec<_>>(),
+        threshold,
+    ))
+}
+
+#[wasm_bindgen(js_name = initializeAssociatedAccount)]
+pub fn initialize_associated_account(
+    program_id: &str,
+    bank: &str,
+    wallet: &str,
+    payer: &str,
+) -> Result<JsValue, JsError> {
+    to_js(instruction::initialize_associated_account(
+        &parse_pubkey("program id", program_id)?,
+        &parse_pubkey("bank", bank)?,
+        &parse_pubkey("wallet", wallet)?,
+        &parse_pubkey("payer", payer)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_views() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let ix = instruction::revoke(
+            &program_id,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &owner,
+        )
+        .unwrap();
+        let data = ix.data.clone();
+        let view = InstructionView::from(ix);
+        assert_eq!(view.program_id, program_id.to_string());
+        assert_eq!(
+            view.keys[1],
+            AccountMetaView {
+                pubkey: owner.to_string(),
+                is_signer: true,
+                is_writable: true,
+            }
+        );
+        assert_eq!(view.data, data);
+
+        let account = Account {
+            amount: u64::MAX,
+            is_opened: true,
+            is_initialized: true,
+            owner,
+            delegate: COption::None,
+            delegated_amount: 0,
+            bank: Pubkey::new_unique(),
+            permit_nonce: 0,
+            is_frozen: false,
+            last_accrual: 0,
+        };
+        let mut data = vec![0u8; Account::LEN];
+        Account::pack(account, &mut data).unwrap();
+        let view = account_view(&data).unwrap();
+        assert_eq!(view.amount, u64::MAX.to_string());
+        assert_eq!(view.owner, owner.to_string());
+        assert!(bank_view(&data).is_err());
+    }
+}
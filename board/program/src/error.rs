@@ -0,0 +1,17 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BoardError {
+    /// The post isn't the board's program address for its next index.
+    InvalidPost,
+    /// Posts must say something.
+    EmptyMessage,
+    /// The message is longer than `MAX_MESSAGE_LEN`.
+    MessageTooLong,
+}
+
+impl From<BoardError> for ProgramError {
+    fn from(e: BoardError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
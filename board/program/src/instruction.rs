@@ -0,0 +1,165 @@
+use crate::{error::BoardError, state::MAX_MESSAGE_LEN};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+
+/// Seed prefix of a post, followed by the board's address and the post's index.
+pub const POST_SEED: &[u8] = b"post";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BoardInstruction {
+    /// Opens an empty board.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The board, an uninitialized account owned by this program.
+    CreateBoard,
+
+    /// Posts `message` as the board's next post, in a new account the author pays the
+    /// rent of.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The author, paying for the post.
+    ///   1. `[writable]` The board.
+    ///   2. `[writable]` The post, the board's program address for its post count.
+    ///   3. `[]` The system program.
+    Post { message: String },
+}
+
+impl BoardInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => Self::CreateBoard,
+            1 => {
+                let (message, _rest) = Self::unpack_str(rest)?;
+                Self::Post { message }
+            }
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self {
+            Self::CreateBoard => buf.push(0),
+            Self::Post { message } => {
+                buf.push(1);
+                Self::pack_str(message, &mut buf);
+            }
+        }
+        buf
+    }
+
+    /// A string prefixed by its length in bytes, as a u8.
+    fn unpack_str(input: &[u8]) -> Result<(String, &[u8]), ProgramError> {
+        let (&len, rest) = input
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let bytes = rest
+            .get(..len as usize)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let value =
+            String::from_utf8(bytes.to_vec()).map_err(|_| ProgramError::InvalidInstructionData)?;
+        Ok((value, &rest[len as usize..]))
+    }
+
+    /// Strings over 255 bytes are cut short; no valid message is that long.
+    fn pack_str(value: &str, buf: &mut Vec<u8>) {
+        let bytes = &value.as_bytes()[..value.len().min(u8::MAX as usize)];
+        buf.push(bytes.len() as u8);
+        buf.extend_from_slice(bytes);
+    }
+}
+
+/// Address and bump of post `index` on `board`.
+pub fn post_address(board_program_id: &Pubkey, board: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[POST_SEED, board.as_ref(), &index.to_le_bytes()],
+        board_program_id,
+    )
+}
+
+pub fn create_board(
+    board_program_id: &Pubkey,
+    board: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = BoardInstruction::CreateBoard.pack();
+    let accounts = vec![AccountMeta::new(*board, false)];
+    Ok(Instruction {
+        program_id: *board_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Posts `message` as post `index`, which must be the board's current post count.
+pub fn post(
+    board_program_id: &Pubkey,
+    author: &Pubkey,
+    board: &Pubkey,
+    index: u64,
+    message: &str,
+) -> Result<Instruction, ProgramError> {
+    if message.len() > MAX_MESSAGE_LEN {
+        return Err(BoardError::MessageTooLong.into());
+    }
+    let data = BoardInstruction::Post {
+        message: message.to_string(),
+    }
+    .pack();
+    let (post, _) = post_address(board_program_id, board, index);
+    let accounts = vec![
+        AccountMeta::new(*author, true),
+        AccountMeta::new(*board, false),
+        AccountMeta::new(post, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *board_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            BoardInstruction::CreateBoard,
+            BoardInstruction::Post {
+                message: "gm".to_string(),
+            },
+            BoardInstruction::Post {
+                message: String::new(),
+            },
+        ];
+        for instruction in cases {
+            assert_eq!(
+                BoardInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        let mut truncated = BoardInstruction::Post {
+            message: "gm".to_string(),
+        }
+        .pack();
+        truncated.pop();
+        assert_eq!(
+            BoardInstruction::unpack(&truncated),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            BoardInstruction::unpack(&[2]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{
+    error::BoardError,
+    instruction::{BoardInstruction, POST_SEED},
+    state::{Board, Post, MAX_MESSAGE_LEN},
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = BoardInstruction::unpack(input)?;
+
+        match instruction {
+            BoardInstruction::CreateBoard => {
+                msg!("Instruction: CreateBoard");
+                Self::process_create_board(program_id, accounts)
+            }
+            BoardInstruction::Post { message } => {
+                msg!("Instruction: Post");
+                Self::process_post(program_id, accounts, message)
+            }
+        }
+    }
+
+    pub fn process_create_board(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let board_info = next_account_info(account_info_iter)?;
+
+        if board_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut board = Board::unpack_unchecked(&board_info.data.borrow())?;
+        if board.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        board.is_initialized = true;
+        Board::pack(board, &mut board_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_post(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        message: String,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let author_info = next_account_info(account_info_iter)?;
+        let board_info = next_account_info(account_info_iter)?;
+        let post_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !author_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if board_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut board = Board::unpack(&board_info.data.borrow())?;
+        if message.is_empty() {
+            return Err(BoardError::EmptyMessage.into());
+        }
+        if message.len() > MAX_MESSAGE_LEN {
+            return Err(BoardError::MessageTooLong.into());
+        }
+        // Posts fill the indexes in order, so two authors racing for the same index
+        // can't both land; the loser retries with the next one.
+        let index = board.post_count;
+        let index_bytes = index.to_le_bytes();
+        let (address, bump) = Pubkey::find_program_address(
+            &[POST_SEED, board_info.key.as_ref(), &index_bytes],
+            program_id,
+        );
+        if address != *post_info.key {
+            return Err(BoardError::InvalidPost.into());
+        }
+
+        invoke_signed(
+            &system_instruction::create_account(
+                author_info.key,
+                post_info.key,
+                Rent::get()?.minimum_balance(Post::LEN),
+                Post::LEN as u64,
+                program_id,
+            ),
+            &[
+                author_info.clone(),
+                post_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[&[POST_SEED, board_info.key.as_ref(), &index_bytes, &[bump]]],
+        )?;
+
+        let post = Post {
+            is_initialized: true,
+            board: *board_info.key,
+            author: *author_info.key,
+            index,
+            posted_ts: Clock::get()?.unix_timestamp,
+            message,
+        };
+        Post::pack(post, &mut post_info.data.borrow_mut())?;
+
+        board.post_count += 1;
+        Board::pack(board, &mut board_info.data.borrow_mut())?;
+        Ok(())
+    }
+}
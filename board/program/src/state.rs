@@ -0,0 +1,148 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Longest message, in bytes.
+pub const MAX_MESSAGE_LEN: usize = 200;
+/// Where a post's board starts, for filtering a board's posts by memcmp.
+pub const POST_BOARD_OFFSET: usize = 1;
+
+/// The index of a board's posts. Posts are numbered from zero in the order they were
+/// made, each at a program address derived from the board and its index, so any page of
+/// them can be fetched by address knowing only `post_count`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Board {
+    pub is_initialized: bool,
+    pub post_count: u64,
+}
+
+impl Sealed for Board {}
+impl IsInitialized for Board {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Board {
+    const LEN: usize = 9;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 9];
+        let (is_initialized, post_count) = array_refs![src, 1, 8];
+        Ok(Board {
+            is_initialized: unpack_bool(is_initialized)?,
+            post_count: u64::from_le_bytes(*post_count),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 9];
+        let (is_initialized, post_count) = mut_array_refs![dst, 1, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        *post_count = self.post_count.to_le_bytes();
+    }
+}
+
+/// Post number `index` on `board`, paid for by `author`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Post {
+    pub is_initialized: bool,
+    pub board: Pubkey,
+    pub author: Pubkey,
+    pub index: u64,
+    pub posted_ts: i64,
+    pub message: String,
+}
+
+impl Sealed for Post {}
+impl IsInitialized for Post {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Post {
+    const LEN: usize = 282;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 282];
+        let (is_initialized, board, author, index, posted_ts, message_len, message) =
+            array_refs![src, 1, 32, 32, 8, 8, 1, MAX_MESSAGE_LEN];
+        Ok(Post {
+            is_initialized: unpack_bool(is_initialized)?,
+            board: Pubkey::new_from_array(*board),
+            author: Pubkey::new_from_array(*author),
+            index: u64::from_le_bytes(*index),
+            posted_ts: i64::from_le_bytes(*posted_ts),
+            message: unpack_str(message_len, message)?,
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 282];
+        let (is_initialized, board, author, index, posted_ts, message_len, message) =
+            mut_array_refs![dst, 1, 32, 32, 8, 8, 1, MAX_MESSAGE_LEN];
+        is_initialized[0] = self.is_initialized as u8;
+        board.copy_from_slice(self.board.as_ref());
+        author.copy_from_slice(self.author.as_ref());
+        *index = self.index.to_le_bytes();
+        *posted_ts = self.posted_ts.to_le_bytes();
+        pack_str(&self.message, message_len, message);
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+fn unpack_str(len: &[u8; 1], src: &[u8]) -> Result<String, ProgramError> {
+    let bytes = src
+        .get(..len[0] as usize)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| ProgramError::InvalidAccountData)
+}
+
+/// Writes `value` zero-padded into `dst`, which the processor has checked it fits.
+fn pack_str(value: &str, len: &mut [u8; 1], dst: &mut [u8]) {
+    len[0] = value.len() as u8;
+    dst.fill(0);
+    dst[..value.len()].copy_from_slice(value.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let board = Board {
+            is_initialized: true,
+            post_count: 42,
+        };
+        let mut packed = vec![0xff; Board::LEN];
+        Board::pack(board.clone(), &mut packed).unwrap();
+        assert_eq!(Board::unpack(&packed), Ok(board));
+
+        let post = Post {
+            is_initialized: true,
+            board: Pubkey::new_unique(),
+            author: Pubkey::new_unique(),
+            index: 41,
+            posted_ts: 1_700_000_000,
+            message: "m".repeat(MAX_MESSAGE_LEN),
+        };
+        let mut packed = vec![0xff; Post::LEN];
+        Post::pack(post.clone(), &mut packed).unwrap();
+        assert_eq!(Post::unpack(&packed), Ok(post.clone()));
+        assert_eq!(
+            &packed[POST_BOARD_OFFSET..POST_BOARD_OFFSET + 32],
+            post.board.as_ref()
+        );
+
+        packed[81] = MAX_MESSAGE_LEN as u8 + 1;
+        assert_eq!(Post::unpack(&packed), Err(ProgramError::InvalidAccountData));
+    }
+}
@@ -0,0 +1,198 @@
+use solana_board::{
+    error::BoardError,
+    instruction::{create_board, post, post_address, BoardInstruction},
+    processor::Processor,
+    state::{Board, Post, MAX_MESSAGE_LEN},
+};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+/// A fresh board, with alice able to pay for posts.
+struct Fixture {
+    context: ProgramTestContext,
+    board_program_id: Pubkey,
+    board: Pubkey,
+    alice: Keypair,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let board_program_id = Pubkey::new_unique();
+        let program_test = ProgramTest::new(
+            "solana_board",
+            board_program_id,
+            processor!(Processor::process),
+        );
+        let mut fixture = Fixture {
+            context: program_test.start_with_context().await,
+            board_program_id,
+            board: Pubkey::default(),
+            alice: Keypair::new(),
+        };
+
+        let board = Keypair::new();
+        let rent = fixture.context.banks_client.get_rent().await.unwrap();
+        let instructions = [
+            system_instruction::create_account(
+                &fixture.context.payer.pubkey(),
+                &board.pubkey(),
+                rent.minimum_balance(Board::LEN),
+                Board::LEN as u64,
+                &board_program_id,
+            ),
+            create_board(&board_program_id, &board.pubkey()).unwrap(),
+            system_instruction::transfer(
+                &fixture.context.payer.pubkey(),
+                &fixture.alice.pubkey(),
+                100_000_000,
+            ),
+        ];
+        fixture.process(&instructions, &[&board]).await.unwrap();
+        fixture.board = board.pubkey();
+        fixture
+    }
+
+    async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), TransactionError> {
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            self.context.last_blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .map_err(|e| e.unwrap())
+    }
+
+    /// Posts `message` as alice at `index`.
+    async fn post(&mut self, index: u64, message: &str) -> Result<(), TransactionError> {
+        let instruction = post(
+            &self.board_program_id,
+            &self.alice.pubkey(),
+            &self.board,
+            index,
+            message,
+        )
+        .unwrap();
+        let alice = Keypair::from_bytes(&self.alice.to_bytes()).unwrap();
+        self.process(&[instruction], &[&alice]).await
+    }
+
+    async fn data(&mut self, account: &Pubkey) -> Vec<u8> {
+        self.context
+            .banks_client
+            .get_account(*account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+    }
+
+    async fn post_count(&mut self) -> u64 {
+        let board = self.board;
+        Board::unpack(&self.data(&board).await).unwrap().post_count
+    }
+
+    async fn read(&mut self, index: u64) -> Post {
+        let (address, _) = post_address(&self.board_program_id, &self.board, index);
+        Post::unpack(&self.data(&address).await).unwrap()
+    }
+}
+
+fn custom(error: BoardError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_post() {
+    let mut fixture = Fixture::new().await;
+    assert_eq!(fixture.post_count().await, 0);
+    let lamports = fixture
+        .context
+        .banks_client
+        .get_balance(fixture.alice.pubkey())
+        .await
+        .unwrap();
+
+    fixture.post(0, "first").await.unwrap();
+    fixture.post(1, "second").await.unwrap();
+    assert_eq!(fixture.post_count().await, 2);
+    let post = fixture.read(1).await;
+    assert_eq!(post.board, fixture.board);
+    assert_eq!(post.author, fixture.alice.pubkey());
+    assert_eq!((post.index, post.message.as_str()), (1, "second"));
+    assert_eq!(fixture.read(0).await.message, "first");
+
+    // Alice pays the rent of both posts.
+    let rent = fixture.context.banks_client.get_rent().await.unwrap();
+    assert_eq!(
+        fixture
+            .context
+            .banks_client
+            .get_balance(fixture.alice.pubkey())
+            .await
+            .unwrap(),
+        lamports - 2 * rent.minimum_balance(Post::LEN)
+    );
+}
+
+#[tokio::test]
+async fn test_post_rejects_stale_index() {
+    let mut fixture = Fixture::new().await;
+    fixture.post(0, "first").await.unwrap();
+    assert_eq!(
+        fixture.post(0, "also first").await,
+        custom(BoardError::InvalidPost)
+    );
+    assert_eq!(
+        fixture.post(2, "third").await,
+        custom(BoardError::InvalidPost)
+    );
+    assert_eq!(fixture.post_count().await, 1);
+}
+
+#[tokio::test]
+async fn test_post_rejects_bad_messages() {
+    let mut fixture = Fixture::new().await;
+    assert_eq!(fixture.post(0, "").await, custom(BoardError::EmptyMessage));
+
+    // The builder refuses long messages, so pack one by hand.
+    let mut instruction = post(
+        &fixture.board_program_id,
+        &fixture.alice.pubkey(),
+        &fixture.board,
+        0,
+        "",
+    )
+    .unwrap();
+    instruction.data = BoardInstruction::Post {
+        message: "m".repeat(MAX_MESSAGE_LEN + 1),
+    }
+    .pack();
+    let alice = Keypair::from_bytes(&fixture.alice.to_bytes()).unwrap();
+    assert_eq!(
+        fixture.process(&[instruction], &[&alice]).await,
+        custom(BoardError::MessageTooLong)
+    );
+    assert_eq!(fixture.post_count().await, 0);
+}
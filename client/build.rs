@@ -0,0 +1,53 @@
+//! Renders the man pages of `bank-cli` from its command line: `bank-cli.1` for the top
+//! level and `bank-cli-<command>[-<subcommand>].1` for every command under it, into
+//! `target/<profile>/man`. With the `grpc` feature it also generates the code of
+//! `proto/bank.proto` for `client::grpc`.
+
+use clap::IntoApp;
+use clap_mangen::Man;
+use std::{env, fs, io, path::Path};
+
+#[allow(dead_code)]
+mod cli {
+    include!("src/bin/bank_cli/cli.rs");
+}
+
+fn render(command: clap::Command, name: &str, dir: &Path) -> io::Result<()> {
+    let command = command.name(name.to_string());
+    let mut page = Vec::new();
+    Man::new(command.clone()).render(&mut page)?;
+    fs::write(dir.join(format!("{}.1", name)), page)?;
+    for subcommand in command.get_subcommands() {
+        let sub_name = format!("{}-{}", name, subcommand.get_name());
+        render(subcommand.clone(), &sub_name, dir)?;
+    }
+    Ok(())
+}
+
+/// Uses the `protoc` of protoc-bin-vendored unless `PROTOC` names one. Only the server
+/// is generated; the client code needs the 2021 prelude.
+#[cfg(feature = "grpc")]
+fn compile_protos() -> io::Result<()> {
+    println!("cargo:rerun-if-changed=proto/bank.proto");
+    if env::var_os("PROTOC").is_none() {
+        let protoc = protoc_bin_vendored::protoc_bin_path()
+            .map_err(|e| io::Error::new(io::ErrorKind::NotFound, e.to_string()))?;
+        env::set_var("PROTOC", protoc);
+    }
+    tonic_build::configure()
+        .build_client(false)
+        .compile(&["proto/bank.proto"], &["proto"])
+}
+
+fn main() -> io::Result<()> {
+    #[cfg(feature = "grpc")]
+    compile_protos()?;
+
+    println!("cargo:rerun-if-changed=src/bin/bank_cli/cli.rs");
+
+    // OUT_DIR is target/<profile>/build/client-<hash>/out.
+    let out_dir = env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo");
+    let dir = Path::new(&out_dir).ancestors().nth(3).unwrap().join("man");
+    fs::create_dir_all(&dir)?;
+    render(cli::Cli::command(), "bank-cli", &dir)
+}
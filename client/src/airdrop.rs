@@ -0,0 +1,116 @@
+use solana_distributor::merkle::{self, MerkleTree};
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::HashSet, str::FromStr};
+
+/// An airdrop read from a CSV of `recipient,amount` lines, with the merkle tree the
+/// distributor program checks claims against. A recipient's index is its line's position
+/// among the allocations, so the same file always gives the same tree.
+pub struct Airdrop {
+    pub allocations: Vec<(Pubkey, u64)>,
+    tree: MerkleTree,
+}
+
+/// One recipient's allocation, with what the claim instruction needs to prove it.
+#[derive(Debug, PartialEq)]
+pub struct Claim {
+    pub index: u64,
+    pub amount: u64,
+    pub proof: Vec<[u8; 32]>,
+}
+
+impl Airdrop {
+    /// Blank lines, `#` comments and a `recipient,amount` header are skipped.
+    pub fn from_csv(csv: &str) -> Result<Airdrop, String> {
+        let mut allocations = Vec::new();
+        let mut seen = HashSet::new();
+        for (number, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line == "recipient,amount" {
+                continue;
+            }
+            let (recipient, amount) = line
+                .split_once(',')
+                .ok_or_else(|| format!("line {}: expected recipient,amount", number + 1))?;
+            let recipient = Pubkey::from_str(recipient.trim())
+                .map_err(|e| format!("line {}: bad recipient: {}", number + 1, e))?;
+            let amount = amount
+                .trim()
+                .parse::<u64>()
+                .map_err(|e| format!("line {}: bad amount: {}", number + 1, e))?;
+            if !seen.insert(recipient) {
+                return Err(format!(
+                    "line {}: {} is listed twice",
+                    number + 1,
+                    recipient
+                ));
+            }
+            allocations.push((recipient, amount));
+        }
+        if allocations.is_empty() {
+            return Err("no allocations in the file".to_string());
+        }
+        let tree = MerkleTree::new(
+            allocations
+                .iter()
+                .enumerate()
+                .map(|(index, (recipient, amount))| merkle::leaf(index as u64, recipient, *amount))
+                .collect(),
+        );
+        Ok(Airdrop { allocations, tree })
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.tree.root()
+    }
+
+    /// Sum of every allocation. None when it doesn't fit a u64.
+    pub fn total(&self) -> Option<u64> {
+        self.allocations
+            .iter()
+            .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+    }
+
+    /// The allocation of `recipient` and its proof. None when it isn't in the airdrop.
+    pub fn claim(&self, recipient: &Pubkey) -> Option<Claim> {
+        let index = self
+            .allocations
+            .iter()
+            .position(|(other, _)| other == recipient)?;
+        Some(Claim {
+            index: index as u64,
+            amount: self.allocations[index].1,
+            proof: self.tree.proof(index)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_csv() {
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let csv = format!(
+            "recipient,amount\n# early testers\n{}, 100\n\n{},250\n",
+            alice, bob
+        );
+        let airdrop = Airdrop::from_csv(&csv).unwrap();
+        assert_eq!(airdrop.allocations, vec![(alice, 100), (bob, 250)]);
+        assert_eq!(airdrop.total(), Some(350));
+
+        let claim = airdrop.claim(&bob).unwrap();
+        assert_eq!((claim.index, claim.amount), (1, 250));
+        assert!(merkle::verify(
+            &claim.proof,
+            &airdrop.root(),
+            merkle::leaf(1, &bob, 250)
+        ));
+        assert_eq!(airdrop.claim(&Pubkey::new_unique()), None);
+
+        assert!(Airdrop::from_csv(&format!("{}\n", alice)).is_err());
+        assert!(Airdrop::from_csv(&format!("{},-1\n", alice)).is_err());
+        assert!(Airdrop::from_csv(&format!("{},1\n{},2\n", alice, alice)).is_err());
+        assert!(Airdrop::from_csv("recipient,amount\n").is_err());
+    }
+}
@@ -0,0 +1,568 @@
+use crate::{
+    blockhash::BlockhashCache,
+    util::{self, SendMode},
+};
+use solana_bank::{
+    associated_account,
+    history::{self, TransferRecord},
+    instruction::{self, BankInstruction},
+    registry,
+    state::{Account, Bank, Version},
+    version,
+};
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::RpcProgramAccountsConfig,
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_program::program_pack::Pack;
+use solana_sdk::{
+    instruction::Instruction,
+    message::Message,
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::{unique_signers, Signer},
+    system_instruction,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{error, warn};
+
+/// Offset of `Account::owner` in the packed account layout.
+const ACCOUNT_OWNER_OFFSET: usize = 10;
+/// Offset of `Account::bank` in the packed account layout.
+const ACCOUNT_BANK_OFFSET: usize = 86;
+
+pub struct BankClient {
+    pub rpc: RpcClient,
+    pub program_id: Pubkey,
+    pub blockhash_cache: BlockhashCache,
+    /// Set once the program's version record passed `check_version`, so that only the
+    /// first send reads it.
+    version_checked: AtomicBool,
+}
+
+impl BankClient {
+    pub fn new(rpc: RpcClient, program_id: Pubkey) -> BankClient {
+        BankClient {
+            rpc,
+            program_id,
+            blockhash_cache: BlockhashCache::default(),
+            version_checked: AtomicBool::new(false),
+        }
+    }
+
+    pub fn with_blockhash_cache(mut self, blockhash_cache: BlockhashCache) -> BankClient {
+        self.blockhash_cache = blockhash_cache;
+        self
+    }
+
+    /// Sends `instructions` in one transaction; the first signer pays the fee. The first
+    /// send fails without sending anything if the program is incompatible with this
+    /// client, see `check_version`.
+    pub fn send(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&dyn Signer],
+        mode: SendMode,
+    ) -> Result<Option<Signature>, String> {
+        if !self.version_checked.load(Ordering::Relaxed) {
+            if let Some(version) = self.get_version()? {
+                check_version(&version)?;
+            }
+            self.version_checked.store(true, Ordering::Relaxed);
+        }
+        let payer = signers[0].pubkey();
+        let msg = Message::new(instructions, Some(&payer));
+        util::sign_and_submit(
+            &self.rpc,
+            &self.blockhash_cache,
+            msg,
+            signers,
+            mode,
+            Some(decode_instruction),
+        )
+    }
+
+    /// The system instruction creating `account`, `space` bytes long and owned by the
+    /// program, rent-exempt at `payer`'s expense.
+    pub fn create_account_instruction(
+        &self,
+        payer: &Pubkey,
+        account: &Pubkey,
+        space: usize,
+    ) -> Result<Instruction, String> {
+        let rent = match self.rpc.get_minimum_balance_for_rent_exemption(space) {
+            Ok(r) => r,
+            Err(e) => {
+                error!(error = %e, "get rent exemption failed");
+                return Err("get rent exemption failed".to_string());
+            }
+        };
+        Ok(system_instruction::create_account(
+            payer,
+            account,
+            rent,
+            space as u64,
+            &self.program_id,
+        ))
+    }
+
+    /// Creates the bank at `bank`'s address and opens it with `decimals` for
+    /// `bank_owner`, in one transaction `payer` pays for.
+    pub fn create_and_initialize_bank(
+        &self,
+        payer: &dyn Signer,
+        bank: &dyn Signer,
+        bank_owner: &dyn Signer,
+        decimals: u8,
+        mode: SendMode,
+    ) -> Result<Option<Signature>, String> {
+        let instructions = [
+            self.create_account_instruction(&payer.pubkey(), &bank.pubkey(), Bank::LEN)?,
+            instruction::initialize_bank(
+                &self.program_id,
+                &bank.pubkey(),
+                &bank_owner.pubkey(),
+                decimals,
+            )
+            .map_err(|e| e.to_string())?,
+        ];
+        self.send(
+            &instructions,
+            &unique_signers(vec![payer, bank, bank_owner]),
+            mode,
+        )
+    }
+
+    /// Creates the account at `account`'s address and opens it in `bank` for `owner`, in
+    /// one transaction `payer` pays for.
+    pub fn create_bank_account(
+        &self,
+        payer: &dyn Signer,
+        bank: &Pubkey,
+        account: &dyn Signer,
+        owner: &dyn Signer,
+        mode: SendMode,
+    ) -> Result<Option<Signature>, String> {
+        let instructions = [
+            self.create_account_instruction(&payer.pubkey(), &account.pubkey(), Account::LEN)?,
+            instruction::initialize_account(
+                &self.program_id,
+                bank,
+                &account.pubkey(),
+                &owner.pubkey(),
+            )
+            .map_err(|e| e.to_string())?,
+        ];
+        self.send(
+            &instructions,
+            &unique_signers(vec![payer, account, owner]),
+            mode,
+        )
+    }
+
+    /// Mints `amount` into `account`, signed by its bank's owner, who pays.
+    pub fn mint_to(
+        &self,
+        bank_owner: &dyn Signer,
+        account: &Pubkey,
+        amount: u64,
+        mode: SendMode,
+    ) -> Result<Option<Signature>, String> {
+        let bank = self.get_account(account)?.bank;
+        let instruction = instruction::mint_to(
+            &self.program_id,
+            &bank,
+            account,
+            &bank_owner.pubkey(),
+            amount,
+        )
+        .map_err(|e| e.to_string())?;
+        self.send(&[instruction], &[bank_owner], mode)
+    }
+
+    /// Moves `amount` from `from` to `to`, signed by `from`'s owner or delegate, who pays.
+    pub fn transfer(
+        &self,
+        authority: &dyn Signer,
+        from: &Pubkey,
+        to: &Pubkey,
+        amount: u64,
+        mode: SendMode,
+    ) -> Result<Option<Signature>, String> {
+        let bank = self.get_account(from)?.bank;
+        let instruction = instruction::transfer(
+            &self.program_id,
+            &bank,
+            from,
+            to,
+            &authority.pubkey(),
+            amount,
+        )
+        .map_err(|e| e.to_string())?;
+        self.send(&[instruction], &[authority], mode)
+    }
+
+    /// The program's version record, or `None` if it never ran `InitializeVersion`.
+    pub fn get_version(&self) -> Result<Option<Version>, String> {
+        let (address, _) = version::address(&self.program_id);
+        let account = match self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+        {
+            Ok(response) => response.value,
+            Err(e) => {
+                error!(error = %e, %address, "get version record failed");
+                return Err("get version record failed".to_string());
+            }
+        };
+        let account = match account {
+            Some(account) if account.owner == self.program_id => account,
+            _ => {
+                warn!(program_id = %self.program_id, "program has no version record");
+                return Ok(None);
+            }
+        };
+        match Version::unpack(&account.data) {
+            Ok(v) => Ok(Some(v)),
+            Err(e) => {
+                error!(error = %e, %address, "unpack version record failed");
+                Err("unpack version record failed".to_string())
+            }
+        }
+    }
+
+    pub fn get_bank(&self, bank: &Pubkey) -> Result<Bank, String> {
+        let data = self.get_program_account_data(bank)?;
+        match Bank::unpack(&data) {
+            Ok(b) => Ok(b),
+            Err(e) => {
+                error!(error = %e, %bank, "unpack bank failed");
+                Err("unpack bank failed".to_string())
+            }
+        }
+    }
+
+    pub fn get_account(&self, account: &Pubkey) -> Result<Account, String> {
+        let data = self.get_program_account_data(account)?;
+        match Account::unpack_extended(&data) {
+            Ok(a) => Ok(a),
+            Err(e) => {
+                error!(error = %e, %account, "unpack account failed");
+                Err("unpack account failed".to_string())
+            }
+        }
+    }
+
+    /// `wallet`'s associated account in `bank` and its address, see `associated_account`.
+    pub fn get_associated_account(
+        &self,
+        wallet: &Pubkey,
+        bank: &Pubkey,
+    ) -> Result<(Pubkey, Account), String> {
+        let address =
+            associated_account::get_associated_bank_account_address(wallet, bank, &self.program_id);
+        Ok((address, self.get_account(&address)?))
+    }
+
+    /// The latest transfers of `account`, newest first; empty unless it was created
+    /// with room for a transfer history.
+    pub fn get_transfer_history(&self, account: &Pubkey) -> Result<Vec<TransferRecord>, String> {
+        let data = self.get_program_account_data(account)?;
+        Ok(history::records(&data))
+    }
+
+    /// All initialized accounts belonging to `bank`, found through getProgramAccounts.
+    pub fn get_bank_accounts(&self, bank: &Pubkey) -> Result<Vec<(Pubkey, Account)>, String> {
+        self.get_accounts_by(ACCOUNT_BANK_OFFSET, bank)
+    }
+
+    /// The accounts registered in `bank`'s registry, in the order they were opened, read
+    /// page by page for RPC providers that disable getProgramAccounts. Accounts opened
+    /// without registering are missing, and so are closed ones.
+    pub fn get_registered_accounts(&self, bank: &Pubkey) -> Result<Vec<(Pubkey, Account)>, String> {
+        let bank_state = self.get_bank(bank)?;
+        let mut keys = Vec::new();
+        for page in 0..registry::page_count(&bank_state) {
+            let (address, _) = registry::page_address(&self.program_id, bank, page);
+            let data = self.get_program_account_data(&address)?;
+            keys.extend(registry::entries(&bank_state, page, &data));
+        }
+
+        let mut accounts = Vec::with_capacity(keys.len());
+        // getMultipleAccounts takes at most 100 keys.
+        for chunk in keys.chunks(100) {
+            let fetched = match self.rpc.get_multiple_accounts(chunk) {
+                Ok(a) => a,
+                Err(e) => {
+                    error!(error = %e, "get multiple accounts failed");
+                    return Err("get multiple accounts failed".to_string());
+                }
+            };
+            for (key, account) in chunk.iter().zip(fetched) {
+                if let Some(account) = account.filter(|a| a.owner == self.program_id) {
+                    if let Ok(bank_account) = Account::unpack_extended(&account.data) {
+                        accounts.push((*key, bank_account));
+                    }
+                }
+            }
+        }
+        Ok(accounts)
+    }
+
+    /// All initialized accounts owned by `owner`, across every bank of the program.
+    pub fn get_owner_accounts(&self, owner: &Pubkey) -> Result<Vec<(Pubkey, Account)>, String> {
+        self.get_accounts_by(ACCOUNT_OWNER_OFFSET, owner)
+    }
+
+    /// `get_owner_accounts`, along with the lamports each account holds.
+    pub fn get_owner_accounts_with_lamports(
+        &self,
+        owner: &Pubkey,
+    ) -> Result<Vec<(Pubkey, Account, u64)>, String> {
+        Ok(self
+            .get_program_accounts_by(ACCOUNT_OWNER_OFFSET, owner)?
+            .into_iter()
+            .filter_map(|(key, account)| {
+                Account::unpack(&account.data)
+                    .ok()
+                    .map(|bank_account| (key, bank_account, account.lamports))
+            })
+            .collect())
+    }
+
+    pub fn audit(&self, bank: &Pubkey) -> Result<AuditReport, String> {
+        let bank_state = self.get_bank(bank)?;
+        let accounts = self.get_bank_accounts(bank)?;
+        Ok(audit_accounts(&bank_state, &accounts))
+    }
+
+    fn get_accounts_by(
+        &self,
+        offset: usize,
+        key: &Pubkey,
+    ) -> Result<Vec<(Pubkey, Account)>, String> {
+        Ok(self
+            .get_program_accounts_by(offset, key)?
+            .into_iter()
+            .filter_map(|(key, account)| {
+                Account::unpack(&account.data)
+                    .ok()
+                    .map(|bank_account| (key, bank_account))
+            })
+            .collect())
+    }
+
+    /// The program's accounts of `Account::LEN` bytes with `key` at `offset`.
+    fn get_program_accounts_by(
+        &self,
+        offset: usize,
+        key: &Pubkey,
+    ) -> Result<Vec<(Pubkey, solana_sdk::account::Account)>, String> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::DataSize(Account::LEN as u64),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(offset, key.to_bytes().to_vec())),
+            ]),
+            ..RpcProgramAccountsConfig::default()
+        };
+        let accounts = match self
+            .rpc
+            .get_program_accounts_with_config(&self.program_id, config)
+        {
+            Ok(a) => a,
+            Err(e) => {
+                error!(error = %e, "get program accounts failed");
+                return Err("get program accounts failed".to_string());
+            }
+        };
+        Ok(accounts)
+    }
+
+    fn get_program_account_data(&self, key: &Pubkey) -> Result<Vec<u8>, String> {
+        let account = match self.rpc.get_account(key) {
+            Ok(a) => a,
+            Err(e) => {
+                error!(error = %e, %key, "get account failed");
+                return Err("get account failed".to_string());
+            }
+        };
+        if account.owner != self.program_id {
+            return Err("account is not owned by the bank program".to_string());
+        }
+        Ok(account.data)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct AuditReport {
+    pub total_supply: u64,
+    /// Σ amount over every account of the bank; allowances hold no tokens of their own.
+    pub accounted_supply: u128,
+    pub account_count: usize,
+    /// Accounts whose own state is inconsistent, with the reason.
+    pub offending_accounts: Vec<(Pubkey, String)>,
+}
+
+impl AuditReport {
+    pub fn is_consistent(&self) -> bool {
+        self.total_supply as u128 == self.accounted_supply && self.offending_accounts.is_empty()
+    }
+}
+
+pub fn audit_accounts(bank: &Bank, accounts: &[(Pubkey, Account)]) -> AuditReport {
+    let mut accounted_supply: u128 = 0;
+    let mut offending_accounts = Vec::new();
+    for (key, account) in accounts.iter() {
+        accounted_supply += account.amount as u128;
+
+        if account.delegate.is_none() && account.delegated_amount > 0 {
+            offending_accounts.push((*key, "delegated amount without a delegate".to_string()));
+        }
+        if !account.is_opened && (account.amount > 0 || account.delegated_amount > 0) {
+            offending_accounts.push((*key, "closed account still holds tokens".to_string()));
+        }
+    }
+
+    AuditReport {
+        total_supply: bank.total_supply,
+        accounted_supply,
+        account_count: accounts.len(),
+        offending_accounts,
+    }
+}
+
+/// Fails unless the program whose version record holds `version` is one this client
+/// can talk to: the same major release, writing a state layout the client reads.
+pub fn check_version(version: &Version) -> Result<(), String> {
+    if version::is_compatible(version) {
+        return Ok(());
+    }
+    Err(format!(
+        "program {}.{}.{} (layout {}) is incompatible with this client's {}.{}.{} (layout {})",
+        version.major,
+        version.minor,
+        version.patch,
+        version.layout,
+        version::MAJOR,
+        version::MINOR,
+        version::PATCH,
+        version::LAYOUT,
+    ))
+}
+
+pub fn decode_instruction(data: &[u8]) -> Option<String> {
+    BankInstruction::unpack(data)
+        .ok()
+        .map(|i| format!("{:?}", i))
+}
+
+/// Formats a raw token amount using the bank's decimals, e.g. 1050 with 2 decimals is "10.50".
+pub fn ui_amount(amount: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+    let raw = format!("{:0>width$}", amount, width = decimals as usize + 1);
+    let (whole, fraction) = raw.split_at(raw.len() - decimals as usize);
+    format!("{}.{}", whole, fraction)
+}
+
+/// The inverse of `ui_amount`: base units of a whole-token amount with at most
+/// `decimals` fractional digits.
+pub fn parse_ui_amount(amount: &str, decimals: u8) -> Result<u64, String> {
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if whole.is_empty() || !digits(whole) || !digits(fraction) || amount.ends_with('.') {
+        return Err(format!("invalid amount {}", amount));
+    }
+    if fraction.len() > decimals as usize {
+        return Err(format!("{} has more than {} decimals", amount, decimals));
+    }
+    format!("{}{:0<width$}", whole, fraction, width = decimals as usize)
+        .parse()
+        .map_err(|_| format!("{} is too large", amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::program_option::COption;
+
+    fn account(amount: u64, delegated_amount: u64, is_opened: bool) -> Account {
+        Account {
+            amount,
+            is_opened,
+            is_initialized: true,
+            delegate: if delegated_amount > 0 {
+                COption::Some(Pubkey::new_unique())
+            } else {
+                COption::None
+            },
+            delegated_amount,
+            ..Account::default()
+        }
+    }
+
+    #[test]
+    fn test_audit_accounts() {
+        let bank = Bank {
+            total_supply: 150,
+            is_opened: true,
+            ..Bank::default()
+        };
+        let mut accounts = vec![
+            (Pubkey::new_unique(), account(100, 0, true)),
+            (Pubkey::new_unique(), account(50, 30, true)),
+        ];
+        let report = audit_accounts(&bank, &accounts);
+        assert!(report.is_consistent());
+        assert_eq!(report.accounted_supply, 150);
+
+        let closed = Pubkey::new_unique();
+        accounts.push((closed, account(5, 0, false)));
+        let report = audit_accounts(&bank, &accounts);
+        assert!(!report.is_consistent());
+        assert_eq!(report.accounted_supply, 155);
+        assert_eq!(report.offending_accounts.len(), 1);
+        assert_eq!(report.offending_accounts[0].0, closed);
+    }
+
+    #[test]
+    fn test_check_version() {
+        assert_eq!(check_version(&version::current()), Ok(()));
+        let newer_minor = Version {
+            minor: version::MINOR + 1,
+            ..version::current()
+        };
+        assert_eq!(check_version(&newer_minor), Ok(()));
+        let newer_layout = Version {
+            layout: version::LAYOUT + 1,
+            ..version::current()
+        };
+        assert!(check_version(&newer_layout).is_err());
+        let newer_major = Version {
+            major: version::MAJOR + 1,
+            ..version::current()
+        };
+        assert!(check_version(&newer_major).is_err());
+    }
+
+    #[test]
+    fn test_ui_amount() {
+        assert_eq!(ui_amount(1050, 2), "10.50");
+        assert_eq!(ui_amount(5, 3), "0.005");
+        assert_eq!(ui_amount(42, 0), "42");
+    }
+
+    #[test]
+    fn test_parse_ui_amount() {
+        assert_eq!(parse_ui_amount("10.50", 2), Ok(1050));
+        assert_eq!(parse_ui_amount("10.5", 2), Ok(1050));
+        assert_eq!(parse_ui_amount("0.005", 3), Ok(5));
+        assert_eq!(parse_ui_amount("42", 0), Ok(42));
+        assert!(parse_ui_amount("0.005", 2).is_err());
+        assert!(parse_ui_amount("1.", 2).is_err());
+        assert!(parse_ui_amount(".5", 2).is_err());
+        assert!(parse_ui_amount("18446744073709551616", 0).is_err());
+    }
+}
@@ -0,0 +1,579 @@
+//! Load for measuring the program against a local validator: a fresh bank with a ring
+//! of funded accounts, and a loop that sends operations around the ring at a fixed rate
+//! while polling for their confirmations. `bank-cli bench` sends transfers only;
+//! `bank-cli loadgen` sends a `Mix` of them.
+
+use crate::{bank::BankClient, blockhash::BlockhashCache, rpc::RpcApi, util};
+use solana_bank::{instruction, state::Account};
+use solana_program::program_pack::Pack;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    message::Message,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+    thread::sleep,
+    time::{Duration, Instant},
+};
+use tracing::{debug, error, info, warn};
+
+/// getSignatureStatuses accepts at most this many signatures per call.
+const MAX_STATUSES_PER_CALL: usize = 256;
+/// Tokens minted to every account of the ring; each transfer moves one.
+const INITIAL_BALANCE: u64 = 1_000_000_000;
+
+pub struct BenchConfig {
+    /// Transfers sent per second.
+    pub tps: u32,
+    /// How long transfers are sent for; confirmations are awaited after that.
+    pub duration: Duration,
+    pub poll_interval: Duration,
+    pub commitment: CommitmentConfig,
+}
+
+impl Default for BenchConfig {
+    fn default() -> BenchConfig {
+        BenchConfig {
+            tps: 50,
+            duration: Duration::from_secs(30),
+            poll_interval: Duration::from_millis(250),
+            commitment: CommitmentConfig::confirmed(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BenchReport {
+    pub sent: usize,
+    pub confirmed: usize,
+    /// From the first send until the last transaction confirmed, failed or expired.
+    pub elapsed: Duration,
+    /// Send-to-confirmation time of every confirmed transaction.
+    pub latencies: Vec<Duration>,
+    /// The same, by the operation each transaction carried.
+    pub latencies_by_operation: BTreeMap<&'static str, Vec<Duration>>,
+    /// Number of transactions that didn't confirm, by reason.
+    pub failures: BTreeMap<String, usize>,
+}
+
+impl BenchReport {
+    pub fn confirmed_tps(&self) -> f64 {
+        if self.elapsed.as_secs_f64() == 0.0 {
+            return 0.0;
+        }
+        self.confirmed as f64 / self.elapsed.as_secs_f64()
+    }
+
+    pub fn median_latency(&self) -> Option<Duration> {
+        percentile(&self.latencies, 50.0)
+    }
+
+    fn fail(&mut self, reason: String) {
+        *self.failures.entry(reason).or_insert(0) += 1;
+    }
+}
+
+/// The nearest-rank `p`th percentile of `latencies`, `p` in (0, 100].
+pub fn percentile(latencies: &[Duration], p: f64) -> Option<Duration> {
+    let mut latencies = latencies.to_vec();
+    latencies.sort();
+    let rank = (p / 100.0 * latencies.len() as f64).ceil() as usize;
+    latencies.get(rank.max(1) - 1).copied()
+}
+
+/// What a load transaction does to the ring.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// One token to the next account.
+    Transfer,
+    /// One new token into an account, signed by the bank owner.
+    Mint,
+    /// A new allowance for the next account.
+    Approve,
+}
+
+impl Operation {
+    pub fn name(self) -> &'static str {
+        match self {
+            Operation::Transfer => "transfer",
+            Operation::Mint => "mint",
+            Operation::Approve => "approve",
+        }
+    }
+}
+
+impl FromStr for Operation {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        [Operation::Transfer, Operation::Mint, Operation::Approve]
+            .iter()
+            .copied()
+            .find(|operation| operation.name() == name)
+            .ok_or_else(|| format!("unknown operation {}", name))
+    }
+}
+
+/// Relative weights of the operations a load sends, written `transfer:80,mint:10,approve:10`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mix {
+    weights: Vec<(Operation, u32)>,
+    total: u32,
+}
+
+impl Mix {
+    /// The operation of the `seq`th transaction. Operations come in proportion to their
+    /// weights, spread out rather than in runs, and the same `seq` always picks the same.
+    pub fn pick(&self, seq: u64) -> Operation {
+        let mut point = (spread(seq) % self.total as u64) as u32;
+        for (operation, weight) in self.weights.iter() {
+            if point < *weight {
+                return *operation;
+            }
+            point -= weight;
+        }
+        unreachable!("the weights add up to the total")
+    }
+}
+
+impl FromStr for Mix {
+    type Err = String;
+
+    fn from_str(mix: &str) -> Result<Self, String> {
+        let mut weights: Vec<(Operation, u32)> = Vec::new();
+        for entry in mix.split(',') {
+            let (name, weight) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("expected operation:weight, got {}", entry))?;
+            let operation = name.trim().parse::<Operation>()?;
+            let weight = weight
+                .trim()
+                .parse::<u32>()
+                .map_err(|e| format!("bad weight of {}: {}", name, e))?;
+            if weights.iter().any(|(other, _)| *other == operation) {
+                return Err(format!("{} is listed twice", name));
+            }
+            weights.push((operation, weight));
+        }
+        let total = weights
+            .iter()
+            .try_fold(0u32, |total, (_, weight)| total.checked_add(*weight))
+            .ok_or_else(|| "the weights add up past u32::MAX".to_string())?;
+        if total == 0 {
+            return Err("the weights add up to 0".to_string());
+        }
+        Ok(Mix { weights, total })
+    }
+}
+
+/// splitmix64's finalizer: consecutive sequence numbers land far apart.
+fn spread(seq: u64) -> u64 {
+    let mut z = seq.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Accounts of a bench bank, all owned by the bench signer.
+pub struct BenchRing {
+    pub bank: Pubkey,
+    pub accounts: Vec<Pubkey>,
+}
+
+impl BenchRing {
+    /// Opens a new bank owned by `signer` with `count` accounts of `INITIAL_BALANCE` each.
+    pub fn create(
+        bank_client: &BankClient,
+        signer: &Keypair,
+        count: usize,
+    ) -> Result<BenchRing, String> {
+        if count < 2 {
+            return Err("a bench needs at least two accounts".to_string());
+        }
+        let program_id = &bank_client.program_id;
+        let bank = Keypair::new();
+        bank_client.create_and_initialize_bank(
+            signer,
+            &bank,
+            signer,
+            0,
+            util::SendMode::Broadcast,
+        )?;
+
+        let mut accounts = Vec::with_capacity(count);
+        for _ in 0..count {
+            let account = Keypair::new();
+            let instructions = vec![
+                bank_client.create_account_instruction(
+                    &signer.pubkey(),
+                    &account.pubkey(),
+                    Account::LEN,
+                )?,
+                instruction::initialize_account(
+                    program_id,
+                    &bank.pubkey(),
+                    &account.pubkey(),
+                    &signer.pubkey(),
+                )
+                .map_err(|e| e.to_string())?,
+                instruction::mint_to(
+                    program_id,
+                    &bank.pubkey(),
+                    &account.pubkey(),
+                    &signer.pubkey(),
+                    INITIAL_BALANCE,
+                )
+                .map_err(|e| e.to_string())?,
+            ];
+            bank_client.send(
+                &instructions,
+                &[signer, &account],
+                util::SendMode::Broadcast,
+            )?;
+            accounts.push(account.pubkey());
+        }
+        info!(bank = %bank.pubkey(), accounts = count, "bench accounts ready");
+        Ok(BenchRing {
+            bank: bank.pubkey(),
+            accounts,
+        })
+    }
+
+    /// The `seq`th transfer: one token to the next account of the ring. The memo keeps
+    /// transfers between the same pair from sharing a signature under one blockhash.
+    pub fn transfer(
+        &self,
+        program_id: &Pubkey,
+        owner: &Pubkey,
+        seq: u64,
+    ) -> Result<Vec<Instruction>, String> {
+        let from = (seq % self.accounts.len() as u64) as usize;
+        let to = (from + 1) % self.accounts.len();
+        let transfer = instruction::transfer(
+            program_id,
+            &self.bank,
+            &self.accounts[from],
+            &self.accounts[to],
+            owner,
+            1,
+        )
+        .map_err(|e| e.to_string())?;
+        Ok(vec![
+            transfer,
+            util::memo_instruction(&format!("bench {}", seq), &[owner]),
+        ])
+    }
+
+    /// The `seq`th transaction of a load of `operation`s, `owner` owning both the bank
+    /// and its accounts.
+    pub fn operation(
+        &self,
+        program_id: &Pubkey,
+        owner: &Pubkey,
+        operation: Operation,
+        seq: u64,
+    ) -> Result<Vec<Instruction>, String> {
+        let account = (seq % self.accounts.len() as u64) as usize;
+        let next = (account + 1) % self.accounts.len();
+        let instruction = match operation {
+            Operation::Transfer => return self.transfer(program_id, owner, seq),
+            Operation::Mint => {
+                instruction::mint_to(program_id, &self.bank, &self.accounts[account], owner, 1)
+            }
+            // A new amount each time, so the allowance always changes.
+            Operation::Approve => instruction::approve(
+                program_id,
+                &self.bank,
+                &self.accounts[account],
+                &self.accounts[next],
+                owner,
+                seq,
+            ),
+        }
+        .map_err(|e| e.to_string())?;
+        Ok(vec![
+            instruction,
+            util::memo_instruction(&format!("bench {}", seq), &[owner]),
+        ])
+    }
+}
+
+struct InFlight {
+    operation: &'static str,
+    sent_at: Instant,
+    last_valid_slot: u64,
+}
+
+/// Sends the transactions built by `build`, each named by the operation it carries, for
+/// `config.duration` at `config.tps`, then waits until every one of them has confirmed,
+/// failed or expired.
+pub fn run<F>(
+    client: &dyn RpcApi,
+    signer: &dyn Signer,
+    config: &BenchConfig,
+    mut build: F,
+) -> Result<BenchReport, String>
+where
+    F: FnMut(u64) -> Result<(&'static str, Vec<Instruction>), String>,
+{
+    let blockhash_cache = BlockhashCache::new(config.commitment, Duration::from_secs(1));
+    let send_interval = Duration::from_secs(1) / config.tps.max(1);
+    let mut report = BenchReport::default();
+    let mut in_flight: HashMap<Signature, InFlight> = HashMap::new();
+    let started = Instant::now();
+    let mut next_send = started;
+    let mut next_poll = started + config.poll_interval;
+    let mut seq = 0;
+
+    loop {
+        let now = Instant::now();
+        let sending = now.duration_since(started) < config.duration;
+        if !sending && in_flight.is_empty() {
+            break;
+        }
+
+        while sending && next_send <= now {
+            let (blockhash, last_valid_slot) = blockhash_cache.get(client)?;
+            let (operation, instructions) = build(seq)?;
+            let message = Message::new(&instructions, Some(&signer.pubkey()));
+            seq += 1;
+            next_send += send_interval;
+            let mut t = Transaction::new_unsigned(message);
+            if let Err(e) = t.try_sign(&[signer], blockhash) {
+                error!(error = %e, "sign transaction failed");
+                return Err("sign transaction failed".to_string());
+            }
+
+            report.sent += 1;
+            match client.send_transaction(&t) {
+                Ok(signature) => {
+                    debug!(%signature, "bench transaction sent");
+                    in_flight.insert(
+                        signature,
+                        InFlight {
+                            operation,
+                            sent_at: Instant::now(),
+                            last_valid_slot,
+                        },
+                    );
+                }
+                Err(e) => {
+                    warn!(error = %e, "send transaction failed");
+                    report.fail(match e.get_transaction_error() {
+                        Some(err) => err.to_string(),
+                        None => "send failed".to_string(),
+                    });
+                }
+            }
+        }
+
+        if now >= next_poll {
+            next_poll = now + config.poll_interval;
+            poll(client, config.commitment, &mut in_flight, &mut report);
+        }
+        let wake = if sending {
+            next_send.min(next_poll)
+        } else {
+            next_poll
+        };
+        sleep(wake.saturating_duration_since(Instant::now()));
+    }
+
+    report.elapsed = started.elapsed();
+    Ok(report)
+}
+
+fn poll(
+    client: &dyn RpcApi,
+    commitment: CommitmentConfig,
+    in_flight: &mut HashMap<Signature, InFlight>,
+    report: &mut BenchReport,
+) {
+    let slot = match client.get_slot_with_commitment(commitment) {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "get slot failed");
+            return;
+        }
+    };
+    let signatures: Vec<Signature> = in_flight.keys().copied().collect();
+    for chunk in signatures.chunks(MAX_STATUSES_PER_CALL) {
+        let statuses = match client.get_signature_statuses(chunk) {
+            Ok(r) => r.value,
+            Err(e) => {
+                error!(error = %e, "get signature statuses failed");
+                return;
+            }
+        };
+        for (signature, status) in chunk.iter().zip(statuses) {
+            let sent = &in_flight[signature];
+            match status {
+                Some(s) if s.err.is_some() => {
+                    report.fail(s.err.unwrap().to_string());
+                }
+                Some(s) if s.satisfies_commitment(commitment) => {
+                    let latency = sent.sent_at.elapsed();
+                    report.confirmed += 1;
+                    report.latencies.push(latency);
+                    report
+                        .latencies_by_operation
+                        .entry(sent.operation)
+                        .or_default()
+                        .push(latency);
+                }
+                None if slot > sent.last_valid_slot => {
+                    report.fail("blockhash expired".to_string());
+                }
+                _ => continue,
+            }
+            in_flight.remove(signature);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::mock::MockRpc;
+    use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+
+    fn config() -> BenchConfig {
+        BenchConfig {
+            tps: 100,
+            duration: Duration::from_millis(100),
+            poll_interval: Duration::from_millis(10),
+            ..BenchConfig::default()
+        }
+    }
+
+    fn transfer(seq: u64) -> Result<(&'static str, Vec<Instruction>), String> {
+        Ok(("memo", vec![util::memo_instruction(&seq.to_string(), &[])]))
+    }
+
+    #[test]
+    fn test_run_confirms_at_rate() {
+        let mock = MockRpc::default();
+        let report = run(&mock, &Keypair::new(), &config(), transfer).unwrap();
+
+        assert!((9..=11).contains(&report.sent), "sent {}", report.sent);
+        assert_eq!(mock.sent().len(), report.sent);
+        assert_eq!(report.confirmed, report.sent);
+        assert!(report.failures.is_empty());
+        assert_eq!(report.latencies.len(), report.confirmed);
+        assert_eq!(report.latencies_by_operation["memo"], report.latencies);
+        assert!(report.median_latency().is_some());
+        assert!(report.confirmed_tps() > 0.0);
+    }
+
+    #[test]
+    fn test_run_reports_failures() {
+        let mut mock = MockRpc::default().drop_transactions(3);
+        mock.slot = mock.last_valid_slot + 1;
+        let report = run(&mock, &Keypair::new(), &config(), transfer).unwrap();
+        assert_eq!(report.failures["blockhash expired"], 3);
+        assert_eq!(report.confirmed, report.sent - 3);
+
+        let mut mock = MockRpc::default();
+        mock.transaction_error = Some(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidAccountData,
+        ));
+        let report = run(&mock, &Keypair::new(), &config(), transfer).unwrap();
+        assert_eq!(report.confirmed, 0);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures.values().sum::<usize>(), report.sent);
+
+        let mock = MockRpc::default().fail("send_transaction");
+        let report = run(&mock, &Keypair::new(), &config(), transfer).unwrap();
+        assert_eq!(report.failures["send failed"], report.sent);
+    }
+
+    #[test]
+    fn test_median_latency() {
+        let report = BenchReport {
+            latencies: vec![
+                Duration::from_millis(30),
+                Duration::from_millis(10),
+                Duration::from_millis(20),
+            ],
+            ..BenchReport::default()
+        };
+        assert_eq!(report.median_latency(), Some(Duration::from_millis(20)));
+        assert_eq!(BenchReport::default().median_latency(), None);
+
+        let latencies: Vec<Duration> = (1..=100).rev().map(Duration::from_millis).collect();
+        assert_eq!(
+            percentile(&latencies, 90.0),
+            Some(Duration::from_millis(90))
+        );
+        assert_eq!(
+            percentile(&latencies, 99.9),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(percentile(&latencies, 0.1), Some(Duration::from_millis(1)));
+    }
+
+    #[test]
+    fn test_mix() {
+        let mix: Mix = "transfer:80, mint:10,approve:10".parse().unwrap();
+        let mut counts = HashMap::new();
+        for seq in 0..10_000 {
+            *counts.entry(mix.pick(seq)).or_insert(0) += 1;
+        }
+        assert!((7_700..8_300).contains(&counts[&Operation::Transfer]));
+        assert!((800..1_200).contains(&counts[&Operation::Mint]));
+        assert!((800..1_200).contains(&counts[&Operation::Approve]));
+        assert_eq!(mix.pick(7), mix.pick(7));
+        // No runs of one operation a weighted round robin would send.
+        assert!((0..20).any(|seq| mix.pick(seq) != Operation::Transfer));
+
+        let transfers: Mix = "transfer:1,mint:0".parse().unwrap();
+        assert!((0..100).all(|seq| transfers.pick(seq) == Operation::Transfer));
+
+        for bad in [
+            "",
+            "transfer",
+            "burn:1",
+            "mint:-1",
+            "mint:0",
+            "mint:1,mint:2",
+        ]
+        .iter()
+        {
+            assert!(bad.parse::<Mix>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_ring_transfer() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let ring = BenchRing {
+            bank: Pubkey::new_unique(),
+            accounts: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+        };
+        let instructions = ring.transfer(&program_id, &owner, 3).unwrap();
+        assert_eq!(instructions[0].accounts[0].pubkey, ring.accounts[1]);
+        assert_eq!(instructions[0].accounts[1].pubkey, ring.accounts[0]);
+        assert_ne!(
+            instructions[1].data,
+            ring.transfer(&program_id, &owner, 5).unwrap()[1].data
+        );
+
+        let approve = ring
+            .operation(&program_id, &owner, Operation::Approve, 3)
+            .unwrap();
+        assert_eq!(approve[0].accounts[0].pubkey, ring.accounts[1]);
+        assert_eq!(approve[0].accounts[1].pubkey, ring.accounts[0]);
+        let mint = ring
+            .operation(&program_id, &owner, Operation::Mint, 4)
+            .unwrap();
+        assert_eq!(mint[0].accounts[1].pubkey, ring.accounts[0]);
+    }
+}
@@ -1,74 +1,136 @@
 use std::str::FromStr;
 
+use bank_interface::{instruction, BankAccount, BankInstruction};
 use borsh::{BorshDeserialize, BorshSerialize};
-use client::util;
+use client::{blockhash::BlockhashCache, preflight, progress::Steps, util};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
-    instruction, instruction::AccountMeta, message, pubkey::Pubkey, signature::Signer, transaction,
+    commitment_config::CommitmentConfig,
+    message,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
 };
-
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
-pub struct BankAccount {
-    pub amount: u64,
-    pub authority: Pubkey,
-    pub name: String,
-}
-
-#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
-pub enum BankInstruction {
-    InitAccount { amount: u64, name: String },
-
-    Transfer { amount: u64 },
+use tracing::info;
+
+#[cfg(feature = "bulk")]
+use client::bulk::{AsyncClient, DEFAULT_MAX_IN_FLIGHT};
+#[cfg(feature = "bulk")]
+use std::sync::Arc;
+
+/// `--bulk <count>`: then send that many transfers of 1 at once, see `bulk_transfer`.
+fn bulk_arg() -> Option<usize> {
+    let args: Vec<String> = std::env::args().collect();
+    let position = args.iter().position(|arg| arg == "--bulk")?;
+    match args.get(position + 1).map(|count| count.parse()) {
+        Some(Ok(count)) => Some(count),
+        _ => panic!("--bulk takes a number of transfers"),
+    }
 }
 
 fn main() {
-    let client = util::new_dev_client();
+    util::init_logging(std::env::args().any(|arg| arg == "--verbose"));
+    util::install_interrupt_handler();
+    preflight::set_auto_airdrop(std::env::args().any(|arg| arg == "--airdrop"));
+    let mode = util::SendMode::from_args();
+    let cluster = util::Cluster::from_args().unwrap();
+    let client = util::new_client(&cluster, CommitmentConfig::default());
+    let blockhash_cache = BlockhashCache::default();
     let program_id = Pubkey::from_str("Hk3sQwqGNbVzc8nbxpBWwQXLQXLEuuNcKCKDr8fs3Xfc").unwrap();
 
     util::check_program(&client, &program_id).unwrap();
-    println!("using program {}", program_id);
+    info!(program = %program_id, "using program");
 
-    let key_pair = util::get_keypair();
+    let key_pair = util::keypair_from_args().unwrap();
+    let signer = || Box::new(Keypair::from_bytes(&key_pair.to_bytes()).unwrap());
     let mut buffer: Vec<u8> = Vec::new();
-    BankAccount {
-        amount: 100,
-        name: "vic".to_string(),
-        authority: key_pair.pubkey(),
+    BankAccount::new(100, key_pair.pubkey(), "vic".to_string())
+        .serialize(&mut buffer)
+        .unwrap();
+    let mut steps = Steps::new(5);
+    let program_account = steps
+        .run("create account vic_bank_test1", || {
+            util::create_program_account(
+                &client,
+                &program_id,
+                "vic_bank_test1",
+                signer(),
+                buffer.len() as u64,
+                &blockhash_cache,
+                mode,
+            )
+        })
+        .unwrap();
+    steps
+        .run("init account vic_bank_test1", || {
+            init_bank_account(
+                &client,
+                &blockhash_cache,
+                &program_account,
+                signer(),
+                &program_id,
+                mode,
+            )
+        })
+        .unwrap();
+
+    let program_account2 = steps
+        .run("create account vic_bank_test2", || {
+            util::create_program_account(
+                &client,
+                &program_id,
+                "vic_bank_test2",
+                signer(),
+                buffer.len() as u64,
+                &blockhash_cache,
+                mode,
+            )
+        })
+        .unwrap();
+    steps
+        .run("init account vic_bank_test2", || {
+            init_bank_account(
+                &client,
+                &blockhash_cache,
+                &program_account2,
+                signer(),
+                &program_id,
+                mode,
+            )
+        })
+        .unwrap();
+    steps
+        .run("transfer 50", || {
+            transfer_bank_account(
+                &client,
+                &blockhash_cache,
+                &program_account,
+                &program_account2,
+                &program_id,
+                signer(),
+                50,
+                mode,
+            )
+        })
+        .unwrap();
+    if mode != util::SendMode::Broadcast {
+        return;
+    }
+    if let Some(count) = bulk_arg() {
+        #[cfg(feature = "bulk")]
+        bulk_transfer(
+            &cluster,
+            &program_id,
+            &program_account,
+            &program_account2,
+            Arc::from(signer()),
+            count,
+        );
+        #[cfg(not(feature = "bulk"))]
+        panic!(
+            "--bulk {} needs the client built with --features bulk",
+            count
+        );
     }
-    .serialize(&mut buffer)
-    .unwrap();
-    let program_account = util::create_program_account(
-        &client,
-        &program_id,
-        "vic_bank_test1",
-        Box::new(key_pair),
-        buffer.len() as u64,
-    )
-    .unwrap();
-    let key_pair = util::get_keypair();
-    init_bank_account(&client, &program_account, Box::new(key_pair), &program_id).unwrap();
-
-    let key_pair = util::get_keypair();
-    let program_account2 = util::create_program_account(
-        &client,
-        &program_id,
-        "vic_bank_test2",
-        Box::new(key_pair),
-        buffer.len() as u64,
-    )
-    .unwrap();
-    let key_pair = util::get_keypair();
-    init_bank_account(&client, &program_account2, Box::new(key_pair), &program_id).unwrap();
-    let key_pair = util::get_keypair();
-    transfer_bank_account(
-        &client,
-        &program_account,
-        &program_account2,
-        &program_id,
-        Box::new(key_pair),
-        50,
-    )
-    .unwrap();
 
     println!(
         "program_account: {}",
@@ -80,77 +142,93 @@ fn main() {
     )
 }
 
+/// Sends `count` transfers of 1 from `from` to `to` concurrently through
+/// `client::bulk`, each with its own memo so none share a signature.
+#[cfg(feature = "bulk")]
+fn bulk_transfer(
+    cluster: &util::Cluster,
+    program_id: &Pubkey,
+    from: &Pubkey,
+    to: &Pubkey,
+    signer: Arc<Keypair>,
+    count: usize,
+) {
+    let client = AsyncClient::new(
+        util::new_client(cluster, CommitmentConfig::default()),
+        DEFAULT_MAX_IN_FLIGHT,
+    );
+    let transactions = (0..count)
+        .map(|i| {
+            vec![
+                instruction::transfer(program_id, from, to, &signer.pubkey(), 1),
+                util::memo_instruction(&format!("bulk {}", i), &[&signer.pubkey()]),
+            ]
+        })
+        .collect();
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let results = runtime.block_on(client.send_all(transactions, vec![signer]));
+    let confirmed = results.iter().filter(|r| r.is_ok()).count();
+    println!("bulk: {} of {} transfers confirmed", confirmed, count);
+}
+
 fn init_bank_account(
     client: &RpcClient,
+    blockhash_cache: &BlockhashCache,
     program_account: &Pubkey,
     signer: Box<dyn Signer>,
     program_id: &Pubkey,
-) -> Result<(), String> {
-    let accounts = vec![
-        AccountMeta::new(program_account.to_owned(), false),
-        AccountMeta::new(signer.pubkey(), true),
-    ];
-
-    let i = BankInstruction::InitAccount {
-        amount: 1000,
-        name: "vic".to_string(),
-    };
-    let init_account_inst =
-        instruction::Instruction::new_with_borsh(program_id.to_owned(), &i, accounts);
+    mode: util::SendMode,
+) -> Result<Option<Signature>, String> {
+    let init_account_inst = instruction::init_account(
+        program_id,
+        program_account,
+        &signer.pubkey(),
+        1000,
+        "vic".to_string(),
+    );
 
     let msg = message::Message::new(&[init_account_inst][..], Some(&signer.pubkey()));
-    let block = match client.get_recent_blockhash() {
-        Ok(r) => r,
-        Err(e) => {
-            println!("err: {}", e);
-            return Err("get block failed".to_string());
-        }
-    };
-
-    let t = transaction::Transaction::new(&vec![signer], msg, block.0);
-    match client.send_and_confirm_transaction(&t) {
-        Err(e) => {
-            println!("err: {}", e);
-            return Err("send tx failed".to_string());
-        }
-        _ => Ok(()),
-    }
+    util::sign_and_submit(
+        client,
+        blockhash_cache,
+        msg,
+        &[signer.as_ref()],
+        mode,
+        Some(decode_instruction),
+    )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn transfer_bank_account(
     client: &RpcClient,
+    blockhash_cache: &BlockhashCache,
     from: &Pubkey,
     to: &Pubkey,
     program_id: &Pubkey,
     signer: Box<dyn Signer>,
     amount: u64,
-) -> Result<(), String> {
-    let i = BankInstruction::Transfer { amount: amount };
-    let accounts = vec![
-        AccountMeta::new(from.to_owned(), false),
-        AccountMeta::new(to.to_owned(), false),
-        AccountMeta::new(signer.pubkey(), true),
-    ];
-
-    let transfer_ints =
-        instruction::Instruction::new_with_borsh(program_id.to_owned(), &i, accounts);
+    mode: util::SendMode,
+) -> Result<Option<Signature>, String> {
+    let transfer_ints = instruction::transfer(program_id, from, to, &signer.pubkey(), amount);
 
     let msg = message::Message::new(&[transfer_ints][..], Some(&signer.pubkey()));
-    let block = match client.get_recent_blockhash() {
-        Ok(r) => r,
-        Err(e) => {
-            println!("err: {}", e);
-            return Err("get block failed".to_string());
-        }
-    };
-    let t = transaction::Transaction::new(&vec![signer], msg, block.0);
-    match client.send_and_confirm_transaction(&t) {
-        Err(e) => {
-            println!("err: {}", e);
-            return Err("send tx failed".to_string());
-        }
-        _ => Ok(()),
-    }
+    util::sign_and_submit(
+        client,
+        blockhash_cache,
+        msg,
+        &[signer.as_ref()],
+        mode,
+        Some(decode_instruction),
+    )
+}
+
+fn decode_instruction(data: &[u8]) -> Option<String> {
+    BankInstruction::try_from_slice(data)
+        .ok()
+        .map(|i| format!("{:?}", i))
 }
 
 fn get_account_balance(client: &RpcClient, account: &Pubkey) -> Result<u64, String> {
@@ -0,0 +1,3150 @@
+#[path = "bank_cli/cli.rs"]
+mod cli;
+
+use clap::{IntoApp, Parser};
+use cli::{
+    AirdropCommand, BoardCommand, Cli, ClosePolicyArg, Command, EscrowCommand, FaucetCommand,
+    InvoiceCommand, KeygenCommand, MultisigCommand, PayCommand, PayrollCommand, ReportFormat,
+    TipCommand, VestingCommand, WrapperCommand,
+};
+use client::{
+    airdrop::Airdrop,
+    bank::{parse_ui_amount, ui_amount, BankClient},
+    bench::{self, BenchConfig, BenchRing, Mix},
+    blockhash::BlockhashCache,
+    board::page_indices,
+    claim::{self, Claimable},
+    idl::{self, Idl},
+    invoice::pay_url,
+    keygen,
+    pay::{self, TransferRequest},
+    preflight,
+    proposal::{self, ProposalFile},
+    sweep,
+    util::{self, SendMode},
+    wallet::WalletSigner,
+    watch::{self, BankUpdate},
+};
+use solana_bank::{
+    instruction,
+    state::{Account, Bank, BurnPolicy, ClosePolicy},
+};
+use solana_board::{
+    instruction as board_instruction,
+    state::{Board, Post},
+};
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::RpcProgramAccountsConfig,
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_distributor::{
+    instruction as distributor_instruction,
+    state::{bitmap_len, Distributor},
+};
+use solana_escrow::{instruction as escrow_instruction, state::Escrow};
+use solana_faucet::{instruction as faucet_instruction, state::Faucet};
+use solana_invoice::{instruction as invoice_instruction, state::Invoice};
+use solana_multisig::{
+    instruction as multisig_instruction,
+    state::{Multisig, Proposal, MAX_ACCOUNTS, MAX_DATA, PROPOSAL_MULTISIG_OFFSET},
+};
+use solana_names::{
+    instruction as names_instruction,
+    state::{is_valid_name, NameRecord},
+};
+use solana_payroll::{
+    instruction as payroll_instruction,
+    state::{Employee, Payroll, EMPLOYEE_PAYROLL_OFFSET},
+};
+use solana_program::{program_option::COption, program_pack::Pack};
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    native_token::lamports_to_sol,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::{Signer, SignerError},
+    system_instruction,
+};
+use solana_swap::{curve, instruction as swap_instruction, state::Pool};
+use solana_tipping::{instruction as tipping_instruction, state::TipJar};
+use solana_vesting::{instruction as vesting_instruction, state::Vesting};
+use solana_wrapper::{instruction as wrapper_instruction, state::Wrapper};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account_idempotent,
+};
+use std::{
+    collections::{BTreeSet, HashSet},
+    io,
+    path::Path,
+    process::exit,
+    sync::mpsc::Receiver,
+    thread::sleep,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tracing::{debug, info};
+
+#[cfg(feature = "remote-signer")]
+use client::remote_signer::RemoteSigner;
+
+/// Whoever signs as --keypair: the keypair file, the browser wallet of --wallet, or the
+/// remote signer of --signer-config.
+enum CliSigner {
+    Keypair(Keypair),
+    Wallet(WalletSigner),
+    #[cfg(feature = "remote-signer")]
+    Remote(RemoteSigner),
+}
+
+impl Signer for CliSigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        match self {
+            CliSigner::Keypair(keypair) => keypair.try_pubkey(),
+            CliSigner::Wallet(wallet) => wallet.try_pubkey(),
+            #[cfg(feature = "remote-signer")]
+            CliSigner::Remote(remote) => remote.try_pubkey(),
+        }
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        match self {
+            CliSigner::Keypair(keypair) => keypair.try_sign_message(message),
+            CliSigner::Wallet(wallet) => wallet.try_sign_message(message),
+            #[cfg(feature = "remote-signer")]
+            CliSigner::Remote(remote) => remote.try_sign_message(message),
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        match self {
+            CliSigner::Keypair(keypair) => keypair.is_interactive(),
+            CliSigner::Wallet(wallet) => wallet.is_interactive(),
+            #[cfg(feature = "remote-signer")]
+            CliSigner::Remote(remote) => remote.is_interactive(),
+        }
+    }
+}
+
+impl Cli {
+    fn rpc_client(&self) -> RpcClient {
+        RpcClient::new(self.url.clone())
+    }
+
+    fn bank_client(&self) -> Result<BankClient, String> {
+        let program_id = self
+            .program_id
+            .ok_or_else(|| "--program-id is required".to_string())?;
+        let blockhash_cache =
+            BlockhashCache::default().with_confirm_timeout(self.confirm_timeout());
+        Ok(BankClient::new(self.rpc_client(), program_id).with_blockhash_cache(blockhash_cache))
+    }
+
+    fn signer(&self) -> Result<CliSigner, String> {
+        if let Some(pubkey) = self.wallet {
+            return Ok(CliSigner::Wallet(WalletSigner::new(pubkey)));
+        }
+        #[cfg(feature = "remote-signer")]
+        if let Some(path) = &self.signer_config {
+            return RemoteSigner::from_config_file(path).map(CliSigner::Remote);
+        }
+        self.keypair().map(CliSigner::Keypair)
+    }
+
+    /// The keypair file, for the commands that sign too often to go through a wallet.
+    fn keypair(&self) -> Result<Keypair, String> {
+        if self.wallet.is_some() {
+            return Err("this command can't sign with --wallet; use --keypair".to_string());
+        }
+        #[cfg(feature = "remote-signer")]
+        if self.signer_config.is_some() {
+            return Err("this command can't sign with --signer-config; use --keypair".to_string());
+        }
+        let path = match &self.keypair {
+            Some(p) => p.clone(),
+            None => util::default_keypair_path()?,
+        };
+        util::load_keypair_from_file(&path)
+    }
+
+    fn send_mode(&self) -> SendMode {
+        match (self.dry_run, self.simulate) {
+            (false, _) => SendMode::Broadcast,
+            (true, false) => SendMode::DryRun,
+            (true, true) => SendMode::Simulate,
+        }
+    }
+
+    fn confirm_timeout(&self) -> Duration {
+        Duration::from_secs(self.confirm_timeout)
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    util::init_logging(cli.verbose);
+    util::install_interrupt_handler();
+    preflight::set_auto_airdrop(cli.airdrop);
+
+    let result = match &cli.command {
+        Command::Airdrop {
+            distributor_program_id,
+            command: AirdropCommand::Create { bank, csv },
+        } => cli
+            .bank_client()
+            .and_then(|c| airdrop_create(&cli, &c, distributor_program_id, bank, csv)),
+        Command::Airdrop {
+            distributor_program_id,
+            command:
+                AirdropCommand::Claim {
+                    distributor,
+                    csv,
+                    to,
+                },
+        } => cli
+            .bank_client()
+            .and_then(|c| airdrop_claim(&cli, &c, distributor_program_id, distributor, csv, to)),
+        Command::Airdrop {
+            command: AirdropCommand::Proof { csv, recipient },
+            ..
+        } => airdrop_proof(csv, recipient),
+        Command::Approve {
+            account,
+            delegate,
+            amount,
+        } => cli
+            .bank_client()
+            .and_then(|c| approve(&cli, &c, account, delegate, *amount)),
+        Command::Audit { bank } => cli.bank_client().and_then(|c| audit(&c, bank)),
+        Command::Balance { account } => cli.bank_client().and_then(|c| balance(&c, account)),
+        Command::Bench {
+            tps,
+            duration,
+            accounts,
+            allow_remote,
+        } => cli.bank_client().and_then(|c| {
+            bench(
+                &cli,
+                &c,
+                BenchConfig {
+                    tps: *tps,
+                    duration: Duration::from_secs(*duration),
+                    ..BenchConfig::default()
+                },
+                *accounts,
+                *allow_remote,
+            )
+        }),
+        Command::Board {
+            board_program_id,
+            command: BoardCommand::Create,
+        } => cli
+            .bank_client()
+            .and_then(|c| board_create(&cli, &c, board_program_id)),
+        Command::Board {
+            board_program_id,
+            command: BoardCommand::Post { board, message },
+        } => cli
+            .bank_client()
+            .and_then(|c| board_post(&cli, &c, board_program_id, board, message)),
+        Command::Board {
+            board_program_id,
+            command:
+                BoardCommand::Read {
+                    board,
+                    page,
+                    per_page,
+                },
+        } => cli
+            .bank_client()
+            .and_then(|c| board_read(&c, board_program_id, board, *page, *per_page)),
+        Command::Burn {
+            from,
+            amount,
+            from_owner,
+        } => cli
+            .bank_client()
+            .and_then(|c| burn(&cli, &c, from, *amount, from_owner.as_deref())),
+        Command::Claim {
+            vesting_program_id,
+            stream_program_id,
+            vestings,
+            streams,
+            to,
+            threshold,
+            watch,
+            interval,
+        } => cli.bank_client().and_then(|c| {
+            claim(
+                &cli,
+                &c,
+                vesting_program_id.as_ref(),
+                stream_program_id.as_ref(),
+                vestings,
+                streams,
+                to,
+                *threshold,
+                *watch,
+                Duration::from_secs(*interval),
+            )
+        }),
+        Command::Close {
+            account,
+            dest,
+            burn_remaining,
+            bank_owner,
+        } => cli.bank_client().and_then(|c| {
+            close(
+                &cli,
+                &c,
+                account,
+                dest,
+                *burn_remaining,
+                bank_owner.as_deref(),
+            )
+        }),
+        Command::ClosePolicy { bank, policy } => cli
+            .bank_client()
+            .and_then(|c| set_close_policy(&cli, &c, bank, *policy)),
+        Command::RequireRentExempt { bank, off } => cli
+            .bank_client()
+            .and_then(|c| set_require_rent_exempt(&cli, &c, bank, !*off)),
+        Command::Completions { shell } => {
+            clap_complete::generate(*shell, &mut Cli::command(), "bank-cli", &mut io::stdout());
+            Ok(())
+        }
+        Command::Confirm { signature, wait } => {
+            confirm(&cli.rpc_client(), signature, *wait, cli.confirm_timeout())
+        }
+        Command::CreateAccount { bank, owner } => cli
+            .bank_client()
+            .and_then(|c| create_bank_account(&cli, &c, bank, owner.as_deref())),
+        Command::Escrow {
+            escrow_program_id,
+            command:
+                EscrowCommand::Init {
+                    from,
+                    receive,
+                    amount,
+                    expected_amount,
+                },
+        } => cli.bank_client().and_then(|c| {
+            escrow_init(
+                &cli,
+                &c,
+                escrow_program_id,
+                from,
+                receive,
+                *amount,
+                *expected_amount,
+            )
+        }),
+        Command::Escrow {
+            escrow_program_id,
+            command: EscrowCommand::Fulfill { escrow, from, to },
+        } => cli
+            .bank_client()
+            .and_then(|c| escrow_fulfill(&cli, &c, escrow_program_id, escrow, from, to)),
+        Command::Escrow {
+            escrow_program_id,
+            command: EscrowCommand::Cancel { escrow, to },
+        } => cli
+            .bank_client()
+            .and_then(|c| escrow_cancel(&cli, &c, escrow_program_id, escrow, to)),
+        Command::Escrow {
+            escrow_program_id,
+            command: EscrowCommand::Show { escrow },
+        } => cli
+            .bank_client()
+            .and_then(|c| escrow_show(&c, escrow_program_id, escrow)),
+        Command::Faucet {
+            faucet_program_id,
+            command: FaucetCommand::Create { bank, daily_cap },
+        } => cli
+            .bank_client()
+            .and_then(|c| faucet_create(&cli, &c, faucet_program_id, bank, *daily_cap)),
+        Command::Faucet {
+            faucet_program_id,
+            command: FaucetCommand::Tap { faucet, to, amount },
+        } => cli
+            .bank_client()
+            .and_then(|c| faucet_tap(&cli, &c, faucet_program_id, faucet, to, *amount)),
+        #[cfg(feature = "index")]
+        Command::Index {
+            db,
+            history,
+            volume,
+        } => index(&cli, db, history.as_ref(), volume.as_ref()),
+        Command::InitBank { decimals } => cli
+            .bank_client()
+            .and_then(|c| init_bank(&cli, &c, *decimals)),
+        Command::Inspect { bank } => cli.bank_client().and_then(|c| inspect(&c, bank)),
+        Command::Invoice {
+            invoice_program_id,
+            command:
+                InvoiceCommand::Create {
+                    to,
+                    amount,
+                    memo,
+                    expires_in,
+                    id,
+                },
+        } => cli.bank_client().and_then(|c| {
+            invoice_create(
+                &cli,
+                &c,
+                invoice_program_id,
+                to,
+                *amount,
+                memo,
+                *expires_in,
+                *id,
+            )
+        }),
+        Command::Invoice {
+            invoice_program_id,
+            command: InvoiceCommand::Pay { invoice, from },
+        } => cli
+            .bank_client()
+            .and_then(|c| invoice_pay(&cli, &c, invoice_program_id, invoice, from)),
+        Command::Invoice {
+            command: InvoiceCommand::Status { invoice },
+            ..
+        } => cli.bank_client().and_then(|c| invoice_status(&c, invoice)),
+        Command::Keygen {
+            command:
+                KeygenCommand::New {
+                    outfile,
+                    encrypt,
+                    force,
+                },
+        } => keygen_new(outfile, *encrypt, *force),
+        Command::Loadgen {
+            mix,
+            tps,
+            duration,
+            accounts,
+            allow_remote,
+        } => cli.bank_client().and_then(|c| {
+            loadgen(
+                &cli,
+                &c,
+                mix,
+                BenchConfig {
+                    tps: *tps,
+                    duration: Duration::from_secs(*duration),
+                    ..BenchConfig::default()
+                },
+                *accounts,
+                *allow_remote,
+            )
+        }),
+        Command::Mint { to, amount } => cli.bank_client().and_then(|c| mint(&cli, &c, to, *amount)),
+        Command::Multisig {
+            multisig_program_id,
+            command: MultisigCommand::Create { owners, threshold },
+        } => cli
+            .bank_client()
+            .and_then(|c| multisig_create(&cli, &c, multisig_program_id, owners, *threshold)),
+        Command::Multisig {
+            multisig_program_id,
+            command: MultisigCommand::ProposeBank { multisig, decimals },
+        } => cli.bank_client().and_then(|c| {
+            multisig_propose_bank(&cli, &c, multisig_program_id, multisig, *decimals)
+        }),
+        Command::Multisig {
+            multisig_program_id,
+            command:
+                MultisigCommand::ProposeMint {
+                    multisig,
+                    to,
+                    amount,
+                },
+        } => cli.bank_client().and_then(|c| {
+            multisig_propose_mint(&cli, &c, multisig_program_id, multisig, to, *amount)
+        }),
+        Command::Multisig {
+            multisig_program_id,
+            command: MultisigCommand::Propose { file },
+        } => cli
+            .bank_client()
+            .and_then(|c| multisig_propose_file(&cli, &c, multisig_program_id, file)),
+        Command::Multisig {
+            multisig_program_id,
+            command: MultisigCommand::Approve { proposal, expect },
+        } => cli.bank_client().and_then(|c| {
+            multisig_approve(&cli, &c, multisig_program_id, proposal, expect.as_deref())
+        }),
+        Command::Multisig {
+            multisig_program_id,
+            command: MultisigCommand::Execute { proposal },
+        } => cli
+            .bank_client()
+            .and_then(|c| multisig_execute(&cli, &c, multisig_program_id, proposal)),
+        Command::Multisig {
+            multisig_program_id,
+            command: MultisigCommand::List { multisig },
+        } => cli
+            .bank_client()
+            .and_then(|c| multisig_list(&c, multisig_program_id, multisig)),
+        Command::Multisig {
+            command: MultisigCommand::Export { proposal, out },
+            ..
+        } => cli
+            .bank_client()
+            .and_then(|c| multisig_export(&c, proposal, out)),
+        #[cfg(feature = "notify")]
+        Command::Notify {
+            webhook,
+            accounts,
+            min_amount,
+            max_retries,
+        } => cli.bank_client().and_then(|c| {
+            let mut balances = std::collections::HashMap::new();
+            for account in accounts.iter() {
+                balances.insert(*account, c.get_account(account)?.amount);
+            }
+            let updates = watch::watch_program(&util::websocket_url(&cli.url), &c.program_id)?;
+            client::notify::run(
+                &updates,
+                client::notify::NotifyConfig {
+                    webhook_url: webhook.clone(),
+                    accounts: balances,
+                    min_amount: *min_amount,
+                    max_retries: *max_retries,
+                },
+            )
+        }),
+        Command::Pay {
+            command: PayCommand::Parse { url, from },
+        } => cli
+            .bank_client()
+            .and_then(|c| pay_parse(&cli, &c, url, from)),
+        Command::Pay {
+            command:
+                PayCommand::Url {
+                    to,
+                    amount,
+                    references,
+                    label,
+                    message,
+                    memo,
+                },
+        } => cli.bank_client().and_then(|c| {
+            pay_request_url(
+                &c,
+                to,
+                *amount,
+                references,
+                label.as_deref(),
+                message.as_deref(),
+                memo.as_deref(),
+            )
+        }),
+        Command::Payroll {
+            payroll_program_id,
+            command: PayrollCommand::Run { payroll },
+        } => cli
+            .bank_client()
+            .and_then(|c| payroll_run(&cli, &c, payroll_program_id, payroll)),
+        Command::Raw {
+            idl,
+            ix,
+            args,
+            accounts,
+        } => cli
+            .bank_client()
+            .and_then(|c| raw(&cli, &c, idl, ix, args, accounts)),
+        Command::Revoke { account } => cli.bank_client().and_then(|c| revoke(&cli, &c, account)),
+        #[cfg(feature = "serve")]
+        Command::Serve { bind } => cli
+            .bank_client()
+            .and_then(|c| client::server::run(c, cli.keypair()?, *bind)),
+        Command::Stats {
+            start_slot,
+            end_slot,
+            format,
+        } => cli
+            .bank_client()
+            .and_then(|c| stats(&c, *start_slot, *end_slot, *format)),
+        Command::Supply { bank } => cli.bank_client().and_then(|c| supply(&c, bank)),
+        Command::Swap {
+            swap_program_id,
+            pool,
+            from,
+            to,
+            amount,
+            min_out,
+        } => cli
+            .bank_client()
+            .and_then(|c| swap(&cli, &c, swap_program_id, pool, from, to, *amount, *min_out)),
+        Command::Sweep { owner, dest } => cli
+            .bank_client()
+            .and_then(|c| sweep(&cli, &c, owner.as_ref(), dest.as_ref())),
+        Command::Tip {
+            tipping_program_id,
+            command: TipCommand::Create { bank },
+        } => cli
+            .bank_client()
+            .and_then(|c| tip_create(&cli, &c, tipping_program_id, bank)),
+        Command::Tip {
+            tipping_program_id,
+            command:
+                TipCommand::Send {
+                    creator,
+                    from,
+                    amount,
+                },
+        } => cli
+            .bank_client()
+            .and_then(|c| tip_send(&cli, &c, tipping_program_id, creator, from, *amount)),
+        Command::Tip {
+            tipping_program_id,
+            command: TipCommand::Show { creator, bank },
+        } => cli
+            .bank_client()
+            .and_then(|c| tip_show(&c, tipping_program_id, creator, bank)),
+        Command::Tip {
+            tipping_program_id,
+            command: TipCommand::Withdraw { bank, to, amount },
+        } => cli
+            .bank_client()
+            .and_then(|c| tip_withdraw(&cli, &c, tipping_program_id, bank, to, *amount)),
+        Command::Transfer {
+            from,
+            to,
+            names_program_id,
+            amount,
+            memo,
+        } => cli.bank_client().and_then(|c| {
+            let to = resolve_recipient(&c, to, names_program_id.as_ref())?;
+            transfer(&cli, &c, from, &to, *amount, memo.as_deref())
+        }),
+        #[cfg(not(feature = "geyser"))]
+        Command::Watch {} => cli
+            .bank_client()
+            .and_then(|c| watch::watch_program(&util::websocket_url(&cli.url), &c.program_id))
+            .and_then(|updates| print_updates(&updates)),
+        #[cfg(feature = "geyser")]
+        Command::Watch { grpc, x_token } => cli
+            .bank_client()
+            .and_then(|c| match grpc {
+                Some(endpoint) => {
+                    client::geyser::watch_program_grpc(endpoint, x_token.clone(), &c.program_id)
+                }
+                None => watch::watch_program(&util::websocket_url(&cli.url), &c.program_id),
+            })
+            .and_then(|updates| print_updates(&updates)),
+        #[cfg(feature = "tui")]
+        Command::Tui { bank } => cli
+            .bank_client()
+            .and_then(|c| client::tui::run(&c, bank, &util::websocket_url(&cli.url))),
+        Command::Vesting {
+            vesting_program_id,
+            command:
+                VestingCommand::Create {
+                    from,
+                    beneficiary,
+                    amount,
+                    start,
+                    cliff,
+                    duration,
+                },
+        } => cli.bank_client().and_then(|c| {
+            vesting_create(
+                &cli,
+                &c,
+                vesting_program_id,
+                from,
+                beneficiary,
+                *amount,
+                *start,
+                *cliff,
+                *duration,
+            )
+        }),
+        Command::Vesting {
+            vesting_program_id,
+            command: VestingCommand::Claim { vesting, to },
+        } => cli
+            .bank_client()
+            .and_then(|c| vesting_claim(&cli, &c, vesting_program_id, vesting, to)),
+        Command::Wrapper {
+            wrapper_program_id,
+            command: WrapperCommand::Create { mint },
+        } => cli
+            .bank_client()
+            .and_then(|c| wrapper_create(&cli, &c, wrapper_program_id, mint)),
+        Command::Wrapper {
+            wrapper_program_id,
+            command:
+                WrapperCommand::Wrap {
+                    wrapper,
+                    to,
+                    amount,
+                },
+        } => cli
+            .bank_client()
+            .and_then(|c| wrapper_wrap(&cli, &c, wrapper_program_id, wrapper, to, *amount)),
+        Command::Wrapper {
+            wrapper_program_id,
+            command:
+                WrapperCommand::Unwrap {
+                    wrapper,
+                    from,
+                    amount,
+                },
+        } => cli
+            .bank_client()
+            .and_then(|c| wrapper_unwrap(&cli, &c, wrapper_program_id, wrapper, from, *amount)),
+    };
+
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        exit(1);
+    }
+}
+
+fn transfer(
+    cli: &Cli,
+    bank_client: &BankClient,
+    from: &Pubkey,
+    to: &Pubkey,
+    amount: u64,
+    memo: Option<&str>,
+) -> Result<(), String> {
+    let authority = cli.signer()?;
+    let bank = bank_client.get_account(from)?.bank;
+    let mut instructions = vec![instruction::transfer(
+        &bank_client.program_id,
+        &bank,
+        from,
+        to,
+        &authority.pubkey(),
+        amount,
+    )
+    .map_err(|e| e.to_string())?];
+    if let Some(memo) = memo {
+        instructions.push(util::memo_instruction(memo, &[&authority.pubkey()]));
+    }
+    if let Some(signature) = bank_client.send(&instructions, &[&authority], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_balance(bank_client, &bank, from)?;
+        print_balance(bank_client, &bank, to)?;
+    }
+    Ok(())
+}
+
+/// The account `to` names: a plain address, or `name:<name>` looked up in the name service.
+fn resolve_recipient(
+    bank_client: &BankClient,
+    to: &str,
+    names_program_id: Option<&Pubkey>,
+) -> Result<Pubkey, String> {
+    let name = match to.strip_prefix("name:") {
+        Some(name) => name,
+        None => {
+            return to
+                .parse()
+                .map_err(|e| format!("invalid --to {}: {}", to, e))
+        }
+    };
+    let names_program_id =
+        names_program_id.ok_or_else(|| "--names-program-id is required for name:".to_string())?;
+    if !is_valid_name(name) {
+        return Err(format!("{} isn't a valid name", name));
+    }
+    let (address, _) = names_instruction::name_address(names_program_id, name);
+    let data = match bank_client.rpc.get_account_data(&address) {
+        Ok(d) => d,
+        Err(_) => return Err(format!("{} isn't registered", name)),
+    };
+    let record = NameRecord::unpack(&data).map_err(|e| e.to_string())?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?
+        .as_secs() as i64;
+    if record.is_expired(now) {
+        return Err(format!("{} has expired", name));
+    }
+    println!("{}: {}", to, record.target);
+    Ok(record.target)
+}
+
+fn approve(
+    cli: &Cli,
+    bank_client: &BankClient,
+    account: &Pubkey,
+    delegate: &Pubkey,
+    amount: u64,
+) -> Result<(), String> {
+    let owner = cli.signer()?;
+    let bank = bank_client.get_account(account)?.bank;
+    let instruction = instruction::approve(
+        &bank_client.program_id,
+        &bank,
+        account,
+        delegate,
+        &owner.pubkey(),
+        amount,
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&owner], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_delegation(bank_client, account)?;
+    }
+    Ok(())
+}
+
+fn revoke(cli: &Cli, bank_client: &BankClient, account: &Pubkey) -> Result<(), String> {
+    let owner = cli.signer()?;
+    let bank = bank_client.get_account(account)?.bank;
+    let instruction = instruction::revoke(&bank_client.program_id, &bank, account, &owner.pubkey())
+        .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&owner], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_delegation(bank_client, account)?;
+    }
+    Ok(())
+}
+
+fn init_bank(cli: &Cli, bank_client: &BankClient, decimals: u8) -> Result<(), String> {
+    let bank_owner = cli.signer()?;
+    let bank = Keypair::new();
+    println!("bank: {}", bank.pubkey());
+    if let Some(signature) = bank_client.create_and_initialize_bank(
+        &bank_owner,
+        &bank,
+        &bank_owner,
+        decimals,
+        cli.send_mode(),
+    )? {
+        println!("signature: {}", signature);
+    }
+    Ok(())
+}
+
+fn create_bank_account(
+    cli: &Cli,
+    bank_client: &BankClient,
+    bank: &Pubkey,
+    owner: Option<&Path>,
+) -> Result<(), String> {
+    let payer = cli.signer()?;
+    // Fails early, rather than in the program, for a bank that doesn't exist.
+    bank_client.get_bank(bank)?;
+    let owner = match owner {
+        Some(path) => Some(keygen::load_keypair(path)?),
+        None => None,
+    };
+    let account = Keypair::new();
+    println!("account: {}", account.pubkey());
+    let owner: &dyn Signer = match owner.as_ref() {
+        Some(owner) => owner,
+        None => &payer,
+    };
+    if let Some(signature) =
+        bank_client.create_bank_account(&payer, bank, &account, owner, cli.send_mode())?
+    {
+        println!("signature: {}", signature);
+    }
+    Ok(())
+}
+
+fn mint(cli: &Cli, bank_client: &BankClient, to: &Pubkey, amount: u64) -> Result<(), String> {
+    let bank_owner = cli.signer()?;
+    let bank = bank_client.get_account(to)?.bank;
+    check_bank_owner(bank_client, &bank, &bank_owner)?;
+
+    let instruction = instruction::mint_to(
+        &bank_client.program_id,
+        &bank,
+        to,
+        &bank_owner.pubkey(),
+        amount,
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&bank_owner], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_balance(bank_client, &bank, to)?;
+    }
+    Ok(())
+}
+
+fn burn(
+    cli: &Cli,
+    bank_client: &BankClient,
+    from: &Pubkey,
+    amount: u64,
+    from_owner: Option<&Path>,
+) -> Result<(), String> {
+    let signer = cli.signer()?;
+    let account = bank_client.get_account(from)?;
+    let burn_policy = bank_client.get_bank(&account.bank)?.burn_policy;
+    if burn_policy == BurnPolicy::OwnerAndHolder {
+        check_bank_owner(bank_client, &account.bank, &signer)?;
+    }
+    let account_owner = match from_owner {
+        Some(path) => Some(keygen::load_keypair(path)?),
+        None => None,
+    };
+    let account_owner_key = account_owner
+        .as_ref()
+        .map_or_else(|| signer.pubkey(), |k| k.pubkey());
+    if account.owner != account_owner_key {
+        return Err(format!(
+            "{} is owned by {}, pass its keypair with --from-owner",
+            from, account.owner
+        ));
+    }
+
+    let instruction = match burn_policy {
+        BurnPolicy::OwnerAndHolder => instruction::burn(
+            &bank_client.program_id,
+            &account.bank,
+            from,
+            &signer.pubkey(),
+            &account_owner_key,
+            amount,
+        ),
+        BurnPolicy::HolderOnly => instruction::burn_by_holder(
+            &bank_client.program_id,
+            &account.bank,
+            from,
+            &account_owner_key,
+            amount,
+        ),
+    }
+    .map_err(|e| e.to_string())?;
+    let mut signers: Vec<&dyn Signer> = vec![&signer];
+    if let Some(k) = account_owner.as_ref() {
+        signers.push(k);
+    }
+    if let Some(signature) = bank_client.send(&[instruction], &signers, cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_balance(bank_client, &account.bank, from)?;
+    }
+    Ok(())
+}
+
+fn close(
+    cli: &Cli,
+    bank_client: &BankClient,
+    account: &Pubkey,
+    dest: &Pubkey,
+    burn_remaining: bool,
+    bank_owner: Option<&Path>,
+) -> Result<(), String> {
+    let owner = cli.signer()?;
+    let state = bank_client.get_account(account)?;
+    if state.owner != owner.pubkey() {
+        return Err(format!("{} is owned by {}", account, state.owner));
+    }
+
+    let remaining = state.amount;
+    let mut instructions = Vec::new();
+    let bank_owner = match bank_owner {
+        Some(path) => Some(keygen::load_keypair(path)?),
+        None => None,
+    };
+    if remaining > 0 {
+        if !burn_remaining {
+            return Err(format!(
+                "{} still holds {} tokens, pass --burn-remaining to burn them",
+                account, remaining
+            ));
+        }
+        let bank = bank_client.get_bank(&state.bank)?;
+        let instruction = match bank.burn_policy {
+            BurnPolicy::OwnerAndHolder => {
+                let bank_owner_key = bank_owner
+                    .as_ref()
+                    .map_or_else(|| owner.pubkey(), |k| k.pubkey());
+                if bank.bank_owner != bank_owner_key {
+                    return Err(format!(
+                        "burning needs the bank owner {}, pass its keypair with --bank-owner",
+                        bank.bank_owner
+                    ));
+                }
+                instruction::burn(
+                    &bank_client.program_id,
+                    &state.bank,
+                    account,
+                    &bank_owner_key,
+                    &owner.pubkey(),
+                    remaining,
+                )
+            }
+            BurnPolicy::HolderOnly => instruction::burn_by_holder(
+                &bank_client.program_id,
+                &state.bank,
+                account,
+                &owner.pubkey(),
+                remaining,
+            ),
+        };
+        instructions.push(instruction.map_err(|e| e.to_string())?);
+    }
+    instructions.push(
+        instruction::close_account(
+            &bank_client.program_id,
+            &state.bank,
+            account,
+            dest,
+            &owner.pubkey(),
+        )
+        .map_err(|e| e.to_string())?,
+    );
+
+    let lamports = match bank_client.rpc.get_balance(account) {
+        Ok(l) => l,
+        Err(e) => return Err(format!("get balance failed: {}", e)),
+    };
+    let mut signers: Vec<&dyn Signer> = vec![&owner];
+    if let Some(k) = bank_owner.as_ref() {
+        signers.push(k);
+    }
+    if let Some(signature) = bank_client.send(&instructions, &signers, cli.send_mode())? {
+        println!("signature: {}", signature);
+        if remaining > 0 {
+            println!("burned: {}", remaining);
+        }
+        println!(
+            "reclaimed: {} lamports ({} SOL) to {}",
+            lamports,
+            lamports_to_sol(lamports),
+            dest
+        );
+    }
+    Ok(())
+}
+
+fn sweep(
+    cli: &Cli,
+    bank_client: &BankClient,
+    owner: Option<&Pubkey>,
+    dest: Option<&Pubkey>,
+) -> Result<(), String> {
+    let signer = cli.signer()?;
+    let owner = owner.copied().unwrap_or_else(|| signer.pubkey());
+    let accounts = bank_client.get_owner_accounts_with_lamports(&owner)?;
+    let mut banks = HashSet::new();
+    for bank in accounts
+        .iter()
+        .map(|(_, account, _)| account.bank)
+        .collect::<BTreeSet<_>>()
+    {
+        match bank_client.get_bank(&bank) {
+            Ok(_) => {
+                banks.insert(bank);
+            }
+            Err(e) => println!("skipping the accounts of bank {}: {}", bank, e),
+        }
+    }
+    let sweepable = sweep::sweepable(&accounts, &banks);
+    if sweepable.is_empty() {
+        println!("nothing to sweep");
+        return Ok(());
+    }
+    for s in sweepable.iter() {
+        println!("{} (bank {}): {} lamports", s.address, s.bank, s.lamports);
+    }
+    let total = sweep::total_lamports(&sweepable);
+    if owner != signer.pubkey() {
+        println!(
+            "reclaimable: {} lamports ({} SOL) in {} accounts; sign with the owner's --keypair to sweep them",
+            total,
+            lamports_to_sol(total),
+            sweepable.len()
+        );
+        return Ok(());
+    }
+
+    let dest = dest.copied().unwrap_or(owner);
+    let mut recovered = 0;
+    for (instructions, batch) in sweep::batches(&bank_client.program_id, &sweepable, &owner, &dest)?
+        .iter()
+        .zip(sweepable.chunks(sweep::CLOSES_PER_TRANSACTION))
+    {
+        if let Some(signature) = bank_client.send(instructions, &[&signer], cli.send_mode())? {
+            println!("signature: {}", signature);
+            recovered += sweep::total_lamports(batch);
+        }
+    }
+    if recovered > 0 {
+        println!(
+            "recovered: {} lamports ({} SOL) to {}",
+            recovered,
+            lamports_to_sol(recovered),
+            dest
+        );
+    }
+    Ok(())
+}
+
+/// The program rejects MintTo and Burn from anyone but `Bank::bank_owner`; check it
+/// up front instead of paying for a failed transaction.
+fn set_close_policy(
+    cli: &Cli,
+    bank_client: &BankClient,
+    bank: &Pubkey,
+    policy: ClosePolicyArg,
+) -> Result<(), String> {
+    let bank_owner = cli.signer()?;
+    check_bank_owner(bank_client, bank, &bank_owner)?;
+    let policy = match policy {
+        ClosePolicyArg::Reject => ClosePolicy::Reject,
+        ClosePolicyArg::Sweep => ClosePolicy::Sweep,
+        ClosePolicyArg::Burn => ClosePolicy::Burn,
+    };
+    let instruction =
+        instruction::set_close_policy(&bank_client.program_id, bank, &bank_owner.pubkey(), policy)
+            .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&bank_owner], cli.send_mode())? {
+        println!("signature: {}", signature);
+        println!(
+            "close policy: {:?}",
+            bank_client.get_bank(bank)?.close_policy
+        );
+    }
+    Ok(())
+}
+
+fn set_require_rent_exempt(
+    cli: &Cli,
+    bank_client: &BankClient,
+    bank: &Pubkey,
+    required: bool,
+) -> Result<(), String> {
+    let bank_owner = cli.signer()?;
+    check_bank_owner(bank_client, bank, &bank_owner)?;
+    let instruction = instruction::set_require_rent_exempt(
+        &bank_client.program_id,
+        bank,
+        &bank_owner.pubkey(),
+        required,
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&bank_owner], cli.send_mode())? {
+        println!("signature: {}", signature);
+        println!(
+            "require rent-exempt: {}",
+            bank_client.get_bank(bank)?.require_rent_exempt
+        );
+    }
+    Ok(())
+}
+
+fn check_bank_owner(
+    bank_client: &BankClient,
+    bank: &Pubkey,
+    signer: &dyn Signer,
+) -> Result<(), String> {
+    let bank_owner = bank_client.get_bank(bank)?.bank_owner;
+    if bank_owner != signer.pubkey() {
+        return Err(format!(
+            "{} is not the owner of bank {} (owner is {})",
+            signer.pubkey(),
+            bank,
+            bank_owner
+        ));
+    }
+    Ok(())
+}
+
+fn print_balance(bank_client: &BankClient, bank: &Pubkey, account: &Pubkey) -> Result<(), String> {
+    let bank_state = bank_client.get_bank(bank)?;
+    let state = bank_client.get_account(account)?;
+    println!(
+        "{}: {}",
+        account,
+        ui_amount(state.amount, bank_state.decimals)
+    );
+    println!(
+        "total supply: {}",
+        ui_amount(bank_state.total_supply, bank_state.decimals)
+    );
+    Ok(())
+}
+
+fn print_delegation(bank_client: &BankClient, account: &Pubkey) -> Result<(), String> {
+    let state = bank_client.get_account(account)?;
+    let bank = bank_client.get_bank(&state.bank)?;
+    println!("account: {}", account);
+    println!("amount: {}", ui_amount(state.amount, bank.decimals));
+    match state.delegate {
+        COption::Some(delegate) => {
+            println!("delegate: {}", delegate);
+            println!(
+                "delegated amount: {}",
+                ui_amount(state.delegated_amount, bank.decimals)
+            );
+        }
+        COption::None => println!("delegate: none"),
+    }
+    Ok(())
+}
+
+fn print_updates(updates: &Receiver<BankUpdate>) -> Result<(), String> {
+    for update in updates.iter() {
+        match update {
+            BankUpdate::Bank { key, slot, bank } => println!(
+                "{} bank {} supply {} opened {}",
+                slot, key, bank.total_supply, bank.is_opened
+            ),
+            BankUpdate::Account { key, slot, account } => println!(
+                "{} account {} amount {} delegated {} opened {}",
+                slot, key, account.amount, account.delegated_amount, account.is_opened
+            ),
+        }
+    }
+    Err("update stream closed".to_string())
+}
+
+fn balance(bank_client: &BankClient, account: &Pubkey) -> Result<(), String> {
+    let state = bank_client.get_account(account)?;
+    let bank = bank_client.get_bank(&state.bank)?;
+    println!("{}", ui_amount(state.amount, bank.decimals));
+    Ok(())
+}
+
+fn supply(bank_client: &BankClient, bank: &Pubkey) -> Result<(), String> {
+    let state = bank_client.get_bank(bank)?;
+    println!("{}", ui_amount(state.total_supply, state.decimals));
+    Ok(())
+}
+
+fn inspect(bank_client: &BankClient, bank: &Pubkey) -> Result<(), String> {
+    let state = bank_client.get_bank(bank)?;
+    println!("bank: {}", bank);
+    println!("owner: {}", state.bank_owner);
+    if let COption::Some(authority) = state.emergency_authority {
+        println!("emergency authority: {}", authority);
+    }
+    if let COption::Some(pending_owner) = state.pending_bank_owner {
+        println!(
+            "pending owner: {} (from slot {})",
+            pending_owner, state.owner_change_slot
+        );
+    }
+    println!("opened: {}", state.is_opened);
+    println!("paused: {}", state.is_paused);
+    println!("decimals: {}", state.decimals);
+    println!(
+        "total supply: {}",
+        ui_amount(state.total_supply, state.decimals)
+    );
+    println!("close policy: {:?}", state.close_policy);
+    println!("burn policy: {:?}", state.burn_policy);
+    println!("require rent exempt: {}", state.require_rent_exempt);
+    if state.dust_threshold > 0 {
+        println!(
+            "dust threshold: {}",
+            ui_amount(state.dust_threshold, state.decimals)
+        );
+    }
+    if state.lamports_per_token > 0 {
+        println!("lamports per token: {}", state.lamports_per_token);
+    }
+    if state.interest_rate_bps > 0 {
+        println!("interest rate: {} bps a year", state.interest_rate_bps);
+    }
+    if state.owner_change_delay > 0 {
+        println!("owner change delay: {} slots", state.owner_change_delay);
+    }
+    println!("transfers: {}", state.transfer_count);
+    println!(
+        "volume: {}",
+        ui_amount(state.cumulative_volume, state.decimals)
+    );
+    println!("holders: {}", state.holder_count);
+    println!("registered accounts: {}", state.registered_accounts);
+    Ok(())
+}
+
+fn audit(bank_client: &BankClient, bank: &Pubkey) -> Result<(), String> {
+    let report = bank_client.audit(bank)?;
+    println!("accounts: {}", report.account_count);
+    println!("total supply: {}", report.total_supply);
+    println!("sum of balances: {}", report.accounted_supply);
+    for (key, reason) in report.offending_accounts.iter() {
+        println!("offending account {}: {}", key, reason);
+    }
+
+    if !report.is_consistent() {
+        return Err(format!(
+            "supply mismatch: total supply {} but accounts hold {}",
+            report.total_supply, report.accounted_supply
+        ));
+    }
+    println!("ok");
+    Ok(())
+}
+
+fn bench(
+    cli: &Cli,
+    bank_client: &BankClient,
+    config: BenchConfig,
+    accounts: usize,
+    allow_remote: bool,
+) -> Result<(), String> {
+    check_local(cli, allow_remote)?;
+    let signer = cli.keypair()?;
+    let ring = BenchRing::create(bank_client, &signer, accounts)?;
+    println!("bank: {}", ring.bank);
+    println!(
+        "sending {} transfers/s for {}s",
+        config.tps,
+        config.duration.as_secs()
+    );
+
+    let program_id = bank_client.program_id;
+    let owner = signer.pubkey();
+    let report = bench::run(&bank_client.rpc, &signer, &config, |seq| {
+        Ok(("transfer", ring.transfer(&program_id, &owner, seq)?))
+    })?;
+    println!("sent: {}", report.sent);
+    println!("confirmed: {}", report.confirmed);
+    println!("confirmed tps: {:.1}", report.confirmed_tps());
+    match report.median_latency() {
+        Some(latency) => println!("median confirmation latency: {:?}", latency),
+        None => println!("median confirmation latency: n/a"),
+    }
+    for (reason, count) in report.failures.iter() {
+        println!("failed ({}): {}", reason, count);
+    }
+    Ok(())
+}
+
+fn loadgen(
+    cli: &Cli,
+    bank_client: &BankClient,
+    mix: &str,
+    config: BenchConfig,
+    accounts: usize,
+    allow_remote: bool,
+) -> Result<(), String> {
+    let mix: Mix = mix.parse().map_err(|e| format!("invalid --mix: {}", e))?;
+    check_local(cli, allow_remote)?;
+    let signer = cli.keypair()?;
+    println!("provisioning {} accounts", accounts);
+    let ring = BenchRing::create(bank_client, &signer, accounts)?;
+    println!("bank: {}", ring.bank);
+    println!(
+        "sending {} transactions/s for {}s",
+        config.tps,
+        config.duration.as_secs()
+    );
+
+    let program_id = bank_client.program_id;
+    let owner = signer.pubkey();
+    let report = bench::run(&bank_client.rpc, &signer, &config, |seq| {
+        let operation = mix.pick(seq);
+        let instructions = ring.operation(&program_id, &owner, operation, seq)?;
+        Ok((operation.name(), instructions))
+    })?;
+    println!("sent: {}", report.sent);
+    println!("confirmed: {}", report.confirmed);
+    println!("confirmed tps: {:.1}", report.confirmed_tps());
+    print_percentiles("all", &report.latencies);
+    for (operation, latencies) in report.latencies_by_operation.iter() {
+        print_percentiles(operation, latencies);
+    }
+    for (reason, count) in report.failures.iter() {
+        println!("failed ({}): {}", reason, count);
+    }
+    Ok(())
+}
+
+/// Confirmation latency percentiles of the `label` transactions of a load.
+fn print_percentiles(label: &str, latencies: &[Duration]) {
+    match bench::percentile(latencies, 50.0) {
+        Some(p50) => println!(
+            "{} latency ({} confirmed): p50 {:?} p90 {:?} p99 {:?}",
+            label,
+            latencies.len(),
+            p50,
+            bench::percentile(latencies, 90.0).unwrap_or(p50),
+            bench::percentile(latencies, 99.0).unwrap_or(p50)
+        ),
+        None => println!("{} latency: n/a", label),
+    }
+}
+
+/// Load only goes to a local validator unless the caller insists.
+fn check_local(cli: &Cli, allow_remote: bool) -> Result<(), String> {
+    let local = cli.url.contains("localhost") || cli.url.contains("127.0.0.1");
+    if !local && !allow_remote {
+        return Err(format!(
+            "{} is not a local validator, pass --allow-remote to load it anyway",
+            cli.url
+        ));
+    }
+    Ok(())
+}
+
+/// Creates a rent-exempt `account` of `space` bytes owned by `owner`, paid for by `payer`.
+fn create_account(
+    bank_client: &BankClient,
+    payer: &dyn Signer,
+    account: &Keypair,
+    space: usize,
+    owner: &Pubkey,
+) -> Result<Instruction, String> {
+    let rent = match bank_client
+        .rpc
+        .get_minimum_balance_for_rent_exemption(space)
+    {
+        Ok(r) => r,
+        Err(e) => return Err(format!("get rent exemption failed: {}", e)),
+    };
+    Ok(system_instruction::create_account(
+        &payer.pubkey(),
+        &account.pubkey(),
+        rent,
+        space as u64,
+        owner,
+    ))
+}
+
+fn escrow_init(
+    cli: &Cli,
+    bank_client: &BankClient,
+    escrow_program_id: &Pubkey,
+    from: &Pubkey,
+    receive: &Pubkey,
+    amount: u64,
+    expected_amount: u64,
+) -> Result<(), String> {
+    let maker = cli.signer()?;
+    let deposit = bank_client.get_account(from)?;
+    if deposit.owner != maker.pubkey() {
+        return Err(format!("{} is owned by {}", from, deposit.owner));
+    }
+    let receive_account = bank_client.get_account(receive)?;
+    if receive_account.owner != maker.pubkey() {
+        return Err(format!("{} is owned by {}", receive, receive_account.owner));
+    }
+
+    let escrow = Keypair::new();
+    let vault = Keypair::new();
+    let instructions = vec![
+        create_account(bank_client, &maker, &escrow, Escrow::LEN, escrow_program_id)?,
+        create_account(
+            bank_client,
+            &maker,
+            &vault,
+            Account::LEN,
+            &bank_client.program_id,
+        )?,
+        escrow_instruction::init_escrow(
+            escrow_program_id,
+            &bank_client.program_id,
+            &maker.pubkey(),
+            &escrow.pubkey(),
+            &vault.pubkey(),
+            from,
+            receive,
+            &deposit.bank,
+            amount,
+            expected_amount,
+        )
+        .map_err(|e| e.to_string())?,
+    ];
+    if let Some(signature) =
+        bank_client.send(&instructions, &[&maker, &escrow, &vault], cli.send_mode())?
+    {
+        println!("signature: {}", signature);
+        println!("escrow: {}", escrow.pubkey());
+        println!("vault: {}", vault.pubkey());
+        print_balance(bank_client, &deposit.bank, from)?;
+    }
+    Ok(())
+}
+
+fn escrow_fulfill(
+    cli: &Cli,
+    bank_client: &BankClient,
+    escrow_program_id: &Pubkey,
+    escrow: &Pubkey,
+    from: &Pubkey,
+    to: &Pubkey,
+) -> Result<(), String> {
+    let taker = cli.signer()?;
+    let state = get_escrow(bank_client, escrow)?;
+    let vault = bank_client.get_account(&state.vault)?;
+    let receive_bank = bank_client.get_account(&state.maker_receive_account)?.bank;
+    if bank_client.get_account(from)?.bank != receive_bank {
+        return Err(format!("{} isn't an account of {}", from, receive_bank));
+    }
+    if bank_client.get_account(to)?.bank != vault.bank {
+        return Err(format!("{} isn't an account of {}", to, vault.bank));
+    }
+
+    // The vault's amount goes in too, so the offer can't change before this lands.
+    let instruction = escrow_instruction::exchange(
+        escrow_program_id,
+        &bank_client.program_id,
+        &taker.pubkey(),
+        from,
+        to,
+        &state.maker,
+        &state.maker_receive_account,
+        escrow,
+        &state.vault,
+        &vault.bank,
+        &receive_bank,
+        vault.amount,
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&taker], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_balance(bank_client, &receive_bank, from)?;
+        print_balance(bank_client, &vault.bank, to)?;
+    }
+    Ok(())
+}
+
+fn escrow_cancel(
+    cli: &Cli,
+    bank_client: &BankClient,
+    escrow_program_id: &Pubkey,
+    escrow: &Pubkey,
+    to: &Pubkey,
+) -> Result<(), String> {
+    let maker = cli.signer()?;
+    let state = get_escrow(bank_client, escrow)?;
+    if state.maker != maker.pubkey() {
+        return Err(format!("{} was opened by {}", escrow, state.maker));
+    }
+    let bank = bank_client.get_account(&state.vault)?.bank;
+
+    let instruction = escrow_instruction::cancel(
+        escrow_program_id,
+        &bank_client.program_id,
+        &maker.pubkey(),
+        to,
+        escrow,
+        &state.vault,
+        &bank,
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&maker], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_balance(bank_client, &bank, to)?;
+    }
+    Ok(())
+}
+
+fn escrow_show(
+    bank_client: &BankClient,
+    escrow_program_id: &Pubkey,
+    escrow: &Pubkey,
+) -> Result<(), String> {
+    let state = get_escrow(bank_client, escrow)?;
+    let vault = bank_client.get_account(&state.vault)?;
+    let deposit_bank = bank_client.get_bank(&vault.bank)?;
+    let receive_bank = bank_client.get_account(&state.maker_receive_account)?.bank;
+    let decimals = bank_client.get_bank(&receive_bank)?.decimals;
+    let (authority, _) = escrow_instruction::vault_authority(escrow_program_id, escrow);
+    println!("escrow: {}", escrow);
+    println!("maker: {}", state.maker);
+    println!(
+        "offers: {} of bank {}",
+        ui_amount(vault.amount, deposit_bank.decimals),
+        vault.bank
+    );
+    println!(
+        "asks: {} of bank {}",
+        ui_amount(state.expected_amount, decimals),
+        receive_bank
+    );
+    println!("paid into: {}", state.maker_receive_account);
+    println!("vault: {}", state.vault);
+    println!("vault authority: {}", authority);
+    Ok(())
+}
+
+fn get_escrow(bank_client: &BankClient, escrow: &Pubkey) -> Result<Escrow, String> {
+    let data = match bank_client.rpc.get_account_data(escrow) {
+        Ok(d) => d,
+        Err(e) => return Err(format!("get escrow account failed: {}", e)),
+    };
+    Escrow::unpack(&data).map_err(|e| e.to_string())
+}
+
+fn faucet_create(
+    cli: &Cli,
+    bank_client: &BankClient,
+    faucet_program_id: &Pubkey,
+    bank: &Pubkey,
+    daily_cap: u64,
+) -> Result<(), String> {
+    let admin = cli.signer()?;
+    let faucet = Keypair::new();
+    let vault = Keypair::new();
+    let instructions = vec![
+        create_account(bank_client, &admin, &faucet, Faucet::LEN, faucet_program_id)?,
+        create_account(
+            bank_client,
+            &admin,
+            &vault,
+            Account::LEN,
+            &bank_client.program_id,
+        )?,
+        faucet_instruction::initialize_faucet(
+            faucet_program_id,
+            &bank_client.program_id,
+            &admin.pubkey(),
+            &faucet.pubkey(),
+            &vault.pubkey(),
+            bank,
+            daily_cap,
+        )
+        .map_err(|e| e.to_string())?,
+    ];
+    if let Some(signature) =
+        bank_client.send(&instructions, &[&admin, &faucet, &vault], cli.send_mode())?
+    {
+        println!("signature: {}", signature);
+        println!("faucet: {}", faucet.pubkey());
+        println!("vault: {}", vault.pubkey());
+        println!("fund it with: bank-cli mint --to {}", vault.pubkey());
+    }
+    Ok(())
+}
+
+fn faucet_tap(
+    cli: &Cli,
+    bank_client: &BankClient,
+    faucet_program_id: &Pubkey,
+    faucet: &Pubkey,
+    to: &Pubkey,
+    amount: Option<u64>,
+) -> Result<(), String> {
+    let user = cli.signer()?;
+    let data = match bank_client.rpc.get_account_data(faucet) {
+        Ok(d) => d,
+        Err(e) => return Err(format!("get faucet account failed: {}", e)),
+    };
+    let state = Faucet::unpack(&data).map_err(|e| e.to_string())?;
+    let amount = amount.unwrap_or(state.daily_cap);
+    if amount > state.daily_cap {
+        return Err(format!(
+            "the faucet hands out at most {} a day",
+            state.daily_cap
+        ));
+    }
+
+    let bank = bank_client.get_account(to)?.bank;
+    let instruction = faucet_instruction::tap(
+        faucet_program_id,
+        &bank_client.program_id,
+        &bank,
+        &user.pubkey(),
+        faucet,
+        &state.vault,
+        to,
+        amount,
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&user], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_balance(bank_client, &bank, to)?;
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn vesting_create(
+    cli: &Cli,
+    bank_client: &BankClient,
+    vesting_program_id: &Pubkey,
+    from: &Pubkey,
+    beneficiary: &Pubkey,
+    amount: u64,
+    start: Option<i64>,
+    cliff: i64,
+    duration: i64,
+) -> Result<(), String> {
+    let grantor = cli.signer()?;
+    let source = bank_client.get_account(from)?;
+    if source.owner != grantor.pubkey() {
+        return Err(format!("{} is owned by {}", from, source.owner));
+    }
+    let start = match start {
+        Some(start) => start,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_secs() as i64,
+    };
+    let (cliff_ts, end_ts) = (start + cliff, start + duration);
+    if !Vesting::is_valid_schedule(start, cliff_ts, end_ts) {
+        return Err("--cliff must be within --duration, and --duration positive".to_string());
+    }
+
+    let vesting = Keypair::new();
+    let vault = Keypair::new();
+    let mut instructions = vec![
+        create_account(
+            bank_client,
+            &grantor,
+            &vesting,
+            Vesting::LEN,
+            vesting_program_id,
+        )?,
+        create_account(
+            bank_client,
+            &grantor,
+            &vault,
+            Account::LEN,
+            &bank_client.program_id,
+        )?,
+    ];
+    instructions.push(
+        vesting_instruction::create_vesting(
+            vesting_program_id,
+            &bank_client.program_id,
+            &grantor.pubkey(),
+            &vesting.pubkey(),
+            &vault.pubkey(),
+            from,
+            &source.bank,
+            beneficiary,
+            amount,
+            start,
+            cliff_ts,
+            end_ts,
+        )
+        .map_err(|e| e.to_string())?,
+    );
+    if let Some(signature) = bank_client.send(
+        &instructions,
+        &[&grantor, &vesting, &vault],
+        cli.send_mode(),
+    )? {
+        println!("signature: {}", signature);
+        println!("vesting: {}", vesting.pubkey());
+        println!("vault: {}", vault.pubkey());
+        print_balance(bank_client, &source.bank, from)?;
+    }
+    Ok(())
+}
+
+fn vesting_claim(
+    cli: &Cli,
+    bank_client: &BankClient,
+    vesting_program_id: &Pubkey,
+    vesting: &Pubkey,
+    to: &Pubkey,
+) -> Result<(), String> {
+    let beneficiary = cli.signer()?;
+    let data = match bank_client.rpc.get_account_data(vesting) {
+        Ok(d) => d,
+        Err(e) => return Err(format!("get vesting account failed: {}", e)),
+    };
+    let state = Vesting::unpack(&data).map_err(|e| e.to_string())?;
+    if state.beneficiary != beneficiary.pubkey() {
+        return Err(format!("{} vests to {}", vesting, state.beneficiary));
+    }
+
+    let bank = bank_client.get_account(to)?.bank;
+    let instruction = vesting_instruction::claim(
+        vesting_program_id,
+        &bank_client.program_id,
+        &bank,
+        &beneficiary.pubkey(),
+        vesting,
+        &state.vault,
+        to,
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&beneficiary], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_balance(bank_client, &bank, to)?;
+        print_balance(bank_client, &bank, &state.vault)?;
+    }
+    Ok(())
+}
+
+/// Claims what the grants and streams hold for --keypair into `to` once it reaches
+/// `threshold`, then, with `watch`, polls every `interval` for more until every schedule
+/// has ended, and so has been emptied.
+#[allow(clippy::too_many_arguments)]
+fn claim(
+    cli: &Cli,
+    bank_client: &BankClient,
+    vesting_program_id: Option<&Pubkey>,
+    stream_program_id: Option<&Pubkey>,
+    vestings: &[Pubkey],
+    streams: &[Pubkey],
+    to: &Pubkey,
+    threshold: u64,
+    watch: bool,
+    interval: Duration,
+) -> Result<(), String> {
+    if vestings.is_empty() && streams.is_empty() {
+        return Err("nothing to claim: pass --vesting or --stream".to_string());
+    }
+    let beneficiary = cli.signer()?;
+    let bank = bank_client.get_account(to)?.bank;
+
+    loop {
+        // clap requires the program of every --vesting and --stream given.
+        let mut claimables = Vec::with_capacity(vestings.len() + streams.len());
+        for vesting in vestings {
+            let data = get_account_data(bank_client, vesting)?;
+            claimables.push(Claimable::vesting(
+                *vesting_program_id.unwrap(),
+                *vesting,
+                &data,
+            )?);
+        }
+        for stream in streams {
+            let data = get_account_data(bank_client, stream)?;
+            claimables.push(Claimable::stream(
+                *stream_program_id.unwrap(),
+                *stream,
+                &data,
+            )?);
+        }
+        for claimable in claimables.iter() {
+            if claimable.beneficiary() != beneficiary.pubkey() {
+                return Err(format!(
+                    "{} pays {}",
+                    claimable.address,
+                    claimable.beneficiary()
+                ));
+            }
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_secs() as i64;
+        for claimable in claimables.iter() {
+            debug!(
+                address = %claimable.address,
+                claimable = claimable.amount(now),
+                ended = claimable.has_ended(now),
+                "polled"
+            );
+        }
+        let due = claim::due(&claimables, now, threshold);
+        if !due.is_empty() {
+            let mut instructions = Vec::with_capacity(due.len());
+            for (claimable, amount) in due {
+                info!(address = %claimable.address, amount, "claiming");
+                instructions.push(claimable.instruction(&bank_client.program_id, &bank, to)?);
+            }
+            if let Some(signature) =
+                bank_client.send(&instructions, &[&beneficiary], cli.send_mode())?
+            {
+                println!("signature: {}", signature);
+                print_balance(bank_client, &bank, to)?;
+            }
+        }
+
+        // An ended schedule holding anything was just claimed whatever the threshold.
+        if !watch || claimables.iter().all(|claimable| claimable.has_ended(now)) {
+            return Ok(());
+        }
+        sleep(interval);
+    }
+}
+
+fn get_account_data(bank_client: &BankClient, address: &Pubkey) -> Result<Vec<u8>, String> {
+    bank_client
+        .rpc
+        .get_account_data(address)
+        .map_err(|e| format!("get {} failed: {}", address, e))
+}
+
+fn raw(
+    cli: &Cli,
+    bank_client: &BankClient,
+    idl: &Path,
+    ix: &str,
+    args: &[String],
+    accounts: &[String],
+) -> Result<(), String> {
+    let signer = cli.signer()?;
+    let instruction = Idl::load(idl)?.instruction(ix)?.build(
+        &bank_client.program_id,
+        &idl::parse_pairs(args)?,
+        &idl::parse_pairs(accounts)?,
+        &signer.pubkey(),
+    )?;
+    if let Some(account) = instruction
+        .accounts
+        .iter()
+        .find(|meta| meta.is_signer && meta.pubkey != signer.pubkey())
+    {
+        return Err(format!(
+            "{} has to sign, but only --keypair does here",
+            account.pubkey
+        ));
+    }
+    if let Some(signature) = bank_client.send(&[instruction], &[&signer], cli.send_mode())? {
+        println!("signature: {}", signature);
+    }
+    Ok(())
+}
+
+fn pay_parse(cli: &Cli, bank_client: &BankClient, url: &str, from: &Pubkey) -> Result<(), String> {
+    let request = TransferRequest::parse(url)?;
+    let amount = request.amount.as_deref().ok_or_else(|| {
+        "the request leaves the amount to the payer; pay with `bank-cli transfer`".to_string()
+    })?;
+    let bank = bank_client.get_account(&request.recipient)?.bank;
+    if let Some(requested) = request.bank {
+        if requested != bank {
+            return Err(format!(
+                "{} holds tokens of bank {}, not {}",
+                request.recipient, bank, requested
+            ));
+        }
+    }
+    let decimals = bank_client.get_bank(&bank)?.decimals;
+    let amount = parse_ui_amount(amount, decimals)?;
+    if let Some(label) = &request.label {
+        println!("label: {}", label);
+    }
+    if let Some(message) = &request.message {
+        println!("message: {}", message);
+    }
+    println!("pay to: {}", request.recipient);
+    println!("amount: {}", ui_amount(amount, decimals));
+
+    let authority = cli.signer()?;
+    let mut transfer = instruction::transfer(
+        &bank_client.program_id,
+        &bank,
+        from,
+        &request.recipient,
+        &authority.pubkey(),
+        amount,
+    )
+    .map_err(|e| e.to_string())?;
+    pay::attach_references(&mut transfer, &request.references);
+    // Solana Pay has the memo come before the transfer.
+    let mut instructions = Vec::new();
+    if let Some(memo) = &request.memo {
+        instructions.push(util::memo_instruction(memo, &[&authority.pubkey()]));
+    }
+    instructions.push(transfer);
+    if let Some(signature) = bank_client.send(&instructions, &[&authority], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_balance(bank_client, &bank, from)?;
+    }
+    Ok(())
+}
+
+fn pay_request_url(
+    bank_client: &BankClient,
+    to: &Pubkey,
+    amount: u64,
+    references: &[Pubkey],
+    label: Option<&str>,
+    message: Option<&str>,
+    memo: Option<&str>,
+) -> Result<(), String> {
+    let bank = bank_client.get_account(to)?.bank;
+    let decimals = bank_client.get_bank(&bank)?.decimals;
+    let request = TransferRequest {
+        amount: Some(ui_amount(amount, decimals)),
+        bank: Some(bank),
+        references: references.to_vec(),
+        label: label.map(str::to_string),
+        message: message.map(str::to_string),
+        memo: memo.map(str::to_string),
+        ..TransferRequest::new(*to)
+    };
+    println!("pay url: {}", request.to_url());
+    Ok(())
+}
+
+/// Employees listed per `RunPayroll`, two accounts each, to stay within a transaction.
+const PAYROLL_BATCH: usize = 10;
+
+fn payroll_run(
+    cli: &Cli,
+    bank_client: &BankClient,
+    payroll_program_id: &Pubkey,
+    payroll: &Pubkey,
+) -> Result<(), String> {
+    let cranker = cli.signer()?;
+    let data = match bank_client.rpc.get_account_data(payroll) {
+        Ok(d) => d,
+        Err(e) => return Err(format!("get payroll account failed: {}", e)),
+    };
+    let state = Payroll::unpack(&data).map_err(|e| e.to_string())?;
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(Employee::LEN as u64),
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                EMPLOYEE_PAYROLL_OFFSET,
+                payroll.to_bytes().to_vec(),
+            )),
+        ]),
+        ..RpcProgramAccountsConfig::default()
+    };
+    let employees = match bank_client
+        .rpc
+        .get_program_accounts_with_config(payroll_program_id, config)
+    {
+        Ok(a) => a,
+        Err(e) => return Err(format!("get employee accounts failed: {}", e)),
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?
+        .as_secs() as i64;
+    let due: Vec<Pubkey> = employees
+        .iter()
+        .filter_map(|(_, account)| Employee::unpack(&account.data).ok())
+        .filter(|employee| employee.periods_due(now) > 0)
+        .map(|employee| employee.destination)
+        .collect();
+    if due.is_empty() {
+        println!("no one is due");
+        return Ok(());
+    }
+
+    for destinations in due.chunks(PAYROLL_BATCH) {
+        let instruction = payroll_instruction::run_payroll(
+            payroll_program_id,
+            &bank_client.program_id,
+            &state.bank,
+            payroll,
+            &state.vault,
+            destinations,
+        )
+        .map_err(|e| e.to_string())?;
+        if let Some(signature) = bank_client.send(&[instruction], &[&cranker], cli.send_mode())? {
+            println!("signature: {}", signature);
+            for destination in destinations.iter() {
+                println!("paid: {}", destination);
+            }
+        }
+    }
+    print_balance(bank_client, &state.bank, &state.vault)
+}
+
+fn get_wrapper(bank_client: &BankClient, wrapper: &Pubkey) -> Result<Wrapper, String> {
+    let data = match bank_client.rpc.get_account_data(wrapper) {
+        Ok(d) => d,
+        Err(e) => return Err(format!("get wrapper account failed: {}", e)),
+    };
+    Wrapper::unpack(&data).map_err(|e| e.to_string())
+}
+
+fn wrapper_create(
+    cli: &Cli,
+    bank_client: &BankClient,
+    wrapper_program_id: &Pubkey,
+    mint: &Pubkey,
+) -> Result<(), String> {
+    let payer = cli.signer()?;
+    let wrapper = Keypair::new();
+    let bank = Keypair::new();
+    let (authority, _) =
+        wrapper_instruction::wrapper_authority(wrapper_program_id, &wrapper.pubkey());
+    let vault = get_associated_token_address(&authority, mint);
+    let instructions = vec![
+        create_associated_token_account_idempotent(
+            &payer.pubkey(),
+            &authority,
+            mint,
+            &spl_token::id(),
+        ),
+        create_account(
+            bank_client,
+            &payer,
+            &wrapper,
+            Wrapper::LEN,
+            wrapper_program_id,
+        )?,
+        create_account(
+            bank_client,
+            &payer,
+            &bank,
+            Bank::LEN,
+            &bank_client.program_id,
+        )?,
+        wrapper_instruction::create_wrapper(
+            wrapper_program_id,
+            &bank_client.program_id,
+            &wrapper.pubkey(),
+            &bank.pubkey(),
+            mint,
+            &vault,
+        )
+        .map_err(|e| e.to_string())?,
+    ];
+    if let Some(signature) =
+        bank_client.send(&instructions, &[&payer, &wrapper, &bank], cli.send_mode())?
+    {
+        println!("signature: {}", signature);
+        println!("wrapper: {}", wrapper.pubkey());
+        println!("bank: {}", bank.pubkey());
+        println!("vault: {}", vault);
+    }
+    Ok(())
+}
+
+fn wrapper_wrap(
+    cli: &Cli,
+    bank_client: &BankClient,
+    wrapper_program_id: &Pubkey,
+    wrapper: &Pubkey,
+    to: &Pubkey,
+    amount: u64,
+) -> Result<(), String> {
+    let owner = cli.signer()?;
+    let state = get_wrapper(bank_client, wrapper)?;
+    let source = get_associated_token_address(&owner.pubkey(), &state.mint);
+    let instruction = wrapper_instruction::wrap(
+        wrapper_program_id,
+        &bank_client.program_id,
+        wrapper,
+        &state,
+        &source,
+        &owner.pubkey(),
+        to,
+        amount,
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&owner], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_balance(bank_client, &state.bank, to)?;
+    }
+    Ok(())
+}
+
+fn wrapper_unwrap(
+    cli: &Cli,
+    bank_client: &BankClient,
+    wrapper_program_id: &Pubkey,
+    wrapper: &Pubkey,
+    from: &Pubkey,
+    amount: u64,
+) -> Result<(), String> {
+    let owner = cli.signer()?;
+    let state = get_wrapper(bank_client, wrapper)?;
+    let destination = get_associated_token_address(&owner.pubkey(), &state.mint);
+    let instructions = vec![
+        create_associated_token_account_idempotent(
+            &owner.pubkey(),
+            &owner.pubkey(),
+            &state.mint,
+            &spl_token::id(),
+        ),
+        wrapper_instruction::unwrap(
+            wrapper_program_id,
+            &bank_client.program_id,
+            wrapper,
+            &state,
+            from,
+            &owner.pubkey(),
+            &destination,
+            amount,
+        )
+        .map_err(|e| e.to_string())?,
+    ];
+    if let Some(signature) = bank_client.send(&instructions, &[&owner], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_balance(bank_client, &state.bank, from)?;
+        println!("released to: {}", destination);
+    }
+    Ok(())
+}
+
+fn get_tip_jar(bank_client: &BankClient, jar: &Pubkey) -> Result<TipJar, String> {
+    let data = match bank_client.rpc.get_account_data(jar) {
+        Ok(d) => d,
+        Err(e) => return Err(format!("get tip jar account failed: {}", e)),
+    };
+    TipJar::unpack(&data).map_err(|e| e.to_string())
+}
+
+fn tip_create(
+    cli: &Cli,
+    bank_client: &BankClient,
+    tipping_program_id: &Pubkey,
+    bank: &Pubkey,
+) -> Result<(), String> {
+    let creator = cli.signer()?;
+    let vault = Keypair::new();
+    let (jar, _) = tipping_instruction::jar_address(tipping_program_id, &creator.pubkey(), bank);
+    let instructions = vec![
+        create_account(
+            bank_client,
+            &creator,
+            &vault,
+            Account::LEN,
+            &bank_client.program_id,
+        )?,
+        tipping_instruction::create_jar(
+            tipping_program_id,
+            &bank_client.program_id,
+            &creator.pubkey(),
+            &vault.pubkey(),
+            bank,
+        )
+        .map_err(|e| e.to_string())?,
+    ];
+    if let Some(signature) =
+        bank_client.send(&instructions, &[&creator, &vault], cli.send_mode())?
+    {
+        println!("signature: {}", signature);
+        println!("jar: {}", jar);
+        println!("vault: {}", vault.pubkey());
+    }
+    Ok(())
+}
+
+fn tip_send(
+    cli: &Cli,
+    bank_client: &BankClient,
+    tipping_program_id: &Pubkey,
+    creator: &Pubkey,
+    from: &Pubkey,
+    amount: u64,
+) -> Result<(), String> {
+    let tipper = cli.signer()?;
+    let bank = bank_client.get_account(from)?.bank;
+    let (jar, _) = tipping_instruction::jar_address(tipping_program_id, creator, &bank);
+    let state = get_tip_jar(bank_client, &jar)
+        .map_err(|e| format!("{} has no tip jar in bank {}: {}", creator, bank, e))?;
+    let instruction = tipping_instruction::tip(
+        tipping_program_id,
+        &bank_client.program_id,
+        &bank,
+        &tipper.pubkey(),
+        &jar,
+        from,
+        &state.vault,
+        amount,
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&tipper], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_balance(bank_client, &bank, from)?;
+    }
+    Ok(())
+}
+
+fn tip_show(
+    bank_client: &BankClient,
+    tipping_program_id: &Pubkey,
+    creator: &Pubkey,
+    bank: &Pubkey,
+) -> Result<(), String> {
+    let (jar, _) = tipping_instruction::jar_address(tipping_program_id, creator, bank);
+    let state = get_tip_jar(bank_client, &jar)?;
+    println!("jar: {}", jar);
+    println!("tips: {}", state.tip_count);
+    println!("tippers: {}", state.tipper_count);
+    println!("total tipped: {}", state.total_tipped);
+    print_balance(bank_client, bank, &state.vault)
+}
+
+fn tip_withdraw(
+    cli: &Cli,
+    bank_client: &BankClient,
+    tipping_program_id: &Pubkey,
+    bank: &Pubkey,
+    to: &Pubkey,
+    amount: Option<u64>,
+) -> Result<(), String> {
+    let creator = cli.signer()?;
+    let (jar, _) = tipping_instruction::jar_address(tipping_program_id, &creator.pubkey(), bank);
+    let state = get_tip_jar(bank_client, &jar)?;
+    let amount = match amount {
+        Some(amount) => amount,
+        None => bank_client.get_account(&state.vault)?.amount,
+    };
+    if amount == 0 {
+        return Err("the tip jar is empty".to_string());
+    }
+    let instruction = tipping_instruction::withdraw(
+        tipping_program_id,
+        &bank_client.program_id,
+        bank,
+        &creator.pubkey(),
+        &jar,
+        &state.vault,
+        to,
+        amount,
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&creator], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_balance(bank_client, bank, to)?;
+    }
+    Ok(())
+}
+
+fn get_invoice(bank_client: &BankClient, invoice: &Pubkey) -> Result<Invoice, String> {
+    let data = match bank_client.rpc.get_account_data(invoice) {
+        Ok(d) => d,
+        Err(e) => return Err(format!("get invoice account failed: {}", e)),
+    };
+    Invoice::unpack(&data).map_err(|e| e.to_string())
+}
+
+fn print_pay_url(
+    bank_client: &BankClient,
+    invoice: &Pubkey,
+    state: &Invoice,
+) -> Result<(), String> {
+    let bank = bank_client.get_account(&state.destination)?.bank;
+    let decimals = bank_client.get_bank(&bank)?.decimals;
+    println!("pay url: {}", pay_url(invoice, state, decimals));
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn invoice_create(
+    cli: &Cli,
+    bank_client: &BankClient,
+    invoice_program_id: &Pubkey,
+    to: &Pubkey,
+    amount: u64,
+    memo: &str,
+    expires_in: i64,
+    id: Option<u64>,
+) -> Result<(), String> {
+    let merchant = cli.signer()?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?;
+    let id = id.unwrap_or(now.as_millis() as u64);
+    let expires_ts = now.as_secs() as i64 + expires_in;
+    let (invoice, _) =
+        invoice_instruction::invoice_address(invoice_program_id, &merchant.pubkey(), id);
+    let instruction = invoice_instruction::create_invoice(
+        invoice_program_id,
+        &merchant.pubkey(),
+        to,
+        id,
+        amount,
+        expires_ts,
+        memo,
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&merchant], cli.send_mode())? {
+        println!("signature: {}", signature);
+        println!("invoice: {}", invoice);
+        let state = get_invoice(bank_client, &invoice)?;
+        print_pay_url(bank_client, &invoice, &state)?;
+    }
+    Ok(())
+}
+
+fn invoice_pay(
+    cli: &Cli,
+    bank_client: &BankClient,
+    invoice_program_id: &Pubkey,
+    invoice: &Pubkey,
+    from: &Pubkey,
+) -> Result<(), String> {
+    let payer = cli.signer()?;
+    let state = get_invoice(bank_client, invoice)?;
+    if state.paid {
+        return Err(format!(
+            "{} has already been paid by {}",
+            invoice, state.payer
+        ));
+    }
+    let bank = bank_client.get_account(from)?.bank;
+    let instruction = invoice_instruction::pay(
+        invoice_program_id,
+        &bank_client.program_id,
+        &bank,
+        &payer.pubkey(),
+        invoice,
+        from,
+        &state.destination,
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&payer], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_balance(bank_client, &bank, from)?;
+    }
+    Ok(())
+}
+
+fn invoice_status(bank_client: &BankClient, invoice: &Pubkey) -> Result<(), String> {
+    let state = get_invoice(bank_client, invoice)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?
+        .as_secs() as i64;
+    println!("merchant: {}", state.merchant);
+    println!("id: {}", state.id);
+    println!("pay to: {}", state.destination);
+    println!("amount: {}", state.amount);
+    if !state.memo.is_empty() {
+        println!("memo: {}", state.memo);
+    }
+    println!("expires: {}", state.expires_ts);
+    if state.paid {
+        println!("status: paid by {} at {}", state.payer, state.paid_ts);
+    } else if state.is_expired(now) {
+        println!("status: expired");
+    } else {
+        println!("status: open");
+        print_pay_url(bank_client, invoice, &state)?;
+    }
+    Ok(())
+}
+
+fn get_board(bank_client: &BankClient, board: &Pubkey) -> Result<Board, String> {
+    let data = match bank_client.rpc.get_account_data(board) {
+        Ok(d) => d,
+        Err(e) => return Err(format!("get board account failed: {}", e)),
+    };
+    Board::unpack(&data).map_err(|e| e.to_string())
+}
+
+fn board_create(
+    cli: &Cli,
+    bank_client: &BankClient,
+    board_program_id: &Pubkey,
+) -> Result<(), String> {
+    let payer = cli.signer()?;
+    let board = Keypair::new();
+    let instructions = vec![
+        create_account(bank_client, &payer, &board, Board::LEN, board_program_id)?,
+        board_instruction::create_board(board_program_id, &board.pubkey())
+            .map_err(|e| e.to_string())?,
+    ];
+    if let Some(signature) = bank_client.send(&instructions, &[&payer, &board], cli.send_mode())? {
+        println!("signature: {}", signature);
+        println!("board: {}", board.pubkey());
+    }
+    Ok(())
+}
+
+fn board_post(
+    cli: &Cli,
+    bank_client: &BankClient,
+    board_program_id: &Pubkey,
+    board: &Pubkey,
+    message: &str,
+) -> Result<(), String> {
+    let author = cli.signer()?;
+    // Someone posting in between takes this index and fails the transaction; post again.
+    let index = get_board(bank_client, board)?.post_count;
+    let instruction =
+        board_instruction::post(board_program_id, &author.pubkey(), board, index, message)
+            .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&author], cli.send_mode())? {
+        let (post, _) = board_instruction::post_address(board_program_id, board, index);
+        println!("signature: {}", signature);
+        println!("post: {} (#{})", post, index);
+    }
+    Ok(())
+}
+
+fn board_read(
+    bank_client: &BankClient,
+    board_program_id: &Pubkey,
+    board: &Pubkey,
+    page: u64,
+    per_page: u64,
+) -> Result<(), String> {
+    let post_count = get_board(bank_client, board)?.post_count;
+    println!("posts: {}", post_count);
+    let indices = page_indices(post_count, page, per_page);
+    if indices.is_empty() {
+        return Ok(());
+    }
+    let addresses: Vec<Pubkey> = indices
+        .iter()
+        .map(|index| board_instruction::post_address(board_program_id, board, *index).0)
+        .collect();
+    let accounts = match bank_client.rpc.get_multiple_accounts(&addresses) {
+        Ok(a) => a,
+        Err(e) => return Err(format!("get post accounts failed: {}", e)),
+    };
+    for (index, account) in indices.iter().zip(accounts) {
+        let account = account.ok_or_else(|| format!("post #{} not found", index))?;
+        let post = Post::unpack(&account.data).map_err(|e| e.to_string())?;
+        println!(
+            "#{} {} at {}: {}",
+            post.index, post.author, post.posted_ts, post.message
+        );
+    }
+    Ok(())
+}
+
+fn read_airdrop(csv: &Path) -> Result<Airdrop, String> {
+    let text = std::fs::read_to_string(csv)
+        .map_err(|e| format!("read {} failed: {}", csv.display(), e))?;
+    Airdrop::from_csv(&text)
+}
+
+fn airdrop_create(
+    cli: &Cli,
+    bank_client: &BankClient,
+    distributor_program_id: &Pubkey,
+    bank: &Pubkey,
+    csv: &Path,
+) -> Result<(), String> {
+    let bank_owner = cli.signer()?;
+    check_bank_owner(bank_client, bank, &bank_owner)?;
+    let airdrop = read_airdrop(csv)?;
+    let total = airdrop
+        .total()
+        .ok_or_else(|| "the allocations add up to more than a u64".to_string())?;
+    let recipients = airdrop.allocations.len() as u64;
+
+    let distributor = Keypair::new();
+    let bitmap = Keypair::new();
+    let vault = Keypair::new();
+    let instructions = vec![
+        create_account(
+            bank_client,
+            &bank_owner,
+            &distributor,
+            Distributor::LEN,
+            distributor_program_id,
+        )?,
+        create_account(
+            bank_client,
+            &bank_owner,
+            &bitmap,
+            bitmap_len(recipients),
+            distributor_program_id,
+        )?,
+        create_account(
+            bank_client,
+            &bank_owner,
+            &vault,
+            Account::LEN,
+            &bank_client.program_id,
+        )?,
+        distributor_instruction::create_distributor(
+            distributor_program_id,
+            &bank_client.program_id,
+            &bank_owner.pubkey(),
+            &distributor.pubkey(),
+            &bitmap.pubkey(),
+            &vault.pubkey(),
+            bank,
+            airdrop.root(),
+            recipients,
+            total,
+        )
+        .map_err(|e| e.to_string())?,
+    ];
+    if let Some(signature) = bank_client.send(
+        &instructions,
+        &[&bank_owner, &distributor, &bitmap, &vault],
+        cli.send_mode(),
+    )? {
+        println!("signature: {}", signature);
+        println!("distributor: {}", distributor.pubkey());
+        println!("root: {}", Hash::new_from_array(airdrop.root()));
+        println!("recipients: {}", recipients);
+        print_balance(bank_client, bank, &vault.pubkey())?;
+    }
+    Ok(())
+}
+
+fn airdrop_claim(
+    cli: &Cli,
+    bank_client: &BankClient,
+    distributor_program_id: &Pubkey,
+    distributor: &Pubkey,
+    csv: &Path,
+    to: &Pubkey,
+) -> Result<(), String> {
+    let recipient = cli.signer()?;
+    let data = match bank_client.rpc.get_account_data(distributor) {
+        Ok(d) => d,
+        Err(e) => return Err(format!("get distributor account failed: {}", e)),
+    };
+    let state = Distributor::unpack(&data).map_err(|e| e.to_string())?;
+    let airdrop = read_airdrop(csv)?;
+    if airdrop.root() != state.root {
+        return Err(format!(
+            "{} isn't the CSV {} was created from",
+            csv.display(),
+            distributor
+        ));
+    }
+    let claim = airdrop
+        .claim(&recipient.pubkey())
+        .ok_or_else(|| format!("{} isn't in the airdrop", recipient.pubkey()))?;
+
+    let instruction = distributor_instruction::claim(
+        distributor_program_id,
+        &bank_client.program_id,
+        &state.bank,
+        &recipient.pubkey(),
+        distributor,
+        &state.bitmap,
+        &state.vault,
+        to,
+        claim.index,
+        claim.amount,
+        claim.proof,
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&recipient], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_balance(bank_client, &state.bank, to)?;
+    }
+    Ok(())
+}
+
+fn airdrop_proof(csv: &Path, recipient: &Pubkey) -> Result<(), String> {
+    let airdrop = read_airdrop(csv)?;
+    let claim = airdrop
+        .claim(recipient)
+        .ok_or_else(|| format!("{} isn't in the airdrop", recipient))?;
+    println!("index: {}", claim.index);
+    println!("amount: {}", claim.amount);
+    for hash in claim.proof {
+        println!("proof: {}", Hash::new_from_array(hash));
+    }
+    Ok(())
+}
+
+fn stats(
+    bank_client: &BankClient,
+    start_slot: u64,
+    end_slot: Option<u64>,
+    format: ReportFormat,
+) -> Result<(), String> {
+    let end_slot = match end_slot {
+        Some(slot) => slot,
+        None => bank_client
+            .rpc
+            .get_slot()
+            .map_err(|e| format!("get slot failed: {}", e))?,
+    };
+    if end_slot < start_slot {
+        return Err("--end-slot is before --start-slot".to_string());
+    }
+    let report = client::stats::scan(
+        &bank_client.rpc,
+        &bank_client.program_id,
+        start_slot,
+        end_slot,
+    )?;
+    match format {
+        ReportFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?
+        ),
+        ReportFormat::Csv => print!("{}", report.to_csv()),
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn swap(
+    cli: &Cli,
+    bank_client: &BankClient,
+    swap_program_id: &Pubkey,
+    pool: &Pubkey,
+    from: &Pubkey,
+    to: &Pubkey,
+    amount: u64,
+    min_out: Option<u64>,
+) -> Result<(), String> {
+    let trader = cli.signer()?;
+    let data = match bank_client.rpc.get_account_data(pool) {
+        Ok(d) => d,
+        Err(e) => return Err(format!("get pool account failed: {}", e)),
+    };
+    let state = Pool::unpack(&data).map_err(|e| e.to_string())?;
+    let source = bank_client.get_account(from)?;
+    let (reserve_in, reserve_out) = match state.reserves_for(&source.bank) {
+        Some(reserves) => reserves,
+        None => return Err(format!("{} does not trade {}", pool, source.bank)),
+    };
+    let destination = bank_client.get_account(to)?;
+    if destination.bank == source.bank {
+        return Err(format!("{} and {} hold the same bank", from, to));
+    }
+
+    let quote = curve::swap_output(
+        amount,
+        bank_client.get_account(&reserve_in)?.amount,
+        bank_client.get_account(&reserve_out)?.amount,
+        state.fee_bps,
+    )
+    .ok_or_else(|| format!("{} has no liquidity", pool))?;
+    println!("quote: {}", quote);
+    let instruction = swap_instruction::swap(
+        swap_program_id,
+        &bank_client.program_id,
+        &source.bank,
+        &destination.bank,
+        &trader.pubkey(),
+        pool,
+        from,
+        to,
+        &reserve_in,
+        &reserve_out,
+        amount,
+        min_out.unwrap_or(quote),
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&trader], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_balance(bank_client, &source.bank, from)?;
+        print_balance(bank_client, &destination.bank, to)?;
+    }
+    Ok(())
+}
+
+fn get_multisig(bank_client: &BankClient, multisig: &Pubkey) -> Result<Multisig, String> {
+    let data = match bank_client.rpc.get_account_data(multisig) {
+        Ok(d) => d,
+        Err(e) => return Err(format!("get multisig account failed: {}", e)),
+    };
+    Multisig::unpack(&data).map_err(|e| e.to_string())
+}
+
+fn get_proposal(bank_client: &BankClient, proposal: &Pubkey) -> Result<Proposal, String> {
+    let data = match bank_client.rpc.get_account_data(proposal) {
+        Ok(d) => d,
+        Err(e) => return Err(format!("get proposal account failed: {}", e)),
+    };
+    Proposal::unpack(&data).map_err(|e| e.to_string())
+}
+
+fn print_approvals(bank_client: &BankClient, proposal: &Pubkey) -> Result<(), String> {
+    let state = get_proposal(bank_client, proposal)?;
+    let multisig = get_multisig(bank_client, &state.multisig)?;
+    println!(
+        "approvals: {}/{}{}",
+        state.approval_count(),
+        multisig.threshold,
+        if state.executed { " (executed)" } else { "" }
+    );
+    Ok(())
+}
+
+fn multisig_create(
+    cli: &Cli,
+    bank_client: &BankClient,
+    multisig_program_id: &Pubkey,
+    owners: &[Pubkey],
+    threshold: u8,
+) -> Result<(), String> {
+    let payer = cli.signer()?;
+    let multisig = Keypair::new();
+    let instructions = vec![
+        create_account(
+            bank_client,
+            &payer,
+            &multisig,
+            Multisig::LEN,
+            multisig_program_id,
+        )?,
+        multisig_instruction::create_multisig(
+            multisig_program_id,
+            &multisig.pubkey(),
+            owners,
+            threshold,
+        )
+        .map_err(|e| e.to_string())?,
+    ];
+    if let Some(signature) =
+        bank_client.send(&instructions, &[&payer, &multisig], cli.send_mode())?
+    {
+        let (signer, _) =
+            multisig_instruction::multisig_signer(multisig_program_id, &multisig.pubkey());
+        println!("signature: {}", signature);
+        println!("multisig: {}", multisig.pubkey());
+        println!("signer: {}", signer);
+    }
+    Ok(())
+}
+
+/// Proposes `instruction` to `multisig` as --keypair, after running `setup` signed by
+/// `setup_signers` in the same transaction.
+fn multisig_propose(
+    cli: &Cli,
+    bank_client: &BankClient,
+    multisig_program_id: &Pubkey,
+    multisig: &Pubkey,
+    instruction: Instruction,
+    setup: Vec<Instruction>,
+    setup_signers: &[&Keypair],
+) -> Result<(), String> {
+    let proposer = cli.signer()?;
+    let state = get_multisig(bank_client, multisig)?;
+    if state.owner_index(&proposer.pubkey()).is_none() {
+        return Err(format!(
+            "{} is not an owner of {}",
+            proposer.pubkey(),
+            multisig
+        ));
+    }
+
+    let proposal = Keypair::new();
+    let mut instructions = setup;
+    instructions.push(create_account(
+        bank_client,
+        &proposer,
+        &proposal,
+        Proposal::LEN,
+        multisig_program_id,
+    )?);
+    instructions.push(
+        multisig_instruction::propose(
+            multisig_program_id,
+            &proposer.pubkey(),
+            multisig,
+            &proposal.pubkey(),
+            instruction,
+        )
+        .map_err(|e| e.to_string())?,
+    );
+    let mut signers: Vec<&dyn Signer> = vec![&proposer, &proposal];
+    signers.extend(setup_signers.iter().map(|s| *s as &dyn Signer));
+    if let Some(signature) = bank_client.send(&instructions, &signers, cli.send_mode())? {
+        println!("signature: {}", signature);
+        println!("proposal: {}", proposal.pubkey());
+        print_approvals(bank_client, &proposal.pubkey())?;
+    }
+    Ok(())
+}
+
+fn multisig_propose_bank(
+    cli: &Cli,
+    bank_client: &BankClient,
+    multisig_program_id: &Pubkey,
+    multisig: &Pubkey,
+    decimals: u8,
+) -> Result<(), String> {
+    let payer = cli.signer()?;
+    let bank = Keypair::new();
+    let (signer, _) = multisig_instruction::multisig_signer(multisig_program_id, multisig);
+    // The bank account exists from now on; it opens when the proposal executes.
+    let setup = vec![create_account(
+        bank_client,
+        &payer,
+        &bank,
+        Bank::LEN,
+        &bank_client.program_id,
+    )?];
+    let instruction =
+        instruction::initialize_bank(&bank_client.program_id, &bank.pubkey(), &signer, decimals)
+            .map_err(|e| e.to_string())?;
+    println!("bank: {}", bank.pubkey());
+    multisig_propose(
+        cli,
+        bank_client,
+        multisig_program_id,
+        multisig,
+        instruction,
+        setup,
+        &[&bank],
+    )
+}
+
+fn multisig_propose_mint(
+    cli: &Cli,
+    bank_client: &BankClient,
+    multisig_program_id: &Pubkey,
+    multisig: &Pubkey,
+    to: &Pubkey,
+    amount: u64,
+) -> Result<(), String> {
+    let bank = bank_client.get_account(to)?.bank;
+    let (signer, _) = multisig_instruction::multisig_signer(multisig_program_id, multisig);
+    let bank_owner = bank_client.get_bank(&bank)?.bank_owner;
+    if bank_owner != signer {
+        return Err(format!(
+            "bank {} is owned by {}, not by the multisig's signer {}",
+            bank, bank_owner, signer
+        ));
+    }
+
+    let instruction = instruction::mint_to(&bank_client.program_id, &bank, to, &signer, amount)
+        .map_err(|e| e.to_string())?;
+    multisig_propose(
+        cli,
+        bank_client,
+        multisig_program_id,
+        multisig,
+        instruction,
+        vec![],
+        &[],
+    )
+}
+
+fn multisig_propose_file(
+    cli: &Cli,
+    bank_client: &BankClient,
+    multisig_program_id: &Pubkey,
+    file: &Path,
+) -> Result<(), String> {
+    let file = ProposalFile::read(file)?;
+    let instruction = file.instruction()?;
+    if !Proposal::fits(&instruction) {
+        return Err(format!(
+            "a proposal holds at most {} accounts and {} bytes of data",
+            MAX_ACCOUNTS, MAX_DATA
+        ));
+    }
+    println!(
+        "{}",
+        proposal::describe(&instruction, &bank_client.program_id)
+    );
+    multisig_propose(
+        cli,
+        bank_client,
+        multisig_program_id,
+        &file.multisig()?,
+        instruction,
+        vec![],
+        &[],
+    )
+}
+
+fn multisig_approve(
+    cli: &Cli,
+    bank_client: &BankClient,
+    multisig_program_id: &Pubkey,
+    proposal: &Pubkey,
+    expect: Option<&Path>,
+) -> Result<(), String> {
+    let owner = cli.signer()?;
+    let state = get_proposal(bank_client, proposal)?;
+    if state.executed {
+        return Err(format!("{} has already been executed", proposal));
+    }
+    if let Some(expect) = expect {
+        let file = ProposalFile::read(expect)?;
+        if file.multisig()? != state.multisig || file.instruction()? != state.instruction() {
+            return Err(format!(
+                "{} doesn't hold the instruction of {}",
+                proposal,
+                expect.display()
+            ));
+        }
+    }
+
+    let instruction = multisig_instruction::approve(
+        multisig_program_id,
+        &owner.pubkey(),
+        &state.multisig,
+        proposal,
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&owner], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_approvals(bank_client, proposal)?;
+    }
+    Ok(())
+}
+
+fn multisig_execute(
+    cli: &Cli,
+    bank_client: &BankClient,
+    multisig_program_id: &Pubkey,
+    proposal: &Pubkey,
+) -> Result<(), String> {
+    let payer = cli.signer()?;
+    let state = get_proposal(bank_client, proposal)?;
+    let multisig = get_multisig(bank_client, &state.multisig)?;
+    if state.executed {
+        return Err(format!("{} has already been executed", proposal));
+    }
+    if state.approval_count() < multisig.threshold as u32 {
+        return Err(format!(
+            "{} has {} of the {} approvals it needs",
+            proposal,
+            state.approval_count(),
+            multisig.threshold
+        ));
+    }
+
+    let instruction =
+        multisig_instruction::execute(multisig_program_id, &state.multisig, proposal, &state)
+            .map_err(|e| e.to_string())?;
+    if let Some(signature) = bank_client.send(&[instruction], &[&payer], cli.send_mode())? {
+        println!("signature: {}", signature);
+        print_approvals(bank_client, proposal)?;
+    }
+    Ok(())
+}
+
+fn multisig_list(
+    bank_client: &BankClient,
+    multisig_program_id: &Pubkey,
+    multisig: &Pubkey,
+) -> Result<(), String> {
+    let state = get_multisig(bank_client, multisig)?;
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(Proposal::LEN as u64),
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                PROPOSAL_MULTISIG_OFFSET,
+                multisig.to_bytes().to_vec(),
+            )),
+        ]),
+        ..RpcProgramAccountsConfig::default()
+    };
+    let accounts = match bank_client
+        .rpc
+        .get_program_accounts_with_config(multisig_program_id, config)
+    {
+        Ok(a) => a,
+        Err(e) => return Err(format!("get proposal accounts failed: {}", e)),
+    };
+    let pending: Vec<(Pubkey, Proposal)> = accounts
+        .into_iter()
+        .filter_map(|(address, account)| Some((address, Proposal::unpack(&account.data).ok()?)))
+        .filter(|(_, proposal)| !proposal.executed)
+        .collect();
+    if pending.is_empty() {
+        println!("no pending proposals");
+        return Ok(());
+    }
+    for (address, proposal) in pending {
+        let approvers: Vec<String> = state
+            .owners
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| proposal.approvals & (1 << index) != 0)
+            .map(|(_, owner)| owner.to_string())
+            .collect();
+        println!("proposal: {}", address);
+        println!(
+            "  instruction: {}",
+            proposal::describe(&proposal.instruction(), &bank_client.program_id)
+        );
+        println!(
+            "  approvals: {}/{} {}",
+            proposal.approval_count(),
+            state.threshold,
+            approvers.join(" ")
+        );
+    }
+    Ok(())
+}
+
+fn multisig_export(bank_client: &BankClient, proposal: &Pubkey, out: &Path) -> Result<(), String> {
+    let state = get_proposal(bank_client, proposal)?;
+    let instruction = state.instruction();
+    let file = ProposalFile {
+        description: Some(proposal::describe(&instruction, &bank_client.program_id)),
+        ..ProposalFile::new(&state.multisig, Some(proposal), &instruction)
+    };
+    file.write(out)?;
+    println!("wrote {}", out.display());
+    Ok(())
+}
+
+fn confirm(
+    rpc: &RpcClient,
+    signature: &Signature,
+    wait: bool,
+    timeout: Duration,
+) -> Result<(), String> {
+    if wait {
+        return match util::wait_for_confirmation(rpc, signature, rpc.commitment(), timeout, None)? {
+            util::Confirmation::Confirmed { slot } => {
+                println!("confirmed in slot {}", slot);
+                Ok(())
+            }
+            util::Confirmation::Expired => Err("blockhash expired".to_string()),
+        };
+    }
+
+    let status = match rpc.get_signature_statuses_with_history(&[*signature]) {
+        Ok(r) => r.value.into_iter().next().flatten(),
+        Err(e) => return Err(format!("get signature status failed: {}", e)),
+    };
+    match status {
+        None => println!("not found: still pending, or its blockhash expired before it landed"),
+        Some(s) => {
+            println!("slot: {}", s.slot);
+            println!("status: {:?}", s.confirmation_status());
+            match s.err {
+                Some(e) => println!("failed: {}", e),
+                None => println!("succeeded"),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "index")]
+fn index(
+    cli: &Cli,
+    db: &str,
+    history: Option<&Pubkey>,
+    volume: Option<&Pubkey>,
+) -> Result<(), String> {
+    use client::{
+        events::{self, BankEvent},
+        index::Indexer,
+    };
+
+    let indexer = Indexer::open(db)?;
+    if let Some(account) = history {
+        for (slot, amount) in indexer.balance_history(account)? {
+            println!("{} {}", slot, amount);
+        }
+        return Ok(());
+    }
+    if let Some(owner) = volume {
+        println!("{}", indexer.owner_volume(owner)?);
+        return Ok(());
+    }
+
+    let bank_client = cli.bank_client()?;
+    let program_id = bank_client.program_id;
+    let ws_url = util::websocket_url(&cli.url);
+    let updates = watch::watch_program(&ws_url, &program_id)?;
+    let events = events::bank_events(cli.rpc_client(), &ws_url, &program_id, updates)?;
+
+    println!("indexing program {} into {}", program_id, db);
+    for event in events.iter() {
+        match event {
+            BankEvent::Update(update) => indexer.record_update(&update)?,
+            BankEvent::Instruction(ix) => indexer.record_instruction(&ix)?,
+        }
+    }
+    Err("update stream closed".to_string())
+}
+
+fn keygen_new(outfile: &Path, encrypt: bool, force: bool) -> Result<(), String> {
+    if outfile.exists() && !force {
+        return Err(format!(
+            "{} already exists, use --force to overwrite",
+            outfile.display()
+        ));
+    }
+
+    let passphrase = if encrypt {
+        let passphrase = rpassword::prompt_password("passphrase: ").map_err(|e| e.to_string())?;
+        let confirm =
+            rpassword::prompt_password("confirm passphrase: ").map_err(|e| e.to_string())?;
+        if passphrase != confirm {
+            return Err("passphrases do not match".to_string());
+        }
+        Some(passphrase)
+    } else {
+        None
+    };
+
+    let keypair = Keypair::new();
+    keygen::write_keypair(&keypair, outfile, passphrase.as_deref())?;
+    println!("pubkey: {}", keypair.pubkey());
+    Ok(())
+}
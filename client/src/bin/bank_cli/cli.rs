@@ -0,0 +1,978 @@
+// The command line of `bank-cli`, kept apart from the commands themselves so that
+// `build.rs` can include it to render the man pages.
+
+use clap::{ArgEnum, Parser, Subcommand};
+use clap_complete::Shell;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+#[cfg(feature = "serve")]
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[clap(name = "bank-cli", about = "Operate the bank program")]
+pub struct Cli {
+    /// JSON RPC url of the cluster
+    #[clap(long, default_value = "https://api.devnet.solana.com")]
+    pub url: String,
+
+    /// Address of the deployed bank program
+    #[clap(long)]
+    pub program_id: Option<Pubkey>,
+
+    /// Keypair that signs and pays for transactions [default: ~/.config/solana/id.json]
+    #[clap(long)]
+    pub keypair: Option<PathBuf>,
+
+    /// Sign with the browser wallet holding this key instead of --keypair; a local page
+    /// hands each transaction to the wallet
+    #[clap(long)]
+    pub wallet: Option<Pubkey>,
+
+    /// Sign through the HTTP signer or Cloud KMS key this JSON file describes instead of
+    /// --keypair
+    #[cfg(feature = "remote-signer")]
+    #[clap(long)]
+    pub signer_config: Option<PathBuf>,
+
+    /// Print the transaction instead of sending it
+    #[clap(long)]
+    pub dry_run: bool,
+
+    /// With --dry-run, also simulate the transaction against the cluster
+    #[clap(long)]
+    pub simulate: bool,
+
+    /// Airdrop SOL to the fee payer when it can't cover fees and rent (devnet/testnet/local)
+    #[clap(long)]
+    pub airdrop: bool,
+
+    /// Seconds to wait for a transaction to confirm before giving up
+    #[clap(long, default_value = "60")]
+    pub confirm_timeout: u64,
+
+    #[clap(long)]
+    pub verbose: bool,
+
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Airdrop a bank's tokens to the recipients of a CSV, or claim from such an airdrop
+    Airdrop {
+        /// Address of the deployed distributor program
+        #[clap(long)]
+        distributor_program_id: Pubkey,
+
+        #[clap(subcommand)]
+        command: AirdropCommand,
+    },
+
+    /// Let a delegate spend up to --amount from an account you own
+    Approve {
+        #[clap(long)]
+        account: Pubkey,
+
+        #[clap(long)]
+        delegate: Pubkey,
+
+        #[clap(long)]
+        amount: u64,
+    },
+
+    /// Check that the bank's total supply matches the sum of its account balances
+    Audit {
+        #[clap(long)]
+        bank: Pubkey,
+    },
+
+    /// Print the tokens an account holds
+    Balance {
+        #[clap(long)]
+        account: Pubkey,
+    },
+
+    /// Load-test a local validator with transfers between fresh accounts signed by --keypair
+    Bench {
+        /// Transfers sent per second
+        #[clap(long, default_value = "50")]
+        tps: u32,
+
+        /// Seconds to keep sending
+        #[clap(long, default_value = "30")]
+        duration: u64,
+
+        /// Accounts the transfers go around
+        #[clap(long, default_value = "8")]
+        accounts: usize,
+
+        /// Run against a --url other than localhost
+        #[clap(long)]
+        allow_remote: bool,
+    },
+
+    /// Open a message board, post short messages to one, or read its posts a page at a time
+    Board {
+        /// Address of the deployed board program
+        #[clap(long)]
+        board_program_id: Pubkey,
+
+        #[clap(subcommand)]
+        command: BoardCommand,
+    },
+
+    /// Destroy tokens held by an account; signed by the account owner, and by the bank
+    /// owner unless the bank lets holders burn alone
+    Burn {
+        #[clap(long)]
+        from: Pubkey,
+
+        #[clap(long)]
+        amount: u64,
+
+        /// Keypair of the account owner, when it isn't --keypair
+        #[clap(long)]
+        from_owner: Option<PathBuf>,
+    },
+
+    /// Claim what vesting grants and streams hold for you into --to, once it reaches
+    /// --threshold; with --watch, keep claiming as more builds up
+    Claim {
+        /// Address of the deployed vesting program, to claim from --vesting
+        #[clap(long)]
+        vesting_program_id: Option<Pubkey>,
+
+        /// Address of the deployed stream program, to withdraw from --stream
+        #[clap(long)]
+        stream_program_id: Option<Pubkey>,
+
+        /// Vesting grant to claim from; repeat for several
+        #[clap(long = "vesting", requires = "vesting_program_id")]
+        vestings: Vec<Pubkey>,
+
+        /// Stream to withdraw from; repeat for several
+        #[clap(long = "stream", requires = "stream_program_id")]
+        streams: Vec<Pubkey>,
+
+        #[clap(long)]
+        to: Pubkey,
+
+        /// Smallest amount worth a claim, in base units; an ended schedule is claimed
+        /// whatever is left
+        #[clap(long, default_value = "1")]
+        threshold: u64,
+
+        /// Keep polling and claiming until every schedule has ended and been emptied
+        #[clap(long)]
+        watch: bool,
+
+        /// Seconds between polls with --watch
+        #[clap(long, default_value = "60")]
+        interval: u64,
+    },
+
+    /// Close an account and send its rent lamports to --dest
+    Close {
+        #[clap(long)]
+        account: Pubkey,
+
+        #[clap(long)]
+        dest: Pubkey,
+
+        /// Burn any tokens left in the account first instead of refusing to close it
+        #[clap(long)]
+        burn_remaining: bool,
+
+        /// Keypair of the bank owner for --burn-remaining, when it isn't --keypair and the
+        /// bank needs it to sign burns
+        #[clap(long)]
+        bank_owner: Option<PathBuf>,
+    },
+
+    /// Set what closing an account of a bank does with the tokens it still holds; signed
+    /// by the bank owner
+    ClosePolicy {
+        #[clap(long)]
+        bank: Pubkey,
+
+        #[clap(arg_enum)]
+        policy: ClosePolicyArg,
+    },
+
+    /// Require the accounts opened in a bank to be rent-exempt; signed by the bank owner
+    RequireRentExempt {
+        #[clap(long)]
+        bank: Pubkey,
+
+        /// Stop requiring it instead
+        #[clap(long)]
+        off: bool,
+    },
+
+    /// Print the completion script of a shell to stdout, e.g.
+    /// `bank-cli completions bash > /etc/bash_completion.d/bank-cli`
+    Completions {
+        #[clap(arg_enum)]
+        shell: Shell,
+    },
+
+    /// Look up, or wait for, a transaction that was left pending
+    Confirm {
+        signature: Signature,
+
+        /// Keep polling until the transaction confirms or --confirm-timeout elapses
+        #[clap(long)]
+        wait: bool,
+    },
+
+    /// Open a new account in a bank; --keypair pays for it
+    CreateAccount {
+        #[clap(long)]
+        bank: Pubkey,
+
+        /// Keypair of the account owner, when it isn't --keypair
+        #[clap(long)]
+        owner: Option<PathBuf>,
+    },
+
+    /// Offer tokens of one bank for an amount of another's through the escrow program:
+    /// open an offer, fulfill or cancel one, or show its terms
+    Escrow {
+        /// Address of the deployed escrow program
+        #[clap(long)]
+        escrow_program_id: Pubkey,
+
+        #[clap(subcommand)]
+        command: EscrowCommand,
+    },
+
+    /// Hand out capped daily amounts of a bank's tokens to testers, or open such a faucet
+    Faucet {
+        /// Address of the deployed faucet program
+        #[clap(long)]
+        faucet_program_id: Pubkey,
+
+        #[clap(subcommand)]
+        command: FaucetCommand,
+    },
+
+    /// Record every account update and bank instruction into SQLite, or query the index
+    #[cfg(feature = "index")]
+    Index {
+        #[clap(long, default_value = "bank-index.db")]
+        db: String,
+
+        /// Print the recorded balance history of an account and exit
+        #[clap(long)]
+        history: Option<Pubkey>,
+
+        /// Print the amount transferred out of an owner's accounts and exit
+        #[clap(long)]
+        volume: Option<Pubkey>,
+    },
+
+    /// Open a new bank owned by --keypair
+    InitBank {
+        #[clap(long, default_value = "0")]
+        decimals: u8,
+    },
+
+    /// Print a bank's settings and statistics: transfers, volume moved and holders
+    Inspect {
+        #[clap(long)]
+        bank: Pubkey,
+    },
+
+    /// Bill for tokens with an invoice, pay one, or check whether it has been paid
+    Invoice {
+        /// Address of the deployed invoice program
+        #[clap(long)]
+        invoice_program_id: Pubkey,
+
+        #[clap(subcommand)]
+        command: InvoiceCommand,
+    },
+
+    /// Manage keypair files
+    Keygen {
+        #[clap(subcommand)]
+        command: KeygenCommand,
+    },
+
+    /// Load-test a local validator with a weighted mix of operations signed by --keypair
+    Loadgen {
+        /// Operations and their relative weights, e.g. transfer:80,mint:10,approve:10
+        #[clap(long, default_value = "transfer:100")]
+        mix: String,
+
+        /// Transactions sent per second
+        #[clap(long, default_value = "50")]
+        tps: u32,
+
+        /// Seconds to keep sending
+        #[clap(long, default_value = "60")]
+        duration: u64,
+
+        /// Accounts to provision before sending
+        #[clap(long, default_value = "100")]
+        accounts: usize,
+
+        /// Run against a --url other than localhost
+        #[clap(long)]
+        allow_remote: bool,
+    },
+
+    /// Create new tokens in an account; signed by the bank owner
+    Mint {
+        #[clap(long)]
+        to: Pubkey,
+
+        #[clap(long)]
+        amount: u64,
+    },
+
+    /// Run bank-owner operations through an m-of-n wallet: create it, propose, approve, execute
+    Multisig {
+        /// Address of the deployed multisig program
+        #[clap(long)]
+        multisig_program_id: Pubkey,
+
+        #[clap(subcommand)]
+        command: MultisigCommand,
+    },
+
+    /// POST a JSON event to a webhook whenever a watched account's balance moves
+    #[cfg(feature = "notify")]
+    Notify {
+        #[clap(long)]
+        webhook: String,
+
+        /// Account to watch; repeat for several
+        #[clap(long = "account", required = true)]
+        accounts: Vec<Pubkey>,
+
+        /// Ignore balance changes smaller than this
+        #[clap(long, default_value = "0")]
+        min_amount: u64,
+
+        #[clap(long, default_value = "5")]
+        max_retries: u32,
+    },
+
+    /// Ask for a payment into a bank account with a Solana Pay link, or pay one
+    Pay {
+        #[clap(subcommand)]
+        command: PayCommand,
+    },
+
+    /// Pay the employees of a payroll; `run` is safe to schedule from cron
+    Payroll {
+        /// Address of the deployed payroll program
+        #[clap(long)]
+        payroll_program_id: Pubkey,
+
+        #[clap(subcommand)]
+        command: PayrollCommand,
+    },
+
+    /// Send any instruction of --idl, e.g. one added to the program before it has a
+    /// command here; signers that aren't given are --keypair
+    Raw {
+        /// IDL exported by `cargo xtask idl`
+        #[clap(long, default_value = "bank/idl.json")]
+        idl: PathBuf,
+
+        /// Name of the instruction, e.g. Transfer
+        #[clap(long)]
+        ix: String,
+
+        /// Arguments as name=value, e.g. amount=50
+        #[clap(long, multiple_values = true)]
+        args: Vec<String>,
+
+        /// Accounts as name=address, e.g. from=<address> to=<address>
+        #[clap(long, multiple_values = true)]
+        accounts: Vec<String>,
+    },
+
+    /// Remove an account's delegate and give its unspent allowance back to the owner
+    Revoke {
+        #[clap(long)]
+        account: Pubkey,
+    },
+
+    /// Print every change to the program's banks and accounts as it happens
+    Watch {
+        /// Yellowstone gRPC endpoint to stream from instead of the RPC websocket
+        #[cfg(feature = "geyser")]
+        #[clap(long)]
+        grpc: Option<String>,
+
+        /// Auth token for the gRPC endpoint
+        #[cfg(feature = "geyser")]
+        #[clap(long)]
+        x_token: Option<String>,
+    },
+
+    /// Serve bank state over HTTP; POST /transfer signs with --keypair
+    #[cfg(feature = "serve")]
+    Serve {
+        #[clap(long, default_value = "127.0.0.1:8080")]
+        bind: SocketAddr,
+    },
+
+    /// Count the program's instructions, users, daily volume and compute units over a
+    /// slot range, from its transaction history
+    Stats {
+        #[clap(long)]
+        start_slot: u64,
+
+        /// Last slot to include [default: the latest confirmed slot]
+        #[clap(long)]
+        end_slot: Option<u64>,
+
+        #[clap(long, arg_enum, default_value = "json")]
+        format: ReportFormat,
+    },
+
+    /// Print the tokens a bank has in circulation
+    Supply {
+        #[clap(long)]
+        bank: Pubkey,
+    },
+
+    /// Trade --amount of --from's tokens for the other bank of --pool, paid into --to
+    Swap {
+        /// Address of the deployed swap program
+        #[clap(long)]
+        swap_program_id: Pubkey,
+
+        #[clap(long)]
+        pool: Pubkey,
+
+        #[clap(long)]
+        from: Pubkey,
+
+        #[clap(long)]
+        to: Pubkey,
+
+        #[clap(long)]
+        amount: u64,
+
+        /// Fail rather than receive less than this [default: the current quote]
+        #[clap(long)]
+        min_out: Option<u64>,
+    },
+
+    /// Close the accounts of an owner that hold no tokens and reclaim their rent; signed by
+    /// the owner
+    Sweep {
+        /// Owner whose accounts to sweep [default: --keypair]; with another key, only lists
+        /// what there is to reclaim
+        #[clap(long)]
+        owner: Option<Pubkey>,
+
+        /// Where the reclaimed lamports go [default: the owner]
+        #[clap(long)]
+        dest: Option<Pubkey>,
+    },
+
+    /// Tip a creator through their tip jar, or open, inspect and withdraw from your own
+    Tip {
+        /// Address of the deployed tipping program
+        #[clap(long)]
+        tipping_program_id: Pubkey,
+
+        #[clap(subcommand)]
+        command: TipCommand,
+    },
+
+    /// Move tokens between two accounts of the same bank, as the owner or delegate of --from
+    Transfer {
+        #[clap(long)]
+        from: Pubkey,
+
+        /// Account to pay, or `name:<name>` for the account a registered name points to
+        #[clap(long)]
+        to: String,
+
+        /// Address of the deployed name service, to resolve --to name:<name>
+        #[clap(long)]
+        names_program_id: Option<Pubkey>,
+
+        #[clap(long)]
+        amount: u64,
+
+        /// Attach an SPL Memo to the transaction, shown by explorers
+        #[clap(long)]
+        memo: Option<String>,
+    },
+
+    /// Live dashboard of a bank's supply, top accounts and transfers
+    #[cfg(feature = "tui")]
+    Tui {
+        #[clap(long)]
+        bank: Pubkey,
+    },
+
+    /// Lock tokens for a beneficiary on a schedule, or claim what has vested
+    Vesting {
+        /// Address of the deployed vesting program
+        #[clap(long)]
+        vesting_program_id: Pubkey,
+
+        #[clap(subcommand)]
+        command: VestingCommand,
+    },
+
+    /// Back bank tokens with SPL tokens: open a wrapper of a mint, wrap or unwrap
+    Wrapper {
+        /// Address of the deployed wrapper program
+        #[clap(long)]
+        wrapper_program_id: Pubkey,
+
+        #[clap(subcommand)]
+        command: WrapperCommand,
+    },
+}
+
+#[derive(Clone, Copy, ArgEnum)]
+pub enum ClosePolicyArg {
+    /// Refuse to close accounts that hold tokens
+    Reject,
+    /// Move the tokens to another account of the bank
+    Sweep,
+    /// Burn the tokens
+    Burn,
+}
+
+#[derive(Clone, Copy, ArgEnum)]
+pub enum ReportFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Subcommand)]
+pub enum AirdropCommand {
+    /// Mint the CSV's total into a new distributor of --bank, as its owner, and publish
+    /// the CSV's merkle root
+    Create {
+        #[clap(long)]
+        bank: Pubkey,
+
+        /// Lines of `recipient,amount`
+        #[clap(long)]
+        csv: PathBuf,
+    },
+
+    /// Claim --keypair's allocation into --to, proving it against the same CSV
+    Claim {
+        #[clap(long)]
+        distributor: Pubkey,
+
+        #[clap(long)]
+        csv: PathBuf,
+
+        #[clap(long)]
+        to: Pubkey,
+    },
+
+    /// Print the index, amount and proof of --recipient's allocation
+    Proof {
+        #[clap(long)]
+        csv: PathBuf,
+
+        #[clap(long)]
+        recipient: Pubkey,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BoardCommand {
+    /// Open a new board paid for by --keypair and print its address
+    Create,
+
+    /// Post --message to --board as --keypair, which pays the post's rent
+    Post {
+        #[clap(long)]
+        board: Pubkey,
+
+        #[clap(long)]
+        message: String,
+    },
+
+    /// Print a page of --board's posts, newest first
+    Read {
+        #[clap(long)]
+        board: Pubkey,
+
+        /// Pages back from the newest, starting at 0
+        #[clap(long, default_value = "0")]
+        page: u64,
+
+        #[clap(long, default_value = "10")]
+        per_page: u64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum EscrowCommand {
+    /// Lock --amount from an account you own in a new escrow, to be paid
+    /// --expected-amount into --receive, and print the escrow's address
+    Init {
+        #[clap(long)]
+        from: Pubkey,
+
+        /// Account you own that the taker pays, possibly in another bank
+        #[clap(long)]
+        receive: Pubkey,
+
+        #[clap(long)]
+        amount: u64,
+
+        #[clap(long)]
+        expected_amount: u64,
+    },
+
+    /// Take an escrow's deposit into --to, paying its maker what they ask from --from
+    Fulfill {
+        #[clap(long)]
+        escrow: Pubkey,
+
+        #[clap(long)]
+        from: Pubkey,
+
+        #[clap(long)]
+        to: Pubkey,
+    },
+
+    /// Close an escrow you opened, returning its deposit to --to
+    Cancel {
+        #[clap(long)]
+        escrow: Pubkey,
+
+        #[clap(long)]
+        to: Pubkey,
+    },
+
+    /// Print an escrow's terms: what it holds and what its maker asks for it
+    Show {
+        #[clap(long)]
+        escrow: Pubkey,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FaucetCommand {
+    /// Open a faucet for --bank signed by --keypair; fund its vault with `mint`
+    Create {
+        #[clap(long)]
+        bank: Pubkey,
+
+        /// Most any one address can tap per UTC day
+        #[clap(long)]
+        daily_cap: u64,
+    },
+
+    /// Take tokens from --faucet into --to, counted against --keypair's daily cap
+    Tap {
+        #[clap(long)]
+        faucet: Pubkey,
+
+        #[clap(long)]
+        to: Pubkey,
+
+        /// Defaults to the whole daily cap
+        #[clap(long)]
+        amount: Option<u64>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum InvoiceCommand {
+    /// Invoice --amount payable into --to, as --keypair, and print a payment link
+    Create {
+        #[clap(long)]
+        to: Pubkey,
+
+        #[clap(long)]
+        amount: u64,
+
+        #[clap(long, default_value = "")]
+        memo: String,
+
+        /// Seconds until the invoice can no longer be paid
+        #[clap(long, default_value = "86400")]
+        expires_in: i64,
+
+        /// Number telling the invoice apart from your others [default: current time in ms]
+        #[clap(long)]
+        id: Option<u64>,
+    },
+
+    /// Pay --invoice from --from, which --keypair owns or is delegated
+    Pay {
+        #[clap(long)]
+        invoice: Pubkey,
+
+        #[clap(long)]
+        from: Pubkey,
+    },
+
+    /// Print --invoice, whether it has been paid, and its payment link
+    Status {
+        #[clap(long)]
+        invoice: Pubkey,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum KeygenCommand {
+    /// Generate a new keypair and print its public key
+    New {
+        #[clap(long)]
+        outfile: PathBuf,
+
+        /// Protect the file with a passphrase (scrypt + AES-256-GCM)
+        #[clap(long)]
+        encrypt: bool,
+
+        /// Overwrite an existing file
+        #[clap(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MultisigCommand {
+    /// Create a wallet and print its address and the signer address acting for it
+    Create {
+        /// Owner of the wallet; repeat for each
+        #[clap(long = "owner", required = true)]
+        owners: Vec<Pubkey>,
+
+        /// Approvals needed to execute a proposal
+        #[clap(long)]
+        threshold: u8,
+    },
+
+    /// Propose opening a new bank owned by the wallet's signer address, as an owner
+    ProposeBank {
+        #[clap(long)]
+        multisig: Pubkey,
+
+        #[clap(long, default_value = "0")]
+        decimals: u8,
+    },
+
+    /// Propose minting --amount into --to from a bank the wallet owns, as an owner
+    ProposeMint {
+        #[clap(long)]
+        multisig: Pubkey,
+
+        #[clap(long)]
+        to: Pubkey,
+
+        #[clap(long)]
+        amount: u64,
+    },
+
+    /// Propose the instruction of a proposal file to the wallet it names, as an owner
+    Propose {
+        #[clap(long)]
+        file: PathBuf,
+    },
+
+    /// Approve a proposal as one of its wallet's owners
+    Approve {
+        #[clap(long)]
+        proposal: Pubkey,
+
+        /// Proposal file shared by the proposer; refuse to approve unless the proposal
+        /// holds the same instruction
+        #[clap(long)]
+        expect: Option<PathBuf>,
+    },
+
+    /// Execute a proposal that has enough approvals
+    Execute {
+        #[clap(long)]
+        proposal: Pubkey,
+    },
+
+    /// Print a wallet's pending proposals: their approvals and decoded instructions
+    List {
+        #[clap(long)]
+        multisig: Pubkey,
+    },
+
+    /// Write a proposal to a file for co-signers to review offline before approving it
+    /// with --expect
+    Export {
+        #[clap(long)]
+        proposal: Pubkey,
+
+        #[clap(long)]
+        out: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PayCommand {
+    /// Pay the request a `solana:` URL encodes from --from, which --keypair owns or is
+    /// delegated, with its references attached
+    Parse {
+        url: String,
+
+        #[clap(long)]
+        from: Pubkey,
+    },
+
+    /// Print a `solana:` URL asking for --amount into --to
+    Url {
+        #[clap(long)]
+        to: Pubkey,
+
+        #[clap(long)]
+        amount: u64,
+
+        /// Key to attach to the payment so it can be looked up; repeat for several
+        #[clap(long = "reference")]
+        references: Vec<Pubkey>,
+
+        /// Who is asking, shown to the payer
+        #[clap(long)]
+        label: Option<String>,
+
+        /// What the payment is for, shown to the payer
+        #[clap(long)]
+        message: Option<String>,
+
+        /// Memo the payment is sent with
+        #[clap(long)]
+        memo: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PayrollCommand {
+    /// Pay every employee of --payroll whose pay period has elapsed, signed by --keypair
+    Run {
+        #[clap(long)]
+        payroll: Pubkey,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TipCommand {
+    /// Open --keypair's tip jar in --bank
+    Create {
+        #[clap(long)]
+        bank: Pubkey,
+    },
+
+    /// Tip --creator --amount from --from, in --from's bank
+    Send {
+        #[clap(long)]
+        creator: Pubkey,
+
+        #[clap(long)]
+        from: Pubkey,
+
+        #[clap(long)]
+        amount: u64,
+    },
+
+    /// Print the totals and balance of --creator's tip jar in --bank
+    Show {
+        #[clap(long)]
+        creator: Pubkey,
+
+        #[clap(long)]
+        bank: Pubkey,
+    },
+
+    /// Move tips out of --keypair's jar in --bank into --to
+    Withdraw {
+        #[clap(long)]
+        bank: Pubkey,
+
+        #[clap(long)]
+        to: Pubkey,
+
+        /// Defaults to everything in the jar
+        #[clap(long)]
+        amount: Option<u64>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum VestingCommand {
+    /// Move --amount from an account you own into a new vesting grant and print its address
+    Create {
+        #[clap(long)]
+        from: Pubkey,
+
+        #[clap(long)]
+        beneficiary: Pubkey,
+
+        #[clap(long)]
+        amount: u64,
+
+        /// Unix timestamp the schedule starts at [default: now]
+        #[clap(long)]
+        start: Option<i64>,
+
+        /// Seconds after --start before anything can be claimed
+        #[clap(long, default_value = "0")]
+        cliff: i64,
+
+        /// Seconds after --start until the whole grant has vested
+        #[clap(long)]
+        duration: i64,
+    },
+
+    /// Claim everything vested so far into --to, as the beneficiary
+    Claim {
+        #[clap(long)]
+        vesting: Pubkey,
+
+        #[clap(long)]
+        to: Pubkey,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WrapperCommand {
+    /// Open a wrapper of an SPL Token --mint, with a new bank of the same decimals
+    Create {
+        #[clap(long)]
+        mint: Pubkey,
+    },
+
+    /// Lock --amount from --keypair's associated token account and mint as much into --to
+    Wrap {
+        #[clap(long)]
+        wrapper: Pubkey,
+
+        #[clap(long)]
+        to: Pubkey,
+
+        #[clap(long)]
+        amount: u64,
+    },
+
+    /// Burn --amount from --from and release as much into --keypair's associated token
+    /// account, creating it if needed
+    Unwrap {
+        #[clap(long)]
+        wrapper: Pubkey,
+
+        #[clap(long)]
+        from: Pubkey,
+
+        #[clap(long)]
+        amount: u64,
+    },
+}
@@ -0,0 +1,49 @@
+//! Serves the bank program over gRPC (`proto/bank.proto`, see `client::grpc`).
+
+use clap::Parser;
+use client::{bank::BankClient, grpc, util};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::{net::SocketAddr, path::PathBuf, process::exit};
+
+#[derive(Parser)]
+#[clap(name = "bank-grpc", about = "Serve the bank program over gRPC")]
+struct Args {
+    /// JSON RPC url of the cluster
+    #[clap(long, default_value = "https://api.devnet.solana.com")]
+    url: String,
+
+    /// Address of the deployed bank program
+    #[clap(long)]
+    program_id: Pubkey,
+
+    /// Keypair that signs and pays for SubmitTransfer [default: ~/.config/solana/id.json]
+    #[clap(long)]
+    keypair: Option<PathBuf>,
+
+    #[clap(long, default_value = "127.0.0.1:50051")]
+    bind: SocketAddr,
+
+    #[clap(long)]
+    verbose: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    util::init_logging(args.verbose);
+    util::install_interrupt_handler();
+    if let Err(e) = run(&args) {
+        eprintln!("error: {}", e);
+        exit(1);
+    }
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    let path = match &args.keypair {
+        Some(p) => p.clone(),
+        None => util::default_keypair_path()?,
+    };
+    let signer = util::load_keypair_from_file(&path)?;
+    let bank_client = BankClient::new(RpcClient::new(args.url.clone()), args.program_id);
+    grpc::run(bank_client, signer, args.bind)
+}
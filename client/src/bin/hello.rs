@@ -1,91 +1,259 @@
 use std::str::FromStr;
 
-use borsh::{BorshDeserialize, BorshSerialize};
-use client::util;
+use client::{blockhash::BlockhashCache, preflight, progress::Steps, util};
+use program::greeting_account::{
+    self, GreetingAccount, GreetingFee, GREETING_LEN, GREETING_WITH_FEE_LEN,
+};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
-    instruction, instruction::AccountMeta, message, pubkey::Pubkey, signature::Signer, transaction,
+    commitment_config::CommitmentConfig,
+    instruction, message,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    system_instruction,
 };
+use tracing::{debug, info};
+
+/// Seeds of the free and the paid greeting account, derived from the signer.
+const SEED: &str = "hello-greeting";
+const PAID_SEED: &str = "hello-paid-greeting";
+
+/// `--fee <lamports>`: greet an account that charges each greeting that much instead.
+fn fee_arg() -> Option<u64> {
+    let args: Vec<String> = std::env::args().collect();
+    let position = args.iter().position(|arg| arg == "--fee")?;
+    match args.get(position + 1).map(|fee| fee.parse()) {
+        Some(Ok(fee)) => Some(fee),
+        _ => panic!("--fee takes an amount of lamports"),
+    }
+}
 
-/// Define the type of state stored in accounts
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
-pub struct GreetingAccount {
-    /// number of greetings
-    pub counter: u32,
+/// What `say_hello` does to each account besides greeting it, from the command line.
+struct Greeting {
+    /// `--times <n>`: greet it `n` times in one instruction, once by default.
+    times: u32,
+    /// `--message <text>`: set its message first, as its authority.
+    message: Option<String>,
+    /// `--reset`: then zero its counter, as its authority.
+    reset: bool,
+}
+
+impl Greeting {
+    fn from_args() -> Greeting {
+        let args: Vec<String> = std::env::args().collect();
+        let value = |name: &str| {
+            let position = args.iter().position(|arg| arg == name)?;
+            match args.get(position + 1) {
+                Some(value) => Some(value.clone()),
+                None => panic!("{} takes a value", name),
+            }
+        };
+        let times = match value("--times").map(|times| times.parse()) {
+            Some(Ok(times)) => times,
+            Some(Err(_)) => panic!("--times takes a number of greetings"),
+            None => 1,
+        };
+        Greeting {
+            times,
+            message: value("--message"),
+            reset: args.iter().any(|arg| arg == "--reset"),
+        }
+    }
 }
 
 fn main() {
-    let client = util::new_dev_client();
+    util::init_logging(std::env::args().any(|arg| arg == "--verbose"));
+    util::install_interrupt_handler();
+    preflight::set_auto_airdrop(std::env::args().any(|arg| arg == "--airdrop"));
+    let mode = util::SendMode::from_args();
+    let cluster = util::Cluster::from_args().unwrap();
+    let client = util::new_client(&cluster, CommitmentConfig::default());
+    let blockhash_cache = BlockhashCache::default();
     let program_id = Pubkey::from_str("8obM4XyWGp8isXpS2NW4zSjYJrTMT7VV4Hkvrv2TXoaV").unwrap();
 
     util::check_program(&client, &program_id).unwrap();
-    println!("using program {}", program_id);
+    info!(program = %program_id, "using program");
 
-    let key_pair = util::get_keypair();
-    let empty_account = &GreetingAccount { counter: 0 };
-    let mut buffer: Vec<u8> = Vec::new();
-    empty_account.serialize(&mut buffer).unwrap();
-    println!("account size {}", buffer.len());
-    let signer = Box::new(key_pair);
+    let key_pair = util::keypair_from_args().unwrap();
+    let signer = || Box::new(Keypair::from_bytes(&key_pair.to_bytes()).unwrap());
+    let greeting = Greeting::from_args();
+    debug!(size = GREETING_LEN, "greeting account size");
 
-    let program_account = util::create_program_account(
-        &client,
-        &program_id,
-        "hello-account",
-        signer,
-        buffer.len() as u64,
-    )
-    .unwrap();
+    let mut steps = Steps::new(2);
+    let seed = if fee_arg().is_some() { PAID_SEED } else { SEED };
+    let program_account = steps
+        .run("create greeting account", || match fee_arg() {
+            Some(fee) => create_paid_greeting_account(
+                &client,
+                &blockhash_cache,
+                &program_id,
+                signer(),
+                fee,
+                mode,
+            ),
+            None => util::create_program_account(
+                &client,
+                &program_id,
+                SEED,
+                signer(),
+                GREETING_LEN as u64,
+                &blockhash_cache,
+                mode,
+            ),
+        })
+        .unwrap();
 
-    let key_pair = util::get_keypair();
-    let signer = Box::new(key_pair);
-    say_hello(&client, vec![program_account], &program_id, signer).unwrap();
-    println!("account say {} hello", program_account);
+    steps
+        .run("say hello", || {
+            say_hello(
+                &client,
+                &blockhash_cache,
+                vec![(program_account, seed)],
+                &program_id,
+                signer(),
+                &greeting,
+                mode,
+            )
+        })
+        .unwrap();
+    if mode != util::SendMode::Broadcast {
+        return;
+    }
+    info!(account = %program_account, "said hello");
 
     let greeting_account = get_greeting_account(&client, &program_account);
     println!(
-        "account {} has {} greeting",
-        program_account, greeting_account.counter
-    )
+        "account {} has {} greeting, the latest in slot {}",
+        program_account, greeting_account.counter, greeting_account.last_greeted_slot
+    );
+    if !greeting_account.message.is_empty() {
+        println!("message: {}", greeting_account.message);
+    }
 }
 
-fn say_hello(
+/// Creates an account charging `fee` lamports per greeting to `signer`, which initializes
+/// it as the account's creator to become the fee's authority.
+fn create_paid_greeting_account(
     client: &RpcClient,
-    accounts: Vec<Pubkey>,
+    blockhash_cache: &BlockhashCache,
     program_id: &Pubkey,
     signer: Box<dyn Signer>,
-) -> Result<bool, String> {
-    let instructions: Vec<instruction::Instruction> = accounts
-        .iter()
-        .map(|a| AccountMeta::new(a.to_owned(), false))
-        .map(|am| {
-            instruction::Instruction::new_with_bincode(program_id.to_owned(), &Some(()), vec![am])
-        })
-        .collect();
-    let msg = message::Message::new(&instructions[..], Some(&signer.pubkey()));
-
-    let block = match client.get_recent_blockhash() {
-        Ok(r) => r,
-        Err(e) => {
-            println!("err: {}", e);
-            return Err("get block failed".to_string());
+    fee: u64,
+    mode: util::SendMode,
+) -> Result<Pubkey, String> {
+    let seed = PAID_SEED;
+    let program_account = Pubkey::create_with_seed(&signer.pubkey(), seed, program_id)
+        .map_err(|e| format!("init public key failed: {}", e))?;
+    if let Ok(account) = client.get_account(&program_account) {
+        if account.owner == *program_id {
+            info!(account = %program_account, "using existing account");
+            return Ok(program_account);
         }
-    };
+    }
 
-    let t = transaction::Transaction::new(&vec![signer], msg, block.0);
-    match client.send_and_confirm_transaction(&t) {
-        Err(e) => {
-            println!("err: {}", e);
-            return Err("send tx failed".to_string());
+    let rent = client
+        .get_minimum_balance_for_rent_exemption(GREETING_WITH_FEE_LEN)
+        .map_err(|e| format!("get rent exemption failed: {}", e))?;
+    let create = system_instruction::create_account_with_seed(
+        &signer.pubkey(),
+        &program_account,
+        &signer.pubkey(),
+        seed,
+        rent,
+        GREETING_WITH_FEE_LEN as u64,
+        program_id,
+    );
+    let initialize = greeting_account::initialize_with_seed(
+        program_id,
+        &program_account,
+        &signer.pubkey(),
+        seed,
+    );
+    let set_fee = greeting_account::set_fee(program_id, &program_account, &signer.pubkey(), fee);
+    let msg = message::Message::new(&[create, initialize, set_fee], Some(&signer.pubkey()));
+    if util::sign_and_submit(client, blockhash_cache, msg, &[signer.as_ref()], mode, None)?
+        .is_some()
+    {
+        info!(account = %program_account, fee, "paid account created");
+    }
+    Ok(program_account)
+}
+
+/// Greets each of `accounts`, derived from the signer with their seeds, as `greeting`
+/// says, paying the fee of those that charge one. The signer first initializes those that
+/// have no authority yet, becoming theirs.
+fn say_hello(
+    client: &RpcClient,
+    blockhash_cache: &BlockhashCache,
+    accounts: Vec<(Pubkey, &str)>,
+    program_id: &Pubkey,
+    signer: Box<dyn Signer>,
+    greeting: &Greeting,
+    mode: util::SendMode,
+) -> Result<Option<Signature>, String> {
+    let mut instructions: Vec<instruction::Instruction> = Vec::new();
+    for (account, seed) in accounts.iter() {
+        // Not created yet, as under --dry-run.
+        let data = client.get_account(account).ok().map(|a| a.data);
+        let initialized = match &data {
+            Some(data) => {
+                let account = GreetingAccount::read(data).map_err(|e| e.to_string())?;
+                account.authority != Pubkey::default()
+            }
+            None => false,
+        };
+        if !initialized {
+            instructions.push(greeting_account::initialize_with_seed(
+                program_id,
+                account,
+                &signer.pubkey(),
+                seed,
+            ));
+        }
+        if let Some(message) = &greeting.message {
+            instructions.push(greeting_account::set_greeting(
+                program_id,
+                account,
+                &signer.pubkey(),
+                message.clone(),
+            ));
         }
-        _ => {}
+        if greeting.reset {
+            instructions.push(greeting_account::reset(
+                program_id,
+                account,
+                &signer.pubkey(),
+            ));
+        }
+        let fee = match &data {
+            Some(data) => GreetingFee::read(data).map_err(|e| e.to_string())?,
+            None => None,
+        };
+        instructions.push(match fee.filter(|fee| fee.lamports > 0) {
+            Some(fee) => {
+                info!(
+                    %account,
+                    lamports = fee.lamports,
+                    authority = %fee.authority,
+                    "paying to greet"
+                );
+                greeting_account::greet_with_fee(
+                    program_id,
+                    account,
+                    &signer.pubkey(),
+                    &fee.authority,
+                    greeting.times,
+                )
+            }
+            None => greeting_account::greet(program_id, account, greeting.times),
+        });
     }
-    Ok(true)
+    let msg = message::Message::new(&instructions[..], Some(&signer.pubkey()));
+    util::sign_and_submit(client, blockhash_cache, msg, &[signer.as_ref()], mode, None)
 }
 
 fn get_greeting_account(client: &RpcClient, account: &Pubkey) -> GreetingAccount {
     let account_info = &client.get_account(account).unwrap();
-    let data = &mut &account_info.data[..];
-    GreetingAccount::deserialize(data).unwrap()
+    // Reads an account with a fee after it as well.
+    GreetingAccount::read(&account_info.data).unwrap()
 }
-
@@ -0,0 +1,122 @@
+//! Streams the bank program's events into Postgres (see `client::pg_index`), and every
+//! `--finalize-interval` seconds drops the rows of slots the finalized chain skipped.
+
+use clap::Parser;
+use client::{events, pg_index::PgIndexer, util, watch};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::{
+    process::exit,
+    sync::mpsc::RecvTimeoutError,
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+#[derive(Parser)]
+#[clap(
+    name = "indexer",
+    about = "Index the bank program's events into Postgres"
+)]
+struct Args {
+    /// JSON RPC url of the cluster
+    #[clap(long, default_value = "https://api.devnet.solana.com")]
+    url: String,
+
+    /// Address of the deployed bank program
+    #[clap(long)]
+    program_id: Pubkey,
+
+    /// Postgres connection string, e.g. postgres://user@localhost/bank
+    #[clap(long)]
+    database_url: String,
+
+    /// Yellowstone gRPC endpoint to stream account updates from instead of the RPC
+    /// websocket
+    #[cfg(feature = "geyser")]
+    #[clap(long)]
+    grpc: Option<String>,
+
+    /// Auth token for the gRPC endpoint
+    #[cfg(feature = "geyser")]
+    #[clap(long)]
+    x_token: Option<String>,
+
+    /// Seconds between checks of the indexed slots against the finalized chain
+    #[clap(long, default_value = "30")]
+    finalize_interval: u64,
+
+    #[clap(long)]
+    verbose: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    util::init_logging(args.verbose);
+    util::install_interrupt_handler();
+    if let Err(e) = run(&args) {
+        eprintln!("error: {}", e);
+        exit(1);
+    }
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    let mut indexer = PgIndexer::connect(&args.database_url)?;
+    let rpc = RpcClient::new(args.url.clone());
+    let ws_url = util::websocket_url(&args.url);
+    #[cfg(feature = "geyser")]
+    let updates = match &args.grpc {
+        Some(endpoint) => {
+            client::geyser::watch_program_grpc(endpoint, args.x_token.clone(), &args.program_id)?
+        }
+        None => watch::watch_program(&ws_url, &args.program_id)?,
+    };
+    #[cfg(not(feature = "geyser"))]
+    let updates = watch::watch_program(&ws_url, &args.program_id)?;
+    let events = events::bank_events(
+        RpcClient::new(args.url.clone()),
+        &ws_url,
+        &args.program_id,
+        updates,
+    )?;
+
+    println!("indexing program {} into postgres", args.program_id);
+    let interval = Duration::from_secs(args.finalize_interval);
+    let mut next_finalize = Instant::now() + interval;
+    loop {
+        let timeout = next_finalize.saturating_duration_since(Instant::now());
+        match events.recv_timeout(timeout) {
+            Ok(event) => indexer.record(&event)?,
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return Err("event stream closed".to_string()),
+        }
+        if Instant::now() >= next_finalize {
+            // A slow RPC node is no reason to stop indexing; the next round catches up.
+            if let Err(e) = finalize(&rpc, &mut indexer) {
+                warn!(error = %e, "finalize failed");
+            }
+            next_finalize = Instant::now() + interval;
+        }
+    }
+}
+
+/// Checks the slots indexed since the last round against the finalized chain.
+fn finalize(rpc: &RpcClient, indexer: &mut PgIndexer) -> Result<(), String> {
+    let start = match indexer.first_unfinalized_slot()? {
+        Some(slot) => slot,
+        None => return Ok(()),
+    };
+    let end = rpc
+        .get_slot_with_commitment(CommitmentConfig::finalized())
+        .map_err(|e| format!("get finalized slot failed: {}", e))?;
+    if end < start {
+        return Ok(());
+    }
+    let blocks = rpc
+        .get_blocks_with_commitment(start, Some(end), CommitmentConfig::finalized())
+        .map_err(|e| format!("get finalized blocks failed: {}", e))?;
+    let dropped = indexer.finalize(start, end, &blocks)?;
+    if dropped > 0 {
+        info!(start, end, dropped, "dropped rows of skipped slots");
+    }
+    Ok(())
+}
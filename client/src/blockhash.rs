@@ -0,0 +1,247 @@
+use crate::{
+    rpc::RpcApi,
+    util::{self, Confirmation},
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig, hash::Hash, message::Message, signature::Signature,
+    signer::Signer, transaction::Transaction,
+};
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tracing::{debug, error, info, info_span, warn};
+
+#[cfg(feature = "metrics")]
+use crate::metrics;
+
+/// A blockhash stays usable for 150 slots (roughly a minute); refreshing well before
+/// that leaves room for the transaction to land.
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(30);
+const DEFAULT_MAX_RESIGNS: u32 = 3;
+
+struct CachedBlockhash {
+    blockhash: Hash,
+    last_valid_slot: u64,
+    fetched_at: Instant,
+}
+
+/// Shares one recent blockhash between transactions, fetching a new one through
+/// `getRecentBlockhash` once the cached one gets old.
+pub struct BlockhashCache {
+    commitment: CommitmentConfig,
+    max_age: Duration,
+    /// How many times an expired transaction is re-signed with a fresh blockhash.
+    max_resigns: u32,
+    /// Total time to wait for a confirmation, across re-signs.
+    confirm_timeout: Duration,
+    cached: Mutex<Option<CachedBlockhash>>,
+}
+
+impl Default for BlockhashCache {
+    fn default() -> BlockhashCache {
+        BlockhashCache::new(CommitmentConfig::confirmed(), DEFAULT_MAX_AGE)
+    }
+}
+
+impl BlockhashCache {
+    pub fn new(commitment: CommitmentConfig, max_age: Duration) -> BlockhashCache {
+        BlockhashCache {
+            commitment,
+            max_age,
+            max_resigns: DEFAULT_MAX_RESIGNS,
+            confirm_timeout: util::DEFAULT_CONFIRM_TIMEOUT,
+            cached: Mutex::new(None),
+        }
+    }
+
+    pub fn with_confirm_timeout(mut self, confirm_timeout: Duration) -> BlockhashCache {
+        self.confirm_timeout = confirm_timeout;
+        self
+    }
+
+    /// The cached blockhash and its last valid slot, refreshed when stale.
+    pub fn get(&self, client: &dyn RpcApi) -> Result<(Hash, u64), String> {
+        let mut cached = self.cached.lock().unwrap();
+        if let Some(c) = cached.as_ref() {
+            if c.fetched_at.elapsed() < self.max_age {
+                return Ok((c.blockhash, c.last_valid_slot));
+            }
+        }
+        let latest = self.fetch(client)?;
+        let result = (latest.blockhash, latest.last_valid_slot);
+        *cached = Some(latest);
+        Ok(result)
+    }
+
+    /// Drops the cached blockhash and fetches a new one.
+    pub fn refresh(&self, client: &dyn RpcApi) -> Result<(Hash, u64), String> {
+        let latest = self.fetch(client)?;
+        let result = (latest.blockhash, latest.last_valid_slot);
+        *self.cached.lock().unwrap() = Some(latest);
+        Ok(result)
+    }
+
+    fn fetch(&self, client: &dyn RpcApi) -> Result<CachedBlockhash, String> {
+        match client.get_recent_blockhash_with_commitment(self.commitment) {
+            Ok(r) => {
+                let (blockhash, _, last_valid_slot) = r.value;
+                debug!(%blockhash, last_valid_slot, "fetched recent blockhash");
+                Ok(CachedBlockhash {
+                    blockhash,
+                    last_valid_slot,
+                    fetched_at: Instant::now(),
+                })
+            }
+            Err(e) => {
+                error!(error = %e, "get recent blockhash failed");
+                Err("get recent blockhash failed".to_string())
+            }
+        }
+    }
+
+    /// Signs `message` with the cached blockhash and sends it, re-signing with a fresh
+    /// blockhash when the previous one expires before the transaction is confirmed.
+    pub fn send_and_confirm(
+        &self,
+        client: &dyn RpcApi,
+        message: &Message,
+        signers: &[&dyn Signer],
+    ) -> Result<Signature, String> {
+        let deadline = Instant::now() + self.confirm_timeout;
+        let (mut blockhash, mut last_valid_slot) = self.get(client)?;
+        for attempt in 0..=self.max_resigns {
+            let mut t = Transaction::new_unsigned(message.clone());
+            if let Err(e) = t.try_sign(signers, blockhash) {
+                error!(error = %e, "sign transaction failed");
+                return Err("sign transaction failed".to_string());
+            }
+            let span = info_span!(
+                "transaction",
+                signature = %t.signatures[0],
+                attempt,
+                slot = tracing::field::Empty
+            );
+            let _enter = span.enter();
+
+            if self.send_and_wait(client, &t, last_valid_slot, deadline)? {
+                info!("transaction confirmed");
+                return Ok(t.signatures[0]);
+            }
+            warn!("blockhash expired before confirmation, re-signing");
+            let latest = self.refresh(client)?;
+            blockhash = latest.0;
+            last_valid_slot = latest.1;
+        }
+        Err("blockhash expired".to_string())
+    }
+
+    /// Returns false when the blockhash expired without the transaction landing.
+    fn send_and_wait(
+        &self,
+        client: &dyn RpcApi,
+        t: &Transaction,
+        last_valid_slot: u64,
+        deadline: Instant,
+    ) -> Result<bool, String> {
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
+        #[cfg(feature = "metrics")]
+        metrics::record_transaction_sent();
+        let signature = match client.send_transaction(t) {
+            Ok(s) => s,
+            Err(e) => {
+                #[cfg(feature = "metrics")]
+                metrics::record_rpc_error(&e);
+                error!(error = %e, "send transaction failed");
+                return Err("send transaction failed".to_string());
+            }
+        };
+
+        match util::wait_for_confirmation(
+            client,
+            &signature,
+            self.commitment,
+            deadline.saturating_duration_since(Instant::now()),
+            Some(last_valid_slot),
+        )? {
+            Confirmation::Confirmed { slot } => {
+                #[cfg(feature = "metrics")]
+                metrics::observe_confirmation_latency(started.elapsed());
+                tracing::Span::current().record("slot", slot);
+                Ok(true)
+            }
+            Confirmation::Expired => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::mock::MockRpc;
+    use solana_sdk::{pubkey::Pubkey, signature::Keypair, system_instruction};
+
+    /// Every transaction that doesn't land expires on its first status check.
+    fn expiring_mock() -> MockRpc {
+        let mut mock = MockRpc::default();
+        mock.slot = 300;
+        mock.last_valid_slot = 250;
+        mock
+    }
+
+    fn transfer_message(payer: &Keypair) -> Message {
+        let ix = system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+        Message::new(&[ix], Some(&payer.pubkey()))
+    }
+
+    #[test]
+    fn test_resign_after_expiry() {
+        let payer = Keypair::new();
+        let mock = expiring_mock().drop_transactions(2);
+        let cache = BlockhashCache::default();
+
+        let signature = cache
+            .send_and_confirm(&mock, &transfer_message(&payer), &[&payer])
+            .unwrap();
+        let sent = mock.sent();
+        assert_eq!(sent.len(), 3);
+        assert_eq!(signature, sent[2].signatures[0]);
+        assert_ne!(
+            sent[0].message.recent_blockhash,
+            sent[1].message.recent_blockhash
+        );
+        assert_ne!(
+            sent[1].message.recent_blockhash,
+            sent[2].message.recent_blockhash
+        );
+    }
+
+    #[test]
+    fn test_resign_gives_up() {
+        let payer = Keypair::new();
+        let mock = expiring_mock().drop_transactions(10);
+        let cache = BlockhashCache::default();
+
+        assert_eq!(
+            cache.send_and_confirm(&mock, &transfer_message(&payer), &[&payer]),
+            Err("blockhash expired".to_string())
+        );
+        assert_eq!(mock.sent().len(), DEFAULT_MAX_RESIGNS as usize + 1);
+    }
+
+    #[test]
+    fn test_cached_blockhash_reused() {
+        let mock = MockRpc::default();
+        let cache = BlockhashCache::default();
+        let (first, _) = cache.get(&mock).unwrap();
+        assert_eq!(cache.get(&mock).unwrap().0, first);
+        assert_ne!(cache.refresh(&mock).unwrap().0, first);
+
+        let failing = MockRpc::default().fail("get_recent_blockhash_with_commitment");
+        assert_eq!(
+            BlockhashCache::default().get(&failing),
+            Err("get recent blockhash failed".to_string())
+        );
+    }
+}
@@ -0,0 +1,25 @@
+/// Indexes of the posts on page `page` of a board with `post_count` posts, newest first,
+/// `per_page` to a page. Past the oldest post the page is empty.
+pub fn page_indices(post_count: u64, page: u64, per_page: u64) -> Vec<u64> {
+    let skipped = page.saturating_mul(per_page);
+    let newest = match post_count.checked_sub(skipped) {
+        Some(end) if end > 0 => end,
+        _ => return Vec::new(),
+    };
+    (newest.saturating_sub(per_page)..newest).rev().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_indices() {
+        assert_eq!(page_indices(25, 0, 10), (15..25).rev().collect::<Vec<_>>());
+        assert_eq!(page_indices(25, 1, 10), (5..15).rev().collect::<Vec<_>>());
+        assert_eq!(page_indices(25, 2, 10), vec![4, 3, 2, 1, 0]);
+        assert!(page_indices(25, 3, 10).is_empty());
+        assert!(page_indices(0, 0, 10).is_empty());
+        assert!(page_indices(25, u64::MAX, u64::MAX).is_empty());
+    }
+}
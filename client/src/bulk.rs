@@ -0,0 +1,184 @@
+//! Async counterparts of `util`'s helpers, for sending many transactions at once the way
+//! a load test of a program does.
+//!
+//! solana-client 1.8 has no `nonblocking` module, so each call runs the blocking one on
+//! tokio's blocking pool, as `grpc` and `server` do. A semaphore bounds how many calls are
+//! in flight, and every transaction goes through one shared `BlockhashCache`, which
+//! re-signs it with a fresh blockhash when the old one expires before it lands.
+
+use crate::{
+    blockhash::BlockhashCache,
+    rpc::RpcApi,
+    util::{self, SendMode},
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::Instruction,
+    message::Message,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tracing::{error, info};
+
+/// How many calls `AsyncClient` runs at once unless told otherwise.
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 16;
+
+pub struct AsyncClient<C = RpcClient> {
+    rpc: Arc<C>,
+    blockhash_cache: Arc<BlockhashCache>,
+    permits: Arc<Semaphore>,
+}
+
+impl<C> Clone for AsyncClient<C> {
+    fn clone(&self) -> AsyncClient<C> {
+        AsyncClient {
+            rpc: self.rpc.clone(),
+            blockhash_cache: self.blockhash_cache.clone(),
+            permits: self.permits.clone(),
+        }
+    }
+}
+
+impl<C: RpcApi + Send + Sync + 'static> AsyncClient<C> {
+    pub fn new(rpc: C, max_in_flight: usize) -> AsyncClient<C> {
+        AsyncClient {
+            rpc: Arc::new(rpc),
+            blockhash_cache: Arc::new(BlockhashCache::default()),
+            permits: Arc::new(Semaphore::new(max_in_flight)),
+        }
+    }
+
+    /// Runs a blocking RPC call off the async workers once a permit is free.
+    async fn blocking<T, F>(&self, f: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&C, &BlockhashCache) -> Result<T, String> + Send + 'static,
+    {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .map_err(|_| "client is closed".to_string())?;
+        let rpc = self.rpc.clone();
+        let blockhash_cache = self.blockhash_cache.clone();
+        match tokio::task::spawn_blocking(move || f(&rpc, &blockhash_cache)).await {
+            Ok(result) => result,
+            Err(e) => {
+                error!(error = %e, "rpc call panicked");
+                Err("rpc call panicked".to_string())
+            }
+        }
+    }
+
+    /// `util::create_program_account`, broadcast.
+    pub async fn create_program_account(
+        &self,
+        program_id: Pubkey,
+        seed: String,
+        signer: Arc<Keypair>,
+        space: u64,
+    ) -> Result<Pubkey, String> {
+        self.blocking(move |rpc, blockhash_cache| {
+            let signer = Keypair::from_bytes(&signer.to_bytes()).map_err(|e| e.to_string())?;
+            util::create_program_account(
+                rpc,
+                &program_id,
+                &seed,
+                Box::new(signer),
+                space,
+                blockhash_cache,
+                SendMode::Broadcast,
+            )
+        })
+        .await
+    }
+
+    /// Sends `instructions` in one transaction the first of `signers` pays for, and waits
+    /// for it to be confirmed.
+    pub async fn send(
+        &self,
+        instructions: Vec<Instruction>,
+        signers: Vec<Arc<Keypair>>,
+    ) -> Result<Signature, String> {
+        let payer = match signers.first() {
+            Some(payer) => payer.pubkey(),
+            None => return Err("a transaction needs a signer".to_string()),
+        };
+        self.blocking(move |rpc, blockhash_cache| {
+            let message = Message::new(&instructions, Some(&payer));
+            let signers: Vec<&dyn Signer> = signers.iter().map(|s| &**s as &dyn Signer).collect();
+            blockhash_cache.send_and_confirm(rpc, &message, &signers)
+        })
+        .await
+    }
+
+    /// Sends each of `transactions` as `send` does, all at once but for the in-flight
+    /// bound, and returns their results in the same order.
+    pub async fn send_all(
+        &self,
+        transactions: Vec<Vec<Instruction>>,
+        signers: Vec<Arc<Keypair>>,
+    ) -> Vec<Result<Signature, String>> {
+        let handles: Vec<_> = transactions
+            .into_iter()
+            .map(|instructions| {
+                let client = self.clone();
+                let signers = signers.clone();
+                tokio::spawn(async move { client.send(instructions, signers).await })
+            })
+            .collect();
+        let mut results = Vec::with_capacity(handles.len());
+        for handle in handles {
+            results.push(handle.await.unwrap_or_else(|e| {
+                error!(error = %e, "send task panicked");
+                Err("send task panicked".to_string())
+            }));
+        }
+        let confirmed = results.iter().filter(|r| r.is_ok()).count();
+        info!(
+            confirmed,
+            failed = results.len() - confirmed,
+            "bulk send finished"
+        );
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::mock::MockRpc;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_send_all() {
+        let payer = Arc::new(Keypair::new());
+        // The first transaction is dropped and its blockhash expires at once.
+        let mut mock = MockRpc::default();
+        mock.slot = 300;
+        let client = AsyncClient::new(mock.drop_transactions(1), 2);
+        let transactions = (0..5)
+            .map(|i| {
+                vec![util::memo_instruction(
+                    &format!("bulk {}", i),
+                    &[&payer.pubkey()],
+                )]
+            })
+            .collect();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        let results = runtime.block_on(client.send_all(transactions, vec![payer]));
+        let signatures: HashSet<_> = results.into_iter().map(Result::unwrap).collect();
+        assert_eq!(signatures.len(), 5);
+        // It was re-signed, so one more transaction went out than was asked for.
+        assert_eq!(client.rpc.sent().len(), 6);
+
+        let err = runtime.block_on(client.send(vec![], vec![])).unwrap_err();
+        assert_eq!(err, "a transaction needs a signer");
+    }
+}
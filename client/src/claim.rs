@@ -0,0 +1,190 @@
+use solana_program::program_pack::Pack;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use solana_stream::{instruction as stream_instruction, state::Stream};
+use solana_vesting::{instruction as vesting_instruction, state::Vesting};
+
+/// A vesting grant or a stream `bank-cli claim` takes tokens out of for their beneficiary.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Schedule {
+    Vesting(Vesting),
+    Stream(Stream),
+}
+
+/// A schedule at `address`, owned by the vesting or stream program `program_id`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Claimable {
+    pub program_id: Pubkey,
+    pub address: Pubkey,
+    pub schedule: Schedule,
+}
+
+impl Claimable {
+    pub fn vesting(program_id: Pubkey, address: Pubkey, data: &[u8]) -> Result<Self, String> {
+        let vesting = Vesting::unpack(data).map_err(|e| format!("{}: {}", address, e))?;
+        Ok(Claimable {
+            program_id,
+            address,
+            schedule: Schedule::Vesting(vesting),
+        })
+    }
+
+    pub fn stream(program_id: Pubkey, address: Pubkey, data: &[u8]) -> Result<Self, String> {
+        let stream = Stream::unpack(data).map_err(|e| format!("{}: {}", address, e))?;
+        Ok(Claimable {
+            program_id,
+            address,
+            schedule: Schedule::Stream(stream),
+        })
+    }
+
+    /// Whoever may claim: the grant's beneficiary or the stream's recipient.
+    pub fn beneficiary(&self) -> Pubkey {
+        match &self.schedule {
+            Schedule::Vesting(vesting) => vesting.beneficiary,
+            Schedule::Stream(stream) => stream.recipient,
+        }
+    }
+
+    /// Amount a claim would take out at `now`.
+    pub fn amount(&self, now: i64) -> u64 {
+        match &self.schedule {
+            Schedule::Vesting(vesting) => vesting.claimable_amount(now),
+            Schedule::Stream(stream) => stream.withdrawable(now),
+        }
+    }
+
+    /// Whether the schedule has run its course by `now`, so nothing more will ever build
+    /// up past what a claim takes out then.
+    pub fn has_ended(&self, now: i64) -> bool {
+        match &self.schedule {
+            Schedule::Vesting(vesting) => now >= vesting.end_ts,
+            Schedule::Stream(stream) => now >= stream.end_ts,
+        }
+    }
+
+    /// Claims into `to`, an account of `bank`.
+    pub fn instruction(
+        &self,
+        bank_program_id: &Pubkey,
+        bank: &Pubkey,
+        to: &Pubkey,
+    ) -> Result<Instruction, String> {
+        match &self.schedule {
+            Schedule::Vesting(vesting) => vesting_instruction::claim(
+                &self.program_id,
+                bank_program_id,
+                bank,
+                &vesting.beneficiary,
+                &self.address,
+                &vesting.vault,
+                to,
+            ),
+            Schedule::Stream(stream) => stream_instruction::withdraw(
+                &self.program_id,
+                bank_program_id,
+                bank,
+                &stream.recipient,
+                &self.address,
+                &stream.vault,
+                to,
+            ),
+        }
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// The schedules worth claiming at `now`, with what each claim takes out: those holding
+/// at least `threshold`, and those that have ended holding anything, since nothing more
+/// will build up in them to reach it.
+pub fn due(claimables: &[Claimable], now: i64, threshold: u64) -> Vec<(&Claimable, u64)> {
+    claimables
+        .iter()
+        .map(|claimable| (claimable, claimable.amount(now)))
+        .filter(|(claimable, amount)| {
+            *amount > 0 && (*amount >= threshold || claimable.has_ended(now))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_due() {
+        let vesting = Claimable {
+            program_id: Pubkey::new_unique(),
+            address: Pubkey::new_unique(),
+            schedule: Schedule::Vesting(Vesting {
+                is_initialized: true,
+                total_amount: 1_000,
+                start_ts: 0,
+                cliff_ts: 100,
+                end_ts: 1_000,
+                ..Vesting::default()
+            }),
+        };
+        let stream = Claimable {
+            program_id: Pubkey::new_unique(),
+            address: Pubkey::new_unique(),
+            schedule: Schedule::Stream(Stream {
+                is_initialized: true,
+                rate: 2,
+                start_ts: 0,
+                end_ts: 200,
+                withdrawn: 150,
+                ..Stream::default()
+            }),
+        };
+        let claimables = [vesting, stream];
+
+        // Nothing before the cliff, and the stream is still short of its last withdrawal.
+        assert!(due(&claimables, 50, 1).is_empty());
+        assert_eq!(due(&claimables, 100, 100), vec![(&vesting, 100)]);
+        assert_eq!(
+            due(&claimables, 150, 100),
+            vec![(&vesting, 150), (&stream, 150)]
+        );
+        // Once a schedule ends, whatever is left is due whatever the threshold.
+        assert_eq!(due(&claimables, 500, 1_000), vec![(&stream, 250)]);
+        assert!(stream.has_ended(500) && !vesting.has_ended(500));
+        assert_eq!(
+            due(&claimables, 1_000, 1_001),
+            vec![(&vesting, 1_000), (&stream, 250)]
+        );
+    }
+
+    #[test]
+    fn test_instruction() {
+        let (bank_program_id, bank, to) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let stream = Stream {
+            is_initialized: true,
+            recipient: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            ..Stream::default()
+        };
+        let mut data = vec![0; Stream::LEN];
+        Stream::pack(stream, &mut data).unwrap();
+        let claimable =
+            Claimable::stream(Pubkey::new_unique(), Pubkey::new_unique(), &data).unwrap();
+        assert_eq!(claimable.beneficiary(), stream.recipient);
+        assert_eq!(
+            claimable.instruction(&bank_program_id, &bank, &to).unwrap(),
+            stream_instruction::withdraw(
+                &claimable.program_id,
+                &bank_program_id,
+                &bank,
+                &stream.recipient,
+                &claimable.address,
+                &stream.vault,
+                &to,
+            )
+            .unwrap()
+        );
+        assert!(Claimable::vesting(Pubkey::new_unique(), Pubkey::new_unique(), &data).is_err());
+    }
+}
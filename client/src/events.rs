@@ -0,0 +1,200 @@
+//! The typed event stream the indexers consume: account updates from the websocket or
+//! Geyser, merged with the bank instructions of every confirmed transaction that
+//! mentions the program.
+
+use crate::watch::BankUpdate;
+use solana_bank::instruction::BankInstruction;
+use solana_client::{
+    pubsub_client::PubsubClient,
+    rpc_client::RpcClient,
+    rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+use std::{
+    str::FromStr,
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+use tracing::{debug, error};
+
+#[derive(Debug, PartialEq)]
+pub enum BankEvent {
+    Update(BankUpdate),
+    Instruction(IndexedInstruction),
+}
+
+/// A bank program instruction found in a confirmed transaction.
+#[derive(Debug, PartialEq)]
+pub struct IndexedInstruction {
+    pub slot: u64,
+    pub signature: String,
+    pub ix_index: u32,
+    pub kind: String,
+    pub amount: Option<u64>,
+    /// First account of the instruction: the source of a transfer, the bank of a mint.
+    pub source: Option<Pubkey>,
+    pub destination: Option<Pubkey>,
+}
+
+/// Merges `updates` with the instructions of the program's confirmed transactions,
+/// found through a logs subscription on `ws_url` and fetched with `rpc`. The stream
+/// ends once the returned receiver is dropped.
+pub fn bank_events(
+    rpc: RpcClient,
+    ws_url: &str,
+    program_id: &Pubkey,
+    updates: Receiver<BankUpdate>,
+) -> Result<Receiver<BankEvent>, String> {
+    let (subscription, logs) = match PubsubClient::logs_subscribe(
+        ws_url,
+        RpcTransactionLogsFilter::Mentions(vec![program_id.to_string()]),
+        RpcTransactionLogsConfig {
+            commitment: Some(CommitmentConfig::confirmed()),
+        },
+    ) {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "logs subscribe failed");
+            return Err("logs subscribe failed".to_string());
+        }
+    };
+
+    let (sender, events) = mpsc::channel();
+    let update_sender = sender.clone();
+    thread::spawn(move || {
+        for update in updates.iter() {
+            if update_sender.send(BankEvent::Update(update)).is_err() {
+                break;
+            }
+        }
+    });
+
+    let program_id = *program_id;
+    thread::spawn(move || {
+        let _subscription = subscription;
+        for response in logs.iter() {
+            if response.value.err.is_some() {
+                continue;
+            }
+            let signature = match Signature::from_str(&response.value.signature) {
+                Ok(s) => s,
+                Err(_) => continue,
+            };
+            let instructions = match fetch_instructions(&rpc, &program_id, &signature) {
+                Ok(i) => i,
+                Err(_) => continue,
+            };
+            for ix in instructions {
+                if sender.send(BankEvent::Instruction(ix)).is_err() {
+                    debug!("logs subscription closed");
+                    return;
+                }
+            }
+        }
+    });
+    Ok(events)
+}
+
+/// Fetches a confirmed transaction and decodes the instructions it sent to `program_id`.
+pub fn fetch_instructions(
+    client: &RpcClient,
+    program_id: &Pubkey,
+    signature: &Signature,
+) -> Result<Vec<IndexedInstruction>, String> {
+    let confirmed = match client.get_transaction(signature, UiTransactionEncoding::Base64) {
+        Ok(t) => t,
+        Err(e) => {
+            error!(error = %e, %signature, "get transaction failed");
+            return Err("get transaction failed".to_string());
+        }
+    };
+    let transaction = match confirmed.transaction.transaction.decode() {
+        Some(t) => t,
+        None => return Err("decode transaction failed".to_string()),
+    };
+
+    let keys = transaction.message.static_account_keys();
+    let account = |ix_accounts: &[u8], i: usize| {
+        ix_accounts
+            .get(i)
+            .and_then(|&index| keys.get(index as usize))
+            .copied()
+    };
+    let mut indexed = Vec::new();
+    for (i, ix) in transaction.message.instructions().iter().enumerate() {
+        if keys.get(ix.program_id_index as usize) != Some(program_id) {
+            continue;
+        }
+        let instruction = match BankInstruction::unpack(&ix.data) {
+            Ok(i) => i,
+            Err(_) => continue,
+        };
+        let (kind, amount) = instruction_kind(&instruction);
+        indexed.push(IndexedInstruction {
+            slot: confirmed.slot,
+            signature: signature.to_string(),
+            ix_index: i as u32,
+            kind,
+            amount,
+            source: account(&ix.accounts, 0),
+            destination: account(&ix.accounts, 1),
+        });
+    }
+    Ok(indexed)
+}
+
+pub(crate) fn instruction_kind(instruction: &BankInstruction) -> (String, Option<u64>) {
+    let (kind, amount) = match instruction {
+        BankInstruction::InitializeBank { .. } => ("InitializeBank", None),
+        BankInstruction::InitializeAccount => ("InitializeAccount", None),
+        BankInstruction::Transfer { amount, .. } => ("Transfer", Some(*amount)),
+        BankInstruction::Approve { amount } => ("Approve", Some(*amount)),
+        BankInstruction::MintTo { amount } => ("MintTo", Some(*amount)),
+        BankInstruction::Burn { amount } => ("Burn", Some(*amount)),
+        BankInstruction::CloseAccount => ("CloseAccount", None),
+        BankInstruction::Revoke => ("Revoke", None),
+        BankInstruction::SetClosePolicy { .. } => ("SetClosePolicy", None),
+        BankInstruction::SetRequireRentExempt { .. } => ("SetRequireRentExempt", None),
+        BankInstruction::PermitApprove { amount, .. } => ("PermitApprove", Some(*amount)),
+        BankInstruction::TransferWithExpiry { amount, .. } => ("TransferWithExpiry", Some(*amount)),
+        BankInstruction::TransferRoute { amount } => ("TransferRoute", Some(*amount)),
+        BankInstruction::AssignToProgram { .. } => ("AssignToProgram", None),
+        BankInstruction::DepositSol { lamports } => ("DepositSol", Some(*lamports)),
+        BankInstruction::WithdrawSol { lamports } => ("WithdrawSol", Some(*lamports)),
+        BankInstruction::SetExchangeRate { .. } => ("SetExchangeRate", None),
+        BankInstruction::Convert { amount } => ("Convert", Some(*amount)),
+        BankInstruction::SetDustThreshold { .. } => ("SetDustThreshold", None),
+        BankInstruction::CloseDustAccount => ("CloseDustAccount", None),
+        BankInstruction::SetGuardians { .. } => ("SetGuardians", None),
+        BankInstruction::StartRecovery { .. } => ("StartRecovery", None),
+        BankInstruction::RecoverOwner => ("RecoverOwner", None),
+        BankInstruction::CancelRecovery => ("CancelRecovery", None),
+        BankInstruction::CreateSessionKey { .. } => ("CreateSessionKey", None),
+        BankInstruction::SetRateLimit { .. } => ("SetRateLimit", None),
+        BankInstruction::InitializeVersion => ("InitializeVersion", None),
+        BankInstruction::SetFeatures { .. } => ("SetFeatures", None),
+        BankInstruction::FreezeAccount => ("FreezeAccount", None),
+        BankInstruction::ThawAccount => ("ThawAccount", None),
+        BankInstruction::InitializeMultisig { .. } => ("InitializeMultisig", None),
+        BankInstruction::InitializeAssociatedAccount => ("InitializeAssociatedAccount", None),
+        BankInstruction::TransferChecked { amount, .. } => ("TransferChecked", Some(*amount)),
+        BankInstruction::MintToChecked { amount, .. } => ("MintToChecked", Some(*amount)),
+        BankInstruction::BurnChecked { amount, .. } => ("BurnChecked", Some(*amount)),
+        BankInstruction::SetAuthority { .. } => ("SetAuthority", None),
+        BankInstruction::TransferBatch { amounts } => (
+            "TransferBatch",
+            Some(
+                amounts
+                    .iter()
+                    .fold(0, |total: u64, amount| total.saturating_add(*amount)),
+            ),
+        ),
+        BankInstruction::PauseBank => ("PauseBank", None),
+        BankInstruction::ResumeBank => ("ResumeBank", None),
+        BankInstruction::AccrueInterest => ("AccrueInterest", None),
+        BankInstruction::SetOwnerChangeDelay { .. } => ("SetOwnerChangeDelay", None),
+        BankInstruction::CancelOwnerChange => ("CancelOwnerChange", None),
+    };
+    (kind.to_string(), amount)
+}
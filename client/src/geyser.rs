@@ -0,0 +1,119 @@
+use crate::watch::{decode_update, BankUpdate};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    io::{BufRead, BufReader},
+    process::{Command, Stdio},
+    str::FromStr,
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+use tracing::{debug, error};
+
+/// One line printed by bank-geyser.
+#[derive(Deserialize)]
+struct AccountUpdate {
+    pubkey: String,
+    slot: u64,
+    data: String,
+}
+
+/// Streams updates of every account owned by `program_id` from a Yellowstone gRPC
+/// endpoint, producing the same `BankUpdate`s as `watch::watch_program`. Unlike the
+/// websocket, the geyser stream does not drop notifications under load.
+///
+/// Yellowstone needs a newer solana than this crate builds against, so the stream is
+/// read from the bank-geyser binary of the `geyser` crate, which must be on the PATH.
+pub fn watch_program_grpc(
+    endpoint: &str,
+    x_token: Option<String>,
+    program_id: &Pubkey,
+) -> Result<Receiver<BankUpdate>, String> {
+    let mut command = Command::new("bank-geyser");
+    command
+        .args(&["--endpoint", endpoint])
+        .args(&["--program-id", &program_id.to_string()]);
+    if let Some(x_token) = &x_token {
+        command.args(&["--x-token", x_token]);
+    }
+    let mut child = match command.stdout(Stdio::piped()).spawn() {
+        Ok(c) => c,
+        Err(e) => {
+            error!(error = %e, "start bank-geyser failed");
+            return Err("start bank-geyser failed".to_string());
+        }
+    };
+    let stdout = child.stdout.take().unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let line = match line {
+                Ok(l) => l,
+                Err(e) => {
+                    error!(error = %e, "read bank-geyser failed");
+                    break;
+                }
+            };
+            let update = match parse_update(&line) {
+                Some(u) => u,
+                None => continue,
+            };
+            if sender.send(update).is_err() {
+                break;
+            }
+        }
+        debug!("geyser stream closed");
+        let _ = child.kill();
+        let _ = child.wait();
+    });
+    Ok(receiver)
+}
+
+fn parse_update(line: &str) -> Option<BankUpdate> {
+    let update: AccountUpdate = serde_json::from_str(line).ok()?;
+    let key = Pubkey::from_str(&update.pubkey).ok()?;
+    let data = base64::decode(&update.data).ok()?;
+    decode_update(key, update.slot, &data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_bank::state::Account;
+    use solana_program::{program_option::COption, program_pack::Pack};
+
+    #[test]
+    fn test_parse_update() {
+        let key = Pubkey::new_unique();
+        let account = Account {
+            amount: 100,
+            is_opened: true,
+            is_initialized: true,
+            owner: Pubkey::new_unique(),
+            delegate: COption::None,
+            delegated_amount: 0,
+            bank: Pubkey::new_unique(),
+            permit_nonce: 0,
+            is_frozen: false,
+            last_accrual: 0,
+        };
+        let mut data = vec![0u8; Account::LEN];
+        Account::pack(account, &mut data).unwrap();
+        let line = format!(
+            r#"{{"pubkey":"{}","slot":8,"data":"{}"}}"#,
+            key,
+            base64::encode(&data)
+        );
+        assert_eq!(
+            parse_update(&line),
+            Some(BankUpdate::Account {
+                key,
+                slot: 8,
+                account
+            })
+        );
+
+        assert_eq!(parse_update("geyser subscription started"), None);
+    }
+}
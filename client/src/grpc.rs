@@ -0,0 +1,245 @@
+//! The `bank.v1.Bank` gRPC service of `proto/bank.proto`, backed by `BankClient`: the
+//! same reads and transfers `server` offers over HTTP, for services that speak gRPC.
+
+use crate::{bank::BankClient, util::SendMode};
+use solana_bank::{
+    instruction,
+    state::{Account, Bank},
+};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use std::{net::SocketAddr, str::FromStr, sync::Arc};
+use tonic::{Request, Response, Status};
+use tracing::{error, info};
+
+pub mod proto {
+    tonic::include_proto!("bank.v1");
+}
+
+use proto::{
+    bank_server::{self, BankServer},
+    AccountState, BankState, GetAccountRequest, GetBankRequest, ListAccountsRequest,
+    ListAccountsResponse, SubmitTransferRequest, SubmitTransferResponse,
+};
+
+impl BankState {
+    pub fn new(address: &Pubkey, bank: &Bank) -> BankState {
+        BankState {
+            address: address.to_string(),
+            decimals: bank.decimals as u32,
+            bank_owner: bank.bank_owner.to_string(),
+            is_opened: bank.is_opened,
+            total_supply: bank.total_supply,
+        }
+    }
+}
+
+impl AccountState {
+    pub fn new(address: &Pubkey, account: &Account) -> AccountState {
+        AccountState {
+            address: address.to_string(),
+            bank: account.bank.to_string(),
+            owner: account.owner.to_string(),
+            amount: account.amount,
+            delegate: Option::<Pubkey>::from(account.delegate).map(|d| d.to_string()),
+            delegated_amount: account.delegated_amount,
+            is_opened: account.is_opened,
+        }
+    }
+}
+
+struct ServiceState {
+    bank_client: BankClient,
+    /// Authority of the transfers submitted through `SubmitTransfer`; also pays their fees.
+    signer: Keypair,
+}
+
+pub struct BankService {
+    state: Arc<ServiceState>,
+}
+
+impl BankService {
+    pub fn new(bank_client: BankClient, signer: Keypair) -> BankService {
+        BankService {
+            state: Arc::new(ServiceState {
+                bank_client,
+                signer,
+            }),
+        }
+    }
+
+    /// Runs a blocking RPC call off the async workers; its error is reported as `code`.
+    async fn blocking<T, F>(&self, code: tonic::Code, f: F) -> Result<T, Status>
+    where
+        T: Send + 'static,
+        F: FnOnce(&ServiceState) -> Result<T, String> + Send + 'static,
+    {
+        let state = self.state.clone();
+        match tokio::task::spawn_blocking(move || f(&state)).await {
+            Ok(result) => result.map_err(|e| Status::new(code, e)),
+            Err(e) => {
+                error!(error = %e, "request handler panicked");
+                Err(Status::internal("internal error"))
+            }
+        }
+    }
+}
+
+fn invalid(name: &str) -> Status {
+    Status::invalid_argument(format!("invalid {}", name))
+}
+
+#[tonic::async_trait]
+impl bank_server::Bank for BankService {
+    async fn get_bank(
+        &self,
+        request: Request<GetBankRequest>,
+    ) -> Result<Response<BankState>, Status> {
+        let key = Pubkey::from_str(&request.get_ref().address).map_err(|_| invalid("address"))?;
+        let bank = self
+            .blocking(tonic::Code::NotFound, move |s| s.bank_client.get_bank(&key))
+            .await?;
+        Ok(Response::new(BankState::new(&key, &bank)))
+    }
+
+    async fn get_account(
+        &self,
+        request: Request<GetAccountRequest>,
+    ) -> Result<Response<AccountState>, Status> {
+        let key = Pubkey::from_str(&request.get_ref().address).map_err(|_| invalid("address"))?;
+        let account = self
+            .blocking(tonic::Code::NotFound, move |s| {
+                s.bank_client.get_account(&key)
+            })
+            .await?;
+        Ok(Response::new(AccountState::new(&key, &account)))
+    }
+
+    async fn list_accounts(
+        &self,
+        request: Request<ListAccountsRequest>,
+    ) -> Result<Response<ListAccountsResponse>, Status> {
+        let owner = Pubkey::from_str(&request.get_ref().owner).map_err(|_| invalid("owner"))?;
+        let accounts = self
+            .blocking(tonic::Code::Unavailable, move |s| {
+                s.bank_client.get_owner_accounts(&owner)
+            })
+            .await?;
+        Ok(Response::new(ListAccountsResponse {
+            accounts: accounts
+                .iter()
+                .map(|(key, account)| AccountState::new(key, account))
+                .collect(),
+        }))
+    }
+
+    async fn submit_transfer(
+        &self,
+        request: Request<SubmitTransferRequest>,
+    ) -> Result<Response<SubmitTransferResponse>, Status> {
+        let request = request.into_inner();
+        let from = Pubkey::from_str(&request.from).map_err(|_| invalid("from"))?;
+        let to = Pubkey::from_str(&request.to).map_err(|_| invalid("to"))?;
+        let amount = request.amount;
+        let memo = request.memo;
+        let signature = self
+            .blocking(tonic::Code::Unavailable, move |s| {
+                let authority = s.signer.pubkey();
+                let bank = s.bank_client.get_account(&from)?.bank;
+                let transfer = instruction::transfer(
+                    &s.bank_client.program_id,
+                    &bank,
+                    &from,
+                    &to,
+                    &authority,
+                    amount,
+                )
+                .map_err(|e| e.to_string())?;
+                let mut instructions = vec![transfer];
+                if let Some(memo) = &memo {
+                    instructions.push(crate::util::memo_instruction(memo, &[&authority]));
+                }
+                s.bank_client
+                    .send(&instructions, &[&s.signer], SendMode::Broadcast)?
+                    .ok_or_else(|| "transaction was not sent".to_string())
+            })
+            .await?;
+        info!(%signature, %from, %to, amount, "transfer sent");
+        Ok(Response::new(SubmitTransferResponse {
+            signature: signature.to_string(),
+        }))
+    }
+}
+
+/// Serves the `Bank` gRPC service on `addr` until the process is stopped.
+/// `SubmitTransfer` signs with `signer`, which must own or be the delegate of the source account.
+pub fn run(bank_client: BankClient, signer: Keypair, addr: SocketAddr) -> Result<(), String> {
+    let runtime = match tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(r) => r,
+        Err(e) => {
+            error!(error = %e, "start runtime failed");
+            return Err("start runtime failed".to_string());
+        }
+    };
+    let service = BankServer::new(BankService::new(bank_client, signer));
+
+    runtime.block_on(async move {
+        info!(%addr, "serving gRPC");
+        tonic::transport::Server::builder()
+            .add_service(service)
+            .serve(addr)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "gRPC server failed");
+                "gRPC server failed".to_string()
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proto::bank_server::Bank as _;
+    use solana_client::rpc_client::RpcClient;
+
+    #[test]
+    fn test_invalid_arguments() {
+        // Nothing listens here; every request below fails before reaching it.
+        let rpc = RpcClient::new("http://127.0.0.1:1".to_string());
+        let service = BankService::new(BankClient::new(rpc, Pubkey::new_unique()), Keypair::new());
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let status = runtime
+            .block_on(service.get_bank(Request::new(GetBankRequest {
+                address: "not-a-key".to_string(),
+            })))
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert_eq!(status.message(), "invalid address");
+
+        let status = runtime
+            .block_on(service.submit_transfer(Request::new(SubmitTransferRequest {
+                from: Pubkey::new_unique().to_string(),
+                to: "nope".to_string(),
+                amount: 1,
+                memo: None,
+            })))
+            .unwrap_err();
+        assert_eq!(status.message(), "invalid to");
+
+        let address = Pubkey::new_unique();
+        let state = AccountState::new(
+            &address,
+            &Account {
+                amount: 5,
+                ..Account::default()
+            },
+        );
+        assert_eq!(state.address, address.to_string());
+        assert_eq!(state.delegate, None);
+    }
+}
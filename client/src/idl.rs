@@ -0,0 +1,289 @@
+//! Instructions built from the IDL `cargo xtask idl` exports (`bank/idl.json`), for
+//! sending ones the CLI has no command for yet. Only what building needs is read: each
+//! instruction's tag, accounts and arguments.
+
+use serde::Deserialize;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use std::{fs, path::Path};
+use tracing::error;
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Idl {
+    pub name: String,
+    pub instructions: Vec<IdlInstruction>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct IdlInstruction {
+    pub name: String,
+    pub discriminant: u8,
+    pub accounts: Vec<IdlAccountMeta>,
+    pub args: Vec<IdlField>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct IdlAccountMeta {
+    pub name: String,
+    pub is_writable: bool,
+    pub is_signer: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct IdlField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+impl Idl {
+    pub fn load(path: &Path) -> Result<Idl, String> {
+        let contents = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => {
+                error!(error = %e, path = %path.display(), "read idl failed");
+                return Err(format!("read idl {} failed", path.display()));
+            }
+        };
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("invalid idl {}: {}", path.display(), e))
+    }
+
+    pub fn instruction(&self, name: &str) -> Result<&IdlInstruction, String> {
+        self.instructions
+            .iter()
+            .find(|ix| ix.name == name)
+            .ok_or_else(|| {
+                let names: Vec<_> = self
+                    .instructions
+                    .iter()
+                    .map(|ix| ix.name.as_str())
+                    .collect();
+                format!(
+                    "{} has no instruction {}; it has {}",
+                    self.name,
+                    name,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl IdlInstruction {
+    /// The tag byte followed by `args` packed in the IDL's order. `args` are
+    /// `(name, value)` pairs; a `coption<publicKey>` or `option<publicKey>` is a key or
+    /// `none`.
+    pub fn data(&self, args: &[(&str, &str)]) -> Result<Vec<u8>, String> {
+        let mut data = vec![self.discriminant];
+        for field in &self.args {
+            let value = lookup(args, &field.name)?;
+            let invalid = |e: String| format!("invalid {} {}: {}", field.name, value, e);
+            match field.ty.as_str() {
+                "u8" => data.push(value.parse::<u8>().map_err(|e| invalid(e.to_string()))?),
+                "u16" => data.extend_from_slice(
+                    &value
+                        .parse::<u16>()
+                        .map_err(|e| invalid(e.to_string()))?
+                        .to_le_bytes(),
+                ),
+                "u64" => data.extend_from_slice(
+                    &value
+                        .parse::<u64>()
+                        .map_err(|e| invalid(e.to_string()))?
+                        .to_le_bytes(),
+                ),
+                "bool" => {
+                    data.push(value.parse::<bool>().map_err(|e| invalid(e.to_string()))? as u8)
+                }
+                "publicKey" => data.extend_from_slice(
+                    value
+                        .parse::<Pubkey>()
+                        .map_err(|e| invalid(e.to_string()))?
+                        .as_ref(),
+                ),
+                "coption<publicKey>" => match value {
+                    "none" => data.extend_from_slice(&[0; 36]),
+                    _ => {
+                        let key = value
+                            .parse::<Pubkey>()
+                            .map_err(|e| invalid(e.to_string()))?;
+                        data.extend_from_slice(&1u32.to_le_bytes());
+                        data.extend_from_slice(key.as_ref());
+                    }
+                },
+                "option<publicKey>" => match value {
+                    "none" => data.push(0),
+                    _ => {
+                        let key = value
+                            .parse::<Pubkey>()
+                            .map_err(|e| invalid(e.to_string()))?;
+                        data.push(1);
+                        data.extend_from_slice(key.as_ref());
+                    }
+                },
+                ty => {
+                    return Err(format!(
+                        "{} has type {}, which isn't supported",
+                        field.name, ty
+                    ))
+                }
+            }
+        }
+        if let Some((name, _)) = args
+            .iter()
+            .find(|(name, _)| !self.args.iter().any(|field| field.name == *name))
+        {
+            return Err(format!("unknown argument {}", name));
+        }
+        Ok(data)
+    }
+
+    /// The instruction with `accounts`, `(name, key)` pairs, in the IDL's order; a signer
+    /// that isn't named is `default_signer`.
+    pub fn build(
+        &self,
+        program_id: &Pubkey,
+        args: &[(&str, &str)],
+        accounts: &[(&str, &str)],
+        default_signer: &Pubkey,
+    ) -> Result<Instruction, String> {
+        let metas = self
+            .accounts
+            .iter()
+            .map(|account| {
+                let key = match accounts.iter().find(|(name, _)| *name == account.name) {
+                    Some((_, key)) => key
+                        .parse()
+                        .map_err(|e| format!("invalid {} {}: {}", account.name, key, e))?,
+                    None if account.is_signer => *default_signer,
+                    None => return Err(format!("missing account {}", account.name)),
+                };
+                Ok(if account.is_writable {
+                    AccountMeta::new(key, account.is_signer)
+                } else {
+                    AccountMeta::new_readonly(key, account.is_signer)
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        if let Some((name, _)) = accounts
+            .iter()
+            .find(|(name, _)| !self.accounts.iter().any(|account| account.name == *name))
+        {
+            return Err(format!("unknown account {}", name));
+        }
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: metas,
+            data: self.data(args)?,
+        })
+    }
+}
+
+/// Splits `name=value` pairs as given on the command line.
+pub fn parse_pairs(pairs: &[String]) -> Result<Vec<(&str, &str)>, String> {
+    pairs
+        .iter()
+        .map(|pair| {
+            pair.split_once('=')
+                .ok_or_else(|| format!("{} isn't name=value", pair))
+        })
+        .collect()
+}
+
+fn lookup<'a>(pairs: &[(&str, &'a str)], name: &str) -> Result<&'a str, String> {
+    pairs
+        .iter()
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| *value)
+        .ok_or_else(|| format!("missing argument {}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_bank::instruction;
+
+    fn bank_idl() -> Idl {
+        serde_json::from_str(include_str!("../../bank/idl.json")).unwrap()
+    }
+
+    #[test]
+    fn test_build_matches_builders() {
+        let idl = bank_idl();
+        let program_id = Pubkey::new_unique();
+        let (from, to, owner, bank) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let (from_s, to_s, bank_s) = (from.to_string(), to.to_string(), bank.to_string());
+        let built = idl
+            .instruction("Transfer")
+            .unwrap()
+            .build(
+                &program_id,
+                &[("amount", "50")],
+                &[("from", &from_s), ("to", &to_s), ("bank", &bank_s)],
+                &owner,
+            )
+            .unwrap();
+        assert_eq!(
+            built,
+            instruction::transfer(&program_id, &bank, &from, &to, &owner, 50).unwrap()
+        );
+
+        let built = idl
+            .instruction("InitializeBank")
+            .unwrap()
+            .build(
+                &program_id,
+                &[
+                    ("decimals", "2"),
+                    ("burn_policy", "0"),
+                    ("lamports_per_token", "0"),
+                    ("freeze_authority", "none"),
+                    ("interest_rate_bps", "0"),
+                ],
+                &[("bank", &from_s)],
+                &owner,
+            )
+            .unwrap();
+        assert_eq!(
+            built,
+            instruction::initialize_bank(&program_id, &from, &owner, 2).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let idl = bank_idl();
+        assert!(idl.instruction("Nope").unwrap_err().contains("Transfer"));
+        let transfer = idl.instruction("Transfer").unwrap();
+        assert_eq!(
+            transfer.data(&[]),
+            Err("missing argument amount".to_string())
+        );
+        assert_eq!(
+            transfer.data(&[("amount", "1"), ("amonut", "2")]),
+            Err("unknown argument amonut".to_string())
+        );
+        assert!(transfer.data(&[("amount", "-1")]).is_err());
+        let key = Pubkey::new_unique().to_string();
+        assert_eq!(
+            transfer.build(
+                &Pubkey::new_unique(),
+                &[("amount", "1")],
+                &[("from", &key)],
+                &Pubkey::new_unique()
+            ),
+            Err("missing account to".to_string())
+        );
+        assert_eq!(
+            parse_pairs(&["amount=5".to_string(), "x".to_string()]),
+            Err("x isn't name=value".to_string())
+        );
+    }
+}
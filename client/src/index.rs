@@ -0,0 +1,196 @@
+use crate::{events::IndexedInstruction, watch::BankUpdate};
+use rusqlite::{params, Connection};
+use solana_sdk::pubkey::Pubkey;
+use tracing::error;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS account_updates (
+    slot INTEGER NOT NULL,
+    pubkey TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    delegated_amount INTEGER,
+    owner TEXT,
+    bank TEXT,
+    is_opened INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS account_updates_pubkey ON account_updates (pubkey, slot);
+CREATE INDEX IF NOT EXISTS account_updates_owner ON account_updates (owner);
+CREATE TABLE IF NOT EXISTS instructions (
+    slot INTEGER NOT NULL,
+    signature TEXT NOT NULL,
+    ix_index INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    amount INTEGER,
+    source TEXT,
+    destination TEXT,
+    PRIMARY KEY (signature, ix_index)
+);
+CREATE INDEX IF NOT EXISTS instructions_source ON instructions (source, slot);
+";
+
+/// SQLite store for observed account updates and decoded instructions; rows keep
+/// their slot so queries can replay state in order.
+pub struct Indexer {
+    conn: Connection,
+}
+
+impl Indexer {
+    pub fn open(path: &str) -> Result<Indexer, String> {
+        let conn = match Connection::open(path) {
+            Ok(c) => c,
+            Err(e) => {
+                error!(error = %e, path, "open index database failed");
+                return Err("open index database failed".to_string());
+            }
+        };
+        if let Err(e) = conn.execute_batch(SCHEMA) {
+            error!(error = %e, "create index schema failed");
+            return Err("create index schema failed".to_string());
+        }
+        Ok(Indexer { conn })
+    }
+
+    pub fn record_update(&self, update: &BankUpdate) -> Result<(), String> {
+        let result = match update {
+            BankUpdate::Bank { key, slot, bank } => self.conn.execute(
+                "INSERT INTO account_updates (slot, pubkey, kind, amount, owner, is_opened)
+                 VALUES (?1, ?2, 'bank', ?3, ?4, ?5)",
+                params![
+                    *slot as i64,
+                    key.to_string(),
+                    bank.total_supply as i64,
+                    bank.bank_owner.to_string(),
+                    bank.is_opened
+                ],
+            ),
+            BankUpdate::Account { key, slot, account } => self.conn.execute(
+                "INSERT INTO account_updates
+                 (slot, pubkey, kind, amount, delegated_amount, owner, bank, is_opened)
+                 VALUES (?1, ?2, 'account', ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    *slot as i64,
+                    key.to_string(),
+                    account.amount as i64,
+                    account.delegated_amount as i64,
+                    account.owner.to_string(),
+                    account.bank.to_string(),
+                    account.is_opened
+                ],
+            ),
+        };
+        result.map(|_| ()).map_err(|e| {
+            error!(error = %e, "insert account update failed");
+            "insert account update failed".to_string()
+        })
+    }
+
+    pub fn record_instruction(&self, ix: &IndexedInstruction) -> Result<(), String> {
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO instructions
+                 (slot, signature, ix_index, kind, amount, source, destination)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    ix.slot as i64,
+                    ix.signature,
+                    ix.ix_index,
+                    ix.kind,
+                    ix.amount.map(|a| a as i64),
+                    ix.source.map(|k| k.to_string()),
+                    ix.destination.map(|k| k.to_string())
+                ],
+            )
+            .map(|_| ())
+            .map_err(|e| {
+                error!(error = %e, "insert instruction failed");
+                "insert instruction failed".to_string()
+            })
+    }
+
+    /// (slot, amount) of every recorded update of `account`, oldest first.
+    pub fn balance_history(&self, account: &Pubkey) -> Result<Vec<(u64, u64)>, String> {
+        let query = || -> rusqlite::Result<Vec<(u64, u64)>> {
+            let mut stmt = self.conn.prepare(
+                "SELECT slot, amount FROM account_updates
+                 WHERE pubkey = ?1 AND kind = 'account' ORDER BY slot, rowid",
+            )?;
+            let rows = stmt.query_map(params![account.to_string()], |row| {
+                Ok((row.get::<_, i64>(0)? as u64, row.get::<_, i64>(1)? as u64))
+            })?;
+            rows.collect()
+        };
+        query().map_err(|e| {
+            error!(error = %e, "query balance history failed");
+            "query balance history failed".to_string()
+        })
+    }
+
+    /// Total amount transferred out of accounts owned by `owner`.
+    pub fn owner_volume(&self, owner: &Pubkey) -> Result<u64, String> {
+        self.conn
+            .query_row(
+                "SELECT SUM(amount) FROM instructions WHERE kind IN ('Transfer', 'TransferWithExpiry', 'TransferChecked', 'TransferBatch') AND source IN
+                 (SELECT DISTINCT pubkey FROM account_updates WHERE kind = 'account' AND owner = ?1)",
+                params![owner.to_string()],
+                |row| row.get::<_, Option<i64>>(0),
+            )
+            .map(|sum| sum.unwrap_or(0) as u64)
+            .map_err(|e| {
+                error!(error = %e, "query owner volume failed");
+                "query owner volume failed".to_string()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::instruction_kind;
+    use solana_bank::{instruction::BankInstruction, state::Account};
+
+    #[test]
+    fn test_balance_history_and_volume() {
+        let indexer = Indexer::open(":memory:").unwrap();
+        let owner = Pubkey::new_unique();
+        let from = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+        for (slot, amount) in [(10, 100), (12, 70), (11, 90)] {
+            indexer
+                .record_update(&BankUpdate::Account {
+                    key: from,
+                    slot,
+                    account: Account {
+                        amount,
+                        owner,
+                        ..Account::default()
+                    },
+                })
+                .unwrap();
+        }
+        assert_eq!(
+            indexer.balance_history(&from).unwrap(),
+            vec![(10, 100), (11, 90), (12, 70)]
+        );
+
+        for (slot, amount) in [(11, 10), (12, 20)] {
+            let (kind, amount) = instruction_kind(&BankInstruction::Transfer {
+                amount,
+                sweep_dust: false,
+            });
+            indexer
+                .record_instruction(&IndexedInstruction {
+                    slot,
+                    signature: format!("sig{}", slot),
+                    ix_index: 0,
+                    kind,
+                    amount,
+                    source: Some(from),
+                    destination: Some(to),
+                })
+                .unwrap();
+        }
+        assert_eq!(indexer.owner_volume(&owner).unwrap(), 30);
+        assert_eq!(indexer.owner_volume(&to).unwrap(), 0);
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{bank::ui_amount, pay::TransferRequest};
+use solana_invoice::state::Invoice;
+use solana_sdk::pubkey::Pubkey;
+
+/// A Solana Pay style transfer request for `invoice`: the destination as recipient, the
+/// amount in whole tokens of a bank with `decimals`, and the invoice address as reference,
+/// so the payment can be found on chain. Wallets see what is owed and where; paying
+/// still goes through the invoice program, e.g. `bank-cli invoice pay`.
+pub fn pay_url(invoice: &Pubkey, state: &Invoice, decimals: u8) -> String {
+    TransferRequest {
+        amount: Some(ui_amount(state.amount, decimals)),
+        references: vec![*invoice],
+        message: Some(state.memo.clone()).filter(|memo| !memo.is_empty()),
+        ..TransferRequest::new(state.destination)
+    }
+    .to_url()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pay_url() {
+        let invoice = Pubkey::new_unique();
+        let state = Invoice {
+            destination: Pubkey::new_unique(),
+            amount: 1_050,
+            memo: "order #7 & co".to_string(),
+            ..Invoice::default()
+        };
+        assert_eq!(
+            pay_url(&invoice, &state, 2),
+            format!(
+                "solana:{}?amount=10.50&reference={}&message=order%20%237%20%26%20co",
+                state.destination, invoice
+            )
+        );
+
+        let state = Invoice {
+            memo: String::new(),
+            ..state
+        };
+        assert_eq!(
+            pay_url(&invoice, &state, 0),
+            format!(
+                "solana:{}?amount=1050&reference={}",
+                state.destination, invoice
+            )
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Nonce,
+};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    signature::{read_keypair_file, write_keypair_file, Keypair},
+    signer::Signer,
+};
+use std::{fs, path::Path};
+use tracing::error;
+
+/// scrypt cost used for new files; the value is stored in the file so it can change later.
+const DEFAULT_LOG_N: u8 = 15;
+
+/// On-disk format of a passphrase protected keypair. The 64 keypair bytes are
+/// sealed with AES-256-GCM under a key derived from the passphrase with scrypt.
+#[derive(Serialize, Deserialize)]
+struct EncryptedKeypair {
+    version: u8,
+    pubkey: String,
+    kdf: String,
+    log_n: u8,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+pub fn write_keypair(
+    keypair: &Keypair,
+    outfile: &Path,
+    passphrase: Option<&str>,
+) -> Result<(), String> {
+    let passphrase = match passphrase {
+        Some(p) => p,
+        None => {
+            return match write_keypair_file(keypair, outfile) {
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    error!(error = %e, "write keypair failed");
+                    Err("write keypair failed".to_string())
+                }
+            };
+        }
+    };
+
+    let encrypted = encrypt_keypair(keypair, passphrase, DEFAULT_LOG_N)?;
+    let json = match serde_json::to_string_pretty(&encrypted) {
+        Ok(j) => j,
+        Err(e) => {
+            error!(error = %e, "serialize keypair failed");
+            return Err("serialize keypair failed".to_string());
+        }
+    };
+    match fs::write(outfile, json) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            error!(error = %e, "write keypair failed");
+            Err("write keypair failed".to_string())
+        }
+    }
+}
+
+pub fn read_encrypted_keypair(path: &Path, passphrase: &str) -> Result<Keypair, String> {
+    let json = match fs::read_to_string(path) {
+        Ok(j) => j,
+        Err(e) => {
+            error!(error = %e, "read keypair failed");
+            return Err("read keypair failed".to_string());
+        }
+    };
+    let encrypted: EncryptedKeypair = match serde_json::from_str(&json) {
+        Ok(k) => k,
+        Err(e) => {
+            error!(error = %e, "parse encrypted keypair failed");
+            return Err("parse encrypted keypair failed".to_string());
+        }
+    };
+    decrypt_keypair(&encrypted, passphrase)
+}
+
+/// Reads a keypair written by `write_keypair`, prompting for the passphrase when the
+/// file is encrypted.
+pub fn load_keypair(path: &Path) -> Result<Keypair, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => {
+            error!(error = %e, path = %path.display(), "read keypair failed");
+            return Err(format!("read keypair {} failed", path.display()));
+        }
+    };
+    if !contents.trim_start().starts_with('{') {
+        return match read_keypair_file(path) {
+            Ok(k) => Ok(k),
+            Err(e) => {
+                error!(error = %e, path = %path.display(), "parse keypair failed");
+                Err(format!("parse keypair {} failed", path.display()))
+            }
+        };
+    }
+
+    let prompt = format!("passphrase for {}: ", path.display());
+    let passphrase = rpassword::prompt_password(prompt).map_err(|e| e.to_string())?;
+    read_encrypted_keypair(path, &passphrase)
+}
+
+fn encrypt_keypair(
+    keypair: &Keypair,
+    passphrase: &str,
+    log_n: u8,
+) -> Result<EncryptedKeypair, String> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt, log_n)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| "invalid key".to_string())?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, keypair.to_bytes().as_ref())
+        .map_err(|_| "encrypt keypair failed".to_string())?;
+
+    Ok(EncryptedKeypair {
+        version: 1,
+        pubkey: keypair.pubkey().to_string(),
+        kdf: "scrypt".to_string(),
+        log_n,
+        salt: base64::encode(salt),
+        nonce: base64::encode(nonce),
+        ciphertext: base64::encode(ciphertext),
+    })
+}
+
+fn decrypt_keypair(encrypted: &EncryptedKeypair, passphrase: &str) -> Result<Keypair, String> {
+    if encrypted.version != 1 || encrypted.kdf != "scrypt" {
+        return Err("unsupported keypair encryption".to_string());
+    }
+    let decode = |field: &str| base64::decode(field).map_err(|_| "invalid base64".to_string());
+    let salt = decode(&encrypted.salt)?;
+    let nonce = decode(&encrypted.nonce)?;
+    let ciphertext = decode(&encrypted.ciphertext)?;
+    if nonce.len() != 12 {
+        return Err("invalid nonce".to_string());
+    }
+
+    let key = derive_key(passphrase, &salt, encrypted.log_n)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| "invalid key".to_string())?;
+    let bytes = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| "wrong passphrase or corrupted keypair".to_string())?;
+    Keypair::from_bytes(&bytes).map_err(|_| "invalid keypair bytes".to_string())
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], log_n: u8) -> Result<[u8; 32], String> {
+    let params =
+        scrypt::Params::new(log_n, 8, 1).map_err(|_| "invalid scrypt params".to_string())?;
+    let mut key = [0u8; 32];
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key)
+        .map_err(|_| "derive key failed".to_string())?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt_keypair() {
+        let keypair = Keypair::new();
+        let encrypted = encrypt_keypair(&keypair, "hunter2", 4).unwrap();
+        assert_eq!(encrypted.pubkey, keypair.pubkey().to_string());
+
+        let decrypted = decrypt_keypair(&encrypted, "hunter2").unwrap();
+        assert_eq!(decrypted.to_bytes(), keypair.to_bytes());
+        assert!(decrypt_keypair(&encrypted, "hunter3").is_err());
+    }
+}
@@ -1 +1,42 @@
+pub mod airdrop;
+pub mod bank;
+pub mod bench;
+pub mod blockhash;
+pub mod board;
+pub mod claim;
+pub mod events;
+pub mod idl;
+pub mod invoice;
+pub mod keygen;
+pub mod pay;
+pub mod pool;
+pub mod preflight;
+pub mod progress;
+pub mod proposal;
+pub mod rpc;
+pub mod stats;
+pub mod sweep;
 pub mod util;
+pub mod wallet;
+pub mod watch;
+
+#[cfg(feature = "bulk")]
+pub mod bulk;
+#[cfg(feature = "geyser")]
+pub mod geyser;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "index")]
+pub mod index;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "notify")]
+pub mod notify;
+#[cfg(feature = "postgres")]
+pub mod pg_index;
+#[cfg(feature = "remote-signer")]
+pub mod remote_signer;
+#[cfg(feature = "serve")]
+pub mod server;
+#[cfg(feature = "tui")]
+pub mod tui;
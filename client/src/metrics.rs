@@ -0,0 +1,83 @@
+use lazy_static::lazy_static;
+use prometheus::{
+    register_histogram, register_int_counter, register_int_counter_vec, Encoder, Histogram,
+    IntCounter, IntCounterVec, TextEncoder,
+};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use std::{thread, time::Duration};
+use tracing::error;
+
+lazy_static! {
+    static ref TRANSACTIONS_SENT: IntCounter = register_int_counter!(
+        "bank_client_transactions_sent_total",
+        "Number of transactions submitted to the cluster"
+    )
+    .unwrap();
+    static ref CONFIRMATION_LATENCY: Histogram = register_histogram!(
+        "bank_client_confirmation_latency_seconds",
+        "Time between submitting a transaction and its confirmation",
+        vec![0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0]
+    )
+    .unwrap();
+    static ref RPC_ERRORS: IntCounterVec = register_int_counter_vec!(
+        "bank_client_rpc_errors_total",
+        "Number of failed RPC calls by error kind",
+        &["kind"]
+    )
+    .unwrap();
+}
+
+pub fn record_transaction_sent() {
+    TRANSACTIONS_SENT.inc();
+}
+
+pub fn observe_confirmation_latency(elapsed: Duration) {
+    CONFIRMATION_LATENCY.observe(elapsed.as_secs_f64());
+}
+
+pub fn record_rpc_error(err: &ClientError) {
+    RPC_ERRORS.with_label_values(&[error_kind(err)]).inc();
+}
+
+fn error_kind(err: &ClientError) -> &'static str {
+    match err.kind() {
+        ClientErrorKind::Io(_) => "io",
+        ClientErrorKind::Reqwest(_) => "reqwest",
+        ClientErrorKind::RpcError(_) => "rpc",
+        ClientErrorKind::SerdeJson(_) => "serde_json",
+        ClientErrorKind::SigningError(_) => "signing",
+        ClientErrorKind::TransactionError(_) => "transaction",
+        _ => "other",
+    }
+}
+
+/// Serve the default prometheus registry as text on `http://<addr>/metrics`
+/// from a background thread.
+pub fn serve(addr: &str) -> Result<thread::JoinHandle<()>, String> {
+    let server = match tiny_http::Server::http(addr) {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "start metrics server failed");
+            return Err("start metrics server failed".to_string());
+        }
+    };
+
+    let handle = thread::spawn(move || {
+        for request in server.incoming_requests() {
+            if request.url() != "/metrics" {
+                let _ = request.respond(tiny_http::Response::empty(404));
+                continue;
+            }
+
+            let mut buffer = Vec::new();
+            let encoder = TextEncoder::new();
+            if let Err(e) = encoder.encode(&prometheus::gather(), &mut buffer) {
+                error!(error = %e, "encode metrics failed");
+                let _ = request.respond(tiny_http::Response::empty(500));
+                continue;
+            }
+            let _ = request.respond(tiny_http::Response::from_data(buffer));
+        }
+    });
+    Ok(handle)
+}
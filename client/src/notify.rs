@@ -0,0 +1,144 @@
+use crate::watch::BankUpdate;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::HashMap, sync::mpsc::Receiver, thread::sleep, time::Duration};
+use tracing::{error, info, warn};
+
+pub struct NotifyConfig {
+    pub webhook_url: String,
+    /// Balances to watch with their last known amount.
+    pub accounts: HashMap<Pubkey, u64>,
+    /// Changes smaller than this are not reported.
+    pub min_amount: u64,
+    pub max_retries: u32,
+}
+
+/// JSON body posted to the webhook.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TransferEvent {
+    pub account: String,
+    pub bank: String,
+    pub slot: u64,
+    /// "in" when the balance grew, "out" when it shrank.
+    pub direction: &'static str,
+    pub change: u64,
+    pub previous_amount: u64,
+    pub amount: u64,
+}
+
+/// Compares an update against the last known balance of a watched account and
+/// records the new balance.
+pub fn transfer_event(
+    balances: &mut HashMap<Pubkey, u64>,
+    update: &BankUpdate,
+    min_amount: u64,
+) -> Option<TransferEvent> {
+    let (key, slot, account) = match update {
+        BankUpdate::Account { key, slot, account } => (key, *slot, account),
+        _ => return None,
+    };
+    let previous_amount = balances.get_mut(key)?;
+    let previous = *previous_amount;
+    *previous_amount = account.amount;
+
+    let (direction, change) = if account.amount >= previous {
+        ("in", account.amount - previous)
+    } else {
+        ("out", previous - account.amount)
+    };
+    if change == 0 || change < min_amount {
+        return None;
+    }
+    Some(TransferEvent {
+        account: key.to_string(),
+        bank: account.bank.to_string(),
+        slot,
+        direction,
+        change,
+        previous_amount: previous,
+        amount: account.amount,
+    })
+}
+
+/// Posts a `TransferEvent` for every balance change of the configured accounts
+/// until the update stream closes.
+pub fn run(updates: &Receiver<BankUpdate>, config: NotifyConfig) -> Result<(), String> {
+    let mut balances = config.accounts;
+    info!(accounts = balances.len(), "watching for transfers");
+    for update in updates.iter() {
+        if let Some(event) = transfer_event(&mut balances, &update, config.min_amount) {
+            info!(
+                account = %event.account,
+                change = event.change,
+                direction = event.direction,
+                "transfer observed"
+            );
+            if let Err(e) = post_with_retries(&config.webhook_url, &event, config.max_retries) {
+                error!(
+                    error = %e,
+                    account = %event.account,
+                    slot = event.slot,
+                    "webhook delivery failed"
+                );
+            }
+        }
+    }
+    Err("update stream closed".to_string())
+}
+
+fn post_with_retries(url: &str, event: &TransferEvent, max_retries: u32) -> Result<(), String> {
+    let mut backoff = Duration::from_millis(500);
+    let mut attempt = 0;
+    loop {
+        match ureq::post(url).send_json(event) {
+            Ok(_) => return Ok(()),
+            // 4xx means the payload was refused; sending it again won't help.
+            Err(ureq::Error::Status(code, _)) if (400..500).contains(&code) => {
+                return Err(format!("webhook rejected the event with status {}", code));
+            }
+            Err(e) if attempt < max_retries => {
+                attempt += 1;
+                warn!(error = %e, attempt, "webhook post failed, retrying");
+                sleep(backoff);
+                backoff *= 2;
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_bank::state::Account;
+
+    #[test]
+    fn test_transfer_event() {
+        let watched = Pubkey::new_unique();
+        let mut balances = HashMap::new();
+        balances.insert(watched, 100);
+        let update = |key: Pubkey, amount: u64| BankUpdate::Account {
+            key,
+            slot: 5,
+            account: Account {
+                amount,
+                ..Account::default()
+            },
+        };
+
+        let event = transfer_event(&mut balances, &update(watched, 40), 10).unwrap();
+        assert_eq!(event.direction, "out");
+        assert_eq!(event.change, 60);
+        assert_eq!(balances[&watched], 40);
+
+        assert_eq!(
+            transfer_event(&mut balances, &update(watched, 45), 10),
+            None
+        );
+        assert_eq!(balances[&watched], 45);
+        assert_eq!(
+            transfer_event(&mut balances, &update(Pubkey::new_unique(), 1_000), 10),
+            None
+        );
+    }
+}
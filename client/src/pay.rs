@@ -0,0 +1,241 @@
+//! Solana Pay transfer requests for bank tokens: `solana:<recipient>?amount=...`. The
+//! recipient is the bank account to credit and `spl-token` names its bank, in the place
+//! an SPL Token request names the mint. Each `reference` is attached to the transfer as
+//! a read-only account, so the merchant finds the payment by looking the reference up.
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TransferRequest {
+    /// Bank account the tokens go to.
+    pub recipient: Pubkey,
+    /// Whole tokens, as `ui_amount` prints them; the payer picks the amount when unset.
+    pub amount: Option<String>,
+    /// Bank of the tokens asked for (`spl-token`).
+    pub bank: Option<Pubkey>,
+    pub references: Vec<Pubkey>,
+    /// Who is asking, e.g. the merchant's name.
+    pub label: Option<String>,
+    /// What the payment is for, shown to the payer.
+    pub message: Option<String>,
+    /// Sent on chain as an SPL Memo with the transfer.
+    pub memo: Option<String>,
+}
+
+impl TransferRequest {
+    pub fn new(recipient: Pubkey) -> TransferRequest {
+        TransferRequest {
+            recipient,
+            ..TransferRequest::default()
+        }
+    }
+
+    pub fn to_url(&self) -> String {
+        let mut params = Vec::new();
+        if let Some(amount) = &self.amount {
+            params.push(format!("amount={}", amount));
+        }
+        if let Some(bank) = &self.bank {
+            params.push(format!("spl-token={}", bank));
+        }
+        for reference in &self.references {
+            params.push(format!("reference={}", reference));
+        }
+        let text = [
+            ("label", &self.label),
+            ("message", &self.message),
+            ("memo", &self.memo),
+        ];
+        for (key, value) in text.iter() {
+            if let Some(value) = value {
+                params.push(format!("{}={}", key, percent_encode(value)));
+            }
+        }
+
+        let mut url = format!("solana:{}", self.recipient);
+        if !params.is_empty() {
+            url.push('?');
+            url.push_str(&params.join("&"));
+        }
+        url
+    }
+
+    /// Reads a transfer request; parameters it doesn't know are ignored, as the
+    /// specification asks.
+    pub fn parse(url: &str) -> Result<TransferRequest, String> {
+        let rest = url
+            .strip_prefix("solana:")
+            .ok_or_else(|| format!("{} isn't a solana: URL", url))?;
+        let (recipient, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let mut request = TransferRequest::new(parse_pubkey("recipient", recipient)?);
+
+        for param in query.split('&').filter(|param| !param.is_empty()) {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            let value = percent_decode(value)?;
+            match key {
+                "amount" => {
+                    once(key, &request.amount)?;
+                    if !is_amount(&value) {
+                        return Err(format!("invalid amount {}", value));
+                    }
+                    request.amount = Some(value);
+                }
+                "spl-token" => {
+                    once(key, &request.bank)?;
+                    request.bank = Some(parse_pubkey(key, &value)?);
+                }
+                "reference" => request.references.push(parse_pubkey(key, &value)?),
+                "label" => {
+                    once(key, &request.label)?;
+                    request.label = Some(value);
+                }
+                "message" => {
+                    once(key, &request.message)?;
+                    request.message = Some(value);
+                }
+                "memo" => {
+                    once(key, &request.memo)?;
+                    request.memo = Some(value);
+                }
+                _ => {}
+            }
+        }
+        Ok(request)
+    }
+}
+
+/// Appends `references` to `instruction` as read-only, non-signing accounts; the bank
+/// program reads only the accounts it expects, so they change nothing but the lookup.
+pub fn attach_references(instruction: &mut Instruction, references: &[Pubkey]) {
+    instruction.accounts.extend(
+        references
+            .iter()
+            .map(|reference| AccountMeta::new_readonly(*reference, false)),
+    );
+}
+
+fn once<T>(key: &str, field: &Option<T>) -> Result<(), String> {
+    match field {
+        Some(_) => Err(format!("{} is given more than once", key)),
+        None => Ok(()),
+    }
+}
+
+fn parse_pubkey(name: &str, value: &str) -> Result<Pubkey, String> {
+    value
+        .parse()
+        .map_err(|e| format!("invalid {} {}: {}", name, value, e))
+}
+
+/// Digits with at most one `.` between them, e.g. `1`, `0.01` or `10.50`.
+fn is_amount(value: &str) -> bool {
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    match value.split_once('.') {
+        Some((whole, fraction)) => digits(whole) && digits(fraction),
+        None => digits(value),
+    }
+}
+
+/// Escapes everything but the characters RFC 3986 leaves unreserved.
+pub fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Undoes `percent_encode`; like a browser's query parser, `+` also reads as a space.
+fn percent_decode(value: &str) -> Result<String, String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let byte = value
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| format!("invalid escape in {}", value))?;
+                decoded.push(byte);
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| format!("{} isn't UTF-8", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let request = TransferRequest {
+            recipient: Pubkey::new_unique(),
+            amount: Some("10.50".to_string()),
+            bank: Some(Pubkey::new_unique()),
+            references: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            label: Some("Coffee & Co".to_string()),
+            message: None,
+            memo: Some("order #7".to_string()),
+        };
+        let url = request.to_url();
+        assert_eq!(
+            url,
+            format!(
+                "solana:{}?amount=10.50&spl-token={}&reference={}&reference={}&label=Coffee%20%26%20Co&memo=order%20%237",
+                request.recipient,
+                request.bank.unwrap(),
+                request.references[0],
+                request.references[1]
+            )
+        );
+        assert_eq!(TransferRequest::parse(&url), Ok(request));
+
+        let recipient = Pubkey::new_unique();
+        let bare = format!("solana:{}", recipient);
+        assert_eq!(TransferRequest::new(recipient).to_url(), bare);
+        assert_eq!(
+            TransferRequest::parse(&bare),
+            Ok(TransferRequest::new(recipient))
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let recipient = Pubkey::new_unique();
+        let request = TransferRequest::parse(&format!(
+            "solana:{}?message=Thanks+for+all+the+fish&utm_source=x&amount=1",
+            recipient
+        ))
+        .unwrap();
+        assert_eq!(request.message.as_deref(), Some("Thanks for all the fish"));
+        assert_eq!(request.amount.as_deref(), Some("1"));
+
+        for query in &["amount=.5", "amount=1.", "amount=-1", "amount=1&amount=2"] {
+            let url = format!("solana:{}?{}", recipient, query);
+            assert!(TransferRequest::parse(&url).is_err(), "{}", url);
+        }
+        assert!(TransferRequest::parse(&format!("solana:{}?memo=%E2%28", recipient)).is_err());
+        assert!(TransferRequest::parse(&format!("solana:{}?memo=%4", recipient)).is_err());
+        assert!(TransferRequest::parse(&format!("bitcoin:{}", recipient)).is_err());
+        assert!(TransferRequest::parse("solana:nope").is_err());
+    }
+}
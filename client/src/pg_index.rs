@@ -0,0 +1,331 @@
+//! Postgres tables of the bank event stream for analytics dashboards: transfers, mints
+//! and burns keyed by (signature, instruction index), and account balances by slot.
+//! Every write is an upsert, so replaying a stretch of events after a restart changes
+//! nothing. Events arrive at confirmed commitment; `finalize` later drops the rows of
+//! slots that didn't make it into the finalized chain.
+
+use crate::{
+    events::{BankEvent, IndexedInstruction},
+    watch::BankUpdate,
+};
+use postgres::{Client, NoTls};
+use solana_sdk::pubkey::Pubkey;
+use tracing::error;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS transfers (
+    signature TEXT NOT NULL,
+    ix_index INTEGER NOT NULL,
+    slot BIGINT NOT NULL,
+    source TEXT,
+    destination TEXT,
+    amount BIGINT NOT NULL,
+    PRIMARY KEY (signature, ix_index)
+);
+CREATE INDEX IF NOT EXISTS transfers_slot ON transfers (slot);
+CREATE TABLE IF NOT EXISTS mints (
+    signature TEXT NOT NULL,
+    ix_index INTEGER NOT NULL,
+    slot BIGINT NOT NULL,
+    bank TEXT,
+    account TEXT,
+    amount BIGINT NOT NULL,
+    PRIMARY KEY (signature, ix_index)
+);
+CREATE INDEX IF NOT EXISTS mints_slot ON mints (slot);
+CREATE TABLE IF NOT EXISTS burns (
+    signature TEXT NOT NULL,
+    ix_index INTEGER NOT NULL,
+    slot BIGINT NOT NULL,
+    bank TEXT,
+    account TEXT,
+    amount BIGINT NOT NULL,
+    PRIMARY KEY (signature, ix_index)
+);
+CREATE INDEX IF NOT EXISTS burns_slot ON burns (slot);
+CREATE TABLE IF NOT EXISTS balances (
+    account TEXT NOT NULL,
+    slot BIGINT NOT NULL,
+    bank TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    delegated_amount BIGINT NOT NULL,
+    is_opened BOOLEAN NOT NULL,
+    PRIMARY KEY (account, slot)
+);
+CREATE INDEX IF NOT EXISTS balances_slot ON balances (slot);
+CREATE TABLE IF NOT EXISTS indexer_state (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    finalized_slot BIGINT NOT NULL
+);
+";
+
+/// Every table with a `slot` column, i.e. everything a reorg can touch.
+const SLOT_TABLES: [&str; 4] = ["transfers", "mints", "burns", "balances"];
+
+pub struct PgIndexer {
+    client: Client,
+}
+
+impl PgIndexer {
+    /// Connects to `url` (e.g. `postgres://user@localhost/bank`) and creates the tables
+    /// that don't exist yet.
+    pub fn connect(url: &str) -> Result<PgIndexer, String> {
+        match Client::connect(url, NoTls) {
+            Ok(client) => PgIndexer::new(client),
+            Err(e) => {
+                error!(error = %e, "connect to postgres failed");
+                Err("connect to postgres failed".to_string())
+            }
+        }
+    }
+
+    fn new(mut client: Client) -> Result<PgIndexer, String> {
+        if let Err(e) = client.batch_execute(SCHEMA) {
+            error!(error = %e, "create postgres schema failed");
+            return Err("create postgres schema failed".to_string());
+        }
+        Ok(PgIndexer { client })
+    }
+
+    /// Writes the rows of `event`. Bank updates and instructions other than transfers,
+    /// mints and burns have no table and are skipped.
+    pub fn record(&mut self, event: &BankEvent) -> Result<(), String> {
+        let result = match event {
+            BankEvent::Update(BankUpdate::Account { key, slot, account }) => self.client.execute(
+                "INSERT INTO balances
+                 (account, slot, bank, owner, amount, delegated_amount, is_opened)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 ON CONFLICT (account, slot) DO UPDATE SET
+                 bank = EXCLUDED.bank, owner = EXCLUDED.owner, amount = EXCLUDED.amount,
+                 delegated_amount = EXCLUDED.delegated_amount, is_opened = EXCLUDED.is_opened",
+                &[
+                    &key.to_string(),
+                    &(*slot as i64),
+                    &account.bank.to_string(),
+                    &account.owner.to_string(),
+                    &(account.amount as i64),
+                    &(account.delegated_amount as i64),
+                    &account.is_opened,
+                ],
+            ),
+            BankEvent::Update(BankUpdate::Bank { .. }) => return Ok(()),
+            BankEvent::Instruction(ix) => match (instruction_table(ix), ix.amount) {
+                (Some((table, from, to)), Some(amount)) => self.client.execute(
+                    format!(
+                        "INSERT INTO {table} (signature, ix_index, slot, {from}, {to}, amount)
+                         VALUES ($1, $2, $3, $4, $5, $6)
+                         ON CONFLICT (signature, ix_index) DO UPDATE SET
+                         slot = EXCLUDED.slot, {from} = EXCLUDED.{from},
+                         {to} = EXCLUDED.{to}, amount = EXCLUDED.amount",
+                        table = table,
+                        from = from,
+                        to = to
+                    )
+                    .as_str(),
+                    &[
+                        &ix.signature,
+                        &(ix.ix_index as i32),
+                        &(ix.slot as i64),
+                        &ix.source.as_ref().map(Pubkey::to_string),
+                        &ix.destination.as_ref().map(Pubkey::to_string),
+                        &(amount as i64),
+                    ],
+                ),
+                _ => return Ok(()),
+            },
+        };
+        result.map(|_| ()).map_err(|e| {
+            error!(error = %e, "upsert event failed");
+            "upsert event failed".to_string()
+        })
+    }
+
+    /// Highest slot whose rows are known to be final, 0 before the first `finalize`.
+    pub fn finalized_slot(&mut self) -> Result<u64, String> {
+        self.client
+            .query_opt("SELECT finalized_slot FROM indexer_state", &[])
+            .map(|row| row.map_or(0, |row| row.get::<_, i64>(0) as u64))
+            .map_err(|e| {
+                error!(error = %e, "query finalized slot failed");
+                "query finalized slot failed".to_string()
+            })
+    }
+
+    /// Lowest slot with rows above the finalized slot, i.e. where `finalize` has to
+    /// start checking; `None` when there is nothing to check.
+    pub fn first_unfinalized_slot(&mut self) -> Result<Option<u64>, String> {
+        let finalized = self.finalized_slot()? as i64;
+        let query = SLOT_TABLES
+            .iter()
+            .map(|table| format!("SELECT MIN(slot) FROM {} WHERE slot > $1", table))
+            .collect::<Vec<_>>()
+            .join(" UNION ALL ");
+        self.client
+            .query_one(
+                format!("SELECT MIN(min) FROM ({}) AS slots", query).as_str(),
+                &[&finalized],
+            )
+            .map(|row| row.get::<_, Option<i64>>(0).map(|slot| slot as u64))
+            .map_err(|e| {
+                error!(error = %e, "query unfinalized slots failed");
+                "query unfinalized slots failed".to_string()
+            })
+    }
+
+    /// Deletes the rows of slots in `start..=end` that aren't among `blocks`, the
+    /// finalized blocks of that range, and marks everything up to `end` final. Returns
+    /// the number of rows dropped.
+    pub fn finalize(&mut self, start: u64, end: u64, blocks: &[u64]) -> Result<u64, String> {
+        let blocks: Vec<i64> = blocks.iter().map(|&slot| slot as i64).collect();
+        let mut finalize = || -> Result<u64, postgres::Error> {
+            let mut transaction = self.client.transaction()?;
+            let mut dropped = 0;
+            for table in SLOT_TABLES.iter() {
+                dropped += transaction.execute(
+                    format!(
+                        "DELETE FROM {} WHERE slot BETWEEN $1 AND $2 AND NOT (slot = ANY($3))",
+                        table
+                    )
+                    .as_str(),
+                    &[&(start as i64), &(end as i64), &blocks],
+                )?;
+            }
+            transaction.execute(
+                "INSERT INTO indexer_state (finalized_slot) VALUES ($1)
+                 ON CONFLICT (id) DO UPDATE SET
+                 finalized_slot = GREATEST(indexer_state.finalized_slot, EXCLUDED.finalized_slot)",
+                &[&(end as i64)],
+            )?;
+            transaction.commit()?;
+            Ok(dropped)
+        };
+        finalize().map_err(|e| {
+            error!(error = %e, "finalize slots failed");
+            "finalize slots failed".to_string()
+        })
+    }
+}
+
+/// The table an instruction goes to, with the columns its first and second accounts
+/// fill.
+fn instruction_table(
+    ix: &IndexedInstruction,
+) -> Option<(&'static str, &'static str, &'static str)> {
+    match ix.kind.as_str() {
+        "Transfer" | "TransferWithExpiry" | "TransferChecked" => {
+            Some(("transfers", "source", "destination"))
+        }
+        "MintTo" | "MintToChecked" => Some(("mints", "bank", "account")),
+        "Burn" | "BurnChecked" => Some(("burns", "bank", "account")),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_bank::state::Account;
+    use std::env;
+
+    /// Tables in a schema of their own on the database at `INDEXER_TEST_DATABASE_URL`;
+    /// `None`, skipping the test, when it isn't set.
+    fn test_indexer() -> Option<PgIndexer> {
+        let url = match env::var("INDEXER_TEST_DATABASE_URL") {
+            Ok(url) => url,
+            Err(_) => {
+                eprintln!("INDEXER_TEST_DATABASE_URL is not set, skipping");
+                return None;
+            }
+        };
+        let mut client = Client::connect(&url, NoTls).unwrap();
+        client
+            .batch_execute(
+                "DROP SCHEMA IF EXISTS bank_indexer_test CASCADE;
+                 CREATE SCHEMA bank_indexer_test;
+                 SET search_path TO bank_indexer_test",
+            )
+            .unwrap();
+        Some(PgIndexer::new(client).unwrap())
+    }
+
+    fn transfer(slot: u64, signature: &str, amount: u64) -> BankEvent {
+        BankEvent::Instruction(IndexedInstruction {
+            slot,
+            signature: signature.to_string(),
+            ix_index: 0,
+            kind: "Transfer".to_string(),
+            amount: Some(amount),
+            source: Some(Pubkey::new_unique()),
+            destination: Some(Pubkey::new_unique()),
+        })
+    }
+
+    fn count(indexer: &mut PgIndexer, table: &str) -> i64 {
+        indexer
+            .client
+            .query_one(format!("SELECT COUNT(*) FROM {}", table).as_str(), &[])
+            .unwrap()
+            .get(0)
+    }
+
+    #[test]
+    fn test_instruction_table() {
+        let mut ix = IndexedInstruction {
+            slot: 1,
+            signature: "sig".to_string(),
+            ix_index: 0,
+            kind: "MintTo".to_string(),
+            amount: Some(1),
+            source: None,
+            destination: None,
+        };
+        assert_eq!(instruction_table(&ix), Some(("mints", "bank", "account")));
+        ix.kind = "Approve".to_string();
+        assert_eq!(instruction_table(&ix), None);
+    }
+
+    #[test]
+    fn test_upserts_and_reorg() {
+        let mut indexer = match test_indexer() {
+            Some(i) => i,
+            None => return,
+        };
+        let account = Pubkey::new_unique();
+        for (slot, amount) in [(10, 100), (11, 90), (11, 80), (12, 70)] {
+            indexer
+                .record(&BankEvent::Update(BankUpdate::Account {
+                    key: account,
+                    slot,
+                    account: Account {
+                        amount,
+                        ..Account::default()
+                    },
+                }))
+                .unwrap();
+        }
+        indexer.record(&transfer(11, "a", 10)).unwrap();
+        indexer.record(&transfer(11, "a", 10)).unwrap();
+        indexer.record(&transfer(12, "b", 10)).unwrap();
+        assert_eq!(count(&mut indexer, "balances"), 3);
+        assert_eq!(count(&mut indexer, "transfers"), 2);
+        let amount: i64 = indexer
+            .client
+            .query_one("SELECT amount FROM balances WHERE slot = 11", &[])
+            .unwrap()
+            .get(0);
+        assert_eq!(amount, 80);
+
+        // Slot 12 was skipped by the finalized chain.
+        assert_eq!(indexer.first_unfinalized_slot().unwrap(), Some(10));
+        assert_eq!(indexer.finalize(10, 12, &[10, 11]).unwrap(), 2);
+        assert_eq!(indexer.finalized_slot().unwrap(), 12);
+        assert_eq!(indexer.first_unfinalized_slot().unwrap(), None);
+        assert_eq!(count(&mut indexer, "balances"), 2);
+        assert_eq!(count(&mut indexer, "transfers"), 1);
+
+        // The reorged transaction landed again in a later slot.
+        indexer.record(&transfer(13, "b", 10)).unwrap();
+        assert_eq!(indexer.first_unfinalized_slot().unwrap(), Some(13));
+    }
+}
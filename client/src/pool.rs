@@ -0,0 +1,223 @@
+use solana_client::{rpc_client::RpcClient, rpc_config::RpcSendTransactionConfig};
+use solana_sdk::{
+    commitment_config::CommitmentConfig, hash::Hash, instruction::Instruction, message,
+    signature::Signature, signer::Signer, transaction::Transaction,
+};
+use std::{
+    thread::sleep,
+    time::{Duration, Instant},
+};
+use tracing::{debug, error, info, warn};
+
+#[cfg(feature = "metrics")]
+use crate::metrics;
+
+/// getSignatureStatuses accepts at most this many signatures per call.
+const MAX_STATUSES_PER_CALL: usize = 256;
+
+pub struct PoolConfig {
+    /// Upper bound on transactions sent per second.
+    pub max_tps: u32,
+    /// How many times an expired transaction is re-signed and sent again.
+    pub max_resubmits: u32,
+    pub poll_interval: Duration,
+    pub commitment: CommitmentConfig,
+}
+
+impl Default for PoolConfig {
+    fn default() -> PoolConfig {
+        PoolConfig {
+            max_tps: 50,
+            max_resubmits: 3,
+            poll_interval: Duration::from_millis(500),
+            commitment: CommitmentConfig::confirmed(),
+        }
+    }
+}
+
+/// One transaction to submit; the first signer pays the fee.
+pub struct TransactionRequest<'a> {
+    pub instructions: Vec<Instruction>,
+    pub signers: Vec<&'a dyn Signer>,
+}
+
+enum Status {
+    Unsent,
+    InFlight {
+        signature: Signature,
+        last_valid_slot: u64,
+    },
+    Done(Result<Signature, String>),
+}
+
+struct Entry {
+    status: Status,
+    attempts: u32,
+}
+
+/// Submits all requests without waiting on each confirmation, capped at `max_tps`,
+/// and returns one result per request in the same order.
+pub fn submit_all(
+    client: &RpcClient,
+    requests: &[TransactionRequest],
+    config: &PoolConfig,
+) -> Vec<Result<Signature, String>> {
+    let mut entries: Vec<Entry> = requests
+        .iter()
+        .map(|_| Entry {
+            status: Status::Unsent,
+            attempts: 0,
+        })
+        .collect();
+    let send_interval = Duration::from_secs(1) / config.max_tps.max(1);
+
+    loop {
+        if entries.iter().any(|e| matches!(e.status, Status::Unsent)) {
+            let (blockhash, last_valid_slot) =
+                match client.get_recent_blockhash_with_commitment(config.commitment) {
+                    Ok(r) => (r.value.0, r.value.2),
+                    Err(e) => {
+                        error!(error = %e, "get recent blockhash failed");
+                        sleep(config.poll_interval);
+                        continue;
+                    }
+                };
+            send_unsent(
+                client,
+                requests,
+                &mut entries,
+                blockhash,
+                last_valid_slot,
+                send_interval,
+            );
+        }
+
+        if entries.iter().all(|e| matches!(e.status, Status::Done(_))) {
+            break;
+        }
+        sleep(config.poll_interval);
+        poll_statuses(client, &mut entries, config);
+    }
+
+    entries
+        .into_iter()
+        .map(|e| match e.status {
+            Status::Done(result) => result,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+fn send_unsent(
+    client: &RpcClient,
+    requests: &[TransactionRequest],
+    entries: &mut [Entry],
+    blockhash: Hash,
+    last_valid_slot: u64,
+    send_interval: Duration,
+) {
+    for (request, entry) in requests.iter().zip(entries.iter_mut()) {
+        if !matches!(entry.status, Status::Unsent) {
+            continue;
+        }
+        let started = Instant::now();
+        let payer = request.signers[0].pubkey();
+        let msg = message::Message::new(&request.instructions, Some(&payer));
+        let mut t = Transaction::new_unsigned(msg);
+        if let Err(e) = t.try_sign(&request.signers, blockhash) {
+            error!(error = %e, "sign transaction failed");
+            entry.status = Status::Done(Err("sign transaction failed".to_string()));
+            continue;
+        }
+
+        entry.attempts += 1;
+        #[cfg(feature = "metrics")]
+        metrics::record_transaction_sent();
+        let config = RpcSendTransactionConfig {
+            skip_preflight: true,
+            ..RpcSendTransactionConfig::default()
+        };
+        match client.send_transaction_with_config(&t, config) {
+            Ok(signature) => {
+                debug!(%signature, attempt = entry.attempts, "transaction sent");
+                entry.status = Status::InFlight {
+                    signature,
+                    last_valid_slot,
+                };
+            }
+            Err(e) => {
+                #[cfg(feature = "metrics")]
+                metrics::record_rpc_error(&e);
+                error!(error = %e, "send transaction failed");
+                entry.status = Status::Done(Err("send transaction failed".to_string()));
+            }
+        }
+
+        let elapsed = started.elapsed();
+        if elapsed < send_interval {
+            sleep(send_interval - elapsed);
+        }
+    }
+}
+
+fn poll_statuses(client: &RpcClient, entries: &mut [Entry], config: &PoolConfig) {
+    let slot = match client.get_slot_with_commitment(config.commitment) {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "get slot failed");
+            return;
+        }
+    };
+
+    let mut in_flight: Vec<&mut Entry> = entries
+        .iter_mut()
+        .filter(|e| matches!(e.status, Status::InFlight { .. }))
+        .collect();
+    for chunk in in_flight.chunks_mut(MAX_STATUSES_PER_CALL) {
+        let signatures: Vec<Signature> = chunk
+            .iter()
+            .map(|e| match e.status {
+                Status::InFlight { signature, .. } => signature,
+                _ => unreachable!(),
+            })
+            .collect();
+        let statuses = match client.get_signature_statuses(&signatures) {
+            Ok(r) => r.value,
+            Err(e) => {
+                error!(error = %e, "get signature statuses failed");
+                return;
+            }
+        };
+
+        for (entry, status) in chunk.iter_mut().zip(statuses) {
+            let (signature, last_valid_slot) = match entry.status {
+                Status::InFlight {
+                    signature,
+                    last_valid_slot,
+                } => (signature, last_valid_slot),
+                _ => unreachable!(),
+            };
+            match status {
+                Some(s) if s.err.is_some() => {
+                    let err = s.err.unwrap();
+                    warn!(%signature, error = %err, "transaction failed");
+                    entry.status = Status::Done(Err(format!("transaction failed: {}", err)));
+                }
+                Some(s) if s.satisfies_commitment(config.commitment) => {
+                    info!(%signature, slot = s.slot, "transaction confirmed");
+                    entry.status = Status::Done(Ok(signature));
+                }
+                Some(_) => {}
+                None if slot > last_valid_slot => {
+                    if entry.attempts > config.max_resubmits {
+                        entry.status = Status::Done(Err("blockhash expired".to_string()));
+                    } else {
+                        warn!(%signature, "blockhash expired, resubmitting");
+                        entry.status = Status::Unsent;
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{
+    rpc::RpcApi,
+    util::{self, Confirmation},
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    message::Message,
+    native_token::{lamports_to_sol, LAMPORTS_PER_SOL},
+    pubkey::Pubkey,
+    system_instruction::SystemInstruction,
+    system_program,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{error, info};
+
+static AUTO_AIRDROP: AtomicBool = AtomicBool::new(false);
+
+/// Lets `check_fee_payer_balance` airdrop the shortfall on clusters that support it
+/// instead of failing.
+pub fn set_auto_airdrop(enabled: bool) {
+    AUTO_AIRDROP.store(enabled, Ordering::SeqCst);
+}
+
+/// Devnet, testnet and local validators hand out airdrops; mainnet does not.
+pub fn airdrop_available(rpc_url: &str) -> bool {
+    ["devnet", "testnet", "localhost", "127.0.0.1"]
+        .iter()
+        .any(|host| rpc_url.contains(host))
+}
+
+/// Lamports the fee payer sends out through system program instructions in `msg`,
+/// such as the rent of accounts it creates.
+pub fn payer_outflow(msg: &Message) -> u64 {
+    let payer = match msg.account_keys.first() {
+        Some(p) => p,
+        None => return 0,
+    };
+    msg.instructions
+        .iter()
+        .filter(|ix| msg.account_keys[ix.program_id_index as usize] == system_program::id())
+        .filter(|ix| {
+            matches!(ix.accounts.first(), Some(&index) if msg.account_keys[index as usize] == *payer)
+        })
+        .filter_map(
+            |ix| match bincode::deserialize::<SystemInstruction>(&ix.data) {
+                Ok(SystemInstruction::CreateAccount { lamports, .. })
+                | Ok(SystemInstruction::CreateAccountWithSeed { lamports, .. })
+                | Ok(SystemInstruction::Transfer { lamports }) => Some(lamports),
+                _ => None,
+            },
+        )
+        .sum()
+}
+
+/// The fee `msg` pays at the rate of its recent blockhash, which must not have expired.
+pub fn fee_for_message(client: &dyn RpcApi, msg: &Message) -> Result<u64, String> {
+    match client.get_fee_calculator_for_blockhash(&msg.recent_blockhash) {
+        Ok(Some(fee_calculator)) => Ok(fee_calculator.calculate_fee(msg)),
+        Ok(None) => {
+            error!(blockhash = %msg.recent_blockhash, "blockhash expired");
+            Err("blockhash expired".to_string())
+        }
+        Err(e) => {
+            error!(error = %e, "get fee failed");
+            Err("get fee failed".to_string())
+        }
+    }
+}
+
+/// Fails fast when the fee payer can't cover the fee plus the lamports `msg` moves
+/// out of it; `msg` must carry a recent blockhash for the fee lookup.
+pub fn check_fee_payer_balance(client: &dyn RpcApi, msg: &Message) -> Result<(), String> {
+    let payer = msg.account_keys[0];
+    let fee = fee_for_message(client, msg)?;
+    let required = fee.saturating_add(payer_outflow(msg));
+    let balance = match client.get_balance(&payer) {
+        Ok(b) => b,
+        Err(e) => {
+            error!(error = %e, %payer, "get balance failed");
+            return Err("get balance failed".to_string());
+        }
+    };
+    if balance >= required {
+        return Ok(());
+    }
+
+    let shortfall = required - balance;
+    if AUTO_AIRDROP.load(Ordering::SeqCst) && airdrop_available(&client.url()) {
+        return airdrop(client, &payer, shortfall.max(LAMPORTS_PER_SOL));
+    }
+    Err(format!(
+        "fund this address: {} holds {} SOL but needs {} SOL (fee and rent)",
+        payer,
+        lamports_to_sol(balance),
+        lamports_to_sol(required)
+    ))
+}
+
+fn airdrop(client: &dyn RpcApi, to: &Pubkey, lamports: u64) -> Result<(), String> {
+    info!(%to, sol = lamports_to_sol(lamports), "requesting airdrop");
+    let signature = match client.request_airdrop(to, lamports) {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "request airdrop failed");
+            return Err(format!(
+                "airdrop failed, fund {} with {} SOL manually",
+                to,
+                lamports_to_sol(lamports)
+            ));
+        }
+    };
+    match util::wait_for_confirmation(
+        client,
+        &signature,
+        CommitmentConfig::confirmed(),
+        util::DEFAULT_CONFIRM_TIMEOUT,
+        None,
+    )? {
+        Confirmation::Confirmed { .. } => Ok(()),
+        Confirmation::Expired => Err("airdrop expired".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::system_instruction;
+
+    #[test]
+    fn test_payer_outflow() {
+        let payer = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let msg = Message::new(
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &Pubkey::new_unique(),
+                    1_000,
+                    118,
+                    &program_id,
+                ),
+                system_instruction::transfer(&payer, &other, 500),
+                system_instruction::transfer(&other, &payer, 7_000),
+            ],
+            Some(&payer),
+        );
+        assert_eq!(payer_outflow(&msg), 1_500);
+    }
+}
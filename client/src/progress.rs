@@ -0,0 +1,69 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::time::Duration;
+
+/// What a step produced, so its line can show the transaction signature.
+pub trait StepOutcome {
+    fn signature(&self) -> Option<&Signature> {
+        None
+    }
+}
+
+impl StepOutcome for Option<Signature> {
+    fn signature(&self) -> Option<&Signature> {
+        self.as_ref()
+    }
+}
+
+impl StepOutcome for Signature {
+    fn signature(&self) -> Option<&Signature> {
+        Some(self)
+    }
+}
+
+impl StepOutcome for Pubkey {}
+
+impl StepOutcome for () {}
+
+/// Reports a fixed sequence of transactions, one line per step with its purpose,
+/// signature and confirmation status.
+pub struct Steps {
+    total: usize,
+    current: usize,
+}
+
+impl Steps {
+    pub fn new(total: usize) -> Steps {
+        Steps { total, current: 0 }
+    }
+
+    /// Runs `f` behind a spinner labelled with `purpose` and leaves the outcome on screen.
+    pub fn run<T, F>(&mut self, purpose: &str, f: F) -> Result<T, String>
+    where
+        T: StepOutcome,
+        F: FnOnce() -> Result<T, String>,
+    {
+        self.current += 1;
+        let bar = ProgressBar::new_spinner();
+        bar.set_style(
+            ProgressStyle::with_template("{spinner} {prefix} {wide_msg}")
+                .unwrap()
+                .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏✓"),
+        );
+        bar.set_prefix(format!("[{}/{}]", self.current, self.total));
+        bar.set_message(format!("{}: sending", purpose));
+        bar.enable_steady_tick(Duration::from_millis(100));
+
+        let result = f();
+        match &result {
+            Ok(outcome) => match outcome.signature() {
+                Some(signature) => {
+                    bar.finish_with_message(format!("{}: confirmed {}", purpose, signature))
+                }
+                None => bar.finish_with_message(format!("{}: done", purpose)),
+            },
+            Err(e) => bar.abandon_with_message(format!("{}: failed: {}", purpose, e)),
+        }
+        result
+    }
+}
@@ -0,0 +1,154 @@
+//! Proposal files: a multisig proposal's instruction written out as JSON, so that owners
+//! who sign offline can check what they approve against what the proposer shared, and so
+//! that an instruction drafted elsewhere can be proposed.
+
+use crate::bank::decode_instruction;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use std::{fs, path::Path, str::FromStr};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProposalFile {
+    pub multisig: String,
+    /// The proposal account, once the instruction has been proposed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proposal: Option<String>,
+    pub program_id: String,
+    pub accounts: Vec<ProposalAccount>,
+    /// Instruction data in base64.
+    pub data: String,
+    /// The instruction as `describe` renders it, for reviewers; never read back.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProposalAccount {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl ProposalFile {
+    pub fn new(multisig: &Pubkey, proposal: Option<&Pubkey>, instruction: &Instruction) -> Self {
+        ProposalFile {
+            multisig: multisig.to_string(),
+            proposal: proposal.map(|proposal| proposal.to_string()),
+            program_id: instruction.program_id.to_string(),
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|meta| ProposalAccount {
+                    pubkey: meta.pubkey.to_string(),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: base64::encode(&instruction.data),
+            description: None,
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| format!("read {} failed: {}", path.display(), e))?;
+        serde_json::from_str(&json).map_err(|e| format!("parse {} failed: {}", path.display(), e))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, json + "\n").map_err(|e| format!("write {} failed: {}", path.display(), e))
+    }
+
+    pub fn multisig(&self) -> Result<Pubkey, String> {
+        parse_pubkey("multisig", &self.multisig)
+    }
+
+    pub fn instruction(&self) -> Result<Instruction, String> {
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|account| {
+                Ok(AccountMeta {
+                    pubkey: parse_pubkey("account", &account.pubkey)?,
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Instruction {
+            program_id: parse_pubkey("program_id", &self.program_id)?,
+            accounts,
+            data: base64::decode(&self.data).map_err(|e| format!("invalid data: {}", e))?,
+        })
+    }
+}
+
+fn parse_pubkey(field: &str, value: &str) -> Result<Pubkey, String> {
+    Pubkey::from_str(value).map_err(|e| format!("invalid {} {}: {}", field, value, e))
+}
+
+/// `instruction` in a line: decoded if it targets the bank program, otherwise its
+/// program and data as they are.
+pub fn describe(instruction: &Instruction, bank_program_id: &Pubkey) -> String {
+    let decoded = if instruction.program_id == *bank_program_id {
+        decode_instruction(&instruction.data)
+    } else {
+        None
+    };
+    match decoded {
+        Some(decoded) => format!("bank {}", decoded),
+        None => format!(
+            "program {} data {}",
+            instruction.program_id,
+            base64::encode(&instruction.data)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_bank::instruction::mint_to;
+
+    #[test]
+    fn test_proposal_file() {
+        let bank_program_id = Pubkey::new_unique();
+        let (multisig, signer) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let instruction = mint_to(
+            &bank_program_id,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &signer,
+            42,
+        )
+        .unwrap();
+        let file = ProposalFile {
+            description: Some(describe(&instruction, &bank_program_id)),
+            ..ProposalFile::new(&multisig, None, &instruction)
+        };
+        let json = serde_json::to_string(&file).unwrap();
+        assert!(!json.contains("\"proposal\""));
+        let read: ProposalFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.instruction().unwrap(), instruction);
+        assert_eq!(read.multisig().unwrap(), multisig);
+        assert_eq!(
+            file.description.unwrap(),
+            "bank MintTo { amount: 42 }".to_string()
+        );
+
+        let other_program = Instruction {
+            program_id: Pubkey::new_unique(),
+            ..instruction
+        };
+        assert!(describe(&other_program, &bank_program_id).starts_with("program "));
+        let bad = ProposalFile {
+            data: "not base64!".to_string(),
+            ..read
+        };
+        assert!(bad.instruction().is_err());
+    }
+}
@@ -0,0 +1,300 @@
+//! Signing through a remote service, for bots and CI that hold no key material. The
+//! signer is described by a JSON config file, e.g.
+//!
+//! ```json
+//! { "type": "http", "url": "https://signer.internal/sign", "pubkey": "<base58>",
+//!   "token_env": "SIGNER_TOKEN" }
+//! { "type": "gcp_kms", "pubkey": "<base58>", "token_env": "GCP_ACCESS_TOKEN",
+//!   "key": "projects/p/locations/global/keyRings/r/cryptoKeys/k/cryptoKeyVersions/1" }
+//! ```
+//!
+//! An HTTP signer is POSTed `{"pubkey", "message"}` with the message in base64 and
+//! answers `{"signature"}` in base58. A Cloud KMS key has to be an `EC_SIGN_ED25519`
+//! key; it signs the message itself through `asymmetricSign`. Either way the token in
+//! `token_env` is sent as a bearer token, and every signature is verified against
+//! `pubkey` before it is used.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::{Signer, SignerError},
+};
+use std::{env, fs, path::Path, str::FromStr};
+use tracing::error;
+
+const GCP_KMS_ENDPOINT: &str = "https://cloudkms.googleapis.com/v1";
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SignerConfig {
+    Http {
+        url: String,
+        pubkey: String,
+        token_env: Option<String>,
+    },
+    GcpKms {
+        /// Resource name of the key version.
+        key: String,
+        pubkey: String,
+        token_env: Option<String>,
+        /// Overrides the Cloud KMS API, e.g. for an emulator.
+        endpoint: Option<String>,
+    },
+}
+
+#[derive(Serialize)]
+struct HttpSignRequest {
+    pubkey: String,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct HttpSignResponse {
+    signature: String,
+}
+
+#[derive(Serialize)]
+struct KmsSignRequest {
+    data: String,
+}
+
+#[derive(Deserialize)]
+struct KmsSignResponse {
+    signature: String,
+}
+
+/// A `Signer` whose key lives behind an HTTP signer or in Cloud KMS.
+#[derive(Debug)]
+pub struct RemoteSigner {
+    pubkey: Pubkey,
+    /// URL the message is POSTed to.
+    url: String,
+    token_env: Option<String>,
+    kms: bool,
+}
+
+impl RemoteSigner {
+    pub fn new(config: SignerConfig) -> Result<RemoteSigner, String> {
+        let (pubkey, url, token_env, kms) = match config {
+            SignerConfig::Http {
+                url,
+                pubkey,
+                token_env,
+            } => (pubkey, url, token_env, false),
+            SignerConfig::GcpKms {
+                key,
+                pubkey,
+                token_env,
+                endpoint,
+            } => {
+                let endpoint = endpoint.unwrap_or_else(|| GCP_KMS_ENDPOINT.to_string());
+                let url = format!("{}/{}:asymmetricSign", endpoint, key);
+                (pubkey, url, token_env, true)
+            }
+        };
+        let pubkey =
+            Pubkey::from_str(&pubkey).map_err(|e| format!("invalid pubkey {}: {}", pubkey, e))?;
+        Ok(RemoteSigner {
+            pubkey,
+            url,
+            token_env,
+            kms,
+        })
+    }
+
+    /// Reads a `SignerConfig` from a JSON file.
+    pub fn from_config_file(path: &Path) -> Result<RemoteSigner, String> {
+        let contents = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => {
+                error!(error = %e, path = %path.display(), "read signer config failed");
+                return Err(format!("read signer config {} failed", path.display()));
+            }
+        };
+        let config = serde_json::from_str(&contents)
+            .map_err(|e| format!("invalid signer config {}: {}", path.display(), e))?;
+        RemoteSigner::new(config)
+    }
+
+    fn request(&self) -> Result<ureq::Request, String> {
+        let request = ureq::post(&self.url);
+        match &self.token_env {
+            Some(name) => {
+                let token = env::var(name).map_err(|_| format!("{} is not set", name))?;
+                Ok(request.set("Authorization", &format!("Bearer {}", token)))
+            }
+            None => Ok(request),
+        }
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Signature, String> {
+        let encoded = base64::encode(message);
+        let bytes = if self.kms {
+            let response: KmsSignResponse = self
+                .request()?
+                .send_json(KmsSignRequest { data: encoded })
+                .map_err(|e| format!("KMS sign failed: {}", e))?
+                .into_json()
+                .map_err(|e| format!("invalid KMS response: {}", e))?;
+            base64::decode(response.signature)
+                .map_err(|e| format!("invalid KMS signature: {}", e))?
+        } else {
+            let response: HttpSignResponse = self
+                .request()?
+                .send_json(HttpSignRequest {
+                    pubkey: self.pubkey.to_string(),
+                    message: encoded,
+                })
+                .map_err(|e| format!("remote sign failed: {}", e))?
+                .into_json()
+                .map_err(|e| format!("invalid signer response: {}", e))?;
+            bs58::decode(response.signature)
+                .into_vec()
+                .map_err(|e| format!("invalid signer signature: {}", e))?
+        };
+        if bytes.len() != 64 {
+            return Err(format!("got a {} byte signature", bytes.len()));
+        }
+        // `Signature::from` is newer than the solana-sdk this builds against.
+        #[allow(deprecated)]
+        let signature = Signature::new(&bytes);
+        if !signature.verify(self.pubkey.as_ref(), message) {
+            return Err(format!(
+                "the signature doesn't verify against {}",
+                self.pubkey
+            ));
+        }
+        Ok(signature)
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.pubkey)
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        self.sign(message).map_err(|e| {
+            error!(error = %e, pubkey = %self.pubkey, "remote sign failed");
+            SignerError::Custom(e)
+        })
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::Keypair;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    /// Answers one POST with `answer(body)` and returns the request's head.
+    fn serve_once(
+        answer: impl FnOnce(&str) -> String + Send + 'static,
+    ) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut head = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+                head.push_str(&line);
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let response = answer(&String::from_utf8(body).unwrap());
+            write!(
+                reader.into_inner(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .unwrap();
+            head
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_config() {
+        let config: SignerConfig = serde_json::from_str(
+            r#"{"type": "gcp_kms", "key": "projects/p/cryptoKeyVersions/1", "pubkey": "11111111111111111111111111111111"}"#,
+        )
+        .unwrap();
+        let signer = RemoteSigner::new(config).unwrap();
+        assert_eq!(
+            signer.url,
+            "https://cloudkms.googleapis.com/v1/projects/p/cryptoKeyVersions/1:asymmetricSign"
+        );
+        assert_eq!(signer.pubkey(), Pubkey::default());
+        assert!(serde_json::from_str::<SignerConfig>(r#"{"type": "ledger"}"#).is_err());
+    }
+
+    #[test]
+    fn test_http_signer() {
+        let keypair = Keypair::new();
+        let signer_key = Keypair::from_bytes(&keypair.to_bytes()).unwrap();
+        let (url, handle) = serve_once(move |body| {
+            let request: serde_json::Value = serde_json::from_str(body).unwrap();
+            let message = base64::decode(request["message"].as_str().unwrap()).unwrap();
+            let signature = signer_key.sign_message(&message);
+            format!(r#"{{"signature": "{}"}}"#, signature)
+        });
+        env::set_var("REMOTE_SIGNER_TEST_TOKEN", "secret");
+        let signer = RemoteSigner::new(SignerConfig::Http {
+            url,
+            pubkey: keypair.pubkey().to_string(),
+            token_env: Some("REMOTE_SIGNER_TEST_TOKEN".to_string()),
+        })
+        .unwrap();
+        assert_eq!(
+            signer.try_sign_message(b"hello"),
+            Ok(keypair.sign_message(b"hello"))
+        );
+        assert!(handle
+            .join()
+            .unwrap()
+            .contains("Authorization: Bearer secret"));
+    }
+
+    #[test]
+    fn test_kms_signature_is_verified() {
+        let other = Keypair::new();
+        let (endpoint, handle) = serve_once(move |body| {
+            let request: serde_json::Value = serde_json::from_str(body).unwrap();
+            let message = base64::decode(request["data"].as_str().unwrap()).unwrap();
+            let signature = other.sign_message(&message);
+            format!(r#"{{"signature": "{}"}}"#, base64::encode(signature))
+        });
+        let signer = RemoteSigner::new(SignerConfig::GcpKms {
+            key: "key".to_string(),
+            pubkey: Pubkey::new_unique().to_string(),
+            token_env: None,
+            endpoint: Some(endpoint),
+        })
+        .unwrap();
+        assert!(signer.try_sign_message(b"hello").is_err());
+        assert!(handle
+            .join()
+            .unwrap()
+            .starts_with("POST /key:asymmetricSign"));
+    }
+}
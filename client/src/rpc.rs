@@ -0,0 +1,108 @@
+//! The JSON RPC calls the client helpers make, behind a trait so they can run
+//! against canned responses in tests instead of a live cluster.
+
+use solana_client::{
+    client_error::Result as ClientResult,
+    rpc_client::RpcClient,
+    rpc_response::{RpcResult, RpcSimulateTransactionResult},
+};
+use solana_sdk::{
+    account::Account, clock::Slot, commitment_config::CommitmentConfig,
+    fee_calculator::FeeCalculator, hash::Hash, pubkey::Pubkey, signature::Signature,
+    transaction::Transaction,
+};
+use solana_transaction_status::TransactionStatus;
+
+#[cfg(test)]
+pub mod mock;
+
+/// Mirrors the `RpcClient` methods of the same names, errors included.
+#[allow(clippy::result_large_err)]
+pub trait RpcApi {
+    fn url(&self) -> String;
+    fn commitment(&self) -> CommitmentConfig;
+    fn get_account(&self, pubkey: &Pubkey) -> ClientResult<Account>;
+    fn get_balance(&self, pubkey: &Pubkey) -> ClientResult<u64>;
+    fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> ClientResult<u64>;
+    fn get_recent_blockhash_with_commitment(
+        &self,
+        commitment: CommitmentConfig,
+    ) -> RpcResult<(Hash, FeeCalculator, Slot)>;
+    fn get_slot_with_commitment(&self, commitment: CommitmentConfig) -> ClientResult<Slot>;
+    fn get_fee_calculator_for_blockhash(
+        &self,
+        blockhash: &Hash,
+    ) -> ClientResult<Option<FeeCalculator>>;
+    fn get_signature_statuses(
+        &self,
+        signatures: &[Signature],
+    ) -> RpcResult<Vec<Option<TransactionStatus>>>;
+    fn send_transaction(&self, transaction: &Transaction) -> ClientResult<Signature>;
+    fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> RpcResult<RpcSimulateTransactionResult>;
+    fn request_airdrop(&self, pubkey: &Pubkey, lamports: u64) -> ClientResult<Signature>;
+}
+
+impl RpcApi for RpcClient {
+    fn url(&self) -> String {
+        RpcClient::url(self)
+    }
+
+    fn commitment(&self) -> CommitmentConfig {
+        RpcClient::commitment(self)
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> ClientResult<Account> {
+        RpcClient::get_account(self, pubkey)
+    }
+
+    fn get_balance(&self, pubkey: &Pubkey) -> ClientResult<u64> {
+        RpcClient::get_balance(self, pubkey)
+    }
+
+    fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> ClientResult<u64> {
+        RpcClient::get_minimum_balance_for_rent_exemption(self, data_len)
+    }
+
+    fn get_recent_blockhash_with_commitment(
+        &self,
+        commitment: CommitmentConfig,
+    ) -> RpcResult<(Hash, FeeCalculator, Slot)> {
+        RpcClient::get_recent_blockhash_with_commitment(self, commitment)
+    }
+
+    fn get_slot_with_commitment(&self, commitment: CommitmentConfig) -> ClientResult<Slot> {
+        RpcClient::get_slot_with_commitment(self, commitment)
+    }
+
+    fn get_fee_calculator_for_blockhash(
+        &self,
+        blockhash: &Hash,
+    ) -> ClientResult<Option<FeeCalculator>> {
+        RpcClient::get_fee_calculator_for_blockhash(self, blockhash)
+    }
+
+    fn get_signature_statuses(
+        &self,
+        signatures: &[Signature],
+    ) -> RpcResult<Vec<Option<TransactionStatus>>> {
+        RpcClient::get_signature_statuses(self, signatures)
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> ClientResult<Signature> {
+        RpcClient::send_transaction(self, transaction)
+    }
+
+    fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> RpcResult<RpcSimulateTransactionResult> {
+        RpcClient::simulate_transaction(self, transaction)
+    }
+
+    fn request_airdrop(&self, pubkey: &Pubkey, lamports: u64) -> ClientResult<Signature> {
+        RpcClient::request_airdrop(self, pubkey, lamports)
+    }
+}
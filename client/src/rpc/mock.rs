@@ -0,0 +1,206 @@
+use super::RpcApi;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    rpc_response::{Response, RpcResponseContext, RpcResult, RpcSimulateTransactionResult},
+};
+use solana_sdk::{
+    account::Account,
+    clock::Slot,
+    commitment_config::CommitmentConfig,
+    fee_calculator::FeeCalculator,
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{Transaction, TransactionError},
+};
+use solana_transaction_status::{TransactionConfirmationStatus, TransactionStatus};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Mutex,
+};
+
+const SLOT: u64 = 1_000;
+
+/// Canned cluster state. Every fetched blockhash is valid through `last_valid_slot`,
+/// so a transaction that doesn't land expires as soon as `slot` passes it.
+pub struct MockRpc {
+    pub accounts: HashMap<Pubkey, Account>,
+    pub balance: u64,
+    pub rent: u64,
+    pub lamports_per_signature: u64,
+    pub slot: Slot,
+    pub last_valid_slot: Slot,
+    /// Error recorded for every transaction that lands.
+    pub transaction_error: Option<TransactionError>,
+    /// Methods answering with an RPC error, by name.
+    pub failing: HashSet<&'static str>,
+    /// Whether each sent transaction lands, in send order; once it runs out they all do.
+    landing: Mutex<VecDeque<bool>>,
+    landed: Mutex<HashSet<Signature>>,
+    sent: Mutex<Vec<Transaction>>,
+}
+
+impl Default for MockRpc {
+    fn default() -> MockRpc {
+        MockRpc {
+            accounts: HashMap::new(),
+            balance: 1_000_000_000,
+            rent: 1_000_000,
+            lamports_per_signature: 5_000,
+            slot: 100,
+            last_valid_slot: 250,
+            transaction_error: None,
+            failing: HashSet::new(),
+            landing: Mutex::new(VecDeque::new()),
+            landed: Mutex::new(HashSet::new()),
+            sent: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl MockRpc {
+    pub fn fail(mut self, method: &'static str) -> MockRpc {
+        self.failing.insert(method);
+        self
+    }
+
+    /// Drops the next `n` sent transactions; with `slot` past `last_valid_slot` they
+    /// expire right away.
+    pub fn drop_transactions(self, n: usize) -> MockRpc {
+        self.landing.lock().unwrap().extend(vec![false; n]);
+        self
+    }
+
+    pub fn sent(&self) -> Vec<Transaction> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn check(&self, method: &str) -> ClientResult<()> {
+        if self.failing.contains(method) {
+            return Err(ClientError::from(ClientErrorKind::Custom(format!(
+                "{} unavailable",
+                method
+            ))));
+        }
+        Ok(())
+    }
+
+    fn response<T>(&self, value: T) -> Response<T> {
+        Response {
+            context: RpcResponseContext::new(SLOT),
+            value,
+        }
+    }
+}
+
+impl RpcApi for MockRpc {
+    fn url(&self) -> String {
+        "http://localhost:8899".to_string()
+    }
+
+    fn commitment(&self) -> CommitmentConfig {
+        CommitmentConfig::confirmed()
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> ClientResult<Account> {
+        self.check("get_account")?;
+        self.accounts.get(pubkey).cloned().ok_or_else(|| {
+            ClientError::from(ClientErrorKind::Custom(format!(
+                "AccountNotFound: pubkey={}",
+                pubkey
+            )))
+        })
+    }
+
+    fn get_balance(&self, _pubkey: &Pubkey) -> ClientResult<u64> {
+        self.check("get_balance")?;
+        Ok(self.balance)
+    }
+
+    fn get_minimum_balance_for_rent_exemption(&self, _data_len: usize) -> ClientResult<u64> {
+        self.check("get_minimum_balance_for_rent_exemption")?;
+        Ok(self.rent)
+    }
+
+    fn get_recent_blockhash_with_commitment(
+        &self,
+        _commitment: CommitmentConfig,
+    ) -> RpcResult<(Hash, FeeCalculator, Slot)> {
+        self.check("get_recent_blockhash_with_commitment")?;
+        Ok(self.response((
+            Hash::new_unique(),
+            FeeCalculator::new(self.lamports_per_signature),
+            self.last_valid_slot,
+        )))
+    }
+
+    fn get_slot_with_commitment(&self, _commitment: CommitmentConfig) -> ClientResult<Slot> {
+        self.check("get_slot_with_commitment")?;
+        Ok(self.slot)
+    }
+
+    fn get_fee_calculator_for_blockhash(
+        &self,
+        _blockhash: &Hash,
+    ) -> ClientResult<Option<FeeCalculator>> {
+        self.check("get_fee_calculator_for_blockhash")?;
+        Ok(Some(FeeCalculator::new(self.lamports_per_signature)))
+    }
+
+    fn get_signature_statuses(
+        &self,
+        signatures: &[Signature],
+    ) -> RpcResult<Vec<Option<TransactionStatus>>> {
+        self.check("get_signature_statuses")?;
+        let landed = self.landed.lock().unwrap();
+        let statuses = signatures
+            .iter()
+            .map(|signature| {
+                if !landed.contains(signature) {
+                    return None;
+                }
+                Some(TransactionStatus {
+                    slot: SLOT,
+                    confirmations: None,
+                    status: self.transaction_error.clone().map_or(Ok(()), Err),
+                    err: self.transaction_error.clone(),
+                    confirmation_status: Some(TransactionConfirmationStatus::Finalized),
+                })
+            })
+            .collect();
+        Ok(self.response(statuses))
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> ClientResult<Signature> {
+        self.check("send_transaction")?;
+        let signature = transaction.signatures[0];
+        self.sent.lock().unwrap().push(transaction.clone());
+        if self.landing.lock().unwrap().pop_front().unwrap_or(true) {
+            self.landed.lock().unwrap().insert(signature);
+        }
+        Ok(signature)
+    }
+
+    fn simulate_transaction(
+        &self,
+        _transaction: &Transaction,
+    ) -> RpcResult<RpcSimulateTransactionResult> {
+        self.check("simulate_transaction")?;
+        Ok(self.response(RpcSimulateTransactionResult {
+            err: self.transaction_error.clone(),
+            logs: Some(Vec::new()),
+            accounts: None,
+            units_consumed: None,
+            return_data: None,
+            inner_instructions: None,
+        }))
+    }
+
+    fn request_airdrop(&self, _pubkey: &Pubkey, _lamports: u64) -> ClientResult<Signature> {
+        self.check("request_airdrop")?;
+        let signature = Signature::new_unique();
+        self.landed.lock().unwrap().insert(signature);
+        Ok(signature)
+    }
+}
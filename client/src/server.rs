@@ -0,0 +1,288 @@
+use crate::{bank::BankClient, util::SendMode};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use solana_bank::{
+    instruction,
+    state::{Account, Bank},
+};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use std::{net::SocketAddr, str::FromStr, sync::Arc};
+use tracing::{error, info};
+
+struct ServerState {
+    bank_client: BankClient,
+    /// Authority of the transfers posted to `/transfer`; also pays their fees.
+    signer: Keypair,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct BankView {
+    pub address: String,
+    pub decimals: u8,
+    pub bank_owner: String,
+    pub is_opened: bool,
+    pub total_supply: u64,
+}
+
+impl BankView {
+    pub fn new(address: &Pubkey, bank: &Bank) -> BankView {
+        BankView {
+            address: address.to_string(),
+            decimals: bank.decimals,
+            bank_owner: bank.bank_owner.to_string(),
+            is_opened: bank.is_opened,
+            total_supply: bank.total_supply,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct AccountView {
+    pub address: String,
+    pub bank: String,
+    pub owner: String,
+    pub amount: u64,
+    pub delegate: Option<String>,
+    pub delegated_amount: u64,
+    pub is_opened: bool,
+}
+
+impl AccountView {
+    pub fn new(address: &Pubkey, account: &Account) -> AccountView {
+        AccountView {
+            address: address.to_string(),
+            bank: account.bank.to_string(),
+            owner: account.owner.to_string(),
+            amount: account.amount,
+            delegate: Option::<Pubkey>::from(account.delegate).map(|d| d.to_string()),
+            delegated_amount: account.delegated_amount,
+            is_opened: account.is_opened,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct OwnerQuery {
+    pub owner: String,
+}
+
+#[derive(Deserialize)]
+pub struct TransferRequest {
+    pub from: String,
+    pub to: String,
+    pub amount: u64,
+    pub memo: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct TransferResponse {
+    pub signature: String,
+}
+
+/// Error returned to HTTP callers as `{"error": "..."}`.
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> ApiError {
+        ApiError {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = Json(serde_json::json!({ "error": self.message }));
+        (self.status, body).into_response()
+    }
+}
+
+fn parse_pubkey(name: &str, value: &str) -> Result<Pubkey, ApiError> {
+    Pubkey::from_str(value)
+        .map_err(|_| ApiError::new(StatusCode::BAD_REQUEST, format!("invalid {}", name)))
+}
+
+/// Runs a blocking RPC call off the async workers; its error is reported with `status`.
+async fn blocking<T, F>(state: Arc<ServerState>, status: StatusCode, f: F) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    F: FnOnce(&ServerState) -> Result<T, String> + Send + 'static,
+{
+    match tokio::task::spawn_blocking(move || f(&state)).await {
+        Ok(result) => result.map_err(|e| ApiError::new(status, e)),
+        Err(e) => {
+            error!(error = %e, "request handler panicked");
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal error",
+            ))
+        }
+    }
+}
+
+async fn get_bank(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+) -> Result<Json<BankView>, ApiError> {
+    let key = parse_pubkey("bank", &id)?;
+    let bank = blocking(state, StatusCode::NOT_FOUND, move |s| {
+        s.bank_client.get_bank(&key)
+    })
+    .await?;
+    Ok(Json(BankView::new(&key, &bank)))
+}
+
+async fn get_account(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+) -> Result<Json<AccountView>, ApiError> {
+    let key = parse_pubkey("account", &id)?;
+    let account = blocking(state, StatusCode::NOT_FOUND, move |s| {
+        s.bank_client.get_account(&key)
+    })
+    .await?;
+    Ok(Json(AccountView::new(&key, &account)))
+}
+
+async fn list_accounts(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<OwnerQuery>,
+) -> Result<Json<Vec<AccountView>>, ApiError> {
+    let owner = parse_pubkey("owner", &query.owner)?;
+    let accounts = blocking(state, StatusCode::BAD_GATEWAY, move |s| {
+        s.bank_client.get_owner_accounts(&owner)
+    })
+    .await?;
+    Ok(Json(
+        accounts
+            .iter()
+            .map(|(key, account)| AccountView::new(key, account))
+            .collect(),
+    ))
+}
+
+async fn transfer(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<TransferRequest>,
+) -> Result<Json<TransferResponse>, ApiError> {
+    let from = parse_pubkey("from", &request.from)?;
+    let to = parse_pubkey("to", &request.to)?;
+    let amount = request.amount;
+    let signature = blocking(state, StatusCode::BAD_GATEWAY, move |s| {
+        let authority = s.signer.pubkey();
+        let bank = s.bank_client.get_account(&from)?.bank;
+        let transfer = instruction::transfer(
+            &s.bank_client.program_id,
+            &bank,
+            &from,
+            &to,
+            &authority,
+            amount,
+        )
+        .map_err(|e| e.to_string())?;
+        let mut instructions = vec![transfer];
+        if let Some(memo) = &request.memo {
+            instructions.push(crate::util::memo_instruction(memo, &[&authority]));
+        }
+        s.bank_client
+            .send(&instructions, &[&s.signer], SendMode::Broadcast)?
+            .ok_or_else(|| "transaction was not sent".to_string())
+    })
+    .await?;
+    info!(%signature, %from, %to, amount, "transfer sent");
+    Ok(Json(TransferResponse {
+        signature: signature.to_string(),
+    }))
+}
+
+fn router(state: Arc<ServerState>) -> Router {
+    Router::new()
+        .route("/banks/:id", get(get_bank))
+        .route("/accounts/:id", get(get_account))
+        .route("/accounts", get(list_accounts))
+        .route("/transfer", post(transfer))
+        .with_state(state)
+}
+
+/// Serves the bank's state over HTTP on `addr` until the process is stopped.
+/// `POST /transfer` signs with `signer`, which must own or be the delegate of the source account.
+pub fn run(bank_client: BankClient, signer: Keypair, addr: SocketAddr) -> Result<(), String> {
+    let runtime = match tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(r) => r,
+        Err(e) => {
+            error!(error = %e, "start runtime failed");
+            return Err("start runtime failed".to_string());
+        }
+    };
+    let state = Arc::new(ServerState {
+        bank_client,
+        signer,
+    });
+
+    runtime.block_on(async move {
+        let server = match axum::Server::try_bind(&addr) {
+            Ok(s) => s,
+            Err(e) => {
+                error!(error = %e, %addr, "bind server failed");
+                return Err("bind server failed".to_string());
+            }
+        };
+        info!(%addr, "serving bank api");
+        server
+            .serve(router(state).into_make_service())
+            .await
+            .map_err(|e| {
+                error!(error = %e, "server failed");
+                "server failed".to_string()
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::program_option::COption;
+
+    #[test]
+    fn test_account_view() {
+        let address = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let account = Account {
+            amount: 70,
+            is_opened: true,
+            is_initialized: true,
+            owner: Pubkey::new_unique(),
+            delegate: COption::Some(delegate),
+            delegated_amount: 30,
+            bank: Pubkey::new_unique(),
+            permit_nonce: 0,
+            is_frozen: false,
+            last_accrual: 0,
+        };
+        let view = AccountView::new(&address, &account);
+        assert_eq!(view.delegate, Some(delegate.to_string()));
+
+        let json = serde_json::to_value(&view).unwrap();
+        assert_eq!(json["address"], address.to_string());
+        assert_eq!(json["amount"], 70);
+
+        assert_eq!(
+            parse_pubkey("owner", "not-a-key").unwrap_err().status,
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
@@ -0,0 +1,383 @@
+//! Usage statistics of the bank program over a slot range, from its transaction
+//! history: how often each instruction is sent and what it costs in compute units, how
+//! many distinct signers use the program, and the transactions and transfer volume of
+//! each UTC day. Compute units are read from the `consumed` lines of the program's
+//! logs, so an instruction whose logs were truncated has a count but no units.
+
+use crate::events::instruction_kind;
+use serde::Serialize;
+use solana_bank::instruction::BankInstruction;
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_config::RpcTransactionConfig,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+use std::{
+    collections::{BTreeMap, HashSet},
+    str::FromStr,
+};
+use tracing::error;
+
+/// Signatures asked for per getSignaturesForAddress page, the most the RPC allows.
+const PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct InstructionStats {
+    pub count: u64,
+    /// Sum over the instructions whose units are known.
+    pub compute_units: u64,
+    pub max_compute_units: u64,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct DayStats {
+    pub transactions: u64,
+    /// Raw amount moved by `Transfer`s, across every bank.
+    pub transfer_volume: u64,
+}
+
+/// A bank instruction of a successful transaction, with the units its top-level
+/// invocation consumed when the logs tell.
+#[derive(Debug, PartialEq)]
+pub struct SampledInstruction {
+    pub kind: String,
+    pub amount: Option<u64>,
+    pub compute_units: Option<u64>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TransactionSample {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub signers: Vec<Pubkey>,
+    pub instructions: Vec<SampledInstruction>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct UsageReport {
+    pub start_slot: u64,
+    pub end_slot: u64,
+    pub transactions: u64,
+    /// Failed transactions are counted here and nowhere else.
+    pub failed_transactions: u64,
+    pub unique_users: usize,
+    pub instructions: BTreeMap<String, InstructionStats>,
+    /// Keyed by `YYYY-MM-DD`; transactions without a block time are left out.
+    pub days: BTreeMap<String, DayStats>,
+    #[serde(skip)]
+    users: HashSet<Pubkey>,
+}
+
+impl UsageReport {
+    pub fn new(start_slot: u64, end_slot: u64) -> UsageReport {
+        UsageReport {
+            start_slot,
+            end_slot,
+            ..UsageReport::default()
+        }
+    }
+
+    pub fn record(&mut self, sample: &TransactionSample) {
+        self.transactions += 1;
+        self.users.extend(sample.signers.iter().copied());
+        self.unique_users = self.users.len();
+        for ix in &sample.instructions {
+            let stats = self.instructions.entry(ix.kind.clone()).or_default();
+            stats.count += 1;
+            if let Some(units) = ix.compute_units {
+                stats.compute_units += units;
+                stats.max_compute_units = stats.max_compute_units.max(units);
+            }
+        }
+        if let Some(time) = sample.block_time {
+            let day = self.days.entry(utc_day(time)).or_default();
+            day.transactions += 1;
+            day.transfer_volume += sample
+                .instructions
+                .iter()
+                .filter(|ix| {
+                    matches!(
+                        ix.kind.as_str(),
+                        "Transfer" | "TransferWithExpiry" | "TransferChecked" | "TransferBatch"
+                    )
+                })
+                .filter_map(|ix| ix.amount)
+                .sum::<u64>();
+        }
+    }
+
+    /// The report as `metric,key,value` rows, one figure per row so a spreadsheet can
+    /// pivot it.
+    pub fn to_csv(&self) -> String {
+        let mut rows = vec![
+            "metric,key,value".to_string(),
+            format!("start_slot,,{}", self.start_slot),
+            format!("end_slot,,{}", self.end_slot),
+            format!("transactions,,{}", self.transactions),
+            format!("failed_transactions,,{}", self.failed_transactions),
+            format!("unique_users,,{}", self.unique_users),
+        ];
+        for (kind, stats) in &self.instructions {
+            rows.push(format!("instruction_count,{},{}", kind, stats.count));
+            rows.push(format!(
+                "instruction_compute_units,{},{}",
+                kind, stats.compute_units
+            ));
+            rows.push(format!(
+                "instruction_max_compute_units,{},{}",
+                kind, stats.max_compute_units
+            ));
+        }
+        for (day, stats) in &self.days {
+            rows.push(format!("day_transactions,{},{}", day, stats.transactions));
+            rows.push(format!(
+                "day_transfer_volume,{},{}",
+                day, stats.transfer_volume
+            ));
+        }
+        rows.join("\n") + "\n"
+    }
+}
+
+/// Walks the program's signatures from the newest back to `start_slot` and aggregates
+/// the transactions that landed in `start_slot..=end_slot`.
+pub fn scan(
+    client: &RpcClient,
+    program_id: &Pubkey,
+    start_slot: u64,
+    end_slot: u64,
+) -> Result<UsageReport, String> {
+    let mut report = UsageReport::new(start_slot, end_slot);
+    let mut before = None;
+    loop {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            before,
+            until: None,
+            limit: Some(PAGE_SIZE),
+            commitment: Some(CommitmentConfig::confirmed()),
+        };
+        let page = match client.get_signatures_for_address_with_config(program_id, config) {
+            Ok(p) => p,
+            Err(e) => {
+                error!(error = %e, "get signatures failed");
+                return Err("get signatures failed".to_string());
+            }
+        };
+        for status in &page {
+            if status.slot > end_slot {
+                continue;
+            }
+            if status.slot < start_slot {
+                return Ok(report);
+            }
+            if status.err.is_some() {
+                report.failed_transactions += 1;
+                continue;
+            }
+            let signature = Signature::from_str(&status.signature)
+                .map_err(|e| format!("invalid signature {}: {}", status.signature, e))?;
+            report.record(&fetch_sample(client, program_id, &signature)?);
+        }
+        match page.last() {
+            Some(last) if page.len() == PAGE_SIZE => {
+                before = Signature::from_str(&last.signature).ok();
+            }
+            _ => return Ok(report),
+        }
+    }
+}
+
+fn fetch_sample(
+    client: &RpcClient,
+    program_id: &Pubkey,
+    signature: &Signature,
+) -> Result<TransactionSample, String> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    let confirmed = match client.get_transaction_with_config(signature, config) {
+        Ok(t) => t,
+        Err(e) => {
+            error!(error = %e, %signature, "get transaction failed");
+            return Err("get transaction failed".to_string());
+        }
+    };
+    let transaction = match confirmed.transaction.transaction.decode() {
+        Some(t) => t,
+        None => return Err("decode transaction failed".to_string()),
+    };
+    let logs: Option<Vec<String>> = confirmed
+        .transaction
+        .meta
+        .and_then(|meta| meta.log_messages.into());
+    let mut units = invocation_units(&logs.unwrap_or_default(), program_id).into_iter();
+
+    let keys = transaction.message.static_account_keys();
+    let signer_count = transaction.message.header().num_required_signatures as usize;
+    let mut instructions = Vec::new();
+    for ix in transaction.message.instructions() {
+        if keys.get(ix.program_id_index as usize) != Some(program_id) {
+            continue;
+        }
+        let compute_units = units.next().flatten();
+        if let Ok(instruction) = BankInstruction::unpack(&ix.data) {
+            let (kind, amount) = instruction_kind(&instruction);
+            instructions.push(SampledInstruction {
+                kind,
+                amount,
+                compute_units,
+            });
+        }
+    }
+    Ok(TransactionSample {
+        slot: confirmed.slot,
+        block_time: confirmed.block_time,
+        signers: keys.iter().take(signer_count).copied().collect(),
+        instructions,
+    })
+}
+
+/// Units consumed by each top-level invocation of `program_id`, in order, as the
+/// runtime logs them: `Program <id> invoke [1]` ... `Program <id> consumed <n> of <m>
+/// compute units`.
+pub fn invocation_units(logs: &[String], program_id: &Pubkey) -> Vec<Option<u64>> {
+    let mut units = Vec::new();
+    let mut depth = 0;
+    for line in logs {
+        // Lines of the runtime are `Program <id> <event>`; `Program log: ...` and the
+        // like are what the programs themselves printed.
+        let (key, event) = match line
+            .strip_prefix("Program ")
+            .and_then(|rest| rest.split_once(' '))
+        {
+            Some(parts) => parts,
+            None => continue,
+        };
+        let key = match Pubkey::from_str(key) {
+            Ok(k) => k,
+            Err(_) => continue,
+        };
+        if let Some(level) = event
+            .strip_prefix("invoke [")
+            .and_then(|level| level.strip_suffix(']'))
+        {
+            depth = level.parse().unwrap_or(depth + 1);
+            if key == *program_id && depth == 1 {
+                units.push(None);
+            }
+        } else if event == "success" || event.starts_with("failed: ") {
+            depth -= 1;
+        } else if let Some(consumed) = event.strip_prefix("consumed ") {
+            let consumed = consumed
+                .split_whitespace()
+                .next()
+                .and_then(|n| n.parse().ok());
+            if let (true, 1, Some(last)) = (key == *program_id, depth, units.last_mut()) {
+                *last = consumed;
+            }
+        }
+    }
+    units
+}
+
+/// `YYYY-MM-DD` of a unix timestamp, in UTC.
+fn utc_day(timestamp: i64) -> String {
+    // Howard Hinnant's civil_from_days.
+    let z = timestamp.div_euclid(86_400) + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invocation_units() {
+        let program_id = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let logs: Vec<String> = vec![
+            format!("Program {} invoke [1]", program_id),
+            "Program log: Instruction: Transfer".to_string(),
+            format!(
+                "Program {} consumed 2100 of 200000 compute units",
+                program_id
+            ),
+            format!("Program {} success", program_id),
+            format!("Program {} invoke [1]", other),
+            format!("Program {} invoke [2]", program_id),
+            format!(
+                "Program {} consumed 900 of 190000 compute units",
+                program_id
+            ),
+            format!("Program {} success", program_id),
+            format!("Program {} consumed 5000 of 197900 compute units", other),
+            format!("Program {} success", other),
+            format!("Program {} invoke [1]", program_id),
+            "Log truncated".to_string(),
+        ];
+        assert_eq!(invocation_units(&logs, &program_id), vec![Some(2100), None]);
+    }
+
+    #[test]
+    fn test_report() {
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let transfer = |amount, compute_units| SampledInstruction {
+            kind: "Transfer".to_string(),
+            amount: Some(amount),
+            compute_units,
+        };
+        let mut report = UsageReport::new(100, 200);
+        report.record(&TransactionSample {
+            slot: 100,
+            block_time: Some(1_700_000_000),
+            signers: vec![alice],
+            instructions: vec![transfer(10, Some(2000)), transfer(5, Some(3000))],
+        });
+        report.record(&TransactionSample {
+            slot: 150,
+            block_time: Some(1_700_000_000 + 86_400),
+            signers: vec![alice, bob],
+            instructions: vec![transfer(1, None)],
+        });
+        report.failed_transactions += 1;
+
+        assert_eq!(report.unique_users, 2);
+        assert_eq!(
+            report.instructions["Transfer"],
+            InstructionStats {
+                count: 3,
+                compute_units: 5000,
+                max_compute_units: 3000,
+            }
+        );
+        assert_eq!(report.days["2023-11-14"].transfer_volume, 15);
+        assert_eq!(report.days["2023-11-15"].transactions, 1);
+
+        let csv = report.to_csv();
+        assert!(csv.starts_with("metric,key,value\nstart_slot,,100\n"));
+        assert!(csv.contains("instruction_count,Transfer,3\n"));
+        assert!(csv.contains("day_transfer_volume,2023-11-15,1\n"));
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["failed_transactions"], 1);
+        assert!(json.get("users").is_none());
+    }
+
+    #[test]
+    fn test_utc_day() {
+        assert_eq!(utc_day(0), "1970-01-01");
+        assert_eq!(utc_day(951_782_400), "2000-02-29");
+        assert_eq!(utc_day(-1), "1969-12-31");
+    }
+}
@@ -0,0 +1,129 @@
+//! What `bank-cli sweep` reclaims: the rent held by an owner's bank accounts that no
+//! longer hold any tokens, closed in batches.
+
+use solana_bank::{instruction, state::Account};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use std::collections::HashSet;
+
+/// Closes per transaction, well inside the transaction size limit: each adds one
+/// account key and a one-byte instruction.
+pub const CLOSES_PER_TRANSACTION: usize = 8;
+
+/// An empty account closing would return `lamports` from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sweepable {
+    pub address: Pubkey,
+    pub bank: Pubkey,
+    pub lamports: u64,
+}
+
+/// The accounts of `accounts`, as (address, state, lamports), that hold no tokens and
+/// whose bank is among `banks`; closing checks the bank, so accounts of a bank that is
+/// gone stay where they are.
+pub fn sweepable(accounts: &[(Pubkey, Account, u64)], banks: &HashSet<Pubkey>) -> Vec<Sweepable> {
+    accounts
+        .iter()
+        .filter(|(_, account, lamports)| {
+            account.amount == 0 && *lamports > 0 && banks.contains(&account.bank)
+        })
+        .map(|(address, account, lamports)| Sweepable {
+            address: *address,
+            bank: account.bank,
+            lamports: *lamports,
+        })
+        .collect()
+}
+
+pub fn total_lamports(sweepable: &[Sweepable]) -> u64 {
+    sweepable
+        .iter()
+        .fold(0, |total, s| total.saturating_add(s.lamports))
+}
+
+/// The transactions closing every `sweepable` account of `owner` into `destination`.
+pub fn batches(
+    program_id: &Pubkey,
+    sweepable: &[Sweepable],
+    owner: &Pubkey,
+    destination: &Pubkey,
+) -> Result<Vec<Vec<Instruction>>, String> {
+    sweepable
+        .chunks(CLOSES_PER_TRANSACTION)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .map(|s| {
+                    instruction::close_account(program_id, &s.bank, &s.address, destination, owner)
+                        .map_err(|e| e.to_string())
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(bank: Pubkey, amount: u64) -> Account {
+        Account {
+            amount,
+            is_opened: true,
+            is_initialized: true,
+            bank,
+            ..Account::default()
+        }
+    }
+
+    #[test]
+    fn test_sweepable() {
+        let (bank, gone) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (empty, holding, orphan) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let accounts = [
+            (empty, account(bank, 0), 2_000),
+            (holding, account(bank, 5), 2_000),
+            (orphan, account(gone, 0), 2_000),
+        ];
+        let banks = [bank].iter().copied().collect();
+        let sweepable = sweepable(&accounts, &banks);
+        assert_eq!(
+            sweepable,
+            vec![Sweepable {
+                address: empty,
+                bank,
+                lamports: 2_000
+            }]
+        );
+        assert_eq!(total_lamports(&sweepable), 2_000);
+    }
+
+    #[test]
+    fn test_batches() {
+        let (program_id, owner, bank) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let sweepable: Vec<Sweepable> = (0..CLOSES_PER_TRANSACTION + 1)
+            .map(|_| Sweepable {
+                address: Pubkey::new_unique(),
+                bank,
+                lamports: 1,
+            })
+            .collect();
+        let batches = batches(&program_id, &sweepable, &owner, &owner).unwrap();
+        assert_eq!(
+            batches.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![CLOSES_PER_TRANSACTION, 1]
+        );
+        assert_eq!(
+            batches[1][0],
+            instruction::close_account(&program_id, &bank, &sweepable[8].address, &owner, &owner)
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use crate::bank::{ui_amount, BankClient};
+use crossterm::{
+    event::{self, Event, KeyCode},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Row, Table},
+    Frame, Terminal,
+};
+use solana_bank::state::{Account, Bank};
+use solana_client::{
+    pubsub_client::PubsubClient,
+    rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+    rpc_response::RpcLogsResponse,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::{cmp::Reverse, collections::VecDeque, io, time::Duration};
+use tracing::error;
+
+const TOP_ACCOUNTS: usize = 10;
+const FEED_SIZE: usize = 50;
+
+struct FeedEntry {
+    slot: u64,
+    signature: String,
+    instruction: String,
+    failed: bool,
+}
+
+struct Dashboard {
+    bank_key: Pubkey,
+    bank: Bank,
+    accounts: Vec<(Pubkey, Account)>,
+    feed: VecDeque<FeedEntry>,
+}
+
+impl Dashboard {
+    fn refresh(&mut self, client: &BankClient) -> Result<(), String> {
+        self.bank = client.get_bank(&self.bank_key)?;
+        let mut accounts = client.get_bank_accounts(&self.bank_key)?;
+        accounts.sort_by_key(|(_, account)| Reverse(account.amount));
+        accounts.truncate(TOP_ACCOUNTS);
+        self.accounts = accounts;
+        Ok(())
+    }
+
+    fn push(&mut self, slot: u64, logs: &RpcLogsResponse) {
+        for line in logs.logs.iter() {
+            let instruction = match line.strip_prefix("Program log: Instruction: ") {
+                Some(i) => i,
+                None => continue,
+            };
+            if !instruction.starts_with("Transfer") {
+                continue;
+            }
+            self.feed.push_front(FeedEntry {
+                slot,
+                signature: logs.signature.clone(),
+                instruction: instruction.to_string(),
+                failed: logs.err.is_some(),
+            });
+        }
+        self.feed.truncate(FEED_SIZE);
+    }
+}
+
+/// Full-screen dashboard for one bank; press `q` to quit.
+pub fn run(client: &BankClient, bank: &Pubkey, ws_url: &str) -> Result<(), String> {
+    let (_subscription, receiver) = match PubsubClient::logs_subscribe(
+        ws_url,
+        RpcTransactionLogsFilter::Mentions(vec![client.program_id.to_string()]),
+        RpcTransactionLogsConfig {
+            commitment: Some(CommitmentConfig::confirmed()),
+        },
+    ) {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "logs subscribe failed");
+            return Err("logs subscribe failed".to_string());
+        }
+    };
+
+    let mut dashboard = Dashboard {
+        bank_key: *bank,
+        bank: Bank::default(),
+        accounts: Vec::new(),
+        feed: VecDeque::with_capacity(FEED_SIZE),
+    };
+    dashboard.refresh(client)?;
+
+    if let Err(e) = enable_raw_mode() {
+        error!(error = %e, "enable raw mode failed");
+        return Err("enable raw mode failed".to_string());
+    }
+    let mut stdout = io::stdout();
+    let _ = execute!(stdout, EnterAlternateScreen);
+    let mut terminal = match Terminal::new(CrosstermBackend::new(stdout)) {
+        Ok(t) => t,
+        Err(e) => {
+            let _ = disable_raw_mode();
+            error!(error = %e, "init terminal failed");
+            return Err("init terminal failed".to_string());
+        }
+    };
+
+    let result = (|| -> Result<(), String> {
+        loop {
+            if let Err(e) = terminal.draw(|f| draw(f, &dashboard)) {
+                error!(error = %e, "draw failed");
+                return Err("draw failed".to_string());
+            }
+
+            let mut updated = false;
+            while let Ok(response) = receiver.try_recv() {
+                dashboard.push(response.context.slot, &response.value);
+                updated = true;
+            }
+            if updated {
+                dashboard.refresh(client)?;
+            }
+
+            if event::poll(Duration::from_millis(250)).unwrap_or(false) {
+                if let Ok(Event::Key(key)) = event::read() {
+                    if key.code == KeyCode::Char('q') || key.code == KeyCode::Esc {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    })();
+
+    let _ = disable_raw_mode();
+    let _ = execute!(terminal.backend_mut(), LeaveAlternateScreen);
+    let _ = terminal.show_cursor();
+    result
+}
+
+fn draw(f: &mut Frame, dashboard: &Dashboard) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(f.size());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[1]);
+
+    let decimals = dashboard.bank.decimals;
+    let summary = Paragraph::new(format!(
+        "bank {}  owner {}  supply {}  (q to quit)",
+        dashboard.bank_key,
+        dashboard.bank.bank_owner,
+        ui_amount(dashboard.bank.total_supply, decimals)
+    ))
+    .block(Block::default().borders(Borders::ALL).title("Bank"));
+    f.render_widget(summary, rows[0]);
+
+    let accounts = dashboard.accounts.iter().map(|(key, account)| {
+        Row::new(vec![
+            key.to_string(),
+            ui_amount(account.amount, decimals),
+            ui_amount(account.delegated_amount, decimals),
+        ])
+    });
+    let table = Table::new(
+        accounts,
+        [
+            Constraint::Percentage(50),
+            Constraint::Percentage(25),
+            Constraint::Percentage(25),
+        ],
+    )
+    .header(Row::new(vec!["account", "amount", "delegated"]))
+    .block(Block::default().borders(Borders::ALL).title("Top accounts"));
+    f.render_widget(table, columns[0]);
+
+    let feed: Vec<ListItem> = dashboard
+        .feed
+        .iter()
+        .map(|entry| {
+            let status = if entry.failed { "failed" } else { "ok" };
+            ListItem::new(format!(
+                "{} {} {} {}",
+                entry.slot, entry.instruction, status, entry.signature
+            ))
+        })
+        .collect();
+    let list = List::new(feed).block(Block::default().borders(Borders::ALL).title("Transfers"));
+    f.render_widget(list, columns[1]);
+}
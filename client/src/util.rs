@@ -1,11 +1,29 @@
+use crate::{blockhash::BlockhashCache, keygen, preflight, rpc::RpcApi};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
-    instruction::Instruction, message, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
-    system_instruction, transaction,
+    commitment_config::CommitmentConfig,
+    instruction::{AccountMeta, Instruction},
+    message,
+    pubkey::Pubkey,
+    signature::{Signature, Signer},
+    signer::keypair::{read_keypair, Keypair},
+    system_instruction::{self, SystemInstruction},
+    system_program, transaction,
 };
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::atomic::{AtomicBool, Ordering},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+use tracing::{debug, error, info, info_span, warn, Level};
+
+#[cfg(feature = "metrics")]
+use crate::metrics;
 
 pub fn create_program_account_instruction(
-    client: &RpcClient,
+    client: &dyn RpcApi,
     program_id: &Pubkey,
     seed: &str,
     signer: Box<dyn Signer>,
@@ -16,7 +34,7 @@ pub fn create_program_account_instruction(
     let min_rent = match client.get_minimum_balance_for_rent_exemption(space as usize) {
         Ok(r) => r,
         Err(e) => {
-            println!("err: {}", e);
+            error!(error = %e, "get rent exemption failed");
             return Err("get rent exemption failed".to_string());
         }
     };
@@ -34,17 +52,19 @@ pub fn create_program_account_instruction(
 }
 
 pub fn create_program_account(
-    client: &RpcClient,
+    client: &dyn RpcApi,
     program_id: &Pubkey,
     seed: &str,
     signer: Box<dyn Signer>,
     space: u64,
+    blockhash_cache: &BlockhashCache,
+    mode: SendMode,
 ) -> Result<Pubkey, String> {
     let pub_key = signer.pubkey();
     let program_account = match Pubkey::create_with_seed(&pub_key, seed, program_id) {
         Ok(r) => r,
         Err(e) => {
-            println!("err: {}", e);
+            error!(error = %e, "init public key failed");
             return Err("init public key failed".to_string());
         }
     };
@@ -52,19 +72,19 @@ pub fn create_program_account(
     match client.get_account(&program_account) {
         Ok(a) => {
             if program_id.eq(&a.owner) {
-                println!("using account {}", program_account);
+                info!(account = %program_account, "using existing account");
                 return Ok(program_account);
             }
         }
         _ => {
-            println!("create an account")
+            debug!(account = %program_account, "account not found, creating")
         }
     }
 
     let min_rent = match client.get_minimum_balance_for_rent_exemption(space as usize) {
         Ok(r) => r,
         Err(e) => {
-            println!("err: {}", e);
+            error!(error = %e, "get rent exemption failed");
             return Err("get rent exemption failed".to_string());
         }
     };
@@ -78,46 +98,513 @@ pub fn create_program_account(
         space,
         program_id,
     );
-    let block = match client.get_recent_blockhash() {
+    let msg = message::Message::new(&[create_account_inst], Some(&pub_key));
+    if sign_and_submit(client, blockhash_cache, msg, &[signer.as_ref()], mode, None)?.is_some() {
+        info!(account = %program_account, "account created");
+    }
+    Ok(program_account)
+}
+
+// A PDA has no private key, so the returned instruction can't be signed by the client;
+// the owning program has to execute it through invoke_signed with `seeds` and the bump.
+pub fn create_pda_account(
+    client: &dyn RpcApi,
+    program_id: &Pubkey,
+    seeds: &[&[u8]],
+    payer: &Pubkey,
+    space: u64,
+) -> Result<(Pubkey, u8, Instruction), String> {
+    let (pda, bump) = Pubkey::find_program_address(seeds, program_id);
+
+    let min_rent = match client.get_minimum_balance_for_rent_exemption(space as usize) {
         Ok(r) => r,
         Err(e) => {
-            println!("err: {}", e);
-            return Err("get recent block failed".to_string());
+            error!(error = %e, "get rent exemption failed");
+            return Err("get rent exemption failed".to_string());
         }
     };
-    let ss = vec![signer];
-    let msg = message::Message::new(&[create_account_inst], Some(&pub_key));
-    let t = transaction::Transaction::new(&ss, msg, block.0);
-    match client.send_and_confirm_transaction(&t) {
+
+    let create_account_inst =
+        system_instruction::create_account(payer, &pda, min_rent, space, program_id);
+    Ok((pda, bump, create_account_inst))
+}
+
+pub const DEFAULT_CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+static WAITING_FOR_CONFIRMATION: AtomicBool = AtomicBool::new(false);
+static CONFIRMATION_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Ctrl-C while waiting on a confirmation stops the wait, so the pending signature
+/// can still be reported; at any other time it exits as usual.
+pub fn install_interrupt_handler() {
+    let result = ctrlc::set_handler(|| {
+        if WAITING_FOR_CONFIRMATION.load(Ordering::SeqCst) {
+            CONFIRMATION_CANCELLED.store(true, Ordering::SeqCst);
+        } else {
+            std::process::exit(130);
+        }
+    });
+    if let Err(e) = result {
+        error!(error = %e, "install interrupt handler failed");
+    }
+}
+
+pub enum Confirmation {
+    Confirmed {
+        slot: u64,
+    },
+    /// The blockhash passed `last_valid_slot` without the transaction landing.
+    Expired,
+}
+
+/// Polls the signature until it reaches `commitment`, fails, expires, `timeout`
+/// elapses or the wait is interrupted with Ctrl-C.
+pub fn wait_for_confirmation(
+    client: &dyn RpcApi,
+    signature: &Signature,
+    commitment: CommitmentConfig,
+    timeout: Duration,
+    last_valid_slot: Option<u64>,
+) -> Result<Confirmation, String> {
+    CONFIRMATION_CANCELLED.store(false, Ordering::SeqCst);
+    WAITING_FOR_CONFIRMATION.store(true, Ordering::SeqCst);
+    let result = poll_confirmation(client, signature, commitment, timeout, last_valid_slot);
+    WAITING_FOR_CONFIRMATION.store(false, Ordering::SeqCst);
+    result
+}
+
+fn poll_confirmation(
+    client: &dyn RpcApi,
+    signature: &Signature,
+    commitment: CommitmentConfig,
+    timeout: Duration,
+    last_valid_slot: Option<u64>,
+) -> Result<Confirmation, String> {
+    let started = Instant::now();
+    loop {
+        let status = match client.get_signature_statuses(&[*signature]) {
+            Ok(r) => r.value.into_iter().next().flatten(),
+            Err(e) => {
+                error!(error = %e, "get signature statuses failed");
+                return Err("get signature statuses failed".to_string());
+            }
+        };
+        match status {
+            Some(s) if s.err.is_some() => {
+                let err = s.err.unwrap();
+                error!(error = %err, "transaction failed");
+                return Err(format!("transaction failed: {}", err));
+            }
+            Some(s) if s.satisfies_commitment(commitment) => {
+                return Ok(Confirmation::Confirmed { slot: s.slot });
+            }
+            _ => {}
+        }
+
+        if let Some(last_valid_slot) = last_valid_slot {
+            let slot = match client.get_slot_with_commitment(commitment) {
+                Ok(s) => s,
+                Err(e) => {
+                    error!(error = %e, "get slot failed");
+                    return Err("get slot failed".to_string());
+                }
+            };
+            if slot > last_valid_slot {
+                return Ok(Confirmation::Expired);
+            }
+        }
+
+        if CONFIRMATION_CANCELLED.load(Ordering::SeqCst) {
+            warn!(%signature, "interrupted while waiting for confirmation");
+            return Err(pending_error("interrupted", signature));
+        }
+        if started.elapsed() >= timeout {
+            warn!(%signature, ?timeout, "timed out waiting for confirmation");
+            return Err(pending_error("timed out", signature));
+        }
+        sleep(CONFIRM_POLL_INTERVAL);
+    }
+}
+
+fn pending_error(reason: &str, signature: &Signature) -> String {
+    format!(
+        "{} before {} was confirmed, check it later with `bank-cli confirm {}`",
+        reason, signature, signature
+    )
+}
+
+pub fn send_and_confirm_transaction(
+    client: &dyn RpcApi,
+    t: &transaction::Transaction,
+) -> Result<Signature, String> {
+    let span = info_span!(
+        "transaction",
+        signature = %t.signatures[0],
+        slot = tracing::field::Empty
+    );
+    let _enter = span.enter();
+    #[cfg(feature = "metrics")]
+    let started = Instant::now();
+    #[cfg(feature = "metrics")]
+    metrics::record_transaction_sent();
+
+    let signature = match client.send_transaction(t) {
+        Ok(s) => s,
         Err(e) => {
-            println!("err: {}", e);
-            return Err("send and confirm transaction failed".to_string());
+            #[cfg(feature = "metrics")]
+            metrics::record_rpc_error(&e);
+            error!(error = %e, "send transaction failed");
+            return Err("send transaction failed".to_string());
+        }
+    };
+    match wait_for_confirmation(
+        client,
+        &signature,
+        client.commitment(),
+        DEFAULT_CONFIRM_TIMEOUT,
+        None,
+    )? {
+        Confirmation::Confirmed { slot } => {
+            #[cfg(feature = "metrics")]
+            metrics::observe_confirmation_latency(started.elapsed());
+            span.record("slot", slot);
+            info!("transaction confirmed");
+            Ok(signature)
         }
-        _ => {}
+        Confirmation::Expired => Err("blockhash expired".to_string()),
+    }
+}
+
+/// How a built transaction is handled; anything but `Broadcast` never reaches the cluster.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SendMode {
+    Broadcast,
+    DryRun,
+    Simulate,
+    /// Print the signed transaction as base64 for submission elsewhere.
+    SignOnly,
+    /// Print the transaction with empty signatures as base64 so an external wallet
+    /// or multisig service can sign and submit it.
+    ExportUnsigned,
+}
+
+impl SendMode {
+    pub fn from_args() -> SendMode {
+        let args: Vec<String> = std::env::args().collect();
+        if args.iter().any(|arg| arg == "--sign-only=false") {
+            return SendMode::ExportUnsigned;
+        }
+        if args
+            .iter()
+            .any(|arg| arg == "--sign-only" || arg == "--sign-only=true")
+        {
+            return SendMode::SignOnly;
+        }
+        if !args.iter().any(|arg| arg == "--dry-run") {
+            return SendMode::Broadcast;
+        }
+        if args.iter().any(|arg| arg == "--simulate") {
+            return SendMode::Simulate;
+        }
+        SendMode::DryRun
     }
-    println!("create account {}", program_account);
-    Ok(program_account)
 }
 
-pub fn check_program(client: &RpcClient, program_id: &Pubkey) -> Result<bool, String> {
+/// Renders the data of a program's instruction for dry-run output.
+pub type InstructionDecoder = fn(&[u8]) -> Option<String>;
+
+pub fn submit_transaction(
+    client: &dyn RpcApi,
+    t: &transaction::Transaction,
+    mode: SendMode,
+    decoder: Option<InstructionDecoder>,
+) -> Result<Option<Signature>, String> {
+    match mode {
+        SendMode::Broadcast => send_and_confirm_transaction(client, t).map(Some),
+        SendMode::DryRun | SendMode::Simulate => {
+            print_transaction(client, t, decoder)?;
+            if mode == SendMode::Simulate {
+                simulate_transaction(client, t)?;
+            }
+            Ok(None)
+        }
+        SendMode::SignOnly => {
+            println!("{}", encode_transaction(t)?);
+            Ok(None)
+        }
+        SendMode::ExportUnsigned => {
+            let unsigned = transaction::Transaction::new_unsigned(t.message.clone());
+            println!("{}", encode_transaction(&unsigned)?);
+            Ok(None)
+        }
+    }
+}
+
+/// Signs `msg` with a blockhash from `blockhash_cache` and hands it to `submit_transaction`;
+/// broadcasts go through the cache so an expired blockhash gets the transaction re-signed.
+pub fn sign_and_submit(
+    client: &dyn RpcApi,
+    blockhash_cache: &BlockhashCache,
+    msg: message::Message,
+    signers: &[&dyn Signer],
+    mode: SendMode,
+    decoder: Option<InstructionDecoder>,
+) -> Result<Option<Signature>, String> {
+    let (blockhash, _) = blockhash_cache.get(client)?;
+    let mut msg = msg;
+    msg.recent_blockhash = blockhash;
+    if mode == SendMode::Broadcast {
+        preflight::check_fee_payer_balance(client, &msg)?;
+        return blockhash_cache
+            .send_and_confirm(client, &msg, signers)
+            .map(Some);
+    }
+
+    let mut t = transaction::Transaction::new_unsigned(msg);
+    if let Err(e) = t.try_sign(signers, blockhash) {
+        error!(error = %e, "sign transaction failed");
+        return Err("sign transaction failed".to_string());
+    }
+    submit_transaction(client, &t, mode, decoder)
+}
+
+/// Wire-format (bincode) transaction encoded as base64, the form wallets and
+/// `sendTransaction` accept.
+pub fn encode_transaction(t: &transaction::Transaction) -> Result<String, String> {
+    match bincode::serialize(t) {
+        Ok(bytes) => Ok(base64::encode(bytes)),
+        Err(e) => {
+            error!(error = %e, "serialize transaction failed");
+            Err("serialize transaction failed".to_string())
+        }
+    }
+}
+
+pub fn print_transaction(
+    client: &dyn RpcApi,
+    t: &transaction::Transaction,
+    decoder: Option<InstructionDecoder>,
+) -> Result<(), String> {
+    let msg = &t.message;
+    println!("dry run: transaction is not broadcast");
+    println!("fee payer: {}", msg.account_keys[0]);
+    for (i, ix) in msg.instructions.iter().enumerate() {
+        let program_id = msg.account_keys[ix.program_id_index as usize];
+        println!("instruction #{} program {}", i, program_id);
+        for &index in ix.accounts.iter() {
+            let index = index as usize;
+            println!(
+                "  {} signer={} writable={}",
+                msg.account_keys[index],
+                msg.is_signer(index),
+                msg.is_writable(index)
+            );
+        }
+        let decoded = if program_id == system_program::id() {
+            bincode::deserialize::<SystemInstruction>(&ix.data)
+                .ok()
+                .map(|i| format!("{:?}", i))
+        } else if program_id == memo_program_id() {
+            String::from_utf8(ix.data.clone())
+                .ok()
+                .map(|memo| format!("Memo {:?}", memo))
+        } else {
+            decoder.and_then(|decode| decode(&ix.data))
+        };
+        match decoded {
+            Some(d) => println!("  data: {}", d),
+            None => println!("  data: 0x{}", to_hex(&ix.data)),
+        }
+    }
+
+    let fee = preflight::fee_for_message(client, msg)?;
+    println!("estimated fee: {} lamports", fee);
+    Ok(())
+}
+
+pub fn simulate_transaction(
+    client: &dyn RpcApi,
+    t: &transaction::Transaction,
+) -> Result<(), String> {
+    let result = match client.simulate_transaction(t) {
+        Ok(r) => r.value,
+        Err(e) => {
+            error!(error = %e, "simulate transaction failed");
+            return Err("simulate transaction failed".to_string());
+        }
+    };
+    for log in result.logs.unwrap_or_default() {
+        println!("  log: {}", log);
+    }
+    match result.err {
+        Some(e) => println!("simulation failed: {}", e),
+        None => println!("simulation succeeded"),
+    }
+    Ok(())
+}
+
+fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn memo_program_id() -> Pubkey {
+    Pubkey::from_str("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr").unwrap()
+}
+
+/// SPL Memo instruction; every pubkey in `signers` has to sign the transaction.
+pub fn memo_instruction(memo: &str, signers: &[&Pubkey]) -> Instruction {
+    Instruction {
+        program_id: memo_program_id(),
+        accounts: signers
+            .iter()
+            .map(|key| AccountMeta::new_readonly(**key, true))
+            .collect(),
+        data: memo.as_bytes().to_vec(),
+    }
+}
+
+pub fn check_program(client: &dyn RpcApi, program_id: &Pubkey) -> Result<bool, String> {
     match client.get_account(&program_id) {
         Ok(acc) => {
-            println!("owner {}", acc.owner);
+            debug!(owner = %acc.owner, "program account found");
             if !acc.executable {
                 return Err("account is not program".to_string());
             }
         }
         Err(e) => {
-            println!("err: {}", e);
+            error!(error = %e, "account is not found");
             return Err("account is not found".to_string());
         }
     }
     Ok(true)
 }
 
+pub fn init_logging(verbose: bool) {
+    let level = if verbose { Level::DEBUG } else { Level::INFO };
+    tracing_subscriber::fmt().with_max_level(level).init();
+}
+
+/// Derives the pubsub endpoint from an RPC url the same way the solana cli does:
+/// http(s) becomes ws(s) and a local 8899 port moves to 8900.
+pub fn websocket_url(rpc_url: &str) -> String {
+    let url = if let Some(rest) = rpc_url.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = rpc_url.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        rpc_url.to_string()
+    };
+    url.replace(":8899", ":8900")
+}
+
+/// A cluster to send to: one of the public ones, a local test validator, or any RPC url.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Cluster {
+    Localnet,
+    Devnet,
+    Testnet,
+    Mainnet,
+    Custom(String),
+}
+
+impl Cluster {
+    pub fn url(&self) -> &str {
+        match self {
+            Cluster::Localnet => "http://localhost:8899",
+            Cluster::Devnet => "https://api.devnet.solana.com",
+            Cluster::Testnet => "https://api.testnet.solana.com",
+            Cluster::Mainnet => "https://api.mainnet-beta.solana.com",
+            Cluster::Custom(url) => url,
+        }
+    }
+
+    /// `--url <cluster>` picks the cluster by name or RPC url; devnet without it.
+    pub fn from_args() -> Result<Cluster, String> {
+        match arg_value("--url") {
+            Some(url) => url.parse(),
+            None => Ok(Cluster::Devnet),
+        }
+    }
+}
+
+impl FromStr for Cluster {
+    type Err = String;
+
+    /// Takes the names and one-letter monikers the solana cli does, or an http(s) url.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "localnet" | "localhost" | "l" => Ok(Cluster::Localnet),
+            "devnet" | "d" => Ok(Cluster::Devnet),
+            "testnet" | "t" => Ok(Cluster::Testnet),
+            "mainnet" | "mainnet-beta" | "m" => Ok(Cluster::Mainnet),
+            url if url.starts_with("http://") || url.starts_with("https://") => {
+                Ok(Cluster::Custom(url.to_string()))
+            }
+            _ => Err(format!("unknown cluster {}", s)),
+        }
+    }
+}
+
+pub fn new_client(cluster: &Cluster, commitment: CommitmentConfig) -> RpcClient {
+    RpcClient::new_with_commitment(cluster.url().to_string(), commitment)
+}
+
 pub fn new_dev_client() -> RpcClient {
-    let url = "https://api.devnet.solana.com".to_string();
-    RpcClient::new(url)
+    new_client(&Cluster::Devnet, CommitmentConfig::default())
+}
+
+/// The value following `name` on the command line.
+fn arg_value(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip_while(|arg| arg != name);
+    args.next()?;
+    args.next()
+}
+
+/// Environment variable `keypair_from_args` reads a keypair from.
+pub const KEYPAIR_ENV: &str = "SOLANA_KEYPAIR";
+
+/// Where the solana cli keeps its default keypair, `~/.config/solana/id.json`.
+pub fn default_keypair_path() -> Result<PathBuf, String> {
+    let home = std::env::var("HOME").map_err(|_| "HOME is not set".to_string())?;
+    Ok(Path::new(&home).join(".config/solana/id.json"))
+}
+
+/// Reads a keypair file in the solana cli's JSON format, or one `keygen` encrypted. A
+/// leading `~` stands for the home directory.
+pub fn load_keypair_from_file(path: &Path) -> Result<Keypair, String> {
+    match path.strip_prefix("~") {
+        Ok(rest) => {
+            let home = std::env::var("HOME").map_err(|_| "HOME is not set".to_string())?;
+            keygen::load_keypair(&Path::new(&home).join(rest))
+        }
+        Err(_) => keygen::load_keypair(path),
+    }
+}
+
+/// Reads a keypair from the environment variable `var`, holding the JSON byte array a
+/// keypair file does.
+pub fn load_keypair_from_env(var: &str) -> Result<Keypair, String> {
+    let value = std::env::var(var).map_err(|_| format!("{} is not set", var))?;
+    match read_keypair(&mut value.as_bytes()) {
+        Ok(k) => Ok(k),
+        Err(e) => {
+            error!(error = %e, var, "parse keypair failed");
+            Err(format!("parse keypair in {} failed", var))
+        }
+    }
+}
+
+/// The keypair the example binaries sign with: the file `--keypair <path>` names, else
+/// the one in `$SOLANA_KEYPAIR`, else `get_keypair`'s.
+pub fn keypair_from_args() -> Result<Keypair, String> {
+    if let Some(path) = arg_value("--keypair") {
+        return load_keypair_from_file(Path::new(&path));
+    }
+    if std::env::var_os(KEYPAIR_ENV).is_some() {
+        return load_keypair_from_env(KEYPAIR_ENV);
+    }
+    Ok(get_keypair())
 }
 
 pub fn get_keypair() -> Keypair {
@@ -129,3 +616,126 @@ pub fn get_keypair() -> Keypair {
     ];
     Keypair::from_bytes(&private_key).unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::mock::MockRpc;
+    use solana_sdk::account::Account;
+
+    const SPACE: u64 = 118;
+
+    fn create(mock: &MockRpc, program_id: &Pubkey, signer: &Keypair) -> Result<Pubkey, String> {
+        create_program_account(
+            mock,
+            program_id,
+            "bank",
+            Box::new(Keypair::from_bytes(&signer.to_bytes()).unwrap()),
+            SPACE,
+            &BlockhashCache::default(),
+            SendMode::Broadcast,
+        )
+    }
+
+    #[test]
+    fn test_create_program_account() {
+        let program_id = Pubkey::new_unique();
+        let signer = Keypair::new();
+        let address = Pubkey::create_with_seed(&signer.pubkey(), "bank", &program_id).unwrap();
+
+        let mock = MockRpc::default();
+        assert_eq!(create(&mock, &program_id, &signer), Ok(address));
+        let sent = mock.sent();
+        assert_eq!(sent.len(), 1);
+        let ix = &sent[0].message.instructions[0];
+        match bincode::deserialize::<SystemInstruction>(&ix.data).unwrap() {
+            SystemInstruction::CreateAccountWithSeed {
+                lamports,
+                space,
+                owner,
+                ..
+            } => {
+                assert_eq!(lamports, mock.rent);
+                assert_eq!(space, SPACE);
+                assert_eq!(owner, program_id);
+            }
+            other => panic!("unexpected instruction {:?}", other),
+        }
+
+        // An account the program already owns is reused as is.
+        let mut mock = MockRpc::default();
+        mock.accounts.insert(
+            address,
+            Account::new(mock.rent, SPACE as usize, &program_id),
+        );
+        assert_eq!(create(&mock, &program_id, &signer), Ok(address));
+        assert!(mock.sent().is_empty());
+    }
+
+    #[test]
+    fn test_rpc_error_mapping() {
+        let program_id = Pubkey::new_unique();
+        let signer = Keypair::new();
+
+        let mock = MockRpc::default().fail("get_minimum_balance_for_rent_exemption");
+        assert_eq!(
+            create(&mock, &program_id, &signer),
+            Err("get rent exemption failed".to_string())
+        );
+
+        let mock = MockRpc::default().fail("send_transaction");
+        assert_eq!(
+            create(&mock, &program_id, &signer),
+            Err("send transaction failed".to_string())
+        );
+
+        let mut mock = MockRpc::default();
+        mock.transaction_error =
+            Some(transaction::TransactionError::InsufficientFundsForRent { account_index: 1 });
+        let err = create(&mock, &program_id, &signer).unwrap_err();
+        assert!(err.starts_with("transaction failed: "), "{}", err);
+
+        let mut mock = MockRpc::default();
+        mock.balance = 0;
+        let err = create(&mock, &program_id, &signer).unwrap_err();
+        assert!(err.starts_with("fund this address: "), "{}", err);
+        assert!(mock.sent().is_empty());
+    }
+
+    #[test]
+    fn test_cluster() {
+        assert_eq!("l".parse(), Ok(Cluster::Localnet));
+        assert_eq!("mainnet-beta".parse(), Ok(Cluster::Mainnet));
+        assert_eq!(
+            "http://10.0.0.2:8899".parse::<Cluster>().unwrap().url(),
+            "http://10.0.0.2:8899"
+        );
+        assert_eq!(
+            "mainnet-alpha".parse::<Cluster>(),
+            Err("unknown cluster mainnet-alpha".to_string())
+        );
+        assert_eq!(Cluster::Localnet.url(), "http://localhost:8899");
+    }
+
+    #[test]
+    fn test_load_keypair_from_env() {
+        let keypair = Keypair::new();
+        let var = "UTIL_TEST_KEYPAIR";
+        std::env::set_var(var, format!("{:?}", keypair.to_bytes().to_vec()));
+        assert_eq!(
+            load_keypair_from_env(var).unwrap().pubkey(),
+            keypair.pubkey()
+        );
+
+        std::env::set_var(var, "[1, 2, 3]");
+        assert_eq!(
+            load_keypair_from_env(var).map(|k| k.pubkey()),
+            Err("parse keypair in UTIL_TEST_KEYPAIR failed".to_string())
+        );
+        std::env::remove_var(var);
+        assert_eq!(
+            load_keypair_from_env(var).map(|k| k.pubkey()),
+            Err("UTIL_TEST_KEYPAIR is not set".to_string())
+        );
+    }
+}
@@ -0,0 +1,322 @@
+//! Signing with a browser wallet instead of a keypair file. `WalletSigner` hands each
+//! transaction it is asked to sign to a page served from 127.0.0.1, which finds the
+//! wallets installed in the browser through the wallet-standard events, has the chosen
+//! one sign with `solana:signTransaction`, and posts the signed transaction back; only
+//! the signature of the wallet's key is taken from it.
+
+use solana_sdk::{
+    message::Message,
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::{Signer, SignerError},
+    transaction::Transaction,
+};
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    process,
+    thread::sleep,
+    time::{Duration, Instant},
+};
+use tracing::{debug, error};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A `Signer` for a key held by a browser wallet. Every signature opens a page, so a
+/// broadcast that has to be re-signed for a fresh blockhash asks the wallet again.
+pub struct WalletSigner {
+    pubkey: Pubkey,
+    timeout: Duration,
+}
+
+impl WalletSigner {
+    pub fn new(pubkey: Pubkey) -> WalletSigner {
+        WalletSigner {
+            pubkey,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// How long to wait for the wallet to sign before giving up.
+    pub fn with_timeout(mut self, timeout: Duration) -> WalletSigner {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl Signer for WalletSigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.pubkey)
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let message: Message = bincode::deserialize(message)
+            .map_err(|e| SignerError::Custom(format!("invalid message: {}", e)))?;
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .map_err(|e| SignerError::Custom(format!("start callback server failed: {}", e)))?;
+        let url = format!(
+            "http://{}/",
+            listener
+                .local_addr()
+                .map_err(|e| SignerError::Custom(e.to_string()))?
+        );
+        println!("sign with the wallet of {} at {}", self.pubkey, url);
+        open_browser(&url);
+        hand_off(listener, &message, &self.pubkey, self.timeout).map_err(SignerError::Custom)
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
+
+/// Best effort; the link is printed for when no browser can be started from here.
+fn open_browser(url: &str) {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    if let Err(e) = process::Command::new(opener).arg(url).spawn() {
+        debug!(error = %e, opener, "open browser failed");
+    }
+}
+
+/// Serves the signing page on `listener` until a transaction carrying `pubkey`'s valid
+/// signature of `message` is posted back, and returns that signature.
+pub fn hand_off(
+    listener: TcpListener,
+    message: &Message,
+    pubkey: &Pubkey,
+    timeout: Duration,
+) -> Result<Signature, String> {
+    let index = message.account_keys[..message.header.num_required_signatures as usize]
+        .iter()
+        .position(|key| key == pubkey)
+        .ok_or_else(|| format!("{} doesn't sign this transaction", pubkey))?;
+    let unsigned = Transaction::new_unsigned(message.clone());
+    let payload = match bincode::serialize(&unsigned) {
+        Ok(bytes) => base64::encode(bytes),
+        Err(e) => {
+            error!(error = %e, "serialize transaction failed");
+            return Err("serialize transaction failed".to_string());
+        }
+    };
+    let page = SIGN_PAGE
+        .replace("{pubkey}", &pubkey.to_string())
+        .replace("{transaction}", &payload);
+
+    if let Err(e) = listener.set_nonblocking(true) {
+        return Err(format!("start callback server failed: {}", e));
+    }
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                sleep(Duration::from_millis(50));
+                continue;
+            }
+            Err(e) => return Err(format!("callback server failed: {}", e)),
+        };
+        match serve(stream, &page, message, index) {
+            Ok(Some(signature)) => return Ok(signature),
+            Ok(None) => {}
+            Err(e) => debug!(error = %e, "callback request failed"),
+        }
+    }
+    Err(format!("the wallet didn't sign within {:?}", timeout))
+}
+
+/// Answers one request: the page on `GET /`, and on `POST /callback` the signature at
+/// `index` of the posted transaction when it signs `message`.
+fn serve(
+    stream: TcpStream,
+    page: &str,
+    message: &Message,
+    index: usize,
+) -> io::Result<Option<Signature>> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    let mut stream = reader.into_inner();
+
+    let mut parts = request_line.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/")) => {
+            respond(&mut stream, "200 OK", "text/html; charset=utf-8", page)?;
+            Ok(None)
+        }
+        (Some("POST"), Some("/callback")) => match signature_of(&body, message, index) {
+            Ok(signature) => {
+                respond(&mut stream, "200 OK", "text/plain", "signed")?;
+                Ok(Some(signature))
+            }
+            Err(e) => {
+                respond(&mut stream, "400 Bad Request", "text/plain", &e)?;
+                Ok(None)
+            }
+        },
+        _ => {
+            respond(&mut stream, "404 Not Found", "text/plain", "not found")?;
+            Ok(None)
+        }
+    }
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+/// The signature at `index` of the base64 transaction in `body`, checked against `message`.
+fn signature_of(body: &[u8], message: &Message, index: usize) -> Result<Signature, String> {
+    let bytes = base64::decode(body).map_err(|_| "the body isn't base64".to_string())?;
+    let signed: Transaction =
+        bincode::deserialize(&bytes).map_err(|_| "the body isn't a transaction".to_string())?;
+    if &signed.message != message {
+        return Err("the wallet changed the transaction".to_string());
+    }
+    let signature = signed
+        .signatures
+        .get(index)
+        .copied()
+        .ok_or_else(|| "the transaction has no signature for the key".to_string())?;
+    if !signature.verify(message.account_keys[index].as_ref(), &message.serialize()) {
+        return Err("the signature doesn't verify".to_string());
+    }
+    Ok(signature)
+}
+
+const SIGN_PAGE: &str = r#"<!doctype html>
+<html>
+<head><meta charset="utf-8"><title>Sign transaction</title></head>
+<body>
+<p>Sign the transaction with the wallet holding <code>{pubkey}</code>.</p>
+<div id="wallets"></div>
+<p id="status"></p>
+<script>
+const PUBKEY = "{pubkey}";
+const TRANSACTION = Uint8Array.from(atob("{transaction}"), (c) => c.charCodeAt(0));
+const status = (text) => (document.getElementById("status").textContent = text);
+
+async function sign(wallet) {
+  try {
+    const { accounts } = await wallet.features["standard:connect"].connect();
+    const account = accounts.find((a) => a.address === PUBKEY);
+    if (!account) return status(`${wallet.name} doesn't hold ${PUBKEY}`);
+    const [{ signedTransaction }] = await wallet.features["solana:signTransaction"]
+      .signTransaction({ account, transaction: TRANSACTION });
+    const body = btoa(String.fromCharCode(...signedTransaction));
+    const response = await fetch("/callback", { method: "POST", body });
+    status(response.ok ? "Signed; you can close this page." : await response.text());
+  } catch (e) {
+    status(`${wallet.name}: ${e.message || e}`);
+  }
+}
+
+const api = {
+  register(...wallets) {
+    for (const wallet of wallets) {
+      if (!wallet.features["solana:signTransaction"]) continue;
+      const button = document.createElement("button");
+      button.textContent = wallet.name;
+      button.onclick = () => sign(wallet);
+      document.getElementById("wallets").appendChild(button);
+    }
+    return () => {};
+  },
+};
+window.addEventListener("wallet-standard:register-wallet", ({ detail }) => detail(api));
+window.dispatchEvent(new CustomEvent("wallet-standard:app-ready", { detail: api }));
+</script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{hash::Hash, signature::Keypair, system_instruction};
+    use std::thread;
+
+    fn request(addr: &str, head: &str, body: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "{}\r\nContent-Length: {}\r\n\r\n{}",
+            head,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_hand_off() {
+        let wallet = Keypair::new();
+        let instruction = system_instruction::transfer(&wallet.pubkey(), &Pubkey::new_unique(), 1);
+        let mut message = Message::new(&[instruction], Some(&wallet.pubkey()));
+        message.recent_blockhash = Hash::new_unique();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let pubkey = wallet.pubkey();
+        let expected = message.clone();
+        let handle =
+            thread::spawn(move || hand_off(listener, &expected, &pubkey, Duration::from_secs(30)));
+
+        let page = request(&addr, "GET / HTTP/1.1", "");
+        let unsigned = Transaction::new_unsigned(message.clone());
+        let payload = base64::encode(bincode::serialize(&unsigned).unwrap());
+        assert!(page.starts_with("HTTP/1.1 200 OK"));
+        assert!(page.contains(&payload));
+
+        let mut tampered = message.clone();
+        tampered.recent_blockhash = Hash::new_unique();
+        let other = Transaction::new(&[&wallet], tampered, Hash::default());
+        let rejected = request(
+            &addr,
+            "POST /callback HTTP/1.1",
+            &base64::encode(bincode::serialize(&other).unwrap()),
+        );
+        assert!(rejected.starts_with("HTTP/1.1 400"));
+
+        let blockhash = message.recent_blockhash;
+        let signed = Transaction::new(&[&wallet], message, blockhash);
+        let accepted = request(
+            &addr,
+            "POST /callback HTTP/1.1",
+            &base64::encode(bincode::serialize(&signed).unwrap()),
+        );
+        assert!(accepted.starts_with("HTTP/1.1 200 OK"));
+        assert_eq!(handle.join().unwrap(), Ok(signed.signatures[0]));
+    }
+}
@@ -0,0 +1,163 @@
+use solana_account_decoder::UiAccountEncoding;
+use solana_bank::state::{Account, Bank};
+use solana_client::{
+    pubsub_client::PubsubClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+};
+use solana_program::program_pack::Pack;
+use solana_sdk::{
+    account::Account as SolanaAccount, commitment_config::CommitmentConfig, pubkey::Pubkey,
+};
+use std::{
+    str::FromStr,
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+use tracing::{debug, error};
+
+/// A decoded change to one of the bank program's accounts.
+#[derive(Debug, PartialEq)]
+pub enum BankUpdate {
+    Bank {
+        key: Pubkey,
+        slot: u64,
+        bank: Bank,
+    },
+    Account {
+        key: Pubkey,
+        slot: u64,
+        account: Account,
+    },
+}
+
+impl BankUpdate {
+    pub fn slot(&self) -> u64 {
+        match self {
+            BankUpdate::Bank { slot, .. } | BankUpdate::Account { slot, .. } => *slot,
+        }
+    }
+}
+
+/// Decodes program account data by its size; anything that isn't a packed `Bank`
+/// or `Account` is skipped.
+pub fn decode_update(key: Pubkey, slot: u64, data: &[u8]) -> Option<BankUpdate> {
+    match data.len() {
+        Bank::LEN => Bank::unpack(data)
+            .ok()
+            .map(|bank| BankUpdate::Bank { key, slot, bank }),
+        Account::LEN => Account::unpack(data)
+            .ok()
+            .map(|account| BankUpdate::Account { key, slot, account }),
+        _ => None,
+    }
+}
+
+/// Streams updates of every account owned by `program_id` over the RPC websocket.
+/// The subscription ends once the returned receiver is dropped.
+pub fn watch_program(ws_url: &str, program_id: &Pubkey) -> Result<Receiver<BankUpdate>, String> {
+    let config = RpcProgramAccountsConfig {
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+    let (subscription, notifications) =
+        match PubsubClient::program_subscribe(ws_url, program_id, Some(config)) {
+            Ok(s) => s,
+            Err(e) => {
+                error!(error = %e, "program subscribe failed");
+                return Err("program subscribe failed".to_string());
+            }
+        };
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _subscription = subscription;
+        for response in notifications.iter() {
+            let slot = response.context.slot;
+            let keyed = response.value;
+            let key = match Pubkey::from_str(&keyed.pubkey) {
+                Ok(k) => k,
+                Err(_) => continue,
+            };
+            let account: SolanaAccount = match keyed.account.decode() {
+                Some(a) => a,
+                None => continue,
+            };
+            if let Some(update) = decode_update(key, slot, &account.data) {
+                if sender.send(update).is_err() {
+                    break;
+                }
+            }
+        }
+        debug!("program subscription closed");
+    });
+    Ok(receiver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_bank::state::{BurnPolicy, ClosePolicy};
+    use solana_program::program_option::COption;
+
+    #[test]
+    fn test_decode_update() {
+        let key = Pubkey::new_unique();
+        let bank = Bank {
+            decimals: 2,
+            bank_owner: Pubkey::new_unique(),
+            is_opened: true,
+            total_supply: 100,
+            close_policy: ClosePolicy::Reject,
+            require_rent_exempt: false,
+            burn_policy: BurnPolicy::OwnerAndHolder,
+            lamports_per_token: 0,
+            transfer_count: 3,
+            cumulative_volume: 60,
+            holder_count: 2,
+            registered_accounts: 4,
+            dust_threshold: 0,
+            freeze_authority: COption::None,
+            is_paused: false,
+            interest_rate_bps: 0,
+            emergency_authority: COption::None,
+            owner_change_delay: 0,
+            pending_bank_owner: COption::None,
+            owner_change_slot: 0,
+        };
+        let mut data = vec![0u8; Bank::LEN];
+        Bank::pack(bank, &mut data).unwrap();
+        assert_eq!(
+            decode_update(key, 7, &data),
+            Some(BankUpdate::Bank { key, slot: 7, bank })
+        );
+
+        let account = Account {
+            amount: 100,
+            is_opened: true,
+            is_initialized: true,
+            owner: Pubkey::new_unique(),
+            delegate: COption::None,
+            delegated_amount: 0,
+            bank: key,
+            permit_nonce: 0,
+            is_frozen: false,
+            last_accrual: 0,
+        };
+        let mut data = vec![0u8; Account::LEN];
+        Account::pack(account, &mut data).unwrap();
+        assert_eq!(
+            decode_update(key, 8, &data),
+            Some(BankUpdate::Account {
+                key,
+                slot: 8,
+                account
+            })
+        );
+
+        assert_eq!(decode_update(key, 9, &[0u8; 5]), None);
+    }
+}
@@ -0,0 +1,274 @@
+//! Full bank lifecycle against a local `solana-test-validator`, driven through the
+//! client SDK. Both programs are loaded from their `target/deploy` builds
+//! (`cargo build-bpf` in `bank/program` and `program`, or `SBF_OUT_DIR`); the test
+//! is skipped when the validator binary or either `.so` is missing.
+
+use client::{
+    bank::BankClient,
+    blockhash::BlockhashCache,
+    util::{self, Confirmation, SendMode},
+};
+use program::greeting_account::{GreetingAccount, GREETING_LEN};
+use solana_bank::instruction;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use std::{
+    env,
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+fn find_program(crate_dir: &str, so_name: &str) -> Option<PathBuf> {
+    let default_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join(crate_dir)
+        .join("target/deploy");
+    ["SBF_OUT_DIR", "BPF_OUT_DIR"]
+        .iter()
+        .filter_map(env::var_os)
+        .map(PathBuf::from)
+        .chain(std::iter::once(default_dir))
+        .map(|dir| dir.join(so_name))
+        .find(|path| path.exists())
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// A test validator with the given programs loaded at genesis, killed on drop.
+struct Validator {
+    process: Child,
+    ledger: PathBuf,
+    url: String,
+}
+
+impl Validator {
+    fn start(programs: &[(Pubkey, PathBuf)]) -> Option<Validator> {
+        let rpc_port = free_port();
+        let ledger = env::temp_dir().join(format!("bank-e2e-ledger-{}", std::process::id()));
+        let mut command = Command::new("solana-test-validator");
+        command
+            .arg("--reset")
+            .arg("--quiet")
+            .arg("--ledger")
+            .arg(&ledger)
+            .arg("--rpc-port")
+            .arg(rpc_port.to_string())
+            .arg("--faucet-port")
+            .arg(free_port().to_string())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        for (program_id, path) in programs {
+            command
+                .arg("--bpf-program")
+                .arg(program_id.to_string())
+                .arg(path);
+        }
+        let process = command.spawn().ok()?;
+        let validator = Validator {
+            process,
+            ledger,
+            url: format!("http://127.0.0.1:{}", rpc_port),
+        };
+
+        let client = validator.client();
+        let started = Instant::now();
+        while client.get_health().is_err() {
+            assert!(
+                started.elapsed() < STARTUP_TIMEOUT,
+                "test validator did not come up within {:?}",
+                STARTUP_TIMEOUT
+            );
+            sleep(Duration::from_millis(500));
+        }
+        Some(validator)
+    }
+
+    fn client(&self) -> RpcClient {
+        RpcClient::new_with_commitment(self.url.clone(), CommitmentConfig::confirmed())
+    }
+}
+
+impl Drop for Validator {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+        let _ = std::fs::remove_dir_all(&self.ledger);
+    }
+}
+
+fn airdrop(client: &RpcClient, to: &Pubkey) {
+    let signature = client.request_airdrop(to, 10 * LAMPORTS_PER_SOL).unwrap();
+    match util::wait_for_confirmation(
+        client,
+        &signature,
+        CommitmentConfig::confirmed(),
+        util::DEFAULT_CONFIRM_TIMEOUT,
+        None,
+    )
+    .unwrap()
+    {
+        Confirmation::Confirmed { .. } => {}
+        Confirmation::Expired => panic!("airdrop expired"),
+    }
+}
+
+fn copy_keypair(keypair: &Keypair) -> Box<Keypair> {
+    Box::new(Keypair::from_bytes(&keypair.to_bytes()).unwrap())
+}
+
+fn create_account(
+    bank_client: &BankClient,
+    program_id: &Pubkey,
+    payer: &Keypair,
+    seed: &str,
+    space: usize,
+) -> Pubkey {
+    util::create_program_account(
+        &bank_client.rpc,
+        program_id,
+        seed,
+        copy_keypair(payer),
+        space as u64,
+        &bank_client.blockhash_cache,
+        SendMode::Broadcast,
+    )
+    .unwrap()
+}
+
+fn send(bank_client: &BankClient, instructions: &[Instruction], signers: &[&dyn Signer]) {
+    bank_client
+        .send(instructions, signers, SendMode::Broadcast)
+        .unwrap()
+        .expect("broadcast returns a signature");
+}
+
+#[test]
+fn test_bank_lifecycle() {
+    let bank_so = find_program("../bank/program", "solana_bank.so");
+    let greeting_so = find_program("../program", "program.so");
+    let (bank_so, greeting_so) = match (bank_so, greeting_so) {
+        (Some(bank), Some(greeting)) => (bank, greeting),
+        _ => {
+            eprintln!("program builds not found, skipping; run `cargo build-bpf` first");
+            return;
+        }
+    };
+    let bank_program_id = Pubkey::new_unique();
+    let greeting_program_id = Pubkey::new_unique();
+    let validator = match Validator::start(&[
+        (bank_program_id, bank_so),
+        (greeting_program_id, greeting_so),
+    ]) {
+        Some(v) => v,
+        None => {
+            eprintln!("solana-test-validator not found, skipping");
+            return;
+        }
+    };
+
+    let payer = Keypair::new();
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    let delegate = Pubkey::new_unique();
+    airdrop(&validator.client(), &payer.pubkey());
+    let bank_client = BankClient::new(validator.client(), bank_program_id)
+        .with_blockhash_cache(BlockhashCache::default());
+    util::check_program(&bank_client.rpc, &bank_program_id).unwrap();
+
+    // The payer owns the bank and pays for every account.
+    let (bank, alice_account, bob_account) = (Keypair::new(), Keypair::new(), Keypair::new());
+    bank_client
+        .create_and_initialize_bank(&payer, &bank, &payer, 2, SendMode::Broadcast)
+        .unwrap();
+    for (account, owner) in [(&alice_account, &alice), (&bob_account, &bob)] {
+        bank_client
+            .create_bank_account(&payer, &bank.pubkey(), account, owner, SendMode::Broadcast)
+            .unwrap();
+    }
+    let (bank, alice_account, bob_account) =
+        (bank.pubkey(), alice_account.pubkey(), bob_account.pubkey());
+    // The transfer is signed, and paid for, by alice alone.
+    airdrop(&validator.client(), &alice.pubkey());
+    bank_client
+        .mint_to(&payer, &alice_account, 1_000, SendMode::Broadcast)
+        .unwrap();
+    bank_client
+        .transfer(
+            &alice,
+            &alice_account,
+            &bob_account,
+            400,
+            SendMode::Broadcast,
+        )
+        .unwrap();
+
+    let id = &bank_program_id;
+    send(
+        &bank_client,
+        &[
+            instruction::approve(id, &bank, &alice_account, &delegate, &alice.pubkey(), 100)
+                .unwrap(),
+        ],
+        &[&payer, &alice],
+    );
+    send(
+        &bank_client,
+        &[instruction::burn(id, &bank, &bob_account, &payer.pubkey(), &bob.pubkey(), 50).unwrap()],
+        &[&payer, &bob],
+    );
+
+    let bank_state = bank_client.get_bank(&bank).unwrap();
+    assert!(bank_state.is_opened);
+    assert_eq!(bank_state.bank_owner, payer.pubkey());
+    assert_eq!(bank_state.total_supply, 950);
+
+    let alice_state = bank_client.get_account(&alice_account).unwrap();
+    assert_eq!(alice_state.owner, alice.pubkey());
+    assert_eq!(alice_state.amount, 600);
+    assert_eq!(alice_state.delegated_amount, 100);
+    assert_eq!(Option::<Pubkey>::from(alice_state.delegate), Some(delegate));
+    assert_eq!(bank_client.get_account(&bob_account).unwrap().amount, 350);
+
+    let mut accounts = bank_client.get_bank_accounts(&bank).unwrap();
+    accounts.sort_by_key(|(key, _)| *key);
+    let mut expected = vec![alice_account, bob_account];
+    expected.sort();
+    assert_eq!(
+        accounts.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+        expected
+    );
+    assert!(bank_client.audit(&bank).unwrap().is_consistent());
+
+    // The greeting program counts how often its account has been greeted.
+    let greeted = create_account(
+        &bank_client,
+        &greeting_program_id,
+        &payer,
+        "hello",
+        GREETING_LEN,
+    );
+    let greet = Instruction::new_with_bincode(
+        greeting_program_id,
+        &(),
+        vec![AccountMeta::new(greeted, false)],
+    );
+    send(&bank_client, &[greet.clone(), greet], &[&payer]);
+    let data = bank_client.rpc.get_account(&greeted).unwrap().data;
+    assert_eq!(GreetingAccount::read(&data).unwrap().counter, 2);
+}
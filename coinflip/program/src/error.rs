@@ -0,0 +1,29 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CoinflipError {
+    /// The wager authority isn't the wager's program address.
+    InvalidWagerAuthority,
+    /// The account isn't the wager's vault.
+    InvalidVault,
+    /// The stake can't be zero.
+    InvalidStake,
+    /// The account isn't the one the player staked from.
+    InvalidPlayerAccount,
+    /// The signer didn't open the wager.
+    NotMaker,
+    /// Someone already took the other side of the wager.
+    AlreadyAccepted,
+    /// Nobody has taken the other side of the wager yet.
+    NotAccepted,
+    /// The hash of the settle slot isn't out yet.
+    SettleTooEarly,
+    /// The wager has already been settled.
+    AlreadySettled,
+}
+
+impl From<CoinflipError> for ProgramError {
+    fn from(e: CoinflipError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,244 @@
+use crate::state::Wager;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the wager authority, followed by the wager's address.
+pub const WAGER_AUTHORITY_SEED: &[u8] = b"coinflip";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CoinflipInstruction {
+    /// Opens a wager of `stake` a side, initializes its vault, owned by the wager
+    /// authority, and escrows the maker's stake in it.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The maker, owner of the maker's account.
+    ///   1. `[writable]` The wager, an uninitialized account owned by this program.
+    ///   2. `[writable]` The vault, an uninitialized account owned by the bank program.
+    ///   3. `[writable]` The bank stakes are paid in.
+    ///   4. `[writable]` The maker's account, staked from and paid into.
+    ///   5. `[writable]` The wager authority.
+    ///   6. `[]` The bank program, owner of the vault.
+    CreateWager { stake: u64 },
+
+    /// Escrows the taker's stake, matching the maker's, and fixes the settle slot.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The taker, owner of the taker's account.
+    ///   1. `[writable]` The wager.
+    ///   2. `[writable]` The taker's account, staked from and paid into.
+    ///   3. `[writable]` The vault.
+    ///   4. `[]` The wager's bank.
+    ///   5. `[]` The bank program, owner of the vault.
+    Accept,
+
+    /// Flips the coin with the settle slot's hash and pays both stakes to the winner.
+    /// Anyone can settle, once the hash is out.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The wager.
+    ///   1. `[writable]` The vault.
+    ///   2. `[writable]` The wager authority.
+    ///   3. `[]` The SlotHashes sysvar.
+    ///   4. `[writable]` The maker's account.
+    ///   5. `[writable]` The taker's account.
+    ///   6. `[]` The wager's bank.
+    ///   7. `[]` The bank program, owner of the vault.
+    Settle,
+
+    /// Refunds the maker's stake of a wager nobody accepted, and closes the wager and its
+    /// vault.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The maker, receiving the closed accounts' lamports.
+    ///   1. `[writable]` The wager.
+    ///   2. `[writable]` The vault.
+    ///   3. `[writable]` The wager authority.
+    ///   4. `[writable]` The maker's account.
+    ///   5. `[]` The wager's bank.
+    ///   6. `[]` The bank program, owner of the vault.
+    Cancel,
+}
+
+impl CoinflipInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (stake, _rest) = Self::unpack_u64(rest)?;
+                Self::CreateWager { stake }
+            }
+            1 => Self::Accept,
+            2 => Self::Settle,
+            3 => Self::Cancel,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(9);
+        match *self {
+            Self::CreateWager { stake } => {
+                buf.push(0);
+                buf.extend_from_slice(&stake.to_le_bytes());
+            }
+            Self::Accept => buf.push(1),
+            Self::Settle => buf.push(2),
+            Self::Cancel => buf.push(3),
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of the authority owning `wager`'s vault.
+pub fn wager_authority(coinflip_program_id: &Pubkey, wager: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WAGER_AUTHORITY_SEED, wager.as_ref()], coinflip_program_id)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_wager(
+    coinflip_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    maker: &Pubkey,
+    wager: &Pubkey,
+    vault: &Pubkey,
+    bank: &Pubkey,
+    maker_account: &Pubkey,
+    stake: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = CoinflipInstruction::CreateWager { stake }.pack();
+    let (authority, _) = wager_authority(coinflip_program_id, wager);
+    let accounts = vec![
+        AccountMeta::new(*maker, true),
+        AccountMeta::new(*wager, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(*maker_account, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *coinflip_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn accept(
+    coinflip_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    taker: &Pubkey,
+    wager: &Pubkey,
+    taker_account: &Pubkey,
+    vault: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = CoinflipInstruction::Accept.pack();
+    let accounts = vec![
+        AccountMeta::new(*taker, true),
+        AccountMeta::new(*wager, false),
+        AccountMeta::new(*taker_account, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *coinflip_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn settle(
+    coinflip_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    wager: &Pubkey,
+    state: &Wager,
+) -> Result<Instruction, ProgramError> {
+    let data = CoinflipInstruction::Settle.pack();
+    let (authority, _) = wager_authority(coinflip_program_id, wager);
+    let accounts = vec![
+        AccountMeta::new(*wager, false),
+        AccountMeta::new(state.vault, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(sysvar::slot_hashes::id(), false),
+        AccountMeta::new(state.maker_account, false),
+        AccountMeta::new(state.taker_account, false),
+        AccountMeta::new_readonly(state.bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *coinflip_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn cancel(
+    coinflip_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    wager: &Pubkey,
+    state: &Wager,
+) -> Result<Instruction, ProgramError> {
+    let data = CoinflipInstruction::Cancel.pack();
+    let (authority, _) = wager_authority(coinflip_program_id, wager);
+    let accounts = vec![
+        AccountMeta::new(state.maker, true),
+        AccountMeta::new(*wager, false),
+        AccountMeta::new(state.vault, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new(state.maker_account, false),
+        AccountMeta::new_readonly(state.bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *coinflip_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            CoinflipInstruction::CreateWager { stake: 100 },
+            CoinflipInstruction::Accept,
+            CoinflipInstruction::Settle,
+            CoinflipInstruction::Cancel,
+        ];
+        for instruction in cases {
+            assert_eq!(
+                CoinflipInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            CoinflipInstruction::unpack(&[0, 1, 2]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            CoinflipInstruction::unpack(&[4]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,343 @@
+use crate::{
+    error::CoinflipError,
+    instruction::{CoinflipInstruction, WAGER_AUTHORITY_SEED},
+    state::{maker_wins, settle_slot_hash, Wager, SETTLE_DELAY},
+};
+use solana_bank::{instruction as bank_instruction, state::Account as BankAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::{self, Sysvar},
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = CoinflipInstruction::unpack(input)?;
+
+        match instruction {
+            CoinflipInstruction::CreateWager { stake } => {
+                msg!("Instruction: CreateWager");
+                Self::process_create_wager(program_id, accounts, stake)
+            }
+            CoinflipInstruction::Accept => {
+                msg!("Instruction: Accept");
+                Self::process_accept(program_id, accounts)
+            }
+            CoinflipInstruction::Settle => {
+                msg!("Instruction: Settle");
+                Self::process_settle(program_id, accounts)
+            }
+            CoinflipInstruction::Cancel => {
+                msg!("Instruction: Cancel");
+                Self::process_cancel(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_create_wager(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        stake: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let maker_info = next_account_info(account_info_iter)?;
+        let wager_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let maker_account_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !maker_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if wager_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut wager = Wager::unpack_unchecked(&wager_info.data.borrow())?;
+        if wager.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if stake == 0 {
+            return Err(CoinflipError::InvalidStake.into());
+        }
+        let (authority, bump) = Pubkey::find_program_address(
+            &[WAGER_AUTHORITY_SEED, wager_info.key.as_ref()],
+            program_id,
+        );
+        if authority != *authority_info.key {
+            return Err(CoinflipError::InvalidWagerAuthority.into());
+        }
+        if vault_info.owner != bank_program_info.key {
+            return Err(CoinflipError::InvalidVault.into());
+        }
+
+        invoke_signed(
+            &bank_instruction::initialize_account(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                authority_info.key,
+            )?,
+            &[
+                bank_info.clone(),
+                vault_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[WAGER_AUTHORITY_SEED, wager_info.key.as_ref(), &[bump]]],
+        )?;
+        // The bank refuses a source outside the vault's bank, so the maker's account
+        // can also be paid into.
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                maker_account_info.key,
+                vault_info.key,
+                maker_info.key,
+                stake,
+            )?,
+            &[
+                maker_account_info.clone(),
+                vault_info.clone(),
+                maker_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        wager.is_initialized = true;
+        wager.bank = *bank_info.key;
+        wager.vault = *vault_info.key;
+        wager.stake = stake;
+        wager.maker = *maker_info.key;
+        wager.maker_account = *maker_account_info.key;
+        wager.bump = bump;
+        Wager::pack(wager, &mut wager_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_accept(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let taker_info = next_account_info(account_info_iter)?;
+        let wager_info = next_account_info(account_info_iter)?;
+        let taker_account_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !taker_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut wager = Self::load_wager(program_id, wager_info, vault_info, bank_program_info)?;
+        if wager.accepted {
+            return Err(CoinflipError::AlreadyAccepted.into());
+        }
+
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                taker_account_info.key,
+                vault_info.key,
+                taker_info.key,
+                wager.stake,
+            )?,
+            &[
+                taker_account_info.clone(),
+                vault_info.clone(),
+                taker_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        wager.taker = *taker_info.key;
+        wager.taker_account = *taker_account_info.key;
+        wager.accepted = true;
+        wager.settle_slot = Clock::get()?
+            .slot
+            .checked_add(SETTLE_DELAY)
+            .ok_or(ProgramError::InvalidArgument)?;
+        Wager::pack(wager, &mut wager_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_settle(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let wager_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let slot_hashes_info = next_account_info(account_info_iter)?;
+        let maker_account_info = next_account_info(account_info_iter)?;
+        let taker_account_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        let mut wager = Self::load_wager(program_id, wager_info, vault_info, bank_program_info)?;
+        if !wager.accepted {
+            return Err(CoinflipError::NotAccepted.into());
+        }
+        if wager.settled {
+            return Err(CoinflipError::AlreadySettled.into());
+        }
+        if wager.maker_account != *maker_account_info.key
+            || wager.taker_account != *taker_account_info.key
+        {
+            return Err(CoinflipError::InvalidPlayerAccount.into());
+        }
+        if *slot_hashes_info.key != sysvar::slot_hashes::id() {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let (slot, slot_hash) =
+            settle_slot_hash(&slot_hashes_info.data.borrow(), wager.settle_slot)
+                .ok_or(CoinflipError::SettleTooEarly)?;
+        let winner_info = if maker_wins(&slot_hash, wager_info.key) {
+            maker_account_info
+        } else {
+            taker_account_info
+        };
+        msg!("settled by the hash of slot {}", slot);
+
+        let signer_seeds: &[&[u8]] =
+            &[WAGER_AUTHORITY_SEED, wager_info.key.as_ref(), &[wager.bump]];
+        Self::check_authority(program_id, signer_seeds, authority_info)?;
+        let pot = BankAccount::unpack(&vault_info.data.borrow())?.amount;
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                winner_info.key,
+                authority_info.key,
+                pot,
+            )?,
+            &[
+                vault_info.clone(),
+                winner_info.clone(),
+                authority_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        wager.settled = true;
+        wager.winner = *winner_info.key;
+        Wager::pack(wager, &mut wager_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_cancel(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let maker_info = next_account_info(account_info_iter)?;
+        let wager_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let maker_account_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !maker_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let wager = Self::load_wager(program_id, wager_info, vault_info, bank_program_info)?;
+        if wager.maker != *maker_info.key {
+            return Err(CoinflipError::NotMaker.into());
+        }
+        if wager.accepted {
+            return Err(CoinflipError::AlreadyAccepted.into());
+        }
+        if wager.maker_account != *maker_account_info.key {
+            return Err(CoinflipError::InvalidPlayerAccount.into());
+        }
+
+        let signer_seeds: &[&[u8]] =
+            &[WAGER_AUTHORITY_SEED, wager_info.key.as_ref(), &[wager.bump]];
+        Self::check_authority(program_id, signer_seeds, authority_info)?;
+        let stake = BankAccount::unpack(&vault_info.data.borrow())?.amount;
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                maker_account_info.key,
+                authority_info.key,
+                stake,
+            )?,
+            &[
+                vault_info.clone(),
+                maker_account_info.clone(),
+                authority_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        invoke_signed(
+            &bank_instruction::close_account(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                maker_info.key,
+                authority_info.key,
+            )?,
+            &[
+                vault_info.clone(),
+                maker_info.clone(),
+                authority_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        let maker_lamports = maker_info.lamports();
+        **maker_info.lamports.borrow_mut() = maker_lamports
+            .checked_add(wager_info.lamports())
+            .ok_or(ProgramError::InvalidArgument)?;
+        **wager_info.lamports.borrow_mut() = 0;
+        wager_info.data.borrow_mut().fill(0);
+        Ok(())
+    }
+
+    /// Loads the wager, checking `vault_info` is its vault. Pinning the bank program to
+    /// the vault's owner keeps any other program from taking a stake, or the wager
+    /// authority's signature, for the bank's.
+    fn load_wager(
+        program_id: &Pubkey,
+        wager_info: &AccountInfo,
+        vault_info: &AccountInfo,
+        bank_program_info: &AccountInfo,
+    ) -> Result<Wager, ProgramError> {
+        if wager_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let wager = Wager::unpack(&wager_info.data.borrow())?;
+        if wager.vault != *vault_info.key || vault_info.owner != bank_program_info.key {
+            return Err(CoinflipError::InvalidVault.into());
+        }
+        Ok(wager)
+    }
+
+    fn check_authority(
+        program_id: &Pubkey,
+        signer_seeds: &[&[u8]],
+        authority_info: &AccountInfo,
+    ) -> ProgramResult {
+        let authority = Pubkey::create_program_address(signer_seeds, program_id)
+            .map_err(|_| ProgramError::from(CoinflipError::InvalidWagerAuthority))?;
+        if authority != *authority_info.key {
+            return Err(CoinflipError::InvalidWagerAuthority.into());
+        }
+        Ok(())
+    }
+}
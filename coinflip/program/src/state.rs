@@ -0,0 +1,215 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    hash::hashv,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+/// Slots between a wager being accepted and the slot whose hash settles it. Nobody, the
+/// taker included, knows that hash when the last stake goes in.
+pub const SETTLE_DELAY: u64 = 1;
+
+/// A wager of `stake` tokens of `bank` a side, escrowed in `vault`, a bank account owned
+/// by the wager authority. The maker stakes on opening it, the taker on accepting it,
+/// and the hash of `settle_slot` then picks who takes both stakes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Wager {
+    pub is_initialized: bool,
+    pub bank: Pubkey,
+    pub vault: Pubkey,
+    pub stake: u64,
+    pub maker: Pubkey,
+    /// Account the maker staked from, and is paid into if they win.
+    pub maker_account: Pubkey,
+    pub taker: Pubkey,
+    /// Account the taker staked from, and is paid into if they win.
+    pub taker_account: Pubkey,
+    pub accepted: bool,
+    /// Slot whose hash settles the wager, once accepted.
+    pub settle_slot: u64,
+    pub settled: bool,
+    /// Account the stakes were paid into, once settled.
+    pub winner: Pubkey,
+    /// Bump seed of the wager authority address.
+    pub bump: u8,
+}
+
+/// Whether the maker wins a wager settled by `slot_hash`. The wager's address is mixed
+/// in so that wagers settled by the same slot don't share an outcome.
+///
+/// A slot hash is only as unpredictable as the leader of that slot is honest: the leader
+/// can skip its slot to discard a hash it dislikes. This is fine for stakes smaller than
+/// a slot's rewards, and nothing else.
+pub fn maker_wins(slot_hash: &[u8; 32], wager: &Pubkey) -> bool {
+    hashv(&[slot_hash, wager.as_ref()]).to_bytes()[0] & 1 == 0
+}
+
+/// Slot and hash of the oldest entry of the SlotHashes sysvar's data at or after
+/// `settle_slot`: the settle slot's own, unless its leader skipped it. Read in place
+/// rather than deserializing every entry. None until that hash is out.
+///
+/// Later hashes don't change the result, so whoever settles can't wait for one they
+/// like, until the settle slot falls out of the sysvar's history of recent slots.
+pub fn settle_slot_hash(data: &[u8], settle_slot: u64) -> Option<(u64, [u8; 32])> {
+    let entries = u64::from_le_bytes(data.get(..8)?.try_into().ok()?);
+    let mut found = None;
+    // Entries are ordered from the most recent slot back.
+    for entry in data.get(8..)?.chunks_exact(40).take(entries as usize) {
+        let slot = u64::from_le_bytes(entry[..8].try_into().ok()?);
+        if slot < settle_slot {
+            break;
+        }
+        found = Some((slot, entry[8..].try_into().ok()?));
+    }
+    found
+}
+
+impl Sealed for Wager {}
+impl IsInitialized for Wager {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Wager {
+    const LEN: usize = 244;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 244];
+        let (
+            is_initialized,
+            bank,
+            vault,
+            stake,
+            maker,
+            maker_account,
+            taker,
+            taker_account,
+            accepted,
+            settle_slot,
+            settled,
+            winner,
+            bump,
+        ) = array_refs![src, 1, 32, 32, 8, 32, 32, 32, 32, 1, 8, 1, 32, 1];
+        Ok(Wager {
+            is_initialized: unpack_bool(is_initialized)?,
+            bank: Pubkey::new_from_array(*bank),
+            vault: Pubkey::new_from_array(*vault),
+            stake: u64::from_le_bytes(*stake),
+            maker: Pubkey::new_from_array(*maker),
+            maker_account: Pubkey::new_from_array(*maker_account),
+            taker: Pubkey::new_from_array(*taker),
+            taker_account: Pubkey::new_from_array(*taker_account),
+            accepted: unpack_bool(accepted)?,
+            settle_slot: u64::from_le_bytes(*settle_slot),
+            settled: unpack_bool(settled)?,
+            winner: Pubkey::new_from_array(*winner),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 244];
+        let (
+            is_initialized,
+            bank,
+            vault,
+            stake,
+            maker,
+            maker_account,
+            taker,
+            taker_account,
+            accepted,
+            settle_slot,
+            settled,
+            winner,
+            bump,
+        ) = mut_array_refs![dst, 1, 32, 32, 8, 32, 32, 32, 32, 1, 8, 1, 32, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        bank.copy_from_slice(self.bank.as_ref());
+        vault.copy_from_slice(self.vault.as_ref());
+        *stake = self.stake.to_le_bytes();
+        maker.copy_from_slice(self.maker.as_ref());
+        maker_account.copy_from_slice(self.maker_account.as_ref());
+        taker.copy_from_slice(self.taker.as_ref());
+        taker_account.copy_from_slice(self.taker_account.as_ref());
+        accepted[0] = self.accepted as u8;
+        *settle_slot = self.settle_slot.to_le_bytes();
+        settled[0] = self.settled as u8;
+        winner.copy_from_slice(self.winner.as_ref());
+        bump[0] = self.bump;
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let wager = Wager {
+            is_initialized: true,
+            bank: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            stake: 100,
+            maker: Pubkey::new_unique(),
+            maker_account: Pubkey::new_unique(),
+            taker: Pubkey::new_unique(),
+            taker_account: Pubkey::new_unique(),
+            accepted: true,
+            settle_slot: 1_001,
+            settled: true,
+            winner: Pubkey::new_unique(),
+            bump: 253,
+        };
+        let mut packed = vec![0u8; Wager::LEN];
+        Wager::pack(wager, &mut packed).unwrap();
+        assert_eq!(Wager::unpack(&packed), Ok(wager));
+
+        packed[201] = 2;
+        assert_eq!(
+            Wager::unpack(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_maker_wins() {
+        let wager = Pubkey::new_unique();
+        let outcomes: Vec<bool> = (0..=255u8)
+            .map(|byte| maker_wins(&[byte; 32], &wager))
+            .collect();
+        // Either side wins with some hash.
+        assert!(outcomes.contains(&true));
+        assert!(outcomes.contains(&false));
+        assert_ne!(
+            (0..=255u8)
+                .map(|byte| maker_wins(&[byte; 32], &Pubkey::new_unique()))
+                .collect::<Vec<_>>(),
+            outcomes
+        );
+    }
+
+    #[test]
+    fn test_settle_slot_hash() {
+        let mut data = vec![3, 0, 0, 0, 0, 0, 0, 0];
+        for (slot, byte) in [(9u64, 3u8), (7, 2), (6, 1)].iter() {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[*byte; 32]);
+        }
+        assert_eq!(settle_slot_hash(&data, 6), Some((6, [1; 32])));
+        // Slot 8 was skipped, so the next one settles.
+        assert_eq!(settle_slot_hash(&data, 8), Some((9, [3; 32])));
+        assert_eq!(settle_slot_hash(&data, 10), None);
+        assert_eq!(settle_slot_hash(&data[..40], 6), None);
+        assert_eq!(settle_slot_hash(&[0; 8], 0), None);
+    }
+}
@@ -0,0 +1,334 @@
+use solana_bank::{state::Account, test_utils::program_test::Env};
+use solana_coinflip::{
+    error::CoinflipError,
+    instruction::{accept, cancel, create_wager, settle, wager_authority},
+    processor::Processor,
+    state::{maker_wins, settle_slot_hash, Wager, SETTLE_DELAY},
+};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// Slot the wager is opened and accepted in, and the stake of each side.
+const OPEN: u64 = 100;
+const STAKE: u64 = 100;
+
+/// Someone holding 1_000 tokens of the wager's bank.
+struct Player {
+    owner: Keypair,
+    account: Pubkey,
+}
+
+/// A wager of `STAKE` opened by alice at `OPEN`, and bob, who could take it.
+struct Fixture {
+    env: Env,
+    bank: Pubkey,
+    wager: Pubkey,
+    alice: Player,
+    bob: Player,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_coinflip", processor!(Processor::process)).await;
+        env.set_slot(OPEN).await;
+        let bank = env.create_bank().await;
+        let (alice, bob) = (Keypair::new(), Keypair::new());
+        let alice_account = env.create_bank_account(&bank, &alice, 1_000).await;
+        let bob_account = env.create_bank_account(&bank, &bob, 1_000).await;
+        let mut fixture = Fixture {
+            env,
+            bank,
+            wager: Pubkey::default(),
+            alice: Player {
+                owner: alice,
+                account: alice_account,
+            },
+            bob: Player {
+                owner: bob,
+                account: bob_account,
+            },
+        };
+        fixture.wager = fixture.create_wager(STAKE).await.unwrap();
+        fixture
+    }
+
+    /// Has alice open a wager of `stake` and returns its address.
+    async fn create_wager(&mut self, stake: u64) -> Result<Pubkey, TransactionError> {
+        let (wager, vault) = (Keypair::new(), Keypair::new());
+        let (coinflip_program_id, bank_program_id) =
+            (self.env.program_id, self.env.bank_program_id);
+        self.env
+            .create_account(&wager, Wager::LEN, &coinflip_program_id)
+            .await;
+        self.env
+            .create_account(&vault, Account::LEN, &bank_program_id)
+            .await;
+        let instruction = create_wager(
+            &coinflip_program_id,
+            &bank_program_id,
+            &self.alice.owner.pubkey(),
+            &wager.pubkey(),
+            &vault.pubkey(),
+            &self.bank,
+            &self.alice.account,
+            stake,
+        )
+        .unwrap();
+        let alice = Keypair::from_bytes(&self.alice.owner.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&alice]).await?;
+        Ok(wager.pubkey())
+    }
+
+    async fn wager(&mut self) -> Option<Wager> {
+        self.env
+            .context
+            .banks_client
+            .get_account(self.wager)
+            .await
+            .unwrap()
+            .map(|account| Wager::unpack(&account.data).unwrap())
+    }
+
+    async fn accept(&mut self, taker: &Player) -> Result<(), TransactionError> {
+        let wager = self.wager().await.unwrap();
+        let instruction = accept(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &wager.bank,
+            &taker.owner.pubkey(),
+            &self.wager,
+            &taker.account,
+            &wager.vault,
+        )
+        .unwrap();
+        let taker = Keypair::from_bytes(&taker.owner.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&taker]).await
+    }
+
+    async fn settle(&mut self) -> Instruction {
+        let state = self.wager().await.unwrap();
+        settle(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.wager,
+            &state,
+        )
+        .unwrap()
+    }
+
+    async fn cancel(&mut self, maker: &Player) -> Result<(), TransactionError> {
+        let mut state = self.wager().await.unwrap();
+        state.maker = maker.owner.pubkey();
+        let instruction = cancel(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.wager,
+            &state,
+        )
+        .unwrap();
+        let maker = Keypair::from_bytes(&maker.owner.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&maker]).await
+    }
+}
+
+fn custom(index: u8, error: CoinflipError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        index,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_wager() {
+    let mut fixture = Fixture::new().await;
+    let wager = fixture.wager().await.unwrap();
+    assert_eq!(wager.bank, fixture.bank);
+    assert_eq!(wager.stake, STAKE);
+    assert_eq!(wager.maker, fixture.alice.owner.pubkey());
+    assert_eq!(wager.maker_account, fixture.alice.account);
+    assert!(!wager.accepted);
+    let (authority, _) = wager_authority(&fixture.env.program_id, &fixture.wager);
+    let vault = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(wager.vault)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Account::unpack(&vault.data).unwrap().owner, authority);
+    let alice = fixture.alice.account;
+    assert_eq!(fixture.env.amount(&alice).await, 1_000 - STAKE);
+    assert_eq!(fixture.env.amount(&wager.vault).await, STAKE);
+
+    assert_eq!(
+        fixture.create_wager(0).await.map(|_| ()),
+        custom(0, CoinflipError::InvalidStake)
+    );
+    // Nothing to flip for until someone takes the other side.
+    let instruction = fixture.settle().await;
+    assert_eq!(
+        fixture.env.process(&[instruction], &[]).await,
+        custom(0, CoinflipError::NotAccepted)
+    );
+}
+
+#[tokio::test]
+async fn test_accept() {
+    let mut fixture = Fixture::new().await;
+    let bob = Player {
+        owner: Keypair::from_bytes(&fixture.bob.owner.to_bytes()).unwrap(),
+        account: fixture.bob.account,
+    };
+    fixture.accept(&bob).await.unwrap();
+    let wager = fixture.wager().await.unwrap();
+    assert!(wager.accepted);
+    assert_eq!(wager.taker, bob.owner.pubkey());
+    assert_eq!(wager.taker_account, bob.account);
+    assert_eq!(wager.settle_slot, OPEN + SETTLE_DELAY);
+    assert_eq!(fixture.env.amount(&bob.account).await, 1_000 - STAKE);
+    assert_eq!(fixture.env.amount(&wager.vault).await, 2 * STAKE);
+
+    let carol = Keypair::new();
+    let bank = fixture.bank;
+    let carol = Player {
+        account: fixture.env.create_bank_account(&bank, &carol, 1_000).await,
+        owner: carol,
+    };
+    assert_eq!(
+        fixture.accept(&carol).await,
+        custom(0, CoinflipError::AlreadyAccepted)
+    );
+    let alice = Player {
+        owner: Keypair::from_bytes(&fixture.alice.owner.to_bytes()).unwrap(),
+        account: fixture.alice.account,
+    };
+    assert_eq!(
+        fixture.cancel(&alice).await,
+        custom(0, CoinflipError::AlreadyAccepted)
+    );
+}
+
+#[tokio::test]
+async fn test_settle() {
+    let mut fixture = Fixture::new().await;
+    let bob = Player {
+        owner: Keypair::from_bytes(&fixture.bob.owner.to_bytes()).unwrap(),
+        account: fixture.bob.account,
+    };
+    fixture.accept(&bob).await.unwrap();
+    let settle = fixture.settle().await;
+
+    // The settle slot's hash isn't out while it's the slot the taker accepted in.
+    assert_eq!(
+        fixture
+            .env
+            .process(&[settle.clone(), settle.clone()], &[])
+            .await,
+        custom(0, CoinflipError::SettleTooEarly)
+    );
+
+    // Warping past the settle slot skips it, so the next slot's hash settles.
+    fixture.env.set_slot(OPEN + 5).await;
+    let settle_slot = fixture.wager().await.unwrap().settle_slot;
+    let (slot, slot_hash) =
+        settle_slot_hash(&fixture.env.slot_hashes().await, settle_slot).unwrap();
+    assert_eq!(slot, OPEN + 4);
+    let mut swapped = settle.clone();
+    swapped.accounts.swap(4, 5);
+    assert_eq!(
+        fixture.env.process(&[swapped], &[]).await,
+        custom(0, CoinflipError::InvalidPlayerAccount)
+    );
+    fixture
+        .env
+        .process(std::slice::from_ref(&settle), &[])
+        .await
+        .unwrap();
+
+    let (alice, bob) = (fixture.alice.account, fixture.bob.account);
+    let (winner, loser) = if maker_wins(&slot_hash, &fixture.wager) {
+        (alice, bob)
+    } else {
+        (bob, alice)
+    };
+    let wager = fixture.wager().await.unwrap();
+    assert!(wager.settled);
+    assert_eq!(wager.winner, winner);
+    assert_eq!(fixture.env.amount(&winner).await, 1_000 + STAKE);
+    assert_eq!(fixture.env.amount(&loser).await, 1_000 - STAKE);
+    assert_eq!(fixture.env.amount(&wager.vault).await, 0);
+
+    assert_eq!(
+        fixture
+            .env
+            .process(&[settle.clone(), settle.clone(), settle], &[])
+            .await,
+        custom(0, CoinflipError::AlreadySettled)
+    );
+}
+
+#[tokio::test]
+async fn test_cancel() {
+    let mut fixture = Fixture::new().await;
+    let vault = fixture.wager().await.unwrap().vault;
+    let bob = Player {
+        owner: Keypair::from_bytes(&fixture.bob.owner.to_bytes()).unwrap(),
+        account: fixture.bob.account,
+    };
+    assert_eq!(
+        fixture.cancel(&bob).await,
+        custom(0, CoinflipError::NotMaker)
+    );
+
+    let alice = Player {
+        owner: Keypair::from_bytes(&fixture.alice.owner.to_bytes()).unwrap(),
+        account: fixture.alice.account,
+    };
+    fixture.cancel(&alice).await.unwrap();
+    assert_eq!(fixture.env.amount(&alice.account).await, 1_000);
+    assert_eq!(fixture.wager().await, None);
+    let vault = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(vault)
+        .await
+        .unwrap();
+    assert_eq!(vault, None);
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    let wager = fixture.wager().await.unwrap();
+
+    // Through a program posing as the bank, bob would take the wager without staking.
+    let instruction = accept(
+        &fixture.env.program_id,
+        &fixture.env.fake_bank_program_id,
+        &wager.bank,
+        &fixture.bob.owner.pubkey(),
+        &fixture.wager,
+        &fixture.bob.account,
+        &wager.vault,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture
+            .env
+            .process(&[instruction], &[&fixture.bob.owner])
+            .await,
+        custom(0, CoinflipError::InvalidVault)
+    );
+    assert!(!fixture.wager().await.unwrap().accepted);
+    assert_eq!(fixture.env.amount(&wager.vault).await, STAKE);
+}
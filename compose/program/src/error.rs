@@ -0,0 +1,15 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ComposeError {
+    /// The counter isn't the program's counter address.
+    InvalidCounter,
+    /// The counter isn't owned by the greeting program passed along.
+    CounterNotGreeting,
+}
+
+impl From<ComposeError> for ProgramError {
+    fn from(e: ComposeError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
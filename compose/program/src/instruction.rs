@@ -0,0 +1,149 @@
+use program::greeting_account::GREETING_LEN;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::convert::TryInto;
+
+/// Seed of the transactions-processed counter.
+pub const COUNTER_SEED: &[u8] = b"transactions_processed";
+/// Size of the counter, a `GreetingAccount`.
+pub const COUNTER_LEN: usize = GREETING_LEN;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ComposeInstruction {
+    /// Creates the transactions-processed counter at the program's counter address,
+    /// owned by the greeting program so that greeting it counts.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The payer.
+    ///   1. `[writable]` The counter.
+    ///   2. `[]` The greeting program.
+    ///   3. `[]` The system program.
+    InitializeCounter,
+
+    /// Transfers `amount` through the bank program, then greets the counter through the
+    /// greeting program. Either both happen or neither does.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The source account.
+    ///   1. `[writable]` The destination account.
+    ///   2. `[writable, signer]` The source account's owner or delegate.
+    ///   3. `[writable]` The counter.
+    ///   4. `[]` The accounts' bank.
+    ///   5. `[]` The bank program.
+    ///   6. `[]` The greeting program.
+    Transfer { amount: u64 },
+}
+
+impl ComposeInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => Self::InitializeCounter,
+            1 => {
+                let amount = rest
+                    .get(..8)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u64::from_le_bytes)
+                    .ok_or(InvalidInstructionData)?;
+                Self::Transfer { amount }
+            }
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(9);
+        match *self {
+            Self::InitializeCounter => buf.push(0),
+            Self::Transfer { amount } => {
+                buf.push(1);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+        }
+        buf
+    }
+}
+
+/// Address and bump of the transactions-processed counter.
+pub fn counter_address(compose_program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[COUNTER_SEED], compose_program_id)
+}
+
+pub fn initialize_counter(
+    compose_program_id: &Pubkey,
+    greeting_program_id: &Pubkey,
+    payer: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = ComposeInstruction::InitializeCounter.pack();
+    let (counter, _) = counter_address(compose_program_id);
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(counter, false),
+        AccountMeta::new_readonly(*greeting_program_id, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *compose_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn transfer(
+    compose_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    greeting_program_id: &Pubkey,
+    from_account: &Pubkey,
+    to_account: &Pubkey,
+    from_account_owner: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = ComposeInstruction::Transfer { amount }.pack();
+    let (counter, _) = counter_address(compose_program_id);
+    let accounts = vec![
+        AccountMeta::new(*from_account, false),
+        AccountMeta::new(*to_account, false),
+        AccountMeta::new(*from_account_owner, true),
+        AccountMeta::new(counter, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+        AccountMeta::new_readonly(*greeting_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *compose_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            ComposeInstruction::InitializeCounter,
+            ComposeInstruction::Transfer { amount: 42 },
+        ];
+        for case in cases {
+            assert_eq!(ComposeInstruction::unpack(&case.pack()), Ok(case));
+        }
+        assert_eq!(
+            ComposeInstruction::unpack(&[1, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            ComposeInstruction::unpack(&[2]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
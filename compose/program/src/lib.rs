@@ -0,0 +1,8 @@
+pub mod error;
+pub mod instruction;
+pub mod processor;
+
+#[cfg(not(feature = "no-entrypoint"))]
+mod entrypoint;
+
+pub use solana_program;
@@ -0,0 +1,129 @@
+use crate::{
+    error::ComposeError,
+    instruction::{counter_address, ComposeInstruction, COUNTER_LEN, COUNTER_SEED},
+};
+use solana_bank::instruction as bank_instruction;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = ComposeInstruction::unpack(input)?;
+
+        match instruction {
+            ComposeInstruction::InitializeCounter => {
+                msg!("Instruction: InitializeCounter");
+                Self::process_initialize_counter(program_id, accounts)
+            }
+            ComposeInstruction::Transfer { amount } => {
+                msg!("Instruction: Transfer");
+                Self::process_transfer(program_id, accounts, amount)
+            }
+        }
+    }
+
+    pub fn process_initialize_counter(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let payer_info = next_account_info(account_info_iter)?;
+        let counter_info = next_account_info(account_info_iter)?;
+        let greeting_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !payer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let (counter, bump) = counter_address(program_id);
+        if counter != *counter_info.key {
+            return Err(ComposeError::InvalidCounter.into());
+        }
+        if !counter_info.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        // The counter is this program's address but the greeting program's account: only
+        // the greeting program can write it, so every count goes through a greeting.
+        invoke_signed(
+            &system_instruction::create_account(
+                payer_info.key,
+                counter_info.key,
+                Rent::get()?.minimum_balance(COUNTER_LEN),
+                COUNTER_LEN as u64,
+                greeting_program_info.key,
+            ),
+            &[
+                payer_info.clone(),
+                counter_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[&[COUNTER_SEED, &[bump]]],
+        )?;
+        Ok(())
+    }
+
+    pub fn process_transfer(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let from_info = next_account_info(account_info_iter)?;
+        let to_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let counter_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+        let greeting_program_info = next_account_info(account_info_iter)?;
+
+        let (counter, _) = counter_address(program_id);
+        if counter != *counter_info.key {
+            return Err(ComposeError::InvalidCounter.into());
+        }
+        if counter_info.owner != greeting_program_info.key {
+            return Err(ComposeError::CounterNotGreeting.into());
+        }
+
+        // The authority signed the outer transaction, so its signature carries into
+        // the bank program.
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                from_info.key,
+                to_info.key,
+                authority_info.key,
+                amount,
+            )?,
+            &[
+                from_info.clone(),
+                to_info.clone(),
+                authority_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+        // Greeting instructions carry no data; the greeted account is the only one.
+        invoke(
+            &Instruction {
+                program_id: *greeting_program_info.key,
+                accounts: vec![AccountMeta::new(*counter_info.key, false)],
+                data: vec![],
+            },
+            &[counter_info.clone(), greeting_program_info.clone()],
+        )?;
+        Ok(())
+    }
+}
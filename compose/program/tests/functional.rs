@@ -0,0 +1,162 @@
+use program::greeting_account::{process_greeting_account, GreetingAccount};
+use solana_bank::{error::BankError, test_utils::program_test::Env};
+use solana_compose::{
+    error::ComposeError,
+    instruction::{counter_address, initialize_counter, transfer},
+    processor::Processor,
+};
+use solana_program::{instruction::InstructionError, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// The compose program and the bank, next to the greeting program whose counter it greets.
+struct Fixture {
+    env: Env,
+    greeting_program_id: Pubkey,
+}
+
+impl Fixture {
+    async fn start() -> Fixture {
+        let greeting_program_id = Pubkey::new_unique();
+        let env = Env::start_with(
+            "solana_compose",
+            processor!(Processor::process),
+            |program_test| {
+                program_test.add_program(
+                    "program",
+                    greeting_program_id,
+                    processor!(process_greeting_account),
+                )
+            },
+        )
+        .await;
+        Fixture {
+            env,
+            greeting_program_id,
+        }
+    }
+
+    async fn initialize_counter(&mut self) -> Result<(), TransactionError> {
+        let instruction = initialize_counter(
+            &self.env.program_id,
+            &self.greeting_program_id,
+            &self.env.context.payer.pubkey(),
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[]).await
+    }
+
+    async fn counter(&mut self) -> u32 {
+        let (counter, _) = counter_address(&self.env.program_id);
+        let account = self.env.account(&counter).await.unwrap();
+        assert_eq!(account.owner, self.greeting_program_id);
+        GreetingAccount::read(&account.data).unwrap().counter
+    }
+
+    async fn transfer(
+        &mut self,
+        bank: &Pubkey,
+        from: &Pubkey,
+        to: &Pubkey,
+        owner: &Keypair,
+        amount: u64,
+    ) -> Result<(), TransactionError> {
+        let instruction = transfer(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            bank,
+            &self.greeting_program_id,
+            from,
+            to,
+            &owner.pubkey(),
+            amount,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[owner]).await
+    }
+}
+
+#[tokio::test]
+async fn test_initialize_counter() {
+    let mut fixture = Fixture::start().await;
+    fixture.initialize_counter().await.unwrap();
+    assert_eq!(fixture.counter().await, 0);
+
+    // Initializing again, from another payer so the transaction is not a duplicate.
+    let payer = Keypair::new();
+    fixture.env.fund(&payer.pubkey(), 100_000_000).await;
+    let instruction = initialize_counter(
+        &fixture.env.program_id,
+        &fixture.greeting_program_id,
+        &payer.pubkey(),
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&payer]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::AccountAlreadyInitialized
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_transfer_greets_counter() {
+    let mut fixture = Fixture::start().await;
+    fixture.initialize_counter().await.unwrap();
+    let bank = fixture.env.create_bank().await;
+    let (alice, bob) = (Keypair::new(), Keypair::new());
+    let alice_account = fixture.env.create_bank_account(&bank, &alice, 1_000).await;
+    let bob_account = fixture.env.create_bank_account(&bank, &bob, 0).await;
+
+    fixture
+        .transfer(&bank, &alice_account, &bob_account, &alice, 300)
+        .await
+        .unwrap();
+    fixture
+        .transfer(&bank, &alice_account, &bob_account, &alice, 200)
+        .await
+        .unwrap();
+    assert_eq!(fixture.env.amount(&alice_account).await, 500);
+    assert_eq!(fixture.env.amount(&bob_account).await, 500);
+    assert_eq!(fixture.counter().await, 2);
+
+    // A failed transfer rolls the greeting back with it.
+    assert_eq!(
+        fixture
+            .transfer(&bank, &alice_account, &bob_account, &alice, 501)
+            .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::InsufficientFunds as u32)
+        ))
+    );
+    assert_eq!(fixture.counter().await, 2);
+}
+
+#[tokio::test]
+async fn test_transfer_without_counter() {
+    let mut fixture = Fixture::start().await;
+    let bank = fixture.env.create_bank().await;
+    let alice = Keypair::new();
+    let alice_account = fixture.env.create_bank_account(&bank, &alice, 1_000).await;
+    let bob_account = fixture
+        .env
+        .create_bank_account(&bank, &Keypair::new(), 0)
+        .await;
+
+    // The counter was never created, so the system program still owns its address.
+    assert_eq!(
+        fixture
+            .transfer(&bank, &alice_account, &bob_account, &alice, 1)
+            .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(ComposeError::CounterNotGreeting as u32)
+        ))
+    );
+    assert_eq!(fixture.env.amount(&alice_account).await, 1_000);
+}
@@ -0,0 +1,35 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CrowdfundError {
+    /// The vault authority isn't the campaign's program address.
+    InvalidVaultAuthority,
+    /// The account isn't the campaign's vault.
+    InvalidVault,
+    /// The receipt isn't the contributor's program address for the campaign.
+    InvalidReceipt,
+    /// A campaign needs a goal above zero.
+    InvalidGoal,
+    /// The deadline has already passed.
+    InvalidDeadline,
+    /// Contributions can't be empty.
+    InvalidAmount,
+    /// The deadline has passed; no more contributions are taken.
+    CampaignEnded,
+    /// The deadline hasn't passed yet.
+    CampaignInProgress,
+    /// The campaign raised less than its goal.
+    GoalNotReached,
+    /// The campaign met its goal, so contributions stay with the creator.
+    GoalReached,
+    /// The creator has already withdrawn the funds.
+    AlreadyWithdrawn,
+    /// The signer didn't create the campaign.
+    NotCreator,
+}
+
+impl From<CrowdfundError> for ProgramError {
+    fn from(e: CrowdfundError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,288 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the vault authority, followed by the campaign's address.
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"crowdfund";
+/// Seed prefix of a receipt, followed by the campaign's and the contributor's addresses.
+pub const RECEIPT_SEED: &[u8] = b"receipt";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CrowdfundInstruction {
+    /// Opens a campaign raising `goal` until `deadline`, and initializes its vault, owned
+    /// by the vault authority.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The creator, who withdraws the funds if the goal is met.
+    ///   1. `[writable]` The campaign, an uninitialized account owned by this program.
+    ///   2. `[writable]` The vault, an uninitialized account owned by the bank program.
+    ///   3. `[writable]` The bank contributions are made in.
+    ///   4. `[writable]` The vault authority.
+    ///   5. `[]` The bank program, owner of the vault.
+    CreateCampaign { goal: u64, deadline: i64 },
+
+    /// Moves `amount` into the vault and adds it to the contributor's receipt, creating
+    /// the receipt on their first contribution.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The contributor, owner of the source account, paying
+    ///      for the receipt.
+    ///   1. `[writable]` The campaign.
+    ///   2. `[writable]` The contributor's receipt.
+    ///   3. `[writable]` The source account.
+    ///   4. `[writable]` The vault.
+    ///   5. `[]` The campaign's bank.
+    ///   6. `[]` The bank program, owner of the vault.
+    ///   7. `[]` The system program.
+    Contribute { amount: u64 },
+
+    /// Pays the whole vault to the creator, once the deadline has passed with the goal
+    /// met.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The creator.
+    ///   1. `[writable]` The campaign.
+    ///   2. `[writable]` The vault.
+    ///   3. `[writable]` The destination account.
+    ///   4. `[writable]` The vault authority.
+    ///   5. `[]` The campaign's bank.
+    ///   6. `[]` The bank program, owner of the vault.
+    Withdraw,
+
+    /// Pays a contributor back what their receipt says, once the deadline has passed
+    /// short of the goal, and closes the receipt.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The contributor, credited the receipt's rent.
+    ///   1. `[]` The campaign.
+    ///   2. `[writable]` The contributor's receipt.
+    ///   3. `[writable]` The vault.
+    ///   4. `[writable]` The destination account.
+    ///   5. `[writable]` The vault authority.
+    ///   6. `[]` The campaign's bank.
+    ///   7. `[]` The bank program, owner of the vault.
+    Reclaim,
+}
+
+impl CrowdfundInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (goal, rest) = Self::unpack_u64(rest)?;
+                let (deadline, _rest) = Self::unpack_u64(rest)?;
+                Self::CreateCampaign {
+                    goal,
+                    deadline: deadline as i64,
+                }
+            }
+            1 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Contribute { amount }
+            }
+            2 => Self::Withdraw,
+            3 => Self::Reclaim,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(17);
+        match *self {
+            Self::CreateCampaign { goal, deadline } => {
+                buf.push(0);
+                buf.extend_from_slice(&goal.to_le_bytes());
+                buf.extend_from_slice(&deadline.to_le_bytes());
+            }
+            Self::Contribute { amount } => {
+                buf.push(1);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Withdraw => buf.push(2),
+            Self::Reclaim => buf.push(3),
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of the authority owning `campaign`'s vault.
+pub fn vault_authority(crowdfund_program_id: &Pubkey, campaign: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VAULT_AUTHORITY_SEED, campaign.as_ref()],
+        crowdfund_program_id,
+    )
+}
+
+/// Address and bump of `contributor`'s receipt for `campaign`.
+pub fn receipt_address(
+    crowdfund_program_id: &Pubkey,
+    campaign: &Pubkey,
+    contributor: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[RECEIPT_SEED, campaign.as_ref(), contributor.as_ref()],
+        crowdfund_program_id,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_campaign(
+    crowdfund_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    creator: &Pubkey,
+    campaign: &Pubkey,
+    vault: &Pubkey,
+    bank: &Pubkey,
+    goal: u64,
+    deadline: i64,
+) -> Result<Instruction, ProgramError> {
+    let data = CrowdfundInstruction::CreateCampaign { goal, deadline }.pack();
+    let (authority, _) = vault_authority(crowdfund_program_id, campaign);
+    let accounts = vec![
+        AccountMeta::new_readonly(*creator, true),
+        AccountMeta::new(*campaign, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *crowdfund_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn contribute(
+    crowdfund_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    contributor: &Pubkey,
+    campaign: &Pubkey,
+    source_account: &Pubkey,
+    vault: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = CrowdfundInstruction::Contribute { amount }.pack();
+    let (receipt, _) = receipt_address(crowdfund_program_id, campaign, contributor);
+    let accounts = vec![
+        AccountMeta::new(*contributor, true),
+        AccountMeta::new(*campaign, false),
+        AccountMeta::new(receipt, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *crowdfund_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn withdraw(
+    crowdfund_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    creator: &Pubkey,
+    campaign: &Pubkey,
+    vault: &Pubkey,
+    destination_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = CrowdfundInstruction::Withdraw.pack();
+    let (authority, _) = vault_authority(crowdfund_program_id, campaign);
+    let accounts = vec![
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(*campaign, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *crowdfund_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn reclaim(
+    crowdfund_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    contributor: &Pubkey,
+    campaign: &Pubkey,
+    vault: &Pubkey,
+    destination_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = CrowdfundInstruction::Reclaim.pack();
+    let (receipt, _) = receipt_address(crowdfund_program_id, campaign, contributor);
+    let (authority, _) = vault_authority(crowdfund_program_id, campaign);
+    let accounts = vec![
+        AccountMeta::new(*contributor, true),
+        AccountMeta::new_readonly(*campaign, false),
+        AccountMeta::new(receipt, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *crowdfund_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            CrowdfundInstruction::CreateCampaign {
+                goal: 1,
+                deadline: -2,
+            },
+            CrowdfundInstruction::Contribute { amount: u64::MAX },
+            CrowdfundInstruction::Withdraw,
+            CrowdfundInstruction::Reclaim,
+        ];
+        for instruction in cases {
+            assert_eq!(
+                CrowdfundInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            CrowdfundInstruction::unpack(&[1, 0, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            CrowdfundInstruction::unpack(&[4]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,389 @@
+use crate::{
+    error::CrowdfundError,
+    instruction::{CrowdfundInstruction, RECEIPT_SEED, VAULT_AUTHORITY_SEED},
+    state::{Campaign, Receipt},
+};
+use solana_bank::{instruction as bank_instruction, state::Account as BankAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = CrowdfundInstruction::unpack(input)?;
+
+        match instruction {
+            CrowdfundInstruction::CreateCampaign { goal, deadline } => {
+                msg!("Instruction: CreateCampaign");
+                Self::process_create_campaign(program_id, accounts, goal, deadline)
+            }
+            CrowdfundInstruction::Contribute { amount } => {
+                msg!("Instruction: Contribute");
+                Self::process_contribute(program_id, accounts, amount)
+            }
+            CrowdfundInstruction::Withdraw => {
+                msg!("Instruction: Withdraw");
+                Self::process_withdraw(program_id, accounts)
+            }
+            CrowdfundInstruction::Reclaim => {
+                msg!("Instruction: Reclaim");
+                Self::process_reclaim(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_create_campaign(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        goal: u64,
+        deadline: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let creator_info = next_account_info(account_info_iter)?;
+        let campaign_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !creator_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if campaign_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut campaign = Campaign::unpack_unchecked(&campaign_info.data.borrow())?;
+        if campaign.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if goal == 0 {
+            return Err(CrowdfundError::InvalidGoal.into());
+        }
+        if deadline <= Clock::get()?.unix_timestamp {
+            return Err(CrowdfundError::InvalidDeadline.into());
+        }
+        let (authority, bump) = Pubkey::find_program_address(
+            &[VAULT_AUTHORITY_SEED, campaign_info.key.as_ref()],
+            program_id,
+        );
+        if authority != *authority_info.key {
+            return Err(CrowdfundError::InvalidVaultAuthority.into());
+        }
+        Self::check_bank_program(vault_info, bank_program_info)?;
+
+        invoke_signed(
+            &bank_instruction::initialize_account(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                authority_info.key,
+            )?,
+            &[
+                bank_info.clone(),
+                vault_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[VAULT_AUTHORITY_SEED, campaign_info.key.as_ref(), &[bump]]],
+        )?;
+
+        campaign.is_initialized = true;
+        campaign.creator = *creator_info.key;
+        campaign.bank = *bank_info.key;
+        campaign.vault = *vault_info.key;
+        campaign.goal = goal;
+        campaign.deadline = deadline;
+        campaign.bump = bump;
+        Campaign::pack(campaign, &mut campaign_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_contribute(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let contributor_info = next_account_info(account_info_iter)?;
+        let campaign_info = next_account_info(account_info_iter)?;
+        let receipt_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !contributor_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut campaign = Self::load_campaign(program_id, campaign_info)?;
+        if campaign.vault != *vault_info.key {
+            return Err(CrowdfundError::InvalidVault.into());
+        }
+        Self::check_bank_program(vault_info, bank_program_info)?;
+        if campaign.has_ended(Clock::get()?.unix_timestamp) {
+            return Err(CrowdfundError::CampaignEnded.into());
+        }
+        if amount == 0 {
+            return Err(CrowdfundError::InvalidAmount.into());
+        }
+        let receipt_bump = Self::check_receipt(
+            program_id,
+            campaign_info.key,
+            contributor_info.key,
+            receipt_info,
+        )?;
+        if receipt_info.data_is_empty() {
+            invoke_signed(
+                &system_instruction::create_account(
+                    contributor_info.key,
+                    receipt_info.key,
+                    Rent::get()?.minimum_balance(Receipt::LEN),
+                    Receipt::LEN as u64,
+                    program_id,
+                ),
+                &[
+                    contributor_info.clone(),
+                    receipt_info.clone(),
+                    system_program_info.clone(),
+                ],
+                &[&[
+                    RECEIPT_SEED,
+                    campaign_info.key.as_ref(),
+                    contributor_info.key.as_ref(),
+                    &[receipt_bump],
+                ]],
+            )?;
+        }
+
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                vault_info.key,
+                contributor_info.key,
+                amount,
+            )?,
+            &[
+                source_info.clone(),
+                vault_info.clone(),
+                contributor_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        let mut receipt = Receipt::unpack_unchecked(&receipt_info.data.borrow())?;
+        receipt.is_initialized = true;
+        receipt.amount = receipt
+            .amount
+            .checked_add(amount)
+            .ok_or(CrowdfundError::InvalidAmount)?;
+        Receipt::pack(receipt, &mut receipt_info.data.borrow_mut())?;
+        campaign.raised = campaign
+            .raised
+            .checked_add(amount)
+            .ok_or(CrowdfundError::InvalidAmount)?;
+        Campaign::pack(campaign, &mut campaign_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_withdraw(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let creator_info = next_account_info(account_info_iter)?;
+        let campaign_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !creator_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut campaign = Self::load_campaign(program_id, campaign_info)?;
+        if campaign.creator != *creator_info.key {
+            return Err(CrowdfundError::NotCreator.into());
+        }
+        if campaign.vault != *vault_info.key {
+            return Err(CrowdfundError::InvalidVault.into());
+        }
+        if !campaign.has_ended(Clock::get()?.unix_timestamp) {
+            return Err(CrowdfundError::CampaignInProgress.into());
+        }
+        if !campaign.goal_reached() {
+            return Err(CrowdfundError::GoalNotReached.into());
+        }
+        if campaign.withdrawn {
+            return Err(CrowdfundError::AlreadyWithdrawn.into());
+        }
+
+        let amount = BankAccount::unpack(&vault_info.data.borrow())?.amount;
+        Self::pay_out(
+            program_id,
+            campaign_info,
+            &campaign,
+            vault_info,
+            destination_info,
+            authority_info,
+            bank_info,
+            bank_program_info,
+            amount,
+        )?;
+        campaign.withdrawn = true;
+        Campaign::pack(campaign, &mut campaign_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_reclaim(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let contributor_info = next_account_info(account_info_iter)?;
+        let campaign_info = next_account_info(account_info_iter)?;
+        let receipt_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !contributor_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let campaign = Self::load_campaign(program_id, campaign_info)?;
+        if campaign.vault != *vault_info.key {
+            return Err(CrowdfundError::InvalidVault.into());
+        }
+        if !campaign.has_ended(Clock::get()?.unix_timestamp) {
+            return Err(CrowdfundError::CampaignInProgress.into());
+        }
+        if campaign.goal_reached() {
+            return Err(CrowdfundError::GoalReached.into());
+        }
+        Self::check_receipt(
+            program_id,
+            campaign_info.key,
+            contributor_info.key,
+            receipt_info,
+        )?;
+        if receipt_info.owner != program_id {
+            return Err(CrowdfundError::InvalidReceipt.into());
+        }
+        let receipt = Receipt::unpack(&receipt_info.data.borrow())?;
+
+        Self::pay_out(
+            program_id,
+            campaign_info,
+            &campaign,
+            vault_info,
+            destination_info,
+            authority_info,
+            bank_info,
+            bank_program_info,
+            receipt.amount,
+        )?;
+
+        // Closing the receipt is what keeps a contributor from reclaiming twice.
+        let lamports = receipt_info.lamports();
+        **receipt_info.lamports.borrow_mut() = 0;
+        **contributor_info.lamports.borrow_mut() = contributor_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(ProgramError::InvalidArgument)?;
+        receipt_info.data.borrow_mut().fill(0);
+        Ok(())
+    }
+
+    fn load_campaign(
+        program_id: &Pubkey,
+        campaign_info: &AccountInfo,
+    ) -> Result<Campaign, ProgramError> {
+        if campaign_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Campaign::unpack(&campaign_info.data.borrow())
+    }
+
+    /// Fails unless `bank_program_info` is the program owning the vault, the bank it was
+    /// opened in. Any other program would be handed the signatures of the contributor or
+    /// the vault authority, and could count a contribution it never moved.
+    fn check_bank_program(
+        vault_info: &AccountInfo,
+        bank_program_info: &AccountInfo,
+    ) -> ProgramResult {
+        if vault_info.owner != bank_program_info.key {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        Ok(())
+    }
+
+    /// Checks `receipt_info` is `contributor`'s receipt for `campaign`, and returns the
+    /// bump seed of its address.
+    fn check_receipt(
+        program_id: &Pubkey,
+        campaign: &Pubkey,
+        contributor: &Pubkey,
+        receipt_info: &AccountInfo,
+    ) -> Result<u8, ProgramError> {
+        let (receipt, bump) = Pubkey::find_program_address(
+            &[RECEIPT_SEED, campaign.as_ref(), contributor.as_ref()],
+            program_id,
+        );
+        if receipt != *receipt_info.key {
+            return Err(CrowdfundError::InvalidReceipt.into());
+        }
+        Ok(bump)
+    }
+
+    /// Transfers `amount` out of the vault, signed by the vault authority.
+    #[allow(clippy::too_many_arguments)]
+    fn pay_out<'a>(
+        program_id: &Pubkey,
+        campaign_info: &AccountInfo<'a>,
+        campaign: &Campaign,
+        vault_info: &AccountInfo<'a>,
+        destination_info: &AccountInfo<'a>,
+        authority_info: &AccountInfo<'a>,
+        bank_info: &AccountInfo<'a>,
+        bank_program_info: &AccountInfo<'a>,
+        amount: u64,
+    ) -> ProgramResult {
+        let bump = [campaign.bump];
+        let signer_seeds: &[&[u8]] = &[VAULT_AUTHORITY_SEED, campaign_info.key.as_ref(), &bump];
+        let authority = Pubkey::create_program_address(signer_seeds, program_id)
+            .map_err(|_| ProgramError::from(CrowdfundError::InvalidVaultAuthority))?;
+        if authority != *authority_info.key {
+            return Err(CrowdfundError::InvalidVaultAuthority.into());
+        }
+        Self::check_bank_program(vault_info, bank_program_info)?;
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                destination_info.key,
+                authority_info.key,
+                amount,
+            )?,
+            &[
+                vault_info.clone(),
+                destination_info.clone(),
+                authority_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )
+    }
+}
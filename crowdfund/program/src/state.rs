@@ -0,0 +1,175 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// A campaign raising `goal` of `bank`'s tokens into `vault`, a bank account owned by the
+/// vault authority, until `deadline`. Past the deadline the creator withdraws everything
+/// if the goal was met, and otherwise each contributor reclaims what their receipt says.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Campaign {
+    pub is_initialized: bool,
+    pub creator: Pubkey,
+    pub bank: Pubkey,
+    pub vault: Pubkey,
+    pub goal: u64,
+    /// Unix timestamp contributions close at.
+    pub deadline: i64,
+    /// Total contributed, including what has since been reclaimed.
+    pub raised: u64,
+    pub withdrawn: bool,
+    /// Bump seed of the vault authority address.
+    pub bump: u8,
+}
+
+impl Campaign {
+    /// Whether the campaign is over at `now`, successful or not.
+    pub fn has_ended(&self, now: i64) -> bool {
+        now >= self.deadline
+    }
+
+    pub fn goal_reached(&self) -> bool {
+        self.raised >= self.goal
+    }
+}
+
+impl Sealed for Campaign {}
+impl IsInitialized for Campaign {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Campaign {
+    const LEN: usize = 123;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 123];
+        let (is_initialized, creator, bank, vault, goal, deadline, raised, withdrawn, bump) =
+            array_refs![src, 1, 32, 32, 32, 8, 8, 8, 1, 1];
+        Ok(Campaign {
+            is_initialized: unpack_bool(is_initialized)?,
+            creator: Pubkey::new_from_array(*creator),
+            bank: Pubkey::new_from_array(*bank),
+            vault: Pubkey::new_from_array(*vault),
+            goal: u64::from_le_bytes(*goal),
+            deadline: i64::from_le_bytes(*deadline),
+            raised: u64::from_le_bytes(*raised),
+            withdrawn: unpack_bool(withdrawn)?,
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 123];
+        let (is_initialized, creator, bank, vault, goal, deadline, raised, withdrawn, bump) =
+            mut_array_refs![dst, 1, 32, 32, 32, 8, 8, 8, 1, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        creator.copy_from_slice(self.creator.as_ref());
+        bank.copy_from_slice(self.bank.as_ref());
+        vault.copy_from_slice(self.vault.as_ref());
+        *goal = self.goal.to_le_bytes();
+        *deadline = self.deadline.to_le_bytes();
+        *raised = self.raised.to_le_bytes();
+        withdrawn[0] = self.withdrawn as u8;
+        bump[0] = self.bump;
+    }
+}
+
+/// What one contributor has put into a campaign, at a program address derived from both.
+/// Reclaiming closes it.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Receipt {
+    pub is_initialized: bool,
+    pub amount: u64,
+}
+
+impl Sealed for Receipt {}
+impl IsInitialized for Receipt {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Receipt {
+    const LEN: usize = 9;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 9];
+        let (is_initialized, amount) = array_refs![src, 1, 8];
+        Ok(Receipt {
+            is_initialized: unpack_bool(is_initialized)?,
+            amount: u64::from_le_bytes(*amount),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 9];
+        let (is_initialized, amount) = mut_array_refs![dst, 1, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        *amount = self.amount.to_le_bytes();
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let campaign = Campaign {
+            is_initialized: true,
+            creator: Pubkey::new_unique(),
+            bank: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            goal: 1_000,
+            deadline: -5,
+            raised: 999,
+            withdrawn: true,
+            bump: 251,
+        };
+        let mut packed = vec![0u8; Campaign::LEN];
+        Campaign::pack(campaign, &mut packed).unwrap();
+        assert_eq!(Campaign::unpack(&packed), Ok(campaign));
+
+        let receipt = Receipt {
+            is_initialized: true,
+            amount: u64::MAX,
+        };
+        let mut packed = vec![0u8; Receipt::LEN];
+        Receipt::pack(receipt, &mut packed).unwrap();
+        assert_eq!(Receipt::unpack(&packed), Ok(receipt));
+
+        packed[0] = 2;
+        assert_eq!(
+            Receipt::unpack(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_outcome() {
+        let campaign = Campaign {
+            goal: 100,
+            deadline: 50,
+            raised: 99,
+            ..Campaign::default()
+        };
+        assert!(!campaign.has_ended(49));
+        assert!(campaign.has_ended(50));
+        assert!(!campaign.goal_reached());
+        assert!(Campaign {
+            raised: 100,
+            ..campaign
+        }
+        .goal_reached());
+    }
+}
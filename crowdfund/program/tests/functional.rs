@@ -0,0 +1,419 @@
+use solana_bank::{state::Account, test_utils::program_test::Env};
+use solana_crowdfund::{
+    error::CrowdfundError,
+    instruction::{
+        contribute, create_campaign, receipt_address, reclaim, vault_authority, withdraw,
+    },
+    processor::Processor,
+    state::{Campaign, Receipt},
+};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// Time the campaign opens at, the time it ends at, and the amount it raises for.
+const OPEN: i64 = 1_000;
+const DEADLINE: i64 = 2_000;
+const GOAL: u64 = 500;
+
+/// Someone holding 1_000 tokens of the campaign's bank, and lamports for a receipt.
+struct Contributor {
+    owner: Keypair,
+    account: Pubkey,
+}
+
+struct Fixture {
+    env: Env,
+    bank: Pubkey,
+    creator: Keypair,
+    creator_account: Pubkey,
+    campaign: Pubkey,
+    vault: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_crowdfund", processor!(Processor::process)).await;
+        env.set_time(OPEN);
+        let bank = env.create_bank().await;
+        let creator = Keypair::new();
+        let creator_account = env.create_bank_account(&bank, &creator, 0).await;
+        let campaign = Keypair::new();
+        let vault = Keypair::new();
+        let crowdfund_program_id = env.program_id;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&campaign, Campaign::LEN, &crowdfund_program_id)
+            .await;
+        env.create_account(&vault, Account::LEN, &bank_program_id)
+            .await;
+        let instruction = create_campaign(
+            &crowdfund_program_id,
+            &bank_program_id,
+            &creator.pubkey(),
+            &campaign.pubkey(),
+            &vault.pubkey(),
+            &bank,
+            GOAL,
+            DEADLINE,
+        )
+        .unwrap();
+        env.process(&[instruction], &[&creator]).await.unwrap();
+        Fixture {
+            env,
+            bank,
+            creator,
+            creator_account,
+            campaign: campaign.pubkey(),
+            vault: vault.pubkey(),
+        }
+    }
+
+    async fn contributor(&mut self) -> Contributor {
+        let owner = Keypair::new();
+        let bank = self.bank;
+        let account = self.env.create_bank_account(&bank, &owner, 1_000).await;
+        self.env.fund(&owner.pubkey(), 10_000_000).await;
+        Contributor { owner, account }
+    }
+
+    async fn contribute(
+        &mut self,
+        contributor: &Contributor,
+        amount: u64,
+    ) -> Result<(), TransactionError> {
+        let instruction = contribute(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &contributor.owner.pubkey(),
+            &self.campaign,
+            &contributor.account,
+            &self.vault,
+            amount,
+        )
+        .unwrap();
+        self.env
+            .process(&[instruction], &[&contributor.owner])
+            .await
+    }
+
+    async fn withdraw(&mut self, destination: &Pubkey) -> Result<(), TransactionError> {
+        let instruction = withdraw(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &self.creator.pubkey(),
+            &self.campaign,
+            &self.vault,
+            destination,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[&self.creator]).await
+    }
+
+    async fn reclaim(
+        &mut self,
+        contributor: &Contributor,
+        destination: &Pubkey,
+    ) -> Result<(), TransactionError> {
+        let instruction = reclaim(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &contributor.owner.pubkey(),
+            &self.campaign,
+            &self.vault,
+            destination,
+        )
+        .unwrap();
+        self.env
+            .process(&[instruction], &[&contributor.owner])
+            .await
+    }
+
+    async fn campaign(&mut self) -> Campaign {
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(self.campaign)
+            .await
+            .unwrap()
+            .unwrap();
+        Campaign::unpack(&account.data).unwrap()
+    }
+
+    /// The contributor's receipt, None once it has been closed.
+    async fn receipt(&mut self, contributor: &Contributor) -> Option<Receipt> {
+        let (receipt, _) = receipt_address(
+            &self.env.program_id,
+            &self.campaign,
+            &contributor.owner.pubkey(),
+        );
+        self.env
+            .context
+            .banks_client
+            .get_account(receipt)
+            .await
+            .unwrap()
+            .map(|account| Receipt::unpack(&account.data).unwrap())
+    }
+}
+
+fn custom(error: CrowdfundError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_campaign() {
+    let mut fixture = Fixture::new().await;
+    let (authority, _) = vault_authority(&fixture.env.program_id, &fixture.campaign);
+    let campaign = fixture.campaign().await;
+    assert_eq!(
+        (
+            campaign.creator,
+            campaign.bank,
+            campaign.goal,
+            campaign.deadline
+        ),
+        (fixture.creator.pubkey(), fixture.bank, GOAL, DEADLINE)
+    );
+    let vault = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(fixture.vault)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Account::unpack(&vault.data).unwrap().owner, authority);
+
+    let crowdfund_program_id = fixture.env.program_id;
+    let bank_program_id = fixture.env.bank_program_id;
+    let bank = fixture.bank;
+    let (campaign, vault) = (Keypair::new(), Keypair::new());
+    fixture
+        .env
+        .create_account(&campaign, Campaign::LEN, &crowdfund_program_id)
+        .await;
+    fixture
+        .env
+        .create_account(&vault, Account::LEN, &bank_program_id)
+        .await;
+    let creator = &fixture.creator;
+    let create = |goal, deadline| {
+        create_campaign(
+            &crowdfund_program_id,
+            &bank_program_id,
+            &creator.pubkey(),
+            &campaign.pubkey(),
+            &vault.pubkey(),
+            &bank,
+            goal,
+            deadline,
+        )
+        .unwrap()
+    };
+    let (no_goal, past) = (create(0, DEADLINE), create(GOAL, OPEN));
+    assert_eq!(
+        fixture.env.process(&[no_goal], &[creator]).await,
+        custom(CrowdfundError::InvalidGoal)
+    );
+    assert_eq!(
+        fixture.env.process(&[past], &[creator]).await,
+        custom(CrowdfundError::InvalidDeadline)
+    );
+}
+
+#[tokio::test]
+async fn test_contribute() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.contributor().await;
+    let bob = fixture.contributor().await;
+
+    fixture.contribute(&alice, 100).await.unwrap();
+    fixture.contribute(&bob, 50).await.unwrap();
+    fixture.contribute(&alice, 20).await.unwrap();
+    assert_eq!(fixture.env.amount(&alice.account).await, 880);
+    let vault = fixture.vault;
+    assert_eq!(fixture.env.amount(&vault).await, 170);
+    assert_eq!(fixture.campaign().await.raised, 170);
+    assert_eq!(fixture.receipt(&alice).await.unwrap().amount, 120);
+    assert_eq!(fixture.receipt(&bob).await.unwrap().amount, 50);
+
+    assert_eq!(
+        fixture.contribute(&bob, 0).await,
+        custom(CrowdfundError::InvalidAmount)
+    );
+    let creator_account = fixture.creator_account;
+    // Nothing comes out before the deadline, whichever way the campaign goes.
+    assert_eq!(
+        fixture.reclaim(&alice, &alice.account).await,
+        custom(CrowdfundError::CampaignInProgress)
+    );
+    assert_eq!(
+        fixture.withdraw(&creator_account).await,
+        custom(CrowdfundError::CampaignInProgress)
+    );
+
+    fixture.env.set_time(DEADLINE);
+    assert_eq!(
+        fixture.contribute(&bob, 1).await,
+        custom(CrowdfundError::CampaignEnded)
+    );
+}
+
+#[tokio::test]
+async fn test_goal_reached() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.contributor().await;
+    let bob = fixture.contributor().await;
+    fixture.contribute(&alice, 300).await.unwrap();
+    fixture.contribute(&bob, 250).await.unwrap();
+
+    fixture.env.set_time(DEADLINE);
+    assert_eq!(
+        fixture.reclaim(&alice, &alice.account).await,
+        custom(CrowdfundError::GoalReached)
+    );
+    // Only the creator can take the funds.
+    let instruction = withdraw(
+        &fixture.env.program_id,
+        &fixture.env.bank_program_id,
+        &fixture.bank,
+        &bob.owner.pubkey(),
+        &fixture.campaign,
+        &fixture.vault,
+        &bob.account,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&bob.owner]).await,
+        custom(CrowdfundError::NotCreator)
+    );
+
+    let creator_account = fixture.creator_account;
+    fixture.withdraw(&creator_account).await.unwrap();
+    assert_eq!(fixture.env.amount(&creator_account).await, 550);
+    let vault = fixture.vault;
+    assert_eq!(fixture.env.amount(&vault).await, 0);
+    assert!(fixture.campaign().await.withdrawn);
+
+    fixture.env.set_time(DEADLINE + 1);
+    assert_eq!(
+        fixture.withdraw(&alice.account).await,
+        custom(CrowdfundError::AlreadyWithdrawn)
+    );
+}
+
+#[tokio::test]
+async fn test_goal_not_reached() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.contributor().await;
+    let bob = fixture.contributor().await;
+    let carol = fixture.contributor().await;
+    fixture.contribute(&alice, 300).await.unwrap();
+    fixture.contribute(&bob, 150).await.unwrap();
+
+    let creator_account = fixture.creator_account;
+    fixture.env.set_time(DEADLINE);
+    assert_eq!(
+        fixture.withdraw(&creator_account).await,
+        custom(CrowdfundError::GoalNotReached)
+    );
+
+    let lamports = |account: Option<solana_sdk::account::Account>| account.unwrap().lamports;
+    let before = lamports(
+        fixture
+            .env
+            .context
+            .banks_client
+            .get_account(alice.owner.pubkey())
+            .await
+            .unwrap(),
+    );
+    fixture.reclaim(&alice, &alice.account).await.unwrap();
+    assert_eq!(fixture.env.amount(&alice.account).await, 1_000);
+    assert_eq!(fixture.receipt(&alice).await, None);
+    // The receipt's rent goes back to its contributor.
+    let after = lamports(
+        fixture
+            .env
+            .context
+            .banks_client
+            .get_account(alice.owner.pubkey())
+            .await
+            .unwrap(),
+    );
+    assert!(after > before);
+
+    fixture.env.set_time(DEADLINE + 1);
+    assert_eq!(
+        fixture.reclaim(&alice, &bob.account).await,
+        custom(CrowdfundError::InvalidReceipt)
+    );
+    assert_eq!(
+        fixture.reclaim(&carol, &carol.account).await,
+        custom(CrowdfundError::InvalidReceipt)
+    );
+    fixture.reclaim(&bob, &bob.account).await.unwrap();
+    assert_eq!(fixture.env.amount(&bob.account).await, 1_000);
+    let vault = fixture.vault;
+    assert_eq!(fixture.env.amount(&vault).await, 0);
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.contributor().await;
+    let crowdfund_program_id = fixture.env.program_id;
+    let fake_bank_program_id = fixture.env.fake_bank_program_id;
+    let wrong_program = Err(TransactionError::InstructionError(
+        0,
+        InstructionError::IncorrectProgramId,
+    ));
+
+    // Through a program posing as the bank, a contribution would count without any tokens
+    // moving into the vault.
+    let instruction = contribute(
+        &crowdfund_program_id,
+        &fake_bank_program_id,
+        &fixture.bank,
+        &alice.owner.pubkey(),
+        &fixture.campaign,
+        &alice.account,
+        &fixture.vault,
+        100,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&alice.owner]).await,
+        wrong_program
+    );
+    assert_eq!(fixture.campaign().await.raised, 0);
+
+    // Nor does the vault authority sign for it.
+    fixture.contribute(&alice, 100).await.unwrap();
+    fixture.env.set_time(DEADLINE);
+    let instruction = reclaim(
+        &crowdfund_program_id,
+        &fake_bank_program_id,
+        &fixture.bank,
+        &alice.owner.pubkey(),
+        &fixture.campaign,
+        &fixture.vault,
+        &alice.account,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&alice.owner]).await,
+        wrong_program
+    );
+    assert_eq!(fixture.receipt(&alice).await.unwrap().amount, 100);
+}
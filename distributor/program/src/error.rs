@@ -0,0 +1,25 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DistributorError {
+    /// The vault authority isn't the distributor's program address.
+    InvalidVaultAuthority,
+    /// The account isn't the distributor's vault.
+    InvalidVault,
+    /// The bitmap isn't the distributor's, or doesn't fit its recipients.
+    InvalidBitmap,
+    /// An airdrop needs recipients and tokens to hand out.
+    NothingToDistribute,
+    /// The index is past the last recipient.
+    InvalidIndex,
+    /// The allocation at this index has already been claimed.
+    AlreadyClaimed,
+    /// The proof doesn't lead to the distributor's root.
+    InvalidProof,
+}
+
+impl From<DistributorError> for ProgramError {
+    fn from(e: DistributorError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,279 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the vault authority, followed by the distributor's address.
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"distributor";
+/// Longest proof a claim can carry, enough for 2^32 recipients.
+pub const MAX_PROOF_LEN: usize = 32;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DistributorInstruction {
+    /// Opens an airdrop of `total` tokens to `recipients` allocations under `root`, and
+    /// mints `total` into its vault, owned by the vault authority.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The bank owner.
+    ///   1. `[writable]` The distributor, an uninitialized account owned by this program.
+    ///   2. `[writable]` The claimed bitmap, a zeroed account owned by this program, of
+    ///      `bitmap_len(recipients)` bytes.
+    ///   3. `[writable]` The vault, an uninitialized account owned by the bank program.
+    ///   4. `[writable]` The bank.
+    ///   5. `[writable]` The vault authority.
+    ///   6. `[]` The bank program, owner of the vault.
+    CreateDistributor {
+        root: [u8; 32],
+        recipients: u64,
+        total: u64,
+    },
+
+    /// Pays the allocation of `amount` at `index` to the recipient, who proves it is
+    /// under the root with `proof`.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The recipient.
+    ///   1. `[writable]` The distributor.
+    ///   2. `[writable]` The claimed bitmap.
+    ///   3. `[writable]` The vault.
+    ///   4. `[writable]` The destination account.
+    ///   5. `[writable]` The vault authority.
+    ///   6. `[]` The distributor's bank.
+    ///   7. `[]` The bank program, owner of the vault.
+    Claim {
+        index: u64,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    },
+}
+
+impl DistributorInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (root, rest) = Self::unpack_hash(rest)?;
+                let (recipients, rest) = Self::unpack_u64(rest)?;
+                let (total, _rest) = Self::unpack_u64(rest)?;
+                Self::CreateDistributor {
+                    root,
+                    recipients,
+                    total,
+                }
+            }
+            1 => {
+                let (index, rest) = Self::unpack_u64(rest)?;
+                let (amount, rest) = Self::unpack_u64(rest)?;
+                let (&len, mut rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                if len as usize > MAX_PROOF_LEN {
+                    return Err(InvalidInstructionData);
+                }
+                let mut proof = Vec::with_capacity(len as usize);
+                for _ in 0..len {
+                    let (hash, next) = Self::unpack_hash(rest)?;
+                    proof.push(hash);
+                    rest = next;
+                }
+                Self::Claim {
+                    index,
+                    amount,
+                    proof,
+                }
+            }
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self {
+            Self::CreateDistributor {
+                root,
+                recipients,
+                total,
+            } => {
+                buf.push(0);
+                buf.extend_from_slice(root);
+                buf.extend_from_slice(&recipients.to_le_bytes());
+                buf.extend_from_slice(&total.to_le_bytes());
+            }
+            Self::Claim {
+                index,
+                amount,
+                proof,
+            } => {
+                buf.push(1);
+                buf.extend_from_slice(&index.to_le_bytes());
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.push(proof.len() as u8);
+                for hash in proof {
+                    buf.extend_from_slice(hash);
+                }
+            }
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+
+    fn unpack_hash(input: &[u8]) -> Result<([u8; 32], &[u8]), ProgramError> {
+        let hash = input
+            .get(..32)
+            .and_then(|slice| slice.try_into().ok())
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((hash, &input[32..]))
+    }
+}
+
+/// Address and bump of the authority owning `distributor`'s vault.
+pub fn vault_authority(distributor_program_id: &Pubkey, distributor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VAULT_AUTHORITY_SEED, distributor.as_ref()],
+        distributor_program_id,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_distributor(
+    distributor_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank_owner: &Pubkey,
+    distributor: &Pubkey,
+    bitmap: &Pubkey,
+    vault: &Pubkey,
+    bank: &Pubkey,
+    root: [u8; 32],
+    recipients: u64,
+    total: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = DistributorInstruction::CreateDistributor {
+        root,
+        recipients,
+        total,
+    }
+    .pack();
+    let (authority, _) = vault_authority(distributor_program_id, distributor);
+    let accounts = vec![
+        AccountMeta::new(*bank_owner, true),
+        AccountMeta::new(*distributor, false),
+        AccountMeta::new(*bitmap, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *distributor_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn claim(
+    distributor_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    recipient: &Pubkey,
+    distributor: &Pubkey,
+    bitmap: &Pubkey,
+    vault: &Pubkey,
+    destination_account: &Pubkey,
+    index: u64,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<Instruction, ProgramError> {
+    if proof.len() > MAX_PROOF_LEN {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let data = DistributorInstruction::Claim {
+        index,
+        amount,
+        proof,
+    }
+    .pack();
+    let (authority, _) = vault_authority(distributor_program_id, distributor);
+    let accounts = vec![
+        AccountMeta::new_readonly(*recipient, true),
+        AccountMeta::new(*distributor, false),
+        AccountMeta::new(*bitmap, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *distributor_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            DistributorInstruction::CreateDistributor {
+                root: [3; 32],
+                recipients: 10,
+                total: u64::MAX,
+            },
+            DistributorInstruction::Claim {
+                index: 9,
+                amount: 50,
+                proof: vec![[1; 32], [2; 32]],
+            },
+            DistributorInstruction::Claim {
+                index: 0,
+                amount: 1,
+                proof: vec![],
+            },
+        ];
+        for instruction in cases {
+            assert_eq!(
+                DistributorInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        // A proof shorter than its length says.
+        let mut short = DistributorInstruction::Claim {
+            index: 0,
+            amount: 1,
+            proof: vec![[1; 32]],
+        }
+        .pack();
+        short.pop();
+        assert_eq!(
+            DistributorInstruction::unpack(&short),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        let mut long = vec![1];
+        long.extend_from_slice(&[0; 16]);
+        long.push(MAX_PROOF_LEN as u8 + 1);
+        long.extend_from_slice(&[0; 32 * (MAX_PROOF_LEN + 1)]);
+        assert_eq!(
+            DistributorInstruction::unpack(&long),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            DistributorInstruction::unpack(&[2]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
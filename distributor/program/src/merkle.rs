@@ -0,0 +1,124 @@
+//! Merkle trees of airdrop allocations. Leaves and inner nodes are hashed with different
+//! prefixes, so a proof can't pass an inner node off as a leaf, and each pair is hashed
+//! in sorted order, so a proof is just the list of siblings from the leaf up.
+
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Leaf of `recipient`'s allocation of `amount`, at `index` in the claimed bitmap.
+pub fn leaf(index: u64, recipient: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[
+        LEAF_PREFIX,
+        &index.to_le_bytes(),
+        recipient.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+fn node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, low, high]).to_bytes()
+}
+
+/// Whether `proof` leads from `leaf` up to `root`.
+pub fn verify(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    proof
+        .iter()
+        .fold(leaf, |hash, sibling| node(&hash, sibling))
+        == *root
+}
+
+/// Every level of a tree, from the leaves up to the root. A node left without a sibling
+/// moves up a level unchanged.
+pub struct MerkleTree {
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: Vec<[u8; 32]>) -> Self {
+        let mut levels = vec![leaves];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => node(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        MerkleTree { levels }
+    }
+
+    /// All zeros for a tree without leaves, which no proof verifies against.
+    pub fn root(&self) -> [u8; 32] {
+        self.levels[self.levels.len() - 1]
+            .first()
+            .copied()
+            .unwrap_or([0; 32])
+    }
+
+    /// Siblings of the leaf at `index` from the bottom up. None past the last leaf.
+    pub fn proof(&self, mut index: usize) -> Option<Vec<[u8; 32]>> {
+        if index >= self.levels[0].len() {
+            return None;
+        }
+        let mut proof = Vec::new();
+        for level in self.levels[..self.levels.len() - 1].iter() {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        Some(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proofs() {
+        for count in 1..=9u64 {
+            let recipients: Vec<Pubkey> = (0..count).map(|_| Pubkey::new_unique()).collect();
+            let leaves = recipients
+                .iter()
+                .enumerate()
+                .map(|(index, recipient)| leaf(index as u64, recipient, 100 + index as u64))
+                .collect();
+            let tree = MerkleTree::new(leaves);
+            let root = tree.root();
+            for (index, recipient) in recipients.iter().enumerate() {
+                let proof = tree.proof(index).unwrap();
+                let amount = 100 + index as u64;
+                assert!(verify(&proof, &root, leaf(index as u64, recipient, amount)));
+                assert!(!verify(
+                    &proof,
+                    &root,
+                    leaf(index as u64, recipient, amount + 1)
+                ));
+                assert!(!verify(
+                    &proof,
+                    &root,
+                    leaf(index as u64 + 1, recipient, amount)
+                ));
+            }
+            assert_eq!(tree.proof(count as usize), None);
+        }
+    }
+
+    #[test]
+    fn test_small_trees() {
+        let only = leaf(0, &Pubkey::new_unique(), 1);
+        let tree = MerkleTree::new(vec![only]);
+        assert_eq!(tree.root(), only);
+        assert_eq!(tree.proof(0), Some(vec![]));
+        assert_eq!(MerkleTree::new(vec![]).root(), [0; 32]);
+        assert_eq!(MerkleTree::new(vec![]).proof(0), None);
+    }
+}
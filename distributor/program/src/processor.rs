@@ -0,0 +1,214 @@
+use crate::{
+    error::DistributorError,
+    instruction::{DistributorInstruction, VAULT_AUTHORITY_SEED},
+    merkle,
+    state::{bitmap_len, is_claimed, set_claimed, Distributor, BITMAP_HEADER_LEN},
+};
+use solana_bank::instruction as bank_instruction;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = DistributorInstruction::unpack(input)?;
+
+        match instruction {
+            DistributorInstruction::CreateDistributor {
+                root,
+                recipients,
+                total,
+            } => {
+                msg!("Instruction: CreateDistributor");
+                Self::process_create_distributor(program_id, accounts, root, recipients, total)
+            }
+            DistributorInstruction::Claim {
+                index,
+                amount,
+                proof,
+            } => {
+                msg!("Instruction: Claim");
+                Self::process_claim(program_id, accounts, index, amount, &proof)
+            }
+        }
+    }
+
+    pub fn process_create_distributor(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        root: [u8; 32],
+        recipients: u64,
+        total: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let bank_owner_info = next_account_info(account_info_iter)?;
+        let distributor_info = next_account_info(account_info_iter)?;
+        let bitmap_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if distributor_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut distributor = Distributor::unpack_unchecked(&distributor_info.data.borrow())?;
+        if distributor.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if recipients == 0 || total == 0 {
+            return Err(DistributorError::NothingToDistribute.into());
+        }
+        {
+            let bitmap = bitmap_info.data.borrow();
+            // A bitmap with any byte set already belongs to another distributor.
+            if bitmap_info.owner != program_id
+                || bitmap.len() != bitmap_len(recipients)
+                || bitmap.iter().any(|byte| *byte != 0)
+            {
+                return Err(DistributorError::InvalidBitmap.into());
+            }
+        }
+        let (authority, bump) = Pubkey::find_program_address(
+            &[VAULT_AUTHORITY_SEED, distributor_info.key.as_ref()],
+            program_id,
+        );
+        if authority != *authority_info.key {
+            return Err(DistributorError::InvalidVaultAuthority.into());
+        }
+        if vault_info.owner != bank_program_info.key {
+            return Err(DistributorError::InvalidVault.into());
+        }
+
+        invoke_signed(
+            &bank_instruction::initialize_account(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                authority_info.key,
+            )?,
+            &[
+                bank_info.clone(),
+                vault_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[VAULT_AUTHORITY_SEED, distributor_info.key.as_ref(), &[bump]]],
+        )?;
+        // The bank program checks the signer owns the bank.
+        invoke(
+            &bank_instruction::mint_to(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                bank_owner_info.key,
+                total,
+            )?,
+            &[
+                bank_info.clone(),
+                vault_info.clone(),
+                bank_owner_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        bitmap_info.data.borrow_mut()[..BITMAP_HEADER_LEN]
+            .copy_from_slice(distributor_info.key.as_ref());
+        distributor.is_initialized = true;
+        distributor.bank = *bank_info.key;
+        distributor.vault = *vault_info.key;
+        distributor.bitmap = *bitmap_info.key;
+        distributor.root = root;
+        distributor.recipients = recipients;
+        distributor.total = total;
+        distributor.bump = bump;
+        Distributor::pack(distributor, &mut distributor_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_claim(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        index: u64,
+        amount: u64,
+        proof: &[[u8; 32]],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let recipient_info = next_account_info(account_info_iter)?;
+        let distributor_info = next_account_info(account_info_iter)?;
+        let bitmap_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !recipient_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if distributor_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut distributor = Distributor::unpack(&distributor_info.data.borrow())?;
+        if distributor.bitmap != *bitmap_info.key {
+            return Err(DistributorError::InvalidBitmap.into());
+        }
+        if distributor.vault != *vault_info.key || vault_info.owner != bank_program_info.key {
+            return Err(DistributorError::InvalidVault.into());
+        }
+        if index >= distributor.recipients {
+            return Err(DistributorError::InvalidIndex.into());
+        }
+        if is_claimed(&bitmap_info.data.borrow(), index) {
+            return Err(DistributorError::AlreadyClaimed.into());
+        }
+        let leaf = merkle::leaf(index, recipient_info.key, amount);
+        if !merkle::verify(proof, &distributor.root, leaf) {
+            return Err(DistributorError::InvalidProof.into());
+        }
+
+        let seeds: &[&[u8]] = &[
+            VAULT_AUTHORITY_SEED,
+            distributor_info.key.as_ref(),
+            &[distributor.bump],
+        ];
+        let authority = Pubkey::create_program_address(seeds, program_id)
+            .map_err(|_| ProgramError::from(DistributorError::InvalidVaultAuthority))?;
+        if authority != *authority_info.key {
+            return Err(DistributorError::InvalidVaultAuthority.into());
+        }
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                destination_info.key,
+                authority_info.key,
+                amount,
+            )?,
+            &[
+                vault_info.clone(),
+                destination_info.clone(),
+                authority_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[seeds],
+        )?;
+
+        set_claimed(&mut bitmap_info.data.borrow_mut(), index);
+        distributor.claimed = distributor
+            .claimed
+            .checked_add(amount)
+            .ok_or(ProgramError::InvalidArgument)?;
+        Distributor::pack(distributor, &mut distributor_info.data.borrow_mut())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Bytes ahead of the bits of a claimed bitmap, holding the address of its distributor
+/// so that two airdrops can't share one.
+pub const BITMAP_HEADER_LEN: usize = 32;
+
+/// An airdrop of `bank` tokens out of `vault`, a bank account owned by the vault
+/// authority. Each of `recipients` allocations is a leaf under `root`, and is marked in
+/// the `bitmap` account once claimed.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Distributor {
+    pub is_initialized: bool,
+    pub bank: Pubkey,
+    pub vault: Pubkey,
+    pub bitmap: Pubkey,
+    pub root: [u8; 32],
+    pub recipients: u64,
+    /// Sum of every allocation, minted into the vault up front.
+    pub total: u64,
+    pub claimed: u64,
+    /// Bump seed of the vault authority address.
+    pub bump: u8,
+}
+
+impl Sealed for Distributor {}
+impl IsInitialized for Distributor {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Distributor {
+    const LEN: usize = 154;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 154];
+        let (is_initialized, bank, vault, bitmap, root, recipients, total, claimed, bump) =
+            array_refs![src, 1, 32, 32, 32, 32, 8, 8, 8, 1];
+        Ok(Distributor {
+            is_initialized: unpack_bool(is_initialized)?,
+            bank: Pubkey::new_from_array(*bank),
+            vault: Pubkey::new_from_array(*vault),
+            bitmap: Pubkey::new_from_array(*bitmap),
+            root: *root,
+            recipients: u64::from_le_bytes(*recipients),
+            total: u64::from_le_bytes(*total),
+            claimed: u64::from_le_bytes(*claimed),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 154];
+        let (is_initialized, bank, vault, bitmap, root, recipients, total, claimed, bump) =
+            mut_array_refs![dst, 1, 32, 32, 32, 32, 8, 8, 8, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        bank.copy_from_slice(self.bank.as_ref());
+        vault.copy_from_slice(self.vault.as_ref());
+        bitmap.copy_from_slice(self.bitmap.as_ref());
+        *root = self.root;
+        *recipients = self.recipients.to_le_bytes();
+        *total = self.total.to_le_bytes();
+        *claimed = self.claimed.to_le_bytes();
+        bump[0] = self.bump;
+    }
+}
+
+/// Size of the bitmap account of an airdrop to `recipients`, one bit each.
+pub fn bitmap_len(recipients: u64) -> usize {
+    BITMAP_HEADER_LEN + recipients.div_ceil(8) as usize
+}
+
+/// Whether the bit of `index` is set in the data of a bitmap account.
+pub fn is_claimed(bitmap: &[u8], index: u64) -> bool {
+    bitmap[BITMAP_HEADER_LEN + (index / 8) as usize] & (1 << (index % 8)) != 0
+}
+
+pub fn set_claimed(bitmap: &mut [u8], index: u64) {
+    bitmap[BITMAP_HEADER_LEN + (index / 8) as usize] |= 1 << (index % 8);
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let distributor = Distributor {
+            is_initialized: true,
+            bank: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            bitmap: Pubkey::new_unique(),
+            root: [7; 32],
+            recipients: 3,
+            total: 600,
+            claimed: 100,
+            bump: 254,
+        };
+        let mut packed = vec![0u8; Distributor::LEN];
+        Distributor::pack(distributor, &mut packed).unwrap();
+        assert_eq!(Distributor::unpack(&packed), Ok(distributor));
+
+        packed[0] = 2;
+        assert_eq!(
+            Distributor::unpack_unchecked(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_bitmap() {
+        assert_eq!(bitmap_len(1), BITMAP_HEADER_LEN + 1);
+        assert_eq!(bitmap_len(8), BITMAP_HEADER_LEN + 1);
+        assert_eq!(bitmap_len(9), BITMAP_HEADER_LEN + 2);
+
+        let mut bitmap = vec![0u8; bitmap_len(20)];
+        set_claimed(&mut bitmap, 0);
+        set_claimed(&mut bitmap, 9);
+        set_claimed(&mut bitmap, 19);
+        let claimed: Vec<u64> = (0..20)
+            .filter(|index| is_claimed(&bitmap, *index))
+            .collect();
+        assert_eq!(claimed, vec![0, 9, 19]);
+        assert!(bitmap[..BITMAP_HEADER_LEN].iter().all(|byte| *byte == 0));
+    }
+}
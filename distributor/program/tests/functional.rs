@@ -0,0 +1,283 @@
+use solana_bank::{state::Account, test_utils::program_test::Env};
+use solana_distributor::{
+    error::DistributorError,
+    instruction::{claim, create_distributor},
+    merkle::{leaf, MerkleTree},
+    processor::Processor,
+    state::{bitmap_len, Distributor, BITMAP_HEADER_LEN},
+};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// What each recipient is allocated, in tree order.
+const AMOUNTS: [u64; 3] = [100, 250, 50];
+
+/// Someone allocated tokens, with an empty account of the airdropped bank.
+struct Recipient {
+    owner: Keypair,
+    account: Pubkey,
+}
+
+struct Fixture {
+    env: Env,
+    bank: Pubkey,
+    distributor: Pubkey,
+    bitmap: Pubkey,
+    vault: Pubkey,
+    recipients: Vec<Recipient>,
+    tree: MerkleTree,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_distributor", processor!(Processor::process)).await;
+        let bank = env.create_bank().await;
+        let mut recipients = Vec::new();
+        for _ in AMOUNTS.iter() {
+            let owner = Keypair::new();
+            let account = env.create_bank_account(&bank, &owner, 0).await;
+            recipients.push(Recipient { owner, account });
+        }
+        let tree = MerkleTree::new(
+            recipients
+                .iter()
+                .zip(AMOUNTS.iter())
+                .enumerate()
+                .map(|(index, (recipient, amount))| {
+                    leaf(index as u64, &recipient.owner.pubkey(), *amount)
+                })
+                .collect(),
+        );
+
+        let (distributor, bitmap, vault) = (Keypair::new(), Keypair::new(), Keypair::new());
+        let distributor_program_id = env.program_id;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&distributor, Distributor::LEN, &distributor_program_id)
+            .await;
+        env.create_account(
+            &bitmap,
+            bitmap_len(AMOUNTS.len() as u64),
+            &distributor_program_id,
+        )
+        .await;
+        env.create_account(&vault, Account::LEN, &bank_program_id)
+            .await;
+        let instruction = create_distributor(
+            &distributor_program_id,
+            &bank_program_id,
+            &env.context.payer.pubkey(),
+            &distributor.pubkey(),
+            &bitmap.pubkey(),
+            &vault.pubkey(),
+            &bank,
+            tree.root(),
+            AMOUNTS.len() as u64,
+            AMOUNTS.iter().sum(),
+        )
+        .unwrap();
+        env.process(&[instruction], &[]).await.unwrap();
+        Fixture {
+            env,
+            bank,
+            distributor: distributor.pubkey(),
+            bitmap: bitmap.pubkey(),
+            vault: vault.pubkey(),
+            recipients,
+            tree,
+        }
+    }
+
+    /// Claims allocation `index` of `amount` as recipient `signer`, into `destination`.
+    async fn claim(
+        &mut self,
+        signer: usize,
+        destination: &Pubkey,
+        index: u64,
+        amount: u64,
+    ) -> Result<(), TransactionError> {
+        let proof = self.tree.proof(index as usize).unwrap_or_default();
+        let instruction = claim(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &self.recipients[signer].owner.pubkey(),
+            &self.distributor,
+            &self.bitmap,
+            &self.vault,
+            destination,
+            index,
+            amount,
+            proof,
+        )
+        .unwrap();
+        let owner = Keypair::from_bytes(&self.recipients[signer].owner.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&owner]).await
+    }
+
+    async fn distributor(&mut self) -> Distributor {
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(self.distributor)
+            .await
+            .unwrap()
+            .unwrap();
+        Distributor::unpack(&account.data).unwrap()
+    }
+}
+
+fn custom(error: DistributorError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_distributor() {
+    let mut fixture = Fixture::new().await;
+    let vault = fixture.vault;
+    assert_eq!(fixture.env.amount(&vault).await, 400);
+    let distributor = fixture.distributor().await;
+    assert_eq!(
+        (distributor.root, distributor.recipients, distributor.total),
+        (fixture.tree.root(), 3, 400)
+    );
+    let bitmap = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(fixture.bitmap)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        &bitmap.data[..BITMAP_HEADER_LEN],
+        fixture.distributor.as_ref()
+    );
+
+    let env = &mut fixture.env;
+    let bank = env.create_bank().await;
+    let distributor_program_id = env.program_id;
+    let bank_program_id = env.bank_program_id;
+    let (distributor, vault) = (Keypair::new(), Keypair::new());
+    env.create_account(&distributor, Distributor::LEN, &distributor_program_id)
+        .await;
+    env.create_account(&vault, Account::LEN, &bank_program_id)
+        .await;
+    let payer = env.context.payer.pubkey();
+    let create = |bitmap: &Pubkey, recipients, total| {
+        create_distributor(
+            &distributor_program_id,
+            &bank_program_id,
+            &payer,
+            &distributor.pubkey(),
+            bitmap,
+            &vault.pubkey(),
+            &bank,
+            [1; 32],
+            recipients,
+            total,
+        )
+        .unwrap()
+    };
+    let empty = create(&fixture.bitmap, 3, 0);
+    assert_eq!(
+        env.process(&[empty], &[]).await,
+        custom(DistributorError::NothingToDistribute)
+    );
+    // The first distributor's bitmap is taken, even with its size right.
+    let shared = create(&fixture.bitmap, 3, 400);
+    assert_eq!(
+        env.process(&[shared], &[]).await,
+        custom(DistributorError::InvalidBitmap)
+    );
+    let small = Keypair::new();
+    env.create_account(&small, bitmap_len(3), &distributor_program_id)
+        .await;
+    let too_many = create(&small.pubkey(), 9, 400);
+    assert_eq!(
+        env.process(&[too_many], &[]).await,
+        custom(DistributorError::InvalidBitmap)
+    );
+}
+
+#[tokio::test]
+async fn test_claim() {
+    let mut fixture = Fixture::new().await;
+    let accounts: Vec<Pubkey> = fixture
+        .recipients
+        .iter()
+        .map(|recipient| recipient.account)
+        .collect();
+
+    fixture.claim(1, &accounts[1], 1, 250).await.unwrap();
+    assert_eq!(fixture.env.amount(&accounts[1]).await, 250);
+    assert_eq!(
+        fixture.claim(1, &accounts[0], 1, 250).await,
+        custom(DistributorError::AlreadyClaimed)
+    );
+
+    // Neither the amount nor the recipient can differ from the leaf.
+    assert_eq!(
+        fixture.claim(0, &accounts[0], 0, 101).await,
+        custom(DistributorError::InvalidProof)
+    );
+    assert_eq!(
+        fixture.claim(2, &accounts[2], 0, 100).await,
+        custom(DistributorError::InvalidProof)
+    );
+    assert_eq!(
+        fixture.claim(2, &accounts[2], 3, 50).await,
+        custom(DistributorError::InvalidIndex)
+    );
+
+    fixture.claim(0, &accounts[0], 0, 100).await.unwrap();
+    fixture.claim(2, &accounts[2], 2, 50).await.unwrap();
+    for (account, amount) in accounts.iter().zip(AMOUNTS.iter()) {
+        assert_eq!(fixture.env.amount(account).await, *amount);
+    }
+    let vault = fixture.vault;
+    assert_eq!(fixture.env.amount(&vault).await, 0);
+    assert_eq!(fixture.distributor().await.claimed, 400);
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    let recipient = &fixture.recipients[1];
+
+    // Through a program posing as the bank, a claim would hand it the vault authority's
+    // signature.
+    let instruction = claim(
+        &fixture.env.program_id,
+        &fixture.env.fake_bank_program_id,
+        &fixture.bank,
+        &recipient.owner.pubkey(),
+        &fixture.distributor,
+        &fixture.bitmap,
+        &fixture.vault,
+        &recipient.account,
+        1,
+        250,
+        fixture.tree.proof(1).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        fixture
+            .env
+            .process(&[instruction], &[&recipient.owner])
+            .await,
+        custom(DistributorError::InvalidVault)
+    );
+    assert_eq!(fixture.distributor().await.claimed, 0);
+
+    let account = fixture.recipients[1].account;
+    fixture.claim(1, &account, 1, 250).await.unwrap();
+    assert_eq!(fixture.env.amount(&account).await, 250);
+}
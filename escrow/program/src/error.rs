@@ -0,0 +1,15 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EscrowError {
+    /// The vault doesn't hold the amount the taker expects to receive.
+    AmountMismatch,
+    /// The vault authority isn't the escrow's program address.
+    InvalidVaultAuthority,
+}
+
+impl From<EscrowError> for ProgramError {
+    fn from(e: EscrowError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
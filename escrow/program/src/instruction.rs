@@ -0,0 +1,248 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the vault authority, followed by the escrow account's address.
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"escrow";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EscrowInstruction {
+    /// Opens an escrow: initializes the vault in the deposit's bank, owned by the vault
+    /// authority, and moves `amount` from the maker's deposit account into it.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The maker.
+    ///   1. `[writable]` The escrow, owned by this program.
+    ///   2. `[writable]` The vault, an uninitialized account owned by the bank program.
+    ///   3. `[writable]` The maker's deposit account.
+    ///   4. `[]` The maker's receive account, possibly in another bank.
+    ///   5. `[writable]` The bank of the deposit account.
+    ///   6. `[writable]` The vault authority.
+    ///   7. `[]` The bank program.
+    InitEscrow { amount: u64, expected_amount: u64 },
+
+    /// Pays `expected_amount` from the taker to the maker, hands the vault's deposit to
+    /// the taker and closes the escrow. `amount` is what the taker expects the vault to
+    /// hold, so the offer can't change between signing and landing.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The taker.
+    ///   1. `[writable]` The taker's account paying the maker.
+    ///   2. `[writable]` The taker's account receiving the deposit.
+    ///   3. `[writable]` The maker, receiving the rent of the closed accounts.
+    ///   4. `[writable]` The maker's receive account.
+    ///   5. `[writable]` The escrow.
+    ///   6. `[writable]` The vault.
+    ///   7. `[writable]` The vault authority.
+    ///   8. `[]` The bank of the deposit account.
+    ///   9. `[]` The bank of the maker's receive account.
+    ///   10. `[]` The bank program.
+    Exchange { amount: u64 },
+
+    /// Returns the deposit to the maker and closes the escrow.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The maker.
+    ///   1. `[writable]` The maker's deposit account.
+    ///   2. `[writable]` The escrow.
+    ///   3. `[writable]` The vault.
+    ///   4. `[writable]` The vault authority.
+    ///   5. `[]` The bank of the deposit account.
+    ///   6. `[]` The bank program.
+    Cancel,
+}
+
+impl EscrowInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (amount, rest) = Self::unpack_u64(rest)?;
+                let (expected_amount, _rest) = Self::unpack_u64(rest)?;
+                Self::InitEscrow {
+                    amount,
+                    expected_amount,
+                }
+            }
+            1 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Exchange { amount }
+            }
+            2 => Self::Cancel,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(17);
+        match *self {
+            Self::InitEscrow {
+                amount,
+                expected_amount,
+            } => {
+                buf.push(0);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(&expected_amount.to_le_bytes());
+            }
+            Self::Exchange { amount } => {
+                buf.push(1);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Cancel => buf.push(2),
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let amount = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((amount, &input[8..]))
+    }
+}
+
+/// Address and bump of the authority owning `escrow`'s vault.
+pub fn vault_authority(escrow_program_id: &Pubkey, escrow: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED, escrow.as_ref()], escrow_program_id)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn init_escrow(
+    escrow_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    maker: &Pubkey,
+    escrow: &Pubkey,
+    vault: &Pubkey,
+    maker_deposit_account: &Pubkey,
+    maker_receive_account: &Pubkey,
+    deposit_bank: &Pubkey,
+    amount: u64,
+    expected_amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = EscrowInstruction::InitEscrow {
+        amount,
+        expected_amount,
+    }
+    .pack();
+    let (authority, _) = vault_authority(escrow_program_id, escrow);
+    let accounts = vec![
+        AccountMeta::new(*maker, true),
+        AccountMeta::new(*escrow, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*maker_deposit_account, false),
+        AccountMeta::new_readonly(*maker_receive_account, false),
+        // The bank program's builders mark the bank and the account owners writable, and
+        // a CPI can't grant more than the outer instruction did.
+        AccountMeta::new(*deposit_bank, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *escrow_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn exchange(
+    escrow_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    taker: &Pubkey,
+    taker_send_account: &Pubkey,
+    taker_receive_account: &Pubkey,
+    maker: &Pubkey,
+    maker_receive_account: &Pubkey,
+    escrow: &Pubkey,
+    vault: &Pubkey,
+    deposit_bank: &Pubkey,
+    receive_bank: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = EscrowInstruction::Exchange { amount }.pack();
+    let (authority, _) = vault_authority(escrow_program_id, escrow);
+    let accounts = vec![
+        AccountMeta::new(*taker, true),
+        AccountMeta::new(*taker_send_account, false),
+        AccountMeta::new(*taker_receive_account, false),
+        AccountMeta::new(*maker, false),
+        AccountMeta::new(*maker_receive_account, false),
+        AccountMeta::new(*escrow, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*deposit_bank, false),
+        AccountMeta::new_readonly(*receive_bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *escrow_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn cancel(
+    escrow_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    maker: &Pubkey,
+    maker_deposit_account: &Pubkey,
+    escrow: &Pubkey,
+    vault: &Pubkey,
+    deposit_bank: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = EscrowInstruction::Cancel.pack();
+    let (authority, _) = vault_authority(escrow_program_id, escrow);
+    let accounts = vec![
+        AccountMeta::new(*maker, true),
+        AccountMeta::new(*maker_deposit_account, false),
+        AccountMeta::new(*escrow, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*deposit_bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *escrow_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            EscrowInstruction::InitEscrow {
+                amount: 100,
+                expected_amount: u64::MAX,
+            },
+            EscrowInstruction::Exchange { amount: 7 },
+            EscrowInstruction::Cancel,
+        ];
+        for instruction in cases {
+            assert_eq!(
+                EscrowInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            EscrowInstruction::unpack(&[0, 1, 2, 3]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            EscrowInstruction::unpack(&[3]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
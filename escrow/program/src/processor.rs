@@ -0,0 +1,325 @@
+use crate::{
+    error::EscrowError,
+    instruction::{EscrowInstruction, VAULT_AUTHORITY_SEED},
+    state::Escrow,
+};
+use solana_bank::{instruction as bank_instruction, state::Account as BankAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = EscrowInstruction::unpack(input)?;
+
+        match instruction {
+            EscrowInstruction::InitEscrow {
+                amount,
+                expected_amount,
+            } => {
+                msg!("Instruction: InitEscrow");
+                Self::process_init_escrow(program_id, accounts, amount, expected_amount)
+            }
+            EscrowInstruction::Exchange { amount } => {
+                msg!("Instruction: Exchange");
+                Self::process_exchange(program_id, accounts, amount)
+            }
+            EscrowInstruction::Cancel => {
+                msg!("Instruction: Cancel");
+                Self::process_cancel(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_init_escrow(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+        expected_amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let maker_info = next_account_info(account_info_iter)?;
+        let escrow_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let deposit_info = next_account_info(account_info_iter)?;
+        let receive_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !maker_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if escrow_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        // The bank program checks the deposit and the vault itself; the receive account
+        // is only recorded here, so it has to be vetted up front.
+        if receive_info.owner != bank_program_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if BankAccount::unpack(&receive_info.data.borrow())?.owner != *maker_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut escrow = Escrow::unpack_unchecked(&escrow_info.data.borrow())?;
+        if escrow.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        let (authority, bump) = Pubkey::find_program_address(
+            &[VAULT_AUTHORITY_SEED, escrow_info.key.as_ref()],
+            program_id,
+        );
+        if authority != *authority_info.key {
+            return Err(EscrowError::InvalidVaultAuthority.into());
+        }
+        let signer_seeds: &[&[u8]] = &[VAULT_AUTHORITY_SEED, escrow_info.key.as_ref(), &[bump]];
+
+        invoke_signed(
+            &bank_instruction::initialize_account(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                authority_info.key,
+            )?,
+            &[
+                bank_info.clone(),
+                vault_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                deposit_info.key,
+                vault_info.key,
+                maker_info.key,
+                amount,
+            )?,
+            &[
+                deposit_info.clone(),
+                vault_info.clone(),
+                maker_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        escrow.is_initialized = true;
+        escrow.maker = *maker_info.key;
+        escrow.vault = *vault_info.key;
+        escrow.maker_receive_account = *receive_info.key;
+        escrow.expected_amount = expected_amount;
+        escrow.bump = bump;
+        Escrow::pack(escrow, &mut escrow_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_exchange(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let taker_info = next_account_info(account_info_iter)?;
+        let taker_send_info = next_account_info(account_info_iter)?;
+        let taker_receive_info = next_account_info(account_info_iter)?;
+        let maker_info = next_account_info(account_info_iter)?;
+        let maker_receive_info = next_account_info(account_info_iter)?;
+        let escrow_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let deposit_bank_info = next_account_info(account_info_iter)?;
+        let receive_bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !taker_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let escrow = Self::load_escrow(program_id, escrow_info, vault_info, bank_program_info)?;
+        if escrow.maker != *maker_info.key
+            || escrow.maker_receive_account != *maker_receive_info.key
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let vault = BankAccount::unpack(&vault_info.data.borrow())?;
+        if vault.amount != amount {
+            return Err(EscrowError::AmountMismatch.into());
+        }
+        let bump = [escrow.bump];
+        let signer_seeds: &[&[u8]] = &[VAULT_AUTHORITY_SEED, escrow_info.key.as_ref(), &bump];
+        Self::check_authority(program_id, authority_info, signer_seeds)?;
+
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                receive_bank_info.key,
+                taker_send_info.key,
+                maker_receive_info.key,
+                taker_info.key,
+                escrow.expected_amount,
+            )?,
+            &[
+                taker_send_info.clone(),
+                maker_receive_info.clone(),
+                taker_info.clone(),
+                receive_bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                deposit_bank_info.key,
+                vault_info.key,
+                taker_receive_info.key,
+                authority_info.key,
+                vault.amount,
+            )?,
+            &[
+                vault_info.clone(),
+                taker_receive_info.clone(),
+                authority_info.clone(),
+                deposit_bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        Self::close(
+            escrow_info,
+            vault_info,
+            authority_info,
+            maker_info,
+            deposit_bank_info,
+            bank_program_info,
+            signer_seeds,
+        )
+    }
+
+    pub fn process_cancel(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let maker_info = next_account_info(account_info_iter)?;
+        let deposit_info = next_account_info(account_info_iter)?;
+        let escrow_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !maker_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let escrow = Self::load_escrow(program_id, escrow_info, vault_info, bank_program_info)?;
+        if escrow.maker != *maker_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let vault = BankAccount::unpack(&vault_info.data.borrow())?;
+        let bump = [escrow.bump];
+        let signer_seeds: &[&[u8]] = &[VAULT_AUTHORITY_SEED, escrow_info.key.as_ref(), &bump];
+        Self::check_authority(program_id, authority_info, signer_seeds)?;
+
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                deposit_info.key,
+                authority_info.key,
+                vault.amount,
+            )?,
+            &[
+                vault_info.clone(),
+                deposit_info.clone(),
+                authority_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        Self::close(
+            escrow_info,
+            vault_info,
+            authority_info,
+            maker_info,
+            bank_info,
+            bank_program_info,
+            signer_seeds,
+        )
+    }
+
+    fn load_escrow(
+        program_id: &Pubkey,
+        escrow_info: &AccountInfo,
+        vault_info: &AccountInfo,
+        bank_program_info: &AccountInfo,
+    ) -> Result<Escrow, ProgramError> {
+        if escrow_info.owner != program_id || vault_info.owner != bank_program_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let escrow = Escrow::unpack(&escrow_info.data.borrow())?;
+        if escrow.vault != *vault_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(escrow)
+    }
+
+    fn check_authority(
+        program_id: &Pubkey,
+        authority_info: &AccountInfo,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        let authority = Pubkey::create_program_address(signer_seeds, program_id)
+            .map_err(|_| ProgramError::from(EscrowError::InvalidVaultAuthority))?;
+        if authority != *authority_info.key {
+            return Err(EscrowError::InvalidVaultAuthority.into());
+        }
+        Ok(())
+    }
+
+    /// Closes the emptied vault through the bank program and the escrow itself, sending
+    /// the rent of both to the maker.
+    fn close<'a>(
+        escrow_info: &AccountInfo<'a>,
+        vault_info: &AccountInfo<'a>,
+        authority_info: &AccountInfo<'a>,
+        maker_info: &AccountInfo<'a>,
+        bank_info: &AccountInfo<'a>,
+        bank_program_info: &AccountInfo<'a>,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        invoke_signed(
+            &bank_instruction::close_account(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                maker_info.key,
+                authority_info.key,
+            )?,
+            &[
+                vault_info.clone(),
+                maker_info.clone(),
+                authority_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        let maker_lamports = maker_info.lamports();
+        **maker_info.lamports.borrow_mut() = maker_lamports
+            .checked_add(escrow_info.lamports())
+            .ok_or(ProgramError::InvalidArgument)?;
+        **escrow_info.lamports.borrow_mut() = 0;
+        escrow_info.data.borrow_mut().fill(0);
+        Ok(())
+    }
+}
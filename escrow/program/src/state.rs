@@ -0,0 +1,88 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// An open offer: the vault holds the maker's deposit until a taker pays
+/// `expected_amount` into `maker_receive_account`, or the maker cancels.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Escrow {
+    pub is_initialized: bool,
+    pub maker: Pubkey,
+    /// Bank account holding the deposit, owned by the escrow's vault authority.
+    pub vault: Pubkey,
+    /// Bank account of the maker that the taker pays into.
+    pub maker_receive_account: Pubkey,
+    pub expected_amount: u64,
+    /// Bump seed of the vault authority address.
+    pub bump: u8,
+}
+
+impl Sealed for Escrow {}
+impl IsInitialized for Escrow {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Escrow {
+    const LEN: usize = 106;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 106];
+        let (is_initialized, maker, vault, maker_receive_account, expected_amount, bump) =
+            array_refs![src, 1, 32, 32, 32, 8, 1];
+        let is_initialized = match is_initialized[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        Ok(Escrow {
+            is_initialized,
+            maker: Pubkey::new_from_array(*maker),
+            vault: Pubkey::new_from_array(*vault),
+            maker_receive_account: Pubkey::new_from_array(*maker_receive_account),
+            expected_amount: u64::from_le_bytes(*expected_amount),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 106];
+        let (is_initialized, maker, vault, maker_receive_account, expected_amount, bump) =
+            mut_array_refs![dst, 1, 32, 32, 32, 8, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        maker.copy_from_slice(self.maker.as_ref());
+        vault.copy_from_slice(self.vault.as_ref());
+        maker_receive_account.copy_from_slice(self.maker_receive_account.as_ref());
+        *expected_amount = self.expected_amount.to_le_bytes();
+        bump[0] = self.bump;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let escrow = Escrow {
+            is_initialized: true,
+            maker: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            maker_receive_account: Pubkey::new_unique(),
+            expected_amount: 0x0102_0304_0506_0708,
+            bump: 254,
+        };
+        let mut packed = vec![0u8; Escrow::LEN];
+        Escrow::pack(escrow, &mut packed).unwrap();
+        assert_eq!(Escrow::unpack(&packed), Ok(escrow));
+
+        packed[0] = 2;
+        assert_eq!(
+            Escrow::unpack_unchecked(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+}
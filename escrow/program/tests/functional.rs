@@ -0,0 +1,218 @@
+use solana_bank::{error::BankError, state::Account, test_utils::program_test::Env};
+use solana_escrow::{
+    error::EscrowError,
+    instruction::{cancel, exchange, init_escrow, vault_authority},
+    processor::Processor,
+    state::Escrow,
+};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// The maker offers 40 tokens of bank A for 25 tokens of bank B, which the taker holds 30 of.
+struct Fixture {
+    env: Env,
+    maker: Keypair,
+    taker: Keypair,
+    bank_a: Pubkey,
+    bank_b: Pubkey,
+    maker_deposit: Pubkey,
+    maker_receive: Pubkey,
+    taker_send: Pubkey,
+    taker_receive: Pubkey,
+    escrow: Pubkey,
+    vault: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_escrow", processor!(Processor::process)).await;
+        let maker = Keypair::new();
+        let taker = Keypair::new();
+        let bank_a = env.create_bank().await;
+        let bank_b = env.create_bank().await;
+        let maker_deposit = env.create_bank_account(&bank_a, &maker, 100).await;
+        let maker_receive = env.create_bank_account(&bank_b, &maker, 0).await;
+        let taker_send = env.create_bank_account(&bank_b, &taker, 30).await;
+        let taker_receive = env.create_bank_account(&bank_a, &taker, 0).await;
+
+        let escrow = Keypair::new();
+        let vault = Keypair::new();
+        let escrow_program_id = env.program_id;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&escrow, Escrow::LEN, &escrow_program_id)
+            .await;
+        env.create_account(&vault, Account::LEN, &bank_program_id)
+            .await;
+        let instruction = init_escrow(
+            &escrow_program_id,
+            &bank_program_id,
+            &maker.pubkey(),
+            &escrow.pubkey(),
+            &vault.pubkey(),
+            &maker_deposit,
+            &maker_receive,
+            &bank_a,
+            40,
+            25,
+        )
+        .unwrap();
+        env.process(&[instruction], &[&maker]).await.unwrap();
+
+        Fixture {
+            env,
+            maker,
+            taker,
+            bank_a,
+            bank_b,
+            maker_deposit,
+            maker_receive,
+            taker_send,
+            taker_receive,
+            escrow: escrow.pubkey(),
+            vault: vault.pubkey(),
+        }
+    }
+
+    fn exchange(&self, amount: u64) -> Instruction {
+        exchange(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.taker.pubkey(),
+            &self.taker_send,
+            &self.taker_receive,
+            &self.maker.pubkey(),
+            &self.maker_receive,
+            &self.escrow,
+            &self.vault,
+            &self.bank_a,
+            &self.bank_b,
+            amount,
+        )
+        .unwrap()
+    }
+
+    fn cancel(&self, signer: &Keypair) -> Instruction {
+        cancel(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &signer.pubkey(),
+            &self.maker_deposit,
+            &self.escrow,
+            &self.vault,
+            &self.bank_a,
+        )
+        .unwrap()
+    }
+}
+
+#[tokio::test]
+async fn test_init_escrow() {
+    let mut fixture = Fixture::new().await;
+    let env = &mut fixture.env;
+
+    let escrow = env.account(&fixture.escrow).await.unwrap();
+    let escrow = Escrow::unpack(&escrow.data).unwrap();
+    assert_eq!(escrow.maker, fixture.maker.pubkey());
+    assert_eq!(escrow.vault, fixture.vault);
+    assert_eq!(escrow.maker_receive_account, fixture.maker_receive);
+    assert_eq!(escrow.expected_amount, 25);
+
+    let vault = env.account(&fixture.vault).await.unwrap();
+    let vault = Account::unpack(&vault.data).unwrap();
+    let (authority, _) = vault_authority(&env.program_id, &fixture.escrow);
+    assert_eq!(vault.owner, authority);
+    assert_eq!(vault.amount, 40);
+    assert_eq!(env.amount(&fixture.maker_deposit).await, 60);
+}
+
+#[tokio::test]
+async fn test_exchange() {
+    let mut fixture = Fixture::new().await;
+    let instruction = fixture.exchange(40);
+    let env = &mut fixture.env;
+
+    env.process(&[instruction], &[&fixture.taker])
+        .await
+        .unwrap();
+    assert_eq!(env.amount(&fixture.taker_receive).await, 40);
+    assert_eq!(env.amount(&fixture.taker_send).await, 5);
+    assert_eq!(env.amount(&fixture.maker_receive).await, 25);
+    assert!(!env.account(&fixture.escrow).await.is_some());
+    assert!(!env.account(&fixture.vault).await.is_some());
+}
+
+#[tokio::test]
+async fn test_exchange_rejects_unexpected_amount() {
+    let mut fixture = Fixture::new().await;
+    let instruction = fixture.exchange(39);
+    let env = &mut fixture.env;
+
+    assert_eq!(
+        env.process(&[instruction], &[&fixture.taker]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(EscrowError::AmountMismatch as u32)
+        ))
+    );
+    assert_eq!(env.amount(&fixture.vault).await, 40);
+}
+
+#[tokio::test]
+async fn test_exchange_needs_full_payment() {
+    let mut fixture = Fixture::new().await;
+    let drain = solana_bank::instruction::transfer(
+        &fixture.env.bank_program_id,
+        &fixture.bank_b,
+        &fixture.taker_send,
+        &fixture.maker_receive,
+        &fixture.taker.pubkey(),
+        10,
+    )
+    .unwrap();
+    let instruction = fixture.exchange(40);
+    let env = &mut fixture.env;
+    env.process(&[drain], &[&fixture.taker]).await.unwrap();
+
+    // 20 left against 25 expected: the taker's transfer fails and nothing moves.
+    assert_eq!(
+        env.process(&[instruction], &[&fixture.taker]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::InsufficientFunds as u32)
+        ))
+    );
+    assert_eq!(env.amount(&fixture.vault).await, 40);
+    assert_eq!(env.amount(&fixture.taker_receive).await, 0);
+    assert!(env.account(&fixture.escrow).await.is_some());
+}
+
+#[tokio::test]
+async fn test_cancel() {
+    let mut fixture = Fixture::new().await;
+    let not_maker = fixture.cancel(&fixture.taker);
+    let instruction = fixture.cancel(&fixture.maker);
+    let env = &mut fixture.env;
+
+    assert_eq!(
+        env.process(&[not_maker], &[&fixture.taker]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+
+    env.process(&[instruction], &[&fixture.maker])
+        .await
+        .unwrap();
+    assert_eq!(env.amount(&fixture.maker_deposit).await, 100);
+    assert!(!env.account(&fixture.escrow).await.is_some());
+    assert!(!env.account(&fixture.vault).await.is_some());
+}
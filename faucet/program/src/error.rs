@@ -0,0 +1,17 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FaucetError {
+    /// The tap would take the address past the faucet's daily cap.
+    DailyCapExceeded,
+    /// The faucet authority isn't the faucet's program address.
+    InvalidFaucetAuthority,
+    /// The tap record isn't the program address of this faucet and user.
+    InvalidTapRecord,
+}
+
+impl From<FaucetError> for ProgramError {
+    fn from(e: FaucetError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,179 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the faucet authority, followed by the faucet's address.
+pub const FAUCET_AUTHORITY_SEED: &[u8] = b"faucet";
+/// Seed prefix of a tap record, followed by the faucet's and the user's addresses.
+pub const TAP_RECORD_SEED: &[u8] = b"tap";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FaucetInstruction {
+    /// Opens a faucet handing out up to `daily_cap` per address per day: initializes the
+    /// vault, owned by the faucet authority. The admin funds it with plain bank mints or
+    /// transfers.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The admin.
+    ///   1. `[writable]` The faucet, owned by this program.
+    ///   2. `[writable]` The vault, an uninitialized account owned by the bank program.
+    ///   3. `[writable]` The bank of the handed out tokens.
+    ///   4. `[writable]` The faucet authority.
+    ///   5. `[]` The bank program.
+    InitializeFaucet { daily_cap: u64 },
+
+    /// Sends `amount` from the vault to the destination, counted against the user's cap
+    /// for the day. The user's tap record is created, at their expense, on first use.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The user.
+    ///   1. `[]` The faucet.
+    ///   2. `[writable]` The user's tap record.
+    ///   3. `[writable]` The vault.
+    ///   4. `[writable]` The destination account.
+    ///   5. `[writable]` The faucet authority.
+    ///   6. `[]` The faucet's bank.
+    ///   7. `[]` The bank program.
+    ///   8. `[]` The system program.
+    Tap { amount: u64 },
+}
+
+impl FaucetInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        let value = rest
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => Self::InitializeFaucet { daily_cap: value },
+            1 => Self::Tap { amount: value },
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(9);
+        match *self {
+            Self::InitializeFaucet { daily_cap } => {
+                buf.push(0);
+                buf.extend_from_slice(&daily_cap.to_le_bytes());
+            }
+            Self::Tap { amount } => {
+                buf.push(1);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+        }
+        buf
+    }
+}
+
+/// Address and bump of the authority owning `faucet`'s vault.
+pub fn faucet_authority(faucet_program_id: &Pubkey, faucet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FAUCET_AUTHORITY_SEED, faucet.as_ref()], faucet_program_id)
+}
+
+/// Address and bump of `user`'s tap record for `faucet`.
+pub fn tap_record_address(
+    faucet_program_id: &Pubkey,
+    faucet: &Pubkey,
+    user: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[TAP_RECORD_SEED, faucet.as_ref(), user.as_ref()],
+        faucet_program_id,
+    )
+}
+
+pub fn initialize_faucet(
+    faucet_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    admin: &Pubkey,
+    faucet: &Pubkey,
+    vault: &Pubkey,
+    bank: &Pubkey,
+    daily_cap: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = FaucetInstruction::InitializeFaucet { daily_cap }.pack();
+    let (authority, _) = faucet_authority(faucet_program_id, faucet);
+    let accounts = vec![
+        AccountMeta::new_readonly(*admin, true),
+        AccountMeta::new(*faucet, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *faucet_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn tap(
+    faucet_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    user: &Pubkey,
+    faucet: &Pubkey,
+    vault: &Pubkey,
+    destination_account: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = FaucetInstruction::Tap { amount }.pack();
+    let (authority, _) = faucet_authority(faucet_program_id, faucet);
+    let (tap_record, _) = tap_record_address(faucet_program_id, faucet, user);
+    let accounts = vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(*faucet, false),
+        AccountMeta::new(tap_record, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *faucet_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            FaucetInstruction::InitializeFaucet { daily_cap: 1_000 },
+            FaucetInstruction::Tap { amount: u64::MAX },
+        ];
+        for instruction in cases {
+            assert_eq!(
+                FaucetInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            FaucetInstruction::unpack(&[1, 2, 3]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            FaucetInstruction::unpack(&[2, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
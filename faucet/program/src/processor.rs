@@ -0,0 +1,194 @@
+use crate::{
+    error::FaucetError,
+    instruction::{FaucetInstruction, FAUCET_AUTHORITY_SEED, TAP_RECORD_SEED},
+    state::{Faucet, TapRecord},
+};
+use solana_bank::instruction as bank_instruction;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = FaucetInstruction::unpack(input)?;
+
+        match instruction {
+            FaucetInstruction::InitializeFaucet { daily_cap } => {
+                msg!("Instruction: InitializeFaucet");
+                Self::process_initialize_faucet(program_id, accounts, daily_cap)
+            }
+            FaucetInstruction::Tap { amount } => {
+                msg!("Instruction: Tap");
+                Self::process_tap(program_id, accounts, amount)
+            }
+        }
+    }
+
+    pub fn process_initialize_faucet(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        daily_cap: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let admin_info = next_account_info(account_info_iter)?;
+        let faucet_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !admin_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if faucet_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut faucet = Faucet::unpack_unchecked(&faucet_info.data.borrow())?;
+        if faucet.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        let (authority, bump) = Pubkey::find_program_address(
+            &[FAUCET_AUTHORITY_SEED, faucet_info.key.as_ref()],
+            program_id,
+        );
+        if authority != *authority_info.key {
+            return Err(FaucetError::InvalidFaucetAuthority.into());
+        }
+        if vault_info.owner != bank_program_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        invoke_signed(
+            &bank_instruction::initialize_account(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                authority_info.key,
+            )?,
+            &[
+                bank_info.clone(),
+                vault_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[FAUCET_AUTHORITY_SEED, faucet_info.key.as_ref(), &[bump]]],
+        )?;
+
+        faucet.is_initialized = true;
+        faucet.admin = *admin_info.key;
+        faucet.vault = *vault_info.key;
+        faucet.daily_cap = daily_cap;
+        faucet.bump = bump;
+        Faucet::pack(faucet, &mut faucet_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_tap(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let user_info = next_account_info(account_info_iter)?;
+        let faucet_info = next_account_info(account_info_iter)?;
+        let record_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !user_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if faucet_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let faucet = Faucet::unpack(&faucet_info.data.borrow())?;
+        // The faucet authority signs the transfer, so only the bank that holds the vault
+        // may receive it.
+        if faucet.vault != *vault_info.key || vault_info.owner != bank_program_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let (record_key, record_bump) = Pubkey::find_program_address(
+            &[
+                TAP_RECORD_SEED,
+                faucet_info.key.as_ref(),
+                user_info.key.as_ref(),
+            ],
+            program_id,
+        );
+        if record_key != *record_info.key {
+            return Err(FaucetError::InvalidTapRecord.into());
+        }
+        let bump = [faucet.bump];
+        let signer_seeds: &[&[u8]] = &[FAUCET_AUTHORITY_SEED, faucet_info.key.as_ref(), &bump];
+        let authority = Pubkey::create_program_address(signer_seeds, program_id)
+            .map_err(|_| ProgramError::from(FaucetError::InvalidFaucetAuthority))?;
+        if authority != *authority_info.key {
+            return Err(FaucetError::InvalidFaucetAuthority.into());
+        }
+
+        if record_info.data_is_empty() {
+            invoke_signed(
+                &system_instruction::create_account(
+                    user_info.key,
+                    record_info.key,
+                    Rent::get()?.minimum_balance(TapRecord::LEN),
+                    TapRecord::LEN as u64,
+                    program_id,
+                ),
+                &[
+                    user_info.clone(),
+                    record_info.clone(),
+                    system_program_info.clone(),
+                ],
+                &[&[
+                    TAP_RECORD_SEED,
+                    faucet_info.key.as_ref(),
+                    user_info.key.as_ref(),
+                    &[record_bump],
+                ]],
+            )?;
+        }
+        let mut record = TapRecord::unpack_unchecked(&record_info.data.borrow())?;
+        record.is_initialized = true;
+        if !record.tap(amount, Clock::get()?.unix_timestamp, faucet.daily_cap) {
+            return Err(FaucetError::DailyCapExceeded.into());
+        }
+
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                destination_info.key,
+                authority_info.key,
+                amount,
+            )?,
+            &[
+                vault_info.clone(),
+                destination_info.clone(),
+                authority_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        TapRecord::pack(record, &mut record_info.data.borrow_mut())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Tokens in `vault` that any address can take up to `daily_cap` of per UTC day.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Faucet {
+    pub is_initialized: bool,
+    pub admin: Pubkey,
+    /// Bank account holding the pre-minted balance, owned by the faucet authority.
+    pub vault: Pubkey,
+    pub daily_cap: u64,
+    /// Bump seed of the faucet authority address.
+    pub bump: u8,
+}
+
+impl Sealed for Faucet {}
+impl IsInitialized for Faucet {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Faucet {
+    const LEN: usize = 74;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 74];
+        let (is_initialized, admin, vault, daily_cap, bump) = array_refs![src, 1, 32, 32, 8, 1];
+        Ok(Faucet {
+            is_initialized: unpack_bool(is_initialized)?,
+            admin: Pubkey::new_from_array(*admin),
+            vault: Pubkey::new_from_array(*vault),
+            daily_cap: u64::from_le_bytes(*daily_cap),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 74];
+        let (is_initialized, admin, vault, daily_cap, bump) = mut_array_refs![dst, 1, 32, 32, 8, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        admin.copy_from_slice(self.admin.as_ref());
+        vault.copy_from_slice(self.vault.as_ref());
+        *daily_cap = self.daily_cap.to_le_bytes();
+        bump[0] = self.bump;
+    }
+}
+
+/// How much one address took from a faucet on its latest day of tapping, kept at the
+/// program address of the faucet and the user.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TapRecord {
+    pub is_initialized: bool,
+    /// Days since the Unix epoch.
+    pub day: i64,
+    pub tapped: u64,
+}
+
+impl TapRecord {
+    /// Records a tap of `amount` at `unix_timestamp`, starting the count over on a new day.
+    /// Returns false, leaving the record untouched, when it would exceed `daily_cap`.
+    pub fn tap(&mut self, amount: u64, unix_timestamp: i64, daily_cap: u64) -> bool {
+        let day = unix_timestamp.div_euclid(SECONDS_PER_DAY);
+        let tapped = if day == self.day { self.tapped } else { 0 };
+        match tapped.checked_add(amount) {
+            Some(tapped) if tapped <= daily_cap => {
+                self.day = day;
+                self.tapped = tapped;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Sealed for TapRecord {}
+impl IsInitialized for TapRecord {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for TapRecord {
+    const LEN: usize = 17;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 17];
+        let (is_initialized, day, tapped) = array_refs![src, 1, 8, 8];
+        Ok(TapRecord {
+            is_initialized: unpack_bool(is_initialized)?,
+            day: i64::from_le_bytes(*day),
+            tapped: u64::from_le_bytes(*tapped),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 17];
+        let (is_initialized, day, tapped) = mut_array_refs![dst, 1, 8, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        *day = self.day.to_le_bytes();
+        *tapped = self.tapped.to_le_bytes();
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let faucet = Faucet {
+            is_initialized: true,
+            admin: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            daily_cap: u64::MAX,
+            bump: 253,
+        };
+        let mut packed = vec![0u8; Faucet::LEN];
+        Faucet::pack(faucet, &mut packed).unwrap();
+        assert_eq!(Faucet::unpack(&packed), Ok(faucet));
+
+        let record = TapRecord {
+            is_initialized: true,
+            day: -1,
+            tapped: 9,
+        };
+        let mut packed = vec![0u8; TapRecord::LEN];
+        TapRecord::pack(record, &mut packed).unwrap();
+        assert_eq!(TapRecord::unpack(&packed), Ok(record));
+    }
+
+    #[test]
+    fn test_tap() {
+        let mut record = TapRecord::default();
+        let noon = 10 * SECONDS_PER_DAY + SECONDS_PER_DAY / 2;
+        assert!(record.tap(60, noon, 100));
+        assert!(record.tap(40, noon + 1, 100));
+        assert!(!record.tap(1, noon + 2, 100));
+        assert_eq!((record.day, record.tapped), (10, 100));
+
+        // The count starts over at midnight UTC, not a day after the first tap.
+        assert!(record.tap(100, 11 * SECONDS_PER_DAY, 100));
+        assert_eq!((record.day, record.tapped), (11, 100));
+        assert!(!record.tap(u64::MAX, 12 * SECONDS_PER_DAY, 100));
+        assert_eq!((record.day, record.tapped), (11, 100));
+    }
+}
@@ -0,0 +1,217 @@
+use solana_bank::{instruction::mint_to, state::Account, test_utils::program_test::Env};
+use solana_faucet::{
+    error::FaucetError,
+    instruction::{faucet_authority, initialize_faucet, tap, tap_record_address},
+    processor::Processor,
+    state::{Faucet, TapRecord, SECONDS_PER_DAY},
+};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const DAILY_CAP: u64 = 100;
+/// Noon UTC, some day.
+const NOON: i64 = 19_000 * SECONDS_PER_DAY + SECONDS_PER_DAY / 2;
+
+/// Someone with a little SOL for their tap record and an empty account in the faucet's bank.
+struct User {
+    keypair: Keypair,
+    account: Pubkey,
+}
+
+/// A faucet holding 1_000 tokens, handing out `DAILY_CAP` per address per day.
+struct Fixture {
+    env: Env,
+    faucet: Pubkey,
+    vault: Pubkey,
+    bank: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_faucet", processor!(Processor::process)).await;
+        let bank = env.create_bank().await;
+        let faucet = Keypair::new();
+        let vault = Keypair::new();
+        let faucet_program_id = env.program_id;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&faucet, Faucet::LEN, &faucet_program_id)
+            .await;
+        env.create_account(&vault, Account::LEN, &bank_program_id)
+            .await;
+        let payer = env.context.payer.pubkey();
+        let instructions = [
+            initialize_faucet(
+                &faucet_program_id,
+                &bank_program_id,
+                &payer,
+                &faucet.pubkey(),
+                &vault.pubkey(),
+                &bank,
+                DAILY_CAP,
+            )
+            .unwrap(),
+            mint_to(&bank_program_id, &bank, &vault.pubkey(), &payer, 1_000).unwrap(),
+        ];
+        env.process(&instructions, &[]).await.unwrap();
+        env.set_time(NOON);
+
+        Fixture {
+            env,
+            faucet: faucet.pubkey(),
+            vault: vault.pubkey(),
+            bank,
+        }
+    }
+
+    async fn user(&mut self) -> User {
+        let keypair = Keypair::new();
+        let bank = self.bank;
+        let account = self.env.create_bank_account(&bank, &keypair, 0).await;
+        let fund = system_instruction::transfer(
+            &self.env.context.payer.pubkey(),
+            &keypair.pubkey(),
+            1_000_000_000,
+        );
+        self.env.process(&[fund], &[]).await.unwrap();
+        User { keypair, account }
+    }
+
+    fn tap(&self, user: &User, amount: u64) -> Instruction {
+        tap(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &user.keypair.pubkey(),
+            &self.faucet,
+            &self.vault,
+            &user.account,
+            amount,
+        )
+        .unwrap()
+    }
+}
+
+#[tokio::test]
+async fn test_tap() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.user().await;
+    let instruction = fixture.tap(&alice, 60);
+    let env = &mut fixture.env;
+
+    env.process(&[instruction], &[&alice.keypair])
+        .await
+        .unwrap();
+    assert_eq!(env.amount(&alice.account).await, 60);
+    assert_eq!(env.amount(&fixture.vault).await, 940);
+
+    let (record, _) = tap_record_address(&env.program_id, &fixture.faucet, &alice.keypair.pubkey());
+    let record = env
+        .context
+        .banks_client
+        .get_account(record)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(record.owner, env.program_id);
+    let record = TapRecord::unpack(&record.data).unwrap();
+    assert_eq!(record.day, NOON / SECONDS_PER_DAY);
+    assert_eq!(record.tapped, 60);
+
+    let vault = env
+        .context
+        .banks_client
+        .get_account(fixture.vault)
+        .await
+        .unwrap()
+        .unwrap();
+    let (authority, _) = faucet_authority(&env.program_id, &fixture.faucet);
+    assert_eq!(Account::unpack(&vault.data).unwrap().owner, authority);
+}
+
+#[tokio::test]
+async fn test_daily_cap() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.user().await;
+    let bob = fixture.user().await;
+    let first = fixture.tap(&alice, 60);
+    let over_cap = fixture.tap(&alice, 41);
+    let rest = fixture.tap(&alice, 40);
+    let bob_tap = fixture.tap(&bob, DAILY_CAP);
+    let tomorrow = fixture.tap(&alice, DAILY_CAP);
+    let env = &mut fixture.env;
+
+    env.process(&[first], &[&alice.keypair]).await.unwrap();
+    assert_eq!(
+        env.process(&[over_cap], &[&alice.keypair]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(FaucetError::DailyCapExceeded as u32)
+        ))
+    );
+    env.process(&[rest], &[&alice.keypair]).await.unwrap();
+    assert_eq!(env.amount(&alice.account).await, DAILY_CAP);
+
+    // Caps are per address.
+    env.process(&[bob_tap], &[&bob.keypair]).await.unwrap();
+    assert_eq!(env.amount(&bob.account).await, DAILY_CAP);
+
+    env.set_time(NOON + SECONDS_PER_DAY / 2);
+    env.process(&[tomorrow], &[&alice.keypair]).await.unwrap();
+    assert_eq!(env.amount(&alice.account).await, 2 * DAILY_CAP);
+    assert_eq!(env.amount(&fixture.vault).await, 1_000 - 3 * DAILY_CAP);
+}
+
+#[tokio::test]
+async fn test_tap_rejects_foreign_record() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.user().await;
+    let bob = fixture.user().await;
+    // Alice taps against Bob's record, so her own would never fill up.
+    let mut instruction = fixture.tap(&alice, 10);
+    instruction.accounts[2].pubkey = fixture.tap(&bob, 10).accounts[2].pubkey;
+    let env = &mut fixture.env;
+
+    assert_eq!(
+        env.process(&[instruction], &[&alice.keypair]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(FaucetError::InvalidTapRecord as u32)
+        ))
+    );
+    assert_eq!(env.amount(&alice.account).await, 0);
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.user().await;
+    let instruction = tap(
+        &fixture.env.program_id,
+        &fixture.env.fake_bank_program_id,
+        &fixture.bank,
+        &alice.keypair.pubkey(),
+        &fixture.faucet,
+        &fixture.vault,
+        &alice.account,
+        60,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&alice.keypair]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidArgument
+        ))
+    );
+    let vault = fixture.vault;
+    assert_eq!(fixture.env.amount(&vault).await, 1_000);
+}
@@ -0,0 +1,118 @@
+use clap::Parser;
+use futures::StreamExt;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::HashMap, convert::TryFrom, process::exit};
+use tracing::{error, info, Level};
+use yellowstone_grpc_client::GeyserGrpcClient;
+use yellowstone_grpc_proto::prelude::{
+    subscribe_update::UpdateOneof, CommitmentLevel, SubscribeRequest,
+    SubscribeRequestFilterAccounts,
+};
+
+#[derive(Parser)]
+#[clap(
+    name = "bank-geyser",
+    about = "Print every update of the accounts owned by a program, one JSON object per line"
+)]
+struct Args {
+    /// Yellowstone gRPC endpoint
+    #[clap(long)]
+    endpoint: String,
+
+    /// Auth token for the endpoint
+    #[clap(long)]
+    x_token: Option<String>,
+
+    #[clap(long)]
+    program_id: Pubkey,
+
+    #[clap(long)]
+    verbose: bool,
+}
+
+/// One line of output. The client's `geyser` module reads these back.
+#[derive(Serialize)]
+struct AccountUpdate {
+    pubkey: String,
+    slot: u64,
+    /// The account data, base64 encoded.
+    data: String,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let args = Args::parse();
+    let level = if args.verbose {
+        Level::DEBUG
+    } else {
+        Level::INFO
+    };
+    // stdout carries the updates.
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .init();
+    if let Err(e) = stream_updates(args.endpoint, args.x_token, args.program_id).await {
+        error!(error = %e, "geyser stream ended");
+        exit(1);
+    }
+}
+
+async fn stream_updates(
+    endpoint: String,
+    x_token: Option<String>,
+    program_id: Pubkey,
+) -> Result<(), String> {
+    let mut client = GeyserGrpcClient::build_from_shared(endpoint)
+        .and_then(|builder| builder.x_token(x_token))
+        .map_err(|e| format!("invalid geyser endpoint: {}", e))?
+        .connect()
+        .await
+        .map_err(|e| format!("connect geyser failed: {}", e))?;
+
+    let mut accounts = HashMap::new();
+    accounts.insert(
+        "bank".to_string(),
+        SubscribeRequestFilterAccounts {
+            owner: vec![program_id.to_string()],
+            ..SubscribeRequestFilterAccounts::default()
+        },
+    );
+    let request = SubscribeRequest {
+        accounts,
+        commitment: Some(CommitmentLevel::Confirmed as i32),
+        ..SubscribeRequest::default()
+    };
+    let (_sink, mut stream) = client
+        .subscribe_with_request(Some(request))
+        .await
+        .map_err(|e| format!("geyser subscribe failed: {}", e))?;
+    info!(%program_id, "geyser subscription started");
+
+    while let Some(message) = stream.next().await {
+        let update = message.map_err(|e| format!("geyser stream error: {}", e))?;
+        let account_update = match update.update_oneof {
+            Some(UpdateOneof::Account(a)) => a,
+            _ => continue,
+        };
+        let info = match account_update.account {
+            Some(i) => i,
+            None => continue,
+        };
+        let key = match Pubkey::try_from(info.pubkey.as_slice()) {
+            Ok(k) => k,
+            Err(_) => continue,
+        };
+        let line = AccountUpdate {
+            pubkey: key.to_string(),
+            slot: account_update.slot,
+            data: base64::encode(&info.data),
+        };
+        match serde_json::to_string(&line) {
+            Ok(line) => println!("{}", line),
+            Err(e) => return Err(format!("serialize update failed: {}", e)),
+        }
+    }
+    Err("geyser stream closed".to_string())
+}
@@ -0,0 +1,35 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GovernanceError {
+    /// The vault authority isn't the governance's program address.
+    InvalidVaultAuthority,
+    /// The voter record isn't the program address of the governance and the voter.
+    InvalidVoterRecord,
+    /// The vote record isn't the program address of the proposal and the voter.
+    InvalidVoteRecord,
+    /// The voter had nothing deposited before the proposal's snapshot.
+    NoVotingWeight,
+    /// The voter already voted on the proposal.
+    AlreadyVoted,
+    /// The proposal's voting period is over.
+    VotingClosed,
+    /// The proposal's voting period isn't over yet.
+    VotingInProgress,
+    /// The proposal didn't get a majority and the quorum.
+    ProposalNotPassed,
+    /// The proposal has already been executed.
+    AlreadyExecuted,
+    /// The deposit backs a vote that is still open.
+    DepositLocked,
+    /// The voter has deposited less than the amount asked to withdraw.
+    InsufficientDeposit,
+    /// The proposed instruction has more accounts or data than a proposal can hold.
+    InstructionTooLarge,
+}
+
+impl From<GovernanceError> for ProgramError {
+    fn from(e: GovernanceError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
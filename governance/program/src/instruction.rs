@@ -0,0 +1,466 @@
+use crate::state::Proposal;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the vault authority, followed by the governance's address.
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"vault";
+/// Seed prefix of the governance signer, followed by the governance's address.
+pub const SIGNER_SEED: &[u8] = b"governance";
+/// Seed prefix of a voter record, followed by the governance's and the voter's addresses.
+pub const VOTER_RECORD_SEED: &[u8] = b"voter";
+/// Seed prefix of a vote record, followed by the proposal's and the voter's addresses.
+pub const VOTE_RECORD_SEED: &[u8] = b"vote";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GovernanceInstruction {
+    /// Sets up a governance over the bank's tokens, with proposals open for
+    /// `voting_slots` and passing with a majority of at least `quorum`: initializes the
+    /// vault, owned by the vault authority.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The governance, an uninitialized account owned by this program.
+    ///   1. `[writable]` The vault, an uninitialized account owned by the bank program.
+    ///   2. `[writable]` The bank of the voting tokens.
+    ///   3. `[writable]` The vault authority.
+    ///   4. `[]` The bank program, owner of the vault.
+    CreateGovernance { voting_slots: u64, quorum: u64 },
+
+    /// Moves `amount` from the voter's source account into the vault, adding it to their
+    /// voting weight. The voter record is created, at their expense, on first use.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The voter.
+    ///   1. `[]` The governance.
+    ///   2. `[writable]` The voter record.
+    ///   3. `[writable]` The source account.
+    ///   4. `[writable]` The vault.
+    ///   5. `[]` The bank of the voting tokens.
+    ///   6. `[]` The bank program, owner of the vault.
+    ///   7. `[]` The system program.
+    Deposit { amount: u64 },
+
+    /// Moves `amount` of the voter's deposit back out of the vault, once every vote cast
+    /// with it has closed.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The voter.
+    ///   1. `[]` The governance.
+    ///   2. `[writable]` The voter record.
+    ///   3. `[writable]` The vault.
+    ///   4. `[writable]` The destination account.
+    ///   5. `[writable]` The vault authority.
+    ///   6. `[]` The bank of the voting tokens.
+    ///   7. `[]` The bank program, owner of the vault.
+    Withdraw { amount: u64 },
+
+    /// Puts `instruction` to the vote, snapshotting deposits at the current slot. Its
+    /// accounts should name the governance signer as a signer where the target program
+    /// expects the governance's signature.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The proposer, a voter with a deposit.
+    ///   1. `[]` The governance.
+    ///   2. `[]` The proposer's voter record.
+    ///   3. `[writable]` The proposal, an uninitialized account owned by this program.
+    CreateProposal { instruction: Instruction },
+
+    /// Votes for or against a proposal with the voter's deposit at its snapshot, locking
+    /// the deposit until the vote closes. The vote record is created, at the voter's
+    /// expense, so nobody votes twice.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The voter.
+    ///   1. `[]` The governance.
+    ///   2. `[writable]` The voter record.
+    ///   3. `[writable]` The proposal.
+    ///   4. `[writable]` The vote record.
+    ///   5. `[]` The system program.
+    CastVote { approve: bool },
+
+    /// Invokes a passed proposal's instruction, signed by the governance signer, once its
+    /// voting period is over. Anyone can execute it.
+    ///
+    /// Accounts expected:
+    ///   0. `[]` The governance.
+    ///   1. `[writable]` The proposal.
+    ///   2. `[]` The program the proposed instruction targets.
+    ///   3. .. The proposed instruction's accounts, with the signer address not signing.
+    Execute,
+}
+
+impl GovernanceInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (voting_slots, rest) = Self::unpack_u64(rest)?;
+                let (quorum, _rest) = Self::unpack_u64(rest)?;
+                Self::CreateGovernance {
+                    voting_slots,
+                    quorum,
+                }
+            }
+            1 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Deposit { amount }
+            }
+            2 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Withdraw { amount }
+            }
+            3 => {
+                let (program_id, rest) = Self::unpack_pubkey(rest)?;
+                let (&count, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let mut accounts = Vec::with_capacity(count as usize);
+                let mut rest = rest;
+                for _ in 0..count {
+                    let (pubkey, tail) = Self::unpack_pubkey(rest)?;
+                    let flags = tail.get(..2).ok_or(InvalidInstructionData)?;
+                    accounts.push(AccountMeta {
+                        pubkey,
+                        is_signer: flags[0] != 0,
+                        is_writable: flags[1] != 0,
+                    });
+                    rest = &tail[2..];
+                }
+                let len = rest
+                    .get(..2)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u16::from_le_bytes)
+                    .ok_or(InvalidInstructionData)? as usize;
+                let data = rest.get(2..2 + len).ok_or(InvalidInstructionData)?;
+                Self::CreateProposal {
+                    instruction: Instruction {
+                        program_id,
+                        accounts,
+                        data: data.to_vec(),
+                    },
+                }
+            }
+            4 => {
+                let approve = match rest.first() {
+                    Some(0) => false,
+                    Some(1) => true,
+                    _ => return Err(InvalidInstructionData),
+                };
+                Self::CastVote { approve }
+            }
+            5 => Self::Execute,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self {
+            Self::CreateGovernance {
+                voting_slots,
+                quorum,
+            } => {
+                buf.push(0);
+                buf.extend_from_slice(&voting_slots.to_le_bytes());
+                buf.extend_from_slice(&quorum.to_le_bytes());
+            }
+            Self::Deposit { amount } => {
+                buf.push(1);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Withdraw { amount } => {
+                buf.push(2);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::CreateProposal { instruction } => {
+                buf.push(3);
+                buf.extend_from_slice(instruction.program_id.as_ref());
+                buf.push(instruction.accounts.len() as u8);
+                for meta in instruction.accounts.iter() {
+                    buf.extend_from_slice(meta.pubkey.as_ref());
+                    buf.push(meta.is_signer as u8);
+                    buf.push(meta.is_writable as u8);
+                }
+                buf.extend_from_slice(&(instruction.data.len() as u16).to_le_bytes());
+                buf.extend_from_slice(&instruction.data);
+            }
+            Self::CastVote { approve } => {
+                buf.push(4);
+                buf.push(*approve as u8);
+            }
+            Self::Execute => buf.push(5),
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+
+    fn unpack_pubkey(input: &[u8]) -> Result<(Pubkey, &[u8]), ProgramError> {
+        if input.len() < 32 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (key, rest) = input.split_at(32);
+        let key = Pubkey::new_from_array(key.try_into().unwrap());
+        Ok((key, rest))
+    }
+}
+
+/// Address and bump of the authority owning `governance`'s vault.
+pub fn vault_authority(governance_program_id: &Pubkey, governance: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VAULT_AUTHORITY_SEED, governance.as_ref()],
+        governance_program_id,
+    )
+}
+
+/// Address and bump of the key `governance` signs passed proposals with.
+pub fn governance_signer(governance_program_id: &Pubkey, governance: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SIGNER_SEED, governance.as_ref()], governance_program_id)
+}
+
+/// Address and bump of `voter`'s record in `governance`.
+pub fn voter_record_address(
+    governance_program_id: &Pubkey,
+    governance: &Pubkey,
+    voter: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VOTER_RECORD_SEED, governance.as_ref(), voter.as_ref()],
+        governance_program_id,
+    )
+}
+
+/// Address and bump of `voter`'s vote on `proposal`.
+pub fn vote_record_address(
+    governance_program_id: &Pubkey,
+    proposal: &Pubkey,
+    voter: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VOTE_RECORD_SEED, proposal.as_ref(), voter.as_ref()],
+        governance_program_id,
+    )
+}
+
+pub fn create_governance(
+    governance_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    governance: &Pubkey,
+    vault: &Pubkey,
+    bank: &Pubkey,
+    voting_slots: u64,
+    quorum: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = GovernanceInstruction::CreateGovernance {
+        voting_slots,
+        quorum,
+    }
+    .pack();
+    let (authority, _) = vault_authority(governance_program_id, governance);
+    let accounts = vec![
+        AccountMeta::new(*governance, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *governance_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn deposit(
+    governance_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    voter: &Pubkey,
+    governance: &Pubkey,
+    source_account: &Pubkey,
+    vault: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = GovernanceInstruction::Deposit { amount }.pack();
+    let (voter_record, _) = voter_record_address(governance_program_id, governance, voter);
+    let accounts = vec![
+        AccountMeta::new(*voter, true),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new(voter_record, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *governance_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw(
+    governance_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    voter: &Pubkey,
+    governance: &Pubkey,
+    vault: &Pubkey,
+    destination_account: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = GovernanceInstruction::Withdraw { amount }.pack();
+    let (voter_record, _) = voter_record_address(governance_program_id, governance, voter);
+    let (authority, _) = vault_authority(governance_program_id, governance);
+    let accounts = vec![
+        AccountMeta::new_readonly(*voter, true),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new(voter_record, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *governance_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn create_proposal(
+    governance_program_id: &Pubkey,
+    proposer: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    instruction: Instruction,
+) -> Result<Instruction, ProgramError> {
+    let data = GovernanceInstruction::CreateProposal { instruction }.pack();
+    let (voter_record, _) = voter_record_address(governance_program_id, governance, proposer);
+    let accounts = vec![
+        AccountMeta::new_readonly(*proposer, true),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new_readonly(voter_record, false),
+        AccountMeta::new(*proposal, false),
+    ];
+    Ok(Instruction {
+        program_id: *governance_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn cast_vote(
+    governance_program_id: &Pubkey,
+    voter: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    approve: bool,
+) -> Result<Instruction, ProgramError> {
+    let data = GovernanceInstruction::CastVote { approve }.pack();
+    let (voter_record, _) = voter_record_address(governance_program_id, governance, voter);
+    let (vote_record, _) = vote_record_address(governance_program_id, proposal, voter);
+    let accounts = vec![
+        AccountMeta::new(*voter, true),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new(voter_record, false),
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new(vote_record, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *governance_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Executes `proposal`, whose stored state is `state`.
+pub fn execute(
+    governance_program_id: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    state: &Proposal,
+) -> Result<Instruction, ProgramError> {
+    let data = GovernanceInstruction::Execute.pack();
+    let (signer, _) = governance_signer(governance_program_id, governance);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new_readonly(state.program_id, false),
+    ];
+    // The program signs for its signer address inside the CPI, not in the transaction.
+    accounts.extend(state.accounts.iter().map(|meta| AccountMeta {
+        is_signer: meta.is_signer && meta.pubkey != signer,
+        ..meta.clone()
+    }));
+    Ok(Instruction {
+        program_id: *governance_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            GovernanceInstruction::CreateGovernance {
+                voting_slots: 1_000,
+                quorum: u64::MAX,
+            },
+            GovernanceInstruction::Deposit { amount: 10 },
+            GovernanceInstruction::Withdraw { amount: 10 },
+            GovernanceInstruction::CreateProposal {
+                instruction: Instruction {
+                    program_id: Pubkey::new_unique(),
+                    accounts: vec![
+                        AccountMeta::new(Pubkey::new_unique(), false),
+                        AccountMeta::new_readonly(Pubkey::new_unique(), true),
+                    ],
+                    data: vec![4, 1, 2, 3],
+                },
+            },
+            GovernanceInstruction::CastVote { approve: true },
+            GovernanceInstruction::CastVote { approve: false },
+            GovernanceInstruction::Execute,
+        ];
+        for instruction in cases {
+            assert_eq!(
+                GovernanceInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            GovernanceInstruction::unpack(&[0, 1, 0, 0, 0, 0, 0, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            GovernanceInstruction::unpack(&[4, 2]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            GovernanceInstruction::unpack(&[6]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,495 @@
+use crate::{
+    error::GovernanceError,
+    instruction::{
+        GovernanceInstruction, SIGNER_SEED, VAULT_AUTHORITY_SEED, VOTER_RECORD_SEED,
+        VOTE_RECORD_SEED,
+    },
+    state::{Governance, Proposal, VoteRecord, VoterRecord},
+};
+use solana_bank::instruction as bank_instruction;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = GovernanceInstruction::unpack(input)?;
+
+        match instruction {
+            GovernanceInstruction::CreateGovernance {
+                voting_slots,
+                quorum,
+            } => {
+                msg!("Instruction: CreateGovernance");
+                Self::process_create_governance(program_id, accounts, voting_slots, quorum)
+            }
+            GovernanceInstruction::Deposit { amount } => {
+                msg!("Instruction: Deposit");
+                Self::process_deposit(program_id, accounts, amount)
+            }
+            GovernanceInstruction::Withdraw { amount } => {
+                msg!("Instruction: Withdraw");
+                Self::process_withdraw(program_id, accounts, amount)
+            }
+            GovernanceInstruction::CreateProposal { instruction } => {
+                msg!("Instruction: CreateProposal");
+                Self::process_create_proposal(program_id, accounts, instruction)
+            }
+            GovernanceInstruction::CastVote { approve } => {
+                msg!("Instruction: CastVote");
+                Self::process_cast_vote(program_id, accounts, approve)
+            }
+            GovernanceInstruction::Execute => {
+                msg!("Instruction: Execute");
+                Self::process_execute(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_create_governance(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        voting_slots: u64,
+        quorum: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let governance_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if governance_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if vault_info.owner != bank_program_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let mut governance = Governance::unpack_unchecked(&governance_info.data.borrow())?;
+        if governance.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        let (authority, vault_bump) = Pubkey::find_program_address(
+            &[VAULT_AUTHORITY_SEED, governance_info.key.as_ref()],
+            program_id,
+        );
+        if authority != *authority_info.key {
+            return Err(GovernanceError::InvalidVaultAuthority.into());
+        }
+        let (_, signer_bump) =
+            Pubkey::find_program_address(&[SIGNER_SEED, governance_info.key.as_ref()], program_id);
+
+        invoke_signed(
+            &bank_instruction::initialize_account(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                authority_info.key,
+            )?,
+            &[
+                bank_info.clone(),
+                vault_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[
+                VAULT_AUTHORITY_SEED,
+                governance_info.key.as_ref(),
+                &[vault_bump],
+            ]],
+        )?;
+
+        governance.is_initialized = true;
+        governance.bank = *bank_info.key;
+        governance.vault = *vault_info.key;
+        governance.voting_slots = voting_slots;
+        governance.quorum = quorum;
+        governance.vault_bump = vault_bump;
+        governance.signer_bump = signer_bump;
+        Governance::pack(governance, &mut governance_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_deposit(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let voter_info = next_account_info(account_info_iter)?;
+        let governance_info = next_account_info(account_info_iter)?;
+        let record_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !voter_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let governance = Self::load_governance(program_id, governance_info)?;
+        if governance.vault != *vault_info.key || vault_info.owner != bank_program_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let (record_key, record_bump) = Pubkey::find_program_address(
+            &[
+                VOTER_RECORD_SEED,
+                governance_info.key.as_ref(),
+                voter_info.key.as_ref(),
+            ],
+            program_id,
+        );
+        if record_key != *record_info.key {
+            return Err(GovernanceError::InvalidVoterRecord.into());
+        }
+        if record_info.data_is_empty() {
+            Self::create_record(
+                program_id,
+                voter_info,
+                record_info,
+                system_program_info,
+                VoterRecord::LEN,
+                &[
+                    VOTER_RECORD_SEED,
+                    governance_info.key.as_ref(),
+                    voter_info.key.as_ref(),
+                    &[record_bump],
+                ],
+            )?;
+        }
+
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                vault_info.key,
+                voter_info.key,
+                amount,
+            )?,
+            &[
+                source_info.clone(),
+                vault_info.clone(),
+                voter_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        let mut record = VoterRecord::unpack_unchecked(&record_info.data.borrow())?;
+        record.is_initialized = true;
+        record.amount = record
+            .amount
+            .checked_add(amount)
+            .ok_or(ProgramError::InvalidArgument)?;
+        record.deposit_slot = Clock::get()?.slot;
+        VoterRecord::pack(record, &mut record_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_withdraw(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let voter_info = next_account_info(account_info_iter)?;
+        let governance_info = next_account_info(account_info_iter)?;
+        let record_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !voter_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let governance = Self::load_governance(program_id, governance_info)?;
+        if governance.vault != *vault_info.key || vault_info.owner != bank_program_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let mut record =
+            Self::load_voter_record(program_id, governance_info, voter_info, record_info)?;
+        if Clock::get()?.slot < record.unlock_slot {
+            return Err(GovernanceError::DepositLocked.into());
+        }
+        record.amount = record
+            .amount
+            .checked_sub(amount)
+            .ok_or(GovernanceError::InsufficientDeposit)?;
+        let bump = [governance.vault_bump];
+        let signer_seeds: &[&[u8]] = &[VAULT_AUTHORITY_SEED, governance_info.key.as_ref(), &bump];
+        let authority = Pubkey::create_program_address(signer_seeds, program_id)
+            .map_err(|_| ProgramError::from(GovernanceError::InvalidVaultAuthority))?;
+        if authority != *authority_info.key {
+            return Err(GovernanceError::InvalidVaultAuthority.into());
+        }
+
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                destination_info.key,
+                authority_info.key,
+                amount,
+            )?,
+            &[
+                vault_info.clone(),
+                destination_info.clone(),
+                authority_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        VoterRecord::pack(record, &mut record_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_create_proposal(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        instruction: Instruction,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let proposer_info = next_account_info(account_info_iter)?;
+        let governance_info = next_account_info(account_info_iter)?;
+        let record_info = next_account_info(account_info_iter)?;
+        let proposal_info = next_account_info(account_info_iter)?;
+
+        if !proposer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let governance = Self::load_governance(program_id, governance_info)?;
+        let record =
+            Self::load_voter_record(program_id, governance_info, proposer_info, record_info)?;
+        if record.amount == 0 {
+            return Err(GovernanceError::NoVotingWeight.into());
+        }
+        if proposal_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut proposal = Proposal::unpack_unchecked(&proposal_info.data.borrow())?;
+        if proposal.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if !Proposal::fits(&instruction) {
+            return Err(GovernanceError::InstructionTooLarge.into());
+        }
+        let slot = Clock::get()?.slot;
+
+        proposal.is_initialized = true;
+        proposal.governance = *governance_info.key;
+        proposal.snapshot_slot = slot;
+        proposal.voting_end_slot = slot.saturating_add(governance.voting_slots);
+        proposal.yes_weight = 0;
+        proposal.no_weight = 0;
+        proposal.executed = false;
+        proposal.program_id = instruction.program_id;
+        proposal.accounts = instruction.accounts;
+        proposal.data = instruction.data;
+        Proposal::pack(proposal, &mut proposal_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_cast_vote(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        approve: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let voter_info = next_account_info(account_info_iter)?;
+        let governance_info = next_account_info(account_info_iter)?;
+        let record_info = next_account_info(account_info_iter)?;
+        let proposal_info = next_account_info(account_info_iter)?;
+        let vote_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !voter_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        Self::load_governance(program_id, governance_info)?;
+        let mut record =
+            Self::load_voter_record(program_id, governance_info, voter_info, record_info)?;
+        let mut proposal = Self::load_proposal(program_id, governance_info, proposal_info)?;
+        if Clock::get()?.slot >= proposal.voting_end_slot {
+            return Err(GovernanceError::VotingClosed.into());
+        }
+        let weight = record.weight_at(proposal.snapshot_slot);
+        if weight == 0 {
+            return Err(GovernanceError::NoVotingWeight.into());
+        }
+        let (vote_key, vote_bump) = Pubkey::find_program_address(
+            &[
+                VOTE_RECORD_SEED,
+                proposal_info.key.as_ref(),
+                voter_info.key.as_ref(),
+            ],
+            program_id,
+        );
+        if vote_key != *vote_info.key {
+            return Err(GovernanceError::InvalidVoteRecord.into());
+        }
+        if !vote_info.data_is_empty() {
+            return Err(GovernanceError::AlreadyVoted.into());
+        }
+        Self::create_record(
+            program_id,
+            voter_info,
+            vote_info,
+            system_program_info,
+            VoteRecord::LEN,
+            &[
+                VOTE_RECORD_SEED,
+                proposal_info.key.as_ref(),
+                voter_info.key.as_ref(),
+                &[vote_bump],
+            ],
+        )?;
+
+        proposal.vote(approve, weight);
+        // Withdrawing and depositing again elsewhere would count the same tokens twice.
+        record.unlock_slot = record.unlock_slot.max(proposal.voting_end_slot);
+        let vote = VoteRecord {
+            is_initialized: true,
+            approve,
+            weight,
+        };
+        VoteRecord::pack(vote, &mut vote_info.data.borrow_mut())?;
+        VoterRecord::pack(record, &mut record_info.data.borrow_mut())?;
+        Proposal::pack(proposal, &mut proposal_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_execute(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let governance_info = next_account_info(account_info_iter)?;
+        let proposal_info = next_account_info(account_info_iter)?;
+        let target_program_info = next_account_info(account_info_iter)?;
+        let instruction_infos = account_info_iter.as_slice();
+
+        let governance = Self::load_governance(program_id, governance_info)?;
+        let mut proposal = Self::load_proposal(program_id, governance_info, proposal_info)?;
+        if Clock::get()?.slot < proposal.voting_end_slot {
+            return Err(GovernanceError::VotingInProgress.into());
+        }
+        if !proposal.passed(governance.quorum) {
+            return Err(GovernanceError::ProposalNotPassed.into());
+        }
+        if proposal.program_id != *target_program_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let instruction = proposal.instruction();
+
+        // Marked before the call so the proposal can't run twice, even if the target
+        // program calls back into this one.
+        proposal.executed = true;
+        Proposal::pack(proposal, &mut proposal_info.data.borrow_mut())?;
+
+        let mut infos = instruction_infos.to_vec();
+        infos.push(target_program_info.clone());
+        invoke_signed(
+            &instruction,
+            &infos,
+            &[&[
+                SIGNER_SEED,
+                governance_info.key.as_ref(),
+                &[governance.signer_bump],
+            ]],
+        )
+    }
+
+    fn load_governance(
+        program_id: &Pubkey,
+        governance_info: &AccountInfo,
+    ) -> Result<Governance, ProgramError> {
+        if governance_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Governance::unpack(&governance_info.data.borrow())
+    }
+
+    fn load_voter_record(
+        program_id: &Pubkey,
+        governance_info: &AccountInfo,
+        voter_info: &AccountInfo,
+        record_info: &AccountInfo,
+    ) -> Result<VoterRecord, ProgramError> {
+        let (record_key, _) = Pubkey::find_program_address(
+            &[
+                VOTER_RECORD_SEED,
+                governance_info.key.as_ref(),
+                voter_info.key.as_ref(),
+            ],
+            program_id,
+        );
+        if record_key != *record_info.key {
+            return Err(GovernanceError::InvalidVoterRecord.into());
+        }
+        if record_info.owner != program_id {
+            return Err(GovernanceError::NoVotingWeight.into());
+        }
+        VoterRecord::unpack(&record_info.data.borrow())
+    }
+
+    /// Loads a proposal of the governance that hasn't been executed yet.
+    fn load_proposal(
+        program_id: &Pubkey,
+        governance_info: &AccountInfo,
+        proposal_info: &AccountInfo,
+    ) -> Result<Proposal, ProgramError> {
+        if proposal_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let proposal = Proposal::unpack(&proposal_info.data.borrow())?;
+        if proposal.governance != *governance_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        if proposal.executed {
+            return Err(GovernanceError::AlreadyExecuted.into());
+        }
+        Ok(proposal)
+    }
+
+    /// Creates the rent-exempt record at the program address of `seeds`, paid by `payer`.
+    fn create_record<'a>(
+        program_id: &Pubkey,
+        payer_info: &AccountInfo<'a>,
+        record_info: &AccountInfo<'a>,
+        system_program_info: &AccountInfo<'a>,
+        space: usize,
+        seeds: &[&[u8]],
+    ) -> ProgramResult {
+        invoke_signed(
+            &system_instruction::create_account(
+                payer_info.key,
+                record_info.key,
+                Rent::get()?.minimum_balance(space),
+                space as u64,
+                program_id,
+            ),
+            &[
+                payer_info.clone(),
+                record_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[seeds],
+        )
+    }
+}
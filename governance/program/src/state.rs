@@ -0,0 +1,394 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Most accounts a proposed instruction can take.
+pub const MAX_ACCOUNTS: usize = 8;
+/// Most data bytes a proposed instruction can carry.
+pub const MAX_DATA: usize = 128;
+
+/// Bytes of one stored account meta: the key, then the signer and writable flags.
+const META_LEN: usize = 34;
+
+/// Holders of `bank`'s tokens vote by depositing them into `vault`. Proposals that pass
+/// are signed by the governance signer, so it can own banks or accounts of its own.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Governance {
+    pub is_initialized: bool,
+    pub bank: Pubkey,
+    /// Bank account holding the deposits, owned by the vault authority.
+    pub vault: Pubkey,
+    /// Slots a proposal stays open for voting.
+    pub voting_slots: u64,
+    /// Least weight that must vote for a proposal for it to pass.
+    pub quorum: u64,
+    /// Bump seed of the vault authority address.
+    pub vault_bump: u8,
+    /// Bump seed of the governance signer address.
+    pub signer_bump: u8,
+}
+
+impl Sealed for Governance {}
+impl IsInitialized for Governance {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Governance {
+    const LEN: usize = 83;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 83];
+        let (is_initialized, bank, vault, voting_slots, quorum, vault_bump, signer_bump) =
+            array_refs![src, 1, 32, 32, 8, 8, 1, 1];
+        Ok(Governance {
+            is_initialized: unpack_bool(is_initialized[0])?,
+            bank: Pubkey::new_from_array(*bank),
+            vault: Pubkey::new_from_array(*vault),
+            voting_slots: u64::from_le_bytes(*voting_slots),
+            quorum: u64::from_le_bytes(*quorum),
+            vault_bump: vault_bump[0],
+            signer_bump: signer_bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 83];
+        let (is_initialized, bank, vault, voting_slots, quorum, vault_bump, signer_bump) =
+            mut_array_refs![dst, 1, 32, 32, 8, 8, 1, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        bank.copy_from_slice(self.bank.as_ref());
+        vault.copy_from_slice(self.vault.as_ref());
+        *voting_slots = self.voting_slots.to_le_bytes();
+        *quorum = self.quorum.to_le_bytes();
+        vault_bump[0] = self.vault_bump;
+        signer_bump[0] = self.signer_bump;
+    }
+}
+
+/// One holder's deposit into a governance, kept at the program address of the
+/// governance and the voter.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct VoterRecord {
+    pub is_initialized: bool,
+    pub amount: u64,
+    /// Slot of the latest deposit.
+    pub deposit_slot: u64,
+    /// The deposit can't be withdrawn before this slot, the end of the latest vote cast
+    /// with it.
+    pub unlock_slot: u64,
+}
+
+impl VoterRecord {
+    /// Weight of the deposit in a proposal snapshotted at `snapshot_slot`. Only a deposit
+    /// made before the snapshot counts, so a top-up moves the whole deposit past the
+    /// snapshots of proposals already open.
+    pub fn weight_at(&self, snapshot_slot: u64) -> u64 {
+        if self.deposit_slot < snapshot_slot {
+            self.amount
+        } else {
+            0
+        }
+    }
+}
+
+impl Sealed for VoterRecord {}
+impl IsInitialized for VoterRecord {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for VoterRecord {
+    const LEN: usize = 25;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 25];
+        let (is_initialized, amount, deposit_slot, unlock_slot) = array_refs![src, 1, 8, 8, 8];
+        Ok(VoterRecord {
+            is_initialized: unpack_bool(is_initialized[0])?,
+            amount: u64::from_le_bytes(*amount),
+            deposit_slot: u64::from_le_bytes(*deposit_slot),
+            unlock_slot: u64::from_le_bytes(*unlock_slot),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 25];
+        let (is_initialized, amount, deposit_slot, unlock_slot) = mut_array_refs![dst, 1, 8, 8, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        *amount = self.amount.to_le_bytes();
+        *deposit_slot = self.deposit_slot.to_le_bytes();
+        *unlock_slot = self.unlock_slot.to_le_bytes();
+    }
+}
+
+/// An instruction put to a governance's vote.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Proposal {
+    pub is_initialized: bool,
+    pub governance: Pubkey,
+    /// Slot the proposal was created at; deposits made from then on don't count.
+    pub snapshot_slot: u64,
+    /// First slot votes are no longer accepted and the proposal can execute.
+    pub voting_end_slot: u64,
+    pub yes_weight: u64,
+    pub no_weight: u64,
+    pub executed: bool,
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+impl Proposal {
+    /// Whether `instruction` fits into a proposal.
+    pub fn fits(instruction: &Instruction) -> bool {
+        instruction.accounts.len() <= MAX_ACCOUNTS && instruction.data.len() <= MAX_DATA
+    }
+
+    /// Adds a vote of `weight` for or against.
+    pub fn vote(&mut self, approve: bool, weight: u64) {
+        let tally = if approve {
+            &mut self.yes_weight
+        } else {
+            &mut self.no_weight
+        };
+        // Deposits share one bank's supply, which can't overflow a u64.
+        *tally = tally.saturating_add(weight);
+    }
+
+    /// Whether more weight voted for than against, and at least `quorum` for.
+    pub fn passed(&self, quorum: u64) -> bool {
+        self.yes_weight > self.no_weight && self.yes_weight >= quorum
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: self.accounts.clone(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl Sealed for Proposal {}
+impl IsInitialized for Proposal {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Proposal {
+    const LEN: usize = 501;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 501];
+        let (votes, instruction) = array_refs![src, 66, 435];
+        let (
+            is_initialized,
+            governance,
+            snapshot_slot,
+            voting_end_slot,
+            yes_weight,
+            no_weight,
+            executed,
+        ) = array_refs![votes, 1, 32, 8, 8, 8, 8, 1];
+        let (program_id, account_count, accounts, data_len, data) =
+            array_refs![instruction, 32, 1, META_LEN * MAX_ACCOUNTS, 2, MAX_DATA];
+        let account_count = account_count[0] as usize;
+        let data_len = u16::from_le_bytes(*data_len) as usize;
+        if account_count > MAX_ACCOUNTS || data_len > MAX_DATA {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let accounts = accounts
+            .chunks_exact(META_LEN)
+            .take(account_count)
+            .map(|meta| {
+                Ok(AccountMeta {
+                    pubkey: Pubkey::new_from_array(*array_ref![meta, 0, 32]),
+                    is_signer: unpack_bool(meta[32])?,
+                    is_writable: unpack_bool(meta[33])?,
+                })
+            })
+            .collect::<Result<_, ProgramError>>()?;
+        Ok(Proposal {
+            is_initialized: unpack_bool(is_initialized[0])?,
+            governance: Pubkey::new_from_array(*governance),
+            snapshot_slot: u64::from_le_bytes(*snapshot_slot),
+            voting_end_slot: u64::from_le_bytes(*voting_end_slot),
+            yes_weight: u64::from_le_bytes(*yes_weight),
+            no_weight: u64::from_le_bytes(*no_weight),
+            executed: unpack_bool(executed[0])?,
+            program_id: Pubkey::new_from_array(*program_id),
+            accounts,
+            data: data[..data_len].to_vec(),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 501];
+        let (votes, instruction) = mut_array_refs![dst, 66, 435];
+        let (
+            is_initialized,
+            governance,
+            snapshot_slot,
+            voting_end_slot,
+            yes_weight,
+            no_weight,
+            executed,
+        ) = mut_array_refs![votes, 1, 32, 8, 8, 8, 8, 1];
+        let (program_id, account_count, accounts, data_len, data) =
+            mut_array_refs![instruction, 32, 1, META_LEN * MAX_ACCOUNTS, 2, MAX_DATA];
+        is_initialized[0] = self.is_initialized as u8;
+        governance.copy_from_slice(self.governance.as_ref());
+        *snapshot_slot = self.snapshot_slot.to_le_bytes();
+        *voting_end_slot = self.voting_end_slot.to_le_bytes();
+        *yes_weight = self.yes_weight.to_le_bytes();
+        *no_weight = self.no_weight.to_le_bytes();
+        executed[0] = self.executed as u8;
+        program_id.copy_from_slice(self.program_id.as_ref());
+        account_count[0] = self.accounts.len() as u8;
+        accounts.fill(0);
+        for (dst, meta) in accounts
+            .chunks_exact_mut(META_LEN)
+            .zip(self.accounts.iter())
+        {
+            dst[..32].copy_from_slice(meta.pubkey.as_ref());
+            dst[32] = meta.is_signer as u8;
+            dst[33] = meta.is_writable as u8;
+        }
+        *data_len = (self.data.len() as u16).to_le_bytes();
+        data.fill(0);
+        data[..self.data.len()].copy_from_slice(&self.data);
+    }
+}
+
+/// One voter's vote on a proposal, kept at the program address of the proposal and the
+/// voter so nobody votes twice.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct VoteRecord {
+    pub is_initialized: bool,
+    pub approve: bool,
+    pub weight: u64,
+}
+
+impl Sealed for VoteRecord {}
+impl IsInitialized for VoteRecord {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for VoteRecord {
+    const LEN: usize = 10;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 10];
+        let (is_initialized, approve, weight) = array_refs![src, 1, 1, 8];
+        Ok(VoteRecord {
+            is_initialized: unpack_bool(is_initialized[0])?,
+            approve: unpack_bool(approve[0])?,
+            weight: u64::from_le_bytes(*weight),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 10];
+        let (is_initialized, approve, weight) = mut_array_refs![dst, 1, 1, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        approve[0] = self.approve as u8;
+        *weight = self.weight.to_le_bytes();
+    }
+}
+
+fn unpack_bool(byte: u8) -> Result<bool, ProgramError> {
+    match byte {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let governance = Governance {
+            is_initialized: true,
+            bank: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            voting_slots: 1_000,
+            quorum: u64::MAX,
+            vault_bump: 255,
+            signer_bump: 251,
+        };
+        let mut packed = vec![0u8; Governance::LEN];
+        Governance::pack(governance, &mut packed).unwrap();
+        assert_eq!(Governance::unpack(&packed), Ok(governance));
+
+        let record = VoterRecord {
+            is_initialized: true,
+            amount: 7,
+            deposit_slot: 10,
+            unlock_slot: 1_010,
+        };
+        let mut packed = vec![0u8; VoterRecord::LEN];
+        VoterRecord::pack(record, &mut packed).unwrap();
+        assert_eq!(VoterRecord::unpack(&packed), Ok(record));
+
+        let proposal = Proposal {
+            is_initialized: true,
+            governance: Pubkey::new_unique(),
+            snapshot_slot: 10,
+            voting_end_slot: 1_010,
+            yes_weight: 3,
+            no_weight: u64::MAX,
+            executed: false,
+            program_id: Pubkey::new_unique(),
+            accounts: vec![
+                AccountMeta::new(Pubkey::new_unique(), false),
+                AccountMeta::new_readonly(Pubkey::new_unique(), true),
+            ],
+            data: vec![5; MAX_DATA],
+        };
+        let mut packed = vec![0u8; Proposal::LEN];
+        Proposal::pack(proposal.clone(), &mut packed).unwrap();
+        assert_eq!(Proposal::unpack(&packed), Ok(proposal));
+
+        let vote = VoteRecord {
+            is_initialized: true,
+            approve: true,
+            weight: 7,
+        };
+        let mut packed = vec![0u8; VoteRecord::LEN];
+        VoteRecord::pack(vote, &mut packed).unwrap();
+        assert_eq!(VoteRecord::unpack(&packed), Ok(vote));
+    }
+
+    #[test]
+    fn test_weight_at() {
+        let record = VoterRecord {
+            is_initialized: true,
+            amount: 50,
+            deposit_slot: 10,
+            unlock_slot: 0,
+        };
+        assert_eq!(record.weight_at(11), 50);
+        // A deposit in the snapshot's own slot may have landed after the proposal.
+        assert_eq!(record.weight_at(10), 0);
+        assert_eq!(record.weight_at(9), 0);
+    }
+
+    #[test]
+    fn test_passed() {
+        let mut proposal = Proposal::default();
+        proposal.vote(true, 40);
+        proposal.vote(false, 40);
+        assert!(!proposal.passed(0));
+        proposal.vote(true, 1);
+        assert!(proposal.passed(41));
+        assert!(!proposal.passed(42));
+    }
+}
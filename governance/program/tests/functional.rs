@@ -0,0 +1,421 @@
+use solana_bank::{
+    instruction::{initialize_account, initialize_bank, mint_to},
+    state::{Account, Bank},
+    test_utils::program_test::Env,
+};
+use solana_governance::{
+    error::GovernanceError,
+    instruction::{
+        cast_vote, create_governance, create_proposal, deposit, execute, governance_signer,
+        vault_authority, voter_record_address, withdraw,
+    },
+    processor::Processor,
+    state::{Governance, Proposal, VoterRecord},
+};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// Slots a proposal stays open, and the weight that must vote for it.
+const VOTING_SLOTS: u64 = 100;
+const QUORUM: u64 = 50;
+/// Slots voters deposit at and proposals are created at.
+const DEPOSIT: u64 = 10;
+const SNAPSHOT: u64 = 20;
+
+/// A holder of 100 voting tokens in `account`, with lamports for their records.
+struct Voter {
+    owner: Keypair,
+    account: Pubkey,
+}
+
+/// A governance over the tokens of a bank owned by the payer.
+struct Fixture {
+    env: Env,
+    bank: Pubkey,
+    governance: Pubkey,
+    vault: Pubkey,
+    voters: Vec<Voter>,
+}
+
+impl Fixture {
+    /// Opens the governance with three voters, of which the first two deposit 60 and 30
+    /// tokens at slot `DEPOSIT`.
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_governance", processor!(Processor::process)).await;
+        let bank = env.create_bank().await;
+        let governance = Keypair::new();
+        let vault = Keypair::new();
+        let governance_program_id = env.program_id;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&governance, Governance::LEN, &governance_program_id)
+            .await;
+        env.create_account(&vault, Account::LEN, &bank_program_id)
+            .await;
+        let instruction = create_governance(
+            &governance_program_id,
+            &bank_program_id,
+            &governance.pubkey(),
+            &vault.pubkey(),
+            &bank,
+            VOTING_SLOTS,
+            QUORUM,
+        )
+        .unwrap();
+        env.process(&[instruction], &[]).await.unwrap();
+
+        let mut voters = vec![];
+        for _ in 0..3 {
+            let owner = Keypair::new();
+            let account = env.create_bank_account(&bank, &owner, 100).await;
+            let fund = system_instruction::transfer(
+                &env.context.payer.pubkey(),
+                &owner.pubkey(),
+                1_000_000_000,
+            );
+            env.process(&[fund], &[]).await.unwrap();
+            voters.push(Voter { owner, account });
+        }
+        let mut fixture = Fixture {
+            env,
+            bank,
+            governance: governance.pubkey(),
+            vault: vault.pubkey(),
+            voters,
+        };
+        fixture.env.set_slot(DEPOSIT).await;
+        fixture.deposit(0, 60).await.unwrap();
+        fixture.deposit(1, 30).await.unwrap();
+        fixture
+    }
+
+    async fn deposit(&mut self, voter: usize, amount: u64) -> Result<(), TransactionError> {
+        let voter = &self.voters[voter];
+        let instruction = deposit(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &voter.owner.pubkey(),
+            &self.governance,
+            &voter.account,
+            &self.vault,
+            amount,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[&voter.owner]).await
+    }
+
+    async fn withdraw(&mut self, voter: usize, amount: u64) -> Result<(), TransactionError> {
+        let instruction = self.withdraw_instruction(voter, amount);
+        let voter = &self.voters[voter];
+        self.env.process(&[instruction], &[&voter.owner]).await
+    }
+
+    fn withdraw_instruction(&self, voter: usize, amount: u64) -> Instruction {
+        let voter = &self.voters[voter];
+        withdraw(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &voter.owner.pubkey(),
+            &self.governance,
+            &self.vault,
+            &voter.account,
+            amount,
+        )
+        .unwrap()
+    }
+
+    /// Has `proposer` put `instruction` to the vote at the current slot.
+    async fn propose(&mut self, proposer: usize, instruction: Instruction) -> Pubkey {
+        let proposal = Keypair::new();
+        let governance_program_id = self.env.program_id;
+        self.env
+            .create_account(&proposal, Proposal::LEN, &governance_program_id)
+            .await;
+        let proposer = &self.voters[proposer];
+        let instruction = create_proposal(
+            &governance_program_id,
+            &proposer.owner.pubkey(),
+            &self.governance,
+            &proposal.pubkey(),
+            instruction,
+        )
+        .unwrap();
+        self.env
+            .process(&[instruction], &[&proposer.owner])
+            .await
+            .unwrap();
+        proposal.pubkey()
+    }
+
+    async fn vote(
+        &mut self,
+        voter: usize,
+        proposal: &Pubkey,
+        approve: bool,
+    ) -> Result<(), TransactionError> {
+        let voter = &self.voters[voter];
+        let instruction = cast_vote(
+            &self.env.program_id,
+            &voter.owner.pubkey(),
+            &self.governance,
+            proposal,
+            approve,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[&voter.owner]).await
+    }
+
+    async fn execute(&mut self, proposal: &Pubkey) -> Result<(), TransactionError> {
+        let instruction = self.execute_instruction(proposal).await;
+        self.env.process(&[instruction], &[]).await
+    }
+
+    async fn execute_instruction(&mut self, proposal: &Pubkey) -> Instruction {
+        let state = self.env.unpack::<Proposal>(proposal).await;
+        execute(&self.env.program_id, &self.governance, proposal, &state).unwrap()
+    }
+
+    async fn voter_record(&mut self, voter: usize) -> VoterRecord {
+        let (record, _) = voter_record_address(
+            &self.env.program_id,
+            &self.governance,
+            &self.voters[voter].owner.pubkey(),
+        );
+        self.env.unpack(&record).await
+    }
+
+    /// An uninitialized bank account for a proposal to open.
+    async fn new_bank(&mut self) -> Pubkey {
+        let bank = Keypair::new();
+        let bank_program_id = self.env.bank_program_id;
+        self.env
+            .create_account(&bank, Bank::LEN, &bank_program_id)
+            .await;
+        bank.pubkey()
+    }
+}
+
+fn custom(error: GovernanceError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_deposit_and_withdraw() {
+    let mut fixture = Fixture::new().await;
+    let governance_program_id = fixture.env.program_id;
+    let governance = fixture.env.unpack::<Governance>(&fixture.governance).await;
+    let (authority, _) = vault_authority(&governance_program_id, &fixture.governance);
+    let vault = fixture.env.unpack::<Account>(&fixture.vault).await;
+    assert_eq!(governance.vault, fixture.vault);
+    assert_eq!(vault.owner, authority);
+    assert_eq!(vault.amount, 90);
+    let record = fixture.voter_record(0).await;
+    assert_eq!((record.amount, record.deposit_slot), (60, DEPOSIT));
+
+    fixture.deposit(0, 5).await.unwrap();
+    assert_eq!(fixture.voter_record(0).await.amount, 65);
+    assert_eq!(
+        fixture.withdraw(0, 66).await,
+        custom(GovernanceError::InsufficientDeposit)
+    );
+    fixture.withdraw(0, 45).await.unwrap();
+    assert_eq!(fixture.voter_record(0).await.amount, 20);
+    let account = fixture.voters[0].account;
+    assert_eq!(fixture.env.amount(&account).await, 80);
+    assert_eq!(fixture.env.amount(&fixture.vault).await, 50);
+}
+
+#[tokio::test]
+async fn test_vote_and_execute() {
+    let mut fixture = Fixture::new().await;
+    let governance_program_id = fixture.env.program_id;
+    let bank_program_id = fixture.env.bank_program_id;
+    let (signer, _) = governance_signer(&governance_program_id, &fixture.governance);
+    let bank = fixture.new_bank().await;
+    let open = initialize_bank(&bank_program_id, &bank, &signer, 0).unwrap();
+    fixture.env.set_slot(SNAPSHOT).await;
+    let proposal = fixture.propose(0, open).await;
+    let state = fixture.env.unpack::<Proposal>(&proposal).await;
+    assert_eq!(state.snapshot_slot, SNAPSHOT);
+    assert_eq!(state.voting_end_slot, SNAPSHOT + VOTING_SLOTS);
+
+    fixture.vote(0, &proposal, true).await.unwrap();
+    fixture.vote(1, &proposal, false).await.unwrap();
+    assert_eq!(
+        fixture.vote(0, &proposal, false).await,
+        custom(GovernanceError::AlreadyVoted)
+    );
+    // Tokens deposited after the snapshot don't count.
+    fixture.deposit(2, 100).await.unwrap();
+    assert_eq!(
+        fixture.vote(2, &proposal, true).await,
+        custom(GovernanceError::NoVotingWeight)
+    );
+    assert_eq!(
+        fixture.withdraw(1, 30).await,
+        custom(GovernanceError::DepositLocked)
+    );
+    assert_eq!(
+        fixture.execute(&proposal).await,
+        custom(GovernanceError::VotingInProgress)
+    );
+    let state = fixture.env.unpack::<Proposal>(&proposal).await;
+    assert_eq!((state.yes_weight, state.no_weight), (60, 30));
+
+    fixture.env.set_slot(SNAPSHOT + VOTING_SLOTS).await;
+    assert_eq!(
+        fixture.vote(1, &proposal, true).await,
+        custom(GovernanceError::VotingClosed)
+    );
+    // Bob's deposit unlocks as the vote closes. Withdrawing it in the same transaction
+    // also keeps this one from repeating the failed execution above.
+    let instructions = [
+        fixture.execute_instruction(&proposal).await,
+        fixture.withdraw_instruction(1, 30),
+    ];
+    fixture
+        .env
+        .process(&instructions, &[&fixture.voters[1].owner])
+        .await
+        .unwrap();
+    let opened = fixture.env.unpack::<Bank>(&bank).await;
+    assert_eq!(opened.bank_owner, signer);
+    assert!(fixture.env.unpack::<Proposal>(&proposal).await.executed);
+    assert_eq!(fixture.voter_record(1).await.amount, 0);
+}
+
+#[tokio::test]
+async fn test_proposal_not_passed() {
+    let mut fixture = Fixture::new().await;
+    let bank_program_id = fixture.env.bank_program_id;
+    let (signer, _) = governance_signer(&fixture.env.program_id, &fixture.governance);
+    let bank = fixture.new_bank().await;
+    let open = initialize_bank(&bank_program_id, &bank, &signer, 0).unwrap();
+    fixture.env.set_slot(SNAPSHOT).await;
+    let short_of_quorum = fixture.propose(1, open.clone()).await;
+    let outvoted = fixture.propose(1, open).await;
+
+    fixture.vote(1, &short_of_quorum, true).await.unwrap();
+    fixture.vote(1, &outvoted, true).await.unwrap();
+    fixture.vote(0, &outvoted, false).await.unwrap();
+
+    fixture.env.set_slot(SNAPSHOT + VOTING_SLOTS).await;
+    assert_eq!(
+        fixture.execute(&short_of_quorum).await,
+        custom(GovernanceError::ProposalNotPassed)
+    );
+    assert_eq!(
+        fixture.execute(&outvoted).await,
+        custom(GovernanceError::ProposalNotPassed)
+    );
+    let bank = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(bank)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!Bank::unpack_unchecked(&bank.data).unwrap().is_opened);
+}
+
+#[tokio::test]
+async fn test_mint_through_governance() {
+    let mut fixture = Fixture::new().await;
+    let bank_program_id = fixture.env.bank_program_id;
+    let (signer, _) = governance_signer(&fixture.env.program_id, &fixture.governance);
+    let bank = fixture.new_bank().await;
+    let open = initialize_bank(&bank_program_id, &bank, &signer, 0).unwrap();
+    fixture.env.set_slot(SNAPSHOT).await;
+    let proposal = fixture.propose(0, open).await;
+    fixture.vote(0, &proposal, true).await.unwrap();
+    fixture.env.set_slot(SNAPSHOT + VOTING_SLOTS).await;
+    fixture.execute(&proposal).await.unwrap();
+
+    let holder = Keypair::new();
+    let account = Keypair::new();
+    fixture
+        .env
+        .create_account(&account, Account::LEN, &bank_program_id)
+        .await;
+    let instruction =
+        initialize_account(&bank_program_id, &bank, &account.pubkey(), &holder.pubkey()).unwrap();
+    fixture
+        .env
+        .process(&[instruction], &[&holder])
+        .await
+        .unwrap();
+
+    let mint = mint_to(&bank_program_id, &bank, &account.pubkey(), &signer, 500).unwrap();
+    let proposal = fixture.propose(1, mint).await;
+    fixture.vote(0, &proposal, true).await.unwrap();
+    fixture.vote(1, &proposal, true).await.unwrap();
+    fixture.env.set_slot(SNAPSHOT + 2 * VOTING_SLOTS).await;
+    fixture.execute(&proposal).await.unwrap();
+    assert_eq!(fixture.env.amount(&account.pubkey()).await, 500);
+    let state = fixture.env.unpack::<Proposal>(&proposal).await;
+    assert_eq!((state.yes_weight, state.no_weight), (90, 0));
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    let governance_program_id = fixture.env.program_id;
+    let fake_bank_program_id = fixture.env.fake_bank_program_id;
+    let invalid = Err(TransactionError::InstructionError(
+        0,
+        InstructionError::InvalidArgument,
+    ));
+
+    // Through a program posing as the bank, a deposit would add voting weight without any
+    // tokens moving into the vault.
+    let voter = &fixture.voters[1];
+    let instruction = deposit(
+        &governance_program_id,
+        &fake_bank_program_id,
+        &fixture.bank,
+        &voter.owner.pubkey(),
+        &fixture.governance,
+        &voter.account,
+        &fixture.vault,
+        100,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&voter.owner]).await,
+        invalid
+    );
+    assert_eq!(fixture.voter_record(1).await.amount, 30);
+
+    // Nor does the vault authority sign for it.
+    let voter = &fixture.voters[0];
+    let instruction = withdraw(
+        &governance_program_id,
+        &fake_bank_program_id,
+        &fixture.bank,
+        &voter.owner.pubkey(),
+        &fixture.governance,
+        &fixture.vault,
+        &voter.account,
+        60,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&voter.owner]).await,
+        invalid
+    );
+    assert_eq!(fixture.voter_record(0).await.amount, 60);
+}
@@ -0,0 +1,27 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InheritanceError {
+    /// The switch isn't at the program address for its owner, beneficiary and bank.
+    InvalidSwitch,
+    /// The account isn't the switch's vault.
+    InvalidVault,
+    /// A switch needs a timeout above zero.
+    InvalidTimeout,
+    /// Withdrawals can't be empty.
+    InvalidAmount,
+    /// The signer doesn't own the switch.
+    NotOwner,
+    /// The signer isn't the switch's beneficiary.
+    NotBeneficiary,
+    /// The owner checked in within the timeout.
+    OwnerActive,
+    /// The beneficiary has already claimed the vault.
+    AlreadyClaimed,
+}
+
+impl From<InheritanceError> for ProgramError {
+    fn from(e: InheritanceError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,253 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of a switch, followed by the owner's, the beneficiary's and the bank's
+/// addresses.
+pub const SWITCH_SEED: &[u8] = b"inheritance";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum InheritanceInstruction {
+    /// Creates the owner's switch for a beneficiary and a bank, armed as of now, and
+    /// initializes its vault, owned by the switch. The owner escrows tokens by
+    /// transferring them into the vault.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The owner, paying for the switch.
+    ///   1. `[writable]` The switch, the program address for the owner, the beneficiary
+    ///      and the bank.
+    ///   2. `[]` The beneficiary.
+    ///   3. `[writable]` The vault, an uninitialized account owned by the bank program.
+    ///   4. `[writable]` The bank.
+    ///   5. `[]` The bank program, owner of the vault.
+    ///   6. `[]` The system program.
+    CreateSwitch { timeout: i64 },
+
+    /// Checks in, pushing the deadline back to `timeout` seconds from now. Still works
+    /// past the deadline, as long as the beneficiary hasn't claimed.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The owner.
+    ///   1. `[writable]` The switch.
+    Ping,
+
+    /// Pays `amount` out of the vault to the owner, which also counts as a ping.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The owner.
+    ///   1. `[writable]` The switch.
+    ///   2. `[writable]` The vault.
+    ///   3. `[writable]` The destination account.
+    ///   4. `[]` The switch's bank.
+    ///   5. `[]` The bank program, owner of the vault.
+    Withdraw { amount: u64 },
+
+    /// Pays everything in the vault to the beneficiary once the owner has been silent
+    /// past the deadline, and spends the switch.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The beneficiary.
+    ///   1. `[writable]` The switch.
+    ///   2. `[writable]` The vault.
+    ///   3. `[writable]` The destination account.
+    ///   4. `[]` The switch's bank.
+    ///   5. `[]` The bank program, owner of the vault.
+    Claim,
+}
+
+impl InheritanceInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (timeout, _rest) = Self::unpack_u64(rest)?;
+                Self::CreateSwitch {
+                    timeout: timeout as i64,
+                }
+            }
+            1 => Self::Ping,
+            2 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Withdraw { amount }
+            }
+            3 => Self::Claim,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(9);
+        match *self {
+            Self::CreateSwitch { timeout } => {
+                buf.push(0);
+                buf.extend_from_slice(&timeout.to_le_bytes());
+            }
+            Self::Ping => buf.push(1),
+            Self::Withdraw { amount } => {
+                buf.push(2);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Claim => buf.push(3),
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of `owner`'s switch for `beneficiary` in `bank`.
+pub fn switch_address(
+    inheritance_program_id: &Pubkey,
+    owner: &Pubkey,
+    beneficiary: &Pubkey,
+    bank: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SWITCH_SEED,
+            owner.as_ref(),
+            beneficiary.as_ref(),
+            bank.as_ref(),
+        ],
+        inheritance_program_id,
+    )
+}
+
+pub fn create_switch(
+    inheritance_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    owner: &Pubkey,
+    beneficiary: &Pubkey,
+    vault: &Pubkey,
+    bank: &Pubkey,
+    timeout: i64,
+) -> Result<Instruction, ProgramError> {
+    let data = InheritanceInstruction::CreateSwitch { timeout }.pack();
+    let (switch, _) = switch_address(inheritance_program_id, owner, beneficiary, bank);
+    let accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(switch, false),
+        AccountMeta::new_readonly(*beneficiary, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *inheritance_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn ping(
+    inheritance_program_id: &Pubkey,
+    owner: &Pubkey,
+    switch: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = InheritanceInstruction::Ping.pack();
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*switch, false),
+    ];
+    Ok(Instruction {
+        program_id: *inheritance_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw(
+    inheritance_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    owner: &Pubkey,
+    switch: &Pubkey,
+    vault: &Pubkey,
+    destination_account: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = InheritanceInstruction::Withdraw { amount }.pack();
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*switch, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *inheritance_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn claim(
+    inheritance_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    beneficiary: &Pubkey,
+    switch: &Pubkey,
+    vault: &Pubkey,
+    destination_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = InheritanceInstruction::Claim.pack();
+    let accounts = vec![
+        AccountMeta::new_readonly(*beneficiary, true),
+        AccountMeta::new(*switch, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *inheritance_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            InheritanceInstruction::CreateSwitch { timeout: 2_592_000 },
+            InheritanceInstruction::Ping,
+            InheritanceInstruction::Withdraw { amount: u64::MAX },
+            InheritanceInstruction::Claim,
+        ];
+        for instruction in cases {
+            assert_eq!(
+                InheritanceInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            InheritanceInstruction::unpack(&[0, 1, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            InheritanceInstruction::unpack(&[4]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,290 @@
+use crate::{
+    error::InheritanceError,
+    instruction::{InheritanceInstruction, SWITCH_SEED},
+    state::Switch,
+};
+use solana_bank::{instruction as bank_instruction, state::Account as BankAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = InheritanceInstruction::unpack(input)?;
+
+        match instruction {
+            InheritanceInstruction::CreateSwitch { timeout } => {
+                msg!("Instruction: CreateSwitch");
+                Self::process_create_switch(program_id, accounts, timeout)
+            }
+            InheritanceInstruction::Ping => {
+                msg!("Instruction: Ping");
+                Self::process_ping(program_id, accounts)
+            }
+            InheritanceInstruction::Withdraw { amount } => {
+                msg!("Instruction: Withdraw");
+                Self::process_withdraw(program_id, accounts, amount)
+            }
+            InheritanceInstruction::Claim => {
+                msg!("Instruction: Claim");
+                Self::process_claim(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_create_switch(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        timeout: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let switch_info = next_account_info(account_info_iter)?;
+        let beneficiary_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let (switch, bump) = Pubkey::find_program_address(
+            &[
+                SWITCH_SEED,
+                owner_info.key.as_ref(),
+                beneficiary_info.key.as_ref(),
+                bank_info.key.as_ref(),
+            ],
+            program_id,
+        );
+        if switch != *switch_info.key {
+            return Err(InheritanceError::InvalidSwitch.into());
+        }
+        if !switch_info.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if timeout <= 0 {
+            return Err(InheritanceError::InvalidTimeout.into());
+        }
+        if vault_info.owner != bank_program_info.key {
+            return Err(InheritanceError::InvalidVault.into());
+        }
+        let signer_seeds: &[&[u8]] = &[
+            SWITCH_SEED,
+            owner_info.key.as_ref(),
+            beneficiary_info.key.as_ref(),
+            bank_info.key.as_ref(),
+            &[bump],
+        ];
+
+        invoke_signed(
+            &system_instruction::create_account(
+                owner_info.key,
+                switch_info.key,
+                Rent::get()?.minimum_balance(Switch::LEN),
+                Switch::LEN as u64,
+                program_id,
+            ),
+            &[
+                owner_info.clone(),
+                switch_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        invoke_signed(
+            &bank_instruction::initialize_account(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                switch_info.key,
+            )?,
+            &[
+                bank_info.clone(),
+                vault_info.clone(),
+                switch_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        let switch = Switch {
+            is_initialized: true,
+            owner: *owner_info.key,
+            beneficiary: *beneficiary_info.key,
+            bank: *bank_info.key,
+            vault: *vault_info.key,
+            timeout,
+            last_ping: Clock::get()?.unix_timestamp,
+            claimed: false,
+            bump,
+        };
+        Switch::pack(switch, &mut switch_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_ping(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let switch_info = next_account_info(account_info_iter)?;
+
+        let mut switch = Self::load_owned_switch(program_id, owner_info, switch_info)?;
+        switch.last_ping = Clock::get()?.unix_timestamp;
+        Switch::pack(switch, &mut switch_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_withdraw(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let switch_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        let mut switch = Self::load_owned_switch(program_id, owner_info, switch_info)?;
+        if switch.vault != *vault_info.key || vault_info.owner != bank_program_info.key {
+            return Err(InheritanceError::InvalidVault.into());
+        }
+        if amount == 0 {
+            return Err(InheritanceError::InvalidAmount.into());
+        }
+
+        Self::pay_out(
+            &switch,
+            switch_info,
+            vault_info,
+            destination_info,
+            bank_info,
+            bank_program_info,
+            amount,
+        )?;
+
+        switch.last_ping = Clock::get()?.unix_timestamp;
+        Switch::pack(switch, &mut switch_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_claim(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let beneficiary_info = next_account_info(account_info_iter)?;
+        let switch_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !beneficiary_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut switch = Self::load_switch(program_id, switch_info)?;
+        if switch.beneficiary != *beneficiary_info.key {
+            return Err(InheritanceError::NotBeneficiary.into());
+        }
+        if switch.claimed {
+            return Err(InheritanceError::AlreadyClaimed.into());
+        }
+        if !switch.has_expired(Clock::get()?.unix_timestamp) {
+            return Err(InheritanceError::OwnerActive.into());
+        }
+        if switch.vault != *vault_info.key || vault_info.owner != bank_program_info.key {
+            return Err(InheritanceError::InvalidVault.into());
+        }
+
+        let amount = BankAccount::unpack(&vault_info.data.borrow())?.amount;
+        if amount > 0 {
+            Self::pay_out(
+                &switch,
+                switch_info,
+                vault_info,
+                destination_info,
+                bank_info,
+                bank_program_info,
+                amount,
+            )?;
+        }
+
+        switch.claimed = true;
+        Switch::pack(switch, &mut switch_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn load_switch(program_id: &Pubkey, switch_info: &AccountInfo) -> Result<Switch, ProgramError> {
+        if switch_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Switch::unpack(&switch_info.data.borrow())
+    }
+
+    /// Loads a switch its owner signed for, which only works until it has been claimed.
+    fn load_owned_switch(
+        program_id: &Pubkey,
+        owner_info: &AccountInfo,
+        switch_info: &AccountInfo,
+    ) -> Result<Switch, ProgramError> {
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let switch = Self::load_switch(program_id, switch_info)?;
+        if switch.owner != *owner_info.key {
+            return Err(InheritanceError::NotOwner.into());
+        }
+        if switch.claimed {
+            return Err(InheritanceError::AlreadyClaimed.into());
+        }
+        Ok(switch)
+    }
+
+    /// Moves `amount` out of the vault, signed for by the switch.
+    fn pay_out<'a>(
+        switch: &Switch,
+        switch_info: &AccountInfo<'a>,
+        vault_info: &AccountInfo<'a>,
+        destination_info: &AccountInfo<'a>,
+        bank_info: &AccountInfo<'a>,
+        bank_program_info: &AccountInfo<'a>,
+        amount: u64,
+    ) -> ProgramResult {
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                destination_info.key,
+                switch_info.key,
+                amount,
+            )?,
+            &[
+                vault_info.clone(),
+                destination_info.clone(),
+                switch_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[
+                SWITCH_SEED,
+                switch.owner.as_ref(),
+                switch.beneficiary.as_ref(),
+                switch.bank.as_ref(),
+                &[switch.bump],
+            ]],
+        )
+    }
+}
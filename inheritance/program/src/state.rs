@@ -0,0 +1,136 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// A dead man's switch over `vault`, a bank account of `bank` owned by the switch. The
+/// owner keeps it armed by pinging at least every `timeout` seconds and can withdraw at
+/// will; once they have been silent for longer, `beneficiary` can claim everything in
+/// the vault, after which the switch is spent. Lives at a program address derived from
+/// the owner, the beneficiary and the bank.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Switch {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    pub beneficiary: Pubkey,
+    pub bank: Pubkey,
+    pub vault: Pubkey,
+    /// Longest the owner can go without pinging, in seconds.
+    pub timeout: i64,
+    /// Unix timestamp the owner last pinged or withdrew at.
+    pub last_ping: i64,
+    pub claimed: bool,
+    /// Bump seed of the switch's address.
+    pub bump: u8,
+}
+
+impl Switch {
+    /// When the beneficiary can claim, unless the owner pings first.
+    pub fn deadline(&self) -> i64 {
+        self.last_ping.saturating_add(self.timeout)
+    }
+
+    /// Whether the owner has been silent past the timeout at `now`.
+    pub fn has_expired(&self, now: i64) -> bool {
+        now >= self.deadline()
+    }
+}
+
+impl Sealed for Switch {}
+impl IsInitialized for Switch {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Switch {
+    const LEN: usize = 147;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 147];
+        let (is_initialized, owner, beneficiary, bank, vault, timeout, last_ping, claimed, bump) =
+            array_refs![src, 1, 32, 32, 32, 32, 8, 8, 1, 1];
+        Ok(Switch {
+            is_initialized: unpack_bool(is_initialized)?,
+            owner: Pubkey::new_from_array(*owner),
+            beneficiary: Pubkey::new_from_array(*beneficiary),
+            bank: Pubkey::new_from_array(*bank),
+            vault: Pubkey::new_from_array(*vault),
+            timeout: i64::from_le_bytes(*timeout),
+            last_ping: i64::from_le_bytes(*last_ping),
+            claimed: unpack_bool(claimed)?,
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 147];
+        let (is_initialized, owner, beneficiary, bank, vault, timeout, last_ping, claimed, bump) =
+            mut_array_refs![dst, 1, 32, 32, 32, 32, 8, 8, 1, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        owner.copy_from_slice(self.owner.as_ref());
+        beneficiary.copy_from_slice(self.beneficiary.as_ref());
+        bank.copy_from_slice(self.bank.as_ref());
+        vault.copy_from_slice(self.vault.as_ref());
+        *timeout = self.timeout.to_le_bytes();
+        *last_ping = self.last_ping.to_le_bytes();
+        claimed[0] = self.claimed as u8;
+        bump[0] = self.bump;
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let switch = Switch {
+            is_initialized: true,
+            owner: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            bank: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            timeout: 30 * 24 * 60 * 60,
+            last_ping: 1_700_000_000,
+            claimed: true,
+            bump: 252,
+        };
+        let mut packed = vec![0u8; Switch::LEN];
+        Switch::pack(switch, &mut packed).unwrap();
+        assert_eq!(Switch::unpack(&packed), Ok(switch));
+
+        packed[145] = 2;
+        assert_eq!(
+            Switch::unpack(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_has_expired() {
+        let switch = Switch {
+            timeout: 100,
+            last_ping: 1_000,
+            ..Switch::default()
+        };
+        assert_eq!(switch.deadline(), 1_100);
+        assert!(!switch.has_expired(1_099));
+        assert!(switch.has_expired(1_100));
+
+        let switch = Switch {
+            timeout: i64::MAX,
+            ..switch
+        };
+        assert!(!switch.has_expired(i64::MAX - 1));
+    }
+}
@@ -0,0 +1,254 @@
+use solana_bank::{instruction::transfer, state::Account, test_utils::program_test::Env};
+use solana_inheritance::{
+    error::InheritanceError,
+    instruction::{claim, create_switch, ping, switch_address, withdraw},
+    processor::Processor,
+    state::Switch,
+};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// Time the fixture's switch is created at.
+const NOW: i64 = 1_000;
+/// The fixture's timeout, a month.
+const TIMEOUT: i64 = 30 * 24 * 60 * 60;
+
+/// An owner who escrowed 600 of their 1_000 tokens in a switch for their heir.
+struct Fixture {
+    env: Env,
+    bank: Pubkey,
+    owner: Keypair,
+    owner_account: Pubkey,
+    heir: Keypair,
+    heir_account: Pubkey,
+    switch: Pubkey,
+    vault: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_inheritance", processor!(Processor::process)).await;
+        env.set_time(NOW);
+        let bank = env.create_bank().await;
+        let (owner, heir, vault) = (Keypair::new(), Keypair::new(), Keypair::new());
+        env.fund(&owner.pubkey(), 100_000_000).await;
+        let owner_account = env.create_bank_account(&bank, &owner, 1_000).await;
+        let heir_account = env.create_bank_account(&bank, &heir, 0).await;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&vault, Account::LEN, &bank_program_id)
+            .await;
+        let (switch, _) = switch_address(&env.program_id, &owner.pubkey(), &heir.pubkey(), &bank);
+        let instructions = [
+            create_switch(
+                &env.program_id,
+                &bank_program_id,
+                &owner.pubkey(),
+                &heir.pubkey(),
+                &vault.pubkey(),
+                &bank,
+                TIMEOUT,
+            )
+            .unwrap(),
+            transfer(
+                &bank_program_id,
+                &bank,
+                &owner_account,
+                &vault.pubkey(),
+                &owner.pubkey(),
+                600,
+            )
+            .unwrap(),
+        ];
+        env.process(&instructions, &[&owner]).await.unwrap();
+        Fixture {
+            env,
+            bank,
+            owner,
+            owner_account,
+            heir,
+            heir_account,
+            switch,
+            vault: vault.pubkey(),
+        }
+    }
+
+    async fn switch(&mut self) -> Switch {
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(self.switch)
+            .await
+            .unwrap()
+            .unwrap();
+        Switch::unpack(&account.data).unwrap()
+    }
+
+    async fn ping(&mut self) -> Result<(), TransactionError> {
+        let instruction = ping(&self.env.program_id, &self.owner.pubkey(), &self.switch).unwrap();
+        let owner = Keypair::from_bytes(&self.owner.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&owner]).await
+    }
+
+    async fn withdraw(&mut self, amount: u64) -> Result<(), TransactionError> {
+        let instruction = withdraw(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &self.owner.pubkey(),
+            &self.switch,
+            &self.vault,
+            &self.owner_account,
+            amount,
+        )
+        .unwrap();
+        let owner = Keypair::from_bytes(&self.owner.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&owner]).await
+    }
+
+    async fn claim(
+        &mut self,
+        claimant: &Keypair,
+        destination_account: &Pubkey,
+    ) -> Result<(), TransactionError> {
+        let instruction = claim(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &claimant.pubkey(),
+            &self.switch,
+            &self.vault,
+            destination_account,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[claimant]).await
+    }
+}
+
+fn custom(error: InheritanceError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_switch() {
+    let mut fixture = Fixture::new().await;
+    let switch = fixture.switch().await;
+    assert_eq!(switch.owner, fixture.owner.pubkey());
+    assert_eq!(switch.beneficiary, fixture.heir.pubkey());
+    assert_eq!(switch.vault, fixture.vault);
+    assert_eq!((switch.timeout, switch.last_ping), (TIMEOUT, NOW));
+    assert!(!switch.claimed);
+    let vault = fixture.vault;
+    assert_eq!(fixture.env.amount(&vault).await, 600);
+}
+
+#[tokio::test]
+async fn test_ping_and_withdraw() {
+    let mut fixture = Fixture::new().await;
+    fixture.env.set_time(NOW + TIMEOUT - 1);
+    fixture.ping().await.unwrap();
+    assert_eq!(fixture.switch().await.last_ping, NOW + TIMEOUT - 1);
+
+    // Withdrawing checks in too.
+    fixture.env.set_time(NOW + 2 * TIMEOUT - 2);
+    fixture.withdraw(100).await.unwrap();
+    assert_eq!(fixture.switch().await.last_ping, NOW + 2 * TIMEOUT - 2);
+    let (vault, owner_account) = (fixture.vault, fixture.owner_account);
+    assert_eq!(fixture.env.amount(&vault).await, 500);
+    assert_eq!(fixture.env.amount(&owner_account).await, 500);
+    assert_eq!(
+        fixture.withdraw(0).await,
+        custom(InheritanceError::InvalidAmount)
+    );
+
+    let heir = Keypair::from_bytes(&fixture.heir.to_bytes()).unwrap();
+    let instruction = ping(&fixture.env.program_id, &heir.pubkey(), &fixture.switch).unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&heir]).await,
+        custom(InheritanceError::NotOwner)
+    );
+}
+
+#[tokio::test]
+async fn test_claim() {
+    let mut fixture = Fixture::new().await;
+    let heir = Keypair::from_bytes(&fixture.heir.to_bytes()).unwrap();
+    let (vault, heir_account) = (fixture.vault, fixture.heir_account);
+    let other_account = fixture.owner_account;
+    fixture.env.set_time(NOW + TIMEOUT - 1);
+    assert_eq!(
+        fixture.claim(&heir, &other_account).await,
+        custom(InheritanceError::OwnerActive)
+    );
+
+    fixture.env.set_time(NOW + TIMEOUT);
+    assert_eq!(
+        fixture.claim(&Keypair::new(), &heir_account).await,
+        custom(InheritanceError::NotBeneficiary)
+    );
+    fixture.claim(&heir, &heir_account).await.unwrap();
+    assert!(fixture.switch().await.claimed);
+    assert_eq!(fixture.env.amount(&vault).await, 0);
+    assert_eq!(fixture.env.amount(&heir_account).await, 600);
+
+    // Once claimed, the switch is spent for everyone.
+    assert_eq!(
+        fixture.claim(&heir, &vault).await,
+        custom(InheritanceError::AlreadyClaimed)
+    );
+    assert_eq!(
+        fixture.ping().await,
+        custom(InheritanceError::AlreadyClaimed)
+    );
+    assert_eq!(
+        fixture.withdraw(1).await,
+        custom(InheritanceError::AlreadyClaimed)
+    );
+}
+
+#[tokio::test]
+async fn test_late_ping_rearms() {
+    let mut fixture = Fixture::new().await;
+    let heir = Keypair::from_bytes(&fixture.heir.to_bytes()).unwrap();
+    fixture.env.set_time(NOW + 2 * TIMEOUT);
+    fixture.ping().await.unwrap();
+    let heir_account = fixture.heir_account;
+    assert_eq!(
+        fixture.claim(&heir, &heir_account).await,
+        custom(InheritanceError::OwnerActive)
+    );
+    assert_eq!(fixture.switch().await.deadline(), NOW + 3 * TIMEOUT);
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    fixture.env.set_time(NOW + TIMEOUT);
+
+    // Through a program posing as the bank, the switch would be spent without the heir
+    // being paid, or the switch's signature over the vault handed to that program.
+    let instruction = claim(
+        &fixture.env.program_id,
+        &fixture.env.fake_bank_program_id,
+        &fixture.bank,
+        &fixture.heir.pubkey(),
+        &fixture.switch,
+        &fixture.vault,
+        &fixture.heir_account,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&fixture.heir]).await,
+        custom(InheritanceError::InvalidVault)
+    );
+    assert!(!fixture.switch().await.claimed);
+    let vault = fixture.vault;
+    assert_eq!(fixture.env.amount(&vault).await, 600);
+}
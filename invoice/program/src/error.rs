@@ -0,0 +1,25 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InvoiceError {
+    /// The invoice isn't the merchant's program address for the id.
+    InvalidInvoice,
+    /// Invoices must ask for more than zero.
+    InvalidAmount,
+    /// The expiry has already passed.
+    InvalidExpiry,
+    /// The memo is longer than `MAX_MEMO_LEN`.
+    MemoTooLong,
+    /// The account isn't the one the invoice is payable to.
+    InvalidDestination,
+    /// The invoice has already been paid.
+    AlreadyPaid,
+    /// The invoice expired before it was paid.
+    Expired,
+}
+
+impl From<InvoiceError> for ProgramError {
+    fn from(e: InvoiceError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{error::InvoiceError, state::MAX_MEMO_LEN};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of an invoice, followed by the merchant's address and the invoice id.
+pub const INVOICE_SEED: &[u8] = b"invoice";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum InvoiceInstruction {
+    /// Issues invoice `id` for `amount`, payable into the destination account until
+    /// `expires_ts`.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The merchant, paying for the invoice.
+    ///   1. `[writable]` The invoice, the merchant's program address for `id`.
+    ///   2. `[]` The destination account.
+    ///   3. `[]` The system program.
+    CreateInvoice {
+        id: u64,
+        amount: u64,
+        expires_ts: i64,
+        memo: String,
+    },
+
+    /// Transfers the invoiced amount into its destination account and marks the invoice
+    /// paid.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The payer, owner or delegate of the source account.
+    ///   1. `[writable]` The invoice.
+    ///   2. `[writable]` The source account.
+    ///   3. `[writable]` The destination account.
+    ///   4. `[]` The accounts' bank.
+    ///   5. `[]` The bank program.
+    Pay,
+}
+
+impl InvoiceInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (id, rest) = Self::unpack_u64(rest)?;
+                let (amount, rest) = Self::unpack_u64(rest)?;
+                let (expires_ts, rest) = Self::unpack_u64(rest)?;
+                let (memo, _rest) = Self::unpack_str(rest)?;
+                Self::CreateInvoice {
+                    id,
+                    amount,
+                    expires_ts: expires_ts as i64,
+                    memo,
+                }
+            }
+            1 => Self::Pay,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self {
+            Self::CreateInvoice {
+                id,
+                amount,
+                expires_ts,
+                memo,
+            } => {
+                buf.push(0);
+                buf.extend_from_slice(&id.to_le_bytes());
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(&expires_ts.to_le_bytes());
+                Self::pack_str(memo, &mut buf);
+            }
+            Self::Pay => buf.push(1),
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+
+    /// A string prefixed by its length in bytes, as a u8.
+    fn unpack_str(input: &[u8]) -> Result<(String, &[u8]), ProgramError> {
+        let (&len, rest) = input
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let bytes = rest
+            .get(..len as usize)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let value =
+            String::from_utf8(bytes.to_vec()).map_err(|_| ProgramError::InvalidInstructionData)?;
+        Ok((value, &rest[len as usize..]))
+    }
+
+    /// Strings over 255 bytes are cut short; no valid memo is that long.
+    fn pack_str(value: &str, buf: &mut Vec<u8>) {
+        let bytes = &value.as_bytes()[..value.len().min(u8::MAX as usize)];
+        buf.push(bytes.len() as u8);
+        buf.extend_from_slice(bytes);
+    }
+}
+
+/// Address and bump of `merchant`'s invoice `id`.
+pub fn invoice_address(invoice_program_id: &Pubkey, merchant: &Pubkey, id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[INVOICE_SEED, merchant.as_ref(), &id.to_le_bytes()],
+        invoice_program_id,
+    )
+}
+
+pub fn create_invoice(
+    invoice_program_id: &Pubkey,
+    merchant: &Pubkey,
+    destination_account: &Pubkey,
+    id: u64,
+    amount: u64,
+    expires_ts: i64,
+    memo: &str,
+) -> Result<Instruction, ProgramError> {
+    if memo.len() > MAX_MEMO_LEN {
+        return Err(InvoiceError::MemoTooLong.into());
+    }
+    let data = InvoiceInstruction::CreateInvoice {
+        id,
+        amount,
+        expires_ts,
+        memo: memo.to_string(),
+    }
+    .pack();
+    let (invoice, _) = invoice_address(invoice_program_id, merchant, id);
+    let accounts = vec![
+        AccountMeta::new(*merchant, true),
+        AccountMeta::new(invoice, false),
+        AccountMeta::new_readonly(*destination_account, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *invoice_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn pay(
+    invoice_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    payer: &Pubkey,
+    invoice: &Pubkey,
+    source_account: &Pubkey,
+    destination_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = InvoiceInstruction::Pay.pack();
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(*invoice, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *invoice_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            InvoiceInstruction::CreateInvoice {
+                id: 7,
+                amount: 1_500,
+                expires_ts: -1,
+                memo: "order #7".to_string(),
+            },
+            InvoiceInstruction::CreateInvoice {
+                id: u64::MAX,
+                amount: 1,
+                expires_ts: 0,
+                memo: String::new(),
+            },
+            InvoiceInstruction::Pay,
+        ];
+        for instruction in cases {
+            assert_eq!(
+                InvoiceInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        let mut truncated = InvoiceInstruction::CreateInvoice {
+            id: 7,
+            amount: 1_500,
+            expires_ts: 0,
+            memo: "order".to_string(),
+        }
+        .pack();
+        truncated.pop();
+        assert_eq!(
+            InvoiceInstruction::unpack(&truncated),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            InvoiceInstruction::unpack(&[2]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
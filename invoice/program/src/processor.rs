@@ -0,0 +1,169 @@
+use crate::{
+    error::InvoiceError,
+    instruction::{InvoiceInstruction, INVOICE_SEED},
+    state::{Invoice, MAX_MEMO_LEN},
+};
+use solana_bank::{instruction as bank_instruction, state::Account as BankAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = InvoiceInstruction::unpack(input)?;
+
+        match instruction {
+            InvoiceInstruction::CreateInvoice {
+                id,
+                amount,
+                expires_ts,
+                memo,
+            } => {
+                msg!("Instruction: CreateInvoice");
+                Self::process_create_invoice(program_id, accounts, id, amount, expires_ts, memo)
+            }
+            InvoiceInstruction::Pay => {
+                msg!("Instruction: Pay");
+                Self::process_pay(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_create_invoice(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        id: u64,
+        amount: u64,
+        expires_ts: i64,
+        memo: String,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let merchant_info = next_account_info(account_info_iter)?;
+        let invoice_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !merchant_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let id_bytes = id.to_le_bytes();
+        let (address, bump) = Pubkey::find_program_address(
+            &[INVOICE_SEED, merchant_info.key.as_ref(), &id_bytes],
+            program_id,
+        );
+        if address != *invoice_info.key {
+            return Err(InvoiceError::InvalidInvoice.into());
+        }
+        if !invoice_info.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if amount == 0 {
+            return Err(InvoiceError::InvalidAmount.into());
+        }
+        let now = Clock::get()?.unix_timestamp;
+        if expires_ts <= now {
+            return Err(InvoiceError::InvalidExpiry.into());
+        }
+        if memo.len() > MAX_MEMO_LEN {
+            return Err(InvoiceError::MemoTooLong.into());
+        }
+        // Which bank program owns the destination is checked when the invoice is paid.
+        BankAccount::unpack(&destination_info.data.borrow())?;
+
+        invoke_signed(
+            &system_instruction::create_account(
+                merchant_info.key,
+                invoice_info.key,
+                Rent::get()?.minimum_balance(Invoice::LEN),
+                Invoice::LEN as u64,
+                program_id,
+            ),
+            &[
+                merchant_info.clone(),
+                invoice_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[&[INVOICE_SEED, merchant_info.key.as_ref(), &id_bytes, &[bump]]],
+        )?;
+
+        let invoice = Invoice {
+            is_initialized: true,
+            merchant: *merchant_info.key,
+            destination: *destination_info.key,
+            id,
+            amount,
+            expires_ts,
+            memo,
+            ..Invoice::default()
+        };
+        Invoice::pack(invoice, &mut invoice_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_pay(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let payer_info = next_account_info(account_info_iter)?;
+        let invoice_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !payer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if invoice_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut invoice = Invoice::unpack(&invoice_info.data.borrow())?;
+        if invoice.paid {
+            return Err(InvoiceError::AlreadyPaid.into());
+        }
+        let now = Clock::get()?.unix_timestamp;
+        if invoice.is_expired(now) {
+            return Err(InvoiceError::Expired.into());
+        }
+        // The destination being the bank program's own account is what makes the
+        // transfer below a real payment.
+        if invoice.destination != *destination_info.key
+            || destination_info.owner != bank_program_info.key
+        {
+            return Err(InvoiceError::InvalidDestination.into());
+        }
+
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                destination_info.key,
+                payer_info.key,
+                invoice.amount,
+            )?,
+            &[
+                source_info.clone(),
+                destination_info.clone(),
+                payer_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        invoice.paid = true;
+        invoice.payer = *payer_info.key;
+        invoice.paid_ts = now;
+        Invoice::pack(invoice, &mut invoice_info.data.borrow_mut())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Longest memo, in bytes.
+pub const MAX_MEMO_LEN: usize = 64;
+
+/// A request from `merchant` for `amount` to be paid into `destination`, a bank account,
+/// before `expires_ts`. Lives at a program address derived from the merchant and `id`.
+/// Paying it records who paid and when, so it can only be paid once.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Invoice {
+    pub is_initialized: bool,
+    pub merchant: Pubkey,
+    pub destination: Pubkey,
+    /// Chosen by the merchant, unique among their invoices.
+    pub id: u64,
+    pub amount: u64,
+    pub expires_ts: i64,
+    pub paid: bool,
+    pub payer: Pubkey,
+    pub paid_ts: i64,
+    pub memo: String,
+}
+
+impl Invoice {
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires_ts
+    }
+}
+
+impl Sealed for Invoice {}
+impl IsInitialized for Invoice {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Invoice {
+    const LEN: usize = 195;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 195];
+        let (
+            is_initialized,
+            merchant,
+            destination,
+            id,
+            amount,
+            expires_ts,
+            paid,
+            payer,
+            paid_ts,
+            memo_len,
+            memo,
+        ) = array_refs![src, 1, 32, 32, 8, 8, 8, 1, 32, 8, 1, MAX_MEMO_LEN];
+        Ok(Invoice {
+            is_initialized: unpack_bool(is_initialized)?,
+            merchant: Pubkey::new_from_array(*merchant),
+            destination: Pubkey::new_from_array(*destination),
+            id: u64::from_le_bytes(*id),
+            amount: u64::from_le_bytes(*amount),
+            expires_ts: i64::from_le_bytes(*expires_ts),
+            paid: unpack_bool(paid)?,
+            payer: Pubkey::new_from_array(*payer),
+            paid_ts: i64::from_le_bytes(*paid_ts),
+            memo: unpack_str(memo_len, memo)?,
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 195];
+        let (
+            is_initialized,
+            merchant,
+            destination,
+            id,
+            amount,
+            expires_ts,
+            paid,
+            payer,
+            paid_ts,
+            memo_len,
+            memo,
+        ) = mut_array_refs![dst, 1, 32, 32, 8, 8, 8, 1, 32, 8, 1, MAX_MEMO_LEN];
+        is_initialized[0] = self.is_initialized as u8;
+        merchant.copy_from_slice(self.merchant.as_ref());
+        destination.copy_from_slice(self.destination.as_ref());
+        *id = self.id.to_le_bytes();
+        *amount = self.amount.to_le_bytes();
+        *expires_ts = self.expires_ts.to_le_bytes();
+        paid[0] = self.paid as u8;
+        payer.copy_from_slice(self.payer.as_ref());
+        *paid_ts = self.paid_ts.to_le_bytes();
+        pack_str(&self.memo, memo_len, memo);
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+fn unpack_str(len: &[u8; 1], src: &[u8]) -> Result<String, ProgramError> {
+    let bytes = src
+        .get(..len[0] as usize)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| ProgramError::InvalidAccountData)
+}
+
+/// Writes `value` zero-padded into `dst`, which the processor has checked it fits.
+fn pack_str(value: &str, len: &mut [u8; 1], dst: &mut [u8]) {
+    len[0] = value.len() as u8;
+    dst.fill(0);
+    dst[..value.len()].copy_from_slice(value.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let invoice = Invoice {
+            is_initialized: true,
+            merchant: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            id: 42,
+            amount: 1_500,
+            expires_ts: 1_700_000_000,
+            paid: true,
+            payer: Pubkey::new_unique(),
+            paid_ts: 1_699_999_000,
+            memo: "m".repeat(MAX_MEMO_LEN),
+        };
+        let mut packed = vec![0xff; Invoice::LEN];
+        Invoice::pack(invoice.clone(), &mut packed).unwrap();
+        assert_eq!(Invoice::unpack(&packed), Ok(invoice));
+
+        packed[130] = MAX_MEMO_LEN as u8 + 1;
+        assert_eq!(
+            Invoice::unpack(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+}
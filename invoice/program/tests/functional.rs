@@ -0,0 +1,182 @@
+use solana_bank::test_utils::program_test::Env;
+use solana_invoice::{
+    error::InvoiceError,
+    instruction::{create_invoice, invoice_address, pay},
+    processor::Processor,
+    state::Invoice,
+};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// Time the fixture's invoice is issued at, and when it expires.
+const NOW: i64 = 1_000;
+const EXPIRES: i64 = NOW + 3_600;
+
+/// Invoice 1 of a merchant for 250, and alice holding 1_000 to pay it with.
+struct Fixture {
+    env: Env,
+    bank: Pubkey,
+    merchant: Keypair,
+    destination: Pubkey,
+    invoice: Pubkey,
+    alice: Keypair,
+    alice_account: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_invoice", processor!(Processor::process)).await;
+        env.set_time(NOW);
+        let bank = env.create_bank().await;
+        let (merchant, alice) = (Keypair::new(), Keypair::new());
+        env.fund(&merchant.pubkey(), 100_000_000).await;
+        let destination = env.create_bank_account(&bank, &merchant, 0).await;
+        let alice_account = env.create_bank_account(&bank, &alice, 1_000).await;
+        let mut fixture = Fixture {
+            env,
+            bank,
+            merchant,
+            destination,
+            invoice: Pubkey::default(),
+            alice,
+            alice_account,
+        };
+        fixture.create(1, 250, EXPIRES, "order #1").await.unwrap();
+        fixture.invoice = invoice_address(&fixture.env.program_id, &fixture.merchant.pubkey(), 1).0;
+        fixture
+    }
+
+    async fn create(
+        &mut self,
+        id: u64,
+        amount: u64,
+        expires_ts: i64,
+        memo: &str,
+    ) -> Result<(), TransactionError> {
+        let instruction = create_invoice(
+            &self.env.program_id,
+            &self.merchant.pubkey(),
+            &self.destination,
+            id,
+            amount,
+            expires_ts,
+            memo,
+        )
+        .unwrap();
+        let merchant = Keypair::from_bytes(&self.merchant.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&merchant]).await
+    }
+
+    async fn invoice(&mut self) -> Invoice {
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(self.invoice)
+            .await
+            .unwrap()
+            .unwrap();
+        Invoice::unpack(&account.data).unwrap()
+    }
+
+    async fn pay(
+        &mut self,
+        payer: &Keypair,
+        source: &Pubkey,
+        destination: &Pubkey,
+    ) -> Result<(), TransactionError> {
+        let instruction = pay(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &payer.pubkey(),
+            &self.invoice,
+            source,
+            destination,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[payer]).await
+    }
+}
+
+fn custom(error: InvoiceError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_invoice() {
+    let mut fixture = Fixture::new().await;
+    let invoice = fixture.invoice().await;
+    assert_eq!(invoice.merchant, fixture.merchant.pubkey());
+    assert_eq!(invoice.destination, fixture.destination);
+    assert_eq!((invoice.id, invoice.amount), (1, 250));
+    assert_eq!(invoice.expires_ts, EXPIRES);
+    assert_eq!(invoice.memo, "order #1");
+    assert!(!invoice.paid);
+
+    assert_eq!(
+        fixture.create(1, 300, EXPIRES, "again").await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::AccountAlreadyInitialized
+        ))
+    );
+    assert_eq!(
+        fixture.create(2, 0, EXPIRES, "").await,
+        custom(InvoiceError::InvalidAmount)
+    );
+    assert_eq!(
+        fixture.create(3, 250, NOW, "").await,
+        custom(InvoiceError::InvalidExpiry)
+    );
+}
+
+#[tokio::test]
+async fn test_pay() {
+    let mut fixture = Fixture::new().await;
+    let alice = Keypair::from_bytes(&fixture.alice.to_bytes()).unwrap();
+    let (alice_account, destination) = (fixture.alice_account, fixture.destination);
+
+    assert_eq!(
+        fixture.pay(&alice, &alice_account, &alice_account).await,
+        custom(InvoiceError::InvalidDestination)
+    );
+    fixture.env.set_time(NOW + 10);
+    fixture
+        .pay(&alice, &alice_account, &destination)
+        .await
+        .unwrap();
+    assert_eq!(fixture.env.amount(&alice_account).await, 750);
+    assert_eq!(fixture.env.amount(&destination).await, 250);
+    let invoice = fixture.invoice().await;
+    assert!(invoice.paid);
+    assert_eq!(invoice.payer, alice.pubkey());
+    assert_eq!(invoice.paid_ts, NOW + 10);
+
+    let (bob, bank) = (Keypair::new(), fixture.bank);
+    let bob_account = fixture.env.create_bank_account(&bank, &bob, 1_000).await;
+    assert_eq!(
+        fixture.pay(&bob, &bob_account, &destination).await,
+        custom(InvoiceError::AlreadyPaid)
+    );
+}
+
+#[tokio::test]
+async fn test_pay_expired() {
+    let mut fixture = Fixture::new().await;
+    let alice = Keypair::from_bytes(&fixture.alice.to_bytes()).unwrap();
+    let (alice_account, destination) = (fixture.alice_account, fixture.destination);
+    fixture.env.set_time(EXPIRES);
+    assert_eq!(
+        fixture.pay(&alice, &alice_account, &destination).await,
+        custom(InvoiceError::Expired)
+    );
+    assert_eq!(fixture.env.amount(&alice_account).await, 1_000);
+}
@@ -0,0 +1,15 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LeaderboardError {
+    /// A leaderboard holds between one and `MAX_ENTRIES` entries.
+    InvalidCapacity,
+    /// The account isn't a greeting account of the leaderboard's greeting program.
+    InvalidGreeting,
+}
+
+impl From<LeaderboardError> for ProgramError {
+    fn from(e: LeaderboardError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,116 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LeaderboardInstruction {
+    /// Opens an empty leaderboard of the greeting program's accounts, keeping the top
+    /// `capacity` of them.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The leaderboard, an uninitialized account owned by this program.
+    ///   1. `[]` The greeting program.
+    CreateLeaderboard { capacity: u8 },
+
+    /// Reads the greeting account's counter and puts it on the leaderboard, if it makes
+    /// the cut. Anyone can submit any account, as the score comes from the account
+    /// itself.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The leaderboard.
+    ///   1. `[]` The greeting account.
+    SubmitScore,
+}
+
+impl LeaderboardInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let &capacity = rest.first().ok_or(InvalidInstructionData)?;
+                Self::CreateLeaderboard { capacity }
+            }
+            1 => Self::SubmitScore,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(2);
+        match *self {
+            Self::CreateLeaderboard { capacity } => {
+                buf.push(0);
+                buf.push(capacity);
+            }
+            Self::SubmitScore => buf.push(1),
+        }
+        buf
+    }
+}
+
+pub fn create_leaderboard(
+    leaderboard_program_id: &Pubkey,
+    leaderboard: &Pubkey,
+    greeting_program_id: &Pubkey,
+    capacity: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = LeaderboardInstruction::CreateLeaderboard { capacity }.pack();
+    let accounts = vec![
+        AccountMeta::new(*leaderboard, false),
+        AccountMeta::new_readonly(*greeting_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *leaderboard_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn submit_score(
+    leaderboard_program_id: &Pubkey,
+    leaderboard: &Pubkey,
+    greeting: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = LeaderboardInstruction::SubmitScore.pack();
+    let accounts = vec![
+        AccountMeta::new(*leaderboard, false),
+        AccountMeta::new_readonly(*greeting, false),
+    ];
+    Ok(Instruction {
+        program_id: *leaderboard_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            LeaderboardInstruction::CreateLeaderboard { capacity: 10 },
+            LeaderboardInstruction::SubmitScore,
+        ];
+        for instruction in cases {
+            assert_eq!(
+                LeaderboardInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            LeaderboardInstruction::unpack(&[0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            LeaderboardInstruction::unpack(&[2]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{
+    error::LeaderboardError,
+    instruction::LeaderboardInstruction,
+    state::{Leaderboard, MAX_ENTRIES},
+};
+use program::greeting_account::GreetingAccount;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = LeaderboardInstruction::unpack(input)?;
+
+        match instruction {
+            LeaderboardInstruction::CreateLeaderboard { capacity } => {
+                msg!("Instruction: CreateLeaderboard");
+                Self::process_create_leaderboard(program_id, accounts, capacity)
+            }
+            LeaderboardInstruction::SubmitScore => {
+                msg!("Instruction: SubmitScore");
+                Self::process_submit_score(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_create_leaderboard(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        capacity: u8,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let leaderboard_info = next_account_info(account_info_iter)?;
+        let greeting_program_info = next_account_info(account_info_iter)?;
+
+        if leaderboard_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut leaderboard = Leaderboard::unpack_unchecked(&leaderboard_info.data.borrow())?;
+        if leaderboard.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if capacity == 0 || capacity as usize > MAX_ENTRIES {
+            return Err(LeaderboardError::InvalidCapacity.into());
+        }
+
+        leaderboard.is_initialized = true;
+        leaderboard.greeting_program = *greeting_program_info.key;
+        leaderboard.capacity = capacity;
+        Leaderboard::pack(leaderboard, &mut leaderboard_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_submit_score(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let leaderboard_info = next_account_info(account_info_iter)?;
+        let greeting_info = next_account_info(account_info_iter)?;
+
+        if leaderboard_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut leaderboard = Leaderboard::unpack(&leaderboard_info.data.borrow())?;
+        // Only the greeting program can have set the counter of an account it owns.
+        if *greeting_info.owner != leaderboard.greeting_program {
+            return Err(LeaderboardError::InvalidGreeting.into());
+        }
+        let counter = GreetingAccount::read(&greeting_info.data.borrow())
+            .map_err(|_| ProgramError::from(LeaderboardError::InvalidGreeting))?
+            .counter;
+
+        match leaderboard.submit(*greeting_info.key, counter) {
+            Some(rank) => msg!("Ranked #{} with {} greeting(s)", rank + 1, counter),
+            None => msg!("{} greeting(s) didn't make the leaderboard", counter),
+        }
+        Leaderboard::pack(leaderboard, &mut leaderboard_info.data.borrow_mut())?;
+        Ok(())
+    }
+}
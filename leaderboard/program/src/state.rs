@@ -0,0 +1,171 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Most entries a leaderboard can hold.
+pub const MAX_ENTRIES: usize = 10;
+const ENTRY_LEN: usize = 36;
+
+/// A greeting account and its counter when it was last submitted.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Entry {
+    pub greeting: Pubkey,
+    pub counter: u32,
+}
+
+/// The `capacity` most greeted accounts of `greeting_program` that have been submitted,
+/// most greeted first; of equal counters, the one that got there first ranks higher.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Leaderboard {
+    pub is_initialized: bool,
+    pub greeting_program: Pubkey,
+    pub capacity: u8,
+    pub entries: Vec<Entry>,
+}
+
+impl Leaderboard {
+    /// Records `counter` for `greeting`, replacing its previous entry, and returns its
+    /// zero-based rank, or `None` if it doesn't make the board.
+    pub fn submit(&mut self, greeting: Pubkey, counter: u32) -> Option<usize> {
+        self.entries.retain(|entry| entry.greeting != greeting);
+        let rank = self
+            .entries
+            .iter()
+            .position(|entry| entry.counter < counter)
+            .unwrap_or(self.entries.len());
+        if rank >= self.capacity as usize {
+            return None;
+        }
+        self.entries.insert(rank, Entry { greeting, counter });
+        self.entries.truncate(self.capacity as usize);
+        Some(rank)
+    }
+}
+
+impl Sealed for Leaderboard {}
+impl IsInitialized for Leaderboard {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Leaderboard {
+    const LEN: usize = 395;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 395];
+        let (is_initialized, greeting_program, capacity, len, entries) =
+            array_refs![src, 1, 32, 1, 1, MAX_ENTRIES * ENTRY_LEN];
+        let len = len[0] as usize;
+        if len > MAX_ENTRIES {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(Leaderboard {
+            is_initialized: unpack_bool(is_initialized)?,
+            greeting_program: Pubkey::new_from_array(*greeting_program),
+            capacity: capacity[0],
+            entries: entries
+                .chunks(ENTRY_LEN)
+                .take(len)
+                .map(|entry| {
+                    let (greeting, counter) = array_refs![array_ref![entry, 0, ENTRY_LEN], 32, 4];
+                    Entry {
+                        greeting: Pubkey::new_from_array(*greeting),
+                        counter: u32::from_le_bytes(*counter),
+                    }
+                })
+                .collect(),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 395];
+        let (is_initialized, greeting_program, capacity, len, entries) =
+            mut_array_refs![dst, 1, 32, 1, 1, MAX_ENTRIES * ENTRY_LEN];
+        is_initialized[0] = self.is_initialized as u8;
+        greeting_program.copy_from_slice(self.greeting_program.as_ref());
+        capacity[0] = self.capacity;
+        len[0] = self.entries.len() as u8;
+        entries.fill(0);
+        for (dst, entry) in entries.chunks_mut(ENTRY_LEN).zip(&self.entries) {
+            let (greeting, counter) = mut_array_refs![array_mut_ref![dst, 0, ENTRY_LEN], 32, 4];
+            greeting.copy_from_slice(entry.greeting.as_ref());
+            *counter = entry.counter.to_le_bytes();
+        }
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counters(leaderboard: &Leaderboard) -> Vec<u32> {
+        leaderboard
+            .entries
+            .iter()
+            .map(|entry| entry.counter)
+            .collect()
+    }
+
+    #[test]
+    fn test_pack_unpack() {
+        let leaderboard = Leaderboard {
+            is_initialized: true,
+            greeting_program: Pubkey::new_unique(),
+            capacity: MAX_ENTRIES as u8,
+            entries: (0..MAX_ENTRIES as u32)
+                .map(|counter| Entry {
+                    greeting: Pubkey::new_unique(),
+                    counter: 100 - counter,
+                })
+                .collect(),
+        };
+        let mut packed = vec![0u8; Leaderboard::LEN];
+        Leaderboard::pack(leaderboard.clone(), &mut packed).unwrap();
+        assert_eq!(Leaderboard::unpack(&packed), Ok(leaderboard));
+
+        packed[34] = MAX_ENTRIES as u8 + 1;
+        assert_eq!(
+            Leaderboard::unpack(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_submit() {
+        let mut leaderboard = Leaderboard {
+            capacity: 3,
+            ..Leaderboard::default()
+        };
+        let (a, b, c, d) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        assert_eq!(leaderboard.submit(a, 5), Some(0));
+        assert_eq!(leaderboard.submit(b, 7), Some(0));
+        // Ties go to whoever was there first.
+        assert_eq!(leaderboard.submit(c, 5), Some(2));
+        assert_eq!(counters(&leaderboard), vec![7, 5, 5]);
+
+        // A full board only takes strictly better counters, pushing out the last.
+        assert_eq!(leaderboard.submit(d, 5), None);
+        assert_eq!(leaderboard.submit(d, 6), Some(1));
+        assert_eq!(leaderboard.entries[2].greeting, a);
+
+        // Resubmitting moves an entry rather than adding another.
+        assert_eq!(leaderboard.submit(a, 9), Some(0));
+        assert_eq!(counters(&leaderboard), vec![9, 7, 6]);
+        assert_eq!(leaderboard.entries.len(), 3);
+    }
+}
@@ -0,0 +1,243 @@
+use program::greeting_account::{process_greeting_account, GREETING_LEN};
+use solana_leaderboard::{
+    error::LeaderboardError,
+    instruction::{create_leaderboard, submit_score},
+    processor::Processor,
+    state::Leaderboard,
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+struct Env {
+    context: ProgramTestContext,
+    leaderboard_program_id: Pubkey,
+    greeting_program_id: Pubkey,
+}
+impl Env {
+    async fn start() -> Env {
+        let leaderboard_program_id = Pubkey::new_unique();
+        let greeting_program_id = Pubkey::new_unique();
+        let mut program_test = ProgramTest::new(
+            "solana_leaderboard",
+            leaderboard_program_id,
+            processor!(Processor::process),
+        );
+        program_test.add_program(
+            "program",
+            greeting_program_id,
+            processor!(process_greeting_account),
+        );
+        Env {
+            context: program_test.start_with_context().await,
+            leaderboard_program_id,
+            greeting_program_id,
+        }
+    }
+
+    async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), TransactionError> {
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            self.context.last_blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .map_err(|e| e.unwrap())
+    }
+
+    /// Creates a rent-exempt account of `space` bytes owned by `owner`.
+    async fn create_account(&mut self, account: &Keypair, space: usize, owner: &Pubkey) {
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let instruction = system_instruction::create_account(
+            &self.context.payer.pubkey(),
+            &account.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            owner,
+        );
+        self.process(&[instruction], &[account]).await.unwrap();
+    }
+
+    /// Creates a greeting account and greets it `times` times.
+    async fn create_greeting(&mut self, times: usize) -> Pubkey {
+        let greeting = Keypair::new();
+        let greeting_program_id = self.greeting_program_id;
+        self.create_account(&greeting, GREETING_LEN, &greeting_program_id)
+            .await;
+        self.greet(&greeting.pubkey(), times).await;
+        greeting.pubkey()
+    }
+
+    async fn greet(&mut self, greeting: &Pubkey, times: usize) {
+        let greet = Instruction::new_with_bincode(
+            self.greeting_program_id,
+            &(),
+            vec![AccountMeta::new(*greeting, false)],
+        );
+        if times > 0 {
+            self.process(&vec![greet; times], &[]).await.unwrap();
+        }
+    }
+}
+
+/// A leaderboard of the top three greeting accounts.
+struct Fixture {
+    env: Env,
+    leaderboard: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start().await;
+        let leaderboard = Keypair::new();
+        let leaderboard_program_id = env.leaderboard_program_id;
+        env.create_account(&leaderboard, Leaderboard::LEN, &leaderboard_program_id)
+            .await;
+        let instruction = create_leaderboard(
+            &leaderboard_program_id,
+            &leaderboard.pubkey(),
+            &env.greeting_program_id,
+            3,
+        )
+        .unwrap();
+        env.process(&[instruction], &[]).await.unwrap();
+        Fixture {
+            env,
+            leaderboard: leaderboard.pubkey(),
+        }
+    }
+
+    async fn submit(&mut self, greeting: &Pubkey) -> Result<(), TransactionError> {
+        let instruction = submit_score(
+            &self.env.leaderboard_program_id,
+            &self.leaderboard,
+            greeting,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[]).await
+    }
+
+    /// The greeting accounts on the board, best first, with their counters.
+    async fn standings(&mut self) -> Vec<(Pubkey, u32)> {
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(self.leaderboard)
+            .await
+            .unwrap()
+            .unwrap();
+        Leaderboard::unpack(&account.data)
+            .unwrap()
+            .entries
+            .iter()
+            .map(|entry| (entry.greeting, entry.counter))
+            .collect()
+    }
+}
+
+fn custom(error: LeaderboardError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_leaderboard() {
+    let mut fixture = Fixture::new().await;
+    assert!(fixture.standings().await.is_empty());
+
+    let leaderboard = Keypair::new();
+    let leaderboard_program_id = fixture.env.leaderboard_program_id;
+    fixture
+        .env
+        .create_account(&leaderboard, Leaderboard::LEN, &leaderboard_program_id)
+        .await;
+    let instruction = create_leaderboard(
+        &leaderboard_program_id,
+        &leaderboard.pubkey(),
+        &fixture.env.greeting_program_id,
+        11,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[]).await,
+        custom(LeaderboardError::InvalidCapacity)
+    );
+}
+
+#[tokio::test]
+async fn test_submit_score() {
+    let mut fixture = Fixture::new().await;
+    let a = fixture.env.create_greeting(2).await;
+    let b = fixture.env.create_greeting(5).await;
+    let c = fixture.env.create_greeting(3).await;
+    let d = fixture.env.create_greeting(1).await;
+    for greeting in [a, b, c, d].iter() {
+        fixture.submit(greeting).await.unwrap();
+    }
+    assert_eq!(fixture.standings().await, vec![(b, 5), (c, 3), (a, 2)]);
+
+    // Greeting more and resubmitting climbs the board.
+    let greet = Instruction::new_with_bincode(
+        fixture.env.greeting_program_id,
+        &(),
+        vec![AccountMeta::new(d, false)],
+    );
+    let submit = submit_score(
+        &fixture.env.leaderboard_program_id,
+        &fixture.leaderboard,
+        &d,
+    )
+    .unwrap();
+    fixture
+        .env
+        .process(&[greet.clone(), greet.clone(), greet, submit], &[])
+        .await
+        .unwrap();
+    assert_eq!(fixture.standings().await, vec![(b, 5), (d, 4), (c, 3)]);
+}
+
+#[tokio::test]
+async fn test_submit_rejects_other_accounts() {
+    let mut fixture = Fixture::new().await;
+    let impostor = Keypair::new();
+    fixture
+        .env
+        .create_account(&impostor, GREETING_LEN, &Pubkey::new_unique())
+        .await;
+    assert_eq!(
+        fixture.submit(&impostor.pubkey()).await,
+        custom(LeaderboardError::InvalidGreeting)
+    );
+
+    let undersized = Keypair::new();
+    let greeting_program_id = fixture.env.greeting_program_id;
+    fixture
+        .env
+        .create_account(&undersized, GREETING_LEN - 1, &greeting_program_id)
+        .await;
+    assert_eq!(
+        fixture.submit(&undersized.pubkey()).await,
+        custom(LeaderboardError::InvalidGreeting)
+    );
+    assert!(fixture.standings().await.is_empty());
+}
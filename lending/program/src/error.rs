@@ -0,0 +1,31 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LendingError {
+    /// The market authority isn't the market's program address.
+    InvalidMarketAuthority,
+    /// The account isn't one of the market's vaults.
+    InvalidVault,
+    /// The account isn't the market's oracle.
+    InvalidOracle,
+    /// The signer isn't the oracle's authority.
+    InvalidOracleAuthority,
+    /// Prices must be above zero.
+    InvalidPrice,
+    /// The LTV must be below the liquidation threshold, and both at most 100%.
+    InvalidConfig,
+    /// The obligation holds less collateral than the amount asked to withdraw.
+    InsufficientCollateral,
+    /// The obligation's debt would exceed what its collateral allows borrowing.
+    ExceedsBorrowLimit,
+    /// The obligation has no debt.
+    NothingToRepay,
+    /// The obligation's debt is within its liquidation threshold.
+    ObligationHealthy,
+}
+
+impl From<LendingError> for ProgramError {
+    fn from(e: LendingError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
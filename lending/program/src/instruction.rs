@@ -0,0 +1,525 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the market authority, followed by the market's address.
+pub const MARKET_AUTHORITY_SEED: &[u8] = b"market";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LendingInstruction {
+    /// Opens an oracle quoting `price`, which only the signer can update.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The oracle authority.
+    ///   1. `[writable]` The oracle, owned by this program.
+    InitializeOracle { price: u64 },
+
+    /// Sets the oracle's price.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The oracle authority.
+    ///   1. `[writable]` The oracle.
+    SetPrice { price: u64 },
+
+    /// Opens a market priced by the oracle: initializes the collateral vault and the
+    /// liquidity vault, both owned by the market authority. Liquidity is funded by plain
+    /// bank transfers into the liquidity vault.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The admin.
+    ///   1. `[writable]` The market, owned by this program.
+    ///   2. `[writable]` The collateral vault, an uninitialized account owned by the bank
+    ///      program.
+    ///   3. `[writable]` The liquidity vault, an uninitialized account owned by the bank
+    ///      program.
+    ///   4. `[writable]` The bank of the collateral tokens.
+    ///   5. `[writable]` The bank of the borrowed tokens.
+    ///   6. `[]` The oracle.
+    ///   7. `[writable]` The market authority.
+    ///   8. `[]` The bank program, owner of the vaults.
+    InitializeMarket {
+        ltv_bps: u16,
+        liquidation_threshold_bps: u16,
+        liquidation_bonus_bps: u16,
+        interest_rate_bps: u16,
+    },
+
+    /// Moves `amount` from the owner's source account into the collateral vault. The
+    /// obligation is claimed for the owner on first use.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The owner.
+    ///   1. `[]` The market.
+    ///   2. `[writable]` The owner's obligation, owned by this program.
+    ///   3. `[writable]` The source account.
+    ///   4. `[writable]` The collateral vault.
+    ///   5. `[]` The bank of the collateral tokens.
+    ///   6. `[]` The bank program, owner of the vault.
+    Deposit { amount: u64 },
+
+    /// Moves `amount` of the owner's collateral back out of the collateral vault, as long
+    /// as what is left covers the debt.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The owner.
+    ///   1. `[writable]` The market.
+    ///   2. `[writable]` The owner's obligation.
+    ///   3. `[writable]` The collateral vault.
+    ///   4. `[writable]` The destination account.
+    ///   5. `[]` The oracle.
+    ///   6. `[writable]` The market authority.
+    ///   7. `[]` The bank of the collateral tokens.
+    ///   8. `[]` The bank program, owner of the vault.
+    Withdraw { amount: u64 },
+
+    /// Lends `amount` out of the liquidity vault against the owner's collateral.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The owner.
+    ///   1. `[writable]` The market.
+    ///   2. `[writable]` The owner's obligation.
+    ///   3. `[writable]` The liquidity vault.
+    ///   4. `[writable]` The destination account.
+    ///   5. `[]` The oracle.
+    ///   6. `[writable]` The market authority.
+    ///   7. `[]` The bank of the borrowed tokens.
+    ///   8. `[]` The bank program, owner of the vault.
+    Borrow { amount: u64 },
+
+    /// Pays back up to `amount` of an obligation's debt, from anyone's source account.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The owner of the source account.
+    ///   1. `[writable]` The market.
+    ///   2. `[writable]` The obligation.
+    ///   3. `[writable]` The source account.
+    ///   4. `[writable]` The liquidity vault.
+    ///   5. `[]` The bank of the borrowed tokens.
+    ///   6. `[]` The bank program, owner of the vault.
+    Repay { amount: u64 },
+
+    /// Pays back up to `amount` of the debt of an obligation past its liquidation
+    /// threshold, for as much of its collateral plus the liquidation bonus.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The liquidator.
+    ///   1. `[writable]` The market.
+    ///   2. `[writable]` The obligation.
+    ///   3. `[writable]` The liquidator's source account.
+    ///   4. `[writable]` The liquidity vault.
+    ///   5. `[writable]` The collateral vault.
+    ///   6. `[writable]` The liquidator's destination account.
+    ///   7. `[]` The oracle.
+    ///   8. `[writable]` The market authority.
+    ///   9. `[]` The bank of the collateral tokens.
+    ///   10. `[]` The bank of the borrowed tokens.
+    ///   11. `[]` The bank program, owner of the vaults.
+    Liquidate { amount: u64 },
+}
+
+impl LendingInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (price, _rest) = Self::unpack_u64(rest)?;
+                Self::InitializeOracle { price }
+            }
+            1 => {
+                let (price, _rest) = Self::unpack_u64(rest)?;
+                Self::SetPrice { price }
+            }
+            2 => {
+                let (ltv_bps, rest) = Self::unpack_u16(rest)?;
+                let (liquidation_threshold_bps, rest) = Self::unpack_u16(rest)?;
+                let (liquidation_bonus_bps, rest) = Self::unpack_u16(rest)?;
+                let (interest_rate_bps, _rest) = Self::unpack_u16(rest)?;
+                Self::InitializeMarket {
+                    ltv_bps,
+                    liquidation_threshold_bps,
+                    liquidation_bonus_bps,
+                    interest_rate_bps,
+                }
+            }
+            3 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Deposit { amount }
+            }
+            4 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Withdraw { amount }
+            }
+            5 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Borrow { amount }
+            }
+            6 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Repay { amount }
+            }
+            7 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Liquidate { amount }
+            }
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(9);
+        match *self {
+            Self::InitializeOracle { price } => {
+                buf.push(0);
+                buf.extend_from_slice(&price.to_le_bytes());
+            }
+            Self::SetPrice { price } => {
+                buf.push(1);
+                buf.extend_from_slice(&price.to_le_bytes());
+            }
+            Self::InitializeMarket {
+                ltv_bps,
+                liquidation_threshold_bps,
+                liquidation_bonus_bps,
+                interest_rate_bps,
+            } => {
+                buf.push(2);
+                buf.extend_from_slice(&ltv_bps.to_le_bytes());
+                buf.extend_from_slice(&liquidation_threshold_bps.to_le_bytes());
+                buf.extend_from_slice(&liquidation_bonus_bps.to_le_bytes());
+                buf.extend_from_slice(&interest_rate_bps.to_le_bytes());
+            }
+            Self::Deposit { amount } => {
+                buf.push(3);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Withdraw { amount } => {
+                buf.push(4);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Borrow { amount } => {
+                buf.push(5);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Repay { amount } => {
+                buf.push(6);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Liquidate { amount } => {
+                buf.push(7);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+        }
+        buf
+    }
+
+    fn unpack_u16(input: &[u8]) -> Result<(u16, &[u8]), ProgramError> {
+        let value = input
+            .get(..2)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u16::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[2..]))
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of the authority owning `market`'s vaults.
+pub fn market_authority(lending_program_id: &Pubkey, market: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MARKET_AUTHORITY_SEED, market.as_ref()],
+        lending_program_id,
+    )
+}
+
+pub fn initialize_oracle(
+    lending_program_id: &Pubkey,
+    authority: &Pubkey,
+    oracle: &Pubkey,
+    price: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = LendingInstruction::InitializeOracle { price }.pack();
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*oracle, false),
+    ];
+    Ok(Instruction {
+        program_id: *lending_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn set_price(
+    lending_program_id: &Pubkey,
+    authority: &Pubkey,
+    oracle: &Pubkey,
+    price: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = LendingInstruction::SetPrice { price }.pack();
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*oracle, false),
+    ];
+    Ok(Instruction {
+        program_id: *lending_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_market(
+    lending_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    admin: &Pubkey,
+    market: &Pubkey,
+    collateral_vault: &Pubkey,
+    liquidity_vault: &Pubkey,
+    collateral_bank: &Pubkey,
+    liquidity_bank: &Pubkey,
+    oracle: &Pubkey,
+    ltv_bps: u16,
+    liquidation_threshold_bps: u16,
+    liquidation_bonus_bps: u16,
+    interest_rate_bps: u16,
+) -> Result<Instruction, ProgramError> {
+    let data = LendingInstruction::InitializeMarket {
+        ltv_bps,
+        liquidation_threshold_bps,
+        liquidation_bonus_bps,
+        interest_rate_bps,
+    }
+    .pack();
+    let (authority, _) = market_authority(lending_program_id, market);
+    let accounts = vec![
+        AccountMeta::new_readonly(*admin, true),
+        AccountMeta::new(*market, false),
+        AccountMeta::new(*collateral_vault, false),
+        AccountMeta::new(*liquidity_vault, false),
+        AccountMeta::new(*collateral_bank, false),
+        AccountMeta::new(*liquidity_bank, false),
+        AccountMeta::new_readonly(*oracle, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *lending_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn deposit(
+    lending_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    collateral_bank: &Pubkey,
+    owner: &Pubkey,
+    market: &Pubkey,
+    obligation: &Pubkey,
+    source_account: &Pubkey,
+    collateral_vault: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = LendingInstruction::Deposit { amount }.pack();
+    let accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new_readonly(*market, false),
+        AccountMeta::new(*obligation, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new(*collateral_vault, false),
+        AccountMeta::new_readonly(*collateral_bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *lending_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw(
+    lending_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    collateral_bank: &Pubkey,
+    owner: &Pubkey,
+    market: &Pubkey,
+    obligation: &Pubkey,
+    collateral_vault: &Pubkey,
+    destination_account: &Pubkey,
+    oracle: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = LendingInstruction::Withdraw { amount }.pack();
+    let (authority, _) = market_authority(lending_program_id, market);
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*market, false),
+        AccountMeta::new(*obligation, false),
+        AccountMeta::new(*collateral_vault, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new_readonly(*oracle, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*collateral_bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *lending_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn borrow(
+    lending_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    liquidity_bank: &Pubkey,
+    owner: &Pubkey,
+    market: &Pubkey,
+    obligation: &Pubkey,
+    liquidity_vault: &Pubkey,
+    destination_account: &Pubkey,
+    oracle: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = LendingInstruction::Borrow { amount }.pack();
+    let (authority, _) = market_authority(lending_program_id, market);
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*market, false),
+        AccountMeta::new(*obligation, false),
+        AccountMeta::new(*liquidity_vault, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new_readonly(*oracle, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*liquidity_bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *lending_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn repay(
+    lending_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    liquidity_bank: &Pubkey,
+    payer: &Pubkey,
+    market: &Pubkey,
+    obligation: &Pubkey,
+    source_account: &Pubkey,
+    liquidity_vault: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = LendingInstruction::Repay { amount }.pack();
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(*market, false),
+        AccountMeta::new(*obligation, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new(*liquidity_vault, false),
+        AccountMeta::new_readonly(*liquidity_bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *lending_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn liquidate(
+    lending_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    collateral_bank: &Pubkey,
+    liquidity_bank: &Pubkey,
+    liquidator: &Pubkey,
+    market: &Pubkey,
+    obligation: &Pubkey,
+    source_account: &Pubkey,
+    liquidity_vault: &Pubkey,
+    collateral_vault: &Pubkey,
+    destination_account: &Pubkey,
+    oracle: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = LendingInstruction::Liquidate { amount }.pack();
+    let (authority, _) = market_authority(lending_program_id, market);
+    let accounts = vec![
+        AccountMeta::new(*liquidator, true),
+        AccountMeta::new(*market, false),
+        AccountMeta::new(*obligation, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new(*liquidity_vault, false),
+        AccountMeta::new(*collateral_vault, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new_readonly(*oracle, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*collateral_bank, false),
+        AccountMeta::new_readonly(*liquidity_bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *lending_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            LendingInstruction::InitializeOracle { price: 1_000_000 },
+            LendingInstruction::SetPrice { price: 7 },
+            LendingInstruction::InitializeMarket {
+                ltv_bps: 5_000,
+                liquidation_threshold_bps: 8_000,
+                liquidation_bonus_bps: 500,
+                interest_rate_bps: u16::MAX,
+            },
+            LendingInstruction::Deposit { amount: 1 },
+            LendingInstruction::Withdraw { amount: 2 },
+            LendingInstruction::Borrow { amount: 3 },
+            LendingInstruction::Repay { amount: u64::MAX },
+            LendingInstruction::Liquidate { amount: 5 },
+        ];
+        for instruction in cases {
+            assert_eq!(
+                LendingInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            LendingInstruction::unpack(&[2, 1, 0, 2, 0, 3, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            LendingInstruction::unpack(&[8]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
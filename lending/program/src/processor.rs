@@ -0,0 +1,616 @@
+use crate::{
+    error::LendingError,
+    instruction::{LendingInstruction, MARKET_AUTHORITY_SEED},
+    state::{Market, Obligation, Oracle, BPS, INDEX_PRECISION},
+};
+use solana_bank::instruction as bank_instruction;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = LendingInstruction::unpack(input)?;
+
+        match instruction {
+            LendingInstruction::InitializeOracle { price } => {
+                msg!("Instruction: InitializeOracle");
+                Self::process_initialize_oracle(program_id, accounts, price)
+            }
+            LendingInstruction::SetPrice { price } => {
+                msg!("Instruction: SetPrice");
+                Self::process_set_price(program_id, accounts, price)
+            }
+            LendingInstruction::InitializeMarket {
+                ltv_bps,
+                liquidation_threshold_bps,
+                liquidation_bonus_bps,
+                interest_rate_bps,
+            } => {
+                msg!("Instruction: InitializeMarket");
+                Self::process_initialize_market(
+                    program_id,
+                    accounts,
+                    ltv_bps,
+                    liquidation_threshold_bps,
+                    liquidation_bonus_bps,
+                    interest_rate_bps,
+                )
+            }
+            LendingInstruction::Deposit { amount } => {
+                msg!("Instruction: Deposit");
+                Self::process_deposit(program_id, accounts, amount)
+            }
+            LendingInstruction::Withdraw { amount } => {
+                msg!("Instruction: Withdraw");
+                Self::process_withdraw(program_id, accounts, amount)
+            }
+            LendingInstruction::Borrow { amount } => {
+                msg!("Instruction: Borrow");
+                Self::process_borrow(program_id, accounts, amount)
+            }
+            LendingInstruction::Repay { amount } => {
+                msg!("Instruction: Repay");
+                Self::process_repay(program_id, accounts, amount)
+            }
+            LendingInstruction::Liquidate { amount } => {
+                msg!("Instruction: Liquidate");
+                Self::process_liquidate(program_id, accounts, amount)
+            }
+        }
+    }
+
+    pub fn process_initialize_oracle(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        price: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority_info = next_account_info(account_info_iter)?;
+        let oracle_info = next_account_info(account_info_iter)?;
+
+        if !authority_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if oracle_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut oracle = Oracle::unpack_unchecked(&oracle_info.data.borrow())?;
+        if oracle.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if price == 0 {
+            return Err(LendingError::InvalidPrice.into());
+        }
+
+        oracle.is_initialized = true;
+        oracle.authority = *authority_info.key;
+        oracle.price = price;
+        Oracle::pack(oracle, &mut oracle_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_set_price(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        price: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority_info = next_account_info(account_info_iter)?;
+        let oracle_info = next_account_info(account_info_iter)?;
+
+        if !authority_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if oracle_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut oracle = Oracle::unpack(&oracle_info.data.borrow())?;
+        if oracle.authority != *authority_info.key {
+            return Err(LendingError::InvalidOracleAuthority.into());
+        }
+        if price == 0 {
+            return Err(LendingError::InvalidPrice.into());
+        }
+
+        oracle.price = price;
+        Oracle::pack(oracle, &mut oracle_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_initialize_market(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        ltv_bps: u16,
+        liquidation_threshold_bps: u16,
+        liquidation_bonus_bps: u16,
+        interest_rate_bps: u16,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let admin_info = next_account_info(account_info_iter)?;
+        let market_info = next_account_info(account_info_iter)?;
+        let collateral_vault_info = next_account_info(account_info_iter)?;
+        let liquidity_vault_info = next_account_info(account_info_iter)?;
+        let collateral_bank_info = next_account_info(account_info_iter)?;
+        let liquidity_bank_info = next_account_info(account_info_iter)?;
+        let oracle_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !admin_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if market_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut market = Market::unpack_unchecked(&market_info.data.borrow())?;
+        if market.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if ltv_bps >= liquidation_threshold_bps || liquidation_threshold_bps as u128 > BPS {
+            return Err(LendingError::InvalidConfig.into());
+        }
+        if oracle_info.owner != program_id {
+            return Err(LendingError::InvalidOracle.into());
+        }
+        Oracle::unpack(&oracle_info.data.borrow())?;
+        let (authority, bump) = Pubkey::find_program_address(
+            &[MARKET_AUTHORITY_SEED, market_info.key.as_ref()],
+            program_id,
+        );
+        if authority != *authority_info.key {
+            return Err(LendingError::InvalidMarketAuthority.into());
+        }
+        let signer_seeds: &[&[u8]] = &[MARKET_AUTHORITY_SEED, market_info.key.as_ref(), &[bump]];
+
+        for (bank_info, vault_info) in [
+            (collateral_bank_info, collateral_vault_info),
+            (liquidity_bank_info, liquidity_vault_info),
+        ] {
+            if vault_info.owner != bank_program_info.key {
+                return Err(LendingError::InvalidVault.into());
+            }
+            invoke_signed(
+                &bank_instruction::initialize_account(
+                    bank_program_info.key,
+                    bank_info.key,
+                    vault_info.key,
+                    authority_info.key,
+                )?,
+                &[
+                    bank_info.clone(),
+                    vault_info.clone(),
+                    authority_info.clone(),
+                    bank_program_info.clone(),
+                ],
+                &[signer_seeds],
+            )?;
+        }
+
+        market.is_initialized = true;
+        market.admin = *admin_info.key;
+        market.oracle = *oracle_info.key;
+        market.collateral_vault = *collateral_vault_info.key;
+        market.liquidity_vault = *liquidity_vault_info.key;
+        market.ltv_bps = ltv_bps;
+        market.liquidation_threshold_bps = liquidation_threshold_bps;
+        market.liquidation_bonus_bps = liquidation_bonus_bps;
+        market.interest_rate_bps = interest_rate_bps;
+        market.borrow_index = INDEX_PRECISION;
+        market.last_update_ts = Clock::get()?.unix_timestamp;
+        market.bump = bump;
+        Market::pack(market, &mut market_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_deposit(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let market_info = next_account_info(account_info_iter)?;
+        let obligation_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let collateral_vault_info = next_account_info(account_info_iter)?;
+        let collateral_bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let market = Self::load_market(program_id, market_info)?;
+        Self::check_vault(
+            &market.collateral_vault,
+            collateral_vault_info,
+            bank_program_info,
+        )?;
+        if obligation_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut obligation = Obligation::unpack_unchecked(&obligation_info.data.borrow())?;
+        if !obligation.is_initialized {
+            obligation.is_initialized = true;
+            obligation.market = *market_info.key;
+            obligation.owner = *owner_info.key;
+            obligation.borrow_index = market.borrow_index;
+        }
+        if obligation.market != *market_info.key || obligation.owner != *owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                collateral_bank_info.key,
+                source_info.key,
+                collateral_vault_info.key,
+                owner_info.key,
+                amount,
+            )?,
+            &[
+                source_info.clone(),
+                collateral_vault_info.clone(),
+                owner_info.clone(),
+                collateral_bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        obligation.collateral = obligation
+            .collateral
+            .checked_add(amount)
+            .ok_or(ProgramError::InvalidArgument)?;
+        Obligation::pack(obligation, &mut obligation_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_withdraw(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let market_info = next_account_info(account_info_iter)?;
+        let obligation_info = next_account_info(account_info_iter)?;
+        let collateral_vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let oracle_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let collateral_bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut market = Self::load_market(program_id, market_info)?;
+        Self::check_vault(
+            &market.collateral_vault,
+            collateral_vault_info,
+            bank_program_info,
+        )?;
+        let mut obligation = Self::load_obligation(program_id, obligation_info, market_info)?;
+        if obligation.owner != *owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let price = Self::load_price(program_id, oracle_info, &market)?;
+
+        market.accrue(Clock::get()?.unix_timestamp);
+        let collateral = obligation
+            .collateral
+            .checked_sub(amount)
+            .ok_or(LendingError::InsufficientCollateral)?;
+        if obligation.debt(&market) as u128 > market.borrow_limit(collateral, price) {
+            return Err(LendingError::ExceedsBorrowLimit.into());
+        }
+        Self::pay_out(
+            program_id,
+            market_info,
+            &market,
+            collateral_vault_info,
+            destination_info,
+            authority_info,
+            collateral_bank_info,
+            bank_program_info,
+            amount,
+        )?;
+
+        obligation.collateral = collateral;
+        Market::pack(market, &mut market_info.data.borrow_mut())?;
+        Obligation::pack(obligation, &mut obligation_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_borrow(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let market_info = next_account_info(account_info_iter)?;
+        let obligation_info = next_account_info(account_info_iter)?;
+        let liquidity_vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let oracle_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let liquidity_bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut market = Self::load_market(program_id, market_info)?;
+        Self::check_vault(
+            &market.liquidity_vault,
+            liquidity_vault_info,
+            bank_program_info,
+        )?;
+        let mut obligation = Self::load_obligation(program_id, obligation_info, market_info)?;
+        if obligation.owner != *owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let price = Self::load_price(program_id, oracle_info, &market)?;
+
+        market.accrue(Clock::get()?.unix_timestamp);
+        let debt = obligation
+            .debt(&market)
+            .checked_add(amount)
+            .ok_or(LendingError::ExceedsBorrowLimit)?;
+        if debt as u128 > market.borrow_limit(obligation.collateral, price) {
+            return Err(LendingError::ExceedsBorrowLimit.into());
+        }
+        Self::pay_out(
+            program_id,
+            market_info,
+            &market,
+            liquidity_vault_info,
+            destination_info,
+            authority_info,
+            liquidity_bank_info,
+            bank_program_info,
+            amount,
+        )?;
+
+        obligation.set_debt(debt, &market);
+        Market::pack(market, &mut market_info.data.borrow_mut())?;
+        Obligation::pack(obligation, &mut obligation_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_repay(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let payer_info = next_account_info(account_info_iter)?;
+        let market_info = next_account_info(account_info_iter)?;
+        let obligation_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let liquidity_vault_info = next_account_info(account_info_iter)?;
+        let liquidity_bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !payer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut market = Self::load_market(program_id, market_info)?;
+        Self::check_vault(
+            &market.liquidity_vault,
+            liquidity_vault_info,
+            bank_program_info,
+        )?;
+        let mut obligation = Self::load_obligation(program_id, obligation_info, market_info)?;
+
+        market.accrue(Clock::get()?.unix_timestamp);
+        let debt = obligation.debt(&market);
+        if debt == 0 {
+            return Err(LendingError::NothingToRepay.into());
+        }
+        let amount = amount.min(debt);
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                liquidity_bank_info.key,
+                source_info.key,
+                liquidity_vault_info.key,
+                payer_info.key,
+                amount,
+            )?,
+            &[
+                source_info.clone(),
+                liquidity_vault_info.clone(),
+                payer_info.clone(),
+                liquidity_bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        obligation.set_debt(debt - amount, &market);
+        Market::pack(market, &mut market_info.data.borrow_mut())?;
+        Obligation::pack(obligation, &mut obligation_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_liquidate(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let liquidator_info = next_account_info(account_info_iter)?;
+        let market_info = next_account_info(account_info_iter)?;
+        let obligation_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let liquidity_vault_info = next_account_info(account_info_iter)?;
+        let collateral_vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let oracle_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let collateral_bank_info = next_account_info(account_info_iter)?;
+        let liquidity_bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !liquidator_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut market = Self::load_market(program_id, market_info)?;
+        Self::check_vault(
+            &market.liquidity_vault,
+            liquidity_vault_info,
+            bank_program_info,
+        )?;
+        Self::check_vault(
+            &market.collateral_vault,
+            collateral_vault_info,
+            bank_program_info,
+        )?;
+        let mut obligation = Self::load_obligation(program_id, obligation_info, market_info)?;
+        let price = Self::load_price(program_id, oracle_info, &market)?;
+
+        market.accrue(Clock::get()?.unix_timestamp);
+        let debt = obligation.debt(&market);
+        if debt as u128 <= market.liquidation_limit(obligation.collateral, price) {
+            return Err(LendingError::ObligationHealthy.into());
+        }
+        let repaid = amount.min(debt);
+        // Collateral worth less than the debt is given up whole.
+        let seized = market
+            .seized(repaid, price)
+            .min(obligation.collateral as u128) as u64;
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                liquidity_bank_info.key,
+                source_info.key,
+                liquidity_vault_info.key,
+                liquidator_info.key,
+                repaid,
+            )?,
+            &[
+                source_info.clone(),
+                liquidity_vault_info.clone(),
+                liquidator_info.clone(),
+                liquidity_bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+        Self::pay_out(
+            program_id,
+            market_info,
+            &market,
+            collateral_vault_info,
+            destination_info,
+            authority_info,
+            collateral_bank_info,
+            bank_program_info,
+            seized,
+        )?;
+
+        obligation.set_debt(debt - repaid, &market);
+        obligation.collateral -= seized;
+        Market::pack(market, &mut market_info.data.borrow_mut())?;
+        Obligation::pack(obligation, &mut obligation_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn load_market(program_id: &Pubkey, market_info: &AccountInfo) -> Result<Market, ProgramError> {
+        if market_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Market::unpack(&market_info.data.borrow())
+    }
+
+    /// Checks `vault_info` is the market's vault `expected`. Pinning the bank program to
+    /// its owner keeps any other program from taking deposits, or the market authority's
+    /// signature, for the bank's.
+    fn check_vault(
+        expected: &Pubkey,
+        vault_info: &AccountInfo,
+        bank_program_info: &AccountInfo,
+    ) -> ProgramResult {
+        if *vault_info.key != *expected || vault_info.owner != bank_program_info.key {
+            return Err(LendingError::InvalidVault.into());
+        }
+        Ok(())
+    }
+
+    fn load_obligation(
+        program_id: &Pubkey,
+        obligation_info: &AccountInfo,
+        market_info: &AccountInfo,
+    ) -> Result<Obligation, ProgramError> {
+        if obligation_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let obligation = Obligation::unpack(&obligation_info.data.borrow())?;
+        if obligation.market != *market_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Ok(obligation)
+    }
+
+    fn load_price(
+        program_id: &Pubkey,
+        oracle_info: &AccountInfo,
+        market: &Market,
+    ) -> Result<u64, ProgramError> {
+        if oracle_info.owner != program_id || market.oracle != *oracle_info.key {
+            return Err(LendingError::InvalidOracle.into());
+        }
+        Ok(Oracle::unpack(&oracle_info.data.borrow())?.price)
+    }
+
+    /// Transfers `amount` out of one of the market's vaults, signed by the market
+    /// authority.
+    #[allow(clippy::too_many_arguments)]
+    fn pay_out<'a>(
+        program_id: &Pubkey,
+        market_info: &AccountInfo<'a>,
+        market: &Market,
+        vault_info: &AccountInfo<'a>,
+        destination_info: &AccountInfo<'a>,
+        authority_info: &AccountInfo<'a>,
+        bank_info: &AccountInfo<'a>,
+        bank_program_info: &AccountInfo<'a>,
+        amount: u64,
+    ) -> ProgramResult {
+        let bump = [market.bump];
+        let signer_seeds: &[&[u8]] = &[MARKET_AUTHORITY_SEED, market_info.key.as_ref(), &bump];
+        let authority = Pubkey::create_program_address(signer_seeds, program_id)
+            .map_err(|_| ProgramError::from(LendingError::InvalidMarketAuthority))?;
+        if authority != *authority_info.key {
+            return Err(LendingError::InvalidMarketAuthority.into());
+        }
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                destination_info.key,
+                authority_info.key,
+                amount,
+            )?,
+            &[
+                vault_info.clone(),
+                destination_info.clone(),
+                authority_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )
+    }
+}
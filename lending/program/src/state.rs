@@ -0,0 +1,352 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+use std::convert::TryFrom;
+
+/// Fixed-point scale of `Oracle::price`.
+pub const PRICE_PRECISION: u128 = 1_000_000;
+/// Fixed-point scale of `Market::borrow_index`.
+pub const INDEX_PRECISION: u128 = 1_000_000_000_000;
+/// Basis points in 100%.
+pub const BPS: u128 = 10_000;
+pub const SECONDS_PER_YEAR: u128 = 365 * 24 * 60 * 60;
+
+/// Price of one collateral token in borrowed tokens, times `PRICE_PRECISION`, as last set
+/// by `authority`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Oracle {
+    pub is_initialized: bool,
+    pub authority: Pubkey,
+    pub price: u64,
+}
+
+impl Sealed for Oracle {}
+impl IsInitialized for Oracle {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Oracle {
+    const LEN: usize = 41;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 41];
+        let (is_initialized, authority, price) = array_refs![src, 1, 32, 8];
+        Ok(Oracle {
+            is_initialized: unpack_bool(is_initialized)?,
+            authority: Pubkey::new_from_array(*authority),
+            price: u64::from_le_bytes(*price),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 41];
+        let (is_initialized, authority, price) = mut_array_refs![dst, 1, 32, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        authority.copy_from_slice(self.authority.as_ref());
+        *price = self.price.to_le_bytes();
+    }
+}
+
+/// A lending market: collateral sits in `collateral_vault`, and loans are paid out of
+/// `liquidity_vault`, which lenders fund with plain bank transfers. An obligation may
+/// borrow up to `ltv_bps` of its collateral's value at the `oracle` price, and can be
+/// liquidated once its debt passes `liquidation_threshold_bps` of it.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Market {
+    pub is_initialized: bool,
+    pub admin: Pubkey,
+    pub oracle: Pubkey,
+    /// Bank accounts owned by the market authority.
+    pub collateral_vault: Pubkey,
+    pub liquidity_vault: Pubkey,
+    pub ltv_bps: u16,
+    pub liquidation_threshold_bps: u16,
+    /// Extra collateral a liquidator receives over the value of the debt repaid.
+    pub liquidation_bonus_bps: u16,
+    /// Yearly interest on debt, accrued by the second.
+    pub interest_rate_bps: u16,
+    /// What one borrowed token at the market's opening is owed now, times
+    /// `INDEX_PRECISION`.
+    pub borrow_index: u128,
+    pub last_update_ts: i64,
+    /// Bump seed of the market authority address.
+    pub bump: u8,
+}
+
+impl Market {
+    /// Brings `borrow_index` up to `now`.
+    pub fn accrue(&mut self, now: i64) {
+        if now <= self.last_update_ts {
+            return;
+        }
+        let elapsed = (now - self.last_update_ts) as u128;
+        let interest = self
+            .borrow_index
+            .saturating_mul(self.interest_rate_bps as u128)
+            .saturating_mul(elapsed)
+            / (BPS * SECONDS_PER_YEAR);
+        self.borrow_index = self.borrow_index.saturating_add(interest);
+        self.last_update_ts = now;
+    }
+
+    /// Most an obligation holding `collateral` may owe at `price`.
+    pub fn borrow_limit(&self, collateral: u64, price: u64) -> u128 {
+        Self::value(collateral, price) * self.ltv_bps as u128 / BPS
+    }
+
+    /// Most an obligation holding `collateral` may owe at `price` before it can be
+    /// liquidated.
+    pub fn liquidation_limit(&self, collateral: u64, price: u64) -> u128 {
+        Self::value(collateral, price) * self.liquidation_threshold_bps as u128 / BPS
+    }
+
+    /// Collateral paid to a liquidator repaying `repay` at `price`, bonus included.
+    pub fn seized(&self, repay: u64, price: u64) -> u128 {
+        repay as u128 * (BPS + self.liquidation_bonus_bps as u128) * PRICE_PRECISION
+            / (BPS * price as u128)
+    }
+
+    fn value(collateral: u64, price: u64) -> u128 {
+        collateral as u128 * price as u128 / PRICE_PRECISION
+    }
+}
+
+impl Sealed for Market {}
+impl IsInitialized for Market {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Market {
+    const LEN: usize = 162;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 162];
+        let (
+            is_initialized,
+            admin,
+            oracle,
+            collateral_vault,
+            liquidity_vault,
+            ltv_bps,
+            liquidation_threshold_bps,
+            liquidation_bonus_bps,
+            interest_rate_bps,
+            borrow_index,
+            last_update_ts,
+            bump,
+        ) = array_refs![src, 1, 32, 32, 32, 32, 2, 2, 2, 2, 16, 8, 1];
+        Ok(Market {
+            is_initialized: unpack_bool(is_initialized)?,
+            admin: Pubkey::new_from_array(*admin),
+            oracle: Pubkey::new_from_array(*oracle),
+            collateral_vault: Pubkey::new_from_array(*collateral_vault),
+            liquidity_vault: Pubkey::new_from_array(*liquidity_vault),
+            ltv_bps: u16::from_le_bytes(*ltv_bps),
+            liquidation_threshold_bps: u16::from_le_bytes(*liquidation_threshold_bps),
+            liquidation_bonus_bps: u16::from_le_bytes(*liquidation_bonus_bps),
+            interest_rate_bps: u16::from_le_bytes(*interest_rate_bps),
+            borrow_index: u128::from_le_bytes(*borrow_index),
+            last_update_ts: i64::from_le_bytes(*last_update_ts),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 162];
+        let (
+            is_initialized,
+            admin,
+            oracle,
+            collateral_vault,
+            liquidity_vault,
+            ltv_bps,
+            liquidation_threshold_bps,
+            liquidation_bonus_bps,
+            interest_rate_bps,
+            borrow_index,
+            last_update_ts,
+            bump,
+        ) = mut_array_refs![dst, 1, 32, 32, 32, 32, 2, 2, 2, 2, 16, 8, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        admin.copy_from_slice(self.admin.as_ref());
+        oracle.copy_from_slice(self.oracle.as_ref());
+        collateral_vault.copy_from_slice(self.collateral_vault.as_ref());
+        liquidity_vault.copy_from_slice(self.liquidity_vault.as_ref());
+        *ltv_bps = self.ltv_bps.to_le_bytes();
+        *liquidation_threshold_bps = self.liquidation_threshold_bps.to_le_bytes();
+        *liquidation_bonus_bps = self.liquidation_bonus_bps.to_le_bytes();
+        *interest_rate_bps = self.interest_rate_bps.to_le_bytes();
+        *borrow_index = self.borrow_index.to_le_bytes();
+        *last_update_ts = self.last_update_ts.to_le_bytes();
+        bump[0] = self.bump;
+    }
+}
+
+/// One owner's collateral and debt in a market.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Obligation {
+    pub is_initialized: bool,
+    pub market: Pubkey,
+    pub owner: Pubkey,
+    pub collateral: u64,
+    /// Debt as of `borrow_index`; it grows with the market's index from there.
+    pub borrowed: u64,
+    pub borrow_index: u128,
+}
+
+impl Obligation {
+    /// What is owed at the market's current index, rounded up.
+    pub fn debt(&self, market: &Market) -> u64 {
+        if self.borrowed == 0 {
+            return 0;
+        }
+        let debt = (self.borrowed as u128 * market.borrow_index).div_ceil(self.borrow_index);
+        u64::try_from(debt).unwrap_or(u64::MAX)
+    }
+
+    /// Resets the debt to `debt` as of the market's current index.
+    pub fn set_debt(&mut self, debt: u64, market: &Market) {
+        self.borrowed = debt;
+        self.borrow_index = market.borrow_index;
+    }
+}
+
+impl Sealed for Obligation {}
+impl IsInitialized for Obligation {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Obligation {
+    const LEN: usize = 97;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 97];
+        let (is_initialized, market, owner, collateral, borrowed, borrow_index) =
+            array_refs![src, 1, 32, 32, 8, 8, 16];
+        Ok(Obligation {
+            is_initialized: unpack_bool(is_initialized)?,
+            market: Pubkey::new_from_array(*market),
+            owner: Pubkey::new_from_array(*owner),
+            collateral: u64::from_le_bytes(*collateral),
+            borrowed: u64::from_le_bytes(*borrowed),
+            borrow_index: u128::from_le_bytes(*borrow_index),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 97];
+        let (is_initialized, market, owner, collateral, borrowed, borrow_index) =
+            mut_array_refs![dst, 1, 32, 32, 8, 8, 16];
+        is_initialized[0] = self.is_initialized as u8;
+        market.copy_from_slice(self.market.as_ref());
+        owner.copy_from_slice(self.owner.as_ref());
+        *collateral = self.collateral.to_le_bytes();
+        *borrowed = self.borrowed.to_le_bytes();
+        *borrow_index = self.borrow_index.to_le_bytes();
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market() -> Market {
+        Market {
+            ltv_bps: 5_000,
+            liquidation_threshold_bps: 8_000,
+            liquidation_bonus_bps: 500,
+            interest_rate_bps: 1_000,
+            borrow_index: INDEX_PRECISION,
+            last_update_ts: 100,
+            ..Market::default()
+        }
+    }
+
+    #[test]
+    fn test_pack_unpack() {
+        let oracle = Oracle {
+            is_initialized: true,
+            authority: Pubkey::new_unique(),
+            price: 1_500_000,
+        };
+        let mut packed = vec![0u8; Oracle::LEN];
+        Oracle::pack(oracle, &mut packed).unwrap();
+        assert_eq!(Oracle::unpack(&packed), Ok(oracle));
+
+        let market = Market {
+            is_initialized: true,
+            admin: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+            collateral_vault: Pubkey::new_unique(),
+            liquidity_vault: Pubkey::new_unique(),
+            bump: 255,
+            ..market()
+        };
+        let mut packed = vec![0u8; Market::LEN];
+        Market::pack(market, &mut packed).unwrap();
+        assert_eq!(Market::unpack(&packed), Ok(market));
+
+        let obligation = Obligation {
+            is_initialized: true,
+            market: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            collateral: 500,
+            borrowed: 200,
+            borrow_index: INDEX_PRECISION * 2,
+        };
+        let mut packed = vec![0u8; Obligation::LEN];
+        Obligation::pack(obligation, &mut packed).unwrap();
+        assert_eq!(Obligation::unpack(&packed), Ok(obligation));
+
+        packed[0] = 2;
+        assert_eq!(
+            Obligation::unpack_unchecked(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_accrue() {
+        let mut market = market();
+        market.accrue(50);
+        assert_eq!(market.borrow_index, INDEX_PRECISION);
+        market.accrue(100 + SECONDS_PER_YEAR as i64);
+        assert_eq!(market.borrow_index, INDEX_PRECISION * 11 / 10);
+
+        let mut obligation = Obligation::default();
+        assert_eq!(obligation.debt(&market), 0);
+        obligation.set_debt(1_000, &market);
+        market.accrue(100 + 2 * SECONDS_PER_YEAR as i64);
+        assert_eq!(obligation.debt(&market), 1_100);
+        market.accrue(101 + 2 * SECONDS_PER_YEAR as i64);
+        // Rounded up in the market's favor.
+        assert_eq!(obligation.debt(&market), 1_101);
+    }
+
+    #[test]
+    fn test_limits() {
+        let market = market();
+        // 1_000 collateral at 2.0 is worth 2_000.
+        assert_eq!(market.borrow_limit(1_000, 2_000_000), 1_000);
+        assert_eq!(market.liquidation_limit(1_000, 2_000_000), 1_600);
+        assert_eq!(market.seized(1_000, 2_000_000), 525);
+        assert_eq!(
+            market.borrow_limit(u64::MAX, u64::MAX),
+            u64::MAX as u128 * u64::MAX as u128 / PRICE_PRECISION / 2
+        );
+    }
+}
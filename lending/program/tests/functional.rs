@@ -0,0 +1,474 @@
+use solana_bank::{instruction::mint_to, state::Account, test_utils::program_test::Env};
+use solana_lending::{
+    error::LendingError,
+    instruction::{
+        borrow, deposit, initialize_market, initialize_oracle, liquidate, market_authority, repay,
+        set_price, withdraw,
+    },
+    processor::Processor,
+    state::{Market, Obligation, Oracle, SECONDS_PER_YEAR},
+};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// Time the market opens at.
+const OPEN: i64 = 1_000;
+/// One collateral token is worth two borrowed tokens to begin with.
+const PRICE: u64 = 2_000_000;
+
+/// A market lending 10_000 tokens of `liquidity_bank` against tokens of
+/// `collateral_bank`, at 50% LTV, an 80% liquidation threshold and a 5% bonus. The
+/// borrower holds 1_000 of each, and the liquidator 5_000 borrowed tokens.
+struct Fixture {
+    env: Env,
+    oracle_authority: Keypair,
+    oracle: Pubkey,
+    market: Pubkey,
+    collateral_bank: Pubkey,
+    liquidity_bank: Pubkey,
+    collateral_vault: Pubkey,
+    liquidity_vault: Pubkey,
+    borrower: Keypair,
+    obligation: Pubkey,
+    collateral_account: Pubkey,
+    loan_account: Pubkey,
+    liquidator: Keypair,
+    liquidator_source: Pubkey,
+    liquidator_destination: Pubkey,
+}
+
+impl Fixture {
+    async fn new(interest_rate_bps: u16) -> Fixture {
+        let mut env = Env::start("solana_lending", processor!(Processor::process)).await;
+        env.set_time(OPEN);
+        let lending_program_id = env.program_id;
+        let bank_program_id = env.bank_program_id;
+        let collateral_bank = env.create_bank().await;
+        let liquidity_bank = env.create_bank().await;
+
+        let (oracle_authority, oracle) = (Keypair::new(), Keypair::new());
+        env.create_account(&oracle, Oracle::LEN, &lending_program_id)
+            .await;
+        let instruction = initialize_oracle(
+            &lending_program_id,
+            &oracle_authority.pubkey(),
+            &oracle.pubkey(),
+            PRICE,
+        )
+        .unwrap();
+        env.process(&[instruction], &[&oracle_authority])
+            .await
+            .unwrap();
+
+        let (market, collateral_vault, liquidity_vault) =
+            (Keypair::new(), Keypair::new(), Keypair::new());
+        env.create_account(&market, Market::LEN, &lending_program_id)
+            .await;
+        env.create_account(&collateral_vault, Account::LEN, &bank_program_id)
+            .await;
+        env.create_account(&liquidity_vault, Account::LEN, &bank_program_id)
+            .await;
+        let instructions = [
+            initialize_market(
+                &lending_program_id,
+                &bank_program_id,
+                &env.context.payer.pubkey(),
+                &market.pubkey(),
+                &collateral_vault.pubkey(),
+                &liquidity_vault.pubkey(),
+                &collateral_bank,
+                &liquidity_bank,
+                &oracle.pubkey(),
+                5_000,
+                8_000,
+                500,
+                interest_rate_bps,
+            )
+            .unwrap(),
+            mint_to(
+                &bank_program_id,
+                &liquidity_bank,
+                &liquidity_vault.pubkey(),
+                &env.context.payer.pubkey(),
+                10_000,
+            )
+            .unwrap(),
+        ];
+        env.process(&instructions, &[]).await.unwrap();
+
+        let (borrower, obligation) = (Keypair::new(), Keypair::new());
+        env.create_account(&obligation, Obligation::LEN, &lending_program_id)
+            .await;
+        let collateral_account = env
+            .create_bank_account(&collateral_bank, &borrower, 1_000)
+            .await;
+        let loan_account = env
+            .create_bank_account(&liquidity_bank, &borrower, 1_000)
+            .await;
+        let liquidator = Keypair::new();
+        let liquidator_source = env
+            .create_bank_account(&liquidity_bank, &liquidator, 5_000)
+            .await;
+        let liquidator_destination = env
+            .create_bank_account(&collateral_bank, &liquidator, 0)
+            .await;
+        Fixture {
+            env,
+            oracle_authority,
+            oracle: oracle.pubkey(),
+            market: market.pubkey(),
+            collateral_bank,
+            liquidity_bank,
+            collateral_vault: collateral_vault.pubkey(),
+            liquidity_vault: liquidity_vault.pubkey(),
+            borrower,
+            obligation: obligation.pubkey(),
+            collateral_account,
+            loan_account,
+            liquidator,
+            liquidator_source,
+            liquidator_destination,
+        }
+    }
+
+    async fn deposit(&mut self, amount: u64) -> Result<(), TransactionError> {
+        let instruction = deposit(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.collateral_bank,
+            &self.borrower.pubkey(),
+            &self.market,
+            &self.obligation,
+            &self.collateral_account,
+            &self.collateral_vault,
+            amount,
+        )
+        .unwrap();
+        let borrower = Keypair::from_bytes(&self.borrower.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&borrower]).await
+    }
+
+    async fn withdraw(&mut self, amount: u64) -> Result<(), TransactionError> {
+        let instruction = withdraw(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.collateral_bank,
+            &self.borrower.pubkey(),
+            &self.market,
+            &self.obligation,
+            &self.collateral_vault,
+            &self.collateral_account,
+            &self.oracle,
+            amount,
+        )
+        .unwrap();
+        let borrower = Keypair::from_bytes(&self.borrower.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&borrower]).await
+    }
+
+    async fn borrow(&mut self, amount: u64) -> Result<(), TransactionError> {
+        let instruction = borrow(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.liquidity_bank,
+            &self.borrower.pubkey(),
+            &self.market,
+            &self.obligation,
+            &self.liquidity_vault,
+            &self.loan_account,
+            &self.oracle,
+            amount,
+        )
+        .unwrap();
+        let borrower = Keypair::from_bytes(&self.borrower.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&borrower]).await
+    }
+
+    async fn repay(&mut self, amount: u64) -> Result<(), TransactionError> {
+        let instruction = repay(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.liquidity_bank,
+            &self.borrower.pubkey(),
+            &self.market,
+            &self.obligation,
+            &self.loan_account,
+            &self.liquidity_vault,
+            amount,
+        )
+        .unwrap();
+        let borrower = Keypair::from_bytes(&self.borrower.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&borrower]).await
+    }
+
+    async fn set_price(&mut self, authority: &Keypair, price: u64) -> Result<(), TransactionError> {
+        let instruction = set_price(
+            &self.env.program_id,
+            &authority.pubkey(),
+            &self.oracle,
+            price,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[authority]).await
+    }
+
+    async fn liquidate(&mut self, amount: u64) -> Result<(), TransactionError> {
+        let instruction = liquidate(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.collateral_bank,
+            &self.liquidity_bank,
+            &self.liquidator.pubkey(),
+            &self.market,
+            &self.obligation,
+            &self.liquidator_source,
+            &self.liquidity_vault,
+            &self.collateral_vault,
+            &self.liquidator_destination,
+            &self.oracle,
+            amount,
+        )
+        .unwrap();
+        let liquidator = Keypair::from_bytes(&self.liquidator.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&liquidator]).await
+    }
+
+    async fn market(&mut self) -> Market {
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(self.market)
+            .await
+            .unwrap()
+            .unwrap();
+        Market::unpack(&account.data).unwrap()
+    }
+
+    async fn obligation(&mut self) -> Obligation {
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(self.obligation)
+            .await
+            .unwrap()
+            .unwrap();
+        Obligation::unpack(&account.data).unwrap()
+    }
+}
+
+fn custom(error: LendingError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_initialize_market() {
+    let mut fixture = Fixture::new(1_000).await;
+    let market = fixture.market().await;
+    assert_eq!(
+        (market.oracle, market.ltv_bps, market.last_update_ts),
+        (fixture.oracle, 5_000, OPEN)
+    );
+    let (authority, _) = market_authority(&fixture.env.program_id, &fixture.market);
+    let vault = fixture.collateral_vault;
+    let vault = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(vault)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Account::unpack(&vault.data).unwrap().owner, authority);
+    let liquidity_vault = fixture.liquidity_vault;
+    assert_eq!(fixture.env.amount(&liquidity_vault).await, 10_000);
+
+    // The LTV has to stay below the liquidation threshold.
+    let lending_program_id = fixture.env.program_id;
+    let bank_program_id = fixture.env.bank_program_id;
+    let (market, collateral_vault, liquidity_vault) =
+        (Keypair::new(), Keypair::new(), Keypair::new());
+    fixture
+        .env
+        .create_account(&market, Market::LEN, &lending_program_id)
+        .await;
+    for vault in [&collateral_vault, &liquidity_vault] {
+        fixture
+            .env
+            .create_account(vault, Account::LEN, &bank_program_id)
+            .await;
+    }
+    let bank = Pubkey::new_unique();
+    let instruction = initialize_market(
+        &lending_program_id,
+        &bank_program_id,
+        &fixture.env.context.payer.pubkey(),
+        &market.pubkey(),
+        &collateral_vault.pubkey(),
+        &liquidity_vault.pubkey(),
+        &bank,
+        &bank,
+        &fixture.oracle,
+        8_000,
+        8_000,
+        500,
+        0,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[]).await,
+        custom(LendingError::InvalidConfig)
+    );
+}
+
+#[tokio::test]
+async fn test_borrow_limit() {
+    let mut fixture = Fixture::new(0).await;
+    fixture.deposit(500).await.unwrap();
+    // 500 collateral at 2.0 lends up to 500.
+    assert_eq!(
+        fixture.borrow(501).await,
+        custom(LendingError::ExceedsBorrowLimit)
+    );
+    fixture.borrow(500).await.unwrap();
+    let loan_account = fixture.loan_account;
+    assert_eq!(fixture.env.amount(&loan_account).await, 1_500);
+    assert_eq!(
+        fixture.withdraw(1).await,
+        custom(LendingError::ExceedsBorrowLimit)
+    );
+
+    fixture.repay(100).await.unwrap();
+    fixture.withdraw(100).await.unwrap();
+    let obligation = fixture.obligation().await;
+    assert_eq!((obligation.collateral, obligation.borrowed), (400, 400));
+    assert_eq!(
+        fixture.withdraw(2).await,
+        custom(LendingError::ExceedsBorrowLimit)
+    );
+    assert_eq!(
+        fixture.withdraw(401).await,
+        custom(LendingError::InsufficientCollateral)
+    );
+    let collateral_account = fixture.collateral_account;
+    assert_eq!(fixture.env.amount(&collateral_account).await, 600);
+}
+
+#[tokio::test]
+async fn test_interest() {
+    let mut fixture = Fixture::new(1_000).await;
+    fixture.deposit(1_000).await.unwrap();
+    fixture.borrow(500).await.unwrap();
+
+    let now = OPEN + SECONDS_PER_YEAR as i64;
+    fixture.env.set_time(now);
+    let mut market = fixture.market().await;
+    market.accrue(now);
+    assert_eq!(fixture.obligation().await.debt(&market), 550);
+
+    // Repaying more than is owed pays the debt off.
+    fixture.repay(u64::MAX).await.unwrap();
+    assert_eq!(fixture.obligation().await.borrowed, 0);
+    let (loan_account, liquidity_vault) = (fixture.loan_account, fixture.liquidity_vault);
+    assert_eq!(fixture.env.amount(&loan_account).await, 950);
+    assert_eq!(fixture.env.amount(&liquidity_vault).await, 10_050);
+    assert_eq!(fixture.repay(1).await, custom(LendingError::NothingToRepay));
+    fixture.withdraw(1_000).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_liquidate() {
+    let mut fixture = Fixture::new(0).await;
+    fixture.deposit(500).await.unwrap();
+    fixture.borrow(500).await.unwrap();
+    assert_eq!(
+        fixture.liquidate(200).await,
+        custom(LendingError::ObligationHealthy)
+    );
+
+    let borrower = Keypair::from_bytes(&fixture.borrower.to_bytes()).unwrap();
+    assert_eq!(
+        fixture.set_price(&borrower, 1_000_000).await,
+        custom(LendingError::InvalidOracleAuthority)
+    );
+    // At 1.2, 500 collateral is worth 600, and 500 of debt is past 80% of it.
+    let oracle_authority = Keypair::from_bytes(&fixture.oracle_authority.to_bytes()).unwrap();
+    fixture
+        .set_price(&oracle_authority, 1_200_000)
+        .await
+        .unwrap();
+    fixture.liquidate(250).await.unwrap();
+
+    // 250 repaid plus 5% is worth 218 collateral at 1.2.
+    let obligation = fixture.obligation().await;
+    assert_eq!((obligation.collateral, obligation.borrowed), (282, 250));
+    let (source, destination) = (fixture.liquidator_source, fixture.liquidator_destination);
+    assert_eq!(fixture.env.amount(&source).await, 4_750);
+    assert_eq!(fixture.env.amount(&destination).await, 218);
+    assert_eq!(
+        fixture.liquidate(100).await,
+        custom(LendingError::ObligationHealthy)
+    );
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new(0).await;
+    let lending_program_id = fixture.env.program_id;
+    let fake_bank_program_id = fixture.env.fake_bank_program_id;
+
+    // Through a program posing as the bank, a deposit would count as collateral without
+    // any tokens moving into the vault.
+    let instruction = deposit(
+        &lending_program_id,
+        &fake_bank_program_id,
+        &fixture.collateral_bank,
+        &fixture.borrower.pubkey(),
+        &fixture.market,
+        &fixture.obligation,
+        &fixture.collateral_account,
+        &fixture.collateral_vault,
+        1_000_000,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture
+            .env
+            .process(&[instruction], &[&fixture.borrower])
+            .await,
+        custom(LendingError::InvalidVault)
+    );
+
+    // Nor does the market authority sign for the liquidity vault.
+    fixture.deposit(500).await.unwrap();
+    let instruction = borrow(
+        &lending_program_id,
+        &fake_bank_program_id,
+        &fixture.liquidity_bank,
+        &fixture.borrower.pubkey(),
+        &fixture.market,
+        &fixture.obligation,
+        &fixture.liquidity_vault,
+        &fixture.loan_account,
+        &fixture.oracle,
+        500,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture
+            .env
+            .process(&[instruction], &[&fixture.borrower])
+            .await,
+        custom(LendingError::InvalidVault)
+    );
+    let obligation = fixture.obligation().await;
+    assert_eq!((obligation.collateral, obligation.borrowed), (500, 0));
+}
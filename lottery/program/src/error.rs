@@ -0,0 +1,37 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LotteryError {
+    /// The lottery authority isn't the lottery's program address.
+    InvalidLotteryAuthority,
+    /// The account isn't the lottery's prize vault.
+    InvalidPrizeVault,
+    /// Tickets can't be free.
+    InvalidTicketPrice,
+    /// The deadline has already passed.
+    InvalidDeadline,
+    /// No tickets were asked for, or their cost overflows.
+    InvalidTicketCount,
+    /// Ticket sales closed at the deadline.
+    SalesClosed,
+    /// Every player slot of the lottery is taken.
+    TooManyPlayers,
+    /// The payout account isn't in the lottery's bank.
+    InvalidPayout,
+    /// The deadline hasn't passed yet.
+    DrawTooEarly,
+    /// The most recent slot hash was known before ticket sales closed.
+    StaleRandomness,
+    /// The lottery has already been drawn.
+    AlreadyDrawn,
+    /// No tickets were sold.
+    NoTickets,
+    /// The winner's payout account wasn't passed to the draw.
+    WinnerNotProvided,
+}
+
+impl From<LotteryError> for ProgramError {
+    fn from(e: LotteryError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,234 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the lottery authority, followed by the lottery's address.
+pub const LOTTERY_AUTHORITY_SEED: &[u8] = b"lottery";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LotteryInstruction {
+    /// Opens a lottery selling tickets at `ticket_price` until `deadline_slot`, and
+    /// initializes its prize vault, owned by the lottery authority.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The lottery, an uninitialized account owned by this program.
+    ///   1. `[writable]` The prize vault, an uninitialized account owned by the bank program.
+    ///   2. `[writable]` The bank tickets are paid in.
+    ///   3. `[writable]` The lottery authority.
+    ///   4. `[]` The bank program, owner of the prize vault.
+    CreateLottery {
+        ticket_price: u64,
+        deadline_slot: u64,
+    },
+
+    /// Pays for `count` tickets into the prize vault; the pot goes to the payout account
+    /// if one of them wins.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The buyer, owner of the source account.
+    ///   1. `[writable]` The lottery.
+    ///   2. `[writable]` The source account.
+    ///   3. `[]` The payout account, in the lottery's bank.
+    ///   4. `[writable]` The prize vault.
+    ///   5. `[]` The lottery's bank.
+    ///   6. `[]` The bank program, owner of the prize vault.
+    BuyTickets { count: u64 },
+
+    /// Draws the winning ticket from the most recent slot hash and pays it the whole
+    /// prize vault. Anyone can draw, once, after the deadline.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The lottery.
+    ///   1. `[writable]` The prize vault.
+    ///   2. `[writable]` The lottery authority.
+    ///   3. `[]` The SlotHashes sysvar.
+    ///   4. `[]` The lottery's bank.
+    ///   5. `[]` The bank program, owner of the prize vault.
+    ///   6. ..6+N `[writable]` The payout account of every player, since the winner
+    ///      isn't known before the draw.
+    Draw,
+}
+
+impl LotteryInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (ticket_price, rest) = Self::unpack_u64(rest)?;
+                let (deadline_slot, _rest) = Self::unpack_u64(rest)?;
+                Self::CreateLottery {
+                    ticket_price,
+                    deadline_slot,
+                }
+            }
+            1 => {
+                let (count, _rest) = Self::unpack_u64(rest)?;
+                Self::BuyTickets { count }
+            }
+            2 => Self::Draw,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(17);
+        match *self {
+            Self::CreateLottery {
+                ticket_price,
+                deadline_slot,
+            } => {
+                buf.push(0);
+                buf.extend_from_slice(&ticket_price.to_le_bytes());
+                buf.extend_from_slice(&deadline_slot.to_le_bytes());
+            }
+            Self::BuyTickets { count } => {
+                buf.push(1);
+                buf.extend_from_slice(&count.to_le_bytes());
+            }
+            Self::Draw => buf.push(2),
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of the authority owning `lottery`'s prize vault.
+pub fn lottery_authority(lottery_program_id: &Pubkey, lottery: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[LOTTERY_AUTHORITY_SEED, lottery.as_ref()],
+        lottery_program_id,
+    )
+}
+
+pub fn create_lottery(
+    lottery_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    lottery: &Pubkey,
+    prize_vault: &Pubkey,
+    bank: &Pubkey,
+    ticket_price: u64,
+    deadline_slot: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = LotteryInstruction::CreateLottery {
+        ticket_price,
+        deadline_slot,
+    }
+    .pack();
+    let (authority, _) = lottery_authority(lottery_program_id, lottery);
+    let accounts = vec![
+        AccountMeta::new(*lottery, false),
+        AccountMeta::new(*prize_vault, false),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *lottery_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn buy_tickets(
+    lottery_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    buyer: &Pubkey,
+    lottery: &Pubkey,
+    source_account: &Pubkey,
+    payout_account: &Pubkey,
+    prize_vault: &Pubkey,
+    count: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = LotteryInstruction::BuyTickets { count }.pack();
+    let accounts = vec![
+        AccountMeta::new(*buyer, true),
+        AccountMeta::new(*lottery, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new_readonly(*payout_account, false),
+        AccountMeta::new(*prize_vault, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *lottery_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// `payout_accounts` are those of `Lottery::players`.
+pub fn draw(
+    lottery_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    lottery: &Pubkey,
+    prize_vault: &Pubkey,
+    payout_accounts: &[Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = LotteryInstruction::Draw.pack();
+    let (authority, _) = lottery_authority(lottery_program_id, lottery);
+    let mut accounts = vec![
+        AccountMeta::new(*lottery, false),
+        AccountMeta::new(*prize_vault, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(sysvar::slot_hashes::id(), false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    for payout_account in payout_accounts {
+        accounts.push(AccountMeta::new(*payout_account, false));
+    }
+    Ok(Instruction {
+        program_id: *lottery_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            LotteryInstruction::CreateLottery {
+                ticket_price: 1,
+                deadline_slot: u64::MAX,
+            },
+            LotteryInstruction::BuyTickets { count: 2 },
+            LotteryInstruction::Draw,
+        ];
+        for instruction in cases {
+            assert_eq!(
+                LotteryInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            LotteryInstruction::unpack(&[0, 1, 0, 0, 0, 0, 0, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            LotteryInstruction::unpack(&[3]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use crate::{
+    error::LotteryError,
+    instruction::{LotteryInstruction, LOTTERY_AUTHORITY_SEED},
+    state::{most_recent_slot_hash, winning_ticket, Lottery},
+};
+use solana_bank::{instruction as bank_instruction, state::Account as BankAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::{self, Sysvar},
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = LotteryInstruction::unpack(input)?;
+
+        match instruction {
+            LotteryInstruction::CreateLottery {
+                ticket_price,
+                deadline_slot,
+            } => {
+                msg!("Instruction: CreateLottery");
+                Self::process_create_lottery(program_id, accounts, ticket_price, deadline_slot)
+            }
+            LotteryInstruction::BuyTickets { count } => {
+                msg!("Instruction: BuyTickets");
+                Self::process_buy_tickets(program_id, accounts, count)
+            }
+            LotteryInstruction::Draw => {
+                msg!("Instruction: Draw");
+                Self::process_draw(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_create_lottery(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        ticket_price: u64,
+        deadline_slot: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let lottery_info = next_account_info(account_info_iter)?;
+        let prize_vault_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if lottery_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut lottery = Lottery::unpack_unchecked(&lottery_info.data.borrow())?;
+        if lottery.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if ticket_price == 0 {
+            return Err(LotteryError::InvalidTicketPrice.into());
+        }
+        if deadline_slot <= Clock::get()?.slot {
+            return Err(LotteryError::InvalidDeadline.into());
+        }
+        let (authority, bump) = Pubkey::find_program_address(
+            &[LOTTERY_AUTHORITY_SEED, lottery_info.key.as_ref()],
+            program_id,
+        );
+        if authority != *authority_info.key {
+            return Err(LotteryError::InvalidLotteryAuthority.into());
+        }
+        if prize_vault_info.owner != bank_program_info.key {
+            return Err(LotteryError::InvalidPrizeVault.into());
+        }
+
+        invoke_signed(
+            &bank_instruction::initialize_account(
+                bank_program_info.key,
+                bank_info.key,
+                prize_vault_info.key,
+                authority_info.key,
+            )?,
+            &[
+                bank_info.clone(),
+                prize_vault_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[LOTTERY_AUTHORITY_SEED, lottery_info.key.as_ref(), &[bump]]],
+        )?;
+
+        lottery.is_initialized = true;
+        lottery.bank = *bank_info.key;
+        lottery.prize_vault = *prize_vault_info.key;
+        lottery.ticket_price = ticket_price;
+        lottery.deadline_slot = deadline_slot;
+        lottery.bump = bump;
+        Lottery::pack(lottery, &mut lottery_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_buy_tickets(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        count: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let buyer_info = next_account_info(account_info_iter)?;
+        let lottery_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let payout_info = next_account_info(account_info_iter)?;
+        let prize_vault_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !buyer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut lottery = Self::load_lottery(program_id, lottery_info)?;
+        if lottery.prize_vault != *prize_vault_info.key
+            || prize_vault_info.owner != bank_program_info.key
+        {
+            return Err(LotteryError::InvalidPrizeVault.into());
+        }
+        if Clock::get()?.slot >= lottery.deadline_slot {
+            return Err(LotteryError::SalesClosed.into());
+        }
+        let cost = match lottery.ticket_price.checked_mul(count) {
+            Some(cost) if count > 0 => cost,
+            _ => return Err(LotteryError::InvalidTicketCount.into()),
+        };
+        // A payout the bank would refuse to credit would leave the pot stuck if it won.
+        if payout_info.owner != bank_program_info.key
+            || BankAccount::unpack(&payout_info.data.borrow())?.bank != lottery.bank
+        {
+            return Err(LotteryError::InvalidPayout.into());
+        }
+        // Every ticket sold was paid for in the bank's tokens, so their count can't
+        // overflow before the bank's supply does.
+        lottery
+            .add_tickets(*payout_info.key, count)
+            .ok_or(LotteryError::TooManyPlayers)?;
+
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                prize_vault_info.key,
+                buyer_info.key,
+                cost,
+            )?,
+            &[
+                source_info.clone(),
+                prize_vault_info.clone(),
+                buyer_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+        Lottery::pack(lottery, &mut lottery_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_draw(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let lottery_info = next_account_info(account_info_iter)?;
+        let prize_vault_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let slot_hashes_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        let mut lottery = Self::load_lottery(program_id, lottery_info)?;
+        if lottery.prize_vault != *prize_vault_info.key
+            || prize_vault_info.owner != bank_program_info.key
+        {
+            return Err(LotteryError::InvalidPrizeVault.into());
+        }
+        if lottery.drawn {
+            return Err(LotteryError::AlreadyDrawn.into());
+        }
+        if Clock::get()?.slot < lottery.deadline_slot {
+            return Err(LotteryError::DrawTooEarly.into());
+        }
+        if lottery.tickets_sold == 0 {
+            return Err(LotteryError::NoTickets.into());
+        }
+        if *slot_hashes_info.key != sysvar::slot_hashes::id() {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let (slot, slot_hash) = most_recent_slot_hash(&slot_hashes_info.data.borrow())
+            .ok_or(ProgramError::InvalidAccountData)?;
+        // Buyers saw every hash before the deadline, and could have timed their tickets
+        // to it.
+        if slot < lottery.deadline_slot {
+            return Err(LotteryError::StaleRandomness.into());
+        }
+        let ticket = winning_ticket(&slot_hash, lottery_info.key, lottery.tickets_sold);
+        let winner = lottery
+            .holder(ticket)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        let payout_info = account_info_iter
+            .find(|info| *info.key == winner)
+            .ok_or(LotteryError::WinnerNotProvided)?;
+        msg!("winning ticket: {}", ticket);
+
+        let bump = [lottery.bump];
+        let signer_seeds: &[&[u8]] = &[LOTTERY_AUTHORITY_SEED, lottery_info.key.as_ref(), &bump];
+        let authority = Pubkey::create_program_address(signer_seeds, program_id)
+            .map_err(|_| ProgramError::from(LotteryError::InvalidLotteryAuthority))?;
+        if authority != *authority_info.key {
+            return Err(LotteryError::InvalidLotteryAuthority.into());
+        }
+        let pot = BankAccount::unpack(&prize_vault_info.data.borrow())?.amount;
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                prize_vault_info.key,
+                payout_info.key,
+                authority_info.key,
+                pot,
+            )?,
+            &[
+                prize_vault_info.clone(),
+                payout_info.clone(),
+                authority_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        lottery.drawn = true;
+        lottery.winner = winner;
+        Lottery::pack(lottery, &mut lottery_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn load_lottery(
+        program_id: &Pubkey,
+        lottery_info: &AccountInfo,
+    ) -> Result<Lottery, ProgramError> {
+        if lottery_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Lottery::unpack(&lottery_info.data.borrow())
+    }
+}
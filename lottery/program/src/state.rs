@@ -0,0 +1,300 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    hash::hashv,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+/// Distinct payout accounts a lottery sells tickets to. The draw takes all of them, so
+/// this also bounds the size of its transaction.
+pub const MAX_PLAYERS: usize = 16;
+
+const HEADER_LEN: usize = 123;
+const PLAYER_LEN: usize = 40;
+
+/// Tickets bought for one payout account.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Player {
+    pub payout: Pubkey,
+    pub tickets: u64,
+}
+
+/// A lottery selling tickets of `bank` tokens into `prize_vault`, a bank account owned by
+/// the lottery authority, until `deadline_slot`. Afterwards anyone can draw it once,
+/// paying the whole vault to the payout account of the winning ticket.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Lottery {
+    pub is_initialized: bool,
+    pub bank: Pubkey,
+    pub prize_vault: Pubkey,
+    pub ticket_price: u64,
+    /// First slot tickets can no longer be bought in, and the lottery can be drawn.
+    pub deadline_slot: u64,
+    pub tickets_sold: u64,
+    pub drawn: bool,
+    /// Payout account of the winning ticket, once drawn.
+    pub winner: Pubkey,
+    /// Bump seed of the lottery authority address.
+    pub bump: u8,
+    pub players: [Option<Player>; MAX_PLAYERS],
+}
+
+impl Lottery {
+    /// Adds `count` tickets for `payout`, in its existing slot or the first free one.
+    /// None when every slot is taken by other payout accounts.
+    pub fn add_tickets(&mut self, payout: Pubkey, count: u64) -> Option<()> {
+        let slot = match self
+            .players
+            .iter()
+            .position(|player| matches!(player, Some(player) if player.payout == payout))
+        {
+            Some(index) => index,
+            None => self.players.iter().position(|player| player.is_none())?,
+        };
+        let tickets = self.players[slot].map_or(0, |player| player.tickets);
+        self.players[slot] = Some(Player {
+            payout,
+            tickets: tickets.checked_add(count)?,
+        });
+        self.tickets_sold = self.tickets_sold.checked_add(count)?;
+        Some(())
+    }
+
+    /// Payout account holding ticket number `ticket`, counting tickets in the order of
+    /// the player slots.
+    pub fn holder(&self, ticket: u64) -> Option<Pubkey> {
+        let mut first = 0u64;
+        for player in self.players.iter().flatten() {
+            if ticket < first + player.tickets {
+                return Some(player.payout);
+            }
+            first += player.tickets;
+        }
+        None
+    }
+}
+
+/// Ticket drawn out of `tickets_sold` by a slot hash. The lottery's address is mixed in
+/// so that lotteries drawn in the same slot don't share an outcome.
+///
+/// A slot hash is only as unpredictable as the leader of that slot is honest: the leader
+/// can skip its slot to discard a hash it dislikes. This is fine for pots smaller than
+/// a slot's rewards, and nothing else.
+pub fn winning_ticket(slot_hash: &[u8; 32], lottery: &Pubkey, tickets_sold: u64) -> u64 {
+    let seed = hashv(&[slot_hash, lottery.as_ref()]).to_bytes();
+    u64::from_le_bytes(seed[..8].try_into().unwrap()) % tickets_sold
+}
+
+/// Slot and hash of the most recent entry of the SlotHashes sysvar's data, read in place
+/// rather than deserializing every entry.
+pub fn most_recent_slot_hash(data: &[u8]) -> Option<(u64, [u8; 32])> {
+    let entries = u64::from_le_bytes(data.get(..8)?.try_into().ok()?);
+    if entries == 0 {
+        return None;
+    }
+    let slot = u64::from_le_bytes(data.get(8..16)?.try_into().ok()?);
+    let hash = data.get(16..48)?.try_into().ok()?;
+    Some((slot, hash))
+}
+
+impl Sealed for Lottery {}
+impl IsInitialized for Lottery {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Lottery {
+    const LEN: usize = HEADER_LEN + MAX_PLAYERS * PLAYER_LEN;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let header = array_ref![src, 0, HEADER_LEN];
+        let (
+            is_initialized,
+            bank,
+            prize_vault,
+            ticket_price,
+            deadline_slot,
+            tickets_sold,
+            drawn,
+            winner,
+            bump,
+        ) = array_refs![header, 1, 32, 32, 8, 8, 8, 1, 32, 1];
+        let mut lottery = Lottery {
+            is_initialized: unpack_bool(is_initialized)?,
+            bank: Pubkey::new_from_array(*bank),
+            prize_vault: Pubkey::new_from_array(*prize_vault),
+            ticket_price: u64::from_le_bytes(*ticket_price),
+            deadline_slot: u64::from_le_bytes(*deadline_slot),
+            tickets_sold: u64::from_le_bytes(*tickets_sold),
+            drawn: unpack_bool(drawn)?,
+            winner: Pubkey::new_from_array(*winner),
+            bump: bump[0],
+            players: [None; MAX_PLAYERS],
+        };
+        for (slot, src) in lottery
+            .players
+            .iter_mut()
+            .zip(src[HEADER_LEN..Self::LEN].chunks_exact(PLAYER_LEN))
+        {
+            let (payout, tickets) = array_refs![array_ref![src, 0, PLAYER_LEN], 32, 8];
+            let tickets = u64::from_le_bytes(*tickets);
+            // A slot is free when nobody holds a ticket in it.
+            if tickets > 0 {
+                *slot = Some(Player {
+                    payout: Pubkey::new_from_array(*payout),
+                    tickets,
+                });
+            }
+        }
+        Ok(lottery)
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let header = array_mut_ref![dst, 0, HEADER_LEN];
+        let (
+            is_initialized,
+            bank,
+            prize_vault,
+            ticket_price,
+            deadline_slot,
+            tickets_sold,
+            drawn,
+            winner,
+            bump,
+        ) = mut_array_refs![header, 1, 32, 32, 8, 8, 8, 1, 32, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        bank.copy_from_slice(self.bank.as_ref());
+        prize_vault.copy_from_slice(self.prize_vault.as_ref());
+        *ticket_price = self.ticket_price.to_le_bytes();
+        *deadline_slot = self.deadline_slot.to_le_bytes();
+        *tickets_sold = self.tickets_sold.to_le_bytes();
+        drawn[0] = self.drawn as u8;
+        winner.copy_from_slice(self.winner.as_ref());
+        bump[0] = self.bump;
+        for (player, dst) in self
+            .players
+            .iter()
+            .zip(dst[HEADER_LEN..Self::LEN].chunks_exact_mut(PLAYER_LEN))
+        {
+            let (payout, tickets) = mut_array_refs![array_mut_ref![dst, 0, PLAYER_LEN], 32, 8];
+            match player {
+                Some(player) => {
+                    payout.copy_from_slice(player.payout.as_ref());
+                    *tickets = player.tickets.to_le_bytes();
+                }
+                None => {
+                    *payout = [0; 32];
+                    *tickets = [0; 8];
+                }
+            }
+        }
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let mut lottery = Lottery {
+            is_initialized: true,
+            bank: Pubkey::new_unique(),
+            prize_vault: Pubkey::new_unique(),
+            ticket_price: 10,
+            deadline_slot: 1_000,
+            drawn: true,
+            winner: Pubkey::new_unique(),
+            bump: 252,
+            ..Lottery::default()
+        };
+        lottery.add_tickets(Pubkey::new_unique(), 3).unwrap();
+        lottery.add_tickets(Pubkey::new_unique(), 1).unwrap();
+        lottery.players[0] = None;
+        let mut packed = vec![0u8; Lottery::LEN];
+        Lottery::pack(lottery.clone(), &mut packed).unwrap();
+        assert_eq!(Lottery::unpack(&packed), Ok(lottery));
+
+        packed[89] = 2;
+        assert_eq!(
+            Lottery::unpack(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_add_tickets() {
+        let mut lottery = Lottery::default();
+        let payouts: Vec<Pubkey> = (0..=MAX_PLAYERS).map(|_| Pubkey::new_unique()).collect();
+        for payout in payouts[..MAX_PLAYERS].iter() {
+            lottery.add_tickets(*payout, 2).unwrap();
+        }
+        assert_eq!(lottery.add_tickets(payouts[MAX_PLAYERS], 1), None);
+        // Someone already playing can still buy more.
+        lottery.add_tickets(payouts[3], 5).unwrap();
+        assert_eq!(lottery.players[3].unwrap().tickets, 7);
+        assert_eq!(lottery.tickets_sold, 2 * MAX_PLAYERS as u64 + 5);
+        assert_eq!(lottery.add_tickets(payouts[3], u64::MAX), None);
+    }
+
+    #[test]
+    fn test_holder() {
+        let mut lottery = Lottery::default();
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+        lottery.add_tickets(alice, 2).unwrap();
+        lottery.add_tickets(bob, 3).unwrap();
+        lottery.add_tickets(alice, 1).unwrap();
+        let holders: Vec<_> = (0..7).map(|ticket| lottery.holder(ticket)).collect();
+        assert_eq!(
+            holders,
+            vec![
+                Some(alice),
+                Some(alice),
+                Some(alice),
+                Some(bob),
+                Some(bob),
+                Some(bob),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_winning_ticket() {
+        let lottery = Pubkey::new_unique();
+        let tickets: Vec<u64> = (0..=255u8)
+            .map(|byte| winning_ticket(&[byte; 32], &lottery, 4))
+            .collect();
+        assert!(tickets.iter().all(|ticket| *ticket < 4));
+        // Every ticket gets drawn by some hash.
+        for ticket in 0..4 {
+            assert!(tickets.contains(&ticket));
+        }
+        assert_ne!(
+            (0..=255u8)
+                .map(|byte| winning_ticket(&[byte; 32], &Pubkey::new_unique(), 4))
+                .collect::<Vec<_>>(),
+            tickets
+        );
+    }
+
+    #[test]
+    fn test_most_recent_slot_hash() {
+        let mut data = vec![2, 0, 0, 0, 0, 0, 0, 0, 7, 0, 0, 0, 0, 0, 0, 0];
+        data.extend_from_slice(&[9; 32]);
+        data.extend_from_slice(&[6, 0, 0, 0, 0, 0, 0, 0]);
+        data.extend_from_slice(&[8; 32]);
+        assert_eq!(most_recent_slot_hash(&data), Some((7, [9; 32])));
+        assert_eq!(most_recent_slot_hash(&data[..40]), None);
+        assert_eq!(most_recent_slot_hash(&[0; 8]), None);
+    }
+}
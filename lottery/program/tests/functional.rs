@@ -0,0 +1,322 @@
+use solana_bank::{state::Account, test_utils::program_test::Env};
+use solana_lottery::{
+    error::LotteryError,
+    instruction::{buy_tickets, create_lottery, draw, lottery_authority},
+    processor::Processor,
+    state::{most_recent_slot_hash, winning_ticket, Lottery},
+};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// Slot the lottery opens at, the slot its sales close at, and the price of a ticket.
+const OPEN: u64 = 100;
+const DEADLINE: u64 = 200;
+const TICKET_PRICE: u64 = 10;
+
+/// Someone holding 1_000 tokens of the lottery's bank, paid out into the same account.
+struct Player {
+    owner: Keypair,
+    account: Pubkey,
+}
+
+struct Fixture {
+    env: Env,
+    bank: Pubkey,
+    lottery: Pubkey,
+    prize_vault: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_lottery", processor!(Processor::process)).await;
+        env.set_slot(OPEN).await;
+        let bank = env.create_bank().await;
+        let lottery = Keypair::new();
+        let prize_vault = Keypair::new();
+        let lottery_program_id = env.program_id;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&lottery, Lottery::LEN, &lottery_program_id)
+            .await;
+        env.create_account(&prize_vault, Account::LEN, &bank_program_id)
+            .await;
+        let instruction = create_lottery(
+            &lottery_program_id,
+            &bank_program_id,
+            &lottery.pubkey(),
+            &prize_vault.pubkey(),
+            &bank,
+            TICKET_PRICE,
+            DEADLINE,
+        )
+        .unwrap();
+        env.process(&[instruction], &[]).await.unwrap();
+        Fixture {
+            env,
+            bank,
+            lottery: lottery.pubkey(),
+            prize_vault: prize_vault.pubkey(),
+        }
+    }
+
+    async fn player(&mut self) -> Player {
+        let owner = Keypair::new();
+        let bank = self.bank;
+        let account = self.env.create_bank_account(&bank, &owner, 1_000).await;
+        Player { owner, account }
+    }
+
+    async fn buy(&mut self, player: &Player, count: u64) -> Result<(), TransactionError> {
+        let instruction = buy_tickets(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &player.owner.pubkey(),
+            &self.lottery,
+            &player.account,
+            &player.account,
+            &self.prize_vault,
+            count,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[&player.owner]).await
+    }
+
+    async fn draw(&mut self, payouts: &[Pubkey]) -> Result<(), TransactionError> {
+        let instruction = draw(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &self.lottery,
+            &self.prize_vault,
+            payouts,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[]).await
+    }
+
+    async fn lottery(&mut self) -> Lottery {
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(self.lottery)
+            .await
+            .unwrap()
+            .unwrap();
+        Lottery::unpack(&account.data).unwrap()
+    }
+}
+
+fn custom(error: LotteryError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_lottery() {
+    let mut fixture = Fixture::new().await;
+    let (authority, _) = lottery_authority(&fixture.env.program_id, &fixture.lottery);
+    let lottery = fixture.lottery().await;
+    assert_eq!(
+        (lottery.bank, lottery.ticket_price, lottery.deadline_slot),
+        (fixture.bank, TICKET_PRICE, DEADLINE)
+    );
+    let vault = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(fixture.prize_vault)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Account::unpack(&vault.data).unwrap().owner, authority);
+
+    let lottery_program_id = fixture.env.program_id;
+    let bank_program_id = fixture.env.bank_program_id;
+    let bank = fixture.bank;
+    let (lottery, prize_vault) = (Keypair::new(), Keypair::new());
+    fixture
+        .env
+        .create_account(&lottery, Lottery::LEN, &lottery_program_id)
+        .await;
+    fixture
+        .env
+        .create_account(&prize_vault, Account::LEN, &bank_program_id)
+        .await;
+    let create = |ticket_price, deadline_slot| {
+        create_lottery(
+            &lottery_program_id,
+            &bank_program_id,
+            &lottery.pubkey(),
+            &prize_vault.pubkey(),
+            &bank,
+            ticket_price,
+            deadline_slot,
+        )
+        .unwrap()
+    };
+    let (free, past) = (create(0, DEADLINE), create(TICKET_PRICE, OPEN));
+    assert_eq!(
+        fixture.env.process(&[free], &[]).await,
+        custom(LotteryError::InvalidTicketPrice)
+    );
+    assert_eq!(
+        fixture.env.process(&[past], &[]).await,
+        custom(LotteryError::InvalidDeadline)
+    );
+}
+
+#[tokio::test]
+async fn test_buy_tickets() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.player().await;
+    let bob = fixture.player().await;
+
+    fixture.buy(&alice, 3).await.unwrap();
+    fixture.buy(&bob, 2).await.unwrap();
+    fixture.buy(&alice, 1).await.unwrap();
+    assert_eq!(fixture.env.amount(&alice.account).await, 960);
+    let prize_vault = fixture.prize_vault;
+    assert_eq!(fixture.env.amount(&prize_vault).await, 60);
+    let lottery = fixture.lottery().await;
+    assert_eq!(lottery.tickets_sold, 6);
+    assert_eq!(lottery.players.iter().flatten().count(), 2);
+
+    assert_eq!(
+        fixture.buy(&bob, 0).await,
+        custom(LotteryError::InvalidTicketCount)
+    );
+    assert_eq!(
+        fixture.buy(&bob, u64::MAX).await,
+        custom(LotteryError::InvalidTicketCount)
+    );
+
+    // The pot can only be paid out in the lottery's bank.
+    let other_bank = fixture.env.create_bank().await;
+    let other = fixture
+        .env
+        .create_bank_account(&other_bank, &bob.owner, 0)
+        .await;
+    let instruction = buy_tickets(
+        &fixture.env.program_id,
+        &fixture.env.bank_program_id,
+        &fixture.bank,
+        &bob.owner.pubkey(),
+        &fixture.lottery,
+        &bob.account,
+        &other,
+        &fixture.prize_vault,
+        1,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&bob.owner]).await,
+        custom(LotteryError::InvalidPayout)
+    );
+
+    fixture.env.set_slot(DEADLINE).await;
+    assert_eq!(
+        fixture.buy(&bob, 1).await,
+        custom(LotteryError::SalesClosed)
+    );
+}
+
+#[tokio::test]
+async fn test_draw() {
+    let mut fixture = Fixture::new().await;
+    let players = [
+        fixture.player().await,
+        fixture.player().await,
+        fixture.player().await,
+    ];
+    for (player, count) in players.iter().zip([3, 2, 1].iter()) {
+        fixture.buy(player, *count).await.unwrap();
+    }
+    let accounts: Vec<Pubkey> = players.iter().map(|player| player.account).collect();
+
+    fixture.env.set_slot(DEADLINE - 1).await;
+    assert_eq!(
+        fixture.draw(&accounts).await,
+        custom(LotteryError::DrawTooEarly)
+    );
+    // A hash from before the deadline was known while tickets were on sale.
+    fixture.env.set_slot(DEADLINE).await;
+    let reversed: Vec<Pubkey> = accounts.iter().rev().cloned().collect();
+    assert_eq!(
+        fixture.draw(&reversed).await,
+        custom(LotteryError::StaleRandomness)
+    );
+
+    fixture.env.set_slot(DEADLINE + 1).await;
+    let (_, slot_hash) = most_recent_slot_hash(&fixture.env.slot_hashes().await).unwrap();
+    let lottery = fixture.lottery().await;
+    let ticket = winning_ticket(&slot_hash, &fixture.lottery, 6);
+    let winner = lottery.holder(ticket).unwrap();
+    let losers: Vec<Pubkey> = accounts
+        .iter()
+        .filter(|account| **account != winner)
+        .cloned()
+        .collect();
+    assert_eq!(
+        fixture.draw(&losers).await,
+        custom(LotteryError::WinnerNotProvided)
+    );
+
+    fixture.draw(&[winner]).await.unwrap();
+    let lottery = fixture.lottery().await;
+    assert!(lottery.drawn);
+    assert_eq!(lottery.winner, winner);
+    let paid_in = [30, 20, 10];
+    for (account, paid) in accounts.iter().zip(paid_in.iter()) {
+        let expected = if *account == winner { 1_060 } else { 1_000 };
+        assert_eq!(fixture.env.amount(account).await, expected - paid);
+    }
+    let prize_vault = fixture.prize_vault;
+    assert_eq!(fixture.env.amount(&prize_vault).await, 0);
+
+    assert_eq!(
+        fixture.draw(&[losers[0], winner]).await,
+        custom(LotteryError::AlreadyDrawn)
+    );
+}
+
+#[tokio::test]
+async fn test_draw_without_tickets() {
+    let mut fixture = Fixture::new().await;
+    fixture.env.set_slot(DEADLINE + 1).await;
+    assert_eq!(fixture.draw(&[]).await, custom(LotteryError::NoTickets));
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    let player = fixture.player().await;
+    fixture.buy(&player, 3).await.unwrap();
+    fixture.env.set_slot(DEADLINE + 1).await;
+
+    // Through a program posing as the bank, a draw would close the lottery without
+    // paying out the pot.
+    let instruction = draw(
+        &fixture.env.program_id,
+        &fixture.env.fake_bank_program_id,
+        &fixture.bank,
+        &fixture.lottery,
+        &fixture.prize_vault,
+        &[player.account],
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[]).await,
+        custom(LotteryError::InvalidPrizeVault)
+    );
+    assert!(!fixture.lottery().await.drawn);
+
+    fixture.draw(&[player.account]).await.unwrap();
+    assert_eq!(fixture.env.amount(&player.account).await, 1_000);
+}
@@ -0,0 +1,25 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MatchingError {
+    /// The pool isn't at the program address for its sponsor and charity.
+    InvalidPool,
+    /// The account isn't the pool's vault.
+    InvalidVault,
+    /// The charity isn't a bank account of the pool's bank, or not the pool's charity.
+    InvalidCharity,
+    /// The donor record isn't at the program address for the pool and the donor.
+    InvalidDonorRecord,
+    /// Donations and withdrawals can't be empty.
+    InvalidAmount,
+    /// A pool has to match something for each donor.
+    InvalidCap,
+    /// The signer isn't the pool's sponsor.
+    NotSponsor,
+}
+
+impl From<MatchingError> for ProgramError {
+    fn from(e: MatchingError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
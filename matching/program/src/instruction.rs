@@ -0,0 +1,247 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of a matching pool, followed by the sponsor's and the charity's addresses.
+pub const POOL_SEED: &[u8] = b"matching_pool";
+/// Seed prefix of a donor record, followed by the pool's and the donor's addresses.
+pub const DONOR_RECORD_SEED: &[u8] = b"donor_record";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MatchingInstruction {
+    /// Creates the sponsor's matching pool for a charity, matching up to `cap` per donor,
+    /// and initializes its vault, owned by the pool. The sponsor funds the pool by
+    /// transferring into the vault.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The sponsor, paying for the pool.
+    ///   1. `[writable]` The pool, the program address for the sponsor and the charity.
+    ///   2. `[]` The charity's bank account.
+    ///   3. `[writable]` The vault, an uninitialized account owned by the bank program.
+    ///   4. `[writable]` The charity's bank.
+    ///   5. `[]` The bank program, owner of the vault and the charity's account.
+    ///   6. `[]` The system program.
+    CreatePool { cap: u64 },
+
+    /// Moves `amount` from the donor to the charity, and as much again out of the vault
+    /// as the donor's cap and the pool's budget allow. Creates the donor's record and
+    /// counts them as a new donor on their first donation.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The donor, owner of the source account, paying for the
+    ///      record.
+    ///   1. `[writable]` The pool.
+    ///   2. `[writable]` The donor's record.
+    ///   3. `[writable]` The source account.
+    ///   4. `[writable]` The charity's bank account.
+    ///   5. `[writable]` The vault.
+    ///   6. `[]` The charity's bank.
+    ///   7. `[]` The bank program, owner of the vault.
+    ///   8. `[]` The system program.
+    Donate { amount: u64 },
+
+    /// Pays `amount` of the unused budget out of the vault to the sponsor.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The sponsor.
+    ///   1. `[writable]` The pool.
+    ///   2. `[writable]` The vault.
+    ///   3. `[writable]` The destination account.
+    ///   4. `[]` The charity's bank.
+    ///   5. `[]` The bank program, owner of the vault.
+    Withdraw { amount: u64 },
+}
+
+impl MatchingInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (cap, _rest) = Self::unpack_u64(rest)?;
+                Self::CreatePool { cap }
+            }
+            1 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Donate { amount }
+            }
+            2 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Withdraw { amount }
+            }
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(9);
+        match *self {
+            Self::CreatePool { cap } => {
+                buf.push(0);
+                buf.extend_from_slice(&cap.to_le_bytes());
+            }
+            Self::Donate { amount } => {
+                buf.push(1);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Withdraw { amount } => {
+                buf.push(2);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of `sponsor`'s matching pool for `charity`.
+pub fn pool_address(
+    matching_program_id: &Pubkey,
+    sponsor: &Pubkey,
+    charity: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[POOL_SEED, sponsor.as_ref(), charity.as_ref()],
+        matching_program_id,
+    )
+}
+
+/// Address and bump of `donor`'s record for `pool`.
+pub fn donor_record_address(
+    matching_program_id: &Pubkey,
+    pool: &Pubkey,
+    donor: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[DONOR_RECORD_SEED, pool.as_ref(), donor.as_ref()],
+        matching_program_id,
+    )
+}
+
+pub fn create_pool(
+    matching_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    sponsor: &Pubkey,
+    charity: &Pubkey,
+    vault: &Pubkey,
+    bank: &Pubkey,
+    cap: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = MatchingInstruction::CreatePool { cap }.pack();
+    let (pool, _) = pool_address(matching_program_id, sponsor, charity);
+    let accounts = vec![
+        AccountMeta::new(*sponsor, true),
+        AccountMeta::new(pool, false),
+        AccountMeta::new_readonly(*charity, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *matching_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn donate(
+    matching_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    donor: &Pubkey,
+    pool: &Pubkey,
+    source_account: &Pubkey,
+    charity: &Pubkey,
+    vault: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = MatchingInstruction::Donate { amount }.pack();
+    let (record, _) = donor_record_address(matching_program_id, pool, donor);
+    let accounts = vec![
+        AccountMeta::new(*donor, true),
+        AccountMeta::new(*pool, false),
+        AccountMeta::new(record, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new(*charity, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *matching_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw(
+    matching_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    sponsor: &Pubkey,
+    pool: &Pubkey,
+    vault: &Pubkey,
+    destination_account: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = MatchingInstruction::Withdraw { amount }.pack();
+    let accounts = vec![
+        AccountMeta::new_readonly(*sponsor, true),
+        AccountMeta::new(*pool, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *matching_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            MatchingInstruction::CreatePool { cap: 500 },
+            MatchingInstruction::Donate { amount: 1 },
+            MatchingInstruction::Withdraw { amount: u64::MAX },
+        ];
+        for instruction in cases {
+            assert_eq!(
+                MatchingInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            MatchingInstruction::unpack(&[1, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            MatchingInstruction::unpack(&[3, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
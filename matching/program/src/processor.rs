@@ -0,0 +1,322 @@
+use crate::{
+    error::MatchingError,
+    instruction::{MatchingInstruction, DONOR_RECORD_SEED, POOL_SEED},
+    state::{DonorRecord, MatchPool},
+};
+use solana_bank::{instruction as bank_instruction, state::Account as BankAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = MatchingInstruction::unpack(input)?;
+
+        match instruction {
+            MatchingInstruction::CreatePool { cap } => {
+                msg!("Instruction: CreatePool");
+                Self::process_create_pool(program_id, accounts, cap)
+            }
+            MatchingInstruction::Donate { amount } => {
+                msg!("Instruction: Donate");
+                Self::process_donate(program_id, accounts, amount)
+            }
+            MatchingInstruction::Withdraw { amount } => {
+                msg!("Instruction: Withdraw");
+                Self::process_withdraw(program_id, accounts, amount)
+            }
+        }
+    }
+
+    pub fn process_create_pool(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        cap: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let sponsor_info = next_account_info(account_info_iter)?;
+        let pool_info = next_account_info(account_info_iter)?;
+        let charity_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !sponsor_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let (pool, bump) = Pubkey::find_program_address(
+            &[
+                POOL_SEED,
+                sponsor_info.key.as_ref(),
+                charity_info.key.as_ref(),
+            ],
+            program_id,
+        );
+        if pool != *pool_info.key {
+            return Err(MatchingError::InvalidPool.into());
+        }
+        if !pool_info.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if charity_info.owner != bank_program_info.key
+            || BankAccount::unpack(&charity_info.data.borrow())?.bank != *bank_info.key
+        {
+            return Err(MatchingError::InvalidCharity.into());
+        }
+        if vault_info.owner != bank_program_info.key {
+            return Err(MatchingError::InvalidVault.into());
+        }
+        if cap == 0 {
+            return Err(MatchingError::InvalidCap.into());
+        }
+        let signer_seeds: &[&[u8]] = &[
+            POOL_SEED,
+            sponsor_info.key.as_ref(),
+            charity_info.key.as_ref(),
+            &[bump],
+        ];
+
+        invoke_signed(
+            &system_instruction::create_account(
+                sponsor_info.key,
+                pool_info.key,
+                Rent::get()?.minimum_balance(MatchPool::LEN),
+                MatchPool::LEN as u64,
+                program_id,
+            ),
+            &[
+                sponsor_info.clone(),
+                pool_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        invoke_signed(
+            &bank_instruction::initialize_account(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                pool_info.key,
+            )?,
+            &[
+                bank_info.clone(),
+                vault_info.clone(),
+                pool_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        let pool = MatchPool {
+            is_initialized: true,
+            sponsor: *sponsor_info.key,
+            charity: *charity_info.key,
+            vault: *vault_info.key,
+            cap,
+            bump,
+            ..MatchPool::default()
+        };
+        MatchPool::pack(pool, &mut pool_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_donate(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let donor_info = next_account_info(account_info_iter)?;
+        let pool_info = next_account_info(account_info_iter)?;
+        let record_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let charity_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !donor_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut pool = Self::load_pool(program_id, pool_info)?;
+        if pool.charity != *charity_info.key {
+            return Err(MatchingError::InvalidCharity.into());
+        }
+        if pool.vault != *vault_info.key || vault_info.owner != bank_program_info.key {
+            return Err(MatchingError::InvalidVault.into());
+        }
+        if amount == 0 {
+            return Err(MatchingError::InvalidAmount.into());
+        }
+        let (record, record_bump) = Pubkey::find_program_address(
+            &[
+                DONOR_RECORD_SEED,
+                pool_info.key.as_ref(),
+                donor_info.key.as_ref(),
+            ],
+            program_id,
+        );
+        if record != *record_info.key {
+            return Err(MatchingError::InvalidDonorRecord.into());
+        }
+        let first_donation = record_info.data_is_empty();
+        if first_donation {
+            invoke_signed(
+                &system_instruction::create_account(
+                    donor_info.key,
+                    record_info.key,
+                    Rent::get()?.minimum_balance(DonorRecord::LEN),
+                    DonorRecord::LEN as u64,
+                    program_id,
+                ),
+                &[
+                    donor_info.clone(),
+                    record_info.clone(),
+                    system_program_info.clone(),
+                ],
+                &[&[
+                    DONOR_RECORD_SEED,
+                    pool_info.key.as_ref(),
+                    donor_info.key.as_ref(),
+                    &[record_bump],
+                ]],
+            )?;
+        }
+
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                charity_info.key,
+                donor_info.key,
+                amount,
+            )?,
+            &[
+                source_info.clone(),
+                charity_info.clone(),
+                donor_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        let mut record = DonorRecord::unpack_unchecked(&record_info.data.borrow())?;
+        let available = BankAccount::unpack(&vault_info.data.borrow())?.amount;
+        let matched = pool.match_amount(amount, record.matched, available);
+        if matched > 0 {
+            invoke_signed(
+                &bank_instruction::transfer(
+                    bank_program_info.key,
+                    bank_info.key,
+                    vault_info.key,
+                    charity_info.key,
+                    pool_info.key,
+                    matched,
+                )?,
+                &[
+                    vault_info.clone(),
+                    charity_info.clone(),
+                    pool_info.clone(),
+                    bank_info.clone(),
+                    bank_program_info.clone(),
+                ],
+                &[&[
+                    POOL_SEED,
+                    pool.sponsor.as_ref(),
+                    pool.charity.as_ref(),
+                    &[pool.bump],
+                ]],
+            )?;
+        }
+
+        record.is_initialized = true;
+        record.donated = record
+            .donated
+            .checked_add(amount)
+            .ok_or(MatchingError::InvalidAmount)?;
+        record.matched += matched;
+        DonorRecord::pack(record, &mut record_info.data.borrow_mut())?;
+        pool.total_donated = pool
+            .total_donated
+            .checked_add(amount)
+            .ok_or(MatchingError::InvalidAmount)?;
+        pool.total_matched += matched;
+        if first_donation {
+            pool.donor_count += 1;
+        }
+        MatchPool::pack(pool, &mut pool_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_withdraw(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let sponsor_info = next_account_info(account_info_iter)?;
+        let pool_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !sponsor_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let pool = Self::load_pool(program_id, pool_info)?;
+        if pool.sponsor != *sponsor_info.key {
+            return Err(MatchingError::NotSponsor.into());
+        }
+        if pool.vault != *vault_info.key || vault_info.owner != bank_program_info.key {
+            return Err(MatchingError::InvalidVault.into());
+        }
+        if amount == 0 {
+            return Err(MatchingError::InvalidAmount.into());
+        }
+
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                destination_info.key,
+                pool_info.key,
+                amount,
+            )?,
+            &[
+                vault_info.clone(),
+                destination_info.clone(),
+                pool_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[
+                POOL_SEED,
+                pool.sponsor.as_ref(),
+                pool.charity.as_ref(),
+                &[pool.bump],
+            ]],
+        )
+    }
+
+    fn load_pool(program_id: &Pubkey, pool_info: &AccountInfo) -> Result<MatchPool, ProgramError> {
+        if pool_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        MatchPool::unpack(&pool_info.data.borrow())
+    }
+}
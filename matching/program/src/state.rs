@@ -0,0 +1,190 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// A sponsor's matching pool for `charity`, a bank account, at a program address derived
+/// from both. The budget sits in `vault`, a bank account owned by the pool, and every
+/// donation made through the pool is matched out of it, up to `cap` per donor.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MatchPool {
+    pub is_initialized: bool,
+    pub sponsor: Pubkey,
+    pub charity: Pubkey,
+    pub vault: Pubkey,
+    /// Most the pool matches for any one donor, over all their donations.
+    pub cap: u64,
+    pub total_donated: u64,
+    pub total_matched: u64,
+    /// Number of distinct donors, each counted on their first donation.
+    pub donor_count: u64,
+    /// Bump seed of the pool's address.
+    pub bump: u8,
+}
+
+impl MatchPool {
+    /// What the pool adds to `amount` from a donor it has already matched `matched` for,
+    /// with `available` left in the vault.
+    pub fn match_amount(&self, amount: u64, matched: u64, available: u64) -> u64 {
+        amount.min(self.cap.saturating_sub(matched)).min(available)
+    }
+}
+
+impl Sealed for MatchPool {}
+impl IsInitialized for MatchPool {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for MatchPool {
+    const LEN: usize = 130;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 130];
+        let (
+            is_initialized,
+            sponsor,
+            charity,
+            vault,
+            cap,
+            total_donated,
+            total_matched,
+            donor_count,
+            bump,
+        ) = array_refs![src, 1, 32, 32, 32, 8, 8, 8, 8, 1];
+        Ok(MatchPool {
+            is_initialized: unpack_bool(is_initialized)?,
+            sponsor: Pubkey::new_from_array(*sponsor),
+            charity: Pubkey::new_from_array(*charity),
+            vault: Pubkey::new_from_array(*vault),
+            cap: u64::from_le_bytes(*cap),
+            total_donated: u64::from_le_bytes(*total_donated),
+            total_matched: u64::from_le_bytes(*total_matched),
+            donor_count: u64::from_le_bytes(*donor_count),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 130];
+        let (
+            is_initialized,
+            sponsor,
+            charity,
+            vault,
+            cap,
+            total_donated,
+            total_matched,
+            donor_count,
+            bump,
+        ) = mut_array_refs![dst, 1, 32, 32, 32, 8, 8, 8, 8, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        sponsor.copy_from_slice(self.sponsor.as_ref());
+        charity.copy_from_slice(self.charity.as_ref());
+        vault.copy_from_slice(self.vault.as_ref());
+        *cap = self.cap.to_le_bytes();
+        *total_donated = self.total_donated.to_le_bytes();
+        *total_matched = self.total_matched.to_le_bytes();
+        *donor_count = self.donor_count.to_le_bytes();
+        bump[0] = self.bump;
+    }
+}
+
+/// What one donor has given through a pool and had matched, at a program address
+/// derived from both.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DonorRecord {
+    pub is_initialized: bool,
+    pub donated: u64,
+    pub matched: u64,
+}
+
+impl Sealed for DonorRecord {}
+impl IsInitialized for DonorRecord {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for DonorRecord {
+    const LEN: usize = 17;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 17];
+        let (is_initialized, donated, matched) = array_refs![src, 1, 8, 8];
+        Ok(DonorRecord {
+            is_initialized: unpack_bool(is_initialized)?,
+            donated: u64::from_le_bytes(*donated),
+            matched: u64::from_le_bytes(*matched),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 17];
+        let (is_initialized, donated, matched) = mut_array_refs![dst, 1, 8, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        *donated = self.donated.to_le_bytes();
+        *matched = self.matched.to_le_bytes();
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let pool = MatchPool {
+            is_initialized: true,
+            sponsor: Pubkey::new_unique(),
+            charity: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            cap: 500,
+            total_donated: u64::MAX,
+            total_matched: 1_200,
+            donor_count: 3,
+            bump: 255,
+        };
+        let mut packed = vec![0u8; MatchPool::LEN];
+        MatchPool::pack(pool, &mut packed).unwrap();
+        assert_eq!(MatchPool::unpack(&packed), Ok(pool));
+
+        let record = DonorRecord {
+            is_initialized: true,
+            donated: 700,
+            matched: 500,
+        };
+        let mut packed = vec![0u8; DonorRecord::LEN];
+        DonorRecord::pack(record, &mut packed).unwrap();
+        assert_eq!(DonorRecord::unpack(&packed), Ok(record));
+
+        packed[0] = 2;
+        assert_eq!(
+            DonorRecord::unpack(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_match_amount() {
+        let pool = MatchPool {
+            cap: 500,
+            ..MatchPool::default()
+        };
+        assert_eq!(pool.match_amount(200, 0, 1_000), 200);
+        // The donor's cap and the pool's budget both limit the match.
+        assert_eq!(pool.match_amount(200, 400, 1_000), 100);
+        assert_eq!(pool.match_amount(200, 500, 1_000), 0);
+        assert_eq!(pool.match_amount(200, 0, 150), 150);
+        assert_eq!(pool.match_amount(200, 0, 0), 0);
+    }
+}
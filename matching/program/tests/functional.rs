@@ -0,0 +1,290 @@
+use solana_bank::{instruction::transfer, state::Account, test_utils::program_test::Env};
+use solana_matching::{
+    error::MatchingError,
+    instruction::{create_pool, donate, donor_record_address, pool_address, withdraw},
+    processor::Processor,
+    state::{DonorRecord, MatchPool},
+};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// A sponsor matching up to 300 per donor out of a 1_000 budget for a charity, in a bank
+/// where alice and bob each hold 1_000 tokens.
+struct Fixture {
+    env: Env,
+    bank: Pubkey,
+    sponsor: Keypair,
+    sponsor_account: Pubkey,
+    charity: Pubkey,
+    pool: Pubkey,
+    vault: Pubkey,
+    alice: Keypair,
+    alice_account: Pubkey,
+    bob: Keypair,
+    bob_account: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_matching", processor!(Processor::process)).await;
+        let bank = env.create_bank().await;
+        let (sponsor, vault) = (Keypair::new(), Keypair::new());
+        env.fund(&sponsor.pubkey(), 100_000_000).await;
+        let sponsor_account = env.create_bank_account(&bank, &sponsor, 1_000).await;
+        let charity = env.create_bank_account(&bank, &Keypair::new(), 0).await;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&vault, Account::LEN, &bank_program_id)
+            .await;
+        let instructions = [
+            create_pool(
+                &env.program_id,
+                &bank_program_id,
+                &sponsor.pubkey(),
+                &charity,
+                &vault.pubkey(),
+                &bank,
+                300,
+            )
+            .unwrap(),
+            transfer(
+                &bank_program_id,
+                &bank,
+                &sponsor_account,
+                &vault.pubkey(),
+                &sponsor.pubkey(),
+                1_000,
+            )
+            .unwrap(),
+        ];
+        env.process(&instructions, &[&sponsor]).await.unwrap();
+        let (pool, _) = pool_address(&env.program_id, &sponsor.pubkey(), &charity);
+
+        let (alice, bob) = (Keypair::new(), Keypair::new());
+        env.fund(&alice.pubkey(), 100_000_000).await;
+        env.fund(&bob.pubkey(), 100_000_000).await;
+        let alice_account = env.create_bank_account(&bank, &alice, 1_000).await;
+        let bob_account = env.create_bank_account(&bank, &bob, 1_000).await;
+        Fixture {
+            env,
+            bank,
+            sponsor,
+            sponsor_account,
+            charity,
+            pool,
+            vault: vault.pubkey(),
+            alice,
+            alice_account,
+            bob,
+            bob_account,
+        }
+    }
+
+    async fn pool(&mut self) -> MatchPool {
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(self.pool)
+            .await
+            .unwrap()
+            .unwrap();
+        MatchPool::unpack(&account.data).unwrap()
+    }
+
+    async fn record(&mut self, donor: &Pubkey) -> DonorRecord {
+        let (record, _) = donor_record_address(&self.env.program_id, &self.pool, donor);
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(record)
+            .await
+            .unwrap()
+            .unwrap();
+        DonorRecord::unpack(&account.data).unwrap()
+    }
+
+    async fn donate(
+        &mut self,
+        donor: &Keypair,
+        source_account: &Pubkey,
+        amount: u64,
+    ) -> Result<(), TransactionError> {
+        let instruction = donate(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &donor.pubkey(),
+            &self.pool,
+            source_account,
+            &self.charity,
+            &self.vault,
+            amount,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[donor]).await
+    }
+
+    async fn withdraw(&mut self, sponsor: &Keypair, amount: u64) -> Result<(), TransactionError> {
+        let instruction = withdraw(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &sponsor.pubkey(),
+            &self.pool,
+            &self.vault,
+            &self.sponsor_account,
+            amount,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[sponsor]).await
+    }
+}
+
+fn custom(error: MatchingError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_pool() {
+    let mut fixture = Fixture::new().await;
+    let pool = fixture.pool().await;
+    assert_eq!(pool.sponsor, fixture.sponsor.pubkey());
+    assert_eq!((pool.charity, pool.vault), (fixture.charity, fixture.vault));
+    assert_eq!(
+        (pool.cap, pool.total_matched, pool.donor_count),
+        (300, 0, 0)
+    );
+    let vault = fixture.vault;
+    assert_eq!(fixture.env.amount(&vault).await, 1_000);
+
+    // The charity has to bank with the bank the vault is opened in.
+    let other_bank = fixture.env.create_bank().await;
+    let vault = Keypair::new();
+    let bank_program_id = fixture.env.bank_program_id;
+    fixture
+        .env
+        .create_account(&vault, Account::LEN, &bank_program_id)
+        .await;
+    let instruction = create_pool(
+        &fixture.env.program_id,
+        &bank_program_id,
+        &fixture.alice.pubkey(),
+        &fixture.charity,
+        &vault.pubkey(),
+        &other_bank,
+        300,
+    )
+    .unwrap();
+    let alice = Keypair::from_bytes(&fixture.alice.to_bytes()).unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&alice]).await,
+        custom(MatchingError::InvalidCharity)
+    );
+    let instruction = create_pool(
+        &fixture.env.program_id,
+        &bank_program_id,
+        &fixture.alice.pubkey(),
+        &fixture.charity,
+        &vault.pubkey(),
+        &fixture.bank,
+        0,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&alice]).await,
+        custom(MatchingError::InvalidCap)
+    );
+}
+
+#[tokio::test]
+async fn test_donate() {
+    let mut fixture = Fixture::new().await;
+    let alice = Keypair::from_bytes(&fixture.alice.to_bytes()).unwrap();
+    let bob = Keypair::from_bytes(&fixture.bob.to_bytes()).unwrap();
+    let (alice_account, bob_account) = (fixture.alice_account, fixture.bob_account);
+    let (charity, vault) = (fixture.charity, fixture.vault);
+
+    fixture.donate(&alice, &alice_account, 200).await.unwrap();
+    assert_eq!(fixture.env.amount(&charity).await, 400);
+    assert_eq!(fixture.env.amount(&vault).await, 800);
+    // Only the rest of alice's cap is matched, then nothing.
+    fixture.donate(&alice, &alice_account, 150).await.unwrap();
+    fixture.donate(&alice, &alice_account, 50).await.unwrap();
+    assert_eq!(fixture.env.amount(&alice_account).await, 600);
+    assert_eq!(fixture.env.amount(&charity).await, 700);
+    let record = fixture.record(&alice.pubkey()).await;
+    assert_eq!((record.donated, record.matched), (400, 300));
+
+    fixture.donate(&bob, &bob_account, 100).await.unwrap();
+    assert_eq!(fixture.env.amount(&charity).await, 900);
+    assert_eq!(fixture.env.amount(&vault).await, 600);
+    let pool = fixture.pool().await;
+    assert_eq!(
+        (pool.total_donated, pool.total_matched, pool.donor_count),
+        (500, 400, 2)
+    );
+
+    assert_eq!(
+        fixture.donate(&bob, &bob_account, 0).await,
+        custom(MatchingError::InvalidAmount)
+    );
+}
+
+#[tokio::test]
+async fn test_budget() {
+    let mut fixture = Fixture::new().await;
+    let sponsor = Keypair::from_bytes(&fixture.sponsor.to_bytes()).unwrap();
+    let bob = Keypair::from_bytes(&fixture.bob.to_bytes()).unwrap();
+    assert_eq!(
+        fixture.withdraw(&bob, 900).await,
+        custom(MatchingError::NotSponsor)
+    );
+    fixture.withdraw(&sponsor, 900).await.unwrap();
+    let sponsor_account = fixture.sponsor_account;
+    assert_eq!(fixture.env.amount(&sponsor_account).await, 900);
+
+    // The match stops where the budget does, and donations still go through after.
+    let (bob_account, charity, vault) = (fixture.bob_account, fixture.charity, fixture.vault);
+    fixture.donate(&bob, &bob_account, 250).await.unwrap();
+    assert_eq!(fixture.env.amount(&vault).await, 0);
+    fixture.donate(&bob, &bob_account, 10).await.unwrap();
+    assert_eq!(fixture.env.amount(&charity).await, 360);
+    let record = fixture.record(&bob.pubkey()).await;
+    assert_eq!((record.donated, record.matched), (260, 100));
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+
+    // Through a program posing as the bank, alice's donation would be matched without
+    // reaching the charity.
+    let instruction = donate(
+        &fixture.env.program_id,
+        &fixture.env.fake_bank_program_id,
+        &fixture.bank,
+        &fixture.alice.pubkey(),
+        &fixture.pool,
+        &fixture.alice_account,
+        &fixture.charity,
+        &fixture.vault,
+        300,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&fixture.alice]).await,
+        custom(MatchingError::InvalidVault)
+    );
+    let pool = fixture.pool().await;
+    assert_eq!((pool.total_donated, pool.total_matched), (0, 0));
+    let vault = fixture.vault;
+    assert_eq!(fixture.env.amount(&vault).await, 1_000);
+}
@@ -0,0 +1,23 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MultisigError {
+    /// The threshold is zero or larger than the number of owners.
+    InvalidThreshold,
+    /// The owners are empty, repeat a key, or are more than `MAX_OWNERS`.
+    InvalidOwners,
+    /// The signer isn't one of the wallet's owners.
+    NotAnOwner,
+    /// The proposal has already been executed.
+    AlreadyExecuted,
+    /// The proposal has fewer approvals than the wallet's threshold.
+    NotEnoughApprovals,
+    /// The proposed instruction has more accounts or data than a proposal holds.
+    InstructionTooLarge,
+}
+
+impl From<MultisigError> for ProgramError {
+    fn from(e: MultisigError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
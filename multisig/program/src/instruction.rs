@@ -0,0 +1,270 @@
+use crate::state::Proposal;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of a wallet's signer address, followed by the wallet's address.
+pub const SIGNER_SEED: &[u8] = b"multisig";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MultisigInstruction {
+    /// Sets up a wallet where `threshold` of `owners` must approve each instruction.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The wallet, an uninitialized account owned by this program.
+    CreateMultisig { threshold: u8, owners: Vec<Pubkey> },
+
+    /// Stores `instruction` for the wallet to execute, approved by the proposer. Its
+    /// accounts should name the wallet's signer address as a signer where the target
+    /// program expects the wallet's signature.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The proposer, one of the owners.
+    ///   1. `[]` The wallet.
+    ///   2. `[writable]` The proposal, an uninitialized account owned by this program.
+    Propose { instruction: Instruction },
+
+    /// Adds the signer's approval to a proposal.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` One of the owners.
+    ///   1. `[]` The wallet.
+    ///   2. `[writable]` The proposal.
+    Approve,
+
+    /// Invokes a proposal's instruction, signed by the wallet's signer address, once it
+    /// has enough approvals. Anyone can execute it.
+    ///
+    /// Accounts expected:
+    ///   0. `[]` The wallet.
+    ///   1. `[writable]` The proposal.
+    ///   2. `[]` The program the proposed instruction targets.
+    ///   3. .. The proposed instruction's accounts, with the signer address not signing.
+    Execute,
+}
+
+impl MultisigInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (&threshold, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let (&count, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let mut owners = Vec::with_capacity(count as usize);
+                let mut rest = rest;
+                for _ in 0..count {
+                    let (owner, tail) = Self::unpack_pubkey(rest)?;
+                    owners.push(owner);
+                    rest = tail;
+                }
+                Self::CreateMultisig { threshold, owners }
+            }
+            1 => {
+                let (program_id, rest) = Self::unpack_pubkey(rest)?;
+                let (&count, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let mut accounts = Vec::with_capacity(count as usize);
+                let mut rest = rest;
+                for _ in 0..count {
+                    let (pubkey, tail) = Self::unpack_pubkey(rest)?;
+                    let flags = tail.get(..2).ok_or(InvalidInstructionData)?;
+                    accounts.push(AccountMeta {
+                        pubkey,
+                        is_signer: flags[0] != 0,
+                        is_writable: flags[1] != 0,
+                    });
+                    rest = &tail[2..];
+                }
+                let len = rest
+                    .get(..2)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u16::from_le_bytes)
+                    .ok_or(InvalidInstructionData)? as usize;
+                let data = rest.get(2..2 + len).ok_or(InvalidInstructionData)?;
+                Self::Propose {
+                    instruction: Instruction {
+                        program_id,
+                        accounts,
+                        data: data.to_vec(),
+                    },
+                }
+            }
+            2 => Self::Approve,
+            3 => Self::Execute,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self {
+            Self::CreateMultisig { threshold, owners } => {
+                buf.push(0);
+                buf.push(*threshold);
+                buf.push(owners.len() as u8);
+                for owner in owners {
+                    buf.extend_from_slice(owner.as_ref());
+                }
+            }
+            Self::Propose { instruction } => {
+                buf.push(1);
+                buf.extend_from_slice(instruction.program_id.as_ref());
+                buf.push(instruction.accounts.len() as u8);
+                for meta in instruction.accounts.iter() {
+                    buf.extend_from_slice(meta.pubkey.as_ref());
+                    buf.push(meta.is_signer as u8);
+                    buf.push(meta.is_writable as u8);
+                }
+                buf.extend_from_slice(&(instruction.data.len() as u16).to_le_bytes());
+                buf.extend_from_slice(&instruction.data);
+            }
+            Self::Approve => buf.push(2),
+            Self::Execute => buf.push(3),
+        }
+        buf
+    }
+
+    fn unpack_pubkey(input: &[u8]) -> Result<(Pubkey, &[u8]), ProgramError> {
+        if input.len() < 32 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (key, rest) = input.split_at(32);
+        let key = Pubkey::new_from_array(key.try_into().unwrap());
+        Ok((key, rest))
+    }
+}
+
+/// Address and bump of the key `multisig` signs its executed instructions with.
+pub fn multisig_signer(multisig_program_id: &Pubkey, multisig: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SIGNER_SEED, multisig.as_ref()], multisig_program_id)
+}
+
+pub fn create_multisig(
+    multisig_program_id: &Pubkey,
+    multisig: &Pubkey,
+    owners: &[Pubkey],
+    threshold: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = MultisigInstruction::CreateMultisig {
+        threshold,
+        owners: owners.to_vec(),
+    }
+    .pack();
+    let accounts = vec![AccountMeta::new(*multisig, false)];
+    Ok(Instruction {
+        program_id: *multisig_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn propose(
+    multisig_program_id: &Pubkey,
+    proposer: &Pubkey,
+    multisig: &Pubkey,
+    proposal: &Pubkey,
+    instruction: Instruction,
+) -> Result<Instruction, ProgramError> {
+    let data = MultisigInstruction::Propose { instruction }.pack();
+    let accounts = vec![
+        AccountMeta::new_readonly(*proposer, true),
+        AccountMeta::new_readonly(*multisig, false),
+        AccountMeta::new(*proposal, false),
+    ];
+    Ok(Instruction {
+        program_id: *multisig_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn approve(
+    multisig_program_id: &Pubkey,
+    owner: &Pubkey,
+    multisig: &Pubkey,
+    proposal: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = MultisigInstruction::Approve.pack();
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new_readonly(*multisig, false),
+        AccountMeta::new(*proposal, false),
+    ];
+    Ok(Instruction {
+        program_id: *multisig_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Executes `proposal`, whose stored state is `state`.
+pub fn execute(
+    multisig_program_id: &Pubkey,
+    multisig: &Pubkey,
+    proposal: &Pubkey,
+    state: &Proposal,
+) -> Result<Instruction, ProgramError> {
+    let data = MultisigInstruction::Execute.pack();
+    let (signer, _) = multisig_signer(multisig_program_id, multisig);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*multisig, false),
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new_readonly(state.program_id, false),
+    ];
+    // The program signs for its signer address inside the CPI, not in the transaction.
+    accounts.extend(state.accounts.iter().map(|meta| AccountMeta {
+        is_signer: meta.is_signer && meta.pubkey != signer,
+        ..meta.clone()
+    }));
+    Ok(Instruction {
+        program_id: *multisig_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            MultisigInstruction::CreateMultisig {
+                threshold: 2,
+                owners: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            },
+            MultisigInstruction::Propose {
+                instruction: Instruction {
+                    program_id: Pubkey::new_unique(),
+                    accounts: vec![
+                        AccountMeta::new(Pubkey::new_unique(), false),
+                        AccountMeta::new_readonly(Pubkey::new_unique(), true),
+                    ],
+                    data: vec![4, 1, 2, 3],
+                },
+            },
+            MultisigInstruction::Approve,
+            MultisigInstruction::Execute,
+        ];
+        for instruction in cases {
+            assert_eq!(
+                MultisigInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            MultisigInstruction::unpack(&[0, 1, 1, 7]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            MultisigInstruction::unpack(&[4]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
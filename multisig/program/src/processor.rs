@@ -0,0 +1,198 @@
+use crate::{
+    error::MultisigError,
+    instruction::{MultisigInstruction, SIGNER_SEED},
+    state::{Multisig, Proposal, MAX_OWNERS},
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = MultisigInstruction::unpack(input)?;
+
+        match instruction {
+            MultisigInstruction::CreateMultisig { threshold, owners } => {
+                msg!("Instruction: CreateMultisig");
+                Self::process_create_multisig(program_id, accounts, threshold, owners)
+            }
+            MultisigInstruction::Propose { instruction } => {
+                msg!("Instruction: Propose");
+                Self::process_propose(program_id, accounts, instruction)
+            }
+            MultisigInstruction::Approve => {
+                msg!("Instruction: Approve");
+                Self::process_approve(program_id, accounts)
+            }
+            MultisigInstruction::Execute => {
+                msg!("Instruction: Execute");
+                Self::process_execute(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_create_multisig(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        threshold: u8,
+        owners: Vec<Pubkey>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let multisig_info = next_account_info(account_info_iter)?;
+
+        if multisig_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut multisig = Multisig::unpack_unchecked(&multisig_info.data.borrow())?;
+        if multisig.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        let repeats = owners
+            .iter()
+            .enumerate()
+            .any(|(i, owner)| owners[..i].contains(owner));
+        if owners.is_empty() || owners.len() > MAX_OWNERS || repeats {
+            return Err(MultisigError::InvalidOwners.into());
+        }
+        if threshold == 0 || threshold as usize > owners.len() {
+            return Err(MultisigError::InvalidThreshold.into());
+        }
+        let (_, bump) =
+            Pubkey::find_program_address(&[SIGNER_SEED, multisig_info.key.as_ref()], program_id);
+
+        multisig.is_initialized = true;
+        multisig.threshold = threshold;
+        multisig.bump = bump;
+        multisig.owners = owners;
+        Multisig::pack(multisig, &mut multisig_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_propose(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        instruction: Instruction,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let proposer_info = next_account_info(account_info_iter)?;
+        let multisig_info = next_account_info(account_info_iter)?;
+        let proposal_info = next_account_info(account_info_iter)?;
+
+        let index = Self::owner_index(program_id, multisig_info, proposer_info)?;
+        if proposal_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut proposal = Proposal::unpack_unchecked(&proposal_info.data.borrow())?;
+        if proposal.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if !Proposal::fits(&instruction) {
+            return Err(MultisigError::InstructionTooLarge.into());
+        }
+
+        proposal.is_initialized = true;
+        proposal.multisig = *multisig_info.key;
+        proposal.program_id = instruction.program_id;
+        proposal.accounts = instruction.accounts;
+        proposal.data = instruction.data;
+        proposal.approvals = 0;
+        proposal.approve(index);
+        proposal.executed = false;
+        Proposal::pack(proposal, &mut proposal_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_approve(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let multisig_info = next_account_info(account_info_iter)?;
+        let proposal_info = next_account_info(account_info_iter)?;
+
+        let index = Self::owner_index(program_id, multisig_info, owner_info)?;
+        let mut proposal = Self::load_proposal(program_id, multisig_info, proposal_info)?;
+        // Approving twice is harmless, so it isn't an error.
+        proposal.approve(index);
+        Proposal::pack(proposal, &mut proposal_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_execute(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let multisig_info = next_account_info(account_info_iter)?;
+        let proposal_info = next_account_info(account_info_iter)?;
+        let target_program_info = next_account_info(account_info_iter)?;
+        let instruction_infos = account_info_iter.as_slice();
+
+        if multisig_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let multisig = Multisig::unpack(&multisig_info.data.borrow())?;
+        let mut proposal = Self::load_proposal(program_id, multisig_info, proposal_info)?;
+        if proposal.approval_count() < multisig.threshold as u32 {
+            return Err(MultisigError::NotEnoughApprovals.into());
+        }
+        if proposal.program_id != *target_program_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let instruction = proposal.instruction();
+
+        // Marked before the call so the proposal can't run twice, even if the target
+        // program calls back into this one.
+        proposal.executed = true;
+        Proposal::pack(proposal, &mut proposal_info.data.borrow_mut())?;
+
+        let mut infos = instruction_infos.to_vec();
+        infos.push(target_program_info.clone());
+        invoke_signed(
+            &instruction,
+            &infos,
+            &[&[SIGNER_SEED, multisig_info.key.as_ref(), &[multisig.bump]]],
+        )
+    }
+
+    /// Index of the signing owner in the wallet.
+    fn owner_index(
+        program_id: &Pubkey,
+        multisig_info: &AccountInfo,
+        owner_info: &AccountInfo,
+    ) -> Result<usize, ProgramError> {
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if multisig_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let multisig = Multisig::unpack(&multisig_info.data.borrow())?;
+        let index = multisig
+            .owner_index(owner_info.key)
+            .ok_or(MultisigError::NotAnOwner)?;
+        Ok(index)
+    }
+
+    /// Loads a proposal of the wallet that hasn't been executed yet.
+    fn load_proposal(
+        program_id: &Pubkey,
+        multisig_info: &AccountInfo,
+        proposal_info: &AccountInfo,
+    ) -> Result<Proposal, ProgramError> {
+        if proposal_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let proposal = Proposal::unpack(&proposal_info.data.borrow())?;
+        if proposal.multisig != *multisig_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        if proposal.executed {
+            return Err(MultisigError::AlreadyExecuted.into());
+        }
+        Ok(proposal)
+    }
+}
@@ -0,0 +1,288 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Most owners a wallet can have; approvals are a bitmask over their indices.
+pub const MAX_OWNERS: usize = 10;
+/// Most accounts a proposed instruction can take.
+pub const MAX_ACCOUNTS: usize = 8;
+/// Most data bytes a proposed instruction can carry.
+pub const MAX_DATA: usize = 128;
+
+/// Bytes of one stored account meta: the key, then the signer and writable flags.
+const META_LEN: usize = 34;
+
+/// Offset of `Proposal::multisig`, for finding every proposal to a wallet.
+pub const PROPOSAL_MULTISIG_OFFSET: usize = 1;
+
+/// An m-of-n wallet. Instructions it approves are signed by its signer address, the
+/// program address derived from the wallet's own.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Multisig {
+    pub is_initialized: bool,
+    /// Approvals needed before a proposal can execute.
+    pub threshold: u8,
+    /// Bump seed of the signer address.
+    pub bump: u8,
+    pub owners: Vec<Pubkey>,
+}
+
+impl Multisig {
+    pub fn owner_index(&self, owner: &Pubkey) -> Option<usize> {
+        self.owners.iter().position(|o| o == owner)
+    }
+}
+
+impl Sealed for Multisig {}
+impl IsInitialized for Multisig {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Multisig {
+    const LEN: usize = 324;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 324];
+        let (is_initialized, threshold, bump, owner_count, owners) =
+            array_refs![src, 1, 1, 1, 1, 32 * MAX_OWNERS];
+        let owner_count = owner_count[0] as usize;
+        if owner_count > MAX_OWNERS {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(Multisig {
+            is_initialized: unpack_bool(is_initialized[0])?,
+            threshold: threshold[0],
+            bump: bump[0],
+            owners: owners
+                .chunks_exact(32)
+                .take(owner_count)
+                .map(|owner| Pubkey::new_from_array(*array_ref![owner, 0, 32]))
+                .collect(),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 324];
+        let (is_initialized, threshold, bump, owner_count, owners) =
+            mut_array_refs![dst, 1, 1, 1, 1, 32 * MAX_OWNERS];
+        is_initialized[0] = self.is_initialized as u8;
+        threshold[0] = self.threshold;
+        bump[0] = self.bump;
+        owner_count[0] = self.owners.len() as u8;
+        owners.fill(0);
+        for (dst, owner) in owners.chunks_exact_mut(32).zip(self.owners.iter()) {
+            dst.copy_from_slice(owner.as_ref());
+        }
+    }
+}
+
+/// An instruction waiting for a wallet's approvals.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Proposal {
+    pub is_initialized: bool,
+    pub multisig: Pubkey,
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+    /// Bit `i` is set once the wallet's owner `i` approved.
+    pub approvals: u16,
+    pub executed: bool,
+}
+
+impl Proposal {
+    /// Whether `instruction` fits into a proposal.
+    pub fn fits(instruction: &Instruction) -> bool {
+        instruction.accounts.len() <= MAX_ACCOUNTS && instruction.data.len() <= MAX_DATA
+    }
+
+    /// Records the approval of owner `index`; false if they had already approved.
+    pub fn approve(&mut self, index: usize) -> bool {
+        let bit = 1 << index;
+        let new = self.approvals & bit == 0;
+        self.approvals |= bit;
+        new
+    }
+
+    pub fn approval_count(&self) -> u32 {
+        self.approvals.count_ones()
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: self.accounts.clone(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl Sealed for Proposal {}
+impl IsInitialized for Proposal {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Proposal {
+    const LEN: usize = 471;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 471];
+        let (
+            is_initialized,
+            multisig,
+            program_id,
+            account_count,
+            accounts,
+            data_len,
+            data,
+            approvals,
+            executed,
+        ) = array_refs![
+            src,
+            1,
+            32,
+            32,
+            1,
+            META_LEN * MAX_ACCOUNTS,
+            2,
+            MAX_DATA,
+            2,
+            1
+        ];
+        let account_count = account_count[0] as usize;
+        let data_len = u16::from_le_bytes(*data_len) as usize;
+        if account_count > MAX_ACCOUNTS || data_len > MAX_DATA {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let accounts = accounts
+            .chunks_exact(META_LEN)
+            .take(account_count)
+            .map(|meta| {
+                Ok(AccountMeta {
+                    pubkey: Pubkey::new_from_array(*array_ref![meta, 0, 32]),
+                    is_signer: unpack_bool(meta[32])?,
+                    is_writable: unpack_bool(meta[33])?,
+                })
+            })
+            .collect::<Result<_, ProgramError>>()?;
+        Ok(Proposal {
+            is_initialized: unpack_bool(is_initialized[0])?,
+            multisig: Pubkey::new_from_array(*multisig),
+            program_id: Pubkey::new_from_array(*program_id),
+            accounts,
+            data: data[..data_len].to_vec(),
+            approvals: u16::from_le_bytes(*approvals),
+            executed: unpack_bool(executed[0])?,
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 471];
+        let (
+            is_initialized,
+            multisig,
+            program_id,
+            account_count,
+            accounts,
+            data_len,
+            data,
+            approvals,
+            executed,
+        ) = mut_array_refs![
+            dst,
+            1,
+            32,
+            32,
+            1,
+            META_LEN * MAX_ACCOUNTS,
+            2,
+            MAX_DATA,
+            2,
+            1
+        ];
+        is_initialized[0] = self.is_initialized as u8;
+        multisig.copy_from_slice(self.multisig.as_ref());
+        program_id.copy_from_slice(self.program_id.as_ref());
+        account_count[0] = self.accounts.len() as u8;
+        accounts.fill(0);
+        for (dst, meta) in accounts
+            .chunks_exact_mut(META_LEN)
+            .zip(self.accounts.iter())
+        {
+            dst[..32].copy_from_slice(meta.pubkey.as_ref());
+            dst[32] = meta.is_signer as u8;
+            dst[33] = meta.is_writable as u8;
+        }
+        *data_len = (self.data.len() as u16).to_le_bytes();
+        data.fill(0);
+        data[..self.data.len()].copy_from_slice(&self.data);
+        *approvals = self.approvals.to_le_bytes();
+        executed[0] = self.executed as u8;
+    }
+}
+
+fn unpack_bool(byte: u8) -> Result<bool, ProgramError> {
+    match byte {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let multisig = Multisig {
+            is_initialized: true,
+            threshold: 2,
+            bump: 250,
+            owners: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+        };
+        let mut packed = vec![0u8; Multisig::LEN];
+        Multisig::pack(multisig.clone(), &mut packed).unwrap();
+        assert_eq!(Multisig::unpack(&packed), Ok(multisig));
+
+        let proposal = Proposal {
+            is_initialized: true,
+            multisig: Pubkey::new_unique(),
+            program_id: Pubkey::new_unique(),
+            accounts: vec![
+                AccountMeta::new(Pubkey::new_unique(), false),
+                AccountMeta::new_readonly(Pubkey::new_unique(), true),
+            ],
+            data: vec![3; MAX_DATA],
+            approvals: 0b101,
+            executed: false,
+        };
+        let mut packed = vec![0u8; Proposal::LEN];
+        Proposal::pack(proposal.clone(), &mut packed).unwrap();
+        assert_eq!(Proposal::unpack(&packed), Ok(proposal.clone()));
+        assert_eq!(
+            packed[PROPOSAL_MULTISIG_OFFSET..PROPOSAL_MULTISIG_OFFSET + 32],
+            proposal.multisig.to_bytes()
+        );
+
+        // A count past the maximum can only come from corrupt data.
+        packed[65] = MAX_ACCOUNTS as u8 + 1;
+        assert_eq!(
+            Proposal::unpack(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_approve() {
+        let mut proposal = Proposal::default();
+        assert!(proposal.approve(0));
+        assert!(proposal.approve(9));
+        assert!(!proposal.approve(0));
+        assert_eq!(proposal.approval_count(), 2);
+        assert_eq!(proposal.approvals, 0b10_0000_0001);
+    }
+}
@@ -0,0 +1,230 @@
+use solana_bank::{
+    instruction::{initialize_account, initialize_bank, mint_to},
+    state::{Account, Bank},
+    test_utils::program_test::Env,
+};
+use solana_multisig::{
+    error::MultisigError,
+    instruction::{approve, create_multisig, execute, multisig_signer, propose},
+    processor::Processor,
+    state::{Multisig, Proposal},
+};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// A 2-of-3 wallet whose signer address owns a bank, opened through the wallet itself.
+struct Fixture {
+    env: Env,
+    owners: Vec<Keypair>,
+    multisig: Pubkey,
+    signer: Pubkey,
+    bank: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_multisig", processor!(Processor::process)).await;
+        let owners = vec![Keypair::new(), Keypair::new(), Keypair::new()];
+        let owner_keys = owners.iter().map(|o| o.pubkey()).collect::<Vec<_>>();
+        let multisig = Keypair::new();
+        let multisig_program_id = env.program_id;
+        env.create_account(&multisig, Multisig::LEN, &multisig_program_id)
+            .await;
+        let instruction =
+            create_multisig(&multisig_program_id, &multisig.pubkey(), &owner_keys, 2).unwrap();
+        env.process(&[instruction], &[]).await.unwrap();
+        let (signer, _) = multisig_signer(&multisig_program_id, &multisig.pubkey());
+
+        let bank = Keypair::new();
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&bank, Bank::LEN, &bank_program_id).await;
+        let mut fixture = Fixture {
+            env,
+            owners,
+            multisig: multisig.pubkey(),
+            signer,
+            bank: bank.pubkey(),
+        };
+        let open = initialize_bank(&bank_program_id, &bank.pubkey(), &signer, 0).unwrap();
+        let proposal = fixture.propose(0, open).await;
+        fixture.approve_and_execute(1, &proposal).await.unwrap();
+        fixture
+    }
+
+    /// Has owner `proposer` propose `instruction` and returns the proposal's address.
+    async fn propose(&mut self, proposer: usize, instruction: Instruction) -> Pubkey {
+        let proposal = Keypair::new();
+        let multisig_program_id = self.env.program_id;
+        self.env
+            .create_account(&proposal, Proposal::LEN, &multisig_program_id)
+            .await;
+        let instruction = propose(
+            &multisig_program_id,
+            &self.owners[proposer].pubkey(),
+            &self.multisig,
+            &proposal.pubkey(),
+            instruction,
+        )
+        .unwrap();
+        self.env
+            .process(&[instruction], &[&self.owners[proposer]])
+            .await
+            .unwrap();
+        proposal.pubkey()
+    }
+
+    async fn proposal(&mut self, proposal: &Pubkey) -> Proposal {
+        let account = self.env.account(proposal).await.unwrap();
+        Proposal::unpack(&account.data).unwrap()
+    }
+
+    fn approve(&self, owner: &Keypair, proposal: &Pubkey) -> Instruction {
+        approve(
+            &self.env.program_id,
+            &owner.pubkey(),
+            &self.multisig,
+            proposal,
+        )
+        .unwrap()
+    }
+
+    async fn execute(&mut self, proposal: &Pubkey) -> Instruction {
+        let state = self.proposal(proposal).await;
+        execute(&self.env.program_id, &self.multisig, proposal, &state).unwrap()
+    }
+
+    /// Has owner `approver` approve the proposal and executes it in the same transaction.
+    async fn approve_and_execute(
+        &mut self,
+        approver: usize,
+        proposal: &Pubkey,
+    ) -> Result<(), TransactionError> {
+        let instructions = [
+            self.approve(&self.owners[approver], proposal),
+            self.execute(proposal).await,
+        ];
+        self.env
+            .process(&instructions, &[&self.owners[approver]])
+            .await
+    }
+}
+
+#[tokio::test]
+async fn test_create_multisig() {
+    let mut env = Env::start("solana_multisig", processor!(Processor::process)).await;
+    let owners = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let multisig = Keypair::new();
+    let multisig_program_id = env.program_id;
+    env.create_account(&multisig, Multisig::LEN, &multisig_program_id)
+        .await;
+
+    let invalid = [
+        (&owners[..], 0, MultisigError::InvalidThreshold),
+        (&owners[..], 3, MultisigError::InvalidThreshold),
+        (&[owners[0], owners[0]][..], 1, MultisigError::InvalidOwners),
+        (&[][..], 1, MultisigError::InvalidOwners),
+    ];
+    for (owners, threshold, error) in invalid.iter().cloned() {
+        let instruction =
+            create_multisig(&multisig_program_id, &multisig.pubkey(), owners, threshold).unwrap();
+        assert_eq!(
+            env.process(&[instruction], &[]).await,
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(error as u32)
+            ))
+        );
+    }
+
+    let instruction =
+        create_multisig(&multisig_program_id, &multisig.pubkey(), &owners, 2).unwrap();
+    env.process(&[instruction], &[]).await.unwrap();
+    let account = env.account(&multisig.pubkey()).await.unwrap();
+    let state = Multisig::unpack(&account.data).unwrap();
+    assert_eq!(state.owners, owners.to_vec());
+    assert_eq!(state.threshold, 2);
+}
+
+#[tokio::test]
+async fn test_open_bank_through_multisig() {
+    let mut fixture = Fixture::new().await;
+    let bank = fixture.env.account(&fixture.bank).await.unwrap();
+    let bank = Bank::unpack(&bank.data).unwrap();
+    assert_eq!(bank.bank_owner, fixture.signer);
+    assert!(bank.is_opened);
+}
+
+#[tokio::test]
+async fn test_mint_through_multisig() {
+    let mut fixture = Fixture::new().await;
+    let holder = Keypair::new();
+    let account = Keypair::new();
+    let bank_program_id = fixture.env.bank_program_id;
+    fixture
+        .env
+        .create_account(&account, Account::LEN, &bank_program_id)
+        .await;
+    let open = initialize_account(
+        &bank_program_id,
+        &fixture.bank,
+        &account.pubkey(),
+        &holder.pubkey(),
+    )
+    .unwrap();
+    fixture.env.process(&[open], &[&holder]).await.unwrap();
+
+    let mint = mint_to(
+        &bank_program_id,
+        &fixture.bank,
+        &account.pubkey(),
+        &fixture.signer,
+        50,
+    )
+    .unwrap();
+    let proposal = fixture.propose(0, mint).await;
+    assert_eq!(fixture.proposal(&proposal).await.approval_count(), 1);
+
+    // One approval of two: nothing happens yet.
+    let early = fixture.execute(&proposal).await;
+    assert_eq!(
+        fixture.env.process(&[early], &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::NotEnoughApprovals as u32)
+        ))
+    );
+    let outsider = Keypair::new();
+    let outsider_approval = fixture.approve(&outsider, &proposal);
+    assert_eq!(
+        fixture
+            .env
+            .process(&[outsider_approval], &[&outsider])
+            .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::NotAnOwner as u32)
+        ))
+    );
+    assert_eq!(fixture.env.amount(&account.pubkey()).await, 0);
+
+    fixture.approve_and_execute(2, &proposal).await.unwrap();
+    assert_eq!(fixture.env.amount(&account.pubkey()).await, 50);
+    assert!(fixture.proposal(&proposal).await.executed);
+
+    let late = fixture.approve(&fixture.owners[1], &proposal);
+    assert_eq!(
+        fixture.env.process(&[late], &[&fixture.owners[1]]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MultisigError::AlreadyExecuted as u32)
+        ))
+    );
+}
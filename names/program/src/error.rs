@@ -0,0 +1,21 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NameError {
+    /// The name is empty, too long, or has characters other than `a-z`, `0-9` and `-`.
+    InvalidName,
+    /// The record isn't at the name's program address.
+    InvalidRecord,
+    /// The name is registered and hasn't expired.
+    NameTaken,
+    /// The signer doesn't own the name.
+    NotOwner,
+    /// The name's registration has run out; it can only be registered again.
+    Expired,
+}
+
+impl From<NameError> for ProgramError {
+    fn from(e: NameError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
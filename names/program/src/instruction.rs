@@ -0,0 +1,249 @@
+use crate::{error::NameError, state::is_valid_name};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of a name's record, followed by the name.
+pub const NAME_SEED: &[u8] = b"name";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum NameInstruction {
+    /// Registers `name` to the registrant for `REGISTRATION_PERIOD`, pointing at
+    /// `target`. Creates the record, or takes it over once the previous registration has
+    /// expired.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The registrant, paying for the record.
+    ///   1. `[writable]` The name's record.
+    ///   2. `[]` The system program.
+    Register { name: String, target: Pubkey },
+
+    /// Extends the registration by `REGISTRATION_PERIOD`.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The name's owner.
+    ///   1. `[writable]` The name's record.
+    Renew,
+
+    /// Points the name at `target`.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The name's owner.
+    ///   1. `[writable]` The name's record.
+    SetTarget { target: Pubkey },
+
+    /// Hands the name over to `new_owner`, keeping its target and expiry.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The name's owner.
+    ///   1. `[writable]` The name's record.
+    Transfer { new_owner: Pubkey },
+}
+
+impl NameInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (name, rest) = Self::unpack_str(rest)?;
+                let (target, _rest) = Self::unpack_pubkey(rest)?;
+                Self::Register { name, target }
+            }
+            1 => Self::Renew,
+            2 => {
+                let (target, _rest) = Self::unpack_pubkey(rest)?;
+                Self::SetTarget { target }
+            }
+            3 => {
+                let (new_owner, _rest) = Self::unpack_pubkey(rest)?;
+                Self::Transfer { new_owner }
+            }
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self {
+            Self::Register { name, target } => {
+                buf.push(0);
+                Self::pack_str(name, &mut buf);
+                buf.extend_from_slice(target.as_ref());
+            }
+            Self::Renew => buf.push(1),
+            Self::SetTarget { target } => {
+                buf.push(2);
+                buf.extend_from_slice(target.as_ref());
+            }
+            Self::Transfer { new_owner } => {
+                buf.push(3);
+                buf.extend_from_slice(new_owner.as_ref());
+            }
+        }
+        buf
+    }
+
+    fn unpack_pubkey(input: &[u8]) -> Result<(Pubkey, &[u8]), ProgramError> {
+        if input.len() < 32 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (key, rest) = input.split_at(32);
+        let key = Pubkey::new_from_array(key.try_into().unwrap());
+        Ok((key, rest))
+    }
+
+    /// A string prefixed by its length in bytes, as a u8.
+    fn unpack_str(input: &[u8]) -> Result<(String, &[u8]), ProgramError> {
+        let (&len, rest) = input
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let bytes = rest
+            .get(..len as usize)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let value =
+            String::from_utf8(bytes.to_vec()).map_err(|_| ProgramError::InvalidInstructionData)?;
+        Ok((value, &rest[len as usize..]))
+    }
+
+    /// Strings over 255 bytes are cut short; no valid name is that long.
+    fn pack_str(value: &str, buf: &mut Vec<u8>) {
+        let bytes = &value.as_bytes()[..value.len().min(u8::MAX as usize)];
+        buf.push(bytes.len() as u8);
+        buf.extend_from_slice(bytes);
+    }
+}
+
+/// Address and bump of `name`'s record.
+pub fn name_address(names_program_id: &Pubkey, name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[NAME_SEED, name.as_bytes()], names_program_id)
+}
+
+pub fn register(
+    names_program_id: &Pubkey,
+    registrant: &Pubkey,
+    name: &str,
+    target: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    if !is_valid_name(name) {
+        return Err(NameError::InvalidName.into());
+    }
+    let data = NameInstruction::Register {
+        name: name.to_string(),
+        target: *target,
+    }
+    .pack();
+    let (record, _) = name_address(names_program_id, name);
+    let accounts = vec![
+        AccountMeta::new(*registrant, true),
+        AccountMeta::new(record, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *names_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn renew(
+    names_program_id: &Pubkey,
+    owner: &Pubkey,
+    name: &str,
+) -> Result<Instruction, ProgramError> {
+    owner_instruction(names_program_id, owner, name, NameInstruction::Renew)
+}
+
+pub fn set_target(
+    names_program_id: &Pubkey,
+    owner: &Pubkey,
+    name: &str,
+    target: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let instruction = NameInstruction::SetTarget { target: *target };
+    owner_instruction(names_program_id, owner, name, instruction)
+}
+
+pub fn transfer(
+    names_program_id: &Pubkey,
+    owner: &Pubkey,
+    name: &str,
+    new_owner: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let instruction = NameInstruction::Transfer {
+        new_owner: *new_owner,
+    };
+    owner_instruction(names_program_id, owner, name, instruction)
+}
+
+fn owner_instruction(
+    names_program_id: &Pubkey,
+    owner: &Pubkey,
+    name: &str,
+    instruction: NameInstruction,
+) -> Result<Instruction, ProgramError> {
+    if !is_valid_name(name) {
+        return Err(NameError::InvalidName.into());
+    }
+    let (record, _) = name_address(names_program_id, name);
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(record, false),
+    ];
+    Ok(Instruction {
+        program_id: *names_program_id,
+        accounts,
+        data: instruction.pack(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            NameInstruction::Register {
+                name: "alice".to_string(),
+                target: Pubkey::new_unique(),
+            },
+            NameInstruction::Renew,
+            NameInstruction::SetTarget {
+                target: Pubkey::new_unique(),
+            },
+            NameInstruction::Transfer {
+                new_owner: Pubkey::new_unique(),
+            },
+        ];
+        for instruction in cases {
+            assert_eq!(
+                NameInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            NameInstruction::unpack(&[0, 5, b'a', b'l']),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            NameInstruction::unpack(&[2, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            register(
+                &Pubkey::new_unique(),
+                &Pubkey::new_unique(),
+                "Alice",
+                &Pubkey::new_unique()
+            ),
+            Err(NameError::InvalidName.into())
+        );
+    }
+}
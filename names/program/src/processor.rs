@@ -0,0 +1,173 @@
+use crate::{
+    error::NameError,
+    instruction::{NameInstruction, NAME_SEED},
+    state::{is_valid_name, NameRecord, REGISTRATION_PERIOD},
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = NameInstruction::unpack(input)?;
+
+        match instruction {
+            NameInstruction::Register { name, target } => {
+                msg!("Instruction: Register");
+                Self::process_register(program_id, accounts, name, target)
+            }
+            NameInstruction::Renew => {
+                msg!("Instruction: Renew");
+                Self::process_renew(program_id, accounts)
+            }
+            NameInstruction::SetTarget { target } => {
+                msg!("Instruction: SetTarget");
+                Self::process_set_target(program_id, accounts, target)
+            }
+            NameInstruction::Transfer { new_owner } => {
+                msg!("Instruction: Transfer");
+                Self::process_transfer(program_id, accounts, new_owner)
+            }
+        }
+    }
+
+    pub fn process_register(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        name: String,
+        target: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let registrant_info = next_account_info(account_info_iter)?;
+        let record_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !registrant_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if !is_valid_name(&name) {
+            return Err(NameError::InvalidName.into());
+        }
+        let (address, bump) =
+            Pubkey::find_program_address(&[NAME_SEED, name.as_bytes()], program_id);
+        if address != *record_info.key {
+            return Err(NameError::InvalidRecord.into());
+        }
+        let now = Clock::get()?.unix_timestamp;
+
+        if record_info.data_is_empty() {
+            invoke_signed(
+                &system_instruction::create_account(
+                    registrant_info.key,
+                    record_info.key,
+                    Rent::get()?.minimum_balance(NameRecord::LEN),
+                    NameRecord::LEN as u64,
+                    program_id,
+                ),
+                &[
+                    registrant_info.clone(),
+                    record_info.clone(),
+                    system_program_info.clone(),
+                ],
+                &[&[NAME_SEED, name.as_bytes(), &[bump]]],
+            )?;
+        } else {
+            if record_info.owner != program_id {
+                return Err(ProgramError::IllegalOwner);
+            }
+            let previous = NameRecord::unpack(&record_info.data.borrow())?;
+            if !previous.is_expired(now) {
+                return Err(NameError::NameTaken.into());
+            }
+        }
+
+        let record = NameRecord {
+            is_initialized: true,
+            owner: *registrant_info.key,
+            target,
+            expires_ts: now
+                .checked_add(REGISTRATION_PERIOD)
+                .ok_or(ProgramError::InvalidArgument)?,
+            name,
+        };
+        NameRecord::pack(record, &mut record_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_renew(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let record_info = next_account_info(account_info_iter)?;
+
+        let mut record = Self::load_owned(program_id, owner_info, record_info)?;
+        record.expires_ts = record
+            .expires_ts
+            .checked_add(REGISTRATION_PERIOD)
+            .ok_or(ProgramError::InvalidArgument)?;
+        NameRecord::pack(record, &mut record_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_set_target(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        target: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let record_info = next_account_info(account_info_iter)?;
+
+        let mut record = Self::load_owned(program_id, owner_info, record_info)?;
+        record.target = target;
+        NameRecord::pack(record, &mut record_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_transfer(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        new_owner: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let record_info = next_account_info(account_info_iter)?;
+
+        let mut record = Self::load_owned(program_id, owner_info, record_info)?;
+        record.owner = new_owner;
+        NameRecord::pack(record, &mut record_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    /// The record, checked to be signed for by its owner and not expired.
+    fn load_owned(
+        program_id: &Pubkey,
+        owner_info: &AccountInfo,
+        record_info: &AccountInfo,
+    ) -> Result<NameRecord, ProgramError> {
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if record_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let record = NameRecord::unpack(&record_info.data.borrow())?;
+        if record.owner != *owner_info.key {
+            return Err(NameError::NotOwner.into());
+        }
+        if record.is_expired(Clock::get()?.unix_timestamp) {
+            return Err(NameError::Expired.into());
+        }
+        Ok(record)
+    }
+}
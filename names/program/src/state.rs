@@ -0,0 +1,130 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Longest name, in bytes; also the most a seed can hold.
+pub const MAX_NAME_LEN: usize = 32;
+/// Seconds a registration or renewal lasts: a year.
+pub const REGISTRATION_PERIOD: i64 = 365 * 24 * 60 * 60;
+
+/// Points `name` at `target`, at a program address derived from the name. `owner` can
+/// repoint, renew or hand over the name until `expires_ts`, after which anyone can
+/// register it again.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NameRecord {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    pub target: Pubkey,
+    pub expires_ts: i64,
+    pub name: String,
+}
+
+impl NameRecord {
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires_ts
+    }
+}
+
+/// Whether `name` can be registered: 1 to `MAX_NAME_LEN` lowercase letters, digits or
+/// dashes, so that each name has a single spelling.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .bytes()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == b'-')
+}
+
+impl Sealed for NameRecord {}
+impl IsInitialized for NameRecord {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for NameRecord {
+    const LEN: usize = 106;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 106];
+        let (is_initialized, owner, target, expires_ts, name_len, name) =
+            array_refs![src, 1, 32, 32, 8, 1, MAX_NAME_LEN];
+        Ok(NameRecord {
+            is_initialized: unpack_bool(is_initialized)?,
+            owner: Pubkey::new_from_array(*owner),
+            target: Pubkey::new_from_array(*target),
+            expires_ts: i64::from_le_bytes(*expires_ts),
+            name: unpack_str(name_len, name)?,
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 106];
+        let (is_initialized, owner, target, expires_ts, name_len, name) =
+            mut_array_refs![dst, 1, 32, 32, 8, 1, MAX_NAME_LEN];
+        is_initialized[0] = self.is_initialized as u8;
+        owner.copy_from_slice(self.owner.as_ref());
+        target.copy_from_slice(self.target.as_ref());
+        *expires_ts = self.expires_ts.to_le_bytes();
+        pack_str(&self.name, name_len, name);
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+fn unpack_str(len: &[u8; 1], src: &[u8]) -> Result<String, ProgramError> {
+    let bytes = src
+        .get(..len[0] as usize)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| ProgramError::InvalidAccountData)
+}
+
+/// Writes `value` zero-padded into `dst`, which the processor has checked it fits.
+fn pack_str(value: &str, len: &mut [u8; 1], dst: &mut [u8]) {
+    len[0] = value.len() as u8;
+    dst.fill(0);
+    dst[..value.len()].copy_from_slice(value.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let record = NameRecord {
+            is_initialized: true,
+            owner: Pubkey::new_unique(),
+            target: Pubkey::new_unique(),
+            expires_ts: 1_700_000_000,
+            name: "a".repeat(MAX_NAME_LEN),
+        };
+        let mut packed = vec![0xff; NameRecord::LEN];
+        NameRecord::pack(record.clone(), &mut packed).unwrap();
+        assert_eq!(NameRecord::unpack(&packed), Ok(record));
+
+        packed[73] = MAX_NAME_LEN as u8 + 1;
+        assert_eq!(
+            NameRecord::unpack(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_is_valid_name() {
+        assert!(is_valid_name("alice"));
+        assert!(is_valid_name("bob-2"));
+        assert!(is_valid_name(&"a".repeat(MAX_NAME_LEN)));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("Alice"));
+        assert!(!is_valid_name("alice.sol"));
+        assert!(!is_valid_name(&"a".repeat(MAX_NAME_LEN + 1)));
+    }
+}
@@ -0,0 +1,215 @@
+use solana_bank::test_utils::clock::{self, TimeLock};
+use solana_names::{
+    error::NameError,
+    instruction::{name_address, register, renew, set_target, transfer},
+    processor::Processor,
+    state::{NameRecord, REGISTRATION_PERIOD},
+};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+/// Time the fixture's name is registered at.
+const NOW: i64 = 1_000;
+
+struct Env {
+    context: ProgramTestContext,
+    names_program_id: Pubkey,
+    _time: TimeLock,
+}
+impl Env {
+    async fn start() -> Env {
+        let names_program_id = Pubkey::new_unique();
+        let program_test = ProgramTest::new(
+            "solana_names",
+            names_program_id,
+            processor!(Processor::process),
+        );
+        let time = clock::lock();
+        Env {
+            context: program_test.start_with_context().await,
+            names_program_id,
+            _time: time,
+        }
+    }
+
+    async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), TransactionError> {
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            self.context.last_blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .map_err(|e| e.unwrap())
+    }
+
+    /// Moves the time seen by the program's `Clock` to `unix_timestamp`.
+    fn set_time(&mut self, unix_timestamp: i64) {
+        clock::set_time(unix_timestamp);
+    }
+
+    async fn fund(&mut self, to: &Pubkey, lamports: u64) {
+        let instruction = system_instruction::transfer(&self.context.payer.pubkey(), to, lamports);
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    async fn record(&mut self, name: &str) -> NameRecord {
+        let (address, _) = name_address(&self.names_program_id, name);
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        NameRecord::unpack(&account.data).unwrap()
+    }
+}
+
+/// "alice" registered by alice at `NOW`, pointing at `target`; bob holds no name.
+struct Fixture {
+    env: Env,
+    alice: Keypair,
+    bob: Keypair,
+    target: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start().await;
+        env.set_time(NOW);
+        let (alice, bob, target) = (Keypair::new(), Keypair::new(), Pubkey::new_unique());
+        env.fund(&alice.pubkey(), LAMPORTS_PER_SOL).await;
+        env.fund(&bob.pubkey(), LAMPORTS_PER_SOL).await;
+        let instruction =
+            register(&env.names_program_id, &alice.pubkey(), "alice", &target).unwrap();
+        env.process(&[instruction], &[&alice]).await.unwrap();
+        Fixture {
+            env,
+            alice,
+            bob,
+            target,
+        }
+    }
+
+    /// Sends `instruction` signed by alice, or by bob if `by_bob`.
+    async fn process(
+        &mut self,
+        instruction: Instruction,
+        by_bob: bool,
+    ) -> Result<(), TransactionError> {
+        let signer = if by_bob { &self.bob } else { &self.alice };
+        let signer = Keypair::from_bytes(&signer.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&signer]).await
+    }
+}
+
+fn custom(error: NameError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_register() {
+    let mut fixture = Fixture::new().await;
+    let record = fixture.env.record("alice").await;
+    assert_eq!(record.owner, fixture.alice.pubkey());
+    assert_eq!(record.target, fixture.target);
+    assert_eq!(record.expires_ts, NOW + REGISTRATION_PERIOD);
+    assert_eq!(record.name, "alice");
+
+    let program_id = fixture.env.names_program_id;
+    let bob = fixture.bob.pubkey();
+    let instruction = register(&program_id, &bob, "alice", &bob).unwrap();
+    assert_eq!(
+        fixture.process(instruction, true).await,
+        custom(NameError::NameTaken)
+    );
+    // The record of one name can't be registered under another.
+    let mut instruction = register(&program_id, &bob, "bob", &bob).unwrap();
+    instruction.accounts[1].pubkey = name_address(&program_id, "carol").0;
+    assert_eq!(
+        fixture.process(instruction, true).await,
+        custom(NameError::InvalidRecord)
+    );
+    let instruction = register(&program_id, &bob, "bob", &bob).unwrap();
+    fixture.process(instruction, true).await.unwrap();
+    assert_eq!(fixture.env.record("bob").await.owner, bob);
+}
+
+#[tokio::test]
+async fn test_set_target_and_transfer() {
+    let mut fixture = Fixture::new().await;
+    let program_id = fixture.env.names_program_id;
+    let (alice, bob) = (fixture.alice.pubkey(), fixture.bob.pubkey());
+    let new_target = Pubkey::new_unique();
+
+    let instruction = set_target(&program_id, &bob, "alice", &bob).unwrap();
+    assert_eq!(
+        fixture.process(instruction, true).await,
+        custom(NameError::NotOwner)
+    );
+    let instruction = set_target(&program_id, &alice, "alice", &new_target).unwrap();
+    fixture.process(instruction, false).await.unwrap();
+    assert_eq!(fixture.env.record("alice").await.target, new_target);
+
+    let instruction = transfer(&program_id, &alice, "alice", &bob).unwrap();
+    fixture.process(instruction, false).await.unwrap();
+    let record = fixture.env.record("alice").await;
+    assert_eq!((record.owner, record.target), (bob, new_target));
+    let instruction = set_target(&program_id, &alice, "alice", &alice).unwrap();
+    assert_eq!(
+        fixture.process(instruction, false).await,
+        custom(NameError::NotOwner)
+    );
+    let bob_target = Pubkey::new_unique();
+    let instruction = set_target(&program_id, &bob, "alice", &bob_target).unwrap();
+    fixture.process(instruction, true).await.unwrap();
+    assert_eq!(fixture.env.record("alice").await.target, bob_target);
+}
+
+#[tokio::test]
+async fn test_expiry() {
+    let mut fixture = Fixture::new().await;
+    let program_id = fixture.env.names_program_id;
+    let (alice, bob) = (fixture.alice.pubkey(), fixture.bob.pubkey());
+
+    fixture.env.set_time(NOW + REGISTRATION_PERIOD - 1);
+    let instruction = renew(&program_id, &alice, "alice").unwrap();
+    fixture.process(instruction, false).await.unwrap();
+    let expires_ts = NOW + 2 * REGISTRATION_PERIOD;
+    assert_eq!(fixture.env.record("alice").await.expires_ts, expires_ts);
+
+    fixture.env.set_time(expires_ts);
+    let instruction = set_target(&program_id, &alice, "alice", &alice).unwrap();
+    assert_eq!(
+        fixture.process(instruction, false).await,
+        custom(NameError::Expired)
+    );
+    let instruction = register(&program_id, &bob, "alice", &bob).unwrap();
+    fixture.process(instruction, true).await.unwrap();
+    let record = fixture.env.record("alice").await;
+    assert_eq!((record.owner, record.target), (bob, bob));
+    assert_eq!(record.expires_ts, expires_ts + REGISTRATION_PERIOD);
+}
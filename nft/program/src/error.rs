@@ -0,0 +1,21 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NftError {
+    /// The mint authority isn't the bank's program address.
+    InvalidMintAuthority,
+    /// The metadata isn't the bank's program address.
+    InvalidMetadata,
+    /// The name is longer than `MAX_NAME_LEN` bytes.
+    NameTooLong,
+    /// The URI is longer than `MAX_URI_LEN` bytes.
+    UriTooLong,
+    /// The account doesn't hold tokens of a bank minted by this program.
+    NotAnNft,
+}
+
+impl From<NftError> for ProgramError {
+    fn from(e: NftError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
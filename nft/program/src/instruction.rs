@@ -0,0 +1,207 @@
+use crate::{
+    error::NftError,
+    state::{MAX_NAME_LEN, MAX_URI_LEN},
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+
+/// Seed prefix of the mint authority, followed by the bank's address.
+pub const MINT_AUTHORITY_SEED: &[u8] = b"mint";
+/// Seed prefix of the metadata, followed by the bank's address.
+pub const METADATA_SEED: &[u8] = b"metadata";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum NftInstruction {
+    /// Opens the bank as a token of `decimals` 0 owned by the mint authority, mints its
+    /// only token into a new holder account, and records `name` and `uri` in its
+    /// metadata.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The creator, paying for the metadata.
+    ///   1. `[writable]` The bank, an uninitialized account owned by the bank program.
+    ///   2. `[writable]` The holder account, an uninitialized account owned by the bank
+    ///      program.
+    ///   3. `[writable, signer]` The holder account's owner.
+    ///   4. `[writable]` The mint authority.
+    ///   5. `[writable]` The metadata.
+    ///   6. `[]` The bank program, owner of the bank and the holder account.
+    ///   7. `[]` The system program.
+    Mint { name: String, uri: String },
+
+    /// Moves the token from the source account to the destination account.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The source account's owner.
+    ///   1. `[writable]` The source account.
+    ///   2. `[writable]` The destination account.
+    ///   3. `[]` The token's metadata.
+    ///   4. `[]` The token's bank.
+    ///   5. `[]` The bank program, owner of the source account.
+    Transfer,
+}
+
+impl NftInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (name, rest) = Self::unpack_str(rest)?;
+                let (uri, _rest) = Self::unpack_str(rest)?;
+                Self::Mint { name, uri }
+            }
+            1 => Self::Transfer,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self {
+            Self::Mint { name, uri } => {
+                buf.push(0);
+                Self::pack_str(name, &mut buf);
+                Self::pack_str(uri, &mut buf);
+            }
+            Self::Transfer => buf.push(1),
+        }
+        buf
+    }
+
+    /// A string prefixed by its length in bytes, as a u8.
+    fn unpack_str(input: &[u8]) -> Result<(String, &[u8]), ProgramError> {
+        let (&len, rest) = input
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let bytes = rest
+            .get(..len as usize)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let value =
+            String::from_utf8(bytes.to_vec()).map_err(|_| ProgramError::InvalidInstructionData)?;
+        Ok((value, &rest[len as usize..]))
+    }
+
+    /// Strings over 255 bytes are cut short; no valid name or URI is that long.
+    fn pack_str(value: &str, buf: &mut Vec<u8>) {
+        let bytes = &value.as_bytes()[..value.len().min(u8::MAX as usize)];
+        buf.push(bytes.len() as u8);
+        buf.extend_from_slice(bytes);
+    }
+}
+
+/// Address and bump of the authority owning `bank`.
+pub fn mint_authority(nft_program_id: &Pubkey, bank: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MINT_AUTHORITY_SEED, bank.as_ref()], nft_program_id)
+}
+
+/// Address and bump of `bank`'s metadata.
+pub fn metadata_address(nft_program_id: &Pubkey, bank: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[METADATA_SEED, bank.as_ref()], nft_program_id)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn mint(
+    nft_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    creator: &Pubkey,
+    bank: &Pubkey,
+    holder_account: &Pubkey,
+    holder: &Pubkey,
+    name: &str,
+    uri: &str,
+) -> Result<Instruction, ProgramError> {
+    if name.len() > MAX_NAME_LEN {
+        return Err(NftError::NameTooLong.into());
+    }
+    if uri.len() > MAX_URI_LEN {
+        return Err(NftError::UriTooLong.into());
+    }
+    let data = NftInstruction::Mint {
+        name: name.to_string(),
+        uri: uri.to_string(),
+    }
+    .pack();
+    let (authority, _) = mint_authority(nft_program_id, bank);
+    let (metadata, _) = metadata_address(nft_program_id, bank);
+    let accounts = vec![
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(*holder_account, false),
+        AccountMeta::new(*holder, true),
+        AccountMeta::new(authority, false),
+        AccountMeta::new(metadata, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *nft_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn transfer(
+    nft_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    owner: &Pubkey,
+    source_account: &Pubkey,
+    destination_account: &Pubkey,
+    bank: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = NftInstruction::Transfer.pack();
+    let (metadata, _) = metadata_address(nft_program_id, bank);
+    let accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new_readonly(metadata, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *nft_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            NftInstruction::Mint {
+                name: "Play #1".to_string(),
+                uri: "https://example.com/1.json".to_string(),
+            },
+            NftInstruction::Mint {
+                name: String::new(),
+                uri: String::new(),
+            },
+            NftInstruction::Transfer,
+        ];
+        for instruction in cases {
+            assert_eq!(NftInstruction::unpack(&instruction.pack()), Ok(instruction));
+        }
+
+        assert_eq!(
+            NftInstruction::unpack(&[0, 3, b'a', b'b']),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            NftInstruction::unpack(&[0, 1, 0xff, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            NftInstruction::unpack(&[2]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use crate::{
+    error::NftError,
+    instruction::{NftInstruction, METADATA_SEED, MINT_AUTHORITY_SEED},
+    state::{Metadata, MAX_NAME_LEN, MAX_URI_LEN},
+};
+use solana_bank::{instruction as bank_instruction, state::Account as BankAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = NftInstruction::unpack(input)?;
+
+        match instruction {
+            NftInstruction::Mint { name, uri } => {
+                msg!("Instruction: Mint");
+                Self::process_mint(program_id, accounts, name, uri)
+            }
+            NftInstruction::Transfer => {
+                msg!("Instruction: Transfer");
+                Self::process_transfer(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_mint(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        name: String,
+        uri: String,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let creator_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let holder_account_info = next_account_info(account_info_iter)?;
+        let holder_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let metadata_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !creator_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if name.len() > MAX_NAME_LEN {
+            return Err(NftError::NameTooLong.into());
+        }
+        if uri.len() > MAX_URI_LEN {
+            return Err(NftError::UriTooLong.into());
+        }
+        let (authority, authority_bump) = Pubkey::find_program_address(
+            &[MINT_AUTHORITY_SEED, bank_info.key.as_ref()],
+            program_id,
+        );
+        if authority != *authority_info.key {
+            return Err(NftError::InvalidMintAuthority.into());
+        }
+        let (metadata, metadata_bump) =
+            Pubkey::find_program_address(&[METADATA_SEED, bank_info.key.as_ref()], program_id);
+        if metadata != *metadata_info.key {
+            return Err(NftError::InvalidMetadata.into());
+        }
+        // The mint authority signs only for a bank of the bank program.
+        if bank_info.owner != bank_program_info.key
+            || holder_account_info.owner != bank_program_info.key
+        {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let authority_seeds: &[&[u8]] = &[
+            MINT_AUTHORITY_SEED,
+            bank_info.key.as_ref(),
+            &[authority_bump],
+        ];
+
+        // Only the mint authority can mint into the bank, and it mints this one token,
+        // so the supply stays at one for good.
+        invoke_signed(
+            &bank_instruction::initialize_bank(
+                bank_program_info.key,
+                bank_info.key,
+                &authority,
+                0,
+            )?,
+            &[
+                bank_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[authority_seeds],
+        )?;
+        invoke(
+            &bank_instruction::initialize_account(
+                bank_program_info.key,
+                bank_info.key,
+                holder_account_info.key,
+                holder_info.key,
+            )?,
+            &[
+                bank_info.clone(),
+                holder_account_info.clone(),
+                holder_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+        invoke_signed(
+            &bank_instruction::mint_to(
+                bank_program_info.key,
+                bank_info.key,
+                holder_account_info.key,
+                &authority,
+                1,
+            )?,
+            &[
+                bank_info.clone(),
+                holder_account_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[authority_seeds],
+        )?;
+
+        invoke_signed(
+            &system_instruction::create_account(
+                creator_info.key,
+                metadata_info.key,
+                Rent::get()?.minimum_balance(Metadata::LEN),
+                Metadata::LEN as u64,
+                program_id,
+            ),
+            &[
+                creator_info.clone(),
+                metadata_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[&[METADATA_SEED, bank_info.key.as_ref(), &[metadata_bump]]],
+        )?;
+        let metadata = Metadata {
+            is_initialized: true,
+            bank: *bank_info.key,
+            creator: *creator_info.key,
+            name,
+            uri,
+        };
+        Metadata::pack(metadata, &mut metadata_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_transfer(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let metadata_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if metadata_info.owner != program_id {
+            return Err(NftError::InvalidMetadata.into());
+        }
+        let metadata = Metadata::unpack(&metadata_info.data.borrow())?;
+        if source_info.owner != bank_program_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        // The bank program checks the destination is in the same bank.
+        let source = BankAccount::unpack(&source_info.data.borrow())?;
+        if source.bank != metadata.bank {
+            return Err(NftError::NotAnNft.into());
+        }
+
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                destination_info.key,
+                owner_info.key,
+                1,
+            )?,
+            &[
+                source_info.clone(),
+                destination_info.clone(),
+                owner_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )
+    }
+}
@@ -0,0 +1,110 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Longest name, in bytes, a token can be minted with.
+pub const MAX_NAME_LEN: usize = 32;
+/// Longest URI, in bytes, a token can be minted with.
+pub const MAX_URI_LEN: usize = 200;
+
+/// Describes the token of `bank`, at a program address derived from it. The bank is owned
+/// by the mint authority, which minted its single token and never mints again.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Metadata {
+    pub is_initialized: bool,
+    pub bank: Pubkey,
+    pub creator: Pubkey,
+    pub name: String,
+    /// Where the token's off-chain description, such as its image, lives.
+    pub uri: String,
+}
+
+impl Sealed for Metadata {}
+impl IsInitialized for Metadata {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Metadata {
+    const LEN: usize = 299;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 299];
+        let (is_initialized, bank, creator, name_len, name, uri_len, uri) =
+            array_refs![src, 1, 32, 32, 1, MAX_NAME_LEN, 1, MAX_URI_LEN];
+        Ok(Metadata {
+            is_initialized: unpack_bool(is_initialized)?,
+            bank: Pubkey::new_from_array(*bank),
+            creator: Pubkey::new_from_array(*creator),
+            name: unpack_str(name_len, name)?,
+            uri: unpack_str(uri_len, uri)?,
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 299];
+        let (is_initialized, bank, creator, name_len, name, uri_len, uri) =
+            mut_array_refs![dst, 1, 32, 32, 1, MAX_NAME_LEN, 1, MAX_URI_LEN];
+        is_initialized[0] = self.is_initialized as u8;
+        bank.copy_from_slice(self.bank.as_ref());
+        creator.copy_from_slice(self.creator.as_ref());
+        pack_str(&self.name, name_len, name);
+        pack_str(&self.uri, uri_len, uri);
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+fn unpack_str(len: &[u8; 1], src: &[u8]) -> Result<String, ProgramError> {
+    let bytes = src
+        .get(..len[0] as usize)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| ProgramError::InvalidAccountData)
+}
+
+/// Writes `value` zero-padded into `dst`, which the processor has checked it fits.
+fn pack_str(value: &str, len: &mut [u8; 1], dst: &mut [u8]) {
+    len[0] = value.len() as u8;
+    dst.fill(0);
+    dst[..value.len()].copy_from_slice(value.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let metadata = Metadata {
+            is_initialized: true,
+            bank: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            name: "Play #1".to_string(),
+            uri: "x".repeat(MAX_URI_LEN),
+        };
+        let mut packed = vec![0xff; Metadata::LEN];
+        Metadata::pack(metadata.clone(), &mut packed).unwrap();
+        assert_eq!(Metadata::unpack(&packed), Ok(metadata));
+
+        // A name running past its field.
+        packed[65] = MAX_NAME_LEN as u8 + 1;
+        assert_eq!(
+            Metadata::unpack(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+        packed[65] = 1;
+        packed[66] = 0xff;
+        assert_eq!(
+            Metadata::unpack(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+}
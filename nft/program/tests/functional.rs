@@ -0,0 +1,303 @@
+use solana_bank::{
+    instruction::{initialize_bank, mint_to},
+    state::{Account, Bank},
+    test_utils::program_test::Env,
+};
+use solana_nft::{
+    error::NftError,
+    instruction::{metadata_address, mint, mint_authority, transfer, NftInstruction},
+    processor::Processor,
+    state::{Metadata, MAX_NAME_LEN},
+};
+use solana_program::{
+    instruction::InstructionError, program_pack::Pack, pubkey::Pubkey, system_instruction,
+};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// A token minted to `holder`, and the account holding it.
+struct Fixture {
+    env: Env,
+    creator: Keypair,
+    bank: Pubkey,
+    holder: Keypair,
+    holder_account: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_nft", processor!(Processor::process)).await;
+        let creator = Keypair::new();
+        let payer = env.context.payer.pubkey();
+        let fund = system_instruction::transfer(&payer, &creator.pubkey(), 10_000_000);
+        env.process(&[fund], &[]).await.unwrap();
+        let (bank, holder_account, holder) = (Keypair::new(), Keypair::new(), Keypair::new());
+        let nft_program_id = env.program_id;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&bank, Bank::LEN, &bank_program_id).await;
+        env.create_account(&holder_account, Account::LEN, &bank_program_id)
+            .await;
+        let instruction = mint(
+            &nft_program_id,
+            &bank_program_id,
+            &creator.pubkey(),
+            &bank.pubkey(),
+            &holder_account.pubkey(),
+            &holder.pubkey(),
+            "Play #1",
+            "https://example.com/1.json",
+        )
+        .unwrap();
+        env.process(&[instruction], &[&creator, &holder])
+            .await
+            .unwrap();
+        Fixture {
+            env,
+            creator,
+            bank: bank.pubkey(),
+            holder,
+            holder_account: holder_account.pubkey(),
+        }
+    }
+
+    async fn metadata(&mut self) -> Metadata {
+        let (metadata, _) = metadata_address(&self.env.program_id, &self.bank);
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(metadata)
+            .await
+            .unwrap()
+            .unwrap();
+        Metadata::unpack(&account.data).unwrap()
+    }
+
+    async fn bank(&mut self) -> Bank {
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(self.bank)
+            .await
+            .unwrap()
+            .unwrap();
+        Bank::unpack(&account.data).unwrap()
+    }
+
+    async fn transfer(
+        &mut self,
+        owner: &Keypair,
+        source: &Pubkey,
+        destination: &Pubkey,
+    ) -> Result<(), TransactionError> {
+        let instruction = transfer(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &owner.pubkey(),
+            source,
+            destination,
+            &self.bank,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[owner]).await
+    }
+}
+
+fn custom(error: NftError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_mint() {
+    let mut fixture = Fixture::new().await;
+    let (authority, _) = mint_authority(&fixture.env.program_id, &fixture.bank);
+    let bank = fixture.bank().await;
+    assert_eq!(
+        (bank.decimals, bank.bank_owner, bank.total_supply),
+        (0, authority, 1)
+    );
+    let holder_account = fixture.holder_account;
+    assert_eq!(fixture.env.amount(&holder_account).await, 1);
+    let metadata = fixture.metadata().await;
+    assert_eq!(
+        metadata,
+        Metadata {
+            is_initialized: true,
+            bank: fixture.bank,
+            creator: fixture.creator.pubkey(),
+            name: "Play #1".to_string(),
+            uri: "https://example.com/1.json".to_string(),
+        }
+    );
+
+    // Only this program can sign for the authority, so nothing more can be minted.
+    let bank_program_id = fixture.env.bank_program_id;
+    let mut instruction = mint_to(
+        &bank_program_id,
+        &fixture.bank,
+        &holder_account,
+        &authority,
+        1,
+    )
+    .unwrap();
+    instruction.accounts[2].is_signer = false;
+    assert_eq!(
+        fixture.env.process(&[instruction], &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::MissingRequiredSignature
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_mint_rejects_long_name() {
+    let mut env = Env::start("solana_nft", processor!(Processor::process)).await;
+    let nft_program_id = env.program_id;
+    let bank_program_id = env.bank_program_id;
+    let (bank, holder_account, holder) = (Keypair::new(), Keypair::new(), Keypair::new());
+    env.create_account(&bank, Bank::LEN, &bank_program_id).await;
+    env.create_account(&holder_account, Account::LEN, &bank_program_id)
+        .await;
+    let name = "x".repeat(MAX_NAME_LEN + 1);
+    let payer = env.context.payer.pubkey();
+    assert!(mint(
+        &nft_program_id,
+        &bank_program_id,
+        &payer,
+        &bank.pubkey(),
+        &holder_account.pubkey(),
+        &holder.pubkey(),
+        &name,
+        "",
+    )
+    .is_err());
+
+    // The program checks as well, for instructions not built by `mint`.
+    let mut instruction = mint(
+        &nft_program_id,
+        &bank_program_id,
+        &payer,
+        &bank.pubkey(),
+        &holder_account.pubkey(),
+        &holder.pubkey(),
+        "",
+        "",
+    )
+    .unwrap();
+    instruction.data = NftInstruction::Mint {
+        name,
+        uri: String::new(),
+    }
+    .pack();
+    assert_eq!(
+        env.process(&[instruction], &[&holder]).await,
+        custom(NftError::NameTooLong)
+    );
+}
+
+#[tokio::test]
+async fn test_transfer() {
+    let mut fixture = Fixture::new().await;
+    let bank = fixture.bank;
+    let alice = Keypair::new();
+    let alice_account = fixture.env.create_bank_account(&bank, &alice, 0).await;
+    let holder = Keypair::from_bytes(&fixture.holder.to_bytes()).unwrap();
+    let holder_account = fixture.holder_account;
+
+    // Only the holder can move the token.
+    assert_eq!(
+        fixture
+            .transfer(&alice, &holder_account, &alice_account)
+            .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    fixture
+        .transfer(&holder, &holder_account, &alice_account)
+        .await
+        .unwrap();
+    assert_eq!(fixture.env.amount(&holder_account).await, 0);
+    assert_eq!(fixture.env.amount(&alice_account).await, 1);
+    fixture
+        .transfer(&alice, &alice_account, &holder_account)
+        .await
+        .unwrap();
+    assert_eq!(fixture.env.amount(&holder_account).await, 1);
+
+    // Tokens of a bank this program didn't mint aren't NFTs.
+    let fungible = Keypair::new();
+    let bank_program_id = fixture.env.bank_program_id;
+    fixture
+        .env
+        .create_account(&fungible, Bank::LEN, &bank_program_id)
+        .await;
+    let payer = fixture.env.context.payer.pubkey();
+    let instruction = initialize_bank(&bank_program_id, &fungible.pubkey(), &payer, 0).unwrap();
+    fixture.env.process(&[instruction], &[]).await.unwrap();
+    let source = fixture
+        .env
+        .create_bank_account(&fungible.pubkey(), &alice, 0)
+        .await;
+    let destination = fixture
+        .env
+        .create_bank_account(&fungible.pubkey(), &holder, 0)
+        .await;
+    let instruction = mint_to(&bank_program_id, &fungible.pubkey(), &source, &payer, 5).unwrap();
+    fixture.env.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(
+        fixture.transfer(&alice, &source, &destination).await,
+        custom(NftError::NotAnNft)
+    );
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    let (bank, holder_account) = (Keypair::new(), Keypair::new());
+    let nft_program_id = fixture.env.program_id;
+    let bank_program_id = fixture.env.bank_program_id;
+    fixture
+        .env
+        .create_account(&bank, Bank::LEN, &bank_program_id)
+        .await;
+    fixture
+        .env
+        .create_account(&holder_account, Account::LEN, &bank_program_id)
+        .await;
+
+    // Through a program posing as the bank, metadata would be recorded for a token that
+    // was never minted.
+    let instruction = mint(
+        &nft_program_id,
+        &fixture.env.fake_bank_program_id,
+        &fixture.creator.pubkey(),
+        &bank.pubkey(),
+        &holder_account.pubkey(),
+        &fixture.holder.pubkey(),
+        "Play #2",
+        "https://example.com/2.json",
+    )
+    .unwrap();
+    assert_eq!(
+        fixture
+            .env
+            .process(&[instruction], &[&fixture.creator, &fixture.holder])
+            .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    let (metadata, _) = metadata_address(&nft_program_id, &bank.pubkey());
+    assert!(fixture.env.account(&metadata).await.is_none());
+}
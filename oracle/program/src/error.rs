@@ -0,0 +1,23 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OracleError {
+    /// The signer isn't the feed's authority.
+    NotAuthority,
+    /// Prices must be above zero.
+    InvalidPrice,
+    /// The longest age a price can be used at must not be negative.
+    InvalidMaxAge,
+    /// The feed's price is older than its maximum age.
+    StalePrice,
+    /// The bank or the source account isn't the one the feed prices.
+    BankMismatch,
+    /// The USD amount is worth less than one base unit of the token.
+    ZeroAmount,
+}
+
+impl From<OracleError> for ProgramError {
+    fn from(e: OracleError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,193 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OracleInstruction {
+    /// Opens a USD price feed for a bank's token, posting its first `price`. Prices can
+    /// be used for `max_age` seconds after they are posted.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The feed, an uninitialized account owned by this program.
+    ///   1. `[signer]` The authority allowed to post prices.
+    ///   2. `[]` The bank.
+    CreateFeed { price: u64, max_age: i64 },
+
+    /// Posts a new price.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The feed.
+    ///   1. `[signer]` The feed's authority.
+    PostPrice { price: u64 },
+
+    /// Transfers what `usd_amount` is worth at the feed's price between two accounts of
+    /// the feed's bank. Fails if the price is stale.
+    ///
+    /// Accounts expected:
+    ///   0. `[]` The feed.
+    ///   1. `[]` The bank.
+    ///   2. `[writable]` The source account.
+    ///   3. `[writable]` The destination account.
+    ///   4. `[writable, signer]` The source account's owner or delegate.
+    ///   5. `[]` The bank program.
+    TransferValue { usd_amount: u64 },
+}
+
+impl OracleInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (price, rest) = Self::unpack_u64(rest)?;
+                let (max_age, _rest) = Self::unpack_u64(rest)?;
+                Self::CreateFeed {
+                    price,
+                    max_age: max_age as i64,
+                }
+            }
+            1 => {
+                let (price, _rest) = Self::unpack_u64(rest)?;
+                Self::PostPrice { price }
+            }
+            2 => {
+                let (usd_amount, _rest) = Self::unpack_u64(rest)?;
+                Self::TransferValue { usd_amount }
+            }
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(17);
+        match *self {
+            Self::CreateFeed { price, max_age } => {
+                buf.push(0);
+                buf.extend_from_slice(&price.to_le_bytes());
+                buf.extend_from_slice(&max_age.to_le_bytes());
+            }
+            Self::PostPrice { price } => {
+                buf.push(1);
+                buf.extend_from_slice(&price.to_le_bytes());
+            }
+            Self::TransferValue { usd_amount } => {
+                buf.push(2);
+                buf.extend_from_slice(&usd_amount.to_le_bytes());
+            }
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+pub fn create_feed(
+    oracle_program_id: &Pubkey,
+    feed: &Pubkey,
+    authority: &Pubkey,
+    bank: &Pubkey,
+    price: u64,
+    max_age: i64,
+) -> Result<Instruction, ProgramError> {
+    let data = OracleInstruction::CreateFeed { price, max_age }.pack();
+    let accounts = vec![
+        AccountMeta::new(*feed, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(*bank, false),
+    ];
+    Ok(Instruction {
+        program_id: *oracle_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn post_price(
+    oracle_program_id: &Pubkey,
+    feed: &Pubkey,
+    authority: &Pubkey,
+    price: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = OracleInstruction::PostPrice { price }.pack();
+    let accounts = vec![
+        AccountMeta::new(*feed, false),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+    Ok(Instruction {
+        program_id: *oracle_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_value(
+    oracle_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    feed: &Pubkey,
+    bank: &Pubkey,
+    from_account: &Pubkey,
+    to_account: &Pubkey,
+    from_account_owner: &Pubkey,
+    usd_amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = OracleInstruction::TransferValue { usd_amount }.pack();
+    let accounts = vec![
+        AccountMeta::new_readonly(*feed, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new(*from_account, false),
+        AccountMeta::new(*to_account, false),
+        AccountMeta::new(*from_account_owner, true),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *oracle_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            OracleInstruction::CreateFeed {
+                price: 2_500_000,
+                max_age: 60,
+            },
+            OracleInstruction::PostPrice { price: 1 },
+            OracleInstruction::TransferValue {
+                usd_amount: 10_000_000,
+            },
+        ];
+        for instruction in cases {
+            assert_eq!(
+                OracleInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            OracleInstruction::unpack(&[0, 1, 0, 0, 0, 0, 0, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            OracleInstruction::unpack(&[3]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{error::OracleError, instruction::OracleInstruction, state::PriceFeed};
+use solana_bank::{
+    instruction as bank_instruction,
+    state::{Account as BankAccount, Bank},
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = OracleInstruction::unpack(input)?;
+
+        match instruction {
+            OracleInstruction::CreateFeed { price, max_age } => {
+                msg!("Instruction: CreateFeed");
+                Self::process_create_feed(program_id, accounts, price, max_age)
+            }
+            OracleInstruction::PostPrice { price } => {
+                msg!("Instruction: PostPrice");
+                Self::process_post_price(program_id, accounts, price)
+            }
+            OracleInstruction::TransferValue { usd_amount } => {
+                msg!("Instruction: TransferValue");
+                Self::process_transfer_value(program_id, accounts, usd_amount)
+            }
+        }
+    }
+
+    pub fn process_create_feed(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        price: u64,
+        max_age: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let feed_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+
+        if !authority_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if feed_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut feed = PriceFeed::unpack_unchecked(&feed_info.data.borrow())?;
+        if feed.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if price == 0 {
+            return Err(OracleError::InvalidPrice.into());
+        }
+        if max_age < 0 {
+            return Err(OracleError::InvalidMaxAge.into());
+        }
+
+        feed.is_initialized = true;
+        feed.authority = *authority_info.key;
+        feed.bank = *bank_info.key;
+        feed.price = price;
+        feed.max_age = max_age;
+        feed.updated_ts = Clock::get()?.unix_timestamp;
+        PriceFeed::pack(feed, &mut feed_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_post_price(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        price: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let feed_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+
+        if !authority_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut feed = Self::load_feed(program_id, feed_info)?;
+        if feed.authority != *authority_info.key {
+            return Err(OracleError::NotAuthority.into());
+        }
+        if price == 0 {
+            return Err(OracleError::InvalidPrice.into());
+        }
+
+        feed.price = price;
+        feed.updated_ts = Clock::get()?.unix_timestamp;
+        PriceFeed::pack(feed, &mut feed_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_transfer_value(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        usd_amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let feed_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        let feed = Self::load_feed(program_id, feed_info)?;
+        if !feed.is_fresh(Clock::get()?.unix_timestamp) {
+            return Err(OracleError::StalePrice.into());
+        }
+        // The bank program checks the destination is in the same bank as the source.
+        if feed.bank != *bank_info.key || bank_info.owner != bank_program_info.key {
+            return Err(OracleError::BankMismatch.into());
+        }
+        if BankAccount::unpack(&source_info.data.borrow())?.bank != feed.bank {
+            return Err(OracleError::BankMismatch.into());
+        }
+        let decimals = Bank::unpack(&bank_info.data.borrow())?.decimals;
+        let amount = match feed.amount_for(usd_amount, decimals) {
+            Some(0) => return Err(OracleError::ZeroAmount.into()),
+            Some(amount) => amount,
+            None => return Err(ProgramError::InvalidArgument),
+        };
+        msg!("{} micro-USD is {} base units", usd_amount, amount);
+
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                destination_info.key,
+                owner_info.key,
+                amount,
+            )?,
+            &[
+                source_info.clone(),
+                destination_info.clone(),
+                owner_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )
+    }
+
+    fn load_feed(program_id: &Pubkey, feed_info: &AccountInfo) -> Result<PriceFeed, ProgramError> {
+        if feed_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        PriceFeed::unpack(&feed_info.data.borrow())
+    }
+}
@@ -0,0 +1,142 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+use std::convert::TryFrom;
+
+/// Fixed-point scale of prices and USD amounts: both are in millionths of a dollar.
+pub const USD_PRECISION: u64 = 1_000_000;
+
+/// USD price of one whole token of `bank`, times `USD_PRECISION`, as last posted by
+/// `authority` at `updated_ts`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PriceFeed {
+    pub is_initialized: bool,
+    pub authority: Pubkey,
+    pub bank: Pubkey,
+    pub price: u64,
+    /// Longest time, in seconds, the price can be used for after it was posted.
+    pub max_age: i64,
+    pub updated_ts: i64,
+}
+
+impl PriceFeed {
+    /// Whether the price can still be used at `now`.
+    pub fn is_fresh(&self, now: i64) -> bool {
+        now.saturating_sub(self.updated_ts) <= self.max_age
+    }
+
+    /// Base units of a bank with `decimals` worth `usd_amount` at the feed's price,
+    /// rounded down; `None` if it doesn't fit in a `u64`.
+    pub fn amount_for(&self, usd_amount: u64, decimals: u8) -> Option<u64> {
+        let scale = 10u128.checked_pow(decimals as u32)?;
+        let amount = (usd_amount as u128)
+            .checked_mul(scale)?
+            .checked_div(self.price as u128)?;
+        u64::try_from(amount).ok()
+    }
+}
+
+impl Sealed for PriceFeed {}
+impl IsInitialized for PriceFeed {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for PriceFeed {
+    const LEN: usize = 89;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 89];
+        let (is_initialized, authority, bank, price, max_age, updated_ts) =
+            array_refs![src, 1, 32, 32, 8, 8, 8];
+        Ok(PriceFeed {
+            is_initialized: unpack_bool(is_initialized)?,
+            authority: Pubkey::new_from_array(*authority),
+            bank: Pubkey::new_from_array(*bank),
+            price: u64::from_le_bytes(*price),
+            max_age: i64::from_le_bytes(*max_age),
+            updated_ts: i64::from_le_bytes(*updated_ts),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 89];
+        let (is_initialized, authority, bank, price, max_age, updated_ts) =
+            mut_array_refs![dst, 1, 32, 32, 8, 8, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        authority.copy_from_slice(self.authority.as_ref());
+        bank.copy_from_slice(self.bank.as_ref());
+        *price = self.price.to_le_bytes();
+        *max_age = self.max_age.to_le_bytes();
+        *updated_ts = self.updated_ts.to_le_bytes();
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let feed = PriceFeed {
+            is_initialized: true,
+            authority: Pubkey::new_unique(),
+            bank: Pubkey::new_unique(),
+            price: 2_500_000,
+            max_age: 60,
+            updated_ts: 1_700_000_000,
+        };
+        let mut packed = vec![0u8; PriceFeed::LEN];
+        PriceFeed::pack(feed, &mut packed).unwrap();
+        assert_eq!(PriceFeed::unpack(&packed), Ok(feed));
+
+        packed[0] = 2;
+        assert_eq!(
+            PriceFeed::unpack_unchecked(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_amount_for() {
+        let feed = PriceFeed {
+            price: 2_500_000,
+            ..PriceFeed::default()
+        };
+        // $10 buys 4 whole tokens at $2.50, which is 400 base units at 2 decimals.
+        assert_eq!(feed.amount_for(10 * USD_PRECISION, 0), Some(4));
+        assert_eq!(feed.amount_for(10 * USD_PRECISION, 2), Some(400));
+        // Rounded down, so a cent buys nothing of an indivisible token.
+        assert_eq!(feed.amount_for(10_000, 0), Some(0));
+        assert_eq!(feed.amount_for(u64::MAX, 18), None);
+
+        let feed = PriceFeed {
+            price: 1,
+            ..PriceFeed::default()
+        };
+        assert_eq!(feed.amount_for(u64::MAX, 1), None);
+    }
+
+    #[test]
+    fn test_is_fresh() {
+        let feed = PriceFeed {
+            max_age: 60,
+            updated_ts: 1_000,
+            ..PriceFeed::default()
+        };
+        assert!(feed.is_fresh(1_000));
+        assert!(feed.is_fresh(1_060));
+        assert!(!feed.is_fresh(1_061));
+    }
+}
@@ -0,0 +1,215 @@
+use solana_bank::test_utils::program_test::Env;
+use solana_oracle::{
+    error::OracleError,
+    instruction::{create_feed, post_price, transfer_value},
+    processor::Processor,
+    state::{PriceFeed, USD_PRECISION},
+};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// Time the fixture's price is posted at, and how long it stays usable.
+const NOW: i64 = 1_000;
+const MAX_AGE: i64 = 60;
+
+/// A feed pricing a bank's token at $2.50, alice holding 100.00 of it and bob none.
+struct Fixture {
+    env: Env,
+    authority: Keypair,
+    bank: Pubkey,
+    feed: Pubkey,
+    alice: Keypair,
+    alice_account: Pubkey,
+    bob_account: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_oracle", processor!(Processor::process)).await;
+        env.set_time(NOW);
+        let bank = env.create_bank_with_decimals(2).await;
+        let (authority, feed) = (Keypair::new(), Keypair::new());
+        let oracle_program_id = env.program_id;
+        env.create_account(&feed, PriceFeed::LEN, &oracle_program_id)
+            .await;
+        let instruction = create_feed(
+            &oracle_program_id,
+            &feed.pubkey(),
+            &authority.pubkey(),
+            &bank,
+            2_500_000,
+            MAX_AGE,
+        )
+        .unwrap();
+        env.process(&[instruction], &[&authority]).await.unwrap();
+
+        let (alice, bob) = (Keypair::new(), Keypair::new());
+        let alice_account = env.create_bank_account(&bank, &alice, 10_000).await;
+        let bob_account = env.create_bank_account(&bank, &bob, 0).await;
+        Fixture {
+            env,
+            authority,
+            bank,
+            feed: feed.pubkey(),
+            alice,
+            alice_account,
+            bob_account,
+        }
+    }
+
+    async fn feed(&mut self) -> PriceFeed {
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(self.feed)
+            .await
+            .unwrap()
+            .unwrap();
+        PriceFeed::unpack(&account.data).unwrap()
+    }
+
+    async fn post(&mut self, authority: &Keypair, price: u64) -> Result<(), TransactionError> {
+        let instruction =
+            post_price(&self.env.program_id, &self.feed, &authority.pubkey(), price).unwrap();
+        self.env.process(&[instruction], &[authority]).await
+    }
+
+    /// Has alice send bob what `usd_amount` is worth.
+    async fn transfer(&mut self, usd_amount: u64) -> Result<(), TransactionError> {
+        let instruction = transfer_value(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.feed,
+            &self.bank,
+            &self.alice_account,
+            &self.bob_account,
+            &self.alice.pubkey(),
+            usd_amount,
+        )
+        .unwrap();
+        let alice = Keypair::from_bytes(&self.alice.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&alice]).await
+    }
+}
+
+fn custom(error: OracleError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_feed() {
+    let mut fixture = Fixture::new().await;
+    let feed = fixture.feed().await;
+    assert_eq!(feed.authority, fixture.authority.pubkey());
+    assert_eq!(feed.bank, fixture.bank);
+    assert_eq!(feed.price, 2_500_000);
+    assert_eq!((feed.max_age, feed.updated_ts), (MAX_AGE, NOW));
+
+    let feed = Keypair::new();
+    let oracle_program_id = fixture.env.program_id;
+    fixture
+        .env
+        .create_account(&feed, PriceFeed::LEN, &oracle_program_id)
+        .await;
+    let authority = Keypair::from_bytes(&fixture.authority.to_bytes()).unwrap();
+    let instruction = create_feed(
+        &oracle_program_id,
+        &feed.pubkey(),
+        &authority.pubkey(),
+        &fixture.bank,
+        0,
+        MAX_AGE,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&authority]).await,
+        custom(OracleError::InvalidPrice)
+    );
+}
+
+#[tokio::test]
+async fn test_post_price() {
+    let mut fixture = Fixture::new().await;
+    fixture.env.set_time(NOW + 30);
+    let authority = Keypair::from_bytes(&fixture.authority.to_bytes()).unwrap();
+    fixture.post(&authority, 3_000_000).await.unwrap();
+    let feed = fixture.feed().await;
+    assert_eq!((feed.price, feed.updated_ts), (3_000_000, NOW + 30));
+
+    let outsider = Keypair::new();
+    assert_eq!(
+        fixture.post(&outsider, 1).await,
+        custom(OracleError::NotAuthority)
+    );
+    assert_eq!(
+        fixture.post(&authority, 0).await,
+        custom(OracleError::InvalidPrice)
+    );
+}
+
+#[tokio::test]
+async fn test_transfer_value() {
+    let mut fixture = Fixture::new().await;
+    let (alice, bob) = (fixture.alice_account, fixture.bob_account);
+
+    // $10 at $2.50 is 4.00 tokens.
+    fixture.transfer(10 * USD_PRECISION).await.unwrap();
+    assert_eq!(fixture.env.amount(&alice).await, 9_600);
+    assert_eq!(fixture.env.amount(&bob).await, 400);
+    // Less than a hundredth of a token's worth moves nothing, so it is refused.
+    assert_eq!(
+        fixture.transfer(20_000).await,
+        custom(OracleError::ZeroAmount)
+    );
+
+    fixture.env.set_time(NOW + MAX_AGE + 1);
+    assert_eq!(
+        fixture.transfer(5 * USD_PRECISION).await,
+        custom(OracleError::StalePrice)
+    );
+    let authority = Keypair::from_bytes(&fixture.authority.to_bytes()).unwrap();
+    fixture.post(&authority, 5_000_000).await.unwrap();
+    fixture.transfer(6 * USD_PRECISION).await.unwrap();
+    assert_eq!(fixture.env.amount(&bob).await, 520);
+}
+
+#[tokio::test]
+async fn test_transfer_value_other_bank() {
+    let mut fixture = Fixture::new().await;
+    let other_bank = fixture.env.create_bank_with_decimals(2).await;
+    let holder = Keypair::new();
+    let source = fixture
+        .env
+        .create_bank_account(&other_bank, &holder, 10_000)
+        .await;
+    let destination = fixture
+        .env
+        .create_bank_account(&other_bank, &holder, 0)
+        .await;
+
+    for bank in [fixture.bank, other_bank].iter() {
+        let instruction = transfer_value(
+            &fixture.env.program_id,
+            &fixture.env.bank_program_id,
+            &fixture.feed,
+            bank,
+            &source,
+            &destination,
+            &holder.pubkey(),
+            10 * USD_PRECISION,
+        )
+        .unwrap();
+        assert_eq!(
+            fixture.env.process(&[instruction], &[&holder]).await,
+            custom(OracleError::BankMismatch)
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OrderbookError {
+    /// The market authority isn't the market's program address.
+    InvalidMarketAuthority,
+    /// The base and quote banks are the same bank.
+    SameBank,
+    /// The account isn't the market's vault for that side.
+    InvalidVault,
+    /// The order has a zero price or quantity, or its cost overflows.
+    InvalidOrder,
+    /// The proceeds account isn't in the bank the order is paid in, or isn't the
+    /// maker's.
+    InvalidProceeds,
+    /// Every order slot of the market is taken.
+    OrderBookFull,
+    /// The order would match resting orders; take them instead.
+    OrderWouldCross,
+    /// No resting order has that id.
+    OrderNotFound,
+    /// The signer didn't place the order.
+    NotOrderOwner,
+    /// No resting order matches the limit price.
+    NoMatch,
+}
+
+impl From<OrderbookError> for ProgramError {
+    fn from(e: OrderbookError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
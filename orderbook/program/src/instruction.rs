@@ -0,0 +1,360 @@
+use crate::state::Side;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the market authority, followed by the market's address.
+pub const MARKET_AUTHORITY_SEED: &[u8] = b"market";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OrderbookInstruction {
+    /// Opens an empty market trading base tokens for quote tokens: initializes both
+    /// vaults, owned by the market authority.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The market, an uninitialized account owned by this program.
+    ///   1. `[writable]` The base vault, an uninitialized account owned by the bank program.
+    ///   2. `[writable]` The quote vault, an uninitialized account owned by the bank program.
+    ///   3. `[writable]` The base bank.
+    ///   4. `[writable]` The quote bank.
+    ///   5. `[writable]` The market authority.
+    ///   6. `[]` The bank program, owner of the vaults.
+    InitializeMarket,
+
+    /// Rests an order for `quantity` base tokens at `price` quote tokens each, moving
+    /// its escrow from the source into the vault of its side. Orders that would match
+    /// the book are refused.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The maker, owner of the source account.
+    ///   1. `[writable]` The market.
+    ///   2. `[writable]` The source account: in the quote bank for a bid, the base bank
+    ///      for an ask.
+    ///   3. `[]` The maker's account credited on fills, in the other bank.
+    ///   4. `[writable]` The vault of the source's bank.
+    ///   5. `[]` The bank of the source account.
+    ///   6. `[]` The bank program, owner of the vault.
+    PlaceOrder {
+        side: Side,
+        price: u64,
+        quantity: u64,
+    },
+
+    /// Removes a resting order and refunds what is left of its escrow.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The maker.
+    ///   1. `[writable]` The market.
+    ///   2. `[writable]` The account refunded, in the bank of the order's escrow.
+    ///   3. `[writable]` The vault holding the order's escrow.
+    ///   4. `[writable]` The market authority.
+    ///   5. `[]` The bank of the order's escrow.
+    ///   6. `[]` The bank program, owner of the vault.
+    CancelOrder { order_id: u64 },
+
+    /// Fills up to `quantity` base tokens against resting orders at `limit_price` or
+    /// better, best price first. The taker pays each maker at the maker's price and is
+    /// paid out of the escrow of the orders filled.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The taker, owner of the source account.
+    ///   1. `[writable]` The market.
+    ///   2. `[writable]` The source account: in the quote bank for a bid, the base bank
+    ///      for an ask.
+    ///   3. `[writable]` The destination account, in the other bank.
+    ///   4. `[writable]` The vault paying the taker.
+    ///   5. `[writable]` The market authority.
+    ///   6. `[]` The bank of the source account.
+    ///   7. `[]` The bank of the destination account.
+    ///   8. `[]` The bank program, owner of the vault.
+    ///   9. ..9+N `[writable]` The proceeds account of each order filled, in the order
+    ///      they fill.
+    Take {
+        side: Side,
+        limit_price: u64,
+        quantity: u64,
+    },
+}
+
+impl OrderbookInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => Self::InitializeMarket,
+            1 => {
+                let (side, rest) = Self::unpack_side(rest)?;
+                let (price, rest) = Self::unpack_u64(rest)?;
+                let (quantity, _rest) = Self::unpack_u64(rest)?;
+                Self::PlaceOrder {
+                    side,
+                    price,
+                    quantity,
+                }
+            }
+            2 => {
+                let (order_id, _rest) = Self::unpack_u64(rest)?;
+                Self::CancelOrder { order_id }
+            }
+            3 => {
+                let (side, rest) = Self::unpack_side(rest)?;
+                let (limit_price, rest) = Self::unpack_u64(rest)?;
+                let (quantity, _rest) = Self::unpack_u64(rest)?;
+                Self::Take {
+                    side,
+                    limit_price,
+                    quantity,
+                }
+            }
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(18);
+        match *self {
+            Self::InitializeMarket => buf.push(0),
+            Self::PlaceOrder {
+                side,
+                price,
+                quantity,
+            } => {
+                buf.push(1);
+                buf.push(side as u8);
+                buf.extend_from_slice(&price.to_le_bytes());
+                buf.extend_from_slice(&quantity.to_le_bytes());
+            }
+            Self::CancelOrder { order_id } => {
+                buf.push(2);
+                buf.extend_from_slice(&order_id.to_le_bytes());
+            }
+            Self::Take {
+                side,
+                limit_price,
+                quantity,
+            } => {
+                buf.push(3);
+                buf.push(side as u8);
+                buf.extend_from_slice(&limit_price.to_le_bytes());
+                buf.extend_from_slice(&quantity.to_le_bytes());
+            }
+        }
+        buf
+    }
+
+    fn unpack_side(input: &[u8]) -> Result<(Side, &[u8]), ProgramError> {
+        let (&side, rest) = input
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let side = Side::from_u8(side).ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((side, rest))
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of the authority owning `market`'s vaults.
+pub fn market_authority(orderbook_program_id: &Pubkey, market: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MARKET_AUTHORITY_SEED, market.as_ref()],
+        orderbook_program_id,
+    )
+}
+
+pub fn initialize_market(
+    orderbook_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    market: &Pubkey,
+    base_vault: &Pubkey,
+    quote_vault: &Pubkey,
+    base_bank: &Pubkey,
+    quote_bank: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = OrderbookInstruction::InitializeMarket.pack();
+    let (authority, _) = market_authority(orderbook_program_id, market);
+    let accounts = vec![
+        AccountMeta::new(*market, false),
+        AccountMeta::new(*base_vault, false),
+        AccountMeta::new(*quote_vault, false),
+        AccountMeta::new(*base_bank, false),
+        AccountMeta::new(*quote_bank, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *orderbook_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn place_order(
+    orderbook_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    source_bank: &Pubkey,
+    maker: &Pubkey,
+    market: &Pubkey,
+    source_account: &Pubkey,
+    proceeds_account: &Pubkey,
+    vault: &Pubkey,
+    side: Side,
+    price: u64,
+    quantity: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = OrderbookInstruction::PlaceOrder {
+        side,
+        price,
+        quantity,
+    }
+    .pack();
+    let accounts = vec![
+        AccountMeta::new(*maker, true),
+        AccountMeta::new(*market, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new_readonly(*proceeds_account, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*source_bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *orderbook_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn cancel_order(
+    orderbook_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    maker: &Pubkey,
+    market: &Pubkey,
+    refund_account: &Pubkey,
+    vault: &Pubkey,
+    order_id: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = OrderbookInstruction::CancelOrder { order_id }.pack();
+    let (authority, _) = market_authority(orderbook_program_id, market);
+    let accounts = vec![
+        AccountMeta::new(*maker, true),
+        AccountMeta::new(*market, false),
+        AccountMeta::new(*refund_account, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *orderbook_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// `proceeds_accounts` are those of the orders the take fills, in the order
+/// `Market::matches` returns them.
+#[allow(clippy::too_many_arguments)]
+pub fn take(
+    orderbook_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    source_bank: &Pubkey,
+    destination_bank: &Pubkey,
+    taker: &Pubkey,
+    market: &Pubkey,
+    source_account: &Pubkey,
+    destination_account: &Pubkey,
+    vault: &Pubkey,
+    proceeds_accounts: &[Pubkey],
+    side: Side,
+    limit_price: u64,
+    quantity: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = OrderbookInstruction::Take {
+        side,
+        limit_price,
+        quantity,
+    }
+    .pack();
+    let (authority, _) = market_authority(orderbook_program_id, market);
+    let mut accounts = vec![
+        AccountMeta::new(*taker, true),
+        AccountMeta::new(*market, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*source_bank, false),
+        AccountMeta::new_readonly(*destination_bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    for proceeds_account in proceeds_accounts {
+        accounts.push(AccountMeta::new(*proceeds_account, false));
+    }
+    Ok(Instruction {
+        program_id: *orderbook_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            OrderbookInstruction::InitializeMarket,
+            OrderbookInstruction::PlaceOrder {
+                side: Side::Bid,
+                price: 1,
+                quantity: u64::MAX,
+            },
+            OrderbookInstruction::CancelOrder { order_id: 2 },
+            OrderbookInstruction::Take {
+                side: Side::Ask,
+                limit_price: 3,
+                quantity: 4,
+            },
+        ];
+        for instruction in cases {
+            assert_eq!(
+                OrderbookInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        let mut data = OrderbookInstruction::Take {
+            side: Side::Bid,
+            limit_price: 3,
+            quantity: 4,
+        }
+        .pack();
+        data[1] = 0;
+        assert_eq!(
+            OrderbookInstruction::unpack(&data),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            OrderbookInstruction::unpack(&[2, 0, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            OrderbookInstruction::unpack(&[4]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,359 @@
+use crate::{
+    error::OrderbookError,
+    instruction::{OrderbookInstruction, MARKET_AUTHORITY_SEED},
+    state::{Market, Order, Side},
+};
+use solana_bank::{instruction as bank_instruction, state::Account as BankAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = OrderbookInstruction::unpack(input)?;
+
+        match instruction {
+            OrderbookInstruction::InitializeMarket => {
+                msg!("Instruction: InitializeMarket");
+                Self::process_initialize_market(program_id, accounts)
+            }
+            OrderbookInstruction::PlaceOrder {
+                side,
+                price,
+                quantity,
+            } => {
+                msg!("Instruction: PlaceOrder");
+                Self::process_place_order(program_id, accounts, side, price, quantity)
+            }
+            OrderbookInstruction::CancelOrder { order_id } => {
+                msg!("Instruction: CancelOrder");
+                Self::process_cancel_order(program_id, accounts, order_id)
+            }
+            OrderbookInstruction::Take {
+                side,
+                limit_price,
+                quantity,
+            } => {
+                msg!("Instruction: Take");
+                Self::process_take(program_id, accounts, side, limit_price, quantity)
+            }
+        }
+    }
+
+    pub fn process_initialize_market(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let market_info = next_account_info(account_info_iter)?;
+        let base_vault_info = next_account_info(account_info_iter)?;
+        let quote_vault_info = next_account_info(account_info_iter)?;
+        let base_bank_info = next_account_info(account_info_iter)?;
+        let quote_bank_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if market_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut market = Market::unpack_unchecked(&market_info.data.borrow())?;
+        if market.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if base_bank_info.key == quote_bank_info.key {
+            return Err(OrderbookError::SameBank.into());
+        }
+        let (authority, bump) = Pubkey::find_program_address(
+            &[MARKET_AUTHORITY_SEED, market_info.key.as_ref()],
+            program_id,
+        );
+        if authority != *authority_info.key {
+            return Err(OrderbookError::InvalidMarketAuthority.into());
+        }
+        let signer_seeds: &[&[u8]] = &[MARKET_AUTHORITY_SEED, market_info.key.as_ref(), &[bump]];
+
+        for (vault_info, bank_info) in [
+            (base_vault_info, base_bank_info),
+            (quote_vault_info, quote_bank_info),
+        ] {
+            if vault_info.owner != bank_program_info.key {
+                return Err(ProgramError::IllegalOwner);
+            }
+            invoke_signed(
+                &bank_instruction::initialize_account(
+                    bank_program_info.key,
+                    bank_info.key,
+                    vault_info.key,
+                    authority_info.key,
+                )?,
+                &[
+                    bank_info.clone(),
+                    vault_info.clone(),
+                    authority_info.clone(),
+                    bank_program_info.clone(),
+                ],
+                &[signer_seeds],
+            )?;
+        }
+
+        market.is_initialized = true;
+        market.base_bank = *base_bank_info.key;
+        market.quote_bank = *quote_bank_info.key;
+        market.base_vault = *base_vault_info.key;
+        market.quote_vault = *quote_vault_info.key;
+        market.bump = bump;
+        Market::pack(market, &mut market_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_place_order(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        side: Side,
+        price: u64,
+        quantity: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let maker_info = next_account_info(account_info_iter)?;
+        let market_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let proceeds_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let source_bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !maker_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut market = Self::load_market(program_id, market_info)?;
+        Self::check_vault(market.vault(side), vault_info, bank_program_info)?;
+        if price == 0 || quantity == 0 {
+            return Err(OrderbookError::InvalidOrder.into());
+        }
+        let escrow = Order::escrow(side, price, quantity).ok_or(OrderbookError::InvalidOrder)?;
+        // A bad proceeds account would fail every take reaching the order, so it is
+        // checked now rather than on the first fill.
+        if proceeds_info.owner != bank_program_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let proceeds = BankAccount::unpack(&proceeds_info.data.borrow())?;
+        if proceeds.bank != *market.proceeds_bank(side) || proceeds.owner != *maker_info.key {
+            return Err(OrderbookError::InvalidProceeds.into());
+        }
+        if !market.matches(side, price, 1).is_empty() {
+            return Err(OrderbookError::OrderWouldCross.into());
+        }
+        let order_id = market
+            .insert(side, *maker_info.key, *proceeds_info.key, price, quantity)
+            .ok_or(OrderbookError::OrderBookFull)?;
+        msg!("order id: {}", order_id);
+
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                source_bank_info.key,
+                source_info.key,
+                vault_info.key,
+                maker_info.key,
+                escrow,
+            )?,
+            &[
+                source_info.clone(),
+                vault_info.clone(),
+                maker_info.clone(),
+                source_bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+        Market::pack(market, &mut market_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_cancel_order(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        order_id: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let maker_info = next_account_info(account_info_iter)?;
+        let market_info = next_account_info(account_info_iter)?;
+        let refund_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !maker_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut market = Self::load_market(program_id, market_info)?;
+        let index = market.find(order_id).ok_or(OrderbookError::OrderNotFound)?;
+        let order = market.orders[index].ok_or(OrderbookError::OrderNotFound)?;
+        if order.owner != *maker_info.key {
+            return Err(OrderbookError::NotOrderOwner.into());
+        }
+        Self::check_vault(market.vault(order.side), vault_info, bank_program_info)?;
+        let bump = [market.bump];
+        let signer_seeds: &[&[u8]] = &[MARKET_AUTHORITY_SEED, market_info.key.as_ref(), &bump];
+        Self::check_authority(program_id, signer_seeds, authority_info)?;
+        let refund = Order::escrow(order.side, order.price, order.quantity)
+            .ok_or(OrderbookError::InvalidOrder)?;
+        market.orders[index] = None;
+
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                refund_info.key,
+                authority_info.key,
+                refund,
+            )?,
+            &[
+                vault_info.clone(),
+                refund_info.clone(),
+                authority_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        Market::pack(market, &mut market_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_take(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        side: Side,
+        limit_price: u64,
+        quantity: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let taker_info = next_account_info(account_info_iter)?;
+        let market_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let source_bank_info = next_account_info(account_info_iter)?;
+        let destination_bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !taker_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut market = Self::load_market(program_id, market_info)?;
+        Self::check_vault(market.vault(side.opposite()), vault_info, bank_program_info)?;
+        let bump = [market.bump];
+        let signer_seeds: &[&[u8]] = &[MARKET_AUTHORITY_SEED, market_info.key.as_ref(), &bump];
+        Self::check_authority(program_id, signer_seeds, authority_info)?;
+        let fills = market.matches(side, limit_price, quantity);
+        if fills.is_empty() {
+            return Err(OrderbookError::NoMatch.into());
+        }
+
+        // The taker pays every maker directly, and is paid once out of the escrow of
+        // all the orders filled.
+        let mut payout = 0u64;
+        for fill in fills.iter() {
+            let proceeds_info = next_account_info(account_info_iter)?;
+            let order = market.orders[fill.index].ok_or(OrderbookError::OrderNotFound)?;
+            if order.proceeds != *proceeds_info.key {
+                return Err(OrderbookError::InvalidProceeds.into());
+            }
+            let cost = fill
+                .price
+                .checked_mul(fill.quantity)
+                .ok_or(OrderbookError::InvalidOrder)?;
+            let (payment, received) = match side {
+                Side::Bid => (cost, fill.quantity),
+                Side::Ask => (fill.quantity, cost),
+            };
+            payout = payout
+                .checked_add(received)
+                .ok_or(OrderbookError::InvalidOrder)?;
+            invoke(
+                &bank_instruction::transfer(
+                    bank_program_info.key,
+                    source_bank_info.key,
+                    source_info.key,
+                    proceeds_info.key,
+                    taker_info.key,
+                    payment,
+                )?,
+                &[
+                    source_info.clone(),
+                    proceeds_info.clone(),
+                    taker_info.clone(),
+                    source_bank_info.clone(),
+                    bank_program_info.clone(),
+                ],
+            )?;
+            market.apply(fill);
+        }
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                destination_bank_info.key,
+                vault_info.key,
+                destination_info.key,
+                authority_info.key,
+                payout,
+            )?,
+            &[
+                vault_info.clone(),
+                destination_info.clone(),
+                authority_info.clone(),
+                destination_bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        Market::pack(market, &mut market_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn load_market(program_id: &Pubkey, market_info: &AccountInfo) -> Result<Market, ProgramError> {
+        if market_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Market::unpack(&market_info.data.borrow())
+    }
+
+    /// Checks `vault_info` is the market's vault `expected`, held in the bank program
+    /// the market authority is about to sign for.
+    fn check_vault(
+        expected: &Pubkey,
+        vault_info: &AccountInfo,
+        bank_program_info: &AccountInfo,
+    ) -> ProgramResult {
+        if vault_info.key != expected {
+            return Err(OrderbookError::InvalidVault.into());
+        }
+        if vault_info.owner != bank_program_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Ok(())
+    }
+
+    fn check_authority(
+        program_id: &Pubkey,
+        signer_seeds: &[&[u8]],
+        authority_info: &AccountInfo,
+    ) -> ProgramResult {
+        let authority = Pubkey::create_program_address(signer_seeds, program_id)
+            .map_err(|_| ProgramError::from(OrderbookError::InvalidMarketAuthority))?;
+        if authority != *authority_info.key {
+            return Err(OrderbookError::InvalidMarketAuthority.into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,408 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Resting orders a market holds at once, few enough for the unpacked market to fit a
+/// program's 4KB stack frame.
+pub const MAX_ORDERS: usize = 16;
+
+const HEADER_LEN: usize = 138;
+const ORDER_LEN: usize = 89;
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Side {
+    /// Buys base tokens, paying quote tokens.
+    Bid = 1,
+    /// Sells base tokens for quote tokens.
+    Ask = 2,
+}
+
+impl Side {
+    pub fn from_u8(value: u8) -> Option<Side> {
+        match value {
+            1 => Some(Side::Bid),
+            2 => Some(Side::Ask),
+            _ => None,
+        }
+    }
+
+    pub fn opposite(self) -> Side {
+        match self {
+            Side::Bid => Side::Ask,
+            Side::Ask => Side::Bid,
+        }
+    }
+}
+
+/// A resting order. Its maker's tokens sit in the market vault of the bank it pays in:
+/// `price * quantity` quote tokens for a bid, `quantity` base tokens for an ask.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Order {
+    pub id: u64,
+    pub side: Side,
+    pub owner: Pubkey,
+    /// The maker's account credited when the order fills, in the bank it is paid in.
+    pub proceeds: Pubkey,
+    /// Quote tokens per base token.
+    pub price: u64,
+    /// Base tokens left to fill.
+    pub quantity: u64,
+}
+
+impl Order {
+    /// Tokens locked in escrow for `quantity` at `price`: quote tokens for a bid and base
+    /// tokens for an ask. None when the order's cost in quote tokens overflows.
+    pub fn escrow(side: Side, price: u64, quantity: u64) -> Option<u64> {
+        let cost = price.checked_mul(quantity)?;
+        match side {
+            Side::Bid => Some(cost),
+            Side::Ask => Some(quantity),
+        }
+    }
+}
+
+/// Part of a resting order filled by a taker.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fill {
+    /// Slot of the order in `Market::orders`.
+    pub index: usize,
+    pub price: u64,
+    pub quantity: u64,
+}
+
+/// A book of bids and asks trading `base_bank` tokens for `quote_bank` tokens. Both
+/// vaults are bank accounts owned by the market authority.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Market {
+    pub is_initialized: bool,
+    pub base_bank: Pubkey,
+    pub quote_bank: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    /// Bump seed of the market authority address.
+    pub bump: u8,
+    /// Id given to the next order placed.
+    pub next_order_id: u64,
+    pub orders: [Option<Order>; MAX_ORDERS],
+}
+
+impl Market {
+    /// The vault holding the escrow of orders on `side`.
+    pub fn vault(&self, side: Side) -> &Pubkey {
+        match side {
+            Side::Bid => &self.quote_vault,
+            Side::Ask => &self.base_vault,
+        }
+    }
+
+    /// The bank orders on `side` are paid in.
+    pub fn proceeds_bank(&self, side: Side) -> &Pubkey {
+        match side {
+            Side::Bid => &self.base_bank,
+            Side::Ask => &self.quote_bank,
+        }
+    }
+
+    /// Rests an order in the first free slot and returns its id, or None when the book
+    /// is full.
+    pub fn insert(
+        &mut self,
+        side: Side,
+        owner: Pubkey,
+        proceeds: Pubkey,
+        price: u64,
+        quantity: u64,
+    ) -> Option<u64> {
+        let slot = self.orders.iter_mut().find(|order| order.is_none())?;
+        let id = self.next_order_id;
+        *slot = Some(Order {
+            id,
+            side,
+            owner,
+            proceeds,
+            price,
+            quantity,
+        });
+        self.next_order_id += 1;
+        Some(id)
+    }
+
+    /// Slot of the resting order `id`.
+    pub fn find(&self, id: u64) -> Option<usize> {
+        self.orders
+            .iter()
+            .position(|order| matches!(order, Some(order) if order.id == id))
+    }
+
+    /// Resting orders a taker on `side` fills for up to `quantity` base tokens at
+    /// `limit_price` or better: best price first, and oldest first within a price.
+    pub fn matches(&self, side: Side, limit_price: u64, quantity: u64) -> Vec<Fill> {
+        let mut resting: Vec<(usize, Order)> = self
+            .orders
+            .iter()
+            .enumerate()
+            .filter_map(|(index, order)| order.map(|order| (index, order)))
+            .filter(|(_, order)| order.side == side.opposite() && crosses(side, limit_price, order))
+            .collect();
+        resting.sort_by_key(|(_, order)| match side {
+            Side::Bid => (order.price, order.id),
+            Side::Ask => (u64::MAX - order.price, order.id),
+        });
+
+        let mut left = quantity;
+        let mut fills = Vec::new();
+        for (index, order) in resting {
+            if left == 0 {
+                break;
+            }
+            let filled = left.min(order.quantity);
+            fills.push(Fill {
+                index,
+                price: order.price,
+                quantity: filled,
+            });
+            left -= filled;
+        }
+        fills
+    }
+
+    /// Takes `fill.quantity` off its order, and frees the slot once nothing is left.
+    pub fn apply(&mut self, fill: &Fill) {
+        if let Some(order) = self.orders[fill.index].as_mut() {
+            order.quantity -= fill.quantity;
+            if order.quantity == 0 {
+                self.orders[fill.index] = None;
+            }
+        }
+    }
+}
+
+/// Whether a taker on `side` at `limit_price` trades with `order`.
+fn crosses(side: Side, limit_price: u64, order: &Order) -> bool {
+    match side {
+        Side::Bid => order.price <= limit_price,
+        Side::Ask => order.price >= limit_price,
+    }
+}
+
+impl Sealed for Market {}
+impl IsInitialized for Market {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Market {
+    const LEN: usize = HEADER_LEN + MAX_ORDERS * ORDER_LEN;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let header = array_ref![src, 0, HEADER_LEN];
+        let (is_initialized, base_bank, quote_bank, base_vault, quote_vault, bump, next_order_id) =
+            array_refs![header, 1, 32, 32, 32, 32, 1, 8];
+        let mut market = Market {
+            is_initialized: unpack_bool(is_initialized)?,
+            base_bank: Pubkey::new_from_array(*base_bank),
+            quote_bank: Pubkey::new_from_array(*quote_bank),
+            base_vault: Pubkey::new_from_array(*base_vault),
+            quote_vault: Pubkey::new_from_array(*quote_vault),
+            bump: bump[0],
+            next_order_id: u64::from_le_bytes(*next_order_id),
+            orders: [None; MAX_ORDERS],
+        };
+        for (slot, src) in market
+            .orders
+            .iter_mut()
+            .zip(src[HEADER_LEN..Self::LEN].chunks_exact(ORDER_LEN))
+        {
+            *slot = unpack_order(array_ref![src, 0, ORDER_LEN])?;
+        }
+        Ok(market)
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let header = array_mut_ref![dst, 0, HEADER_LEN];
+        let (is_initialized, base_bank, quote_bank, base_vault, quote_vault, bump, next_order_id) =
+            mut_array_refs![header, 1, 32, 32, 32, 32, 1, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        base_bank.copy_from_slice(self.base_bank.as_ref());
+        quote_bank.copy_from_slice(self.quote_bank.as_ref());
+        base_vault.copy_from_slice(self.base_vault.as_ref());
+        quote_vault.copy_from_slice(self.quote_vault.as_ref());
+        bump[0] = self.bump;
+        *next_order_id = self.next_order_id.to_le_bytes();
+        for (order, dst) in self
+            .orders
+            .iter()
+            .zip(dst[HEADER_LEN..Self::LEN].chunks_exact_mut(ORDER_LEN))
+        {
+            pack_order(order, array_mut_ref![dst, 0, ORDER_LEN]);
+        }
+    }
+}
+
+/// A free slot is all zeroes; a taken one starts with the order's side.
+fn unpack_order(src: &[u8; ORDER_LEN]) -> Result<Option<Order>, ProgramError> {
+    let (side, id, owner, proceeds, price, quantity) = array_refs![src, 1, 8, 32, 32, 8, 8];
+    if side[0] == 0 {
+        return Ok(None);
+    }
+    Ok(Some(Order {
+        id: u64::from_le_bytes(*id),
+        side: Side::from_u8(side[0]).ok_or(ProgramError::InvalidAccountData)?,
+        owner: Pubkey::new_from_array(*owner),
+        proceeds: Pubkey::new_from_array(*proceeds),
+        price: u64::from_le_bytes(*price),
+        quantity: u64::from_le_bytes(*quantity),
+    }))
+}
+
+fn pack_order(order: &Option<Order>, dst: &mut [u8; ORDER_LEN]) {
+    let order = match order {
+        Some(order) => order,
+        None => {
+            *dst = [0; ORDER_LEN];
+            return;
+        }
+    };
+    let (side, id, owner, proceeds, price, quantity) = mut_array_refs![dst, 1, 8, 32, 32, 8, 8];
+    side[0] = order.side as u8;
+    *id = order.id.to_le_bytes();
+    owner.copy_from_slice(order.owner.as_ref());
+    proceeds.copy_from_slice(order.proceeds.as_ref());
+    *price = order.price.to_le_bytes();
+    *quantity = order.quantity.to_le_bytes();
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(orders: &[(Side, u64, u64)]) -> Market {
+        let mut market = Market::default();
+        for &(side, price, quantity) in orders {
+            market
+                .insert(
+                    side,
+                    Pubkey::new_unique(),
+                    Pubkey::new_unique(),
+                    price,
+                    quantity,
+                )
+                .unwrap();
+        }
+        market
+    }
+
+    #[test]
+    fn test_pack_unpack() {
+        let mut market = book(&[(Side::Bid, 9, 100), (Side::Ask, 11, 50), (Side::Ask, 12, 1)]);
+        market.is_initialized = true;
+        market.base_bank = Pubkey::new_unique();
+        market.quote_bank = Pubkey::new_unique();
+        market.base_vault = Pubkey::new_unique();
+        market.quote_vault = Pubkey::new_unique();
+        market.bump = 253;
+        market.orders[1] = None;
+        let mut packed = vec![0u8; Market::LEN];
+        Market::pack(market.clone(), &mut packed).unwrap();
+        assert_eq!(Market::unpack(&packed), Ok(market));
+
+        packed[HEADER_LEN] = 3;
+        assert_eq!(
+            Market::unpack(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+        packed[0] = 2;
+        assert_eq!(
+            Market::unpack(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut market = book(&[(Side::Bid, 1, 1); MAX_ORDERS]);
+        assert_eq!(market.next_order_id, MAX_ORDERS as u64);
+        let (owner, proceeds) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_eq!(market.insert(Side::Ask, owner, proceeds, 2, 1), None);
+
+        // A freed slot is reused, under a new id.
+        market.orders[5] = None;
+        assert_eq!(
+            market.insert(Side::Ask, owner, proceeds, 2, 1),
+            Some(MAX_ORDERS as u64)
+        );
+        assert_eq!(market.find(MAX_ORDERS as u64), Some(5));
+        assert_eq!(market.find(5), None);
+    }
+
+    #[test]
+    fn test_matches() {
+        let market = book(&[
+            (Side::Ask, 12, 10),
+            (Side::Ask, 11, 5),
+            (Side::Bid, 9, 20),
+            (Side::Ask, 11, 7),
+            (Side::Bid, 10, 3),
+            (Side::Bid, 9, 4),
+        ]);
+        let fill = |index, price, quantity| Fill {
+            index,
+            price,
+            quantity,
+        };
+
+        // Cheapest asks first, and the older of two at the same price.
+        assert_eq!(
+            market.matches(Side::Bid, 12, 15),
+            vec![fill(1, 11, 5), fill(3, 11, 7), fill(0, 12, 3)]
+        );
+        assert_eq!(
+            market.matches(Side::Bid, 11, 100),
+            vec![fill(1, 11, 5), fill(3, 11, 7)]
+        );
+        assert_eq!(market.matches(Side::Bid, 10, 100), vec![]);
+
+        // Dearest bids first.
+        assert_eq!(
+            market.matches(Side::Ask, 9, 10),
+            vec![fill(4, 10, 3), fill(2, 9, 7)]
+        );
+        assert_eq!(market.matches(Side::Ask, 10, 10), vec![fill(4, 10, 3)]);
+        assert_eq!(market.matches(Side::Ask, 1, 0), vec![]);
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut market = book(&[(Side::Ask, 11, 5)]);
+        market.apply(&Fill {
+            index: 0,
+            price: 11,
+            quantity: 2,
+        });
+        assert_eq!(market.orders[0].unwrap().quantity, 3);
+        market.apply(&Fill {
+            index: 0,
+            price: 11,
+            quantity: 3,
+        });
+        assert_eq!(market.orders[0], None);
+    }
+
+    #[test]
+    fn test_escrow() {
+        assert_eq!(Order::escrow(Side::Bid, 3, 4), Some(12));
+        assert_eq!(Order::escrow(Side::Ask, 3, 4), Some(4));
+        assert_eq!(Order::escrow(Side::Ask, 2, u64::MAX), None);
+    }
+}
@@ -0,0 +1,474 @@
+use solana_bank::{state::Account, test_utils::program_test::Env};
+use solana_orderbook::{
+    error::OrderbookError,
+    instruction::{cancel_order, initialize_market, market_authority, place_order, take},
+    processor::Processor,
+    state::{Market, Side, MAX_ORDERS},
+};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// Someone holding 100_000 tokens of each bank.
+struct Trader {
+    owner: Keypair,
+    base: Pubkey,
+    quote: Pubkey,
+}
+
+/// An empty market between two banks owned by the payer.
+struct Fixture {
+    env: Env,
+    market: Pubkey,
+    base_bank: Pubkey,
+    quote_bank: Pubkey,
+    base_vault: Pubkey,
+    quote_vault: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_orderbook", processor!(Processor::process)).await;
+        let base_bank = env.create_bank().await;
+        let quote_bank = env.create_bank().await;
+        let market = Keypair::new();
+        let base_vault = Keypair::new();
+        let quote_vault = Keypair::new();
+        let orderbook_program_id = env.program_id;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&market, Market::LEN, &orderbook_program_id)
+            .await;
+        env.create_account(&base_vault, Account::LEN, &bank_program_id)
+            .await;
+        env.create_account(&quote_vault, Account::LEN, &bank_program_id)
+            .await;
+        let instruction = initialize_market(
+            &orderbook_program_id,
+            &bank_program_id,
+            &market.pubkey(),
+            &base_vault.pubkey(),
+            &quote_vault.pubkey(),
+            &base_bank,
+            &quote_bank,
+        )
+        .unwrap();
+        env.process(&[instruction], &[]).await.unwrap();
+        Fixture {
+            env,
+            market: market.pubkey(),
+            base_bank,
+            quote_bank,
+            base_vault: base_vault.pubkey(),
+            quote_vault: quote_vault.pubkey(),
+        }
+    }
+
+    async fn trader(&mut self) -> Trader {
+        let owner = Keypair::new();
+        let (base_bank, quote_bank) = (self.base_bank, self.quote_bank);
+        let base = self
+            .env
+            .create_bank_account(&base_bank, &owner, 100_000)
+            .await;
+        let quote = self
+            .env
+            .create_bank_account(&quote_bank, &owner, 100_000)
+            .await;
+        Trader { owner, base, quote }
+    }
+
+    async fn market(&mut self) -> Market {
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(self.market)
+            .await
+            .unwrap()
+            .unwrap();
+        Market::unpack(&account.data).unwrap()
+    }
+
+    /// The accounts a bid pays from and is paid into, or the other way around for an ask.
+    fn accounts(trader: &Trader, side: Side) -> (Pubkey, Pubkey) {
+        match side {
+            Side::Bid => (trader.quote, trader.base),
+            Side::Ask => (trader.base, trader.quote),
+        }
+    }
+
+    fn vault(&self, side: Side) -> Pubkey {
+        match side {
+            Side::Bid => self.quote_vault,
+            Side::Ask => self.base_vault,
+        }
+    }
+
+    /// The bank a side pays in.
+    fn bank(&self, side: Side) -> Pubkey {
+        match side {
+            Side::Bid => self.quote_bank,
+            Side::Ask => self.base_bank,
+        }
+    }
+
+    async fn place(
+        &mut self,
+        maker: &Trader,
+        side: Side,
+        price: u64,
+        quantity: u64,
+    ) -> Result<(), TransactionError> {
+        let (source, proceeds) = Self::accounts(maker, side);
+        let instruction = place_order(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank(side),
+            &maker.owner.pubkey(),
+            &self.market,
+            &source,
+            &proceeds,
+            &self.vault(side),
+            side,
+            price,
+            quantity,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[&maker.owner]).await
+    }
+
+    async fn cancel(&mut self, maker: &Trader, order_id: u64) -> Result<(), TransactionError> {
+        let market = self.market().await;
+        let side = market
+            .find(order_id)
+            .and_then(|index| market.orders[index])
+            .map_or(Side::Bid, |order| order.side);
+        let (refund, _) = Self::accounts(maker, side);
+        let instruction = cancel_order(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank(side),
+            &maker.owner.pubkey(),
+            &self.market,
+            &refund,
+            &self.vault(side),
+            order_id,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[&maker.owner]).await
+    }
+
+    /// Takes with the proceeds accounts of the orders the book says will fill.
+    async fn take(
+        &mut self,
+        taker: &Trader,
+        side: Side,
+        limit_price: u64,
+        quantity: u64,
+    ) -> Result<(), TransactionError> {
+        let market = self.market().await;
+        let proceeds: Vec<Pubkey> = market
+            .matches(side, limit_price, quantity)
+            .iter()
+            .map(|fill| market.orders[fill.index].unwrap().proceeds)
+            .collect();
+        self.take_with(taker, side, limit_price, quantity, &proceeds)
+            .await
+    }
+
+    async fn take_with(
+        &mut self,
+        taker: &Trader,
+        side: Side,
+        limit_price: u64,
+        quantity: u64,
+        proceeds: &[Pubkey],
+    ) -> Result<(), TransactionError> {
+        let (source, destination) = Self::accounts(taker, side);
+        let instruction = take(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank(side),
+            &self.bank(side.opposite()),
+            &taker.owner.pubkey(),
+            &self.market,
+            &source,
+            &destination,
+            &self.vault(side.opposite()),
+            proceeds,
+            side,
+            limit_price,
+            quantity,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[&taker.owner]).await
+    }
+
+    /// Balances of a trader's base and quote accounts.
+    async fn balances(&mut self, trader: &Trader) -> (u64, u64) {
+        (
+            self.env.amount(&trader.base).await,
+            self.env.amount(&trader.quote).await,
+        )
+    }
+
+    async fn vaults(&mut self) -> (u64, u64) {
+        let (base_vault, quote_vault) = (self.base_vault, self.quote_vault);
+        (
+            self.env.amount(&base_vault).await,
+            self.env.amount(&quote_vault).await,
+        )
+    }
+}
+
+fn custom(error: OrderbookError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_initialize_market() {
+    let mut fixture = Fixture::new().await;
+    let (authority, _) = market_authority(&fixture.env.program_id, &fixture.market);
+    let market = fixture.market().await;
+    assert_eq!(
+        (market.base_bank, market.quote_bank),
+        (fixture.base_bank, fixture.quote_bank)
+    );
+    assert_eq!(market.orders, [None; MAX_ORDERS]);
+    let vault = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(fixture.quote_vault)
+        .await
+        .unwrap()
+        .unwrap();
+    let vault = Account::unpack(&vault.data).unwrap();
+    assert_eq!((vault.owner, vault.bank), (authority, fixture.quote_bank));
+
+    let orderbook_program_id = fixture.env.program_id;
+    let bank_program_id = fixture.env.bank_program_id;
+    let accounts = [Keypair::new(), Keypair::new(), Keypair::new()];
+    fixture
+        .env
+        .create_account(&accounts[0], Market::LEN, &orderbook_program_id)
+        .await;
+    fixture
+        .env
+        .create_account(&accounts[1], Account::LEN, &bank_program_id)
+        .await;
+    fixture
+        .env
+        .create_account(&accounts[2], Account::LEN, &bank_program_id)
+        .await;
+    let instruction = initialize_market(
+        &orderbook_program_id,
+        &bank_program_id,
+        &accounts[0].pubkey(),
+        &accounts[1].pubkey(),
+        &accounts[2].pubkey(),
+        &fixture.base_bank,
+        &fixture.base_bank,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[]).await,
+        custom(OrderbookError::SameBank)
+    );
+}
+
+#[tokio::test]
+async fn test_place_and_cancel() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.trader().await;
+    let bob = fixture.trader().await;
+
+    // A bid escrows what it would pay, an ask what it sells.
+    fixture.place(&alice, Side::Bid, 10, 50).await.unwrap();
+    fixture.place(&alice, Side::Ask, 12, 20).await.unwrap();
+    assert_eq!(fixture.balances(&alice).await, (99_980, 99_500));
+    assert_eq!(fixture.vaults().await, (20, 500));
+
+    assert_eq!(
+        fixture.place(&bob, Side::Ask, 10, 5).await,
+        custom(OrderbookError::OrderWouldCross)
+    );
+    assert_eq!(
+        fixture.place(&bob, Side::Bid, 0, 5).await,
+        custom(OrderbookError::InvalidOrder)
+    );
+    assert_eq!(
+        fixture.place(&bob, Side::Bid, 2, u64::MAX).await,
+        custom(OrderbookError::InvalidOrder)
+    );
+    // Proceeds have to go to the maker, in the bank the order is paid in.
+    let instruction = place_order(
+        &fixture.env.program_id,
+        &fixture.env.bank_program_id,
+        &fixture.quote_bank,
+        &bob.owner.pubkey(),
+        &fixture.market,
+        &bob.quote,
+        &alice.base,
+        &fixture.quote_vault,
+        Side::Bid,
+        9,
+        5,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&bob.owner]).await,
+        custom(OrderbookError::InvalidProceeds)
+    );
+
+    assert_eq!(
+        fixture.cancel(&bob, 0).await,
+        custom(OrderbookError::NotOrderOwner)
+    );
+    fixture.cancel(&alice, 0).await.unwrap();
+    assert_eq!(fixture.balances(&alice).await, (99_980, 100_000));
+    assert_eq!(
+        fixture.cancel(&alice, 2).await,
+        custom(OrderbookError::OrderNotFound)
+    );
+    fixture.cancel(&alice, 1).await.unwrap();
+    assert_eq!(fixture.balances(&alice).await, (100_000, 100_000));
+    assert_eq!(fixture.vaults().await, (0, 0));
+}
+
+#[tokio::test]
+async fn test_take() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.trader().await;
+    let bob = fixture.trader().await;
+    let carol = fixture.trader().await;
+    let dave = fixture.trader().await;
+    fixture.place(&carol, Side::Ask, 12, 10).await.unwrap();
+    fixture.place(&alice, Side::Ask, 11, 5).await.unwrap();
+    fixture.place(&bob, Side::Ask, 11, 7).await.unwrap();
+
+    assert_eq!(
+        fixture.take(&dave, Side::Bid, 10, 15).await,
+        custom(OrderbookError::NoMatch)
+    );
+    // Proceeds accounts have to follow the fills: Alice's first, being older than Bob's.
+    assert_eq!(
+        fixture
+            .take_with(
+                &dave,
+                Side::Bid,
+                12,
+                15,
+                &[bob.quote, alice.quote, carol.quote]
+            )
+            .await,
+        custom(OrderbookError::InvalidProceeds)
+    );
+
+    // Both orders at 11 fill before Carol's at 12, each at its maker's price.
+    fixture.take(&dave, Side::Bid, 12, 15).await.unwrap();
+    assert_eq!(
+        fixture.balances(&dave).await,
+        (100_015, 100_000 - 55 - 77 - 36)
+    );
+    assert_eq!(fixture.balances(&alice).await, (99_995, 100_055));
+    assert_eq!(fixture.balances(&bob).await, (99_993, 100_077));
+    assert_eq!(fixture.balances(&carol).await, (99_990, 100_036));
+    assert_eq!(fixture.vaults().await, (7, 0));
+    let market = fixture.market().await;
+    let resting: Vec<_> = market.orders.iter().flatten().collect();
+    assert_eq!(resting.len(), 1);
+    assert_eq!((resting[0].id, resting[0].quantity), (0, 7));
+
+    // Selling into the bids pays the taker out of the quote vault.
+    fixture.place(&alice, Side::Bid, 9, 10).await.unwrap();
+    fixture.place(&bob, Side::Bid, 8, 10).await.unwrap();
+    fixture.take(&dave, Side::Ask, 8, 12).await.unwrap();
+    assert_eq!(fixture.balances(&dave).await, (100_003, 99_832 + 90 + 16));
+    assert_eq!(fixture.balances(&alice).await, (100_005, 99_965));
+    assert_eq!(fixture.balances(&bob).await, (99_995, 99_997));
+    assert_eq!(fixture.vaults().await, (7, 64));
+
+    fixture.cancel(&bob, 4).await.unwrap();
+    fixture.cancel(&carol, 0).await.unwrap();
+    assert_eq!(fixture.vaults().await, (0, 0));
+}
+
+#[tokio::test]
+async fn test_book_full() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.trader().await;
+    for price in 1..=MAX_ORDERS as u64 {
+        fixture.place(&alice, Side::Ask, price, 1).await.unwrap();
+    }
+    assert_eq!(
+        fixture.place(&alice, Side::Ask, 100, 1).await,
+        custom(OrderbookError::OrderBookFull)
+    );
+
+    // Filling an order frees its slot.
+    let bob = fixture.trader().await;
+    fixture.take(&bob, Side::Bid, 1, 1).await.unwrap();
+    fixture.place(&alice, Side::Ask, 101, 1).await.unwrap();
+    assert_eq!(fixture.market().await.next_order_id, MAX_ORDERS as u64 + 1);
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.trader().await;
+    let bob = fixture.trader().await;
+    fixture.place(&alice, Side::Bid, 10, 50).await.unwrap();
+    let orderbook_program_id = fixture.env.program_id;
+    let fake_bank_program_id = fixture.env.fake_bank_program_id;
+    let wrong_owner = Err(TransactionError::InstructionError(
+        0,
+        InstructionError::IllegalOwner,
+    ));
+
+    // Through a program posing as the bank, a cancel or a take would hand it the market
+    // authority's signature over the vault.
+    let instruction = cancel_order(
+        &orderbook_program_id,
+        &fake_bank_program_id,
+        &fixture.quote_bank,
+        &alice.owner.pubkey(),
+        &fixture.market,
+        &alice.quote,
+        &fixture.quote_vault,
+        0,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&alice.owner]).await,
+        wrong_owner
+    );
+    let instruction = take(
+        &orderbook_program_id,
+        &fake_bank_program_id,
+        &fixture.base_bank,
+        &fixture.quote_bank,
+        &bob.owner.pubkey(),
+        &fixture.market,
+        &bob.base,
+        &bob.quote,
+        &fixture.quote_vault,
+        &[alice.base],
+        Side::Ask,
+        10,
+        50,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&bob.owner]).await,
+        wrong_owner
+    );
+    assert_eq!(fixture.vaults().await, (0, 500));
+    assert!(fixture.market().await.find(0).is_some());
+}
@@ -0,0 +1,24 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PayrollError {
+    /// The vault authority isn't the payroll's program address.
+    InvalidVaultAuthority,
+    /// The account isn't the payroll's vault.
+    InvalidVault,
+    /// The employee isn't the program address of the destination in the payroll, or isn't
+    /// registered in it.
+    InvalidEmployee,
+    /// The destination isn't an account of the payroll's bank, or isn't the employee's.
+    InvalidDestination,
+    /// The pay and the pay period must be above zero.
+    InvalidSchedule,
+    /// The signer isn't the payroll's employer.
+    NotEmployer,
+}
+
+impl From<PayrollError> for ProgramError {
+    fn from(e: PayrollError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,274 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the vault authority, followed by the payroll's address.
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"payroll";
+/// Seed prefix of an employee, followed by the payroll's and the destination's addresses.
+pub const EMPLOYEE_SEED: &[u8] = b"employee";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PayrollInstruction {
+    /// Opens a payroll and initializes its vault, owned by the vault authority.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The employer.
+    ///   1. `[writable]` The payroll, an uninitialized account owned by this program.
+    ///   2. `[writable]` The vault, an uninitialized account owned by the bank program.
+    ///   3. `[writable]` The bank pay is made in.
+    ///   4. `[writable]` The vault authority.
+    ///   5. `[]` The bank program, owner of the vault.
+    CreatePayroll,
+
+    /// Registers pay of `amount` into the destination account every `period` seconds,
+    /// the first at `first_pay_ts`.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The employer, paying for the employee account.
+    ///   1. `[]` The payroll.
+    ///   2. `[writable]` The employee, the program address of the destination.
+    ///   3. `[]` The destination account.
+    ///   4. `[]` The system program.
+    AddEmployee {
+        amount: u64,
+        period: i64,
+        first_pay_ts: i64,
+    },
+
+    /// Stops paying an employee and closes its account.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The employer, credited the employee account's rent.
+    ///   1. `[]` The payroll.
+    ///   2. `[writable]` The employee.
+    RemoveEmployee,
+
+    /// Pays every listed employee whose pay period has elapsed, for all the periods that
+    /// have. Anyone can run it; employees not due yet are skipped.
+    ///
+    /// Accounts expected:
+    ///   0. `[]` The payroll.
+    ///   1. `[writable]` The vault.
+    ///   2. `[writable]` The vault authority.
+    ///   3. `[]` The payroll's bank.
+    ///   4. `[]` The bank program, owner of the vault.
+    ///   5. `[writable]` The first employee, followed by its destination account, then
+    ///      the next employee and its destination, and so on.
+    RunPayroll,
+}
+
+impl PayrollInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => Self::CreatePayroll,
+            1 => {
+                let (amount, rest) = Self::unpack_u64(rest)?;
+                let (period, rest) = Self::unpack_u64(rest)?;
+                let (first_pay_ts, _rest) = Self::unpack_u64(rest)?;
+                Self::AddEmployee {
+                    amount,
+                    period: period as i64,
+                    first_pay_ts: first_pay_ts as i64,
+                }
+            }
+            2 => Self::RemoveEmployee,
+            3 => Self::RunPayroll,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(25);
+        match *self {
+            Self::CreatePayroll => buf.push(0),
+            Self::AddEmployee {
+                amount,
+                period,
+                first_pay_ts,
+            } => {
+                buf.push(1);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(&period.to_le_bytes());
+                buf.extend_from_slice(&first_pay_ts.to_le_bytes());
+            }
+            Self::RemoveEmployee => buf.push(2),
+            Self::RunPayroll => buf.push(3),
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of the authority owning `payroll`'s vault.
+pub fn vault_authority(payroll_program_id: &Pubkey, payroll: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VAULT_AUTHORITY_SEED, payroll.as_ref()],
+        payroll_program_id,
+    )
+}
+
+/// Address and bump of the employee paid into `destination` by `payroll`.
+pub fn employee_address(
+    payroll_program_id: &Pubkey,
+    payroll: &Pubkey,
+    destination: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[EMPLOYEE_SEED, payroll.as_ref(), destination.as_ref()],
+        payroll_program_id,
+    )
+}
+
+pub fn create_payroll(
+    payroll_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    employer: &Pubkey,
+    payroll: &Pubkey,
+    vault: &Pubkey,
+    bank: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = PayrollInstruction::CreatePayroll.pack();
+    let (authority, _) = vault_authority(payroll_program_id, payroll);
+    let accounts = vec![
+        AccountMeta::new_readonly(*employer, true),
+        AccountMeta::new(*payroll, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *payroll_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn add_employee(
+    payroll_program_id: &Pubkey,
+    employer: &Pubkey,
+    payroll: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+    period: i64,
+    first_pay_ts: i64,
+) -> Result<Instruction, ProgramError> {
+    let data = PayrollInstruction::AddEmployee {
+        amount,
+        period,
+        first_pay_ts,
+    }
+    .pack();
+    let (employee, _) = employee_address(payroll_program_id, payroll, destination);
+    let accounts = vec![
+        AccountMeta::new(*employer, true),
+        AccountMeta::new_readonly(*payroll, false),
+        AccountMeta::new(employee, false),
+        AccountMeta::new_readonly(*destination, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *payroll_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn remove_employee(
+    payroll_program_id: &Pubkey,
+    employer: &Pubkey,
+    payroll: &Pubkey,
+    destination: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = PayrollInstruction::RemoveEmployee.pack();
+    let (employee, _) = employee_address(payroll_program_id, payroll, destination);
+    let accounts = vec![
+        AccountMeta::new(*employer, true),
+        AccountMeta::new_readonly(*payroll, false),
+        AccountMeta::new(employee, false),
+    ];
+    Ok(Instruction {
+        program_id: *payroll_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Runs the payroll for the employees paid into each of `destinations`.
+pub fn run_payroll(
+    payroll_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    payroll: &Pubkey,
+    vault: &Pubkey,
+    destinations: &[Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = PayrollInstruction::RunPayroll.pack();
+    let (authority, _) = vault_authority(payroll_program_id, payroll);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*payroll, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    for destination in destinations.iter() {
+        let (employee, _) = employee_address(payroll_program_id, payroll, destination);
+        accounts.push(AccountMeta::new(employee, false));
+        accounts.push(AccountMeta::new(*destination, false));
+    }
+    Ok(Instruction {
+        program_id: *payroll_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            PayrollInstruction::CreatePayroll,
+            PayrollInstruction::AddEmployee {
+                amount: 3_000,
+                period: 1_209_600,
+                first_pay_ts: -5,
+            },
+            PayrollInstruction::RemoveEmployee,
+            PayrollInstruction::RunPayroll,
+        ];
+        for instruction in cases {
+            assert_eq!(
+                PayrollInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            PayrollInstruction::unpack(&[1, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            PayrollInstruction::unpack(&[4]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
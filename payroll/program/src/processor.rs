@@ -0,0 +1,295 @@
+use crate::{
+    error::PayrollError,
+    instruction::{PayrollInstruction, EMPLOYEE_SEED, VAULT_AUTHORITY_SEED},
+    state::{Employee, Payroll},
+};
+use solana_bank::{instruction as bank_instruction, state::Account as BankAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = PayrollInstruction::unpack(input)?;
+
+        match instruction {
+            PayrollInstruction::CreatePayroll => {
+                msg!("Instruction: CreatePayroll");
+                Self::process_create_payroll(program_id, accounts)
+            }
+            PayrollInstruction::AddEmployee {
+                amount,
+                period,
+                first_pay_ts,
+            } => {
+                msg!("Instruction: AddEmployee");
+                Self::process_add_employee(program_id, accounts, amount, period, first_pay_ts)
+            }
+            PayrollInstruction::RemoveEmployee => {
+                msg!("Instruction: RemoveEmployee");
+                Self::process_remove_employee(program_id, accounts)
+            }
+            PayrollInstruction::RunPayroll => {
+                msg!("Instruction: RunPayroll");
+                Self::process_run_payroll(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_create_payroll(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let employer_info = next_account_info(account_info_iter)?;
+        let payroll_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !employer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if payroll_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut payroll = Payroll::unpack_unchecked(&payroll_info.data.borrow())?;
+        if payroll.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        let (authority, bump) = Pubkey::find_program_address(
+            &[VAULT_AUTHORITY_SEED, payroll_info.key.as_ref()],
+            program_id,
+        );
+        if authority != *authority_info.key {
+            return Err(PayrollError::InvalidVaultAuthority.into());
+        }
+        if vault_info.owner != bank_program_info.key {
+            return Err(PayrollError::InvalidVault.into());
+        }
+
+        invoke_signed(
+            &bank_instruction::initialize_account(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                authority_info.key,
+            )?,
+            &[
+                bank_info.clone(),
+                vault_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[VAULT_AUTHORITY_SEED, payroll_info.key.as_ref(), &[bump]]],
+        )?;
+
+        payroll.is_initialized = true;
+        payroll.employer = *employer_info.key;
+        payroll.bank = *bank_info.key;
+        payroll.vault = *vault_info.key;
+        payroll.bump = bump;
+        Payroll::pack(payroll, &mut payroll_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_add_employee(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+        period: i64,
+        first_pay_ts: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let employer_info = next_account_info(account_info_iter)?;
+        let payroll_info = next_account_info(account_info_iter)?;
+        let employee_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        let payroll = Self::load_payroll(program_id, payroll_info, employer_info)?;
+        if amount == 0 || period <= 0 {
+            return Err(PayrollError::InvalidSchedule.into());
+        }
+        if BankAccount::unpack(&destination_info.data.borrow())?.bank != payroll.bank {
+            return Err(PayrollError::InvalidDestination.into());
+        }
+        let (employee, bump) = Pubkey::find_program_address(
+            &[
+                EMPLOYEE_SEED,
+                payroll_info.key.as_ref(),
+                destination_info.key.as_ref(),
+            ],
+            program_id,
+        );
+        if employee != *employee_info.key {
+            return Err(PayrollError::InvalidEmployee.into());
+        }
+        if !employee_info.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        invoke_signed(
+            &system_instruction::create_account(
+                employer_info.key,
+                employee_info.key,
+                Rent::get()?.minimum_balance(Employee::LEN),
+                Employee::LEN as u64,
+                program_id,
+            ),
+            &[
+                employer_info.clone(),
+                employee_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[&[
+                EMPLOYEE_SEED,
+                payroll_info.key.as_ref(),
+                destination_info.key.as_ref(),
+                &[bump],
+            ]],
+        )?;
+
+        let employee = Employee {
+            is_initialized: true,
+            payroll: *payroll_info.key,
+            destination: *destination_info.key,
+            amount,
+            period,
+            next_pay_ts: first_pay_ts,
+        };
+        Employee::pack(employee, &mut employee_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_remove_employee(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let employer_info = next_account_info(account_info_iter)?;
+        let payroll_info = next_account_info(account_info_iter)?;
+        let employee_info = next_account_info(account_info_iter)?;
+
+        Self::load_payroll(program_id, payroll_info, employer_info)?;
+        Self::load_employee(program_id, employee_info, payroll_info)?;
+
+        let lamports = employee_info.lamports();
+        **employee_info.lamports.borrow_mut() = 0;
+        **employer_info.lamports.borrow_mut() = employer_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(ProgramError::InvalidArgument)?;
+        employee_info.data.borrow_mut().fill(0);
+        Ok(())
+    }
+
+    pub fn process_run_payroll(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let payroll_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if payroll_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let payroll = Payroll::unpack(&payroll_info.data.borrow())?;
+        // Anyone can run the payroll, so the bank program is pinned to the vault's owner:
+        // any other program would take the vault authority's signature, and the pay
+        // periods would be marked paid without paying.
+        if payroll.vault != *vault_info.key || vault_info.owner != bank_program_info.key {
+            return Err(PayrollError::InvalidVault.into());
+        }
+        let bump = [payroll.bump];
+        let signer_seeds: &[&[u8]] = &[VAULT_AUTHORITY_SEED, payroll_info.key.as_ref(), &bump];
+        let authority = Pubkey::create_program_address(signer_seeds, program_id)
+            .map_err(|_| ProgramError::from(PayrollError::InvalidVaultAuthority))?;
+        if authority != *authority_info.key {
+            return Err(PayrollError::InvalidVaultAuthority.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let mut paid = 0;
+        while let Some(employee_info) = account_info_iter.next() {
+            let destination_info = next_account_info(account_info_iter)?;
+            let mut employee = Self::load_employee(program_id, employee_info, payroll_info)?;
+            if employee.destination != *destination_info.key {
+                return Err(PayrollError::InvalidDestination.into());
+            }
+            let periods = employee.periods_due(now);
+            if periods == 0 {
+                continue;
+            }
+            let amount = employee
+                .amount
+                .checked_mul(periods)
+                .ok_or(ProgramError::InvalidArgument)?;
+            invoke_signed(
+                &bank_instruction::transfer(
+                    bank_program_info.key,
+                    bank_info.key,
+                    vault_info.key,
+                    destination_info.key,
+                    authority_info.key,
+                    amount,
+                )?,
+                &[
+                    vault_info.clone(),
+                    destination_info.clone(),
+                    authority_info.clone(),
+                    bank_info.clone(),
+                    bank_program_info.clone(),
+                ],
+                &[signer_seeds],
+            )?;
+
+            employee.next_pay_ts += employee.period * periods as i64;
+            Employee::pack(employee, &mut employee_info.data.borrow_mut())?;
+            paid += 1;
+        }
+        msg!("Paid {} employees", paid);
+        Ok(())
+    }
+
+    /// Loads the payroll, checking it is signed for by its employer.
+    fn load_payroll(
+        program_id: &Pubkey,
+        payroll_info: &AccountInfo,
+        employer_info: &AccountInfo,
+    ) -> Result<Payroll, ProgramError> {
+        if !employer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if payroll_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let payroll = Payroll::unpack(&payroll_info.data.borrow())?;
+        if payroll.employer != *employer_info.key {
+            return Err(PayrollError::NotEmployer.into());
+        }
+        Ok(payroll)
+    }
+
+    fn load_employee(
+        program_id: &Pubkey,
+        employee_info: &AccountInfo,
+        payroll_info: &AccountInfo,
+    ) -> Result<Employee, ProgramError> {
+        if employee_info.owner != program_id {
+            return Err(PayrollError::InvalidEmployee.into());
+        }
+        let employee = Employee::unpack_unchecked(&employee_info.data.borrow())?;
+        if !employee.is_initialized || employee.payroll != *payroll_info.key {
+            return Err(PayrollError::InvalidEmployee.into());
+        }
+        Ok(employee)
+    }
+}
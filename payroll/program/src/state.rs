@@ -0,0 +1,173 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Offset of `Employee::payroll`, for finding every employee of a payroll.
+pub const EMPLOYEE_PAYROLL_OFFSET: usize = 1;
+
+/// An employer's payroll in `bank` tokens, paid out of `vault`, a bank account owned by
+/// the vault authority. The employer funds it with plain bank transfers.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Payroll {
+    pub is_initialized: bool,
+    pub employer: Pubkey,
+    pub bank: Pubkey,
+    pub vault: Pubkey,
+    /// Bump seed of the vault authority address.
+    pub bump: u8,
+}
+
+impl Sealed for Payroll {}
+impl IsInitialized for Payroll {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Payroll {
+    const LEN: usize = 98;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 98];
+        let (is_initialized, employer, bank, vault, bump) = array_refs![src, 1, 32, 32, 32, 1];
+        Ok(Payroll {
+            is_initialized: unpack_bool(is_initialized)?,
+            employer: Pubkey::new_from_array(*employer),
+            bank: Pubkey::new_from_array(*bank),
+            vault: Pubkey::new_from_array(*vault),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 98];
+        let (is_initialized, employer, bank, vault, bump) = mut_array_refs![dst, 1, 32, 32, 32, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        employer.copy_from_slice(self.employer.as_ref());
+        bank.copy_from_slice(self.bank.as_ref());
+        vault.copy_from_slice(self.vault.as_ref());
+        bump[0] = self.bump;
+    }
+}
+
+/// Pay of `amount` into `destination` every `period` seconds, next due at `next_pay_ts`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Employee {
+    pub is_initialized: bool,
+    pub payroll: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub period: i64,
+    pub next_pay_ts: i64,
+}
+
+impl Employee {
+    /// Pay periods elapsed and unpaid at `now`. A crank that runs late pays them all.
+    pub fn periods_due(&self, now: i64) -> u64 {
+        if now < self.next_pay_ts {
+            return 0;
+        }
+        ((now - self.next_pay_ts) / self.period) as u64 + 1
+    }
+}
+
+impl Sealed for Employee {}
+impl IsInitialized for Employee {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Employee {
+    const LEN: usize = 89;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 89];
+        let (is_initialized, payroll, destination, amount, period, next_pay_ts) =
+            array_refs![src, 1, 32, 32, 8, 8, 8];
+        Ok(Employee {
+            is_initialized: unpack_bool(is_initialized)?,
+            payroll: Pubkey::new_from_array(*payroll),
+            destination: Pubkey::new_from_array(*destination),
+            amount: u64::from_le_bytes(*amount),
+            period: i64::from_le_bytes(*period),
+            next_pay_ts: i64::from_le_bytes(*next_pay_ts),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 89];
+        let (is_initialized, payroll, destination, amount, period, next_pay_ts) =
+            mut_array_refs![dst, 1, 32, 32, 8, 8, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        payroll.copy_from_slice(self.payroll.as_ref());
+        destination.copy_from_slice(self.destination.as_ref());
+        *amount = self.amount.to_le_bytes();
+        *period = self.period.to_le_bytes();
+        *next_pay_ts = self.next_pay_ts.to_le_bytes();
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let payroll = Payroll {
+            is_initialized: true,
+            employer: Pubkey::new_unique(),
+            bank: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            bump: 252,
+        };
+        let mut packed = vec![0u8; Payroll::LEN];
+        Payroll::pack(payroll, &mut packed).unwrap();
+        assert_eq!(Payroll::unpack(&packed), Ok(payroll));
+
+        let employee = Employee {
+            is_initialized: true,
+            payroll: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            amount: 3_000,
+            period: 14 * 24 * 60 * 60,
+            next_pay_ts: -1,
+        };
+        let mut packed = vec![0u8; Employee::LEN];
+        Employee::pack(employee, &mut packed).unwrap();
+        assert_eq!(Employee::unpack(&packed), Ok(employee));
+        assert_eq!(
+            packed[EMPLOYEE_PAYROLL_OFFSET..EMPLOYEE_PAYROLL_OFFSET + 32],
+            employee.payroll.to_bytes()
+        );
+
+        packed[0] = 2;
+        assert_eq!(
+            Employee::unpack_unchecked(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_periods_due() {
+        let employee = Employee {
+            period: 100,
+            next_pay_ts: 1_000,
+            ..Employee::default()
+        };
+        let due: Vec<u64> = [0, 999, 1_000, 1_099, 1_100, 1_350]
+            .iter()
+            .map(|now| employee.periods_due(*now))
+            .collect();
+        assert_eq!(due, vec![0, 0, 1, 1, 2, 4]);
+    }
+}
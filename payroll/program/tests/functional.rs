@@ -0,0 +1,343 @@
+use solana_bank::{instruction::mint_to, state::Account, test_utils::program_test::Env};
+use solana_payroll::{
+    error::PayrollError,
+    instruction::{
+        add_employee, create_payroll, employee_address, remove_employee, run_payroll,
+        vault_authority,
+    },
+    processor::Processor,
+    state::{Employee, Payroll},
+};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// Time the fixture's employees are hired at.
+const OPEN: i64 = 1_000;
+
+/// A payroll holding 10_000 tokens, paying alice 100 every 100 seconds from `OPEN + 100`
+/// and bob 250 every 1_000 seconds from `OPEN + 1_000`.
+struct Fixture {
+    env: Env,
+    bank: Pubkey,
+    employer: Keypair,
+    payroll: Pubkey,
+    vault: Pubkey,
+    alice: Pubkey,
+    bob: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_payroll", processor!(Processor::process)).await;
+        env.set_time(OPEN);
+        let bank = env.create_bank().await;
+        let employer = Keypair::new();
+        env.fund(&employer.pubkey(), 10_000_000).await;
+        let (payroll, vault) = (Keypair::new(), Keypair::new());
+        let payroll_program_id = env.program_id;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&payroll, Payroll::LEN, &payroll_program_id)
+            .await;
+        env.create_account(&vault, Account::LEN, &bank_program_id)
+            .await;
+        let instructions = [
+            create_payroll(
+                &payroll_program_id,
+                &bank_program_id,
+                &employer.pubkey(),
+                &payroll.pubkey(),
+                &vault.pubkey(),
+                &bank,
+            )
+            .unwrap(),
+            mint_to(
+                &bank_program_id,
+                &bank,
+                &vault.pubkey(),
+                &env.context.payer.pubkey(),
+                10_000,
+            )
+            .unwrap(),
+        ];
+        env.process(&instructions, &[&employer]).await.unwrap();
+
+        let alice = env.create_bank_account(&bank, &Keypair::new(), 0).await;
+        let bob = env.create_bank_account(&bank, &Keypair::new(), 0).await;
+        let mut fixture = Fixture {
+            env,
+            bank,
+            employer,
+            payroll: payroll.pubkey(),
+            vault: vault.pubkey(),
+            alice,
+            bob,
+        };
+        fixture.add(&alice, 100, 100, OPEN + 100).await.unwrap();
+        fixture.add(&bob, 250, 1_000, OPEN + 1_000).await.unwrap();
+        fixture
+    }
+
+    async fn add(
+        &mut self,
+        destination: &Pubkey,
+        amount: u64,
+        period: i64,
+        first_pay_ts: i64,
+    ) -> Result<(), TransactionError> {
+        let instruction = add_employee(
+            &self.env.program_id,
+            &self.employer.pubkey(),
+            &self.payroll,
+            destination,
+            amount,
+            period,
+            first_pay_ts,
+        )
+        .unwrap();
+        let employer = Keypair::from_bytes(&self.employer.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&employer]).await
+    }
+
+    async fn run(&mut self, destinations: &[Pubkey]) -> Result<(), TransactionError> {
+        let instruction = run_payroll(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &self.payroll,
+            &self.vault,
+            destinations,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[]).await
+    }
+
+    async fn employee(&mut self, destination: &Pubkey) -> Option<Employee> {
+        let (employee, _) = employee_address(&self.env.program_id, &self.payroll, destination);
+        self.env
+            .context
+            .banks_client
+            .get_account(employee)
+            .await
+            .unwrap()
+            .map(|account| Employee::unpack(&account.data).unwrap())
+    }
+
+    async fn amounts(&mut self) -> (u64, u64, u64) {
+        let (vault, alice, bob) = (self.vault, self.alice, self.bob);
+        (
+            self.env.amount(&vault).await,
+            self.env.amount(&alice).await,
+            self.env.amount(&bob).await,
+        )
+    }
+}
+
+fn custom(error: PayrollError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_payroll() {
+    let mut fixture = Fixture::new().await;
+    let account = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(fixture.payroll)
+        .await
+        .unwrap()
+        .unwrap();
+    let payroll = Payroll::unpack(&account.data).unwrap();
+    assert_eq!(payroll.employer, fixture.employer.pubkey());
+    assert_eq!(payroll.bank, fixture.bank);
+    assert_eq!(payroll.vault, fixture.vault);
+    let (authority, _) = vault_authority(&fixture.env.program_id, &fixture.payroll);
+    let vault = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(fixture.vault)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Account::unpack(&vault.data).unwrap().owner, authority);
+}
+
+#[tokio::test]
+async fn test_add_employee() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.alice;
+    assert_eq!(
+        fixture.employee(&alice).await,
+        Some(Employee {
+            is_initialized: true,
+            payroll: fixture.payroll,
+            destination: alice,
+            amount: 100,
+            period: 100,
+            next_pay_ts: OPEN + 100,
+        })
+    );
+    assert_eq!(
+        fixture.add(&alice, 200, 100, OPEN).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::AccountAlreadyInitialized
+        ))
+    );
+
+    let bank = fixture.bank;
+    let carol = fixture
+        .env
+        .create_bank_account(&bank, &Keypair::new(), 0)
+        .await;
+    assert_eq!(
+        fixture.add(&carol, 0, 100, OPEN).await,
+        custom(PayrollError::InvalidSchedule)
+    );
+    assert_eq!(
+        fixture.add(&carol, 100, 0, OPEN).await,
+        custom(PayrollError::InvalidSchedule)
+    );
+    let other_bank = fixture.env.create_bank().await;
+    let dave = fixture
+        .env
+        .create_bank_account(&other_bank, &Keypair::new(), 0)
+        .await;
+    assert_eq!(
+        fixture.add(&dave, 100, 100, OPEN).await,
+        custom(PayrollError::InvalidDestination)
+    );
+
+    let impostor = Keypair::new();
+    fixture.env.fund(&impostor.pubkey(), 10_000_000).await;
+    let instruction = add_employee(
+        &fixture.env.program_id,
+        &impostor.pubkey(),
+        &fixture.payroll,
+        &carol,
+        100,
+        100,
+        OPEN,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&impostor]).await,
+        custom(PayrollError::NotEmployer)
+    );
+}
+
+#[tokio::test]
+async fn test_run_payroll() {
+    let mut fixture = Fixture::new().await;
+    let (alice, bob) = (fixture.alice, fixture.bob);
+
+    // No one is due yet, so running pays nothing.
+    fixture.env.set_time(OPEN + 50);
+    fixture.run(&[bob]).await.unwrap();
+    assert_eq!(fixture.amounts().await, (10_000, 0, 0));
+
+    fixture.env.set_time(OPEN + 100);
+    fixture.run(&[alice]).await.unwrap();
+    assert_eq!(fixture.amounts().await, (9_900, 100, 0));
+    assert_eq!(
+        fixture.employee(&alice).await.unwrap().next_pay_ts,
+        OPEN + 200
+    );
+
+    // A late run pays every elapsed period at once, and only those.
+    fixture.env.set_time(OPEN + 350);
+    fixture.run(&[bob, alice]).await.unwrap();
+    assert_eq!(fixture.amounts().await, (9_700, 300, 0));
+    assert_eq!(
+        fixture.employee(&alice).await.unwrap().next_pay_ts,
+        OPEN + 400
+    );
+
+    fixture.env.set_time(OPEN + 1_000);
+    fixture.run(&[alice, bob]).await.unwrap();
+    assert_eq!(fixture.amounts().await, (8_750, 1_000, 250));
+    assert_eq!(
+        fixture.employee(&bob).await.unwrap().next_pay_ts,
+        OPEN + 2_000
+    );
+
+    // An employee listed with someone else's account is rejected.
+    let mut instruction = run_payroll(
+        &fixture.env.program_id,
+        &fixture.env.bank_program_id,
+        &fixture.bank,
+        &fixture.payroll,
+        &fixture.vault,
+        &[alice],
+    )
+    .unwrap();
+    instruction.accounts[6].pubkey = bob;
+    assert_eq!(
+        fixture.env.process(&[instruction], &[]).await,
+        custom(PayrollError::InvalidDestination)
+    );
+}
+
+#[tokio::test]
+async fn test_remove_employee() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.alice;
+    let instruction = remove_employee(
+        &fixture.env.program_id,
+        &fixture.employer.pubkey(),
+        &fixture.payroll,
+        &alice,
+    )
+    .unwrap();
+    let employer = Keypair::from_bytes(&fixture.employer.to_bytes()).unwrap();
+    fixture
+        .env
+        .process(&[instruction], &[&employer])
+        .await
+        .unwrap();
+    assert_eq!(fixture.employee(&alice).await, None);
+
+    fixture.env.set_time(OPEN + 100);
+    assert_eq!(
+        fixture.run(&[alice]).await,
+        custom(PayrollError::InvalidEmployee)
+    );
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.alice;
+    fixture.env.set_time(OPEN + 100);
+
+    // Through a program posing as the bank, anyone could mark alice's period paid
+    // without paying her.
+    let instruction = run_payroll(
+        &fixture.env.program_id,
+        &fixture.env.fake_bank_program_id,
+        &fixture.bank,
+        &fixture.payroll,
+        &fixture.vault,
+        &[alice],
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[]).await,
+        custom(PayrollError::InvalidVault)
+    );
+    assert_eq!(
+        fixture.employee(&alice).await.unwrap().next_pay_ts,
+        OPEN + 100
+    );
+
+    fixture.run(&[alice]).await.unwrap();
+    assert_eq!(fixture.amounts().await, (9_900, 100, 0));
+}
@@ -3,41 +3,8 @@ use solana_program::{
     account_info::next_account_info, account_info::AccountInfo, entrypoint::ProgramResult, msg,
     program_error::ProgramError, pubkey::Pubkey,
 };
-#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
-pub enum BankInstruction {
-    InitAccount { amount: u64, name: String },
 
-    Transfer { amount: u64 },
-}
-
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
-pub struct BankAccount {
-    pub amount: u64,
-    pub authority: Pubkey,
-    pub name: String,
-}
-
-impl BankAccount {
-    pub fn new(amount: u64, authority: Pubkey, name: String) -> BankAccount {
-        BankAccount {
-            amount,
-            authority,
-            name,
-        }
-    }
-
-    pub fn sub_with<'a>(&'a mut self, sub: u64) -> Result<&'a BankAccount, String> {
-        if self.amount < sub {
-            return Err("amount is insufficient".to_string());
-        }
-        self.amount -= sub;
-        return Ok(self);
-    }
-    pub fn add_with<'a>(&'a mut self, add: u64) -> Result<&'a BankAccount, String> {
-        self.amount += add;
-        return Ok(self);
-    }
-}
+pub use bank_interface::{BankAccount, BankInstruction};
 
 pub fn process_bank_instruction(
     program_id: &Pubkey,
@@ -1,28 +1,253 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
     msg,
+    program::invoke,
     program_error::ProgramError,
     pubkey::Pubkey,
+    system_instruction, system_program,
+    sysvar::Sysvar,
 };
+use std::slice::Iter;
 
 /// Define the type of state stored in accounts
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Clone, Debug, Default, PartialEq)]
 pub struct GreetingAccount {
     /// number of greetings
     pub counter: u32,
+    /// Slot of the latest greeting.
+    pub last_greeted_slot: u64,
+    /// Alone may reset the counter or change the message. Set by `Initialize`; nobody may
+    /// before it.
+    pub authority: Pubkey,
+    /// At most `MAX_MESSAGE_LEN` bytes.
+    pub message: String,
 }
 
+/// Longest message a greeting account holds, in bytes.
+pub const MAX_MESSAGE_LEN: usize = 64;
+/// Size of a `GreetingAccount` with room for the longest message.
+pub const GREETING_LEN: usize = 4 + 8 + 32 + 4 + MAX_MESSAGE_LEN;
+/// Size of a `GreetingFee`.
+pub const GREETING_FEE_LEN: usize = 32 + 8;
+/// Size of an account with room for a fee after its `GreetingAccount`.
+pub const GREETING_WITH_FEE_LEN: usize = GREETING_LEN + GREETING_FEE_LEN;
+
+impl GreetingAccount {
+    /// The greeting account at the start of an account's data, whatever follows it.
+    pub fn read(data: &[u8]) -> Result<GreetingAccount, ProgramError> {
+        match data.get(..GREETING_LEN) {
+            Some(mut account) => Ok(GreetingAccount::deserialize(&mut account)?),
+            None => Err(ProgramError::AccountDataTooSmall),
+        }
+    }
+
+    /// Stores the account at the start of `data`, leaving any fee after it be.
+    pub fn write(&self, data: &mut [u8]) -> ProgramResult {
+        if self.message.len() > MAX_MESSAGE_LEN {
+            msg!("Messages hold at most {} bytes", MAX_MESSAGE_LEN);
+            return Err(ProgramError::InvalidArgument);
+        }
+        match data.get_mut(..GREETING_LEN) {
+            Some(mut account) => Ok(self.serialize(&mut account)?),
+            None => Err(ProgramError::AccountDataTooSmall),
+        }
+    }
+}
+
+/// What each greeting of an account costs, kept right after its `GreetingAccount` in
+/// accounts of `GREETING_WITH_FEE_LEN` bytes. Accounts without the room, and accounts
+/// whose fee was never set, greet for free.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Clone, Debug, Default, PartialEq)]
+pub struct GreetingFee {
+    /// Receives the fees, and alone may change them. Set by `Initialize`.
+    pub authority: Pubkey,
+    pub lamports: u64,
+}
+
+impl GreetingFee {
+    /// The fee stored in a greeting account's data, if it has room for one.
+    pub fn read(data: &[u8]) -> Result<Option<GreetingFee>, ProgramError> {
+        match data.get(GREETING_LEN..GREETING_WITH_FEE_LEN) {
+            Some(fee) => Ok(Some(GreetingFee::try_from_slice(fee)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Empty instruction data greets once, as every instruction did before the program had
+/// any others.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Clone, Debug, PartialEq)]
+pub enum GreetingInstruction {
+    /// Counts `times` greetings and records the slot. If the account charges a fee, the
+    /// caller pays it `times` over to the fee's authority.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The greeted account.
+    ///   When the account charges a fee:
+    ///   1. `[writable, signer]` The caller.
+    ///   2. `[writable]` The fee's authority.
+    ///   3. `[]` The system program.
+    Greet { times: u32 },
+
+    /// Sets what each greeting costs; 0 makes greetings free again. Only the fee's
+    /// authority may, so the account must be initialized first.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The greeted account, of `GREETING_WITH_FEE_LEN` bytes.
+    ///   1. `[signer]` The fee's authority.
+    SetFee { lamports: u64 },
+
+    /// Zeroes the counter and the slot of the latest greeting, keeping the message.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The greeted account.
+    ///   1. `[signer]` The account's authority.
+    Reset,
+
+    /// Sets the account's message, of at most `MAX_MESSAGE_LEN` bytes. Only the account's
+    /// authority may, so the account must be initialized first.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The greeted account.
+    ///   1. `[signer]` The account's authority.
+    SetGreeting { message: String },
+
+    /// Makes the account's creator its authority and, if it has room for one, its fee's.
+    /// Only the creator may: either the account signs, having been created from a keypair
+    /// the creator holds, or its address derives from the creator with `seed`, as
+    /// `create_account_with_seed` made it.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The greeted account, a signer unless derived with `seed`.
+    ///   1. `[signer]` The creator.
+    Initialize { seed: String },
+}
+
+pub fn greet(program_id: &Pubkey, greeted: &Pubkey, times: u32) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &GreetingInstruction::Greet { times },
+        vec![AccountMeta::new(*greeted, false)],
+    )
+}
+
+/// `greet` of an account charging a fee, which `payer` pays to `authority`.
+pub fn greet_with_fee(
+    program_id: &Pubkey,
+    greeted: &Pubkey,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    times: u32,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &GreetingInstruction::Greet { times },
+        vec![
+            AccountMeta::new(*greeted, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*authority, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn set_fee(
+    program_id: &Pubkey,
+    greeted: &Pubkey,
+    authority: &Pubkey,
+    lamports: u64,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &GreetingInstruction::SetFee { lamports },
+        vec![
+            AccountMeta::new(*greeted, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+pub fn reset(program_id: &Pubkey, greeted: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &GreetingInstruction::Reset,
+        vec![
+            AccountMeta::new(*greeted, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+pub fn set_greeting(
+    program_id: &Pubkey,
+    greeted: &Pubkey,
+    authority: &Pubkey,
+    message: String,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &GreetingInstruction::SetGreeting { message },
+        vec![
+            AccountMeta::new(*greeted, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// `Initialize` of an account created from a keypair, which signs; `authority` becomes
+/// its authority.
+pub fn initialize(program_id: &Pubkey, greeted: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &GreetingInstruction::Initialize {
+            seed: String::new(),
+        },
+        vec![
+            AccountMeta::new(*greeted, true),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// `Initialize` of an account `create_account_with_seed` derived from `base` and `seed`;
+/// `base` becomes its authority.
+pub fn initialize_with_seed(
+    program_id: &Pubkey,
+    greeted: &Pubkey,
+    base: &Pubkey,
+    seed: &str,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &GreetingInstruction::Initialize {
+            seed: seed.to_string(),
+        },
+        vec![
+            AccountMeta::new(*greeted, false),
+            AccountMeta::new_readonly(*base, true),
+        ],
+    )
+}
 
 // Program entrypoint's implementation
 pub fn process_greeting_account(
     program_id: &Pubkey, // Public key of the account the hello world program was loaded into
     accounts: &[AccountInfo], // The account to say hello to
-    _instruction_data: &[u8], // Ignored, all helloworld instructions are hellos
+    instruction_data: &[u8],
 ) -> ProgramResult {
     msg!("Hello World Rust program entrypoint");
 
+    let instruction = if instruction_data.is_empty() {
+        GreetingInstruction::Greet { times: 1 }
+    } else {
+        GreetingInstruction::try_from_slice(instruction_data)
+            .map_err(|_| ProgramError::InvalidInstructionData)?
+    };
+
     // Iterating accounts is safer then indexing
     let accounts_iter = &mut accounts.iter();
 
@@ -35,29 +260,206 @@ pub fn process_greeting_account(
         return Err(ProgramError::IncorrectProgramId);
     }
 
-    // Increment and store the number of times the account has been greeted
-    let mut greeting_account = GreetingAccount::try_from_slice(&account.data.borrow())?;
-    greeting_account.counter += 1;
-    greeting_account.serialize(&mut &mut account.data.borrow_mut()[..])?;
+    match instruction {
+        GreetingInstruction::Greet { times } => process_greet(account, accounts_iter, times),
+        GreetingInstruction::SetFee { lamports } => {
+            process_set_fee(account, accounts_iter, lamports)
+        }
+        GreetingInstruction::Reset => process_reset(account, accounts_iter),
+        GreetingInstruction::SetGreeting { message } => {
+            process_set_greeting(account, accounts_iter, message)
+        }
+        GreetingInstruction::Initialize { seed } => {
+            process_initialize(program_id, account, accounts_iter, &seed)
+        }
+    }
+}
+
+fn process_greet(
+    account: &AccountInfo,
+    accounts_iter: &mut Iter<AccountInfo>,
+    times: u32,
+) -> ProgramResult {
+    let fee = GreetingFee::read(&account.data.borrow())?;
+    if let Some(fee) = fee.filter(|fee| fee.lamports > 0) {
+        let payer = next_account_info(accounts_iter)?;
+        let authority = next_account_info(accounts_iter)?;
+        let system_program_info = next_account_info(accounts_iter)?;
+        if !payer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if *authority.key != fee.authority {
+            msg!("Fee goes to {}", fee.authority);
+            return Err(ProgramError::InvalidArgument);
+        }
+        let lamports = fee
+            .lamports
+            .checked_mul(u64::from(times))
+            .ok_or(ProgramError::InvalidArgument)?;
+        invoke(
+            &system_instruction::transfer(payer.key, authority.key, lamports),
+            &[
+                payer.clone(),
+                authority.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+        msg!("Paid {} lamport(s) to greet", lamports);
+    }
+
+    // Increment and store the number of times the account has been greeted.
+    let mut data = account.data.borrow_mut();
+    let mut greeting_account = GreetingAccount::read(&data)?;
+    greeting_account.counter = greeting_account
+        .counter
+        .checked_add(times)
+        .ok_or(ProgramError::InvalidArgument)?;
+    greeting_account.last_greeted_slot = Clock::get()?.slot;
+    greeting_account.write(&mut data)?;
 
     msg!("Greeted {} time(s)!", greeting_account.counter);
 
     Ok(())
 }
 
+fn process_set_fee(
+    account: &AccountInfo,
+    accounts_iter: &mut Iter<AccountInfo>,
+    lamports: u64,
+) -> ProgramResult {
+    let authority = next_account_info(accounts_iter)?;
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut data = account.data.borrow_mut();
+    let fee = match GreetingFee::read(&data)? {
+        Some(fee) => fee,
+        None => {
+            msg!("Greeted account has no room for a fee");
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+    };
+    // An uninitialized account's fee authority is the default key, which nobody signs for.
+    if fee.authority != *authority.key {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let fee = GreetingFee {
+        authority: fee.authority,
+        lamports,
+    };
+    fee.serialize(&mut &mut data[GREETING_LEN..])?;
+    msg!("Greetings cost {} lamport(s)", lamports);
+    Ok(())
+}
+
+fn process_reset(account: &AccountInfo, accounts_iter: &mut Iter<AccountInfo>) -> ProgramResult {
+    let authority = next_account_info(accounts_iter)?;
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut data = account.data.borrow_mut();
+    let mut greeting_account = GreetingAccount::read(&data)?;
+    // An uninitialized account's authority is the default key, which nobody signs for.
+    if greeting_account.authority != *authority.key {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    greeting_account.counter = 0;
+    greeting_account.last_greeted_slot = 0;
+    greeting_account.write(&mut data)?;
+    msg!("Greetings reset");
+    Ok(())
+}
+
+fn process_set_greeting(
+    account: &AccountInfo,
+    accounts_iter: &mut Iter<AccountInfo>,
+    message: String,
+) -> ProgramResult {
+    let authority = next_account_info(accounts_iter)?;
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut data = account.data.borrow_mut();
+    let mut greeting_account = GreetingAccount::read(&data)?;
+    // As in `process_reset`, an uninitialized account's authority signs for nobody.
+    if greeting_account.authority != *authority.key {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    greeting_account.message = message;
+    greeting_account.write(&mut data)?;
+    msg!("Greeting set to {:?}", greeting_account.message);
+    Ok(())
+}
+
+fn process_initialize(
+    program_id: &Pubkey,
+    account: &AccountInfo,
+    accounts_iter: &mut Iter<AccountInfo>,
+    seed: &str,
+) -> ProgramResult {
+    let authority = next_account_info(accounts_iter)?;
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !account.is_signer {
+        let derived = Pubkey::create_with_seed(authority.key, seed, program_id)
+            .map_err(|_| ProgramError::InvalidSeeds)?;
+        if derived != *account.key {
+            msg!("Greeted account isn't derived from the creator with the seed");
+            return Err(ProgramError::InvalidSeeds);
+        }
+    }
+    let mut data = account.data.borrow_mut();
+    let mut greeting_account = GreetingAccount::read(&data)?;
+    if greeting_account.authority != Pubkey::default() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    greeting_account.authority = *authority.key;
+    greeting_account.write(&mut data)?;
+    if let Some(fee) = GreetingFee::read(&data)? {
+        let fee = GreetingFee {
+            authority: *authority.key,
+            ..fee
+        };
+        fee.serialize(&mut &mut data[GREETING_LEN..])?;
+    }
+    msg!("Greeting account belongs to {}", authority.key);
+    Ok(())
+}
+
 // Sanity tests
 #[cfg(test)]
 mod test {
     use super::*;
-    use solana_program::clock::Epoch;
-    use std::mem;
+    use solana_program::{clock::Epoch, entrypoint::SUCCESS, program_stubs};
+
+    const SLOT: u64 = 42;
+
+    /// Answers `Clock::get` off-chain, at `SLOT`.
+    struct ClockStubs;
+
+    impl program_stubs::SyscallStubs for ClockStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            let clock = Clock {
+                slot: SLOT,
+                ..Clock::default()
+            };
+            unsafe { *(var_addr as *mut Clock) = clock };
+            SUCCESS
+        }
+    }
 
     #[test]
     fn test_sanity() {
+        program_stubs::set_syscall_stubs(Box::new(ClockStubs));
         let program_id = Pubkey::default();
         let key = Pubkey::default();
         let mut lamports = 0;
-        let mut data = vec![0; mem::size_of::<u32>()];
+        let mut data = vec![0; GREETING_LEN];
         let owner = Pubkey::default();
         let account = AccountInfo::new(
             &key,
@@ -74,24 +476,44 @@ mod test {
         let accounts = vec![account];
 
         assert_eq!(
-            GreetingAccount::try_from_slice(&accounts[0].data.borrow())
+            GreetingAccount::read(&accounts[0].data.borrow())
                 .unwrap()
                 .counter,
             0
         );
         process_greeting_account(&program_id, &accounts, &instruction_data).unwrap();
         assert_eq!(
-            GreetingAccount::try_from_slice(&accounts[0].data.borrow())
+            GreetingAccount::read(&accounts[0].data.borrow())
                 .unwrap()
                 .counter,
             1
         );
         process_greeting_account(&program_id, &accounts, &instruction_data).unwrap();
         assert_eq!(
-            GreetingAccount::try_from_slice(&accounts[0].data.borrow())
-                .unwrap()
-                .counter,
-            2
+            GreetingAccount::read(&accounts[0].data.borrow()).unwrap(),
+            GreetingAccount {
+                counter: 2,
+                last_greeted_slot: SLOT,
+                ..GreetingAccount::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_write_checks_message_len() {
+        let mut data = vec![0; GREETING_LEN];
+        let mut account = GreetingAccount {
+            message: "a".repeat(MAX_MESSAGE_LEN),
+            ..GreetingAccount::default()
+        };
+        account.write(&mut data).unwrap();
+        assert_eq!(GreetingAccount::read(&data), Ok(account.clone()));
+
+        account.message.push('a');
+        assert_eq!(account.write(&mut data), Err(ProgramError::InvalidArgument));
+        assert_eq!(
+            GreetingAccount::read(&data[..GREETING_LEN - 1]),
+            Err(ProgramError::AccountDataTooSmall)
         );
     }
 }
@@ -1,10 +1,13 @@
 pub mod greeting_account;
 pub mod bank_account;
 
+#[cfg(not(feature = "no-entrypoint"))]
 use greeting_account::process_greeting_account;
 use bank_account::process_bank_instruction;
+#[cfg(not(feature = "no-entrypoint"))]
 use solana_program::entrypoint;
 
 // Declare and export the program's entrypoint
 // entrypoint!(process_greeting_account);
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_greeting_account);
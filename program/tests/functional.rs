@@ -0,0 +1,480 @@
+use program::greeting_account::{
+    greet, greet_with_fee, initialize, initialize_with_seed, process_greeting_account, reset,
+    set_fee, set_greeting, GreetingAccount, GreetingFee, GREETING_LEN, GREETING_WITH_FEE_LEN,
+    MAX_MESSAGE_LEN,
+};
+use solana_program::{
+    clock::Clock,
+    hash::Hash,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+struct Env {
+    banks_client: BanksClient,
+    payer: Keypair,
+    recent_blockhash: Hash,
+    program_id: Pubkey,
+}
+
+impl Env {
+    async fn start() -> Env {
+        let program_id = Pubkey::new_unique();
+        let program_test =
+            ProgramTest::new("program", program_id, processor!(process_greeting_account));
+        let (banks_client, payer, recent_blockhash) = program_test.start().await;
+        Env {
+            banks_client,
+            payer,
+            recent_blockhash,
+            program_id,
+        }
+    }
+
+    async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), TransactionError> {
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            self.recent_blockhash,
+        );
+        self.banks_client
+            .process_transaction(transaction)
+            .await
+            .map_err(|e| e.unwrap())
+    }
+
+    /// Creates a rent-exempt account of `space` bytes owned by `owner`.
+    async fn create_account(&mut self, account: &Keypair, space: usize, owner: &Pubkey) {
+        let rent = self.banks_client.get_rent().await.unwrap();
+        let instruction = system_instruction::create_account(
+            &self.payer.pubkey(),
+            &account.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            owner,
+        );
+        self.process(&[instruction], &[account]).await.unwrap();
+    }
+
+    /// Creates a greeting account owned by the program.
+    async fn create_greeting_account(&mut self, account: &Keypair) {
+        let program_id = self.program_id;
+        self.create_account(account, GREETING_LEN, &program_id)
+            .await;
+    }
+
+    fn greet(&self, account: &Pubkey) -> Instruction {
+        Instruction::new_with_bincode(
+            self.program_id,
+            &(),
+            vec![AccountMeta::new(*account, false)],
+        )
+    }
+
+    async fn greeting_account(&mut self, account: &Pubkey) -> GreetingAccount {
+        let account = self
+            .banks_client
+            .get_account(*account)
+            .await
+            .unwrap()
+            .unwrap();
+        GreetingAccount::read(&account.data).unwrap()
+    }
+
+    async fn counter(&mut self, account: &Pubkey) -> u32 {
+        self.greeting_account(account).await.counter
+    }
+}
+
+#[tokio::test]
+async fn test_create_greeting_account() {
+    let mut env = Env::start().await;
+    let greeted = Keypair::new();
+    env.create_greeting_account(&greeted).await;
+
+    let account = env
+        .banks_client
+        .get_account(greeted.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, env.program_id);
+    assert_eq!(account.data.len(), GREETING_LEN);
+    assert_eq!(env.counter(&greeted.pubkey()).await, 0);
+}
+
+#[tokio::test]
+async fn test_greeting_increments() {
+    let mut env = Env::start().await;
+    let greeted = Keypair::new();
+    env.create_greeting_account(&greeted).await;
+
+    env.process(&[env.greet(&greeted.pubkey())], &[])
+        .await
+        .unwrap();
+    assert_eq!(env.counter(&greeted.pubkey()).await, 1);
+
+    // Each instruction of a transaction counts on its own.
+    let greet = env.greet(&greeted.pubkey());
+    env.process(&[greet.clone(), greet], &[]).await.unwrap();
+    assert_eq!(env.counter(&greeted.pubkey()).await, 3);
+}
+
+#[tokio::test]
+async fn test_greeting_rejects_wrong_owner() {
+    let mut env = Env::start().await;
+    let not_owned = Keypair::new();
+    env.create_account(&not_owned, GREETING_LEN, &Pubkey::new_unique())
+        .await;
+
+    let greet = env.greet(&not_owned.pubkey());
+    assert_eq!(
+        env.process(&[greet], &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IncorrectProgramId
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_greeting_rejects_undersized_account() {
+    let mut env = Env::start().await;
+    let program_id = env.program_id;
+    let undersized = Keypair::new();
+    env.create_account(&undersized, GREETING_LEN - 1, &program_id)
+        .await;
+
+    // An account without room for the longest message isn't greeted, and the failed
+    // instruction must leave the data untouched.
+    let greet = env.greet(&undersized.pubkey());
+    assert_eq!(
+        env.process(&[greet], &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::AccountDataTooSmall
+        ))
+    );
+    let account = env
+        .banks_client
+        .get_account(undersized.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.data, vec![0; GREETING_LEN - 1]);
+}
+
+#[tokio::test]
+async fn test_greeting_fee() {
+    let mut env = Env::start().await;
+    let program_id = env.program_id;
+    let (greeted, authority) = (Keypair::new(), Keypair::new());
+    let fee = 1_000_000;
+
+    // The account's creator makes the authority its own and sets the fee.
+    let rent = env.banks_client.get_rent().await.unwrap();
+    let create = system_instruction::create_account(
+        &env.payer.pubkey(),
+        &greeted.pubkey(),
+        rent.minimum_balance(GREETING_WITH_FEE_LEN),
+        GREETING_WITH_FEE_LEN as u64,
+        &program_id,
+    );
+    let init = initialize(&program_id, &greeted.pubkey(), &authority.pubkey());
+    let set = set_fee(&program_id, &greeted.pubkey(), &authority.pubkey(), fee);
+    env.process(&[create, init, set], &[&greeted, &authority])
+        .await
+        .unwrap();
+
+    // A greeting that doesn't pay, or pays someone else, doesn't count.
+    let unpaid = env.greet(&greeted.pubkey());
+    assert_eq!(
+        env.process(&[unpaid], &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::NotEnoughAccountKeys
+        ))
+    );
+    let payer = env.payer.pubkey();
+    let misdirected = greet_with_fee(&program_id, &greeted.pubkey(), &payer, &payer, 1);
+    assert_eq!(
+        env.process(&[misdirected], &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidArgument
+        ))
+    );
+    assert_eq!(env.counter(&greeted.pubkey()).await, 0);
+
+    let paid = greet_with_fee(
+        &program_id,
+        &greeted.pubkey(),
+        &payer,
+        &authority.pubkey(),
+        1,
+    );
+    env.process(&[paid], &[]).await.unwrap();
+    assert_eq!(env.counter(&greeted.pubkey()).await, 1);
+    assert_eq!(
+        env.banks_client
+            .get_balance(authority.pubkey())
+            .await
+            .unwrap(),
+        fee
+    );
+
+    // Only the authority changes the fee, and without one greetings are free again.
+    let impostor = Keypair::new();
+    let hijack = set_fee(&program_id, &greeted.pubkey(), &impostor.pubkey(), 0);
+    assert_eq!(
+        env.process(&[hijack], &[&impostor]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    let free = set_fee(&program_id, &greeted.pubkey(), &authority.pubkey(), 0);
+    let greet = env.greet(&greeted.pubkey());
+    env.process(&[free, greet.clone(), greet], &[&authority])
+        .await
+        .unwrap();
+    assert_eq!(env.counter(&greeted.pubkey()).await, 3);
+}
+
+#[tokio::test]
+async fn test_greeting_fee_needs_room() {
+    let mut env = Env::start().await;
+    let program_id = env.program_id;
+    let (greeted, authority) = (Keypair::new(), Keypair::new());
+    env.create_greeting_account(&greeted).await;
+
+    let set = set_fee(&program_id, &greeted.pubkey(), &authority.pubkey(), 1);
+    assert_eq!(
+        env.process(&[set], &[&authority]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::AccountDataTooSmall
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_binds_creator() {
+    let mut env = Env::start().await;
+    let program_id = env.program_id;
+    let (base, impostor) = (Keypair::new(), Keypair::new());
+    let seed = "greeting";
+    let greeted = Pubkey::create_with_seed(&base.pubkey(), seed, &program_id).unwrap();
+    let rent = env.banks_client.get_rent().await.unwrap();
+    let create = system_instruction::create_account_with_seed(
+        &env.payer.pubkey(),
+        &greeted,
+        &base.pubkey(),
+        seed,
+        rent.minimum_balance(GREETING_WITH_FEE_LEN),
+        GREETING_WITH_FEE_LEN as u64,
+        &program_id,
+    );
+    env.process(&[create], &[&base]).await.unwrap();
+
+    // Nobody sets the fee of an uninitialized account, or initializes another's.
+    let hijack = set_fee(&program_id, &greeted, &impostor.pubkey(), 1);
+    assert_eq!(
+        env.process(&[hijack], &[&impostor]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    let hijack = initialize_with_seed(&program_id, &greeted, &impostor.pubkey(), seed);
+    assert_eq!(
+        env.process(&[hijack], &[&impostor]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidSeeds
+        ))
+    );
+
+    let init = initialize_with_seed(&program_id, &greeted, &base.pubkey(), seed);
+    env.process(&[init], &[&base]).await.unwrap();
+    let account = env
+        .banks_client
+        .get_account(greeted)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        GreetingAccount::read(&account.data).unwrap().authority,
+        base.pubkey()
+    );
+    assert_eq!(
+        GreetingFee::read(&account.data).unwrap(),
+        Some(GreetingFee {
+            authority: base.pubkey(),
+            lamports: 0,
+        })
+    );
+    let again = initialize_with_seed(&program_id, &greeted, &base.pubkey(), seed);
+    assert_eq!(
+        env.process(&[again], &[&base]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::AccountAlreadyInitialized
+        ))
+    );
+    let set = set_fee(&program_id, &greeted, &base.pubkey(), 1);
+    env.process(&[set], &[&base]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_greet_times() {
+    let mut env = Env::start().await;
+    let program_id = env.program_id;
+    let greeted = Keypair::new();
+    env.create_greeting_account(&greeted).await;
+
+    env.process(&[greet(&program_id, &greeted.pubkey(), 3)], &[])
+        .await
+        .unwrap();
+    let slot = env.banks_client.get_sysvar::<Clock>().await.unwrap().slot;
+    let account = env.greeting_account(&greeted.pubkey()).await;
+    assert_eq!(account.counter, 3);
+    assert_eq!(account.last_greeted_slot, slot);
+
+    let overflow = greet(&program_id, &greeted.pubkey(), u32::MAX);
+    assert_eq!(
+        env.process(&[overflow], &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidArgument
+        ))
+    );
+    assert_eq!(env.counter(&greeted.pubkey()).await, 3);
+}
+
+#[tokio::test]
+async fn test_greet_times_pays_each_fee() {
+    let mut env = Env::start().await;
+    let program_id = env.program_id;
+    let (greeted, authority) = (Keypair::new(), Keypair::new());
+    let fee = 1_000_000;
+    env.create_account(&greeted, GREETING_WITH_FEE_LEN, &program_id)
+        .await;
+    let init = initialize(&program_id, &greeted.pubkey(), &authority.pubkey());
+    let set = set_fee(&program_id, &greeted.pubkey(), &authority.pubkey(), fee);
+    env.process(&[init, set], &[&greeted, &authority])
+        .await
+        .unwrap();
+
+    let payer = env.payer.pubkey();
+    let paid = greet_with_fee(
+        &program_id,
+        &greeted.pubkey(),
+        &payer,
+        &authority.pubkey(),
+        3,
+    );
+    env.process(&[paid], &[]).await.unwrap();
+    assert_eq!(env.counter(&greeted.pubkey()).await, 3);
+    assert_eq!(
+        env.banks_client
+            .get_balance(authority.pubkey())
+            .await
+            .unwrap(),
+        3 * fee
+    );
+}
+
+#[tokio::test]
+async fn test_set_greeting_and_reset() {
+    let mut env = Env::start().await;
+    let program_id = env.program_id;
+    let (greeted, authority, impostor) = (Keypair::new(), Keypair::new(), Keypair::new());
+    env.create_greeting_account(&greeted).await;
+    env.process(&[env.greet(&greeted.pubkey())], &[])
+        .await
+        .unwrap();
+
+    // Nobody sets the message of an account or resets it before it's initialized.
+    let early_set = set_greeting(
+        &program_id,
+        &greeted.pubkey(),
+        &authority.pubkey(),
+        "early".to_string(),
+    );
+    let early_reset = reset(&program_id, &greeted.pubkey(), &authority.pubkey());
+    for instruction in [early_set, early_reset] {
+        assert_eq!(
+            env.process(&[instruction], &[&authority]).await,
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::IllegalOwner
+            ))
+        );
+    }
+
+    let init = initialize(&program_id, &greeted.pubkey(), &authority.pubkey());
+    let set = set_greeting(
+        &program_id,
+        &greeted.pubkey(),
+        &authority.pubkey(),
+        "hello".to_string(),
+    );
+    env.process(&[init, set], &[&greeted, &authority])
+        .await
+        .unwrap();
+    let account = env.greeting_account(&greeted.pubkey()).await;
+    assert_eq!(account.authority, authority.pubkey());
+    assert_eq!(account.message, "hello");
+
+    // Only the authority changes the message or resets the counter.
+    let hijack = set_greeting(
+        &program_id,
+        &greeted.pubkey(),
+        &impostor.pubkey(),
+        "mine".to_string(),
+    );
+    let impostor_reset = reset(&program_id, &greeted.pubkey(), &impostor.pubkey());
+    for instruction in [hijack, impostor_reset] {
+        assert_eq!(
+            env.process(&[instruction], &[&impostor]).await,
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::IllegalOwner
+            ))
+        );
+    }
+    let too_long = set_greeting(
+        &program_id,
+        &greeted.pubkey(),
+        &authority.pubkey(),
+        "a".repeat(MAX_MESSAGE_LEN + 1),
+    );
+    assert_eq!(
+        env.process(&[too_long], &[&authority]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidArgument
+        ))
+    );
+
+    let reset = reset(&program_id, &greeted.pubkey(), &authority.pubkey());
+    env.process(&[reset], &[&authority]).await.unwrap();
+    let account = env.greeting_account(&greeted.pubkey()).await;
+    assert_eq!(account.counter, 0);
+    assert_eq!(account.last_greeted_slot, 0);
+    assert_eq!(account.message, "hello");
+}
@@ -0,0 +1,23 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SaleError {
+    /// The authority isn't the sale's program address.
+    InvalidAuthority,
+    /// The bank isn't the sale's.
+    InvalidBank,
+    /// The curve has no base price, or an exponential curve never doubles.
+    InvalidCurve,
+    /// Nothing to buy or sell, or more to sell than was ever bought.
+    InvalidAmount,
+    /// The trade is too large to price on the curve.
+    CurveOverflow,
+    /// The price moved past the limit the trader set.
+    SlippageExceeded,
+}
+
+impl From<SaleError> for ProgramError {
+    fn from(e: SaleError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
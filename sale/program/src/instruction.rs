@@ -0,0 +1,276 @@
+use crate::state::Curve;
+use arrayref::array_ref;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the authority, followed by the sale's address.
+pub const AUTHORITY_SEED: &[u8] = b"sale";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SaleInstruction {
+    /// Opens a sale along `curve`, initializing its bank with `decimals` and the
+    /// authority as bank owner. The creator pays the authority's rent, so that the
+    /// lamports it holds for sellers can all be paid out.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The creator.
+    ///   1. `[writable]` The sale, an uninitialized account owned by this program.
+    ///   2. `[writable]` The bank, an uninitialized account owned by the bank program.
+    ///   3. `[writable]` The authority.
+    ///   4. `[]` The bank program, owner of the bank.
+    ///   5. `[]` The system program.
+    CreateSale { curve: Curve, decimals: u8 },
+
+    /// Mints `amount` tokens to the buyer for their price on the curve, failing if that
+    /// comes to more than `max_cost` lamports.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The buyer, paying the lamports.
+    ///   1. `[writable]` The sale.
+    ///   2. `[writable]` The authority.
+    ///   3. `[writable]` The bank.
+    ///   4. `[writable]` The destination bank account.
+    ///   5. `[]` The bank program, owner of the bank.
+    ///   6. `[]` The system program.
+    Buy { amount: u64, max_cost: u64 },
+
+    /// Burns `amount` of the seller's tokens and pays them their price on the curve,
+    /// failing if that comes to less than `min_proceeds` lamports.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The seller, owner of the source account, paid the
+    ///      lamports.
+    ///   1. `[writable]` The sale.
+    ///   2. `[writable]` The authority.
+    ///   3. `[writable]` The bank.
+    ///   4. `[writable]` The source bank account.
+    ///   5. `[]` The bank program, owner of the bank.
+    ///   6. `[]` The system program.
+    Sell { amount: u64, min_proceeds: u64 },
+}
+
+impl SaleInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (&decimals, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let curve = rest
+                    .get(..Curve::LEN)
+                    .ok_or(InvalidInstructionData)
+                    .and_then(|curve| {
+                        Curve::unpack(array_ref![curve, 0, 17]).map_err(|_| InvalidInstructionData)
+                    })?;
+                Self::CreateSale { curve, decimals }
+            }
+            1 => {
+                let (amount, rest) = Self::unpack_u64(rest)?;
+                let (max_cost, _rest) = Self::unpack_u64(rest)?;
+                Self::Buy { amount, max_cost }
+            }
+            2 => {
+                let (amount, rest) = Self::unpack_u64(rest)?;
+                let (min_proceeds, _rest) = Self::unpack_u64(rest)?;
+                Self::Sell {
+                    amount,
+                    min_proceeds,
+                }
+            }
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(19);
+        match *self {
+            Self::CreateSale { curve, decimals } => {
+                buf.push(0);
+                buf.push(decimals);
+                let mut packed = [0u8; 17];
+                curve.pack(&mut packed);
+                buf.extend_from_slice(&packed);
+            }
+            Self::Buy { amount, max_cost } => {
+                buf.push(1);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(&max_cost.to_le_bytes());
+            }
+            Self::Sell {
+                amount,
+                min_proceeds,
+            } => {
+                buf.push(2);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(&min_proceeds.to_le_bytes());
+            }
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of the authority owning `sale`'s bank and holding its lamports.
+pub fn sale_authority(sale_program_id: &Pubkey, sale: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUTHORITY_SEED, sale.as_ref()], sale_program_id)
+}
+
+pub fn create_sale(
+    sale_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    creator: &Pubkey,
+    sale: &Pubkey,
+    bank: &Pubkey,
+    curve: Curve,
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = SaleInstruction::CreateSale { curve, decimals }.pack();
+    let (authority, _) = sale_authority(sale_program_id, sale);
+    let accounts = vec![
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(*sale, false),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *sale_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn buy(
+    sale_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    buyer: &Pubkey,
+    sale: &Pubkey,
+    bank: &Pubkey,
+    destination_account: &Pubkey,
+    amount: u64,
+    max_cost: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = SaleInstruction::Buy { amount, max_cost }.pack();
+    let (authority, _) = sale_authority(sale_program_id, sale);
+    let accounts = vec![
+        AccountMeta::new(*buyer, true),
+        AccountMeta::new(*sale, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *sale_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn sell(
+    sale_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    seller: &Pubkey,
+    sale: &Pubkey,
+    bank: &Pubkey,
+    source_account: &Pubkey,
+    amount: u64,
+    min_proceeds: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = SaleInstruction::Sell {
+        amount,
+        min_proceeds,
+    }
+    .pack();
+    let (authority, _) = sale_authority(sale_program_id, sale);
+    let accounts = vec![
+        AccountMeta::new(*seller, true),
+        AccountMeta::new(*sale, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *sale_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            SaleInstruction::CreateSale {
+                curve: Curve::Linear {
+                    base_price: 1_000,
+                    slope: 1,
+                },
+                decimals: 2,
+            },
+            SaleInstruction::CreateSale {
+                curve: Curve::Exponential {
+                    base_price: 1,
+                    doubling: 1_000_000,
+                },
+                decimals: 9,
+            },
+            SaleInstruction::Buy {
+                amount: 10,
+                max_cost: u64::MAX,
+            },
+            SaleInstruction::Sell {
+                amount: 10,
+                min_proceeds: 0,
+            },
+        ];
+        for instruction in cases {
+            assert_eq!(
+                SaleInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        let mut data = SaleInstruction::CreateSale {
+            curve: Curve::default(),
+            decimals: 0,
+        }
+        .pack();
+        data[2] = 2;
+        assert_eq!(
+            SaleInstruction::unpack(&data),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            SaleInstruction::unpack(&[1, 1, 0, 0, 0, 0, 0, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            SaleInstruction::unpack(&[3]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
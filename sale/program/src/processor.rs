@@ -0,0 +1,289 @@
+use crate::{
+    error::SaleError,
+    instruction::{SaleInstruction, AUTHORITY_SEED},
+    state::{Curve, Sale},
+};
+use solana_bank::{instruction as bank_instruction, state::Bank};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = SaleInstruction::unpack(input)?;
+
+        match instruction {
+            SaleInstruction::CreateSale { curve, decimals } => {
+                msg!("Instruction: CreateSale");
+                Self::process_create_sale(program_id, accounts, curve, decimals)
+            }
+            SaleInstruction::Buy { amount, max_cost } => {
+                msg!("Instruction: Buy");
+                Self::process_buy(program_id, accounts, amount, max_cost)
+            }
+            SaleInstruction::Sell {
+                amount,
+                min_proceeds,
+            } => {
+                msg!("Instruction: Sell");
+                Self::process_sell(program_id, accounts, amount, min_proceeds)
+            }
+        }
+    }
+
+    pub fn process_create_sale(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        curve: Curve,
+        decimals: u8,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let creator_info = next_account_info(account_info_iter)?;
+        let sale_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !creator_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if sale_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut sale = Sale::unpack_unchecked(&sale_info.data.borrow())?;
+        if sale.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        let (authority, bump) =
+            Pubkey::find_program_address(&[AUTHORITY_SEED, sale_info.key.as_ref()], program_id);
+        if authority != *authority_info.key {
+            return Err(SaleError::InvalidAuthority.into());
+        }
+        if !curve.is_valid() {
+            return Err(SaleError::InvalidCurve.into());
+        }
+        if bank_info.owner != bank_program_info.key {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let rent = Rent::get()?
+            .minimum_balance(0)
+            .saturating_sub(authority_info.lamports());
+        if rent > 0 {
+            invoke(
+                &system_instruction::transfer(creator_info.key, authority_info.key, rent),
+                &[
+                    creator_info.clone(),
+                    authority_info.clone(),
+                    system_program_info.clone(),
+                ],
+            )?;
+        }
+        invoke_signed(
+            &bank_instruction::initialize_bank(
+                bank_program_info.key,
+                bank_info.key,
+                authority_info.key,
+                decimals,
+            )?,
+            &[
+                bank_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[AUTHORITY_SEED, sale_info.key.as_ref(), &[bump]]],
+        )?;
+
+        sale.is_initialized = true;
+        sale.creator = *creator_info.key;
+        sale.bank = *bank_info.key;
+        sale.curve = curve;
+        sale.bump = bump;
+        Sale::pack(sale, &mut sale_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_buy(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+        max_cost: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let buyer_info = next_account_info(account_info_iter)?;
+        let sale_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !buyer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut sale = Self::load_sale(
+            program_id,
+            sale_info,
+            authority_info,
+            bank_info,
+            bank_program_info,
+        )?;
+        if amount == 0 {
+            return Err(SaleError::InvalidAmount.into());
+        }
+        let supply = Bank::unpack(&bank_info.data.borrow())?.total_supply;
+        let cost = sale
+            .curve
+            .cost(supply, amount)
+            .ok_or(SaleError::CurveOverflow)?;
+        if cost > max_cost {
+            return Err(SaleError::SlippageExceeded.into());
+        }
+
+        invoke(
+            &system_instruction::transfer(buyer_info.key, authority_info.key, cost),
+            &[
+                buyer_info.clone(),
+                authority_info.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+        invoke_signed(
+            &bank_instruction::mint_to(
+                bank_program_info.key,
+                bank_info.key,
+                destination_info.key,
+                authority_info.key,
+                amount,
+            )?,
+            &[
+                bank_info.clone(),
+                destination_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[AUTHORITY_SEED, sale_info.key.as_ref(), &[sale.bump]]],
+        )?;
+
+        sale.reserve = sale
+            .reserve
+            .checked_add(cost)
+            .ok_or(SaleError::CurveOverflow)?;
+        Sale::pack(sale, &mut sale_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_sell(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+        min_proceeds: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let seller_info = next_account_info(account_info_iter)?;
+        let sale_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !seller_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut sale = Self::load_sale(
+            program_id,
+            sale_info,
+            authority_info,
+            bank_info,
+            bank_program_info,
+        )?;
+        let supply = Bank::unpack(&bank_info.data.borrow())?.total_supply;
+        if amount == 0 || amount > supply {
+            return Err(SaleError::InvalidAmount.into());
+        }
+        // Selling walks back down the curve the last buyers went up.
+        let proceeds = sale
+            .curve
+            .cost(supply - amount, amount)
+            .ok_or(SaleError::CurveOverflow)?;
+        if proceeds < min_proceeds {
+            return Err(SaleError::SlippageExceeded.into());
+        }
+        let signer_seeds: &[&[u8]] = &[AUTHORITY_SEED, sale_info.key.as_ref(), &[sale.bump]];
+
+        invoke_signed(
+            &bank_instruction::burn(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                authority_info.key,
+                seller_info.key,
+                amount,
+            )?,
+            &[
+                bank_info.clone(),
+                source_info.clone(),
+                authority_info.clone(),
+                seller_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        invoke_signed(
+            &system_instruction::transfer(authority_info.key, seller_info.key, proceeds),
+            &[
+                authority_info.clone(),
+                seller_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        sale.reserve = sale
+            .reserve
+            .checked_sub(proceeds)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        Sale::pack(sale, &mut sale_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn load_sale(
+        program_id: &Pubkey,
+        sale_info: &AccountInfo,
+        authority_info: &AccountInfo,
+        bank_info: &AccountInfo,
+        bank_program_info: &AccountInfo,
+    ) -> Result<Sale, ProgramError> {
+        if sale_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let sale = Sale::unpack(&sale_info.data.borrow())?;
+        let authority = Pubkey::create_program_address(
+            &[AUTHORITY_SEED, sale_info.key.as_ref(), &[sale.bump]],
+            program_id,
+        )?;
+        if authority != *authority_info.key {
+            return Err(SaleError::InvalidAuthority.into());
+        }
+        if sale.bank != *bank_info.key {
+            return Err(SaleError::InvalidBank.into());
+        }
+        // The authority signs for the bank program, so it has to be the real one.
+        if bank_info.owner != bank_program_info.key {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        Ok(sale)
+    }
+}
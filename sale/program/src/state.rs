@@ -0,0 +1,233 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+use std::convert::TryFrom;
+
+/// The price of each base unit of a sale's bank tokens, in lamports, by how many units
+/// are in circulation before it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Curve {
+    /// The `i`th unit costs `base_price + slope * i`.
+    Linear { base_price: u64, slope: u64 },
+    /// The price starts at `base_price` and doubles every `doubling` units.
+    Exponential { base_price: u64, doubling: u64 },
+}
+
+impl Default for Curve {
+    fn default() -> Self {
+        Curve::Linear {
+            base_price: 0,
+            slope: 0,
+        }
+    }
+}
+
+impl Curve {
+    pub const LEN: usize = 17;
+
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Curve::Linear { base_price, .. } => base_price > 0,
+            Curve::Exponential {
+                base_price,
+                doubling,
+            } => base_price > 0 && doubling > 0,
+        }
+    }
+
+    /// Lamports for the `amount` units after the first `supply`, which is both what
+    /// buying them costs and what selling them back returns. `None` if it overflows.
+    pub fn cost(&self, supply: u64, amount: u64) -> Option<u64> {
+        if amount == 0 {
+            return Some(0);
+        }
+        let (supply, amount) = (supply as u128, amount as u128);
+        let cost = match *self {
+            Curve::Linear { base_price, slope } => {
+                // Units supply..supply + amount; one of amount and 2 * supply + amount - 1
+                // is even, so the halving is exact.
+                let steps = amount.checked_mul(2 * supply + amount - 1)? / 2;
+                (base_price as u128)
+                    .checked_mul(amount)?
+                    .checked_add((slope as u128).checked_mul(steps)?)?
+            }
+            Curve::Exponential {
+                base_price,
+                doubling,
+            } => {
+                let doubling = doubling as u128;
+                let (mut unit, end, mut cost) = (supply, supply + amount, 0u128);
+                while unit < end {
+                    let doublings = unit / doubling;
+                    if doublings >= 64 {
+                        return None;
+                    }
+                    let price = (base_price as u128) << doublings;
+                    let next = end.min((doublings + 1) * doubling);
+                    cost = cost.checked_add(price.checked_mul(next - unit)?)?;
+                    unit = next;
+                }
+                cost
+            }
+        };
+        u64::try_from(cost).ok()
+    }
+
+    pub(crate) fn unpack(src: &[u8; 17]) -> Result<Self, ProgramError> {
+        let (kind, base_price, param) = array_refs![src, 1, 8, 8];
+        let (base_price, param) = (u64::from_le_bytes(*base_price), u64::from_le_bytes(*param));
+        match kind[0] {
+            0 => Ok(Curve::Linear {
+                base_price,
+                slope: param,
+            }),
+            1 => Ok(Curve::Exponential {
+                base_price,
+                doubling: param,
+            }),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    pub(crate) fn pack(&self, dst: &mut [u8; 17]) {
+        let (kind, base_price, param) = mut_array_refs![dst, 1, 8, 8];
+        let (tag, price, value) = match *self {
+            Curve::Linear { base_price, slope } => (0, base_price, slope),
+            Curve::Exponential {
+                base_price,
+                doubling,
+            } => (1, base_price, doubling),
+        };
+        kind[0] = tag;
+        *base_price = price.to_le_bytes();
+        *param = value.to_le_bytes();
+    }
+}
+
+/// A bonding-curve sale of `bank`'s tokens, whose owner is the sale's authority. Buying
+/// mints tokens for lamports paid into the authority and selling burns them for the
+/// same lamports back, priced by `curve` at the bank's total supply; `reserve` is what
+/// the authority holds for sellers on top of its rent.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Sale {
+    pub is_initialized: bool,
+    pub creator: Pubkey,
+    pub bank: Pubkey,
+    pub curve: Curve,
+    pub reserve: u64,
+    /// Bump seed of the authority address.
+    pub bump: u8,
+}
+
+impl Sealed for Sale {}
+impl IsInitialized for Sale {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Sale {
+    const LEN: usize = 91;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 91];
+        let (is_initialized, creator, bank, curve, reserve, bump) =
+            array_refs![src, 1, 32, 32, 17, 8, 1];
+        Ok(Sale {
+            is_initialized: unpack_bool(is_initialized)?,
+            creator: Pubkey::new_from_array(*creator),
+            bank: Pubkey::new_from_array(*bank),
+            curve: Curve::unpack(curve)?,
+            reserve: u64::from_le_bytes(*reserve),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 91];
+        let (is_initialized, creator, bank, curve, reserve, bump) =
+            mut_array_refs![dst, 1, 32, 32, 17, 8, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        creator.copy_from_slice(self.creator.as_ref());
+        bank.copy_from_slice(self.bank.as_ref());
+        self.curve.pack(curve);
+        *reserve = self.reserve.to_le_bytes();
+        bump[0] = self.bump;
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let sale = Sale {
+            is_initialized: true,
+            creator: Pubkey::new_unique(),
+            bank: Pubkey::new_unique(),
+            curve: Curve::Exponential {
+                base_price: 1_000,
+                doubling: 500,
+            },
+            reserve: 42_000,
+            bump: 253,
+        };
+        let mut packed = vec![0u8; Sale::LEN];
+        Sale::pack(sale, &mut packed).unwrap();
+        assert_eq!(Sale::unpack(&packed), Ok(sale));
+
+        packed[65] = 2;
+        assert_eq!(Sale::unpack(&packed), Err(ProgramError::InvalidAccountData));
+        assert_eq!(Sale::unpack_unchecked(&[0; 91]), Ok(Sale::default()));
+    }
+
+    #[test]
+    fn test_linear_cost() {
+        let curve = Curve::Linear {
+            base_price: 100,
+            slope: 10,
+        };
+        // 100 + 110 + 120
+        assert_eq!(curve.cost(0, 3), Some(330));
+        // Buying in pieces costs the same as all at once.
+        assert_eq!(curve.cost(0, 1), Some(100));
+        assert_eq!(curve.cost(1, 2), Some(230));
+        assert_eq!(curve.cost(7, 0), Some(0));
+        assert_eq!(curve.cost(u64::MAX, u64::MAX), None);
+
+        let flat = Curve::Linear {
+            base_price: 5,
+            slope: 0,
+        };
+        assert_eq!(flat.cost(1_000_000, 4), Some(20));
+    }
+
+    #[test]
+    fn test_exponential_cost() {
+        let curve = Curve::Exponential {
+            base_price: 100,
+            doubling: 2,
+        };
+        // 100 + 100 + 200 + 200 + 400
+        assert_eq!(curve.cost(0, 5), Some(1_000));
+        assert_eq!(curve.cost(3, 2), Some(600));
+        assert_eq!(curve.cost(0, 200), None);
+        assert!(curve.is_valid());
+        assert!(!Curve::Exponential {
+            base_price: 100,
+            doubling: 0,
+        }
+        .is_valid());
+    }
+}
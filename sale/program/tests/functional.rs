@@ -0,0 +1,244 @@
+use solana_bank::{state::Bank, test_utils::program_test::Env};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_sale::{
+    error::SaleError,
+    instruction::{buy, create_sale, sale_authority, sell},
+    processor::Processor,
+    state::{Curve, Sale},
+};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const CURVE: Curve = Curve::Linear {
+    base_price: 1_000,
+    slope: 10,
+};
+
+/// A sale along `CURVE` where alice has lamports and an empty account in its bank.
+struct Fixture {
+    env: Env,
+    sale: Pubkey,
+    bank: Pubkey,
+    alice: Keypair,
+    alice_account: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_sale", processor!(Processor::process)).await;
+        let bank_program_id = env.bank_program_id;
+        let (sale, bank) = open_sale(&mut env, &bank_program_id, CURVE).await.unwrap();
+
+        let alice = Keypair::new();
+        env.fund(&alice.pubkey(), 100_000_000).await;
+        let alice_account = env.create_bank_account(&bank, &alice, 0).await;
+        Fixture {
+            env,
+            sale,
+            bank,
+            alice,
+            alice_account,
+        }
+    }
+
+    async fn buy(&mut self, amount: u64, max_cost: u64) -> Result<(), TransactionError> {
+        let instruction = buy(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.alice.pubkey(),
+            &self.sale,
+            &self.bank,
+            &self.alice_account,
+            amount,
+            max_cost,
+        )
+        .unwrap();
+        let alice = Keypair::from_bytes(&self.alice.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&alice]).await
+    }
+
+    async fn sell(&mut self, amount: u64, min_proceeds: u64) -> Result<(), TransactionError> {
+        let instruction = sell(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.alice.pubkey(),
+            &self.sale,
+            &self.bank,
+            &self.alice_account,
+            amount,
+            min_proceeds,
+        )
+        .unwrap();
+        let alice = Keypair::from_bytes(&self.alice.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&alice]).await
+    }
+
+    async fn state(&mut self) -> Sale {
+        let sale = self.sale;
+        self.env.unpack::<Sale>(&sale).await
+    }
+
+    async fn tokens(&mut self) -> u64 {
+        let account = self.alice_account;
+        self.env.amount(&account).await
+    }
+}
+
+/// Opens a sale along `curve` in a new bank of the bank program, through `bank_program_id`.
+async fn open_sale(
+    env: &mut Env,
+    bank_program_id: &Pubkey,
+    curve: Curve,
+) -> Result<(Pubkey, Pubkey), TransactionError> {
+    let (sale, bank) = (Keypair::new(), Keypair::new());
+    let (sale_program_id, real_bank_program_id) = (env.program_id, env.bank_program_id);
+    env.create_account(&sale, Sale::LEN, &sale_program_id).await;
+    env.create_account(&bank, Bank::LEN, &real_bank_program_id)
+        .await;
+    let instruction = create_sale(
+        &sale_program_id,
+        bank_program_id,
+        &env.context.payer.pubkey(),
+        &sale.pubkey(),
+        &bank.pubkey(),
+        curve,
+        0,
+    )
+    .unwrap();
+    env.process(&[instruction], &[]).await?;
+    Ok((sale.pubkey(), bank.pubkey()))
+}
+
+fn custom(error: SaleError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_sale() {
+    let mut fixture = Fixture::new().await;
+    let sale = fixture.state().await;
+    assert_eq!(sale.creator, fixture.env.context.payer.pubkey());
+    assert_eq!(
+        (sale.bank, sale.curve, sale.reserve),
+        (fixture.bank, CURVE, 0)
+    );
+    let (authority, _) = sale_authority(&fixture.env.program_id, &fixture.sale);
+    let bank = fixture.bank;
+    let bank = fixture.env.unpack::<Bank>(&bank).await;
+    assert_eq!((bank.bank_owner, bank.total_supply), (authority, 0));
+    let rent = fixture.env.context.banks_client.get_rent().await.unwrap();
+    assert_eq!(
+        fixture.env.lamports(&authority).await,
+        rent.minimum_balance(0)
+    );
+
+    let bank_program_id = fixture.env.bank_program_id;
+
+    assert_eq!(
+        open_sale(
+            &mut fixture.env,
+            &bank_program_id,
+            Curve::Exponential {
+                base_price: 1_000,
+                doubling: 0,
+            },
+        )
+        .await
+        .map(|_| ()),
+        custom(SaleError::InvalidCurve)
+    );
+}
+
+#[tokio::test]
+async fn test_buy() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.alice.pubkey();
+    let (authority, _) = sale_authority(&fixture.env.program_id, &fixture.sale);
+    let (lamports, reserve) = (
+        fixture.env.lamports(&alice).await,
+        fixture.env.lamports(&authority).await,
+    );
+
+    // 1_000 + 1_010 + ... + 1_090
+    fixture.buy(10, 10_450).await.unwrap();
+    assert_eq!(fixture.tokens().await, 10);
+    assert_eq!(fixture.env.lamports(&alice).await, lamports - 10_450);
+    assert_eq!(fixture.env.lamports(&authority).await, reserve + 10_450);
+    assert_eq!(fixture.state().await.reserve, 10_450);
+
+    // The next 5 cost more, 1_100 + ... + 1_140.
+    assert_eq!(
+        fixture.buy(5, 5_599).await,
+        custom(SaleError::SlippageExceeded)
+    );
+    fixture.buy(5, 5_600).await.unwrap();
+    assert_eq!(fixture.state().await.reserve, 16_050);
+    assert_eq!(fixture.buy(0, 0).await, custom(SaleError::InvalidAmount));
+}
+
+#[tokio::test]
+async fn test_sell() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.alice.pubkey();
+    let (authority, _) = sale_authority(&fixture.env.program_id, &fixture.sale);
+    let rent = fixture.env.lamports(&authority).await;
+    fixture.buy(15, u64::MAX).await.unwrap();
+    let lamports = fixture.env.lamports(&alice).await;
+
+    // Selling the last 5 returns what they cost.
+    assert_eq!(
+        fixture.sell(5, 5_601).await,
+        custom(SaleError::SlippageExceeded)
+    );
+    fixture.sell(5, 5_600).await.unwrap();
+    assert_eq!(fixture.tokens().await, 10);
+    assert_eq!(fixture.env.lamports(&alice).await, lamports + 5_600);
+    assert_eq!(fixture.sell(11, 0).await, custom(SaleError::InvalidAmount));
+
+    fixture.sell(10, 10_450).await.unwrap();
+    assert_eq!(fixture.tokens().await, 0);
+    assert_eq!(fixture.state().await.reserve, 0);
+    assert_eq!(fixture.env.lamports(&authority).await, rent);
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    let fake_bank_program_id = fixture.env.fake_bank_program_id;
+    assert_eq!(
+        open_sale(&mut fixture.env, &fake_bank_program_id, CURVE)
+            .await
+            .map(|_| ()),
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IncorrectProgramId
+        ))
+    );
+
+    let instruction = buy(
+        &fixture.env.program_id,
+        &fake_bank_program_id,
+        &fixture.alice.pubkey(),
+        &fixture.sale,
+        &fixture.bank,
+        &fixture.alice_account,
+        10,
+        u64::MAX,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&fixture.alice]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IncorrectProgramId
+        ))
+    );
+    assert_eq!(fixture.state().await.reserve, 0);
+    assert_eq!(fixture.tokens().await, 0);
+}
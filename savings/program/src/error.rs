@@ -0,0 +1,21 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SavingsError {
+    /// The reserve authority isn't the vault's program address.
+    InvalidReserveAuthority,
+    /// The account isn't the vault's reserve.
+    InvalidReserve,
+    /// The share account isn't the owner's program address for the vault.
+    InvalidShareAccount,
+    /// The amount is too small to mint a share, or to redeem for a token.
+    ZeroAmount,
+    /// The share account holds fewer shares than asked to redeem.
+    InsufficientShares,
+}
+
+impl From<SavingsError> for ProgramError {
+    fn from(e: SavingsError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,227 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the reserve authority, followed by the vault's address.
+pub const RESERVE_AUTHORITY_SEED: &[u8] = b"savings";
+/// Seed prefix of a share account, followed by the vault's and the owner's addresses.
+pub const SHARES_SEED: &[u8] = b"shares";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SavingsInstruction {
+    /// Opens a vault and initializes its reserve, owned by the reserve authority.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The vault, an uninitialized account owned by this program.
+    ///   1. `[writable]` The reserve, an uninitialized account owned by the bank program.
+    ///   2. `[writable]` The bank of the saved tokens.
+    ///   3. `[writable]` The reserve authority.
+    ///   4. `[]` The bank program, owner of the reserve.
+    InitializeVault,
+
+    /// Moves `amount` into the reserve and mints the owner its worth of shares, creating
+    /// the share account on the first deposit.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The owner, of the source account and the shares, paying
+    ///      for the share account.
+    ///   1. `[writable]` The vault.
+    ///   2. `[writable]` The owner's share account.
+    ///   3. `[writable]` The source account.
+    ///   4. `[writable]` The reserve.
+    ///   5. `[]` The bank of the saved tokens.
+    ///   6. `[]` The bank program, owner of the reserve.
+    ///   7. `[]` The system program.
+    Deposit { amount: u64 },
+
+    /// Burns `shares` of the owner's and pays out their worth from the reserve.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The owner.
+    ///   1. `[writable]` The vault.
+    ///   2. `[writable]` The owner's share account.
+    ///   3. `[writable]` The reserve.
+    ///   4. `[writable]` The destination account.
+    ///   5. `[writable]` The reserve authority.
+    ///   6. `[]` The bank of the saved tokens.
+    ///   7. `[]` The bank program, owner of the reserve.
+    Withdraw { shares: u64 },
+}
+
+impl SavingsInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => Self::InitializeVault,
+            1 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Deposit { amount }
+            }
+            2 => {
+                let (shares, _rest) = Self::unpack_u64(rest)?;
+                Self::Withdraw { shares }
+            }
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(9);
+        match *self {
+            Self::InitializeVault => buf.push(0),
+            Self::Deposit { amount } => {
+                buf.push(1);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Withdraw { shares } => {
+                buf.push(2);
+                buf.extend_from_slice(&shares.to_le_bytes());
+            }
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of the authority owning `vault`'s reserve.
+pub fn reserve_authority(savings_program_id: &Pubkey, vault: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[RESERVE_AUTHORITY_SEED, vault.as_ref()],
+        savings_program_id,
+    )
+}
+
+/// Address and bump of `owner`'s share account in `vault`.
+pub fn share_address(savings_program_id: &Pubkey, vault: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SHARES_SEED, vault.as_ref(), owner.as_ref()],
+        savings_program_id,
+    )
+}
+
+pub fn initialize_vault(
+    savings_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    vault: &Pubkey,
+    reserve: &Pubkey,
+    bank: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = SavingsInstruction::InitializeVault.pack();
+    let (authority, _) = reserve_authority(savings_program_id, vault);
+    let accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*reserve, false),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *savings_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn deposit(
+    savings_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    owner: &Pubkey,
+    vault: &Pubkey,
+    source_account: &Pubkey,
+    reserve: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = SavingsInstruction::Deposit { amount }.pack();
+    let (shares, _) = share_address(savings_program_id, vault, owner);
+    let accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(shares, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new(*reserve, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *savings_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw(
+    savings_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    owner: &Pubkey,
+    vault: &Pubkey,
+    reserve: &Pubkey,
+    destination_account: &Pubkey,
+    shares: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = SavingsInstruction::Withdraw { shares }.pack();
+    let (share_account, _) = share_address(savings_program_id, vault, owner);
+    let (authority, _) = reserve_authority(savings_program_id, vault);
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(share_account, false),
+        AccountMeta::new(*reserve, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *savings_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            SavingsInstruction::InitializeVault,
+            SavingsInstruction::Deposit { amount: 42 },
+            SavingsInstruction::Withdraw { shares: u64::MAX },
+        ];
+        for instruction in cases {
+            assert_eq!(
+                SavingsInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            SavingsInstruction::unpack(&[1, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            SavingsInstruction::unpack(&[3]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
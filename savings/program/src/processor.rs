@@ -0,0 +1,288 @@
+use crate::{
+    error::SavingsError,
+    instruction::{SavingsInstruction, RESERVE_AUTHORITY_SEED, SHARES_SEED},
+    state::{ShareAccount, Vault},
+};
+use solana_bank::{instruction as bank_instruction, state::Account as BankAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = SavingsInstruction::unpack(input)?;
+
+        match instruction {
+            SavingsInstruction::InitializeVault => {
+                msg!("Instruction: InitializeVault");
+                Self::process_initialize_vault(program_id, accounts)
+            }
+            SavingsInstruction::Deposit { amount } => {
+                msg!("Instruction: Deposit");
+                Self::process_deposit(program_id, accounts, amount)
+            }
+            SavingsInstruction::Withdraw { shares } => {
+                msg!("Instruction: Withdraw");
+                Self::process_withdraw(program_id, accounts, shares)
+            }
+        }
+    }
+
+    pub fn process_initialize_vault(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let vault_info = next_account_info(account_info_iter)?;
+        let reserve_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if vault_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut vault = Vault::unpack_unchecked(&vault_info.data.borrow())?;
+        if vault.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        let (authority, bump) = Pubkey::find_program_address(
+            &[RESERVE_AUTHORITY_SEED, vault_info.key.as_ref()],
+            program_id,
+        );
+        if authority != *authority_info.key {
+            return Err(SavingsError::InvalidReserveAuthority.into());
+        }
+        Self::check_bank_program(reserve_info, bank_program_info)?;
+
+        invoke_signed(
+            &bank_instruction::initialize_account(
+                bank_program_info.key,
+                bank_info.key,
+                reserve_info.key,
+                authority_info.key,
+            )?,
+            &[
+                bank_info.clone(),
+                reserve_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[RESERVE_AUTHORITY_SEED, vault_info.key.as_ref(), &[bump]]],
+        )?;
+
+        vault.is_initialized = true;
+        vault.reserve = *reserve_info.key;
+        vault.total_shares = 0;
+        vault.bump = bump;
+        Vault::pack(vault, &mut vault_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_deposit(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let shares_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let reserve_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut vault = Self::load_vault(program_id, vault_info, reserve_info, bank_program_info)?;
+        let assets = BankAccount::unpack(&reserve_info.data.borrow())?.amount;
+        let minted = match vault.shares_for(amount, assets) {
+            Some(0) => return Err(SavingsError::ZeroAmount.into()),
+            Some(minted) => minted,
+            None => return Err(ProgramError::InvalidArgument),
+        };
+        let shares_bump =
+            Self::check_share_account(program_id, vault_info.key, owner_info.key, shares_info)?;
+        if shares_info.data_is_empty() {
+            invoke_signed(
+                &system_instruction::create_account(
+                    owner_info.key,
+                    shares_info.key,
+                    Rent::get()?.minimum_balance(ShareAccount::LEN),
+                    ShareAccount::LEN as u64,
+                    program_id,
+                ),
+                &[
+                    owner_info.clone(),
+                    shares_info.clone(),
+                    system_program_info.clone(),
+                ],
+                &[&[
+                    SHARES_SEED,
+                    vault_info.key.as_ref(),
+                    owner_info.key.as_ref(),
+                    &[shares_bump],
+                ]],
+            )?;
+        }
+
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                reserve_info.key,
+                owner_info.key,
+                amount,
+            )?,
+            &[
+                source_info.clone(),
+                reserve_info.clone(),
+                owner_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        let mut shares = ShareAccount::unpack_unchecked(&shares_info.data.borrow())?;
+        shares.is_initialized = true;
+        shares.shares = shares
+            .shares
+            .checked_add(minted)
+            .ok_or(ProgramError::InvalidArgument)?;
+        ShareAccount::pack(shares, &mut shares_info.data.borrow_mut())?;
+        vault.total_shares = vault
+            .total_shares
+            .checked_add(minted)
+            .ok_or(ProgramError::InvalidArgument)?;
+        Vault::pack(vault, &mut vault_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_withdraw(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        burned: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let shares_info = next_account_info(account_info_iter)?;
+        let reserve_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut vault = Self::load_vault(program_id, vault_info, reserve_info, bank_program_info)?;
+        Self::check_share_account(program_id, vault_info.key, owner_info.key, shares_info)?;
+        if shares_info.owner != program_id {
+            return Err(SavingsError::InsufficientShares.into());
+        }
+        let mut shares = ShareAccount::unpack(&shares_info.data.borrow())?;
+        if shares.shares < burned {
+            return Err(SavingsError::InsufficientShares.into());
+        }
+        let assets = BankAccount::unpack(&reserve_info.data.borrow())?.amount;
+        let amount = vault.assets_for(burned, assets);
+        if amount == 0 {
+            return Err(SavingsError::ZeroAmount.into());
+        }
+
+        let bump = [vault.bump];
+        let signer_seeds: &[&[u8]] = &[RESERVE_AUTHORITY_SEED, vault_info.key.as_ref(), &bump];
+        let authority = Pubkey::create_program_address(signer_seeds, program_id)
+            .map_err(|_| ProgramError::from(SavingsError::InvalidReserveAuthority))?;
+        if authority != *authority_info.key {
+            return Err(SavingsError::InvalidReserveAuthority.into());
+        }
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                reserve_info.key,
+                destination_info.key,
+                authority_info.key,
+                amount,
+            )?,
+            &[
+                reserve_info.clone(),
+                destination_info.clone(),
+                authority_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        shares.shares -= burned;
+        ShareAccount::pack(shares, &mut shares_info.data.borrow_mut())?;
+        vault.total_shares -= burned;
+        Vault::pack(vault, &mut vault_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn load_vault(
+        program_id: &Pubkey,
+        vault_info: &AccountInfo,
+        reserve_info: &AccountInfo,
+        bank_program_info: &AccountInfo,
+    ) -> Result<Vault, ProgramError> {
+        if vault_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let vault = Vault::unpack(&vault_info.data.borrow())?;
+        if vault.reserve != *reserve_info.key {
+            return Err(SavingsError::InvalidReserve.into());
+        }
+        Self::check_bank_program(reserve_info, bank_program_info)?;
+        Ok(vault)
+    }
+
+    /// Fails unless `bank_program_info` is the program owning the reserve. Any other
+    /// program could be handed the depositor's or the reserve authority's signature, and
+    /// mint shares for tokens it never moved.
+    fn check_bank_program(
+        reserve_info: &AccountInfo,
+        bank_program_info: &AccountInfo,
+    ) -> ProgramResult {
+        if reserve_info.owner != bank_program_info.key {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        Ok(())
+    }
+
+    /// Checks `shares_info` is `owner`'s share account in `vault`, and returns the bump
+    /// seed of its address.
+    fn check_share_account(
+        program_id: &Pubkey,
+        vault: &Pubkey,
+        owner: &Pubkey,
+        shares_info: &AccountInfo,
+    ) -> Result<u8, ProgramError> {
+        let (shares, bump) = Pubkey::find_program_address(
+            &[SHARES_SEED, vault.as_ref(), owner.as_ref()],
+            program_id,
+        );
+        if shares != *shares_info.key {
+            return Err(SavingsError::InvalidShareAccount.into());
+        }
+        Ok(bump)
+    }
+}
@@ -0,0 +1,166 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+use std::convert::TryFrom;
+
+/// Shares and tokens counted on top of the real ones when converting between the two.
+/// They make inflating the price of a share with a donation ahead of other deposits cost
+/// the donor more than it takes from them.
+pub const VIRTUAL_SHARES: u128 = 1_000;
+pub const VIRTUAL_ASSETS: u128 = 1;
+
+/// A savings vault over `reserve`, a bank account owned by the reserve authority. Shares
+/// claim the reserve pro rata, so tokens sent straight to it are yield for every holder.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vault {
+    pub is_initialized: bool,
+    pub reserve: Pubkey,
+    pub total_shares: u64,
+    /// Bump seed of the reserve authority address.
+    pub bump: u8,
+}
+
+impl Vault {
+    /// Shares minted for depositing `amount` into a reserve holding `assets`, rounded
+    /// down. None when they don't fit a u64.
+    pub fn shares_for(&self, amount: u64, assets: u64) -> Option<u64> {
+        let shares = amount as u128 * (self.total_shares as u128 + VIRTUAL_SHARES)
+            / (assets as u128 + VIRTUAL_ASSETS);
+        u64::try_from(shares).ok()
+    }
+
+    /// Tokens paid for redeeming `shares` from a reserve holding `assets`, rounded down.
+    pub fn assets_for(&self, shares: u64, assets: u64) -> u64 {
+        let amount = shares as u128 * (assets as u128 + VIRTUAL_ASSETS)
+            / (self.total_shares as u128 + VIRTUAL_SHARES);
+        // Never more than the reserve, as long as `shares` is at most the total.
+        u64::try_from(amount).unwrap_or(u64::MAX).min(assets)
+    }
+}
+
+impl Sealed for Vault {}
+impl IsInitialized for Vault {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Vault {
+    const LEN: usize = 42;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 42];
+        let (is_initialized, reserve, total_shares, bump) = array_refs![src, 1, 32, 8, 1];
+        Ok(Vault {
+            is_initialized: unpack_bool(is_initialized)?,
+            reserve: Pubkey::new_from_array(*reserve),
+            total_shares: u64::from_le_bytes(*total_shares),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 42];
+        let (is_initialized, reserve, total_shares, bump) = mut_array_refs![dst, 1, 32, 8, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        reserve.copy_from_slice(self.reserve.as_ref());
+        *total_shares = self.total_shares.to_le_bytes();
+        bump[0] = self.bump;
+    }
+}
+
+/// Shares of one owner in a vault, at the program address derived from both.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ShareAccount {
+    pub is_initialized: bool,
+    pub shares: u64,
+}
+
+impl Sealed for ShareAccount {}
+impl IsInitialized for ShareAccount {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for ShareAccount {
+    const LEN: usize = 9;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 9];
+        let (is_initialized, shares) = array_refs![src, 1, 8];
+        Ok(ShareAccount {
+            is_initialized: unpack_bool(is_initialized)?,
+            shares: u64::from_le_bytes(*shares),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 9];
+        let (is_initialized, shares) = mut_array_refs![dst, 1, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        *shares = self.shares.to_le_bytes();
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let vault = Vault {
+            is_initialized: true,
+            reserve: Pubkey::new_unique(),
+            total_shares: 1_000_000,
+            bump: 250,
+        };
+        let mut packed = vec![0u8; Vault::LEN];
+        Vault::pack(vault, &mut packed).unwrap();
+        assert_eq!(Vault::unpack(&packed), Ok(vault));
+
+        let shares = ShareAccount {
+            is_initialized: true,
+            shares: u64::MAX,
+        };
+        let mut packed = vec![0u8; ShareAccount::LEN];
+        ShareAccount::pack(shares, &mut packed).unwrap();
+        assert_eq!(ShareAccount::unpack(&packed), Ok(shares));
+
+        packed[0] = 2;
+        assert_eq!(
+            ShareAccount::unpack_unchecked(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_conversions() {
+        let mut vault = Vault::default();
+        assert_eq!(vault.shares_for(100, 0), Some(100_000));
+        vault.total_shares = 100_000;
+        // Yield of 50 lifts the price of a share by half.
+        assert_eq!(vault.shares_for(150, 150), Some(100_331));
+        assert_eq!(vault.assets_for(100_000, 150), 149);
+        assert_eq!(vault.assets_for(vault.total_shares, 0), 0);
+
+        // Donating a million after a first deposit of 1 leaves a deposit of 1_000 with a
+        // single share, but the donor only gets half the donation back.
+        let mut vault = Vault {
+            total_shares: 1_000,
+            ..Vault::default()
+        };
+        assert_eq!(vault.shares_for(1_000, 1_000_001), Some(1));
+        vault.total_shares = 1_001;
+        assert_eq!(vault.assets_for(1_000, 1_001_001), 500_250);
+    }
+}
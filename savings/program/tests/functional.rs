@@ -0,0 +1,279 @@
+use solana_bank::{instruction::mint_to, state::Account, test_utils::program_test::Env};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_savings::{
+    error::SavingsError,
+    instruction::{deposit, initialize_vault, reserve_authority, share_address, withdraw},
+    processor::Processor,
+    state::{ShareAccount, Vault},
+};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// Someone holding 1_000 tokens of the vault's bank, and lamports for a share account.
+struct Saver {
+    owner: Keypair,
+    account: Pubkey,
+}
+
+struct Fixture {
+    env: Env,
+    bank: Pubkey,
+    vault: Pubkey,
+    reserve: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_savings", processor!(Processor::process)).await;
+        let bank = env.create_bank().await;
+        let (vault, reserve) = (Keypair::new(), Keypair::new());
+        let savings_program_id = env.program_id;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&vault, Vault::LEN, &savings_program_id)
+            .await;
+        env.create_account(&reserve, Account::LEN, &bank_program_id)
+            .await;
+        let instruction = initialize_vault(
+            &savings_program_id,
+            &bank_program_id,
+            &vault.pubkey(),
+            &reserve.pubkey(),
+            &bank,
+        )
+        .unwrap();
+        env.process(&[instruction], &[]).await.unwrap();
+        Fixture {
+            env,
+            bank,
+            vault: vault.pubkey(),
+            reserve: reserve.pubkey(),
+        }
+    }
+
+    async fn saver(&mut self) -> Saver {
+        let owner = Keypair::new();
+        let bank = self.bank;
+        let account = self.env.create_bank_account(&bank, &owner, 1_000).await;
+        self.env.fund(&owner.pubkey(), 10_000_000).await;
+        Saver { owner, account }
+    }
+
+    async fn deposit(&mut self, saver: &Saver, amount: u64) -> Result<(), TransactionError> {
+        let instruction = deposit(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &saver.owner.pubkey(),
+            &self.vault,
+            &saver.account,
+            &self.reserve,
+            amount,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[&saver.owner]).await
+    }
+
+    async fn withdraw(&mut self, saver: &Saver, shares: u64) -> Result<(), TransactionError> {
+        let instruction = withdraw(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &saver.owner.pubkey(),
+            &self.vault,
+            &self.reserve,
+            &saver.account,
+            shares,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[&saver.owner]).await
+    }
+
+    /// Sends `amount` freshly minted tokens straight to the reserve.
+    async fn donate(&mut self, amount: u64) {
+        let instruction = mint_to(
+            &self.env.bank_program_id,
+            &self.bank,
+            &self.reserve,
+            &self.env.context.payer.pubkey(),
+            amount,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[]).await.unwrap();
+    }
+
+    async fn vault(&mut self) -> Vault {
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(self.vault)
+            .await
+            .unwrap()
+            .unwrap();
+        Vault::unpack(&account.data).unwrap()
+    }
+
+    async fn shares(&mut self, saver: &Saver) -> u64 {
+        let (shares, _) = share_address(&self.env.program_id, &self.vault, &saver.owner.pubkey());
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(shares)
+            .await
+            .unwrap()
+            .unwrap();
+        ShareAccount::unpack(&account.data).unwrap().shares
+    }
+}
+
+fn custom(error: SavingsError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_initialize_vault() {
+    let mut fixture = Fixture::new().await;
+    assert_eq!(fixture.vault().await.total_shares, 0);
+    let (authority, _) = reserve_authority(&fixture.env.program_id, &fixture.vault);
+    let reserve = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(fixture.reserve)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Account::unpack(&reserve.data).unwrap().owner, authority);
+
+    let reserve = Keypair::new();
+    let bank_program_id = fixture.env.bank_program_id;
+    fixture
+        .env
+        .create_account(&reserve, Account::LEN, &bank_program_id)
+        .await;
+    let instruction = initialize_vault(
+        &fixture.env.program_id,
+        &bank_program_id,
+        &fixture.vault,
+        &reserve.pubkey(),
+        &fixture.bank,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::AccountAlreadyInitialized
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_deposit_and_withdraw() {
+    let mut fixture = Fixture::new().await;
+    let (alice, bob) = (fixture.saver().await, fixture.saver().await);
+    fixture.deposit(&alice, 100).await.unwrap();
+    fixture.deposit(&bob, 300).await.unwrap();
+    assert_eq!(fixture.shares(&alice).await, 100_000);
+    assert_eq!(fixture.shares(&bob).await, 300_000);
+    assert_eq!(fixture.vault().await.total_shares, 400_000);
+    assert_eq!(
+        fixture.deposit(&alice, 0).await,
+        custom(SavingsError::ZeroAmount)
+    );
+
+    assert_eq!(
+        fixture.withdraw(&alice, 100_001).await,
+        custom(SavingsError::InsufficientShares)
+    );
+    fixture.withdraw(&alice, 50_000).await.unwrap();
+    assert_eq!(fixture.shares(&alice).await, 50_000);
+    assert_eq!(fixture.env.amount(&alice.account).await, 950);
+    let reserve = fixture.reserve;
+    assert_eq!(fixture.env.amount(&reserve).await, 350);
+    // Less than a token's worth of shares can't be redeemed.
+    assert_eq!(
+        fixture.withdraw(&alice, 999).await,
+        custom(SavingsError::ZeroAmount)
+    );
+}
+
+#[tokio::test]
+async fn test_yield() {
+    let mut fixture = Fixture::new().await;
+    let (alice, bob) = (fixture.saver().await, fixture.saver().await);
+    fixture.deposit(&alice, 100).await.unwrap();
+    fixture.deposit(&bob, 300).await.unwrap();
+    fixture.donate(200).await;
+
+    // Each share is now worth half a token more, less what the virtual shares keep.
+    fixture.withdraw(&alice, 100_000).await.unwrap();
+    assert_eq!(fixture.env.amount(&alice.account).await, 1_049);
+    fixture.withdraw(&bob, 300_000).await.unwrap();
+    assert_eq!(fixture.env.amount(&bob.account).await, 1_150);
+    assert_eq!(fixture.vault().await.total_shares, 0);
+    let reserve = fixture.reserve;
+    assert_eq!(fixture.env.amount(&reserve).await, 1);
+
+    // A later deposit buys in at the price the remaining token sets.
+    let carol = fixture.saver().await;
+    fixture.deposit(&carol, 100).await.unwrap();
+    assert_eq!(fixture.shares(&carol).await, 50_000);
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.saver().await;
+    let savings_program_id = fixture.env.program_id;
+    let fake_bank_program_id = fixture.env.fake_bank_program_id;
+    let wrong_program = Err(TransactionError::InstructionError(
+        0,
+        InstructionError::IncorrectProgramId,
+    ));
+
+    // Through a program posing as the bank, a deposit would mint shares without any tokens
+    // moving into the reserve.
+    let instruction = deposit(
+        &savings_program_id,
+        &fake_bank_program_id,
+        &fixture.bank,
+        &alice.owner.pubkey(),
+        &fixture.vault,
+        &alice.account,
+        &fixture.reserve,
+        100,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&alice.owner]).await,
+        wrong_program
+    );
+    assert_eq!(fixture.vault().await.total_shares, 0);
+
+    // Nor does the reserve authority sign for it.
+    fixture.deposit(&alice, 100).await.unwrap();
+    let instruction = withdraw(
+        &savings_program_id,
+        &fake_bank_program_id,
+        &fixture.bank,
+        &alice.owner.pubkey(),
+        &fixture.vault,
+        &fixture.reserve,
+        &alice.account,
+        100_000,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&alice.owner]).await,
+        wrong_program
+    );
+    assert_eq!(fixture.shares(&alice).await, 100_000);
+}
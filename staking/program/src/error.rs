@@ -0,0 +1,17 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StakingError {
+    /// The stake account holds less than the amount asked to unstake.
+    InsufficientStake,
+    /// No rewards have accrued since the last claim.
+    NothingToClaim,
+    /// The pool authority isn't the pool's program address.
+    InvalidPoolAuthority,
+}
+
+impl From<StakingError> for ProgramError {
+    fn from(e: StakingError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,276 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the pool authority, followed by the pool's address.
+pub const POOL_AUTHORITY_SEED: &[u8] = b"pool";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StakingInstruction {
+    /// Opens a pool paying `reward_rate` tokens per slot: initializes the stake vault and
+    /// the reward vault, both owned by the pool authority. Rewards are funded by plain bank
+    /// transfers into the reward vault.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The admin.
+    ///   1. `[writable]` The pool, owned by this program.
+    ///   2. `[writable]` The stake vault, an uninitialized account owned by the bank program.
+    ///   3. `[writable]` The reward vault, an uninitialized account owned by the bank program.
+    ///   4. `[writable]` The bank of the staked tokens.
+    ///   5. `[writable]` The bank of the reward tokens.
+    ///   6. `[writable]` The pool authority.
+    ///   7. `[]` The bank program, owner of the vaults.
+    InitializePool { reward_rate: u64 },
+
+    /// Moves `amount` from the owner's source account into the stake vault. The stake
+    /// account is claimed for the owner on first use.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The owner.
+    ///   1. `[writable]` The pool.
+    ///   2. `[writable]` The owner's stake account, owned by this program.
+    ///   3. `[writable]` The source account.
+    ///   4. `[writable]` The stake vault.
+    ///   5. `[]` The bank of the staked tokens.
+    ///   6. `[]` The bank program, owner of the vaults.
+    Stake { amount: u64 },
+
+    /// Moves `amount` of the owner's stake back out of the stake vault. Rewards earned so
+    /// far stay claimable.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The owner.
+    ///   1. `[writable]` The pool.
+    ///   2. `[writable]` The owner's stake account.
+    ///   3. `[writable]` The stake vault.
+    ///   4. `[writable]` The destination account.
+    ///   5. `[writable]` The pool authority.
+    ///   6. `[]` The bank of the staked tokens.
+    ///   7. `[]` The bank program, owner of the vaults.
+    Unstake { amount: u64 },
+
+    /// Pays the owner's accrued rewards out of the reward vault, as far as it is funded.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The owner.
+    ///   1. `[writable]` The pool.
+    ///   2. `[writable]` The owner's stake account.
+    ///   3. `[writable]` The reward vault.
+    ///   4. `[writable]` The destination account.
+    ///   5. `[writable]` The pool authority.
+    ///   6. `[]` The bank of the reward tokens.
+    ///   7. `[]` The bank program, owner of the vaults.
+    ClaimRewards,
+}
+
+impl StakingInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (reward_rate, _rest) = Self::unpack_u64(rest)?;
+                Self::InitializePool { reward_rate }
+            }
+            1 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Stake { amount }
+            }
+            2 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Unstake { amount }
+            }
+            3 => Self::ClaimRewards,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(9);
+        match *self {
+            Self::InitializePool { reward_rate } => {
+                buf.push(0);
+                buf.extend_from_slice(&reward_rate.to_le_bytes());
+            }
+            Self::Stake { amount } => {
+                buf.push(1);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Unstake { amount } => {
+                buf.push(2);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::ClaimRewards => buf.push(3),
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of the authority owning `pool`'s vaults.
+pub fn pool_authority(staking_program_id: &Pubkey, pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_AUTHORITY_SEED, pool.as_ref()], staking_program_id)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_pool(
+    staking_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    admin: &Pubkey,
+    pool: &Pubkey,
+    stake_vault: &Pubkey,
+    reward_vault: &Pubkey,
+    stake_bank: &Pubkey,
+    reward_bank: &Pubkey,
+    reward_rate: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = StakingInstruction::InitializePool { reward_rate }.pack();
+    let (authority, _) = pool_authority(staking_program_id, pool);
+    let accounts = vec![
+        AccountMeta::new_readonly(*admin, true),
+        AccountMeta::new(*pool, false),
+        AccountMeta::new(*stake_vault, false),
+        AccountMeta::new(*reward_vault, false),
+        AccountMeta::new(*stake_bank, false),
+        AccountMeta::new(*reward_bank, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *staking_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn stake(
+    staking_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    stake_bank: &Pubkey,
+    owner: &Pubkey,
+    pool: &Pubkey,
+    stake_account: &Pubkey,
+    source_account: &Pubkey,
+    stake_vault: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = StakingInstruction::Stake { amount }.pack();
+    let accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(*pool, false),
+        AccountMeta::new(*stake_account, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new(*stake_vault, false),
+        AccountMeta::new_readonly(*stake_bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *staking_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn unstake(
+    staking_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    stake_bank: &Pubkey,
+    owner: &Pubkey,
+    pool: &Pubkey,
+    stake_account: &Pubkey,
+    stake_vault: &Pubkey,
+    destination_account: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = StakingInstruction::Unstake { amount }.pack();
+    let (authority, _) = pool_authority(staking_program_id, pool);
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*pool, false),
+        AccountMeta::new(*stake_account, false),
+        AccountMeta::new(*stake_vault, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*stake_bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *staking_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn claim_rewards(
+    staking_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    reward_bank: &Pubkey,
+    owner: &Pubkey,
+    pool: &Pubkey,
+    stake_account: &Pubkey,
+    reward_vault: &Pubkey,
+    destination_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = StakingInstruction::ClaimRewards.pack();
+    let (authority, _) = pool_authority(staking_program_id, pool);
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*pool, false),
+        AccountMeta::new(*stake_account, false),
+        AccountMeta::new(*reward_vault, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*reward_bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *staking_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            StakingInstruction::InitializePool { reward_rate: 10 },
+            StakingInstruction::Stake { amount: u64::MAX },
+            StakingInstruction::Unstake { amount: 1 },
+            StakingInstruction::ClaimRewards,
+        ];
+        for instruction in cases {
+            assert_eq!(
+                StakingInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            StakingInstruction::unpack(&[1, 2, 3]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            StakingInstruction::unpack(&[4]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
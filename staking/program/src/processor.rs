@@ -0,0 +1,338 @@
+use crate::{
+    error::StakingError,
+    instruction::{StakingInstruction, POOL_AUTHORITY_SEED},
+    state::{Pool, StakeAccount},
+};
+use solana_bank::{instruction as bank_instruction, state::Account as BankAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = StakingInstruction::unpack(input)?;
+
+        match instruction {
+            StakingInstruction::InitializePool { reward_rate } => {
+                msg!("Instruction: InitializePool");
+                Self::process_initialize_pool(program_id, accounts, reward_rate)
+            }
+            StakingInstruction::Stake { amount } => {
+                msg!("Instruction: Stake");
+                Self::process_stake(program_id, accounts, amount)
+            }
+            StakingInstruction::Unstake { amount } => {
+                msg!("Instruction: Unstake");
+                Self::process_unstake(program_id, accounts, amount)
+            }
+            StakingInstruction::ClaimRewards => {
+                msg!("Instruction: ClaimRewards");
+                Self::process_claim_rewards(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_initialize_pool(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        reward_rate: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let admin_info = next_account_info(account_info_iter)?;
+        let pool_info = next_account_info(account_info_iter)?;
+        let stake_vault_info = next_account_info(account_info_iter)?;
+        let reward_vault_info = next_account_info(account_info_iter)?;
+        let stake_bank_info = next_account_info(account_info_iter)?;
+        let reward_bank_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !admin_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if pool_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut pool = Pool::unpack_unchecked(&pool_info.data.borrow())?;
+        if pool.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        let (authority, bump) = Pubkey::find_program_address(
+            &[POOL_AUTHORITY_SEED, pool_info.key.as_ref()],
+            program_id,
+        );
+        if authority != *authority_info.key {
+            return Err(StakingError::InvalidPoolAuthority.into());
+        }
+        let signer_seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, pool_info.key.as_ref(), &[bump]];
+
+        for (bank_info, vault_info) in [
+            (stake_bank_info, stake_vault_info),
+            (reward_bank_info, reward_vault_info),
+        ] {
+            if vault_info.owner != bank_program_info.key {
+                return Err(ProgramError::InvalidArgument);
+            }
+            invoke_signed(
+                &bank_instruction::initialize_account(
+                    bank_program_info.key,
+                    bank_info.key,
+                    vault_info.key,
+                    authority_info.key,
+                )?,
+                &[
+                    bank_info.clone(),
+                    vault_info.clone(),
+                    authority_info.clone(),
+                    bank_program_info.clone(),
+                ],
+                &[signer_seeds],
+            )?;
+        }
+
+        pool.is_initialized = true;
+        pool.admin = *admin_info.key;
+        pool.stake_vault = *stake_vault_info.key;
+        pool.reward_vault = *reward_vault_info.key;
+        pool.reward_rate = reward_rate;
+        pool.reward_per_share = 0;
+        pool.last_update_slot = Clock::get()?.slot;
+        pool.total_staked = 0;
+        pool.bump = bump;
+        Pool::pack(pool, &mut pool_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_stake(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let pool_info = next_account_info(account_info_iter)?;
+        let stake_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let stake_vault_info = next_account_info(account_info_iter)?;
+        let stake_bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut pool = Self::load_pool(program_id, pool_info)?;
+        if pool.stake_vault != *stake_vault_info.key
+            || stake_vault_info.owner != bank_program_info.key
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+        if stake_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut stake = StakeAccount::unpack_unchecked(&stake_info.data.borrow())?;
+        if !stake.is_initialized {
+            stake.is_initialized = true;
+            stake.pool = *pool_info.key;
+            stake.owner = *owner_info.key;
+        }
+        if stake.pool != *pool_info.key || stake.owner != *owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                stake_bank_info.key,
+                source_info.key,
+                stake_vault_info.key,
+                owner_info.key,
+                amount,
+            )?,
+            &[
+                source_info.clone(),
+                stake_vault_info.clone(),
+                owner_info.clone(),
+                stake_bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        pool.accrue(Clock::get()?.slot);
+        stake.settle(&pool);
+        let staked = stake
+            .amount
+            .checked_add(amount)
+            .ok_or(ProgramError::InvalidArgument)?;
+        stake.set_amount(staked, &pool);
+        pool.total_staked = pool
+            .total_staked
+            .checked_add(amount)
+            .ok_or(ProgramError::InvalidArgument)?;
+        Pool::pack(pool, &mut pool_info.data.borrow_mut())?;
+        StakeAccount::pack(stake, &mut stake_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_unstake(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let pool_info = next_account_info(account_info_iter)?;
+        let stake_info = next_account_info(account_info_iter)?;
+        let stake_vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let stake_bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut pool = Self::load_pool(program_id, pool_info)?;
+        if pool.stake_vault != *stake_vault_info.key
+            || stake_vault_info.owner != bank_program_info.key
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let mut stake = Self::load_stake(program_id, stake_info, pool_info, owner_info)?;
+        if stake.amount < amount {
+            return Err(StakingError::InsufficientStake.into());
+        }
+        let bump = [pool.bump];
+        let signer_seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, pool_info.key.as_ref(), &bump];
+        Self::check_authority(program_id, authority_info, signer_seeds)?;
+
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                stake_bank_info.key,
+                stake_vault_info.key,
+                destination_info.key,
+                authority_info.key,
+                amount,
+            )?,
+            &[
+                stake_vault_info.clone(),
+                destination_info.clone(),
+                authority_info.clone(),
+                stake_bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        pool.accrue(Clock::get()?.slot);
+        stake.settle(&pool);
+        stake.set_amount(stake.amount - amount, &pool);
+        pool.total_staked -= amount;
+        Pool::pack(pool, &mut pool_info.data.borrow_mut())?;
+        StakeAccount::pack(stake, &mut stake_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_claim_rewards(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let pool_info = next_account_info(account_info_iter)?;
+        let stake_info = next_account_info(account_info_iter)?;
+        let reward_vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let reward_bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut pool = Self::load_pool(program_id, pool_info)?;
+        if pool.reward_vault != *reward_vault_info.key
+            || reward_vault_info.owner != bank_program_info.key
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let mut stake = Self::load_stake(program_id, stake_info, pool_info, owner_info)?;
+        let bump = [pool.bump];
+        let signer_seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, pool_info.key.as_ref(), &bump];
+        Self::check_authority(program_id, authority_info, signer_seeds)?;
+
+        pool.accrue(Clock::get()?.slot);
+        stake.settle(&pool);
+        // An underfunded vault pays what it has; the rest stays owed until it's topped up.
+        let funded = BankAccount::unpack(&reward_vault_info.data.borrow())?.amount;
+        let amount = stake.pending_rewards.min(funded);
+        if amount == 0 {
+            return Err(StakingError::NothingToClaim.into());
+        }
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                reward_bank_info.key,
+                reward_vault_info.key,
+                destination_info.key,
+                authority_info.key,
+                amount,
+            )?,
+            &[
+                reward_vault_info.clone(),
+                destination_info.clone(),
+                authority_info.clone(),
+                reward_bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        stake.pending_rewards -= amount;
+        Pool::pack(pool, &mut pool_info.data.borrow_mut())?;
+        StakeAccount::pack(stake, &mut stake_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn load_pool(program_id: &Pubkey, pool_info: &AccountInfo) -> Result<Pool, ProgramError> {
+        if pool_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Pool::unpack(&pool_info.data.borrow())
+    }
+
+    fn load_stake(
+        program_id: &Pubkey,
+        stake_info: &AccountInfo,
+        pool_info: &AccountInfo,
+        owner_info: &AccountInfo,
+    ) -> Result<StakeAccount, ProgramError> {
+        if stake_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let stake = StakeAccount::unpack(&stake_info.data.borrow())?;
+        if stake.pool != *pool_info.key || stake.owner != *owner_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Ok(stake)
+    }
+
+    fn check_authority(
+        program_id: &Pubkey,
+        authority_info: &AccountInfo,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        let authority = Pubkey::create_program_address(signer_seeds, program_id)
+            .map_err(|_| ProgramError::from(StakingError::InvalidPoolAuthority))?;
+        if authority != *authority_info.key {
+            return Err(StakingError::InvalidPoolAuthority.into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,271 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+use std::convert::TryFrom;
+
+/// Fixed-point scale of `Pool::reward_per_share`.
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+/// A staking pool: stakes sit in `stake_vault` and every slot `reward_rate` tokens of
+/// `reward_vault` are shared among them in proportion to their amounts.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Pool {
+    pub is_initialized: bool,
+    pub admin: Pubkey,
+    /// Bank accounts owned by the pool authority.
+    pub stake_vault: Pubkey,
+    pub reward_vault: Pubkey,
+    /// Reward tokens paid out per slot, across all stakers.
+    pub reward_rate: u64,
+    /// Rewards earned by one staked token since the pool opened, times `REWARD_PRECISION`.
+    pub reward_per_share: u128,
+    pub last_update_slot: u64,
+    pub total_staked: u64,
+    /// Bump seed of the pool authority address.
+    pub bump: u8,
+}
+
+impl Pool {
+    /// Brings `reward_per_share` up to `slot`. Slots with nothing staked pay no one.
+    pub fn accrue(&mut self, slot: u64) {
+        if slot <= self.last_update_slot {
+            return;
+        }
+        if self.total_staked > 0 {
+            let reward = self.reward_rate as u128 * (slot - self.last_update_slot) as u128;
+            self.reward_per_share = self.reward_per_share.saturating_add(
+                reward.saturating_mul(REWARD_PRECISION) / self.total_staked as u128,
+            );
+        }
+        self.last_update_slot = slot;
+    }
+}
+
+impl Sealed for Pool {}
+impl IsInitialized for Pool {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Pool {
+    const LEN: usize = 138;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 138];
+        let (
+            is_initialized,
+            admin,
+            stake_vault,
+            reward_vault,
+            reward_rate,
+            reward_per_share,
+            last_update_slot,
+            total_staked,
+            bump,
+        ) = array_refs![src, 1, 32, 32, 32, 8, 16, 8, 8, 1];
+        Ok(Pool {
+            is_initialized: unpack_bool(is_initialized)?,
+            admin: Pubkey::new_from_array(*admin),
+            stake_vault: Pubkey::new_from_array(*stake_vault),
+            reward_vault: Pubkey::new_from_array(*reward_vault),
+            reward_rate: u64::from_le_bytes(*reward_rate),
+            reward_per_share: u128::from_le_bytes(*reward_per_share),
+            last_update_slot: u64::from_le_bytes(*last_update_slot),
+            total_staked: u64::from_le_bytes(*total_staked),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 138];
+        let (
+            is_initialized,
+            admin,
+            stake_vault,
+            reward_vault,
+            reward_rate,
+            reward_per_share,
+            last_update_slot,
+            total_staked,
+            bump,
+        ) = mut_array_refs![dst, 1, 32, 32, 32, 8, 16, 8, 8, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        admin.copy_from_slice(self.admin.as_ref());
+        stake_vault.copy_from_slice(self.stake_vault.as_ref());
+        reward_vault.copy_from_slice(self.reward_vault.as_ref());
+        *reward_rate = self.reward_rate.to_le_bytes();
+        *reward_per_share = self.reward_per_share.to_le_bytes();
+        *last_update_slot = self.last_update_slot.to_le_bytes();
+        *total_staked = self.total_staked.to_le_bytes();
+        bump[0] = self.bump;
+    }
+}
+
+/// One owner's share of a pool.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StakeAccount {
+    pub is_initialized: bool,
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    /// `amount` times the pool's `reward_per_share` when rewards were last settled.
+    pub reward_debt: u128,
+    /// Rewards settled but not paid out yet.
+    pub pending_rewards: u64,
+}
+
+impl StakeAccount {
+    /// Moves the rewards earned since the last settlement into `pending_rewards`. Call it,
+    /// with `pool` accrued, before every change to `amount`.
+    pub fn settle(&mut self, pool: &Pool) {
+        let earned = self.earned(pool);
+        let owed = earned.saturating_sub(self.reward_debt);
+        self.pending_rewards = self
+            .pending_rewards
+            .saturating_add(u64::try_from(owed).unwrap_or(u64::MAX));
+        self.reward_debt = earned;
+    }
+
+    /// Sets `amount` after a settlement, so rewards only count from here on.
+    pub fn set_amount(&mut self, amount: u64, pool: &Pool) {
+        self.amount = amount;
+        self.reward_debt = self.earned(pool);
+    }
+
+    fn earned(&self, pool: &Pool) -> u128 {
+        (self.amount as u128)
+            .saturating_mul(pool.reward_per_share)
+            .checked_div(REWARD_PRECISION)
+            .unwrap_or(0)
+    }
+}
+
+impl Sealed for StakeAccount {}
+impl IsInitialized for StakeAccount {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for StakeAccount {
+    const LEN: usize = 97;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 97];
+        let (is_initialized, pool, owner, amount, reward_debt, pending_rewards) =
+            array_refs![src, 1, 32, 32, 8, 16, 8];
+        Ok(StakeAccount {
+            is_initialized: unpack_bool(is_initialized)?,
+            pool: Pubkey::new_from_array(*pool),
+            owner: Pubkey::new_from_array(*owner),
+            amount: u64::from_le_bytes(*amount),
+            reward_debt: u128::from_le_bytes(*reward_debt),
+            pending_rewards: u64::from_le_bytes(*pending_rewards),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 97];
+        let (is_initialized, pool, owner, amount, reward_debt, pending_rewards) =
+            mut_array_refs![dst, 1, 32, 32, 8, 16, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        pool.copy_from_slice(self.pool.as_ref());
+        owner.copy_from_slice(self.owner.as_ref());
+        *amount = self.amount.to_le_bytes();
+        *reward_debt = self.reward_debt.to_le_bytes();
+        *pending_rewards = self.pending_rewards.to_le_bytes();
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool() -> Pool {
+        Pool {
+            is_initialized: true,
+            admin: Pubkey::new_unique(),
+            stake_vault: Pubkey::new_unique(),
+            reward_vault: Pubkey::new_unique(),
+            reward_rate: 100,
+            reward_per_share: 0,
+            last_update_slot: 10,
+            total_staked: 0,
+            bump: 254,
+        }
+    }
+
+    #[test]
+    fn test_pack_unpack() {
+        let pool = Pool {
+            reward_per_share: u128::MAX - 1,
+            total_staked: 7,
+            ..pool()
+        };
+        let mut packed = vec![0u8; Pool::LEN];
+        Pool::pack(pool, &mut packed).unwrap();
+        assert_eq!(Pool::unpack(&packed), Ok(pool));
+
+        let stake = StakeAccount {
+            is_initialized: true,
+            pool: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            amount: 5,
+            reward_debt: u128::MAX,
+            pending_rewards: 3,
+        };
+        let mut packed = vec![0u8; StakeAccount::LEN];
+        StakeAccount::pack(stake, &mut packed).unwrap();
+        assert_eq!(StakeAccount::unpack(&packed), Ok(stake));
+    }
+
+    #[test]
+    fn test_rewards_split_by_share() {
+        let mut pool = pool();
+        let mut alice = StakeAccount::default();
+        let mut bob = StakeAccount::default();
+
+        // Nobody staked for the first 10 slots, so those rewards go nowhere.
+        pool.accrue(20);
+        alice.settle(&pool);
+        alice.set_amount(100, &pool);
+        pool.total_staked = 100;
+
+        // Alice alone for 10 slots: 1_000.
+        pool.accrue(30);
+        bob.settle(&pool);
+        bob.set_amount(300, &pool);
+        pool.total_staked = 400;
+
+        // Then a quarter and three quarters of 100 per slot for 4 slots.
+        pool.accrue(34);
+        alice.settle(&pool);
+        bob.settle(&pool);
+        assert_eq!(alice.pending_rewards, 1_100);
+        assert_eq!(bob.pending_rewards, 300);
+
+        // Settling twice at the same slot pays nothing more.
+        pool.accrue(34);
+        alice.settle(&pool);
+        assert_eq!(alice.pending_rewards, 1_100);
+
+        // Unstaking stops the accrual of that share.
+        alice.set_amount(0, &pool);
+        pool.total_staked = 300;
+        pool.accrue(40);
+        alice.settle(&pool);
+        bob.settle(&pool);
+        assert_eq!(alice.pending_rewards, 1_100);
+        assert_eq!(bob.pending_rewards, 900);
+    }
+}
@@ -0,0 +1,348 @@
+use solana_bank::{instruction::mint_to, state::Account, test_utils::program_test::Env};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use solana_staking::{
+    error::StakingError,
+    instruction::{claim_rewards, initialize_pool, pool_authority, stake, unstake},
+    processor::Processor,
+    state::{Pool, StakeAccount},
+};
+
+/// Slot the pool opens at, and the rewards it pays per slot.
+const OPEN: u64 = 100;
+const REWARD_RATE: u64 = 10;
+
+/// A staker with 1_000 tokens in `source`, an empty `rewards` account in the same bank and
+/// a stake account for the pool.
+struct Staker {
+    owner: Keypair,
+    source: Pubkey,
+    rewards: Pubkey,
+    stake: Pubkey,
+}
+
+/// A pool staking and paying out tokens of a single bank.
+struct Fixture {
+    env: Env,
+    pool: Pubkey,
+    stake_vault: Pubkey,
+    reward_vault: Pubkey,
+    bank: Pubkey,
+}
+
+impl Fixture {
+    /// Opens the pool at slot `OPEN` with `funded` reward tokens in its vault.
+    async fn new(funded: u64) -> Fixture {
+        let mut env = Env::start("solana_staking", processor!(Processor::process)).await;
+        let bank = env.create_bank().await;
+        let pool = Keypair::new();
+        let stake_vault = Keypair::new();
+        let reward_vault = Keypair::new();
+        let staking_program_id = env.program_id;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&pool, Pool::LEN, &staking_program_id)
+            .await;
+        env.create_account(&stake_vault, Account::LEN, &bank_program_id)
+            .await;
+        env.create_account(&reward_vault, Account::LEN, &bank_program_id)
+            .await;
+
+        env.set_slot(OPEN).await;
+        let payer = env.context.payer.pubkey();
+        let instructions = [
+            initialize_pool(
+                &staking_program_id,
+                &bank_program_id,
+                &payer,
+                &pool.pubkey(),
+                &stake_vault.pubkey(),
+                &reward_vault.pubkey(),
+                &bank,
+                &bank,
+                REWARD_RATE,
+            )
+            .unwrap(),
+            mint_to(
+                &bank_program_id,
+                &bank,
+                &reward_vault.pubkey(),
+                &payer,
+                funded,
+            )
+            .unwrap(),
+        ];
+        env.process(&instructions, &[]).await.unwrap();
+
+        Fixture {
+            env,
+            pool: pool.pubkey(),
+            stake_vault: stake_vault.pubkey(),
+            reward_vault: reward_vault.pubkey(),
+            bank,
+        }
+    }
+
+    async fn staker(&mut self) -> Staker {
+        let owner = Keypair::new();
+        let bank = self.bank;
+        let source = self.env.create_bank_account(&bank, &owner, 1_000).await;
+        let rewards = self.env.create_bank_account(&bank, &owner, 0).await;
+        let stake = Keypair::new();
+        let staking_program_id = self.env.program_id;
+        self.env
+            .create_account(&stake, StakeAccount::LEN, &staking_program_id)
+            .await;
+        Staker {
+            owner,
+            source,
+            rewards,
+            stake: stake.pubkey(),
+        }
+    }
+
+    /// Stakes `amount` of `staker`'s source at `slot`.
+    async fn stake(&mut self, staker: &Staker, slot: u64, amount: u64) {
+        let instruction = stake(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &staker.owner.pubkey(),
+            &self.pool,
+            &staker.stake,
+            &staker.source,
+            &self.stake_vault,
+            amount,
+        )
+        .unwrap();
+        self.env.set_slot(slot).await;
+        self.env
+            .process(&[instruction], &[&staker.owner])
+            .await
+            .unwrap();
+    }
+
+    fn unstake(&self, staker: &Staker, amount: u64) -> Instruction {
+        unstake(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &staker.owner.pubkey(),
+            &self.pool,
+            &staker.stake,
+            &self.stake_vault,
+            &staker.source,
+            amount,
+        )
+        .unwrap()
+    }
+
+    fn claim(&self, staker: &Staker, destination: &Pubkey) -> Instruction {
+        claim_rewards(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &staker.owner.pubkey(),
+            &self.pool,
+            &staker.stake,
+            &self.reward_vault,
+            destination,
+        )
+        .unwrap()
+    }
+}
+
+#[tokio::test]
+async fn test_stake_and_claim() {
+    let mut fixture = Fixture::new(10_000).await;
+    let alice = fixture.staker().await;
+    fixture.stake(&alice, OPEN, 100).await;
+    let instruction = fixture.claim(&alice, &alice.rewards);
+    let env = &mut fixture.env;
+
+    let pool = env.unpack::<Pool>(&fixture.pool).await;
+    assert_eq!(pool.total_staked, 100);
+    assert_eq!(pool.last_update_slot, OPEN);
+    let vault = env
+        .context
+        .banks_client
+        .get_account(fixture.stake_vault)
+        .await
+        .unwrap()
+        .unwrap();
+    let vault = Account::unpack(&vault.data).unwrap();
+    let (authority, _) = pool_authority(&env.program_id, &fixture.pool);
+    assert_eq!(vault.owner, authority);
+    assert_eq!(vault.amount, 100);
+    assert_eq!(env.amount(&alice.source).await, 900);
+
+    env.set_slot(OPEN + 10).await;
+    env.process(&[instruction], &[&alice.owner]).await.unwrap();
+    assert_eq!(env.amount(&alice.rewards).await, 100);
+    assert_eq!(env.amount(&fixture.reward_vault).await, 9_900);
+    let stake = env.unpack::<StakeAccount>(&alice.stake).await;
+    assert_eq!(stake.owner, alice.owner.pubkey());
+    assert_eq!(stake.amount, 100);
+    assert_eq!(stake.pending_rewards, 0);
+}
+
+#[tokio::test]
+async fn test_rewards_split_between_stakers() {
+    let mut fixture = Fixture::new(10_000).await;
+    let alice = fixture.staker().await;
+    let bob = fixture.staker().await;
+    fixture.stake(&alice, OPEN, 100).await;
+    fixture.stake(&bob, OPEN + 20, 300).await;
+    let alice_claim = fixture.claim(&alice, &alice.rewards);
+    let bob_claim = fixture.claim(&bob, &bob.rewards);
+    let env = &mut fixture.env;
+
+    // Alice earns all of the first 20 slots, then a quarter of the next 20.
+    env.set_slot(OPEN + 40).await;
+    env.process(&[alice_claim, bob_claim], &[&alice.owner, &bob.owner])
+        .await
+        .unwrap();
+    assert_eq!(env.amount(&alice.rewards).await, 250);
+    assert_eq!(env.amount(&bob.rewards).await, 150);
+    assert_eq!(env.unpack::<Pool>(&fixture.pool).await.total_staked, 400);
+}
+
+#[tokio::test]
+async fn test_unstake() {
+    let mut fixture = Fixture::new(10_000).await;
+    let alice = fixture.staker().await;
+    fixture.stake(&alice, OPEN, 100).await;
+    let partial = fixture.unstake(&alice, 50);
+    let too_much = fixture.unstake(&alice, 51);
+    let instruction = fixture.claim(&alice, &alice.rewards);
+    let env = &mut fixture.env;
+
+    env.set_slot(OPEN + 10).await;
+    env.process(&[partial], &[&alice.owner]).await.unwrap();
+    assert_eq!(env.amount(&alice.source).await, 950);
+    assert_eq!(env.amount(&fixture.stake_vault).await, 50);
+    assert_eq!(
+        env.process(&[too_much], &[&alice.owner]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(StakingError::InsufficientStake as u32)
+        ))
+    );
+
+    // Rewards earned on the full stake survive the unstake; alone in the pool, the
+    // smaller stake still earns the whole rate.
+    env.set_slot(OPEN + 20).await;
+    env.process(&[instruction], &[&alice.owner]).await.unwrap();
+    assert_eq!(env.amount(&alice.rewards).await, 200);
+    assert_eq!(env.unpack::<Pool>(&fixture.pool).await.total_staked, 50);
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new(10_000).await;
+    let alice = fixture.staker().await;
+    let staking_program_id = fixture.env.program_id;
+    let fake_bank_program_id = fixture.env.fake_bank_program_id;
+    let wrong_vault = Err(TransactionError::InstructionError(
+        0,
+        InstructionError::InvalidArgument,
+    ));
+
+    // Through a program posing as the bank, a stake would count without any tokens moving
+    // into the vault.
+    let instruction = stake(
+        &staking_program_id,
+        &fake_bank_program_id,
+        &fixture.bank,
+        &alice.owner.pubkey(),
+        &fixture.pool,
+        &alice.stake,
+        &alice.source,
+        &fixture.stake_vault,
+        100,
+    )
+    .unwrap();
+    fixture.env.set_slot(OPEN).await;
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&alice.owner]).await,
+        wrong_vault
+    );
+    let pool = fixture.pool;
+    assert_eq!(fixture.env.unpack::<Pool>(&pool).await.total_staked, 0);
+
+    // Nor does the pool authority sign for it.
+    fixture.stake(&alice, OPEN, 100).await;
+    let instruction = unstake(
+        &staking_program_id,
+        &fake_bank_program_id,
+        &fixture.bank,
+        &alice.owner.pubkey(),
+        &fixture.pool,
+        &alice.stake,
+        &fixture.stake_vault,
+        &alice.source,
+        100,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&alice.owner]).await,
+        wrong_vault
+    );
+    assert_eq!(fixture.env.unpack::<Pool>(&pool).await.total_staked, 100);
+}
+
+#[tokio::test]
+async fn test_claim_limited_by_funding() {
+    let mut fixture = Fixture::new(50).await;
+    let alice = fixture.staker().await;
+    fixture.stake(&alice, OPEN, 100).await;
+    let claim = fixture.claim(&alice, &alice.rewards);
+    let claim_rest = fixture.claim(&alice, &alice.rewards);
+    let claim_to_source = fixture.claim(&alice, &alice.source);
+    let top_up = mint_to(
+        &fixture.env.bank_program_id,
+        &fixture.bank,
+        &fixture.reward_vault,
+        &fixture.env.context.payer.pubkey(),
+        1_000,
+    )
+    .unwrap();
+    let env = &mut fixture.env;
+
+    env.set_slot(OPEN + 10).await;
+    env.process(&[claim], &[&alice.owner]).await.unwrap();
+    assert_eq!(env.amount(&alice.rewards).await, 50);
+    assert_eq!(
+        env.unpack::<StakeAccount>(&alice.stake)
+            .await
+            .pending_rewards,
+        50
+    );
+
+    assert_eq!(
+        env.process(&[claim_to_source], &[&alice.owner]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(StakingError::NothingToClaim as u32)
+        ))
+    );
+
+    // Once the vault is topped up the rest of what's owed comes out.
+    env.process(&[top_up, claim_rest], &[&alice.owner])
+        .await
+        .unwrap();
+    assert_eq!(env.amount(&alice.rewards).await, 100);
+    assert_eq!(
+        env.unpack::<StakeAccount>(&alice.stake)
+            .await
+            .pending_rewards,
+        0
+    );
+}
@@ -0,0 +1,31 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StreamError {
+    /// The vault authority isn't the stream's program address.
+    InvalidVaultAuthority,
+    /// The account isn't the stream's vault.
+    InvalidVault,
+    /// The stream must start before it ends.
+    InvalidSchedule,
+    /// The rate must be above zero, and the deposit it adds up to fit a u64.
+    InvalidRate,
+    /// Nothing has accrued since the last withdrawal.
+    NothingToWithdraw,
+    /// The stream has run its course; there is nothing left to cancel.
+    StreamEnded,
+    /// The stream has already been canceled.
+    AlreadyCanceled,
+    /// The signer is neither the stream's payer nor its recipient.
+    NotParty,
+    /// The refund account isn't owned by the payer.
+    InvalidRefundAccount,
+    /// The recipient's account isn't owned by the recipient.
+    InvalidRecipientAccount,
+}
+
+impl From<StreamError> for ProgramError {
+    fn from(e: StreamError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
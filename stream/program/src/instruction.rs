@@ -0,0 +1,242 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the vault authority, followed by the stream's address.
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"stream";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StreamInstruction {
+    /// Opens a stream paying `rate` a second from `start_ts` to `end_ts`: initializes the
+    /// vault in the source account's bank, owned by the vault authority, and locks the
+    /// whole deposit in it.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The payer.
+    ///   1. `[writable]` The stream, owned by this program.
+    ///   2. `[writable]` The vault, an uninitialized account owned by the bank program.
+    ///   3. `[writable]` The payer's source account.
+    ///   4. `[writable]` The bank of the source account.
+    ///   5. `[]` The recipient.
+    ///   6. `[writable]` The vault authority.
+    ///   7. `[]` The bank program, owner of the vault.
+    Create {
+        rate: u64,
+        start_ts: i64,
+        end_ts: i64,
+    },
+
+    /// Moves everything accrued and not yet withdrawn from the vault to the recipient's
+    /// destination account.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The recipient.
+    ///   1. `[writable]` The stream.
+    ///   2. `[writable]` The vault.
+    ///   3. `[writable]` The destination account.
+    ///   4. `[writable]` The vault authority.
+    ///   5. `[]` The stream's bank.
+    ///   6. `[]` The bank program, owner of the vault.
+    Withdraw,
+
+    /// Stops the stream: pays the recipient what has accrued and not been withdrawn, and
+    /// refunds the payer the rest.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The payer or the recipient.
+    ///   1. `[writable]` The stream.
+    ///   2. `[writable]` The vault.
+    ///   3. `[writable]` The payer's refund account.
+    ///   4. `[writable]` The recipient's account.
+    ///   5. `[writable]` The vault authority.
+    ///   6. `[]` The stream's bank.
+    ///   7. `[]` The bank program, owner of the vault.
+    Cancel,
+}
+
+impl StreamInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (rate, rest) = Self::unpack_u64(rest)?;
+                let (start_ts, rest) = Self::unpack_u64(rest)?;
+                let (end_ts, _rest) = Self::unpack_u64(rest)?;
+                Self::Create {
+                    rate,
+                    start_ts: start_ts as i64,
+                    end_ts: end_ts as i64,
+                }
+            }
+            1 => Self::Withdraw,
+            2 => Self::Cancel,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(25);
+        match *self {
+            Self::Create {
+                rate,
+                start_ts,
+                end_ts,
+            } => {
+                buf.push(0);
+                buf.extend_from_slice(&rate.to_le_bytes());
+                buf.extend_from_slice(&start_ts.to_le_bytes());
+                buf.extend_from_slice(&end_ts.to_le_bytes());
+            }
+            Self::Withdraw => buf.push(1),
+            Self::Cancel => buf.push(2),
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of the authority owning `stream`'s vault.
+pub fn vault_authority(stream_program_id: &Pubkey, stream: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED, stream.as_ref()], stream_program_id)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_stream(
+    stream_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    payer: &Pubkey,
+    stream: &Pubkey,
+    vault: &Pubkey,
+    source_account: &Pubkey,
+    bank: &Pubkey,
+    recipient: &Pubkey,
+    rate: u64,
+    start_ts: i64,
+    end_ts: i64,
+) -> Result<Instruction, ProgramError> {
+    let data = StreamInstruction::Create {
+        rate,
+        start_ts,
+        end_ts,
+    }
+    .pack();
+    let (authority, _) = vault_authority(stream_program_id, stream);
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(*stream, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new_readonly(*recipient, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *stream_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn withdraw(
+    stream_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    recipient: &Pubkey,
+    stream: &Pubkey,
+    vault: &Pubkey,
+    destination_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = StreamInstruction::Withdraw.pack();
+    let (authority, _) = vault_authority(stream_program_id, stream);
+    let accounts = vec![
+        AccountMeta::new_readonly(*recipient, true),
+        AccountMeta::new(*stream, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *stream_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn cancel(
+    stream_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    signer: &Pubkey,
+    stream: &Pubkey,
+    vault: &Pubkey,
+    refund_account: &Pubkey,
+    recipient_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = StreamInstruction::Cancel.pack();
+    let (authority, _) = vault_authority(stream_program_id, stream);
+    let accounts = vec![
+        AccountMeta::new_readonly(*signer, true),
+        AccountMeta::new(*stream, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*refund_account, false),
+        AccountMeta::new(*recipient_account, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *stream_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            StreamInstruction::Create {
+                rate: 7,
+                start_ts: -1,
+                end_ts: i64::MAX,
+            },
+            StreamInstruction::Withdraw,
+            StreamInstruction::Cancel,
+        ];
+        for instruction in cases {
+            assert_eq!(
+                StreamInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            StreamInstruction::unpack(&[0, 1, 0, 0, 0, 0, 0, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            StreamInstruction::unpack(&[3]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
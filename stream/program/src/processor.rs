@@ -0,0 +1,292 @@
+use crate::{
+    error::StreamError,
+    instruction::{StreamInstruction, VAULT_AUTHORITY_SEED},
+    state::Stream,
+};
+use solana_bank::{instruction as bank_instruction, state::Account as BankAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = StreamInstruction::unpack(input)?;
+
+        match instruction {
+            StreamInstruction::Create {
+                rate,
+                start_ts,
+                end_ts,
+            } => {
+                msg!("Instruction: Create");
+                Self::process_create(program_id, accounts, rate, start_ts, end_ts)
+            }
+            StreamInstruction::Withdraw => {
+                msg!("Instruction: Withdraw");
+                Self::process_withdraw(program_id, accounts)
+            }
+            StreamInstruction::Cancel => {
+                msg!("Instruction: Cancel");
+                Self::process_cancel(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_create(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        rate: u64,
+        start_ts: i64,
+        end_ts: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let payer_info = next_account_info(account_info_iter)?;
+        let stream_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let recipient_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !payer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if stream_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if start_ts >= end_ts {
+            return Err(StreamError::InvalidSchedule.into());
+        }
+        let deposit = match Stream::deposit(rate, start_ts, end_ts) {
+            Some(deposit) if deposit > 0 => deposit,
+            _ => return Err(StreamError::InvalidRate.into()),
+        };
+        let mut stream = Stream::unpack_unchecked(&stream_info.data.borrow())?;
+        if stream.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        let (authority, bump) = Pubkey::find_program_address(
+            &[VAULT_AUTHORITY_SEED, stream_info.key.as_ref()],
+            program_id,
+        );
+        if authority != *authority_info.key {
+            return Err(StreamError::InvalidVaultAuthority.into());
+        }
+        if vault_info.owner != bank_program_info.key {
+            return Err(StreamError::InvalidVault.into());
+        }
+
+        invoke_signed(
+            &bank_instruction::initialize_account(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                authority_info.key,
+            )?,
+            &[
+                bank_info.clone(),
+                vault_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[VAULT_AUTHORITY_SEED, stream_info.key.as_ref(), &[bump]]],
+        )?;
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                vault_info.key,
+                payer_info.key,
+                deposit,
+            )?,
+            &[
+                source_info.clone(),
+                vault_info.clone(),
+                payer_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        stream.is_initialized = true;
+        stream.payer = *payer_info.key;
+        stream.recipient = *recipient_info.key;
+        stream.vault = *vault_info.key;
+        stream.rate = rate;
+        stream.start_ts = start_ts;
+        stream.end_ts = end_ts;
+        stream.bump = bump;
+        Stream::pack(stream, &mut stream_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_withdraw(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let recipient_info = next_account_info(account_info_iter)?;
+        let stream_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !recipient_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut stream = Self::load_stream(program_id, stream_info, vault_info, bank_program_info)?;
+        if stream.recipient != *recipient_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        let amount = stream.withdrawable(Clock::get()?.unix_timestamp);
+        if amount == 0 {
+            return Err(StreamError::NothingToWithdraw.into());
+        }
+        Self::pay_out(
+            program_id,
+            stream_info,
+            &stream,
+            vault_info,
+            destination_info,
+            authority_info,
+            bank_info,
+            bank_program_info,
+            amount,
+        )?;
+
+        stream.withdrawn += amount;
+        Stream::pack(stream, &mut stream_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_cancel(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let signer_info = next_account_info(account_info_iter)?;
+        let stream_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let refund_info = next_account_info(account_info_iter)?;
+        let recipient_account_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !signer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut stream = Self::load_stream(program_id, stream_info, vault_info, bank_program_info)?;
+        if *signer_info.key != stream.payer && *signer_info.key != stream.recipient {
+            return Err(StreamError::NotParty.into());
+        }
+        // Either side can cancel, so neither gets to pick where the other's share goes.
+        if BankAccount::unpack(&refund_info.data.borrow())?.owner != stream.payer {
+            return Err(StreamError::InvalidRefundAccount.into());
+        }
+        if BankAccount::unpack(&recipient_account_info.data.borrow())?.owner != stream.recipient {
+            return Err(StreamError::InvalidRecipientAccount.into());
+        }
+        if stream.canceled {
+            return Err(StreamError::AlreadyCanceled.into());
+        }
+        let now = Clock::get()?.unix_timestamp;
+        if now >= stream.end_ts {
+            return Err(StreamError::StreamEnded.into());
+        }
+
+        let due = stream.withdrawable(now);
+        let deposit = Stream::deposit(stream.rate, stream.start_ts, stream.end_ts)
+            .ok_or(StreamError::InvalidRate)?;
+        let refund = deposit - stream.accrued(now);
+        for (destination_info, amount) in
+            [(recipient_account_info, due), (refund_info, refund)].iter()
+        {
+            if *amount > 0 {
+                Self::pay_out(
+                    program_id,
+                    stream_info,
+                    &stream,
+                    vault_info,
+                    destination_info,
+                    authority_info,
+                    bank_info,
+                    bank_program_info,
+                    *amount,
+                )?;
+            }
+        }
+
+        stream.withdrawn += due;
+        stream.end_ts = now.max(stream.start_ts);
+        stream.canceled = true;
+        Stream::pack(stream, &mut stream_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    /// Loads the stream, checking `vault_info` is its vault, held in the bank program the
+    /// vault authority signs for.
+    fn load_stream(
+        program_id: &Pubkey,
+        stream_info: &AccountInfo,
+        vault_info: &AccountInfo,
+        bank_program_info: &AccountInfo,
+    ) -> Result<Stream, ProgramError> {
+        if stream_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let stream = Stream::unpack(&stream_info.data.borrow())?;
+        if stream.vault != *vault_info.key || vault_info.owner != bank_program_info.key {
+            return Err(StreamError::InvalidVault.into());
+        }
+        Ok(stream)
+    }
+
+    /// Transfers `amount` out of the vault, signed by the vault authority.
+    #[allow(clippy::too_many_arguments)]
+    fn pay_out<'a>(
+        program_id: &Pubkey,
+        stream_info: &AccountInfo<'a>,
+        stream: &Stream,
+        vault_info: &AccountInfo<'a>,
+        destination_info: &AccountInfo<'a>,
+        authority_info: &AccountInfo<'a>,
+        bank_info: &AccountInfo<'a>,
+        bank_program_info: &AccountInfo<'a>,
+        amount: u64,
+    ) -> ProgramResult {
+        let bump = [stream.bump];
+        let signer_seeds: &[&[u8]] = &[VAULT_AUTHORITY_SEED, stream_info.key.as_ref(), &bump];
+        let authority = Pubkey::create_program_address(signer_seeds, program_id)
+            .map_err(|_| ProgramError::from(StreamError::InvalidVaultAuthority))?;
+        if authority != *authority_info.key {
+            return Err(StreamError::InvalidVaultAuthority.into());
+        }
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                destination_info.key,
+                authority_info.key,
+                amount,
+            )?,
+            &[
+                vault_info.clone(),
+                destination_info.clone(),
+                authority_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )
+    }
+}
@@ -0,0 +1,179 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Tokens locked in `vault` by `payer`, accruing to `recipient` at `rate` per second from
+/// `start_ts` until `end_ts`. Canceling moves `end_ts` back to the time of the cancel,
+/// so what accrued until then stays the recipient's and the rest goes back to the payer.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Stream {
+    pub is_initialized: bool,
+    pub payer: Pubkey,
+    pub recipient: Pubkey,
+    /// Bank account holding the tokens not yet withdrawn, owned by the vault authority.
+    pub vault: Pubkey,
+    pub rate: u64,
+    /// Unix timestamps the stream runs between.
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub withdrawn: u64,
+    pub canceled: bool,
+    /// Bump seed of the vault authority address.
+    pub bump: u8,
+}
+
+impl Stream {
+    /// Tokens a stream of `rate` from `start_ts` to `end_ts` locks up. None when the
+    /// schedule is empty or the total doesn't fit a u64.
+    pub fn deposit(rate: u64, start_ts: i64, end_ts: i64) -> Option<u64> {
+        if start_ts >= end_ts {
+            return None;
+        }
+        rate.checked_mul(end_ts.checked_sub(start_ts)? as u64)
+    }
+
+    /// Amount accrued to the recipient by `now`, withdrawn or not.
+    pub fn accrued(&self, now: i64) -> u64 {
+        let elapsed = now.clamp(self.start_ts, self.end_ts) - self.start_ts;
+        // The deposit was checked to fit at creation, and elapsed never exceeds its span.
+        self.rate * elapsed as u64
+    }
+
+    /// Amount the recipient can withdraw at `now`.
+    pub fn withdrawable(&self, now: i64) -> u64 {
+        self.accrued(now).saturating_sub(self.withdrawn)
+    }
+}
+
+impl Sealed for Stream {}
+impl IsInitialized for Stream {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Stream {
+    const LEN: usize = 131;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 131];
+        let (
+            is_initialized,
+            payer,
+            recipient,
+            vault,
+            rate,
+            start_ts,
+            end_ts,
+            withdrawn,
+            canceled,
+            bump,
+        ) = array_refs![src, 1, 32, 32, 32, 8, 8, 8, 8, 1, 1];
+        Ok(Stream {
+            is_initialized: unpack_bool(is_initialized)?,
+            payer: Pubkey::new_from_array(*payer),
+            recipient: Pubkey::new_from_array(*recipient),
+            vault: Pubkey::new_from_array(*vault),
+            rate: u64::from_le_bytes(*rate),
+            start_ts: i64::from_le_bytes(*start_ts),
+            end_ts: i64::from_le_bytes(*end_ts),
+            withdrawn: u64::from_le_bytes(*withdrawn),
+            canceled: unpack_bool(canceled)?,
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 131];
+        let (
+            is_initialized,
+            payer,
+            recipient,
+            vault,
+            rate,
+            start_ts,
+            end_ts,
+            withdrawn,
+            canceled,
+            bump,
+        ) = mut_array_refs![dst, 1, 32, 32, 32, 8, 8, 8, 8, 1, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        payer.copy_from_slice(self.payer.as_ref());
+        recipient.copy_from_slice(self.recipient.as_ref());
+        vault.copy_from_slice(self.vault.as_ref());
+        *rate = self.rate.to_le_bytes();
+        *start_ts = self.start_ts.to_le_bytes();
+        *end_ts = self.end_ts.to_le_bytes();
+        *withdrawn = self.withdrawn.to_le_bytes();
+        canceled[0] = self.canceled as u8;
+        bump[0] = self.bump;
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let stream = Stream {
+            is_initialized: true,
+            payer: Pubkey::new_unique(),
+            recipient: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            rate: 3,
+            start_ts: -10,
+            end_ts: 90,
+            withdrawn: 30,
+            canceled: true,
+            bump: 253,
+        };
+        let mut packed = vec![0u8; Stream::LEN];
+        Stream::pack(stream, &mut packed).unwrap();
+        assert_eq!(Stream::unpack(&packed), Ok(stream));
+
+        packed[129] = 2;
+        assert_eq!(
+            Stream::unpack(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_deposit() {
+        assert_eq!(Stream::deposit(5, 100, 110), Some(50));
+        assert_eq!(Stream::deposit(5, 110, 110), None);
+        assert_eq!(Stream::deposit(5, 110, 100), None);
+        assert_eq!(Stream::deposit(u64::MAX, 0, 2), None);
+        assert_eq!(Stream::deposit(1, i64::MIN, i64::MAX), None);
+    }
+
+    #[test]
+    fn test_accrued() {
+        let stream = Stream {
+            rate: 5,
+            start_ts: 100,
+            end_ts: 110,
+            withdrawn: 20,
+            ..Stream::default()
+        };
+        let accrued: Vec<u64> = [0, 100, 101, 104, 110, 1_000]
+            .iter()
+            .map(|now| stream.accrued(*now))
+            .collect();
+        assert_eq!(accrued, vec![0, 0, 5, 20, 50, 50]);
+        assert_eq!(stream.withdrawable(103), 0);
+        assert_eq!(stream.withdrawable(105), 5);
+        assert_eq!(stream.withdrawable(i64::MAX), 30);
+    }
+}
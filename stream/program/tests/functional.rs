@@ -0,0 +1,355 @@
+use solana_bank::{state::Account, test_utils::program_test::Env};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use solana_stream::{
+    error::StreamError,
+    instruction::{cancel, create_stream, vault_authority, withdraw},
+    processor::Processor,
+    state::Stream,
+};
+
+/// The stream pays RATE a second from START to END, 1_000 in all.
+const START: i64 = 1_000;
+const END: i64 = 1_100;
+const RATE: u64 = 10;
+
+/// A stream from `payer` to `recipient`, each with an account of the streamed bank.
+struct Fixture {
+    env: Env,
+    bank: Pubkey,
+    payer: Keypair,
+    payer_account: Pubkey,
+    recipient: Keypair,
+    recipient_account: Pubkey,
+    stream: Pubkey,
+    vault: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_stream", processor!(Processor::process)).await;
+        env.set_time(START - 10);
+        let bank = env.create_bank().await;
+        let (payer, recipient) = (Keypair::new(), Keypair::new());
+        let payer_account = env.create_bank_account(&bank, &payer, 5_000).await;
+        let recipient_account = env.create_bank_account(&bank, &recipient, 0).await;
+        let (stream, vault) = (Keypair::new(), Keypair::new());
+        let stream_program_id = env.program_id;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&stream, Stream::LEN, &stream_program_id)
+            .await;
+        env.create_account(&vault, Account::LEN, &bank_program_id)
+            .await;
+        let instruction = create_stream(
+            &stream_program_id,
+            &bank_program_id,
+            &payer.pubkey(),
+            &stream.pubkey(),
+            &vault.pubkey(),
+            &payer_account,
+            &bank,
+            &recipient.pubkey(),
+            RATE,
+            START,
+            END,
+        )
+        .unwrap();
+        env.process(&[instruction], &[&payer]).await.unwrap();
+        Fixture {
+            env,
+            bank,
+            payer,
+            payer_account,
+            recipient,
+            recipient_account,
+            stream: stream.pubkey(),
+            vault: vault.pubkey(),
+        }
+    }
+
+    /// Opens another empty account of the recipient.
+    async fn destination(&mut self) -> Pubkey {
+        let bank = self.bank;
+        let recipient = Keypair::from_bytes(&self.recipient.to_bytes()).unwrap();
+        self.env.create_bank_account(&bank, &recipient, 0).await
+    }
+
+    async fn withdraw(&mut self, destination: &Pubkey) -> Result<(), TransactionError> {
+        let instruction = withdraw(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &self.recipient.pubkey(),
+            &self.stream,
+            &self.vault,
+            destination,
+        )
+        .unwrap();
+        let recipient = Keypair::from_bytes(&self.recipient.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&recipient]).await
+    }
+
+    async fn cancel(&mut self, signer: &Keypair) -> Result<(), TransactionError> {
+        let instruction = cancel(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &signer.pubkey(),
+            &self.stream,
+            &self.vault,
+            &self.payer_account,
+            &self.recipient_account,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[signer]).await
+    }
+
+    async fn stream(&mut self) -> Stream {
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(self.stream)
+            .await
+            .unwrap()
+            .unwrap();
+        Stream::unpack(&account.data).unwrap()
+    }
+
+    /// Balances of the payer's account, the recipient's account and the vault.
+    async fn balances(&mut self) -> (u64, u64, u64) {
+        let (payer_account, recipient_account, vault) =
+            (self.payer_account, self.recipient_account, self.vault);
+        (
+            self.env.amount(&payer_account).await,
+            self.env.amount(&recipient_account).await,
+            self.env.amount(&vault).await,
+        )
+    }
+}
+
+fn custom(error: StreamError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_stream() {
+    let mut fixture = Fixture::new().await;
+    assert_eq!(fixture.balances().await, (4_000, 0, 1_000));
+    let (authority, _) = vault_authority(&fixture.env.program_id, &fixture.stream);
+    let vault = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(fixture.vault)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Account::unpack(&vault.data).unwrap().owner, authority);
+    let stream = fixture.stream().await;
+    assert_eq!(
+        (stream.payer, stream.recipient, stream.rate),
+        (fixture.payer.pubkey(), fixture.recipient.pubkey(), RATE)
+    );
+
+    let stream_program_id = fixture.env.program_id;
+    let bank_program_id = fixture.env.bank_program_id;
+    let (stream, vault) = (Keypair::new(), Keypair::new());
+    fixture
+        .env
+        .create_account(&stream, Stream::LEN, &stream_program_id)
+        .await;
+    fixture
+        .env
+        .create_account(&vault, Account::LEN, &bank_program_id)
+        .await;
+    let bank = Account::unpack(
+        &fixture
+            .env
+            .context
+            .banks_client
+            .get_account(fixture.payer_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data,
+    )
+    .unwrap()
+    .bank;
+    let payer = &fixture.payer;
+    let payer_account = fixture.payer_account;
+    let recipient = fixture.recipient.pubkey();
+    let create = |rate, start_ts, end_ts| {
+        create_stream(
+            &stream_program_id,
+            &bank_program_id,
+            &payer.pubkey(),
+            &stream.pubkey(),
+            &vault.pubkey(),
+            &payer_account,
+            &bank,
+            &recipient,
+            rate,
+            start_ts,
+            end_ts,
+        )
+        .unwrap()
+    };
+    let (backwards, free, overflowing) = (
+        create(RATE, END, START),
+        create(0, START, END),
+        create(u64::MAX, START, END),
+    );
+    assert_eq!(
+        fixture.env.process(&[backwards], &[payer]).await,
+        custom(StreamError::InvalidSchedule)
+    );
+    assert_eq!(
+        fixture.env.process(&[free], &[payer]).await,
+        custom(StreamError::InvalidRate)
+    );
+    assert_eq!(
+        fixture.env.process(&[overflowing], &[payer]).await,
+        custom(StreamError::InvalidRate)
+    );
+}
+
+#[tokio::test]
+async fn test_withdraw() {
+    let mut fixture = Fixture::new().await;
+    let recipient_account = fixture.recipient_account;
+    assert_eq!(
+        fixture.withdraw(&recipient_account).await,
+        custom(StreamError::NothingToWithdraw)
+    );
+
+    let destinations = [
+        fixture.destination().await,
+        fixture.destination().await,
+        fixture.destination().await,
+    ];
+    fixture.env.set_time(START + 25);
+    fixture.withdraw(&destinations[0]).await.unwrap();
+    // Only what accrued since is left to withdraw.
+    fixture.env.set_time(START + 30);
+    fixture.withdraw(&destinations[1]).await.unwrap();
+    assert_eq!(fixture.stream().await.withdrawn, 300);
+    // Nothing accrues past the end.
+    fixture.env.set_time(END + 1_000);
+    fixture.withdraw(&destinations[2]).await.unwrap();
+    for (destination, amount) in destinations.iter().zip([250, 50, 700].iter()) {
+        assert_eq!(fixture.env.amount(destination).await, *amount);
+    }
+    let vault = fixture.vault;
+    assert_eq!(fixture.env.amount(&vault).await, 0);
+    assert_eq!(
+        fixture.withdraw(&recipient_account).await,
+        custom(StreamError::NothingToWithdraw)
+    );
+}
+
+#[tokio::test]
+async fn test_cancel() {
+    let mut fixture = Fixture::new().await;
+    let destination = fixture.destination().await;
+    fixture.env.set_time(START + 20);
+    fixture.withdraw(&destination).await.unwrap();
+
+    let outsider = Keypair::new();
+    assert_eq!(
+        fixture.cancel(&outsider).await,
+        custom(StreamError::NotParty)
+    );
+
+    // The recipient gets what accrued since the withdrawal, the payer the rest.
+    fixture.env.set_time(START + 60);
+    let payer = Keypair::from_bytes(&fixture.payer.to_bytes()).unwrap();
+    fixture.cancel(&payer).await.unwrap();
+    assert_eq!(fixture.balances().await, (4_400, 400, 0));
+    let stream = fixture.stream().await;
+    assert!(stream.canceled);
+    assert_eq!((stream.end_ts, stream.withdrawn), (START + 60, 600));
+
+    fixture.env.set_time(START + 70);
+    assert_eq!(
+        fixture.withdraw(&fixture.recipient_account.clone()).await,
+        custom(StreamError::NothingToWithdraw)
+    );
+    let recipient = Keypair::from_bytes(&fixture.recipient.to_bytes()).unwrap();
+    assert_eq!(
+        fixture.cancel(&recipient).await,
+        custom(StreamError::AlreadyCanceled)
+    );
+}
+
+#[tokio::test]
+async fn test_cancel_by_recipient() {
+    let mut fixture = Fixture::new().await;
+    let recipient = Keypair::from_bytes(&fixture.recipient.to_bytes()).unwrap();
+
+    // The payer's share can't be sent anywhere but the payer's account.
+    let instruction = cancel(
+        &fixture.env.program_id,
+        &fixture.env.bank_program_id,
+        &fixture.bank,
+        &recipient.pubkey(),
+        &fixture.stream,
+        &fixture.vault,
+        &fixture.recipient_account,
+        &fixture.recipient_account,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&recipient]).await,
+        custom(StreamError::InvalidRefundAccount)
+    );
+
+    // Canceled before it starts, the whole deposit goes back.
+    fixture.cancel(&recipient).await.unwrap();
+    assert_eq!(fixture.balances().await, (5_000, 0, 0));
+    assert_eq!(fixture.stream().await.end_ts, START);
+}
+
+#[tokio::test]
+async fn test_cancel_after_end() {
+    let mut fixture = Fixture::new().await;
+    fixture.env.set_time(END);
+    let payer = Keypair::from_bytes(&fixture.payer.to_bytes()).unwrap();
+    assert_eq!(
+        fixture.cancel(&payer).await,
+        custom(StreamError::StreamEnded)
+    );
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    fixture.env.set_time(START + 25);
+
+    // Through a program posing as the bank, a cancel would hand it the vault authority's
+    // signature, and settle the stream without paying either side.
+    let instruction = cancel(
+        &fixture.env.program_id,
+        &fixture.env.fake_bank_program_id,
+        &fixture.bank,
+        &fixture.payer.pubkey(),
+        &fixture.stream,
+        &fixture.vault,
+        &fixture.payer_account,
+        &fixture.recipient_account,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&fixture.payer]).await,
+        custom(StreamError::InvalidVault)
+    );
+    assert!(!fixture.stream().await.canceled);
+    assert_eq!(fixture.balances().await, (4_000, 0, 1_000));
+}
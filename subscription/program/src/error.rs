@@ -0,0 +1,27 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SubscriptionError {
+    /// The subscription isn't the program address of the source account in the plan, or
+    /// isn't open.
+    InvalidSubscription,
+    /// The source isn't an account of the plan's bank owned by the subscriber, or isn't
+    /// the subscription's.
+    InvalidSource,
+    /// The destination isn't the plan's.
+    InvalidDestination,
+    /// The price and the billing period must be above zero.
+    InvalidSchedule,
+    /// This period's payment has already been collected.
+    NotDue,
+    /// The subscription's allowance on the source account can't cover the price.
+    AllowanceExhausted,
+    /// The signer isn't the subscription's subscriber.
+    NotSubscriber,
+}
+
+impl From<SubscriptionError> for ProgramError {
+    fn from(e: SubscriptionError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,251 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of a subscription, followed by the plan's and the source account's addresses.
+pub const SUBSCRIPTION_SEED: &[u8] = b"subscription";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SubscriptionInstruction {
+    /// Offers a plan billing `amount` every `period` seconds, paid into the destination.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The merchant.
+    ///   1. `[writable]` The plan, an uninitialized account owned by this program.
+    ///   2. `[]` The destination, a bank account of the bank the plan is priced in.
+    CreatePlan { amount: u64, period: i64 },
+
+    /// Subscribes the source account to a plan, approving the subscription as its delegate
+    /// for `periods` times the plan's price. The first period can be collected right away.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The subscriber, owner of the source account and paying for
+    ///      the subscription account.
+    ///   1. `[]` The plan.
+    ///   2. `[writable]` The subscription, the program address of the source account.
+    ///   3. `[writable]` The source account.
+    ///   4. `[]` The source account's bank.
+    ///   5. `[]` The bank program, owner of the source account.
+    ///   6. `[]` The system program.
+    Subscribe { periods: u64 },
+
+    /// Pulls the plan's price from the source account into the plan's destination, at
+    /// most once per period. Anyone can run it.
+    ///
+    /// Accounts expected:
+    ///   0. `[]` The plan.
+    ///   1. `[writable]` The subscription.
+    ///   2. `[writable]` The source account.
+    ///   3. `[writable]` The destination.
+    ///   4. `[]` The plan's bank.
+    ///   5. `[]` The bank program, owner of the source account.
+    Collect,
+
+    /// Ends a subscription, revoking what is left of its allowance and closing its account.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The subscriber, credited the subscription account's rent.
+    ///   1. `[writable]` The subscription.
+    ///   2. `[writable]` The source account.
+    ///   3. `[]` The source account's bank.
+    ///   4. `[]` The bank program, owner of the source account.
+    Cancel,
+}
+
+impl SubscriptionInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (amount, rest) = Self::unpack_u64(rest)?;
+                let (period, _rest) = Self::unpack_u64(rest)?;
+                Self::CreatePlan {
+                    amount,
+                    period: period as i64,
+                }
+            }
+            1 => {
+                let (periods, _rest) = Self::unpack_u64(rest)?;
+                Self::Subscribe { periods }
+            }
+            2 => Self::Collect,
+            3 => Self::Cancel,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(17);
+        match *self {
+            Self::CreatePlan { amount, period } => {
+                buf.push(0);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(&period.to_le_bytes());
+            }
+            Self::Subscribe { periods } => {
+                buf.push(1);
+                buf.extend_from_slice(&periods.to_le_bytes());
+            }
+            Self::Collect => buf.push(2),
+            Self::Cancel => buf.push(3),
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of the subscription paying `plan` from `source`.
+pub fn subscription_address(
+    subscription_program_id: &Pubkey,
+    plan: &Pubkey,
+    source: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SUBSCRIPTION_SEED, plan.as_ref(), source.as_ref()],
+        subscription_program_id,
+    )
+}
+
+pub fn create_plan(
+    subscription_program_id: &Pubkey,
+    merchant: &Pubkey,
+    plan: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+    period: i64,
+) -> Result<Instruction, ProgramError> {
+    let data = SubscriptionInstruction::CreatePlan { amount, period }.pack();
+    let accounts = vec![
+        AccountMeta::new_readonly(*merchant, true),
+        AccountMeta::new(*plan, false),
+        AccountMeta::new_readonly(*destination, false),
+    ];
+    Ok(Instruction {
+        program_id: *subscription_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn subscribe(
+    subscription_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    subscriber: &Pubkey,
+    plan: &Pubkey,
+    source: &Pubkey,
+    periods: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = SubscriptionInstruction::Subscribe { periods }.pack();
+    let (subscription, _) = subscription_address(subscription_program_id, plan, source);
+    let accounts = vec![
+        AccountMeta::new(*subscriber, true),
+        AccountMeta::new_readonly(*plan, false),
+        AccountMeta::new(subscription, false),
+        AccountMeta::new(*source, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *subscription_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn collect(
+    subscription_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    plan: &Pubkey,
+    source: &Pubkey,
+    destination: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = SubscriptionInstruction::Collect.pack();
+    let (subscription, _) = subscription_address(subscription_program_id, plan, source);
+    let accounts = vec![
+        AccountMeta::new_readonly(*plan, false),
+        AccountMeta::new(subscription, false),
+        AccountMeta::new(*source, false),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *subscription_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn cancel(
+    subscription_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    subscriber: &Pubkey,
+    plan: &Pubkey,
+    source: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = SubscriptionInstruction::Cancel.pack();
+    let (subscription, _) = subscription_address(subscription_program_id, plan, source);
+    let accounts = vec![
+        AccountMeta::new(*subscriber, true),
+        AccountMeta::new(subscription, false),
+        AccountMeta::new(*source, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *subscription_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            SubscriptionInstruction::CreatePlan {
+                amount: 999,
+                period: 2_592_000,
+            },
+            SubscriptionInstruction::Subscribe { periods: 12 },
+            SubscriptionInstruction::Collect,
+            SubscriptionInstruction::Cancel,
+        ];
+        for instruction in cases {
+            assert_eq!(
+                SubscriptionInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            SubscriptionInstruction::unpack(&[1, 0, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            SubscriptionInstruction::unpack(&[4]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use crate::{
+    error::SubscriptionError,
+    instruction::{SubscriptionInstruction, SUBSCRIPTION_SEED},
+    state::{Plan, Subscription},
+};
+use solana_bank::{instruction as bank_instruction, state::Account as BankAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = SubscriptionInstruction::unpack(input)?;
+
+        match instruction {
+            SubscriptionInstruction::CreatePlan { amount, period } => {
+                msg!("Instruction: CreatePlan");
+                Self::process_create_plan(program_id, accounts, amount, period)
+            }
+            SubscriptionInstruction::Subscribe { periods } => {
+                msg!("Instruction: Subscribe");
+                Self::process_subscribe(program_id, accounts, periods)
+            }
+            SubscriptionInstruction::Collect => {
+                msg!("Instruction: Collect");
+                Self::process_collect(program_id, accounts)
+            }
+            SubscriptionInstruction::Cancel => {
+                msg!("Instruction: Cancel");
+                Self::process_cancel(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_create_plan(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+        period: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let merchant_info = next_account_info(account_info_iter)?;
+        let plan_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+
+        if !merchant_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if plan_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut plan = Plan::unpack_unchecked(&plan_info.data.borrow())?;
+        if plan.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if amount == 0 || period <= 0 {
+            return Err(SubscriptionError::InvalidSchedule.into());
+        }
+        let destination = BankAccount::unpack(&destination_info.data.borrow())?;
+
+        plan.is_initialized = true;
+        plan.merchant = *merchant_info.key;
+        plan.bank = destination.bank;
+        plan.destination = *destination_info.key;
+        plan.amount = amount;
+        plan.period = period;
+        Plan::pack(plan, &mut plan_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_subscribe(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        periods: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let subscriber_info = next_account_info(account_info_iter)?;
+        let plan_info = next_account_info(account_info_iter)?;
+        let subscription_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !subscriber_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let plan = Self::load_plan(program_id, plan_info)?;
+        if periods == 0 {
+            return Err(SubscriptionError::InvalidSchedule.into());
+        }
+        if source_info.owner != bank_program_info.key {
+            return Err(SubscriptionError::InvalidSource.into());
+        }
+        let source = BankAccount::unpack(&source_info.data.borrow())?;
+        if source.bank != plan.bank || source.owner != *subscriber_info.key {
+            return Err(SubscriptionError::InvalidSource.into());
+        }
+        let (subscription, bump) = Pubkey::find_program_address(
+            &[
+                SUBSCRIPTION_SEED,
+                plan_info.key.as_ref(),
+                source_info.key.as_ref(),
+            ],
+            program_id,
+        );
+        if subscription != *subscription_info.key {
+            return Err(SubscriptionError::InvalidSubscription.into());
+        }
+        if !subscription_info.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        let allowance = plan
+            .amount
+            .checked_mul(periods)
+            .ok_or(ProgramError::InvalidArgument)?;
+
+        invoke_signed(
+            &system_instruction::create_account(
+                subscriber_info.key,
+                subscription_info.key,
+                Rent::get()?.minimum_balance(Subscription::LEN),
+                Subscription::LEN as u64,
+                program_id,
+            ),
+            &[
+                subscriber_info.clone(),
+                subscription_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[&[
+                SUBSCRIPTION_SEED,
+                plan_info.key.as_ref(),
+                source_info.key.as_ref(),
+                &[bump],
+            ]],
+        )?;
+        invoke(
+            &bank_instruction::approve(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                subscription_info.key,
+                subscriber_info.key,
+                allowance,
+            )?,
+            &[
+                source_info.clone(),
+                subscription_info.clone(),
+                subscriber_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        let subscription = Subscription {
+            is_initialized: true,
+            plan: *plan_info.key,
+            subscriber: *subscriber_info.key,
+            source: *source_info.key,
+            next_charge_ts: Clock::get()?.unix_timestamp,
+            bump,
+        };
+        Subscription::pack(subscription, &mut subscription_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_collect(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let plan_info = next_account_info(account_info_iter)?;
+        let subscription_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        let plan = Self::load_plan(program_id, plan_info)?;
+        if plan.destination != *destination_info.key {
+            return Err(SubscriptionError::InvalidDestination.into());
+        }
+        let mut subscription = Self::load_subscription(program_id, subscription_info)?;
+        if subscription.plan != *plan_info.key {
+            return Err(SubscriptionError::InvalidSubscription.into());
+        }
+        // Anyone can collect, so the bank program is pinned to the source's owner: any
+        // other program would take the subscription's signature as delegate, and the
+        // period would be marked paid without paying.
+        if subscription.source != *source_info.key || source_info.owner != bank_program_info.key {
+            return Err(SubscriptionError::InvalidSource.into());
+        }
+        let now = Clock::get()?.unix_timestamp;
+        if now < subscription.next_charge_ts {
+            return Err(SubscriptionError::NotDue.into());
+        }
+        let source = BankAccount::unpack(&source_info.data.borrow())?;
+        if source.delegate != COption::Some(*subscription_info.key)
+            || source.delegated_amount < plan.amount
+        {
+            return Err(SubscriptionError::AllowanceExhausted.into());
+        }
+
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                destination_info.key,
+                subscription_info.key,
+                plan.amount,
+            )?,
+            &[
+                source_info.clone(),
+                destination_info.clone(),
+                subscription_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[
+                SUBSCRIPTION_SEED,
+                plan_info.key.as_ref(),
+                source_info.key.as_ref(),
+                &[subscription.bump],
+            ]],
+        )?;
+
+        subscription.next_charge_ts = subscription.next_charge_after(now, plan.period);
+        Subscription::pack(subscription, &mut subscription_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_cancel(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let subscriber_info = next_account_info(account_info_iter)?;
+        let subscription_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !subscriber_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let subscription = Self::load_subscription(program_id, subscription_info)?;
+        if subscription.subscriber != *subscriber_info.key {
+            return Err(SubscriptionError::NotSubscriber.into());
+        }
+        if subscription.source != *source_info.key || source_info.owner != bank_program_info.key {
+            return Err(SubscriptionError::InvalidSource.into());
+        }
+
+        // The subscriber may have handed the account to another delegate since; only
+        // our own allowance is ours to revoke.
+        let source = BankAccount::unpack(&source_info.data.borrow())?;
+        if source.delegate == COption::Some(*subscription_info.key) {
+            invoke(
+                &bank_instruction::revoke(
+                    bank_program_info.key,
+                    bank_info.key,
+                    source_info.key,
+                    subscriber_info.key,
+                )?,
+                &[
+                    source_info.clone(),
+                    subscriber_info.clone(),
+                    bank_info.clone(),
+                    bank_program_info.clone(),
+                ],
+            )?;
+        }
+
+        let lamports = subscription_info.lamports();
+        **subscription_info.lamports.borrow_mut() = 0;
+        **subscriber_info.lamports.borrow_mut() = subscriber_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(ProgramError::InvalidArgument)?;
+        subscription_info.data.borrow_mut().fill(0);
+        Ok(())
+    }
+
+    fn load_plan(program_id: &Pubkey, plan_info: &AccountInfo) -> Result<Plan, ProgramError> {
+        if plan_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Plan::unpack(&plan_info.data.borrow())
+    }
+
+    fn load_subscription(
+        program_id: &Pubkey,
+        subscription_info: &AccountInfo,
+    ) -> Result<Subscription, ProgramError> {
+        if subscription_info.owner != program_id {
+            return Err(SubscriptionError::InvalidSubscription.into());
+        }
+        let subscription = Subscription::unpack_unchecked(&subscription_info.data.borrow())?;
+        if !subscription.is_initialized {
+            return Err(SubscriptionError::InvalidSubscription.into());
+        }
+        Ok(subscription)
+    }
+}
@@ -0,0 +1,172 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// A merchant's offer of `amount` tokens of `bank` every `period` seconds, paid into
+/// `destination`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Plan {
+    pub is_initialized: bool,
+    pub merchant: Pubkey,
+    pub bank: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub period: i64,
+}
+
+impl Sealed for Plan {}
+impl IsInitialized for Plan {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Plan {
+    const LEN: usize = 113;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 113];
+        let (is_initialized, merchant, bank, destination, amount, period) =
+            array_refs![src, 1, 32, 32, 32, 8, 8];
+        Ok(Plan {
+            is_initialized: unpack_bool(is_initialized)?,
+            merchant: Pubkey::new_from_array(*merchant),
+            bank: Pubkey::new_from_array(*bank),
+            destination: Pubkey::new_from_array(*destination),
+            amount: u64::from_le_bytes(*amount),
+            period: i64::from_le_bytes(*period),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 113];
+        let (is_initialized, merchant, bank, destination, amount, period) =
+            mut_array_refs![dst, 1, 32, 32, 32, 8, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        merchant.copy_from_slice(self.merchant.as_ref());
+        bank.copy_from_slice(self.bank.as_ref());
+        destination.copy_from_slice(self.destination.as_ref());
+        *amount = self.amount.to_le_bytes();
+        *period = self.period.to_le_bytes();
+    }
+}
+
+/// `subscriber`'s subscription to `plan`, paid from `source`. The subscription's address
+/// is the delegate of `source`, so what it can ever collect is capped by what the
+/// subscriber approved.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Subscription {
+    pub is_initialized: bool,
+    pub plan: Pubkey,
+    pub subscriber: Pubkey,
+    pub source: Pubkey,
+    /// Start of the first period not paid for yet.
+    pub next_charge_ts: i64,
+    /// Bump seed of the subscription's address.
+    pub bump: u8,
+}
+
+impl Subscription {
+    /// Start of the period after the one `now` falls in, once that one is paid for.
+    /// Periods nobody collected in are skipped rather than charged later.
+    pub fn next_charge_after(&self, now: i64, period: i64) -> i64 {
+        self.next_charge_ts + ((now - self.next_charge_ts) / period + 1) * period
+    }
+}
+
+impl Sealed for Subscription {}
+impl IsInitialized for Subscription {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Subscription {
+    const LEN: usize = 106;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 106];
+        let (is_initialized, plan, subscriber, source, next_charge_ts, bump) =
+            array_refs![src, 1, 32, 32, 32, 8, 1];
+        Ok(Subscription {
+            is_initialized: unpack_bool(is_initialized)?,
+            plan: Pubkey::new_from_array(*plan),
+            subscriber: Pubkey::new_from_array(*subscriber),
+            source: Pubkey::new_from_array(*source),
+            next_charge_ts: i64::from_le_bytes(*next_charge_ts),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 106];
+        let (is_initialized, plan, subscriber, source, next_charge_ts, bump) =
+            mut_array_refs![dst, 1, 32, 32, 32, 8, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        plan.copy_from_slice(self.plan.as_ref());
+        subscriber.copy_from_slice(self.subscriber.as_ref());
+        source.copy_from_slice(self.source.as_ref());
+        *next_charge_ts = self.next_charge_ts.to_le_bytes();
+        bump[0] = self.bump;
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let plan = Plan {
+            is_initialized: true,
+            merchant: Pubkey::new_unique(),
+            bank: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            amount: 999,
+            period: 30 * 24 * 60 * 60,
+        };
+        let mut packed = vec![0u8; Plan::LEN];
+        Plan::pack(plan, &mut packed).unwrap();
+        assert_eq!(Plan::unpack(&packed), Ok(plan));
+
+        let subscription = Subscription {
+            is_initialized: true,
+            plan: Pubkey::new_unique(),
+            subscriber: Pubkey::new_unique(),
+            source: Pubkey::new_unique(),
+            next_charge_ts: -7,
+            bump: 254,
+        };
+        let mut packed = vec![0u8; Subscription::LEN];
+        Subscription::pack(subscription, &mut packed).unwrap();
+        assert_eq!(Subscription::unpack(&packed), Ok(subscription));
+
+        packed[0] = 2;
+        assert_eq!(
+            Subscription::unpack_unchecked(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_next_charge_after() {
+        let subscription = Subscription {
+            next_charge_ts: 1_000,
+            ..Subscription::default()
+        };
+        let next: Vec<i64> = [1_000, 1_099, 1_100, 1_350]
+            .iter()
+            .map(|now| subscription.next_charge_after(*now, 100))
+            .collect();
+        assert_eq!(next, vec![1_100, 1_100, 1_200, 1_400]);
+    }
+}
@@ -0,0 +1,330 @@
+use solana_bank::{state::Account, test_utils::program_test::Env};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use solana_subscription::{
+    error::SubscriptionError,
+    instruction::{cancel, collect, create_plan, subscribe, subscription_address},
+    processor::Processor,
+    state::{Plan, Subscription},
+};
+
+/// Time subscriptions start at.
+const OPEN: i64 = 1_000;
+
+/// Someone holding 1_000 tokens of the plan's bank, and lamports for a subscription.
+struct Subscriber {
+    owner: Keypair,
+    source: Pubkey,
+}
+
+/// A plan billing 100 tokens every 100 seconds.
+struct Fixture {
+    env: Env,
+    bank: Pubkey,
+    plan: Pubkey,
+    destination: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_subscription", processor!(Processor::process)).await;
+        env.set_time(OPEN);
+        let bank = env.create_bank().await;
+        let merchant = Keypair::new();
+        let destination = env.create_bank_account(&bank, &merchant, 0).await;
+        let plan = Keypair::new();
+        let subscription_program_id = env.program_id;
+        env.create_account(&plan, Plan::LEN, &subscription_program_id)
+            .await;
+        let instruction = create_plan(
+            &subscription_program_id,
+            &merchant.pubkey(),
+            &plan.pubkey(),
+            &destination,
+            100,
+            100,
+        )
+        .unwrap();
+        env.process(&[instruction], &[&merchant]).await.unwrap();
+        Fixture {
+            env,
+            bank,
+            plan: plan.pubkey(),
+            destination,
+        }
+    }
+
+    async fn subscriber(&mut self) -> Subscriber {
+        let owner = Keypair::new();
+        let bank = self.bank;
+        let source = self.env.create_bank_account(&bank, &owner, 1_000).await;
+        self.env.fund(&owner.pubkey(), 10_000_000).await;
+        Subscriber { owner, source }
+    }
+
+    async fn subscribe(
+        &mut self,
+        subscriber: &Subscriber,
+        periods: u64,
+    ) -> Result<(), TransactionError> {
+        let instruction = subscribe(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &subscriber.owner.pubkey(),
+            &self.plan,
+            &subscriber.source,
+            periods,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[&subscriber.owner]).await
+    }
+
+    /// Collects from each of `subscribers` in turn, in a single transaction.
+    async fn collect(&mut self, subscribers: &[&Subscriber]) -> Result<(), TransactionError> {
+        let instructions: Vec<Instruction> = subscribers
+            .iter()
+            .map(|subscriber| {
+                collect(
+                    &self.env.program_id,
+                    &self.env.bank_program_id,
+                    &self.bank,
+                    &self.plan,
+                    &subscriber.source,
+                    &self.destination,
+                )
+                .unwrap()
+            })
+            .collect();
+        self.env.process(&instructions, &[]).await
+    }
+
+    async fn cancel(
+        &mut self,
+        signer: &Keypair,
+        subscriber: &Subscriber,
+    ) -> Result<(), TransactionError> {
+        let instruction = cancel(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &signer.pubkey(),
+            &self.plan,
+            &subscriber.source,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[signer]).await
+    }
+
+    async fn subscription(&mut self, subscriber: &Subscriber) -> Option<Subscription> {
+        let (subscription, _) =
+            subscription_address(&self.env.program_id, &self.plan, &subscriber.source);
+        self.env
+            .context
+            .banks_client
+            .get_account(subscription)
+            .await
+            .unwrap()
+            .map(|account| Subscription::unpack(&account.data).unwrap())
+    }
+
+    async fn collected(&mut self) -> u64 {
+        let destination = self.destination;
+        self.env.amount(&destination).await
+    }
+}
+
+fn custom(index: u8, error: SubscriptionError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        index,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_plan() {
+    let mut fixture = Fixture::new().await;
+    let account = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(fixture.plan)
+        .await
+        .unwrap()
+        .unwrap();
+    let plan = Plan::unpack(&account.data).unwrap();
+    assert_eq!(plan.bank, fixture.bank);
+    assert_eq!(plan.destination, fixture.destination);
+    assert_eq!((plan.amount, plan.period), (100, 100));
+
+    let (merchant, plan) = (Keypair::new(), Keypair::new());
+    let subscription_program_id = fixture.env.program_id;
+    fixture
+        .env
+        .create_account(&plan, Plan::LEN, &subscription_program_id)
+        .await;
+    let instruction = create_plan(
+        &subscription_program_id,
+        &merchant.pubkey(),
+        &plan.pubkey(),
+        &fixture.destination,
+        100,
+        0,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&merchant]).await,
+        custom(0, SubscriptionError::InvalidSchedule)
+    );
+}
+
+#[tokio::test]
+async fn test_subscribe() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.subscriber().await;
+    fixture.subscribe(&alice, 3).await.unwrap();
+    let (subscription, _) =
+        subscription_address(&fixture.env.program_id, &fixture.plan, &alice.source);
+    let source = fixture.env.unpack::<Account>(&alice.source).await;
+    assert_eq!(source.amount, 1_000);
+    assert_eq!(source.delegate, COption::Some(subscription));
+    assert_eq!(source.delegated_amount, 300);
+    let state = fixture.subscription(&alice).await.unwrap();
+    assert_eq!(state.plan, fixture.plan);
+    assert_eq!(state.subscriber, alice.owner.pubkey());
+    assert_eq!(state.next_charge_ts, OPEN);
+    assert_eq!(
+        fixture.subscribe(&alice, 1).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::AccountAlreadyInitialized
+        ))
+    );
+
+    // Only the owner of the source account can subscribe it.
+    let bob = fixture.subscriber().await;
+    let mallory = Subscriber {
+        owner: Keypair::from_bytes(&bob.owner.to_bytes()).unwrap(),
+        source: alice.source,
+    };
+    assert_eq!(
+        fixture.subscribe(&mallory, 2).await,
+        custom(0, SubscriptionError::InvalidSource)
+    );
+    assert_eq!(
+        fixture.subscribe(&bob, 0).await,
+        custom(0, SubscriptionError::InvalidSchedule)
+    );
+}
+
+#[tokio::test]
+async fn test_collect() {
+    let mut fixture = Fixture::new().await;
+    let (alice, bob, carol) = (
+        fixture.subscriber().await,
+        fixture.subscriber().await,
+        fixture.subscriber().await,
+    );
+    fixture.subscribe(&alice, 3).await.unwrap();
+    fixture.subscribe(&bob, 1).await.unwrap();
+    fixture.subscribe(&carol, 3).await.unwrap();
+
+    fixture.collect(&[&alice]).await.unwrap();
+    assert_eq!(fixture.collected().await, 100);
+    // A period is only paid for once, however often the crank runs.
+    assert_eq!(
+        fixture.collect(&[&bob, &alice]).await,
+        custom(1, SubscriptionError::NotDue)
+    );
+    fixture.collect(&[&bob]).await.unwrap();
+    assert_eq!(fixture.collected().await, 200);
+
+    // Bob only approved one period.
+    fixture.env.set_time(OPEN + 250);
+    assert_eq!(
+        fixture.collect(&[&alice, &bob]).await,
+        custom(1, SubscriptionError::AllowanceExhausted)
+    );
+
+    // Periods nobody collected in are skipped, not charged later.
+    fixture.collect(&[&carol, &alice]).await.unwrap();
+    assert_eq!(fixture.collected().await, 400);
+    assert_eq!(
+        fixture.subscription(&alice).await.unwrap().next_charge_ts,
+        OPEN + 300
+    );
+    assert_eq!(
+        fixture.subscription(&carol).await.unwrap().next_charge_ts,
+        OPEN + 300
+    );
+    let source = fixture.env.unpack::<Account>(&alice.source).await;
+    assert_eq!((source.amount, source.delegated_amount), (800, 100));
+}
+
+#[tokio::test]
+async fn test_cancel() {
+    let mut fixture = Fixture::new().await;
+    let (alice, bob) = (fixture.subscriber().await, fixture.subscriber().await);
+    fixture.subscribe(&alice, 3).await.unwrap();
+    fixture.subscribe(&bob, 3).await.unwrap();
+    fixture.collect(&[&alice]).await.unwrap();
+
+    let bob_owner = Keypair::from_bytes(&bob.owner.to_bytes()).unwrap();
+    assert_eq!(
+        fixture.cancel(&bob_owner, &alice).await,
+        custom(0, SubscriptionError::NotSubscriber)
+    );
+    let alice_owner = Keypair::from_bytes(&alice.owner.to_bytes()).unwrap();
+    fixture.cancel(&alice_owner, &alice).await.unwrap();
+    assert_eq!(fixture.subscription(&alice).await, None);
+    let source = fixture.env.unpack::<Account>(&alice.source).await;
+    assert_eq!(source.amount, 900);
+    assert_eq!(source.delegate, COption::None);
+    assert_eq!(source.delegated_amount, 0);
+
+    fixture.cancel(&bob_owner, &bob).await.unwrap();
+    assert_eq!(
+        fixture.collect(&[&bob]).await,
+        custom(0, SubscriptionError::InvalidSubscription)
+    );
+    assert_eq!(fixture.collected().await, 100);
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.subscriber().await;
+    fixture.subscribe(&alice, 3).await.unwrap();
+
+    // Through a program posing as the bank, anyone could mark alice's period paid without
+    // the merchant being paid.
+    let instruction = collect(
+        &fixture.env.program_id,
+        &fixture.env.fake_bank_program_id,
+        &fixture.bank,
+        &fixture.plan,
+        &alice.source,
+        &fixture.destination,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[]).await,
+        custom(0, SubscriptionError::InvalidSource)
+    );
+    assert_eq!(
+        fixture.subscription(&alice).await.unwrap().next_charge_ts,
+        OPEN
+    );
+
+    fixture.collect(&[&alice]).await.unwrap();
+    assert_eq!(fixture.collected().await, 100);
+}
@@ -0,0 +1,155 @@
+//! Constant-product pricing: a pool holding reserves `x` and `y` only trades so that
+//! `x * y` never shrinks, and rounds every amount in the pool's favor.
+
+use std::convert::TryFrom;
+
+/// Fees are expressed in basis points of the amount swapped in.
+pub const FEE_DENOMINATOR: u64 = 10_000;
+
+/// Tokens paid out of `reserve_out` for `amount_in` paid into `reserve_in`. The fee of
+/// `fee_bps` is kept out of the price and stays in the pool for the LPs. None when either
+/// reserve is empty.
+pub fn swap_output(amount_in: u64, reserve_in: u64, reserve_out: u64, fee_bps: u16) -> Option<u64> {
+    if reserve_in == 0 || reserve_out == 0 || fee_bps as u64 > FEE_DENOMINATOR {
+        return None;
+    }
+    let in_after_fee =
+        amount_in as u128 * (FEE_DENOMINATOR - fee_bps as u64) as u128 / FEE_DENOMINATOR as u128;
+    let out = reserve_out as u128 * in_after_fee / (reserve_in as u128 + in_after_fee);
+    u64::try_from(out).ok()
+}
+
+/// Shares minted for depositing up to `amount_a` and `amount_b` into a pool with
+/// `supply` shares outstanding, and the amounts actually taken. Past the first deposit,
+/// which sets the price, the amounts are taken in the ratio of the reserves.
+pub fn deposit(
+    amount_a: u64,
+    amount_b: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    supply: u64,
+) -> Option<(u64, u64, u64)> {
+    if supply == 0 {
+        let shares = u64::try_from(sqrt(amount_a as u128 * amount_b as u128)).ok()?;
+        return Some((shares, amount_a, amount_b));
+    }
+    if reserve_a == 0 || reserve_b == 0 {
+        return None;
+    }
+    let shares = (amount_a as u128 * supply as u128 / reserve_a as u128)
+        .min(amount_b as u128 * supply as u128 / reserve_b as u128);
+    let used_a = (shares * reserve_a as u128).div_ceil(supply as u128);
+    let used_b = (shares * reserve_b as u128).div_ceil(supply as u128);
+    Some((
+        u64::try_from(shares).ok()?,
+        u64::try_from(used_a).ok()?,
+        u64::try_from(used_b).ok()?,
+    ))
+}
+
+/// Reserves paid out for burning `shares` of `supply`.
+pub fn withdraw(shares: u64, reserve_a: u64, reserve_b: u64, supply: u64) -> Option<(u64, u64)> {
+    if shares > supply {
+        return None;
+    }
+    let amount_a = reserve_a as u128 * shares as u128 / supply as u128;
+    let amount_b = reserve_b as u128 * shares as u128 / supply as u128;
+    Some((amount_a as u64, amount_b as u64))
+}
+
+/// Integer square root, rounded down.
+fn sqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESERVES: [u64; 6] = [1, 7, 1_000, 1_000_003, u32::MAX as u64, u64::MAX / 2];
+    const AMOUNTS: [u64; 6] = [0, 1, 3, 999, 1_000_000_007, u64::MAX / 2];
+
+    #[test]
+    fn test_sqrt() {
+        for n in [0u128, 1, 2, 3, 4, 99, 100, u64::MAX as u128, u128::MAX].iter() {
+            let root = sqrt(*n);
+            assert!(root * root <= *n);
+            if let Some(square) = (root + 1).checked_mul(root + 1) {
+                assert!(square > *n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_swap_output() {
+        assert_eq!(swap_output(100, 1_000, 1_000, 0), Some(90));
+        // 0.3% of 100 is 0.3, so 99 tokens get priced.
+        assert_eq!(swap_output(100, 1_000, 1_000, 30), Some(90));
+        assert_eq!(swap_output(1_000, 1_000, 1_000, 30), Some(499));
+        assert_eq!(swap_output(100, 0, 1_000, 30), None);
+        assert_eq!(swap_output(100, 1_000, 1_000, 10_001), None);
+    }
+
+    #[test]
+    fn test_swap_keeps_invariant() {
+        for &reserve_in in RESERVES.iter() {
+            for &reserve_out in RESERVES.iter() {
+                for &amount_in in AMOUNTS.iter() {
+                    for &fee_bps in [0u16, 30, 10_000].iter() {
+                        let out = swap_output(amount_in, reserve_in, reserve_out, fee_bps).unwrap();
+                        assert!(out < reserve_out);
+                        let before = reserve_in as u128 * reserve_out as u128;
+                        let after =
+                            (reserve_in as u128 + amount_in as u128) * (reserve_out - out) as u128;
+                        assert!(
+                            after >= before,
+                            "{} {} {}",
+                            reserve_in,
+                            reserve_out,
+                            amount_in
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_deposit() {
+        assert_eq!(deposit(100, 400, 0, 0, 0), Some((200, 100, 400)));
+        // The excess of either side is left with the depositor.
+        assert_eq!(deposit(50, 1_000, 100, 400, 200), Some((100, 50, 200)));
+        assert_eq!(deposit(1, 1, 100, 400, 200), Some((0, 0, 0)));
+        assert_eq!(deposit(1, 1, 0, 400, 200), None);
+    }
+
+    #[test]
+    fn test_deposit_and_withdraw_keep_share_value() {
+        for &reserve_a in RESERVES.iter() {
+            for &reserve_b in RESERVES.iter() {
+                let supply = sqrt(reserve_a as u128 * reserve_b as u128) as u64;
+                for &amount in AMOUNTS.iter() {
+                    let (shares, used_a, used_b) =
+                        deposit(amount, amount, reserve_a, reserve_b, supply).unwrap();
+                    assert!(used_a <= amount && used_b <= amount);
+                    // Each share is backed by at least as much as before the deposit.
+                    let (reserve_a, reserve_b) = (reserve_a + used_a, reserve_b + used_b);
+                    let supply = supply + shares;
+                    let (out_a, out_b) = withdraw(shares, reserve_a, reserve_b, supply).unwrap();
+                    assert!(out_a <= used_a && out_b <= used_b);
+                }
+            }
+        }
+        assert_eq!(withdraw(11, 100, 100, 10), None);
+        assert_eq!(withdraw(5, 100, 301, 10), Some((50, 150)));
+    }
+}
@@ -0,0 +1,23 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SwapError {
+    /// The pool authority isn't the pool's program address.
+    InvalidPoolAuthority,
+    /// Both sides of the pool are the same bank.
+    SameBank,
+    /// The fee is more than the whole amount swapped in.
+    InvalidFee,
+    /// The account isn't one of the pool's reserves, or is on the wrong side.
+    InvalidReserve,
+    /// The pool has no liquidity on one side, or the amount is too small to trade.
+    InsufficientLiquidity,
+    /// The trade would return less than the minimum asked for.
+    SlippageExceeded,
+}
+
+impl From<SwapError> for ProgramError {
+    fn from(e: SwapError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,393 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the pool authority, followed by the pool's address.
+pub const POOL_AUTHORITY_SEED: &[u8] = b"pool";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SwapInstruction {
+    /// Opens an empty pool between two banks charging `fee_bps` on swaps: initializes
+    /// both reserves and the LP bank, all owned by the pool authority.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The pool, an uninitialized account owned by this program.
+    ///   1. `[writable]` Reserve A, an uninitialized account owned by the bank program.
+    ///   2. `[writable]` Reserve B, an uninitialized account owned by the bank program.
+    ///   3. `[writable]` Bank A.
+    ///   4. `[writable]` Bank B.
+    ///   5. `[writable]` The LP bank, an uninitialized bank owned by the bank program.
+    ///   6. `[writable]` The pool authority.
+    ///   7. `[]` The bank program, owner of the reserves and the LP bank.
+    InitializePool { fee_bps: u16 },
+
+    /// Moves up to `amount_a` and `amount_b` into the reserves, in their current ratio,
+    /// and mints LP tokens for them. The first deposit sets the price.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The owner of both source accounts.
+    ///   1. `[]` The pool.
+    ///   2. `[writable]` The source account in bank A.
+    ///   3. `[writable]` The source account in bank B.
+    ///   4. `[writable]` Reserve A.
+    ///   5. `[writable]` Reserve B.
+    ///   6. `[writable]` The LP bank.
+    ///   7. `[writable]` The account receiving the LP tokens.
+    ///   8. `[writable]` The pool authority.
+    ///   9. `[]` Bank A.
+    ///   10. `[]` Bank B.
+    ///   11. `[]` The bank program, owner of the reserves and the LP bank.
+    Deposit {
+        amount_a: u64,
+        amount_b: u64,
+        min_shares: u64,
+    },
+
+    /// Burns `shares` LP tokens for their part of both reserves.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The owner of the LP account.
+    ///   1. `[]` The pool.
+    ///   2. `[writable]` The LP account.
+    ///   3. `[writable]` The LP bank.
+    ///   4. `[writable]` Reserve A.
+    ///   5. `[writable]` Reserve B.
+    ///   6. `[writable]` The destination account in bank A.
+    ///   7. `[writable]` The destination account in bank B.
+    ///   8. `[writable]` The pool authority.
+    ///   9. `[]` Bank A.
+    ///   10. `[]` Bank B.
+    ///   11. `[]` The bank program, owner of the reserves and the LP bank.
+    Withdraw {
+        shares: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    },
+
+    /// Trades `amount_in` of one bank's tokens for the other's at the constant-product
+    /// price, less the fee.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The owner of the source account.
+    ///   1. `[]` The pool.
+    ///   2. `[writable]` The source account.
+    ///   3. `[writable]` The destination account, in the other bank.
+    ///   4. `[writable]` The reserve of the source's bank.
+    ///   5. `[writable]` The reserve of the destination's bank.
+    ///   6. `[writable]` The pool authority.
+    ///   7. `[]` The source's bank.
+    ///   8. `[]` The destination's bank.
+    ///   9. `[]` The bank program, owner of the reserves.
+    Swap { amount_in: u64, min_amount_out: u64 },
+}
+
+impl SwapInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let fee_bps = rest
+                    .get(..2)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u16::from_le_bytes)
+                    .ok_or(InvalidInstructionData)?;
+                Self::InitializePool { fee_bps }
+            }
+            1 => {
+                let (amount_a, rest) = Self::unpack_u64(rest)?;
+                let (amount_b, rest) = Self::unpack_u64(rest)?;
+                let (min_shares, _rest) = Self::unpack_u64(rest)?;
+                Self::Deposit {
+                    amount_a,
+                    amount_b,
+                    min_shares,
+                }
+            }
+            2 => {
+                let (shares, rest) = Self::unpack_u64(rest)?;
+                let (min_amount_a, rest) = Self::unpack_u64(rest)?;
+                let (min_amount_b, _rest) = Self::unpack_u64(rest)?;
+                Self::Withdraw {
+                    shares,
+                    min_amount_a,
+                    min_amount_b,
+                }
+            }
+            3 => {
+                let (amount_in, rest) = Self::unpack_u64(rest)?;
+                let (min_amount_out, _rest) = Self::unpack_u64(rest)?;
+                Self::Swap {
+                    amount_in,
+                    min_amount_out,
+                }
+            }
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(25);
+        match *self {
+            Self::InitializePool { fee_bps } => {
+                buf.push(0);
+                buf.extend_from_slice(&fee_bps.to_le_bytes());
+            }
+            Self::Deposit {
+                amount_a,
+                amount_b,
+                min_shares,
+            } => {
+                buf.push(1);
+                buf.extend_from_slice(&amount_a.to_le_bytes());
+                buf.extend_from_slice(&amount_b.to_le_bytes());
+                buf.extend_from_slice(&min_shares.to_le_bytes());
+            }
+            Self::Withdraw {
+                shares,
+                min_amount_a,
+                min_amount_b,
+            } => {
+                buf.push(2);
+                buf.extend_from_slice(&shares.to_le_bytes());
+                buf.extend_from_slice(&min_amount_a.to_le_bytes());
+                buf.extend_from_slice(&min_amount_b.to_le_bytes());
+            }
+            Self::Swap {
+                amount_in,
+                min_amount_out,
+            } => {
+                buf.push(3);
+                buf.extend_from_slice(&amount_in.to_le_bytes());
+                buf.extend_from_slice(&min_amount_out.to_le_bytes());
+            }
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of the authority owning `pool`'s reserves and LP bank.
+pub fn pool_authority(swap_program_id: &Pubkey, pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_AUTHORITY_SEED, pool.as_ref()], swap_program_id)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_pool(
+    swap_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    pool: &Pubkey,
+    reserve_a: &Pubkey,
+    reserve_b: &Pubkey,
+    bank_a: &Pubkey,
+    bank_b: &Pubkey,
+    lp_bank: &Pubkey,
+    fee_bps: u16,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::InitializePool { fee_bps }.pack();
+    let (authority, _) = pool_authority(swap_program_id, pool);
+    let accounts = vec![
+        AccountMeta::new(*pool, false),
+        AccountMeta::new(*reserve_a, false),
+        AccountMeta::new(*reserve_b, false),
+        AccountMeta::new(*bank_a, false),
+        AccountMeta::new(*bank_b, false),
+        AccountMeta::new(*lp_bank, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *swap_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn deposit(
+    swap_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank_a: &Pubkey,
+    bank_b: &Pubkey,
+    owner: &Pubkey,
+    pool: &Pubkey,
+    source_a: &Pubkey,
+    source_b: &Pubkey,
+    reserve_a: &Pubkey,
+    reserve_b: &Pubkey,
+    lp_bank: &Pubkey,
+    lp_account: &Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+    min_shares: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::Deposit {
+        amount_a,
+        amount_b,
+        min_shares,
+    }
+    .pack();
+    let (authority, _) = pool_authority(swap_program_id, pool);
+    let accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new_readonly(*pool, false),
+        AccountMeta::new(*source_a, false),
+        AccountMeta::new(*source_b, false),
+        AccountMeta::new(*reserve_a, false),
+        AccountMeta::new(*reserve_b, false),
+        AccountMeta::new(*lp_bank, false),
+        AccountMeta::new(*lp_account, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_a, false),
+        AccountMeta::new_readonly(*bank_b, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *swap_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw(
+    swap_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank_a: &Pubkey,
+    bank_b: &Pubkey,
+    owner: &Pubkey,
+    pool: &Pubkey,
+    lp_account: &Pubkey,
+    lp_bank: &Pubkey,
+    reserve_a: &Pubkey,
+    reserve_b: &Pubkey,
+    destination_a: &Pubkey,
+    destination_b: &Pubkey,
+    shares: u64,
+    min_amount_a: u64,
+    min_amount_b: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::Withdraw {
+        shares,
+        min_amount_a,
+        min_amount_b,
+    }
+    .pack();
+    let (authority, _) = pool_authority(swap_program_id, pool);
+    let accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new_readonly(*pool, false),
+        AccountMeta::new(*lp_account, false),
+        AccountMeta::new(*lp_bank, false),
+        AccountMeta::new(*reserve_a, false),
+        AccountMeta::new(*reserve_b, false),
+        AccountMeta::new(*destination_a, false),
+        AccountMeta::new(*destination_b, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_a, false),
+        AccountMeta::new_readonly(*bank_b, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *swap_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn swap(
+    swap_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    source_bank: &Pubkey,
+    destination_bank: &Pubkey,
+    owner: &Pubkey,
+    pool: &Pubkey,
+    source_account: &Pubkey,
+    destination_account: &Pubkey,
+    reserve_in: &Pubkey,
+    reserve_out: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::Swap {
+        amount_in,
+        min_amount_out,
+    }
+    .pack();
+    let (authority, _) = pool_authority(swap_program_id, pool);
+    let accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new_readonly(*pool, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new(*reserve_in, false),
+        AccountMeta::new(*reserve_out, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*source_bank, false),
+        AccountMeta::new_readonly(*destination_bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *swap_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            SwapInstruction::InitializePool { fee_bps: 30 },
+            SwapInstruction::Deposit {
+                amount_a: 1,
+                amount_b: 2,
+                min_shares: u64::MAX,
+            },
+            SwapInstruction::Withdraw {
+                shares: 3,
+                min_amount_a: 4,
+                min_amount_b: 5,
+            },
+            SwapInstruction::Swap {
+                amount_in: 6,
+                min_amount_out: 7,
+            },
+        ];
+        for instruction in cases {
+            assert_eq!(
+                SwapInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            SwapInstruction::unpack(&[0, 30]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            SwapInstruction::unpack(&[3, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            SwapInstruction::unpack(&[4]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,10 @@
+pub mod curve;
+pub mod error;
+pub mod instruction;
+pub mod processor;
+pub mod state;
+
+#[cfg(not(feature = "no-entrypoint"))]
+mod entrypoint;
+
+pub use solana_program;
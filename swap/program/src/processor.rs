@@ -0,0 +1,441 @@
+use crate::{
+    curve,
+    error::SwapError,
+    instruction::{SwapInstruction, POOL_AUTHORITY_SEED},
+    state::Pool,
+};
+use solana_bank::{
+    instruction as bank_instruction,
+    state::{Account as BankAccount, Bank},
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = SwapInstruction::unpack(input)?;
+
+        match instruction {
+            SwapInstruction::InitializePool { fee_bps } => {
+                msg!("Instruction: InitializePool");
+                Self::process_initialize_pool(program_id, accounts, fee_bps)
+            }
+            SwapInstruction::Deposit {
+                amount_a,
+                amount_b,
+                min_shares,
+            } => {
+                msg!("Instruction: Deposit");
+                Self::process_deposit(program_id, accounts, amount_a, amount_b, min_shares)
+            }
+            SwapInstruction::Withdraw {
+                shares,
+                min_amount_a,
+                min_amount_b,
+            } => {
+                msg!("Instruction: Withdraw");
+                Self::process_withdraw(program_id, accounts, shares, min_amount_a, min_amount_b)
+            }
+            SwapInstruction::Swap {
+                amount_in,
+                min_amount_out,
+            } => {
+                msg!("Instruction: Swap");
+                Self::process_swap(program_id, accounts, amount_in, min_amount_out)
+            }
+        }
+    }
+
+    pub fn process_initialize_pool(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        fee_bps: u16,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let pool_info = next_account_info(account_info_iter)?;
+        let reserve_a_info = next_account_info(account_info_iter)?;
+        let reserve_b_info = next_account_info(account_info_iter)?;
+        let bank_a_info = next_account_info(account_info_iter)?;
+        let bank_b_info = next_account_info(account_info_iter)?;
+        let lp_bank_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if pool_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut pool = Pool::unpack_unchecked(&pool_info.data.borrow())?;
+        if pool.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if bank_a_info.key == bank_b_info.key {
+            return Err(SwapError::SameBank.into());
+        }
+        if fee_bps as u64 > curve::FEE_DENOMINATOR {
+            return Err(SwapError::InvalidFee.into());
+        }
+        let (authority, bump) = Pubkey::find_program_address(
+            &[POOL_AUTHORITY_SEED, pool_info.key.as_ref()],
+            program_id,
+        );
+        if authority != *authority_info.key {
+            return Err(SwapError::InvalidPoolAuthority.into());
+        }
+        let signer_seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, pool_info.key.as_ref(), &[bump]];
+        // The pool authority signs only for accounts of the bank program.
+        if lp_bank_info.owner != bank_program_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        for (reserve_info, bank_info) in
+            [(reserve_a_info, bank_a_info), (reserve_b_info, bank_b_info)]
+        {
+            if reserve_info.owner != bank_program_info.key {
+                return Err(ProgramError::IllegalOwner);
+            }
+            invoke_signed(
+                &bank_instruction::initialize_account(
+                    bank_program_info.key,
+                    bank_info.key,
+                    reserve_info.key,
+                    authority_info.key,
+                )?,
+                &[
+                    bank_info.clone(),
+                    reserve_info.clone(),
+                    authority_info.clone(),
+                    bank_program_info.clone(),
+                ],
+                &[signer_seeds],
+            )?;
+        }
+        // A share is the geometric mean of the amounts deposited, so it takes the mean
+        // of their decimals.
+        let decimals_a = Bank::unpack(&bank_a_info.data.borrow())?.decimals;
+        let decimals_b = Bank::unpack(&bank_b_info.data.borrow())?.decimals;
+        invoke_signed(
+            &bank_instruction::initialize_bank(
+                bank_program_info.key,
+                lp_bank_info.key,
+                authority_info.key,
+                ((decimals_a as u16 + decimals_b as u16) / 2) as u8,
+            )?,
+            &[
+                lp_bank_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        pool.is_initialized = true;
+        pool.bank_a = *bank_a_info.key;
+        pool.bank_b = *bank_b_info.key;
+        pool.reserve_a = *reserve_a_info.key;
+        pool.reserve_b = *reserve_b_info.key;
+        pool.lp_bank = *lp_bank_info.key;
+        pool.fee_bps = fee_bps;
+        pool.bump = bump;
+        Pool::pack(pool, &mut pool_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_deposit(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount_a: u64,
+        amount_b: u64,
+        min_shares: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let pool_info = next_account_info(account_info_iter)?;
+        let source_a_info = next_account_info(account_info_iter)?;
+        let source_b_info = next_account_info(account_info_iter)?;
+        let reserve_a_info = next_account_info(account_info_iter)?;
+        let reserve_b_info = next_account_info(account_info_iter)?;
+        let lp_bank_info = next_account_info(account_info_iter)?;
+        let lp_account_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_a_info = next_account_info(account_info_iter)?;
+        let bank_b_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let pool = Self::load_pool(program_id, pool_info)?;
+        let reserve_a = Self::reserve_amount(&pool.reserve_a, reserve_a_info, bank_program_info)?;
+        let reserve_b = Self::reserve_amount(&pool.reserve_b, reserve_b_info, bank_program_info)?;
+        let supply = Self::lp_supply(&pool, lp_bank_info, bank_program_info)?;
+        let (shares, used_a, used_b) =
+            curve::deposit(amount_a, amount_b, reserve_a, reserve_b, supply)
+                .ok_or(SwapError::InsufficientLiquidity)?;
+        if shares == 0 {
+            return Err(SwapError::InsufficientLiquidity.into());
+        }
+        if shares < min_shares {
+            return Err(SwapError::SlippageExceeded.into());
+        }
+        let bump = [pool.bump];
+        let signer_seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, pool_info.key.as_ref(), &bump];
+        Self::check_authority(program_id, signer_seeds, authority_info)?;
+
+        for (source_info, reserve_info, bank_info, amount) in [
+            (source_a_info, reserve_a_info, bank_a_info, used_a),
+            (source_b_info, reserve_b_info, bank_b_info, used_b),
+        ] {
+            invoke(
+                &bank_instruction::transfer(
+                    bank_program_info.key,
+                    bank_info.key,
+                    source_info.key,
+                    reserve_info.key,
+                    owner_info.key,
+                    amount,
+                )?,
+                &[
+                    source_info.clone(),
+                    reserve_info.clone(),
+                    owner_info.clone(),
+                    bank_info.clone(),
+                    bank_program_info.clone(),
+                ],
+            )?;
+        }
+        invoke_signed(
+            &bank_instruction::mint_to(
+                bank_program_info.key,
+                lp_bank_info.key,
+                lp_account_info.key,
+                authority_info.key,
+                shares,
+            )?,
+            &[
+                lp_bank_info.clone(),
+                lp_account_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )
+    }
+
+    pub fn process_withdraw(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        shares: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let pool_info = next_account_info(account_info_iter)?;
+        let lp_account_info = next_account_info(account_info_iter)?;
+        let lp_bank_info = next_account_info(account_info_iter)?;
+        let reserve_a_info = next_account_info(account_info_iter)?;
+        let reserve_b_info = next_account_info(account_info_iter)?;
+        let destination_a_info = next_account_info(account_info_iter)?;
+        let destination_b_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_a_info = next_account_info(account_info_iter)?;
+        let bank_b_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let pool = Self::load_pool(program_id, pool_info)?;
+        let reserve_a = Self::reserve_amount(&pool.reserve_a, reserve_a_info, bank_program_info)?;
+        let reserve_b = Self::reserve_amount(&pool.reserve_b, reserve_b_info, bank_program_info)?;
+        let supply = Self::lp_supply(&pool, lp_bank_info, bank_program_info)?;
+        let (amount_a, amount_b) = curve::withdraw(shares, reserve_a, reserve_b, supply)
+            .ok_or(SwapError::InsufficientLiquidity)?;
+        if amount_a < min_amount_a || amount_b < min_amount_b {
+            return Err(SwapError::SlippageExceeded.into());
+        }
+        let bump = [pool.bump];
+        let signer_seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, pool_info.key.as_ref(), &bump];
+        Self::check_authority(program_id, signer_seeds, authority_info)?;
+
+        invoke_signed(
+            &bank_instruction::burn(
+                bank_program_info.key,
+                lp_bank_info.key,
+                lp_account_info.key,
+                authority_info.key,
+                owner_info.key,
+                shares,
+            )?,
+            &[
+                lp_bank_info.clone(),
+                lp_account_info.clone(),
+                authority_info.clone(),
+                owner_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        for (reserve_info, destination_info, bank_info, amount) in [
+            (reserve_a_info, destination_a_info, bank_a_info, amount_a),
+            (reserve_b_info, destination_b_info, bank_b_info, amount_b),
+        ] {
+            invoke_signed(
+                &bank_instruction::transfer(
+                    bank_program_info.key,
+                    bank_info.key,
+                    reserve_info.key,
+                    destination_info.key,
+                    authority_info.key,
+                    amount,
+                )?,
+                &[
+                    reserve_info.clone(),
+                    destination_info.clone(),
+                    authority_info.clone(),
+                    bank_info.clone(),
+                    bank_program_info.clone(),
+                ],
+                &[signer_seeds],
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn process_swap(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner_info = next_account_info(account_info_iter)?;
+        let pool_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let reserve_in_info = next_account_info(account_info_iter)?;
+        let reserve_out_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let source_bank_info = next_account_info(account_info_iter)?;
+        let destination_bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let pool = Self::load_pool(program_id, pool_info)?;
+        let (reserve_in, reserve_out) = if *reserve_in_info.key == pool.reserve_a {
+            (pool.reserve_a, pool.reserve_b)
+        } else {
+            (pool.reserve_b, pool.reserve_a)
+        };
+        let reserve_in = Self::reserve_amount(&reserve_in, reserve_in_info, bank_program_info)?;
+        let reserve_out = Self::reserve_amount(&reserve_out, reserve_out_info, bank_program_info)?;
+        let amount_out = curve::swap_output(amount_in, reserve_in, reserve_out, pool.fee_bps)
+            .ok_or(SwapError::InsufficientLiquidity)?;
+        if amount_out == 0 {
+            return Err(SwapError::InsufficientLiquidity.into());
+        }
+        if amount_out < min_amount_out {
+            return Err(SwapError::SlippageExceeded.into());
+        }
+        let bump = [pool.bump];
+        let signer_seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, pool_info.key.as_ref(), &bump];
+        Self::check_authority(program_id, signer_seeds, authority_info)?;
+
+        // The bank program refuses transfers between banks, so the source has to be in
+        // the bank of the reserve it pays into.
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                source_bank_info.key,
+                source_info.key,
+                reserve_in_info.key,
+                owner_info.key,
+                amount_in,
+            )?,
+            &[
+                source_info.clone(),
+                reserve_in_info.clone(),
+                owner_info.clone(),
+                source_bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                destination_bank_info.key,
+                reserve_out_info.key,
+                destination_info.key,
+                authority_info.key,
+                amount_out,
+            )?,
+            &[
+                reserve_out_info.clone(),
+                destination_info.clone(),
+                authority_info.clone(),
+                destination_bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )
+    }
+
+    fn load_pool(program_id: &Pubkey, pool_info: &AccountInfo) -> Result<Pool, ProgramError> {
+        if pool_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Pool::unpack(&pool_info.data.borrow())
+    }
+
+    fn check_authority(
+        program_id: &Pubkey,
+        signer_seeds: &[&[u8]],
+        authority_info: &AccountInfo,
+    ) -> ProgramResult {
+        let authority = Pubkey::create_program_address(signer_seeds, program_id)
+            .map_err(|_| ProgramError::from(SwapError::InvalidPoolAuthority))?;
+        if authority != *authority_info.key {
+            return Err(SwapError::InvalidPoolAuthority.into());
+        }
+        Ok(())
+    }
+
+    /// Balance of the pool's reserve `expected`, which must be a real bank account.
+    fn reserve_amount(
+        expected: &Pubkey,
+        reserve_info: &AccountInfo,
+        bank_program_info: &AccountInfo,
+    ) -> Result<u64, ProgramError> {
+        if reserve_info.key != expected {
+            return Err(SwapError::InvalidReserve.into());
+        }
+        if reserve_info.owner != bank_program_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Ok(BankAccount::unpack(&reserve_info.data.borrow())?.amount)
+    }
+
+    fn lp_supply(
+        pool: &Pool,
+        lp_bank_info: &AccountInfo,
+        bank_program_info: &AccountInfo,
+    ) -> Result<u64, ProgramError> {
+        if pool.lp_bank != *lp_bank_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        if lp_bank_info.owner != bank_program_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Ok(Bank::unpack(&lp_bank_info.data.borrow())?.total_supply)
+    }
+}
@@ -0,0 +1,129 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// A pool trading the tokens of `bank_a` against those of `bank_b`. Its reserves sit in
+/// bank accounts owned by the pool authority, which also owns `lp_bank`, the bank whose
+/// tokens are the LPs' shares of the reserves.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Pool {
+    pub is_initialized: bool,
+    pub bank_a: Pubkey,
+    pub bank_b: Pubkey,
+    pub reserve_a: Pubkey,
+    pub reserve_b: Pubkey,
+    pub lp_bank: Pubkey,
+    /// Fee on swaps, in basis points of the amount swapped in.
+    pub fee_bps: u16,
+    /// Bump seed of the pool authority address.
+    pub bump: u8,
+}
+
+impl Pool {
+    /// The reserves a swap out of `source_bank` pays into and out of.
+    pub fn reserves_for(&self, source_bank: &Pubkey) -> Option<(Pubkey, Pubkey)> {
+        if *source_bank == self.bank_a {
+            Some((self.reserve_a, self.reserve_b))
+        } else if *source_bank == self.bank_b {
+            Some((self.reserve_b, self.reserve_a))
+        } else {
+            None
+        }
+    }
+}
+
+impl Sealed for Pool {}
+impl IsInitialized for Pool {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Pool {
+    const LEN: usize = 164;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 164];
+        let (is_initialized, bank_a, bank_b, reserve_a, reserve_b, lp_bank, fee_bps, bump) =
+            array_refs![src, 1, 32, 32, 32, 32, 32, 2, 1];
+        Ok(Pool {
+            is_initialized: unpack_bool(is_initialized)?,
+            bank_a: Pubkey::new_from_array(*bank_a),
+            bank_b: Pubkey::new_from_array(*bank_b),
+            reserve_a: Pubkey::new_from_array(*reserve_a),
+            reserve_b: Pubkey::new_from_array(*reserve_b),
+            lp_bank: Pubkey::new_from_array(*lp_bank),
+            fee_bps: u16::from_le_bytes(*fee_bps),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 164];
+        let (is_initialized, bank_a, bank_b, reserve_a, reserve_b, lp_bank, fee_bps, bump) =
+            mut_array_refs![dst, 1, 32, 32, 32, 32, 32, 2, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        bank_a.copy_from_slice(self.bank_a.as_ref());
+        bank_b.copy_from_slice(self.bank_b.as_ref());
+        reserve_a.copy_from_slice(self.reserve_a.as_ref());
+        reserve_b.copy_from_slice(self.reserve_b.as_ref());
+        lp_bank.copy_from_slice(self.lp_bank.as_ref());
+        *fee_bps = self.fee_bps.to_le_bytes();
+        bump[0] = self.bump;
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let pool = Pool {
+            is_initialized: true,
+            bank_a: Pubkey::new_unique(),
+            bank_b: Pubkey::new_unique(),
+            reserve_a: Pubkey::new_unique(),
+            reserve_b: Pubkey::new_unique(),
+            lp_bank: Pubkey::new_unique(),
+            fee_bps: 30,
+            bump: 254,
+        };
+        let mut packed = vec![0u8; Pool::LEN];
+        Pool::pack(pool, &mut packed).unwrap();
+        assert_eq!(Pool::unpack(&packed), Ok(pool));
+
+        packed[0] = 2;
+        assert_eq!(Pool::unpack(&packed), Err(ProgramError::InvalidAccountData));
+    }
+
+    #[test]
+    fn test_reserves_for() {
+        let pool = Pool {
+            bank_a: Pubkey::new_unique(),
+            bank_b: Pubkey::new_unique(),
+            reserve_a: Pubkey::new_unique(),
+            reserve_b: Pubkey::new_unique(),
+            ..Pool::default()
+        };
+        assert_eq!(
+            pool.reserves_for(&pool.bank_a),
+            Some((pool.reserve_a, pool.reserve_b))
+        );
+        assert_eq!(
+            pool.reserves_for(&pool.bank_b),
+            Some((pool.reserve_b, pool.reserve_a))
+        );
+        assert_eq!(pool.reserves_for(&Pubkey::new_unique()), None);
+    }
+}
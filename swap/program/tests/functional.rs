@@ -0,0 +1,456 @@
+use solana_bank::{
+    instruction::initialize_account,
+    state::{Account, Bank},
+    test_utils::program_test::Env,
+};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use solana_swap::{
+    error::SwapError,
+    instruction::{deposit, initialize_pool, pool_authority, swap, withdraw},
+    processor::Processor,
+    state::Pool,
+};
+
+/// Fee of the pool, in basis points.
+const FEE_BPS: u16 = 30;
+
+/// Someone holding 100_000 tokens of each bank and an empty LP account.
+struct Trader {
+    owner: Keypair,
+    account_a: Pubkey,
+    account_b: Pubkey,
+    lp_account: Pubkey,
+}
+
+/// An empty pool between two banks owned by the payer.
+struct Fixture {
+    env: Env,
+    pool: Pubkey,
+    bank_a: Pubkey,
+    bank_b: Pubkey,
+    reserve_a: Pubkey,
+    reserve_b: Pubkey,
+    lp_bank: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_swap", processor!(Processor::process)).await;
+        let bank_a = env.create_bank().await;
+        let bank_b = env.create_bank().await;
+        let pool = Keypair::new();
+        let reserve_a = Keypair::new();
+        let reserve_b = Keypair::new();
+        let lp_bank = Keypair::new();
+        let swap_program_id = env.program_id;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&pool, Pool::LEN, &swap_program_id).await;
+        env.create_account(&reserve_a, Account::LEN, &bank_program_id)
+            .await;
+        env.create_account(&reserve_b, Account::LEN, &bank_program_id)
+            .await;
+        env.create_account(&lp_bank, Bank::LEN, &bank_program_id)
+            .await;
+        let instruction = initialize_pool(
+            &swap_program_id,
+            &bank_program_id,
+            &pool.pubkey(),
+            &reserve_a.pubkey(),
+            &reserve_b.pubkey(),
+            &bank_a,
+            &bank_b,
+            &lp_bank.pubkey(),
+            FEE_BPS,
+        )
+        .unwrap();
+        env.process(&[instruction], &[]).await.unwrap();
+        Fixture {
+            env,
+            pool: pool.pubkey(),
+            bank_a,
+            bank_b,
+            reserve_a: reserve_a.pubkey(),
+            reserve_b: reserve_b.pubkey(),
+            lp_bank: lp_bank.pubkey(),
+        }
+    }
+
+    async fn trader(&mut self) -> Trader {
+        let owner = Keypair::new();
+        let (bank_a, bank_b) = (self.bank_a, self.bank_b);
+        let account_a = self.env.create_bank_account(&bank_a, &owner, 100_000).await;
+        let account_b = self.env.create_bank_account(&bank_b, &owner, 100_000).await;
+        let lp_account = Keypair::new();
+        let bank_program_id = self.env.bank_program_id;
+        self.env
+            .create_account(&lp_account, Account::LEN, &bank_program_id)
+            .await;
+        let instruction = initialize_account(
+            &bank_program_id,
+            &self.lp_bank,
+            &lp_account.pubkey(),
+            &owner.pubkey(),
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[&owner]).await.unwrap();
+        Trader {
+            owner,
+            account_a,
+            account_b,
+            lp_account: lp_account.pubkey(),
+        }
+    }
+
+    async fn deposit(
+        &mut self,
+        trader: &Trader,
+        amount_a: u64,
+        amount_b: u64,
+        min_shares: u64,
+    ) -> Result<(), TransactionError> {
+        let instruction = deposit(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank_a,
+            &self.bank_b,
+            &trader.owner.pubkey(),
+            &self.pool,
+            &trader.account_a,
+            &trader.account_b,
+            &self.reserve_a,
+            &self.reserve_b,
+            &self.lp_bank,
+            &trader.lp_account,
+            amount_a,
+            amount_b,
+            min_shares,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[&trader.owner]).await
+    }
+
+    async fn withdraw(&mut self, trader: &Trader, shares: u64) -> Result<(), TransactionError> {
+        let instruction = withdraw(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank_a,
+            &self.bank_b,
+            &trader.owner.pubkey(),
+            &self.pool,
+            &trader.lp_account,
+            &self.lp_bank,
+            &self.reserve_a,
+            &self.reserve_b,
+            &trader.account_a,
+            &trader.account_b,
+            shares,
+            0,
+            0,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[&trader.owner]).await
+    }
+
+    /// Swaps `amount_in` of bank A's tokens for bank B's, or the other way around.
+    async fn swap(
+        &mut self,
+        trader: &Trader,
+        a_to_b: bool,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<(), TransactionError> {
+        let (source, destination, reserve_in, reserve_out) = if a_to_b {
+            (
+                trader.account_a,
+                trader.account_b,
+                self.reserve_a,
+                self.reserve_b,
+            )
+        } else {
+            (
+                trader.account_b,
+                trader.account_a,
+                self.reserve_b,
+                self.reserve_a,
+            )
+        };
+        let (source_bank, destination_bank) = if a_to_b {
+            (self.bank_a, self.bank_b)
+        } else {
+            (self.bank_b, self.bank_a)
+        };
+        let instruction = swap(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &source_bank,
+            &destination_bank,
+            &trader.owner.pubkey(),
+            &self.pool,
+            &source,
+            &destination,
+            &reserve_in,
+            &reserve_out,
+            amount_in,
+            min_amount_out,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[&trader.owner]).await
+    }
+
+    async fn reserves(&mut self) -> (u64, u64) {
+        let (reserve_a, reserve_b) = (self.reserve_a, self.reserve_b);
+        (
+            self.env.amount(&reserve_a).await,
+            self.env.amount(&reserve_b).await,
+        )
+    }
+}
+
+fn custom(error: SwapError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_initialize_pool() {
+    let mut fixture = Fixture::new().await;
+    let (authority, _) = pool_authority(&fixture.env.program_id, &fixture.pool);
+    let pool = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(fixture.pool)
+        .await
+        .unwrap()
+        .unwrap();
+    let pool = Pool::unpack(&pool.data).unwrap();
+    assert_eq!((pool.bank_a, pool.bank_b), (fixture.bank_a, fixture.bank_b));
+    assert_eq!(pool.fee_bps, FEE_BPS);
+    let lp_bank = fixture.lp_bank;
+    assert_eq!(
+        fixture.env.unpack::<Bank>(&lp_bank).await.bank_owner,
+        authority
+    );
+    let reserve = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(fixture.reserve_a)
+        .await
+        .unwrap()
+        .unwrap();
+    let reserve = Account::unpack(&reserve.data).unwrap();
+    assert_eq!((reserve.owner, reserve.bank), (authority, fixture.bank_a));
+
+    let swap_program_id = fixture.env.program_id;
+    let bank_program_id = fixture.env.bank_program_id;
+    let (bank_a, bank_b) = (fixture.bank_a, fixture.bank_b);
+    let env = &mut fixture.env;
+    let accounts = [
+        Keypair::new(),
+        Keypair::new(),
+        Keypair::new(),
+        Keypair::new(),
+    ];
+    env.create_account(&accounts[0], Pool::LEN, &swap_program_id)
+        .await;
+    env.create_account(&accounts[1], Account::LEN, &bank_program_id)
+        .await;
+    env.create_account(&accounts[2], Account::LEN, &bank_program_id)
+        .await;
+    env.create_account(&accounts[3], Bank::LEN, &bank_program_id)
+        .await;
+    let initialize = |bank_b: &Pubkey, fee_bps| {
+        initialize_pool(
+            &swap_program_id,
+            &bank_program_id,
+            &accounts[0].pubkey(),
+            &accounts[1].pubkey(),
+            &accounts[2].pubkey(),
+            &bank_a,
+            bank_b,
+            &accounts[3].pubkey(),
+            fee_bps,
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        env.process(&[initialize(&bank_a, FEE_BPS)], &[]).await,
+        custom(SwapError::SameBank)
+    );
+    assert_eq!(
+        env.process(&[initialize(&bank_b, 10_001)], &[]).await,
+        custom(SwapError::InvalidFee)
+    );
+}
+
+#[tokio::test]
+async fn test_deposit_and_withdraw() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.trader().await;
+    let bob = fixture.trader().await;
+
+    // The first deposit sets the price at 4 B per A.
+    fixture.deposit(&alice, 1_000, 4_000, 2_000).await.unwrap();
+    assert_eq!(fixture.env.amount(&alice.lp_account).await, 2_000);
+    assert_eq!(fixture.reserves().await, (1_000, 4_000));
+
+    // Bob offers more B than the price asks for; only what matches his A is taken.
+    assert_eq!(
+        fixture.deposit(&bob, 500, 5_000, 1_001).await,
+        custom(SwapError::SlippageExceeded)
+    );
+    fixture.deposit(&bob, 500, 5_000, 1_000).await.unwrap();
+    assert_eq!(fixture.env.amount(&bob.lp_account).await, 1_000);
+    assert_eq!(fixture.env.amount(&bob.account_a).await, 99_500);
+    assert_eq!(fixture.env.amount(&bob.account_b).await, 98_000);
+    let lp_bank = fixture.lp_bank;
+    assert_eq!(
+        fixture.env.unpack::<Bank>(&lp_bank).await.total_supply,
+        3_000
+    );
+
+    fixture.withdraw(&bob, 1_000).await.unwrap();
+    assert_eq!(fixture.env.amount(&bob.lp_account).await, 0);
+    assert_eq!(fixture.env.amount(&bob.account_a).await, 100_000);
+    assert_eq!(fixture.env.amount(&bob.account_b).await, 100_000);
+    assert_eq!(fixture.reserves().await, (1_000, 4_000));
+    assert_eq!(
+        fixture.env.unpack::<Bank>(&lp_bank).await.total_supply,
+        2_000
+    );
+}
+
+#[tokio::test]
+async fn test_swap() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.trader().await;
+    let bob = fixture.trader().await;
+    assert_eq!(
+        fixture.swap(&bob, true, 100, 0).await,
+        custom(SwapError::InsufficientLiquidity)
+    );
+    fixture.deposit(&alice, 1_000, 4_000, 0).await.unwrap();
+
+    // 0.3% of 100 goes to the LPs; the remaining 99 buy 4_000 * 99 / 1_099 B.
+    assert_eq!(
+        fixture.swap(&bob, true, 100, 361).await,
+        custom(SwapError::SlippageExceeded)
+    );
+    fixture.swap(&bob, true, 100, 360).await.unwrap();
+    assert_eq!(fixture.env.amount(&bob.account_a).await, 99_900);
+    assert_eq!(fixture.env.amount(&bob.account_b).await, 100_360);
+    assert_eq!(fixture.reserves().await, (1_100, 3_640));
+
+    fixture.swap(&bob, false, 360, 0).await.unwrap();
+    let (reserve_a, reserve_b) = fixture.reserves().await;
+    assert_eq!(reserve_b, 4_000);
+    // The round trip leaves Bob short by the fees and rounding, all kept by the pool.
+    assert!(reserve_a > 1_000);
+    assert_eq!(
+        fixture.env.amount(&bob.account_a).await,
+        100_000 - (reserve_a - 1_000)
+    );
+
+    // Paying into the reserve of the wrong side is refused.
+    let instruction = swap(
+        &fixture.env.program_id,
+        &fixture.env.bank_program_id,
+        &fixture.bank_a,
+        &fixture.bank_b,
+        &bob.owner.pubkey(),
+        &fixture.pool,
+        &bob.account_a,
+        &bob.account_b,
+        &fixture.reserve_a,
+        &fixture.reserve_a,
+        10,
+        0,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&bob.owner]).await,
+        custom(SwapError::InvalidReserve)
+    );
+}
+
+#[tokio::test]
+async fn test_swaps_keep_invariant() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.trader().await;
+    let bob = fixture.trader().await;
+    fixture.deposit(&alice, 10_000, 30_000, 0).await.unwrap();
+
+    let mut k = 10_000u128 * 30_000;
+    for (i, amount) in [2u64, 7, 2_500, 9_999, 40_000, 30, 12_345, 60_000]
+        .iter()
+        .enumerate()
+    {
+        fixture.swap(&bob, i % 2 == 0, *amount, 0).await.unwrap();
+        let (reserve_a, reserve_b) = fixture.reserves().await;
+        let product = reserve_a as u128 * reserve_b as u128;
+        assert!(
+            product >= k,
+            "swap {} shrank k from {} to {}",
+            i,
+            k,
+            product
+        );
+        k = product;
+    }
+
+    // Alice's shares now redeem for the whole pool, fees included.
+    let (reserve_a, reserve_b) = fixture.reserves().await;
+    fixture.withdraw(&alice, 17_320).await.unwrap();
+    assert_eq!(fixture.reserves().await, (0, 0));
+    assert_eq!(
+        fixture.env.amount(&alice.account_a).await,
+        90_000 + reserve_a
+    );
+    assert_eq!(
+        fixture.env.amount(&alice.account_b).await,
+        70_000 + reserve_b
+    );
+    assert!(reserve_a as u128 * reserve_b as u128 > 10_000u128 * 30_000);
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    let alice = fixture.trader().await;
+    let bob = fixture.trader().await;
+    fixture.deposit(&alice, 1_000, 4_000, 0).await.unwrap();
+
+    // Through a program posing as the bank, the pool authority would sign the reserve's
+    // side of the swap over to it.
+    let instruction = swap(
+        &fixture.env.program_id,
+        &fixture.env.fake_bank_program_id,
+        &fixture.bank_a,
+        &fixture.bank_b,
+        &bob.owner.pubkey(),
+        &fixture.pool,
+        &bob.account_a,
+        &bob.account_b,
+        &fixture.reserve_a,
+        &fixture.reserve_b,
+        100,
+        0,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&bob.owner]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    assert_eq!(fixture.reserves().await, (1_000, 4_000));
+}
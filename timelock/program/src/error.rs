@@ -0,0 +1,23 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TimelockError {
+    /// The delay must not be negative.
+    InvalidDelay,
+    /// The signer isn't the timelock's admin.
+    NotAdmin,
+    /// The operation would unlock before the timelock's delay has passed.
+    EtaTooSoon,
+    /// The operation's unlock time hasn't come yet.
+    NotReady,
+    /// The operation has already been executed.
+    AlreadyExecuted,
+    /// The queued instruction has more accounts or data than an operation holds.
+    InstructionTooLarge,
+}
+
+impl From<TimelockError> for ProgramError {
+    fn from(e: TimelockError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
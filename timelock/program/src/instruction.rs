@@ -0,0 +1,271 @@
+use crate::state::Operation;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of a timelock's signer address, followed by the timelock's address.
+pub const SIGNER_SEED: &[u8] = b"timelock";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimelockInstruction {
+    /// Sets up a timelock holding each of the signer's instructions back `delay` seconds.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The timelock, an uninitialized account owned by this program.
+    ///   1. `[signer]` The admin.
+    CreateTimelock { delay: i64 },
+
+    /// Stores `instruction` for the timelock to execute at `eta` or later, which must be
+    /// at least the timelock's delay away. Its accounts should name the timelock's signer
+    /// address as a signer where the target program expects the timelock's signature.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The admin.
+    ///   1. `[]` The timelock.
+    ///   2. `[writable]` The operation, an uninitialized account owned by this program.
+    Queue { eta: i64, instruction: Instruction },
+
+    /// Invokes an operation's instruction, signed by the timelock's signer address, once
+    /// its unlock time has come. Anyone can execute it.
+    ///
+    /// Accounts expected:
+    ///   0. `[]` The timelock.
+    ///   1. `[writable]` The operation.
+    ///   2. `[]` The program the queued instruction targets.
+    ///   3. .. The queued instruction's accounts, with the signer address not signing.
+    Execute,
+
+    /// Drops an operation that hasn't been executed, closing its account.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The admin, credited the operation account's rent.
+    ///   1. `[]` The timelock.
+    ///   2. `[writable]` The operation.
+    Cancel,
+}
+
+impl TimelockInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (delay, _rest) = Self::unpack_u64(rest)?;
+                Self::CreateTimelock {
+                    delay: delay as i64,
+                }
+            }
+            1 => {
+                let (eta, rest) = Self::unpack_u64(rest)?;
+                let (program_id, rest) = Self::unpack_pubkey(rest)?;
+                let (&count, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let mut accounts = Vec::with_capacity(count as usize);
+                let mut rest = rest;
+                for _ in 0..count {
+                    let (pubkey, tail) = Self::unpack_pubkey(rest)?;
+                    let flags = tail.get(..2).ok_or(InvalidInstructionData)?;
+                    accounts.push(AccountMeta {
+                        pubkey,
+                        is_signer: flags[0] != 0,
+                        is_writable: flags[1] != 0,
+                    });
+                    rest = &tail[2..];
+                }
+                let len = rest
+                    .get(..2)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u16::from_le_bytes)
+                    .ok_or(InvalidInstructionData)? as usize;
+                let data = rest.get(2..2 + len).ok_or(InvalidInstructionData)?;
+                Self::Queue {
+                    eta: eta as i64,
+                    instruction: Instruction {
+                        program_id,
+                        accounts,
+                        data: data.to_vec(),
+                    },
+                }
+            }
+            2 => Self::Execute,
+            3 => Self::Cancel,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self {
+            Self::CreateTimelock { delay } => {
+                buf.push(0);
+                buf.extend_from_slice(&delay.to_le_bytes());
+            }
+            Self::Queue { eta, instruction } => {
+                buf.push(1);
+                buf.extend_from_slice(&eta.to_le_bytes());
+                buf.extend_from_slice(instruction.program_id.as_ref());
+                buf.push(instruction.accounts.len() as u8);
+                for meta in instruction.accounts.iter() {
+                    buf.extend_from_slice(meta.pubkey.as_ref());
+                    buf.push(meta.is_signer as u8);
+                    buf.push(meta.is_writable as u8);
+                }
+                buf.extend_from_slice(&(instruction.data.len() as u16).to_le_bytes());
+                buf.extend_from_slice(&instruction.data);
+            }
+            Self::Execute => buf.push(2),
+            Self::Cancel => buf.push(3),
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+
+    fn unpack_pubkey(input: &[u8]) -> Result<(Pubkey, &[u8]), ProgramError> {
+        if input.len() < 32 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (key, rest) = input.split_at(32);
+        let key = Pubkey::new_from_array(key.try_into().unwrap());
+        Ok((key, rest))
+    }
+}
+
+/// Address and bump of the key `timelock` signs its executed instructions with.
+pub fn timelock_signer(timelock_program_id: &Pubkey, timelock: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SIGNER_SEED, timelock.as_ref()], timelock_program_id)
+}
+
+pub fn create_timelock(
+    timelock_program_id: &Pubkey,
+    timelock: &Pubkey,
+    admin: &Pubkey,
+    delay: i64,
+) -> Result<Instruction, ProgramError> {
+    let data = TimelockInstruction::CreateTimelock { delay }.pack();
+    let accounts = vec![
+        AccountMeta::new(*timelock, false),
+        AccountMeta::new_readonly(*admin, true),
+    ];
+    Ok(Instruction {
+        program_id: *timelock_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn queue(
+    timelock_program_id: &Pubkey,
+    admin: &Pubkey,
+    timelock: &Pubkey,
+    operation: &Pubkey,
+    eta: i64,
+    instruction: Instruction,
+) -> Result<Instruction, ProgramError> {
+    let data = TimelockInstruction::Queue { eta, instruction }.pack();
+    let accounts = vec![
+        AccountMeta::new_readonly(*admin, true),
+        AccountMeta::new_readonly(*timelock, false),
+        AccountMeta::new(*operation, false),
+    ];
+    Ok(Instruction {
+        program_id: *timelock_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Executes `operation`, whose stored state is `state`.
+pub fn execute(
+    timelock_program_id: &Pubkey,
+    timelock: &Pubkey,
+    operation: &Pubkey,
+    state: &Operation,
+) -> Result<Instruction, ProgramError> {
+    let data = TimelockInstruction::Execute.pack();
+    let (signer, _) = timelock_signer(timelock_program_id, timelock);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*timelock, false),
+        AccountMeta::new(*operation, false),
+        AccountMeta::new_readonly(state.program_id, false),
+    ];
+    // The program signs for its signer address inside the CPI, not in the transaction.
+    accounts.extend(state.accounts.iter().map(|meta| AccountMeta {
+        is_signer: meta.is_signer && meta.pubkey != signer,
+        ..meta.clone()
+    }));
+    Ok(Instruction {
+        program_id: *timelock_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn cancel(
+    timelock_program_id: &Pubkey,
+    admin: &Pubkey,
+    timelock: &Pubkey,
+    operation: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = TimelockInstruction::Cancel.pack();
+    let accounts = vec![
+        AccountMeta::new(*admin, true),
+        AccountMeta::new_readonly(*timelock, false),
+        AccountMeta::new(*operation, false),
+    ];
+    Ok(Instruction {
+        program_id: *timelock_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            TimelockInstruction::CreateTimelock { delay: 86_400 },
+            TimelockInstruction::Queue {
+                eta: 1_700_000_000,
+                instruction: Instruction {
+                    program_id: Pubkey::new_unique(),
+                    accounts: vec![
+                        AccountMeta::new(Pubkey::new_unique(), false),
+                        AccountMeta::new_readonly(Pubkey::new_unique(), true),
+                    ],
+                    data: vec![4, 1, 2, 3],
+                },
+            },
+            TimelockInstruction::Execute,
+            TimelockInstruction::Cancel,
+        ];
+        for instruction in cases {
+            assert_eq!(
+                TimelockInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            TimelockInstruction::unpack(&[1, 0, 0, 0, 0, 0, 0, 0, 0, 7]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            TimelockInstruction::unpack(&[4]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{
+    error::TimelockError,
+    instruction::{TimelockInstruction, SIGNER_SEED},
+    state::{Operation, Timelock},
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = TimelockInstruction::unpack(input)?;
+
+        match instruction {
+            TimelockInstruction::CreateTimelock { delay } => {
+                msg!("Instruction: CreateTimelock");
+                Self::process_create_timelock(program_id, accounts, delay)
+            }
+            TimelockInstruction::Queue { eta, instruction } => {
+                msg!("Instruction: Queue");
+                Self::process_queue(program_id, accounts, eta, instruction)
+            }
+            TimelockInstruction::Execute => {
+                msg!("Instruction: Execute");
+                Self::process_execute(program_id, accounts)
+            }
+            TimelockInstruction::Cancel => {
+                msg!("Instruction: Cancel");
+                Self::process_cancel(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_create_timelock(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        delay: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let timelock_info = next_account_info(account_info_iter)?;
+        let admin_info = next_account_info(account_info_iter)?;
+
+        if !admin_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if timelock_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut timelock = Timelock::unpack_unchecked(&timelock_info.data.borrow())?;
+        if timelock.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if delay < 0 {
+            return Err(TimelockError::InvalidDelay.into());
+        }
+        let (_, bump) =
+            Pubkey::find_program_address(&[SIGNER_SEED, timelock_info.key.as_ref()], program_id);
+
+        timelock.is_initialized = true;
+        timelock.admin = *admin_info.key;
+        timelock.delay = delay;
+        timelock.bump = bump;
+        Timelock::pack(timelock, &mut timelock_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_queue(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        eta: i64,
+        instruction: Instruction,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let admin_info = next_account_info(account_info_iter)?;
+        let timelock_info = next_account_info(account_info_iter)?;
+        let operation_info = next_account_info(account_info_iter)?;
+
+        let timelock = Self::load_timelock(program_id, timelock_info, admin_info)?;
+        if operation_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut operation = Operation::unpack_unchecked(&operation_info.data.borrow())?;
+        if operation.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if !Operation::fits(&instruction) {
+            return Err(TimelockError::InstructionTooLarge.into());
+        }
+        let earliest = Clock::get()?
+            .unix_timestamp
+            .checked_add(timelock.delay)
+            .ok_or(ProgramError::InvalidArgument)?;
+        if eta < earliest {
+            return Err(TimelockError::EtaTooSoon.into());
+        }
+
+        operation.is_initialized = true;
+        operation.timelock = *timelock_info.key;
+        operation.program_id = instruction.program_id;
+        operation.accounts = instruction.accounts;
+        operation.data = instruction.data;
+        operation.eta = eta;
+        operation.executed = false;
+        Operation::pack(operation, &mut operation_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_execute(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let timelock_info = next_account_info(account_info_iter)?;
+        let operation_info = next_account_info(account_info_iter)?;
+        let target_program_info = next_account_info(account_info_iter)?;
+        let instruction_infos = account_info_iter.as_slice();
+
+        if timelock_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let timelock = Timelock::unpack(&timelock_info.data.borrow())?;
+        let mut operation = Self::load_operation(program_id, timelock_info, operation_info)?;
+        if Clock::get()?.unix_timestamp < operation.eta {
+            return Err(TimelockError::NotReady.into());
+        }
+        if operation.program_id != *target_program_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let instruction = operation.instruction();
+
+        // Marked before the call so the operation can't run twice, even if the target
+        // program calls back into this one.
+        operation.executed = true;
+        Operation::pack(operation, &mut operation_info.data.borrow_mut())?;
+
+        let mut infos = instruction_infos.to_vec();
+        infos.push(target_program_info.clone());
+        invoke_signed(
+            &instruction,
+            &infos,
+            &[&[SIGNER_SEED, timelock_info.key.as_ref(), &[timelock.bump]]],
+        )
+    }
+
+    pub fn process_cancel(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let admin_info = next_account_info(account_info_iter)?;
+        let timelock_info = next_account_info(account_info_iter)?;
+        let operation_info = next_account_info(account_info_iter)?;
+
+        Self::load_timelock(program_id, timelock_info, admin_info)?;
+        Self::load_operation(program_id, timelock_info, operation_info)?;
+
+        let lamports = operation_info.lamports();
+        **operation_info.lamports.borrow_mut() = 0;
+        **admin_info.lamports.borrow_mut() = admin_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(ProgramError::InvalidArgument)?;
+        operation_info.data.borrow_mut().fill(0);
+        Ok(())
+    }
+
+    /// Loads the timelock, checking it is signed for by its admin.
+    fn load_timelock(
+        program_id: &Pubkey,
+        timelock_info: &AccountInfo,
+        admin_info: &AccountInfo,
+    ) -> Result<Timelock, ProgramError> {
+        if !admin_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if timelock_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let timelock = Timelock::unpack(&timelock_info.data.borrow())?;
+        if timelock.admin != *admin_info.key {
+            return Err(TimelockError::NotAdmin.into());
+        }
+        Ok(timelock)
+    }
+
+    /// Loads an operation of the timelock that hasn't been executed yet.
+    fn load_operation(
+        program_id: &Pubkey,
+        timelock_info: &AccountInfo,
+        operation_info: &AccountInfo,
+    ) -> Result<Operation, ProgramError> {
+        if operation_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let operation = Operation::unpack(&operation_info.data.borrow())?;
+        if operation.timelock != *timelock_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        if operation.executed {
+            return Err(TimelockError::AlreadyExecuted.into());
+        }
+        Ok(operation)
+    }
+}
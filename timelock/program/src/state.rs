@@ -0,0 +1,239 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Most accounts a queued instruction can take.
+pub const MAX_ACCOUNTS: usize = 8;
+/// Most data bytes a queued instruction can carry.
+pub const MAX_DATA: usize = 128;
+
+/// Bytes of one stored account meta: the key, then the signer and writable flags.
+const META_LEN: usize = 34;
+
+/// A delay every instruction of `admin`'s goes through. Executed instructions are signed
+/// by the timelock's signer address, the program address derived from its own, so
+/// whatever that address owns, such as a bank, can only change `delay` seconds after
+/// the change was announced.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Timelock {
+    pub is_initialized: bool,
+    pub admin: Pubkey,
+    /// Least seconds between queueing an operation and executing it.
+    pub delay: i64,
+    /// Bump seed of the signer address.
+    pub bump: u8,
+}
+
+impl Sealed for Timelock {}
+impl IsInitialized for Timelock {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Timelock {
+    const LEN: usize = 42;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 42];
+        let (is_initialized, admin, delay, bump) = array_refs![src, 1, 32, 8, 1];
+        Ok(Timelock {
+            is_initialized: unpack_bool(is_initialized[0])?,
+            admin: Pubkey::new_from_array(*admin),
+            delay: i64::from_le_bytes(*delay),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 42];
+        let (is_initialized, admin, delay, bump) = mut_array_refs![dst, 1, 32, 8, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        admin.copy_from_slice(self.admin.as_ref());
+        *delay = self.delay.to_le_bytes();
+        bump[0] = self.bump;
+    }
+}
+
+/// An instruction waiting for its unlock time, `eta`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Operation {
+    pub is_initialized: bool,
+    pub timelock: Pubkey,
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+    pub eta: i64,
+    pub executed: bool,
+}
+
+impl Operation {
+    /// Whether `instruction` fits into an operation.
+    pub fn fits(instruction: &Instruction) -> bool {
+        instruction.accounts.len() <= MAX_ACCOUNTS && instruction.data.len() <= MAX_DATA
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: self.accounts.clone(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl Sealed for Operation {}
+impl IsInitialized for Operation {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Operation {
+    const LEN: usize = 477;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 477];
+        let (
+            is_initialized,
+            timelock,
+            program_id,
+            account_count,
+            accounts,
+            data_len,
+            data,
+            eta,
+            executed,
+        ) = array_refs![
+            src,
+            1,
+            32,
+            32,
+            1,
+            META_LEN * MAX_ACCOUNTS,
+            2,
+            MAX_DATA,
+            8,
+            1
+        ];
+        let account_count = account_count[0] as usize;
+        let data_len = u16::from_le_bytes(*data_len) as usize;
+        if account_count > MAX_ACCOUNTS || data_len > MAX_DATA {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let accounts = accounts
+            .chunks_exact(META_LEN)
+            .take(account_count)
+            .map(|meta| {
+                Ok(AccountMeta {
+                    pubkey: Pubkey::new_from_array(*array_ref![meta, 0, 32]),
+                    is_signer: unpack_bool(meta[32])?,
+                    is_writable: unpack_bool(meta[33])?,
+                })
+            })
+            .collect::<Result<_, ProgramError>>()?;
+        Ok(Operation {
+            is_initialized: unpack_bool(is_initialized[0])?,
+            timelock: Pubkey::new_from_array(*timelock),
+            program_id: Pubkey::new_from_array(*program_id),
+            accounts,
+            data: data[..data_len].to_vec(),
+            eta: i64::from_le_bytes(*eta),
+            executed: unpack_bool(executed[0])?,
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 477];
+        let (
+            is_initialized,
+            timelock,
+            program_id,
+            account_count,
+            accounts,
+            data_len,
+            data,
+            eta,
+            executed,
+        ) = mut_array_refs![
+            dst,
+            1,
+            32,
+            32,
+            1,
+            META_LEN * MAX_ACCOUNTS,
+            2,
+            MAX_DATA,
+            8,
+            1
+        ];
+        is_initialized[0] = self.is_initialized as u8;
+        timelock.copy_from_slice(self.timelock.as_ref());
+        program_id.copy_from_slice(self.program_id.as_ref());
+        account_count[0] = self.accounts.len() as u8;
+        accounts.fill(0);
+        for (dst, meta) in accounts
+            .chunks_exact_mut(META_LEN)
+            .zip(self.accounts.iter())
+        {
+            dst[..32].copy_from_slice(meta.pubkey.as_ref());
+            dst[32] = meta.is_signer as u8;
+            dst[33] = meta.is_writable as u8;
+        }
+        *data_len = (self.data.len() as u16).to_le_bytes();
+        data.fill(0);
+        data[..self.data.len()].copy_from_slice(&self.data);
+        *eta = self.eta.to_le_bytes();
+        executed[0] = self.executed as u8;
+    }
+}
+
+fn unpack_bool(byte: u8) -> Result<bool, ProgramError> {
+    match byte {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let timelock = Timelock {
+            is_initialized: true,
+            admin: Pubkey::new_unique(),
+            delay: 2 * 24 * 60 * 60,
+            bump: 253,
+        };
+        let mut packed = vec![0u8; Timelock::LEN];
+        Timelock::pack(timelock, &mut packed).unwrap();
+        assert_eq!(Timelock::unpack(&packed), Ok(timelock));
+
+        let operation = Operation {
+            is_initialized: true,
+            timelock: Pubkey::new_unique(),
+            program_id: Pubkey::new_unique(),
+            accounts: vec![
+                AccountMeta::new(Pubkey::new_unique(), false),
+                AccountMeta::new_readonly(Pubkey::new_unique(), true),
+            ],
+            data: vec![5; MAX_DATA],
+            eta: 1_700_000_000,
+            executed: true,
+        };
+        let mut packed = vec![0u8; Operation::LEN];
+        Operation::pack(operation.clone(), &mut packed).unwrap();
+        assert_eq!(Operation::unpack(&packed), Ok(operation));
+
+        // A count past the maximum can only come from corrupt data.
+        packed[65] = MAX_ACCOUNTS as u8 + 1;
+        assert_eq!(
+            Operation::unpack(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+}
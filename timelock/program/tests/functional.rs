@@ -0,0 +1,314 @@
+use solana_bank::{
+    instruction::{initialize_account, initialize_bank, mint_to},
+    state::{Account, Bank},
+    test_utils::program_test::Env,
+};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use solana_timelock::{
+    error::TimelockError,
+    instruction::{cancel, create_timelock, execute, queue, timelock_signer},
+    processor::Processor,
+    state::{Operation, Timelock},
+};
+
+/// Time operations are queued at, and the timelock's delay.
+const NOW: i64 = 1_000;
+const DELAY: i64 = 1_000;
+
+/// A timelock whose signer address owns a bank, opened through the timelock itself, and
+/// an empty account in that bank. The clock is back at `NOW` afterwards.
+struct Fixture {
+    env: Env,
+    admin: Keypair,
+    timelock: Pubkey,
+    signer: Pubkey,
+    bank: Pubkey,
+    account: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_timelock", processor!(Processor::process)).await;
+        env.set_time(NOW);
+        let admin = Keypair::new();
+        let timelock = Keypair::new();
+        let timelock_program_id = env.program_id;
+        env.create_account(&timelock, Timelock::LEN, &timelock_program_id)
+            .await;
+        let instruction = create_timelock(
+            &timelock_program_id,
+            &timelock.pubkey(),
+            &admin.pubkey(),
+            DELAY,
+        )
+        .unwrap();
+        env.process(&[instruction], &[&admin]).await.unwrap();
+        let (signer, _) = timelock_signer(&timelock_program_id, &timelock.pubkey());
+
+        let bank = Keypair::new();
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&bank, Bank::LEN, &bank_program_id).await;
+        let mut fixture = Fixture {
+            env,
+            admin,
+            timelock: timelock.pubkey(),
+            signer,
+            bank: bank.pubkey(),
+            account: Pubkey::default(),
+        };
+        let open = initialize_bank(&bank_program_id, &bank.pubkey(), &signer, 0).unwrap();
+        let operation = fixture.queue(NOW + DELAY, open).await.unwrap();
+        fixture.env.set_time(NOW + DELAY);
+        let instruction = fixture.execute(&operation).await;
+        fixture.env.process(&[instruction], &[]).await.unwrap();
+        fixture.env.set_time(NOW);
+
+        let (holder, account) = (Keypair::new(), Keypair::new());
+        fixture
+            .env
+            .create_account(&account, Account::LEN, &bank_program_id)
+            .await;
+        let open = initialize_account(
+            &bank_program_id,
+            &bank.pubkey(),
+            &account.pubkey(),
+            &holder.pubkey(),
+        )
+        .unwrap();
+        fixture.env.process(&[open], &[&holder]).await.unwrap();
+        fixture.account = account.pubkey();
+        fixture
+    }
+
+    /// Has the admin queue `instruction` for `eta` and returns the operation's address.
+    async fn queue(
+        &mut self,
+        eta: i64,
+        instruction: Instruction,
+    ) -> Result<Pubkey, TransactionError> {
+        let operation = Keypair::new();
+        let timelock_program_id = self.env.program_id;
+        self.env
+            .create_account(&operation, Operation::LEN, &timelock_program_id)
+            .await;
+        let instruction = queue(
+            &timelock_program_id,
+            &self.admin.pubkey(),
+            &self.timelock,
+            &operation.pubkey(),
+            eta,
+            instruction,
+        )
+        .unwrap();
+        let admin = Keypair::from_bytes(&self.admin.to_bytes()).unwrap();
+        self.env.process(&[instruction], &[&admin]).await?;
+        Ok(operation.pubkey())
+    }
+
+    async fn operation(&mut self, operation: &Pubkey) -> Option<Operation> {
+        self.env
+            .context
+            .banks_client
+            .get_account(*operation)
+            .await
+            .unwrap()
+            .map(|account| Operation::unpack(&account.data).unwrap())
+    }
+
+    async fn execute(&mut self, operation: &Pubkey) -> Instruction {
+        let state = self.operation(operation).await.unwrap();
+        execute(&self.env.program_id, &self.timelock, operation, &state).unwrap()
+    }
+
+    fn mint(&self, amount: u64) -> Instruction {
+        mint_to(
+            &self.env.bank_program_id,
+            &self.bank,
+            &self.account,
+            &self.signer,
+            amount,
+        )
+        .unwrap()
+    }
+}
+
+fn custom(index: u8, error: TimelockError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        index,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_timelock() {
+    let mut fixture = Fixture::new().await;
+    let account = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(fixture.timelock)
+        .await
+        .unwrap()
+        .unwrap();
+    let timelock = Timelock::unpack(&account.data).unwrap();
+    assert_eq!(timelock.admin, fixture.admin.pubkey());
+    assert_eq!(timelock.delay, DELAY);
+
+    let timelock = Keypair::new();
+    let timelock_program_id = fixture.env.program_id;
+    fixture
+        .env
+        .create_account(&timelock, Timelock::LEN, &timelock_program_id)
+        .await;
+    let admin = Keypair::from_bytes(&fixture.admin.to_bytes()).unwrap();
+    let instruction = create_timelock(
+        &timelock_program_id,
+        &timelock.pubkey(),
+        &admin.pubkey(),
+        -1,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&admin]).await,
+        custom(0, TimelockError::InvalidDelay)
+    );
+}
+
+#[tokio::test]
+async fn test_open_bank_through_timelock() {
+    let mut fixture = Fixture::new().await;
+    let bank = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(fixture.bank)
+        .await
+        .unwrap()
+        .unwrap();
+    let bank = Bank::unpack(&bank.data).unwrap();
+    assert_eq!(bank.bank_owner, fixture.signer);
+    assert!(bank.is_opened);
+}
+
+#[tokio::test]
+async fn test_queue() {
+    let mut fixture = Fixture::new().await;
+    let mint = fixture.mint(500);
+    assert_eq!(
+        fixture.queue(NOW + DELAY - 1, mint.clone()).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TimelockError::EtaTooSoon as u32)
+        ))
+    );
+
+    let (outsider, operation) = (Keypair::new(), Keypair::new());
+    let timelock_program_id = fixture.env.program_id;
+    fixture
+        .env
+        .create_account(&operation, Operation::LEN, &timelock_program_id)
+        .await;
+    let instruction = queue(
+        &timelock_program_id,
+        &outsider.pubkey(),
+        &fixture.timelock,
+        &operation.pubkey(),
+        NOW + DELAY,
+        mint,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&outsider]).await,
+        custom(0, TimelockError::NotAdmin)
+    );
+}
+
+#[tokio::test]
+async fn test_mint_after_delay() {
+    let mut fixture = Fixture::new().await;
+    let first = fixture.queue(NOW + DELAY, fixture.mint(500)).await.unwrap();
+    let second = fixture.queue(NOW + DELAY, fixture.mint(200)).await.unwrap();
+    let (first, second) = (
+        fixture.execute(&first).await,
+        fixture.execute(&second).await,
+    );
+
+    fixture.env.set_time(NOW + DELAY - 1);
+    assert_eq!(
+        fixture
+            .env
+            .process(&[first.clone(), second.clone()], &[])
+            .await,
+        custom(0, TimelockError::NotReady)
+    );
+    let account = fixture.account;
+    assert_eq!(fixture.env.amount(&account).await, 0);
+
+    fixture.env.set_time(NOW + DELAY);
+    fixture
+        .env
+        .process(std::slice::from_ref(&first), &[])
+        .await
+        .unwrap();
+    assert_eq!(fixture.env.amount(&account).await, 500);
+    assert_eq!(
+        fixture.env.process(&[second.clone(), first], &[]).await,
+        custom(1, TimelockError::AlreadyExecuted)
+    );
+    fixture.env.process(&[second], &[]).await.unwrap();
+    assert_eq!(fixture.env.amount(&account).await, 700);
+}
+
+#[tokio::test]
+async fn test_cancel() {
+    let mut fixture = Fixture::new().await;
+    let operation = fixture.queue(NOW + DELAY, fixture.mint(500)).await.unwrap();
+    let instruction = fixture.execute(&operation).await;
+
+    let outsider = Keypair::new();
+    let outsider_cancel = cancel(
+        &fixture.env.program_id,
+        &outsider.pubkey(),
+        &fixture.timelock,
+        &operation,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[outsider_cancel], &[&outsider]).await,
+        custom(0, TimelockError::NotAdmin)
+    );
+    let admin = Keypair::from_bytes(&fixture.admin.to_bytes()).unwrap();
+    let admin_cancel = cancel(
+        &fixture.env.program_id,
+        &admin.pubkey(),
+        &fixture.timelock,
+        &operation,
+    )
+    .unwrap();
+    fixture
+        .env
+        .process(&[admin_cancel], &[&admin])
+        .await
+        .unwrap();
+    assert_eq!(fixture.operation(&operation).await, None);
+
+    fixture.env.set_time(NOW + DELAY);
+    assert_eq!(
+        fixture.env.process(&[instruction], &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    let account = fixture.account;
+    assert_eq!(fixture.env.amount(&account).await, 0);
+}
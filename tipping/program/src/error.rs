@@ -0,0 +1,21 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TippingError {
+    /// The jar isn't the creator's program address for the bank.
+    InvalidJar,
+    /// The account isn't the jar's vault.
+    InvalidVault,
+    /// The tip record isn't the tipper's program address for the jar.
+    InvalidTipRecord,
+    /// Tips and withdrawals can't be empty.
+    InvalidAmount,
+    /// The signer doesn't own the jar.
+    NotCreator,
+}
+
+impl From<TippingError> for ProgramError {
+    fn from(e: TippingError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,228 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of a tip jar, followed by the creator's and the bank's addresses.
+pub const JAR_SEED: &[u8] = b"tip_jar";
+/// Seed prefix of a tip record, followed by the jar's and the tipper's addresses.
+pub const TIP_RECORD_SEED: &[u8] = b"tip_record";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TippingInstruction {
+    /// Creates the creator's tip jar for a bank, and initializes its vault, owned by the
+    /// jar.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The creator, paying for the jar.
+    ///   1. `[writable]` The jar, the creator's program address for the bank.
+    ///   2. `[writable]` The vault, an uninitialized account owned by the bank program.
+    ///   3. `[writable]` The bank.
+    ///   4. `[]` The bank program, owner of the vault.
+    ///   5. `[]` The system program.
+    CreateJar,
+
+    /// Moves `amount` into the jar's vault, creating the tipper's record and counting
+    /// them as a new tipper on their first tip.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The tipper, owner of the source account, paying for the
+    ///      record.
+    ///   1. `[writable]` The jar.
+    ///   2. `[writable]` The tipper's record.
+    ///   3. `[writable]` The source account.
+    ///   4. `[writable]` The vault.
+    ///   5. `[]` The jar's bank.
+    ///   6. `[]` The bank program, owner of the vault.
+    ///   7. `[]` The system program.
+    Tip { amount: u64 },
+
+    /// Pays `amount` out of the vault to the creator.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The creator.
+    ///   1. `[writable]` The jar.
+    ///   2. `[writable]` The vault.
+    ///   3. `[writable]` The destination account.
+    ///   4. `[]` The jar's bank.
+    ///   5. `[]` The bank program, owner of the vault.
+    Withdraw { amount: u64 },
+}
+
+impl TippingInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => Self::CreateJar,
+            1 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Tip { amount }
+            }
+            2 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Withdraw { amount }
+            }
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(9);
+        match *self {
+            Self::CreateJar => buf.push(0),
+            Self::Tip { amount } => {
+                buf.push(1);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Withdraw { amount } => {
+                buf.push(2);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of `creator`'s tip jar for `bank`.
+pub fn jar_address(tipping_program_id: &Pubkey, creator: &Pubkey, bank: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[JAR_SEED, creator.as_ref(), bank.as_ref()],
+        tipping_program_id,
+    )
+}
+
+/// Address and bump of `tipper`'s record for `jar`.
+pub fn tip_record_address(
+    tipping_program_id: &Pubkey,
+    jar: &Pubkey,
+    tipper: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[TIP_RECORD_SEED, jar.as_ref(), tipper.as_ref()],
+        tipping_program_id,
+    )
+}
+
+pub fn create_jar(
+    tipping_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    creator: &Pubkey,
+    vault: &Pubkey,
+    bank: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = TippingInstruction::CreateJar.pack();
+    let (jar, _) = jar_address(tipping_program_id, creator, bank);
+    let accounts = vec![
+        AccountMeta::new(*creator, true),
+        AccountMeta::new(jar, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *tipping_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn tip(
+    tipping_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    tipper: &Pubkey,
+    jar: &Pubkey,
+    source_account: &Pubkey,
+    vault: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = TippingInstruction::Tip { amount }.pack();
+    let (record, _) = tip_record_address(tipping_program_id, jar, tipper);
+    let accounts = vec![
+        AccountMeta::new(*tipper, true),
+        AccountMeta::new(*jar, false),
+        AccountMeta::new(record, false),
+        AccountMeta::new(*source_account, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *tipping_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw(
+    tipping_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    creator: &Pubkey,
+    jar: &Pubkey,
+    vault: &Pubkey,
+    destination_account: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = TippingInstruction::Withdraw { amount }.pack();
+    let accounts = vec![
+        AccountMeta::new_readonly(*creator, true),
+        AccountMeta::new(*jar, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *tipping_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            TippingInstruction::CreateJar,
+            TippingInstruction::Tip { amount: 5 },
+            TippingInstruction::Withdraw { amount: u64::MAX },
+        ];
+        for instruction in cases {
+            assert_eq!(
+                TippingInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            TippingInstruction::unpack(&[1, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            TippingInstruction::unpack(&[3]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use crate::{
+    error::TippingError,
+    instruction::{TippingInstruction, JAR_SEED, TIP_RECORD_SEED},
+    state::{TipJar, TipRecord},
+};
+use solana_bank::instruction as bank_instruction;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = TippingInstruction::unpack(input)?;
+
+        match instruction {
+            TippingInstruction::CreateJar => {
+                msg!("Instruction: CreateJar");
+                Self::process_create_jar(program_id, accounts)
+            }
+            TippingInstruction::Tip { amount } => {
+                msg!("Instruction: Tip");
+                Self::process_tip(program_id, accounts, amount)
+            }
+            TippingInstruction::Withdraw { amount } => {
+                msg!("Instruction: Withdraw");
+                Self::process_withdraw(program_id, accounts, amount)
+            }
+        }
+    }
+
+    pub fn process_create_jar(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let creator_info = next_account_info(account_info_iter)?;
+        let jar_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !creator_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let (jar, bump) = Pubkey::find_program_address(
+            &[JAR_SEED, creator_info.key.as_ref(), bank_info.key.as_ref()],
+            program_id,
+        );
+        if jar != *jar_info.key {
+            return Err(TippingError::InvalidJar.into());
+        }
+        if !jar_info.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if vault_info.owner != bank_program_info.key {
+            return Err(TippingError::InvalidVault.into());
+        }
+        let signer_seeds: &[&[u8]] = &[
+            JAR_SEED,
+            creator_info.key.as_ref(),
+            bank_info.key.as_ref(),
+            &[bump],
+        ];
+
+        invoke_signed(
+            &system_instruction::create_account(
+                creator_info.key,
+                jar_info.key,
+                Rent::get()?.minimum_balance(TipJar::LEN),
+                TipJar::LEN as u64,
+                program_id,
+            ),
+            &[
+                creator_info.clone(),
+                jar_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        invoke_signed(
+            &bank_instruction::initialize_account(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                jar_info.key,
+            )?,
+            &[
+                bank_info.clone(),
+                vault_info.clone(),
+                jar_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        let jar = TipJar {
+            is_initialized: true,
+            creator: *creator_info.key,
+            bank: *bank_info.key,
+            vault: *vault_info.key,
+            bump,
+            ..TipJar::default()
+        };
+        TipJar::pack(jar, &mut jar_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_tip(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let tipper_info = next_account_info(account_info_iter)?;
+        let jar_info = next_account_info(account_info_iter)?;
+        let record_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !tipper_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut jar = Self::load_jar(program_id, jar_info)?;
+        if jar.vault != *vault_info.key || vault_info.owner != bank_program_info.key {
+            return Err(TippingError::InvalidVault.into());
+        }
+        if amount == 0 {
+            return Err(TippingError::InvalidAmount.into());
+        }
+        let (record, record_bump) = Pubkey::find_program_address(
+            &[
+                TIP_RECORD_SEED,
+                jar_info.key.as_ref(),
+                tipper_info.key.as_ref(),
+            ],
+            program_id,
+        );
+        if record != *record_info.key {
+            return Err(TippingError::InvalidTipRecord.into());
+        }
+        let first_tip = record_info.data_is_empty();
+        if first_tip {
+            invoke_signed(
+                &system_instruction::create_account(
+                    tipper_info.key,
+                    record_info.key,
+                    Rent::get()?.minimum_balance(TipRecord::LEN),
+                    TipRecord::LEN as u64,
+                    program_id,
+                ),
+                &[
+                    tipper_info.clone(),
+                    record_info.clone(),
+                    system_program_info.clone(),
+                ],
+                &[&[
+                    TIP_RECORD_SEED,
+                    jar_info.key.as_ref(),
+                    tipper_info.key.as_ref(),
+                    &[record_bump],
+                ]],
+            )?;
+        }
+
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                vault_info.key,
+                tipper_info.key,
+                amount,
+            )?,
+            &[
+                source_info.clone(),
+                vault_info.clone(),
+                tipper_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        let mut record = TipRecord::unpack_unchecked(&record_info.data.borrow())?;
+        record.is_initialized = true;
+        record.amount = record
+            .amount
+            .checked_add(amount)
+            .ok_or(TippingError::InvalidAmount)?;
+        TipRecord::pack(record, &mut record_info.data.borrow_mut())?;
+        jar.total_tipped = jar
+            .total_tipped
+            .checked_add(amount)
+            .ok_or(TippingError::InvalidAmount)?;
+        jar.tip_count += 1;
+        if first_tip {
+            jar.tipper_count += 1;
+        }
+        TipJar::pack(jar, &mut jar_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_withdraw(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let creator_info = next_account_info(account_info_iter)?;
+        let jar_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !creator_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let jar = Self::load_jar(program_id, jar_info)?;
+        if jar.creator != *creator_info.key {
+            return Err(TippingError::NotCreator.into());
+        }
+        if jar.vault != *vault_info.key || vault_info.owner != bank_program_info.key {
+            return Err(TippingError::InvalidVault.into());
+        }
+        if amount == 0 {
+            return Err(TippingError::InvalidAmount.into());
+        }
+
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                destination_info.key,
+                jar_info.key,
+                amount,
+            )?,
+            &[
+                vault_info.clone(),
+                destination_info.clone(),
+                jar_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[
+                JAR_SEED,
+                jar.creator.as_ref(),
+                jar.bank.as_ref(),
+                &[jar.bump],
+            ]],
+        )
+    }
+
+    fn load_jar(program_id: &Pubkey, jar_info: &AccountInfo) -> Result<TipJar, ProgramError> {
+        if jar_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        TipJar::unpack(&jar_info.data.borrow())
+    }
+}
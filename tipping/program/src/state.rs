@@ -0,0 +1,142 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// A creator's tip jar for `bank`, at a program address derived from both. Tips land in
+/// `vault`, a bank account owned by the jar itself, which only the creator can withdraw
+/// from.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TipJar {
+    pub is_initialized: bool,
+    pub creator: Pubkey,
+    pub bank: Pubkey,
+    pub vault: Pubkey,
+    /// Total ever tipped, including what has since been withdrawn.
+    pub total_tipped: u64,
+    pub tip_count: u64,
+    /// Number of distinct tippers, each counted on their first tip.
+    pub tipper_count: u64,
+    /// Bump seed of the jar's address.
+    pub bump: u8,
+}
+
+impl Sealed for TipJar {}
+impl IsInitialized for TipJar {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for TipJar {
+    const LEN: usize = 122;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 122];
+        let (is_initialized, creator, bank, vault, total_tipped, tip_count, tipper_count, bump) =
+            array_refs![src, 1, 32, 32, 32, 8, 8, 8, 1];
+        Ok(TipJar {
+            is_initialized: unpack_bool(is_initialized)?,
+            creator: Pubkey::new_from_array(*creator),
+            bank: Pubkey::new_from_array(*bank),
+            vault: Pubkey::new_from_array(*vault),
+            total_tipped: u64::from_le_bytes(*total_tipped),
+            tip_count: u64::from_le_bytes(*tip_count),
+            tipper_count: u64::from_le_bytes(*tipper_count),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 122];
+        let (is_initialized, creator, bank, vault, total_tipped, tip_count, tipper_count, bump) =
+            mut_array_refs![dst, 1, 32, 32, 32, 8, 8, 8, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        creator.copy_from_slice(self.creator.as_ref());
+        bank.copy_from_slice(self.bank.as_ref());
+        vault.copy_from_slice(self.vault.as_ref());
+        *total_tipped = self.total_tipped.to_le_bytes();
+        *tip_count = self.tip_count.to_le_bytes();
+        *tipper_count = self.tipper_count.to_le_bytes();
+        bump[0] = self.bump;
+    }
+}
+
+/// What one tipper has given a jar, at a program address derived from both. Its
+/// existence is what marks the tipper as already counted.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TipRecord {
+    pub is_initialized: bool,
+    pub amount: u64,
+}
+
+impl Sealed for TipRecord {}
+impl IsInitialized for TipRecord {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for TipRecord {
+    const LEN: usize = 9;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 9];
+        let (is_initialized, amount) = array_refs![src, 1, 8];
+        Ok(TipRecord {
+            is_initialized: unpack_bool(is_initialized)?,
+            amount: u64::from_le_bytes(*amount),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 9];
+        let (is_initialized, amount) = mut_array_refs![dst, 1, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        *amount = self.amount.to_le_bytes();
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let jar = TipJar {
+            is_initialized: true,
+            creator: Pubkey::new_unique(),
+            bank: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            total_tipped: u64::MAX,
+            tip_count: 7,
+            tipper_count: 3,
+            bump: 254,
+        };
+        let mut packed = vec![0u8; TipJar::LEN];
+        TipJar::pack(jar, &mut packed).unwrap();
+        assert_eq!(TipJar::unpack(&packed), Ok(jar));
+
+        let record = TipRecord {
+            is_initialized: true,
+            amount: 42,
+        };
+        let mut packed = vec![0u8; TipRecord::LEN];
+        TipRecord::pack(record, &mut packed).unwrap();
+        assert_eq!(TipRecord::unpack(&packed), Ok(record));
+
+        packed[0] = 2;
+        assert_eq!(
+            TipRecord::unpack(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+}
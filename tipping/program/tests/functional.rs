@@ -0,0 +1,261 @@
+use solana_bank::{state::Account, test_utils::program_test::Env};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use solana_tipping::{
+    error::TippingError,
+    instruction::{create_jar, jar_address, tip, tip_record_address, withdraw},
+    processor::Processor,
+    state::{TipJar, TipRecord},
+};
+
+/// A creator's tip jar in a bank where alice and bob each hold 1_000 tokens.
+struct Fixture {
+    env: Env,
+    bank: Pubkey,
+    creator: Keypair,
+    jar: Pubkey,
+    vault: Pubkey,
+    alice: Keypair,
+    alice_account: Pubkey,
+    bob: Keypair,
+    bob_account: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_tipping", processor!(Processor::process)).await;
+        let bank = env.create_bank().await;
+        let (creator, vault) = (Keypair::new(), Keypair::new());
+        env.fund(&creator.pubkey(), 100_000_000).await;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&vault, Account::LEN, &bank_program_id)
+            .await;
+        let instruction = create_jar(
+            &env.program_id,
+            &bank_program_id,
+            &creator.pubkey(),
+            &vault.pubkey(),
+            &bank,
+        )
+        .unwrap();
+        env.process(&[instruction], &[&creator]).await.unwrap();
+        let (jar, _) = jar_address(&env.program_id, &creator.pubkey(), &bank);
+
+        let (alice, bob) = (Keypair::new(), Keypair::new());
+        env.fund(&alice.pubkey(), 100_000_000).await;
+        env.fund(&bob.pubkey(), 100_000_000).await;
+        let alice_account = env.create_bank_account(&bank, &alice, 1_000).await;
+        let bob_account = env.create_bank_account(&bank, &bob, 1_000).await;
+        Fixture {
+            env,
+            bank,
+            creator,
+            jar,
+            vault: vault.pubkey(),
+            alice,
+            alice_account,
+            bob,
+            bob_account,
+        }
+    }
+
+    async fn jar(&mut self) -> TipJar {
+        let account = self
+            .env
+            .context
+            .banks_client
+            .get_account(self.jar)
+            .await
+            .unwrap()
+            .unwrap();
+        TipJar::unpack(&account.data).unwrap()
+    }
+
+    async fn tip(
+        &mut self,
+        tipper: &Keypair,
+        source: &Pubkey,
+        amount: u64,
+    ) -> Result<(), TransactionError> {
+        let instruction = tip(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &tipper.pubkey(),
+            &self.jar,
+            source,
+            &self.vault,
+            amount,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[tipper]).await
+    }
+
+    async fn withdraw(
+        &mut self,
+        creator: &Keypair,
+        destination: &Pubkey,
+        amount: u64,
+    ) -> Result<(), TransactionError> {
+        let instruction = withdraw(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &creator.pubkey(),
+            &self.jar,
+            &self.vault,
+            destination,
+            amount,
+        )
+        .unwrap();
+        self.env.process(&[instruction], &[creator]).await
+    }
+}
+
+fn custom(error: TippingError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_jar() {
+    let mut fixture = Fixture::new().await;
+    let jar = fixture.jar().await;
+    assert_eq!(jar.creator, fixture.creator.pubkey());
+    assert_eq!(jar.bank, fixture.bank);
+    assert_eq!(jar.vault, fixture.vault);
+    assert_eq!(
+        (jar.total_tipped, jar.tip_count, jar.tipper_count),
+        (0, 0, 0)
+    );
+    let vault = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(fixture.vault)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Account::unpack(&vault.data).unwrap().owner, fixture.jar);
+
+    let vault = Keypair::new();
+    let bank_program_id = fixture.env.bank_program_id;
+    fixture
+        .env
+        .create_account(&vault, Account::LEN, &bank_program_id)
+        .await;
+    let creator = Keypair::from_bytes(&fixture.creator.to_bytes()).unwrap();
+    let instruction = create_jar(
+        &fixture.env.program_id,
+        &bank_program_id,
+        &creator.pubkey(),
+        &vault.pubkey(),
+        &fixture.bank,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&creator]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::AccountAlreadyInitialized
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_tip() {
+    let mut fixture = Fixture::new().await;
+    let alice = Keypair::from_bytes(&fixture.alice.to_bytes()).unwrap();
+    let bob = Keypair::from_bytes(&fixture.bob.to_bytes()).unwrap();
+    let (alice_account, bob_account) = (fixture.alice_account, fixture.bob_account);
+
+    fixture.tip(&alice, &alice_account, 100).await.unwrap();
+    fixture.tip(&alice, &alice_account, 30).await.unwrap();
+    fixture.tip(&bob, &bob_account, 50).await.unwrap();
+    let jar = fixture.jar().await;
+    assert_eq!(
+        (jar.total_tipped, jar.tip_count, jar.tipper_count),
+        (180, 3, 2)
+    );
+    let vault = fixture.vault;
+    assert_eq!(fixture.env.amount(&vault).await, 180);
+    assert_eq!(fixture.env.amount(&alice_account).await, 870);
+
+    let (record, _) = tip_record_address(&fixture.env.program_id, &fixture.jar, &alice.pubkey());
+    let record = fixture
+        .env
+        .context
+        .banks_client
+        .get_account(record)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(TipRecord::unpack(&record.data).unwrap().amount, 130);
+
+    assert_eq!(
+        fixture.tip(&bob, &bob_account, 0).await,
+        custom(TippingError::InvalidAmount)
+    );
+}
+
+#[tokio::test]
+async fn test_withdraw() {
+    let mut fixture = Fixture::new().await;
+    let alice = Keypair::from_bytes(&fixture.alice.to_bytes()).unwrap();
+    let alice_account = fixture.alice_account;
+    fixture.tip(&alice, &alice_account, 300).await.unwrap();
+
+    let creator = Keypair::from_bytes(&fixture.creator.to_bytes()).unwrap();
+    let bank = fixture.bank;
+    let creator_account = fixture.env.create_bank_account(&bank, &creator, 0).await;
+    assert_eq!(
+        fixture.withdraw(&alice, &alice_account, 300).await,
+        custom(TippingError::NotCreator)
+    );
+    fixture
+        .withdraw(&creator, &creator_account, 200)
+        .await
+        .unwrap();
+    let vault = fixture.vault;
+    assert_eq!(fixture.env.amount(&creator_account).await, 200);
+    assert_eq!(fixture.env.amount(&vault).await, 100);
+    // Withdrawals leave the totals alone.
+    assert_eq!(fixture.jar().await.total_tipped, 300);
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+
+    // Through a program posing as the bank, alice would be counted as a tipper without
+    // any tokens reaching the jar.
+    let instruction = tip(
+        &fixture.env.program_id,
+        &fixture.env.fake_bank_program_id,
+        &fixture.bank,
+        &fixture.alice.pubkey(),
+        &fixture.jar,
+        &fixture.alice_account,
+        &fixture.vault,
+        1_000,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[&fixture.alice]).await,
+        custom(TippingError::InvalidVault)
+    );
+    let jar = fixture.jar().await;
+    assert_eq!((jar.total_tipped, jar.tip_count), (0, 0));
+    let (record, _) = tip_record_address(
+        &fixture.env.program_id,
+        &fixture.jar,
+        &fixture.alice.pubkey(),
+    );
+    assert!(fixture.env.account(&record).await.is_none());
+}
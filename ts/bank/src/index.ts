@@ -0,0 +1,1231 @@
+// Generated by `cargo xtask ts` from bank/idl.json; do not edit.
+
+export interface AccountMeta {
+  pubkey: string;
+  isSigner: boolean;
+  isWritable: boolean;
+}
+
+/** An instruction with base58 addresses, the fields of web3.js's `TransactionInstruction`. */
+export interface Instruction {
+  programId: string;
+  keys: AccountMeta[];
+  data: Uint8Array;
+}
+
+const BASE58_ALPHABET = '123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz';
+
+function encodeBase58(bytes: Uint8Array): string {
+  const digits: number[] = [];
+  for (const byte of bytes) {
+    let carry = byte;
+    for (let i = 0; i < digits.length; i++) {
+      carry += digits[i] * 256;
+      digits[i] = carry % 58;
+      carry = Math.floor(carry / 58);
+    }
+    while (carry > 0) {
+      digits.push(carry % 58);
+      carry = Math.floor(carry / 58);
+    }
+  }
+  let encoded = '';
+  for (let i = 0; i < bytes.length && bytes[i] === 0; i++) {
+    encoded += '1';
+  }
+  for (let i = digits.length - 1; i >= 0; i--) {
+    encoded += BASE58_ALPHABET[digits[i]];
+  }
+  return encoded;
+}
+
+function decodeBase58(encoded: string): Uint8Array {
+  const bytes: number[] = [];
+  for (const char of encoded) {
+    let carry = BASE58_ALPHABET.indexOf(char);
+    if (carry < 0) {
+      throw new Error(`invalid base58 character ${char}`);
+    }
+    for (let i = 0; i < bytes.length; i++) {
+      carry += bytes[i] * 58;
+      bytes[i] = carry & 0xff;
+      carry >>= 8;
+    }
+    while (carry > 0) {
+      bytes.push(carry & 0xff);
+      carry >>= 8;
+    }
+  }
+  for (let i = 0; i < encoded.length && encoded[i] === '1'; i++) {
+    bytes.push(0);
+  }
+  return Uint8Array.from(bytes.reverse());
+}
+
+function pushU8(data: number[], value: number): void {
+  if (!Number.isInteger(value) || value < 0 || value > 0xff) {
+    throw new RangeError(`${value} is not a u8`);
+  }
+  data.push(value);
+}
+
+function pushU16(data: number[], value: number): void {
+  if (!Number.isInteger(value) || value < 0 || value > 0xffff) {
+    throw new RangeError(`${value} is not a u16`);
+  }
+  data.push(value & 0xff, value >> 8);
+}
+
+function pushU64(data: number[], value: bigint): void {
+  if (value < BigInt(0) || value > BigInt('0xffffffffffffffff')) {
+    throw new RangeError(`${value} is not a u64`);
+  }
+  for (let i = 0; i < 8; i++) {
+    data.push(Number((value >> BigInt(8 * i)) & BigInt(0xff)));
+  }
+}
+
+function pushI64(data: number[], value: bigint): void {
+  if (value < -BigInt('0x8000000000000000') || value > BigInt('0x7fffffffffffffff')) {
+    throw new RangeError(`${value} is not an i64`);
+  }
+  pushU64(data, value < BigInt(0) ? value + BigInt('0x10000000000000000') : value);
+}
+
+function pushSeeds(data: number[], seeds: Uint8Array[]): void {
+  if (seeds.length > 16) {
+    throw new RangeError(`${seeds.length} seeds, more than 16`);
+  }
+  pushU8(data, seeds.length);
+  for (const seed of seeds) {
+    if (seed.length > 32) {
+      throw new RangeError(`a seed of ${seed.length} bytes, more than 32`);
+    }
+    pushU8(data, seed.length);
+    data.push(...seed);
+  }
+}
+
+function pushPublicKey(data: number[], key: string): void {
+  const bytes = decodeBase58(key);
+  if (bytes.length !== 32) {
+    throw new RangeError(`${key} is not a public key`);
+  }
+  data.push(...bytes);
+}
+
+function pushOptionPublicKey(data: number[], key: string | null): void {
+  if (key === null) {
+    data.push(0);
+  } else {
+    data.push(1);
+    pushPublicKey(data, key);
+  }
+}
+
+function pushPublicKeys(data: number[], keys: string[]): void {
+  pushU8(data, keys.length);
+  for (const key of keys) {
+    pushPublicKey(data, key);
+  }
+}
+
+function pushU64s(data: number[], values: bigint[]): void {
+  pushU8(data, values.length);
+  for (const value of values) {
+    pushU64(data, value);
+  }
+}
+
+function readU8(data: Uint8Array, offset: number): number {
+  return data[offset];
+}
+
+function readU16(data: Uint8Array, offset: number): number {
+  return data[offset] | (data[offset + 1] << 8);
+}
+
+function readU64(data: Uint8Array, offset: number): bigint {
+  let value = BigInt(0);
+  for (let i = 7; i >= 0; i--) {
+    value = (value << BigInt(8)) | BigInt(data[offset + i]);
+  }
+  return value;
+}
+
+function readI64(data: Uint8Array, offset: number): bigint {
+  return BigInt.asIntN(64, readU64(data, offset));
+}
+
+function readBool(data: Uint8Array, offset: number): boolean {
+  return data[offset] === 1;
+}
+
+function readPublicKey(data: Uint8Array, offset: number): string {
+  return encodeBase58(data.subarray(offset, offset + 32));
+}
+
+function readPublicKeyArray(data: Uint8Array, offset: number): string[] {
+  const keys: string[] = [];
+  for (let i = 0; i < 8; i++) {
+    keys.push(readPublicKey(data, offset + 32 * i));
+  }
+  return keys;
+}
+
+function readSignerArray(data: Uint8Array, offset: number): string[] {
+  const keys: string[] = [];
+  for (let i = 0; i < 11; i++) {
+    keys.push(readPublicKey(data, offset + 32 * i));
+  }
+  return keys;
+}
+
+function readCOptionPublicKey(data: Uint8Array, offset: number): string | null {
+  const tag = data.subarray(offset, offset + 4).join(',');
+  if (tag === '0,0,0,0') {
+    return null;
+  }
+  if (tag === '1,0,0,0') {
+    return readPublicKey(data, offset + 4);
+  }
+  throw new Error(`invalid option tag ${tag}`);
+}
+
+/** Opens a bank with `decimals` and `burn_policy`; the signer becomes its owner. */
+export function initializeBank(
+  programId: string,
+  accounts: { bank: string; bankOwner: string },
+  args: { decimals: number; burnPolicy: number; lamportsPerToken: bigint; freezeAuthority: string | null; interestRateBps: number },
+): Instruction {
+  const data: number[] = [0];
+  pushU8(data, args.decimals);
+  pushU8(data, args.burnPolicy);
+  pushU64(data, args.lamportsPerToken);
+  pushOptionPublicKey(data, args.freezeAuthority);
+  pushU16(data, args.interestRateBps);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.bankOwner, isSigner: true, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Opens an empty account in a bank. */
+export function initializeAccount(
+  programId: string,
+  accounts: { bank: string; account: string; owner: string },
+): Instruction {
+  const data: number[] = [1];
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Moves `amount` between two accounts of the same bank. */
+export function transfer(
+  programId: string,
+  accounts: { from: string; to: string; owner: string; bank: string },
+  args: { amount: bigint; sweepDust: boolean },
+): Instruction {
+  const data: number[] = [2];
+  pushU64(data, args.amount);
+  pushBool(data, args.sweepDust);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.from, isSigner: false, isWritable: true },
+      { pubkey: accounts.to, isSigner: false, isWritable: true },
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Lets a delegate spend up to `amount` more of an account's tokens, which stay in it. */
+export function approve(
+  programId: string,
+  accounts: { account: string; delegate: string; owner: string; bank: string },
+  args: { amount: bigint },
+): Instruction {
+  const data: number[] = [3];
+  pushU64(data, args.amount);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.delegate, isSigner: false, isWritable: true },
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+      { pubkey: accounts.bank, isSigner: false, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Creates `amount` new tokens in an account. */
+export function mintTo(
+  programId: string,
+  accounts: { bank: string; account: string; bankOwner: string },
+  args: { amount: bigint },
+): Instruction {
+  const data: number[] = [4];
+  pushU64(data, args.amount);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.bankOwner, isSigner: true, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Destroys `amount` tokens of an account. */
+export function burn(
+  programId: string,
+  accounts: { bank: string; account: string; bankOwner: string; owner: string },
+  args: { amount: bigint },
+): Instruction {
+  const data: number[] = [5];
+  pushU64(data, args.amount);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.bankOwner, isSigner: true, isWritable: true },
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Closes an account and sends its lamports to the destination. */
+export function closeAccount(
+  programId: string,
+  accounts: { account: string; destination: string; owner: string; bank: string },
+): Instruction {
+  const data: number[] = [6];
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.destination, isSigner: false, isWritable: true },
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+      { pubkey: accounts.bank, isSigner: false, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Cancels the delegation of an account along with what is left of its allowance. */
+export function revoke(
+  programId: string,
+  accounts: { account: string; owner: string; bank: string },
+): Instruction {
+  const data: number[] = [7];
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+      { pubkey: accounts.bank, isSigner: false, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Sets what closing an account of the bank does with the tokens it still holds. */
+export function setClosePolicy(
+  programId: string,
+  accounts: { bank: string; bankOwner: string },
+  args: { policy: number },
+): Instruction {
+  const data: number[] = [8];
+  pushU8(data, args.policy);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.bankOwner, isSigner: true, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Sets whether accounts opened in the bank must be rent-exempt. */
+export function setRequireRentExempt(
+  programId: string,
+  accounts: { bank: string; bankOwner: string },
+  args: { required: boolean },
+): Instruction {
+  const data: number[] = [9];
+  pushBool(data, args.required);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.bankOwner, isSigner: true, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Lets a delegate spend up to `amount` more of an account's tokens on the owner's off-chain signature of the permit `permit::message` describes, instead of theirs on the transaction. */
+export function permitApprove(
+  programId: string,
+  accounts: { account: string; delegate: string; bank: string; instructionsSysvar: string; featureGate: string },
+  args: { amount: bigint; nonce: bigint; expiry: bigint },
+): Instruction {
+  const data: number[] = [10];
+  pushU64(data, args.amount);
+  pushU64(data, args.nonce);
+  pushI64(data, args.expiry);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.delegate, isSigner: false, isWritable: false },
+      { pubkey: accounts.bank, isSigner: false, isWritable: false },
+      { pubkey: accounts.instructionsSysvar, isSigner: false, isWritable: false },
+      { pubkey: accounts.featureGate, isSigner: false, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** `Transfer`, refused once the clock's slot passes `max_slot`, so a transfer signed ahead of time (e.g. against a durable nonce) can't be sent arbitrarily late. */
+export function transferWithExpiry(
+  programId: string,
+  accounts: { from: string; to: string; owner: string; bank: string },
+  args: { amount: bigint; maxSlot: bigint },
+): Instruction {
+  const data: number[] = [11];
+  pushU64(data, args.amount);
+  pushU64(data, args.maxSlot);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.from, isSigner: false, isWritable: true },
+      { pubkey: accounts.to, isSigner: false, isWritable: true },
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Moves `amount` along a chain of accounts of the same bank, each hop a `Transfer` from one account to the next, so the route goes through whole or not at all. */
+export function transferRoute(
+  programId: string,
+  accounts: { bank: string; from: string; owner: string; to: string; featureGate: string },
+  args: { amount: bigint },
+): Instruction {
+  const data: number[] = [12];
+  pushU64(data, args.amount);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.from, isSigner: false, isWritable: true },
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+      { pubkey: accounts.to, isSigner: false, isWritable: true },
+      { pubkey: accounts.featureGate, isSigner: false, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Hands an account over to the address `seeds`, bump included, derive from a program, so that only that program can move its tokens, by signing with the seeds. */
+export function assignToProgram(
+  programId: string,
+  accounts: { account: string; owner: string; bank: string; program: string },
+  args: { seeds: Uint8Array[] },
+): Instruction {
+  const data: number[] = [13];
+  pushSeeds(data, args.seeds);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+      { pubkey: accounts.bank, isSigner: false, isWritable: false },
+      { pubkey: accounts.program, isSigner: false, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Moves `lamports` from the depositor into the bank and credits the account with the tokens they buy at the bank's `lamports_per_token`. */
+export function depositSol(
+  programId: string,
+  accounts: { bank: string; account: string; depositor: string; systemProgram: string },
+  args: { lamports: bigint },
+): Instruction {
+  const data: number[] = [14];
+  pushU64(data, args.lamports);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.depositor, isSigner: true, isWritable: true },
+      { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Burns the tokens `lamports` buys at the bank's `lamports_per_token` from the account and pays `lamports` out of the bank. */
+export function withdrawSol(
+  programId: string,
+  accounts: { bank: string; account: string; owner: string; destination: string },
+  args: { lamports: bigint },
+): Instruction {
+  const data: number[] = [15];
+  pushU64(data, args.lamports);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+      { pubkey: accounts.destination, isSigner: false, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Sets the rate `Convert` moves tokens of one bank into another at, to `numerator / denominator` whole tokens of the second per whole token of the first. */
+export function setExchangeRate(
+  programId: string,
+  accounts: { rate: string; fromBank: string; toBank: string; fromBankOwner: string; toBankOwner: string },
+  args: { numerator: bigint; denominator: bigint },
+): Instruction {
+  const data: number[] = [16];
+  pushU64(data, args.numerator);
+  pushU64(data, args.denominator);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.rate, isSigner: false, isWritable: true },
+      { pubkey: accounts.fromBank, isSigner: false, isWritable: false },
+      { pubkey: accounts.toBank, isSigner: false, isWritable: false },
+      { pubkey: accounts.fromBankOwner, isSigner: true, isWritable: true },
+      { pubkey: accounts.toBankOwner, isSigner: true, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Burns `amount` from an account and mints what it's worth at an `ExchangeRate` to an account of the other bank, rescaled between the banks' decimals and rounded down. */
+export function convert(
+  programId: string,
+  accounts: { rate: string; fromBank: string; fromAccount: string; owner: string; toBank: string; toAccount: string },
+  args: { amount: bigint },
+): Instruction {
+  const data: number[] = [17];
+  pushU64(data, args.amount);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.rate, isSigner: false, isWritable: false },
+      { pubkey: accounts.fromBank, isSigner: false, isWritable: true },
+      { pubkey: accounts.fromAccount, isSigner: false, isWritable: true },
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+      { pubkey: accounts.toBank, isSigner: false, isWritable: true },
+      { pubkey: accounts.toAccount, isSigner: false, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Sets the bank's `dust_threshold`. */
+export function setDustThreshold(
+  programId: string,
+  accounts: { bank: string; bankOwner: string },
+  args: { threshold: bigint },
+): Instruction {
+  const data: number[] = [18];
+  pushU64(data, args.threshold);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.bankOwner, isSigner: true, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Closes an account holding nothing or dust, and sends its lamports to its owner. */
+export function closeDustAccount(
+  programId: string,
+  accounts: { account: string; owner: string; bank: string },
+): Instruction {
+  const data: number[] = [19];
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.owner, isSigner: false, isWritable: true },
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Names the guardians who can recover an account, `threshold` of whom must sign, and the `delay` in slots between starting and completing a recovery. */
+export function setGuardians(
+  programId: string,
+  accounts: { account: string; owner: string; bank: string; recovery: string; payer: string; systemProgram: string },
+  args: { threshold: number; delay: bigint; guardians: string[] },
+): Instruction {
+  const data: number[] = [20];
+  pushU8(data, args.threshold);
+  pushU64(data, args.delay);
+  pushPublicKeys(data, args.guardians);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.account, isSigner: false, isWritable: false },
+      { pubkey: accounts.owner, isSigner: true, isWritable: false },
+      { pubkey: accounts.bank, isSigner: false, isWritable: false },
+      { pubkey: accounts.recovery, isSigner: false, isWritable: true },
+      { pubkey: accounts.payer, isSigner: true, isWritable: true },
+      { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Starts a recovery of an account to `new_owner`, replacing any started before; the threshold of its guardians sign, passed after the recovery state. */
+export function startRecovery(
+  programId: string,
+  accounts: { account: string; bank: string; recovery: string; guardian: string },
+  args: { newOwner: string },
+): Instruction {
+  const data: number[] = [21];
+  pushPublicKey(data, args.newOwner);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.account, isSigner: false, isWritable: false },
+      { pubkey: accounts.bank, isSigner: false, isWritable: false },
+      { pubkey: accounts.recovery, isSigner: false, isWritable: true },
+      { pubkey: accounts.guardian, isSigner: true, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Completes the started recovery once its delay has passed, making its new owner the account's; the threshold of its guardians sign, passed after the recovery state. */
+export function recoverOwner(
+  programId: string,
+  accounts: { account: string; bank: string; recovery: string; guardian: string },
+): Instruction {
+  const data: number[] = [22];
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.bank, isSigner: false, isWritable: false },
+      { pubkey: accounts.recovery, isSigner: false, isWritable: true },
+      { pubkey: accounts.guardian, isSigner: true, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Calls off the started recovery of an account. */
+export function cancelRecovery(
+  programId: string,
+  accounts: { account: string; owner: string; bank: string; recovery: string },
+): Instruction {
+  const data: number[] = [23];
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.account, isSigner: false, isWritable: false },
+      { pubkey: accounts.owner, isSigner: true, isWritable: false },
+      { pubkey: accounts.bank, isSigner: false, isWritable: false },
+      { pubkey: accounts.recovery, isSigner: false, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Lets `session_key` transfer up to `max_spend` out of an account, until the slot passes `expiry_slot`; its transfers pass the key's session state after the bank. */
+export function createSessionKey(
+  programId: string,
+  accounts: { account: string; owner: string; bank: string; session: string; payer: string; systemProgram: string },
+  args: { sessionKey: string; maxSpend: bigint; expirySlot: bigint },
+): Instruction {
+  const data: number[] = [24];
+  pushPublicKey(data, args.sessionKey);
+  pushU64(data, args.maxSpend);
+  pushU64(data, args.expirySlot);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.account, isSigner: false, isWritable: false },
+      { pubkey: accounts.owner, isSigner: true, isWritable: false },
+      { pubkey: accounts.bank, isSigner: false, isWritable: false },
+      { pubkey: accounts.session, isSigner: false, isWritable: true },
+      { pubkey: accounts.payer, isSigner: true, isWritable: true },
+      { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Caps how fast tokens leave an account created with room for a rate-limit bucket: up to `capacity` at once, regaining `refill_per_slot` per slot; a `capacity` of 0 lifts the limit. */
+export function setRateLimit(
+  programId: string,
+  accounts: { account: string; owner: string; bank: string },
+  args: { capacity: bigint; refillPerSlot: bigint },
+): Instruction {
+  const data: number[] = [25];
+  pushU64(data, args.capacity);
+  pushU64(data, args.refillPerSlot);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.owner, isSigner: true, isWritable: false },
+      { pubkey: accounts.bank, isSigner: false, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Records the running program's release and state layout in its version record, creating the record on first use at the payer's expense. */
+export function initializeVersion(
+  programId: string,
+  accounts: { version: string; payer: string; systemProgram: string },
+): Instruction {
+  const data: number[] = [26];
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.version, isSigner: false, isWritable: true },
+      { pubkey: accounts.payer, isSigner: true, isWritable: true },
+      { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Sets which gated features a bank runs, see `feature_gate`, creating the bank's gate on first use at the payer's expense. */
+export function setFeatures(
+  programId: string,
+  accounts: { bank: string; bankOwner: string; featureGate: string; payer: string; systemProgram: string },
+  args: { enabled: bigint },
+): Instruction {
+  const data: number[] = [27];
+  pushU64(data, args.enabled);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: false },
+      { pubkey: accounts.bankOwner, isSigner: true, isWritable: false },
+      { pubkey: accounts.featureGate, isSigner: false, isWritable: true },
+      { pubkey: accounts.payer, isSigner: true, isWritable: true },
+      { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Freezes an account of a bank with a freeze authority, so nothing moves in or out of it until it's thawed. */
+export function freezeAccount(
+  programId: string,
+  accounts: { account: string; bank: string; freezeAuthority: string },
+): Instruction {
+  const data: number[] = [28];
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.bank, isSigner: false, isWritable: false },
+      { pubkey: accounts.freezeAuthority, isSigner: true, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Thaws an account `FreezeAccount` froze. */
+export function thawAccount(
+  programId: string,
+  accounts: { account: string; bank: string; freezeAuthority: string },
+): Instruction {
+  const data: number[] = [29];
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.bank, isSigner: false, isWritable: false },
+      { pubkey: accounts.freezeAuthority, isSigner: true, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Names the signers of a multisig owner, `threshold` of whom must sign in its place; the signers, up to 11, are passed after the multisig. */
+export function initializeMultisig(
+  programId: string,
+  accounts: { multisig: string; signer: string },
+  args: { threshold: number },
+): Instruction {
+  const data: number[] = [30];
+  pushU8(data, args.threshold);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.multisig, isSigner: false, isWritable: true },
+      { pubkey: accounts.signer, isSigner: false, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Creates and opens a wallet's associated account in a bank, at the PDA of this program derived from `associated`, the wallet and the bank; the wallet needn't sign. */
+export function initializeAssociatedAccount(
+  programId: string,
+  accounts: { bank: string; account: string; wallet: string; payer: string; systemProgram: string },
+): Instruction {
+  const data: number[] = [31];
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: false },
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.wallet, isSigner: false, isWritable: false },
+      { pubkey: accounts.payer, isSigner: true, isWritable: true },
+      { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** `Transfer` without sweeping dust, refused unless the bank has `decimals`. */
+export function transferChecked(
+  programId: string,
+  accounts: { from: string; to: string; owner: string; bank: string },
+  args: { amount: bigint; decimals: number },
+): Instruction {
+  const data: number[] = [32];
+  pushU64(data, args.amount);
+  pushU8(data, args.decimals);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.from, isSigner: false, isWritable: true },
+      { pubkey: accounts.to, isSigner: false, isWritable: true },
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** `MintTo`, refused unless the bank has `decimals`. */
+export function mintToChecked(
+  programId: string,
+  accounts: { bank: string; account: string; bankOwner: string },
+  args: { amount: bigint; decimals: number },
+): Instruction {
+  const data: number[] = [33];
+  pushU64(data, args.amount);
+  pushU8(data, args.decimals);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.bankOwner, isSigner: true, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** `Burn`, refused unless the bank has `decimals`. */
+export function burnChecked(
+  programId: string,
+  accounts: { bank: string; account: string; bankOwner: string; owner: string },
+  args: { amount: bigint; decimals: number },
+): Instruction {
+  const data: number[] = [34];
+  pushU64(data, args.amount);
+  pushU8(data, args.decimals);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.bankOwner, isSigner: true, isWritable: true },
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Hands the `authority_type` authority of an account or a bank to `new_authority`, or removes the emergency authority; a new account owner starts without a delegate, and a new bank owner waits out the bank's `owner_change_delay` as pending, applied by sending the same instruction again. */
+export function setAuthority(
+  programId: string,
+  accounts: { owned: string; authority: string },
+  args: { authorityType: number; newAuthority: string | null },
+): Instruction {
+  const data: number[] = [35];
+  pushU8(data, args.authorityType);
+  pushOptionPublicKey(data, args.newAuthority);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.owned, isSigner: false, isWritable: true },
+      { pubkey: accounts.authority, isSigner: true, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Pays the `i`th of `amounts` from one account to the `i`th destination, passed after the bank, each payment a `Transfer` of its own, so the batch goes through whole or not at all. */
+export function transferBatch(
+  programId: string,
+  accounts: { from: string; owner: string; bank: string; to: string },
+  args: { amounts: bigint[] },
+): Instruction {
+  const data: number[] = [36];
+  pushU64s(data, args.amounts);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.from, isSigner: false, isWritable: true },
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.to, isSigner: false, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Pauses a bank for an emergency, so its transfers, approvals, mints and burns fail until it's resumed. */
+export function pauseBank(
+  programId: string,
+  accounts: { bank: string; bankOwner: string },
+): Instruction {
+  const data: number[] = [37];
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.bankOwner, isSigner: true, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Resumes a bank `PauseBank` paused. */
+export function resumeBank(
+  programId: string,
+  accounts: { bank: string; bankOwner: string },
+): Instruction {
+  const data: number[] = [38];
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.bankOwner, isSigner: true, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Pays an account the interest its bank owes it so far; anyone can send it. */
+export function accrueInterest(
+  programId: string,
+  accounts: { account: string; bank: string },
+): Instruction {
+  const data: number[] = [39];
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.account, isSigner: false, isWritable: true },
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Sets the slots a bank owner change waits before it can be applied; it can only be raised. */
+export function setOwnerChangeDelay(
+  programId: string,
+  accounts: { bank: string; bankOwner: string },
+  args: { delay: bigint },
+): Instruction {
+  const data: number[] = [40];
+  pushU64(data, args.delay);
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.bankOwner, isSigner: true, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+/** Calls off the change of bank owner a `SetAuthority` left pending; the bank owner or the emergency authority may send it. */
+export function cancelOwnerChange(
+  programId: string,
+  accounts: { bank: string; authority: string },
+): Instruction {
+  const data: number[] = [41];
+  return {
+    programId,
+    keys: [
+      { pubkey: accounts.bank, isSigner: false, isWritable: true },
+      { pubkey: accounts.authority, isSigner: true, isWritable: false },
+    ],
+    data: Uint8Array.from(data),
+  };
+}
+
+export const BANK_SIZE = 220;
+
+export interface Bank {
+  decimals: number;
+  bankOwner: string;
+  isOpened: boolean;
+  totalSupply: bigint;
+  closePolicy: number;
+  requireRentExempt: boolean;
+  burnPolicy: number;
+  lamportsPerToken: bigint;
+  transferCount: bigint;
+  cumulativeVolume: bigint;
+  holderCount: bigint;
+  registeredAccounts: bigint;
+  dustThreshold: bigint;
+  freezeAuthority: string | null;
+  isPaused: boolean;
+  interestRateBps: number;
+  emergencyAuthority: string | null;
+  ownerChangeDelay: bigint;
+  pendingBankOwner: string | null;
+  ownerChangeSlot: bigint;
+}
+
+export function decodeBank(data: Uint8Array): Bank {
+  if (data.length !== BANK_SIZE) {
+    throw new RangeError(`Bank is ${BANK_SIZE} bytes, got ${data.length}`);
+  }
+  return {
+    decimals: readU8(data, 0),
+    bankOwner: readPublicKey(data, 1),
+    isOpened: readBool(data, 33),
+    totalSupply: readU64(data, 34),
+    closePolicy: readU8(data, 42),
+    requireRentExempt: readBool(data, 43),
+    burnPolicy: readU8(data, 44),
+    lamportsPerToken: readU64(data, 45),
+    transferCount: readU64(data, 53),
+    cumulativeVolume: readU64(data, 61),
+    holderCount: readU64(data, 69),
+    registeredAccounts: readU64(data, 77),
+    dustThreshold: readU64(data, 85),
+    freezeAuthority: readCOptionPublicKey(data, 93),
+    isPaused: readBool(data, 129),
+    interestRateBps: readU16(data, 130),
+    emergencyAuthority: readCOptionPublicKey(data, 132),
+    ownerChangeDelay: readU64(data, 168),
+    pendingBankOwner: readCOptionPublicKey(data, 176),
+    ownerChangeSlot: readU64(data, 212),
+  };
+}
+
+export const ACCOUNT_SIZE = 135;
+
+export interface Account {
+  amount: bigint;
+  isOpened: boolean;
+  isInitialized: boolean;
+  owner: string;
+  delegate: string | null;
+  delegatedAmount: bigint;
+  bank: string;
+  permitNonce: bigint;
+  isFrozen: boolean;
+  lastAccrual: bigint;
+}
+
+export function decodeAccount(data: Uint8Array): Account {
+  if (data.length !== ACCOUNT_SIZE) {
+    throw new RangeError(`Account is ${ACCOUNT_SIZE} bytes, got ${data.length}`);
+  }
+  return {
+    amount: readU64(data, 0),
+    isOpened: readBool(data, 8),
+    isInitialized: readBool(data, 9),
+    owner: readPublicKey(data, 10),
+    delegate: readCOptionPublicKey(data, 42),
+    delegatedAmount: readU64(data, 78),
+    bank: readPublicKey(data, 86),
+    permitNonce: readU64(data, 118),
+    isFrozen: readBool(data, 126),
+    lastAccrual: readI64(data, 127),
+  };
+}
+
+export const EXCHANGERATE_SIZE = 81;
+
+export interface ExchangeRate {
+  isInitialized: boolean;
+  fromBank: string;
+  toBank: string;
+  numerator: bigint;
+  denominator: bigint;
+}
+
+export function decodeExchangeRate(data: Uint8Array): ExchangeRate {
+  if (data.length !== EXCHANGERATE_SIZE) {
+    throw new RangeError(`ExchangeRate is ${EXCHANGERATE_SIZE} bytes, got ${data.length}`);
+  }
+  return {
+    isInitialized: readBool(data, 0),
+    fromBank: readPublicKey(data, 1),
+    toBank: readPublicKey(data, 33),
+    numerator: readU64(data, 65),
+    denominator: readU64(data, 73),
+  };
+}
+
+export const RECOVERY_SIZE = 375;
+
+export interface Recovery {
+  isInitialized: boolean;
+  account: string;
+  owner: string;
+  threshold: number;
+  guardianCount: number;
+  guardians: string[];
+  delay: bigint;
+  pendingOwner: string | null;
+  activationSlot: bigint;
+}
+
+export function decodeRecovery(data: Uint8Array): Recovery {
+  if (data.length !== RECOVERY_SIZE) {
+    throw new RangeError(`Recovery is ${RECOVERY_SIZE} bytes, got ${data.length}`);
+  }
+  return {
+    isInitialized: readBool(data, 0),
+    account: readPublicKey(data, 1),
+    owner: readPublicKey(data, 33),
+    threshold: readU8(data, 65),
+    guardianCount: readU8(data, 66),
+    guardians: readPublicKeyArray(data, 67),
+    delay: readU64(data, 323),
+    pendingOwner: readCOptionPublicKey(data, 331),
+    activationSlot: readU64(data, 367),
+  };
+}
+
+export const SESSION_SIZE = 113;
+
+export interface Session {
+  isInitialized: boolean;
+  account: string;
+  owner: string;
+  sessionKey: string;
+  remainingSpend: bigint;
+  expirySlot: bigint;
+}
+
+export function decodeSession(data: Uint8Array): Session {
+  if (data.length !== SESSION_SIZE) {
+    throw new RangeError(`Session is ${SESSION_SIZE} bytes, got ${data.length}`);
+  }
+  return {
+    isInitialized: readBool(data, 0),
+    account: readPublicKey(data, 1),
+    owner: readPublicKey(data, 33),
+    sessionKey: readPublicKey(data, 65),
+    remainingSpend: readU64(data, 97),
+    expirySlot: readU64(data, 105),
+  };
+}
+
+export const VERSION_SIZE = 9;
+
+export interface Version {
+  isInitialized: boolean;
+  major: number;
+  minor: number;
+  patch: number;
+  layout: number;
+}
+
+export function decodeVersion(data: Uint8Array): Version {
+  if (data.length !== VERSION_SIZE) {
+    throw new RangeError(`Version is ${VERSION_SIZE} bytes, got ${data.length}`);
+  }
+  return {
+    isInitialized: readBool(data, 0),
+    major: readU16(data, 1),
+    minor: readU16(data, 3),
+    patch: readU16(data, 5),
+    layout: readU16(data, 7),
+  };
+}
+
+export const FEATUREGATE_SIZE = 41;
+
+export interface FeatureGate {
+  isInitialized: boolean;
+  bank: string;
+  enabled: bigint;
+}
+
+export function decodeFeatureGate(data: Uint8Array): FeatureGate {
+  if (data.length !== FEATUREGATE_SIZE) {
+    throw new RangeError(`FeatureGate is ${FEATUREGATE_SIZE} bytes, got ${data.length}`);
+  }
+  return {
+    isInitialized: readBool(data, 0),
+    bank: readPublicKey(data, 1),
+    enabled: readU64(data, 33),
+  };
+}
+
+export const MULTISIG_SIZE = 355;
+
+export interface Multisig {
+  isInitialized: boolean;
+  threshold: number;
+  signerCount: number;
+  signers: string[];
+}
+
+export function decodeMultisig(data: Uint8Array): Multisig {
+  if (data.length !== MULTISIG_SIZE) {
+    throw new RangeError(`Multisig is ${MULTISIG_SIZE} bytes, got ${data.length}`);
+  }
+  return {
+    isInitialized: readBool(data, 0),
+    threshold: readU8(data, 1),
+    signerCount: readU8(data, 2),
+    signers: readSignerArray(data, 3),
+  };
+}
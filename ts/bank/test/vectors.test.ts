@@ -0,0 +1,396 @@
+// Checks the generated builders and decoders against the bytes the Rust program
+// produces, from bank/program/tests/fixtures/vectors.json.
+
+import { strict as assert } from 'assert';
+import { readFileSync } from 'fs';
+import { join } from 'path';
+import { test } from 'node:test';
+import * as bank from '../src';
+
+interface InstructionVector {
+  name: string;
+  fields: { [name: string]: any };
+  program_id: string;
+  accounts: { pubkey: string; is_signer: boolean; is_writable: boolean }[];
+  data: string;
+}
+
+interface StateVector {
+  type: string;
+  fields: { [name: string]: any };
+  data: string;
+}
+
+const vectors: { instructions: InstructionVector[]; states: StateVector[] } = JSON.parse(
+  readFileSync(join(__dirname, '../../../../bank/program/tests/fixtures/vectors.json'), 'utf8'),
+);
+
+function fromHex(hex: string): Uint8Array {
+  return Uint8Array.from(Buffer.from(hex, 'hex'));
+}
+
+function build(vector: InstructionVector): bank.Instruction {
+  const [a, b, c, d, e, f] = vector.accounts.map((meta) => meta.pubkey);
+  const programId = vector.program_id;
+  const fields = vector.fields;
+  switch (vector.name) {
+    case 'InitializeBank':
+      return bank.initializeBank(
+        programId,
+        { bank: a, bankOwner: b },
+        {
+          decimals: fields.decimals,
+          burnPolicy: fields.burn_policy,
+          lamportsPerToken: BigInt(fields.lamports_per_token),
+          freezeAuthority: fields.freeze_authority,
+          interestRateBps: fields.interest_rate_bps,
+        },
+      );
+    case 'InitializeAccount':
+      return bank.initializeAccount(programId, { bank: a, account: b, owner: c });
+    case 'Transfer':
+      return bank.transfer(
+        programId,
+        { from: a, to: b, owner: c, bank: d },
+        { amount: BigInt(fields.amount), sweepDust: fields.sweep_dust },
+      );
+    case 'Approve':
+      return bank.approve(
+        programId,
+        { account: a, delegate: b, owner: c, bank: d },
+        { amount: BigInt(fields.amount) },
+      );
+    case 'MintTo':
+      return bank.mintTo(
+        programId,
+        { bank: a, account: b, bankOwner: c },
+        { amount: BigInt(fields.amount) },
+      );
+    case 'Burn':
+      return bank.burn(
+        programId,
+        { bank: a, account: b, bankOwner: c, owner: d },
+        { amount: BigInt(fields.amount) },
+      );
+    case 'CloseAccount':
+      return bank.closeAccount(programId, { account: a, destination: b, owner: c, bank: d });
+    case 'Revoke':
+      return bank.revoke(programId, { account: a, owner: b, bank: c });
+    case 'SetClosePolicy':
+      return bank.setClosePolicy(programId, { bank: a, bankOwner: b }, { policy: fields.policy });
+    case 'SetRequireRentExempt':
+      return bank.setRequireRentExempt(
+        programId,
+        { bank: a, bankOwner: b },
+        { required: fields.required },
+      );
+    case 'PermitApprove':
+      return bank.permitApprove(
+        programId,
+        { account: a, delegate: b, bank: c, instructionsSysvar: d, featureGate: e },
+        {
+          amount: BigInt(fields.amount),
+          nonce: BigInt(fields.nonce),
+          expiry: BigInt(fields.expiry),
+        },
+      );
+    case 'TransferWithExpiry':
+      return bank.transferWithExpiry(
+        programId,
+        { from: a, to: b, owner: c, bank: d },
+        { amount: BigInt(fields.amount), maxSlot: BigInt(fields.max_slot) },
+      );
+    case 'TransferRoute':
+      return bank.transferRoute(
+        programId,
+        { bank: a, from: b, owner: c, to: d, featureGate: e },
+        { amount: BigInt(fields.amount) },
+      );
+    case 'AssignToProgram':
+      return bank.assignToProgram(
+        programId,
+        { account: a, owner: b, bank: c, program: d },
+        { seeds: fields.seeds.map(fromHex) },
+      );
+    case 'DepositSol':
+      return bank.depositSol(
+        programId,
+        { bank: a, account: b, depositor: c, systemProgram: d },
+        { lamports: BigInt(fields.lamports) },
+      );
+    case 'WithdrawSol':
+      return bank.withdrawSol(
+        programId,
+        { bank: a, account: b, owner: c, destination: d },
+        { lamports: BigInt(fields.lamports) },
+      );
+    case 'SetExchangeRate':
+      return bank.setExchangeRate(
+        programId,
+        { rate: a, fromBank: b, toBank: c, fromBankOwner: d, toBankOwner: e },
+        { numerator: BigInt(fields.numerator), denominator: BigInt(fields.denominator) },
+      );
+    case 'Convert':
+      return bank.convert(
+        programId,
+        { rate: a, fromBank: b, fromAccount: c, owner: d, toBank: e, toAccount: f },
+        { amount: BigInt(fields.amount) },
+      );
+    case 'SetDustThreshold':
+      return bank.setDustThreshold(
+        programId,
+        { bank: a, bankOwner: b },
+        { threshold: BigInt(fields.threshold) },
+      );
+    case 'CloseDustAccount':
+      return bank.closeDustAccount(programId, { account: a, owner: b, bank: c });
+    case 'SetGuardians':
+      return bank.setGuardians(
+        programId,
+        { account: a, owner: b, bank: c, recovery: d, payer: e, systemProgram: f },
+        {
+          threshold: fields.threshold,
+          delay: BigInt(fields.delay),
+          guardians: fields.guardians,
+        },
+      );
+    case 'StartRecovery':
+      return bank.startRecovery(
+        programId,
+        { account: a, bank: b, recovery: c, guardian: d },
+        { newOwner: fields.new_owner },
+      );
+    case 'RecoverOwner':
+      return bank.recoverOwner(programId, { account: a, bank: b, recovery: c, guardian: d });
+    case 'CancelRecovery':
+      return bank.cancelRecovery(programId, { account: a, owner: b, bank: c, recovery: d });
+    case 'CreateSessionKey':
+      return bank.createSessionKey(
+        programId,
+        { account: a, owner: b, bank: c, session: d, payer: e, systemProgram: f },
+        {
+          sessionKey: fields.session_key,
+          maxSpend: BigInt(fields.max_spend),
+          expirySlot: BigInt(fields.expiry_slot),
+        },
+      );
+    case 'SetRateLimit':
+      return bank.setRateLimit(
+        programId,
+        { account: a, owner: b, bank: c },
+        { capacity: BigInt(fields.capacity), refillPerSlot: BigInt(fields.refill_per_slot) },
+      );
+    case 'InitializeVersion':
+      return bank.initializeVersion(programId, { version: a, payer: b, systemProgram: c });
+    case 'SetFeatures':
+      return bank.setFeatures(
+        programId,
+        { bank: a, bankOwner: b, featureGate: c, payer: d, systemProgram: e },
+        { enabled: BigInt(fields.enabled) },
+      );
+    case 'FreezeAccount':
+      return bank.freezeAccount(programId, { account: a, bank: b, freezeAuthority: c });
+    case 'ThawAccount':
+      return bank.thawAccount(programId, { account: a, bank: b, freezeAuthority: c });
+    case 'InitializeMultisig':
+      return bank.initializeMultisig(
+        programId,
+        { multisig: a, signer: b },
+        { threshold: fields.threshold },
+      );
+    case 'InitializeAssociatedAccount':
+      return bank.initializeAssociatedAccount(programId, {
+        bank: a,
+        account: b,
+        wallet: c,
+        payer: d,
+        systemProgram: e,
+      });
+    case 'TransferChecked':
+      return bank.transferChecked(
+        programId,
+        { from: a, to: b, owner: c, bank: d },
+        { amount: BigInt(fields.amount), decimals: fields.decimals },
+      );
+    case 'MintToChecked':
+      return bank.mintToChecked(
+        programId,
+        { bank: a, account: b, bankOwner: c },
+        { amount: BigInt(fields.amount), decimals: fields.decimals },
+      );
+    case 'BurnChecked':
+      return bank.burnChecked(
+        programId,
+        { bank: a, account: b, bankOwner: c, owner: d },
+        { amount: BigInt(fields.amount), decimals: fields.decimals },
+      );
+    case 'SetAuthority':
+      return bank.setAuthority(
+        programId,
+        { owned: a, authority: b },
+        { authorityType: fields.authority_type, newAuthority: fields.new_authority },
+      );
+    case 'TransferBatch':
+      return bank.transferBatch(
+        programId,
+        { from: a, owner: b, bank: c, to: d },
+        { amounts: fields.amounts.map(BigInt) },
+      );
+    case 'PauseBank':
+      return bank.pauseBank(programId, { bank: a, bankOwner: b });
+    case 'ResumeBank':
+      return bank.resumeBank(programId, { bank: a, bankOwner: b });
+    case 'AccrueInterest':
+      return bank.accrueInterest(programId, { account: a, bank: b });
+    case 'SetOwnerChangeDelay':
+      return bank.setOwnerChangeDelay(
+        programId,
+        { bank: a, bankOwner: b },
+        { delay: BigInt(fields.delay) },
+      );
+    case 'CancelOwnerChange':
+      return bank.cancelOwnerChange(programId, { bank: a, authority: b });
+    default:
+      throw new Error(`no builder for ${vector.name}`);
+  }
+}
+
+for (const vector of vectors.instructions) {
+  test(`builds ${vector.name}`, () => {
+    const instruction = build(vector);
+    assert.equal(instruction.programId, vector.program_id);
+    assert.deepEqual(
+      instruction.keys,
+      vector.accounts.map((meta) => ({
+        pubkey: meta.pubkey,
+        isSigner: meta.is_signer,
+        isWritable: meta.is_writable,
+      })),
+    );
+    assert.deepEqual(instruction.data, fromHex(vector.data));
+  });
+}
+
+vectors.states.forEach((vector, i) => {
+  test(`decodes ${vector.type} #${i}`, () => {
+    const data = fromHex(vector.data);
+    const f = vector.fields;
+    if (vector.type === 'Bank') {
+      assert.deepEqual(bank.decodeBank(data), {
+        decimals: f.decimals,
+        bankOwner: f.bank_owner,
+        isOpened: f.is_opened,
+        totalSupply: BigInt(f.total_supply),
+        closePolicy: f.close_policy,
+        requireRentExempt: f.require_rent_exempt,
+        burnPolicy: f.burn_policy,
+        lamportsPerToken: BigInt(f.lamports_per_token),
+        transferCount: BigInt(f.transfer_count),
+        cumulativeVolume: BigInt(f.cumulative_volume),
+        holderCount: BigInt(f.holder_count),
+        registeredAccounts: BigInt(f.registered_accounts),
+        dustThreshold: BigInt(f.dust_threshold),
+        freezeAuthority: f.freeze_authority,
+        isPaused: f.is_paused,
+        interestRateBps: f.interest_rate_bps,
+        emergencyAuthority: f.emergency_authority,
+        ownerChangeDelay: BigInt(f.owner_change_delay),
+        pendingBankOwner: f.pending_bank_owner,
+        ownerChangeSlot: BigInt(f.owner_change_slot),
+      });
+    } else if (vector.type === 'ExchangeRate') {
+      assert.deepEqual(bank.decodeExchangeRate(data), {
+        isInitialized: f.is_initialized,
+        fromBank: f.from_bank,
+        toBank: f.to_bank,
+        numerator: BigInt(f.numerator),
+        denominator: BigInt(f.denominator),
+      });
+    } else if (vector.type === 'Recovery') {
+      assert.deepEqual(bank.decodeRecovery(data), {
+        isInitialized: f.is_initialized,
+        account: f.account,
+        owner: f.owner,
+        threshold: f.threshold,
+        guardianCount: f.guardian_count,
+        guardians: f.guardians,
+        delay: BigInt(f.delay),
+        pendingOwner: f.pending_owner,
+        activationSlot: BigInt(f.activation_slot),
+      });
+    } else if (vector.type === 'Session') {
+      assert.deepEqual(bank.decodeSession(data), {
+        isInitialized: f.is_initialized,
+        account: f.account,
+        owner: f.owner,
+        sessionKey: f.session_key,
+        remainingSpend: BigInt(f.remaining_spend),
+        expirySlot: BigInt(f.expiry_slot),
+      });
+    } else if (vector.type === 'Version') {
+      assert.deepEqual(bank.decodeVersion(data), {
+        isInitialized: f.is_initialized,
+        major: f.major,
+        minor: f.minor,
+        patch: f.patch,
+        layout: f.layout,
+      });
+    } else if (vector.type === 'FeatureGate') {
+      assert.deepEqual(bank.decodeFeatureGate(data), {
+        isInitialized: f.is_initialized,
+        bank: f.bank,
+        enabled: BigInt(f.enabled),
+      });
+    } else if (vector.type === 'Multisig') {
+      assert.deepEqual(bank.decodeMultisig(data), {
+        isInitialized: f.is_initialized,
+        threshold: f.threshold,
+        signerCount: f.signer_count,
+        signers: f.signers,
+      });
+    } else {
+      assert.deepEqual(bank.decodeAccount(data), {
+        amount: BigInt(f.amount),
+        isOpened: f.is_opened,
+        isInitialized: f.is_initialized,
+        owner: f.owner,
+        delegate: f.delegate,
+        delegatedAmount: BigInt(f.delegated_amount),
+        bank: f.bank,
+        permitNonce: BigInt(f.permit_nonce),
+        isFrozen: f.is_frozen,
+        lastAccrual: BigInt(f.last_accrual),
+      });
+    }
+    const decode = {
+      Bank: bank.decodeBank,
+      Account: bank.decodeAccount,
+      ExchangeRate: bank.decodeExchangeRate,
+      Recovery: bank.decodeRecovery,
+      Session: bank.decodeSession,
+      Version: bank.decodeVersion,
+      FeatureGate: bank.decodeFeatureGate,
+      Multisig: bank.decodeMultisig,
+    };
+    assert.throws(() => decode[vector.type as keyof typeof decode](data.subarray(1)));
+  });
+});
+
+test('rejects out of range arguments', () => {
+  const key = vectors.instructions[0].program_id;
+  assert.throws(() => bank.initializeBank(key, { bank: key, bankOwner: key }, { decimals: 256 }));
+  assert.throws(() =>
+    bank.transfer(
+      key,
+      { from: key, to: key, owner: key, bank: key },
+      { amount: BigInt(2) ** BigInt(64) },
+    ),
+  );
+  assert.throws(() =>
+    bank.startRecovery(
+      key,
+      { account: key, bank: key, recovery: key, guardian: key },
+      { newOwner: key.slice(0, 8) },
+    ),
+  );
+});
@@ -0,0 +1,17 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VestingError {
+    /// The schedule doesn't satisfy `start <= cliff <= end` with `start < end`.
+    InvalidSchedule,
+    /// Nothing has vested since the last claim.
+    NothingToClaim,
+    /// The vault authority isn't the vesting account's program address.
+    InvalidVaultAuthority,
+}
+
+impl From<VestingError> for ProgramError {
+    fn from(e: VestingError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
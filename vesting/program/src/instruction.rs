@@ -0,0 +1,207 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the vault authority, followed by the vesting account's address.
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"vesting";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum VestingInstruction {
+    /// Locks `amount` of the grantor's tokens for the beneficiary: initializes the vault in
+    /// the source account's bank, owned by the vault authority, and moves the grant into it.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable, signer]` The grantor.
+    ///   1. `[writable]` The vesting account, owned by this program.
+    ///   2. `[writable]` The vault, an uninitialized account owned by the bank program.
+    ///   3. `[writable]` The grantor's source account.
+    ///   4. `[writable]` The bank of the source account.
+    ///   5. `[]` The beneficiary.
+    ///   6. `[writable]` The vault authority.
+    ///   7. `[]` The bank program.
+    Create {
+        amount: u64,
+        start_ts: i64,
+        cliff_ts: i64,
+        end_ts: i64,
+    },
+
+    /// Moves everything vested and not yet claimed from the vault to the beneficiary's
+    /// destination account.
+    ///
+    /// Accounts expected:
+    ///   0. `[signer]` The beneficiary.
+    ///   1. `[writable]` The vesting account.
+    ///   2. `[writable]` The vault.
+    ///   3. `[writable]` The destination account.
+    ///   4. `[writable]` The vault authority.
+    ///   5. `[]` The vault's bank.
+    ///   6. `[]` The bank program.
+    Claim,
+}
+
+impl VestingInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let (amount, rest) = Self::unpack_u64(rest)?;
+                let (start_ts, rest) = Self::unpack_u64(rest)?;
+                let (cliff_ts, rest) = Self::unpack_u64(rest)?;
+                let (end_ts, _rest) = Self::unpack_u64(rest)?;
+                Self::Create {
+                    amount,
+                    start_ts: start_ts as i64,
+                    cliff_ts: cliff_ts as i64,
+                    end_ts: end_ts as i64,
+                }
+            }
+            1 => Self::Claim,
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(33);
+        match *self {
+            Self::Create {
+                amount,
+                start_ts,
+                cliff_ts,
+                end_ts,
+            } => {
+                buf.push(0);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(&start_ts.to_le_bytes());
+                buf.extend_from_slice(&cliff_ts.to_le_bytes());
+                buf.extend_from_slice(&end_ts.to_le_bytes());
+            }
+            Self::Claim => buf.push(1),
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of the authority owning `vesting`'s vault.
+pub fn vault_authority(vesting_program_id: &Pubkey, vesting: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VAULT_AUTHORITY_SEED, vesting.as_ref()],
+        vesting_program_id,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_vesting(
+    vesting_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    grantor: &Pubkey,
+    vesting: &Pubkey,
+    vault: &Pubkey,
+    source_account: &Pubkey,
+    bank: &Pubkey,
+    beneficiary: &Pubkey,
+    amount: u64,
+    start_ts: i64,
+    cliff_ts: i64,
+    end_ts: i64,
+) -> Result<Instruction, ProgramError> {
+    let data = VestingInstruction::Create {
+        amount,
+        start_ts,
+        cliff_ts,
+        end_ts,
+    }
+    .pack();
+    let (authority, _) = vault_authority(vesting_program_id, vesting);
+    let accounts = vec![
+        AccountMeta::new(*grantor, true),
+        AccountMeta::new(*vesting, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*source_account, false),
+        // Writable for the same reason as the escrow's deposit bank: the bank program's
+        // builders ask for it and a CPI can't grant more than the outer instruction did.
+        AccountMeta::new(*bank, false),
+        AccountMeta::new_readonly(*beneficiary, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *vesting_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn claim(
+    vesting_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    bank: &Pubkey,
+    beneficiary: &Pubkey,
+    vesting: &Pubkey,
+    vault: &Pubkey,
+    destination_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = VestingInstruction::Claim.pack();
+    let (authority, _) = vault_authority(vesting_program_id, vesting);
+    let accounts = vec![
+        AccountMeta::new_readonly(*beneficiary, true),
+        AccountMeta::new(*vesting, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *vesting_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            VestingInstruction::Create {
+                amount: u64::MAX,
+                start_ts: -1,
+                cliff_ts: 0,
+                end_ts: i64::MAX,
+            },
+            VestingInstruction::Claim,
+        ];
+        for instruction in cases {
+            assert_eq!(
+                VestingInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            VestingInstruction::unpack(&[0, 1, 2, 3]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            VestingInstruction::unpack(&[2]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{
+    error::VestingError,
+    instruction::{VestingInstruction, VAULT_AUTHORITY_SEED},
+    state::Vesting,
+};
+use solana_bank::instruction as bank_instruction;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = VestingInstruction::unpack(input)?;
+
+        match instruction {
+            VestingInstruction::Create {
+                amount,
+                start_ts,
+                cliff_ts,
+                end_ts,
+            } => {
+                msg!("Instruction: Create");
+                Self::process_create(program_id, accounts, amount, start_ts, cliff_ts, end_ts)
+            }
+            VestingInstruction::Claim => {
+                msg!("Instruction: Claim");
+                Self::process_claim(program_id, accounts)
+            }
+        }
+    }
+
+    pub fn process_create(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+        start_ts: i64,
+        cliff_ts: i64,
+        end_ts: i64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let grantor_info = next_account_info(account_info_iter)?;
+        let vesting_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let beneficiary_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !grantor_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if vesting_info.owner != program_id || vault_info.owner != bank_program_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if !Vesting::is_valid_schedule(start_ts, cliff_ts, end_ts) {
+            return Err(VestingError::InvalidSchedule.into());
+        }
+        let mut vesting = Vesting::unpack_unchecked(&vesting_info.data.borrow())?;
+        if vesting.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        let (authority, bump) = Pubkey::find_program_address(
+            &[VAULT_AUTHORITY_SEED, vesting_info.key.as_ref()],
+            program_id,
+        );
+        if authority != *authority_info.key {
+            return Err(VestingError::InvalidVaultAuthority.into());
+        }
+        let signer_seeds: &[&[u8]] = &[VAULT_AUTHORITY_SEED, vesting_info.key.as_ref(), &[bump]];
+
+        invoke_signed(
+            &bank_instruction::initialize_account(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                authority_info.key,
+            )?,
+            &[
+                bank_info.clone(),
+                vault_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        invoke(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                vault_info.key,
+                grantor_info.key,
+                amount,
+            )?,
+            &[
+                source_info.clone(),
+                vault_info.clone(),
+                grantor_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+        )?;
+
+        vesting.is_initialized = true;
+        vesting.grantor = *grantor_info.key;
+        vesting.beneficiary = *beneficiary_info.key;
+        vesting.vault = *vault_info.key;
+        vesting.total_amount = amount;
+        vesting.claimed_amount = 0;
+        vesting.start_ts = start_ts;
+        vesting.cliff_ts = cliff_ts;
+        vesting.end_ts = end_ts;
+        vesting.bump = bump;
+        Vesting::pack(vesting, &mut vesting_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_claim(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let beneficiary_info = next_account_info(account_info_iter)?;
+        let vesting_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if !beneficiary_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if vesting_info.owner != program_id || vault_info.owner != bank_program_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut vesting = Vesting::unpack(&vesting_info.data.borrow())?;
+        if vesting.beneficiary != *beneficiary_info.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if vesting.vault != *vault_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let bump = [vesting.bump];
+        let signer_seeds: &[&[u8]] = &[VAULT_AUTHORITY_SEED, vesting_info.key.as_ref(), &bump];
+        let authority = Pubkey::create_program_address(signer_seeds, program_id)
+            .map_err(|_| ProgramError::from(VestingError::InvalidVaultAuthority))?;
+        if authority != *authority_info.key {
+            return Err(VestingError::InvalidVaultAuthority.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let amount = vesting.claimable_amount(now);
+        if amount == 0 {
+            return Err(VestingError::NothingToClaim.into());
+        }
+        invoke_signed(
+            &bank_instruction::transfer(
+                bank_program_info.key,
+                bank_info.key,
+                vault_info.key,
+                destination_info.key,
+                authority_info.key,
+                amount,
+            )?,
+            &[
+                vault_info.clone(),
+                destination_info.clone(),
+                authority_info.clone(),
+                bank_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        vesting.claimed_amount += amount;
+        Vesting::pack(vesting, &mut vesting_info.data.borrow_mut())?;
+        Ok(())
+    }
+}
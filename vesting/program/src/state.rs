@@ -0,0 +1,180 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+use std::convert::TryFrom;
+
+/// Tokens locked in `vault` for `beneficiary`: nothing is claimable before `cliff_ts`,
+/// then the grant vests linearly from `start_ts` until all of it has at `end_ts`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vesting {
+    pub is_initialized: bool,
+    pub grantor: Pubkey,
+    pub beneficiary: Pubkey,
+    /// Bank account holding the unclaimed tokens, owned by the vault authority.
+    pub vault: Pubkey,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    /// Unix timestamps of the schedule.
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+    /// Bump seed of the vault authority address.
+    pub bump: u8,
+}
+
+impl Vesting {
+    pub fn is_valid_schedule(start_ts: i64, cliff_ts: i64, end_ts: i64) -> bool {
+        start_ts <= cliff_ts && cliff_ts <= end_ts && start_ts < end_ts
+    }
+
+    /// Amount vested by `now`, claimed or not.
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        if now < self.cliff_ts {
+            return 0;
+        }
+        if now >= self.end_ts {
+            return self.total_amount;
+        }
+        let elapsed = (now - self.start_ts) as u128;
+        let duration = (self.end_ts - self.start_ts) as u128;
+        // elapsed < duration, so the share always fits back into a u64.
+        u64::try_from(self.total_amount as u128 * elapsed / duration).unwrap()
+    }
+
+    /// Amount the beneficiary can claim at `now`.
+    pub fn claimable_amount(&self, now: i64) -> u64 {
+        self.vested_amount(now).saturating_sub(self.claimed_amount)
+    }
+}
+
+impl Sealed for Vesting {}
+impl IsInitialized for Vesting {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Vesting {
+    const LEN: usize = 138;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 138];
+        let (
+            is_initialized,
+            grantor,
+            beneficiary,
+            vault,
+            total_amount,
+            claimed_amount,
+            start_ts,
+            cliff_ts,
+            end_ts,
+            bump,
+        ) = array_refs![src, 1, 32, 32, 32, 8, 8, 8, 8, 8, 1];
+        let is_initialized = match is_initialized[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        Ok(Vesting {
+            is_initialized,
+            grantor: Pubkey::new_from_array(*grantor),
+            beneficiary: Pubkey::new_from_array(*beneficiary),
+            vault: Pubkey::new_from_array(*vault),
+            total_amount: u64::from_le_bytes(*total_amount),
+            claimed_amount: u64::from_le_bytes(*claimed_amount),
+            start_ts: i64::from_le_bytes(*start_ts),
+            cliff_ts: i64::from_le_bytes(*cliff_ts),
+            end_ts: i64::from_le_bytes(*end_ts),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 138];
+        let (
+            is_initialized,
+            grantor,
+            beneficiary,
+            vault,
+            total_amount,
+            claimed_amount,
+            start_ts,
+            cliff_ts,
+            end_ts,
+            bump,
+        ) = mut_array_refs![dst, 1, 32, 32, 32, 8, 8, 8, 8, 8, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        grantor.copy_from_slice(self.grantor.as_ref());
+        beneficiary.copy_from_slice(self.beneficiary.as_ref());
+        vault.copy_from_slice(self.vault.as_ref());
+        *total_amount = self.total_amount.to_le_bytes();
+        *claimed_amount = self.claimed_amount.to_le_bytes();
+        *start_ts = self.start_ts.to_le_bytes();
+        *cliff_ts = self.cliff_ts.to_le_bytes();
+        *end_ts = self.end_ts.to_le_bytes();
+        bump[0] = self.bump;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vesting() -> Vesting {
+        Vesting {
+            is_initialized: true,
+            grantor: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            total_amount: 1_000,
+            claimed_amount: 0,
+            start_ts: 100,
+            cliff_ts: 200,
+            end_ts: 1_100,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_pack_unpack() {
+        let vesting = Vesting {
+            claimed_amount: 42,
+            start_ts: -5,
+            ..vesting()
+        };
+        let mut packed = vec![0u8; Vesting::LEN];
+        Vesting::pack(vesting, &mut packed).unwrap();
+        assert_eq!(Vesting::unpack(&packed), Ok(vesting));
+    }
+
+    #[test]
+    fn test_vested_amount() {
+        let vesting = vesting();
+        assert_eq!(vesting.vested_amount(0), 0);
+        assert_eq!(vesting.vested_amount(199), 0);
+        // At the cliff everything since the start vests at once.
+        assert_eq!(vesting.vested_amount(200), 100);
+        assert_eq!(vesting.vested_amount(600), 500);
+        assert_eq!(vesting.vested_amount(1_100), 1_000);
+        assert_eq!(vesting.vested_amount(i64::MAX), 1_000);
+
+        let vesting = Vesting {
+            total_amount: u64::MAX,
+            claimed_amount: u64::MAX / 2,
+            ..vesting
+        };
+        assert_eq!(
+            vesting.vested_amount(1_099),
+            (u64::MAX as u128 * 999 / 1_000) as u64
+        );
+        assert_eq!(vesting.claimable_amount(200), 0);
+        assert_eq!(vesting.claimable_amount(1_100), u64::MAX - u64::MAX / 2);
+
+        assert!(Vesting::is_valid_schedule(0, 0, 1));
+        assert!(!Vesting::is_valid_schedule(5, 5, 5));
+        assert!(!Vesting::is_valid_schedule(0, 2, 1));
+    }
+}
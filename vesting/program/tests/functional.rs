@@ -0,0 +1,252 @@
+use solana_bank::{state::Account, test_utils::program_test::Env};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use solana_vesting::{
+    error::VestingError,
+    instruction::{claim, create_vesting, vault_authority},
+    processor::Processor,
+    state::Vesting,
+};
+
+const START: i64 = 1_000;
+const CLIFF: i64 = 1_250;
+const END: i64 = 2_000;
+
+/// The grantor locks 1_000 of their 1_200 tokens for the beneficiary, who holds two empty
+/// accounts in the same bank.
+struct Fixture {
+    env: Env,
+    grantor: Keypair,
+    beneficiary: Keypair,
+    source: Pubkey,
+    destinations: [Pubkey; 2],
+    vesting: Keypair,
+    vault: Keypair,
+    bank: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_vesting", processor!(Processor::process)).await;
+        let grantor = Keypair::new();
+        let beneficiary = Keypair::new();
+        let bank = env.create_bank().await;
+        let source = env.create_bank_account(&bank, &grantor, 1_200).await;
+        let destinations = [
+            env.create_bank_account(&bank, &beneficiary, 0).await,
+            env.create_bank_account(&bank, &beneficiary, 0).await,
+        ];
+
+        let vesting = Keypair::new();
+        let vault = Keypair::new();
+        let vesting_program_id = env.program_id;
+        let bank_program_id = env.bank_program_id;
+        env.create_account(&vesting, Vesting::LEN, &vesting_program_id)
+            .await;
+        env.create_account(&vault, Account::LEN, &bank_program_id)
+            .await;
+
+        Fixture {
+            env,
+            grantor,
+            beneficiary,
+            source,
+            destinations,
+            vesting,
+            vault,
+            bank,
+        }
+    }
+
+    fn create(&self, cliff_ts: i64) -> Instruction {
+        create_vesting(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.grantor.pubkey(),
+            &self.vesting.pubkey(),
+            &self.vault.pubkey(),
+            &self.source,
+            &self.bank,
+            &self.beneficiary.pubkey(),
+            1_000,
+            START,
+            cliff_ts,
+            END,
+        )
+        .unwrap()
+    }
+
+    async fn created() -> Fixture {
+        let mut fixture = Fixture::new().await;
+        let instruction = fixture.create(CLIFF);
+        fixture
+            .env
+            .process(&[instruction], &[&fixture.grantor])
+            .await
+            .unwrap();
+        fixture
+    }
+
+    fn claim(&self, signer: &Keypair, destination: usize) -> Instruction {
+        claim(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.bank,
+            &signer.pubkey(),
+            &self.vesting.pubkey(),
+            &self.vault.pubkey(),
+            &self.destinations[destination],
+        )
+        .unwrap()
+    }
+}
+
+#[tokio::test]
+async fn test_create_vesting() {
+    let mut fixture = Fixture::new().await;
+    let backwards = fixture.create(END + 1);
+    let instruction = fixture.create(CLIFF);
+    let env = &mut fixture.env;
+
+    assert_eq!(
+        env.process(&[backwards], &[&fixture.grantor]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(VestingError::InvalidSchedule as u32)
+        ))
+    );
+
+    env.process(&[instruction], &[&fixture.grantor])
+        .await
+        .unwrap();
+    let vesting = env.unpack::<Vesting>(&fixture.vesting.pubkey()).await;
+    assert_eq!(vesting.grantor, fixture.grantor.pubkey());
+    assert_eq!(vesting.beneficiary, fixture.beneficiary.pubkey());
+    assert_eq!(vesting.vault, fixture.vault.pubkey());
+    assert_eq!(vesting.total_amount, 1_000);
+    assert_eq!(vesting.claimed_amount, 0);
+    assert_eq!(
+        (vesting.start_ts, vesting.cliff_ts, vesting.end_ts),
+        (START, CLIFF, END)
+    );
+
+    let vault = env
+        .context
+        .banks_client
+        .get_account(fixture.vault.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let vault = Account::unpack(&vault.data).unwrap();
+    let (authority, _) = vault_authority(&env.program_id, &fixture.vesting.pubkey());
+    assert_eq!(vault.owner, authority);
+    assert_eq!(vault.amount, 1_000);
+    assert_eq!(env.amount(&fixture.source).await, 200);
+}
+
+#[tokio::test]
+async fn test_claim_before_cliff() {
+    let mut fixture = Fixture::created().await;
+    let instruction = fixture.claim(&fixture.beneficiary, 0);
+    let env = &mut fixture.env;
+
+    // A quarter of the schedule has passed, but none of it is claimable until the cliff.
+    env.set_time(CLIFF - 1);
+    assert_eq!(
+        env.process(&[instruction], &[&fixture.beneficiary]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(VestingError::NothingToClaim as u32)
+        ))
+    );
+    assert_eq!(env.amount(&fixture.vault.pubkey()).await, 1_000);
+}
+
+#[tokio::test]
+async fn test_linear_release() {
+    let mut fixture = Fixture::created().await;
+    let halfway = fixture.claim(&fixture.beneficiary, 0);
+    let rest = fixture.claim(&fixture.beneficiary, 1);
+    let env = &mut fixture.env;
+
+    env.set_time((START + END) / 2);
+    env.process(&[halfway], &[&fixture.beneficiary])
+        .await
+        .unwrap();
+    assert_eq!(env.amount(&fixture.destinations[0]).await, 500);
+    assert_eq!(
+        env.unpack::<Vesting>(&fixture.vesting.pubkey())
+            .await
+            .claimed_amount,
+        500
+    );
+
+    // Past the end everything left is released, however long the beneficiary waited.
+    env.set_time(END + 1_000);
+    env.process(&[rest], &[&fixture.beneficiary]).await.unwrap();
+    assert_eq!(env.amount(&fixture.destinations[1]).await, 500);
+    assert_eq!(env.amount(&fixture.vault.pubkey()).await, 0);
+    assert_eq!(
+        env.unpack::<Vesting>(&fixture.vesting.pubkey())
+            .await
+            .claimed_amount,
+        1_000
+    );
+}
+
+#[tokio::test]
+async fn test_claim_rejects_other_signer() {
+    let mut fixture = Fixture::created().await;
+    let instruction = fixture.claim(&fixture.grantor, 0);
+    let env = &mut fixture.env;
+
+    env.set_time(END);
+    assert_eq!(
+        env.process(&[instruction], &[&fixture.grantor]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+    assert_eq!(env.amount(&fixture.vault.pubkey()).await, 1_000);
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    // Through a program posing as the bank, the vesting would promise tokens that never
+    // moved into the vault.
+    let instruction = create_vesting(
+        &fixture.env.program_id,
+        &fixture.env.fake_bank_program_id,
+        &fixture.grantor.pubkey(),
+        &fixture.vesting.pubkey(),
+        &fixture.vault.pubkey(),
+        &fixture.source,
+        &fixture.bank,
+        &fixture.beneficiary.pubkey(),
+        1_000,
+        START,
+        CLIFF,
+        END,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture
+            .env
+            .process(&[instruction], &[&fixture.grantor])
+            .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+}
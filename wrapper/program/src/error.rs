@@ -0,0 +1,22 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WrapperError {
+    /// The authority isn't the wrapper's program address.
+    InvalidAuthority,
+    /// The mint isn't the wrapper's, or isn't an initialized SPL Token mint.
+    InvalidMint,
+    /// The vault isn't the wrapper's, or isn't a token account of the mint held by the
+    /// authority.
+    InvalidVault,
+    /// The bank isn't the wrapper's.
+    InvalidBank,
+    /// Nothing to wrap or unwrap.
+    ZeroAmount,
+}
+
+impl From<WrapperError> for ProgramError {
+    fn from(e: WrapperError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
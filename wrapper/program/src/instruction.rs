@@ -0,0 +1,224 @@
+use crate::state::Wrapper;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+/// Seed prefix of the authority, followed by the wrapper's address.
+pub const AUTHORITY_SEED: &[u8] = b"wrapper";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum WrapperInstruction {
+    /// Opens a wrapper of an SPL Token mint, initializing its bank with the mint's
+    /// decimals and the authority as bank owner.
+    ///
+    /// Accounts expected:
+    ///   0. `[writable]` The wrapper, an uninitialized account owned by this program.
+    ///   1. `[writable]` The bank, an uninitialized account owned by the bank program.
+    ///   2. `[]` The SPL Token mint.
+    ///   3. `[]` The vault, a token account of the mint held by the authority; usually
+    ///      the authority's associated token account.
+    ///   4. `[writable]` The authority.
+    ///   5. `[]` The bank program, owner of the bank.
+    CreateWrapper,
+
+    /// Locks `amount` of the mint in the vault and mints as many bank tokens.
+    ///
+    /// Accounts expected:
+    ///   0. `[]` The wrapper.
+    ///   1. `[writable]` The authority.
+    ///   2. `[writable]` The bank.
+    ///   3. `[]` The mint.
+    ///   4. `[writable]` The vault.
+    ///   5. `[writable]` The source token account.
+    ///   6. `[signer]` The source token account's owner or delegate.
+    ///   7. `[writable]` The destination bank account.
+    ///   8. `[]` The SPL Token program.
+    ///   9. `[]` The bank program.
+    Wrap { amount: u64 },
+
+    /// Burns `amount` bank tokens and releases as much of the mint from the vault.
+    ///
+    /// Accounts expected:
+    ///   0. `[]` The wrapper.
+    ///   1. `[writable]` The authority.
+    ///   2. `[writable]` The bank.
+    ///   3. `[]` The mint.
+    ///   4. `[writable]` The vault.
+    ///   5. `[writable]` The source bank account.
+    ///   6. `[writable, signer]` The source bank account's owner.
+    ///   7. `[writable]` The destination token account.
+    ///   8. `[]` The SPL Token program.
+    ///   9. `[]` The bank program.
+    Unwrap { amount: u64 },
+}
+
+impl WrapperInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use ProgramError::InvalidInstructionData;
+
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => Self::CreateWrapper,
+            1 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Wrap { amount }
+            }
+            2 => {
+                let (amount, _rest) = Self::unpack_u64(rest)?;
+                Self::Unwrap { amount }
+            }
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(9);
+        match *self {
+            Self::CreateWrapper => buf.push(0),
+            Self::Wrap { amount } => {
+                buf.push(1);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::Unwrap { amount } => {
+                buf.push(2);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+        }
+        buf
+    }
+
+    fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+        let value = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok((value, &input[8..]))
+    }
+}
+
+/// Address and bump of the authority owning `wrapper`'s bank and vault.
+pub fn wrapper_authority(wrapper_program_id: &Pubkey, wrapper: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUTHORITY_SEED, wrapper.as_ref()], wrapper_program_id)
+}
+
+pub fn create_wrapper(
+    wrapper_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    wrapper: &Pubkey,
+    bank: &Pubkey,
+    mint: &Pubkey,
+    vault: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = WrapperInstruction::CreateWrapper.pack();
+    let (authority, _) = wrapper_authority(wrapper_program_id, wrapper);
+    let accounts = vec![
+        AccountMeta::new(*wrapper, false),
+        AccountMeta::new(*bank, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new_readonly(*vault, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *wrapper_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn wrap(
+    wrapper_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    wrapper: &Pubkey,
+    state: &Wrapper,
+    source: &Pubkey,
+    source_owner: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = WrapperInstruction::Wrap { amount }.pack();
+    let (authority, _) = wrapper_authority(wrapper_program_id, wrapper);
+    let accounts = vec![
+        AccountMeta::new_readonly(*wrapper, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new(state.bank, false),
+        AccountMeta::new_readonly(state.mint, false),
+        AccountMeta::new(state.vault, false),
+        AccountMeta::new(*source, false),
+        AccountMeta::new_readonly(*source_owner, true),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *wrapper_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn unwrap(
+    wrapper_program_id: &Pubkey,
+    bank_program_id: &Pubkey,
+    wrapper: &Pubkey,
+    state: &Wrapper,
+    source: &Pubkey,
+    source_owner: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = WrapperInstruction::Unwrap { amount }.pack();
+    let (authority, _) = wrapper_authority(wrapper_program_id, wrapper);
+    let accounts = vec![
+        AccountMeta::new_readonly(*wrapper, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new(state.bank, false),
+        AccountMeta::new_readonly(state.mint, false),
+        AccountMeta::new(state.vault, false),
+        AccountMeta::new(*source, false),
+        AccountMeta::new(*source_owner, true),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*bank_program_id, false),
+    ];
+    Ok(Instruction {
+        program_id: *wrapper_program_id,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let cases = vec![
+            WrapperInstruction::CreateWrapper,
+            WrapperInstruction::Wrap { amount: 1_000 },
+            WrapperInstruction::Unwrap { amount: u64::MAX },
+        ];
+        for instruction in cases {
+            assert_eq!(
+                WrapperInstruction::unpack(&instruction.pack()),
+                Ok(instruction)
+            );
+        }
+
+        assert_eq!(
+            WrapperInstruction::unpack(&[1, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            WrapperInstruction::unpack(&[3]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
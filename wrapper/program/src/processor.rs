@@ -0,0 +1,290 @@
+use crate::{
+    error::WrapperError,
+    instruction::{WrapperInstruction, AUTHORITY_SEED},
+    state::Wrapper,
+};
+use solana_bank::instruction as bank_instruction;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use spl_token::state::{Account as TokenAccount, Mint};
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = WrapperInstruction::unpack(input)?;
+
+        match instruction {
+            WrapperInstruction::CreateWrapper => {
+                msg!("Instruction: CreateWrapper");
+                Self::process_create_wrapper(program_id, accounts)
+            }
+            WrapperInstruction::Wrap { amount } => {
+                msg!("Instruction: Wrap");
+                Self::process_wrap(program_id, accounts, amount)
+            }
+            WrapperInstruction::Unwrap { amount } => {
+                msg!("Instruction: Unwrap");
+                Self::process_unwrap(program_id, accounts, amount)
+            }
+        }
+    }
+
+    pub fn process_create_wrapper(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let wrapper_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let mint_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        if wrapper_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut wrapper = Wrapper::unpack_unchecked(&wrapper_info.data.borrow())?;
+        if wrapper.is_initialized {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        let (authority, bump) =
+            Pubkey::find_program_address(&[AUTHORITY_SEED, wrapper_info.key.as_ref()], program_id);
+        if authority != *authority_info.key {
+            return Err(WrapperError::InvalidAuthority.into());
+        }
+        if mint_info.owner != &spl_token::id() {
+            return Err(WrapperError::InvalidMint.into());
+        }
+        let mint = Mint::unpack(&mint_info.data.borrow())
+            .map_err(|_| ProgramError::from(WrapperError::InvalidMint))?;
+        // Only the authority can move tokens out of the vault, and only on unwrap.
+        if vault_info.owner != &spl_token::id() {
+            return Err(WrapperError::InvalidVault.into());
+        }
+        let vault = TokenAccount::unpack(&vault_info.data.borrow())
+            .map_err(|_| ProgramError::from(WrapperError::InvalidVault))?;
+        if vault.mint != *mint_info.key
+            || vault.owner != authority
+            || vault.delegate.is_some()
+            || vault.close_authority.is_some()
+        {
+            return Err(WrapperError::InvalidVault.into());
+        }
+        // A bank of any other program could mint without the vault backing it, and take
+        // the authority's signature over the vault with it.
+        if bank_info.owner != bank_program_info.key {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        invoke_signed(
+            &bank_instruction::initialize_bank(
+                bank_program_info.key,
+                bank_info.key,
+                authority_info.key,
+                mint.decimals,
+            )?,
+            &[
+                bank_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[AUTHORITY_SEED, wrapper_info.key.as_ref(), &[bump]]],
+        )?;
+
+        wrapper.is_initialized = true;
+        wrapper.mint = *mint_info.key;
+        wrapper.bank = *bank_info.key;
+        wrapper.vault = *vault_info.key;
+        wrapper.bump = bump;
+        Wrapper::pack(wrapper, &mut wrapper_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    pub fn process_wrap(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let wrapper_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let mint_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        let wrapper = Self::load_wrapper(
+            program_id,
+            wrapper_info,
+            authority_info,
+            bank_info,
+            mint_info,
+            vault_info,
+        )?;
+        Self::check_programs(bank_info, token_program_info, bank_program_info)?;
+        if amount == 0 {
+            return Err(WrapperError::ZeroAmount.into());
+        }
+        let decimals = Mint::unpack(&mint_info.data.borrow())?.decimals;
+
+        invoke(
+            &spl_token::instruction::transfer_checked(
+                token_program_info.key,
+                source_info.key,
+                mint_info.key,
+                vault_info.key,
+                owner_info.key,
+                &[],
+                amount,
+                decimals,
+            )?,
+            &[
+                source_info.clone(),
+                mint_info.clone(),
+                vault_info.clone(),
+                owner_info.clone(),
+                token_program_info.clone(),
+            ],
+        )?;
+        invoke_signed(
+            &bank_instruction::mint_to(
+                bank_program_info.key,
+                bank_info.key,
+                destination_info.key,
+                authority_info.key,
+                amount,
+            )?,
+            &[
+                bank_info.clone(),
+                destination_info.clone(),
+                authority_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[&[AUTHORITY_SEED, wrapper_info.key.as_ref(), &[wrapper.bump]]],
+        )
+    }
+
+    pub fn process_unwrap(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let wrapper_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let bank_info = next_account_info(account_info_iter)?;
+        let mint_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let bank_program_info = next_account_info(account_info_iter)?;
+
+        let wrapper = Self::load_wrapper(
+            program_id,
+            wrapper_info,
+            authority_info,
+            bank_info,
+            mint_info,
+            vault_info,
+        )?;
+        Self::check_programs(bank_info, token_program_info, bank_program_info)?;
+        if amount == 0 {
+            return Err(WrapperError::ZeroAmount.into());
+        }
+        let decimals = Mint::unpack(&mint_info.data.borrow())?.decimals;
+        let signer_seeds: &[&[u8]] = &[AUTHORITY_SEED, wrapper_info.key.as_ref(), &[wrapper.bump]];
+
+        invoke_signed(
+            &bank_instruction::burn(
+                bank_program_info.key,
+                bank_info.key,
+                source_info.key,
+                authority_info.key,
+                owner_info.key,
+                amount,
+            )?,
+            &[
+                bank_info.clone(),
+                source_info.clone(),
+                authority_info.clone(),
+                owner_info.clone(),
+                bank_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        invoke_signed(
+            &spl_token::instruction::transfer_checked(
+                token_program_info.key,
+                vault_info.key,
+                mint_info.key,
+                destination_info.key,
+                authority_info.key,
+                &[],
+                amount,
+                decimals,
+            )?,
+            &[
+                vault_info.clone(),
+                mint_info.clone(),
+                destination_info.clone(),
+                authority_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )
+    }
+
+    fn load_wrapper(
+        program_id: &Pubkey,
+        wrapper_info: &AccountInfo,
+        authority_info: &AccountInfo,
+        bank_info: &AccountInfo,
+        mint_info: &AccountInfo,
+        vault_info: &AccountInfo,
+    ) -> Result<Wrapper, ProgramError> {
+        if wrapper_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let wrapper = Wrapper::unpack(&wrapper_info.data.borrow())?;
+        let authority = Pubkey::create_program_address(
+            &[AUTHORITY_SEED, wrapper_info.key.as_ref(), &[wrapper.bump]],
+            program_id,
+        )?;
+        if authority != *authority_info.key {
+            return Err(WrapperError::InvalidAuthority.into());
+        }
+        if wrapper.bank != *bank_info.key {
+            return Err(WrapperError::InvalidBank.into());
+        }
+        if wrapper.mint != *mint_info.key {
+            return Err(WrapperError::InvalidMint.into());
+        }
+        if wrapper.vault != *vault_info.key {
+            return Err(WrapperError::InvalidVault.into());
+        }
+        Ok(wrapper)
+    }
+
+    /// The authority signs for the called programs, so they have to be the real ones.
+    fn check_programs(
+        bank_info: &AccountInfo,
+        token_program_info: &AccountInfo,
+        bank_program_info: &AccountInfo,
+    ) -> ProgramResult {
+        if token_program_info.key != &spl_token::id() || bank_info.owner != bank_program_info.key {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        Ok(())
+    }
+}
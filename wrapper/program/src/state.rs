@@ -0,0 +1,84 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Wraps the SPL Token `mint` into `bank`, whose owner is the wrapper's authority. Every
+/// bank token is backed by one base unit of the mint locked in `vault`, a token account
+/// held by the authority, so the bank's total supply never exceeds the vault's balance.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Wrapper {
+    pub is_initialized: bool,
+    pub mint: Pubkey,
+    pub bank: Pubkey,
+    pub vault: Pubkey,
+    /// Bump seed of the authority address.
+    pub bump: u8,
+}
+
+impl Sealed for Wrapper {}
+impl IsInitialized for Wrapper {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Wrapper {
+    const LEN: usize = 98;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 98];
+        let (is_initialized, mint, bank, vault, bump) = array_refs![src, 1, 32, 32, 32, 1];
+        Ok(Wrapper {
+            is_initialized: unpack_bool(is_initialized)?,
+            mint: Pubkey::new_from_array(*mint),
+            bank: Pubkey::new_from_array(*bank),
+            vault: Pubkey::new_from_array(*vault),
+            bump: bump[0],
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 98];
+        let (is_initialized, mint, bank, vault, bump) = mut_array_refs![dst, 1, 32, 32, 32, 1];
+        is_initialized[0] = self.is_initialized as u8;
+        mint.copy_from_slice(self.mint.as_ref());
+        bank.copy_from_slice(self.bank.as_ref());
+        vault.copy_from_slice(self.vault.as_ref());
+        bump[0] = self.bump;
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let wrapper = Wrapper {
+            is_initialized: true,
+            mint: Pubkey::new_unique(),
+            bank: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            bump: 254,
+        };
+        let mut packed = vec![0u8; Wrapper::LEN];
+        Wrapper::pack(wrapper, &mut packed).unwrap();
+        assert_eq!(Wrapper::unpack(&packed), Ok(wrapper));
+
+        packed[0] = 2;
+        assert_eq!(
+            Wrapper::unpack_unchecked(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+}
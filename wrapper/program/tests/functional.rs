@@ -0,0 +1,324 @@
+use solana_bank::{
+    error::BankError, instruction::initialize_account, state::Bank, test_utils::program_test::Env,
+};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use solana_program_test::processor;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use solana_wrapper::{
+    error::WrapperError,
+    instruction::{create_wrapper, unwrap, wrap, wrapper_authority},
+    processor::Processor,
+    state::Wrapper,
+};
+use spl_token::state::{Account as TokenAccount, Mint};
+
+const DECIMALS: u8 = 6;
+
+/// Creates an SPL Token mint whose mint authority is the payer.
+async fn create_mint(env: &mut Env) -> Pubkey {
+    let mint = Keypair::new();
+    env.create_account(&mint, Mint::LEN, &spl_token::id()).await;
+    let instruction = spl_token::instruction::initialize_mint(
+        &spl_token::id(),
+        &mint.pubkey(),
+        &env.context.payer.pubkey(),
+        None,
+        DECIMALS,
+    )
+    .unwrap();
+    env.process(&[instruction], &[]).await.unwrap();
+    mint.pubkey()
+}
+
+/// Opens a token account of `mint` held by `owner`, with `amount` freshly minted.
+async fn create_token_account(env: &mut Env, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+    let account = Keypair::new();
+    env.create_account(&account, TokenAccount::LEN, &spl_token::id())
+        .await;
+    let mut instructions = vec![spl_token::instruction::initialize_account(
+        &spl_token::id(),
+        &account.pubkey(),
+        mint,
+        owner,
+    )
+    .unwrap()];
+    if amount > 0 {
+        instructions.push(
+            spl_token::instruction::mint_to(
+                &spl_token::id(),
+                mint,
+                &account.pubkey(),
+                &env.context.payer.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap(),
+        );
+    }
+    env.process(&instructions, &[]).await.unwrap();
+    account.pubkey()
+}
+
+/// A wrapper of a fresh mint, with alice holding 1.0 of the mint and an empty account in
+/// the wrapper's bank.
+struct Fixture {
+    env: Env,
+    wrapper: Pubkey,
+    state: Wrapper,
+    alice: Keypair,
+    alice_tokens: Pubkey,
+    alice_account: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Fixture {
+        let mut env = Env::start("solana_wrapper", processor!(Processor::process)).await;
+        let mint = create_mint(&mut env).await;
+        let (wrapper, bank) = (Keypair::new(), Keypair::new());
+        let (wrapper_program_id, bank_program_id) = (env.program_id, env.bank_program_id);
+        env.create_account(&wrapper, Wrapper::LEN, &wrapper_program_id)
+            .await;
+        env.create_account(&bank, Bank::LEN, &bank_program_id).await;
+        let (authority, _) = wrapper_authority(&wrapper_program_id, &wrapper.pubkey());
+        let vault = create_token_account(&mut env, &mint, &authority, 0).await;
+        let instruction = create_wrapper(
+            &wrapper_program_id,
+            &bank_program_id,
+            &wrapper.pubkey(),
+            &bank.pubkey(),
+            &mint,
+            &vault,
+        )
+        .unwrap();
+        env.process(&[instruction], &[]).await.unwrap();
+        let state = env.unpack::<Wrapper>(&wrapper.pubkey()).await;
+
+        let alice = Keypair::new();
+        let alice_tokens = create_token_account(&mut env, &mint, &alice.pubkey(), 1_000_000).await;
+        let alice_account = env.create_bank_account(&bank.pubkey(), &alice, 0).await;
+        Fixture {
+            env,
+            wrapper: wrapper.pubkey(),
+            state,
+            alice,
+            alice_tokens,
+            alice_account,
+        }
+    }
+
+    fn wrap(&self, amount: u64) -> Instruction {
+        wrap(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.wrapper,
+            &self.state,
+            &self.alice_tokens,
+            &self.alice.pubkey(),
+            &self.alice_account,
+            amount,
+        )
+        .unwrap()
+    }
+
+    fn unwrap(&self, amount: u64) -> Instruction {
+        unwrap(
+            &self.env.program_id,
+            &self.env.bank_program_id,
+            &self.wrapper,
+            &self.state,
+            &self.alice_account,
+            &self.alice.pubkey(),
+            &self.alice_tokens,
+            amount,
+        )
+        .unwrap()
+    }
+
+    async fn process(&mut self, instructions: &[Instruction]) -> Result<(), TransactionError> {
+        let alice = Keypair::from_bytes(&self.alice.to_bytes()).unwrap();
+        self.env.process(instructions, &[&alice]).await
+    }
+
+    async fn total_supply(&mut self) -> u64 {
+        let bank = self.state.bank;
+        self.env.unpack::<Bank>(&bank).await.total_supply
+    }
+}
+
+fn custom(error: WrapperError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_create_wrapper() {
+    let mut fixture = Fixture::new().await;
+    let (authority, _) = wrapper_authority(&fixture.env.program_id, &fixture.wrapper);
+    let bank = fixture.state.bank;
+    let bank = fixture.env.unpack::<Bank>(&bank).await;
+    assert_eq!(bank.bank_owner, authority);
+    assert_eq!(bank.decimals, DECIMALS);
+    assert!(bank.is_opened);
+
+    // A vault anyone else can spend from would leave bank tokens unbacked.
+    let (wrapper, bank) = (Keypair::new(), Keypair::new());
+    let (wrapper_program_id, bank_program_id) =
+        (fixture.env.program_id, fixture.env.bank_program_id);
+    fixture
+        .env
+        .create_account(&wrapper, Wrapper::LEN, &wrapper_program_id)
+        .await;
+    fixture
+        .env
+        .create_account(&bank, Bank::LEN, &bank_program_id)
+        .await;
+    let instruction = create_wrapper(
+        &wrapper_program_id,
+        &bank_program_id,
+        &wrapper.pubkey(),
+        &bank.pubkey(),
+        &fixture.state.mint,
+        &fixture.alice_tokens,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[]).await,
+        custom(WrapperError::InvalidVault)
+    );
+}
+
+#[tokio::test]
+async fn test_wrap() {
+    let mut fixture = Fixture::new().await;
+    let (tokens, account, vault) = (
+        fixture.alice_tokens,
+        fixture.alice_account,
+        fixture.state.vault,
+    );
+    let instruction = fixture.wrap(300_000);
+    fixture.process(&[instruction]).await.unwrap();
+    assert_eq!(
+        fixture.env.unpack::<TokenAccount>(&tokens).await.amount,
+        700_000
+    );
+    assert_eq!(
+        fixture.env.unpack::<TokenAccount>(&vault).await.amount,
+        300_000
+    );
+    assert_eq!(fixture.env.amount(&account).await, 300_000);
+    assert_eq!(fixture.total_supply().await, 300_000);
+
+    let instruction = fixture.wrap(0);
+    assert_eq!(
+        fixture.process(&[instruction]).await,
+        custom(WrapperError::ZeroAmount)
+    );
+    // Tokens paid anywhere but the vault don't back anything.
+    let mut instruction = fixture.wrap(100_000);
+    instruction.accounts[4].pubkey = tokens;
+    assert_eq!(
+        fixture.process(&[instruction]).await,
+        custom(WrapperError::InvalidVault)
+    );
+}
+
+#[tokio::test]
+async fn test_unwrap() {
+    let mut fixture = Fixture::new().await;
+    let (tokens, account, vault) = (
+        fixture.alice_tokens,
+        fixture.alice_account,
+        fixture.state.vault,
+    );
+    let instructions = [fixture.wrap(300_000), fixture.unwrap(100_000)];
+    fixture.process(&instructions).await.unwrap();
+    assert_eq!(
+        fixture.env.unpack::<TokenAccount>(&tokens).await.amount,
+        800_000
+    );
+    assert_eq!(
+        fixture.env.unpack::<TokenAccount>(&vault).await.amount,
+        200_000
+    );
+    assert_eq!(fixture.env.amount(&account).await, 200_000);
+    assert_eq!(fixture.total_supply().await, 200_000);
+
+    let instruction = fixture.unwrap(200_001);
+    assert_eq!(
+        fixture.process(&[instruction]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(BankError::InsufficientFunds as u32)
+        ))
+    );
+    let instruction = fixture.unwrap(200_000);
+    fixture.process(&[instruction]).await.unwrap();
+    assert_eq!(
+        fixture.env.unpack::<TokenAccount>(&tokens).await.amount,
+        1_000_000
+    );
+    assert_eq!(fixture.env.unpack::<TokenAccount>(&vault).await.amount, 0);
+    assert_eq!(fixture.total_supply().await, 0);
+}
+
+#[tokio::test]
+async fn test_fake_bank_program() {
+    let mut fixture = Fixture::new().await;
+    let wrapper_program_id = fixture.env.program_id;
+    let fake_bank_program_id = fixture.env.fake_bank_program_id;
+    let wrong_program = Err(TransactionError::InstructionError(
+        0,
+        InstructionError::IncorrectProgramId,
+    ));
+
+    // A wrapper over a bank of a program posing as the bank would have that program mint
+    // freely, and unwrap into the real tokens.
+    let (wrapper, bank) = (Keypair::new(), Keypair::new());
+    fixture
+        .env
+        .create_account(&wrapper, Wrapper::LEN, &wrapper_program_id)
+        .await;
+    fixture
+        .env
+        .create_account(&bank, Bank::LEN, &fake_bank_program_id)
+        .await;
+    let (authority, _) = wrapper_authority(&wrapper_program_id, &wrapper.pubkey());
+    let mint = fixture.state.mint;
+    let vault = create_token_account(&mut fixture.env, &mint, &authority, 0).await;
+    let instruction = create_wrapper(
+        &wrapper_program_id,
+        &fake_bank_program_id,
+        &wrapper.pubkey(),
+        &bank.pubkey(),
+        &mint,
+        &vault,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.env.process(&[instruction], &[]).await,
+        wrong_program
+    );
+
+    // Nor does the authority sign for it on an existing wrapper.
+    let instruction = unwrap(
+        &wrapper_program_id,
+        &fake_bank_program_id,
+        &fixture.wrapper,
+        &fixture.state,
+        &fixture.alice_account,
+        &fixture.alice.pubkey(),
+        &fixture.alice_tokens,
+        1,
+    )
+    .unwrap();
+    assert_eq!(fixture.process(&[instruction]).await, wrong_program);
+}
@@ -0,0 +1,55 @@
+//! Borsh schemas of the borsh program's accounts and instructions, exported to
+//! `program/schema/` for tools that decode Borsh data without the Rust types: each file
+//! is the Borsh-serialized `BorshSchemaContainer` of the type it is named after.
+
+use bank_interface::{BankAccount, BankInstruction};
+use borsh::{schema::BorshSchemaContainer, BorshSchema};
+use program::greeting_account::{GreetingAccount, GreetingFee, GreetingInstruction};
+
+/// Each exported type's name and schema container.
+pub fn containers() -> Vec<(&'static str, BorshSchemaContainer)> {
+    vec![
+        ("GreetingAccount", GreetingAccount::schema_container()),
+        ("BankAccount", BankAccount::schema_container()),
+        ("BankInstruction", BankInstruction::schema_container()),
+        ("GreetingFee", GreetingFee::schema_container()),
+        (
+            "GreetingInstruction",
+            GreetingInstruction::schema_container(),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::schema::{Definition, Fields};
+
+    #[test]
+    fn test_containers() {
+        let containers = containers();
+        let (_, instruction) = &containers[2];
+        assert_eq!(instruction.declaration, "BankInstruction");
+        let variants = match &instruction.definitions["BankInstruction"] {
+            Definition::Enum { variants } => variants,
+            definition => panic!("{:?} isn't an enum", definition),
+        };
+        let names: Vec<_> = variants.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["InitAccount", "Transfer"]);
+
+        let (_, account) = &containers[1];
+        match &account.definitions["BankAccount"] {
+            Definition::Struct {
+                fields: Fields::NamedFields(fields),
+            } => assert_eq!(
+                fields,
+                &[
+                    ("amount".to_string(), "u64".to_string()),
+                    ("authority".to_string(), "Pubkey".to_string()),
+                    ("name".to_string(), "string".to_string()),
+                ]
+            ),
+            definition => panic!("{:?} isn't a struct", definition),
+        }
+    }
+}
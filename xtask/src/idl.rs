@@ -0,0 +1,905 @@
+//! The bank program's interface as data, exported to `bank/idl.json` for code generators:
+//! each instruction's tag, arguments and accounts in the order `solana_bank::instruction`
+//! builds them, and each state struct's fields at the offsets its `Pack` impl uses.
+
+use serde::{Deserialize, Serialize};
+use solana_bank::state::{
+    Account, Bank, ExchangeRate, FeatureGate, Multisig, Recovery, Session, Version,
+};
+use solana_program::program_pack::Pack;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Idl {
+    pub name: String,
+    pub instructions: Vec<IdlInstruction>,
+    pub accounts: Vec<IdlAccount>,
+}
+
+/// An instruction: its tag byte, followed by `args` packed in order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IdlInstruction {
+    pub name: String,
+    pub docs: String,
+    pub discriminant: u8,
+    pub accounts: Vec<IdlAccountMeta>,
+    pub args: Vec<IdlField>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IdlAccountMeta {
+    pub name: String,
+    pub is_writable: bool,
+    pub is_signer: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IdlField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: IdlType,
+}
+
+/// A packed state struct of `size` bytes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IdlAccount {
+    pub name: String,
+    pub size: usize,
+    pub fields: Vec<IdlLayoutField>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IdlLayoutField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: IdlType,
+    pub offset: usize,
+}
+
+/// Little-endian integers, signed ones in two's complement, a bool as a byte that is 1
+/// when true, `COption<Pubkey>` as a 4-byte tag followed by the key, an optional key
+/// argument as a byte that is 1 when followed by the key, seeds as a count byte followed
+/// by each seed's length byte and bytes, a vector of keys or of `u64`s as a count byte
+/// followed by them, and an array of keys as the keys back to back.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum IdlType {
+    #[serde(rename = "u8")]
+    U8,
+    #[serde(rename = "u16")]
+    U16,
+    #[serde(rename = "u64")]
+    U64,
+    #[serde(rename = "i64")]
+    I64,
+    #[serde(rename = "bool")]
+    Bool,
+    #[serde(rename = "publicKey")]
+    PublicKey,
+    #[serde(rename = "coption<publicKey>")]
+    COptionPublicKey,
+    #[serde(rename = "option<publicKey>")]
+    OptionPublicKey,
+    #[serde(rename = "seeds")]
+    Seeds,
+    #[serde(rename = "vec<publicKey>")]
+    PublicKeys,
+    #[serde(rename = "vec<u64>")]
+    U64s,
+    #[serde(rename = "[publicKey; 8]")]
+    PublicKeyArray,
+    #[serde(rename = "[publicKey; 11]")]
+    SignerArray,
+}
+
+impl IdlType {
+    pub fn size(self) -> usize {
+        match self {
+            IdlType::U8 | IdlType::Bool => 1,
+            IdlType::U16 => 2,
+            IdlType::U64 | IdlType::I64 => 8,
+            IdlType::PublicKey => 32,
+            IdlType::COptionPublicKey => 36,
+            IdlType::PublicKeyArray => 8 * 32,
+            IdlType::SignerArray => 11 * 32,
+            IdlType::OptionPublicKey => panic!("an optional key has no fixed size"),
+            IdlType::Seeds => panic!("seeds have no fixed size"),
+            IdlType::PublicKeys => panic!("a vector of keys has no fixed size"),
+            IdlType::U64s => panic!("a vector of u64s has no fixed size"),
+        }
+    }
+}
+
+fn instruction(
+    name: &str,
+    docs: &str,
+    discriminant: u8,
+    accounts: &[(&str, bool, bool)],
+    args: &[(&str, IdlType)],
+) -> IdlInstruction {
+    IdlInstruction {
+        name: name.to_string(),
+        docs: docs.to_string(),
+        discriminant,
+        accounts: accounts
+            .iter()
+            .map(|&(name, is_writable, is_signer)| IdlAccountMeta {
+                name: name.to_string(),
+                is_writable,
+                is_signer,
+            })
+            .collect(),
+        args: args
+            .iter()
+            .map(|&(name, ty)| IdlField {
+                name: name.to_string(),
+                ty,
+            })
+            .collect(),
+    }
+}
+
+/// Lays `fields` out back to back from offset 0.
+fn account(name: &str, size: usize, fields: &[(&str, IdlType)]) -> IdlAccount {
+    let mut offset = 0;
+    let fields = fields
+        .iter()
+        .map(|&(name, ty)| {
+            let field = IdlLayoutField {
+                name: name.to_string(),
+                ty,
+                offset,
+            };
+            offset += ty.size();
+            field
+        })
+        .collect();
+    assert_eq!(offset, size, "{} fields don't add up to its LEN", name);
+    IdlAccount {
+        name: name.to_string(),
+        size,
+        fields,
+    }
+}
+
+pub fn bank_idl() -> Idl {
+    use IdlType::*;
+
+    // (name, writable, signer), as the builders mark them.
+    let instructions = vec![
+        instruction(
+            "InitializeBank",
+            "Opens a bank with `decimals` and `burn_policy`; the signer becomes its owner.",
+            0,
+            &[("bank", true, false), ("bank_owner", true, true)],
+            &[
+                ("decimals", U8),
+                ("burn_policy", U8),
+                ("lamports_per_token", U64),
+                ("freeze_authority", OptionPublicKey),
+                ("interest_rate_bps", U16),
+            ],
+        ),
+        instruction(
+            "InitializeAccount",
+            "Opens an empty account in a bank.",
+            1,
+            &[
+                ("bank", true, false),
+                ("account", true, false),
+                ("owner", true, true),
+            ],
+            &[],
+        ),
+        instruction(
+            "Transfer",
+            "Moves `amount` between two accounts of the same bank.",
+            2,
+            &[
+                ("from", true, false),
+                ("to", true, false),
+                ("owner", true, true),
+                ("bank", true, false),
+            ],
+            &[("amount", U64), ("sweep_dust", Bool)],
+        ),
+        instruction(
+            "Approve",
+            "Lets a delegate spend up to `amount` more of an account's tokens, which stay in it.",
+            3,
+            &[
+                ("account", true, false),
+                ("delegate", true, false),
+                ("owner", true, true),
+                ("bank", false, false),
+            ],
+            &[("amount", U64)],
+        ),
+        instruction(
+            "MintTo",
+            "Creates `amount` new tokens in an account.",
+            4,
+            &[
+                ("bank", true, false),
+                ("account", true, false),
+                ("bank_owner", true, true),
+            ],
+            &[("amount", U64)],
+        ),
+        instruction(
+            "Burn",
+            "Destroys `amount` tokens of an account.",
+            5,
+            &[
+                ("bank", true, false),
+                ("account", true, false),
+                ("bank_owner", true, true),
+                ("owner", true, true),
+            ],
+            &[("amount", U64)],
+        ),
+        instruction(
+            "CloseAccount",
+            "Closes an account and sends its lamports to the destination.",
+            6,
+            &[
+                ("account", true, false),
+                ("destination", true, false),
+                ("owner", true, true),
+                ("bank", false, false),
+            ],
+            &[],
+        ),
+        instruction(
+            "Revoke",
+            "Cancels the delegation of an account along with what is left of its allowance.",
+            7,
+            &[
+                ("account", true, false),
+                ("owner", true, true),
+                ("bank", false, false),
+            ],
+            &[],
+        ),
+        instruction(
+            "SetClosePolicy",
+            "Sets what closing an account of the bank does with the tokens it still holds.",
+            8,
+            &[("bank", true, false), ("bank_owner", true, true)],
+            &[("policy", U8)],
+        ),
+        instruction(
+            "SetRequireRentExempt",
+            "Sets whether accounts opened in the bank must be rent-exempt.",
+            9,
+            &[("bank", true, false), ("bank_owner", true, true)],
+            &[("required", Bool)],
+        ),
+        instruction(
+            "PermitApprove",
+            "Lets a delegate spend up to `amount` more of an account's tokens on the owner's off-chain signature of the permit `permit::message` describes, instead of theirs on the transaction.",
+            10,
+            &[
+                ("account", true, false),
+                ("delegate", false, false),
+                ("bank", false, false),
+                ("instructions_sysvar", false, false),
+                ("feature_gate", false, false),
+            ],
+            &[("amount", U64), ("nonce", U64), ("expiry", I64)],
+        ),
+        instruction(
+            "TransferWithExpiry",
+            "`Transfer`, refused once the clock's slot passes `max_slot`, so a transfer signed ahead of time (e.g. against a durable nonce) can't be sent arbitrarily late.",
+            11,
+            &[
+                ("from", true, false),
+                ("to", true, false),
+                ("owner", true, true),
+                ("bank", true, false),
+            ],
+            &[("amount", U64), ("max_slot", U64)],
+        ),
+        // A one-hop route; each further hop adds its account and authority before `to`.
+        instruction(
+            "TransferRoute",
+            "Moves `amount` along a chain of accounts of the same bank, each hop a `Transfer` from one account to the next, so the route goes through whole or not at all.",
+            12,
+            &[
+                ("bank", true, false),
+                ("from", true, false),
+                ("owner", true, true),
+                ("to", true, false),
+                ("feature_gate", false, false),
+            ],
+            &[("amount", U64)],
+        ),
+        instruction(
+            "AssignToProgram",
+            "Hands an account over to the address `seeds`, bump included, derive from a program, so that only that program can move its tokens, by signing with the seeds.",
+            13,
+            &[
+                ("account", true, false),
+                ("owner", true, true),
+                ("bank", false, false),
+                ("program", false, false),
+            ],
+            &[("seeds", Seeds)],
+        ),
+        instruction(
+            "DepositSol",
+            "Moves `lamports` from the depositor into the bank and credits the account with the tokens they buy at the bank's `lamports_per_token`.",
+            14,
+            &[
+                ("bank", true, false),
+                ("account", true, false),
+                ("depositor", true, true),
+                ("system_program", false, false),
+            ],
+            &[("lamports", U64)],
+        ),
+        instruction(
+            "WithdrawSol",
+            "Burns the tokens `lamports` buys at the bank's `lamports_per_token` from the account and pays `lamports` out of the bank.",
+            15,
+            &[
+                ("bank", true, false),
+                ("account", true, false),
+                ("owner", true, true),
+                ("destination", true, false),
+            ],
+            &[("lamports", U64)],
+        ),
+        instruction(
+            "SetExchangeRate",
+            "Sets the rate `Convert` moves tokens of one bank into another at, to `numerator / denominator` whole tokens of the second per whole token of the first.",
+            16,
+            &[
+                ("rate", true, false),
+                ("from_bank", false, false),
+                ("to_bank", false, false),
+                ("from_bank_owner", true, true),
+                ("to_bank_owner", true, true),
+            ],
+            &[("numerator", U64), ("denominator", U64)],
+        ),
+        instruction(
+            "Convert",
+            "Burns `amount` from an account and mints what it's worth at an `ExchangeRate` to an account of the other bank, rescaled between the banks' decimals and rounded down.",
+            17,
+            &[
+                ("rate", false, false),
+                ("from_bank", true, false),
+                ("from_account", true, false),
+                ("owner", true, true),
+                ("to_bank", true, false),
+                ("to_account", true, false),
+            ],
+            &[("amount", U64)],
+        ),
+        instruction(
+            "SetDustThreshold",
+            "Sets the bank's `dust_threshold`.",
+            18,
+            &[("bank", true, false), ("bank_owner", true, true)],
+            &[("threshold", U64)],
+        ),
+        instruction(
+            "CloseDustAccount",
+            "Closes an account holding nothing or dust, and sends its lamports to its owner.",
+            19,
+            &[
+                ("account", true, false),
+                ("owner", true, false),
+                ("bank", true, false),
+            ],
+            &[],
+        ),
+        instruction(
+            "SetGuardians",
+            "Names the guardians who can recover an account, `threshold` of whom must sign, and the `delay` in slots between starting and completing a recovery.",
+            20,
+            &[
+                ("account", false, false),
+                ("owner", false, true),
+                ("bank", false, false),
+                ("recovery", true, false),
+                ("payer", true, true),
+                ("system_program", false, false),
+            ],
+            &[
+                ("threshold", U8),
+                ("delay", U64),
+                ("guardians", PublicKeys),
+            ],
+        ),
+        instruction(
+            "StartRecovery",
+            "Starts a recovery of an account to `new_owner`, replacing any started before; the threshold of its guardians sign, passed after the recovery state.",
+            21,
+            &[
+                ("account", false, false),
+                ("bank", false, false),
+                ("recovery", true, false),
+                ("guardian", false, true),
+            ],
+            &[("new_owner", PublicKey)],
+        ),
+        instruction(
+            "RecoverOwner",
+            "Completes the started recovery once its delay has passed, making its new owner the account's; the threshold of its guardians sign, passed after the recovery state.",
+            22,
+            &[
+                ("account", true, false),
+                ("bank", false, false),
+                ("recovery", true, false),
+                ("guardian", false, true),
+            ],
+            &[],
+        ),
+        instruction(
+            "CancelRecovery",
+            "Calls off the started recovery of an account.",
+            23,
+            &[
+                ("account", false, false),
+                ("owner", false, true),
+                ("bank", false, false),
+                ("recovery", true, false),
+            ],
+            &[],
+        ),
+        instruction(
+            "CreateSessionKey",
+            "Lets `session_key` transfer up to `max_spend` out of an account, until the slot passes `expiry_slot`; its transfers pass the key's session state after the bank.",
+            24,
+            &[
+                ("account", false, false),
+                ("owner", false, true),
+                ("bank", false, false),
+                ("session", true, false),
+                ("payer", true, true),
+                ("system_program", false, false),
+            ],
+            &[
+                ("session_key", PublicKey),
+                ("max_spend", U64),
+                ("expiry_slot", U64),
+            ],
+        ),
+        instruction(
+            "SetRateLimit",
+            "Caps how fast tokens leave an account created with room for a rate-limit bucket: up to `capacity` at once, regaining `refill_per_slot` per slot; a `capacity` of 0 lifts the limit.",
+            25,
+            &[
+                ("account", true, false),
+                ("owner", false, true),
+                ("bank", false, false),
+            ],
+            &[("capacity", U64), ("refill_per_slot", U64)],
+        ),
+        instruction(
+            "InitializeVersion",
+            "Records the running program's release and state layout in its version record, creating the record on first use at the payer's expense.",
+            26,
+            &[
+                ("version", true, false),
+                ("payer", true, true),
+                ("system_program", false, false),
+            ],
+            &[],
+        ),
+        instruction(
+            "SetFeatures",
+            "Sets which gated features a bank runs, see `feature_gate`, creating the bank's gate on first use at the payer's expense.",
+            27,
+            &[
+                ("bank", false, false),
+                ("bank_owner", false, true),
+                ("feature_gate", true, false),
+                ("payer", true, true),
+                ("system_program", false, false),
+            ],
+            &[("enabled", U64)],
+        ),
+        instruction(
+            "FreezeAccount",
+            "Freezes an account of a bank with a freeze authority, so nothing moves in or out of it until it's thawed.",
+            28,
+            &[
+                ("account", true, false),
+                ("bank", false, false),
+                ("freeze_authority", false, true),
+            ],
+            &[],
+        ),
+        instruction(
+            "ThawAccount",
+            "Thaws an account `FreezeAccount` froze.",
+            29,
+            &[
+                ("account", true, false),
+                ("bank", false, false),
+                ("freeze_authority", false, true),
+            ],
+            &[],
+        ),
+        instruction(
+            "InitializeMultisig",
+            "Names the signers of a multisig owner, `threshold` of whom must sign in its place; the signers, up to 11, are passed after the multisig.",
+            30,
+            &[("multisig", true, false), ("signer", false, false)],
+            &[("threshold", U8)],
+        ),
+        instruction(
+            "InitializeAssociatedAccount",
+            "Creates and opens a wallet's associated account in a bank, at the PDA of this program derived from `associated`, the wallet and the bank; the wallet needn't sign.",
+            31,
+            &[
+                ("bank", false, false),
+                ("account", true, false),
+                ("wallet", false, false),
+                ("payer", true, true),
+                ("system_program", false, false),
+            ],
+            &[],
+        ),
+        instruction(
+            "TransferChecked",
+            "`Transfer` without sweeping dust, refused unless the bank has `decimals`.",
+            32,
+            &[
+                ("from", true, false),
+                ("to", true, false),
+                ("owner", true, true),
+                ("bank", true, false),
+            ],
+            &[("amount", U64), ("decimals", U8)],
+        ),
+        instruction(
+            "MintToChecked",
+            "`MintTo`, refused unless the bank has `decimals`.",
+            33,
+            &[
+                ("bank", true, false),
+                ("account", true, false),
+                ("bank_owner", true, true),
+            ],
+            &[("amount", U64), ("decimals", U8)],
+        ),
+        instruction(
+            "BurnChecked",
+            "`Burn`, refused unless the bank has `decimals`.",
+            34,
+            &[
+                ("bank", true, false),
+                ("account", true, false),
+                ("bank_owner", true, true),
+                ("owner", true, true),
+            ],
+            &[("amount", U64), ("decimals", U8)],
+        ),
+        instruction(
+            "SetAuthority",
+            "Hands the `authority_type` authority of an account or a bank to `new_authority`, or removes the emergency authority; a new account owner starts without a delegate, and a new bank owner waits out the bank's `owner_change_delay` as pending, applied by sending the same instruction again.",
+            35,
+            &[("owned", true, false), ("authority", true, true)],
+            &[("authority_type", U8), ("new_authority", OptionPublicKey)],
+        ),
+        instruction(
+            "TransferBatch",
+            "Pays the `i`th of `amounts` from one account to the `i`th destination, passed after the bank, each payment a `Transfer` of its own, so the batch goes through whole or not at all.",
+            36,
+            &[
+                ("from", true, false),
+                ("owner", true, true),
+                ("bank", true, false),
+                ("to", true, false),
+            ],
+            &[("amounts", U64s)],
+        ),
+        instruction(
+            "PauseBank",
+            "Pauses a bank for an emergency, so its transfers, approvals, mints and burns fail until it's resumed.",
+            37,
+            &[("bank", true, false), ("bank_owner", false, true)],
+            &[],
+        ),
+        instruction(
+            "ResumeBank",
+            "Resumes a bank `PauseBank` paused.",
+            38,
+            &[("bank", true, false), ("bank_owner", false, true)],
+            &[],
+        ),
+        instruction(
+            "AccrueInterest",
+            "Pays an account the interest its bank owes it so far; anyone can send it.",
+            39,
+            &[("account", true, false), ("bank", true, false)],
+            &[],
+        ),
+        instruction(
+            "SetOwnerChangeDelay",
+            "Sets the slots a bank owner change waits before it can be applied; it can only be raised.",
+            40,
+            &[("bank", true, false), ("bank_owner", false, true)],
+            &[("delay", U64)],
+        ),
+        instruction(
+            "CancelOwnerChange",
+            "Calls off the change of bank owner a `SetAuthority` left pending; the bank owner or the emergency authority may send it.",
+            41,
+            &[("bank", true, false), ("authority", false, true)],
+            &[],
+        ),
+    ];
+    let accounts = vec![
+        account(
+            "Bank",
+            Bank::LEN,
+            &[
+                ("decimals", U8),
+                ("bank_owner", PublicKey),
+                ("is_opened", Bool),
+                ("total_supply", U64),
+                ("close_policy", U8),
+                ("require_rent_exempt", Bool),
+                ("burn_policy", U8),
+                ("lamports_per_token", U64),
+                ("transfer_count", U64),
+                ("cumulative_volume", U64),
+                ("holder_count", U64),
+                ("registered_accounts", U64),
+                ("dust_threshold", U64),
+                ("freeze_authority", COptionPublicKey),
+                ("is_paused", Bool),
+                ("interest_rate_bps", U16),
+                ("emergency_authority", COptionPublicKey),
+                ("owner_change_delay", U64),
+                ("pending_bank_owner", COptionPublicKey),
+                ("owner_change_slot", U64),
+            ],
+        ),
+        account(
+            "Account",
+            Account::LEN,
+            &[
+                ("amount", U64),
+                ("is_opened", Bool),
+                ("is_initialized", Bool),
+                ("owner", PublicKey),
+                ("delegate", COptionPublicKey),
+                ("delegated_amount", U64),
+                ("bank", PublicKey),
+                ("permit_nonce", U64),
+                ("is_frozen", Bool),
+                ("last_accrual", I64),
+            ],
+        ),
+        account(
+            "ExchangeRate",
+            ExchangeRate::LEN,
+            &[
+                ("is_initialized", Bool),
+                ("from_bank", PublicKey),
+                ("to_bank", PublicKey),
+                ("numerator", U64),
+                ("denominator", U64),
+            ],
+        ),
+        account(
+            "Recovery",
+            Recovery::LEN,
+            &[
+                ("is_initialized", Bool),
+                ("account", PublicKey),
+                ("owner", PublicKey),
+                ("threshold", U8),
+                ("guardian_count", U8),
+                ("guardians", PublicKeyArray),
+                ("delay", U64),
+                ("pending_owner", COptionPublicKey),
+                ("activation_slot", U64),
+            ],
+        ),
+        account(
+            "Session",
+            Session::LEN,
+            &[
+                ("is_initialized", Bool),
+                ("account", PublicKey),
+                ("owner", PublicKey),
+                ("session_key", PublicKey),
+                ("remaining_spend", U64),
+                ("expiry_slot", U64),
+            ],
+        ),
+        account(
+            "Version",
+            Version::LEN,
+            &[
+                ("is_initialized", Bool),
+                ("major", U16),
+                ("minor", U16),
+                ("patch", U16),
+                ("layout", U16),
+            ],
+        ),
+        account(
+            "FeatureGate",
+            FeatureGate::LEN,
+            &[
+                ("is_initialized", Bool),
+                ("bank", PublicKey),
+                ("enabled", U64),
+            ],
+        ),
+        account(
+            "Multisig",
+            Multisig::LEN,
+            &[
+                ("is_initialized", Bool),
+                ("threshold", U8),
+                ("signer_count", U8),
+                ("signers", SignerArray),
+            ],
+        ),
+    ];
+    Idl {
+        name: "bank".to_string(),
+        instructions,
+        accounts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use solana_program::pubkey::Pubkey;
+    use std::{convert::TryInto, fs, str::FromStr};
+
+    const VECTORS: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../bank/program/tests/fixtures/vectors.json"
+    );
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    /// Packs a vector's field value the way the program reads `ty`.
+    fn encode(ty: IdlType, value: &Value) -> Vec<u8> {
+        match ty {
+            IdlType::U8 => vec![value.as_u64().unwrap() as u8],
+            IdlType::U16 => (value.as_u64().unwrap() as u16).to_le_bytes().to_vec(),
+            IdlType::Bool => vec![value.as_bool().unwrap() as u8],
+            IdlType::U64 => value
+                .as_str()
+                .unwrap()
+                .parse::<u64>()
+                .unwrap()
+                .to_le_bytes()
+                .to_vec(),
+            IdlType::I64 => value
+                .as_str()
+                .unwrap()
+                .parse::<i64>()
+                .unwrap()
+                .to_le_bytes()
+                .to_vec(),
+            IdlType::PublicKey => Pubkey::from_str(value.as_str().unwrap())
+                .unwrap()
+                .to_bytes()
+                .to_vec(),
+            IdlType::OptionPublicKey => match value.as_str() {
+                Some(_) => [vec![1], encode(IdlType::PublicKey, value)].concat(),
+                None => vec![0],
+            },
+            IdlType::PublicKeys => {
+                let keys = value.as_array().unwrap();
+                let mut bytes = vec![keys.len() as u8];
+                for key in keys {
+                    bytes.extend(encode(IdlType::PublicKey, key));
+                }
+                bytes
+            }
+            IdlType::U64s => {
+                let amounts = value.as_array().unwrap();
+                let mut bytes = vec![amounts.len() as u8];
+                for amount in amounts {
+                    bytes.extend(encode(IdlType::U64, amount));
+                }
+                bytes
+            }
+            IdlType::Seeds => {
+                let seeds = value.as_array().unwrap();
+                let mut bytes = vec![seeds.len() as u8];
+                for seed in seeds {
+                    let seed = from_hex(seed.as_str().unwrap());
+                    bytes.push(seed.len() as u8);
+                    bytes.extend(seed);
+                }
+                bytes
+            }
+            ty => panic!("no {:?} instruction arguments", ty),
+        }
+    }
+
+    /// Reads `ty` at `offset` into the form the vectors write field values in.
+    fn decode(ty: IdlType, data: &[u8], offset: usize) -> Value {
+        let bytes = &data[offset..offset + ty.size()];
+        let key = |bytes: &[u8]| Pubkey::new_from_array(bytes.try_into().unwrap()).to_string();
+        match ty {
+            IdlType::U8 => Value::from(bytes[0]),
+            IdlType::U16 => Value::from(u16::from_le_bytes(bytes.try_into().unwrap())),
+            IdlType::U64 => Value::from(u64::from_le_bytes(bytes.try_into().unwrap()).to_string()),
+            IdlType::I64 => Value::from(i64::from_le_bytes(bytes.try_into().unwrap()).to_string()),
+            IdlType::Bool => Value::from(bytes[0] == 1),
+            IdlType::PublicKey => Value::from(key(bytes)),
+            IdlType::COptionPublicKey => match bytes[..4] {
+                [0, 0, 0, 0] => Value::Null,
+                _ => Value::from(key(&bytes[4..])),
+            },
+            IdlType::PublicKeyArray | IdlType::SignerArray => {
+                Value::from(bytes.chunks(32).map(key).collect::<Vec<_>>())
+            }
+            IdlType::OptionPublicKey | IdlType::Seeds | IdlType::PublicKeys | IdlType::U64s => {
+                panic!("no {:?} in state", ty)
+            }
+        }
+    }
+
+    #[test]
+    fn test_idl_matches_vectors() {
+        let idl = bank_idl();
+        let vectors: Value = serde_json::from_str(&fs::read_to_string(VECTORS).unwrap()).unwrap();
+
+        let instruction_vectors = vectors["instructions"].as_array().unwrap();
+        assert_eq!(instruction_vectors.len(), idl.instructions.len());
+        for vector in instruction_vectors {
+            let name = vector["name"].as_str().unwrap();
+            let instruction = idl
+                .instructions
+                .iter()
+                .find(|instruction| instruction.name == name)
+                .unwrap_or_else(|| panic!("{} is missing from the IDL", name));
+            let mut data = vec![instruction.discriminant];
+            for arg in &instruction.args {
+                data.extend(encode(arg.ty, &vector["fields"][&arg.name]));
+            }
+            assert_eq!(data, from_hex(vector["data"].as_str().unwrap()), "{}", name);
+
+            let metas = vector["accounts"].as_array().unwrap();
+            assert_eq!(metas.len(), instruction.accounts.len(), "{}", name);
+            for (meta, account) in metas.iter().zip(&instruction.accounts) {
+                assert_eq!(
+                    (meta["is_writable"].as_bool(), meta["is_signer"].as_bool()),
+                    (Some(account.is_writable), Some(account.is_signer)),
+                    "{}.{}",
+                    name,
+                    account.name
+                );
+            }
+        }
+
+        for vector in vectors["states"].as_array().unwrap() {
+            let name = vector["type"].as_str().unwrap();
+            let account = idl
+                .accounts
+                .iter()
+                .find(|account| account.name == name)
+                .unwrap();
+            let data = from_hex(vector["data"].as_str().unwrap());
+            assert_eq!(data.len(), account.size);
+            let fields: serde_json::Map<String, Value> = account
+                .fields
+                .iter()
+                .map(|field| (field.name.clone(), decode(field.ty, &data, field.offset)))
+                .collect();
+            assert_eq!(Value::Object(fields), vector["fields"], "{}", name);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Repository tasks, run from the repository root as `cargo xtask <task>`:
+//!
+//! - `idl` writes the bank program's interface to `bank/idl.json`.
+//! - `ts` generates the `@solana-play/bank` package in `ts/bank` from `bank/idl.json`.
+//! - `schema` writes the bank's account layouts to `bank/schema.json` for indexers.
+//! - `borsh-schema` writes the Borsh schemas of the borsh program's types to `program/schema`.
+//! - `header` generates `bank/ffi/include/solana_bank.h` from the C bindings in `bank/ffi`.
+//! - `size` reports the size of each on-chain program's build, after `cargo build-bpf`.
+//!
+//! Run the first three after changing an instruction or state layout of the bank program,
+//! `borsh-schema` after changing a type of the borsh program and `header` after changing
+//! `bank/ffi`; `cargo test` here fails until the checked-in files match.
+
+mod borsh_schema;
+mod idl;
+mod schema;
+mod size;
+mod ts;
+
+use borsh::BorshSerialize;
+use idl::Idl;
+use std::{env, fs, path::PathBuf, process::exit};
+
+fn root() -> PathBuf {
+    PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/.."))
+}
+
+fn idl_path() -> PathBuf {
+    root().join("bank/idl.json")
+}
+
+fn ts_path() -> PathBuf {
+    root().join("ts/bank/src/index.ts")
+}
+
+fn schema_path() -> PathBuf {
+    root().join("bank/schema.json")
+}
+
+fn borsh_schema_path(name: &str) -> PathBuf {
+    root()
+        .join("program/schema")
+        .join(format!("{}.borsh", name))
+}
+
+fn ffi_dir() -> PathBuf {
+    root().join("bank/ffi")
+}
+
+fn header_path() -> PathBuf {
+    ffi_dir().join("include/solana_bank.h")
+}
+
+fn export_idl() -> Result<(), String> {
+    let json = serde_json::to_string_pretty(&idl::bank_idl()).map_err(|e| e.to_string())?;
+    let path = idl_path();
+    fs::write(&path, json + "\n").map_err(|e| format!("write {} failed: {}", path.display(), e))?;
+    println!("wrote {}", path.display());
+    Ok(())
+}
+
+fn export_schema() -> Result<(), String> {
+    let json = serde_json::to_string_pretty(&schema::bank_schema()).map_err(|e| e.to_string())?;
+    let path = schema_path();
+    fs::write(&path, json + "\n").map_err(|e| format!("write {} failed: {}", path.display(), e))?;
+    println!("wrote {}", path.display());
+    Ok(())
+}
+
+fn export_borsh_schema() -> Result<(), String> {
+    for (name, container) in borsh_schema::containers() {
+        let path = borsh_schema_path(name);
+        let data = container.try_to_vec().map_err(|e| e.to_string())?;
+        fs::write(&path, data).map_err(|e| format!("write {} failed: {}", path.display(), e))?;
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn read_idl() -> Result<Idl, String> {
+    let path = idl_path();
+    let json = fs::read_to_string(&path).map_err(|e| {
+        format!(
+            "read {} failed, run `cargo xtask idl`: {}",
+            path.display(),
+            e
+        )
+    })?;
+    serde_json::from_str(&json).map_err(|e| format!("parse {} failed: {}", path.display(), e))
+}
+
+fn generate_ts() -> Result<(), String> {
+    let path = ts_path();
+    fs::write(&path, ts::generate(&read_idl()?))
+        .map_err(|e| format!("write {} failed: {}", path.display(), e))?;
+    println!("wrote {}", path.display());
+    Ok(())
+}
+
+fn header() -> Result<String, String> {
+    let config = cbindgen::Config::from_file(ffi_dir().join("cbindgen.toml"))?;
+    let bindings = cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(ffi_dir().join("src/lib.rs"))
+        .generate()
+        .map_err(|e| e.to_string())?;
+    let mut header = Vec::new();
+    bindings.write(&mut header);
+    String::from_utf8(header).map_err(|e| e.to_string())
+}
+
+fn generate_header() -> Result<(), String> {
+    let path = header_path();
+    fs::write(&path, header()?).map_err(|e| format!("write {} failed: {}", path.display(), e))?;
+    println!("wrote {}", path.display());
+    Ok(())
+}
+
+fn size_report() -> Result<(), String> {
+    print!("{}", size::report(&root())?);
+    Ok(())
+}
+
+fn main() {
+    let result = match env::args().nth(1).as_deref() {
+        Some("idl") => export_idl(),
+        Some("ts") => generate_ts(),
+        Some("schema") => export_schema(),
+        Some("borsh-schema") => export_borsh_schema(),
+        Some("header") => generate_header(),
+        Some("size") => size_report(),
+        _ => Err("usage: cargo xtask <idl|ts|schema|borsh-schema|header|size>".to_string()),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_files_are_current() {
+        assert!(
+            read_idl() == Ok(idl::bank_idl()),
+            "bank/idl.json is stale, run `cargo xtask idl`"
+        );
+        assert!(
+            fs::read_to_string(ts_path()).ok() == Some(ts::generate(&idl::bank_idl())),
+            "ts/bank/src/index.ts is stale, run `cargo xtask ts`"
+        );
+        let schema = fs::read_to_string(schema_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok());
+        assert!(
+            schema == Some(schema::bank_schema()),
+            "bank/schema.json is stale, run `cargo xtask schema`"
+        );
+        for (name, container) in borsh_schema::containers() {
+            assert!(
+                fs::read(borsh_schema_path(name)).ok() == container.try_to_vec().ok(),
+                "program/schema/{}.borsh is stale, run `cargo xtask borsh-schema`",
+                name
+            );
+        }
+        assert!(
+            fs::read_to_string(header_path()).ok() == Some(header().unwrap()),
+            "bank/ffi/include/solana_bank.h is stale, run `cargo xtask header`"
+        );
+    }
+}
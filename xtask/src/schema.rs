@@ -0,0 +1,318 @@
+//! The bank program's account layouts for indexers, exported to `bank/schema.json`: each
+//! field's name, type, offset and size, and how each type is encoded. Offsets and sizes
+//! come from packing rather than from the IDL: a field's bytes are the ones that change
+//! when only that field is set to a value with no zero bytes.
+
+use crate::idl::IdlType;
+use serde::{Deserialize, Serialize};
+use solana_bank::state::{
+    Account, Bank, BurnPolicy, ClosePolicy, ExchangeRate, FeatureGate, Multisig, Recovery, Session,
+    Version,
+};
+use solana_program::{program_option::COption, program_pack::Pack, pubkey::Pubkey};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Schema {
+    pub program: String,
+    pub types: Vec<SchemaType>,
+    pub accounts: Vec<SchemaAccount>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SchemaType {
+    pub name: IdlType,
+    pub size: usize,
+    pub encoding: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SchemaAccount {
+    pub name: String,
+    pub size: usize,
+    pub fields: Vec<SchemaField>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SchemaField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: IdlType,
+    pub offset: usize,
+    pub size: usize,
+}
+
+const TYPES: &[(IdlType, &str)] = &[
+    (IdlType::U8, "unsigned byte"),
+    (IdlType::U16, "unsigned 16-bit integer, little-endian"),
+    (IdlType::U64, "unsigned 64-bit integer, little-endian"),
+    (IdlType::I64, "signed 64-bit integer, little-endian, two's complement"),
+    (IdlType::Bool, "byte, 1 when true and 0 when false"),
+    (IdlType::PublicKey, "32-byte public key"),
+    (
+        IdlType::COptionPublicKey,
+        "4-byte little-endian tag, 1 when set and 0 when not, then a publicKey that is zeroed when not set",
+    ),
+    (IdlType::PublicKeyArray, "8 publicKeys back to back"),
+    (IdlType::SignerArray, "11 publicKeys back to back"),
+];
+
+/// A field of `T`: its name, its type, and how to set it to a value with no zero bytes.
+type Field<T> = (&'static str, IdlType, fn(&mut T));
+
+fn ones() -> Pubkey {
+    Pubkey::new_from_array([0xff; 32])
+}
+
+/// The bytes `set` changes in a packed default `T`, as `(offset, size)`.
+fn probe<T: Pack + Default>(set: fn(&mut T)) -> (usize, usize) {
+    let mut before = vec![0; T::LEN];
+    T::default().pack_into_slice(&mut before);
+    let mut value = T::default();
+    set(&mut value);
+    let mut after = vec![0; T::LEN];
+    value.pack_into_slice(&mut after);
+
+    let changed: Vec<usize> = (0..T::LEN).filter(|&i| before[i] != after[i]).collect();
+    match (changed.first(), changed.last()) {
+        (Some(&first), Some(&last)) => (first, last + 1 - first),
+        _ => panic!("setting a field changed no bytes"),
+    }
+}
+
+fn account<T: Pack + Default>(name: &str, fields: &[Field<T>]) -> SchemaAccount {
+    let mut end = 0;
+    let fields = fields
+        .iter()
+        .map(|&(field, ty, set)| {
+            let (offset, size) = probe(set);
+            assert_eq!(
+                (offset, size),
+                (end, ty.size()),
+                "{}.{} isn't packed where expected",
+                name,
+                field
+            );
+            end = offset + size;
+            SchemaField {
+                name: field.to_string(),
+                ty,
+                offset,
+                size,
+            }
+        })
+        .collect();
+    assert_eq!(end, T::LEN, "{} fields don't add up to its LEN", name);
+    SchemaAccount {
+        name: name.to_string(),
+        size: T::LEN,
+        fields,
+    }
+}
+
+pub fn bank_schema() -> Schema {
+    use IdlType::*;
+
+    let types = TYPES
+        .iter()
+        .map(|&(name, encoding)| SchemaType {
+            name,
+            size: name.size(),
+            encoding: encoding.to_string(),
+        })
+        .collect();
+    let accounts = vec![
+        account::<Bank>(
+            "Bank",
+            &[
+                ("decimals", U8, |bank| bank.decimals = u8::MAX),
+                ("bank_owner", PublicKey, |bank| bank.bank_owner = ones()),
+                ("is_opened", Bool, |bank| bank.is_opened = true),
+                ("total_supply", U64, |bank| bank.total_supply = u64::MAX),
+                ("close_policy", U8, |bank| {
+                    bank.close_policy = ClosePolicy::Burn
+                }),
+                ("require_rent_exempt", Bool, |bank| {
+                    bank.require_rent_exempt = true
+                }),
+                ("burn_policy", U8, |bank| {
+                    bank.burn_policy = BurnPolicy::HolderOnly
+                }),
+                ("lamports_per_token", U64, |bank| {
+                    bank.lamports_per_token = u64::MAX
+                }),
+                ("transfer_count", U64, |bank| bank.transfer_count = u64::MAX),
+                ("cumulative_volume", U64, |bank| {
+                    bank.cumulative_volume = u64::MAX
+                }),
+                ("holder_count", U64, |bank| bank.holder_count = u64::MAX),
+                ("registered_accounts", U64, |bank| {
+                    bank.registered_accounts = u64::MAX
+                }),
+                ("dust_threshold", U64, |bank| bank.dust_threshold = u64::MAX),
+                ("freeze_authority", COptionPublicKey, |bank| {
+                    bank.freeze_authority = COption::Some(ones())
+                }),
+                ("is_paused", Bool, |bank| bank.is_paused = true),
+                ("interest_rate_bps", U16, |bank| {
+                    bank.interest_rate_bps = u16::MAX
+                }),
+                ("emergency_authority", COptionPublicKey, |bank| {
+                    bank.emergency_authority = COption::Some(ones())
+                }),
+                ("owner_change_delay", U64, |bank| {
+                    bank.owner_change_delay = u64::MAX
+                }),
+                ("pending_bank_owner", COptionPublicKey, |bank| {
+                    bank.pending_bank_owner = COption::Some(ones())
+                }),
+                ("owner_change_slot", U64, |bank| {
+                    bank.owner_change_slot = u64::MAX
+                }),
+            ],
+        ),
+        account::<Account>(
+            "Account",
+            &[
+                ("amount", U64, |account| account.amount = u64::MAX),
+                ("is_opened", Bool, |account| account.is_opened = true),
+                ("is_initialized", Bool, |account| {
+                    account.is_initialized = true
+                }),
+                ("owner", PublicKey, |account| account.owner = ones()),
+                ("delegate", COptionPublicKey, |account| {
+                    account.delegate = COption::Some(ones())
+                }),
+                ("delegated_amount", U64, |account| {
+                    account.delegated_amount = u64::MAX
+                }),
+                ("bank", PublicKey, |account| account.bank = ones()),
+                ("permit_nonce", U64, |account| {
+                    account.permit_nonce = u64::MAX
+                }),
+                ("is_frozen", Bool, |account| account.is_frozen = true),
+                ("last_accrual", I64, |account| account.last_accrual = -1),
+            ],
+        ),
+        account::<ExchangeRate>(
+            "ExchangeRate",
+            &[
+                ("is_initialized", Bool, |rate| rate.is_initialized = true),
+                ("from_bank", PublicKey, |rate| rate.from_bank = ones()),
+                ("to_bank", PublicKey, |rate| rate.to_bank = ones()),
+                ("numerator", U64, |rate| rate.numerator = u64::MAX),
+                ("denominator", U64, |rate| rate.denominator = u64::MAX),
+            ],
+        ),
+        account::<Recovery>(
+            "Recovery",
+            &[
+                ("is_initialized", Bool, |recovery| {
+                    recovery.is_initialized = true
+                }),
+                ("account", PublicKey, |recovery| recovery.account = ones()),
+                ("owner", PublicKey, |recovery| recovery.owner = ones()),
+                ("threshold", U8, |recovery| recovery.threshold = u8::MAX),
+                ("guardian_count", U8, |recovery| {
+                    recovery.guardian_count = u8::MAX
+                }),
+                ("guardians", PublicKeyArray, |recovery| {
+                    recovery.guardians = [ones(); 8]
+                }),
+                ("delay", U64, |recovery| recovery.delay = u64::MAX),
+                ("pending_owner", COptionPublicKey, |recovery| {
+                    recovery.pending_owner = COption::Some(ones())
+                }),
+                ("activation_slot", U64, |recovery| {
+                    recovery.activation_slot = u64::MAX
+                }),
+            ],
+        ),
+        account::<Session>(
+            "Session",
+            &[
+                ("is_initialized", Bool, |session| {
+                    session.is_initialized = true
+                }),
+                ("account", PublicKey, |session| session.account = ones()),
+                ("owner", PublicKey, |session| session.owner = ones()),
+                ("session_key", PublicKey, |session| {
+                    session.session_key = ones()
+                }),
+                ("remaining_spend", U64, |session| {
+                    session.remaining_spend = u64::MAX
+                }),
+                ("expiry_slot", U64, |session| session.expiry_slot = u64::MAX),
+            ],
+        ),
+        account::<Version>(
+            "Version",
+            &[
+                ("is_initialized", Bool, |version| {
+                    version.is_initialized = true
+                }),
+                ("major", U16, |version| version.major = u16::MAX),
+                ("minor", U16, |version| version.minor = u16::MAX),
+                ("patch", U16, |version| version.patch = u16::MAX),
+                ("layout", U16, |version| version.layout = u16::MAX),
+            ],
+        ),
+        account::<FeatureGate>(
+            "FeatureGate",
+            &[
+                ("is_initialized", Bool, |gate| gate.is_initialized = true),
+                ("bank", PublicKey, |gate| gate.bank = ones()),
+                ("enabled", U64, |gate| gate.enabled = u64::MAX),
+            ],
+        ),
+        account::<Multisig>(
+            "Multisig",
+            &[
+                ("is_initialized", Bool, |multisig| {
+                    multisig.is_initialized = true
+                }),
+                ("threshold", U8, |multisig| multisig.threshold = u8::MAX),
+                ("signer_count", U8, |multisig| {
+                    multisig.signer_count = u8::MAX
+                }),
+                ("signers", SignerArray, |multisig| {
+                    multisig.signers = [ones(); 11]
+                }),
+            ],
+        ),
+    ];
+    Schema {
+        program: "bank".to_string(),
+        types,
+        accounts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl;
+
+    #[test]
+    fn test_schema_matches_idl() {
+        let schema = bank_schema();
+        let idl = idl::bank_idl();
+        assert_eq!(schema.accounts.len(), idl.accounts.len());
+        for (account, idl_account) in schema.accounts.iter().zip(&idl.accounts) {
+            assert_eq!(
+                (&account.name, account.size),
+                (&idl_account.name, idl_account.size)
+            );
+            let fields: Vec<_> = account
+                .fields
+                .iter()
+                .map(|field| (field.name.as_str(), field.ty, field.offset))
+                .collect();
+            let idl_fields: Vec<_> = idl_account
+                .fields
+                .iter()
+                .map(|field| (field.name.as_str(), field.ty, field.offset))
+                .collect();
+            assert_eq!(fields, idl_fields, "{}", account.name);
+        }
+    }
+}
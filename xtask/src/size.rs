@@ -0,0 +1,148 @@
+//! Sizes of the on-chain programs' builds, read from what `cargo build-bpf` left in each
+//! program's `target/deploy`. A program is a crate defining the `no-entrypoint` feature;
+//! the ones that haven't been built are listed as such.
+
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct Program {
+    /// The crate's directory, relative to the repository root.
+    pub dir: PathBuf,
+    pub name: String,
+}
+
+impl Program {
+    /// The shared object `cargo build-bpf` writes for the program.
+    pub fn so_path(&self, root: &Path) -> PathBuf {
+        root.join(&self.dir)
+            .join("target/deploy")
+            .join(format!("{}.so", self.name.replace('-', "_")))
+    }
+}
+
+/// The program crate described by `manifest`, if it is one.
+pub fn parse_manifest(dir: PathBuf, manifest: &str) -> Option<Program> {
+    let lines = || manifest.lines().map(str::trim);
+    if !lines().any(|line| line.starts_with("no-entrypoint =")) {
+        return None;
+    }
+    // The first `name` is the package's; `[package]` comes first in every manifest here.
+    let name = lines()
+        .find_map(|line| line.strip_prefix("name ="))?
+        .trim()
+        .trim_matches('"');
+    Some(Program {
+        dir,
+        name: name.to_string(),
+    })
+}
+
+/// Program crates one or two levels below the root, sorted by directory.
+pub fn programs(root: &Path) -> Result<Vec<Program>, String> {
+    let mut dirs = vec![PathBuf::new()];
+    let mut programs = Vec::new();
+    for depth in 1..=2 {
+        let mut next = Vec::new();
+        for dir in dirs {
+            let entries = fs::read_dir(root.join(&dir))
+                .map_err(|e| format!("read {} failed: {}", root.join(&dir).display(), e))?;
+            for entry in entries.filter_map(Result::ok) {
+                let name = entry.file_name();
+                if name.to_string_lossy().starts_with('.') || !entry.path().is_dir() {
+                    continue;
+                }
+                let dir = dir.join(name);
+                if let Ok(manifest) = fs::read_to_string(root.join(&dir).join("Cargo.toml")) {
+                    programs.extend(parse_manifest(dir.clone(), &manifest));
+                }
+                if depth < 2 {
+                    next.push(dir);
+                }
+            }
+        }
+        dirs = next;
+    }
+    programs.sort_by(|a, b| a.dir.cmp(&b.dir));
+    Ok(programs)
+}
+
+/// One line per program with the size of its build, largest first, then the total.
+pub fn report(root: &Path) -> Result<String, String> {
+    let mut built = Vec::new();
+    let mut unbuilt = Vec::new();
+    for program in programs(root)? {
+        match fs::metadata(program.so_path(root)) {
+            Ok(metadata) => built.push((program, metadata.len())),
+            Err(_) => unbuilt.push(program),
+        }
+    }
+    built.sort_by_key(|(_, size)| Reverse(*size));
+
+    let mut report = String::new();
+    for (program, size) in &built {
+        report += &format!(
+            "{:<28} {:>10} bytes  {}\n",
+            program.name,
+            size,
+            program.dir.display()
+        );
+    }
+    for program in &unbuilt {
+        report += &format!(
+            "{:<28} {:>10}        {}\n",
+            program.name,
+            "not built",
+            program.dir.display()
+        );
+    }
+    report += &format!(
+        "{:<28} {:>10} bytes  {} of {} programs built\n",
+        "total",
+        built.iter().map(|(_, size)| size).sum::<u64>(),
+        built.len(),
+        built.len() + unbuilt.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = r#"
+[package]
+name = "solana_bank"
+version = "0.1.0"
+
+[features]
+no-entrypoint = []
+
+[lib]
+name = "other"
+"#;
+        let program = parse_manifest(PathBuf::from("bank/program"), manifest).unwrap();
+        assert_eq!(program.name, "solana_bank");
+        assert_eq!(
+            program.so_path(Path::new("/repo")),
+            PathBuf::from("/repo/bank/program/target/deploy/solana_bank.so")
+        );
+
+        let manifest = "[package]\nname = \"client\"\n\n[dependencies]\nsolana_bank = { path = \"../bank/program\", features = [\"no-entrypoint\"] }\n";
+        assert_eq!(parse_manifest(PathBuf::from("client"), manifest), None);
+    }
+
+    #[test]
+    fn test_programs() {
+        let programs = programs(&crate::root()).unwrap();
+        let dirs: Vec<_> = programs.iter().map(|p| p.dir.to_str().unwrap()).collect();
+        assert!(dirs.contains(&"program"));
+        assert!(dirs.contains(&"bank/program"));
+        assert!(!dirs.contains(&"client"));
+    }
+}
@@ -0,0 +1,376 @@
+//! TypeScript for the `@solana-play/bank` package: an instruction builder per IDL
+//! instruction and a decoder per IDL account, with no dependencies so it works with any
+//! version of `@solana/web3.js`, or none.
+
+use crate::idl::{Idl, IdlAccount, IdlInstruction, IdlType};
+use std::fmt::Write;
+
+const PRELUDE: &str = r#"export interface AccountMeta {
+  pubkey: string;
+  isSigner: boolean;
+  isWritable: boolean;
+}
+
+/** An instruction with base58 addresses, the fields of web3.js's `TransactionInstruction`. */
+export interface Instruction {
+  programId: string;
+  keys: AccountMeta[];
+  data: Uint8Array;
+}
+
+const BASE58_ALPHABET = '123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz';
+
+function encodeBase58(bytes: Uint8Array): string {
+  const digits: number[] = [];
+  for (const byte of bytes) {
+    let carry = byte;
+    for (let i = 0; i < digits.length; i++) {
+      carry += digits[i] * 256;
+      digits[i] = carry % 58;
+      carry = Math.floor(carry / 58);
+    }
+    while (carry > 0) {
+      digits.push(carry % 58);
+      carry = Math.floor(carry / 58);
+    }
+  }
+  let encoded = '';
+  for (let i = 0; i < bytes.length && bytes[i] === 0; i++) {
+    encoded += '1';
+  }
+  for (let i = digits.length - 1; i >= 0; i--) {
+    encoded += BASE58_ALPHABET[digits[i]];
+  }
+  return encoded;
+}
+
+function decodeBase58(encoded: string): Uint8Array {
+  const bytes: number[] = [];
+  for (const char of encoded) {
+    let carry = BASE58_ALPHABET.indexOf(char);
+    if (carry < 0) {
+      throw new Error(`invalid base58 character ${char}`);
+    }
+    for (let i = 0; i < bytes.length; i++) {
+      carry += bytes[i] * 58;
+      bytes[i] = carry & 0xff;
+      carry >>= 8;
+    }
+    while (carry > 0) {
+      bytes.push(carry & 0xff);
+      carry >>= 8;
+    }
+  }
+  for (let i = 0; i < encoded.length && encoded[i] === '1'; i++) {
+    bytes.push(0);
+  }
+  return Uint8Array.from(bytes.reverse());
+}
+
+function pushU8(data: number[], value: number): void {
+  if (!Number.isInteger(value) || value < 0 || value > 0xff) {
+    throw new RangeError(`${value} is not a u8`);
+  }
+  data.push(value);
+}
+
+function pushU16(data: number[], value: number): void {
+  if (!Number.isInteger(value) || value < 0 || value > 0xffff) {
+    throw new RangeError(`${value} is not a u16`);
+  }
+  data.push(value & 0xff, value >> 8);
+}
+
+function pushU64(data: number[], value: bigint): void {
+  if (value < BigInt(0) || value > BigInt('0xffffffffffffffff')) {
+    throw new RangeError(`${value} is not a u64`);
+  }
+  for (let i = 0; i < 8; i++) {
+    data.push(Number((value >> BigInt(8 * i)) & BigInt(0xff)));
+  }
+}
+
+function pushI64(data: number[], value: bigint): void {
+  if (value < -BigInt('0x8000000000000000') || value > BigInt('0x7fffffffffffffff')) {
+    throw new RangeError(`${value} is not an i64`);
+  }
+  pushU64(data, value < BigInt(0) ? value + BigInt('0x10000000000000000') : value);
+}
+
+function pushSeeds(data: number[], seeds: Uint8Array[]): void {
+  if (seeds.length > 16) {
+    throw new RangeError(`${seeds.length} seeds, more than 16`);
+  }
+  pushU8(data, seeds.length);
+  for (const seed of seeds) {
+    if (seed.length > 32) {
+      throw new RangeError(`a seed of ${seed.length} bytes, more than 32`);
+    }
+    pushU8(data, seed.length);
+    data.push(...seed);
+  }
+}
+
+function pushPublicKey(data: number[], key: string): void {
+  const bytes = decodeBase58(key);
+  if (bytes.length !== 32) {
+    throw new RangeError(`${key} is not a public key`);
+  }
+  data.push(...bytes);
+}
+
+function pushOptionPublicKey(data: number[], key: string | null): void {
+  if (key === null) {
+    data.push(0);
+  } else {
+    data.push(1);
+    pushPublicKey(data, key);
+  }
+}
+
+function pushPublicKeys(data: number[], keys: string[]): void {
+  pushU8(data, keys.length);
+  for (const key of keys) {
+    pushPublicKey(data, key);
+  }
+}
+
+function pushU64s(data: number[], values: bigint[]): void {
+  pushU8(data, values.length);
+  for (const value of values) {
+    pushU64(data, value);
+  }
+}
+
+function readU8(data: Uint8Array, offset: number): number {
+  return data[offset];
+}
+
+function readU16(data: Uint8Array, offset: number): number {
+  return data[offset] | (data[offset + 1] << 8);
+}
+
+function readU64(data: Uint8Array, offset: number): bigint {
+  let value = BigInt(0);
+  for (let i = 7; i >= 0; i--) {
+    value = (value << BigInt(8)) | BigInt(data[offset + i]);
+  }
+  return value;
+}
+
+function readI64(data: Uint8Array, offset: number): bigint {
+  return BigInt.asIntN(64, readU64(data, offset));
+}
+
+function readBool(data: Uint8Array, offset: number): boolean {
+  return data[offset] === 1;
+}
+
+function readPublicKey(data: Uint8Array, offset: number): string {
+  return encodeBase58(data.subarray(offset, offset + 32));
+}
+
+function readPublicKeyArray(data: Uint8Array, offset: number): string[] {
+  const keys: string[] = [];
+  for (let i = 0; i < 8; i++) {
+    keys.push(readPublicKey(data, offset + 32 * i));
+  }
+  return keys;
+}
+
+function readSignerArray(data: Uint8Array, offset: number): string[] {
+  const keys: string[] = [];
+  for (let i = 0; i < 11; i++) {
+    keys.push(readPublicKey(data, offset + 32 * i));
+  }
+  return keys;
+}
+
+function readCOptionPublicKey(data: Uint8Array, offset: number): string | null {
+  const tag = data.subarray(offset, offset + 4).join(',');
+  if (tag === '0,0,0,0') {
+    return null;
+  }
+  if (tag === '1,0,0,0') {
+    return readPublicKey(data, offset + 4);
+  }
+  throw new Error(`invalid option tag ${tag}`);
+}
+"#;
+
+/// `bank_owner` as `bankOwner`.
+fn camel_case(name: &str) -> String {
+    let mut camel = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            camel.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+/// `MintTo` as `mintTo`.
+fn lower_first(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn ts_type(ty: IdlType) -> &'static str {
+    match ty {
+        IdlType::U8 | IdlType::U16 => "number",
+        IdlType::U64 | IdlType::I64 => "bigint",
+        IdlType::Bool => "boolean",
+        IdlType::PublicKey => "string",
+        IdlType::COptionPublicKey | IdlType::OptionPublicKey => "string | null",
+        IdlType::Seeds => "Uint8Array[]",
+        IdlType::PublicKeys | IdlType::PublicKeyArray | IdlType::SignerArray => "string[]",
+        IdlType::U64s => "bigint[]",
+    }
+}
+
+/// The prelude function reading or writing `ty`.
+fn ts_codec(ty: IdlType) -> &'static str {
+    match ty {
+        IdlType::U8 => "U8",
+        IdlType::U16 => "U16",
+        IdlType::U64 => "U64",
+        IdlType::I64 => "I64",
+        IdlType::Bool => "Bool",
+        IdlType::PublicKey => "PublicKey",
+        IdlType::COptionPublicKey => "COptionPublicKey",
+        IdlType::OptionPublicKey => "OptionPublicKey",
+        IdlType::Seeds => "Seeds",
+        IdlType::PublicKeys => "PublicKeys",
+        IdlType::U64s => "U64s",
+        IdlType::PublicKeyArray => "PublicKeyArray",
+        IdlType::SignerArray => "SignerArray",
+    }
+}
+
+fn write_instruction(ts: &mut String, instruction: &IdlInstruction) {
+    let accounts: Vec<String> = instruction
+        .accounts
+        .iter()
+        .map(|account| format!("{}: string", camel_case(&account.name)))
+        .collect();
+    writeln!(ts, "\n/** {} */", instruction.docs).unwrap();
+    writeln!(ts, "export function {}(", lower_first(&instruction.name)).unwrap();
+    writeln!(ts, "  programId: string,").unwrap();
+    writeln!(ts, "  accounts: {{ {} }},", accounts.join("; ")).unwrap();
+    if !instruction.args.is_empty() {
+        let args: Vec<String> = instruction
+            .args
+            .iter()
+            .map(|arg| format!("{}: {}", camel_case(&arg.name), ts_type(arg.ty)))
+            .collect();
+        writeln!(ts, "  args: {{ {} }},", args.join("; ")).unwrap();
+    }
+    writeln!(ts, "): Instruction {{").unwrap();
+    writeln!(
+        ts,
+        "  const data: number[] = [{}];",
+        instruction.discriminant
+    )
+    .unwrap();
+    for arg in &instruction.args {
+        writeln!(
+            ts,
+            "  push{}(data, args.{});",
+            ts_codec(arg.ty),
+            camel_case(&arg.name)
+        )
+        .unwrap();
+    }
+    writeln!(ts, "  return {{").unwrap();
+    writeln!(ts, "    programId,").unwrap();
+    writeln!(ts, "    keys: [").unwrap();
+    for account in &instruction.accounts {
+        writeln!(
+            ts,
+            "      {{ pubkey: accounts.{}, isSigner: {}, isWritable: {} }},",
+            camel_case(&account.name),
+            account.is_signer,
+            account.is_writable
+        )
+        .unwrap();
+    }
+    writeln!(ts, "    ],").unwrap();
+    writeln!(ts, "    data: Uint8Array.from(data),").unwrap();
+    writeln!(ts, "  }};").unwrap();
+    writeln!(ts, "}}").unwrap();
+}
+
+fn write_account(ts: &mut String, account: &IdlAccount) {
+    let size = format!("{}_SIZE", account.name.to_uppercase());
+    writeln!(ts, "\nexport const {} = {};", size, account.size).unwrap();
+    writeln!(ts, "\nexport interface {} {{", account.name).unwrap();
+    for field in &account.fields {
+        writeln!(ts, "  {}: {};", camel_case(&field.name), ts_type(field.ty)).unwrap();
+    }
+    writeln!(ts, "}}").unwrap();
+    writeln!(
+        ts,
+        "\nexport function decode{}(data: Uint8Array): {} {{",
+        account.name, account.name
+    )
+    .unwrap();
+    writeln!(ts, "  if (data.length !== {}) {{", size).unwrap();
+    writeln!(
+        ts,
+        "    throw new RangeError(`{} is ${{{}}} bytes, got ${{data.length}}`);",
+        account.name, size
+    )
+    .unwrap();
+    writeln!(ts, "  }}").unwrap();
+    writeln!(ts, "  return {{").unwrap();
+    for field in &account.fields {
+        writeln!(
+            ts,
+            "    {}: read{}(data, {}),",
+            camel_case(&field.name),
+            ts_codec(field.ty),
+            field.offset
+        )
+        .unwrap();
+    }
+    writeln!(ts, "  }};").unwrap();
+    writeln!(ts, "}}").unwrap();
+}
+
+/// The package's `src/index.ts`.
+pub fn generate(idl: &Idl) -> String {
+    let mut ts = format!(
+        "// Generated by `cargo xtask ts` from {}/idl.json; do not edit.\n\n",
+        idl.name
+    );
+    ts.push_str(PRELUDE);
+    for instruction in &idl.instructions {
+        write_instruction(&mut ts, instruction);
+    }
+    for account in &idl.accounts {
+        write_account(&mut ts, account);
+    }
+    ts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        assert_eq!(camel_case("bank_owner"), "bankOwner");
+        assert_eq!(camel_case("is_opened"), "isOpened");
+        assert_eq!(camel_case("amount"), "amount");
+        assert_eq!(lower_first("MintTo"), "mintTo");
+    }
+}